@@ -0,0 +1,170 @@
+//! OZ-mode particle update: `AoS` vs `SoA` + F32x8.
+//!
+//! Advances 50k+ rotunda particles one 60 fps frame at a time, first as the
+//! old `Vec<TextParticle>` loop (layer match, focus check and fade branches
+//! per particle), then through `simd::particles::update_particles`, and
+//! prints the time per frame for each.
+//!
+//! ```bash
+//! cargo bench --bench particles
+//! ```
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use alice_browser::simd::particles::update_particles;
+use alice_browser::simd::soa::ParticlesSoA;
+
+/// Particle counts to compare (aggressive prefetch reaches the upper end).
+const COUNTS: [usize; 3] = [1_000, 50_000, 200_000];
+/// Frames simulated per measurement.
+const FRAMES: u32 = 200;
+const DT: f32 = 1.0 / 60.0;
+const EASE: f32 = 4.0 * DT;
+
+const FADE_IN_DURATION: f32 = 1.5;
+const FADE_OUT_DURATION: f32 = 2.5;
+
+#[derive(Clone, Copy)]
+enum Layer {
+    Upper,
+    Eye,
+    Lower,
+}
+
+/// The per-particle motion fields of the `AoS` layout.
+struct AosParticle {
+    angle: f32,
+    age: f32,
+    lifetime: f32,
+    layer: Layer,
+    pool_index: usize,
+    grabbed: bool,
+    pull: f32,
+    opacity: f32,
+}
+
+/// Deterministic 0–1 value for particle `i`.
+fn hash(i: usize) -> f32 {
+    let x = i.wrapping_mul(2_654_435_761) ^ i.wrapping_mul(340_573_321);
+    ((x & 0xFFFF) as f32) / 65535.0
+}
+
+fn layer_of(i: usize) -> Layer {
+    match i % 3 {
+        0 => Layer::Upper,
+        1 => Layer::Eye,
+        _ => Layer::Lower,
+    }
+}
+
+const fn speed(layer: Layer) -> f32 {
+    match layer {
+        Layer::Upper => 0.08,
+        Layer::Eye => 0.20,
+        Layer::Lower => -0.35,
+    }
+}
+
+fn aos_particles(n: usize) -> Vec<AosParticle> {
+    (0..n)
+        .map(|i| AosParticle {
+            angle: hash(i) * std::f32::consts::TAU,
+            age: hash(i * 19) * 15.0,
+            lifetime: 15.0 + hash(i * 71) * 15.0,
+            layer: layer_of(i),
+            pool_index: i,
+            grabbed: i == 7,
+            pull: 0.0,
+            opacity: 0.0,
+        })
+        .collect()
+}
+
+fn soa_particles(n: usize) -> ParticlesSoA {
+    let mut soa = ParticlesSoA::with_capacity(n);
+    for i in 0..n {
+        soa.push(
+            hash(i) * std::f32::consts::TAU,
+            0.0,
+            speed(layer_of(i)),
+            hash(i * 19) * 15.0,
+            15.0 + hash(i * 71) * 15.0,
+        );
+        let focused = (100..400).contains(&i);
+        soa.pull_targets.as_mut_slice()[i] = if focused { 1.0 } else { 0.0 };
+    }
+    soa.moving.as_mut_slice()[7] = 0.0;
+    soa
+}
+
+/// The pre-`SoA` `StreamState::update_flow` loop.
+fn aos_update(particles: &mut [AosParticle], focus: (usize, usize), expired: &mut Vec<usize>) {
+    for (i, p) in particles.iter_mut().enumerate() {
+        let target = if (focus.0..focus.1).contains(&p.pool_index) {
+            1.0
+        } else {
+            0.0
+        };
+        p.pull = (target - p.pull).mul_add(EASE, p.pull);
+        if p.grabbed {
+            p.opacity = 1.0;
+            continue;
+        }
+        p.angle += speed(p.layer) * DT;
+        p.age += DT;
+        let fade_out_start = p.lifetime - FADE_OUT_DURATION;
+        p.opacity = if p.age < FADE_IN_DURATION {
+            p.age / FADE_IN_DURATION
+        } else if p.age < fade_out_start {
+            1.0
+        } else {
+            (1.0 - (p.age - fade_out_start) / FADE_OUT_DURATION).max(0.0)
+        };
+        if p.age >= p.lifetime {
+            expired.push(i);
+        }
+    }
+}
+
+fn per_frame(elapsed: Duration) -> f64 {
+    elapsed.as_secs_f64() * 1e6 / f64::from(FRAMES)
+}
+
+fn main() {
+    println!(
+        "{:>9}  {:>12}  {:>12}  {:>7}",
+        "particles", "AoS µs/frame", "SoA µs/frame", "speedup"
+    );
+    for n in COUNTS {
+        let mut expired = Vec::new();
+
+        let mut aos = aos_particles(n);
+        let start = Instant::now();
+        for _ in 0..FRAMES {
+            expired.clear();
+            aos_update(black_box(&mut aos), (100, 400), &mut expired);
+            // Respawn: restart the expired particles
+            for &i in &expired {
+                aos[i].age = 0.0;
+            }
+        }
+        let aos_us = per_frame(start.elapsed());
+
+        let mut soa = soa_particles(n);
+        let start = Instant::now();
+        for _ in 0..FRAMES {
+            expired.clear();
+            update_particles(black_box(&mut soa), DT, EASE, &mut expired);
+            for &i in &expired {
+                soa.ages.as_mut_slice()[i] = 0.0;
+            }
+        }
+        let soa_us = per_frame(start.elapsed());
+
+        println!(
+            "{n:>9}  {aos_us:>12.1}  {soa_us:>12.1}  {:>6.1}x",
+            aos_us / soa_us
+        );
+    }
+}
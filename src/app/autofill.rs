@@ -0,0 +1,172 @@
+//! Autofill profiles for `BrowserApp`.
+//!
+//! Named profiles (name, email, phone, postal address) are stored as TOML
+//! sealed in `autofill.vault` in the profile directory (see
+//! [`alice_browser::vault`]); a plain `autofill.toml` left by an older
+//! build is encrypted on first load and removed. Edited in the autofill
+//! window (stats panel); web forms offer them through the **Autofill**
+//! button (see [`crate::ui::forms`]).
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use alice_browser::dom::autofill::AutofillProfile;
+use alice_browser::vault::Vault;
+
+use super::BrowserApp;
+
+/// File name of the sealed autofill profiles inside the profile directory.
+pub const AUTOFILL_FILE: &str = "autofill.vault";
+
+/// Unencrypted file of older builds, migrated by [`AutofillProfiles::load`].
+const PLAIN_AUTOFILL_FILE: &str = "autofill.toml";
+
+/// Every saved profile, in menu order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutofillProfiles {
+    pub profiles: Vec<AutofillProfile>,
+}
+
+impl AutofillProfiles {
+    /// Load from the profile directory; a missing, malformed or
+    /// undecryptable file yields no profiles.
+    #[must_use]
+    pub fn load() -> Self {
+        let vault = match Vault::profile() {
+            Ok(vault) => vault,
+            Err(e) => {
+                log::warn!("No autofill vault: {e}");
+                return Self::default();
+            }
+        };
+        let Ok(path) = alice_browser::profile::file_path(AUTOFILL_FILE) else {
+            return Self::default();
+        };
+        match vault.read(&path) {
+            Ok(bytes) => Self::parse(&String::from_utf8_lossy(&bytes), &path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::migrate_plain(&vault),
+            Err(e) => {
+                log::warn!("Ignoring {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    fn parse(text: &str, path: &std::path::Path) -> Self {
+        toml::from_str(text).unwrap_or_else(|e| {
+            log::warn!("Ignoring malformed {}: {e}", path.display());
+            Self::default()
+        })
+    }
+
+    /// Seal the profiles of an older build's plain file and remove it.
+    fn migrate_plain(vault: &Vault) -> Self {
+        let Ok(path) = alice_browser::profile::file_path(PLAIN_AUTOFILL_FILE) else {
+            return Self::default();
+        };
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        let profiles = Self::parse(&text, &path);
+        match profiles.write(vault) {
+            Ok(()) => {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    log::warn!("Could not remove {}: {e}", path.display());
+                }
+            }
+            Err(e) => log::warn!("Could not encrypt autofill profiles: {e}"),
+        }
+        profiles
+    }
+
+    fn write(&self, vault: &Vault) -> std::io::Result<()> {
+        let path = alice_browser::profile::file_path(AUTOFILL_FILE)?;
+        let text = toml::to_string_pretty(self).map_err(std::io::Error::other)?;
+        vault.write(&path, text.as_bytes())
+    }
+
+    /// Seal into the profile directory.
+    pub fn save(&self) {
+        if let Err(e) = Vault::profile().and_then(|vault| self.write(&vault)) {
+            log::warn!("Could not save autofill profiles: {e}");
+        }
+    }
+}
+
+impl BrowserApp {
+    /// Window listing the autofill profiles, each editable in place.
+    pub fn draw_autofill(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_autofill;
+        let mut changed = false;
+        egui::Window::new("Autofill profiles")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.weak("Encrypted in the profile directory, with the key beside it");
+                ui.separator();
+
+                let mut remove = None;
+                egui::ScrollArea::vertical()
+                    .max_height(420.0)
+                    .show(ui, |ui| {
+                        for (i, profile) in self.autofill.profiles.iter_mut().enumerate() {
+                            let title = profile.display_label().to_string();
+                            egui::CollapsingHeader::new(title)
+                                .id_salt(("autofill_profile", i))
+                                .show(ui, |ui| {
+                                    changed |= profile_form(ui, i, profile);
+                                    if ui.button("Delete profile").clicked() {
+                                        remove = Some(i);
+                                    }
+                                });
+                        }
+                    });
+                if let Some(i) = remove {
+                    self.autofill.profiles.remove(i);
+                    changed = true;
+                }
+
+                ui.separator();
+                if ui.button("Add profile").clicked() {
+                    self.autofill.profiles.push(AutofillProfile {
+                        label: format!("Profile {}", self.autofill.profiles.len() + 1),
+                        ..AutofillProfile::default()
+                    });
+                    changed = true;
+                }
+            });
+        self.show_autofill = open;
+
+        if changed {
+            self.autofill.save();
+        }
+    }
+}
+
+/// Text fields for one profile; returns whether any changed.
+fn profile_form(ui: &mut egui::Ui, index: usize, profile: &mut AutofillProfile) -> bool {
+    let fields: [(&str, &mut String); 10] = [
+        ("Label", &mut profile.label),
+        ("Full name", &mut profile.name),
+        ("Email", &mut profile.email),
+        ("Phone", &mut profile.tel),
+        ("Organization", &mut profile.organization),
+        ("Street address", &mut profile.street_address),
+        ("City", &mut profile.city),
+        ("State / region", &mut profile.region),
+        ("Postal code", &mut profile.postal_code),
+        ("Country", &mut profile.country),
+    ];
+    let mut changed = false;
+    egui::Grid::new(("autofill_fields", index))
+        .num_columns(2)
+        .show(ui, |ui| {
+            for (label, value) in fields {
+                ui.label(label);
+                changed |= ui.text_edit_singleline(value).changed();
+                ui.end_row();
+            }
+        });
+    changed
+}
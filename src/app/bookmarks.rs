@@ -0,0 +1,356 @@
+//! Bookmarks for `BrowserApp`.
+//!
+//! A flat list of bookmarks and folders (folders may nest), persisted as
+//! `bookmarks.json` in the profile directory. The toolbar star toggles the
+//! current page, the side panel manages folders, and bookmarked URLs feed
+//! the URL-bar suggestions.
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use super::BrowserApp;
+
+/// File name of the bookmark store inside the profile directory.
+pub const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub id: u64,
+    pub title: String,
+    pub url: String,
+    /// Containing folder; `None` = top level
+    pub folder: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Folder {
+    pub id: u64,
+    pub name: String,
+    pub parent: Option<u64>,
+}
+
+/// All bookmarks and folders.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BookmarkStore {
+    next_id: u64,
+    pub folders: Vec<Folder>,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl BookmarkStore {
+    /// Load from the profile directory; missing or unreadable files yield an
+    /// empty store.
+    #[must_use]
+    pub fn load() -> Self {
+        let Ok(path) = alice_browser::profile::file_path(BOOKMARKS_FILE) else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                log::warn!("Ignoring malformed {}: {e}", path.display());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Write to the profile directory.
+    pub fn save(&self) {
+        let result = alice_browser::profile::file_path(BOOKMARKS_FILE).and_then(|path| {
+            let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+            std::fs::write(path, json)
+        });
+        if let Err(e) = result {
+            log::warn!("Could not save bookmarks: {e}");
+        }
+    }
+
+    fn alloc_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    pub fn add(&mut self, url: &str, title: &str, folder: Option<u64>) -> u64 {
+        let id = self.alloc_id();
+        let title = if title.trim().is_empty() { url } else { title };
+        self.bookmarks.push(Bookmark {
+            id,
+            title: title.to_string(),
+            url: url.to_string(),
+            folder,
+        });
+        id
+    }
+
+    pub fn remove(&mut self, id: u64) {
+        self.bookmarks.retain(|b| b.id != id);
+    }
+
+    pub fn rename(&mut self, id: u64, title: &str) {
+        if let Some(b) = self.bookmarks.iter_mut().find(|b| b.id == id) {
+            b.title = title.to_string();
+        }
+    }
+
+    pub fn move_to(&mut self, id: u64, folder: Option<u64>) {
+        if let Some(b) = self.bookmarks.iter_mut().find(|b| b.id == id) {
+            b.folder = folder;
+        }
+    }
+
+    #[must_use]
+    pub fn find_by_url(&self, url: &str) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|b| b.url == url)
+    }
+
+    pub fn add_folder(&mut self, name: &str, parent: Option<u64>) -> u64 {
+        let id = self.alloc_id();
+        self.folders.push(Folder {
+            id,
+            name: name.to_string(),
+            parent,
+        });
+        id
+    }
+
+    pub fn rename_folder(&mut self, id: u64, name: &str) {
+        if let Some(f) = self.folders.iter_mut().find(|f| f.id == id) {
+            f.name = name.to_string();
+        }
+    }
+
+    /// Delete a folder; its bookmarks and sub-folders move up to its parent.
+    pub fn remove_folder(&mut self, id: u64) {
+        let Some(pos) = self.folders.iter().position(|f| f.id == id) else {
+            return;
+        };
+        let parent = self.folders.remove(pos).parent;
+        for b in self.bookmarks.iter_mut().filter(|b| b.folder == Some(id)) {
+            b.folder = parent;
+        }
+        for f in self.folders.iter_mut().filter(|f| f.parent == Some(id)) {
+            f.parent = parent;
+        }
+    }
+
+    /// Bookmarks whose URL or title contains `query` (case-insensitive).
+    #[must_use]
+    pub fn suggest(&self, query: &str, limit: usize) -> Vec<&Bookmark> {
+        let q = query.trim().to_lowercase();
+        if q.is_empty() {
+            return Vec::new();
+        }
+        self.bookmarks
+            .iter()
+            .filter(|b| b.url.to_lowercase().contains(&q) || b.title.to_lowercase().contains(&q))
+            .take(limit)
+            .collect()
+    }
+}
+
+/// Item being renamed inline in the side panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookmarkEditTarget {
+    Bookmark(u64),
+    Folder(u64),
+}
+
+/// Deferred panel action, applied after drawing.
+enum PanelAction {
+    Open(String),
+    Remove(u64),
+    RemoveFolder(u64),
+    Move(u64, Option<u64>),
+    StartRename(BookmarkEditTarget, String),
+    CommitRename,
+}
+
+impl BrowserApp {
+    /// Whether the page in the URL bar is bookmarked.
+    #[must_use]
+    pub fn is_bookmarked(&self) -> bool {
+        self.bookmarks.find_by_url(&self.url_input).is_some()
+    }
+
+    /// Star button: bookmark the current page, or remove its bookmark.
+    pub fn toggle_bookmark(&mut self) {
+        if let Some(id) = self.bookmarks.find_by_url(&self.url_input).map(|b| b.id) {
+            self.bookmarks.remove(id);
+        } else {
+            let title = self
+                .page
+                .as_ref()
+                .map(|p| p.dom.title.clone())
+                .unwrap_or_default();
+            let url = self.url_input.clone();
+            self.bookmarks.add(&url, &title, None);
+        }
+        self.bookmarks.save();
+    }
+
+    /// Bookmarks side panel.
+    pub fn draw_bookmarks_panel(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        ui.horizontal(|ui| {
+            ui.heading("Bookmarks");
+            if ui.small_button("+ Folder").clicked() {
+                let id = self.bookmarks.add_folder("New folder", None);
+                self.bookmark_edit = Some((BookmarkEditTarget::Folder(id), "New folder".into()));
+            }
+        });
+        ui.separator();
+
+        if self.bookmarks.bookmarks.is_empty() && self.bookmarks.folders.is_empty() {
+            ui.colored_label(egui::Color32::GRAY, "No bookmarks yet — use \u{2606}");
+            return;
+        }
+
+        let mut action = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            self.draw_bookmark_level(ui, None, &mut action);
+        });
+
+        let Some(action) = action else {
+            return;
+        };
+        match action {
+            PanelAction::Open(url) => {
+                self.url_input = url;
+                self.navigate_typed(ctx);
+                return;
+            }
+            PanelAction::Remove(id) => self.bookmarks.remove(id),
+            PanelAction::RemoveFolder(id) => self.bookmarks.remove_folder(id),
+            PanelAction::Move(id, folder) => self.bookmarks.move_to(id, folder),
+            PanelAction::StartRename(target, text) => {
+                self.bookmark_edit = Some((target, text));
+                return;
+            }
+            PanelAction::CommitRename => {
+                if let Some((target, text)) = self.bookmark_edit.take() {
+                    match target {
+                        BookmarkEditTarget::Bookmark(id) => self.bookmarks.rename(id, &text),
+                        BookmarkEditTarget::Folder(id) => self.bookmarks.rename_folder(id, &text),
+                    }
+                }
+            }
+        }
+        self.bookmarks.save();
+    }
+
+    /// One folder level: sub-folders first, then bookmarks.
+    fn draw_bookmark_level(
+        &mut self,
+        ui: &mut egui::Ui,
+        parent: Option<u64>,
+        action: &mut Option<PanelAction>,
+    ) {
+        let folders: Vec<(u64, String)> = self
+            .bookmarks
+            .folders
+            .iter()
+            .filter(|f| f.parent == parent)
+            .map(|f| (f.id, f.name.clone()))
+            .collect();
+
+        for (id, name) in folders {
+            if self.draw_rename_field(ui, BookmarkEditTarget::Folder(id), action) {
+                continue;
+            }
+            let header = egui::CollapsingHeader::new(format!("\u{1F4C1} {name}"))
+                .id_salt(("bm_folder", id))
+                .show(ui, |ui| self.draw_bookmark_level(ui, Some(id), action));
+            header.header_response.context_menu(|ui| {
+                if ui.button("Rename").clicked() {
+                    *action = Some(PanelAction::StartRename(
+                        BookmarkEditTarget::Folder(id),
+                        name.clone(),
+                    ));
+                    ui.close_menu();
+                }
+                if ui.button("Delete folder").clicked() {
+                    *action = Some(PanelAction::RemoveFolder(id));
+                    ui.close_menu();
+                }
+            });
+        }
+
+        let entries: Vec<Bookmark> = self
+            .bookmarks
+            .bookmarks
+            .iter()
+            .filter(|b| b.folder == parent)
+            .cloned()
+            .collect();
+        let all_folders: Vec<(u64, String)> = self
+            .bookmarks
+            .folders
+            .iter()
+            .map(|f| (f.id, f.name.clone()))
+            .collect();
+
+        for b in entries {
+            if self.draw_rename_field(ui, BookmarkEditTarget::Bookmark(b.id), action) {
+                continue;
+            }
+            let response = ui
+                .selectable_label(
+                    b.url == self.url_input,
+                    crate::ui::truncate_str(&b.title, 32),
+                )
+                .on_hover_text(&b.url);
+            if response.clicked() {
+                *action = Some(PanelAction::Open(b.url.clone()));
+            }
+            response.context_menu(|ui| {
+                if ui.button("Rename").clicked() {
+                    *action = Some(PanelAction::StartRename(
+                        BookmarkEditTarget::Bookmark(b.id),
+                        b.title.clone(),
+                    ));
+                    ui.close_menu();
+                }
+                ui.menu_button("Move to", |ui| {
+                    if b.folder.is_some() && ui.button("(top level)").clicked() {
+                        *action = Some(PanelAction::Move(b.id, None));
+                        ui.close_menu();
+                    }
+                    for (fid, fname) in &all_folders {
+                        if Some(*fid) != b.folder && ui.button(fname).clicked() {
+                            *action = Some(PanelAction::Move(b.id, Some(*fid)));
+                            ui.close_menu();
+                        }
+                    }
+                });
+                if ui.button("Delete").clicked() {
+                    *action = Some(PanelAction::Remove(b.id));
+                    ui.close_menu();
+                }
+            });
+        }
+    }
+
+    /// Inline rename field; returns `true` if `target` is being edited.
+    fn draw_rename_field(
+        &mut self,
+        ui: &mut egui::Ui,
+        target: BookmarkEditTarget,
+        action: &mut Option<PanelAction>,
+    ) -> bool {
+        let Some((editing, ref mut text)) = self.bookmark_edit else {
+            return false;
+        };
+        if editing != target {
+            return false;
+        }
+        let response = ui.text_edit_singleline(text);
+        response.request_focus();
+        if response.lost_focus() {
+            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                self.bookmark_edit = None;
+            } else {
+                *action = Some(PanelAction::CommitRename);
+            }
+        }
+        true
+    }
+}
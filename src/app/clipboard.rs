@@ -0,0 +1,72 @@
+//! System clipboard access for `BrowserApp`.
+//!
+//! Carries out the copy requests raised by the page renderers
+//! (`ui::clipboard`): formatted copies go on the clipboard as HTML with a
+//! plain-text alternative, and "Copy image" places the decoded RGBA pixels
+//! there. Both go through `arboard`; where the platform clipboard refuses
+//! a flavour, the copy falls back to plain text through egui.
+
+use eframe::egui;
+
+use crate::ui::clipboard::{self, CopyRequest};
+
+use super::BrowserApp;
+
+impl BrowserApp {
+    /// The system clipboard, opened on first use.
+    fn system_clipboard(&mut self) -> Option<&mut arboard::Clipboard> {
+        if self.clipboard.is_none() {
+            match arboard::Clipboard::new() {
+                Ok(cb) => self.clipboard = Some(cb),
+                Err(e) => log::warn!("Clipboard unavailable: {e}"),
+            }
+        }
+        self.clipboard.as_mut()
+    }
+
+    /// Handle this frame's copy request and any image copy waiting for its
+    /// download.
+    pub fn handle_copy_requests(&mut self, ctx: &egui::Context) {
+        match clipboard::take(ctx) {
+            Some(CopyRequest::Rich(content)) => {
+                let copied = self
+                    .system_clipboard()
+                    .map(|cb| cb.set_html(content.html.as_str(), Some(content.text.as_str())));
+                if !matches!(copied, Some(Ok(()))) {
+                    if let Some(Err(e)) = copied {
+                        log::warn!("Could not copy HTML: {e}");
+                    }
+                    ctx.copy_text(content.text);
+                }
+            }
+            Some(CopyRequest::Image(url)) => {
+                // Flat view never fetched the pixels; copy once they arrive
+                self.image_loader.request(&url);
+                self.pending_image_copy = Some(url);
+            }
+            None => {}
+        }
+
+        let Some(url) = self.pending_image_copy.take() else {
+            return;
+        };
+        if self.image_loader.is_pending(&url) {
+            self.pending_image_copy = Some(url);
+            return;
+        }
+        let Some(data) = self.image_loader.get(&url) else {
+            log::warn!("Could not copy image {url}: it failed to load");
+            return;
+        };
+        let image = arboard::ImageData {
+            width: data.width as usize,
+            height: data.height as usize,
+            bytes: std::borrow::Cow::Owned(data.rgba.clone()),
+        };
+        let copied = self.system_clipboard().map(|cb| cb.set_image(image));
+        if let Some(Err(e)) = copied {
+            log::warn!("Could not copy image ({e}); copying its address instead");
+            ctx.copy_text(url);
+        }
+    }
+}
@@ -1,20 +1,212 @@
 //! Content-area rendering for `BrowserApp`.
 //!
-//! Contains four methods:
+//! Contains:
 //!
 //! - `draw_content`      — top-level dispatcher (spinner, error, flat/SDF/3-D)
+//! - `set_render_mode`   — switch modes, dropping per-mode scene state
+//! - `reflow_page`       — re-layout the 2-D views when the window resizes
+//! - `set_page_zoom`     — page zoom (Cmd+Plus/Minus/0), remembered per site
 //! - `draw_sdf_paint`    — 2-D SDF paint layer (always compiled)
+//! - `oz_click_at` / `oz_open_grabbed` — OZ grab, ring focus and link open
 //! - `draw_sdf_content`  — 3-D / OZ raymarched view (`sdf-render` feature)
 //! - `draw_stats_panel`  — right-side statistics panel
 
+use alice_browser::engine::pipeline::{step_zoom, ZOOM_LEVELS};
+#[cfg(feature = "sdf-render")]
+use alice_browser::net::scheduler::{self, Priority};
+use alice_browser::render::layout::LayoutNode;
+use alice_browser::render::layout_debug::layout_boxes;
+use alice_browser::render::tab_order::FocusKind;
 use alice_browser::render::RenderMode;
+use alice_browser::telemetry::frame_profiler::FrameStage;
 use eframe::egui;
 
+use super::keyboard::HintMode;
 use super::BrowserApp;
 use crate::oz::{fetch_link_preview, resolve_url, LinkPreviewStatus};
-use crate::ui::{render_layout_node, truncate_str};
+use crate::ui::{render_layout_node, truncate_str, Find};
+
+/// Cross-fade time from a Spatial3D frame to a sharper pass of it.
+#[cfg(feature = "sdf-render")]
+const SDF_FADE_SECS: f32 = 0.12;
+
+/// Fewest texts an OZ stream is grouped by topic for; smaller pages keep
+/// their sections.
+#[cfg(all(feature = "sdf-render", feature = "search"))]
+const OZ_TOPIC_MIN_TEXTS: usize = 16;
+
+/// A rendered Spatial3D frame, ready to draw.
+#[cfg(feature = "sdf-render")]
+pub enum SdfFrame {
+    /// Pixels read back from the raymarcher (or the CPU renderer) and
+    /// uploaded as an egui texture
+    Image(egui::TextureHandle),
+    /// The GPU raymarcher's output texture, drawn in place, and its size
+    Gpu(egui::TextureId, [u32; 2]),
+}
+
+#[cfg(feature = "sdf-render")]
+impl SdfFrame {
+    #[must_use]
+    pub fn id(&self) -> egui::TextureId {
+        match self {
+            Self::Image(tex) => tex.id(),
+            Self::Gpu(id, _) => *id,
+        }
+    }
+
+    /// Size in pixels.
+    #[must_use]
+    pub fn size(&self) -> [usize; 2] {
+        match self {
+            Self::Image(tex) => tex.size(),
+            Self::Gpu(_, [w, h]) => [*w as usize, *h as usize],
+        }
+    }
+}
 
 impl BrowserApp {
+    // ── Reduced motion ───────────────────────────────────────────────────────
+
+    /// Apply the current `reduce_motion` setting to egui and any live scene.
+    pub fn apply_reduce_motion(&mut self, ctx: &egui::Context) {
+        let reduce = self.reduce_motion;
+        ctx.style_mut(|s| s.animation_time = if reduce { 0.0 } else { 1.0 / 12.0 });
+
+        #[cfg(feature = "sdf-render")]
+        {
+            if reduce {
+                if let Some(ref mut stream) = self.stream_state {
+                    stream.arrange_static();
+                }
+                if self.oz_hologram_start.is_some() {
+                    self.oz_hologram_alpha = 1.0;
+                }
+            }
+            self.last_frame_time = std::time::Instant::now();
+            self.cam_dirty = true;
+        }
+    }
+
+    // ── Render mode ──────────────────────────────────────────────────────────
+
+    /// Switch to `mode`, dropping state built for the previous one.
+    pub fn set_render_mode(&mut self, mode: RenderMode) {
+        if self.render_mode != mode {
+            self.render_mode = mode;
+            self.render_mode_changed();
+        }
+    }
+
+    /// Invalidate the spatial scene after `render_mode` changed.
+    pub fn render_mode_changed(&mut self) {
+        #[cfg(feature = "sdf-render")]
+        {
+            self.spatial_scene = None;
+            self.focus_3d = None;
+            self.stream_state = None;
+            self.refiner.cancel();
+            self.sdf_fade = None;
+            self.cull_stats = None;
+            self.site_map.targets.clear();
+            self.cam_dirty = true;
+            self.oz_prefetch_started = false;
+            self.oz_prefetch_rx = None;
+            self.oz_prefetch_buffer.clear();
+        }
+    }
+
+    // ── Reflow ───────────────────────────────────────────────────────────────
+
+    /// Re-layout the current page for a content area `width` pixels wide.
+    ///
+    /// Only the 2-D views follow the window width; the 3-D scenes are built
+    /// once per page and keep their own geometry.
+    pub fn reflow_page(&mut self, width: f32) {
+        if !matches!(self.render_mode, RenderMode::Flat | RenderMode::Sdf2D) {
+            return;
+        }
+        if let Some(ref mut page) = self.page {
+            let zoomed = page.set_zoom(self.page_zoom);
+            if page.relayout(width) || zoomed {
+                // Rebuilt lazily from the new layout
+                self.paint_elements = None;
+            }
+        }
+    }
+
+    // ── Zoom ─────────────────────────────────────────────────────────────────
+
+    /// Zoom the page to `zoom` and remember it for the current site.
+    ///
+    /// The 2-D views and Reader mode lay the page out again at the new size
+    /// on their next frame; the 3-D scenes are unaffected.
+    pub fn set_page_zoom(&mut self, zoom: f32) {
+        let zoom = zoom.clamp(ZOOM_LEVELS[0], ZOOM_LEVELS[ZOOM_LEVELS.len() - 1]);
+        self.page_zoom = zoom;
+        if let Some(origin) = self.site.origin.clone() {
+            let mut prefs = self.site.prefs.clone();
+            prefs.zoom = ((zoom - 1.0).abs() > f32::EPSILON).then_some(zoom);
+            if prefs != self.site.prefs {
+                self.site_settings.set(&origin, prefs.clone());
+                self.site_settings.save();
+                self.site.prefs = prefs;
+            }
+        }
+    }
+
+    /// Cmd+Plus / Cmd+= zoom in, Cmd+Minus out, Cmd+0 resets.
+    pub fn handle_zoom_keys(&mut self, ctx: &egui::Context) {
+        let step = ctx.input_mut(|i| {
+            if i.consume_key(egui::Modifiers::COMMAND, egui::Key::Num0) {
+                Some(0)
+            } else if i.consume_key(egui::Modifiers::COMMAND, egui::Key::Plus)
+                || i.consume_key(egui::Modifiers::COMMAND, egui::Key::Equals)
+            {
+                Some(1)
+            } else if i.consume_key(egui::Modifiers::COMMAND, egui::Key::Minus) {
+                Some(-1)
+            } else {
+                None
+            }
+        });
+        if let Some(step) = step {
+            self.set_page_zoom(step_zoom(self.page_zoom, step));
+        }
+    }
+
+    /// Lay out more of a long page around the region `top..bottom` (layout
+    /// pixels) and refresh what was derived from the layout.
+    pub fn extend_page_layout(&mut self, top: f32, bottom: f32) {
+        let Some(ref mut page) = self.page else {
+            return;
+        };
+        let start = std::time::Instant::now();
+        let extended = page.extend_layout(top, bottom);
+        self.frame_profiler.add(FrameStage::Layout, start.elapsed());
+        if !extended {
+            return;
+        }
+        self.paint_elements = None;
+        #[cfg(feature = "search")]
+        self.refresh_page_search();
+    }
+
+    /// Request the page's images near the view `top..bottom` (layout
+    /// pixels) and cancel downloads of those scrolled far away — unless
+    /// they were dropped to stay under the memory budget, or the print
+    /// window wants all of them.
+    pub fn load_visible_images(&mut self, top: f32, bottom: f32) {
+        let Some(ref page) = self.page else {
+            return;
+        };
+        if self.show_print || self.memory.images_dropped(&page.dom.url) {
+            return;
+        }
+        let images = alice_browser::net::image::page_images(&page.layout);
+        self.image_loader.load_near(&images, top, bottom);
+    }
+
     // ── 2-D SDF paint ────────────────────────────────────────────────────────
 
     /// Lazily build and paint the 2-D SDF element list.  Returns the href of
@@ -23,32 +215,336 @@ impl BrowserApp {
         // Lazily generate paint elements
         if self.paint_elements.is_none() {
             if let Some(ref page) = self.page {
+                let start = std::time::Instant::now();
                 self.paint_elements =
-                    Some(alice_browser::render::sdf_ui::layout_to_paint(&page.layout));
+                    Some(alice_browser::render::sdf_ui::layout_to_paint_with_media(
+                        &page.layout,
+                        &self.media.meta,
+                    ));
+                self.sdf_paint_state.menu_boxes =
+                    alice_browser::render::context_menu::selector_boxes(&page.layout);
+                #[cfg(feature = "sdf-render")]
+                {
+                    self.sdf_paint_state.focus_blocks = page.layout.focus_blocks();
+                }
+                self.frame_profiler
+                    .add(FrameStage::SceneBuild, start.elapsed());
+                // The layout changed: follow the focused node to its new box
+                self.refresh_keyboard_focus();
             }
         }
 
-        // Request images for any image placeholders
-        if let Some(ref elems) = self.paint_elements {
-            for elem in elems {
-                if let Some(ref url) = elem.image_url {
-                    self.image_loader.request(url);
-                }
+        // Resource-timing heat map (recomputed as images arrive)
+        self.sdf_paint_state.overlay = match self.page {
+            Some(ref page) if self.show_cost_overlay => {
+                let loader = &self.image_loader;
+                Some(alice_browser::render::cost::element_costs(
+                    &page.layout,
+                    &page.timings,
+                    &|url| loader.encoded_bytes(url),
+                ))
             }
-        }
+            _ => None,
+        };
 
-        let dark_mode = self.dark_mode;
+        self.sdf_paint_state.layout_boxes = self
+            .page
+            .as_ref()
+            .filter(|_| self.show_layout_boxes)
+            .map(|page| layout_boxes(&page.layout));
+
+        let dark_mode = self.effective_dark_mode();
         let paint_state = &mut self.sdf_paint_state;
+        paint_state.min_height = self.page.as_ref().map_or(0.0, |p| p.layout.bounds.height);
+        paint_state.focus_ring = self.keyboard.focused.as_ref().map(|t| t.rect);
+        paint_state.scroll_to_focus |= std::mem::take(&mut self.keyboard.scroll_pending);
+        paint_state.inspected = self
+            .page
+            .as_ref()
+            .and_then(|p| self.dom_inspector.node(p))
+            .map(|n| [n.bounds.x, n.bounds.y, n.bounds.width, n.bounds.height]);
+        paint_state.scroll_to_inspected |= std::mem::take(&mut self.dom_inspector.scroll_pending);
+        if let Some(y) = self.restore_scroll.take() {
+            paint_state.scroll_to = Some(y);
+        }
+        if let Some(id) = self.pending_anchor.take() {
+            paint_state.scroll_to = self
+                .page
+                .as_ref()
+                .and_then(|p| p.layout.find_anchor(&id))
+                .map(|n| n.bounds.y);
+        }
+        paint_state.hints = self.keyboard.hints.as_ref().map_or_else(Vec::new, |h| {
+            h.matching()
+                .map(|(code, target)| (target.rect, code.clone()))
+                .collect()
+        });
+        paint_state.hint_typed = self.keyboard.hints.as_ref().map_or(0, |h| h.typed.len());
         let elements = &self.paint_elements;
         let textures = &self.image_textures;
 
-        elements
+        let clicked = elements
             .as_ref()
-            .and_then(|elems| paint_state.paint(ui, ctx, elems, dark_mode, textures))
+            .and_then(|elems| paint_state.paint(ui, ctx, elems, dark_mode, textures));
+        if let Some(href) = elements
+            .as_ref()
+            .and_then(|elems| paint_state.hovered_link(elems))
+        {
+            crate::ui::report_hovered_link(ctx, href);
+        }
+        if let Some(action) = paint_state.menu_action.take() {
+            crate::ui::page_menu::request(ctx, action);
+        }
+        #[cfg(feature = "sdf-render")]
+        if let Some(rect) = paint_state.focus_request.take() {
+            self.focus_in_3d(rect);
+            return None;
+        }
+        if let Some((top, bottom)) = paint_state.visible {
+            self.keyboard.view = (top, bottom);
+            self.extend_page_layout(top, bottom);
+            self.load_visible_images(top, bottom);
+        }
+        clicked
+    }
+
+    // ── OZ interaction ───────────────────────────────────────────────────────
+
+    /// Handle a click at `pos` inside the OZ view `rect`: go back to a past
+    /// page's galaxy, focus a heading ring segment, or grab the nearest
+    /// particle and start its preview.
+    #[cfg(feature = "sdf-render")]
+    pub fn oz_click_at(&mut self, pos: egui::Pos2, rect: egui::Rect) {
+        use std::sync::mpsc;
+
+        let fov: f32 = 110.0_f32.to_radians();
+        let fov_h = fov * 0.5;
+        let aspect = rect.width() / rect.height();
+        let fov_v = fov_h / aspect;
+
+        let ndc_x = (pos.x - rect.center().x) / (rect.width() * 0.5);
+        let ndc_y = (pos.y - rect.center().y) / (rect.height() * 0.5);
+
+        // A past page's galaxy takes the reader back to it
+        let galaxy_hit = self
+            .oz_galaxies
+            .pick([pos.x, pos.y], 24.0, self.oz_galaxy_time, |world| {
+                let (nx, ny, _) = alice_browser::render::docmap::project(
+                    world,
+                    self.cam_params.azimuth,
+                    self.cam_params.elevation,
+                    fov_h.tan(),
+                    aspect,
+                )?;
+                Some([
+                    (nx * rect.width()).mul_add(0.5, rect.center().x),
+                    (ny * rect.height()).mul_add(0.5, rect.center().y),
+                ])
+            })
+            .map(|galaxy| galaxy.url.clone());
+        if let Some(url) = galaxy_hit {
+            self.oz_pending_url = Some(url);
+            return;
+        }
+
+        // Document map: a heading ring segment focuses its section
+        let ring_hit = self.oz_doc_map.as_ref().and_then(|map| {
+            let (x, z) = alice_browser::render::docmap::unproject_to_ring_plane(
+                ndc_x,
+                ndc_y,
+                self.cam_params.azimuth,
+                self.cam_params.elevation,
+                fov_h.tan(),
+                aspect,
+            )?;
+            let idx = map.pick(x, z)?;
+            let s = &map.sections[idx];
+            Some((idx, s.pool_start, s.pool_end))
+        });
+
+        if let Some((idx, start, end)) = ring_hit {
+            self.oz_focus_section = if self.oz_focus_section == Some(idx) {
+                None
+            } else {
+                Some(idx)
+            };
+            let range = self.oz_focus_section.map(|_| (start, end));
+            if let Some(ref mut stream) = self.stream_state {
+                stream.focus_pool_range(range);
+                if self.reduce_motion {
+                    stream.settle_focus();
+                }
+            }
+        } else if let Some(ref mut stream) = self.stream_state {
+            stream.try_grab_screen(
+                ndc_x,
+                ndc_y,
+                self.cam_params.azimuth,
+                self.cam_params.elevation,
+                fov_h,
+                fov_v,
+                aspect,
+            );
+
+            if let Some(info) = stream.grabbed_info() {
+                #[cfg(feature = "spatial-audio")]
+                {
+                    use alice_browser::audio::{CueKind, Listener};
+                    let listener = Listener::rotunda(self.cam_params.azimuth);
+                    self.audio.play(
+                        CueKind::Tone(info.particle.category_index),
+                        stream.particle_world_pos(info.index),
+                        &listener,
+                    );
+                }
+
+                self.oz_hologram_screen_pos = Some(pos);
+                self.oz_hologram_alpha = 0.0;
+                self.oz_hologram_start = Some(std::time::Instant::now());
+
+                let fetch_url_str = if let Some(ref href) = info.meta.href {
+                    resolve_url(&self.url_input, href)
+                } else {
+                    let query = info.meta.display.trim();
+                    if query.len() > 1 {
+                        self.settings.web_search().url(query)
+                    } else {
+                        String::new()
+                    }
+                };
+
+                if !fetch_url_str.is_empty()
+                    && self.oz_preview_for.as_deref() != Some(&fetch_url_str)
+                {
+                    self.oz_preview_for = Some(fetch_url_str.clone());
+                    self.oz_preview = Some(crate::oz::LinkPreview {
+                        _url: fetch_url_str.clone(),
+                        title: String::new(),
+                        description: String::new(),
+                        texts: Vec::new(),
+                        favicon: None,
+                        status: LinkPreviewStatus::Loading,
+                    });
+                    let (tx, rx) = mpsc::channel();
+                    self.oz_preview_rx = Some(rx);
+                    let url_for_job = fetch_url_str.clone();
+                    let work = &self.page_work;
+                    scheduler::shared().submit(
+                        &fetch_url_str,
+                        Priority::Preview,
+                        work,
+                        move |_| {
+                            let preview = fetch_link_preview(&url_for_job);
+                            let _ = tx.send(preview);
+                        },
+                    );
+                }
+            } else {
+                // Grab failed: clear hologram state
+                self.oz_hologram_screen_pos = None;
+                self.oz_hologram_alpha = 0.0;
+                self.oz_hologram_start = None;
+                self.oz_preview = None;
+                self.oz_preview_for = None;
+                self.oz_preview_rx = None;
+            }
+        }
+    }
+
+    /// Schedule navigation to the grabbed particle's link, if it has one.
+    /// Returns `true` if a navigation was scheduled.
+    #[cfg(feature = "sdf-render")]
+    pub fn oz_open_grabbed(&mut self) -> bool {
+        let Some(ref stream) = self.stream_state else {
+            return false;
+        };
+        let Some(info) = stream.grabbed_info() else {
+            return false;
+        };
+        let Some(ref href) = info.meta.href else {
+            return false;
+        };
+        self.oz_pending_url = Some(href.clone());
+
+        #[cfg(feature = "spatial-audio")]
+        {
+            use alice_browser::audio::{CueKind, Listener};
+            let pos = stream.particle_world_pos(info.index);
+            let listener = Listener::rotunda(self.cam_params.azimuth);
+            self.audio.play(CueKind::Click, pos, &listener);
+        }
+        true
     }
 
     // ── 3-D / OZ raymarched view ─────────────────────────────────────────────
 
+    /// Set up the GPU raymarcher. When eframe runs on a wgpu device that can
+    /// run compute shaders, the raymarcher shares it and frames are drawn
+    /// from its output texture; otherwise it gets a device of its own and
+    /// frames are read back.
+    #[cfg(feature = "sdf-render")]
+    pub fn attach_gpu(&mut self, render_state: Option<&eframe::egui_wgpu::RenderState>) {
+        use alice_browser::render::gpu_renderer::GpuRenderer;
+        use eframe::wgpu::DownlevelFlags;
+
+        if let Some(rs) = render_state {
+            super::oz_gpu::install(rs);
+            self.oz_glyphs = Some(super::oz_gpu::OzGlyphs::default());
+        }
+        let shared = render_state.filter(|rs| {
+            rs.adapter
+                .get_downlevel_capabilities()
+                .flags
+                .contains(DownlevelFlags::COMPUTE_SHADERS | DownlevelFlags::VIEW_FORMATS)
+        });
+        if let Some(rs) = shared {
+            self.gpu_renderer = Some(GpuRenderer::with_device(
+                rs.device.clone(),
+                rs.queue.clone(),
+            ));
+            self.egui_wgpu = Some(rs.renderer.clone());
+            log::info!("GPU raymarcher shares the UI device");
+        } else {
+            self.gpu_renderer = GpuRenderer::new();
+        }
+    }
+
+    /// Raymarch the scene into the GPU output texture at `size` and point
+    /// the UI's texture at it. Returns `false` if the texture path is not
+    /// available, in which case nothing was rendered.
+    #[cfg(feature = "sdf-render")]
+    fn render_gpu_frame(&mut self, size: [u32; 2]) -> bool {
+        let (Some(gpu), Some(egui_wgpu), Some(scene)) = (
+            self.gpu_renderer.as_mut(),
+            self.egui_wgpu.as_ref(),
+            self.spatial_scene.as_ref(),
+        ) else {
+            return false;
+        };
+        let Some(recreated) = gpu.render_to_texture(scene, size[0], size[1], &self.cam_params)
+        else {
+            return false;
+        };
+        self.cull_stats = Some(gpu.culling());
+        let Some(view) = gpu.output_view() else {
+            return false;
+        };
+        let filter = eframe::wgpu::FilterMode::Linear;
+        let mut renderer = egui_wgpu.write();
+        let id = match self.gpu_texture {
+            Some(id) => {
+                if recreated {
+                    renderer.update_egui_texture_from_wgpu_texture(gpu.device(), view, filter, id);
+                }
+                id
+            }
+            None => renderer.register_native_texture(gpu.device(), view, filter),
+        };
+        self.gpu_texture = Some(id);
+        self.sdf_texture = Some(SdfFrame::Gpu(id, size));
+        true
+    }
+
     #[cfg(feature = "sdf-render")]
     #[allow(
         clippy::cast_possible_truncation,
@@ -61,64 +557,168 @@ impl BrowserApp {
         clippy::tuple_array_conversions
     )]
     pub fn draw_sdf_content(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
-        use alice_browser::render::sdf_renderer::{auto_camera, render_sdf_interactive};
-        use std::sync::mpsc;
+        use alice_browser::render::palette::ThemeProvider;
+        use alice_browser::render::sdf_renderer::{
+            auto_camera, fly_bounds, render_sdf_culled, CameraMode, CameraParams,
+        };
+        use alice_browser::render::spatial::{
+            layout_to_spatial, layout_to_spatial_with_sources, SpatialConfig,
+        };
+
+        // Switch palettes when night falls or the theme setting changes;
+        // the Spatial3D scene is recoloured in place, keeping the camera
+        let theme = ThemeProvider {
+            choice: self.settings.scene_theme,
+            dark_mode: self.effective_dark_mode(),
+            ..ThemeProvider::default()
+        }
+        .current();
+        if theme != self.scene_theme {
+            self.scene_theme = theme;
+            if let Some(ref mut gpu) = self.gpu_renderer {
+                gpu.set_sky(theme.palette().sky);
+            }
+            if self.render_mode == RenderMode::Spatial3D {
+                if let (Some(page), Some(_)) = (&self.page, &self.spatial_scene) {
+                    let config = SpatialConfig {
+                        palette: *theme.palette(),
+                        ..SpatialConfig::default()
+                    };
+                    let root = self.focus_3d.as_ref().map_or(&page.layout, |f| &f.root);
+                    self.spatial_scene = Some(layout_to_spatial(root, &config));
+                    if let Some(ref mut gpu) = self.gpu_renderer {
+                        gpu.invalidate();
+                    }
+                }
+            }
+            self.cam_dirty = true;
+        }
 
         // Build spatial scene lazily
         if self.spatial_scene.is_none() {
-            if let Some(ref page) = self.page {
+            if let Some(ref mut page) = self.page {
+                // The 3-D scenes show the whole page at once
+                let start = std::time::Instant::now();
+                if page.complete_layout() {
+                    self.paint_elements = None;
+                }
+                self.frame_profiler.add(FrameStage::Layout, start.elapsed());
+                let start = std::time::Instant::now();
                 if self.render_mode == RenderMode::OzMode {
                     // OZ "The Stream" Mode: cylindrical immersion
-                    let stream =
+                    let mut stream =
                         alice_browser::render::stream::StreamState::from_layout(&page.layout);
+                    // Colour the wall by topic rather than by page section,
+                    // so prefetched texts can join the page's topics
+                    #[cfg(feature = "search")]
+                    {
+                        use alice_browser::render::stream::{StreamState, MAX_TOPICS};
+                        use alice_browser::search::cluster::cluster_texts;
+
+                        self.oz_topics = None;
+                        if stream.text_pool.len() >= OZ_TOPIC_MIN_TEXTS {
+                            let mut pool = stream.text_pool;
+                            let model = cluster_texts(&mut pool, MAX_TOPICS);
+                            stream = StreamState::from_topics(model.names.clone(), pool);
+                            self.oz_topics = Some(model);
+                        }
+                    }
+                    if self.reduce_motion {
+                        stream.arrange_static();
+                    }
                     let scene = stream.to_sdf_scene();
+                    // The page the rotunda showed until now recedes into a
+                    // background galaxy
+                    self.oz_galaxies.enter(
+                        &page.dom.url,
+                        &page.dom.title,
+                        self.stream_state.as_ref(),
+                    );
                     self.cam_params = alice_browser::render::sdf_renderer::CameraParams {
                         azimuth: 0.0,
                         elevation: 0.0,
                         distance: 0.0,
                         target: [0.0, 0.0, 0.0],
+                        ..Default::default()
                     };
                     self.spatial_scene = Some(scene);
+                    self.spatial_pick = super::picking::SpatialPick::default();
+                    self.oz_doc_map = Some(alice_browser::render::docmap::DocMap::from_pool(
+                        &stream.text_pool,
+                    ));
+                    self.oz_focus_section = None;
+                    self.oz_search_query.clear();
                     self.stream_state = Some(stream);
                     self.last_frame_time = std::time::Instant::now();
 
                     // Inject any prefetched texts that arrived while in another mode
                     if !self.oz_prefetch_buffer.is_empty() {
                         if let Some(ref mut ss) = self.stream_state {
-                            ss.append_texts(self.oz_prefetch_buffer.drain(..).collect());
+                            let batch: Vec<_> = self.oz_prefetch_buffer.drain(..).collect();
+                            #[cfg(feature = "search")]
+                            let batch = self
+                                .oz_topics
+                                .as_ref()
+                                .map_or(batch, |topics| topics.labelled(batch));
+                            ss.append_texts(batch);
                         }
                     }
                 } else {
-                    // Spatial3D: Deep Web corridor layout
-                    let scene = alice_browser::render::spatial::layout_to_spatial(
-                        &page.layout,
-                        &alice_browser::render::spatial::SpatialConfig::default(),
-                    );
-                    self.cam_params = auto_camera(&scene);
+                    // Spatial3D: Deep Web corridor layout, of the whole page
+                    // or of the block chosen with "Focus in 3D"
+                    let config = SpatialConfig {
+                        palette: *self.scene_theme.palette(),
+                        ..SpatialConfig::default()
+                    };
+                    let root = self.focus_3d.as_ref().map_or(&page.layout, |f| &f.root);
+                    let (scene, sources) = layout_to_spatial_with_sources(root, &config);
+                    // A new page is framed afresh, in the mode the user chose
+                    self.cam_params = CameraParams {
+                        mode: self.cam_params.mode,
+                        ..auto_camera(&scene)
+                    };
                     self.spatial_scene = Some(scene);
+                    self.spatial_pick = super::picking::SpatialPick::with_sources(sources);
                     self.stream_state = None;
                 }
                 self.cam_dirty = true;
                 if let Some(ref mut gpu) = self.gpu_renderer {
                     gpu.invalidate();
                 }
+                self.frame_profiler
+                    .add(FrameStage::SceneBuild, start.elapsed());
             }
         }
 
-        // OZ mode: update particle flow every frame
+        // OZ mode: update particle flow every frame (frozen in reduced motion)
         if self.render_mode == RenderMode::OzMode {
             if let Some(ref mut stream) = self.stream_state {
-                let now = std::time::Instant::now();
-                let dt = (now - self.last_frame_time).as_secs_f32().min(0.1);
-                self.last_frame_time = now;
-                stream.update_flow(dt);
-                ctx.request_repaint();
+                if !self.reduce_motion {
+                    let now = std::time::Instant::now();
+                    let dt = (now - self.last_frame_time).as_secs_f32().min(0.1);
+                    self.last_frame_time = now;
+                    stream.update_flow(dt * self.oz_flow_speed);
+                    self.oz_galaxy_time += dt;
+                    self.frame_profiler
+                        .add(FrameStage::Particles, now.elapsed());
+
+                    #[cfg(feature = "spatial-audio")]
+                    if let Some(&i) = stream.spawned().first() {
+                        let listener =
+                            alice_browser::audio::Listener::rotunda(self.cam_params.azimuth);
+                        self.audio.tick(stream.particle_world_pos(i), &listener);
+                    }
+                    ctx.request_repaint();
+                }
             }
 
-            // Animate hologram fade-in
+            // Animate hologram fade-in (instant in reduced motion)
             if let Some(start) = self.oz_hologram_start {
-                let elapsed = start.elapsed().as_secs_f32();
-                self.oz_hologram_alpha = (elapsed / 0.3).clamp(0.0, 1.0);
+                self.oz_hologram_alpha = if self.reduce_motion {
+                    1.0
+                } else {
+                    (start.elapsed().as_secs_f32() / 0.3).clamp(0.0, 1.0)
+                };
             }
         }
 
@@ -137,172 +737,312 @@ impl BrowserApp {
                     .y
                     .mul_add(0.005, self.cam_params.elevation)
                     .clamp(-0.8, 0.8);
+
+                // The wall sweeps past as fast as the view turns
+                #[cfg(feature = "spatial-audio")]
+                {
+                    let dt = ui.input(|i| i.stable_dt).max(1e-3);
+                    self.audio.flow(delta.x * 0.005 / dt);
+                }
             }
 
-            // OZ: click to grab nearest text
+            // OZ: click to grab nearest text (or focus a heading ring)
             if response.clicked() {
                 if let Some(pos) = response.interact_pointer_pos() {
-                    let rect = response.rect;
-                    let fov: f32 = 110.0_f32.to_radians();
-                    let fov_h = fov * 0.5;
-                    let aspect = rect.width() / rect.height();
-                    let fov_v = fov_h / aspect;
-
-                    let ndc_x = (pos.x - rect.center().x) / (rect.width() * 0.5);
-                    let ndc_y = (pos.y - rect.center().y) / (rect.height() * 0.5);
-
-                    if let Some(ref mut stream) = self.stream_state {
-                        stream.try_grab_screen(
-                            ndc_x,
-                            ndc_y,
-                            self.cam_params.azimuth,
-                            self.cam_params.elevation,
-                            fov_h,
-                            fov_v,
-                            aspect,
-                        );
-
-                        if let Some(info) = stream.grabbed_info() {
-                            self.oz_hologram_screen_pos = Some(pos);
-                            self.oz_hologram_alpha = 0.0;
-                            self.oz_hologram_start = Some(std::time::Instant::now());
-
-                            let fetch_url_str = if let Some(ref href) = info.meta.href {
-                                resolve_url(&self.url_input, href)
-                            } else {
-                                let query = info.meta.display.trim().to_string();
-                                if query.len() > 1 {
-                                    format!(
-                                        "https://www.google.com/search?q={}",
-                                        query.replace(' ', "+")
-                                    )
-                                } else {
-                                    String::new()
-                                }
-                            };
-
-                            if !fetch_url_str.is_empty()
-                                && self.oz_preview_for.as_deref() != Some(&fetch_url_str)
-                            {
-                                self.oz_preview_for = Some(fetch_url_str.clone());
-                                self.oz_preview = Some(crate::oz::LinkPreview {
-                                    _url: fetch_url_str.clone(),
-                                    title: String::new(),
-                                    description: String::new(),
-                                    texts: Vec::new(),
-                                    status: LinkPreviewStatus::Loading,
-                                });
-                                let (tx, rx) = mpsc::channel();
-                                self.oz_preview_rx = Some(rx);
-                                let url_for_thread = fetch_url_str;
-                                std::thread::spawn(move || {
-                                    let preview = fetch_link_preview(&url_for_thread);
-                                    let _ = tx.send(preview);
-                                });
-                            }
-                        } else {
-                            // Grab failed: clear hologram state
-                            self.oz_hologram_screen_pos = None;
-                            self.oz_hologram_alpha = 0.0;
-                            self.oz_hologram_start = None;
-                            self.oz_preview = None;
-                            self.oz_preview_for = None;
-                            self.oz_preview_rx = None;
-                        }
-                    }
+                    self.oz_click_at(pos, response.rect);
                 }
             }
 
             // OZ: double-click on grabbed link → schedule navigation
             if response.double_clicked() {
-                if let Some(ref stream) = self.stream_state {
-                    if let Some(info) = stream.grabbed_info() {
-                        if let Some(ref href) = info.meta.href {
-                            self.oz_pending_url = Some(href.clone());
-                        }
-                    }
-                }
+                self.oz_open_grabbed();
             }
         } else {
-            // Spatial3D: drag to orbit camera around scene
+            let flying = self.cam_params.mode == CameraMode::Fly;
+
+            // Spatial3D: drag to orbit camera around scene, or to look
+            // around when flying
             if response.dragged() {
                 let delta = response.drag_delta();
-                self.cam_params.azimuth += delta.x * 0.008;
-                self.cam_params.elevation = delta
-                    .y
-                    .mul_add(-0.008, self.cam_params.elevation)
-                    .clamp(0.05, std::f32::consts::FRAC_PI_2 - 0.05);
+                if flying {
+                    self.cam_params.look(-delta.x * 0.004, delta.y * 0.004);
+                } else {
+                    self.cam_params.azimuth += delta.x * 0.008;
+                    self.cam_params.elevation = delta
+                        .y
+                        .mul_add(-0.008, self.cam_params.elevation)
+                        .clamp(0.05, std::f32::consts::FRAC_PI_2 - 0.05);
+                }
                 self.cam_dirty = true;
                 self.cam_dragging = true;
             } else {
                 self.cam_dragging = false;
             }
 
+            // Fly: WASD to move, E/Q to rise and sink, Shift to hurry
+            if flying && !ctx.wants_keyboard_input() {
+                if let Some(ref scene) = self.spatial_scene {
+                    let (ahead, right, up, fast, dt) = ui.input(|i| {
+                        let axis = |plus, minus| {
+                            f32::from(u8::from(i.key_down(plus)))
+                                - f32::from(u8::from(i.key_down(minus)))
+                        };
+                        (
+                            axis(egui::Key::W, egui::Key::S),
+                            axis(egui::Key::D, egui::Key::A),
+                            axis(egui::Key::E, egui::Key::Q),
+                            i.modifiers.shift,
+                            i.stable_dt.min(0.1),
+                        )
+                    });
+                    if ahead != 0.0 || right != 0.0 || up != 0.0 {
+                        let bounds = fly_bounds(scene);
+                        let size = (0..3)
+                            .map(|i| bounds.1[i] - bounds.0[i])
+                            .fold(0.0, f32::max);
+                        let step = size / 4.0 * if fast { 3.0 } else { 1.0 } * dt;
+                        self.cam_params
+                            .fly(ahead * step, right * step, up * step, bounds);
+                        self.cam_dirty = true;
+                        self.cam_dragging = true;
+                        ctx.request_repaint();
+                    }
+                }
+            }
+
+            self.hover_spatial(ui, &response);
+
+            // Link portal: click to fly through it to the linked page
+            self.click_spatial_portal(ctx, &response);
+            self.step_portal_flight(ctx);
+
+            // Site map orbital system: click a planet or satellite to open it
+            if response.clicked() && self.page.is_none() {
+                let url = response
+                    .interact_pointer_pos()
+                    .and_then(|pos| self.site_map_target_at(pos, response.rect));
+                if let Some(url) = url {
+                    self.url_input = url;
+                    self.navigate(ctx);
+                }
+            }
+
             // Scroll to dolly in/out (zoom)
             if response.hovered() {
                 let scroll = ui.input(|i| i.raw_scroll_delta.y);
                 if scroll.abs() > 0.1 {
-                    self.cam_params.distance *= scroll.mul_add(-0.003, 1.0);
-                    self.cam_params.distance = self.cam_params.distance.clamp(0.2, 100.0);
+                    if flying {
+                        if let Some(ref scene) = self.spatial_scene {
+                            let step = scroll * 0.003 * self.cam_params.distance;
+                            self.cam_params.fly(step, 0.0, 0.0, fly_bounds(scene));
+                        }
+                    } else {
+                        self.cam_params.distance *= scroll.mul_add(-0.003, 1.0);
+                        self.cam_params.distance = self.cam_params.distance.clamp(0.2, 100.0);
+                    }
                     self.cam_dirty = true;
+
+                    #[cfg(feature = "spatial-audio")]
+                    self.audio.whoosh(scroll.abs() / 60.0);
                 }
             }
         }
 
+        // Reduced motion: no progressive low-res frames while dragging —
+        // render once, at full resolution, when the drag ends.
+        let hold_render = self.reduce_motion && self.cam_dragging && self.sdf_texture.is_some();
+
+        // With a shared GPU device, frames are rendered at the viewport's
+        // full resolution, orbiting included, and drawn without a readback
+        let gpu_size = self.egui_wgpu.as_ref().map(|_| {
+            let px = response.rect.size() * ctx.pixels_per_point();
+            [
+                (px.x.round() as u32).clamp(1, 4096),
+                (px.y.round() as u32).clamp(1, 4096),
+            ]
+        });
+        let resized = matches!(
+            (&self.sdf_texture, gpu_size),
+            (Some(SdfFrame::Gpu(_, size)), Some(want)) if *size != want
+        );
+
         // Raymarch render (Spatial3D only — OZ uses egui overlay)
-        if self.render_mode != RenderMode::OzMode && (self.cam_dirty || self.sdf_texture.is_none())
+        if self.render_mode != RenderMode::OzMode
+            && !hold_render
+            && (self.cam_dirty || resized || self.sdf_texture.is_none())
         {
-            if let Some(ref scene) = self.spatial_scene {
+            let start = std::time::Instant::now();
+            if gpu_size.is_some_and(|size| self.render_gpu_frame(size)) {
+                // Thumbnails still need pixels: read one frame back
+                if !self.cam_dragging {
+                    if let Some(url) = self.thumb_capture_pending.clone() {
+                        let (w, h) = (640, 480);
+                        let pixels = self.gpu_renderer.as_mut().zip(self.spatial_scene.as_ref());
+                        if let Some(pixels) = pixels
+                            .and_then(|(gpu, scene)| gpu.render(scene, w, h, &self.cam_params))
+                        {
+                            self.store_thumbnail(&url, &pixels, w, h);
+                        }
+                    }
+                }
+                self.sdf_mode_rendered = Some(self.render_mode);
+                self.cam_dirty = false;
+                if self.cam_dragging {
+                    ctx.request_repaint();
+                }
+            } else if let Some(ref scene) = self.spatial_scene {
                 let has_gpu = self.gpu_renderer.is_some();
-                let (w, h) = if self.cam_dragging {
-                    if has_gpu {
+                if !has_gpu && !self.cam_dragging {
+                    // CPU: refine in the background; the frame on screen
+                    // stays up until sharper passes arrive
+                    let first_stride = if self.sdf_texture.is_some() { 2 } else { 4 };
+                    self.refiner.start(
+                        scene.clone(),
+                        640,
+                        480,
+                        self.cam_params,
+                        self.scene_theme.palette().sky,
+                        first_stride,
+                    );
+                } else {
+                    self.refiner.cancel();
+                    self.sdf_fade = None;
+                    let (w, h) = if !has_gpu {
+                        (240, 180)
+                    } else if self.cam_dragging {
                         (640, 480)
                     } else {
-                        (240, 180)
-                    }
-                } else if has_gpu {
-                    (1280, 960)
-                } else {
-                    (640, 480)
-                };
+                        (1280, 960)
+                    };
+
+                    let rendered = self
+                        .gpu_renderer
+                        .as_mut()
+                        .and_then(|gpu| {
+                            let pixels = gpu.render(scene, w, h, &self.cam_params)?;
+                            Some((pixels, gpu.culling()))
+                        })
+                        .or_else(|| {
+                            render_sdf_culled(
+                                scene,
+                                w,
+                                h,
+                                &self.cam_params,
+                                &self.scene_theme.palette().sky,
+                            )
+                        });
 
-                let pixels = self
-                    .gpu_renderer
-                    .as_mut()
-                    .and_then(|gpu| gpu.render(scene, w, h, &self.cam_params))
-                    .or_else(|| render_sdf_interactive(scene, w, h, &self.cam_params));
-
-                if let Some(pixels) = pixels {
-                    let image = egui::ColorImage::from_rgba_unmultiplied([w, h], &pixels);
-                    self.sdf_texture =
-                        Some(ctx.load_texture("sdf_view", image, egui::TextureOptions::LINEAR));
-                    self.sdf_mode_rendered = Some(self.render_mode);
+                    if let Some((pixels, culling)) = rendered {
+                        self.cull_stats = Some(culling);
+                        // Offscreen thumbnail straight from the raymarch output
+                        if !self.cam_dragging {
+                            if let Some(url) = self.thumb_capture_pending.clone() {
+                                self.store_thumbnail(&url, &pixels, w, h);
+                            }
+                        }
+                        let image = egui::ColorImage::from_rgba_unmultiplied([w, h], &pixels);
+                        self.sdf_texture = Some(SdfFrame::Image(ctx.load_texture(
+                            "sdf_view",
+                            image,
+                            egui::TextureOptions::LINEAR,
+                        )));
+                        self.sdf_mode_rendered = Some(self.render_mode);
+                    }
                 }
                 self.cam_dirty = false;
                 if self.cam_dragging {
                     ctx.request_repaint();
                 }
             }
+            self.frame_profiler
+                .add(FrameStage::Raymarch, start.elapsed());
+        }
+
+        // Sharper passes of a background render cross-fade over the frame
+        // they replace (or swap in at once in reduced motion)
+        if let Some(frame) = self.refiner.poll() {
+            self.cull_stats = Some(frame.culling);
+            if frame.is_final() {
+                if let Some(url) = self.thumb_capture_pending.clone() {
+                    self.store_thumbnail(&url, &frame.pixels, frame.width, frame.height);
+                }
+            }
+            let image = egui::ColorImage::from_rgba_unmultiplied(
+                [frame.width, frame.height],
+                &frame.pixels,
+            );
+            let texture = ctx.load_texture("sdf_view", image, egui::TextureOptions::LINEAR);
+            let previous = self.sdf_texture.replace(SdfFrame::Image(texture));
+            self.sdf_fade = previous
+                .filter(|_| !self.reduce_motion)
+                .map(|previous| (previous, std::time::Instant::now()));
+            self.sdf_mode_rendered = Some(self.render_mode);
+        }
+        if self.refiner.is_running() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(16));
         }
 
         // Draw background
+        let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
         if self.render_mode == RenderMode::OzMode {
             ui.painter()
                 .rect_filled(response.rect, 0.0, egui::Color32::WHITE);
-        } else if let Some(ref tex) = self.sdf_texture {
+        } else if let Some(ref frame) = self.sdf_texture {
+            let mut alpha = 1.0;
+            if let Some((ref previous, start)) = self.sdf_fade {
+                alpha = (start.elapsed().as_secs_f32() / SDF_FADE_SECS).min(1.0);
+                ui.painter()
+                    .image(previous.id(), response.rect, uv, egui::Color32::WHITE);
+            }
             ui.painter().image(
-                tex.id(),
+                frame.id(),
                 response.rect,
-                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                egui::Color32::WHITE,
+                uv,
+                egui::Color32::from_white_alpha((alpha * 255.0) as u8),
             );
+            if alpha < 1.0 {
+                ctx.request_repaint();
+            } else {
+                self.sdf_fade = None;
+            }
         } else {
             ui.colored_label(egui::Color32::GRAY, "SDF scene is empty");
         }
+        if self.render_mode != RenderMode::OzMode {
+            // Orbit / fly toggle, over the scene's top-left corner
+            let flying = self.cam_params.mode == CameraMode::Fly;
+            let toggle = egui::Rect::from_min_size(
+                response.rect.left_top() + egui::vec2(8.0, 8.0),
+                egui::vec2(64.0, 22.0),
+            );
+            let (label, hover) = if flying {
+                (
+                    "\u{2708} Fly",
+                    "Flying: WASD to move, E/Q up and down, drag to look. Click to orbit",
+                )
+            } else {
+                (
+                    "\u{21BB} Orbit",
+                    "Orbiting: drag to turn, scroll to zoom. Click to fly",
+                )
+            };
+            if ui
+                .put(toggle, egui::Button::new(label))
+                .on_hover_text(hover)
+                .clicked()
+            {
+                self.cam_params.mode = if flying {
+                    CameraMode::Orbit
+                } else {
+                    CameraMode::Fly
+                };
+            }
+        }
 
         // OZ Rotunda: perspective-project cylinder wall text onto screen
         if self.render_mode == RenderMode::OzMode {
             if let Some(ref stream) = self.stream_state {
+                use super::oz_gpu::OzLabel;
                 use alice_browser::render::stream::StreamState;
 
                 let rect = response.rect;
@@ -313,15 +1053,148 @@ impl BrowserApp {
                 let aspect = rect.width() / rect.height();
                 let cam_az = self.cam_params.azimuth;
                 let cam_el = self.cam_params.elevation;
-                let time = stream.time;
 
                 let sin_az = cam_az.sin();
                 let cos_az = cam_az.cos();
                 let sin_el = cam_el.sin();
                 let cos_el = cam_el.cos();
 
-                for p in &stream.particles {
-                    let world = StreamState::particle_world_pos(p, time);
+                // ── Past pages: dim galaxies beyond the wall ──
+                {
+                    use alice_browser::render::docmap::project;
+                    use alice_browser::render::galaxy::Galaxies;
+
+                    let to_screen = |world: [f32; 3]| {
+                        let (nx, ny, depth) = project(world, cam_az, cam_el, tan_fov_h, aspect)?;
+                        if nx.abs() > 1.3 || ny.abs() > 1.3 {
+                            return None;
+                        }
+                        Some((
+                            egui::pos2(
+                                (nx * rect.width()).mul_add(0.5, rect.center().x),
+                                (ny * rect.height()).mul_add(0.5, rect.center().y),
+                            ),
+                            depth,
+                        ))
+                    };
+                    let hovered = response.hover_pos().and_then(|pos| {
+                        self.oz_galaxies
+                            .pick([pos.x, pos.y], 24.0, self.oz_galaxy_time, |w| {
+                                to_screen(w).map(|(p, _)| [p.x, p.y])
+                            })
+                            .map(|g| g.url.clone())
+                    });
+                    for (k, galaxy) in self.oz_galaxies.iter().enumerate() {
+                        let placement = Galaxies::placement(k, self.oz_galaxy_time);
+                        let lit = hovered.as_deref() == Some(galaxy.url.as_str());
+                        let opacity = if lit { 0.9 } else { placement.opacity };
+                        for star in &galaxy.stars {
+                            let Some((pos, depth)) = to_screen(placement.star_pos(star)) else {
+                                continue;
+                            };
+                            let c = galaxy.color(star);
+                            let size =
+                                (star.importance.mul_add(8.0, 6.0) * 16.0 / depth).clamp(5.0, 14.0);
+                            painter.text(
+                                pos,
+                                egui::Align2::CENTER_CENTER,
+                                crate::ui::truncate_str(&star.text, 16),
+                                egui::FontId::proportional(size),
+                                egui::Color32::from_rgba_unmultiplied(
+                                    (c[0] * 255.0) as u8,
+                                    (c[1] * 255.0) as u8,
+                                    (c[2] * 255.0) as u8,
+                                    (opacity * 255.0) as u8,
+                                ),
+                            );
+                        }
+                        if let Some((pos, _)) = to_screen(placement.center) {
+                            let title = if galaxy.title.is_empty() {
+                                &galaxy.url
+                            } else {
+                                &galaxy.title
+                            };
+                            painter.text(
+                                pos + egui::vec2(0.0, placement.radius * 4.0),
+                                egui::Align2::CENTER_TOP,
+                                crate::ui::truncate_str(title, 40),
+                                egui::FontId::proportional(if lit { 13.0 } else { 11.0 }),
+                                egui::Color32::from_white_alpha((opacity * 200.0) as u8),
+                            );
+                        }
+                    }
+                    if hovered.is_some() {
+                        ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                    }
+                }
+
+                // ── Document map: faint heading rings below the stream ──
+                if let Some(ref map) = self.oz_doc_map {
+                    use alice_browser::render::docmap::{project, DocMap};
+
+                    let to_screen = |world: [f32; 3]| {
+                        let (nx, ny, depth) = project(world, cam_az, cam_el, tan_fov_h, aspect)?;
+                        if nx.abs() > 1.5 || ny.abs() > 1.5 {
+                            return None;
+                        }
+                        Some((
+                            egui::pos2(
+                                (nx * rect.width()).mul_add(0.5, rect.center().x),
+                                (ny * rect.height()).mul_add(0.5, rect.center().y),
+                            ),
+                            depth,
+                        ))
+                    };
+
+                    for (si, section) in map.sections.iter().enumerate() {
+                        let focused = self.oz_focus_section == Some(si);
+                        let alpha: u8 = if focused { 170 } else { 45 };
+                        let stroke = egui::Stroke::new(
+                            if focused { 3.0 } else { 1.5 },
+                            egui::Color32::from_rgba_unmultiplied(60, 80, 120, alpha),
+                        );
+
+                        // Leave a small gap between neighbouring arcs
+                        let gap = 0.02;
+                        let a0 = section.start_angle + gap;
+                        let a1 = (section.end_angle - gap).max(a0);
+                        let steps = (((a1 - a0) / 0.05) as usize).clamp(2, 96);
+                        let points: Vec<Option<egui::Pos2>> = (0..=steps)
+                            .map(|k| {
+                                let a = (a1 - a0).mul_add(k as f32 / steps as f32, a0);
+                                to_screen(DocMap::ring_point(section.level, a)).map(|(p, _)| p)
+                            })
+                            .collect();
+                        for pair in points.windows(2) {
+                            if let [Some(from), Some(to)] = pair {
+                                painter.line_segment([*from, *to], stroke);
+                            }
+                        }
+
+                        if let Some((label_pos, depth)) =
+                            to_screen(DocMap::ring_point(section.level, section.mid_angle()))
+                        {
+                            let size = (9.0 * 8.0 / depth).clamp(8.0, 14.0);
+                            painter.text(
+                                label_pos,
+                                egui::Align2::CENTER_BOTTOM,
+                                crate::ui::truncate_str(&section.title, 28),
+                                egui::FontId::proportional(size),
+                                egui::Color32::from_rgba_unmultiplied(
+                                    60,
+                                    80,
+                                    120,
+                                    alpha.saturating_add(40),
+                                ),
+                            );
+                        }
+                    }
+                }
+
+                // Labels for the GPU path; painted by egui when it is off
+                let mut labels = self.oz_glyphs.as_ref().map(|_| Vec::new());
+                for (i, p) in stream.particles.iter().enumerate() {
+                    let world = stream.particle_world_pos(i);
 
                     let wx = world[0];
                     let wy = world[1];
@@ -357,7 +1230,7 @@ impl BrowserApp {
                         .get(p.category_index)
                         .map_or([0.3, 0.3, 0.3, 1.0], |c| c.color);
 
-                    let alpha = StreamState::particle_opacity(p);
+                    let alpha = stream.particle_opacity(i) * stream.search_dim(i);
                     if alpha < 0.01 {
                         continue;
                     }
@@ -376,13 +1249,23 @@ impl BrowserApp {
                     let a = (alpha * 255.0) as u8;
                     let color = egui::Color32::from_rgba_unmultiplied(r, g, b, a);
 
-                    painter.text(
-                        egui::pos2(sx, sy),
-                        egui::Align2::CENTER_CENTER,
-                        &p.text,
-                        egui::FontId::proportional(font_size),
-                        color,
-                    );
+                    if let Some(ref mut labels) = labels {
+                        labels.push(OzLabel::new(
+                            &painter,
+                            &p.text,
+                            egui::pos2(sx, sy),
+                            font_size,
+                            color,
+                        ));
+                    } else {
+                        painter.text(
+                            egui::pos2(sx, sy),
+                            egui::Align2::CENTER_CENTER,
+                            &p.text,
+                            egui::FontId::proportional(font_size),
+                            color,
+                        );
+                    }
 
                     // Grabbed: highlight background
                     if p.grabbed {
@@ -404,6 +1287,10 @@ impl BrowserApp {
                     }
                 }
 
+                if let (Some(labels), Some(glyphs)) = (labels, self.oz_glyphs.as_mut()) {
+                    glyphs.paint(&painter, rect, &labels);
+                }
+
                 // ── Hologram Overlay ──────────────────────────────────────────
                 if let Some(info) = stream.grabbed_info() {
                     let holo_alpha = self.oz_hologram_alpha;
@@ -818,18 +1705,35 @@ impl BrowserApp {
 
     /// Render the central content panel.
     pub fn draw_content(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
-        if self.loading {
+        // A soft reload keeps the page on screen until its copy arrives
+        if self.loading && !(self.reloading && self.page.is_some()) {
             ui.centered_and_justified(|ui| {
                 ui.spinner();
             });
             return;
         }
 
+        if self.draw_tls_interstitial(ui, ctx) {
+            return;
+        }
+        self.draw_wayback_offer(ui, ctx);
         if let Some(ref error) = self.error {
             ui.colored_label(egui::Color32::RED, error);
             return;
         }
 
+        if self.page.is_none() && self.draw_discarded(ui, ctx) {
+            return;
+        }
+
+        self.reflow_page(ui.available_width());
+
+        // Reader view (main article only)
+        if self.render_mode == RenderMode::Reader && self.page.is_some() {
+            self.draw_reader(ui, ctx);
+            return;
+        }
+
         // SDF Paint mode (interactive 2-D)
         if self.render_mode == RenderMode::Sdf2D && self.page.is_some() {
             let clicked = self.draw_sdf_paint(ui, ctx);
@@ -841,16 +1745,27 @@ impl BrowserApp {
             return;
         }
 
-        // Raymarched 3-D mode (Spatial3D or OzMode)
+        // Raymarched 3-D mode (Spatial3D or OzMode); a scene opened from
+        // the gallery has no page
         #[cfg(feature = "sdf-render")]
         if (self.render_mode == RenderMode::Spatial3D || self.render_mode == RenderMode::OzMode)
-            && self.page.is_some()
+            && (self.page.is_some() || self.spatial_scene.is_some())
         {
+            self.draw_focus_bar(ui);
+            if self.render_mode == RenderMode::OzMode {
+                self.draw_oz_search_bar(ui);
+            }
             self.draw_sdf_content(ui, ctx);
             return;
         }
 
         if let Some(ref page) = self.page {
+            // JSON, CSV, XML and plain text get their own viewer
+            if let Some(ref view) = page.text_view {
+                crate::ui::text_view::render_text_view(ui, view, &mut self.wrap_text);
+                return;
+            }
+
             // Page title
             if !page.dom.title.is_empty() {
                 ui.heading(&page.dom.title);
@@ -861,23 +1776,94 @@ impl BrowserApp {
             let base_url = page.dom.url.clone();
 
             #[cfg(feature = "search")]
-            let highlight = if self.search_query.is_empty() {
-                None
-            } else {
-                Some(self.search_query.as_str())
+            let find = Find {
+                query: (!self.search_query.is_empty()).then_some(self.search_query.as_str()),
+                current: self.find.current_node(&page.layout),
+                scroll_to_current: std::mem::take(&mut self.find.scroll_pending),
+                ..Find::default()
             };
             #[cfg(not(feature = "search"))]
-            let highlight: Option<&str> = None;
+            let find = Find::default();
+
+            // Keyboard focus ring: links through `find`, fields through `forms`
+            let keyboard = self.keyboard.focused.as_ref();
+            let scroll_to_focused = std::mem::take(&mut self.keyboard.scroll_pending);
+            // Link hints: links through `find`, fields through `forms`
+            let hints = self.keyboard.hints.as_ref();
+            let hint_typed = hints.map_or(0, |h| h.typed.len());
+            let link_hints: Vec<(&LayoutNode, String)> = hints
+                .into_iter()
+                .flat_map(HintMode::matching)
+                .filter(|(_, t)| matches!(t.kind, FocusKind::Link(_)))
+                .filter_map(|(code, t)| Some((t.node(&page.layout)?, code.clone())))
+                .collect();
+            let anchor = self.pending_anchor.take();
+            let find = Find {
+                focused: keyboard.and_then(|t| t.node(&page.layout)),
+                scroll_to_focused,
+                hints: &link_hints,
+                hint_typed,
+                feedback: self.show_removed,
+                anchor: anchor.as_deref().and_then(|id| page.layout.find_anchor(id)),
+                media: Some(&self.media.meta),
+                inspected: self.dom_inspector.node(page),
+                scroll_to_inspected: std::mem::take(&mut self.dom_inspector.scroll_pending),
+                layout_boxes: self.show_layout_boxes,
+                ..find
+            };
 
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                render_layout_node(ui, &page.layout, 0, &mut clicked_link, highlight);
+            let forms = &mut self.forms;
+            forms.set_profiles(&self.autofill.profiles);
+            forms.begin_frame();
+            forms.focused = keyboard.and_then(|t| match t.kind {
+                FocusKind::Control(idx) => Some(idx),
+                FocusKind::Link(_) => None,
+            });
+            forms.scroll_to_focused = scroll_to_focused;
+            forms.hints = hints
+                .into_iter()
+                .flat_map(HintMode::matching)
+                .filter_map(|(code, t)| match t.kind {
+                    FocusKind::Control(idx) => Some((idx, code.clone())),
+                    FocusKind::Link(_) => None,
+                })
+                .collect();
+            forms.hint_typed = hint_typed;
+            let zoom = page.zoom;
+            let scroll = egui::ScrollArea::vertical().show(ui, |ui| {
+                for font in ui.style_mut().text_styles.values_mut() {
+                    font.size *= zoom;
+                }
+                render_layout_node(ui, &page.layout, 0, &mut clicked_link, &find, forms);
             });
+            let submission = forms.end_frame(&base_url);
+
+            // Widgets don't sit at their layout positions, so map the
+            // scrolled share of the content onto the layout
+            let content = scroll.content_size.y.max(1.0);
+            let to_layout = page.layout.bounds.height / content;
+            let top = scroll.state.offset.y * to_layout;
+            let bottom = (scroll.state.offset.y + scroll.inner_rect.height()) * to_layout;
+            self.keyboard.view = (top, bottom);
+            self.extend_page_layout(top, bottom);
+            self.load_visible_images(top, bottom);
+
+            // A page back from the back/forward cache returns to where it
+            // was read, once its content has been measured
+            if let Some(y) = self.restore_scroll.take() {
+                let mut state = scroll.state;
+                state.offset.y = y / to_layout;
+                state.store(ctx, scroll.id);
+                ctx.request_repaint();
+            }
 
             // Navigate to clicked link
             if let Some(href) = clicked_link {
                 let resolved = resolve_url(&base_url, &href);
                 self.url_input = resolved;
                 self.navigate(ctx);
+            } else if let Some(submission) = submission {
+                self.submit_form(submission, ctx);
             }
         } else {
             ui.centered_and_justified(|ui| {
@@ -896,7 +1882,7 @@ impl BrowserApp {
 
     /// Render the right-side statistics panel.
     #[allow(clippy::cast_precision_loss, clippy::too_many_lines)]
-    pub fn draw_stats_panel(&self, ui: &mut egui::Ui) {
+    pub fn draw_stats_panel(&mut self, ui: &mut egui::Ui) {
         if let Some(ref page) = self.page {
             let stats = &page.filter_stats;
 
@@ -920,15 +1906,55 @@ impl BrowserApp {
                 egui::Color32::from_rgb(100, 150, 255),
                 format!("Navigation: {}", stats.nav_nodes),
             );
+            ui.weak(format!("Classifier: {}", page.decisions.classifier));
 
             ui.separator();
-            ui.label(format!("Removed: {} nodes", stats.removed_nodes));
+            let allowlisted = page.decisions.allowlisted;
+            if allowlisted {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 160, 0),
+                    "Blocking is off on this site",
+                );
+                ui.label(format!("Would remove: {} nodes", stats.removed_nodes));
+            } else {
+                ui.label(format!("Removed: {} nodes", stats.removed_nodes));
+            }
 
             if stats.total_nodes > 0 {
                 let pct = (stats.removed_nodes as f32 / stats.total_nodes as f32) * 100.0;
                 ui.label(format!("Reduction: {pct:.1}%"));
             }
 
+            // What blocking takes out of the page, as a diff against what is shown
+            let removed = &page.decisions.removed;
+            if !removed.is_empty() {
+                let title = if allowlisted {
+                    format!("Would be removed ({})", removed.len())
+                } else {
+                    format!("Removed ({})", removed.len())
+                };
+                egui::CollapsingHeader::new(title)
+                    .id_salt("removed_diff")
+                    .default_open(allowlisted)
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .id_salt("removed_diff_scroll")
+                            .max_height(200.0)
+                            .show(ui, |ui| {
+                                for removal in removed {
+                                    ui.colored_label(
+                                        egui::Color32::from_rgb(255, 80, 80),
+                                        format!("\u{2212} {}", removal.path),
+                                    )
+                                    .on_hover_text(removal.describe());
+                                    if !removal.excerpt.is_empty() {
+                                        ui.weak(format!("  {}", removal.excerpt));
+                                    }
+                                }
+                            });
+                    });
+            }
+
             ui.separator();
             ui.heading("Page Info");
             ui.label(format!("Title: {}", page.dom.title));
@@ -948,6 +1974,7 @@ impl BrowserApp {
                         RenderMode::Sdf2D => "ALICE-SDF 2D",
                         RenderMode::Spatial3D => "ALICE-SDF 3D",
                         RenderMode::OzMode => "OZ Orbital",
+                        RenderMode::Reader => "Off (Reader)",
                     }
                 ));
                 if self.render_mode == RenderMode::Spatial3D
@@ -956,20 +1983,37 @@ impl BrowserApp {
                     if let Some(ref scene) = self.spatial_scene {
                         ui.label(format!("3D Primitives: {}", scene.primitives.len()));
                     }
-                    let res = if self.cam_dragging {
-                        "240x180"
-                    } else {
-                        "640x480"
-                    };
-                    if self.sdf_texture.is_some() {
+                    if let Some(culling) = self.cull_stats {
+                        ui.label(format!(
+                            "{:.0}% of primitives rendered",
+                            culling.percent_rendered()
+                        ))
+                        .on_hover_text(format!(
+                            "{} of {}: {} outside the view, {} hidden behind walls",
+                            culling.rendered(),
+                            culling.total,
+                            culling.outside_view,
+                            culling.occluded
+                        ));
+                    }
+                    if let Some(ref frame) = self.sdf_texture {
+                        let [w, h] = frame.size();
+                        let path = match frame {
+                            SdfFrame::Gpu(..) => " (GPU texture)",
+                            SdfFrame::Image(_) => "",
+                        };
                         ui.colored_label(
                             egui::Color32::from_rgb(0, 180, 0),
-                            format!("Raymarched: {res}"),
+                            format!("Raymarched: {w}x{h}{path}"),
                         );
                     }
                     ui.label(format!("Cam dist: {:.2}", self.cam_params.distance));
-                } else if self.sdf_texture.is_some() {
-                    ui.colored_label(egui::Color32::from_rgb(0, 180, 0), "Raymarched: 640x480");
+                } else if let Some(ref frame) = self.sdf_texture {
+                    let [w, h] = frame.size();
+                    ui.colored_label(
+                        egui::Color32::from_rgb(0, 180, 0),
+                        format!("Raymarched: {w}x{h}"),
+                    );
                 }
             }
         }
@@ -982,6 +2026,13 @@ impl BrowserApp {
             if !self.search_query.is_empty() {
                 ui.label(format!("Query: \"{}\"", self.search_query));
                 ui.label(format!("Matches: {}", idx.count(&self.search_query)));
+                if !self.find.matches.is_empty() {
+                    ui.label(format!(
+                        "Current: {} of {}",
+                        self.find.current + 1,
+                        self.find.matches.len()
+                    ));
+                }
             }
         }
 
@@ -996,6 +2047,67 @@ impl BrowserApp {
             ));
         }
 
+        #[cfg(feature = "sdf-render")]
+        {
+            let stats = self.prefetch_policy.stats();
+            ui.separator();
+            ui.heading("Link prefetch");
+            if ui
+                .checkbox(&mut self.settings.prefetch_links, "Prefetch links")
+                .on_hover_text("Fetch a page's links in the background for the OZ stream")
+                .changed()
+            {
+                self.settings.save();
+            }
+            ui.label(format!(
+                "Fetched: {} ({} failed)",
+                stats.fetched, stats.failed
+            ));
+            if stats.in_flight > 0 {
+                ui.label(format!("In flight: {}", stats.in_flight));
+            }
+            ui.label(format!("Already fetched: {}", stats.duplicates));
+            ui.label(format!("Blocked by robots.txt: {}", stats.disallowed));
+            ui.label(format!(
+                "Deferred: {} ({} \u{00D7} 429/503)",
+                stats.deferred, stats.throttled
+            ))
+            .on_hover_text("Skipped while a site backs off or asks for a long crawl delay");
+            if !stats.waited.is_zero() {
+                ui.label(format!(
+                    "Crawl delay waited: {:.1} s",
+                    stats.waited.as_secs_f32()
+                ));
+            }
+        }
+
+        ui.separator();
+        ui.heading("Hovered links");
+        ui.label(self.settings.speculation.label());
+        ui.label(alice_browser::net::speculate::shared().stats().summary())
+            .on_hover_text("Hosts resolved and connections opened ahead of clicks");
+
+        ui.separator();
+        self.draw_memory_stats(ui);
+
+        ui.separator();
+        ui.heading("Frames");
+        let mean = self.frame_profiler.average();
+        ui.label(format!(
+            "Mean frame: {:.1} ms (UI paint {:.1} ms)",
+            mean.total_ms(),
+            mean.ms(FrameStage::Paint)
+        ));
+        ui.checkbox(&mut self.show_frame_profiler, "Frame profiler overlay")
+            .on_hover_text("Time per frame by stage, over the last frames");
+        #[cfg(feature = "sdf-render")]
+        if self.render_mode == RenderMode::OzMode {
+            ui.label(match self.oz_glyphs {
+                Some(ref glyphs) => format!("OZ text: GPU, {} glyphs", glyphs.last_instances),
+                None => "OZ text: egui painter".to_owned(),
+            });
+        }
+
         #[cfg(feature = "telemetry")]
         {
             let snap = self.metrics.snapshot();
@@ -1006,8 +2118,63 @@ impl BrowserApp {
                 ui.label(format!("P50 load: {:.0} ms", snap.p50_load_ms));
                 ui.label(format!("P99 load: {:.0} ms", snap.p99_load_ms));
             }
+            for (stage, ms) in &snap.stage_p50_ms {
+                ui.label(format!("P50 {}: {ms:.1} ms", stage.to_lowercase()));
+            }
             ui.label(format!("Domains: ~{:.0}", snap.unique_domains));
             ui.label(format!("Total blocked: {}", snap.total_blocked));
         }
+
+        // Volume and mute are in the settings window
+        #[cfg(feature = "spatial-audio")]
+        if !self.audio.is_available() {
+            ui.separator();
+            ui.colored_label(egui::Color32::GRAY, "Audio: no output device");
+        }
+
+        ui.separator();
+        if ui.button("Filter diagnostics…").clicked() {
+            self.show_filter_diagnostics = true;
+        }
+        if ui.button("Page inspector…").clicked() {
+            self.show_inspector = true;
+        }
+        if ui.button("DOM tree…").clicked() {
+            self.show_dom_tree = true;
+        }
+        if ui.button("Network…").clicked() {
+            self.show_network = true;
+        }
+        if ui.button("Site settings…").clicked() {
+            self.show_site_settings = true;
+        }
+        if ui.button("Resources…").clicked() {
+            self.show_resources = true;
+        }
+        if ui.button("Save page as…").clicked() {
+            self.open_save_page();
+        }
+        if ui.button("Print…").on_hover_text("Cmd+P").clicked() {
+            self.open_print();
+        }
+        if ui.button("Autofill profiles…").clicked() {
+            self.show_autofill = true;
+        }
+        if ui.button("Task manager…").clicked() {
+            self.show_tasks = true;
+        }
+        if ui.button("Open list…").clicked() {
+            self.show_session = true;
+        }
+        if ui.button("Site map…").clicked() {
+            self.open_site_map();
+        }
+        #[cfg(feature = "sdf-render")]
+        if ui.button("Scene gallery…").clicked() {
+            self.show_gallery = true;
+        }
+        if ui.button("Welcome tour…").clicked() {
+            self.start_onboarding(&ui.ctx().clone());
+        }
     }
 }
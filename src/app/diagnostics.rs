@@ -0,0 +1,243 @@
+//! Filter-list diagnostics window for `BrowserApp`.
+//!
+//! Benchmarks the ad blocker against the bundled corpus plus the current
+//! page's resource URLs, lists rules slowest first, and lets heavy or
+//! useless rules be switched off (and custom rules be loaded) in place.
+//! The same window loads classifier weights from a file (see
+//! [`alice_browser::dom::model`]); a new model replaces the old one for
+//! every later page load, and the current page is reloaded with it.
+
+use eframe::egui;
+use std::path::Path;
+use std::sync::Arc;
+
+use alice_browser::dom::model::ClassifierModel;
+use alice_browser::net::adblock::{AdBlockEngine, RuleKind};
+use alice_browser::net::adblock_bench;
+
+use super::BrowserApp;
+
+/// Benchmark repetitions, so per-rule timings rise above timer noise.
+const BENCH_ROUNDS: usize = 50;
+
+/// Rows shown in the per-rule table.
+const MAX_RULE_ROWS: usize = 60;
+
+impl BrowserApp {
+    /// Mutable access to the ad blocker, if no page load currently shares it.
+    fn adblock_mut(&mut self) -> Option<&mut AdBlockEngine> {
+        Arc::get_mut(&mut self.adblock)
+    }
+
+    /// Benchmark the current rule set and store the report.
+    pub fn run_filter_bench(&mut self) {
+        let mut corpus = adblock_bench::bundled_corpus();
+        if let Some(ref page) = self.page {
+            corpus.extend(adblock_bench::urls_from_dom(&page.dom.root, &page.dom.url));
+        }
+        self.filter_bench = Some(adblock_bench::run(&self.adblock, &corpus, BENCH_ROUNDS));
+    }
+
+    /// Load the classifier model named in the settings; a file that does
+    /// not load leaves the built-in classifier in use.
+    pub fn load_classifier_model(&mut self) {
+        self.model_path_input
+            .clone_from(&self.settings.classifier_model);
+        let path = self.settings.classifier_model.trim();
+        if path.is_empty() {
+            return;
+        }
+        match ClassifierModel::load(Path::new(path)) {
+            Ok(model) => {
+                log::info!("Classifier model {} loaded from {path}", model.label());
+                self.classifier_model = Some(Arc::new(model));
+            }
+            Err(e) => {
+                log::warn!("Classifier model not loaded: {e}");
+                self.model_error = Some(e.message);
+            }
+        }
+    }
+
+    /// Classify with the model at `path` from now on (empty: the built-in
+    /// classifier) and reload the page with it. A file that does not load
+    /// keeps the current model.
+    fn swap_classifier_model(&mut self, path: &str, ctx: &egui::Context) {
+        let path = path.trim();
+        let model = if path.is_empty() {
+            None
+        } else {
+            match ClassifierModel::load(Path::new(path)) {
+                Ok(model) => Some(Arc::new(model)),
+                Err(e) => {
+                    self.model_error = Some(e.message);
+                    return;
+                }
+            }
+        };
+        // Loads already running keep the model they were started with
+        self.classifier_model = model;
+        self.model_error = None;
+        self.settings.classifier_model = path.to_string();
+        self.settings.save();
+        if self.page.is_some() {
+            self.reload(ctx);
+        }
+    }
+
+    /// Draw the diagnostics window.
+    pub fn draw_filter_diagnostics(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_filter_diagnostics;
+        let mut toggle: Option<(RuleKind, String, bool)> = None;
+        let mut load_custom = false;
+        let mut swap_model: Option<String> = None;
+        let mut rerun = false;
+
+        egui::Window::new("Filter diagnostics")
+            .open(&mut open)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                ui.label(format!("Active rules: {}", self.adblock.rule_count()));
+
+                ui.collapsing("Custom rules", |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.custom_rules_input)
+                            .hint_text("||ads.example.com^\n/promo-banner/")
+                            .desired_rows(4)
+                            .code_editor(),
+                    );
+                    load_custom = ui.button("Load rules").clicked();
+                });
+
+                ui.collapsing("Classifier model", |ui| {
+                    match self.classifier_model {
+                        Some(ref model) => ui.label(format!(
+                            "{} · {} parameters",
+                            model.label(),
+                            model.parameter_count()
+                        )),
+                        None => ui.label("Built-in classifier"),
+                    };
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.model_path_input)
+                            .hint_text("/path/to/model.json")
+                            .desired_width(f32::INFINITY),
+                    );
+                    ui.horizontal(|ui| {
+                        let current = self.classifier_model.is_some()
+                            && self.model_path_input.trim() == self.settings.classifier_model;
+                        let load = if current { "Reload" } else { "Load" };
+                        if ui.button(load).clicked() {
+                            swap_model = Some(self.model_path_input.clone());
+                        }
+                        if ui
+                            .add_enabled(
+                                self.classifier_model.is_some(),
+                                egui::Button::new("Use built-in"),
+                            )
+                            .clicked()
+                        {
+                            swap_model = Some(String::new());
+                        }
+                    });
+                    if let Some(ref e) = self.model_error {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    rerun = ui.button("Run benchmark").clicked();
+                    if self.page.is_some() {
+                        ui.label("bundled corpus + current page");
+                    } else {
+                        ui.label("bundled corpus");
+                    }
+                });
+
+                let Some(ref report) = self.filter_bench else {
+                    return;
+                };
+                ui.separator();
+                ui.label(format!(
+                    "{} URLs · {:.1}% blocked ({} ads, {} trackers) · {:.1} ms",
+                    report.urls,
+                    report.match_rate() * 100.0,
+                    report.blocked_ads,
+                    report.blocked_trackers,
+                    report.elapsed.as_secs_f64() * 1000.0,
+                ));
+                ui.label(format!(
+                    "Rules without matches: {}",
+                    report.unused_rules().count()
+                ));
+
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("filter_bench_rules")
+                            .striped(true)
+                            .num_columns(5)
+                            .show(ui, |ui| {
+                                ui.strong("Kind");
+                                ui.strong("Rule");
+                                ui.strong("Hits");
+                                ui.strong("µs/round");
+                                ui.label("");
+                                ui.end_row();
+
+                                for rule in report.rules.iter().take(MAX_RULE_ROWS) {
+                                    ui.label(rule.kind.as_str());
+                                    ui.monospace(crate::ui::truncate_str(&rule.pattern, 36));
+                                    ui.label(rule.matches.to_string());
+                                    ui.label(format!(
+                                        "{:.2}",
+                                        rule.elapsed.as_secs_f64() * 1e6 / BENCH_ROUNDS as f64
+                                    ));
+                                    if ui.small_button("Disable").clicked() {
+                                        toggle = Some((rule.kind, rule.pattern.clone(), false));
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                let disabled = self.adblock.disabled_rules();
+                if !disabled.is_empty() {
+                    ui.separator();
+                    ui.label(format!("Disabled rules: {}", disabled.len()));
+                    for (kind, pattern) in disabled {
+                        ui.horizontal(|ui| {
+                            ui.label(kind.as_str());
+                            ui.monospace(crate::ui::truncate_str(pattern, 36));
+                            if ui.small_button("Enable").clicked() {
+                                toggle = Some((*kind, pattern.clone(), true));
+                            }
+                        });
+                    }
+                }
+            });
+        self.show_filter_diagnostics = open;
+
+        if load_custom {
+            let rules = std::mem::take(&mut self.custom_rules_input);
+            if let Some(engine) = self.adblock_mut() {
+                engine.load_rules(&rules);
+                rerun = true;
+            } else {
+                // A page load holds the engine; keep the text for another try
+                self.custom_rules_input = rules;
+            }
+        }
+        if let Some(path) = swap_model {
+            self.swap_classifier_model(&path, ctx);
+        }
+        if let Some((kind, pattern, enabled)) = toggle {
+            if let Some(engine) = self.adblock_mut() {
+                rerun |= engine.set_rule_enabled(kind, &pattern, enabled);
+            }
+        }
+        if rerun {
+            self.run_filter_bench();
+        }
+    }
+}
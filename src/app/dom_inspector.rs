@@ -0,0 +1,191 @@
+//! DOM tree inspector window for `BrowserApp`.
+//!
+//! Shows the current page's parsed `DomTree` as a collapsible tree: each
+//! element with its tag, attributes and the semantic filter's
+//! `Classification`, text nodes with their text. Clicking a node outlines
+//! the box it was laid out in, in the Flat and SDF 2-D views, and scrolls
+//! it into view. The tree only holds what the filter kept unless "Show
+//! removed nodes" is on — the page inspector's removed-element view — in
+//! which case ads and trackers appear greyed out, for debugging the filter.
+
+use eframe::egui;
+
+use alice_browser::dom::filter::is_removed_class;
+use alice_browser::dom::{DomNode, NodeType};
+use alice_browser::engine::pipeline::PageResult;
+use alice_browser::render::layout::LayoutNode;
+
+use super::BrowserApp;
+use crate::ui::feedback::REMOVED_TEXT;
+use crate::ui::truncate_str;
+
+/// Children listed per node before truncating.
+const MAX_CHILDREN: usize = 500;
+
+/// Levels open when a page is first shown.
+const OPEN_DEPTH: usize = 2;
+
+/// The node picked in the tree.
+#[derive(Default)]
+pub struct DomInspector {
+    /// Child indices from the document root, and the page they are on
+    selected: Option<(String, Vec<usize>)>,
+    /// Scroll the picked node into view on the next frame
+    pub scroll_pending: bool,
+}
+
+impl DomInspector {
+    /// The layout node of the picked DOM node on `page`, if it was laid
+    /// out (see [`LayoutNode::for_dom_path`]).
+    pub fn node<'a>(&self, page: &'a PageResult) -> Option<&'a LayoutNode> {
+        let (url, path) = self.selected.as_ref()?;
+        if *url != page.dom.url {
+            return None;
+        }
+        page.layout.for_dom_path(&page.dom.root, path)
+    }
+}
+
+/// `<tag attr="value" …>`, attributes in name order.
+fn element_label(node: &DomNode) -> String {
+    let mut attrs: Vec<_> = node.attributes.iter().collect();
+    attrs.sort();
+    let mut label = format!("<{}", node.tag);
+    for (name, value) in attrs {
+        if value.is_empty() {
+            label.push_str(&format!(" {name}"));
+        } else {
+            label.push_str(&format!(" {name}=\"{}\"", truncate_str(value, 40)));
+        }
+    }
+    label.push('>');
+    label
+}
+
+/// Total number of nodes under `node`, itself included, and how many of
+/// them the filter removes.
+fn count_nodes(node: &DomNode) -> (usize, usize) {
+    node.children.iter().map(count_nodes).fold(
+        (1, usize::from(is_removed_class(node.classification))),
+        |(all, removed), (a, r)| (all + a, removed + r),
+    )
+}
+
+/// One row of the tree, and its children below it if expanded. Sets
+/// `picked` to the path of a row clicked this frame.
+fn draw_node(
+    ui: &mut egui::Ui,
+    node: &DomNode,
+    path: &mut Vec<usize>,
+    selected: Option<&[usize]>,
+    picked: &mut Option<Vec<usize>>,
+) {
+    let removed = is_removed_class(node.classification);
+    let row = |ui: &mut egui::Ui| {
+        let text = match node.node_type {
+            NodeType::Text => format!("\u{201c}{}\u{201d}", truncate_str(node.text.trim(), 60)),
+            NodeType::Element => element_label(node),
+            NodeType::Document => node.tag.clone(),
+        };
+        let mut text = egui::RichText::new(truncate_str(&text, 100)).monospace();
+        if removed {
+            text = text.color(REMOVED_TEXT).strikethrough();
+        }
+        let response = ui.selectable_label(selected == Some(path.as_slice()), text);
+        if node.node_type == NodeType::Element {
+            ui.weak(format!("{:?}", node.classification));
+        }
+        if response.clicked() {
+            *picked = Some(path.clone());
+        }
+    };
+
+    if node.children.is_empty() {
+        ui.horizontal(row);
+        return;
+    }
+    let id = ui.make_persistent_id(("dom_node", path.as_slice()));
+    egui::collapsing_header::CollapsingState::load_with_default_open(
+        ui.ctx(),
+        id,
+        path.len() < OPEN_DEPTH,
+    )
+    .show_header(ui, row)
+    .body(|ui| {
+        for (i, child) in node.children.iter().enumerate().take(MAX_CHILDREN) {
+            path.push(i);
+            draw_node(ui, child, path, selected, picked);
+            path.pop();
+        }
+        if node.children.len() > MAX_CHILDREN {
+            ui.weak(format!(
+                "{} more not shown",
+                node.children.len() - MAX_CHILDREN
+            ));
+        }
+    });
+}
+
+impl BrowserApp {
+    /// Draw the DOM tree inspector window.
+    pub fn draw_dom_inspector(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_dom_tree;
+        let mut removed_toggled = false;
+        let mut picked = None;
+        egui::Window::new("DOM tree")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                let Some(ref page) = self.page else {
+                    ui.label("No page loaded");
+                    return;
+                };
+                let (nodes, removed) = count_nodes(&page.dom.root);
+                ui.label(format!("{nodes} nodes · {removed} removed by the filter"));
+                removed_toggled = ui
+                    .checkbox(&mut self.show_removed, "Show removed nodes")
+                    .on_hover_text("Reload keeping ads and trackers, greyed out")
+                    .changed();
+                let selected = self
+                    .dom_inspector
+                    .selected
+                    .as_ref()
+                    .filter(|(url, _)| *url == page.dom.url)
+                    .map(|(_, path)| path.as_slice());
+                if selected.is_some() {
+                    ui.label(match self.dom_inspector.node(page) {
+                        Some(node) => {
+                            let b = &node.bounds;
+                            format!(
+                                "Laid out as <{}> at {:.0}, {:.0} · {:.0} × {:.0}",
+                                node.tag, b.x, b.y, b.width, b.height
+                            )
+                        }
+                        None => "Not laid out".into(),
+                    });
+                }
+                ui.separator();
+                egui::ScrollArea::both()
+                    .max_height(480.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        draw_node(ui, &page.dom.root, &mut Vec::new(), selected, &mut picked);
+                    });
+            });
+        self.show_dom_tree = open;
+
+        if !open {
+            self.dom_inspector.selected = None;
+        } else if let Some(path) = picked {
+            if let Some(ref page) = self.page {
+                self.dom_inspector.selected = Some((page.dom.url.clone(), path));
+                self.dom_inspector.scroll_pending = true;
+            }
+        }
+        if removed_toggled {
+            // Indices shift when removed nodes come and go
+            self.dom_inspector.selected = None;
+            self.apply_show_removed(ctx);
+        }
+    }
+}
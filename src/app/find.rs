@@ -0,0 +1,94 @@
+//! Find in page for `BrowserApp` (feature `search`).
+//!
+//! The page's search index maps each match of the find-bar query back to
+//! the layout node that displays it. `FindState` keeps those matches and
+//! which one is current; stepping through them (Enter / Shift+Enter or the
+//! arrow buttons) asks the Flat view to scroll the current match into view.
+//! On long pages only the laid-out part is searchable; the index is
+//! rebuilt as more of the page is laid out.
+
+use alice_browser::engine::pipeline::PageResult;
+use alice_browser::render::layout::LayoutNode;
+use alice_browser::search::{PageMatch, PageSearch};
+
+use super::BrowserApp;
+
+/// Matches of the find-bar query on the current page.
+#[derive(Debug, Default)]
+pub struct FindState {
+    /// Query the matches were computed for
+    query: String,
+    pub matches: Vec<PageMatch>,
+    /// Index of the current match in `matches`
+    pub current: usize,
+    /// Scroll the current match into view on the next frame
+    pub scroll_pending: bool,
+}
+
+impl BrowserApp {
+    /// Index a freshly shown page and clear the find bar.
+    pub fn index_page_search(&mut self, page: &PageResult) {
+        self.search_index = Some(PageSearch::from_layout(&page.layout));
+        self.search_query.clear();
+        self.find = FindState::default();
+    }
+
+    /// Re-index after more of a long page was laid out, keeping the query
+    /// and, where it still exists, the current match.
+    pub fn refresh_page_search(&mut self) {
+        let Some(ref page) = self.page else {
+            return;
+        };
+        let index = PageSearch::from_layout(&page.layout);
+        let current = self.find.matches.get(self.find.current).cloned();
+        self.find.matches = index.matches(&self.search_query);
+        self.find.current = current
+            .and_then(|m| self.find.matches.iter().position(|n| *n == m))
+            .unwrap_or(0);
+        self.search_index = Some(index);
+    }
+
+    /// Recompute the matches after the query changed; the first match
+    /// becomes current.
+    pub fn update_find(&mut self) {
+        if self.find.query == self.search_query {
+            return;
+        }
+        self.find.query.clone_from(&self.search_query);
+        self.find.matches = self
+            .search_index
+            .as_ref()
+            .map(|idx| idx.matches(&self.search_query))
+            .unwrap_or_default();
+        self.find.current = 0;
+        self.find.scroll_pending = !self.find.matches.is_empty();
+    }
+
+    /// Make the next match current, wrapping around at the end.
+    pub fn find_next(&mut self) {
+        self.step_find(1);
+    }
+
+    /// Make the previous match current, wrapping around at the start.
+    pub fn find_previous(&mut self) {
+        let len = self.find.matches.len();
+        self.step_find(len.saturating_sub(1));
+    }
+
+    fn step_find(&mut self, by: usize) {
+        self.update_find();
+        let len = self.find.matches.len();
+        if len == 0 {
+            return;
+        }
+        self.find.current = (self.find.current + by) % len;
+        self.find.scroll_pending = true;
+    }
+}
+
+impl FindState {
+    /// Text node holding the current match in `layout`.
+    pub fn current_node<'a>(&self, layout: &'a LayoutNode) -> Option<&'a LayoutNode> {
+        self.matches.get(self.current)?.node(layout)
+    }
+}
@@ -0,0 +1,90 @@
+//! "Focus in 3D" for `BrowserApp`.
+//!
+//! Right-clicking an `article` or `section` in the flat view (`ui::focus`)
+//! or the SDF 2-D view (`SdfPaintState::focus_request`) offers to open that
+//! block alone in Spatial3D. The subtree is copied out of the page layout,
+//! moved to the origin and built into its own scene, with the camera framed
+//! on it; a bar above the view names the block and leads back to the whole
+//! page or to the view it came from.
+
+use alice_browser::render::layout::LayoutNode;
+use alice_browser::render::RenderMode;
+use eframe::egui;
+
+use super::BrowserApp;
+use crate::ui::{collect_display_text, truncate_str};
+
+/// A block shown alone in Spatial3D.
+pub struct FocusedBlock {
+    /// The block's subtree, moved to the origin
+    pub root: LayoutNode,
+    /// Its first heading, or its tag
+    pub title: String,
+    /// View the focus was chosen from
+    pub from: RenderMode,
+}
+
+/// Text of the first heading under `node`.
+fn first_heading(node: &LayoutNode) -> Option<String> {
+    if matches!(node.tag.as_str(), "h1" | "h2" | "h3" | "h4" | "h5" | "h6") {
+        let text = collect_display_text(node);
+        return (!text.is_empty()).then_some(text);
+    }
+    node.children.iter().find_map(first_heading)
+}
+
+impl BrowserApp {
+    /// Show the `article` / `section` laid out at `rect` alone in Spatial3D.
+    pub fn focus_in_3d(&mut self, rect: [f32; 4]) {
+        let Some(node) = self.page.as_ref().and_then(|p| p.layout.focus_block(rect)) else {
+            return;
+        };
+        let focused = FocusedBlock {
+            title: first_heading(node).unwrap_or_else(|| format!("<{}>", node.tag)),
+            root: node.detached(),
+            from: self.render_mode,
+        };
+        self.set_render_mode(RenderMode::Spatial3D);
+        // Built, and the camera framed, on the next frame
+        self.focus_3d = Some(focused);
+    }
+
+    /// Carry out a "Focus in 3D" chosen in the flat view this frame.
+    pub fn handle_focus_requests(&mut self, ctx: &egui::Context) {
+        if let Some(rect) = crate::ui::focus::take(ctx) {
+            self.focus_in_3d(rect);
+        }
+    }
+
+    /// Drop the focus and rebuild the scene from the whole page.
+    pub fn show_whole_page(&mut self) {
+        self.focus_3d = None;
+        self.spatial_scene = None;
+        self.cull_stats = None;
+        self.refiner.cancel();
+        self.cam_dirty = true;
+    }
+
+    /// Bar above a focused scene: what it shows, and the ways back.
+    pub fn draw_focus_bar(&mut self, ui: &mut egui::Ui) {
+        let Some(ref focus) = self.focus_3d else {
+            return;
+        };
+        let from = focus.from;
+        let mut whole_page = false;
+        let mut back = false;
+        ui.horizontal(|ui| {
+            ui.label(format!("Focused: {}", truncate_str(&focus.title, 60)));
+            whole_page = ui.button("Whole page").clicked();
+            back = ui.button(format!("Back to {}", from.label())).clicked();
+        });
+        ui.separator();
+
+        if back {
+            // Leaving Spatial3D drops the focus with the scene
+            self.set_render_mode(from);
+        } else if whole_page {
+            self.show_whole_page();
+        }
+    }
+}
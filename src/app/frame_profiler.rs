@@ -0,0 +1,90 @@
+//! Frame profiler overlay for `BrowserApp`.
+//!
+//! The app reports each frame's stages to its
+//! [`FrameProfiler`](alice_browser::telemetry::frame_profiler::FrameProfiler);
+//! this draws the kept frames as a rolling flame bar in the corner of the
+//! page — one column per frame, stacked by stage, with the 60 fps budget
+//! marked — above each stage's mean time. Toggled from the stats panel.
+
+use eframe::egui;
+
+use alice_browser::telemetry::frame_profiler::{FrameStage, HISTORY_FRAMES};
+
+use super::BrowserApp;
+
+/// Size of the bar chart.
+const CHART_SIZE: egui::Vec2 = egui::vec2(HISTORY_FRAMES as f32, 64.0);
+
+/// One frame at 60 fps, in milliseconds.
+const FRAME_BUDGET_MS: f32 = 1000.0 / 60.0;
+
+fn stage_color(stage: FrameStage) -> egui::Color32 {
+    let [r, g, b] = stage.rgb();
+    egui::Color32::from_rgb(r, g, b)
+}
+
+impl BrowserApp {
+    /// Draw the frame profiler overlay over the bottom right of the page.
+    pub fn draw_frame_profiler(&self, ctx: &egui::Context) {
+        let Some(content) = self.content_rect else {
+            return;
+        };
+        let profiler = &self.frame_profiler;
+        egui::Area::new(egui::Id::new("frame_profiler"))
+            .order(egui::Order::Foreground)
+            .pivot(egui::Align2::RIGHT_BOTTOM)
+            .fixed_pos(content.right_bottom() - egui::vec2(8.0, 8.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.strong(format!("Frame profile · {}", self.render_mode.label()));
+
+                    // Taller than the slowest frame, and never shorter than
+                    // twice the budget, so the budget line stays put
+                    let scale_ms = profiler.worst_ms().max(FRAME_BUDGET_MS * 2.0);
+                    let (rect, _) = ui.allocate_exact_size(CHART_SIZE, egui::Sense::hover());
+                    let painter = ui.painter_at(rect);
+                    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+                    let px_per_ms = rect.height() / scale_ms;
+                    let start = HISTORY_FRAMES - profiler.samples().len();
+                    for (i, sample) in profiler.samples().enumerate() {
+                        let x = rect.left() + (start + i) as f32;
+                        let mut y = rect.bottom();
+                        for stage in FrameStage::ALL {
+                            let h = sample.ms(stage) * px_per_ms;
+                            if h <= 0.0 {
+                                continue;
+                            }
+                            painter.line_segment(
+                                [egui::pos2(x, y), egui::pos2(x, y - h)],
+                                egui::Stroke::new(1.0, stage_color(stage)),
+                            );
+                            y -= h;
+                        }
+                    }
+                    let budget = rect.bottom() - FRAME_BUDGET_MS * px_per_ms;
+                    painter.hline(
+                        rect.x_range(),
+                        budget,
+                        egui::Stroke::new(1.0, ui.visuals().weak_text_color()),
+                    );
+
+                    let mean = profiler.average();
+                    ui.label(format!(
+                        "mean {:.1} ms · worst {:.1} ms · budget {FRAME_BUDGET_MS:.1} ms",
+                        mean.total_ms(),
+                        profiler.worst_ms()
+                    ));
+                    egui::Grid::new("frame_profiler_stages")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for stage in FrameStage::ALL {
+                                ui.colored_label(stage_color(stage), stage.label());
+                                ui.monospace(format!("{:>6.2} ms", mean.ms(stage)));
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+    }
+}
@@ -0,0 +1,300 @@
+//! Scene gallery for `BrowserApp` (feature `sdf-render`).
+//!
+//! Named snapshots of Spatial3D scenes — scene, camera and source URL —
+//! are kept as `.aliscene` files in the profile's `scenes` directory.
+//! Opening one shows it in Spatial3D without fetching the page again.
+//! The files are self-contained: copy one to another machine and open it
+//! there from the gallery's path field or by dropping it on the window,
+//! which also adds it to that gallery.
+
+use std::path::{Path, PathBuf};
+
+use eframe::egui;
+
+use alice_browser::render::scene_file::{SceneSnapshot, EXTENSION};
+use alice_browser::render::RenderMode;
+
+use super::history::now_secs;
+use super::BrowserApp;
+
+/// Directory of the gallery inside the profile directory.
+pub const SCENES_DIR: &str = "scenes";
+
+/// One saved snapshot, as listed in the gallery.
+#[derive(Debug, Clone)]
+pub struct GalleryEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub url: String,
+    pub created: u64,
+    pub primitives: usize,
+}
+
+/// Gallery window state.
+#[derive(Debug, Default)]
+pub struct Gallery {
+    /// Saved snapshots, newest first (read when the window opens)
+    pub entries: Vec<GalleryEntry>,
+    /// Name for the next snapshot
+    pub name: String,
+    /// Scene file to open from outside the gallery
+    pub open_path: String,
+    pub status: Option<String>,
+    listed: bool,
+}
+
+impl Gallery {
+    /// Re-read the gallery directory.
+    pub fn refresh(&mut self) {
+        self.listed = true;
+        self.entries.clear();
+        let Ok(dir) = scenes_dir() else {
+            return;
+        };
+        let Ok(files) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for path in files.filter_map(|f| f.ok().map(|f| f.path())) {
+            if !is_scene_file(&path) {
+                continue;
+            }
+            match SceneSnapshot::load(&path) {
+                Ok(snap) => self.entries.push(GalleryEntry {
+                    name: snap.name,
+                    url: snap.url,
+                    created: snap.created,
+                    primitives: snap.scene.primitives.len(),
+                    path,
+                }),
+                Err(e) => log::warn!("Skipping scene file {}: {e}", path.display()),
+            }
+        }
+        self.entries.sort_by_key(|e| std::cmp::Reverse(e.created));
+    }
+}
+
+/// The gallery directory, created if needed.
+fn scenes_dir() -> std::io::Result<PathBuf> {
+    let dir = alice_browser::profile::file_path(SCENES_DIR)?;
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn is_scene_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(EXTENSION))
+}
+
+/// File name for a snapshot: the name reduced to `[a-z0-9-]`, plus the time.
+fn file_name(name: &str, created: u64) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug.trim_matches('-');
+    let slug = if slug.is_empty() { "scene" } else { slug };
+    format!("{}-{created}.{EXTENSION}", &slug[..slug.len().min(40)])
+}
+
+impl BrowserApp {
+    /// Save the Spatial3D scene on screen, with its camera, to the gallery.
+    fn save_scene_snapshot(&self) -> std::io::Result<PathBuf> {
+        let Some(ref scene) = self.spatial_scene else {
+            return Err(std::io::Error::other("no 3-D scene to save"));
+        };
+        let url = self
+            .page
+            .as_ref()
+            .map_or_else(|| self.url_input.clone(), |p| p.dom.url.clone());
+        let name = match self.gallery.name.trim() {
+            "" => self
+                .page
+                .as_ref()
+                .map(|p| p.dom.title.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| url.clone()),
+            name => name.to_string(),
+        };
+        let snapshot = SceneSnapshot {
+            created: now_secs(),
+            name,
+            url,
+            camera: self.cam_params.into(),
+            scene: scene.clone(),
+        };
+        let path = scenes_dir()?.join(file_name(&snapshot.name, snapshot.created));
+        snapshot.save(&path)?;
+        Ok(path)
+    }
+
+    /// Show a snapshot in Spatial3D in place of the current page.
+    pub fn open_scene_snapshot(&mut self, snapshot: SceneSnapshot) {
+        self.set_render_mode(RenderMode::Spatial3D);
+        self.page = None;
+        self.error = None;
+        self.reset_page_views();
+        self.url_input = snapshot.url;
+        self.cam_params = snapshot.camera.into();
+        self.spatial_scene = Some(snapshot.scene);
+        self.spatial_pick = super::picking::SpatialPick::default();
+        self.cam_dirty = true;
+        if let Some(ref mut gpu) = self.gpu_renderer {
+            gpu.invalidate();
+        }
+    }
+
+    /// Open a scene file from anywhere, copying it into the gallery first.
+    pub fn import_scene_file(&mut self, path: &Path) {
+        let snapshot = match SceneSnapshot::load(path) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                self.gallery.status = Some(format!("Could not open {}: {e}", path.display()));
+                return;
+            }
+        };
+        let in_gallery = scenes_dir()
+            .ok()
+            .zip(path.parent())
+            .is_some_and(|(dir, parent)| parent == dir);
+        if !in_gallery {
+            let copied = scenes_dir().and_then(|dir| {
+                let target = dir.join(file_name(&snapshot.name, snapshot.created));
+                std::fs::copy(path, target)
+            });
+            if let Err(e) = copied {
+                log::warn!("Could not add {} to the gallery: {e}", path.display());
+            }
+            self.gallery.listed = false;
+        }
+        self.gallery.status = Some(format!("Opened \"{}\"", snapshot.name));
+        self.open_scene_snapshot(snapshot);
+    }
+
+    /// Open scene files dropped on the window.
+    pub fn handle_dropped_scenes(&mut self, ctx: &egui::Context) {
+        let dropped: Vec<PathBuf> = ctx.input(|i| {
+            i.raw
+                .dropped_files
+                .iter()
+                .filter_map(|f| f.path.clone())
+                .filter(|p| is_scene_file(p))
+                .collect()
+        });
+        for path in dropped {
+            self.import_scene_file(&path);
+            self.show_gallery = true;
+        }
+    }
+
+    /// Draw the scene gallery window.
+    pub fn draw_scene_gallery(&mut self, ctx: &egui::Context) {
+        if !self.gallery.listed {
+            self.gallery.refresh();
+        }
+        let mut open = self.show_gallery;
+        let mut to_open: Option<PathBuf> = None;
+        let mut to_delete: Option<PathBuf> = None;
+        egui::Window::new("Scene gallery")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let can_save =
+                    self.render_mode == RenderMode::Spatial3D && self.spatial_scene.is_some();
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.gallery.name)
+                            .hint_text("Snapshot name (default: page title)")
+                            .desired_width(220.0),
+                    );
+                    let save = ui
+                        .add_enabled(can_save, egui::Button::new("Save current scene"))
+                        .on_disabled_hover_text("Switch to Spatial 3D to snapshot a scene");
+                    if save.clicked() {
+                        self.gallery.status = Some(match self.save_scene_snapshot() {
+                            Ok(path) => format!("Saved {}", path.display()),
+                            Err(e) => format!("Could not save: {e}"),
+                        });
+                        self.gallery.name.clear();
+                        self.gallery.refresh();
+                    }
+                });
+                if let Some(ref status) = self.gallery.status {
+                    ui.label(status);
+                }
+                ui.separator();
+
+                if self.gallery.entries.is_empty() {
+                    ui.label("No snapshots yet.");
+                }
+                let now = now_secs();
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        for entry in &self.gallery.entries {
+                            ui.horizontal(|ui| {
+                                ui.vertical(|ui| {
+                                    ui.strong(crate::ui::truncate_str(&entry.name, 40));
+                                    ui.weak(format!(
+                                        "{} · {} · {} primitives",
+                                        crate::ui::truncate_str(&entry.url, 40),
+                                        super::internal::ago(entry.created, now),
+                                        entry.primitives
+                                    ));
+                                });
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui.small_button("Delete").clicked() {
+                                            to_delete = Some(entry.path.clone());
+                                        }
+                                        if ui
+                                            .small_button("Copy path")
+                                            .on_hover_text(
+                                                "The file can be opened by another ALICE Browser",
+                                            )
+                                            .clicked()
+                                        {
+                                            ctx.copy_text(entry.path.display().to_string());
+                                        }
+                                        if ui.small_button("Open").clicked() {
+                                            to_open = Some(entry.path.clone());
+                                        }
+                                    },
+                                );
+                            });
+                        }
+                    });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.gallery.open_path)
+                            .hint_text(format!("Path to a .{EXTENSION} file"))
+                            .desired_width(280.0),
+                    );
+                    if ui.button("Open file").clicked() && !self.gallery.open_path.trim().is_empty()
+                    {
+                        to_open = Some(PathBuf::from(self.gallery.open_path.trim()));
+                    }
+                });
+                ui.weak("Scene files can also be dropped on the window.");
+            });
+        self.show_gallery = open;
+
+        if let Some(path) = to_delete {
+            if let Err(e) = std::fs::remove_file(&path) {
+                self.gallery.status = Some(format!("Could not delete: {e}"));
+            }
+            self.gallery.refresh();
+        }
+        if let Some(path) = to_open {
+            self.import_scene_file(&path);
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Gamepad navigation for `BrowserApp`.
+//!
+//! Applies one frame of [`PadInput`](alice_browser::gamepad::PadInput) to the
+//! active view mode, with buttons mapped through the bindings in the
+//! settings; see [`alice_browser::gamepad`] for the default layout. While a
+//! controller is connected, hints for the current mode sit in the bottom
+//! left corner of the page.
+
+use eframe::egui;
+
+use alice_browser::gamepad::{PadBindings, PadButton};
+#[cfg(feature = "sdf-render")]
+use alice_browser::render::RenderMode;
+
+use super::BrowserApp;
+
+/// Look / orbit speed at full stick deflection (radians per second).
+#[cfg(feature = "sdf-render")]
+const LOOK_SPEED: f32 = 1.8;
+/// Dolly speed at full deflection (fraction of distance per second).
+#[cfg(feature = "sdf-render")]
+const DOLLY_SPEED: f32 = 1.2;
+/// 2-D scroll speed at full deflection (points per second).
+const SCROLL_SPEED: f32 = 900.0;
+/// OZ flow speed change per second at full trigger.
+#[cfg(feature = "sdf-render")]
+const FLOW_ADJUST_SPEED: f32 = 1.5;
+/// Range of the OZ flow speed multiple.
+#[cfg(feature = "sdf-render")]
+const FLOW_SPEED_RANGE: std::ops::RangeInclusive<f32> = 0.0..=4.0;
+
+impl BrowserApp {
+    /// Poll the controller and apply it. Called once per frame, before the
+    /// panels are laid out.
+    pub fn handle_gamepad(&mut self, ctx: &egui::Context) {
+        // Always drain events so a newly connected pad is picked up
+        let input = self.gamepad.poll(&self.settings.gamepad);
+        if !self.gamepad.is_connected() {
+            return;
+        }
+        let dt = ctx.input(|i| i.stable_dt).min(0.1);
+
+        for button in &input.pressed {
+            match button {
+                PadButton::Activate => self.gamepad_activate(),
+                PadButton::Back => {
+                    if !self.gamepad_release() {
+                        self.go_back(ctx);
+                    }
+                }
+                PadButton::Forward => self.go_forward(ctx),
+                PadButton::Hints => self.show_gamepad_hints = !self.show_gamepad_hints,
+            }
+        }
+
+        if !input.sticks_idle() {
+            self.apply_sticks(ctx, input.left, input.right, dt);
+        }
+
+        // Triggers: left slows the OZ flow, right speeds it up
+        #[cfg(feature = "sdf-render")]
+        if self.render_mode == RenderMode::OzMode {
+            let push = input.triggers[1] - input.triggers[0];
+            self.oz_flow_speed = (push * FLOW_ADJUST_SPEED)
+                .mul_add(dt, self.oz_flow_speed)
+                .clamp(*FLOW_SPEED_RANGE.start(), *FLOW_SPEED_RANGE.end());
+        }
+
+        // Sticks are polled, not evented: keep frames coming
+        ctx.request_repaint();
+    }
+
+    #[cfg(feature = "sdf-render")]
+    fn apply_sticks(&mut self, ctx: &egui::Context, left: [f32; 2], right: [f32; 2], dt: f32) {
+        match self.render_mode {
+            RenderMode::OzMode => {
+                // Both sticks look around; stick right turns right, up looks up
+                let x = left[0] + right[0];
+                let y = left[1] + right[1];
+                self.cam_params.azimuth -= x * LOOK_SPEED * dt;
+                self.cam_params.elevation = (y * LOOK_SPEED)
+                    .mul_add(dt, self.cam_params.elevation)
+                    .clamp(-0.8, 0.8);
+            }
+            RenderMode::Spatial3D => {
+                // Left stick: orbit; right stick: dolly (up moves in)
+                let step = LOOK_SPEED * dt;
+                self.cam_params.azimuth += left[0] * step;
+                self.cam_params.elevation = left[1]
+                    .mul_add(step, self.cam_params.elevation)
+                    .clamp(0.05, std::f32::consts::FRAC_PI_2 - 0.05);
+                if right[1] != 0.0 {
+                    self.cam_params.distance = (self.cam_params.distance
+                        * (right[1] * DOLLY_SPEED).mul_add(-dt, 1.0))
+                    .clamp(0.2, 100.0);
+
+                    #[cfg(feature = "spatial-audio")]
+                    self.audio.whoosh(right[1].abs() * 0.5);
+                }
+                self.cam_dirty = true;
+            }
+            _ => Self::scroll_by_stick(ctx, left, dt),
+        }
+    }
+
+    #[cfg(not(feature = "sdf-render"))]
+    fn apply_sticks(&mut self, ctx: &egui::Context, left: [f32; 2], _right: [f32; 2], dt: f32) {
+        Self::scroll_by_stick(ctx, left, dt);
+    }
+
+    /// 2-D modes: the left stick scrolls whatever scroll area is hovered.
+    fn scroll_by_stick(ctx: &egui::Context, left: [f32; 2], dt: f32) {
+        let delta = egui::vec2(-left[0], left[1]) * SCROLL_SPEED * dt;
+        ctx.input_mut(|i| i.smooth_scroll_delta += delta);
+    }
+
+    /// A: in OZ, grab the particle under the reticle (screen centre); pressing
+    /// again on the same particle opens its link.
+    fn gamepad_activate(&mut self) {
+        #[cfg(feature = "sdf-render")]
+        if self.render_mode == RenderMode::OzMode {
+            let Some(rect) = self.content_rect else {
+                return;
+            };
+            let before = self.stream_state.as_ref().and_then(|s| s.grabbed_index);
+            self.oz_click_at(rect.center(), rect);
+            let after = self.stream_state.as_ref().and_then(|s| s.grabbed_index);
+            if before.is_some() && before == after {
+                self.oz_open_grabbed();
+            }
+        }
+    }
+
+    /// Release the grabbed particle and dismiss its preview. Returns
+    /// `false` if nothing was grabbed.
+    fn gamepad_release(&mut self) -> bool {
+        #[cfg(feature = "sdf-render")]
+        if let Some(ref mut stream) = self.stream_state {
+            if stream.grabbed_index.is_none() {
+                return false;
+            }
+            stream.release_all();
+            self.oz_hologram_screen_pos = None;
+            self.oz_hologram_alpha = 0.0;
+            self.oz_hologram_start = None;
+            self.oz_preview = None;
+            self.oz_preview_for = None;
+            self.oz_preview_rx = None;
+            return true;
+        }
+        false
+    }
+
+    /// Button hints for the current mode, over the bottom left of the page.
+    pub fn draw_gamepad_hints(&self, ctx: &egui::Context) {
+        let (Some(content), true) = (self.content_rect, self.gamepad.is_connected()) else {
+            return;
+        };
+        let bindings = &self.settings.gamepad;
+        let mut hints: Vec<(String, String)> = Vec::new();
+        #[cfg(feature = "sdf-render")]
+        match self.render_mode {
+            RenderMode::OzMode => {
+                hints.push(("L / R stick".into(), "Look".into()));
+                hints.push((
+                    "LT / RT".into(),
+                    format!("Flow speed {:.1}\u{00d7}", self.oz_flow_speed),
+                ));
+                hints.push(button_hint(bindings, PadButton::Activate));
+            }
+            RenderMode::Spatial3D => {
+                hints.push(("L stick".into(), "Orbit".into()));
+                hints.push(("R stick".into(), "Dolly".into()));
+            }
+            _ => hints.push(("L stick".into(), "Scroll".into())),
+        }
+        #[cfg(not(feature = "sdf-render"))]
+        hints.push(("L stick".into(), "Scroll".into()));
+        for action in [PadButton::Back, PadButton::Forward, PadButton::Hints] {
+            hints.push(button_hint(bindings, action));
+        }
+
+        egui::Area::new(egui::Id::new("gamepad_hints"))
+            .order(egui::Order::Foreground)
+            .pivot(egui::Align2::LEFT_BOTTOM)
+            .fixed_pos(content.left_bottom() + egui::vec2(8.0, -8.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    egui::Grid::new("gamepad_hints_grid")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for (control, action) in hints {
+                                ui.strong(control);
+                                ui.label(action);
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+    }
+}
+
+/// Button and action label for one bound action.
+fn button_hint(bindings: &PadBindings, action: PadButton) -> (String, String) {
+    (
+        bindings.key(action).label().to_owned(),
+        action.label().to_owned(),
+    )
+}
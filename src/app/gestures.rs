@@ -0,0 +1,241 @@
+//! Touch and trackpad gestures for `BrowserApp`.
+//!
+//! Touchscreen events are fed to the library's [`GestureRecognizer`];
+//! trackpads arrive already digested by egui, pinches as its zoom delta and
+//! two-finger swipes as horizontal scrolling. Either way, on a page:
+//!
+//! - a pinch steps the page zoom through `ZOOM_LEVELS`
+//! - a two-finger swipe right goes back, left goes forward
+//! - a long-press on a link opens a small menu (open, copy address)
+
+use alice_browser::engine::pipeline::step_zoom;
+use alice_browser::mobile::touch::{Gesture, GestureRecognizer, SwipeDirection};
+use alice_browser::render::RenderMode;
+use eframe::egui;
+
+use super::BrowserApp;
+use crate::oz::resolve_url;
+use crate::ui::truncate_str;
+
+/// Spread, as a factor, that steps the zoom one level.
+const PINCH_STEP: f32 = 1.15;
+
+/// Pinch scale beyond which a two-finger touch counts as a pinch, not a swipe.
+const PINCH_SLOP: f32 = 0.1;
+
+/// Horizontal trackpad scrolling, in points, that makes a swipe.
+const SWIPE_DISTANCE: f32 = 160.0;
+
+/// Gesture tracking between frames.
+pub struct GestureState {
+    recognizer: GestureRecognizer,
+    /// Pinch scale at the last zoom step of the pinch under way
+    pinch_base: f32,
+    /// Whether the touch under way has pinched (so its lift is no swipe)
+    pinched: bool,
+    /// Whether the touch under way already opened the link menu
+    long_pressed: bool,
+    /// Trackpad zoom gathered since the last step
+    trackpad_zoom: f32,
+    /// Horizontal trackpad scrolling gathered toward a swipe
+    swipe: f32,
+    /// A trackpad swipe already navigated; wait for the scrolling to stop
+    swipe_spent: bool,
+    /// Link under the pointer last frame
+    pub hovered_link: Option<String>,
+    /// Long-press menu: where it opened, and for which link
+    link_menu: Option<(egui::Pos2, String)>,
+}
+
+impl Default for GestureState {
+    fn default() -> Self {
+        Self {
+            recognizer: GestureRecognizer::new(0.0, 0.0),
+            pinch_base: 1.0,
+            pinched: false,
+            long_pressed: false,
+            trackpad_zoom: 1.0,
+            swipe: 0.0,
+            swipe_spent: false,
+            hovered_link: None,
+            link_menu: None,
+        }
+    }
+}
+
+impl BrowserApp {
+    /// Whether gestures act on the page: a page is shown in a mode that
+    /// has no camera of its own to pinch or swipe.
+    fn page_gestures(&self) -> bool {
+        self.page.is_some()
+            && matches!(
+                self.render_mode,
+                RenderMode::Flat | RenderMode::Sdf2D | RenderMode::Reader
+            )
+    }
+
+    /// Recognize this frame's touches and trackpad gestures and act on
+    /// them, and draw the long-press link menu.
+    pub fn handle_gestures(&mut self, ctx: &egui::Context) {
+        let (events, zoom, scroll, screen) = ctx.input(|i| {
+            (
+                i.events.clone(),
+                i.zoom_delta(),
+                i.raw_scroll_delta,
+                i.screen_rect(),
+            )
+        });
+        self.gestures
+            .recognizer
+            .set_screen_size(screen.width(), screen.height());
+
+        for event in events {
+            let egui::Event::Touch { id, phase, pos, .. } = event else {
+                continue;
+            };
+            let recognizer = &mut self.gestures.recognizer;
+            let gesture = match phase {
+                egui::TouchPhase::Start => {
+                    recognizer.touch_start(pos.x, pos.y, id.0);
+                    if recognizer.touch_count() == 1 {
+                        self.gestures.pinch_base = 1.0;
+                        self.gestures.pinched = false;
+                        self.gestures.long_pressed = false;
+                    }
+                    Gesture::None
+                }
+                egui::TouchPhase::Move => recognizer.touch_move(pos.x, pos.y, id.0),
+                egui::TouchPhase::End | egui::TouchPhase::Cancel => {
+                    recognizer.touch_end(pos.x, pos.y, id.0)
+                }
+            };
+            self.apply_gesture(ctx, &gesture);
+        }
+
+        // A finger held still on a link
+        if !self.gestures.long_pressed {
+            if let Some((x, y)) = self.gestures.recognizer.check_long_press() {
+                self.gestures.long_pressed = true;
+                self.open_link_menu(egui::pos2(x, y));
+            }
+        }
+
+        // Trackpad pinches and swipes, when no finger is on the screen
+        if self.gestures.recognizer.touch_count() == 0 && self.page_gestures() {
+            if (zoom - 1.0).abs() > f32::EPSILON {
+                self.gestures.trackpad_zoom *= zoom;
+                let factor = self.gestures.trackpad_zoom;
+                if factor >= PINCH_STEP || factor <= 1.0 / PINCH_STEP {
+                    self.gestures.trackpad_zoom = 1.0;
+                    let step = if factor > 1.0 { 1 } else { -1 };
+                    self.set_page_zoom(step_zoom(self.page_zoom, step));
+                }
+            }
+
+            if scroll == egui::Vec2::ZERO {
+                self.gestures.swipe = 0.0;
+                self.gestures.swipe_spent = false;
+            } else if !self.gestures.swipe_spent && scroll.x.abs() > scroll.y.abs() * 2.0 {
+                self.gestures.swipe += scroll.x;
+                if self.gestures.swipe.abs() >= SWIPE_DISTANCE {
+                    self.gestures.swipe_spent = true;
+                    // Content follows the fingers: scrolling right means
+                    // pulling the previous page in from the left
+                    if self.gestures.swipe > 0.0 {
+                        self.go_back(ctx);
+                    } else {
+                        self.go_forward(ctx);
+                    }
+                }
+            }
+        }
+
+        self.draw_link_menu(ctx);
+    }
+
+    fn apply_gesture(&mut self, ctx: &egui::Context, gesture: &Gesture) {
+        match *gesture {
+            Gesture::Pinch { scale, .. } => {
+                if (scale - 1.0).abs() > PINCH_SLOP {
+                    self.gestures.pinched = true;
+                }
+                if !self.page_gestures() {
+                    return;
+                }
+                let ratio = scale / self.gestures.pinch_base;
+                if ratio >= PINCH_STEP || ratio <= 1.0 / PINCH_STEP {
+                    self.gestures.pinch_base = scale;
+                    let step = if ratio > 1.0 { 1 } else { -1 };
+                    self.set_page_zoom(step_zoom(self.page_zoom, step));
+                }
+            }
+            Gesture::Swipe {
+                direction, fingers, ..
+            } if fingers >= 2 && !self.gestures.pinched && self.page_gestures() => {
+                match direction {
+                    SwipeDirection::Right => self.go_back(ctx),
+                    SwipeDirection::Left => self.go_forward(ctx),
+                    SwipeDirection::Up | SwipeDirection::Down => {}
+                }
+            }
+            Gesture::LongPress { x, y } if !self.gestures.long_pressed => {
+                self.gestures.long_pressed = true;
+                self.open_link_menu(egui::pos2(x, y));
+            }
+            // Drags also scroll the page for egui; here they only hide or
+            // show the mobile bars
+            Gesture::Scroll { dy, .. } => self.mobile_ui.on_scroll(-dy),
+            // Taps and double-taps reach egui as pointer events
+            _ => {}
+        }
+    }
+
+    /// Open the link menu at `pos` if a link is under it.
+    fn open_link_menu(&mut self, pos: egui::Pos2) {
+        if let Some(href) = self.gestures.hovered_link.clone() {
+            self.gestures.link_menu = Some((pos, href));
+        }
+    }
+
+    fn draw_link_menu(&mut self, ctx: &egui::Context) {
+        let Some((pos, href)) = self.gestures.link_menu.clone() else {
+            return;
+        };
+        let base = self
+            .page
+            .as_ref()
+            .map_or(self.url_input.as_str(), |p| p.dom.url.as_str());
+        let url = resolve_url(base, &href);
+
+        let mut open = false;
+        let mut close = false;
+        let area = egui::Area::new(egui::Id::new("gesture_link_menu"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(pos + egui::vec2(8.0, 8.0))
+            .show(ctx, |ui| {
+                egui::Frame::menu(ui.style()).show(ui, |ui| {
+                    ui.set_min_width(200.0);
+                    ui.label(egui::RichText::new(truncate_str(&url, 48)).weak().small());
+                    ui.separator();
+                    if ui.button("Open link").clicked() {
+                        open = true;
+                    }
+                    if ui.button("Copy link address").clicked() {
+                        ctx.copy_text(url.clone());
+                        close = true;
+                    }
+                });
+            });
+        if area.response.clicked_elsewhere() || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            close = true;
+        }
+
+        if open {
+            self.gestures.link_menu = None;
+            self.url_input = url;
+            self.navigate(ctx);
+        } else if close {
+            self.gestures.link_menu = None;
+        }
+    }
+}
@@ -0,0 +1,388 @@
+//! Persistent visit history and URL-bar autocomplete for `BrowserApp`.
+//!
+//! Every successful page load is recorded (URL, title, last visit, visit
+//! count) in `history.json` in the profile directory. URL-bar suggestions
+//! combine this log with bookmarks and are ranked by frecency: visit count
+//! weighted by how recently the page was last seen. The back/forward stack
+//! in `navigation` stays per-session.
+//!
+//! The history side panel lists the log by day of last visit, filtered by a
+//! search over titles and URLs; entries can be deleted one by one or by
+//! time range (last hour, today, last week, everything).
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use alice_browser::local_time::{local_date, local_minute_of_day};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use super::BrowserApp;
+
+/// File name of the visit log inside the profile directory.
+pub const HISTORY_FILE: &str = "history.json";
+
+/// Entries kept on disk; the lowest-frecency ones are dropped beyond this.
+const MAX_ENTRIES: usize = 2000;
+
+/// Score added for bookmarked pages.
+const BOOKMARK_BONUS: f32 = 150.0;
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Seconds since the Unix epoch.
+#[must_use]
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// One visited URL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisitEntry {
+    pub url: String,
+    pub title: String,
+    /// Unix seconds of the latest visit
+    pub last_visit: u64,
+    pub visit_count: u32,
+}
+
+impl VisitEntry {
+    /// Visit count weighted by recency buckets.
+    #[must_use]
+    pub fn frecency(&self, now: u64) -> f32 {
+        let age = now.saturating_sub(self.last_visit);
+        let weight = match age {
+            a if a < 4 * DAY => 100.0,
+            a if a < 14 * DAY => 70.0,
+            a if a < 31 * DAY => 50.0,
+            a if a < 90 * DAY => 30.0,
+            _ => 10.0,
+        };
+        self.visit_count as f32 * weight
+    }
+}
+
+/// Every page visited, across sessions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryStore {
+    pub entries: Vec<VisitEntry>,
+}
+
+impl HistoryStore {
+    /// Load from the profile directory; missing or unreadable files yield an
+    /// empty log.
+    #[must_use]
+    pub fn load() -> Self {
+        let Ok(path) = alice_browser::profile::file_path(HISTORY_FILE) else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                log::warn!("Ignoring malformed {}: {e}", path.display());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Write to the profile directory.
+    pub fn save(&self) {
+        let result = alice_browser::profile::file_path(HISTORY_FILE).and_then(|path| {
+            let json = serde_json::to_string(self).map_err(std::io::Error::other)?;
+            std::fs::write(path, json)
+        });
+        if let Err(e) = result {
+            log::warn!("Could not save history: {e}");
+        }
+    }
+
+    /// Record a visit to `url` at `now`.
+    pub fn record_visit(&mut self, url: &str, title: &str, now: u64) {
+        if let Some(e) = self.entries.iter_mut().find(|e| e.url == url) {
+            e.visit_count = e.visit_count.saturating_add(1);
+            e.last_visit = now;
+            if !title.trim().is_empty() {
+                e.title = title.to_string();
+            }
+            return;
+        }
+        self.entries.push(VisitEntry {
+            url: url.to_string(),
+            title: title.to_string(),
+            last_visit: now,
+            visit_count: 1,
+        });
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries
+                .sort_by(|a, b| b.frecency(now).total_cmp(&a.frecency(now)));
+            self.entries.truncate(MAX_ENTRIES);
+        }
+    }
+
+    /// Forget `url`.
+    pub fn remove(&mut self, url: &str) {
+        self.entries.retain(|e| e.url != url);
+    }
+
+    /// Forget pages last visited at or after `since` (Unix seconds).
+    pub fn remove_since(&mut self, since: u64) {
+        self.entries.retain(|e| e.last_visit < since);
+    }
+}
+
+/// Time ranges offered by "Clear".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClearRange {
+    LastHour,
+    Today,
+    LastWeek,
+    Everything,
+}
+
+impl ClearRange {
+    pub const ALL: [Self; 4] = [
+        Self::LastHour,
+        Self::Today,
+        Self::LastWeek,
+        Self::Everything,
+    ];
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::LastHour => "Last hour",
+            Self::Today => "Today",
+            Self::LastWeek => "Last 7 days",
+            Self::Everything => "Everything",
+        }
+    }
+
+    /// Start of the range, in Unix seconds, when it ends at `now`.
+    #[must_use]
+    pub fn since(self, now: u64) -> u64 {
+        match self {
+            Self::LastHour => now.saturating_sub(60 * 60),
+            Self::Today => {
+                let minutes = u64::from(local_minute_of_day());
+                now.saturating_sub(minutes * 60 + now % 60)
+            }
+            Self::LastWeek => now.saturating_sub(7 * DAY),
+            Self::Everything => 0,
+        }
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Heading of a day's visits in the panel.
+pub(super) fn day_label(date: (i64, u32, u32), now: u64) -> String {
+    if date == local_date(now) {
+        return "Today".into();
+    }
+    if date == local_date(now.saturating_sub(DAY)) {
+        return "Yesterday".into();
+    }
+    let (year, month, day) = date;
+    let month = MONTHS[(month as usize).clamp(1, 12) - 1];
+    format!("{day} {month} {year}")
+}
+
+/// What the user did in the history panel this frame.
+enum HistoryAction {
+    Open(String),
+    Remove(String),
+    Clear(ClearRange),
+}
+
+/// One row of the URL-bar dropdown.
+#[derive(Debug, Clone)]
+pub struct UrlSuggestion {
+    pub url: String,
+    pub title: String,
+    pub bookmarked: bool,
+    pub score: f32,
+    /// A search suggested by the search engine, `title` being the query
+    pub search: bool,
+}
+
+impl BrowserApp {
+    /// Draw the history side panel: visits by day, newest first.
+    pub fn draw_history_panel(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        ui.heading("History");
+        ui.add(
+            egui::TextEdit::singleline(&mut self.history_query)
+                .hint_text("Search titles and addresses")
+                .desired_width(f32::INFINITY),
+        );
+        let mut action = None;
+        ui.menu_button("Clear\u{2026}", |ui| {
+            for range in ClearRange::ALL {
+                if ui.button(range.label()).clicked() {
+                    action = Some(HistoryAction::Clear(range));
+                    ui.close_menu();
+                }
+            }
+        });
+        ui.separator();
+
+        let q = self.history_query.trim().to_lowercase();
+        // Owned, as the rows load thumbnails through `self`
+        let mut visits: Vec<VisitEntry> = self
+            .visits
+            .entries
+            .iter()
+            .filter(|e| {
+                q.is_empty()
+                    || e.url.to_lowercase().contains(&q)
+                    || e.title.to_lowercase().contains(&q)
+            })
+            .cloned()
+            .collect();
+        visits.sort_by_key(|e| std::cmp::Reverse(e.last_visit));
+        if visits.is_empty() {
+            let note = if q.is_empty() {
+                "No pages visited yet."
+            } else {
+                "No visits match."
+            };
+            ui.colored_label(egui::Color32::GRAY, note);
+        }
+
+        let now = now_secs();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for day in visits.chunk_by(|a, b| local_date(a.last_visit) == local_date(b.last_visit))
+            {
+                let date = local_date(day[0].last_visit);
+                egui::CollapsingHeader::new(format!("{} ({})", day_label(date, now), day.len()))
+                    .id_salt(("history_day", date))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        for e in day {
+                            let label = if e.title.trim().is_empty() {
+                                e.url.as_str()
+                            } else {
+                                e.title.as_str()
+                            };
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .small_button("\u{2715}")
+                                    .on_hover_text("Remove from history")
+                                    .clicked()
+                                {
+                                    action = Some(HistoryAction::Remove(e.url.clone()));
+                                }
+                                self.draw_panel_thumbnail(ui, ctx, &e.url);
+                                let response = ui
+                                    .selectable_label(
+                                        e.url == self.url_input,
+                                        crate::ui::truncate_str(label, 32),
+                                    )
+                                    .on_hover_text(format!(
+                                        "{}\n{} visits, last {}",
+                                        e.url,
+                                        e.visit_count,
+                                        super::internal::ago(e.last_visit, now)
+                                    ));
+                                if response.clicked() {
+                                    action = Some(HistoryAction::Open(e.url.clone()));
+                                }
+                            });
+                        }
+                    });
+            }
+        });
+
+        match action {
+            Some(HistoryAction::Open(url)) => {
+                self.url_input = url;
+                self.navigate_typed(ctx);
+            }
+            Some(HistoryAction::Remove(url)) => {
+                self.visits.remove(&url);
+                self.visits.save();
+                self.forget_thumbnails([url.as_str()]);
+            }
+            Some(HistoryAction::Clear(range)) => {
+                let since = range.since(now);
+                let removed: Vec<String> = self
+                    .visits
+                    .entries
+                    .iter()
+                    .filter(|e| e.last_visit >= since)
+                    .map(|e| e.url.clone())
+                    .collect();
+                self.visits.remove_since(since);
+                self.visits.save();
+                self.forget_thumbnails(removed.iter().map(String::as_str));
+            }
+            None => {}
+        }
+    }
+
+    /// Log the page now in the URL bar as visited.
+    pub fn record_visit(&mut self, title: &str) {
+        let url = self.url_input.clone();
+        self.visits.record_visit(&url, title, now_secs());
+        self.visits.save();
+    }
+
+    /// History and bookmarks matching the URL-bar text, best first.
+    #[must_use]
+    pub fn url_suggestions(&self, limit: usize) -> Vec<UrlSuggestion> {
+        let q = self.url_input.trim().to_lowercase();
+        if q.is_empty() {
+            return Vec::new();
+        }
+        let now = now_secs();
+        let matches = |url: &str, title: &str| {
+            url.to_lowercase().contains(&q) || title.to_lowercase().contains(&q)
+        };
+
+        let mut out: Vec<UrlSuggestion> = self
+            .visits
+            .entries
+            .iter()
+            .filter(|e| matches(&e.url, &e.title))
+            .map(|e| {
+                let bookmarked = self.bookmarks.find_by_url(&e.url).is_some();
+                UrlSuggestion {
+                    url: e.url.clone(),
+                    title: e.title.clone(),
+                    bookmarked,
+                    score: e.frecency(now) + if bookmarked { BOOKMARK_BONUS } else { 0.0 },
+                    search: false,
+                }
+            })
+            .collect();
+        for b in self.bookmarks.suggest(&q, usize::MAX) {
+            if !out.iter().any(|s| s.url == b.url) {
+                out.push(UrlSuggestion {
+                    url: b.url.clone(),
+                    title: b.title.clone(),
+                    bookmarked: true,
+                    score: BOOKMARK_BONUS,
+                    search: false,
+                });
+            }
+        }
+
+        // Typing the start of a URL (past the scheme) ranks it first
+        for s in &mut out {
+            let bare = s
+                .url
+                .split_once("://")
+                .map_or(s.url.as_str(), |(_, rest)| rest)
+                .trim_start_matches("www.");
+            if bare.to_lowercase().starts_with(&q) {
+                s.score *= 2.0;
+            }
+        }
+
+        out.sort_by(|a, b| b.score.total_cmp(&a.score));
+        out.truncate(limit);
+        out
+    }
+}
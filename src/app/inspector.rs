@@ -0,0 +1,264 @@
+//! Page-load inspector window for `BrowserApp`.
+//!
+//! Shows the current page's decision log: how the cache answered, which
+//! subresource requests the ad blocker refuses and which elements the
+//! semantic filter removed, searchable by selector path or text ("why was
+//! this element removed?"). The log can be copied or saved as JSON.
+//! Stage timings are listed too, and the resource-timing overlay (a heat
+//! map over the SDF 2-D view) and the layout box overlay (every node's
+//! box outlined by tag category) are toggled here.
+//!
+//! Removed elements can also be shown greyed out in the flat view. Their
+//! context menus, and those of the blocks kept, correct the classifier:
+//! "This is content" / "This is an ad" are stored with the site's
+//! preferences as [`FeedbackRules`] and applied on every later load.
+
+use eframe::egui;
+
+use alice_browser::dom::filter::FeedbackRules;
+use alice_browser::engine::decisions::CacheDecision;
+use alice_browser::render::layout_debug::BoxCategory;
+use alice_browser::render::RenderMode;
+
+use super::BrowserApp;
+
+/// Profile file the JSON export is written to.
+const EXPORT_FILE: &str = "load-log.json";
+
+/// Rows shown per list before truncating.
+const MAX_ROWS: usize = 200;
+
+const fn cache_label(decision: CacheDecision) -> &'static str {
+    match decision {
+        CacheDecision::NotFetched => "not fetched",
+        CacheDecision::NoCache => "no cache",
+        CacheDecision::Hit => "hit",
+        CacheDecision::Miss => "miss (stored)",
+        CacheDecision::Bypassed => "bypassed (POST)",
+    }
+}
+
+impl BrowserApp {
+    /// Draw the inspector window.
+    pub fn draw_inspector(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_inspector;
+        let mut overlay_toggled = false;
+        let mut boxes_toggled = false;
+        let mut removed_toggled = false;
+        let mut forget: Option<String> = None;
+        egui::Window::new("Page inspector")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                let Some(ref page) = self.page else {
+                    ui.label("No page loaded");
+                    return;
+                };
+                let log = &page.decisions;
+
+                ui.label(format!(
+                    "Classifier: {} · Cache: {}",
+                    log.classifier,
+                    cache_label(log.cache)
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("Copy JSON").clicked() {
+                        ctx.copy_text(log.to_json());
+                    }
+                    if ui.button("Save JSON").clicked() {
+                        self.inspector_status = Some(
+                            match alice_browser::profile::file_path(EXPORT_FILE).and_then(|path| {
+                                std::fs::write(&path, log.to_json()).map(|()| path)
+                            }) {
+                                Ok(path) => format!("Saved to {}", path.display()),
+                                Err(e) => format!("Save failed: {e}"),
+                            },
+                        );
+                    }
+                    if let Some(ref status) = self.inspector_status {
+                        ui.label(status);
+                    }
+                });
+
+                ui.separator();
+                let t = &page.timings;
+                ui.label(format!(
+                    "Fetch {:.1} · Parse {:.1} · Filter {:.1} · Frames {:.1} · Layout {:.1} · \
+                     Scene {:.1} ms",
+                    t.fetch_ms, t.parse_ms, t.filter_ms, t.frames_ms, t.layout_ms, t.scene_ms
+                ));
+                overlay_toggled = ui
+                    .checkbox(
+                        &mut self.show_cost_overlay,
+                        "Resource timing overlay (SDF 2D view)",
+                    )
+                    .on_hover_text("Tint elements by text bytes, image bytes and layout time")
+                    .changed();
+                boxes_toggled = ui
+                    .checkbox(
+                        &mut self.show_layout_boxes,
+                        "Layout box overlay (Flat / SDF 2D view)",
+                    )
+                    .on_hover_text("Outline every laid-out node; hover one for its box")
+                    .changed();
+                if self.show_layout_boxes {
+                    ui.horizontal_wrapped(|ui| {
+                        for category in BoxCategory::ALL {
+                            let [r, g, b] = category.rgb();
+                            ui.colored_label(egui::Color32::from_rgb(r, g, b), category.label());
+                        }
+                    });
+                }
+
+                ui.separator();
+                ui.collapsing(format!("Blocked requests ({})", log.blocked.len()), |ui| {
+                    for req in log.blocked.iter().take(MAX_ROWS) {
+                        ui.horizontal(|ui| {
+                            ui.label(&req.tag);
+                            ui.monospace(crate::ui::truncate_str(&req.url, 56))
+                                .on_hover_text(&req.url);
+                            ui.colored_label(egui::Color32::GRAY, &req.rule);
+                        });
+                    }
+                });
+
+                if !log.frames.is_empty() {
+                    ui.collapsing(format!("Embedded frames ({})", log.frames.len()), |ui| {
+                        for frame in &log.frames {
+                            ui.monospace(crate::ui::truncate_str(&frame.url, 72))
+                                .on_hover_text(&frame.url);
+                            ui.label(format!("  {} · {:.0} ms", frame.summary(), frame.ms));
+                        }
+                    });
+                }
+
+                ui.separator();
+                ui.label(format!("Removed elements: {}", log.removed.len()));
+                removed_toggled = ui
+                    .checkbox(&mut self.show_removed, "Show removed elements (2D view)")
+                    .on_hover_text(
+                        "Reload keeping them, greyed out; right-click a block to mark it \
+                         as content or as an ad for this site",
+                    )
+                    .changed();
+                let rules = &self.site.prefs.feedback;
+                if !rules.is_empty() {
+                    ui.collapsing(
+                        format!("Corrections for this site ({})", rules.len()),
+                        |ui| {
+                            let marks = rules
+                                .content
+                                .iter()
+                                .map(|s| (s, "content"))
+                                .chain(rules.ads.iter().map(|s| (s, "ad")));
+                            for (selector, verdict) in marks {
+                                ui.horizontal(|ui| {
+                                    ui.monospace(selector);
+                                    ui.weak(verdict);
+                                    if ui.small_button("Forget").clicked() {
+                                        forget = Some(selector.clone());
+                                    }
+                                });
+                            }
+                        },
+                    );
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Why was this removed?");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.inspector_query)
+                            .hint_text("selector or text, e.g. div.ad-slot")
+                            .desired_width(220.0),
+                    );
+                });
+                let matches = log.why_removed(&self.inspector_query);
+                if matches.is_empty() && !self.inspector_query.trim().is_empty() {
+                    ui.colored_label(
+                        egui::Color32::GRAY,
+                        "Nothing matching was removed from this page",
+                    );
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        for removal in matches.into_iter().take(MAX_ROWS) {
+                            ui.monospace(crate::ui::truncate_str(&removal.path, 72))
+                                .on_hover_text(&removal.path);
+                            ui.label(format!(
+                                "  {} · {} node(s)",
+                                removal.describe(),
+                                removal.nodes
+                            ));
+                            if !removal.excerpt.is_empty() {
+                                ui.colored_label(
+                                    egui::Color32::GRAY,
+                                    format!("  \u{201c}{}\u{201d}", removal.excerpt),
+                                );
+                            }
+                        }
+                    });
+            });
+        self.show_inspector = open;
+
+        if let Some(selector) = forget {
+            self.update_feedback(ctx, |rules| rules.forget(&selector));
+        } else if removed_toggled {
+            self.apply_show_removed(ctx);
+        }
+
+        // The heat map is drawn by the SDF 2-D painter
+        if overlay_toggled && self.show_cost_overlay && self.render_mode != RenderMode::Sdf2D {
+            self.set_render_mode(RenderMode::Sdf2D);
+        }
+        if boxes_toggled
+            && self.show_layout_boxes
+            && !matches!(self.render_mode, RenderMode::Flat | RenderMode::Sdf2D)
+        {
+            self.set_render_mode(RenderMode::Flat);
+        }
+    }
+
+    /// Store a correction chosen from a block's context menu this frame.
+    pub fn handle_feedback_marks(&mut self, ctx: &egui::Context) {
+        if let Some(mark) = crate::ui::feedback::take(ctx) {
+            self.update_feedback(ctx, |rules| rules.mark(&mark.selector, mark.verdict));
+        }
+    }
+
+    /// Change the current site's corrections with `change`; if it reports a
+    /// change, save them and load the page again under them.
+    pub(super) fn update_feedback(
+        &mut self,
+        ctx: &egui::Context,
+        change: impl FnOnce(&mut FeedbackRules) -> bool,
+    ) {
+        let Some(origin) = self.site.origin.clone() else {
+            return;
+        };
+        let mut prefs = self.site.prefs.clone();
+        if !change(&mut prefs.feedback) {
+            return;
+        }
+        self.site.prefs = prefs.clone();
+        self.site_settings.set(&origin, prefs);
+        self.site_settings.save();
+        self.reload_for_feedback(ctx);
+    }
+
+    /// Load the page again after `show_removed` was toggled, switching to
+    /// the flat view, which is where removed elements are shown.
+    pub(super) fn apply_show_removed(&mut self, ctx: &egui::Context) {
+        if self.show_removed && self.render_mode != RenderMode::Flat {
+            self.set_render_mode(RenderMode::Flat);
+        }
+        self.reload_for_feedback(ctx);
+    }
+
+    /// Corrections and the removed-element view change what the pipeline
+    /// keeps, so they need a fresh load.
+    fn reload_for_feedback(&mut self, ctx: &egui::Context) {
+        if !self.loading && self.page.is_some() {
+            self.navigate_no_history(ctx);
+        }
+    }
+}
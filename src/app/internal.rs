@@ -0,0 +1,608 @@
+//! `alice://` pages for `BrowserApp`.
+//!
+//! Generates the HTML for the internal pages from the browser's state and
+//! shows it through the normal pipeline, synchronously and without touching
+//! the network. `alice://settings` is also a form: submitting it (a GET to
+//! the same URL) saves the preferences.
+
+use eframe::egui;
+use std::fmt::Write as _;
+
+use alice_browser::engine::internal::{self, Cell, HtmlPage, InternalPage, InternalRequest};
+use alice_browser::engine::pipeline::BrowserEngine;
+use alice_browser::net::proxy::{self, ProxyMode};
+use alice_browser::net::search_engine::SearchEngine;
+use alice_browser::net::speculate::{self, Speculation};
+use alice_browser::net::throttle::NetworkProfile;
+use alice_browser::render::fonts::Script;
+use alice_browser::render::palette::ThemeChoice;
+use alice_browser::render::RenderMode;
+
+use super::history::now_secs;
+use super::BrowserApp;
+
+/// Visit-log rows listed on `alice://history`.
+const MAX_HISTORY_ROWS: usize = 200;
+
+/// "5 min ago"-style age of a Unix timestamp.
+pub fn ago(then: u64, now: u64) -> String {
+    let secs = now.saturating_sub(then);
+    match secs {
+        0..=59 => "just now".into(),
+        60..=3_599 => format!("{} min ago", secs / 60),
+        3_600..=86_399 => format!("{} h ago", secs / 3_600),
+        _ => format!("{} days ago", secs / 86_400),
+    }
+}
+
+impl BrowserApp {
+    /// Generate and show the internal page at `url`.
+    pub fn open_internal(&mut self, url: &str, ctx: &egui::Context) {
+        let Some(request) = internal::parse(url) else {
+            return;
+        };
+        let html = match request.page {
+            Some(InternalPage::About) => Self::about_page(),
+            Some(InternalPage::Settings) => self.settings_page(&request, ctx),
+            Some(InternalPage::History) => self.history_page(),
+            Some(InternalPage::Cache) => self.cache_page(),
+            Some(InternalPage::Stats) => self.stats_page(),
+            Some(InternalPage::Welcome) => super::onboarding::DEMO_HTML.to_string(),
+            None => internal::not_found(&request.host),
+        };
+        // A saved settings form is shown as plain `alice://settings`, keeping
+        // the proxy password out of the address bar and history
+        let plain;
+        let url = if request.page == Some(InternalPage::Settings) && request.param("save").is_some()
+        {
+            plain = InternalPage::Settings.url();
+            if let Some(entry) = self.history.get_mut(self.history_idx) {
+                if entry == url {
+                    entry.clone_from(&plain);
+                }
+            }
+            plain.as_str()
+        } else {
+            url
+        };
+
+        let width = self.content_rect.map_or(800.0, |r| r.width());
+        match BrowserEngine::new(width).process_html(&html, url, 200) {
+            Ok(page) => {
+                self.leave_page();
+                self.reset_page_views();
+                #[cfg(feature = "search")]
+                self.index_page_search(&page);
+                self.url_input = url.to_string();
+                self.page = Some(page);
+                self.error = None;
+                self.enter_site(url);
+            }
+            Err(e) => {
+                self.error = Some(e.to_string());
+                self.page = None;
+            }
+        }
+    }
+
+    fn about_page() -> String {
+        let mut page = HtmlPage::new(InternalPage::About.title());
+        page.paragraph("Built-in pages. Each has a stable URL that can be bookmarked.");
+        let rows = InternalPage::ALL
+            .into_iter()
+            .map(|p| vec![Cell::link(p.url(), p.url()), Cell::text(p.title())]);
+        page.table(&["Address", "Page"], rows);
+        page.finish()
+    }
+
+    /// `alice://settings`; applies and saves the form values when the URL
+    /// carries `save=1`, drops a site's preferences for `forget=<origin>`
+    /// and takes a domain off the ad-block allowlist for `block=<domain>`.
+    fn settings_page(&mut self, request: &InternalRequest, ctx: &egui::Context) -> String {
+        if let Some(origin) = request.param("forget") {
+            self.site_settings.sites.remove(origin);
+            self.site_settings.save();
+        }
+        if let Some(domain) = request.param("block") {
+            if self.adblock_allowlist.remove(domain) {
+                self.save_adblock_allowlist();
+            }
+        }
+        let saved = request.param("save").is_some();
+        if saved {
+            let old = self.settings.clone();
+            if let Some(mode) = request.param("render_mode").and_then(RenderMode::from_key) {
+                self.settings.render_mode = mode;
+            }
+            self.settings.dark_mode = request.param("dark_mode").is_some();
+            self.settings.reduce_motion = request.param("reduce_motion").is_some();
+            self.settings.wayback_fallback = request.param("wayback_fallback").is_some();
+            self.settings.prefetch_links = request.param("prefetch_links").is_some();
+            self.settings.embed_frames = request.param("embed_frames").is_some();
+            self.settings.search_suggestions = request.param("search_suggestions").is_some();
+            if let Some(px) = request.param("max_image_size").and_then(|v| v.parse().ok()) {
+                self.settings.max_image_size = px;
+            }
+            if let Some(secs) = request
+                .param("session_autosave_secs")
+                .and_then(|v| v.parse().ok())
+            {
+                self.settings.session_autosave_secs = secs;
+            }
+            if let Some(profile) = request.param("network").and_then(NetworkProfile::from_key) {
+                self.settings.network_profile = profile;
+            }
+            if let Some(level) = request.param("speculation").and_then(Speculation::from_key) {
+                self.settings.speculation = level;
+            }
+            if let Some(theme) = request.param("scene_theme").and_then(ThemeChoice::from_key) {
+                self.settings.scene_theme = theme;
+            }
+            if let Some(mode) = request.param("proxy_mode").and_then(ProxyMode::from_key) {
+                self.settings.proxy_mode = mode;
+            }
+            if let Some(engine) = request
+                .param("search_engine")
+                .and_then(SearchEngine::from_key)
+            {
+                self.settings.search_engine = engine;
+            }
+            for (name, field) in [
+                ("cache_pages", &mut self.settings.cache_pages),
+                ("memory_budget_mb", &mut self.settings.memory_budget_mb),
+                ("prefetch_max_links", &mut self.settings.prefetch_max_links),
+                (
+                    "prefetch_concurrency",
+                    &mut self.settings.prefetch_concurrency,
+                ),
+            ] {
+                if let Some(n) = request.param(name).and_then(|v| v.parse().ok()) {
+                    *field = n;
+                }
+            }
+            for (name, field) in [
+                ("ui_font_size", &mut self.settings.ui_font_size),
+                ("reader_font_size", &mut self.settings.reader_font_size),
+            ] {
+                if let Some(size) = request
+                    .param(name)
+                    .and_then(|v| v.parse::<f32>().ok())
+                    .filter(|size| size.is_finite() && *size > 0.0)
+                {
+                    *field = size;
+                }
+            }
+            if let Some(script) = request
+                .param("cjk_font")
+                .and_then(Script::from_key)
+                .filter(|s| s.is_cjk())
+            {
+                self.settings.cjk_font = script;
+            }
+            for (name, field) in [
+                ("proxy_url", &mut self.settings.proxy_url),
+                ("proxy_username", &mut self.settings.proxy_username),
+                ("proxy_bypass", &mut self.settings.proxy_bypass),
+                ("frame_hosts", &mut self.settings.frame_hosts),
+                ("font_family", &mut self.settings.font_family),
+                ("homepage", &mut self.settings.homepage),
+                ("search_template", &mut self.settings.search_template),
+                ("suggest_template", &mut self.settings.suggest_template),
+            ] {
+                if let Some(value) = request.param(name) {
+                    *field = value.trim().to_string();
+                }
+            }
+            // The password is never echoed into the form; left blank, it is kept
+            if let Some(password) = request.param("proxy_password").filter(|p| !p.is_empty()) {
+                self.settings.proxy_password = password.to_string();
+            }
+            if self.settings.proxy_username.is_empty() {
+                self.settings.proxy_password.clear();
+            }
+            self.settings.save();
+            self.apply_changed_settings(&old, ctx);
+        }
+
+        let s = &self.settings;
+        let mut form = format!(
+            "<form action=\"{}\" method=\"get\"><input type=\"hidden\" name=\"save\" value=\"1\">\
+             <p>Start in <select name=\"render_mode\">",
+            InternalPage::Settings.url()
+        );
+        for mode in RenderMode::ALL {
+            let selected = if mode == s.render_mode {
+                " selected"
+            } else {
+                ""
+            };
+            let _ = write!(
+                form,
+                "<option value=\"{}\"{selected}>{}</option>",
+                mode.key(),
+                mode.label()
+            );
+        }
+        form.push_str("</select></p>");
+        let _ = write!(
+            form,
+            "<p>Home page <input type=\"text\" name=\"homepage\" value=\"{}\" \
+             placeholder=\"None\"></p><p>Search with <select name=\"search_engine\">",
+            internal::escape(&s.homepage)
+        );
+        for engine in SearchEngine::ALL {
+            let selected = if engine == s.search_engine {
+                " selected"
+            } else {
+                ""
+            };
+            let _ = write!(
+                form,
+                "<option value=\"{}\"{selected}>{}</option>",
+                engine.key(),
+                engine.label()
+            );
+        }
+        let _ = write!(
+            form,
+            "</select> <input type=\"text\" name=\"search_template\" value=\"{}\" \
+             placeholder=\"Custom: https://search.example/?q=%s\"> \
+             <input type=\"text\" name=\"suggest_template\" value=\"{}\" \
+             placeholder=\"Custom suggestions: https://search.example/suggest?q=%s\"></p>",
+            internal::escape(&s.search_template),
+            internal::escape(&s.suggest_template)
+        );
+        for (name, label, on) in [
+            ("dark_mode", "Dark theme", s.dark_mode),
+            ("reduce_motion", "Reduce motion", s.reduce_motion),
+            (
+                "wayback_fallback",
+                "Offer archived copies of dead pages",
+                s.wayback_fallback,
+            ),
+            (
+                "prefetch_links",
+                "Prefetch linked pages for OZ mode (obeys robots.txt)",
+                s.prefetch_links,
+            ),
+            (
+                "embed_frames",
+                "Show embedded frames from the same site inline",
+                s.embed_frames,
+            ),
+            (
+                "search_suggestions",
+                "Suggest searches in the address bar",
+                s.search_suggestions,
+            ),
+        ] {
+            let checked = if on { " checked" } else { "" };
+            let _ = write!(
+                form,
+                "<p><input type=\"checkbox\" name=\"{name}\"{checked}> {label}</p>"
+            );
+        }
+        let _ = write!(
+            form,
+            "<p>Also show frames from <input type=\"text\" name=\"frame_hosts\" \
+             value=\"{}\" placeholder=\"maps.example, video.example\"></p>",
+            internal::escape(&s.frame_hosts)
+        );
+        let _ = write!(
+            form,
+            "<p>Largest image side (px) <input type=\"number\" name=\"max_image_size\" \
+             value=\"{}\"></p>",
+            s.max_image_size
+        );
+        for (name, label, value) in [
+            ("cache_pages", "Pages cached", s.cache_pages.to_string()),
+            (
+                "memory_budget_mb",
+                "Memory budget (MB, 0 for no limit)",
+                s.memory_budget_mb.to_string(),
+            ),
+            (
+                "prefetch_max_links",
+                "Links prefetched per page",
+                s.prefetch_max_links.to_string(),
+            ),
+            (
+                "prefetch_concurrency",
+                "Prefetches at once",
+                s.prefetch_concurrency.to_string(),
+            ),
+            (
+                "ui_font_size",
+                "Interface text size (pt)",
+                s.ui_font_size.to_string(),
+            ),
+            (
+                "reader_font_size",
+                "Reader text size (pt)",
+                s.reader_font_size.to_string(),
+            ),
+        ] {
+            let _ = write!(
+                form,
+                "<p>{label} <input type=\"number\" name=\"{name}\" value=\"{value}\"></p>"
+            );
+        }
+        let _ = write!(
+            form,
+            "<p>Save the session for restoring every <input type=\"number\" \
+             name=\"session_autosave_secs\" value=\"{}\" min=\"0\"> seconds (0: never)</p>",
+            s.session_autosave_secs
+        );
+        let _ = write!(
+            form,
+            "<p>Font <input type=\"text\" name=\"font_family\" value=\"{}\" \
+             placeholder=\"Built-in\"></p><p>Chinese characters drawn as <select name=\"cjk_font\">",
+            internal::escape(&s.font_family)
+        );
+        for script in Script::CJK {
+            let selected = if script == s.cjk_font {
+                " selected"
+            } else {
+                ""
+            };
+            let _ = write!(
+                form,
+                "<option value=\"{}\"{selected}>{}</option>",
+                script.key(),
+                script.label()
+            );
+        }
+        form.push_str("</select></p>");
+        form.push_str("<p>3-D scene colours <select name=\"scene_theme\">");
+        for theme in ThemeChoice::ALL {
+            let selected = if theme == s.scene_theme {
+                " selected"
+            } else {
+                ""
+            };
+            let _ = write!(
+                form,
+                "<option value=\"{}\"{selected}>{}</option>",
+                theme.key(),
+                theme.label()
+            );
+        }
+        form.push_str("</select></p>");
+        form.push_str("<p>Hovered links <select name=\"speculation\">");
+        for level in Speculation::ALL {
+            let selected = if level == s.speculation {
+                " selected"
+            } else {
+                ""
+            };
+            let _ = write!(
+                form,
+                "<option value=\"{}\"{selected}>{}</option>",
+                level.key(),
+                level.label()
+            );
+        }
+        form.push_str("</select></p>");
+        form.push_str("<p>Simulated network (developer) <select name=\"network\">");
+        for profile in NetworkProfile::ALL {
+            let selected = if profile == s.network_profile {
+                " selected"
+            } else {
+                ""
+            };
+            let _ = write!(
+                form,
+                "<option value=\"{}\"{selected}>{}</option>",
+                profile.key(),
+                profile.label()
+            );
+        }
+        form.push_str("</select></p>");
+        form.push_str("<p>Proxy <select name=\"proxy_mode\">");
+        for mode in ProxyMode::ALL {
+            let selected = if mode == s.proxy_mode {
+                " selected"
+            } else {
+                ""
+            };
+            let _ = write!(
+                form,
+                "<option value=\"{}\"{selected}>{}</option>",
+                mode.key(),
+                mode.label()
+            );
+        }
+        form.push_str("</select></p>");
+        let _ = write!(
+            form,
+            "<p>Manual proxy <input type=\"text\" name=\"proxy_url\" value=\"{}\" \
+             placeholder=\"socks5://host:1080\"></p>\
+             <p>User name <input type=\"text\" name=\"proxy_username\" value=\"{}\"> \
+             Password <input type=\"password\" name=\"proxy_password\" placeholder=\"{}\"></p>\
+             <p>No proxy for <input type=\"text\" name=\"proxy_bypass\" value=\"{}\"></p>",
+            internal::escape(&s.proxy_url),
+            internal::escape(&s.proxy_username),
+            if s.proxy_password.is_empty() {
+                "none"
+            } else {
+                "unchanged"
+            },
+            internal::escape(&s.proxy_bypass),
+        );
+        form.push_str("<p><input type=\"submit\" value=\"Save\"></p></form>");
+
+        let mut page = HtmlPage::new(InternalPage::Settings.title());
+        if saved {
+            page.paragraph("Settings saved.");
+        }
+        if let Err(e) = self.settings.proxy_config() {
+            page.paragraph(&format!("Proxy not used: {e}"));
+        }
+        page.raw(&form).heading("Sites");
+        if self.site_settings.sites.is_empty() {
+            page.paragraph("No per-site preferences. Set them for the current site from the stats panel (Site settings…).");
+        }
+        let sites = self.site_settings.sites.iter().map(|(origin, prefs)| {
+            let forget: String = url::form_urlencoded::byte_serialize(origin.as_bytes()).collect();
+            vec![
+                Cell::link(origin.as_str(), origin.as_str()),
+                Cell::text(prefs.summary()),
+                Cell::link(
+                    format!("{}?forget={forget}", InternalPage::Settings.url()),
+                    "forget",
+                ),
+            ]
+        });
+        page.table(&[], sites);
+        page.heading("Ad blocking off");
+        if self.adblock_allowlist.is_empty() {
+            page.paragraph("Blocking is on everywhere. Turn it off for a site with the shield next to the address bar.");
+        }
+        let allowed = self.adblock_allowlist.domains().map(|domain| {
+            let block: String = url::form_urlencoded::byte_serialize(domain.as_bytes()).collect();
+            vec![
+                Cell::text(domain),
+                Cell::link(
+                    format!("{}?block={block}", InternalPage::Settings.url()),
+                    "block again",
+                ),
+            ]
+        });
+        page.table(&[], allowed);
+        page.heading("Other").facts([
+            (
+                "Cookies",
+                if self.persist_cookies {
+                    "kept between sessions".to_string()
+                } else {
+                    "this session only (--no-persist-cookies)".to_string()
+                },
+            ),
+            ("Proxy", proxy::shared().summary()),
+            (
+                "Profile",
+                alice_browser::profile::file_path(super::settings::SETTINGS_FILE)
+                    .map_or_else(|e| e.to_string(), |p| p.display().to_string()),
+            ),
+        ]);
+        page.finish()
+    }
+
+    fn history_page(&self) -> String {
+        let mut page = HtmlPage::new(InternalPage::History.title());
+
+        page.heading("This session");
+        let session = self.history.iter().enumerate().rev().map(|(i, url)| {
+            let marker = if i == self.history_idx { "current" } else { "" };
+            vec![Cell::link(url.as_str(), url.as_str()), Cell::text(marker)]
+        });
+        page.table(&[], session);
+
+        page.heading("All visits");
+        let now = now_secs();
+        let mut visits: Vec<_> = self.visits.entries.iter().collect();
+        visits.sort_by_key(|e| std::cmp::Reverse(e.last_visit));
+        if visits.is_empty() {
+            page.paragraph("No pages visited yet.");
+        }
+        let rows = visits.into_iter().take(MAX_HISTORY_ROWS).map(|e| {
+            let label = if e.title.trim().is_empty() {
+                e.url.as_str()
+            } else {
+                e.title.as_str()
+            };
+            vec![
+                Cell::link(e.url.as_str(), label),
+                Cell::text(e.visit_count.to_string()),
+                Cell::text(ago(e.last_visit, now)),
+            ]
+        });
+        page.table(&["Page", "Visits", "Last visit"], rows);
+        page.finish()
+    }
+
+    fn cache_page(&self) -> String {
+        let mut page = HtmlPage::new(InternalPage::Cache.title());
+
+        page.heading("Page cache");
+        #[cfg(feature = "smart-cache")]
+        page.facts([
+            ("Cached pages", self.page_cache.cached_pages().to_string()),
+            (
+                "Hit rate",
+                format!("{:.1}%", self.page_cache.hit_rate() * 100.0),
+            ),
+        ]);
+        #[cfg(not(feature = "smart-cache"))]
+        page.paragraph("Disabled (built without the smart-cache feature).");
+
+        page.heading("Images and thumbnails");
+        page.facts([
+            (
+                "Images decoded",
+                self.image_loader.loaded_count().to_string(),
+            ),
+            (
+                "Images loading",
+                self.image_loader.pending_count().to_string(),
+            ),
+            ("Page thumbnails", self.thumbnails.len().to_string()),
+        ]);
+        page.finish()
+    }
+
+    fn stats_page(&self) -> String {
+        use std::sync::atomic::Ordering;
+
+        let stats = &self.block_stats;
+        let mut page = HtmlPage::new(InternalPage::Stats.title());
+        page.heading("Ad blocker (this session)").facts([
+            (
+                "Ads blocked",
+                stats.total_ads.load(Ordering::Relaxed).to_string(),
+            ),
+            (
+                "Trackers blocked",
+                stats.total_trackers.load(Ordering::Relaxed).to_string(),
+            ),
+            (
+                "Requests checked",
+                stats.total_checked.load(Ordering::Relaxed).to_string(),
+            ),
+            ("Filter rules", self.adblock.rules().count().to_string()),
+        ]);
+
+        let speculation = speculate::shared().stats();
+        page.heading("Hovered links (this session)").facts([
+            ("Warmed up", speculation.hovers.to_string()),
+            ("Hosts resolved", speculation.lookups.to_string()),
+            ("Connections opened", speculation.preconnects.to_string()),
+            ("Failed", speculation.failures.to_string()),
+            (
+                "Used by a click",
+                format!(
+                    "{} lookups, {} connections",
+                    speculation.dns_hits, speculation.connection_hits
+                ),
+            ),
+            ("Saved", format!("{} ms", speculation.saved.as_millis())),
+        ]);
+
+        #[cfg(feature = "telemetry")]
+        {
+            let snap = self.metrics.snapshot();
+            page.heading("Page loads").facts([
+                ("Pages loaded", snap.page_loads.to_string()),
+                ("P50 load", format!("{:.0} ms", snap.p50_load_ms)),
+                ("P99 load", format!("{:.0} ms", snap.p99_load_ms)),
+                ("Domains", format!("~{:.0}", snap.unique_domains)),
+            ]);
+            page.heading("Pipeline stages (P50)").facts(
+                snap.stage_p50_ms
+                    .iter()
+                    .map(|(stage, ms)| (*stage, format!("{ms:.1} ms"))),
+            );
+        }
+        page.finish()
+    }
+}
@@ -0,0 +1,258 @@
+//! Keyboard navigation for `BrowserApp`'s 2-D views.
+//!
+//! While no widget has the keyboard, Tab and Shift+Tab move a focus ring
+//! through the page's links and form fields in document order
+//! (`render::tab_order`), Enter activates the ringed target, Escape drops
+//! the ring and the arrow keys scroll. The Flat view rings targets through
+//! `ui::Find` and `FormState`, the SDF 2-D view through
+//! `SdfPaintState::focus_ring`. Form fields are only live in the Flat view,
+//! so Enter on one in the SDF view switches to Flat first.
+//!
+//! F shows link hints: every link and field in view gets a letter code,
+//! and typing a code activates its target like Enter on the ring. There
+//! are no tabs, so a code typed with Shift held queues a link behind the
+//! toolbar's **Next** button instead of opening it. Backspace takes back a
+//! letter, Escape hides the hints.
+
+use eframe::egui;
+
+use alice_browser::render::tab_order::{hint_labels, FocusIndex, FocusKind, FocusTarget};
+use alice_browser::render::RenderMode;
+
+use super::BrowserApp;
+use crate::oz::resolve_url;
+
+/// Distance scrolled per arrow-key press, in points.
+const ARROW_SCROLL: f32 = 48.0;
+
+/// Keyboard focus on the current page.
+#[derive(Debug, Default)]
+pub struct KeyboardNav {
+    /// Target with the focus ring
+    pub focused: Option<FocusTarget>,
+    /// Scroll the target into view on the next frame
+    pub scroll_pending: bool,
+    /// Top and bottom of the 2-D view in layout pixels, as last drawn
+    pub view: (f32, f32),
+    /// Link hints on screen
+    pub hints: Option<HintMode>,
+}
+
+/// Link hints shown over the targets in view.
+#[derive(Debug, Default)]
+pub struct HintMode {
+    /// Each hinted target with its code
+    pub targets: Vec<(String, FocusTarget)>,
+    /// Letters typed so far, lower case
+    pub typed: String,
+    /// A letter was typed with Shift: queue the link rather than open it
+    pub queue: bool,
+}
+
+impl HintMode {
+    /// Targets whose code starts with the letters typed so far.
+    pub fn matching(&self) -> impl Iterator<Item = &(String, FocusTarget)> {
+        self.targets
+            .iter()
+            .filter(|(code, _)| code.starts_with(&self.typed))
+    }
+}
+
+/// A navigation key pressed this frame.
+enum NavKey {
+    Next,
+    Previous,
+    Activate,
+    Clear,
+}
+
+impl BrowserApp {
+    /// Apply Tab, Enter, Escape and the arrow keys to the 2-D views. Called
+    /// once per frame, before the panels are laid out.
+    pub fn handle_keyboard_nav(&mut self, ctx: &egui::Context) {
+        if !matches!(self.render_mode, RenderMode::Flat | RenderMode::Sdf2D)
+            || self.page.as_ref().is_none_or(|p| p.text_view.is_some())
+            || ctx.memory(|m| m.focused().is_some())
+        {
+            self.keyboard.hints = None;
+            return;
+        }
+        if self.keyboard.hints.is_some() {
+            self.handle_hint_keys(ctx);
+            return;
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::F)) {
+            self.show_link_hints();
+            return;
+        }
+        let ringed = self.keyboard.focused.is_some();
+        let (key, scroll) = ctx.input_mut(|i| {
+            let key = if i.consume_key(egui::Modifiers::SHIFT, egui::Key::Tab) {
+                Some(NavKey::Previous)
+            } else if i.consume_key(egui::Modifiers::NONE, egui::Key::Tab) {
+                Some(NavKey::Next)
+            } else if ringed && i.consume_key(egui::Modifiers::NONE, egui::Key::Enter) {
+                Some(NavKey::Activate)
+            } else if ringed && i.consume_key(egui::Modifiers::NONE, egui::Key::Escape) {
+                Some(NavKey::Clear)
+            } else {
+                None
+            };
+            let mut scroll = 0.0;
+            if i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown) {
+                scroll -= ARROW_SCROLL;
+            }
+            if i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp) {
+                scroll += ARROW_SCROLL;
+            }
+            if scroll != 0.0 {
+                i.smooth_scroll_delta.y += scroll;
+            }
+            (key, scroll)
+        });
+        if scroll != 0.0 {
+            ctx.request_repaint();
+        }
+
+        match key {
+            Some(NavKey::Next) => self.step_keyboard_focus(true),
+            Some(NavKey::Previous) => self.step_keyboard_focus(false),
+            Some(NavKey::Activate) => self.activate_keyboard_focus(ctx),
+            Some(NavKey::Clear) => self.keyboard.focused = None,
+            None => {}
+        }
+    }
+
+    /// Move the focus ring to the next (or previous) link or form field.
+    fn step_keyboard_focus(&mut self, forward: bool) {
+        let Some(ref page) = self.page else {
+            return;
+        };
+        // Built from the layout as it is now, so lazily laid out parts of
+        // long pages join the order as they appear
+        let index = FocusIndex::build(&page.layout);
+        let (top, bottom) = self.keyboard.view;
+        let next = index
+            .step(self.keyboard.focused.as_ref(), forward, top, bottom)
+            .cloned();
+        self.keyboard.scroll_pending = next.is_some();
+        self.keyboard.focused = next;
+    }
+
+    /// Give every link and field in view a hint code.
+    fn show_link_hints(&mut self) {
+        let Some(ref page) = self.page else {
+            return;
+        };
+        let (top, bottom) = self.keyboard.view;
+        let visible: Vec<FocusTarget> = FocusIndex::build(&page.layout)
+            .targets
+            .into_iter()
+            .filter(|t| t.rect[1] + t.rect[3] >= top && t.rect[1] <= bottom)
+            .collect();
+        if visible.is_empty() {
+            return;
+        }
+        let targets = hint_labels(visible.len())
+            .into_iter()
+            .zip(visible)
+            .collect();
+        self.keyboard.hints = Some(HintMode {
+            targets,
+            ..HintMode::default()
+        });
+    }
+
+    /// Apply the letters, Backspace and Escape typed while hints are shown.
+    fn handle_hint_keys(&mut self, ctx: &egui::Context) {
+        let Some(ref mut hints) = self.keyboard.hints else {
+            return;
+        };
+        let mut cancel = false;
+        ctx.input_mut(|i| {
+            for event in std::mem::take(&mut i.events) {
+                match event {
+                    egui::Event::Text(text) => {
+                        for c in text.chars().filter(char::is_ascii_alphabetic) {
+                            hints.queue |= c.is_ascii_uppercase();
+                            hints.typed.push(c.to_ascii_lowercase());
+                        }
+                    }
+                    egui::Event::Key {
+                        key: egui::Key::Backspace,
+                        pressed: true,
+                        ..
+                    } => {
+                        hints.typed.pop();
+                    }
+                    egui::Event::Key {
+                        key: egui::Key::Escape,
+                        pressed: true,
+                        ..
+                    } => cancel = true,
+                    other => i.events.push(other),
+                }
+            }
+        });
+        ctx.request_repaint();
+
+        // Codes are prefix-free, so a complete code matches alone
+        let first = hints.matching().next().cloned();
+        let Some((code, target)) = first.filter(|_| !cancel) else {
+            self.keyboard.hints = None;
+            return;
+        };
+        if code != hints.typed {
+            return;
+        }
+        let queue = hints.queue;
+        self.keyboard.hints = None;
+        match target.kind {
+            FocusKind::Link(ref href) if queue => {
+                let base = self.page.as_ref().map_or("", |p| p.dom.url.as_str());
+                self.url_queue.push_back(resolve_url(base, href));
+            }
+            _ => {
+                self.keyboard.focused = Some(target);
+                self.activate_keyboard_focus(ctx);
+            }
+        }
+    }
+
+    /// Follow the focused link, or operate the focused form field.
+    fn activate_keyboard_focus(&mut self, ctx: &egui::Context) {
+        let Some(target) = self.keyboard.focused.clone() else {
+            return;
+        };
+        match target.kind {
+            FocusKind::Link(href) => {
+                let base = self.page.as_ref().map_or("", |p| p.dom.url.as_str());
+                self.url_input = resolve_url(base, &href);
+                self.navigate(ctx);
+            }
+            FocusKind::Control(idx) => {
+                self.set_render_mode(RenderMode::Flat);
+                self.forms.activate(idx);
+                self.keyboard.scroll_pending = true;
+            }
+        }
+    }
+
+    /// Keep the focus ring on its node after the layout changed; the ring
+    /// is dropped if the node is gone.
+    pub fn refresh_keyboard_focus(&mut self) {
+        let Some(ref page) = self.page else {
+            return;
+        };
+        let Some(ref mut target) = self.keyboard.focused else {
+            return;
+        };
+        match target.node(&page.layout) {
+            Some(node) => {
+                let b = &node.bounds;
+                target.rect = [b.x, b.y, b.width, b.height];
+            }
+            None => self.keyboard.focused = None,
+        }
+    }
+}
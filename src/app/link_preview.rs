@@ -0,0 +1,217 @@
+//! Link preview cards in Flat mode for `BrowserApp`.
+//!
+//! A link the pointer rests on for [`CARD_DELAY`] gets a small card above
+//! the pointer with the linked page's title, meta description and favicon —
+//! the flat page's counterpart of OZ mode's hologram. The page is fetched
+//! with `oz::fetch_link_preview` as a preview job of the shared fetch
+//! scheduler, and its icon right after it. Finished cards are kept in a
+//! small LRU cache, so hovering a link again shows its card at once instead
+//! of fetching the page again.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use alice_browser::net::image::{fetch_image, ImageData};
+use alice_browser::net::scheduler::{self, Priority};
+use alice_browser::net::speculate::HoverIntent;
+use alice_browser::render::RenderMode;
+use eframe::egui;
+
+use super::BrowserApp;
+use crate::oz::{fetch_link_preview, resolve_url, split_fragment, LinkPreview, LinkPreviewStatus};
+use crate::ui::truncate_str;
+
+/// How long the pointer must rest on a link before its card shows.
+pub const CARD_DELAY: Duration = Duration::from_millis(500);
+
+/// Cards kept for links hovered before.
+const CARD_CACHE_SIZE: usize = 32;
+
+/// Longest side of a decoded favicon.
+const FAVICON_SIZE: u32 = 32;
+
+const CARD_WIDTH: f32 = 320.0;
+
+/// What a card's fetch sends back: the preview and its decoded favicon.
+type CardFetch = (LinkPreview, Option<ImageData>);
+
+/// A finished preview, its favicon uploaded.
+struct LinkCard {
+    preview: LinkPreview,
+    favicon: Option<egui::TextureHandle>,
+}
+
+/// Hover tracking, the card on screen and the cache behind it.
+pub struct LinkCards {
+    intent: HoverIntent,
+    /// Link whose card is on screen
+    shown: Option<String>,
+    cards: HashMap<String, LinkCard>,
+    /// Front = least recently shown
+    order: VecDeque<String>,
+    /// Fetch in flight, and the link it is for
+    pending: Option<(String, mpsc::Receiver<CardFetch>)>,
+}
+
+impl Default for LinkCards {
+    fn default() -> Self {
+        Self {
+            intent: HoverIntent::with_delay(CARD_DELAY),
+            shown: None,
+            cards: HashMap::new(),
+            order: VecDeque::new(),
+            pending: None,
+        }
+    }
+}
+
+impl LinkCards {
+    /// A preview is being fetched.
+    #[must_use]
+    pub fn is_fetching(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Keep a finished card, evicting the least recently shown if full.
+    fn insert(
+        &mut self,
+        ctx: &egui::Context,
+        url: String,
+        preview: LinkPreview,
+        icon: Option<ImageData>,
+    ) {
+        let favicon = icon.map(|icon| {
+            let image = egui::ColorImage::from_rgba_unmultiplied(
+                [icon.width as usize, icon.height as usize],
+                &icon.rgba,
+            );
+            ctx.load_texture(
+                format!("favicon_{url}"),
+                image,
+                egui::TextureOptions::LINEAR,
+            )
+        });
+        self.order.retain(|u| *u != url);
+        self.order.push_back(url.clone());
+        self.cards.insert(url, LinkCard { preview, favicon });
+        while self.cards.len() > CARD_CACHE_SIZE {
+            let Some(old) = self.order.pop_front() else {
+                break;
+            };
+            self.cards.remove(&old);
+        }
+    }
+
+    /// Mark `url` as recently shown.
+    fn touch(&mut self, url: &str) {
+        if let Some(pos) = self.order.iter().position(|u| u == url) {
+            if let Some(u) = self.order.remove(pos) {
+                self.order.push_back(u);
+            }
+        }
+    }
+}
+
+impl BrowserApp {
+    /// Follow the link under the pointer (`hovered`, as reported by the
+    /// page this frame) and show its card once it has rested there for
+    /// [`CARD_DELAY`]. Flat mode only.
+    pub fn update_link_card(&mut self, ctx: &egui::Context, hovered: Option<&str>) {
+        let cards = &mut self.link_cards;
+        match cards.pending.as_ref().map(|(_, rx)| rx.try_recv()) {
+            Some(Ok((preview, icon))) => {
+                if let Some((url, _)) = cards.pending.take() {
+                    cards.insert(ctx, url, preview, icon);
+                }
+            }
+            Some(Err(mpsc::TryRecvError::Disconnected)) => cards.pending = None,
+            _ => {}
+        }
+
+        let target = hovered
+            .filter(|_| self.render_mode == RenderMode::Flat)
+            .zip(self.page.as_ref())
+            .map(|(href, page)| (resolve_url(&page.dom.url, href), &page.dom.url))
+            .filter(|(url, page_url)| {
+                url.starts_with("http") && split_fragment(url).0 != split_fragment(page_url).0
+            })
+            .map(|(url, _)| url);
+
+        let now = Instant::now();
+        let fired = cards.intent.update(target.as_deref(), now);
+        if let Some(wait) = cards.intent.remaining(now) {
+            ctx.request_repaint_after(wait);
+        }
+        if cards.shown != target {
+            cards.shown = None;
+        }
+        if let Some(url) = fired {
+            if cards.cards.contains_key(&url) {
+                cards.touch(&url);
+            } else if cards.pending.as_ref().map(|(u, _)| u) != Some(&url) {
+                let (tx, rx) = mpsc::channel();
+                let network = self.settings.network_profile.conditions();
+                let repaint = ctx.clone();
+                let asked = url.clone();
+                let work = &self.page_work;
+                scheduler::shared().submit(&url, Priority::Preview, work, move |_| {
+                    let preview = fetch_link_preview(&asked);
+                    let icon = preview
+                        .favicon
+                        .as_deref()
+                        .and_then(|icon| fetch_image(icon, FAVICON_SIZE, network));
+                    if tx.send((preview, icon)).is_ok() {
+                        repaint.request_repaint();
+                    }
+                });
+                cards.pending = Some((url.clone(), rx));
+            }
+            cards.shown = Some(url);
+        }
+
+        if let Some(url) = self.link_cards.shown.clone() {
+            self.draw_link_card(ctx, &url);
+        }
+    }
+
+    /// The card for `url` just above the pointer: a spinner while it loads.
+    fn draw_link_card(&self, ctx: &egui::Context, url: &str) {
+        let Some(pointer) = ctx.pointer_hover_pos() else {
+            return;
+        };
+        let card = self.link_cards.cards.get(url);
+        egui::Area::new(egui::Id::new("link_preview_card"))
+            .order(egui::Order::Tooltip)
+            .pivot(egui::Align2::LEFT_BOTTOM)
+            .fixed_pos(pointer + egui::vec2(12.0, -12.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(CARD_WIDTH);
+                    let Some(card) = card else {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.weak("Loading preview\u{2026}");
+                        });
+                        return;
+                    };
+                    let preview = &card.preview;
+                    if let LinkPreviewStatus::Error(ref e) = preview.status {
+                        ui.weak(format!("No preview: {}", truncate_str(e, 80)));
+                        return;
+                    }
+                    ui.horizontal(|ui| {
+                        if let Some(ref tex) = card.favicon {
+                            ui.add(egui::Image::new(tex).fit_to_exact_size(egui::vec2(16.0, 16.0)));
+                        }
+                        ui.strong(truncate_str(&preview.title, 60));
+                    });
+                    if !preview.description.is_empty() {
+                        ui.label(truncate_str(&preview.description, 200));
+                    }
+                    ui.weak(truncate_str(url, 60));
+                });
+            });
+    }
+}
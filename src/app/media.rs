@@ -0,0 +1,146 @@
+//! Audio and video cards for `BrowserApp`.
+//!
+//! Once a page is shown, the files behind its `<video>` and `<audio>`
+//! elements are probed in the background for their type, duration and size
+//! (see `alice_browser::render::media`), and the cards are redrawn as the
+//! answers arrive. Following a card's link, or any link to an audio or
+//! video file, hands the file to the system's default player instead of
+//! loading it as a page.
+
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc;
+
+use alice_browser::dom::DomNode;
+use alice_browser::net::fetch::FetchOptions;
+use alice_browser::net::scheduler::{self, Priority};
+use alice_browser::net::sniff;
+use alice_browser::render::media::{self, MediaInfo, MediaMetadata};
+
+use super::BrowserApp;
+use crate::oz::resolve_url;
+
+/// Most media files probed per page.
+const MAX_PROBES: usize = 8;
+
+/// What is known of the current page's audio and video.
+#[derive(Default)]
+pub struct PageMedia {
+    /// Probed metadata by source, as written in the page
+    pub meta: HashMap<String, MediaMetadata>,
+    /// Sources of the page's cards, resolved
+    sources: HashSet<String>,
+    rx: Option<mpsc::Receiver<(String, MediaMetadata)>>,
+}
+
+impl BrowserApp {
+    /// Start probing the audio and video of the page on screen.
+    pub(super) fn probe_page_media(&mut self, ctx: &egui::Context) {
+        self.media = PageMedia::default();
+        let Some(ref page) = self.page else {
+            return;
+        };
+        let mut found = Vec::new();
+        collect_media(&page.dom.root, &mut found);
+        let base = page.dom.url.clone();
+        let sources: Vec<(String, String)> = found
+            .into_iter()
+            .filter_map(|info| info.src)
+            .map(|src| {
+                let resolved = resolve_url(&base, &src);
+                (src, resolved)
+            })
+            .collect();
+        self.media.sources = sources.iter().map(|(_, url)| url.clone()).collect();
+        if sources.is_empty() {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let options = FetchOptions {
+            network: self.settings.network_profile.conditions(),
+            ..FetchOptions::default()
+        };
+        let ctx = ctx.clone();
+        scheduler::shared().submit(&base, Priority::Preview, &self.page_work, move |token| {
+            let mut seen = HashSet::new();
+            for (src, url) in sources {
+                if token.is_cancelled() {
+                    return;
+                }
+                if seen.len() >= MAX_PROBES || !seen.insert(url.clone()) {
+                    continue;
+                }
+                match media::probe(&url, &options) {
+                    Ok(meta) => {
+                        if tx.send((src, meta)).is_err() {
+                            return;
+                        }
+                        ctx.request_repaint();
+                    }
+                    Err(e) => log::info!("Media probe for {url} failed: {e}"),
+                }
+            }
+        });
+        self.media.rx = Some(rx);
+    }
+
+    /// Take in probe results; the SDF cards are rebuilt with them.
+    pub fn poll_media(&mut self) {
+        let Some(ref rx) = self.media.rx else {
+            return;
+        };
+        let arrived: Vec<_> = rx.try_iter().collect();
+        if arrived.is_empty() {
+            return;
+        }
+        self.media.meta.extend(arrived);
+        self.paint_elements = None;
+    }
+
+    /// Whether `url` is better opened in a media player than as a page:
+    /// a card's source, or a link to an audio or video file.
+    pub(super) fn is_media_link(&self, url: &str) -> bool {
+        self.media.sources.contains(url) || sniff::media_type_from_url(url).is_some()
+    }
+
+    /// Open `url` with the system's default handler.
+    pub(super) fn open_externally(&self, url: &str) {
+        let Ok(parsed) = url::Url::parse(url) else {
+            return;
+        };
+        if !matches!(parsed.scheme(), "http" | "https") {
+            log::warn!("Not opening {url} externally: unsupported scheme");
+            return;
+        }
+        match system_open(parsed.as_str()) {
+            Ok(_) => log::info!("Opened {url} in the default player"),
+            Err(e) => log::warn!("Could not open {url} externally: {e}"),
+        }
+    }
+}
+
+/// Media elements under `node`, in document order.
+fn collect_media(node: &DomNode, out: &mut Vec<MediaInfo>) {
+    if !node.is_visible() {
+        return;
+    }
+    if let Some(info) = MediaInfo::from_node(node) {
+        out.push(info);
+        return;
+    }
+    for child in &node.children {
+        collect_media(child, out);
+    }
+}
+
+/// Hand `url`, or a file path, to the system's default handler.
+pub(super) fn system_open(url: &str) -> std::io::Result<std::process::Child> {
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(target_os = "windows")]
+    let program = "explorer";
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let program = "xdg-open";
+    std::process::Command::new(program).arg(url).spawn()
+}
@@ -0,0 +1,143 @@
+//! Memory budget for `BrowserApp`.
+//!
+//! Every couple of seconds the app adds up what it holds (see
+//! [`MemoryUsage`]) and, when that passes the budget from the settings,
+//! gives memory back as [`relief`] decides: back/forward cached pages,
+//! least recently left first, then the decoded images and their textures.
+//! Dropped images of the shown page are not fetched again until the user
+//! asks, so an image-heavy page cannot thrash. The stats panel shows the
+//! figures and what was dropped last.
+
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+
+use alice_browser::engine::memory::{format_bytes, relief, HeapSize, MemoryUsage};
+
+use super::BrowserApp;
+
+/// How often usage is added up; walking a large page's trees is not free.
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Latest usage and what the budget last made the browser drop.
+#[derive(Default)]
+pub struct MemoryWatch {
+    last_check: Option<Instant>,
+    pub usage: MemoryUsage,
+    /// Page whose images were dropped, until they are reloaded
+    images_dropped: Option<String>,
+    /// What was dropped last, for the stats panel
+    pub last_relief: Option<String>,
+}
+
+impl MemoryWatch {
+    /// Whether the images of `page_url` were dropped under pressure and
+    /// should not be requested again.
+    #[must_use]
+    pub fn images_dropped(&self, page_url: &str) -> bool {
+        self.images_dropped.as_deref() == Some(page_url)
+    }
+}
+
+impl BrowserApp {
+    /// Approximate bytes held by the shown page, images and cached pages.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = MemoryUsage::default();
+        if let Some(ref page) = self.page {
+            let mem = page.memory();
+            usage.dom = mem.dom + mem.text_view;
+            usage.layout = mem.layout;
+            usage.scenes = mem.scene;
+        }
+        if let Some(ref elements) = self.paint_elements {
+            usage.scenes += elements.total_size();
+        }
+        #[cfg(feature = "sdf-render")]
+        if let Some(ref scene) = self.spatial_scene {
+            usage.scenes += scene.total_size();
+        }
+        usage.decoded_images = self.image_loader.decoded_bytes();
+        usage.image_textures = self
+            .image_textures
+            .values()
+            .map(|t| t.size()[0] * t.size()[1] * 4)
+            .sum();
+        usage.cached_pages = self.back_forward.bytes();
+        usage
+    }
+
+    /// Refresh the usage figures if they are due, and free memory if they
+    /// pass the budget.
+    pub fn check_memory_pressure(&mut self) {
+        if self
+            .memory
+            .last_check
+            .is_some_and(|t| t.elapsed() < CHECK_INTERVAL)
+        {
+            return;
+        }
+        self.memory.last_check = Some(Instant::now());
+        self.memory.usage = self.memory_usage();
+
+        let budget = self.settings.memory_budget_mb * 1024 * 1024;
+        let plan = relief(&self.memory.usage, budget);
+        if plan.is_none() {
+            return;
+        }
+        let mut dropped = Vec::new();
+        if plan.cached_pages > 0 {
+            let freed = self.back_forward.shrink_by(plan.cached_pages);
+            dropped.push(format!("{} of cached pages", format_bytes(freed)));
+        }
+        if plan.images {
+            dropped.push(format!(
+                "{} of images",
+                format_bytes(self.memory.usage.images())
+            ));
+            self.image_textures.clear();
+            self.image_loader.clear();
+            self.memory.images_dropped = self.page.as_ref().map(|p| p.dom.url.clone());
+        }
+        let note = format!("Over budget: dropped {}", dropped.join(" and "));
+        log::info!("{note}");
+        self.memory.last_relief = Some(note);
+        self.memory.usage = self.memory_usage();
+    }
+
+    /// Fetch the shown page's images again after they were dropped.
+    pub fn reload_images(&mut self) {
+        self.memory.images_dropped = None;
+    }
+
+    /// The stats panel's memory section.
+    pub fn draw_memory_stats(&mut self, ui: &mut egui::Ui) {
+        let usage = self.memory.usage;
+        ui.heading("Memory");
+        for (part, bytes) in usage.rows() {
+            if bytes > 0 {
+                ui.label(format!("{part}: {}", format_bytes(bytes)));
+            }
+        }
+        let budget = self.settings.memory_budget_mb;
+        ui.label(if budget == 0 {
+            format!("Total: {} (no budget)", format_bytes(usage.total()))
+        } else {
+            format!(
+                "Total: {} of {}",
+                format_bytes(usage.total()),
+                format_bytes(budget * 1024 * 1024)
+            )
+        })
+        .on_hover_text("Approximate; set the budget in Settings");
+        if let Some(ref note) = self.memory.last_relief {
+            ui.weak(note);
+        }
+        let dropped = self
+            .page
+            .as_ref()
+            .is_some_and(|p| self.memory.images_dropped(&p.dom.url));
+        if dropped && ui.button("Reload images").clicked() {
+            self.reload_images();
+        }
+    }
+}
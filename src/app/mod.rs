@@ -3,19 +3,111 @@
 //! This module declares the `BrowserApp` struct and its `Default` impl.
 //! All methods are split across the sibling sub-modules:
 //!
+//! - `autofill`   — saved form autofill profiles (`autofill.vault`)
+//! - `bookmarks`  — bookmark store, star button, side panel
+//! - `clipboard`  — rich copies (HTML, images) to the system clipboard
 //! - `navigation` — page loading, history, async fetch
-//! - `toolbar`    — address bar and controls
+//! - `network`    — network panel: the page's requests, filtering, HAR export
+//! - `toolbar`    — address bar and controls; mobile bottom bar on narrow windows
 //! - `content`    — main viewport rendering (2-D, SDF, OZ)
+//! - `thumbnails` — page snapshots for the history popup and panel, kept in the profile's `thumbnails` directory
+//! - `dom_inspector` — DOM tree window: tags, attributes, classifications, layout boxes
+//! - `diagnostics` — filter-list benchmark window, classifier model loading
+//! - `gallery`    — saved 3-D scene snapshots, `.aliscene` files (feature `sdf-render`)
+//! - `find`       — find in page, jump between matches (feature `search`)
+//! - `focus`      — "Focus in 3D": one article/section alone in Spatial3D (feature `sdf-render`)
+//! - `frame_profiler` — rolling flame-bar overlay of per-frame stage times
+//! - `inspector`  — page-load decision log (removals, blocks, cache), classifier corrections
+//! - `internal`   — `alice://` pages (settings, history, cache, stats)
+//! - `gamepad`    — controller navigation (feature `gamepad`)
+//! - `gestures`   — touch and trackpad gestures: pinch zoom, two-finger swipe back/forward, long-press link menu
+//! - `history`    — persistent visit log, URL-bar autocomplete, history panel
+//! - `keyboard`   — Tab focus ring over links and form fields, arrow-key scrolling
+//! - `link_preview` — Flat-mode cards for hovered links (title, description, favicon)
+//! - `memory`     — memory usage figures, budget-driven eviction of cached pages and images
+//! - `media`      — audio/video cards: metadata probes, opening files externally
+//! - `read_aloud` — reader-mode article spoken paragraph by paragraph (feature `voice-web`)
+//! - `reader`     — reader mode (main article, font size, line width)
+//! - `resources`  — the page's subresources by origin, per-site origin blocking
+//! - `restore`    — session autosave (`session.jsonl`), "Restore previous session" at startup
+//! - `save_page`  — "Save page as…": self-contained HTML or MHTML in the profile's `saved` directory
+//! - `session`    — batch URL lists (text, OPML) loaded as a grid or OZ constellations
+//! - `settings`   — persisted preferences (`settings.toml`), settings window (Cmd+,)
+//! - `site_settings` — per-site preferences (reader, dark, zoom), ad-block allowlist
+//! - `sitemap`    — site map from `sitemap.xml`: tree, OZ constellations, orbital system
+//! - `suggest`    — search-engine suggestions in the URL-bar dropdown
+//! - `onboarding` — first-run tour over a built-in demo page
+//! - `oz_gpu`     — OZ particle text as instanced GPU glyph quads (feature `sdf-render`)
+//! - `oz_search`  — OZ search bar over the page and prefetched links (feature `sdf-render`)
+//! - `picking`    — Spatial3D picking: hover outlines a primitive and names its element, clicking a link portal flies through it (feature `sdf-render`)
+//! - `page_menu`  — right-click menu entries: open or queue links, copy, save images, block elements
+//! - `paste`      — URL-bar paste chooser (open all, search), queued URLs
+//! - `print`      — Cmd+P: the article or page as a PDF on the chosen paper and margins
+//! - `table_export` — "Export table as CSV/JSON" files in the profile's `exports` directory
+//! - `tasks`      — task manager (page memory, frame cost, discard)
+//! - `tls`        — padlock, certificate window, invalid-certificate interstitial
+//! - `wayback`    — Internet Archive copies of dead, previously visited pages
 
+pub mod autofill;
+pub mod bookmarks;
+pub mod clipboard;
 pub mod content;
+pub mod diagnostics;
+pub mod dom_inspector;
+#[cfg(feature = "search")]
+pub mod find;
+#[cfg(feature = "sdf-render")]
+pub mod focus;
+pub mod frame_profiler;
+#[cfg(feature = "sdf-render")]
+pub mod gallery;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+pub mod gestures;
+pub mod history;
+pub mod inspector;
+pub mod internal;
+pub mod keyboard;
+pub mod link_preview;
+pub mod media;
+pub mod memory;
 pub mod navigation;
+pub mod network;
+pub mod onboarding;
+#[cfg(feature = "sdf-render")]
+pub mod oz_gpu;
+#[cfg(feature = "sdf-render")]
+pub mod oz_search;
+pub mod page_menu;
+pub mod paste;
+#[cfg(feature = "sdf-render")]
+pub mod picking;
+pub mod print;
+#[cfg(feature = "voice-web")]
+pub mod read_aloud;
+pub mod reader;
+pub mod resources;
+pub mod restore;
+pub mod save_page;
+pub mod session;
+pub mod settings;
+pub mod site_settings;
+pub mod sitemap;
+pub mod suggest;
+pub mod table_export;
+pub mod tasks;
+pub mod thumbnails;
+pub mod tls;
 pub mod toolbar;
+pub mod wayback;
 
 use eframe::egui;
 use std::sync::{mpsc, Arc};
 
+use alice_browser::dom::model::ClassifierModel;
 use alice_browser::engine::pipeline::{PageError, PageResult};
-use alice_browser::net::adblock::{AdBlockEngine, BlockStats};
+use alice_browser::engine::workers::CancelToken;
+use alice_browser::net::adblock::{AdBlockEngine, Allowlist, BlockStats};
 use alice_browser::render::RenderMode;
 
 use crate::oz::LinkPreview;
@@ -28,22 +120,112 @@ pub struct BrowserApp {
     pub page: Option<PageResult>,
     pub error: Option<String>,
     pub loading: bool,
+    /// The load in flight is a soft reload of `page`
+    pub reloading: bool,
     pub fetch_rx: Option<mpsc::Receiver<Result<PageResult, PageError>>>,
+    /// Image sources of the page being loaded, sent while it is laid out
+    pub image_prefetch_rx: Option<mpsc::Receiver<String>>,
+    /// Cancelled when a new load starts, stopping what was fetched for
+    /// the page left: its link prefetches and previews
+    pub page_work: CancelToken,
+    /// Token of the page load in flight; cancelled by a new navigation or
+    /// the toolbar's stop button
+    pub load_work: CancelToken,
     pub render_mode: RenderMode,
     pub show_stats: bool,
     pub dark_mode: bool,
-    // History (back / forward)
+    /// Accessibility: static OZ grid, no fades, single-shot 3D renders
+    pub reduce_motion: bool,
+    /// Preferences loaded at startup (`settings.toml`)
+    pub settings: settings::Settings,
+    /// Settings window, and the preferences being edited in it
+    pub show_settings: bool,
+    pub settings_draft: Option<settings::Settings>,
+    /// First-run tour, while it is open
+    pub onboarding: Option<onboarding::Onboarding>,
+    /// Save cookies to the profile directory between sessions
+    /// (disabled with `--no-persist-cookies`)
+    pub persist_cookies: bool,
+    // Bookmarks
+    pub bookmarks: bookmarks::BookmarkStore,
+    pub show_bookmarks: bool,
+    /// History side panel and its search box
+    pub show_history_panel: bool,
+    pub history_query: String,
+    pub bookmark_edit: Option<(bookmarks::BookmarkEditTarget, String)>,
+    /// URL-bar suggestion dropdown is open
+    pub url_suggest_open: bool,
+    /// Suggestion highlighted with the arrow keys
+    pub url_suggest_selected: Option<usize>,
+    /// Search-engine completions of the URL-bar text
+    pub search_suggestions: suggest::SearchSuggestions,
+    /// Paste into the URL bar awaiting a choice (open all, search)
+    pub paste_choice: Option<alice_browser::net::paste::PasteIntent>,
+    /// Pasted addresses still to open, behind the toolbar's Next button
+    pub url_queue: std::collections::VecDeque<String>,
+    /// Values typed into the current page's forms
+    pub forms: crate::ui::forms::FormState,
+    /// Tab focus ring on the current page
+    pub keyboard: keyboard::KeyboardNav,
+    // Reader mode
+    pub reader: Option<reader::ReaderView>,
+    pub reader_font_size: f32,
+    pub reader_line_width: f32,
+    #[cfg(feature = "voice-web")]
+    pub read_aloud: read_aloud::ReadAloudState,
+    /// Wrap long lines of plain-text resources
+    pub wrap_text: bool,
+    // History (back / forward, this session)
     pub history: Vec<String>,
     pub history_idx: usize,
+    /// Pages left for another, by history index
+    pub back_forward: alice_browser::engine::back_forward::BackForwardCache,
+    /// History index of the page on screen, if it has one
+    pub page_entry: Option<usize>,
+    /// History index of the page being loaded, if it has one
+    pub load_entry: Option<usize>,
+    /// Scroll position (layout pixels) to return to on the next frame, for
+    /// a page restored from the back/forward cache
+    pub restore_scroll: Option<f32>,
+    /// Id of the element a `#fragment` link scrolls to on the next frame
+    pub pending_anchor: Option<String>,
+    /// Audio and video of the page on screen
+    pub media: media::PageMedia,
+    /// Link under the pointer, warmed up once hovered for a moment
+    pub hover_intent: alice_browser::net::speculate::HoverIntent,
+    /// Preview card of the link under the pointer (Flat mode)
+    pub link_cards: link_preview::LinkCards,
+    /// Visit log across sessions (autocomplete)
+    pub visits: history::HistoryStore,
     // Image loading
     pub image_loader: alice_browser::net::image::ImageLoader,
     pub image_textures: std::collections::HashMap<String, egui::TextureHandle>,
+    // Clipboard (opened on first rich copy)
+    pub clipboard: Option<arboard::Clipboard>,
+    /// "Copy image" waiting for the image to download
+    pub pending_image_copy: Option<String>,
+    /// "Save image" waiting for the image to download
+    pub pending_image_save: Option<String>,
+    /// Where the last table export went, and when
+    pub export_notice: Option<(String, std::time::Instant)>,
+    // Page thumbnails (history popup and panel)
+    pub thumbnails: alice_browser::render::thumbnail::ThumbnailCache,
+    pub thumb_textures: std::collections::HashMap<String, egui::TextureHandle>,
+    /// Addresses with no thumbnail on disk either
+    pub thumb_missing: std::collections::HashSet<String>,
+    /// History URL awaiting a snapshot after load
+    pub thumb_capture_pending: Option<String>,
+    pub thumb_screenshot_requested: bool,
+    /// Central panel rect of the last frame (screenshot crop region)
+    pub content_rect: Option<egui::Rect>,
     #[cfg(feature = "smart-cache")]
     pub page_cache: std::sync::Arc<alice_browser::net::cache::CachedFetcher>,
     #[cfg(feature = "search")]
     pub search_query: String,
     #[cfg(feature = "search")]
     pub search_index: Option<alice_browser::search::PageSearch>,
+    #[cfg(feature = "search")]
+    pub find: find::FindState,
     #[cfg(feature = "telemetry")]
     pub metrics: alice_browser::telemetry::BrowserMetrics,
     #[cfg(feature = "telemetry")]
@@ -51,7 +233,13 @@ pub struct BrowserApp {
     pub sdf_paint_state: alice_browser::render::sdf_paint::SdfPaintState,
     pub paint_elements: Option<Vec<alice_browser::render::sdf_ui::PaintElement>>,
     #[cfg(feature = "sdf-render")]
-    pub sdf_texture: Option<egui::TextureHandle>,
+    pub sdf_texture: Option<content::SdfFrame>,
+    /// Frame `sdf_texture` is fading in over, and when the fade began
+    #[cfg(feature = "sdf-render")]
+    pub sdf_fade: Option<(content::SdfFrame, std::time::Instant)>,
+    /// Background CPU raymarcher refining the still view
+    #[cfg(feature = "sdf-render")]
+    pub refiner: alice_browser::render::sdf_renderer::ProgressiveRenderer,
     #[cfg(feature = "sdf-render")]
     pub sdf_mode_rendered: Option<RenderMode>,
     // 3-D camera state
@@ -63,11 +251,55 @@ pub struct BrowserApp {
     pub cam_dragging: bool,
     #[cfg(feature = "sdf-render")]
     pub spatial_scene: Option<alice_browser::render::sdf_ui::SdfScene>,
+    /// Picking over `spatial_scene`; reset whenever it is replaced
+    #[cfg(feature = "sdf-render")]
+    pub spatial_pick: picking::SpatialPick,
+    /// Block `spatial_scene` shows instead of the whole page
+    #[cfg(feature = "sdf-render")]
+    pub focus_3d: Option<focus::FocusedBlock>,
+    /// What frustum and occlusion culling kept of it in the last frame
+    #[cfg(feature = "sdf-render")]
+    pub cull_stats: Option<alice_browser::render::culling::CullStats>,
+    /// Palette the 3-D scene and sky are currently drawn with
+    #[cfg(feature = "sdf-render")]
+    pub scene_theme: alice_browser::render::palette::SceneTheme,
     #[cfg(feature = "sdf-render")]
     pub gpu_renderer: Option<alice_browser::render::gpu_renderer::GpuRenderer>,
+    /// eframe's wgpu renderer, when `gpu_renderer` shares its device
+    #[cfg(feature = "sdf-render")]
+    pub egui_wgpu: Option<std::sync::Arc<egui::mutex::RwLock<eframe::egui_wgpu::Renderer>>>,
+    /// `gpu_renderer`'s output texture as registered with `egui_wgpu`
+    #[cfg(feature = "sdf-render")]
+    pub gpu_texture: Option<egui::TextureId>,
+    /// OZ particle text on the GPU, when eframe runs on wgpu
+    #[cfg(feature = "sdf-render")]
+    pub oz_glyphs: Option<oz_gpu::OzGlyphs>,
+    /// Saved scene snapshots
+    #[cfg(feature = "sdf-render")]
+    pub gallery: gallery::Gallery,
+    #[cfg(feature = "sdf-render")]
+    pub show_gallery: bool,
     // OZ Stream state
     #[cfg(feature = "sdf-render")]
     pub stream_state: Option<alice_browser::render::stream::StreamState>,
+    /// OZ heading rings (document map) for the current stream
+    #[cfg(feature = "sdf-render")]
+    pub oz_doc_map: Option<alice_browser::render::docmap::DocMap>,
+    /// Document-map section currently pulled forward
+    #[cfg(feature = "sdf-render")]
+    pub oz_focus_section: Option<usize>,
+    /// Topics of the OZ stream's texts, for prefetched texts to join
+    #[cfg(all(feature = "sdf-render", feature = "search"))]
+    pub oz_topics: Option<alice_browser::search::cluster::TopicModel>,
+    /// Pages left in OZ mode, kept as background galaxies
+    #[cfg(feature = "sdf-render")]
+    pub oz_galaxies: alice_browser::render::galaxy::Galaxies,
+    /// Seconds the galaxies have spun for (still in reduced motion)
+    #[cfg(feature = "sdf-render")]
+    pub oz_galaxy_time: f32,
+    /// Text of the OZ search bar
+    #[cfg(feature = "sdf-render")]
+    pub oz_search_query: String,
     /// Pending URL from OZ mode double-click on a link
     #[cfg(feature = "sdf-render")]
     pub oz_pending_url: Option<String>,
@@ -88,6 +320,9 @@ pub struct BrowserApp {
     /// Hologram animation start time
     #[cfg(feature = "sdf-render")]
     pub oz_hologram_start: Option<std::time::Instant>,
+    /// Multiple of the normal particle flow speed (controller triggers)
+    #[cfg(feature = "sdf-render")]
+    pub oz_flow_speed: f32,
     /// Background link prefetch receiver
     #[cfg(feature = "sdf-render")]
     pub oz_prefetch_rx: Option<mpsc::Receiver<Vec<alice_browser::render::stream::TextMeta>>>,
@@ -97,12 +332,106 @@ pub struct BrowserApp {
     /// Buffer for prefetched texts (accumulated before OZ mode is active)
     #[cfg(feature = "sdf-render")]
     pub oz_prefetch_buffer: Vec<alice_browser::render::stream::TextMeta>,
+    /// robots.txt, crawl-delay, dedupe and backoff rules for link prefetch,
+    /// shared by every page's prefetch workers
+    #[cfg(feature = "sdf-render")]
+    pub prefetch_policy: std::sync::Arc<alice_browser::net::prefetch_policy::PrefetchPolicy>,
     pub _app_start: std::time::Instant,
     #[cfg(feature = "sdf-render")]
     pub last_frame_time: std::time::Instant,
     // Ad blocker
-    pub _adblock: Arc<AdBlockEngine>,
+    pub adblock: Arc<AdBlockEngine>,
     pub block_stats: BlockStats,
+    /// Sites loaded without blocking (`adblock-allowlist.txt`)
+    pub adblock_allowlist: Allowlist,
+    /// Filter-list diagnostics window
+    pub show_filter_diagnostics: bool,
+    pub filter_bench: Option<alice_browser::net::adblock_bench::BenchReport>,
+    /// Custom rules typed into the diagnostics window, not yet loaded
+    pub custom_rules_input: String,
+    /// Classifier weights loaded from `settings.classifier_model`; `None`
+    /// for the built-in classifier
+    pub classifier_model: Option<Arc<ClassifierModel>>,
+    /// Model path typed into the diagnostics window
+    pub model_path_input: String,
+    /// Why the last model file was rejected
+    pub model_error: Option<String>,
+    /// Per-site preferences (`site-settings.toml`)
+    pub site_settings: site_settings::SiteSettings,
+    /// Site of the current page and its applied preferences
+    pub site: site_settings::ActiveSite,
+    pub show_site_settings: bool,
+    /// Form autofill profiles (`autofill.vault`)
+    pub autofill: autofill::AutofillProfiles,
+    pub show_autofill: bool,
+    /// Archived copy offered for a dead page, while the error is shown
+    pub wayback: Option<wayback::WaybackOffer>,
+    /// Zoom of the current page; restored from the site's preferences
+    pub page_zoom: f32,
+    /// Page inspector window (load decision log)
+    pub show_inspector: bool,
+    pub inspector_query: String,
+    /// Load pages with removed elements kept and greyed out, for correcting
+    /// the classifier (page inspector)
+    pub show_removed: bool,
+    /// Result of the last JSON export
+    pub inspector_status: Option<String>,
+    /// Tint elements by resource cost (SDF 2-D view)
+    pub show_cost_overlay: bool,
+    /// Outline every layout box by tag category (Flat and SDF 2-D views)
+    pub show_layout_boxes: bool,
+    /// Task manager window
+    pub show_tasks: bool,
+    /// DOM tree inspector window and the node picked in it
+    pub show_dom_tree: bool,
+    pub dom_inspector: dom_inspector::DomInspector,
+    /// Network panel (the page's request log)
+    pub show_network: bool,
+    pub network_panel: network::NetworkPanel,
+    /// Batch URL session and its window
+    pub session: session::SessionWindow,
+    pub show_session: bool,
+    /// Site map of a site's `sitemap.xml` and its window
+    pub site_map: sitemap::SiteMapWindow,
+    pub show_site_map: bool,
+    /// Page held back at the invalid-certificate interstitial
+    pub tls_interstitial: Option<tls::TlsInterstitial>,
+    /// Certificate window, opened from the padlock
+    pub show_certificate: bool,
+    /// Resources window (subresources by origin)
+    pub show_resources: bool,
+    /// Save page window
+    pub save_page: save_page::SavePageWindow,
+    pub show_save_page: bool,
+    /// Print window
+    pub print: print::PrintWindow,
+    pub show_print: bool,
+    /// Session autosave and the restore offer
+    pub restore: restore::SessionRestore,
+    /// CPU time of the last `update` call, in milliseconds
+    pub frame_ms: f32,
+    /// Stage times of the last frames, and their overlay
+    pub frame_profiler: alice_browser::telemetry::frame_profiler::FrameProfiler,
+    pub show_frame_profiler: bool,
+    /// Memory usage and what the budget last dropped
+    pub memory: memory::MemoryWatch,
+    /// URL of a page discarded from the task manager, until it is reloaded
+    pub discarded: Option<String>,
+    // Spatial audio cues
+    #[cfg(feature = "spatial-audio")]
+    pub audio: alice_browser::audio::AudioCues,
+    // Controller navigation
+    #[cfg(feature = "gamepad")]
+    pub gamepad: alice_browser::gamepad::Gamepads,
+    /// Show the controller's button hints over the page
+    #[cfg(feature = "gamepad")]
+    pub show_gamepad_hints: bool,
+    // Touch and trackpad gestures
+    pub gestures: gestures::GestureState,
+    /// Bars of the mobile layout, used on narrow windows
+    pub mobile_ui: alice_browser::mobile::ui::MobileUI,
+    /// Mobile layout at any width (`--mobile`)
+    pub force_mobile: bool,
 }
 
 impl Default for BrowserApp {
@@ -112,20 +441,69 @@ impl Default for BrowserApp {
             page: None,
             error: None,
             loading: false,
+            reloading: false,
             fetch_rx: None,
+            image_prefetch_rx: None,
+            page_work: CancelToken::new(),
+            load_work: CancelToken::new(),
             render_mode: RenderMode::Flat,
             show_stats: true,
             dark_mode: false,
+            reduce_motion: false,
+            settings: settings::Settings::default(),
+            show_settings: false,
+            settings_draft: None,
+            onboarding: None,
+            persist_cookies: true,
+            bookmarks: bookmarks::BookmarkStore::default(),
+            show_bookmarks: false,
+            show_history_panel: false,
+            history_query: String::new(),
+            bookmark_edit: None,
+            url_suggest_open: false,
+            search_suggestions: suggest::SearchSuggestions::default(),
+            url_suggest_selected: None,
+            paste_choice: None,
+            url_queue: std::collections::VecDeque::new(),
+            forms: crate::ui::forms::FormState::default(),
+            keyboard: keyboard::KeyboardNav::default(),
+            reader: None,
+            reader_font_size: reader::DEFAULT_FONT_SIZE,
+            reader_line_width: reader::DEFAULT_LINE_WIDTH,
+            #[cfg(feature = "voice-web")]
+            read_aloud: read_aloud::ReadAloudState::default(),
+            wrap_text: true,
             history: Vec::new(),
             history_idx: 0,
+            back_forward: alice_browser::engine::back_forward::BackForwardCache::default(),
+            page_entry: None,
+            load_entry: None,
+            restore_scroll: None,
+            pending_anchor: None,
+            media: media::PageMedia::default(),
+            hover_intent: alice_browser::net::speculate::HoverIntent::default(),
+            link_cards: link_preview::LinkCards::default(),
+            visits: history::HistoryStore::default(),
             image_loader: alice_browser::net::image::ImageLoader::new(),
             image_textures: std::collections::HashMap::new(),
+            clipboard: None,
+            pending_image_copy: None,
+            pending_image_save: None,
+            export_notice: None,
+            thumbnails: alice_browser::render::thumbnail::ThumbnailCache::new(64),
+            thumb_textures: std::collections::HashMap::new(),
+            thumb_missing: std::collections::HashSet::new(),
+            thumb_capture_pending: None,
+            thumb_screenshot_requested: false,
+            content_rect: None,
             #[cfg(feature = "smart-cache")]
             page_cache: std::sync::Arc::new(alice_browser::net::cache::CachedFetcher::new(256)),
             #[cfg(feature = "search")]
             search_query: String::new(),
             #[cfg(feature = "search")]
             search_index: None,
+            #[cfg(feature = "search")]
+            find: find::FindState::default(),
             #[cfg(feature = "telemetry")]
             metrics: alice_browser::telemetry::BrowserMetrics::new(),
             #[cfg(feature = "telemetry")]
@@ -135,6 +513,10 @@ impl Default for BrowserApp {
             #[cfg(feature = "sdf-render")]
             sdf_texture: None,
             #[cfg(feature = "sdf-render")]
+            sdf_fade: None,
+            #[cfg(feature = "sdf-render")]
+            refiner: alice_browser::render::sdf_renderer::ProgressiveRenderer::default(),
+            #[cfg(feature = "sdf-render")]
             sdf_mode_rendered: None,
             #[cfg(feature = "sdf-render")]
             cam_params: alice_browser::render::sdf_renderer::CameraParams::default(),
@@ -145,10 +527,40 @@ impl Default for BrowserApp {
             #[cfg(feature = "sdf-render")]
             spatial_scene: None,
             #[cfg(feature = "sdf-render")]
-            gpu_renderer: alice_browser::render::gpu_renderer::GpuRenderer::new(),
+            spatial_pick: picking::SpatialPick::default(),
+            #[cfg(feature = "sdf-render")]
+            focus_3d: None,
+            #[cfg(feature = "sdf-render")]
+            cull_stats: None,
+            #[cfg(feature = "sdf-render")]
+            scene_theme: alice_browser::render::palette::SceneTheme::Day,
+            #[cfg(feature = "sdf-render")]
+            gpu_renderer: None,
+            #[cfg(feature = "sdf-render")]
+            egui_wgpu: None,
+            #[cfg(feature = "sdf-render")]
+            gpu_texture: None,
+            #[cfg(feature = "sdf-render")]
+            oz_glyphs: None,
+            #[cfg(feature = "sdf-render")]
+            gallery: gallery::Gallery::default(),
+            #[cfg(feature = "sdf-render")]
+            show_gallery: false,
             #[cfg(feature = "sdf-render")]
             stream_state: None,
             #[cfg(feature = "sdf-render")]
+            oz_doc_map: None,
+            #[cfg(feature = "sdf-render")]
+            oz_focus_section: None,
+            #[cfg(all(feature = "sdf-render", feature = "search"))]
+            oz_topics: None,
+            #[cfg(feature = "sdf-render")]
+            oz_galaxies: alice_browser::render::galaxy::Galaxies::default(),
+            #[cfg(feature = "sdf-render")]
+            oz_galaxy_time: 0.0,
+            #[cfg(feature = "sdf-render")]
+            oz_search_query: String::new(),
+            #[cfg(feature = "sdf-render")]
             oz_pending_url: None,
             #[cfg(feature = "sdf-render")]
             oz_preview: None,
@@ -163,16 +575,71 @@ impl Default for BrowserApp {
             #[cfg(feature = "sdf-render")]
             oz_hologram_start: None,
             #[cfg(feature = "sdf-render")]
+            oz_flow_speed: 1.0,
+            #[cfg(feature = "sdf-render")]
             oz_prefetch_rx: None,
             #[cfg(feature = "sdf-render")]
             oz_prefetch_started: false,
             #[cfg(feature = "sdf-render")]
             oz_prefetch_buffer: Vec::new(),
+            #[cfg(feature = "sdf-render")]
+            prefetch_policy: std::sync::Arc::default(),
             _app_start: std::time::Instant::now(),
             #[cfg(feature = "sdf-render")]
             last_frame_time: std::time::Instant::now(),
-            _adblock: Arc::new(AdBlockEngine::new()),
+            adblock: Arc::new(AdBlockEngine::new()),
             block_stats: BlockStats::new(),
+            adblock_allowlist: Allowlist::new(),
+            show_filter_diagnostics: false,
+            filter_bench: None,
+            custom_rules_input: String::new(),
+            classifier_model: None,
+            model_path_input: String::new(),
+            model_error: None,
+            site_settings: site_settings::SiteSettings::default(),
+            site: site_settings::ActiveSite::default(),
+            show_site_settings: false,
+            autofill: autofill::AutofillProfiles::default(),
+            show_autofill: false,
+            wayback: None,
+            page_zoom: 1.0,
+            show_inspector: false,
+            inspector_query: String::new(),
+            show_removed: false,
+            inspector_status: None,
+            show_cost_overlay: false,
+            show_layout_boxes: false,
+            show_tasks: false,
+            show_dom_tree: false,
+            dom_inspector: dom_inspector::DomInspector::default(),
+            show_network: false,
+            network_panel: network::NetworkPanel::default(),
+            session: session::SessionWindow::default(),
+            show_session: false,
+            site_map: sitemap::SiteMapWindow::default(),
+            show_site_map: false,
+            tls_interstitial: None,
+            show_certificate: false,
+            show_resources: false,
+            save_page: save_page::SavePageWindow::default(),
+            show_save_page: false,
+            print: print::PrintWindow::default(),
+            show_print: false,
+            restore: restore::SessionRestore::default(),
+            frame_ms: 0.0,
+            frame_profiler: alice_browser::telemetry::frame_profiler::FrameProfiler::default(),
+            show_frame_profiler: false,
+            memory: memory::MemoryWatch::default(),
+            discarded: None,
+            #[cfg(feature = "spatial-audio")]
+            audio: alice_browser::audio::AudioCues::new(),
+            #[cfg(feature = "gamepad")]
+            gamepad: alice_browser::gamepad::Gamepads::new(),
+            #[cfg(feature = "gamepad")]
+            show_gamepad_hints: true,
+            gestures: gestures::GestureState::default(),
+            mobile_ui: alice_browser::mobile::ui::MobileUI::new(0.0, 0.0),
+            force_mobile: false,
         }
     }
 }
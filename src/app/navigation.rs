@@ -1,14 +1,39 @@
 //! Navigation methods for `BrowserApp`.
 //!
-//! Covers history management (`go_back`, `go_forward`, `navigate`) and the
-//! asynchronous page-fetch lifecycle (`navigate_no_history`, `check_fetch`).
+//! Covers history management (`go_back`, `go_forward`, `navigate`), form
+//! submission, soft reloads (`reload`), and the asynchronous page-fetch
+//! lifecycle (`navigate_no_history`, `check_fetch`; `alice://` pages are
+//! handed to `open_internal`, saved `file://` pages are read from disk —
+//! only when the user gave the address, see `navigate_typed`),
+//! plus cookie-jar persistence between sessions. Per-site preferences are
+//! applied as pages load; dead pages fall back to the `wayback` offer.
+//! Pages left for another are kept in the back/forward cache
+//! (`alice_browser::engine::back_forward`), so going back to one shows it
+//! at once, scrolled to where it was read. Links that differ from the page
+//! on screen only by their `#fragment` scroll to the element it names
+//! instead of loading; pages loaded for a fragment open scrolled to it.
+//! Loads, like link prefetches, run as jobs of the shared fetch scheduler
+//! (`alice_browser::net::scheduler`); starting a load cancels whatever was
+//! still being fetched for the page left.
 
 use eframe::egui;
 use std::sync::mpsc;
 
-use alice_browser::engine::pipeline::BrowserEngine;
+use alice_browser::dom::form::{FormMethod, FormSubmission};
+use alice_browser::engine::back_forward::CachedPage;
+use alice_browser::engine::frames::FramePolicy;
+use alice_browser::engine::internal;
+use alice_browser::engine::pipeline::{BrowserEngine, PageError, PageResult};
+use alice_browser::engine::snapshot;
+use alice_browser::engine::workers::CancelToken;
+use alice_browser::net::cookies::{self, CookieJar};
+use alice_browser::net::scheduler::{self, Priority};
+use alice_browser::net::search_engine::is_search_query;
+use alice_browser::net::speculate;
+use alice_browser::net::wayback::LinkRot;
 
 use super::BrowserApp;
+use crate::oz::{resolve_url, split_fragment};
 
 impl BrowserApp {
     /// Navigate one step back in history.
@@ -29,28 +54,314 @@ impl BrowserApp {
         }
     }
 
-    /// Push the current URL to history and start loading.
+    /// Jump directly to a history entry (history popup).
+    pub fn go_to_history(&mut self, idx: usize, ctx: &egui::Context) {
+        if idx < self.history.len() && idx != self.history_idx {
+            self.history_idx = idx;
+            self.url_input = self.history[idx].clone();
+            self.navigate_no_history(ctx);
+        }
+    }
+
+    /// Push the current URL to history and start loading. Text that is not
+    /// an address is searched for with the chosen search engine. A
+    /// `file://` URL only opens from a saved or `alice://` page; use
+    /// [`Self::navigate_typed`] for addresses the user gave.
     pub fn navigate(&mut self, ctx: &egui::Context) {
+        self.navigate_from(ctx, false);
+    }
+
+    /// [`Self::navigate`] to an address the user typed or picked in the
+    /// browser's own UI (URL bar, history, bookmarks, saved pages), which
+    /// may be a local `file://` URL.
+    pub fn navigate_typed(&mut self, ctx: &egui::Context) {
+        self.navigate_from(ctx, true);
+    }
+
+    fn navigate_from(&mut self, ctx: &egui::Context, typed: bool) {
+        if is_search_query(&self.url_input) {
+            self.url_input = self.settings.web_search().url(&self.url_input);
+        }
         let url = self.url_input.clone();
+        // Web pages cannot reach into the disk through links or forms
+        if snapshot::is_file_url(&url) && !typed && !self.page_is_local() {
+            log::warn!("Refused to open {url} from a web page");
+            self.error = Some(format!(
+                "A web page cannot open the local file {url}; type its address to open it"
+            ));
+            self.url_input = self.current_history_url();
+            return;
+        }
+        // Audio and video go to the system's player; the page stays
+        if self.is_media_link(&url) {
+            self.open_externally(&url);
+            self.url_input = self.current_history_url();
+            return;
+        }
         if self.history.is_empty() || self.history[self.history_idx] != url {
             // Truncate forward history before pushing
             self.history.truncate(self.history_idx + 1);
+            self.back_forward.truncate(self.history_idx + 1);
             self.history.push(url);
             self.history_idx = self.history.len() - 1;
         }
         self.navigate_no_history(ctx);
     }
 
-    /// Start an async page fetch without touching history.
+    /// Address of the current history entry; empty before the first.
+    fn current_history_url(&self) -> String {
+        self.history
+            .get(self.history_idx)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Whether the page on screen is a saved page or a browser page, whose
+    /// links may lead to other saved pages.
+    fn page_is_local(&self) -> bool {
+        self.page.as_ref().is_some_and(|page| {
+            snapshot::is_file_url(&page.dom.url) || internal::is_internal(&page.dom.url)
+        })
+    }
+
+    /// Submit a form. GET results join history like any other page; POST
+    /// results are shown under the action URL without a history entry, so
+    /// going back never re-posts.
+    pub fn submit_form(&mut self, submission: FormSubmission, ctx: &egui::Context) {
+        self.url_input = submission.url.clone();
+        if submission.method == FormMethod::Get {
+            self.navigate(ctx);
+            return;
+        }
+        self.start_load(ctx, move |engine| engine.submit_form(&submission));
+    }
+
+    /// Load the current page again. The page stays on screen while the new
+    /// copy loads, and is then patched in place (see
+    /// `alice_browser::dom::diff`), keeping the scroll position, loaded
+    /// images and, when the page's structure is unchanged, form input.
+    pub fn reload(&mut self, ctx: &egui::Context) {
+        if self.loading {
+            return;
+        }
+        let url = match self.page {
+            Some(ref page)
+                if !internal::is_internal(&page.dom.url)
+                    && !snapshot::is_file_url(&page.dom.url) =>
+            {
+                page.dom.url.clone()
+            }
+            _ => {
+                self.navigate_no_history(ctx);
+                return;
+            }
+        };
+        self.reloading = true;
+        self.start_load(ctx, move |engine| engine.reload_page(&url));
+    }
+
+    /// Patch the page on screen from `page`, its reloaded copy.
+    fn apply_soft_reload(&mut self, page: PageResult) {
+        #[cfg(feature = "search")]
+        self.index_page_search(&page);
+
+        let bottom = self.keyboard.view.1;
+        let Some(ref mut current) = self.page else {
+            return;
+        };
+        let (changes, patch) = current.soft_reload(page, bottom);
+        log::info!(
+            "Soft reload: {} DOM changes; layout nodes kept {}, updated {}, replaced {}",
+            changes.changes.len(),
+            patch.kept,
+            patch.updated,
+            patch.replaced
+        );
+        if changes.is_empty() {
+            return;
+        }
+        // Views derived from the layout are rebuilt from the patched tree
+        self.paint_elements = None;
+        if changes.is_structural() {
+            self.forms = crate::ui::forms::FormState::default();
+            self.keyboard = super::keyboard::KeyboardNav::default();
+        }
+        #[cfg(feature = "sdf-render")]
+        {
+            self.spatial_scene = None;
+            self.cam_dirty = true;
+        }
+    }
+
+    /// Show the current history entry: from the back/forward cache if it
+    /// is kept there, otherwise by starting an async page fetch. History
+    /// itself is not touched.
     pub fn navigate_no_history(&mut self, ctx: &egui::Context) {
+        // A load still in flight is overtaken by this one
+        self.stop_loading();
+        let url = self.url_input.clone();
+        self.wayback = None;
+        if internal::is_internal(&url) {
+            self.open_internal(&url, ctx);
+            return;
+        }
+        if self.navigate_in_page(&url) {
+            return;
+        }
+        let entry = self.history_idx;
+        if !self.loading {
+            if let Some(cached) = self.back_forward.take(entry, &url) {
+                self.restore_cached_page(cached, ctx);
+                return;
+            }
+        }
+        if snapshot::is_file_url(&url) {
+            self.start_load_for(ctx, Some(entry), move |engine| engine.load_file(&url));
+            return;
+        }
+        self.start_load_for(ctx, Some(entry), move |engine| engine.load_page(&url));
+    }
+
+    /// Follow `url` within the page on screen if only its fragment differs,
+    /// scrolling to the element the fragment names (the top of the page
+    /// without one) instead of loading. The same address without a fragment
+    /// loads the page again, unless it is a step through history.
+    fn navigate_in_page(&mut self, url: &str) -> bool {
         if self.loading {
+            return false;
+        }
+        let traversal = self.page_entry.is_some_and(|e| e != self.history_idx);
+        let Some(ref mut page) = self.page else {
+            return false;
+        };
+        let (address, fragment) = split_fragment(url);
+        if address != split_fragment(&page.dom.url).0 || (fragment.is_none() && !traversal) {
+            return false;
+        }
+        page.dom.url = url.to_string();
+        self.page_entry = Some(self.history_idx);
+        self.scroll_to_fragment(fragment.unwrap_or_default());
+        true
+    }
+
+    /// Scroll the page on screen to the element `fragment` names; an empty
+    /// fragment, or `top` without such an element, is the top of the page.
+    fn scroll_to_fragment(&mut self, fragment: &str) {
+        let Some(ref mut page) = self.page else {
             return;
+        };
+        match page.find_anchor(fragment).and_then(|n| n.id.clone()) {
+            Some(id) => self.pending_anchor = Some(id),
+            None if fragment.is_empty() || fragment.eq_ignore_ascii_case("top") => {
+                self.restore_scroll = Some(0.0);
+            }
+            None => log::info!("No element named #{fragment} on the page"),
         }
-        self.loading = true;
+    }
+
+    /// Resolve (and, if so set, connect to) the origin of a link hovered
+    /// for [`speculate::HOVER_DELAY`], ahead of the likely click. `hovered` is
+    /// the link the page reported under the pointer this frame.
+    pub fn speculate_hovered_link(&mut self, ctx: &egui::Context, hovered: Option<&str>) {
+        let now = std::time::Instant::now();
+        let fired = self.hover_intent.update(hovered, now);
+        if let Some(wait) = self.hover_intent.remaining(now) {
+            ctx.request_repaint_after(wait);
+        }
+        let (Some(href), Some(page)) = (fired, self.page.as_ref()) else {
+            return;
+        };
+        let url = resolve_url(&page.dom.url, &href);
+        if split_fragment(&url).0 == split_fragment(&page.dom.url).0 {
+            return;
+        }
+        // Hosts the ad blocker refuses are never contacted
+        if !self.adblock_allowlist.allows(&url) && self.adblock.check(&url).is_some() {
+            return;
+        }
+        let options = BrowserEngine::builder()
+            .network(self.settings.network_profile.conditions())
+            .fetch_options();
+        speculate::speculate(&url, self.settings.speculation, &options, &self.page_work);
+    }
+
+    /// Move the page on screen into the back/forward cache under its
+    /// history entry, with the position it is scrolled to.
+    pub(super) fn leave_page(&mut self) {
+        let Some(index) = self.page_entry.take() else {
+            return;
+        };
+        let Some(url) = self.history.get(index).cloned() else {
+            return;
+        };
+        let Some(page) = self.page.take() else {
+            return;
+        };
+        let scroll = self.keyboard.view.0;
+        if !self.back_forward.store(index, &url, page, scroll) {
+            log::info!("{url} is too large for the back/forward cache");
+        }
+    }
+
+    /// Show a page taken from the back/forward cache, scrolled to where it
+    /// was left.
+    fn restore_cached_page(&mut self, cached: CachedPage, ctx: &egui::Context) {
+        self.leave_page();
+        self.reset_page_views();
         self.error = None;
+        self.tls_interstitial = None;
+        self.discarded = None;
         self.image_textures.clear();
         self.block_stats.reset_page();
 
+        #[cfg(feature = "search")]
+        self.index_page_search(&cached.page);
+
+        self.image_loader.set_page_url(&cached.page.dom.url);
+        self.image_loader
+            .set_blocked(cached.page.decisions.blocked.iter().map(|b| b.url.clone()));
+        self.enter_site(&cached.page.dom.url);
+        self.page_entry = Some(cached.index);
+        self.restore_scroll = Some(cached.scroll);
+        self.page = Some(cached.page);
+        self.probe_page_media(ctx);
+    }
+
+    /// Run `load` on a background engine and deliver the result to
+    /// `check_fetch`.
+    pub(super) fn start_load<F>(&mut self, ctx: &egui::Context, load: F)
+    where
+        F: FnOnce(&BrowserEngine) -> Result<PageResult, PageError> + Send + 'static,
+    {
+        self.start_load_for(ctx, None, load);
+    }
+
+    /// [`start_load`](Self::start_load) for history entry `entry`; the page
+    /// is kept in the back/forward cache under it when left.
+    pub(super) fn start_load_for<F>(&mut self, ctx: &egui::Context, entry: Option<usize>, load: F)
+    where
+        F: FnOnce(&BrowserEngine) -> Result<PageResult, PageError> + Send + 'static,
+    {
+        if self.loading {
+            return;
+        }
+        self.loading = true;
+        self.load_entry = entry;
+        self.error = None;
+        self.wayback = None;
+        self.tls_interstitial = None;
+        self.discarded = None;
+        if !self.reloading {
+            self.image_textures.clear();
+            self.image_loader.cancel_all();
+        }
+        self.block_stats.reset_page();
+
+        // Whatever was still being fetched for the page left is abandoned
+        self.page_work.cancel();
+        self.page_work = CancelToken::new();
+        self.load_work = CancelToken::new();
+
         #[cfg(feature = "telemetry")]
         {
             self.navigate_start = Some(std::time::Instant::now());
@@ -59,31 +370,82 @@ impl BrowserApp {
         let (tx, rx) = mpsc::channel();
         self.fetch_rx = Some(rx);
 
-        let url = self.url_input.clone();
+        // Images start downloading while the engine lays the page out
+        let (image_tx, image_rx) = mpsc::channel();
+        self.image_prefetch_rx = Some(image_rx);
+        self.image_loader.set_page_url(&self.url_input);
+        let repaint = ctx.clone();
+
         let ctx = ctx.clone();
 
+        // Lay out for the current content area; resizes reflow later
+        let (width, height) = self
+            .content_rect
+            .map_or((800.0, 600.0), |r| (r.width(), r.height()));
+        let prefs = self.site_settings.for_url(&self.url_input);
+        let builder = BrowserEngine::builder()
+            .viewport(width, height)
+            .adblock(Some(std::sync::Arc::clone(&self.adblock)))
+            .allowlist(self.adblock_allowlist.clone())
+            .model(self.classifier_model.clone())
+            .blocked_origins(prefs.blocked_origins)
+            .feedback(prefs.feedback)
+            .show_removed(self.show_removed)
+            .network(self.settings.network_profile.conditions())
+            .frames(FramePolicy::new(
+                self.settings.embed_frames,
+                &self.settings.frame_hosts,
+            ))
+            .cancel_token(self.load_work.clone())
+            .image_prefetch(move |src| {
+                if image_tx.send(src.to_string()).is_ok() {
+                    repaint.request_repaint();
+                }
+            });
         #[cfg(feature = "smart-cache")]
-        let cache = std::sync::Arc::clone(&self.page_cache);
-
-        std::thread::spawn(move || {
-            let engine = BrowserEngine::new(800.0);
-
-            #[cfg(feature = "smart-cache")]
-            let result = engine.load_page_cached(&url, &cache);
+        let builder = builder.cache(std::sync::Arc::clone(&self.page_cache));
 
-            #[cfg(not(feature = "smart-cache"))]
-            let result = engine.load_page(&url);
-
-            let _ = tx.send(result);
+        let url = self.url_input.clone();
+        scheduler::shared().submit(&url, Priority::Page, &self.load_work, move |_| {
+            let engine = builder.build();
+            let _ = tx.send(load(&engine));
             ctx.request_repaint();
         });
     }
 
+    /// Abandon the page load in flight, keeping whatever is on screen. A
+    /// load still waiting for a slot never starts; one under way runs to
+    /// its end unheard.
+    pub fn stop_loading(&mut self) {
+        if !self.loading {
+            return;
+        }
+        self.load_work.cancel();
+        self.loading = false;
+        self.reloading = false;
+        self.load_entry = None;
+        self.fetch_rx = None;
+        self.image_prefetch_rx = None;
+    }
+
     /// Poll the async fetch channel and update app state when a result arrives.
-    pub fn check_fetch(&mut self) {
+    pub fn check_fetch(&mut self, ctx: &egui::Context) {
+        if let Some(ref rx) = self.image_prefetch_rx {
+            for src in rx.try_iter() {
+                self.image_loader.request(&src);
+            }
+        }
         if let Some(rx) = &self.fetch_rx {
-            if let Ok(result) = rx.try_recv() {
+            let received = rx.try_recv();
+            // The load's job was dropped unrun, or panicked
+            if matches!(received, Err(mpsc::TryRecvError::Disconnected)) {
+                self.stop_loading();
+            }
+            if let Ok(result) = received {
+                let reloaded = self.reloading
+                    && matches!((&result, &self.page), (Ok(new), Some(old)) if new.dom.url == old.dom.url);
                 match result {
+                    Ok(page) if reloaded => self.apply_soft_reload(page),
                     Ok(page) => {
                         // Record telemetry
                         #[cfg(feature = "telemetry")]
@@ -97,75 +459,210 @@ impl BrowserApp {
                                 page.filter_stats.total_nodes,
                                 page.filter_stats.removed_nodes,
                             );
+                            self.metrics.record_stage_timings(&page.timings);
                             self.navigate_start = None;
                         }
 
                         // Build search index from page text
                         #[cfg(feature = "search")]
-                        {
-                            let full_text = page.dom.root.collect_text();
-                            self.search_index =
-                                Some(alice_browser::search::PageSearch::build(&full_text));
-                            self.search_query.clear();
-                        }
+                        self.index_page_search(&page);
 
-                        // Invalidate paint elements and SDF texture
-                        self.paint_elements = None;
-                        #[cfg(feature = "sdf-render")]
-                        {
-                            self.sdf_texture = None;
-                            self.sdf_mode_rendered = None;
-                            self.spatial_scene = None;
-                            self.cam_dirty = true;
-                        }
+                        self.leave_page();
+                        self.reset_page_views();
 
-                        // Start background link prefetch immediately on page load
+                        // Start background link prefetch immediately on page
+                        // load; the shared policy paces and filters the requests
                         #[cfg(feature = "sdf-render")]
                         {
-                            use crate::oz::{collect_hrefs_from_dom, extract_prefetch_texts};
+                            use crate::oz::collect_hrefs_from_dom;
+                            use alice_browser::simd::adblock::BatchMatcher;
 
                             self.oz_prefetch_started = true;
                             self.oz_prefetch_buffer.clear();
                             let base_url = self.url_input.clone();
-                            let hrefs = collect_hrefs_from_dom(&page.dom.root, &base_url, 10);
+                            let hrefs = if self.settings.prefetch_links {
+                                let limit = self.settings.prefetch_max_links;
+                                let hrefs =
+                                    collect_hrefs_from_dom(&page.dom.root, &base_url, limit);
+                                // Links the ad blocker would refuse to open
+                                // are not fetched ahead either
+                                let blocked = BatchMatcher::new(&self.adblock).classify(&hrefs);
+                                hrefs
+                                    .into_iter()
+                                    .enumerate()
+                                    .filter(|(i, href)| {
+                                        !blocked.is_blocked(*i)
+                                            || self.adblock_allowlist.allows(href)
+                                    })
+                                    .map(|(_, href)| href)
+                                    .collect()
+                            } else {
+                                Vec::new()
+                            };
                             if !hrefs.is_empty() {
                                 let (tx, rx) = mpsc::channel();
                                 self.oz_prefetch_rx = Some(rx);
-                                std::thread::spawn(move || {
-                                    use alice_browser::dom::parser::parse_html;
-                                    use alice_browser::net::fetch::fetch_url;
-                                    use alice_browser::render::stream::TextMeta;
-
-                                    for href in hrefs {
-                                        let mut batch: Vec<TextMeta> = Vec::new();
-                                        if let Ok(result) = fetch_url(&href) {
-                                            let dom = parse_html(&result.html, &result.url);
-                                            extract_prefetch_texts(&dom.root, &mut batch, 0);
-                                        }
-                                        if !batch.is_empty() && tx.send(batch).is_err() {
-                                            break;
-                                        }
-                                    }
-                                });
+                                let queue = std::sync::Arc::new(std::sync::Mutex::new(
+                                    hrefs.into_iter().collect::<std::collections::VecDeque<_>>(),
+                                ));
+                                // As many chains of jobs as the policy lets
+                                // fetch at once, so none sits in a scheduler
+                                // slot waiting on the policy
+                                let chains = self.prefetch_policy.config().max_concurrent.max(1);
+                                for _ in 0..chains {
+                                    prefetch_next(PrefetchChain {
+                                        queue: std::sync::Arc::clone(&queue),
+                                        policy: std::sync::Arc::clone(&self.prefetch_policy),
+                                        tx: tx.clone(),
+                                        token: self.page_work.clone(),
+                                    });
+                                }
                             }
                         }
 
+                        let history_url = self.url_input.clone();
+                        self.schedule_thumbnail(&history_url);
+                        self.offer_wayback(LinkRot::from_status(page.fetch_status), ctx);
+                        self.record_visit(&page.dom.title);
+                        self.image_loader.set_page_url(&page.dom.url);
+                        self.image_loader
+                            .set_blocked(page.decisions.blocked.iter().map(|b| b.url.clone()));
+                        self.save_cookies();
+                        self.enter_site(&page.dom.url);
+
                         self.page = Some(page);
+                        self.page_entry = self.load_entry.take();
                         self.error = None;
+                        self.probe_page_media(ctx);
+
+                        // Pages of a restored session open where they were left
+                        let restored = self.page_entry.and_then(|i| self.take_restored_scroll(i));
+                        if restored.is_some() {
+                            self.restore_scroll = restored;
+                        }
+
+                        // Pages loaded for a `#fragment` open scrolled to it
+                        let fragment = self
+                            .page_entry
+                            .and_then(|i| self.history.get(i))
+                            .and_then(|url| split_fragment(url).1)
+                            .map(str::to_string);
+                        if let Some(fragment) = fragment {
+                            self.scroll_to_fragment(&fragment);
+                        }
+                    }
+                    Err(e) if e.phase == "tls" => {
+                        let url = self.url_input.clone();
+                        self.hold_invalid_certificate(&url, e.message);
+                        self.leave_page();
+                        self.page = None;
                     }
                     Err(e) => {
+                        if e.phase == "fetch" {
+                            self.offer_wayback(LinkRot::from_error(&e.message), ctx);
+                        }
                         self.error = Some(e.to_string());
+                        self.leave_page();
                         self.page = None;
 
                         #[cfg(feature = "search")]
                         {
                             self.search_index = None;
+                            self.find = super::find::FindState::default();
                         }
                     }
                 }
                 self.loading = false;
+                self.reloading = false;
                 self.fetch_rx = None;
             }
         }
     }
+
+    /// Drop everything derived from the previous page: paint elements, the
+    /// SDF texture and scene, form input, the reader view and any text
+    /// selection.
+    pub fn reset_page_views(&mut self) {
+        self.paint_elements = None;
+        #[cfg(feature = "sdf-render")]
+        {
+            self.sdf_texture = None;
+            self.sdf_fade = None;
+            self.sdf_mode_rendered = None;
+            self.spatial_scene = None;
+            self.focus_3d = None;
+            self.cull_stats = None;
+            self.site_map.targets.clear();
+            self.refiner.cancel();
+            self.cam_dirty = true;
+        }
+        self.forms = crate::ui::forms::FormState::default();
+        self.keyboard = super::keyboard::KeyboardNav::default();
+        self.reader = None;
+        self.pending_anchor = None;
+        self.sdf_paint_state.selection.clear();
+    }
+
+    // ── Cookie persistence ──
+
+    /// Restore the shared cookie jar from the profile directory.
+    pub fn load_cookies(&self) {
+        if !self.persist_cookies {
+            return;
+        }
+        let loaded = alice_browser::profile::file_path(cookies::COOKIE_FILE)
+            .and_then(|path| CookieJar::load(&path));
+        match loaded {
+            Ok(jar) => cookies::shared_jar().replace_with(jar),
+            Err(e) => log::warn!("Could not load cookies: {e}"),
+        }
+    }
+
+    /// Write persistent cookies to the profile directory (unless opted out).
+    pub fn save_cookies(&self) {
+        if !self.persist_cookies {
+            return;
+        }
+        let saved = alice_browser::profile::file_path(cookies::COOKIE_FILE)
+            .and_then(|path| cookies::shared_jar().save(&path));
+        if let Err(e) = saved {
+            log::warn!("Could not save cookies: {e}");
+        }
+    }
+}
+
+/// Link prefetch of one loaded page, fetched one link per scheduler job.
+#[cfg(feature = "sdf-render")]
+struct PrefetchChain {
+    queue: std::sync::Arc<std::sync::Mutex<std::collections::VecDeque<String>>>,
+    policy: std::sync::Arc<alice_browser::net::prefetch_policy::PrefetchPolicy>,
+    tx: mpsc::Sender<Vec<alice_browser::render::stream::TextMeta>>,
+    token: CancelToken,
+}
+
+/// Submit a prefetch of the next queued link, which submits the one after
+/// it when done; stops when the queue runs dry, the OZ view stops listening
+/// or the page is left.
+#[cfg(feature = "sdf-render")]
+fn prefetch_next(chain: PrefetchChain) {
+    use alice_browser::dom::parser::parse_html;
+    use alice_browser::net::fetch::fetch_url;
+    use alice_browser::net::prefetch_policy::Outcome;
+
+    let Some(href) = chain.queue.lock().ok().and_then(|mut q| q.pop_front()) else {
+        return;
+    };
+    let token = chain.token.clone();
+    let host_key = href.clone();
+    scheduler::shared().submit(&host_key, Priority::Prefetch, &token, move |token| {
+        let mut batch = Vec::new();
+        if let Outcome::Fetched(result) = chain.policy.fetch(&href, fetch_url) {
+            let dom = parse_html(&result.html, &result.url);
+            crate::oz::extract_prefetch_texts(&dom.root, &mut batch, 0);
+        }
+        if token.is_cancelled() || (!batch.is_empty() && chain.tx.send(batch).is_err()) {
+            return;
+        }
+        prefetch_next(chain);
+    });
 }
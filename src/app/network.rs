@@ -0,0 +1,165 @@
+//! Network panel for `BrowserApp`.
+//!
+//! Lists the requests of the current page from its network log (see
+//! [`alice_browser::net::netlog`]): the document, embedded frames, images
+//! as they finish, and the subresources the ad blocker refused. Rows can
+//! be filtered by text and by kind; the whole log is copied or saved as a
+//! HAR file to the profile's `exports` directory.
+
+use eframe::egui;
+
+use alice_browser::engine::memory::format_bytes;
+use alice_browser::net::netlog::{RequestKind, RequestRecord};
+
+use super::history::now_secs;
+use super::table_export::{file_name, EXPORTS_DIR};
+use super::BrowserApp;
+
+/// Rows shown before truncating.
+const MAX_ROWS: usize = 500;
+
+/// Filters and the result of the last export.
+#[derive(Default)]
+pub struct NetworkPanel {
+    pub query: String,
+    /// Only requests of this kind; all when `None`
+    pub kind: Option<RequestKind>,
+    pub notice: Option<String>,
+}
+
+/// Note column: the blocking rule, the error, or a page-cache answer.
+fn note(request: &RequestRecord) -> String {
+    if let Some(ref rule) = request.blocked {
+        format!("blocked ({rule})")
+    } else if let Some(ref error) = request.error {
+        error.clone()
+    } else if request.from_cache {
+        "page cache".into()
+    } else {
+        String::new()
+    }
+}
+
+impl BrowserApp {
+    /// Write the current page's log as HAR to the exports directory.
+    fn save_har(&self) -> std::io::Result<std::path::PathBuf> {
+        let Some(ref page) = self.page else {
+            return Err(std::io::Error::other("No page loaded"));
+        };
+        let dir = alice_browser::profile::file_path(EXPORTS_DIR)?;
+        std::fs::create_dir_all(&dir)?;
+        let name = url::Url::parse(&page.dom.url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| page.dom.title.clone());
+        let path = dir.join(file_name(&name, now_secs(), "har"));
+        std::fs::write(&path, page.network.to_har(&page.dom.title))?;
+        Ok(path)
+    }
+
+    /// Draw the network panel.
+    pub fn draw_network_panel(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_network;
+        let mut save = false;
+        egui::Window::new("Network")
+            .open(&mut open)
+            .default_width(720.0)
+            .show(ctx, |ui| {
+                let Some(ref page) = self.page else {
+                    ui.label("No page loaded");
+                    return;
+                };
+                let log = &page.network;
+                let panel = &mut self.network_panel;
+                let blocked = log.entries.iter().filter(|e| e.blocked.is_some()).count();
+                let cached = log.entries.iter().filter(|e| e.from_cache).count();
+                ui.label(format!(
+                    "{} requests · {} transferred · {blocked} blocked · {cached} from cache",
+                    log.entries.len(),
+                    format_bytes(log.transferred_bytes()),
+                ));
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut panel.query)
+                            .hint_text("Filter by address, type or status")
+                            .desired_width(220.0),
+                    );
+                    ui.selectable_value(&mut panel.kind, None, "All");
+                    for kind in RequestKind::ALL {
+                        ui.selectable_value(&mut panel.kind, Some(kind), kind.label());
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Copy HAR").clicked() {
+                        ctx.copy_text(log.to_har(&page.dom.title));
+                    }
+                    save = ui.button("Save HAR").clicked();
+                    if let Some(ref notice) = panel.notice {
+                        ui.label(notice);
+                    }
+                });
+                ui.separator();
+
+                let query = panel.query.trim().to_lowercase();
+                let rows: Vec<&RequestRecord> = log
+                    .entries
+                    .iter()
+                    .filter(|e| panel.kind.is_none_or(|k| e.kind == k) && e.matches(&query))
+                    .collect();
+                if rows.is_empty() {
+                    ui.weak("No requests match");
+                    return;
+                }
+                let start = log.started_ms();
+                egui::ScrollArea::vertical()
+                    .max_height(420.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("network_requests")
+                            .num_columns(7)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for title in ["Status", "Method", "Type", "Address", "Size"] {
+                                    ui.strong(title);
+                                }
+                                ui.strong("Start / time");
+                                ui.strong("Note");
+                                ui.end_row();
+                                for request in rows.iter().take(MAX_ROWS) {
+                                    let status = request.status_label();
+                                    if request.status.is_some_and(|s| s < 400) {
+                                        ui.monospace(status);
+                                    } else {
+                                        ui.colored_label(
+                                            egui::Color32::from_rgb(200, 60, 60),
+                                            status,
+                                        );
+                                    }
+                                    ui.monospace(&request.method);
+                                    ui.label(request.kind.label());
+                                    ui.monospace(crate::ui::truncate_str(&request.url, 56))
+                                        .on_hover_text(&request.url);
+                                    ui.label(format_bytes(request.bytes));
+                                    ui.weak(format!(
+                                        "+{:.0} / {:.0} ms",
+                                        request.started_ms - start,
+                                        request.duration_ms
+                                    ));
+                                    ui.weak(note(request));
+                                    ui.end_row();
+                                }
+                            });
+                        if rows.len() > MAX_ROWS {
+                            ui.weak(format!("{} more not shown", rows.len() - MAX_ROWS));
+                        }
+                    });
+            });
+        self.show_network = open;
+
+        if save {
+            self.network_panel.notice = Some(match self.save_har() {
+                Ok(path) => format!("Saved to {}", path.display()),
+                Err(e) => format!("Save failed: {e}"),
+            });
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! First-run onboarding for `BrowserApp`.
+//!
+//! A small tour window walks through the render modes on a demo page that
+//! ships inside the binary (`alice://welcome`, so it works offline), marks the OZ and 3-D
+//! interactions with pulsing hints over the content area, and ends by
+//! writing the chosen defaults to [`Settings`](super::settings::Settings).
+
+use eframe::egui;
+
+use alice_browser::engine::internal::InternalPage;
+use alice_browser::render::RenderMode;
+
+use super::BrowserApp;
+
+/// The demo page, served as `alice://welcome`.
+pub const DEMO_HTML: &str = include_str!("welcome.html");
+
+/// One page of the tour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourStep {
+    Welcome,
+    Flat,
+    Sdf2D,
+    Spatial3D,
+    Oz,
+    Reader,
+    Finish,
+}
+
+impl TourStep {
+    const ALL: [Self; 7] = [
+        Self::Welcome,
+        Self::Flat,
+        Self::Sdf2D,
+        Self::Spatial3D,
+        Self::Oz,
+        Self::Reader,
+        Self::Finish,
+    ];
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|&s| s == self).unwrap_or(0)
+    }
+
+    fn next(self) -> Option<Self> {
+        Self::ALL.get(self.index() + 1).copied()
+    }
+
+    fn prev(self) -> Option<Self> {
+        self.index().checked_sub(1).map(|i| Self::ALL[i])
+    }
+
+    /// Render mode demonstrated by this step.
+    const fn mode(self) -> Option<RenderMode> {
+        match self {
+            Self::Flat => Some(RenderMode::Flat),
+            Self::Sdf2D => Some(RenderMode::Sdf2D),
+            Self::Spatial3D => Some(RenderMode::Spatial3D),
+            Self::Oz => Some(RenderMode::OzMode),
+            Self::Reader => Some(RenderMode::Reader),
+            Self::Welcome | Self::Finish => None,
+        }
+    }
+
+    const fn title(self) -> &'static str {
+        match self {
+            Self::Welcome => "Welcome to ALICE Browser",
+            Self::Flat => "Flat",
+            Self::Sdf2D => "SDF 2-D",
+            Self::Spatial3D => "Spatial 3-D",
+            Self::Oz => "OZ",
+            Self::Reader => "Reader",
+            Self::Finish => "Make it yours",
+        }
+    }
+
+    const fn body(self) -> &'static str {
+        match self {
+            Self::Welcome => {
+                "Every page is parsed, cleaned of ads and trackers, and rebuilt. \
+                 This short tour shows the ways it can be displayed, using a demo \
+                 page built into the browser."
+            }
+            Self::Flat => {
+                "The classic view: the filtered page laid out for the window width. \
+                 Links and forms work as usual."
+            }
+            Self::Sdf2D => {
+                "The same layout drawn with signed distance fields: crisp at any \
+                 zoom, and every element can be animated."
+            }
+            Self::Spatial3D => {
+                "The page as a raymarched room. Drag to orbit the camera and scroll \
+                 to move closer."
+            }
+            Self::Oz => {
+                "An orbital information space. Grab a particle to preview its link, \
+                 double-click to open it, or click a heading ring to pull its \
+                 section forward."
+            }
+            Self::Reader => {
+                "Just the main article, typeset for reading. Use A− / A+ and the \
+                 width slider to adjust it."
+            }
+            Self::Finish => {
+                "Choose how the browser starts. Everything here can be changed \
+                 later from the toolbar."
+            }
+        }
+    }
+
+    /// Interaction hints, as fractions of the content area.
+    const fn hints(self) -> &'static [(f32, f32, &'static str)] {
+        match self {
+            Self::Spatial3D => &[(0.5, 0.5, "Drag to orbit"), (0.8, 0.3, "Scroll to zoom")],
+            Self::Oz => &[
+                (0.5, 0.5, "Drag to orbit"),
+                (0.3, 0.35, "Click a particle to grab it"),
+                (0.7, 0.6, "Double-click to open its link"),
+                (0.5, 0.82, "Click a ring to focus its section"),
+            ],
+            _ => &[],
+        }
+    }
+}
+
+/// Progress through the tour.
+pub struct Onboarding {
+    pub step: TourStep,
+    /// Mode picked on the final step
+    pub default_mode: RenderMode,
+    started: std::time::Instant,
+}
+
+impl Onboarding {
+    #[must_use]
+    pub fn new(default_mode: RenderMode) -> Self {
+        Self {
+            step: TourStep::Welcome,
+            default_mode,
+            started: std::time::Instant::now(),
+        }
+    }
+}
+
+enum TourAction {
+    Back,
+    Next,
+    Skip,
+    Finish,
+}
+
+impl BrowserApp {
+    /// Open the tour on the built-in demo page.
+    pub fn start_onboarding(&mut self, ctx: &egui::Context) {
+        self.onboarding = Some(Onboarding::new(self.settings.render_mode));
+        self.url_input = InternalPage::Welcome.url();
+        self.navigate(ctx);
+    }
+
+    /// Draw the tour window and the hints for the current step.
+    pub fn draw_onboarding(&mut self, ctx: &egui::Context) {
+        let Some(ref mut tour) = self.onboarding else {
+            return;
+        };
+        let step = tour.step;
+        let mut default_mode = tour.default_mode;
+        let mut reduce_motion = self.reduce_motion;
+        let mut action = None;
+
+        egui::Window::new("Welcome tour")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-16.0, -16.0])
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                ui.weak(format!(
+                    "Step {} of {}",
+                    step.index() + 1,
+                    TourStep::ALL.len()
+                ));
+                ui.heading(step.title());
+                ui.label(step.body());
+                if matches!(
+                    step.mode(),
+                    Some(RenderMode::Spatial3D | RenderMode::OzMode)
+                ) && !cfg!(feature = "sdf-render")
+                {
+                    ui.colored_label(
+                        egui::Color32::GRAY,
+                        "This build lacks the sdf-render feature; the page is shown flat.",
+                    );
+                }
+
+                if step == TourStep::Finish {
+                    ui.add_space(4.0);
+                    egui::ComboBox::from_label("Start in")
+                        .selected_text(default_mode.label())
+                        .show_ui(ui, |ui| {
+                            for mode in RenderMode::ALL {
+                                ui.selectable_value(&mut default_mode, mode, mode.label());
+                            }
+                        });
+                    ui.checkbox(&mut self.dark_mode, "Dark theme");
+                    ui.checkbox(&mut reduce_motion, "Reduce motion");
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if step.prev().is_some() && ui.button("Back").clicked() {
+                        action = Some(TourAction::Back);
+                    }
+                    if step == TourStep::Finish {
+                        if ui.button("Done").clicked() {
+                            action = Some(TourAction::Finish);
+                        }
+                    } else {
+                        if ui.button("Next").clicked() {
+                            action = Some(TourAction::Next);
+                        }
+                        if ui.button("Skip").clicked() {
+                            action = Some(TourAction::Skip);
+                        }
+                    }
+                });
+            });
+
+        tour.default_mode = default_mode;
+        let started = tour.started;
+        if reduce_motion != self.reduce_motion {
+            self.reduce_motion = reduce_motion;
+            self.apply_reduce_motion(ctx);
+        }
+
+        match action {
+            Some(TourAction::Back | TourAction::Next) => {
+                let to = if matches!(action, Some(TourAction::Back)) {
+                    step.prev()
+                } else {
+                    step.next()
+                };
+                if let (Some(to), Some(tour)) = (to, self.onboarding.as_mut()) {
+                    tour.step = to;
+                    if let Some(mode) = to.mode() {
+                        self.set_render_mode(mode);
+                    }
+                }
+            }
+            Some(TourAction::Skip) => {
+                self.settings.onboarding_done = true;
+                self.settings.save();
+                self.set_render_mode(self.settings.render_mode);
+                self.onboarding = None;
+            }
+            Some(TourAction::Finish) => {
+                self.settings.render_mode = default_mode;
+                self.settings.dark_mode = self.dark_mode;
+                self.settings.reduce_motion = self.reduce_motion;
+                self.settings.onboarding_done = true;
+                self.settings.save();
+                self.set_render_mode(default_mode);
+                self.onboarding = None;
+            }
+            None => self.draw_tour_hints(ctx, step, started),
+        }
+    }
+
+    /// Pulsing markers over the content area for the step's interactions.
+    fn draw_tour_hints(&self, ctx: &egui::Context, step: TourStep, started: std::time::Instant) {
+        if !cfg!(feature = "sdf-render") || step.mode() != Some(self.render_mode) {
+            return;
+        }
+        let (Some(rect), hints) = (self.content_rect, step.hints()) else {
+            return;
+        };
+        if hints.is_empty() {
+            return;
+        }
+
+        // One pulse per second; held still with reduced motion
+        let pulse = if self.reduce_motion {
+            0.5
+        } else {
+            ctx.request_repaint();
+            let t = started.elapsed().as_secs_f32();
+            (t * std::f32::consts::TAU).sin().mul_add(0.5, 0.5)
+        };
+
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("tour_hints"),
+        ));
+        let accent = egui::Color32::from_rgb(0, 150, 255);
+        let text = if self.dark_mode {
+            egui::Color32::WHITE
+        } else {
+            egui::Color32::BLACK
+        };
+        for &(fx, fy, label) in hints {
+            let center = rect.lerp_inside(egui::vec2(fx, fy));
+            let alpha = 1.0 - 0.7 * pulse;
+            painter.circle_stroke(
+                center,
+                pulse.mul_add(12.0, 10.0),
+                egui::Stroke::new(2.0, accent.gamma_multiply(alpha)),
+            );
+            painter.circle_filled(center, 4.0, accent);
+            painter.text(
+                center + egui::vec2(0.0, 26.0),
+                egui::Align2::CENTER_TOP,
+                label,
+                egui::FontId::proportional(14.0),
+                text,
+            );
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! GPU particle text for OZ mode (feature `sdf-render`).
+//!
+//! When eframe runs on wgpu, `BrowserApp::attach_gpu` puts an
+//! [`OzGpuRenderer`] into the UI renderer's callback resources. The OZ loop
+//! then collects each visible particle's label ([`OzLabel`]) instead of
+//! painting it, and [`OzGlyphs::paint`] turns them into glyph
+//! instances drawn by one paint callback. The renderer keeps its own copy
+//! of egui's font atlas, re-sent whenever egui adds glyphs.
+
+use std::sync::Arc;
+
+use eframe::egui;
+use eframe::egui_wgpu::{self, wgpu};
+
+use alice_browser::render::oz_gpu::{push_galley, GlyphInstance, OzGpuRenderer, LABEL_FONT_SIZE};
+
+/// GPU text path state, present while the renderer is installed.
+#[derive(Default)]
+pub struct OzGlyphs {
+    /// Atlas size and fill of the copy the renderer holds
+    atlas_key: Option<([usize; 2], f32)>,
+    /// Glyph instances drawn last frame, for the stats panel
+    pub last_instances: usize,
+}
+
+/// One particle's label, laid out at [`LABEL_FONT_SIZE`].
+pub struct OzLabel {
+    pub galley: Arc<egui::Galley>,
+    pub center: egui::Pos2,
+    pub font_size: f32,
+    pub color: egui::Color32,
+}
+
+impl OzLabel {
+    /// Lay out `text` for the GPU path.
+    pub fn new(
+        painter: &egui::Painter,
+        text: &str,
+        center: egui::Pos2,
+        font_size: f32,
+        color: egui::Color32,
+    ) -> Self {
+        let galley = painter.layout_no_wrap(
+            text.to_owned(),
+            egui::FontId::proportional(LABEL_FONT_SIZE),
+            egui::Color32::WHITE,
+        );
+        Self {
+            galley,
+            center,
+            font_size,
+            color,
+        }
+    }
+}
+
+/// Install the renderer on eframe's device.
+pub fn install(render_state: &egui_wgpu::RenderState) {
+    let renderer = OzGpuRenderer::new(&render_state.device, render_state.target_format);
+    render_state
+        .renderer
+        .write()
+        .callback_resources
+        .insert(renderer);
+}
+
+/// One frame's glyphs, and the atlas when it changed.
+struct GlyphCallback {
+    view_size: [f32; 2],
+    instances: Vec<GlyphInstance>,
+    atlas: Option<([u32; 2], Vec<u8>)>,
+}
+
+impl egui_wgpu::CallbackTrait for GlyphCallback {
+    fn prepare(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        _screen_descriptor: &egui_wgpu::ScreenDescriptor,
+        _egui_encoder: &mut wgpu::CommandEncoder,
+        callback_resources: &mut egui_wgpu::CallbackResources,
+    ) -> Vec<wgpu::CommandBuffer> {
+        if let Some(renderer) = callback_resources.get_mut::<OzGpuRenderer>() {
+            if let Some((size, ref coverage)) = self.atlas {
+                renderer.upload_atlas(device, queue, size, coverage);
+            }
+            renderer.prepare(device, queue, self.view_size, &self.instances);
+        }
+        Vec::new()
+    }
+
+    fn paint(
+        &self,
+        _info: egui::PaintCallbackInfo,
+        render_pass: &mut wgpu::RenderPass<'static>,
+        callback_resources: &egui_wgpu::CallbackResources,
+    ) {
+        if let Some(renderer) = callback_resources.get::<OzGpuRenderer>() {
+            renderer.paint(render_pass);
+        }
+    }
+}
+
+impl OzGlyphs {
+    /// Draw `labels` over `rect` in one instanced draw.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn paint(&mut self, painter: &egui::Painter, rect: egui::Rect, labels: &[OzLabel]) {
+        // Read after laying out, which may have grown the atlas
+        let key = painter.fonts(|f| (f.font_image_size(), f.font_atlas_fill_ratio()));
+        let atlas = (self.atlas_key != Some(key)).then(|| {
+            let image = painter.fonts(egui::epaint::text::Fonts::image);
+            let coverage = image.srgba_pixels(None).map(|c| c.a()).collect();
+            ([image.width() as u32, image.height() as u32], coverage)
+        });
+        self.atlas_key = Some(key);
+
+        let mut instances = Vec::new();
+        for label in labels {
+            push_galley(
+                &mut instances,
+                &label.galley,
+                key.0,
+                [label.center.x - rect.left(), label.center.y - rect.top()],
+                label.font_size,
+                label.color.to_array().map(|c| f32::from(c) / 255.0),
+            );
+        }
+        self.last_instances = instances.len();
+        painter.add(egui_wgpu::Callback::new_paint_callback(
+            rect,
+            GlyphCallback {
+                view_size: [rect.width(), rect.height()],
+                instances,
+                atlas,
+            },
+        ));
+    }
+}
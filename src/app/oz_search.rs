@@ -0,0 +1,57 @@
+//! Search across pages in OZ mode for `BrowserApp` (feature `sdf-render`).
+//!
+//! A bar above the Rotunda searches the stream's whole text pool: the
+//! page's own texts and those prefetch has pulled in from its links (see
+//! `StreamState::search`). Particles that don't match fade back, matches
+//! are pulled toward the viewer, and matches not on the wall yet are
+//! brought onto it, so the hall becomes a view of the results. A new
+//! stream starts without a search.
+
+use eframe::egui;
+
+use super::BrowserApp;
+
+impl BrowserApp {
+    /// Search bar above the OZ view, while a stream is shown.
+    pub fn draw_oz_search_bar(&mut self, ui: &mut egui::Ui) {
+        let Some(ref mut stream) = self.stream_state else {
+            return;
+        };
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed = ui
+                .add_sized(
+                    [240.0, 20.0],
+                    egui::TextEdit::singleline(&mut self.oz_search_query)
+                        .hint_text("Search this page and linked pages"),
+                )
+                .changed();
+            if stream.is_searching() {
+                let count = stream.search_hits.len();
+                ui.weak(if count == 1 {
+                    "1 match".to_string()
+                } else {
+                    format!("{count} matches")
+                });
+                if ui
+                    .small_button("\u{2715}")
+                    .on_hover_text("End search")
+                    .clicked()
+                {
+                    self.oz_search_query.clear();
+                    changed = true;
+                }
+            }
+        });
+        ui.separator();
+
+        if changed {
+            stream.search(&self.oz_search_query);
+            // Frozen streams don't respawn: lay the new texts out at once
+            if self.reduce_motion {
+                stream.arrange_static();
+                stream.settle_focus();
+            }
+        }
+    }
+}
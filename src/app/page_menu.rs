@@ -0,0 +1,102 @@
+//! Page context menu entries for `BrowserApp`.
+//!
+//! Carries out what was chosen from a right-click menu in the flat or SDF
+//! view (`ui::page_menu`): links open, queue behind the toolbar's "Next"
+//! button (there are no tabs) or have their address copied; text and images
+//! are copied; images are saved as PNG to the profile's `saved` directory;
+//! and "Block this element" marks the element's selector as an ad in the
+//! site's classifier corrections, so it is removed from this page and every
+//! later one on the site.
+
+use std::io;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use alice_browser::dom::filter::Verdict;
+use alice_browser::render::context_menu::MenuAction;
+use eframe::egui;
+
+use crate::oz::resolve_url;
+use crate::ui::clipboard::{self, CopyRequest};
+use crate::ui::page_menu;
+
+use super::history::now_secs;
+use super::save_page::SAVED_DIR;
+use super::table_export::file_name;
+use super::BrowserApp;
+
+impl BrowserApp {
+    /// Carry out this frame's context menu entry, and save an image whose
+    /// download it was waiting for.
+    pub fn handle_page_menu(&mut self, ctx: &egui::Context) {
+        let base = self
+            .page
+            .as_ref()
+            .map_or_else(|| self.url_input.clone(), |p| p.dom.url.clone());
+        match page_menu::take(ctx) {
+            Some(MenuAction::Open(href)) => {
+                self.url_input = resolve_url(&base, &href);
+                self.navigate(ctx);
+            }
+            Some(MenuAction::OpenInBackground(href)) => {
+                let url = resolve_url(&base, &href);
+                if !self.url_queue.contains(&url) {
+                    self.url_queue.push_back(url);
+                }
+            }
+            Some(MenuAction::CopyLink(href)) => ctx.copy_text(resolve_url(&base, &href)),
+            Some(MenuAction::CopyText(text)) => ctx.copy_text(text),
+            Some(MenuAction::CopyImage(src)) => {
+                clipboard::request(ctx, CopyRequest::Image(resolve_url(&base, &src)));
+            }
+            Some(MenuAction::SaveImage(src)) => {
+                let url = resolve_url(&base, &src);
+                self.image_loader.request(&url);
+                self.pending_image_save = Some(url);
+            }
+            Some(MenuAction::Block(selector)) => {
+                self.update_feedback(ctx, |rules| rules.mark(&selector, Verdict::Ad));
+            }
+            None => {}
+        }
+
+        let Some(url) = self.pending_image_save.take() else {
+            return;
+        };
+        if self.image_loader.is_pending(&url) {
+            self.pending_image_save = Some(url);
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            return;
+        }
+        let notice = match self.save_image(&url) {
+            Ok(path) => format!("Image saved to {}", path.display()),
+            Err(e) => format!("Could not save the image: {e}"),
+        };
+        self.export_notice = Some((notice, Instant::now()));
+    }
+
+    /// Write the decoded image at `url` to the saved directory, named after
+    /// the last segment of its path.
+    fn save_image(&self, url: &str) -> io::Result<PathBuf> {
+        let data = self
+            .image_loader
+            .get(url)
+            .ok_or_else(|| io::Error::other("it failed to load"))?;
+        let dir = alice_browser::profile::file_path(SAVED_DIR)?;
+        std::fs::create_dir_all(&dir)?;
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let stem = path.rsplit('/').next().unwrap_or(path);
+        let stem = stem.split('.').next().unwrap_or(stem);
+        let name = if stem.is_empty() { "image" } else { stem };
+        let path = dir.join(file_name(name, now_secs(), "png"));
+        image::save_buffer(
+            &path,
+            &data.rgba,
+            data.width,
+            data.height,
+            image::ExtendedColorType::Rgba8,
+        )
+        .map_err(io::Error::other)?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,190 @@
+//! URL-bar paste handling for `BrowserApp`.
+//!
+//! Pastes into the URL bar are classified (see
+//! [`alice_browser::net::paste`]). A single address is pasted as usual;
+//! several addresses or a text snippet open a small chooser under the bar
+//! instead — open them all, search the text, or paste it anyway. The
+//! browser shows one page at a time, so "open all" opens the first address
+//! and queues the rest behind a **Next** button in the toolbar; "load as
+//! session" fetches them all in the background instead (`session`).
+
+use std::collections::VecDeque;
+
+use eframe::egui;
+
+use alice_browser::engine::session::SessionEntry;
+use alice_browser::net::paste::{classify_paste, PasteIntent};
+
+use super::BrowserApp;
+
+/// Pasted addresses listed in the chooser before "and N more".
+const MAX_LISTED: usize = 5;
+
+/// What the chooser asked to do.
+enum PasteAction {
+    OpenAll(Vec<String>),
+    /// Load the addresses as a batch session
+    Session(Vec<String>),
+    Search(String),
+    /// Put the text into the bar after all
+    Paste(String),
+    Cancel,
+}
+
+impl BrowserApp {
+    /// Take a paste aimed at the focused URL bar away from the text field
+    /// when it needs a choice, and open the chooser for it.
+    pub fn intercept_url_paste(&mut self, ctx: &egui::Context) {
+        let intent = ctx.input_mut(|i| {
+            let (pos, intent) = i.events.iter().enumerate().find_map(|(pos, e)| match e {
+                egui::Event::Paste(text) => {
+                    Some((pos, classify_paste(text))).filter(|(_, intent)| intent.needs_choice())
+                }
+                _ => None,
+            })?;
+            i.events.remove(pos);
+            Some(intent)
+        });
+        if intent.is_some() {
+            self.paste_choice = intent;
+            self.url_suggest_open = false;
+            self.url_suggest_selected = None;
+        }
+    }
+
+    /// Run the chooser's first action (Enter in the URL bar). Returns
+    /// `false` if no chooser is open.
+    pub fn accept_paste_choice(&mut self, ctx: &egui::Context) -> bool {
+        let Some(intent) = self.paste_choice.take() else {
+            return false;
+        };
+        let action = match intent {
+            PasteIntent::Urls(urls) => PasteAction::OpenAll(urls),
+            PasteIntent::Search(query) => PasteAction::Search(query),
+            PasteIntent::Url(url) => PasteAction::Paste(url),
+        };
+        self.run_paste_action(action, ctx);
+        true
+    }
+
+    fn run_paste_action(&mut self, action: PasteAction, ctx: &egui::Context) {
+        self.paste_choice = None;
+        match action {
+            PasteAction::OpenAll(urls) => {
+                let mut urls = VecDeque::from(urls);
+                if let Some(first) = urls.pop_front() {
+                    self.url_queue = urls;
+                    self.url_input = first;
+                    self.navigate_typed(ctx);
+                }
+            }
+            PasteAction::Session(urls) => {
+                let entries = urls
+                    .into_iter()
+                    .map(|url| SessionEntry { url, group: None })
+                    .collect();
+                self.start_session(entries, ctx);
+            }
+            PasteAction::Search(query) => {
+                self.url_input = self.settings.web_search().url(&query);
+                self.navigate(ctx);
+            }
+            PasteAction::Paste(text) => self.url_input = text,
+            PasteAction::Cancel => {}
+        }
+    }
+
+    /// Open the next queued address.
+    pub fn open_next_queued(&mut self, ctx: &egui::Context) {
+        if let Some(url) = self.url_queue.pop_front() {
+            self.url_input = url;
+            self.navigate_typed(ctx);
+        }
+    }
+
+    /// Chooser under the URL bar for the pending paste.
+    pub fn draw_paste_chooser(&mut self, ctx: &egui::Context, url_rect: egui::Rect) {
+        let Some(ref intent) = self.paste_choice else {
+            return;
+        };
+        let mut action = None;
+        let area = egui::Area::new(egui::Id::new("paste_chooser"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(url_rect.left_bottom())
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(url_rect.width());
+                    match intent {
+                        PasteIntent::Urls(urls) => {
+                            ui.strong(format!("{} addresses pasted", urls.len()));
+                            for url in urls.iter().take(MAX_LISTED) {
+                                ui.monospace(crate::ui::truncate_str(url, 80));
+                            }
+                            if urls.len() > MAX_LISTED {
+                                ui.weak(format!("… and {} more", urls.len() - MAX_LISTED));
+                            }
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .button(format!("Open all ({})", urls.len()))
+                                    .on_hover_text(
+                                        "Open the first now; the rest wait behind Next \u{25B8}",
+                                    )
+                                    .clicked()
+                                {
+                                    action = Some(PasteAction::OpenAll(urls.clone()));
+                                }
+                                if ui
+                                    .button("Load as session")
+                                    .on_hover_text("Fetch them all in the background, side by side")
+                                    .clicked()
+                                {
+                                    action = Some(PasteAction::Session(urls.clone()));
+                                }
+                                if ui.button("Paste first").clicked() {
+                                    action = Some(PasteAction::Paste(urls[0].clone()));
+                                }
+                                if ui.button("Cancel").clicked() {
+                                    action = Some(PasteAction::Cancel);
+                                }
+                            });
+                        }
+                        PasteIntent::Search(query) => {
+                            ui.label(format!(
+                                "Search for \u{201C}{}\u{201D}",
+                                crate::ui::truncate_str(query, 80)
+                            ));
+                            ui.horizontal(|ui| {
+                                if ui.button("Search").clicked() {
+                                    action = Some(PasteAction::Search(query.clone()));
+                                }
+                                if ui.button("Paste as text").clicked() {
+                                    action = Some(PasteAction::Paste(query.clone()));
+                                }
+                                if ui.button("Cancel").clicked() {
+                                    action = Some(PasteAction::Cancel);
+                                }
+                            });
+                        }
+                        PasteIntent::Url(url) => {
+                            action = Some(PasteAction::Paste(url.clone()));
+                        }
+                    }
+                    ui.weak("Enter picks the first action, Esc cancels");
+                });
+            });
+
+        // Esc or a click elsewhere dismisses the chooser
+        let dismissed = ctx.input(|i| {
+            i.key_pressed(egui::Key::Escape)
+                || (i.pointer.any_pressed()
+                    && i.pointer
+                        .interact_pos()
+                        .is_some_and(|p| !area.response.rect.contains(p) && !url_rect.contains(p)))
+        });
+        if let Some(action) = action {
+            self.run_paste_action(action, ctx);
+        } else if dismissed {
+            self.paste_choice = None;
+        }
+    }
+}
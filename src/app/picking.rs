@@ -0,0 +1,220 @@
+//! Primitive picking in Spatial3D (feature `sdf-render`).
+//!
+//! A point of the view casts a ray into the scene through its BVH
+//! ([`ScenePicker`]) to find the primitive it hits, and [`SpatialPick`]
+//! keeps the page element each primitive was built from. Scenes not built
+//! from a page (site maps, saved scenes) have no elements.
+//!
+//! Hovering outlines the primitive under the pointer in the GPU raymarcher,
+//! and a tooltip names the page element behind it: its tag, text, and where
+//! a link or image points; primitives of other scenes show only their own
+//! text.
+//!
+//! Clicking a link portal flies the camera into it ([`portal_flight`]) and
+//! then opens the link; in reduced motion the link opens at once.
+
+use eframe::egui;
+
+use alice_browser::render::sdf_renderer::{portal_flight, CameraParams, ScenePicker};
+use alice_browser::render::sdf_ui::SdfPrimitive;
+use alice_browser::render::spatial::ElementSource;
+
+use crate::oz::resolve_url;
+
+use super::content::SdfFrame;
+use super::BrowserApp;
+
+/// Picking state for the current Spatial3D scene; replaced with the scene.
+#[derive(Default)]
+pub struct SpatialPick {
+    /// Element behind each primitive, when the scene was built from a page
+    sources: Vec<Option<ElementSource>>,
+    /// Built on first pick
+    picker: Option<ScenePicker>,
+    /// Scene index of the primitive under the pointer
+    pub hovered: Option<usize>,
+    /// Fly-through into a clicked portal, under way
+    flight: Option<PortalFlight>,
+}
+
+/// Length of a fly-through into a portal, in seconds.
+const PORTAL_FLIGHT_SECS: f32 = 0.6;
+
+/// A fly-through into a clicked link portal.
+struct PortalFlight {
+    /// Link opened when the camera arrives
+    url: String,
+    /// Camera when the portal was clicked
+    from: CameraParams,
+    /// Centre of the portal's face
+    portal: [f32; 3],
+    start: std::time::Instant,
+}
+
+impl SpatialPick {
+    #[must_use]
+    pub fn with_sources(sources: Vec<Option<ElementSource>>) -> Self {
+        Self {
+            sources,
+            ..Self::default()
+        }
+    }
+
+    /// Element behind the primitive at `index` in the scene.
+    #[must_use]
+    pub fn source(&self, index: usize) -> Option<&ElementSource> {
+        self.sources.get(index)?.as_ref()
+    }
+
+    /// Element behind the hovered primitive.
+    #[must_use]
+    pub fn hovered_source(&self) -> Option<&ElementSource> {
+        self.source(self.hovered?)
+    }
+}
+
+impl BrowserApp {
+    /// Scene index of the primitive seen at `pos` in the Spatial3D view
+    /// drawn in `rect`.
+    fn pick_at(&mut self, pos: egui::Pos2, rect: egui::Rect) -> Option<usize> {
+        let scene = self.spatial_scene.as_ref()?;
+        // CPU frames are rendered at a fixed size and stretched
+        let aspect = match self.sdf_texture {
+            Some(SdfFrame::Image(ref texture)) => texture.aspect_ratio(),
+            _ => rect.width() / rect.height().max(1.0),
+        };
+        let ndc = [
+            (pos.x - rect.left()) / rect.width() * 2.0 - 1.0,
+            1.0 - (pos.y - rect.top()) / rect.height() * 2.0,
+        ];
+        self.spatial_pick
+            .picker
+            .get_or_insert_with(|| ScenePicker::new(scene))
+            .pick(&self.cam_params, aspect, ndc)
+    }
+
+    /// Fly into the link portal under the pointer, if one was clicked; in
+    /// reduced motion the link is opened straight away.
+    pub fn click_spatial_portal(&mut self, ctx: &egui::Context, response: &egui::Response) {
+        if !response.clicked() || self.spatial_pick.flight.is_some() {
+            return;
+        }
+        let Some(index) = response
+            .interact_pointer_pos()
+            .and_then(|pos| self.pick_at(pos, response.rect))
+        else {
+            return;
+        };
+        let Some(href) = self
+            .spatial_pick
+            .source(index)
+            .filter(|source| source.tag == "a")
+            .and_then(|source| source.href.clone())
+        else {
+            return;
+        };
+        let portal = match self
+            .spatial_scene
+            .as_ref()
+            .and_then(|s| s.primitives.get(index))
+        {
+            Some(SdfPrimitive::RoundedBox { center, size, .. }) => {
+                [center[0], center[1], center[2] + size[2] / 2.0]
+            }
+            _ => return,
+        };
+        let url = resolve_url(&self.url_input, &href);
+
+        #[cfg(feature = "spatial-audio")]
+        {
+            use alice_browser::audio::{CueKind, Listener};
+            let cam = &self.cam_params;
+            let listener = Listener::orbit(cam.target, cam.azimuth, cam.elevation, cam.distance);
+            self.audio.play(CueKind::Click, portal, &listener);
+        }
+
+        if self.reduce_motion {
+            self.url_input = url;
+            self.navigate(ctx);
+            return;
+        }
+        self.spatial_pick.flight = Some(PortalFlight {
+            url,
+            from: self.cam_params,
+            portal,
+            start: std::time::Instant::now(),
+        });
+        ctx.request_repaint();
+    }
+
+    /// Move the camera along a portal fly-through under way, and open the
+    /// link once it arrives.
+    pub fn step_portal_flight(&mut self, ctx: &egui::Context) {
+        let Some(ref flight) = self.spatial_pick.flight else {
+            return;
+        };
+        let t = flight.start.elapsed().as_secs_f32() / PORTAL_FLIGHT_SECS;
+        self.cam_params = portal_flight(&flight.from, flight.portal, t);
+        self.cam_dirty = true;
+        if t < 1.0 {
+            ctx.request_repaint();
+            return;
+        }
+        if let Some(flight) = self.spatial_pick.flight.take() {
+            self.url_input = flight.url;
+            self.navigate(ctx);
+        }
+    }
+
+    /// Pick what lies under the pointer in the Spatial3D view, outline it
+    /// and describe it in a tooltip. Nothing is picked while the camera
+    /// moves.
+    pub fn hover_spatial(&mut self, ui: &egui::Ui, response: &egui::Response) {
+        let pointer = response.hover_pos().filter(|_| !self.cam_dragging);
+        let hovered = pointer.and_then(|pos| self.pick_at(pos, response.rect));
+        self.spatial_pick.hovered = hovered;
+        if let Some(ref mut gpu) = self.gpu_renderer {
+            if gpu.set_highlight(hovered) {
+                self.cam_dirty = true;
+            }
+        }
+
+        let Some(index) = hovered else {
+            return;
+        };
+        let source = self.spatial_pick.hovered_source();
+        let own_text =
+            self.spatial_scene
+                .as_ref()
+                .and_then(|scene| match scene.primitives.get(index)? {
+                    SdfPrimitive::TextLabel { text, .. } | SdfPrimitive::Billboard { text, .. } => {
+                        Some(text.as_str())
+                    }
+                    _ => None,
+                });
+        if source.is_none() && own_text.is_none() {
+            return;
+        }
+        egui::show_tooltip_at_pointer(
+            ui.ctx(),
+            ui.layer_id(),
+            egui::Id::new("spatial_pick_tip"),
+            |ui| {
+                ui.set_max_width(320.0);
+                if let Some(source) = source {
+                    if !source.tag.is_empty() {
+                        ui.strong(format!("<{}>", source.tag));
+                    }
+                    if !source.text.is_empty() {
+                        ui.label(&source.text);
+                    }
+                    if let Some(ref href) = source.href {
+                        ui.weak(href);
+                    }
+                } else if let Some(text) = own_text {
+                    ui.label(text);
+                }
+            },
+        );
+    }
+}
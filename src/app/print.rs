@@ -0,0 +1,169 @@
+//! "Print…" window for `BrowserApp` (Cmd+P).
+//!
+//! Exports the page as a PDF on the chosen paper and margins (see
+//! [`alice_browser::engine::print`]) into the profile's `saved` directory,
+//! next to saved pages. The article reader mode shows is printed unless the
+//! whole page is asked for. Images are printed if they have loaded; opening
+//! the window requests those the current view has not.
+
+use std::path::PathBuf;
+
+use eframe::egui;
+
+use alice_browser::engine::print::{self, PaperSize, PrintOptions, PrintSource, MAX_MARGIN_MM};
+use alice_browser::render::layout::LayoutNode;
+
+use super::history::now_secs;
+use super::media::system_open;
+use super::save_page::{slug, SAVED_DIR};
+use super::BrowserApp;
+
+/// Print window state.
+#[derive(Default)]
+pub struct PrintWindow {
+    pub options: PrintOptions,
+    pub status: Option<String>,
+    /// The last PDF written
+    pub saved: Option<PathBuf>,
+}
+
+impl BrowserApp {
+    /// Open the print window and start loading the page's images.
+    pub fn open_print(&mut self) {
+        if let Some(ref page) = self.page {
+            let mut sources = Vec::new();
+            collect_images(&page.layout, &mut sources);
+            for src in sources {
+                self.image_loader.request(&src);
+            }
+        }
+        self.print.status = None;
+        self.print.saved = None;
+        self.show_print = true;
+    }
+
+    /// Cmd+P opens the print window.
+    pub fn handle_print_key(&mut self, ctx: &egui::Context) {
+        if self.page.is_some()
+            && ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::P))
+        {
+            self.open_print();
+        }
+    }
+
+    /// Write the current page as a PDF.
+    fn print_current_page(&mut self) {
+        let Some(ref page) = self.page else {
+            return;
+        };
+        let loader = &self.image_loader;
+        let printed = print::print_pdf(&page.dom, &self.print.options, |src| loader.get(src));
+        let name = slug(&page.dom.title);
+        let result = alice_browser::profile::file_path(SAVED_DIR).and_then(|dir| {
+            std::fs::create_dir_all(&dir)?;
+            let mut path = dir.join(format!("{name}.pdf"));
+            if path.exists() {
+                path = dir.join(format!("{name}-{}.pdf", now_secs()));
+            }
+            std::fs::write(&path, &printed.bytes)?;
+            Ok(path)
+        });
+        match result {
+            Ok(path) => {
+                let what = match (self.print.options.source, printed.source) {
+                    (PrintSource::Reader, PrintSource::Page) => {
+                        "No article found; printed the whole page"
+                    }
+                    _ => "Printed",
+                };
+                let pages = if printed.pages == 1 { "page" } else { "pages" };
+                self.print.status = Some(format!(
+                    "{what}: {} {pages} to {}",
+                    printed.pages,
+                    path.display()
+                ));
+                self.print.saved = Some(path);
+            }
+            Err(e) => {
+                log::warn!("Printing failed: {e}");
+                self.print.status = Some(format!("Could not write the PDF: {e}"));
+            }
+        }
+    }
+
+    /// Draw the print window.
+    pub fn draw_print(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_print;
+        let mut export = false;
+
+        egui::Window::new("Print")
+            .open(&mut open)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                let Some(ref page) = self.page else {
+                    ui.label("No page loaded");
+                    return;
+                };
+                ui.weak(&page.dom.url);
+                let options = &mut self.print.options;
+                egui::Grid::new("print_options").show(ui, |ui| {
+                    ui.label("Paper:");
+                    egui::ComboBox::from_id_salt("print_paper")
+                        .selected_text(options.paper.label())
+                        .show_ui(ui, |ui| {
+                            for paper in PaperSize::ALL {
+                                ui.selectable_value(&mut options.paper, paper, paper.label());
+                            }
+                        });
+                    ui.end_row();
+                    ui.label("Margins:");
+                    ui.add(
+                        egui::DragValue::new(&mut options.margin_mm)
+                            .range(0.0..=MAX_MARGIN_MM)
+                            .suffix(" mm"),
+                    );
+                    ui.end_row();
+                    ui.label("Print:");
+                    ui.vertical(|ui| {
+                        for source in PrintSource::ALL {
+                            ui.radio_value(&mut options.source, source, source.label());
+                        }
+                    });
+                    ui.end_row();
+                });
+                let pending = self.image_loader.pending_count();
+                if pending > 0 {
+                    ui.weak(format!("{pending} images still loading"));
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    export = ui.button("Save as PDF").clicked();
+                    if let Some(ref path) = self.print.saved {
+                        if ui.button("Open PDF").clicked() {
+                            if let Err(e) = system_open(&path.to_string_lossy()) {
+                                log::warn!("Could not open {}: {e}", path.display());
+                            }
+                        }
+                    }
+                });
+                if let Some(ref status) = self.print.status {
+                    ui.label(status);
+                }
+            });
+        self.show_print = open;
+
+        if export {
+            self.print_current_page();
+        }
+    }
+}
+
+/// `src` of every image under `node`, as written in the page.
+fn collect_images(node: &LayoutNode, out: &mut Vec<String>) {
+    if node.tag == "img" {
+        out.extend(node.href.clone());
+    }
+    for child in &node.children {
+        collect_images(child, out);
+    }
+}
@@ -0,0 +1,193 @@
+//! Read aloud for `BrowserApp` (feature `voice-web`).
+//!
+//! In reader mode the toolbar offers **Read aloud**: the article's blocks
+//! go to a [`ReadAloud`] player, the reader view highlights the block being
+//! spoken and scrolls it into view, and the toolbar holds previous,
+//! play/pause, next, stop and the speaking rate. Leaving the article stops
+//! playback.
+
+use std::time::Duration;
+
+use eframe::egui;
+
+use alice_browser::render::layout::LayoutNode;
+use alice_browser::render::RenderMode;
+use alice_browser::speech::{
+    block_text, speakable_blocks, Playback, ReadAloud, Synthesizer, RATES,
+};
+
+use super::BrowserApp;
+use crate::ui::reader::Spoken;
+
+/// How often the player is polled while speaking.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Read-aloud preferences and the article being read.
+pub struct ReadAloudState {
+    /// Found on first use; `Some(None)` when the system has none
+    synth: Option<Option<Synthesizer>>,
+    /// Multiple of the synthesizer's base rate
+    pub rate: f32,
+    session: Option<Session>,
+}
+
+impl Default for ReadAloudState {
+    fn default() -> Self {
+        Self {
+            synth: None,
+            rate: 1.0,
+            session: None,
+        }
+    }
+}
+
+impl ReadAloudState {
+    /// The block of `layout` being read, for the reader view to highlight.
+    pub fn spoken_block<'a>(&mut self, layout: &'a LayoutNode) -> Option<Spoken<'a>> {
+        let session = self.session.as_mut()?;
+        let current = session.player.current()?;
+        let block = *speakable_blocks(layout).get(current)?;
+        let scroll = session.scrolled != Some(current);
+        session.scrolled = Some(current);
+        Some(Spoken { block, scroll })
+    }
+}
+
+struct Session {
+    player: ReadAloud,
+    /// Page the article came from
+    url: String,
+    /// Block last scrolled into view
+    scrolled: Option<usize>,
+}
+
+impl BrowserApp {
+    /// Start reading the reader view's article from the top.
+    pub fn start_read_aloud(&mut self) {
+        let state = &mut self.read_aloud;
+        let Some(synth) = *state.synth.get_or_insert_with(Synthesizer::detect) else {
+            return;
+        };
+        let Some(reader) = self.reader.as_ref() else {
+            return;
+        };
+        let Some((_, ref layout)) = reader.article else {
+            return;
+        };
+        let blocks = speakable_blocks(layout)
+            .into_iter()
+            .map(block_text)
+            .collect();
+        let mut player = ReadAloud::new(synth, blocks, state.rate);
+        player.play();
+        state.session = Some(Session {
+            player,
+            url: reader.url.clone(),
+            scrolled: None,
+        });
+    }
+
+    pub fn stop_read_aloud(&mut self) {
+        self.read_aloud.session = None;
+    }
+
+    /// Advance playback, and stop it once the article is no longer shown.
+    pub fn poll_read_aloud(&mut self, ctx: &egui::Context) {
+        let Some(ref mut session) = self.read_aloud.session else {
+            return;
+        };
+        let shown = self.render_mode == RenderMode::Reader
+            && self.page.as_ref().is_some_and(|p| p.dom.url == session.url);
+        if !shown {
+            self.read_aloud.session = None;
+            return;
+        }
+        if session.player.poll() {
+            ctx.request_repaint();
+        }
+        if session.player.state() == Playback::Playing {
+            ctx.request_repaint_after(POLL_INTERVAL);
+        }
+    }
+
+    /// Toolbar controls, shown in reader mode.
+    pub fn draw_read_aloud_controls(&mut self, ui: &mut egui::Ui) {
+        if self.read_aloud.session.is_none() {
+            let has_article = self.reader.as_ref().is_some_and(|r| r.article.is_some());
+            match self.read_aloud.synth {
+                Some(None) => {
+                    ui.add_enabled(false, egui::Button::new("\u{1F50A} Read aloud"))
+                        .on_disabled_hover_text(
+                            "No speech synthesizer found; install espeak-ng to read aloud",
+                        );
+                }
+                _ => {
+                    if ui
+                        .add_enabled(has_article, egui::Button::new("\u{1F50A} Read aloud"))
+                        .on_hover_text("Read the article aloud")
+                        .clicked()
+                    {
+                        self.start_read_aloud();
+                    }
+                }
+            }
+            return;
+        }
+
+        let Some(ref mut session) = self.read_aloud.session else {
+            return;
+        };
+        let player = &mut session.player;
+        if ui
+            .button("\u{23EE}")
+            .on_hover_text("Previous paragraph")
+            .clicked()
+        {
+            player.skip(-1);
+        }
+        let playing = player.state() == Playback::Playing;
+        if ui
+            .button(if playing { "\u{23F8}" } else { "\u{25B6}" })
+            .on_hover_text(if playing { "Pause" } else { "Play" })
+            .clicked()
+        {
+            if playing {
+                player.pause();
+            } else {
+                player.play();
+            }
+        }
+        if ui
+            .button("\u{23ED}")
+            .on_hover_text("Next paragraph")
+            .clicked()
+        {
+            player.skip(1);
+        }
+        let mut rate = self.read_aloud.rate;
+        egui::ComboBox::from_id_salt("read_aloud_rate")
+            .width(56.0)
+            .selected_text(format!("{rate}\u{00d7}"))
+            .show_ui(ui, |ui| {
+                for r in RATES {
+                    ui.selectable_value(&mut rate, r, format!("{r}\u{00d7}"));
+                }
+            });
+        if (rate - self.read_aloud.rate).abs() > f32::EPSILON {
+            self.read_aloud.rate = rate;
+            player.set_rate(rate);
+        }
+        let position = match player.current() {
+            Some(i) => ui.weak(format!("{}/{}", i + 1, player.len())),
+            None => ui.weak("Done"),
+        };
+        position.on_hover_text(format!("Speaking with {}", player.synthesizer().label()));
+        if ui
+            .button("\u{23F9}")
+            .on_hover_text("Stop reading")
+            .clicked()
+        {
+            self.stop_read_aloud();
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Reader mode for `BrowserApp`.
+//!
+//! Lifts the main article out of the current page with
+//! `dom::readability::extract_article`, lays it out on its own at the
+//! reader's font size and line width, and shows the estimated reading time.
+
+use eframe::egui;
+
+use alice_browser::dom::readability::{extract_article, Article};
+use alice_browser::render::clipboard::ClipboardContent;
+use alice_browser::render::layout::{compute_layout_with_font, LayoutNode};
+use alice_browser::render::RenderMode;
+
+use super::BrowserApp;
+use crate::oz::resolve_url;
+use crate::ui::clipboard::CopyRequest;
+use crate::ui::reader::render_article;
+
+pub const DEFAULT_FONT_SIZE: f32 = 18.0;
+pub const DEFAULT_LINE_WIDTH: f32 = 680.0;
+pub(super) const FONT_RANGE: std::ops::RangeInclusive<f32> = 12.0..=32.0;
+const FONT_STEP: f32 = 2.0;
+const LINE_WIDTH_RANGE: std::ops::RangeInclusive<f32> = 400.0..=1000.0;
+
+/// The article of the current page, laid out for reading.
+pub struct ReaderView {
+    /// Page the article was extracted from
+    pub url: String,
+    pub font_size: f32,
+    pub width: f32,
+    /// `None` if the page has no recognisable main article
+    pub article: Option<(Article, LayoutNode)>,
+}
+
+impl BrowserApp {
+    /// Extract and lay out the article, unless the cached view is current.
+    fn update_reader_view(&mut self, width: f32) {
+        let Some(ref page) = self.page else {
+            self.reader = None;
+            return;
+        };
+        let font_size = self.reader_font_size * self.page_zoom;
+        let current = self.reader.as_ref().is_some_and(|r| {
+            r.url == page.dom.url
+                && (r.font_size - font_size).abs() < f32::EPSILON
+                && (r.width - width).abs() < 0.5
+        });
+        if current {
+            return;
+        }
+        let article = extract_article(&page.dom).map(|article| {
+            let layout = compute_layout_with_font(&article.content, width, font_size);
+            (article, layout)
+        });
+        self.reader = Some(ReaderView {
+            url: page.dom.url.clone(),
+            font_size,
+            width,
+            article,
+        });
+    }
+
+    /// Draw the reader view into the content panel.
+    pub fn draw_reader(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        // Settings bar
+        ui.horizontal(|ui| {
+            if ui
+                .button("A\u{2212}")
+                .on_hover_text("Smaller text")
+                .clicked()
+            {
+                self.reader_font_size =
+                    (self.reader_font_size - FONT_STEP).max(*FONT_RANGE.start());
+            }
+            ui.label(format!("{:.0}px", self.reader_font_size));
+            if ui.button("A+").on_hover_text("Larger text").clicked() {
+                self.reader_font_size = (self.reader_font_size + FONT_STEP).min(*FONT_RANGE.end());
+            }
+            ui.separator();
+            ui.add(
+                egui::Slider::new(&mut self.reader_line_width, LINE_WIDTH_RANGE)
+                    .text("Line width")
+                    .suffix("px"),
+            );
+            ui.separator();
+            if let Some(ReaderView {
+                article: Some((ref article, ref layout)),
+                ..
+            }) = self.reader
+            {
+                if ui
+                    .button("Copy article")
+                    .on_hover_text("Copy with formatting (HTML and plain text)")
+                    .clicked()
+                {
+                    let mut content = ClipboardContent::from_layout(layout);
+                    if !article.title.is_empty() {
+                        content.html = format!(
+                            "<h1>{}</h1>{}",
+                            alice_browser::engine::internal::escape(&article.title),
+                            content.html
+                        );
+                        content.text = format!("{}\n\n{}", article.title, content.text);
+                    }
+                    crate::ui::clipboard::request(ctx, CopyRequest::Rich(content));
+                }
+            }
+        });
+        ui.separator();
+
+        let width = self
+            .reader_line_width
+            .min(ui.available_width() - 16.0)
+            .max(200.0);
+        self.update_reader_view(width);
+
+        let Some(ReaderView {
+            article: Some((ref article, ref layout)),
+            ..
+        }) = self.reader
+        else {
+            ui.vertical_centered(|ui| {
+                ui.add_space(40.0);
+                ui.label("Reader view isn't available for this page.");
+                if ui.button("Show full page").clicked() {
+                    self.render_mode = RenderMode::Flat;
+                }
+            });
+            return;
+        };
+
+        #[cfg(feature = "search")]
+        let highlight = if self.search_query.is_empty() {
+            None
+        } else {
+            Some(self.search_query.as_str())
+        };
+        #[cfg(not(feature = "search"))]
+        let highlight: Option<&str> = None;
+
+        #[cfg(feature = "voice-web")]
+        let spoken = self.read_aloud.spoken_block(layout);
+        #[cfg(not(feature = "voice-web"))]
+        let spoken = None;
+
+        let font_size = self.reader_font_size;
+        let mut clicked_link: Option<String> = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            let margin = ((ui.available_width() - width) / 2.0).max(0.0);
+            ui.horizontal(|ui| {
+                ui.add_space(margin);
+                ui.vertical(|ui| {
+                    ui.set_max_width(width);
+                    ui.add_space(font_size);
+                    if !article.title.is_empty() {
+                        ui.label(
+                            egui::RichText::new(&article.title)
+                                .size(font_size * 1.8)
+                                .strong(),
+                        );
+                    }
+                    ui.colored_label(
+                        egui::Color32::GRAY,
+                        format!(
+                            "{} min read \u{00b7} {} words",
+                            article.minutes, article.words
+                        ),
+                    );
+                    ui.add_space(font_size);
+                    render_article(ui, layout, &mut clicked_link, highlight, spoken);
+                    ui.add_space(font_size * 2.0);
+                });
+            });
+        });
+
+        if let Some(href) = clicked_link {
+            let base = self.page.as_ref().map_or("", |p| p.dom.url.as_str());
+            self.url_input = resolve_url(base, &href);
+            self.navigate(ctx);
+        }
+    }
+}
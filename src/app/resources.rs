@@ -0,0 +1,125 @@
+//! Resources window for `BrowserApp`.
+//!
+//! Lists what the current page refers to — scripts, frames, trackers,
+//! fonts, stylesheets, images and media — grouped by the origin they load
+//! from (see [`alice_browser::engine::resources`]). Each origin can be
+//! blocked for the site; the choice is kept in the site's preferences and
+//! takes effect when the page is reloaded.
+
+use std::collections::BTreeSet;
+
+use eframe::egui;
+
+use alice_browser::engine::resources::ResourceKind;
+
+use super::BrowserApp;
+
+impl BrowserApp {
+    /// Draw the resources window.
+    pub fn draw_resources(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_resources;
+        let mut blocked: Option<BTreeSet<String>> = None;
+        let mut reload = false;
+
+        egui::Window::new("Resources")
+            .open(&mut open)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                let Some(ref page) = self.page else {
+                    ui.label("No page loaded");
+                    return;
+                };
+                let Some(ref origin) = self.site.origin else {
+                    ui.label("Built-in and local pages load no resources");
+                    return;
+                };
+                let manifest = &page.resources;
+                let totals = ResourceKind::ALL
+                    .into_iter()
+                    .filter_map(|k| {
+                        let n = manifest.count(k);
+                        (n > 0).then(|| format!("{n} {}", k.label()))
+                    })
+                    .collect::<Vec<_>>();
+                if totals.is_empty() {
+                    ui.label("The page refers to no subresources");
+                    return;
+                }
+                ui.label(totals.join(" · "));
+                ui.weak(format!("Untick an origin to stop {origin} loading from it"));
+                ui.separator();
+
+                let mut set = self.site.prefs.blocked_origins.clone();
+                egui::ScrollArea::vertical()
+                    .max_height(360.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("resource_origins")
+                            .num_columns(2)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for entry in manifest.origins() {
+                                    let mut allowed = !set.contains(&entry.origin);
+                                    let mut label = entry.origin.clone();
+                                    if entry.first_party {
+                                        label.push_str(" (this site)");
+                                    }
+                                    if ui.checkbox(&mut allowed, label).changed() {
+                                        if allowed {
+                                            set.remove(&entry.origin);
+                                        } else {
+                                            set.insert(entry.origin.clone());
+                                        }
+                                    }
+                                    let summary = ui.weak(entry.summary());
+                                    if entry.count(ResourceKind::Tracker) > 0 {
+                                        summary.on_hover_text("Known tracker (ad-block list)");
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if set != self.site.prefs.blocked_origins {
+                    blocked = Some(set);
+                }
+
+                // What the page was loaded with, against the stored choice
+                let loaded: BTreeSet<&str> = manifest
+                    .resources
+                    .iter()
+                    .filter(|r| r.blocked)
+                    .map(|r| r.origin.as_str())
+                    .collect();
+                let stored = blocked.as_ref().unwrap_or(&self.site.prefs.blocked_origins);
+                let pending = manifest
+                    .origins()
+                    .iter()
+                    .any(|o| loaded.contains(o.origin.as_str()) != stored.contains(&o.origin));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    reload = ui
+                        .add_enabled(
+                            pending && !self.loading,
+                            egui::Button::new("Reload to apply"),
+                        )
+                        .clicked();
+                    if manifest.blocked_count() > 0 {
+                        ui.weak(format!("{} resources not loaded", manifest.blocked_count()));
+                    }
+                });
+            });
+        self.show_resources = open;
+
+        if let Some(set) = blocked {
+            if let Some(origin) = self.site.origin.clone() {
+                let mut prefs = self.site.prefs.clone();
+                prefs.blocked_origins = set;
+                self.site.prefs = prefs.clone();
+                self.site_settings.set(&origin, prefs);
+                self.site_settings.save();
+            }
+        }
+        if reload {
+            self.reload(ctx);
+        }
+    }
+}
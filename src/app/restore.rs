@@ -0,0 +1,272 @@
+//! Session restore for `BrowserApp`.
+//!
+//! The back/forward list, where each of its pages was scrolled to, the
+//! render mode and the queued addresses are written to `session.jsonl` in
+//! the profile directory (see `alice_browser::engine::restore`) when they
+//! change — at most once per `settings.session_autosave_secs` — and when
+//! the window is closed. The file is written aside and renamed over the
+//! old copy, so a crash mid-write never leaves it half-written.
+//!
+//! At startup a saved session is offered back in a "Restore previous
+//! session" window. Restoring rebuilds the history, reloads the current
+//! entry, and opens each page at the position it was left at the first
+//! time it is shown again. Nothing is saved while the offer is open, so
+//! the previous session survives until the user decides.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use alice_browser::engine::restore::{self, SavedEntry, SavedSession};
+use alice_browser::local_time::{local_date, local_minute_of_day};
+use eframe::egui;
+
+use super::history::{day_label, now_secs};
+use super::BrowserApp;
+
+/// File name of the saved session inside the profile directory.
+pub const SESSION_FILE: &str = "session.jsonl";
+
+/// Entries listed in the restore window.
+const LISTED_ENTRIES: usize = 8;
+
+/// Saved-session bookkeeping.
+#[derive(Default)]
+pub struct SessionRestore {
+    /// Session found at startup, until restored or dismissed
+    pub offer: Option<SavedSession>,
+    /// Positions of restored entries not shown yet: history index to
+    /// address and scroll
+    scrolls: HashMap<usize, (String, f32)>,
+    /// Fingerprint of the state last written
+    saved: u64,
+    last_save: Option<Instant>,
+}
+
+/// Read the saved session, if there is one with any entries.
+#[must_use]
+pub fn load_saved_session() -> Option<SavedSession> {
+    let path = alice_browser::profile::file_path(SESSION_FILE).ok()?;
+    let text = std::fs::read_to_string(path).ok()?;
+    restore::decode(&text)
+}
+
+impl BrowserApp {
+    /// Offer the session saved by the last run, unless saving is off.
+    pub fn offer_saved_session(&mut self) {
+        if self.settings.session_autosave_secs > 0 {
+            self.restore.offer = load_saved_session();
+        }
+    }
+
+    /// Write the session if it changed and the autosave interval has
+    /// passed since the last write, or at once when the window is closing.
+    pub fn autosave_session(&mut self, ctx: &egui::Context) {
+        let interval = self.settings.session_autosave_secs;
+        if interval == 0 || self.restore.offer.is_some() {
+            return;
+        }
+        let fingerprint = self.session_fingerprint();
+        if fingerprint == self.restore.saved {
+            return;
+        }
+        let wait = self.restore.last_save.map_or(Duration::ZERO, |t| {
+            Duration::from_secs(u64::from(interval)).saturating_sub(t.elapsed())
+        });
+        let closing = ctx.input(|i| i.viewport().close_requested());
+        if !closing && !wait.is_zero() {
+            ctx.request_repaint_after(wait);
+            return;
+        }
+        self.save_session();
+        self.restore.saved = fingerprint;
+        self.restore.last_save = Some(Instant::now());
+    }
+
+    /// Cheap summary of what a save would write, to notice changes.
+    fn session_fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.history.hash(&mut hasher);
+        self.history_idx.hash(&mut hasher);
+        self.page_entry.hash(&mut hasher);
+        // Whole pixels: sub-pixel scrolling is not worth a write
+        self.keyboard.view.0.round().to_bits().hash(&mut hasher);
+        self.render_mode.key().hash(&mut hasher);
+        self.url_queue.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// What the window shows, as it would be restored.
+    fn session_snapshot(&self) -> SavedSession {
+        let current = self.page.as_ref().filter(|_| self.page_entry.is_some());
+        let entries = self
+            .history
+            .iter()
+            .enumerate()
+            .map(|(i, url)| {
+                let shown = current.filter(|_| self.page_entry == Some(i));
+                let scroll = if shown.is_some() {
+                    self.keyboard.view.0
+                } else {
+                    self.back_forward
+                        .scroll(i)
+                        .or_else(|| {
+                            self.restore
+                                .scrolls
+                                .get(&i)
+                                .filter(|(u, _)| u == url)
+                                .map(|&(_, y)| y)
+                        })
+                        .unwrap_or(0.0)
+                };
+                let title = shown.map_or_else(
+                    || {
+                        self.visits
+                            .entries
+                            .iter()
+                            .find(|v| v.url == *url)
+                            .map(|v| v.title.clone())
+                            .unwrap_or_default()
+                    },
+                    |page| page.dom.title.clone(),
+                );
+                SavedEntry {
+                    url: url.clone(),
+                    title,
+                    scroll: scroll.max(0.0),
+                }
+            })
+            .collect();
+        SavedSession {
+            entries,
+            index: self.history_idx,
+            render_mode: self.render_mode,
+            queue: self.url_queue.iter().cloned().collect(),
+            saved_at: now_secs(),
+        }
+    }
+
+    /// Write the session to the profile directory.
+    fn save_session(&self) {
+        let text = restore::encode(&self.session_snapshot());
+        let result = alice_browser::profile::file_path(SESSION_FILE).and_then(|path| {
+            let partial = path.with_extension("jsonl.part");
+            std::fs::write(&partial, text)?;
+            std::fs::rename(&partial, &path)
+        });
+        if let Err(e) = result {
+            log::warn!("Could not save the session: {e}");
+        }
+    }
+
+    /// Bring back the offered session: its history, render mode and queue,
+    /// loading the entry that was on screen.
+    pub fn restore_session(&mut self, ctx: &egui::Context) {
+        let Some(session) = self.restore.offer.take() else {
+            return;
+        };
+        self.leave_page();
+        self.back_forward.clear();
+        self.restore.scrolls = session
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.scroll > 0.0)
+            .map(|(i, e)| (i, (e.url.clone(), e.scroll)))
+            .collect();
+        self.history = session.entries.into_iter().map(|e| e.url).collect();
+        self.history_idx = session.index;
+        self.url_queue = session.queue.into();
+        self.set_render_mode(session.render_mode);
+        self.url_input = self.history[self.history_idx].clone();
+        self.navigate_no_history(ctx);
+    }
+
+    /// Saved scroll position of history entry `index`, once, if it was
+    /// restored and still holds the same address.
+    pub(super) fn take_restored_scroll(&mut self, index: usize) -> Option<f32> {
+        let (url, scroll) = self.restore.scrolls.remove(&index)?;
+        (self.history.get(index) == Some(&url)).then_some(scroll)
+    }
+
+    /// The "Restore previous session" window, while a session is offered.
+    pub fn draw_restore_offer(&mut self, ctx: &egui::Context) {
+        let Some(ref session) = self.restore.offer else {
+            return;
+        };
+        let mut choice = None;
+        egui::Window::new("Restore previous session")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                let count = session.entries.len();
+                ui.label(format!(
+                    "{count} page{} from {}",
+                    if count == 1 { "" } else { "s" },
+                    saved_label(session.saved_at)
+                ));
+                ui.separator();
+                let first = session.index.saturating_sub(LISTED_ENTRIES / 2);
+                for (i, entry) in session
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .skip(first)
+                    .take(LISTED_ENTRIES)
+                {
+                    let name = if entry.title.trim().is_empty() {
+                        entry.url.as_str()
+                    } else {
+                        entry.title.trim()
+                    };
+                    let text = egui::RichText::new(name);
+                    let text = if i == session.index {
+                        text.strong()
+                    } else {
+                        text.weak()
+                    };
+                    ui.label(text).on_hover_text(entry.url.as_str());
+                }
+                if !session.queue.is_empty() {
+                    ui.label(format!("{} queued", session.queue.len()));
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Restore").clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button("Start fresh").clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        match choice {
+            Some(true) => self.restore_session(ctx),
+            Some(false) => {
+                self.restore.offer = None;
+                self.go_home(ctx);
+            }
+            None => {}
+        }
+    }
+}
+
+/// "today at 14:05", "yesterday" or "3 Mar 2025" for a save at `secs`.
+fn saved_label(secs: u64) -> String {
+    const DAY_MINUTES: u64 = 24 * 60;
+    let now = now_secs();
+    let date = local_date(secs);
+    if date != local_date(now) {
+        let label = day_label(date, now);
+        return if label == "Yesterday" {
+            "yesterday".into()
+        } else {
+            label
+        };
+    }
+    // Local time of the save, counted back from the current local time
+    let ago = (now.saturating_sub(secs) / 60) % DAY_MINUTES;
+    let minutes = (u64::from(local_minute_of_day()) + DAY_MINUTES - ago) % DAY_MINUTES;
+    format!("today at {:02}:{:02}", minutes / 60, minutes % 60)
+}
@@ -0,0 +1,171 @@
+//! "Save page as…" window for `BrowserApp`.
+//!
+//! Saves the page on screen — as filtered, without removed elements — to
+//! the profile's `saved` directory as one self-contained HTML file or an
+//! MHTML archive (see [`alice_browser::engine::snapshot`]). Images and
+//! stylesheets are fetched for the file in the background; the saved copy
+//! can be reopened offline from its `file://` URL.
+
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+use eframe::egui;
+
+use alice_browser::engine::snapshot::{self, Snapshot, SnapshotFormat};
+use alice_browser::engine::workers::CancelToken;
+use alice_browser::net::scheduler::{self, Priority};
+
+use super::history::now_secs;
+use super::BrowserApp;
+
+/// Profile subdirectory holding saved pages.
+pub(super) const SAVED_DIR: &str = "saved";
+
+/// Save page window state.
+#[derive(Default)]
+pub struct SavePageWindow {
+    /// File name, without the extension
+    pub name: String,
+    pub format: SnapshotFormat,
+    pub status: Option<String>,
+    /// The last page saved
+    pub saved: Option<PathBuf>,
+    rx: Option<mpsc::Receiver<Result<PathBuf, String>>>,
+}
+
+/// `name` reduced to `[a-z0-9-]`, or "page".
+pub(super) fn slug(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "page".into()
+    } else {
+        slug[..slug.len().min(60)].to_string()
+    }
+}
+
+impl BrowserApp {
+    /// Open the save window, named after the current page.
+    pub fn open_save_page(&mut self) {
+        if let Some(ref page) = self.page {
+            self.save_page.name = slug(&page.dom.title);
+        }
+        self.save_page.status = None;
+        self.save_page.saved = None;
+        self.show_save_page = true;
+    }
+
+    /// Capture the current page and write it in the background.
+    fn save_current_page(&mut self, ctx: &egui::Context) {
+        let Some(ref page) = self.page else {
+            return;
+        };
+        let dom = page.dom.clone();
+        let host_key = dom.url.clone();
+        let format = self.save_page.format;
+        let name = slug(&self.save_page.name);
+        let (tx, rx) = mpsc::channel();
+        let ctx = ctx.clone();
+        let token = CancelToken::new();
+        scheduler::shared().submit(&host_key, Priority::Preview, &token, move |_| {
+            let result = (|| {
+                let dir = alice_browser::profile::file_path(SAVED_DIR)?;
+                std::fs::create_dir_all(&dir)?;
+                let mut path = dir.join(format!("{name}.{}", format.extension()));
+                if path.exists() {
+                    path = dir.join(format!("{name}-{}.{}", now_secs(), format.extension()));
+                }
+                let snapshot = Snapshot::capture(&dom, snapshot::fetch_resource);
+                std::fs::write(&path, snapshot.render(format))?;
+                Ok::<_, std::io::Error>(path)
+            })()
+            .map_err(|e| e.to_string());
+            let _ = tx.send(result);
+            ctx.request_repaint();
+        });
+        self.save_page.rx = Some(rx);
+        self.save_page.saved = None;
+        self.save_page.status = Some("Saving page and its images…".into());
+    }
+
+    /// Take a finished save.
+    fn poll_save_page(&mut self) {
+        let Some(ref rx) = self.save_page.rx else {
+            return;
+        };
+        let Ok(result) = rx.try_recv() else {
+            return;
+        };
+        self.save_page.rx = None;
+        match result {
+            Ok(path) => {
+                self.save_page.status = Some(format!("Saved to {}", path.display()));
+                self.save_page.saved = Some(path);
+            }
+            Err(e) => {
+                log::warn!("Saving page failed: {e}");
+                self.save_page.status = Some(format!("Could not save the page: {e}"));
+            }
+        }
+    }
+
+    /// Draw the save page window.
+    pub fn draw_save_page(&mut self, ctx: &egui::Context) {
+        self.poll_save_page();
+        let mut open = self.show_save_page;
+        let mut save = false;
+        let mut reopen = None;
+
+        egui::Window::new("Save page as")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let Some(ref page) = self.page else {
+                    ui.label("No page loaded");
+                    return;
+                };
+                ui.weak(&page.dom.url);
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut self.save_page.name);
+                    ui.weak(format!(".{}", self.save_page.format.extension()));
+                });
+                for format in SnapshotFormat::ALL {
+                    ui.radio_value(&mut self.save_page.format, format, format.label());
+                }
+                ui.weak("Removed elements, scripts and frames are left out");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    save = ui
+                        .add_enabled(self.save_page.rx.is_none(), egui::Button::new("Save"))
+                        .clicked();
+                    if let Some(ref path) = self.save_page.saved {
+                        if ui.button("Open saved copy").clicked() {
+                            reopen = snapshot::file_url(path);
+                        }
+                    }
+                });
+                if let Some(ref status) = self.save_page.status {
+                    ui.label(status);
+                }
+            });
+        self.show_save_page = open;
+
+        if save {
+            self.save_current_page(ctx);
+        }
+        if let Some(url) = reopen {
+            self.url_input = url;
+            self.navigate_typed(ctx);
+        }
+    }
+}
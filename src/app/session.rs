@@ -0,0 +1,309 @@
+//! Batch URL sessions for `BrowserApp`.
+//!
+//! "Open list…" reads a text or OPML file of addresses (or takes the URLs
+//! of a paste) and loads them as an `engine::session::Session` in the
+//! background, one page after another. The session window shows progress
+//! and the results as a grid of sketches, grouped by list group or host;
+//! with `sdf-render`, the loaded pages can also be shown as OZ
+//! constellations, one per group, for triage before opening any of them.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use eframe::egui;
+
+use alice_browser::engine::pipeline::BrowserEngine;
+use alice_browser::engine::session::{parse_url_list, Session, SessionEntry, SessionStatus};
+
+use super::BrowserApp;
+use crate::ui::truncate_str;
+
+/// Session window state.
+#[derive(Default)]
+pub struct SessionWindow {
+    pub session: Option<Session>,
+    /// List file to open
+    pub path: String,
+    pub status: Option<String>,
+    /// Page sketches, by page URL
+    textures: HashMap<String, egui::TextureHandle>,
+}
+
+/// Whether `path` looks like a session list (`.txt`, `.opml`).
+fn is_list_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("txt") || ext.eq_ignore_ascii_case("opml"))
+}
+
+impl BrowserApp {
+    /// Start loading `entries` as a new session, replacing any running one.
+    pub fn start_session(&mut self, entries: Vec<SessionEntry>, ctx: &egui::Context) {
+        if entries.is_empty() {
+            self.session.status = Some("No addresses in the list".into());
+            return;
+        }
+        let (width, height) = self
+            .content_rect
+            .map_or((800.0, 600.0), |r| (r.width(), r.height()));
+        let builder = BrowserEngine::builder()
+            .viewport(width, height)
+            .adblock(Some(std::sync::Arc::clone(&self.adblock)))
+            .allowlist(self.adblock_allowlist.clone())
+            .model(self.classifier_model.clone())
+            .network(self.settings.network_profile.conditions());
+        #[cfg(feature = "smart-cache")]
+        let builder = builder.cache(std::sync::Arc::clone(&self.page_cache));
+
+        self.session.status = None;
+        self.session.textures.clear();
+        let repaint = ctx.clone();
+        self.session.session = Some(Session::start(entries, builder, move || {
+            repaint.request_repaint();
+        }));
+        self.show_session = true;
+    }
+
+    /// Read a list file and load it as a session.
+    pub fn open_session_file(&mut self, path: &Path, ctx: &egui::Context) {
+        match std::fs::read_to_string(path) {
+            Ok(text) => self.start_session(parse_url_list(&text), ctx),
+            Err(e) => {
+                self.session.status = Some(format!("Could not open {}: {e}", path.display()));
+                self.show_session = true;
+            }
+        }
+    }
+
+    /// Apply the loader's progress; called every frame.
+    pub fn poll_session(&mut self) {
+        if let Some(ref mut session) = self.session.session {
+            session.poll();
+        }
+    }
+
+    /// Load list files dropped on the window as a session.
+    pub fn handle_dropped_lists(&mut self, ctx: &egui::Context) {
+        let dropped: Option<PathBuf> = ctx.input(|i| {
+            i.raw
+                .dropped_files
+                .iter()
+                .filter_map(|f| f.path.clone())
+                .find(|p| is_list_file(p))
+        });
+        if let Some(path) = dropped {
+            self.open_session_file(&path, ctx);
+        }
+    }
+
+    /// Show the session's loaded pages as OZ constellations, one per group.
+    #[cfg(feature = "sdf-render")]
+    pub fn show_session_constellation(&mut self) {
+        use alice_browser::render::docmap::DocMap;
+        use alice_browser::render::sdf_renderer::CameraParams;
+        use alice_browser::render::stream::StreamState;
+
+        let Some(ref session) = self.session.session else {
+            return;
+        };
+        let groups = session.constellation();
+        if groups.is_empty() {
+            return;
+        }
+        self.set_render_mode(alice_browser::render::RenderMode::OzMode);
+        self.page = None;
+        self.error = None;
+        self.reset_page_views();
+
+        let mut stream = StreamState::from_groups(groups);
+        if self.reduce_motion {
+            stream.arrange_static();
+        }
+        self.cam_params = CameraParams {
+            azimuth: 0.0,
+            elevation: 0.0,
+            distance: 0.0,
+            target: [0.0, 0.0, 0.0],
+            ..CameraParams::default()
+        };
+        self.spatial_scene = Some(stream.to_sdf_scene());
+        self.spatial_pick = super::picking::SpatialPick::default();
+        self.oz_doc_map = Some(DocMap::from_pool(&stream.text_pool));
+        self.oz_focus_section = None;
+        self.oz_search_query.clear();
+        self.stream_state = Some(stream);
+        self.last_frame_time = std::time::Instant::now();
+        self.cam_dirty = true;
+        if let Some(ref mut gpu) = self.gpu_renderer {
+            gpu.invalidate();
+        }
+    }
+
+    /// Texture for a loaded page's sketch, uploaded on first use.
+    fn session_texture(
+        &mut self,
+        ctx: &egui::Context,
+        index: usize,
+    ) -> Option<egui::TextureHandle> {
+        let session = self.session.session.as_ref()?;
+        let SessionStatus::Loaded(ref page) = session.items[index].status else {
+            return None;
+        };
+        let texture = self
+            .session
+            .textures
+            .entry(page.url.clone())
+            .or_insert_with(|| {
+                let thumb = &page.thumbnail;
+                let image = egui::ColorImage::from_rgba_unmultiplied(
+                    [thumb.width, thumb.height],
+                    &thumb.rgba,
+                );
+                ctx.load_texture(
+                    format!("session_{}", page.url),
+                    image,
+                    egui::TextureOptions::LINEAR,
+                )
+            });
+        Some(texture.clone())
+    }
+
+    /// Draw the session window.
+    pub fn draw_session(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_session;
+        let mut to_open: Option<PathBuf> = None;
+        let mut to_visit: Option<String> = None;
+        let mut cancel = false;
+        #[cfg(feature = "sdf-render")]
+        let mut constellation = false;
+
+        // Upload sketches before borrowing the session for drawing
+        let count = self.session.session.as_ref().map_or(0, |s| s.items.len());
+        let textures: Vec<Option<egui::TextureHandle>> =
+            (0..count).map(|i| self.session_texture(ctx, i)).collect();
+
+        egui::Window::new("Session")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.session.path)
+                            .hint_text("Path to a .txt or .opml list of URLs")
+                            .desired_width(320.0),
+                    );
+                    if ui.button("Open list").clicked() && !self.session.path.trim().is_empty() {
+                        to_open = Some(PathBuf::from(self.session.path.trim()));
+                    }
+                });
+                ui.weak("One URL per line; \"# Name\" starts a group. Lists can also be dropped.");
+                if let Some(ref status) = self.session.status {
+                    ui.label(status);
+                }
+
+                let Some(ref session) = self.session.session else {
+                    return;
+                };
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let done = session.done();
+                    let total = session.items.len();
+                    ui.add(
+                        egui::ProgressBar::new(done as f32 / total.max(1) as f32)
+                            .text(format!("{done} / {total}"))
+                            .desired_width(240.0),
+                    );
+                    if session.is_running() && ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                    #[cfg(feature = "sdf-render")]
+                    if ui
+                        .add_enabled(
+                            session
+                                .items
+                                .iter()
+                                .any(|i| matches!(i.status, SessionStatus::Loaded(_))),
+                            egui::Button::new("Show as OZ constellation"),
+                        )
+                        .clicked()
+                    {
+                        constellation = true;
+                    }
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .max_height(420.0)
+                    .show(ui, |ui| {
+                        for (group, members) in session.groups() {
+                            ui.strong(&group);
+                            ui.horizontal_wrapped(|ui| {
+                                for i in members {
+                                    let item = &session.items[i];
+                                    ui.vertical(|ui| {
+                                        ui.set_width(160.0);
+                                        match item.status {
+                                            SessionStatus::Loaded(ref page) => {
+                                                if let Some(Some(ref texture)) = textures.get(i) {
+                                                    let image = egui::Image::new(texture)
+                                                        .fit_to_exact_size(egui::vec2(160.0, 100.0))
+                                                        .sense(egui::Sense::click());
+                                                    if ui
+                                                        .add(image)
+                                                        .on_hover_text(&page.summary)
+                                                        .clicked()
+                                                    {
+                                                        to_visit = Some(page.url.clone());
+                                                    }
+                                                }
+                                                if ui.link(truncate_str(&page.title, 28)).clicked()
+                                                {
+                                                    to_visit = Some(page.url.clone());
+                                                }
+                                            }
+                                            SessionStatus::Loading => {
+                                                ui.add_sized([160.0, 100.0], egui::Spinner::new());
+                                                ui.weak(truncate_str(&item.entry.url, 28));
+                                            }
+                                            SessionStatus::Queued => {
+                                                ui.add_sized(
+                                                    [160.0, 100.0],
+                                                    egui::Label::new("Queued"),
+                                                );
+                                                ui.weak(truncate_str(&item.entry.url, 28));
+                                            }
+                                            SessionStatus::Failed(ref e) => {
+                                                ui.add_sized(
+                                                    [160.0, 100.0],
+                                                    egui::Label::new("Failed"),
+                                                )
+                                                .on_hover_text(e);
+                                                ui.weak(truncate_str(&item.entry.url, 28));
+                                            }
+                                        }
+                                    });
+                                }
+                            });
+                            ui.add_space(6.0);
+                        }
+                    });
+            });
+        self.show_session = open;
+
+        if cancel {
+            if let Some(ref mut session) = self.session.session {
+                session.cancel();
+            }
+        }
+        if let Some(path) = to_open {
+            self.open_session_file(&path, ctx);
+        }
+        #[cfg(feature = "sdf-render")]
+        if constellation {
+            self.show_session_constellation();
+        }
+        if let Some(url) = to_visit {
+            self.url_input = url;
+            self.navigate_typed(ctx);
+        }
+    }
+}
@@ -0,0 +1,673 @@
+//! User preferences for `BrowserApp`.
+//!
+//! Stored as `settings.toml` in the profile directory and applied at
+//! startup. Missing keys take their defaults, so older files keep loading
+//! as preferences are added. They are edited in the settings window
+//! (Cmd+, or the gear button) or on `alice://settings`; either way changes
+//! are saved at once and applied to the running browser where they can be.
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use alice_browser::net::prefetch_policy::PrefetchConfig;
+use alice_browser::net::proxy::{self, ProxyConfig, ProxyMode};
+use alice_browser::net::search_engine::{is_valid_template, SearchEngine, WebSearch};
+use alice_browser::net::speculate::Speculation;
+use alice_browser::net::throttle::NetworkProfile;
+use alice_browser::render::fonts::{FontPreferences, Script};
+use alice_browser::render::palette::ThemeChoice;
+use alice_browser::render::RenderMode;
+
+use super::BrowserApp;
+
+/// File name of the preferences inside the profile directory.
+pub const SETTINGS_FILE: &str = "settings.toml";
+
+/// egui's body text size, which the other text styles are scaled with.
+pub const DEFAULT_UI_FONT_SIZE: f32 = 12.5;
+
+/// Smallest and largest interface text size.
+const UI_FONT_RANGE: std::ops::RangeInclusive<f32> = 9.0..=24.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Render mode the browser starts in
+    pub render_mode: RenderMode,
+    /// Page opened at startup and by the home button; empty for none
+    pub homepage: String,
+    /// Where searches from the URL bar, pasted text and OZ mode go
+    pub search_engine: SearchEngine,
+    /// Results URL with `%s` for the query, for [`SearchEngine::Custom`]
+    pub search_template: String,
+    /// Complete searches typed in the URL bar with the engine's suggestions
+    pub search_suggestions: bool,
+    /// OpenSearch suggestion URL with `%s` for the query, for
+    /// [`SearchEngine::Custom`]; empty for none
+    pub suggest_template: String,
+    pub dark_mode: bool,
+    pub reduce_motion: bool,
+    /// First-run tour finished or skipped
+    pub onboarding_done: bool,
+    /// Longest side of decoded images, in pixels
+    pub max_image_size: u32,
+    /// Simulated network conditions (developer setting)
+    pub network_profile: NetworkProfile,
+    /// Palette of the 3-D modes; `Auto` follows dark mode and the clock
+    pub scene_theme: ThemeChoice,
+    /// Offer Internet Archive copies of dead, previously visited pages
+    pub wayback_fallback: bool,
+    /// Prefetch a page's links in the background for the OZ stream
+    pub prefetch_links: bool,
+    /// Links of a page prefetched for the OZ stream
+    pub prefetch_max_links: usize,
+    /// Prefetch requests in flight at once
+    pub prefetch_concurrency: usize,
+    /// Fetched pages kept in memory for reloads and revisits
+    pub cache_pages: usize,
+    /// Memory the browser stays under by dropping cached pages and images,
+    /// in MB; 0 for no limit
+    pub memory_budget_mb: usize,
+    /// Resolve and connect to the origins of hovered links
+    pub speculation: Speculation,
+    /// Show same-origin `<iframe>` documents, and those from `frame_hosts`,
+    /// inline
+    pub embed_frames: bool,
+    /// Hosts whose frames are shown on any site, comma-separated
+    pub frame_hosts: String,
+    pub proxy_mode: ProxyMode,
+    /// Proxy address for [`ProxyMode::Manual`], e.g. `socks5://host:1080`
+    pub proxy_url: String,
+    pub proxy_username: String,
+    /// Kept in plain text, like the rest of the file
+    pub proxy_password: String,
+    /// Hosts reached without the manual proxy, as in `NO_PROXY`
+    pub proxy_bypass: String,
+    /// Classifier model file; empty for the built-in classifier
+    pub classifier_model: String,
+    /// Font family for Latin text; empty for the built-in font
+    pub font_family: String,
+    /// CJK script whose font draws Han characters
+    pub cjk_font: Script,
+    /// Size of the interface's body text, in points
+    pub ui_font_size: f32,
+    /// Text size reader mode starts at, in points
+    pub reader_font_size: f32,
+    /// Least seconds between session saves; 0 turns saving and the
+    /// restore offer off
+    pub session_autosave_secs: u32,
+    /// Controller button for each action
+    #[cfg(feature = "gamepad")]
+    pub gamepad: alice_browser::gamepad::PadBindings,
+    /// Volume and mute of the 3-D and OZ cues
+    #[cfg(feature = "spatial-audio")]
+    pub audio: alice_browser::audio::AudioSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            render_mode: RenderMode::Flat,
+            homepage: String::new(),
+            search_engine: SearchEngine::default(),
+            search_template: String::new(),
+            search_suggestions: true,
+            suggest_template: String::new(),
+            dark_mode: false,
+            reduce_motion: false,
+            onboarding_done: false,
+            max_image_size: alice_browser::net::image::DEFAULT_MAX_TEXTURE_SIZE,
+            network_profile: NetworkProfile::Off,
+            scene_theme: ThemeChoice::Auto,
+            wayback_fallback: true,
+            prefetch_links: true,
+            prefetch_max_links: 10,
+            prefetch_concurrency: PrefetchConfig::default().max_concurrent,
+            cache_pages: 256,
+            memory_budget_mb: 1024,
+            speculation: Speculation::default(),
+            embed_frames: false,
+            frame_hosts: String::new(),
+            proxy_mode: ProxyMode::System,
+            proxy_url: String::new(),
+            proxy_username: String::new(),
+            proxy_password: String::new(),
+            proxy_bypass: "localhost, 127.0.0.1, ::1".into(),
+            classifier_model: String::new(),
+            font_family: String::new(),
+            cjk_font: Script::Japanese,
+            ui_font_size: DEFAULT_UI_FONT_SIZE,
+            reader_font_size: super::reader::DEFAULT_FONT_SIZE,
+            session_autosave_secs: 10,
+            #[cfg(feature = "gamepad")]
+            gamepad: alice_browser::gamepad::PadBindings::default(),
+            #[cfg(feature = "spatial-audio")]
+            audio: alice_browser::audio::AudioSettings::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Load from the profile directory; a missing or malformed file yields
+    /// the defaults.
+    #[must_use]
+    pub fn load() -> Self {
+        let Ok(path) = alice_browser::profile::file_path(SETTINGS_FILE) else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).unwrap_or_else(|e| {
+                log::warn!("Ignoring malformed {}: {e}", path.display());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// The proxy configuration these settings select.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if the manual proxy address is unusable.
+    pub fn proxy_config(&self) -> Result<ProxyConfig, String> {
+        match self.proxy_mode {
+            ProxyMode::System => Ok(ProxyConfig::from_env()),
+            ProxyMode::Off => Ok(ProxyConfig::DIRECT),
+            ProxyMode::Manual => ProxyConfig::manual(
+                &self.proxy_url,
+                &self.proxy_username,
+                &self.proxy_password,
+                &self.proxy_bypass,
+            ),
+        }
+    }
+
+    /// Route every later request by the proxy settings; an unusable manual
+    /// proxy falls back to connecting directly.
+    pub fn apply_proxy(&self) {
+        let config = self.proxy_config().unwrap_or_else(|e| {
+            log::warn!("Proxy disabled: {e}");
+            ProxyConfig::DIRECT
+        });
+        proxy::set_shared(config);
+    }
+
+    /// The font choices among these settings.
+    #[must_use]
+    pub fn font_preferences(&self) -> FontPreferences {
+        FontPreferences {
+            family: self.font_family.clone(),
+            cjk: self.cjk_font,
+        }
+    }
+
+    /// Prefetch policy with these settings' concurrency limit.
+    #[cfg(feature = "sdf-render")]
+    #[must_use]
+    pub fn prefetch_policy(&self) -> alice_browser::net::prefetch_policy::PrefetchPolicy {
+        alice_browser::net::prefetch_policy::PrefetchPolicy::new(PrefetchConfig {
+            max_concurrent: self.prefetch_concurrency.max(1),
+            ..PrefetchConfig::default()
+        })
+    }
+
+    /// The search engine these settings choose.
+    #[must_use]
+    pub fn web_search(&self) -> WebSearch {
+        let search = WebSearch::new(self.search_engine, &self.search_template);
+        if self.search_engine == SearchEngine::Custom {
+            search.with_suggest_template(&self.suggest_template)
+        } else {
+            search
+        }
+    }
+
+    /// Write to the profile directory.
+    pub fn save(&self) {
+        let result = alice_browser::profile::file_path(SETTINGS_FILE).and_then(|path| {
+            let text = toml::to_string_pretty(self).map_err(std::io::Error::other)?;
+            std::fs::write(path, text)
+        });
+        if let Err(e) = result {
+            log::warn!("Could not save settings: {e}");
+        }
+    }
+}
+
+impl BrowserApp {
+    /// Apply the stored preferences to the running browser.
+    pub fn apply_settings(&mut self, ctx: &egui::Context) {
+        self.set_render_mode(self.settings.render_mode);
+        self.dark_mode = self.settings.dark_mode;
+        self.reduce_motion = self.settings.reduce_motion;
+        self.apply_reduce_motion(ctx);
+        self.image_loader
+            .set_max_texture_size(self.settings.max_image_size);
+        self.image_loader
+            .set_network(self.settings.network_profile.conditions());
+        self.settings.apply_proxy();
+        apply_ui_font_size(ctx, self.settings.ui_font_size);
+        self.reader_font_size = self.settings.reader_font_size;
+        #[cfg(feature = "smart-cache")]
+        {
+            self.page_cache = std::sync::Arc::new(alice_browser::net::cache::CachedFetcher::new(
+                self.settings.cache_pages,
+            ));
+        }
+        #[cfg(feature = "sdf-render")]
+        {
+            self.prefetch_policy = std::sync::Arc::new(self.settings.prefetch_policy());
+        }
+        #[cfg(feature = "spatial-audio")]
+        {
+            self.audio.settings = self.settings.audio;
+        }
+    }
+
+    /// Open the home page, if one is set.
+    pub fn go_home(&mut self, ctx: &egui::Context) {
+        let home = self.settings.homepage.trim();
+        if !home.is_empty() {
+            self.url_input = home.to_string();
+            self.navigate_typed(ctx);
+        }
+    }
+
+    /// Replace the preferences with `settings`, save them and apply what
+    /// changed.
+    pub fn update_settings(&mut self, settings: Settings, ctx: &egui::Context) {
+        if settings == self.settings {
+            return;
+        }
+        let old = std::mem::replace(&mut self.settings, settings);
+        self.settings.save();
+        self.apply_changed_settings(&old, ctx);
+    }
+
+    /// Apply the preferences that differ from `old` to the running browser.
+    /// The render mode and home page are start-up choices and take effect
+    /// on the next start; a new cache size or prefetch limit starts an
+    /// empty cache or a fresh prefetch policy.
+    pub(super) fn apply_changed_settings(&mut self, old: &Settings, ctx: &egui::Context) {
+        let new = self.settings.clone();
+        if new.dark_mode != old.dark_mode {
+            self.dark_mode = new.dark_mode;
+        }
+        if new.reduce_motion != old.reduce_motion {
+            self.reduce_motion = new.reduce_motion;
+            self.apply_reduce_motion(ctx);
+        }
+        self.image_loader.set_max_texture_size(new.max_image_size);
+        self.image_loader
+            .set_network(new.network_profile.conditions());
+        if new.proxy_config() != old.proxy_config() {
+            new.apply_proxy();
+        }
+        if new.font_preferences() != old.font_preferences() {
+            crate::ui::fonts::install(ctx, &new.font_preferences());
+        }
+        if (new.ui_font_size - old.ui_font_size).abs() > f32::EPSILON {
+            apply_ui_font_size(ctx, new.ui_font_size);
+        }
+        if (new.reader_font_size - old.reader_font_size).abs() > f32::EPSILON {
+            self.reader_font_size = new.reader_font_size;
+        }
+        #[cfg(feature = "smart-cache")]
+        if new.cache_pages != old.cache_pages {
+            self.page_cache = std::sync::Arc::new(alice_browser::net::cache::CachedFetcher::new(
+                new.cache_pages,
+            ));
+        }
+        #[cfg(feature = "sdf-render")]
+        if new.prefetch_concurrency != old.prefetch_concurrency {
+            self.prefetch_policy = std::sync::Arc::new(new.prefetch_policy());
+        }
+        #[cfg(feature = "spatial-audio")]
+        {
+            self.audio.settings = new.audio;
+        }
+        if let Some(ref mut draft) = self.settings_draft {
+            draft.clone_from(&new);
+        }
+    }
+
+    /// The settings window: every preference, applied as it is changed
+    /// (text fields when they lose focus) and saved to `settings.toml`.
+    pub fn draw_settings(&mut self, ctx: &egui::Context) {
+        let mut draft = self
+            .settings_draft
+            .take()
+            .unwrap_or_else(|| self.settings.clone());
+        let mut open = true;
+        let mut commit = false;
+        egui::Window::new("Settings")
+            .open(&mut open)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    commit |= settings_form(ui, &mut draft);
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Restore defaults").clicked() {
+                            draft = Settings {
+                                onboarding_done: draft.onboarding_done,
+                                ..Settings::default()
+                            };
+                            commit = true;
+                        }
+                        ui.weak(format!("Saved to {SETTINGS_FILE}"));
+                    });
+                });
+            });
+        if commit {
+            self.update_settings(draft.clone(), ctx);
+        }
+        self.show_settings = open;
+        self.settings_draft = open.then_some(draft);
+    }
+
+    /// Cmd+, opens and closes the settings window.
+    pub fn handle_settings_key(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Comma)) {
+            self.show_settings = !self.show_settings;
+        }
+    }
+}
+
+/// Scale egui's text styles so that body text is `size` points.
+pub fn apply_ui_font_size(ctx: &egui::Context, size: f32) {
+    let scale = size.clamp(*UI_FONT_RANGE.start(), *UI_FONT_RANGE.end()) / DEFAULT_UI_FONT_SIZE;
+    let defaults = egui::Style::default().text_styles;
+    ctx.style_mut(|style| {
+        for (text_style, font) in &mut style.text_styles {
+            if let Some(base) = defaults.get(text_style) {
+                font.size = base.size * scale;
+            }
+        }
+    });
+}
+
+/// The settings window's controls over `s`; `true` when a change is to be
+/// applied.
+#[allow(clippy::too_many_lines)]
+fn settings_form(ui: &mut egui::Ui, s: &mut Settings) -> bool {
+    let mut commit = false;
+    egui::CollapsingHeader::new("General")
+        .default_open(true)
+        .show(ui, |ui| {
+            egui::Grid::new("settings_general")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Home page");
+                    commit |= text_field(ui, &mut s.homepage, "None");
+                    ui.end_row();
+                    ui.label("Search with");
+                    commit |= choice(
+                        ui,
+                        "search_engine",
+                        &mut s.search_engine,
+                        &SearchEngine::ALL,
+                        SearchEngine::label,
+                    );
+                    ui.end_row();
+                    if s.search_engine == SearchEngine::Custom {
+                        ui.label("Search URL");
+                        ui.vertical(|ui| {
+                            commit |= text_field(
+                                ui,
+                                &mut s.search_template,
+                                "https://search.example/?q=%s",
+                            );
+                            if !is_valid_template(&s.search_template) {
+                                ui.weak("An http(s) address with %s for the query");
+                            }
+                        });
+                        ui.end_row();
+                        ui.label("Suggestions URL");
+                        commit |= text_field(
+                            ui,
+                            &mut s.suggest_template,
+                            "https://search.example/suggest?q=%s",
+                        );
+                        ui.end_row();
+                    }
+                    ui.label("Suggestions");
+                    commit |= ui
+                        .checkbox(&mut s.search_suggestions, "While typing in the address bar")
+                        .changed();
+                    ui.end_row();
+                    ui.label("Start in");
+                    commit |= choice(
+                        ui,
+                        "start_mode",
+                        &mut s.render_mode,
+                        &RenderMode::ALL,
+                        RenderMode::label,
+                    );
+                    ui.end_row();
+                    ui.label("Save session every");
+                    commit |= ui
+                        .add(
+                            egui::DragValue::new(&mut s.session_autosave_secs)
+                                .range(0..=600)
+                                .suffix(" s"),
+                        )
+                        .on_hover_text("0 turns session saving and restoring off")
+                        .changed();
+                    ui.end_row();
+                });
+        });
+    egui::CollapsingHeader::new("Appearance")
+        .default_open(true)
+        .show(ui, |ui| {
+            commit |= ui.checkbox(&mut s.dark_mode, "Dark theme").changed();
+            commit |= ui.checkbox(&mut s.reduce_motion, "Reduce motion").changed();
+            egui::Grid::new("settings_appearance")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("3-D scene colours");
+                    commit |= choice(
+                        ui,
+                        "scene_theme",
+                        &mut s.scene_theme,
+                        &ThemeChoice::ALL,
+                        ThemeChoice::label,
+                    );
+                    ui.end_row();
+                    ui.label("Interface text");
+                    commit |= ui
+                        .add(egui::Slider::new(&mut s.ui_font_size, UI_FONT_RANGE).suffix(" pt"))
+                        .changed();
+                    ui.end_row();
+                    ui.label("Reader text");
+                    commit |= ui
+                        .add(
+                            egui::Slider::new(&mut s.reader_font_size, super::reader::FONT_RANGE)
+                                .suffix(" pt"),
+                        )
+                        .changed();
+                    ui.end_row();
+                    ui.label("Font");
+                    commit |= text_field(ui, &mut s.font_family, "Built-in");
+                    ui.end_row();
+                    ui.label("Chinese characters as");
+                    commit |= choice(ui, "cjk_font", &mut s.cjk_font, &Script::CJK, Script::label);
+                    ui.end_row();
+                });
+        });
+    egui::CollapsingHeader::new("Network and cache")
+        .default_open(true)
+        .show(ui, |ui| {
+            commit |= ui
+                .checkbox(&mut s.prefetch_links, "Prefetch linked pages for OZ mode")
+                .changed();
+            commit |= ui
+                .checkbox(
+                    &mut s.wayback_fallback,
+                    "Offer archived copies of dead pages",
+                )
+                .changed();
+            commit |= ui
+                .checkbox(
+                    &mut s.embed_frames,
+                    "Show embedded frames from the same site inline",
+                )
+                .changed();
+            egui::Grid::new("settings_network")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Links prefetched per page");
+                    commit |= ui
+                        .add(egui::DragValue::new(&mut s.prefetch_max_links).range(0..=50))
+                        .changed();
+                    ui.end_row();
+                    ui.label("Prefetches at once");
+                    commit |= ui
+                        .add(egui::DragValue::new(&mut s.prefetch_concurrency).range(1..=8))
+                        .changed();
+                    ui.end_row();
+                    ui.label("Pages cached");
+                    commit |= ui
+                        .add(egui::DragValue::new(&mut s.cache_pages).range(16..=4096))
+                        .changed();
+                    ui.end_row();
+                    ui.label("Memory budget");
+                    commit |= ui
+                        .add(
+                            egui::DragValue::new(&mut s.memory_budget_mb)
+                                .range(0..=65536)
+                                .suffix(" MB"),
+                        )
+                        .on_hover_text("Drop cached pages, then images, past this; 0 for no limit")
+                        .changed();
+                    ui.end_row();
+                    ui.label("Largest image side");
+                    commit |= ui
+                        .add(
+                            egui::DragValue::new(&mut s.max_image_size)
+                                .range(64..=16384)
+                                .suffix(" px"),
+                        )
+                        .changed();
+                    ui.end_row();
+                    ui.label("Hovered links");
+                    commit |= choice(
+                        ui,
+                        "speculation",
+                        &mut s.speculation,
+                        &Speculation::ALL,
+                        Speculation::label,
+                    );
+                    ui.end_row();
+                    ui.label("Also show frames from");
+                    commit |= text_field(ui, &mut s.frame_hosts, "maps.example, video.example");
+                    ui.end_row();
+                    ui.label("Simulated network");
+                    commit |= choice(
+                        ui,
+                        "network",
+                        &mut s.network_profile,
+                        &NetworkProfile::ALL,
+                        NetworkProfile::label,
+                    );
+                    ui.end_row();
+                });
+        });
+    egui::CollapsingHeader::new("Proxy")
+        .default_open(false)
+        .show(ui, |ui| {
+            egui::Grid::new("settings_proxy")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Proxy");
+                    commit |= choice(
+                        ui,
+                        "proxy_mode",
+                        &mut s.proxy_mode,
+                        &ProxyMode::ALL,
+                        ProxyMode::label,
+                    );
+                    ui.end_row();
+                    ui.label("Manual proxy");
+                    commit |= text_field(ui, &mut s.proxy_url, "socks5://host:1080");
+                    ui.end_row();
+                    ui.label("User name");
+                    commit |= text_field(ui, &mut s.proxy_username, "");
+                    ui.end_row();
+                    ui.label("Password");
+                    commit |= ui
+                        .add(egui::TextEdit::singleline(&mut s.proxy_password).password(true))
+                        .lost_focus();
+                    ui.end_row();
+                    ui.label("No proxy for");
+                    commit |= text_field(ui, &mut s.proxy_bypass, "");
+                    ui.end_row();
+                });
+        });
+    #[cfg(feature = "spatial-audio")]
+    egui::CollapsingHeader::new("Audio")
+        .default_open(false)
+        .show(ui, |ui| {
+            commit |= ui.checkbox(&mut s.audio.master_muted, "Mute all").changed();
+            commit |= ui
+                .add_enabled(
+                    !s.audio.master_muted,
+                    egui::Slider::new(&mut s.audio.volume, 0.0..=1.0).text("Volume"),
+                )
+                .changed();
+            commit |= ui
+                .checkbox(&mut s.audio.oz_ambient, "OZ spawn and flow sounds")
+                .on_hover_text("Ticks where text appears, a whoosh while turning the view")
+                .changed();
+        });
+    #[cfg(feature = "gamepad")]
+    {
+        use alice_browser::gamepad::{PadButton, PadKey};
+
+        egui::CollapsingHeader::new("Controller")
+            .default_open(false)
+            .show(ui, |ui| {
+                egui::Grid::new("settings_gamepad")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for action in PadButton::ALL {
+                            ui.label(action.label());
+                            commit |= choice(
+                                ui,
+                                action.label(),
+                                s.gamepad.key_mut(action),
+                                &PadKey::ALL,
+                                PadKey::label,
+                            );
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+    commit
+}
+
+/// One-line text field, applied when it loses focus.
+fn text_field(ui: &mut egui::Ui, value: &mut String, hint: &str) -> bool {
+    ui.add(egui::TextEdit::singleline(value).hint_text(hint))
+        .lost_focus()
+}
+
+/// Drop-down over `options`; `true` when another was picked.
+fn choice<T: Copy + PartialEq>(
+    ui: &mut egui::Ui,
+    id: &str,
+    value: &mut T,
+    options: &[T],
+    label: fn(T) -> &'static str,
+) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(label(*value))
+        .show_ui(ui, |ui| {
+            for &option in options {
+                changed |= ui.selectable_value(value, option, label(option)).changed();
+            }
+        });
+    changed
+}
@@ -0,0 +1,366 @@
+//! Per-site preferences for `BrowserApp`.
+//!
+//! Keyed by origin (`scheme://host[:port]`) and stored as
+//! `site-settings.toml` in the profile directory. A site can always open in
+//! Reader mode, force the dark theme, keep its own page zoom, block the
+//! subresources of some origins or correct the classifier. The origin and
+//! classifier preferences are applied when a request starts, the others
+//! when the page arrives; leaving the site undoes them. Edited in the site settings and resources windows (stats
+//! panel) and listed on `alice://settings`.
+//!
+//! Sites loaded without ad blocking are kept apart, by domain, in the
+//! ad-block allowlist (`adblock-allowlist.txt`, see
+//! [`alice_browser::net::adblock::Allowlist`]); the toolbar shield and the
+//! site settings window toggle the current site.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use alice_browser::dom::filter::FeedbackRules;
+use alice_browser::net::adblock::{self, Allowlist, ALLOWLIST_FILE};
+use alice_browser::render::RenderMode;
+
+use super::BrowserApp;
+
+/// File name of the per-site preferences inside the profile directory.
+pub const SITE_SETTINGS_FILE: &str = "site-settings.toml";
+
+/// Zoom range offered in the site settings window.
+const ZOOM_RANGE: std::ops::RangeInclusive<f32> = 0.5..=3.0;
+
+/// Preferences for one site; the default is "nothing special".
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SitePrefs {
+    /// Switch to Reader mode when a page of the site loads
+    pub reader_mode: bool,
+    /// Load the site's pages without the ad blocker. Superseded by the
+    /// allowlist: read from old files and moved there, never written.
+    #[serde(skip_serializing)]
+    pub disable_adblock: bool,
+    pub force_dark: bool,
+    /// Page zoom while the site is shown (Cmd+Plus/Minus store it here)
+    pub zoom: Option<f32>,
+    /// Origins whose scripts, frames, images and other subresources the
+    /// site's pages do not load (resources window)
+    pub blocked_origins: BTreeSet<String>,
+    /// Elements marked as content or as ads (page inspector), applied
+    /// after the classifier
+    pub feedback: FeedbackRules,
+}
+
+impl SitePrefs {
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Short description for listings, e.g. "reader, dark, 125%".
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.reader_mode {
+            parts.push("reader".to_string());
+        }
+        if self.force_dark {
+            parts.push("dark".to_string());
+        }
+        if let Some(zoom) = self.zoom {
+            parts.push(format!("{:.0}%", zoom * 100.0));
+        }
+        match self.blocked_origins.len() {
+            0 => {}
+            1 => parts.push("1 origin blocked".to_string()),
+            n => parts.push(format!("{n} origins blocked")),
+        }
+        match self.feedback.len() {
+            0 => {}
+            1 => parts.push("1 correction".to_string()),
+            n => parts.push(format!("{n} corrections")),
+        }
+        parts.join(", ")
+    }
+}
+
+/// Preferences of every site that has any.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SiteSettings {
+    pub sites: BTreeMap<String, SitePrefs>,
+}
+
+impl SiteSettings {
+    /// Load from the profile directory; a missing or malformed file yields
+    /// no site preferences.
+    #[must_use]
+    pub fn load() -> Self {
+        let Ok(path) = alice_browser::profile::file_path(SITE_SETTINGS_FILE) else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).unwrap_or_else(|e| {
+                log::warn!("Ignoring malformed {}: {e}", path.display());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Write to the profile directory.
+    pub fn save(&self) {
+        let result = alice_browser::profile::file_path(SITE_SETTINGS_FILE).and_then(|path| {
+            let text = toml::to_string_pretty(self).map_err(std::io::Error::other)?;
+            std::fs::write(path, text)
+        });
+        if let Err(e) = result {
+            log::warn!("Could not save site settings: {e}");
+        }
+    }
+
+    /// Preferences for the site of `url` (defaults if it has none).
+    #[must_use]
+    pub fn for_url(&self, url: &str) -> SitePrefs {
+        origin(url)
+            .and_then(|o| self.sites.get(&o))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Store `prefs` for `origin`; default preferences drop the entry.
+    pub fn set(&mut self, origin: &str, prefs: SitePrefs) {
+        if prefs.is_default() {
+            self.sites.remove(origin);
+        } else {
+            self.sites.insert(origin.to_string(), prefs);
+        }
+    }
+}
+
+/// Origin of an `http(s)` URL; URLs typed without a scheme count as
+/// `https://`. `None` for other schemes (`alice://`, `data:`, …).
+#[must_use]
+pub fn origin(url: &str) -> Option<String> {
+    let url = url.trim();
+    let parsed = if url.contains("://") {
+        url::Url::parse(url)
+    } else {
+        url::Url::parse(&format!("https://{url}"))
+    }
+    .ok()?;
+    matches!(parsed.scheme(), "http" | "https").then(|| parsed.origin().ascii_serialization())
+}
+
+/// The site being shown and what applying its preferences changed.
+#[derive(Debug, Default)]
+pub struct ActiveSite {
+    pub origin: Option<String>,
+    pub prefs: SitePrefs,
+    /// Render mode to return to when leaving a site that forced Reader mode
+    mode_before_reader: Option<RenderMode>,
+}
+
+impl BrowserApp {
+    /// Make `url`'s site the active one and apply its preferences, undoing
+    /// those of the previous site.
+    pub fn enter_site(&mut self, url: &str) {
+        let origin = origin(url);
+        let prefs = self.site_settings.for_url(url);
+        let previous = std::mem::replace(
+            &mut self.site,
+            ActiveSite {
+                origin,
+                prefs: prefs.clone(),
+                mode_before_reader: None,
+            },
+        );
+
+        self.page_zoom = prefs.zoom.unwrap_or(1.0);
+
+        if prefs.reader_mode {
+            self.site.mode_before_reader = previous
+                .mode_before_reader
+                .or((self.render_mode != RenderMode::Reader).then_some(self.render_mode));
+            self.set_render_mode(RenderMode::Reader);
+        } else if let Some(mode) = previous.mode_before_reader {
+            self.set_render_mode(mode);
+        }
+    }
+
+    /// Load the ad-block allowlist from the profile directory, moving in
+    /// sites whose preferences still say "disable ad-block".
+    pub fn load_adblock_allowlist(&mut self) {
+        let loaded = alice_browser::profile::file_path(ALLOWLIST_FILE)
+            .and_then(|path| Allowlist::load(&path));
+        match loaded {
+            Ok(list) => self.adblock_allowlist = list,
+            Err(e) => log::warn!("Could not load the ad-block allowlist: {e}"),
+        }
+
+        let legacy: Vec<String> = self
+            .site_settings
+            .sites
+            .iter()
+            .filter(|(_, prefs)| prefs.disable_adblock)
+            .map(|(origin, _)| origin.clone())
+            .collect();
+        if legacy.is_empty() {
+            return;
+        }
+        for origin in legacy {
+            self.adblock_allowlist.insert(&origin);
+            if let Some(mut prefs) = self.site_settings.sites.get(&origin).cloned() {
+                prefs.disable_adblock = false;
+                self.site_settings.set(&origin, prefs);
+            }
+        }
+        self.save_adblock_allowlist();
+        self.site_settings.save();
+    }
+
+    /// Write the ad-block allowlist to the profile directory.
+    pub fn save_adblock_allowlist(&self) {
+        let saved = alice_browser::profile::file_path(ALLOWLIST_FILE)
+            .and_then(|path| self.adblock_allowlist.save(&path));
+        if let Err(e) = saved {
+            log::warn!("Could not save the ad-block allowlist: {e}");
+        }
+    }
+
+    /// Whether the current site is on the ad-block allowlist.
+    #[must_use]
+    pub fn adblock_allowed_here(&self) -> bool {
+        self.site
+            .origin
+            .as_deref()
+            .is_some_and(|origin| self.adblock_allowlist.allows(origin))
+    }
+
+    /// Turn ad blocking off (`allowed`) or back on for the current site,
+    /// save the allowlist and reload the page to apply it.
+    pub fn set_adblock_allowed_here(&mut self, allowed: bool, ctx: &egui::Context) {
+        let Some(origin) = self.site.origin.clone() else {
+            return;
+        };
+        let changed = if allowed {
+            self.adblock_allowlist.insert(&origin)
+        } else {
+            self.adblock_allowlist.remove(&origin)
+        };
+        if !changed {
+            return;
+        }
+        self.save_adblock_allowlist();
+        // Blocking only changes what a fresh load fetches and keeps
+        if !self.loading && self.page.is_some() {
+            self.navigate_no_history(ctx);
+        }
+    }
+
+    /// Toolbar shield: ad blocking on the current site, click to toggle.
+    pub fn draw_adblock_toggle(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let allowed = self.adblock_allowed_here();
+        let (icon, hover) = if allowed {
+            (
+                "\u{26D4}",
+                "Ad blocking is off here — enable blocking on this site",
+            )
+        } else {
+            ("\u{1F6E1}", "Disable blocking on this site")
+        };
+        let clicked = ui
+            .add_enabled(
+                self.site.origin.is_some() && !self.loading,
+                egui::Button::new(icon)
+                    .selected(allowed)
+                    .min_size(egui::vec2(24.0, 24.0)),
+            )
+            .on_hover_text(hover)
+            .clicked();
+        if clicked {
+            self.set_adblock_allowed_here(!allowed, ctx);
+        }
+    }
+
+    /// Whether the dark theme is in effect (the user's choice or the site's).
+    #[must_use]
+    pub fn effective_dark_mode(&self) -> bool {
+        self.dark_mode || self.site.prefs.force_dark
+    }
+
+    /// Site settings window for the current page's site.
+    pub fn draw_site_settings(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_site_settings;
+        let mut reload = false;
+        let mut allow: Option<bool> = None;
+        egui::Window::new("Site settings")
+            .open(&mut open)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                let Some(origin) = self.site.origin.clone() else {
+                    ui.label("No web page loaded");
+                    return;
+                };
+                ui.strong(&origin);
+                ui.separator();
+
+                let mut prefs = self.site.prefs.clone();
+                ui.checkbox(&mut prefs.reader_mode, "Always open in Reader mode");
+                ui.checkbox(&mut prefs.force_dark, "Force dark theme");
+                let mut allowed = self.adblock_allowed_here();
+                if ui
+                    .checkbox(&mut allowed, "Disable ad-block here")
+                    .on_hover_text(format!(
+                        "Allowlists {} and its subdomains",
+                        adblock::site_domain(&origin)
+                    ))
+                    .changed()
+                {
+                    allow = Some(allowed);
+                }
+                let mut custom_zoom = prefs.zoom.is_some();
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut custom_zoom, "Zoom");
+                    let mut zoom = prefs.zoom.unwrap_or(self.page_zoom);
+                    ui.add_enabled(
+                        custom_zoom,
+                        egui::Slider::new(&mut zoom, ZOOM_RANGE)
+                            .step_by(0.05)
+                            .custom_formatter(|z, _| format!("{:.0}%", z * 100.0)),
+                    );
+                    prefs.zoom = custom_zoom.then_some(zoom);
+                });
+
+                if prefs != self.site.prefs {
+                    self.site_settings.set(&origin, prefs);
+                    self.site_settings.save();
+                    let url = self
+                        .page
+                        .as_ref()
+                        .map_or_else(|| origin.clone(), |p| p.dom.url.clone());
+                    self.enter_site(&url);
+                }
+
+                ui.separator();
+                if ui.button("Reset this site").clicked() {
+                    reload = !self.site.prefs.blocked_origins.is_empty()
+                        || !self.site.prefs.feedback.is_empty();
+                    if self.adblock_allowed_here() {
+                        allow = Some(false);
+                    }
+                    self.site_settings.set(&origin, SitePrefs::default());
+                    self.site_settings.save();
+                    self.enter_site(&origin);
+                }
+            });
+        self.show_site_settings = open;
+
+        if let Some(allowed) = allow {
+            // Reloads the page
+            self.set_adblock_allowed_here(allowed, ctx);
+        } else if reload && !self.loading && self.page.is_some() {
+            self.navigate_no_history(ctx);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Site map window for `BrowserApp`.
+//!
+//! "Site map…" reads the `sitemap.xml` of a site (following sitemap
+//! indexes) in the background, through
+//! [`BrowserEngine::fetch_sitemap`], and shows its pages as a tree by path.
+//! With `sdf-render`, the map can also be shown as OZ constellations, one
+//! per section, or as an orbital system in Spatial3D where clicking a
+//! planet or satellite opens its page.
+
+use std::sync::mpsc;
+
+use eframe::egui;
+
+use alice_browser::engine::pipeline::BrowserEngine;
+use alice_browser::engine::sitemap::SiteNode;
+use alice_browser::engine::workers::CancelToken;
+use alice_browser::net::scheduler::{self, Priority};
+
+use super::BrowserApp;
+
+/// Screen distance, in points, within which a click picks a body of the
+/// orbital system.
+#[cfg(feature = "sdf-render")]
+const PICK_RADIUS: f32 = 24.0;
+
+/// Site map window state.
+#[derive(Default)]
+pub struct SiteMapWindow {
+    /// Site whose map to read
+    pub origin: String,
+    pub status: Option<String>,
+    pub tree: Option<SiteNode>,
+    rx: Option<mpsc::Receiver<Result<SiteNode, String>>>,
+    /// Bodies of the orbital system on screen, with the pages they open
+    #[cfg(feature = "sdf-render")]
+    pub targets: Vec<([f32; 3], String)>,
+}
+
+impl BrowserApp {
+    /// Open the site map window for the site of the current page.
+    pub fn open_site_map(&mut self) {
+        if self.site_map.origin.is_empty() {
+            self.site_map.origin.clone_from(&self.url_input);
+        }
+        self.show_site_map = true;
+    }
+
+    /// Read the site map of `site_map.origin` in the background.
+    pub fn load_site_map(&mut self, ctx: &egui::Context) {
+        let origin = self.site_map.origin.trim().to_string();
+        if origin.is_empty() {
+            return;
+        }
+        let builder = BrowserEngine::builder()
+            .adblock(Some(std::sync::Arc::clone(&self.adblock)))
+            .allowlist(self.adblock_allowlist.clone())
+            .model(self.classifier_model.clone())
+            .network(self.settings.network_profile.conditions());
+        #[cfg(feature = "smart-cache")]
+        let builder = builder.cache(std::sync::Arc::clone(&self.page_cache));
+
+        let (tx, rx) = mpsc::channel();
+        let ctx = ctx.clone();
+        let host_key = origin.clone();
+        let token = CancelToken::new();
+        scheduler::shared().submit(&host_key, Priority::Preview, &token, move |_| {
+            let result = builder
+                .build()
+                .fetch_sitemap(&origin)
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result);
+            ctx.request_repaint();
+        });
+        self.site_map.rx = Some(rx);
+        self.site_map.tree = None;
+        self.site_map.status = Some("Reading sitemap.xml…".into());
+    }
+
+    /// Take a finished site map read; called every frame.
+    pub fn poll_site_map(&mut self) {
+        let Some(ref rx) = self.site_map.rx else {
+            return;
+        };
+        let Ok(result) = rx.try_recv() else {
+            return;
+        };
+        self.site_map.rx = None;
+        match result {
+            Ok(tree) if tree.page_count() == 0 => {
+                self.site_map.status = Some("The sitemap lists no pages on this site".into());
+            }
+            Ok(tree) => {
+                self.site_map.status = Some(format!(
+                    "{} pages in {} sections",
+                    tree.page_count(),
+                    tree.children.len()
+                ));
+                self.site_map.tree = Some(tree);
+            }
+            Err(e) => self.site_map.status = Some(format!("No site map: {e}")),
+        }
+    }
+
+    /// Show the site map as OZ constellations, one per section.
+    #[cfg(feature = "sdf-render")]
+    pub fn show_site_map_constellation(&mut self) {
+        use alice_browser::render::docmap::DocMap;
+        use alice_browser::render::sdf_renderer::CameraParams;
+        use alice_browser::render::stream::StreamState;
+        use alice_browser::render::RenderMode;
+
+        let Some(ref tree) = self.site_map.tree else {
+            return;
+        };
+        let groups = tree.constellation();
+        if groups.is_empty() {
+            return;
+        }
+        self.set_render_mode(RenderMode::OzMode);
+        self.page = None;
+        self.error = None;
+        self.reset_page_views();
+
+        let mut stream = StreamState::from_groups(groups);
+        if self.reduce_motion {
+            stream.arrange_static();
+        }
+        self.cam_params = CameraParams {
+            azimuth: 0.0,
+            elevation: 0.0,
+            distance: 0.0,
+            target: [0.0, 0.0, 0.0],
+            ..CameraParams::default()
+        };
+        self.spatial_scene = Some(stream.to_sdf_scene());
+        self.spatial_pick = super::picking::SpatialPick::default();
+        self.oz_doc_map = Some(DocMap::from_pool(&stream.text_pool));
+        self.oz_focus_section = None;
+        self.oz_search_query.clear();
+        self.stream_state = Some(stream);
+        self.last_frame_time = std::time::Instant::now();
+        self.cam_dirty = true;
+        if let Some(ref mut gpu) = self.gpu_renderer {
+            gpu.invalidate();
+        }
+    }
+
+    /// Show the site map as an orbital system in Spatial3D.
+    #[cfg(feature = "sdf-render")]
+    pub fn show_site_map_orbits(&mut self) {
+        use alice_browser::render::sdf_renderer::auto_camera;
+        use alice_browser::render::RenderMode;
+
+        let Some((scene, targets)) = self.site_map.tree.as_ref().map(SiteNode::orbit_scene) else {
+            return;
+        };
+        self.set_render_mode(RenderMode::Spatial3D);
+        self.page = None;
+        self.error = None;
+        self.reset_page_views();
+        self.cam_params = auto_camera(&scene);
+        self.spatial_scene = Some(scene);
+        self.spatial_pick = super::picking::SpatialPick::default();
+        self.site_map.targets = targets;
+        self.cam_dirty = true;
+        if let Some(ref mut gpu) = self.gpu_renderer {
+            gpu.invalidate();
+        }
+    }
+
+    /// The page of the orbital-system body nearest `pos` in the 3-D view
+    /// drawn in `rect`, if one is close enough.
+    #[cfg(feature = "sdf-render")]
+    pub fn site_map_target_at(&self, pos: egui::Pos2, rect: egui::Rect) -> Option<String> {
+        use alice_browser::render::sdf_renderer::project_point;
+
+        let aspect = rect.width() / rect.height().max(1.0);
+        self.site_map
+            .targets
+            .iter()
+            .filter_map(|(point, url)| {
+                let [x, y] = project_point(&self.cam_params, aspect, *point)?;
+                let on_screen = egui::pos2(
+                    rect.left() + (x + 1.0) * 0.5 * rect.width(),
+                    rect.top() + (1.0 - y) * 0.5 * rect.height(),
+                );
+                Some((on_screen.distance(pos), url))
+            })
+            .filter(|(distance, _)| *distance <= PICK_RADIUS)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, url)| url.clone())
+    }
+
+    /// Draw the site map window.
+    pub fn draw_site_map(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_site_map;
+        let mut load = false;
+        let mut to_visit: Option<String> = None;
+        #[cfg(feature = "sdf-render")]
+        let mut show_oz = false;
+        #[cfg(feature = "sdf-render")]
+        let mut show_orbits = false;
+
+        egui::Window::new("Site map")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let field = ui.add(
+                        egui::TextEdit::singleline(&mut self.site_map.origin)
+                            .hint_text("Site address")
+                            .desired_width(280.0),
+                    );
+                    let enter = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if ui
+                        .add_enabled(self.site_map.rx.is_none(), egui::Button::new("Read"))
+                        .clicked()
+                        || (enter && self.site_map.rx.is_none())
+                    {
+                        load = true;
+                    }
+                });
+                if let Some(ref status) = self.site_map.status {
+                    ui.horizontal(|ui| {
+                        if self.site_map.rx.is_some() {
+                            ui.spinner();
+                        }
+                        ui.label(status);
+                    });
+                }
+
+                let Some(ref tree) = self.site_map.tree else {
+                    return;
+                };
+                #[cfg(feature = "sdf-render")]
+                ui.horizontal(|ui| {
+                    show_oz = ui.button("Show as OZ constellations").clicked();
+                    show_orbits = ui.button("Show as orbital system").clicked();
+                });
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(480.0)
+                    .show(ui, |ui| {
+                        if let Some(ref url) = tree.url {
+                            if ui.link(&tree.name).on_hover_text(url).clicked() {
+                                to_visit = Some(url.clone());
+                            }
+                        }
+                        for section in &tree.children {
+                            site_tree_ui(ui, section, section.name.clone(), &mut to_visit);
+                        }
+                    });
+            });
+        self.show_site_map = open;
+
+        if load {
+            self.load_site_map(ctx);
+        }
+        #[cfg(feature = "sdf-render")]
+        if show_oz {
+            self.show_site_map_constellation();
+        }
+        #[cfg(feature = "sdf-render")]
+        if show_orbits {
+            self.show_site_map_orbits();
+        }
+        if let Some(url) = to_visit {
+            self.url_input = url;
+            self.navigate(ctx);
+        }
+    }
+}
+
+/// One node of the site map: a link for a page, a collapsible section for
+/// a path with pages below it.
+fn site_tree_ui(ui: &mut egui::Ui, node: &SiteNode, path: String, to_visit: &mut Option<String>) {
+    if node.children.is_empty() {
+        page_link(ui, node, &node.name, to_visit);
+        return;
+    }
+    egui::CollapsingHeader::new(format!("{} ({})", node.name, node.page_count()))
+        .id_salt(("site_map", &path))
+        .show(ui, |ui| {
+            page_link(ui, node, "(index)", to_visit);
+            for child in &node.children {
+                site_tree_ui(ui, child, format!("{path}/{}", child.name), to_visit);
+            }
+        });
+}
+
+/// Link to `node`'s page, when the sitemap lists one.
+fn page_link(ui: &mut egui::Ui, node: &SiteNode, label: &str, to_visit: &mut Option<String>) {
+    if let Some(ref url) = node.url {
+        if ui.link(label).on_hover_text(url).clicked() {
+            *to_visit = Some(url.clone());
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Search suggestions in the URL bar for `BrowserApp`.
+//!
+//! While the URL-bar text reads as a search rather than an address, the
+//! search engine is asked to complete it once the text has stayed unchanged
+//! for [`SUGGEST_DEBOUNCE`] (see `alice_browser::net::search_engine`). The
+//! request runs on a worker thread; starting another one drops the receiver
+//! of the last, so a stale answer is never shown and its worker gives up
+//! on sending it. Answers are listed in the dropdown under the history
+//! matches, and picking one searches for it.
+
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use alice_browser::engine::workers::CancelToken;
+use alice_browser::net::fetch::FetchOptions;
+use alice_browser::net::scheduler::{self, Priority};
+use alice_browser::net::search_engine::is_search_query;
+use eframe::egui;
+
+use super::history::UrlSuggestion;
+use super::BrowserApp;
+
+/// How long the URL-bar text must stay unchanged before it is sent.
+pub const SUGGEST_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// The engine's completions of what is typed in the URL bar.
+#[derive(Default)]
+pub struct SearchSuggestions {
+    /// Text the completions in `items` were asked for
+    query: String,
+    items: Vec<String>,
+    /// Text waiting out the debounce, and when it was typed
+    typed: Option<(String, Instant)>,
+    /// Request in flight, and the text it was sent for
+    pending: Option<(String, mpsc::Receiver<Vec<String>>)>,
+}
+
+impl BrowserApp {
+    /// Take in an answer, and ask for completions of the URL-bar text once
+    /// it has settled. Call each frame while the dropdown is open.
+    pub(super) fn update_search_suggestions(&mut self, ctx: &egui::Context) {
+        if !self.settings.search_suggestions {
+            return;
+        }
+        let state = &mut self.search_suggestions;
+        match state.pending.as_ref().map(|(_, rx)| rx.try_recv()) {
+            Some(Ok(items)) => {
+                if let Some((query, _)) = state.pending.take() {
+                    state.query = query;
+                    state.items = items;
+                }
+            }
+            Some(Err(mpsc::TryRecvError::Disconnected)) => state.pending = None,
+            _ => {}
+        }
+
+        let text = self.url_input.trim();
+        let asked = state.pending.as_ref().map(|(q, _)| q.as_str());
+        if !is_search_query(text) || text == state.query || Some(text) == asked {
+            state.typed = None;
+            return;
+        }
+        let waited = match state.typed {
+            Some((ref typed, at)) if typed == text => at.elapsed(),
+            _ => {
+                state.typed = Some((text.to_string(), Instant::now()));
+                Duration::ZERO
+            }
+        };
+        if waited < SUGGEST_DEBOUNCE {
+            ctx.request_repaint_after(SUGGEST_DEBOUNCE - waited);
+            return;
+        }
+        state.typed = None;
+        let text = text.to_string();
+        self.request_search_suggestions(text, ctx);
+    }
+
+    /// Ask the search engine to complete `query` in the background,
+    /// superseding any request still in flight.
+    fn request_search_suggestions(&mut self, query: String, ctx: &egui::Context) {
+        let search = self.settings.web_search();
+        let Some(host_key) = search.suggest_url(&query) else {
+            return;
+        };
+        let (tx, rx) = mpsc::channel();
+        let options = FetchOptions {
+            network: self.settings.network_profile.conditions(),
+            ..FetchOptions::default()
+        };
+        let ctx = ctx.clone();
+        let asked = query.clone();
+        let token = CancelToken::new();
+        scheduler::shared().submit(&host_key, Priority::Preview, &token, move |_| {
+            let items = match search.suggestions(&asked, &options) {
+                Ok(items) => items,
+                Err(e) => {
+                    log::debug!("Search suggestions for {asked:?} failed: {e}");
+                    return;
+                }
+            };
+            if tx.send(items).is_ok() {
+                ctx.request_repaint();
+            }
+        });
+        self.search_suggestions.pending = Some((query, rx));
+    }
+
+    /// Dropdown rows for the engine's completions that still fit the
+    /// URL-bar text, at most `limit`.
+    pub(super) fn search_suggestion_rows(&self, limit: usize) -> Vec<UrlSuggestion> {
+        let state = &self.search_suggestions;
+        let text = self.url_input.trim().to_lowercase();
+        let query = state.query.to_lowercase();
+        if !self.settings.search_suggestions || state.items.is_empty() || !text.starts_with(&query)
+        {
+            return Vec::new();
+        }
+        // Typed on past the query: only completions that still match
+        let search = self.settings.web_search();
+        state
+            .items
+            .iter()
+            .filter(|item| text == query || item.to_lowercase().starts_with(&text))
+            .take(limit)
+            .map(|item| UrlSuggestion {
+                url: search.url(item),
+                title: item.clone(),
+                bookmarked: false,
+                score: 0.0,
+                search: true,
+            })
+            .collect()
+    }
+
+    /// Forget the completions and drop any request in flight.
+    pub(super) fn cancel_search_suggestions(&mut self) {
+        self.search_suggestions = SearchSuggestions::default();
+    }
+}
@@ -0,0 +1,85 @@
+//! Table exports for `BrowserApp`.
+//!
+//! Writes the tables picked from a page's "Export table as…" context menu
+//! (`ui::clipboard::ExportRequest`) to the profile's `exports` directory,
+//! named after the table's caption (or the page title) and the time, and
+//! shows where the file went for a few seconds.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+
+use crate::ui::clipboard::{self, ExportRequest};
+
+use super::history::now_secs;
+use super::BrowserApp;
+
+/// Profile subdirectory holding exported tables (and network logs).
+pub(super) const EXPORTS_DIR: &str = "exports";
+/// How long the export notice stays up.
+const NOTICE_TIME: Duration = Duration::from_secs(4);
+
+/// File name for an export: the name reduced to `[a-z0-9-]`, plus the time.
+pub(super) fn file_name(name: &str, created: u64, extension: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug.trim_matches('-');
+    let slug = if slug.is_empty() { "table" } else { slug };
+    format!("{}-{created}.{extension}", &slug[..slug.len().min(40)])
+}
+
+impl BrowserApp {
+    /// Write `request`'s table to the exports directory.
+    fn export_table(&self, request: &ExportRequest) -> std::io::Result<PathBuf> {
+        let dir = alice_browser::profile::file_path(EXPORTS_DIR)?;
+        std::fs::create_dir_all(&dir)?;
+        let name = request.table.caption.clone().unwrap_or_else(|| {
+            self.page
+                .as_ref()
+                .map(|p| p.dom.title.clone())
+                .unwrap_or_default()
+        });
+        let path = dir.join(file_name(&name, now_secs(), request.format.extension()));
+        std::fs::write(&path, request.format.render(&request.table))?;
+        Ok(path)
+    }
+
+    /// Carry out this frame's table export and show the latest result.
+    pub fn handle_table_exports(&mut self, ctx: &egui::Context) {
+        if let Some(request) = clipboard::take_export(ctx) {
+            let notice = match self.export_table(&request) {
+                Ok(path) => format!("Table saved to {}", path.display()),
+                Err(e) => format!("Table export failed: {e}"),
+            };
+            self.export_notice = Some((notice, Instant::now()));
+        }
+
+        let Some((ref notice, shown)) = self.export_notice else {
+            return;
+        };
+        let left = NOTICE_TIME.saturating_sub(shown.elapsed());
+        if left.is_zero() {
+            self.export_notice = None;
+            return;
+        }
+        egui::Area::new(egui::Id::new("table_export_notice"))
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -24.0))
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(notice);
+                });
+            });
+        ctx.request_repaint_after(left);
+    }
+}
@@ -0,0 +1,193 @@
+//! Task manager window for `BrowserApp`.
+//!
+//! The browser shows one page at a time, so the task manager has one page
+//! entry: its approximate memory by part (see
+//! [`alice_browser::engine::memory`]), the cost of the last frame and the
+//! background work still running for it (page load, image downloads, link
+//! prefetch, the OZ stream animation), plus the pages kept for going back.
+//! Discarding the page frees all of that and leaves a placeholder that
+//! reloads it on demand.
+
+use eframe::egui;
+
+use alice_browser::engine::memory::{format_bytes, HeapSize};
+
+use super::BrowserApp;
+
+impl BrowserApp {
+    /// Memory rows for the current page: (part, bytes).
+    fn page_memory_rows(&self) -> Vec<(&'static str, usize)> {
+        let mut rows = Vec::new();
+        if let Some(ref page) = self.page {
+            let mem = page.memory();
+            rows.push(("DOM", mem.dom));
+            rows.push(("Layout", mem.layout));
+            rows.push(("SDF scene", mem.scene));
+            if mem.text_view > 0 {
+                rows.push(("Text view", mem.text_view));
+            }
+        }
+        if let Some(ref elements) = self.paint_elements {
+            rows.push(("Paint list", elements.total_size()));
+        }
+        rows.push(("Decoded images", self.image_loader.decoded_bytes()));
+        let textures: usize = self
+            .image_textures
+            .values()
+            .map(|t| t.size()[0] * t.size()[1] * 4)
+            .sum();
+        rows.push(("Image textures", textures));
+        #[cfg(feature = "sdf-render")]
+        if let Some(ref scene) = self.spatial_scene {
+            rows.push(("3-D scene", scene.total_size()));
+        }
+        if !self.back_forward.is_empty() {
+            rows.push(("Back/forward cache", self.back_forward.bytes()));
+        }
+        rows
+    }
+
+    /// Background work running for the current page.
+    fn background_activity(&self) -> Vec<String> {
+        let mut tasks = Vec::new();
+        if self.loading {
+            tasks.push("Loading page".to_string());
+        }
+        let downloads = self.image_loader.pending_count();
+        if downloads > 0 {
+            tasks.push(format!("Downloading {downloads} images"));
+        }
+        if self.link_cards.is_fetching() {
+            tasks.push("Fetching link preview card".to_string());
+        }
+        #[cfg(feature = "sdf-render")]
+        {
+            if self.oz_prefetch_rx.is_some() {
+                tasks.push("Prefetching linked pages".to_string());
+            }
+            if self.oz_preview_rx.is_some() {
+                tasks.push("Fetching link preview".to_string());
+            }
+            if self.render_mode == alice_browser::render::RenderMode::OzMode
+                && self.stream_state.is_some()
+                && !self.reduce_motion
+            {
+                tasks.push("Animating OZ stream (every frame)".to_string());
+            }
+        }
+        tasks
+    }
+
+    /// Drop the current page and everything derived from it, keeping its
+    /// URL so it can be reloaded.
+    pub fn discard_page(&mut self) {
+        let Some(page) = self.page.take() else {
+            return;
+        };
+        self.discarded = Some(page.dom.url);
+        self.page_entry = None;
+        self.back_forward.clear();
+        self.reset_page_views();
+        self.image_textures.clear();
+        self.image_loader.clear();
+        #[cfg(feature = "search")]
+        {
+            self.search_index = None;
+            self.find = super::find::FindState::default();
+        }
+        #[cfg(feature = "sdf-render")]
+        {
+            self.stream_state = None;
+            self.oz_doc_map = None;
+            self.oz_prefetch_started = false;
+            self.oz_prefetch_rx = None;
+            self.oz_prefetch_buffer = Vec::new();
+            self.oz_preview = None;
+            self.oz_preview_rx = None;
+        }
+    }
+
+    /// Placeholder for a discarded page; returns `true` if it was drawn.
+    pub fn draw_discarded(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let Some(url) = self.discarded.clone() else {
+            return false;
+        };
+        ui.vertical_centered(|ui| {
+            ui.add_space(80.0);
+            ui.heading("Page discarded");
+            ui.label(format!("{url} was unloaded to free memory."));
+            ui.add_space(12.0);
+            if ui.button("Reload").clicked() {
+                self.url_input = url;
+                self.navigate_no_history(ctx);
+            }
+        });
+        true
+    }
+
+    /// Draw the task manager window.
+    pub fn draw_task_manager(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_tasks;
+        let mut discard = false;
+        egui::Window::new("Task manager")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                match (&self.page, &self.discarded) {
+                    (Some(page), _) => {
+                        let title = if page.dom.title.is_empty() {
+                            &page.dom.url
+                        } else {
+                            &page.dom.title
+                        };
+                        ui.strong(crate::ui::truncate_str(title, 48));
+                    }
+                    (None, Some(url)) => {
+                        ui.strong(format!("{} (discarded)", crate::ui::truncate_str(url, 36)));
+                    }
+                    (None, None) => {
+                        ui.strong("No page");
+                    }
+                }
+                ui.label(format!("Last frame: {:.1} ms", self.frame_ms));
+                ui.separator();
+
+                let rows = self.page_memory_rows();
+                egui::Grid::new("task_memory")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (part, bytes) in &rows {
+                            ui.label(*part);
+                            ui.monospace(format_bytes(*bytes));
+                            ui.end_row();
+                        }
+                        ui.strong("Total (approx.)");
+                        ui.strong(format_bytes(rows.iter().map(|(_, b)| b).sum()));
+                        ui.end_row();
+                    });
+
+                ui.separator();
+                let activity = self.background_activity();
+                if activity.is_empty() {
+                    ui.weak("No background activity");
+                }
+                for task in &activity {
+                    ui.label(format!("• {task}"));
+                }
+
+                ui.separator();
+                if ui
+                    .add_enabled(self.page.is_some(), egui::Button::new("Discard page"))
+                    .on_hover_text("Free the page's memory; it reloads when you return to it")
+                    .clicked()
+                {
+                    discard = true;
+                }
+            });
+        self.show_tasks = open;
+        if discard {
+            self.discard_page();
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! Page thumbnail capture for `BrowserApp`.
+//!
+//! After a page finishes loading, a small snapshot is taken — straight from
+//! the raymarch pixels in Spatial3D, otherwise via an egui screenshot of the
+//! content panel — and stored in the thumbnail cache next to its history entry.
+//!
+//! Each snapshot is also written as a PNG to the profile's `thumbnails`
+//! directory (see `alice_browser::render::thumbnail::file_name`), so the
+//! history panel and popup show pages from earlier sessions. Files are
+//! read back when a row first comes into view; the oldest are deleted
+//! beyond [`MAX_STORED_THUMBNAILS`], and with their history entries.
+
+use std::path::PathBuf;
+
+use eframe::egui;
+
+use alice_browser::render::thumbnail::{
+    self, downscale_rgba, Thumbnail, THUMB_MAX_HEIGHT, THUMB_MAX_WIDTH,
+};
+
+use super::BrowserApp;
+
+/// Directory of the stored thumbnails inside the profile directory.
+pub const THUMBNAIL_DIR: &str = "thumbnails";
+
+/// Thumbnails kept on disk; the least recently written go first.
+pub const MAX_STORED_THUMBNAILS: usize = 500;
+
+/// Size of the thumbnails in the history panel.
+const PANEL_THUMB_SIZE: egui::Vec2 = egui::vec2(48.0, 30.0);
+
+/// File holding the thumbnail of `url`, creating the directory.
+fn thumbnail_path(url: &str) -> std::io::Result<PathBuf> {
+    let dir = alice_browser::profile::file_path(THUMBNAIL_DIR)?;
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(thumbnail::file_name(url)))
+}
+
+/// Write `thumb` for `url`, then delete the oldest files beyond the limit.
+fn save_thumbnail_file(url: &str, thumb: &Thumbnail) {
+    let Some(png) = thumb.to_png() else {
+        return;
+    };
+    let result = thumbnail_path(url).and_then(|path| {
+        std::fs::write(&path, png)?;
+        let dir = path.parent().map(PathBuf::from).unwrap_or_default();
+        prune_thumbnail_files(&dir)
+    });
+    if let Err(e) = result {
+        log::warn!("Could not save the thumbnail of {url}: {e}");
+    }
+}
+
+/// Delete the least recently written thumbnails beyond
+/// [`MAX_STORED_THUMBNAILS`].
+fn prune_thumbnail_files(dir: &std::path::Path) -> std::io::Result<()> {
+    let mut files: Vec<_> = std::fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|x| x == "png"))
+        .map(|e| {
+            let written = e.metadata().and_then(|m| m.modified()).ok();
+            (written, e.path())
+        })
+        .collect();
+    if files.len() <= MAX_STORED_THUMBNAILS {
+        return Ok(());
+    }
+    files.sort_by_key(|(written, _)| std::cmp::Reverse(*written));
+    for (_, path) in files.drain(MAX_STORED_THUMBNAILS..) {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Read the stored thumbnail of `url`, if there is one.
+fn load_thumbnail_file(url: &str) -> Option<Thumbnail> {
+    let bytes = std::fs::read(thumbnail_path(url).ok()?).ok()?;
+    Thumbnail::from_png(&bytes)
+}
+
+impl BrowserApp {
+    /// Mark the current page for a thumbnail capture once it has been drawn.
+    pub fn schedule_thumbnail(&mut self, url: &str) {
+        self.thumb_capture_pending = Some(url.to_string());
+        self.thumb_screenshot_requested = false;
+    }
+
+    /// Downscale a captured RGBA frame and store it for `url`.
+    pub fn store_thumbnail(&mut self, url: &str, rgba: &[u8], width: usize, height: usize) {
+        if let Some(thumb) = downscale_rgba(rgba, width, height, THUMB_MAX_WIDTH, THUMB_MAX_HEIGHT)
+        {
+            save_thumbnail_file(url, &thumb);
+            self.thumb_missing.remove(url);
+            self.thumb_textures.remove(url);
+            if let Some(evicted) = self.thumbnails.insert(url, thumb) {
+                self.thumb_textures.remove(&evicted);
+            }
+        }
+        if self.thumb_capture_pending.as_deref() == Some(url) {
+            self.thumb_capture_pending = None;
+            self.thumb_screenshot_requested = false;
+        }
+    }
+
+    /// Request a screenshot for a pending capture, and consume the reply.
+    ///
+    /// Call once per frame after the content panel has been drawn.
+    pub fn poll_thumbnail_capture(&mut self, ctx: &egui::Context) {
+        let Some(url) = self.thumb_capture_pending.clone() else {
+            return;
+        };
+        if self.loading || self.page.is_none() {
+            return;
+        }
+
+        let screenshot = ctx.input(|i| {
+            i.raw.events.iter().find_map(|e| match e {
+                egui::Event::Screenshot { image, .. } => Some(image.clone()),
+                _ => None,
+            })
+        });
+
+        if let Some(image) = screenshot {
+            let region = self.content_rect.map_or_else(
+                || (*image).clone(),
+                |r| image.region(&r, Some(ctx.pixels_per_point())),
+            );
+            let [w, h] = region.size;
+            self.store_thumbnail(&url, region.as_raw(), w, h);
+        } else if !self.thumb_screenshot_requested {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Screenshot(egui::UserData::default()));
+            self.thumb_screenshot_requested = true;
+            ctx.request_repaint();
+        }
+    }
+
+    /// Texture for a stored thumbnail, uploaded lazily; thumbnails not in
+    /// the cache are read from disk once.
+    pub fn thumbnail_texture(
+        &mut self,
+        ctx: &egui::Context,
+        url: &str,
+    ) -> Option<egui::TextureHandle> {
+        if let Some(tex) = self.thumb_textures.get(url) {
+            return Some(tex.clone());
+        }
+        if !self.thumbnails.contains(url) {
+            if self.thumb_missing.contains(url) {
+                return None;
+            }
+            let Some(thumb) = load_thumbnail_file(url) else {
+                self.thumb_missing.insert(url.to_string());
+                return None;
+            };
+            if let Some(evicted) = self.thumbnails.insert(url, thumb) {
+                self.thumb_textures.remove(&evicted);
+            }
+        }
+        let thumb = self.thumbnails.get(url)?;
+        let image =
+            egui::ColorImage::from_rgba_unmultiplied([thumb.width, thumb.height], &thumb.rgba);
+        let tex = ctx.load_texture(format!("thumb_{url}"), image, egui::TextureOptions::LINEAR);
+        self.thumb_textures.insert(url.to_string(), tex.clone());
+        Some(tex)
+    }
+
+    /// Forget the thumbnails of `urls`, on disk too, as their history
+    /// entries are removed.
+    pub fn forget_thumbnails<'a>(&mut self, urls: impl IntoIterator<Item = &'a str>) {
+        for url in urls {
+            self.thumbnails.remove(url);
+            self.thumb_textures.remove(url);
+            self.thumb_missing.insert(url.to_string());
+            if let Ok(path) = thumbnail_path(url) {
+                // Most pages have none; a missing file is fine
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+
+    /// A history-panel thumbnail of `url` (a blank frame without one),
+    /// loaded only once the row is on screen; hovering shows it larger.
+    pub fn draw_panel_thumbnail(&mut self, ui: &mut egui::Ui, ctx: &egui::Context, url: &str) {
+        let (rect, response) = ui.allocate_exact_size(PANEL_THUMB_SIZE, egui::Sense::hover());
+        let texture = if ui.is_rect_visible(rect) {
+            self.thumbnail_texture(ctx, url)
+        } else {
+            None
+        };
+        let Some(tex) = texture else {
+            ui.painter()
+                .rect_stroke(rect, 2.0, ui.visuals().widgets.noninteractive.bg_stroke);
+            return;
+        };
+        egui::Image::new(&tex).rounding(2.0).paint_at(ui, rect);
+        response.on_hover_ui(|ui| {
+            ui.add(
+                egui::Image::new(&tex)
+                    .fit_to_exact_size(egui::vec2(THUMB_MAX_WIDTH as f32, THUMB_MAX_HEIGHT as f32)),
+            );
+        });
+    }
+
+    /// History popup: one row per entry with its thumbnail. Returns the
+    /// history index the user picked.
+    pub fn draw_history_thumbnails(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+    ) -> Option<usize> {
+        let mut picked = None;
+        let entries: Vec<(usize, String)> =
+            self.history.iter().cloned().enumerate().rev().collect();
+
+        if entries.is_empty() {
+            ui.colored_label(egui::Color32::GRAY, "No history yet");
+            return None;
+        }
+
+        egui::ScrollArea::vertical()
+            .max_height(420.0)
+            .show(ui, |ui| {
+                for (idx, url) in entries {
+                    ui.horizontal(|ui| {
+                        let size =
+                            egui::vec2(THUMB_MAX_WIDTH as f32, THUMB_MAX_HEIGHT as f32) * 0.5;
+                        if let Some(tex) = self.thumbnail_texture(ctx, &url) {
+                            ui.add(egui::Image::new(&tex).fit_to_exact_size(size));
+                        } else {
+                            let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                            ui.painter()
+                                .rect_filled(rect, 2.0, egui::Color32::from_gray(200));
+                        }
+                        let label = crate::ui::truncate_str(&url, 48);
+                        let text = if idx == self.history_idx {
+                            egui::RichText::new(label).strong()
+                        } else {
+                            egui::RichText::new(label)
+                        };
+                        if ui.selectable_label(false, text).clicked() {
+                            picked = Some(idx);
+                        }
+                    });
+                }
+            });
+
+        picked
+    }
+}
@@ -0,0 +1,184 @@
+//! Connection security for `BrowserApp`.
+//!
+//! The padlock left of the address bar shows how the current page was
+//! fetched ([`Security`]) and opens the certificate window: the server
+//! certificate's subject, issuer, validity and names, and the hosts with
+//! certificate exceptions. A page whose certificate fails verification
+//! stops at an interstitial instead of an error line; proceeding adds a
+//! temporary exception for its host (see [`alice_browser::net::tls`]) and
+//! loads it again.
+
+use eframe::egui;
+
+use alice_browser::local_time::local_date;
+use alice_browser::net::tls::{self, Security, Validity, EXCEPTION_LIFETIME};
+
+use super::history::now_secs;
+use super::BrowserApp;
+
+/// A page held back because its certificate was rejected.
+pub struct TlsInterstitial {
+    pub url: String,
+    pub host: String,
+    /// What the TLS stack said
+    pub message: String,
+}
+
+impl BrowserApp {
+    /// Security of the current page, for the padlock.
+    #[must_use]
+    pub fn page_security(&self) -> Security {
+        self.page
+            .as_ref()
+            .map_or(Security::Local, |page| Security::of(&page.dom.url))
+    }
+
+    /// Stop at the interstitial for `url`, whose certificate was rejected.
+    pub fn hold_invalid_certificate(&mut self, url: &str, message: String) {
+        let host = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_default();
+        self.tls_interstitial = Some(TlsInterstitial {
+            url: url.to_string(),
+            host,
+            message,
+        });
+    }
+
+    /// Padlock button; toggles the certificate window.
+    pub fn draw_padlock(&mut self, ui: &mut egui::Ui) {
+        let security = self.page_security();
+        if ui
+            .add(egui::Button::new(security.icon()).min_size(egui::vec2(24.0, 24.0)))
+            .on_hover_text(security.label())
+            .clicked()
+        {
+            self.show_certificate = !self.show_certificate;
+        }
+    }
+
+    /// Interstitial for a page whose certificate was rejected; returns
+    /// whether it was drawn.
+    pub fn draw_tls_interstitial(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let Some(ref held) = self.tls_interstitial else {
+            return false;
+        };
+        let mut back = false;
+        let mut proceed = false;
+        ui.vertical_centered(|ui| {
+            ui.add_space(48.0);
+            ui.heading("\u{26A0} This connection is not private");
+            ui.add_space(8.0);
+            ui.label(format!(
+                "The certificate of {} could not be verified. Someone may be \
+                 impersonating the site, or it may be misconfigured.",
+                held.host
+            ));
+            ui.add_space(4.0);
+            ui.weak(&held.message);
+            ui.add_space(16.0);
+            back = ui.button("Go back").clicked();
+            ui.add_space(8.0);
+            proceed = ui
+                .small_button(format!(
+                    "Proceed to {} anyway (for {} min)",
+                    held.host,
+                    EXCEPTION_LIFETIME.as_secs() / 60
+                ))
+                .on_hover_text("Skip certificate checks for this host until the exception runs out")
+                .clicked();
+        });
+
+        if back {
+            self.tls_interstitial = None;
+            self.error = None;
+            self.go_back(ctx);
+        } else if proceed {
+            if let Some(held) = self.tls_interstitial.take() {
+                tls::exceptions().allow(&held.host);
+                self.url_input = held.url;
+                self.navigate_no_history(ctx);
+            }
+        }
+        true
+    }
+
+    /// Draw the certificate window.
+    pub fn draw_certificate(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_certificate;
+        let security = self.page_security();
+        let mut revoke: Option<String> = None;
+
+        egui::Window::new("Connection")
+            .open(&mut open)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                ui.label(format!("{} {}", security.icon(), security.label()));
+                if let Some(cert) = self.page.as_ref().and_then(|p| p.certificate.as_ref()) {
+                    ui.separator();
+                    let now = i64::try_from(now_secs()).unwrap_or(i64::MAX);
+                    let validity = match cert.validity_at(now) {
+                        Validity::NotYetValid => "not yet valid".to_string(),
+                        Validity::Valid { days_left } => format!("{days_left} days left"),
+                        Validity::Expired => "expired".to_string(),
+                    };
+                    egui::Grid::new("certificate")
+                        .num_columns(2)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("Issued to");
+                            ui.label(&cert.subject);
+                            ui.end_row();
+                            ui.label("Issued by");
+                            ui.label(&cert.issuer);
+                            ui.end_row();
+                            ui.label("Valid");
+                            ui.label(format!(
+                                "{} to {} ({validity})",
+                                date(cert.not_before),
+                                date(cert.not_after)
+                            ));
+                            ui.end_row();
+                            ui.label("Serial");
+                            ui.monospace(&cert.serial);
+                            ui.end_row();
+                        });
+                    ui.label(format!("Names ({})", cert.san.len()));
+                    egui::ScrollArea::vertical()
+                        .max_height(160.0)
+                        .show(ui, |ui| {
+                            for name in &cert.san {
+                                ui.monospace(name);
+                            }
+                        });
+                }
+
+                let exceptions = tls::exceptions().list();
+                if !exceptions.is_empty() {
+                    ui.separator();
+                    ui.label("Certificate exceptions");
+                    for (host, left) in exceptions {
+                        ui.horizontal(|ui| {
+                            ui.monospace(&host);
+                            ui.weak(format!("{} min left", left.as_secs().div_ceil(60)));
+                            if ui.small_button("Revoke").clicked() {
+                                revoke = Some(host.clone());
+                            }
+                        });
+                    }
+                }
+            });
+        self.show_certificate = open;
+
+        if let Some(host) = revoke {
+            tls::exceptions().revoke(&host);
+        }
+    }
+}
+
+/// Local `YYYY-MM-DD` of a Unix time.
+fn date(secs: i64) -> String {
+    let (year, month, day) = local_date(u64::try_from(secs).unwrap_or(0));
+    format!("{year:04}-{month:02}-{day:02}")
+}
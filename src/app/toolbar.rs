@@ -1,14 +1,143 @@
 //! Toolbar rendering for `BrowserApp`.
 //!
-//! Draws the address bar, back/forward buttons, render-mode selector,
-//! dark-mode toggle, and the optional in-page search field.
+//! Draws the address bar with its autocomplete dropdown, back/forward/reload/home buttons,
+//! padlock and ad-block shield, history popup, bookmark star, render-mode selector, panel toggles,
+//! dark-mode and reduced-motion toggles, the settings button, and the optional in-page search
+//! field. Narrow windows (or `--mobile`) get the mobile layout instead: a status bar, a bottom
+//! bar with thumb-sized buttons that hides while the page is scrolled down, and a floating
+//! block-count chip.
 
+use alice_browser::engine::pipeline::step_zoom;
+use alice_browser::mobile::ui::{is_compact, MobileAction, BOTTOM_BAR_HEIGHT, STATUS_BAR_HEIGHT};
+use alice_browser::net::tls::Security;
 use alice_browser::render::RenderMode;
 use eframe::egui;
 
+use super::history::UrlSuggestion;
 use super::BrowserApp;
 
+/// Rows shown in the URL-bar dropdown.
+const MAX_URL_SUGGESTIONS: usize = 8;
+
+/// Rows of the URL-bar dropdown kept for search suggestions when there
+/// are any.
+const SEARCH_SUGGESTION_ROWS: usize = 4;
+
 impl BrowserApp {
+    /// Whether the window gets the mobile layout: it is narrow, or the
+    /// browser was started with `--mobile`.
+    pub fn mobile_layout(&self, ctx: &egui::Context) -> bool {
+        self.force_mobile || is_compact(ctx.screen_rect().width())
+    }
+
+    /// Draw the browser chrome the window's width calls for: the toolbar
+    /// strip, or the mobile bars, menu and block-count chip.
+    pub fn draw_chrome(&mut self, ctx: &egui::Context) {
+        if !self.mobile_layout(ctx) {
+            egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
+                self.draw_toolbar(ui, ctx);
+            });
+            return;
+        }
+
+        self.sync_mobile_ui(ctx);
+        let mut action = MobileAction::None;
+        if self.mobile_ui.status_bar_visible {
+            egui::TopBottomPanel::top("mobile_status")
+                .exact_height(STATUS_BAR_HEIGHT)
+                .show(ctx, |ui| {
+                    self.mobile_ui.render_status_bar(ui);
+                });
+        }
+        if self.mobile_ui.bottom_bar_visible {
+            egui::TopBottomPanel::bottom("mobile_bar")
+                .exact_height(BOTTOM_BAR_HEIGHT)
+                .show(ctx, |ui| {
+                    action = self.mobile_ui.render_bottom_bar(ui);
+                });
+        }
+        if self.mobile_ui.menu_open {
+            let menu = egui::Area::new(egui::Id::new("mobile_menu"))
+                .order(egui::Order::Foreground)
+                .anchor(
+                    egui::Align2::RIGHT_BOTTOM,
+                    egui::vec2(-4.0, -BOTTOM_BAR_HEIGHT - 4.0),
+                )
+                .show(ctx, |ui| self.mobile_ui.render_menu(ui));
+            // The tap that opened the menu lands outside it
+            if menu.response.clicked_elsewhere() && !matches!(action, MobileAction::ToggleMenu) {
+                self.mobile_ui.menu_open = false;
+            }
+            if !matches!(menu.inner, MobileAction::None) {
+                action = menu.inner;
+            }
+        }
+        let chip = self.mobile_ui.render_block_chip(ctx);
+        if !matches!(chip, MobileAction::None) {
+            action = chip;
+        }
+        self.apply_mobile_action(ctx, action);
+    }
+
+    /// Bring the mobile bars up to date with the page, and let this frame's
+    /// wheel or trackpad scrolling hide or show them. Touch drags reach
+    /// them through the gesture recognizer.
+    fn sync_mobile_ui(&mut self, ctx: &egui::Context) {
+        let screen = ctx.screen_rect();
+        let is_secure = self.page_security() == Security::Secure;
+        let ui = &mut self.mobile_ui;
+        ui.gestures.set_screen_size(screen.width(), screen.height());
+        ui.can_go_back = self.history_idx > 0;
+        ui.can_go_forward = self.history_idx + 1 < self.history.len();
+        ui.is_secure = is_secure;
+        if !ui.url_editing {
+            ui.current_url.clone_from(&self.url_input);
+        }
+        if let Some(ref page) = self.page {
+            ui.record_page(
+                &page.dom.url,
+                page.filter_stats.ad_nodes,
+                page.filter_stats.tracker_nodes,
+            );
+        }
+        let dy = ctx.input(|i| i.smooth_scroll_delta.y);
+        ui.on_scroll(-dy);
+    }
+
+    fn apply_mobile_action(&mut self, ctx: &egui::Context, action: MobileAction) {
+        match action {
+            MobileAction::Navigate(url) => {
+                self.url_input = url;
+                self.navigate_typed(ctx);
+            }
+            MobileAction::GoBack => self.go_back(ctx),
+            MobileAction::GoForward => self.go_forward(ctx),
+            MobileAction::Refresh => self.reload(ctx),
+            MobileAction::ZoomIn => self.set_page_zoom(step_zoom(self.page_zoom, 1)),
+            MobileAction::ZoomOut => self.set_page_zoom(step_zoom(self.page_zoom, -1)),
+            MobileAction::ZoomReset => self.set_page_zoom(1.0),
+            MobileAction::ToggleReaderMode => {
+                self.render_mode = if self.render_mode == RenderMode::Reader {
+                    RenderMode::Flat
+                } else {
+                    RenderMode::Reader
+                };
+                self.render_mode_changed();
+            }
+            MobileAction::ToggleDarkMode => self.dark_mode = !self.dark_mode,
+            // The menu holds the block statistics
+            MobileAction::ShowBlockStats => {
+                self.mobile_ui.set_fullscreen(false);
+                self.mobile_ui.menu_open = true;
+            }
+            MobileAction::OpenSettings => self.show_settings = true,
+            MobileAction::ToggleFullscreen
+            | MobileAction::ShowLinkPreview(..)
+            | MobileAction::ToggleMenu
+            | MobileAction::None => {}
+        }
+    }
+
     /// Render the top toolbar strip.
     pub fn draw_toolbar(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
         ui.horizontal(|ui| {
@@ -35,22 +164,151 @@ impl BrowserApp {
             {
                 self.go_forward(ctx);
             }
+            // Reload turns into Stop while a page loads
+            if self.loading {
+                if ui
+                    .add(egui::Button::new("\u{2715}").min_size(egui::vec2(28.0, 24.0)))
+                    .on_hover_text("Stop loading")
+                    .clicked()
+                {
+                    self.stop_loading();
+                }
+            } else if ui
+                .add(egui::Button::new("\u{27F3}").min_size(egui::vec2(28.0, 24.0)))
+                .on_hover_text("Reload, keeping your place on the page")
+                .clicked()
+            {
+                self.reload(ctx);
+            }
+            if ui
+                .add_enabled(
+                    !self.settings.homepage.trim().is_empty(),
+                    egui::Button::new("\u{2302}").min_size(egui::vec2(28.0, 24.0)),
+                )
+                .on_hover_text("Home page")
+                .clicked()
+            {
+                self.go_home(ctx);
+            }
 
-            // URL bar
+            // History popup with page thumbnails
+            ui.menu_button("\u{1F552}", |ui| {
+                if let Some(idx) = self.draw_history_thumbnails(ui, ctx) {
+                    self.go_to_history(idx, ctx);
+                    ui.close_menu();
+                }
+            });
+
+            // URL bar. Arrow keys move through the suggestions, so take them
+            // before the text field sees them.
+            let url_id = egui::Id::new("url_bar");
+            if ctx.memory(|m| m.has_focus(url_id)) {
+                self.intercept_url_paste(ctx);
+            }
+            if self.url_suggest_open && ctx.memory(|m| m.has_focus(url_id)) {
+                let (down, up) = ctx.input_mut(|i| {
+                    (
+                        i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                        i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                    )
+                });
+                self.url_suggest_selected = match (self.url_suggest_selected, down, up) {
+                    (None, true, _) => Some(0),
+                    (Some(i), true, _) => Some(i + 1),
+                    (Some(0), _, true) => None,
+                    (Some(i), _, true) => Some(i - 1),
+                    (sel, _, _) => sel,
+                };
+            }
+            self.draw_padlock(ui);
+            self.draw_adblock_toggle(ui, ctx);
             let response = ui.add_sized(
-                [ui.available_width() - 240.0, 24.0],
+                [ui.available_width() - 390.0, 24.0],
                 egui::TextEdit::singleline(&mut self.url_input)
+                    .id(url_id)
                     .hint_text("Enter URL...")
                     .font(egui::TextStyle::Monospace),
             );
+            if response.changed() {
+                self.url_suggest_open = !self.url_input.is_empty();
+                self.url_suggest_selected = None;
+            }
+
+            let suggestions = if self.url_suggest_open {
+                self.update_search_suggestions(ctx);
+                let searches = self.search_suggestion_rows(SEARCH_SUGGESTION_ROWS);
+                let mut rows = self.url_suggestions(MAX_URL_SUGGESTIONS - searches.len());
+                rows.extend(searches);
+                rows
+            } else {
+                Vec::new()
+            };
+            if let Some(sel) = self.url_suggest_selected {
+                self.url_suggest_selected =
+                    suggestions.len().checked_sub(1).map(|last| sel.min(last));
+            }
 
-            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                self.navigate(ctx);
+            if self.paste_choice.is_some() {
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    self.accept_paste_choice(ctx);
+                } else {
+                    self.draw_paste_chooser(ctx, response.rect);
+                }
+            } else if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                if let Some(s) = self.url_suggest_selected.and_then(|i| suggestions.get(i)) {
+                    self.url_input = s.url.clone();
+                }
+                self.close_url_suggestions();
+                self.navigate_typed(ctx);
+            } else if self.url_suggest_open {
+                if let Some(url) = self.draw_url_suggestions(ctx, response.rect, &suggestions) {
+                    self.url_input = url;
+                    self.navigate_typed(ctx);
+                }
             }
 
             if ui.button("Go").clicked() {
-                self.navigate(ctx);
+                self.close_url_suggestions();
+                self.navigate_typed(ctx);
+            }
+
+            // Addresses queued by pasting several at once
+            if let Some(next) = self.url_queue.front() {
+                let hover = format!("Open {next}");
+                if ui
+                    .button(format!("Next \u{25B8} ({})", self.url_queue.len()))
+                    .on_hover_text(hover)
+                    .clicked()
+                {
+                    self.open_next_queued(ctx);
+                }
+                if ui
+                    .small_button("\u{2715}")
+                    .on_hover_text("Forget the queued addresses")
+                    .clicked()
+                {
+                    self.url_queue.clear();
+                }
+            }
+
+            // Bookmark star for the current page
+            let starred = self.is_bookmarked();
+            if ui
+                .add_enabled(
+                    !self.url_input.is_empty(),
+                    egui::Button::new(if starred { "\u{2605}" } else { "\u{2606}" }),
+                )
+                .on_hover_text(if starred {
+                    "Remove bookmark"
+                } else {
+                    "Bookmark this page"
+                })
+                .clicked()
+            {
+                self.toggle_bookmark();
             }
+            ui.toggle_value(&mut self.show_bookmarks, "Bookmarks");
+            ui.toggle_value(&mut self.show_history_panel, "History");
 
             // Render mode selector
             let prev_mode = self.render_mode;
@@ -60,23 +318,40 @@ impl BrowserApp {
                     RenderMode::Sdf2D => "SDF",
                     RenderMode::Spatial3D => "3D",
                     RenderMode::OzMode => "OZ",
+                    RenderMode::Reader => "Reader",
                 })
                 .show_ui(ui, |ui| {
                     ui.selectable_value(&mut self.render_mode, RenderMode::Flat, "2D Flat");
                     ui.selectable_value(&mut self.render_mode, RenderMode::Sdf2D, "SDF 2D");
                     ui.selectable_value(&mut self.render_mode, RenderMode::Spatial3D, "3D Spatial");
                     ui.selectable_value(&mut self.render_mode, RenderMode::OzMode, "OZ Orbital");
+                    ui.selectable_value(&mut self.render_mode, RenderMode::Reader, "Reader");
                 });
 
-            // Invalidate spatial scene when switching render modes
-            #[cfg(feature = "sdf-render")]
             if self.render_mode != prev_mode {
-                self.spatial_scene = None;
-                self.stream_state = None;
-                self.cam_dirty = true;
-                self.oz_prefetch_started = false;
-                self.oz_prefetch_rx = None;
-                self.oz_prefetch_buffer.clear();
+                self.render_mode_changed();
+            }
+
+            #[cfg(feature = "voice-web")]
+            if self.render_mode == RenderMode::Reader {
+                self.draw_read_aloud_controls(ui);
+            }
+
+            // Reminder that fetches run under simulated conditions
+            let network = self.settings.network_profile;
+            if network.conditions().is_active() {
+                ui.colored_label(egui::Color32::from_rgb(255, 160, 0), network.label())
+                    .on_hover_text("Simulated network conditions; change in alice://settings");
+            }
+
+            // Page zoom indicator; click to reset
+            if (self.page_zoom - 1.0).abs() > f32::EPSILON
+                && ui
+                    .button(format!("{:.0}%", self.page_zoom * 100.0))
+                    .on_hover_text("Page zoom (Cmd+Plus/Minus); click or Cmd+0 to reset")
+                    .clicked()
+            {
+                self.set_page_zoom(1.0);
             }
 
             ui.toggle_value(&mut self.show_stats, "Stats");
@@ -91,28 +366,66 @@ impl BrowserApp {
                 self.dark_mode = !self.dark_mode;
             }
 
+            // Reduced-motion accessibility toggle
+            if ui
+                .toggle_value(&mut self.reduce_motion, "Still")
+                .on_hover_text("Reduce motion: freeze particle flow, fades and progressive renders")
+                .changed()
+            {
+                self.apply_reduce_motion(ctx);
+            }
+            ui.toggle_value(&mut self.show_settings, "\u{2699}")
+                .on_hover_text("Settings (Cmd+,)");
+
             // Page search (feature-gated)
             #[cfg(feature = "search")]
             if self.search_index.is_some() {
                 ui.separator();
-                ui.add_sized(
+                let find_box = ui.add_sized(
                     [120.0, 24.0],
                     egui::TextEdit::singleline(&mut self.search_query)
                         .hint_text("Find...")
                         .font(egui::TextStyle::Monospace),
                 );
+                self.update_find();
+                // Enter / Shift+Enter step through matches, keeping focus
+                if find_box.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    if ui.input(|i| i.modifiers.shift) {
+                        self.find_previous();
+                    } else {
+                        self.find_next();
+                    }
+                    find_box.request_focus();
+                }
                 if !self.search_query.is_empty() {
-                    if let Some(ref idx) = self.search_index {
-                        let count = idx.count(&self.search_query);
-                        ui.colored_label(
-                            if count > 0 {
-                                egui::Color32::from_rgb(0, 180, 0)
-                            } else {
-                                egui::Color32::from_rgb(255, 80, 80)
-                            },
-                            format!("{}", count),
-                        );
+                    let count = self.find.matches.len();
+                    let has_matches = count > 0;
+                    if ui
+                        .add_enabled(has_matches, egui::Button::new("\u{25B2}"))
+                        .on_hover_text("Previous match (Shift+Enter)")
+                        .clicked()
+                    {
+                        self.find_previous();
+                    }
+                    if ui
+                        .add_enabled(has_matches, egui::Button::new("\u{25BC}"))
+                        .on_hover_text("Next match (Enter)")
+                        .clicked()
+                    {
+                        self.find_next();
                     }
+                    ui.colored_label(
+                        if has_matches {
+                            egui::Color32::from_rgb(0, 180, 0)
+                        } else {
+                            egui::Color32::from_rgb(255, 80, 80)
+                        },
+                        if has_matches {
+                            format!("{}/{count}", self.find.current + 1)
+                        } else {
+                            "0".to_string()
+                        },
+                    );
                 }
             }
 
@@ -120,4 +433,73 @@ impl BrowserApp {
             let _ = prev_mode;
         });
     }
+
+    fn close_url_suggestions(&mut self) {
+        self.url_suggest_open = false;
+        self.url_suggest_selected = None;
+        self.cancel_search_suggestions();
+    }
+
+    /// Suggestion dropdown under the URL bar. Returns the URL the user picked.
+    fn draw_url_suggestions(
+        &mut self,
+        ctx: &egui::Context,
+        url_rect: egui::Rect,
+        suggestions: &[UrlSuggestion],
+    ) -> Option<String> {
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.close_url_suggestions();
+            return None;
+        }
+        // Left open while empty: search suggestions may still arrive
+        if suggestions.is_empty() {
+            return None;
+        }
+
+        let mut picked = None;
+        let area = egui::Area::new(egui::Id::new("url_suggestions"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(url_rect.left_bottom())
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(url_rect.width());
+                    for (i, s) in suggestions.iter().enumerate() {
+                        let text = if s.search {
+                            format!("\u{1F50D} {}", crate::ui::truncate_str(&s.title, 100))
+                        } else {
+                            format!(
+                                "{} {}  —  {}",
+                                if s.bookmarked {
+                                    "\u{2605}"
+                                } else {
+                                    "\u{1F552}"
+                                },
+                                crate::ui::truncate_str(&s.title, 40),
+                                crate::ui::truncate_str(&s.url, 60)
+                            )
+                        };
+                        let selected = self.url_suggest_selected == Some(i);
+                        let row = ui.selectable_label(selected, text);
+                        if selected {
+                            row.scroll_to_me(None);
+                        }
+                        if row.clicked() {
+                            picked = Some(s.url.clone());
+                        }
+                    }
+                });
+            });
+
+        // Close on a click anywhere outside the bar and the dropdown
+        let clicked_outside = ctx.input(|i| {
+            i.pointer.any_pressed()
+                && i.pointer
+                    .interact_pos()
+                    .is_some_and(|p| !area.response.rect.contains(p) && !url_rect.contains(p))
+        });
+        if picked.is_some() || clicked_outside {
+            self.close_url_suggestions();
+        }
+        picked
+    }
 }
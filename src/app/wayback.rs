@@ -0,0 +1,127 @@
+//! Wayback fallback for `BrowserApp`.
+//!
+//! When a page that was visited before comes back 404 or 410, or its host
+//! no longer resolves, the navigation error path asks the Internet Archive
+//! for its latest snapshot in the background. If one exists, a bar above
+//! the error (or the 404 page) offers to open it; the copy loads through
+//! [`BrowserEngine::load_archived`], with its assets pointed into the
+//! archive. Off with the "Offer archived copies" setting.
+
+use eframe::egui;
+use std::sync::mpsc;
+
+use alice_browser::engine::pipeline::BrowserEngine;
+use alice_browser::net::fetch::FetchOptions;
+use alice_browser::net::scheduler::{self, Priority};
+use alice_browser::net::wayback::{self, LinkRot, Snapshot};
+
+use super::BrowserApp;
+
+/// Where the archive lookup for a dead page stands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveLookup {
+    Looking,
+    Found(Snapshot),
+    /// No snapshot, or the archive could not be reached
+    Missing,
+}
+
+/// The archived copy offered for the page that just failed.
+pub struct WaybackOffer {
+    pub rot: LinkRot,
+    pub lookup: ArchiveLookup,
+    rx: Option<mpsc::Receiver<Option<Snapshot>>>,
+}
+
+impl BrowserApp {
+    /// Fallback stage of the navigation error path: if the page at the URL
+    /// bar failed with `rot` and was visited before, look for an archived
+    /// copy in the background. Call before the visit is recorded.
+    pub fn offer_wayback(&mut self, rot: Option<LinkRot>, ctx: &egui::Context) {
+        let url = self.url_input.clone();
+        let Some(rot) = rot else {
+            return;
+        };
+        let visited = self.visits.entries.iter().any(|e| e.url == url);
+        if !self.settings.wayback_fallback || !visited || wayback::is_archive_url(&url) {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let ctx = ctx.clone();
+        let host_key = wayback::AVAILABILITY_API;
+        scheduler::shared().submit(host_key, Priority::Preview, &self.page_work, move |_| {
+            let snapshot =
+                wayback::find_snapshot(&url, &FetchOptions::default()).unwrap_or_else(|e| {
+                    log::warn!("Wayback lookup failed: {e}");
+                    None
+                });
+            let _ = tx.send(snapshot);
+            ctx.request_repaint();
+        });
+        self.wayback = Some(WaybackOffer {
+            rot,
+            lookup: ArchiveLookup::Looking,
+            rx: Some(rx),
+        });
+    }
+
+    /// Bar offering the archived copy of the page that just failed; nothing
+    /// when there is no offer or no snapshot.
+    pub fn draw_wayback_offer(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let Some(offer) = self.wayback.as_mut() else {
+            return;
+        };
+        if let Some(rx) = &offer.rx {
+            if let Ok(snapshot) = rx.try_recv() {
+                offer.lookup = snapshot.map_or(ArchiveLookup::Missing, ArchiveLookup::Found);
+                offer.rx = None;
+            }
+        }
+
+        let mut open = None;
+        match offer.lookup {
+            ArchiveLookup::Looking => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.weak(format!(
+                        "{} — looking for an archived copy…",
+                        offer.rot.label()
+                    ));
+                });
+            }
+            ArchiveLookup::Found(ref snapshot) => {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}.", offer.rot.label()));
+                    if ui
+                        .button(format!("Open archived copy ({})", snapshot.date()))
+                        .on_hover_text(&snapshot.url)
+                        .clicked()
+                    {
+                        open = Some(snapshot.clone());
+                    }
+                });
+            }
+            ArchiveLookup::Missing => return,
+        }
+        ui.separator();
+
+        if let Some(snapshot) = open {
+            self.open_snapshot(snapshot, ctx);
+        }
+    }
+
+    /// Load an archived copy under its archive address, as a new history
+    /// entry.
+    fn open_snapshot(&mut self, snapshot: Snapshot, ctx: &egui::Context) {
+        self.url_input.clone_from(&snapshot.url);
+        self.history.truncate(self.history_idx + 1);
+        self.back_forward.truncate(self.history_idx + 1);
+        self.history.push(snapshot.url.clone());
+        self.history_idx = self.history.len() - 1;
+        let entry = Some(self.history_idx);
+        self.start_load_for(ctx, entry, move |engine: &BrowserEngine| {
+            engine.load_archived(&snapshot)
+        });
+    }
+}
@@ -0,0 +1,421 @@
+//! Spatial audio cues for Spatial3D and OZ interactions.
+//!
+//! Short procedurally-synthesised cues, panned by where their source sits
+//! relative to the camera:
+//!
+//! - **Whoosh**: soft filtered noise while the corridor scrolls
+//! - **Ping**: directional chime when a grabbed particle's preview is ready
+//! - **Click**: tactile blip when a link portal is selected
+//! - **Tick**: faint tick where an OZ particle respawns with new text
+//! - **Tone**: short note when an OZ particle is grabbed, pitched by its
+//!   category so sections of a page sound apart
+//!
+//! In OZ mode the whoosh also follows the flow: the faster the wall sweeps
+//! past while the view is dragged, the louder ([`flow_intensity`]).
+//!
+//! Synthesis and panning are pure functions; playback goes through `rodio`.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Output sample rate for all cues (Hz).
+pub const SAMPLE_RATE: u32 = 44_100;
+
+/// Minimum gap between two whoosh cues, so continuous scrolling does not stack them.
+const WHOOSH_MIN_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Minimum gap between two spawn ticks; a frame can respawn many particles.
+const TICK_MIN_INTERVAL: Duration = Duration::from_millis(90);
+
+/// Apparent wall speed (rad/s) below which the flow is silent, above the
+/// layers' own drift.
+const FLOW_QUIET: f32 = 0.6;
+/// Apparent wall speed (rad/s) of the loudest flow whoosh.
+const FLOW_LOUD: f32 = 4.0;
+
+/// Distance attenuation factor (gain = 1 / (1 + d * ROLLOFF)).
+const ROLLOFF: f32 = 0.08;
+
+/// User-facing audio settings.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    /// Silence every cue regardless of volume.
+    pub master_muted: bool,
+    /// Master volume (0.0–1.0).
+    pub volume: f32,
+    /// Spawn ticks and flow whooshes in OZ mode.
+    pub oz_ambient: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_muted: false,
+            volume: 0.6,
+            oz_ambient: true,
+        }
+    }
+}
+
+impl AudioSettings {
+    /// Effective linear gain after mute and clamping.
+    #[must_use]
+    pub fn gain(&self) -> f32 {
+        if self.master_muted {
+            0.0
+        } else {
+            self.volume.clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Kind of interaction cue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CueKind {
+    /// Corridor scroll / dolly
+    Whoosh,
+    /// Preview finished loading
+    Ping,
+    /// Portal (link) selection
+    Click,
+    /// OZ particle respawn
+    Tick,
+    /// OZ particle grabbed; the category index picks the pitch
+    Tone(usize),
+}
+
+/// Pitch (Hz) of the grab tone for `category`: a major pentatonic scale
+/// from A4, an octave up every five categories, wrapping after three.
+#[must_use]
+pub fn category_pitch(category: usize) -> f32 {
+    const STEPS: [i32; 5] = [0, 2, 4, 7, 9];
+    let step = category % 15;
+    let semitones = STEPS[step % 5] + 12 * (step / 5) as i32;
+    440.0 * (semitones as f32 / 12.0).exp2()
+}
+
+/// Whoosh intensity (0.0–1.0) for the wall sweeping past at
+/// `angular_speed` rad/s relative to the camera.
+#[must_use]
+pub fn flow_intensity(angular_speed: f32) -> f32 {
+    ((angular_speed.abs() - FLOW_QUIET) / (FLOW_LOUD - FLOW_QUIET)).clamp(0.0, 1.0)
+}
+
+/// Listener pose derived from the active camera.
+#[derive(Debug, Clone, Copy)]
+pub struct Listener {
+    /// World-space ear position
+    pub position: [f32; 3],
+    /// Unit vector pointing to the listener's right ear
+    pub right: [f32; 3],
+}
+
+impl Listener {
+    /// OZ rotunda listener: standing at the origin, looking along `azimuth`.
+    #[must_use]
+    pub fn rotunda(azimuth: f32) -> Self {
+        Self {
+            position: [0.0, 0.0, 0.0],
+            right: [azimuth.cos(), 0.0, azimuth.sin()],
+        }
+    }
+
+    /// Spatial3D orbit listener, matching the raymarcher's eye placement.
+    #[must_use]
+    pub fn orbit(target: [f32; 3], azimuth: f32, elevation: f32, distance: f32) -> Self {
+        let (sin_az, cos_az) = azimuth.sin_cos();
+        let (sin_el, cos_el) = elevation.sin_cos();
+        Self {
+            position: [
+                (distance * sin_az).mul_add(cos_el, target[0]),
+                distance.mul_add(sin_el, target[1]),
+                (distance * cos_az).mul_add(cos_el, target[2]),
+            ],
+            right: [cos_az, 0.0, -sin_az],
+        }
+    }
+
+    /// Equal-power stereo gains `(left, right)` for a source at `source`.
+    #[must_use]
+    pub fn stereo_gains(&self, source: [f32; 3]) -> (f32, f32) {
+        let dx = source[0] - self.position[0];
+        let dy = source[1] - self.position[1];
+        let dz = source[2] - self.position[2];
+        let dist = dz.mul_add(dz, dx.mul_add(dx, dy * dy)).sqrt();
+
+        let pan = if dist > 1e-4 {
+            (dz.mul_add(self.right[2], dx.mul_add(self.right[0], dy * self.right[1])) / dist)
+                .clamp(-1.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let attenuation = 1.0 / dist.mul_add(ROLLOFF, 1.0);
+        let theta = (pan + 1.0) * std::f32::consts::FRAC_PI_4;
+        (theta.cos() * attenuation, theta.sin() * attenuation)
+    }
+}
+
+/// Synthesise a mono cue at [`SAMPLE_RATE`].
+#[must_use]
+pub fn synth_cue(kind: CueKind) -> Vec<f32> {
+    let rate = SAMPLE_RATE as f32;
+    match kind {
+        CueKind::Whoosh => {
+            // Low-passed noise with a rise-and-fall envelope
+            let n = (rate * 0.35) as usize;
+            let mut seed: u32 = 0x9E37_79B9;
+            let mut lp = 0.0f32;
+            (0..n)
+                .map(|i| {
+                    seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                    let white = (seed >> 8) as f32 / 8_388_608.0 - 1.0;
+                    lp = (white - lp).mul_add(0.06, lp);
+                    let t = i as f32 / n as f32;
+                    let env = (t * std::f32::consts::PI).sin();
+                    lp * env * 0.8
+                })
+                .collect()
+        }
+        CueKind::Ping => {
+            let n = (rate * 0.4) as usize;
+            (0..n)
+                .map(|i| {
+                    let t = i as f32 / rate;
+                    let tone = (t * 1320.0 * std::f32::consts::TAU).sin();
+                    let overtone = (t * 2640.0 * std::f32::consts::TAU).sin() * 0.25;
+                    (tone + overtone) * (-t * 9.0).exp() * 0.5
+                })
+                .collect()
+        }
+        CueKind::Click => {
+            let n = (rate * 0.02) as usize;
+            (0..n)
+                .map(|i| {
+                    let t = i as f32 / rate;
+                    (t * 2000.0 * std::f32::consts::TAU).sin() * (-t * 250.0).exp() * 0.7
+                })
+                .collect()
+        }
+        CueKind::Tick => {
+            let n = (rate * 0.008) as usize;
+            (0..n)
+                .map(|i| {
+                    let t = i as f32 / rate;
+                    (t * 3200.0 * std::f32::consts::TAU).sin() * (-t * 600.0).exp() * 0.25
+                })
+                .collect()
+        }
+        CueKind::Tone(category) => {
+            let n = (rate * 0.25) as usize;
+            let freq = category_pitch(category);
+            (0..n)
+                .map(|i| {
+                    let t = i as f32 / rate;
+                    // 5 ms attack, then an exponential tail
+                    let attack = (t / 0.005).min(1.0);
+                    (t * freq * std::f32::consts::TAU).sin() * attack * (-t * 14.0).exp() * 0.45
+                })
+                .collect()
+        }
+    }
+}
+
+/// Spread a mono buffer to interleaved stereo with the given gains.
+#[must_use]
+pub fn spatialize(mono: &[f32], gains: (f32, f32), master: f32) -> Vec<f32> {
+    let (gl, gr) = (gains.0 * master, gains.1 * master);
+    let mut out = Vec::with_capacity(mono.len() * 2);
+    for &s in mono {
+        out.push(s * gl);
+        out.push(s * gr);
+    }
+    out
+}
+
+/// Cue player holding the audio output device.
+pub struct AudioCues {
+    pub settings: AudioSettings,
+    output: Option<(rodio::OutputStream, rodio::OutputStreamHandle)>,
+    last_whoosh: Option<Instant>,
+    last_tick: Option<Instant>,
+}
+
+impl Default for AudioCues {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioCues {
+    /// Open the default output device. Cues are silently dropped if none exists.
+    #[must_use]
+    pub fn new() -> Self {
+        let output = match rodio::OutputStream::try_default() {
+            Ok(pair) => Some(pair),
+            Err(e) => {
+                log::warn!("Spatial audio disabled: {e}");
+                None
+            }
+        };
+        Self {
+            settings: AudioSettings::default(),
+            output,
+            last_whoosh: None,
+            last_tick: None,
+        }
+    }
+
+    /// Whether an output device is available.
+    #[must_use]
+    pub const fn is_available(&self) -> bool {
+        self.output.is_some()
+    }
+
+    /// Play a cue positioned at `source` relative to `listener`.
+    pub fn play(&self, kind: CueKind, source: [f32; 3], listener: &Listener) {
+        self.play_with_gains(kind, listener.stereo_gains(source));
+    }
+
+    /// Play a centred whoosh scaled by `intensity` (0.0–1.0), rate-limited.
+    pub fn whoosh(&mut self, intensity: f32) {
+        let now = Instant::now();
+        if self
+            .last_whoosh
+            .is_some_and(|t| now.duration_since(t) < WHOOSH_MIN_INTERVAL)
+        {
+            return;
+        }
+        self.last_whoosh = Some(now);
+        let g = intensity.clamp(0.0, 1.0) * std::f32::consts::FRAC_1_SQRT_2;
+        self.play_with_gains(CueKind::Whoosh, (g, g));
+    }
+
+    /// Whoosh for the OZ wall sweeping past at `angular_speed` rad/s;
+    /// silent for the wall's own drift.
+    pub fn flow(&mut self, angular_speed: f32) {
+        let intensity = flow_intensity(angular_speed);
+        if self.settings.oz_ambient && intensity > 0.0 {
+            self.whoosh(intensity);
+        }
+    }
+
+    /// Tick for a particle respawning at `source`, rate-limited.
+    pub fn tick(&mut self, source: [f32; 3], listener: &Listener) {
+        if !self.settings.oz_ambient {
+            return;
+        }
+        let now = Instant::now();
+        if self
+            .last_tick
+            .is_some_and(|t| now.duration_since(t) < TICK_MIN_INTERVAL)
+        {
+            return;
+        }
+        self.last_tick = Some(now);
+        self.play(CueKind::Tick, source, listener);
+    }
+
+    fn play_with_gains(&self, kind: CueKind, gains: (f32, f32)) {
+        let master = self.settings.gain();
+        if master <= 0.0 {
+            return;
+        }
+        let Some((_, ref handle)) = self.output else {
+            return;
+        };
+        let samples = spatialize(&synth_cue(kind), gains, master);
+        let source = rodio::buffer::SamplesBuffer::new(2, SAMPLE_RATE, samples);
+        if let Err(e) = handle.play_raw(source) {
+            log::debug!("Audio cue dropped: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn muted_gain_is_zero() {
+        let s = AudioSettings {
+            master_muted: true,
+            volume: 1.0,
+            ..AudioSettings::default()
+        };
+        assert!(s.gain().abs() < 1e-6);
+        assert!((AudioSettings::default().gain() - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn source_on_right_pans_right() {
+        let l = Listener::rotunda(0.0);
+        let (left, right) = l.stereo_gains([5.0, 0.0, 0.0]);
+        assert!(right > left);
+        let (left, right) = l.stereo_gains([-5.0, 0.0, 0.0]);
+        assert!(left > right);
+    }
+
+    #[test]
+    fn centred_source_is_balanced() {
+        let l = Listener::rotunda(0.0);
+        let (left, right) = l.stereo_gains([0.0, 0.0, 5.0]);
+        assert!((left - right).abs() < 1e-4);
+    }
+
+    #[test]
+    fn distance_attenuates() {
+        let l = Listener::rotunda(0.0);
+        let near = l.stereo_gains([0.0, 0.0, 1.0]);
+        let far = l.stereo_gains([0.0, 0.0, 40.0]);
+        assert!(near.0 > far.0);
+    }
+
+    #[test]
+    fn orbit_right_vector_matches_azimuth() {
+        let l = Listener::orbit([0.0, 0.0, 0.0], 0.0, 0.0, 3.0);
+        assert!((l.position[2] - 3.0).abs() < 1e-5);
+        assert!((l.right[0] - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn cues_are_bounded() {
+        for kind in [
+            CueKind::Whoosh,
+            CueKind::Ping,
+            CueKind::Click,
+            CueKind::Tick,
+            CueKind::Tone(14),
+        ] {
+            let buf = synth_cue(kind);
+            assert!(!buf.is_empty());
+            assert!(buf.iter().all(|s| s.abs() <= 1.0));
+        }
+    }
+
+    #[test]
+    fn category_tones_rise_and_wrap() {
+        assert!((category_pitch(0) - 440.0).abs() < 1e-3);
+        assert!((category_pitch(5) - 880.0).abs() < 1e-2);
+        let pitches: Vec<f32> = (0..15).map(category_pitch).collect();
+        assert!(pitches.windows(2).all(|w| w[1] > w[0]));
+        assert!((category_pitch(15) - category_pitch(0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn layer_drift_is_silent() {
+        assert!(flow_intensity(0.35).abs() < 1e-6);
+        assert!(flow_intensity(-2.0) > 0.0);
+        assert!((flow_intensity(10.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn spatialize_interleaves() {
+        let out = spatialize(&[1.0, 0.5], (0.2, 0.8), 1.0);
+        assert_eq!(out.len(), 4);
+        assert!((out[0] - 0.2).abs() < 1e-6);
+        assert!((out[1] - 0.8).abs() < 1e-6);
+    }
+}
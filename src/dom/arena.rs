@@ -0,0 +1,434 @@
+//! Arena-backed DOM.
+//!
+//! [`DomNode`] owns its children in a `Vec` and its attributes in a
+//! `HashMap`. A [`DomArena`] keeps the nodes of a document in one `Vec`,
+//! linked by [`NodeId`] indices (parent, first and last child, next
+//! sibling), with attributes in a small vector instead of a map: no child
+//! vector and no hash table per node. Each node still owns its tag, text
+//! and attribute strings. [`parse_arena`](crate::dom::parser::parse_arena)
+//! builds one straight from the HTML.
+//!
+//! Only the SIMD pipeline ([`BrowserEngine::process_html_simd`]) runs on an
+//! arena, from parse to layout, converting it once for the page's `dom`.
+//! Page loads ([`BrowserEngine::load_page`]) still parse, filter and lay
+//! out `DomNode` trees. [`DomArena::to_tree`] and [`DomArena::from_tree`]
+//! convert both ways.
+//!
+//! [`BrowserEngine::process_html_simd`]: crate::engine::pipeline::BrowserEngine::process_html_simd
+//! [`BrowserEngine::load_page`]: crate::engine::pipeline::BrowserEngine::load_page
+
+use std::collections::HashMap;
+
+use crate::dom::filter::is_removed_class;
+use crate::dom::{Classification, DomNode, DomTree, NodeType};
+
+/// Index of a node in its [`DomArena`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    /// Position of the node in the arena's storage.
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// One node, linked to its relatives by index.
+#[derive(Debug, Clone)]
+pub struct ArenaNode {
+    pub tag: String,
+    /// In source order
+    pub attributes: Vec<(String, String)>,
+    pub text: String,
+    pub node_type: NodeType,
+    pub classification: Classification,
+    pub parent: Option<NodeId>,
+    pub first_child: Option<NodeId>,
+    pub last_child: Option<NodeId>,
+    pub next_sibling: Option<NodeId>,
+}
+
+impl ArenaNode {
+    fn new(node_type: NodeType, tag: String, attributes: Vec<(String, String)>) -> Self {
+        Self {
+            tag,
+            attributes,
+            text: String::new(),
+            node_type,
+            classification: match node_type {
+                NodeType::Text => Classification::Content,
+                _ => Classification::Unknown,
+            },
+            parent: None,
+            first_child: None,
+            last_child: None,
+            next_sibling: None,
+        }
+    }
+
+    /// Element with `tag` and `attributes`, not yet attached.
+    #[must_use]
+    pub fn element(tag: impl Into<String>, attributes: Vec<(String, String)>) -> Self {
+        Self::new(NodeType::Element, tag.into(), attributes)
+    }
+
+    /// Text node, not yet attached.
+    #[must_use]
+    pub fn text(content: impl Into<String>) -> Self {
+        let mut node = Self::new(NodeType::Text, String::new(), Vec::new());
+        node.text = content.into();
+        node
+    }
+
+    #[must_use]
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A document's nodes in one vector. The root is always the first node.
+#[derive(Debug, Clone)]
+pub struct DomArena {
+    nodes: Vec<ArenaNode>,
+    pub url: String,
+    pub title: String,
+}
+
+impl DomArena {
+    /// Arena holding only `root`.
+    #[must_use]
+    pub fn new(root: ArenaNode, url: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            nodes: vec![root],
+            url: url.into(),
+            title: title.into(),
+        }
+    }
+
+    /// Arena with room for `capacity` nodes, holding only `root`.
+    #[must_use]
+    pub fn with_capacity(
+        capacity: usize,
+        root: ArenaNode,
+        url: impl Into<String>,
+        title: impl Into<String>,
+    ) -> Self {
+        let mut arena = Self::new(root, url, title);
+        arena.nodes.reserve(capacity.saturating_sub(1));
+        arena
+    }
+
+    #[must_use]
+    pub const fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    #[must_use]
+    pub fn node(&self, id: NodeId) -> &ArenaNode {
+        &self.nodes[id.index()]
+    }
+
+    pub fn node_mut(&mut self, id: NodeId) -> &mut ArenaNode {
+        &mut self.nodes[id.index()]
+    }
+
+    /// Nodes stored, including any detached by [`retain`](Self::retain).
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Nodes there is room for without reallocating.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.nodes.capacity()
+    }
+
+    /// Give back storage reserved for nodes that were never added.
+    pub fn shrink_to_fit(&mut self) {
+        self.nodes.shrink_to_fit();
+    }
+
+    /// The storage, for sizing.
+    #[must_use]
+    pub fn nodes(&self) -> &[ArenaNode] {
+        &self.nodes
+    }
+
+    /// Add `node` as the last child of `parent`.
+    pub fn append(&mut self, parent: NodeId, mut node: ArenaNode) -> NodeId {
+        let id = NodeId(self.nodes.len() as u32);
+        node.parent = Some(parent);
+        node.first_child = None;
+        node.last_child = None;
+        node.next_sibling = None;
+        self.nodes.push(node);
+        match self.nodes[parent.index()].last_child {
+            Some(last) => self.nodes[last.index()].next_sibling = Some(id),
+            None => self.nodes[parent.index()].first_child = Some(id),
+        }
+        self.nodes[parent.index()].last_child = Some(id);
+        id
+    }
+
+    /// Children of `id`, in order.
+    pub fn children(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::successors(self.node(id).first_child, |c| self.node(*c).next_sibling)
+    }
+
+    /// `id` and every node below it, parents before their children.
+    #[must_use]
+    pub fn descendants(&self, id: NodeId) -> Vec<NodeId> {
+        let mut out = Vec::new();
+        let mut stack = vec![id];
+        while let Some(n) = stack.pop() {
+            out.push(n);
+            let start = stack.len();
+            stack.extend(self.children(n));
+            // Visit the first child next
+            stack[start..].reverse();
+        }
+        out
+    }
+
+    /// Number of nodes in `id`'s subtree, itself included.
+    #[must_use]
+    pub fn node_count(&self, id: NodeId) -> usize {
+        self.descendants(id).len()
+    }
+
+    /// Text of `id`'s subtree, runs joined by spaces, as
+    /// [`DomNode::collect_text`].
+    #[must_use]
+    pub fn collect_text(&self, id: NodeId) -> String {
+        let mut buf = String::new();
+        for n in self.descendants(id) {
+            let text = &self.node(n).text;
+            if !text.is_empty() {
+                if !buf.is_empty() {
+                    buf.push(' ');
+                }
+                buf.push_str(text.trim());
+            }
+        }
+        buf
+    }
+
+    /// Text-to-markup density of `id`'s subtree, as
+    /// [`DomNode::text_density`].
+    #[must_use]
+    pub fn text_density(&self, id: NodeId) -> f32 {
+        self.collect_text(id).len() as f32 / self.node_count(id) as f32
+    }
+
+    /// Share of `id`'s text in its `<a>` children, as
+    /// [`DomNode::link_density`].
+    #[must_use]
+    pub fn link_density(&self, id: NodeId) -> f32 {
+        let total_text = self.collect_text(id).len() as f32;
+        if total_text == 0.0 {
+            return 0.0;
+        }
+        let link_text: usize = self
+            .children(id)
+            .filter(|&c| self.node(c).tag == "a")
+            .map(|c| self.collect_text(c).len())
+            .sum();
+        link_text as f32 / total_text
+    }
+
+    /// Detach the subtrees of the nodes `keep` rejects from their parents.
+    /// The root is always kept; detached nodes stay in storage, unreachable,
+    /// until the arena is rebuilt.
+    pub fn retain<F>(&mut self, keep: F)
+    where
+        F: Fn(&ArenaNode) -> bool,
+    {
+        let mut stack = vec![self.root()];
+        while let Some(parent) = stack.pop() {
+            let mut prev: Option<NodeId> = None;
+            let mut child = self.node(parent).first_child;
+            while let Some(c) = child {
+                let next = self.node(c).next_sibling;
+                if keep(self.node(c)) {
+                    prev = Some(c);
+                    stack.push(c);
+                } else {
+                    match prev {
+                        Some(p) => self.node_mut(p).next_sibling = next,
+                        None => self.node_mut(parent).first_child = next,
+                    }
+                    let detached = self.node_mut(c);
+                    detached.parent = None;
+                    detached.next_sibling = None;
+                }
+                child = next;
+            }
+            self.node_mut(parent).last_child = prev;
+        }
+    }
+
+    /// Detach the ad and tracker subtrees, as
+    /// [`filter::prune`](crate::dom::filter::prune) does for a `DomTree`.
+    pub fn prune(&mut self) {
+        self.retain(|n| !is_removed_class(n.classification));
+    }
+
+    /// Build an arena from a `DomTree`.
+    #[must_use]
+    pub fn from_tree(tree: &DomTree) -> Self {
+        let mut arena = Self::with_capacity(
+            tree.root.node_count(),
+            owned_node(&tree.root),
+            tree.url.clone(),
+            tree.title.clone(),
+        );
+        let mut stack: Vec<(NodeId, &DomNode)> = vec![(arena.root(), &tree.root)];
+        while let Some((id, node)) = stack.pop() {
+            for child in &node.children {
+                let child_id = arena.append(id, owned_node(child));
+                stack.push((child_id, child));
+            }
+        }
+        arena
+    }
+
+    /// The document as a `DomTree`, for code that walks owned nodes.
+    #[must_use]
+    pub fn to_tree(&self) -> DomTree {
+        DomTree {
+            root: self.to_node(self.root()),
+            url: self.url.clone(),
+            title: self.title.clone(),
+        }
+    }
+
+    /// `id`'s subtree as an owned `DomNode`.
+    #[must_use]
+    pub fn to_node(&self, id: NodeId) -> DomNode {
+        let node = self.node(id);
+        DomNode {
+            tag: node.tag.clone(),
+            attributes: node.attributes.iter().cloned().collect::<HashMap<_, _>>(),
+            text: node.text.clone(),
+            children: self.children(id).map(|c| self.to_node(c)).collect(),
+            node_type: node.node_type,
+            classification: node.classification,
+        }
+    }
+}
+
+fn owned_node(node: &DomNode) -> ArenaNode {
+    let mut attributes: Vec<_> = node
+        .attributes
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    attributes.sort();
+    let mut out = ArenaNode::new(node.node_type, node.tag.clone(), attributes);
+    out.text.clone_from(&node.text);
+    out.classification = node.classification;
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::diff::diff;
+    use crate::dom::parser::{parse_arena, parse_html};
+
+    const PAGE: &str = "<html><head><title> Arena </title></head><body>\
+        <h1 id=\"t\">Title</h1><p>One <a href=\"/x\">link</a> two</p>\
+        <div class=\"ad-banner\"><p>Buy</p></div><script>x()</script></body></html>";
+
+    #[test]
+    fn parses_like_the_owned_tree() {
+        let owned = parse_html(PAGE, "https://example.com/");
+        let arena = parse_arena(PAGE, "https://example.com/");
+        assert_eq!(arena.title, "Arena");
+        assert_eq!(arena.node_count(arena.root()), owned.root.node_count());
+        assert_eq!(arena.collect_text(arena.root()), owned.root.collect_text());
+        assert!(diff(&owned.root, &arena.to_tree().root).is_empty());
+
+        // And back again
+        let round = DomArena::from_tree(&owned);
+        assert!(diff(&owned.root, &round.to_tree().root).is_empty());
+        let h1 = round
+            .descendants(round.root())
+            .into_iter()
+            .find(|&n| round.node(n).tag == "h1")
+            .unwrap();
+        assert_eq!(round.node(h1).attr("id"), Some("t"));
+        assert_eq!(round.collect_text(h1), "Title");
+    }
+
+    #[test]
+    fn descendants_are_in_document_order() {
+        let arena = parse_arena("<p>a</p><p>b</p><p>c</p>", "https://example.com/");
+        let texts: Vec<&str> = arena
+            .descendants(arena.root())
+            .into_iter()
+            .map(|n| arena.node(n).text.as_str())
+            .filter(|t| !t.is_empty())
+            .collect();
+        assert_eq!(texts, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn pruning_detaches_removed_subtrees() {
+        let mut arena = parse_arena(PAGE, "https://example.com/");
+        let ad = arena
+            .descendants(arena.root())
+            .into_iter()
+            .find(|&n| arena.node(n).attr("class") == Some("ad-banner"))
+            .unwrap();
+        arena.node_mut(ad).classification = Classification::Advertisement;
+        let before = arena.node_count(arena.root());
+        arena.prune();
+        assert_eq!(arena.node_count(arena.root()), before - 3);
+        assert!(!arena.collect_text(arena.root()).contains("Buy"));
+        assert!(arena.node(ad).parent.is_none());
+        // Storage is untouched
+        assert_eq!(arena.len(), before);
+    }
+
+    #[test]
+    fn simd_passes_match_the_owned_tree() {
+        use crate::dom::readability::{readability_boost, readability_boost_arena};
+        use crate::simd::classify::{classify_arena, classify_tree, prune_ads};
+        use crate::simd::layout::{flatten_arena, flatten_dom};
+
+        let mut owned = parse_html(PAGE, "https://example.com/");
+        let owned_stats = classify_tree(&mut owned.root);
+        prune_ads(&mut owned.root);
+        readability_boost(&mut owned.root);
+
+        let mut arena = parse_arena(PAGE, "https://example.com/");
+        let stats = classify_arena(&mut arena);
+        arena.prune();
+        readability_boost_arena(&mut arena);
+
+        assert_eq!(stats.removed_nodes, owned_stats.removed_nodes);
+        assert!(diff(&owned.root, &arena.to_tree().root).is_empty());
+
+        let (mut flat, mut owned_flat) = (Vec::new(), Vec::new());
+        flatten_arena(&arena, arena.root(), 0, &mut flat);
+        flatten_dom(&owned.root, 0, &mut owned_flat);
+        let tags = |nodes: &[crate::simd::layout::FlatNode]| {
+            nodes
+                .iter()
+                .map(|n| (n.tag.clone(), n.depth, n.classification))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(tags(&flat), tags(&owned_flat));
+    }
+}
@@ -0,0 +1,287 @@
+//! Form autofill: what a field asks for, and profiles to answer with.
+//!
+//! [`classify_field`] reads a control's `autocomplete` attribute (the
+//! WHATWG autofill field names) and, when there is none, guesses from its
+//! `name`, `id` and placeholder. An [`AutofillProfile`] holds the values —
+//! name, email, phone, postal address — to fill such fields with.
+
+use serde::{Deserialize, Serialize};
+
+use super::form::{ControlKind, FormControl, InputKind};
+
+/// What a form field asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldKind {
+    /// Full name
+    Name,
+    GivenName,
+    FamilyName,
+    Email,
+    Tel,
+    Organization,
+    /// Street and number, possibly several lines
+    StreetAddress,
+    City,
+    /// State, province or prefecture
+    Region,
+    PostalCode,
+    Country,
+}
+
+impl FieldKind {
+    /// Kind named by an `autocomplete` field name, e.g. `"given-name"`.
+    #[must_use]
+    pub fn from_autocomplete(token: &str) -> Option<Self> {
+        Some(match token {
+            "name" => Self::Name,
+            "given-name" => Self::GivenName,
+            "family-name" => Self::FamilyName,
+            "email" => Self::Email,
+            "tel" | "tel-national" => Self::Tel,
+            "organization" => Self::Organization,
+            "street-address" | "address-line1" => Self::StreetAddress,
+            "address-level2" => Self::City,
+            "address-level1" => Self::Region,
+            "postal-code" => Self::PostalCode,
+            "country" | "country-name" => Self::Country,
+            _ => return None,
+        })
+    }
+}
+
+/// Name-fragment heuristics, checked in order: more specific fragments
+/// first, so `first_name` is a given name and `email_address` an email.
+const HEURISTICS: &[(&[&str], FieldKind)] = &[
+    (&["email", "e-mail", "mail"], FieldKind::Email),
+    (
+        &["firstname", "first_name", "first-name", "fname", "given"],
+        FieldKind::GivenName,
+    ),
+    (
+        &[
+            "lastname",
+            "last_name",
+            "last-name",
+            "lname",
+            "surname",
+            "family",
+        ],
+        FieldKind::FamilyName,
+    ),
+    (&["phone", "tel", "mobile"], FieldKind::Tel),
+    (
+        &["company", "organization", "organisation"],
+        FieldKind::Organization,
+    ),
+    (&["zip", "postal", "postcode"], FieldKind::PostalCode),
+    (&["city", "town", "locality"], FieldKind::City),
+    (
+        &["state", "province", "region", "prefecture"],
+        FieldKind::Region,
+    ),
+    (&["country"], FieldKind::Country),
+    (&["address", "street", "addr"], FieldKind::StreetAddress),
+    (
+        &[
+            "fullname",
+            "full_name",
+            "full-name",
+            "yourname",
+            "your-name",
+        ],
+        FieldKind::Name,
+    ),
+];
+
+/// What `control` asks for, if it is a field autofill can answer.
+///
+/// The `autocomplete` attribute wins: `off` or an unknown field name means
+/// no autofill. Otherwise the control's name, id and placeholder are
+/// matched against common fragments (`email`, `zip`, `first_name`, …).
+#[must_use]
+pub fn classify_field(control: &FormControl) -> Option<FieldKind> {
+    match control.kind {
+        ControlKind::Input(InputKind::Text | InputKind::Email)
+        | ControlKind::TextArea { .. }
+        | ControlKind::Select { .. } => {}
+        _ => return None,
+    }
+
+    let autocomplete = control.autocomplete.trim().to_ascii_lowercase();
+    if !autocomplete.is_empty() {
+        // "section-x shipping street-address": the field name comes last
+        return autocomplete
+            .split_ascii_whitespace()
+            .last()
+            .and_then(FieldKind::from_autocomplete);
+    }
+    if control.kind == ControlKind::Input(InputKind::Email) {
+        return Some(FieldKind::Email);
+    }
+
+    let hints = [&control.name, &control.id, &control.placeholder];
+    for hint in hints {
+        let hint = hint.to_ascii_lowercase();
+        if hint.is_empty() || hint.contains("user") || hint.contains("search") {
+            continue;
+        }
+        if let Some(&(_, kind)) = HEURISTICS
+            .iter()
+            .find(|(fragments, _)| fragments.iter().any(|f| hint.contains(f)))
+        {
+            return Some(kind);
+        }
+        if hint == "name" {
+            return Some(FieldKind::Name);
+        }
+    }
+    None
+}
+
+/// A named set of values to fill forms with.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutofillProfile {
+    /// What the profile is called in menus ("Home", "Work")
+    pub label: String,
+    /// Full name; given and family names are split from it
+    pub name: String,
+    pub email: String,
+    pub tel: String,
+    pub organization: String,
+    pub street_address: String,
+    pub city: String,
+    pub region: String,
+    pub postal_code: String,
+    pub country: String,
+}
+
+impl AutofillProfile {
+    /// Value for a field of `kind`; `None` if the profile leaves it empty.
+    #[must_use]
+    pub fn value(&self, kind: FieldKind) -> Option<String> {
+        let name = self.name.trim();
+        let value = match kind {
+            FieldKind::Name => name,
+            // "Ada King Lovelace": given "Ada King", family "Lovelace"
+            FieldKind::GivenName => name.rsplit_once(' ').map_or(name, |(given, _)| given),
+            FieldKind::FamilyName => name.rsplit_once(' ').map_or("", |(_, family)| family),
+            FieldKind::Email => &self.email,
+            FieldKind::Tel => &self.tel,
+            FieldKind::Organization => &self.organization,
+            FieldKind::StreetAddress => &self.street_address,
+            FieldKind::City => &self.city,
+            FieldKind::Region => &self.region,
+            FieldKind::PostalCode => &self.postal_code,
+            FieldKind::Country => &self.country,
+        };
+        let value = value.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    }
+
+    /// Name to show in menus: the label, else the full name, else the email.
+    #[must_use]
+    pub fn display_label(&self) -> &str {
+        [&self.label, &self.name, &self.email]
+            .into_iter()
+            .map(|s| s.trim())
+            .find(|s| !s.is_empty())
+            .unwrap_or("Unnamed profile")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+
+    fn controls(html: &str) -> Vec<FormControl> {
+        fn walk(node: &crate::dom::DomNode, out: &mut Vec<FormControl>) {
+            out.extend(FormControl::from_node(node));
+            for child in &node.children {
+                walk(child, out);
+            }
+        }
+        let dom = parse_html(html, "https://example.com/");
+        let mut out = Vec::new();
+        walk(&dom.root, &mut out);
+        out
+    }
+
+    #[test]
+    fn autocomplete_attribute_wins() {
+        let found: Vec<Option<FieldKind>> = controls(
+            r#"<input name="q1" autocomplete="given-name">
+               <input name="email" autocomplete="section-a shipping postal-code">
+               <input name="email" autocomplete="off">
+               <textarea autocomplete="street-address"></textarea>
+               <input type="password" autocomplete="current-password">"#,
+        )
+        .iter()
+        .map(classify_field)
+        .collect();
+        assert_eq!(
+            found,
+            [
+                Some(FieldKind::GivenName),
+                Some(FieldKind::PostalCode),
+                None,
+                Some(FieldKind::StreetAddress),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn heuristics_read_name_id_and_placeholder() {
+        let found: Vec<Option<FieldKind>> = controls(
+            r#"<form><input name="first_name"><input id="billing-zip">
+               <input placeholder="Email address"><input type="email" name="x">
+               <input name="name"><input name="username"><input name="q">
+               <select name="country"><option>Japan</option></select>
+               <input type="submit" name="address"></form>"#,
+        )
+        .iter()
+        .map(classify_field)
+        .collect();
+        assert_eq!(
+            found,
+            [
+                None, // the <form>
+                Some(FieldKind::GivenName),
+                Some(FieldKind::PostalCode),
+                Some(FieldKind::Email),
+                Some(FieldKind::Email),
+                Some(FieldKind::Name),
+                None,
+                None,
+                Some(FieldKind::Country),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn profile_values_split_the_name() {
+        let profile = AutofillProfile {
+            name: " Ada King Lovelace ".to_string(),
+            email: "ada@example.com".to_string(),
+            ..AutofillProfile::default()
+        };
+        assert_eq!(
+            profile.value(FieldKind::GivenName).as_deref(),
+            Some("Ada King")
+        );
+        assert_eq!(
+            profile.value(FieldKind::FamilyName).as_deref(),
+            Some("Lovelace")
+        );
+        assert_eq!(profile.value(FieldKind::City), None);
+        assert_eq!(profile.display_label(), "Ada King Lovelace");
+        let single = AutofillProfile {
+            name: "Plato".to_string(),
+            ..AutofillProfile::default()
+        };
+        assert_eq!(single.value(FieldKind::FamilyName), None);
+    }
+}
@@ -0,0 +1,231 @@
+//! DOM diff for soft reloads.
+//!
+//! Reloading a page the browser already shows compares the new DOM against
+//! the old one with [`diff`]: nodes are matched by position, and a node whose
+//! tag, type or number of children changed is reported as replaced, its
+//! subtree compared no further. [`patch_layout`] then updates the page's
+//! existing layout tree from the new one in place: nodes that still line up
+//! keep their place and only take the new text, links and geometry, while
+//! subtrees that no longer match are swapped wholesale. The views keep their
+//! scroll position and the images they already loaded.
+
+use crate::dom::DomNode;
+use crate::render::layout::LayoutNode;
+
+/// One difference between two DOM trees. Paths are child indices from the
+/// root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// A text node's text
+    Text { path: Vec<usize>, text: String },
+    /// An element's attributes
+    Attributes { path: Vec<usize> },
+    /// The subtree no longer lines up: different tag, node type or number
+    /// of children
+    Replaced { path: Vec<usize> },
+}
+
+/// Differences between two DOM trees, in document order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DomDiff {
+    pub changes: Vec<Change>,
+}
+
+impl DomDiff {
+    /// Whether the trees are the same.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Whether any subtree was replaced, rather than only edited in place.
+    #[must_use]
+    pub fn is_structural(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|c| matches!(c, Change::Replaced { .. }))
+    }
+}
+
+/// Compare `new` against `old`.
+#[must_use]
+pub fn diff(old: &DomNode, new: &DomNode) -> DomDiff {
+    let mut out = DomDiff::default();
+    diff_node(old, new, &mut Vec::new(), &mut out.changes);
+    out
+}
+
+fn diff_node(old: &DomNode, new: &DomNode, path: &mut Vec<usize>, out: &mut Vec<Change>) {
+    if old.tag != new.tag
+        || old.node_type != new.node_type
+        || old.children.len() != new.children.len()
+    {
+        out.push(Change::Replaced { path: path.clone() });
+        return;
+    }
+    if old.text != new.text {
+        out.push(Change::Text {
+            path: path.clone(),
+            text: new.text.clone(),
+        });
+    }
+    if old.attributes != new.attributes {
+        out.push(Change::Attributes { path: path.clone() });
+    }
+    for (i, (o, n)) in old.children.iter().zip(&new.children).enumerate() {
+        path.push(i);
+        diff_node(o, n, path, out);
+        path.pop();
+    }
+}
+
+/// What [`patch_layout`] did to the layout tree, in nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LayoutPatch {
+    /// Nodes left as they were
+    pub kept: usize,
+    /// Nodes kept in place with new text, links, geometry or controls
+    pub updated: usize,
+    /// Nodes of subtrees swapped for the new layout's
+    pub replaced: usize,
+}
+
+/// Update `old` in place to match `new`, a layout of the reloaded page.
+///
+/// Where a node's tag and number of children match, the node is kept and
+/// takes `new`'s fields; elsewhere the subtree is replaced. Afterwards `old`
+/// equals `new` field for field.
+pub fn patch_layout(old: &mut LayoutNode, new: LayoutNode) -> LayoutPatch {
+    let mut patch = LayoutPatch::default();
+    patch_node(old, new, &mut patch);
+    patch
+}
+
+fn patch_node(old: &mut LayoutNode, new: LayoutNode, patch: &mut LayoutPatch) {
+    if old.tag != new.tag || old.children.len() != new.children.len() {
+        patch.replaced += layout_count(&new);
+        *old = new;
+        return;
+    }
+    let changed = old.bounds != new.bounds
+        || old.text != new.text
+        || old.href != new.href
+        || old.control != new.control
+        || old.table != new.table
+        || old.media != new.media
+        || old.lines != new.lines
+        || old.classification != new.classification
+        || old.selector != new.selector
+        || old.id != new.id
+        || old.is_block != new.is_block
+        || (old.font_size - new.font_size).abs() > f32::EPSILON;
+    if changed {
+        patch.updated += 1;
+    } else {
+        patch.kept += 1;
+    }
+
+    let LayoutNode {
+        text,
+        classification,
+        selector,
+        id,
+        bounds,
+        children,
+        is_block,
+        font_size,
+        href,
+        control,
+        table,
+        media,
+        lazy,
+        lines,
+        ..
+    } = new;
+    old.text = text;
+    old.classification = classification;
+    old.selector = selector;
+    old.id = id;
+    old.bounds = bounds;
+    old.is_block = is_block;
+    old.font_size = font_size;
+    old.href = href;
+    old.control = control;
+    old.table = table;
+    old.media = media;
+    old.lazy = lazy;
+    old.lines = lines;
+    for (o, n) in old.children.iter_mut().zip(children) {
+        patch_node(o, n, patch);
+    }
+}
+
+fn layout_count(node: &LayoutNode) -> usize {
+    1 + node.children.iter().map(layout_count).sum::<usize>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::layout::compute_layout;
+    use std::collections::HashMap;
+
+    fn page(items: &[&str], heading: &str) -> DomNode {
+        let list = items
+            .iter()
+            .map(|t| DomNode::element("li", HashMap::new(), vec![DomNode::text(*t)]))
+            .collect();
+        DomNode::document(vec![DomNode::element(
+            "body",
+            HashMap::new(),
+            vec![
+                DomNode::element("h1", HashMap::new(), vec![DomNode::text(heading)]),
+                DomNode::element("ul", HashMap::new(), list),
+            ],
+        )])
+    }
+
+    #[test]
+    fn text_edits_and_replaced_subtrees() {
+        let old = page(&["one", "two"], "News");
+        assert!(diff(&old, &old).is_empty());
+
+        let edited = page(&["one", "2"], "News");
+        let changes = diff(&old, &edited);
+        assert!(!changes.is_structural());
+        assert_eq!(
+            changes.changes,
+            [Change::Text {
+                path: vec![0, 1, 1, 0],
+                text: "2".into()
+            }]
+        );
+
+        let grown = page(&["one", "two", "three"], "News");
+        let changes = diff(&old, &grown);
+        assert!(changes.is_structural());
+        assert_eq!(changes.changes, [Change::Replaced { path: vec![0, 1] }]);
+    }
+
+    #[test]
+    fn layout_patches_in_place_where_structure_matches() {
+        let mut layout = compute_layout(&page(&["one", "two"], "News"), 800.0);
+        let unchanged = compute_layout(&page(&["one", "two"], "News"), 800.0);
+        let patch = patch_layout(&mut layout, unchanged);
+        assert_eq!(patch.updated + patch.replaced, 0);
+
+        // The list keeps its node; only its items' text is new
+        let list = |l: &LayoutNode| std::ptr::from_ref(&l.children[0].children[1]);
+        let before = list(&layout);
+        let edited = compute_layout(&page(&["one", "2"], "Headlines"), 800.0);
+        let patch = patch_layout(&mut layout, edited.clone());
+        assert!(patch.updated >= 2 && patch.replaced == 0);
+        assert_eq!(list(&layout), before);
+        assert_eq!(format!("{layout:?}"), format!("{edited:?}"));
+
+        let grown = compute_layout(&page(&["one", "2", "three"], "Headlines"), 800.0);
+        let patch = patch_layout(&mut layout, grown.clone());
+        assert!(patch.replaced > 0);
+        assert_eq!(format!("{layout:?}"), format!("{grown:?}"));
+    }
+}
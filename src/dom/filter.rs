@@ -1,13 +1,32 @@
-use crate::dom::{Classification, DomNode, DomTree};
+use std::collections::BTreeSet;
+use std::sync::Arc;
 
-#[cfg(not(feature = "ml-filter"))]
-use crate::dom::NodeType;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::dom::model::ClassifierModel;
+use crate::dom::{Classification, DomNode, DomTree, NodeType};
 
 #[cfg(feature = "ml-filter")]
 #[path = "ml_classifier.rs"]
 mod ml_classifier;
 
+/// Why the filter removed a subtree.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RemovalReason {
+    /// A heuristic rule, e.g. `tag:script` or `ad-pattern:banner`
+    Rule { rule: String },
+    /// A learned classifier's winning output
+    Classifier { model: String, score: f32 },
+}
+
+/// Subtrees with at least this many nodes have their children classified
+/// and pruned in parallel; smaller ones aren't worth a task.
+pub const PARALLEL_GRAIN: usize = 512;
+
 /// Statistics from the semantic filtering pass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
 pub struct FilterStats {
     pub total_nodes: usize,
     pub content_nodes: usize,
@@ -17,8 +36,49 @@ pub struct FilterStats {
     pub removed_nodes: usize,
 }
 
+impl FilterStats {
+    /// Count one node of class `class`.
+    pub(crate) fn count(&mut self, class: Classification) {
+        self.total_nodes += 1;
+        if let Some(n) = self.class_count(class) {
+            *n += 1;
+        }
+    }
+
+    /// Move one counted node from class `from` to class `to`.
+    fn reclassify(&mut self, from: Classification, to: Classification) {
+        if let Some(n) = self.class_count(from) {
+            *n = n.saturating_sub(1);
+        }
+        if let Some(n) = self.class_count(to) {
+            *n += 1;
+        }
+    }
+
+    fn class_count(&mut self, class: Classification) -> Option<&mut usize> {
+        match class {
+            Classification::Content => Some(&mut self.content_nodes),
+            Classification::Advertisement => Some(&mut self.ad_nodes),
+            Classification::Tracker => Some(&mut self.tracker_nodes),
+            Classification::Navigation => Some(&mut self.nav_nodes),
+            _ => None,
+        }
+    }
+}
+
+impl std::ops::AddAssign for FilterStats {
+    fn add_assign(&mut self, other: Self) {
+        self.total_nodes += other.total_nodes;
+        self.content_nodes += other.content_nodes;
+        self.ad_nodes += other.ad_nodes;
+        self.tracker_nodes += other.tracker_nodes;
+        self.nav_nodes += other.nav_nodes;
+        self.removed_nodes += other.removed_nodes;
+    }
+}
+
 /// Known advertising patterns in class names and IDs
-const AD_PATTERNS: &[&str] = &[
+pub(crate) const AD_PATTERNS: &[&str] = &[
     "ad",
     "ads",
     "advert",
@@ -43,7 +103,7 @@ const AD_PATTERNS: &[&str] = &[
     "gpt-ad",
 ];
 
-const TRACKER_PATTERNS: &[&str] = &[
+pub(crate) const TRACKER_PATTERNS: &[&str] = &[
     "tracker",
     "tracking",
     "analytics",
@@ -61,7 +121,6 @@ const TRACKER_PATTERNS: &[&str] = &[
     "popup-overlay",
 ];
 
-#[cfg(not(feature = "ml-filter"))]
 const AD_DOMAINS: &[&str] = &[
     "doubleclick.net",
     "googlesyndication.com",
@@ -79,9 +138,12 @@ const AD_DOMAINS: &[&str] = &[
 ///
 /// Phase 1: Rule-based heuristics (class/id patterns, tag types, content density).
 /// Phase 2: ALICE-ML ternary inference for learned classification.
+///
+/// A [`ClassifierModel`] loaded at runtime takes the place of both.
 pub struct SemanticFilter {
     #[cfg(feature = "ml-filter")]
     ml: ml_classifier::MlClassifier,
+    model: Option<Arc<ClassifierModel>>,
 }
 
 impl SemanticFilter {
@@ -90,30 +152,85 @@ impl SemanticFilter {
         Self {
             #[cfg(feature = "ml-filter")]
             ml: ml_classifier::MlClassifier::new(),
+            model: None,
+        }
+    }
+
+    /// Filter classifying with `model` instead of the built-in classifier.
+    #[must_use]
+    pub fn with_model(model: Arc<ClassifierModel>) -> Self {
+        let mut filter = Self::new();
+        filter.model = Some(model);
+        filter
+    }
+
+    #[must_use]
+    pub fn model(&self) -> Option<&ClassifierModel> {
+        self.model.as_deref()
+    }
+
+    /// What classifies nodes: the loaded model's name and version, or the
+    /// built-in classifier.
+    #[must_use]
+    pub fn label(&self) -> String {
+        match self.model() {
+            Some(model) => format!("model {}", model.label()),
+            None if cfg!(feature = "ml-filter") => "alice-ml".into(),
+            None => "rules".into(),
         }
     }
 
     /// Classify and filter a DOM tree in-place. Returns filter statistics.
     pub fn filter(&self, tree: &mut DomTree) -> FilterStats {
-        let mut stats = FilterStats {
-            total_nodes: 0,
-            content_nodes: 0,
-            ad_nodes: 0,
-            tracker_nodes: 0,
-            nav_nodes: 0,
-            removed_nodes: 0,
-        };
+        let stats = self.classify(tree);
+        prune(tree);
+        stats
+    }
+
+    /// Classify every node without removing anything; [`prune`] removes
+    /// the ad and tracker subtrees afterwards.
+    pub fn classify(&self, tree: &mut DomTree) -> FilterStats {
+        if let Some(model) = self.model() {
+            let mut stats = classify_tree(&mut tree.root, &|node: &DomNode| model.classify(node));
+            stats.removed_nodes = stats.ad_nodes + stats.tracker_nodes;
+            return stats;
+        }
 
         #[cfg(feature = "ml-filter")]
-        classify_recursive_ml(&self.ml, &mut tree.root, &mut stats);
+        let mut stats = classify_tree(&mut tree.root, &|node: &DomNode| self.ml.classify(node));
 
         #[cfg(not(feature = "ml-filter"))]
-        classify_recursive(&mut tree.root, &mut stats);
+        let mut stats = classify_tree(&mut tree.root, &classify_node);
 
-        prune_recursive(&mut tree.root);
         stats.removed_nodes = stats.ad_nodes + stats.tracker_nodes;
         stats
     }
+
+    /// Explain the removal of a node this filter classified as an ad or
+    /// tracker.
+    #[must_use]
+    pub fn explain(&self, node: &DomNode) -> RemovalReason {
+        if let Some(model) = self.model() {
+            let (_, score) = model.classify_scored(node);
+            return RemovalReason::Classifier {
+                model: model.label(),
+                score,
+            };
+        }
+
+        #[cfg(feature = "ml-filter")]
+        {
+            let (_, score) = self.ml.classify_scored(node);
+            RemovalReason::Classifier {
+                model: "alice-ml".into(),
+                score,
+            }
+        }
+        #[cfg(not(feature = "ml-filter"))]
+        RemovalReason::Rule {
+            rule: removal_rule(node),
+        }
+    }
 }
 
 impl Default for SemanticFilter {
@@ -122,62 +239,259 @@ impl Default for SemanticFilter {
     }
 }
 
-/// Recursively classify every node in the tree (rule-based fallback)
-#[cfg(not(feature = "ml-filter"))]
-fn classify_recursive(node: &mut DomNode, stats: &mut FilterStats) {
-    stats.total_nodes += 1;
+/// Classify `node`'s subtree with `classify`, parent before children.
+///
+/// Below [`PARALLEL_GRAIN`] nodes the subtree is walked on this thread;
+/// above it, the children's subtrees are handed to rayon, which steals
+/// them across its threads.
+fn classify_tree<F>(node: &mut DomNode, classify: &F) -> FilterStats
+where
+    F: Fn(&DomNode) -> Classification + Sync,
+{
+    let mut stats = FilterStats::default();
+    if node.node_count() < PARALLEL_GRAIN {
+        classify_recursive(node, classify, &mut stats);
+        return stats;
+    }
+    node.classification = classify(node);
+    stats.count(node.classification);
+    stats += node
+        .children
+        .par_iter_mut()
+        .map(|child| classify_tree(child, classify))
+        .reduce(FilterStats::default, |mut a, b| {
+            a += b;
+            a
+        });
+    stats
+}
 
-    node.classification = classify_node(node);
+/// Recursively classify every node of a subtree on this thread
+fn classify_recursive<F>(node: &mut DomNode, classify: &F, stats: &mut FilterStats)
+where
+    F: Fn(&DomNode) -> Classification,
+{
+    node.classification = classify(node);
+    stats.count(node.classification);
 
-    match node.classification {
-        Classification::Content => stats.content_nodes += 1,
-        Classification::Advertisement => stats.ad_nodes += 1,
-        Classification::Tracker => stats.tracker_nodes += 1,
-        Classification::Navigation => stats.nav_nodes += 1,
-        _ => {}
+    for child in &mut node.children {
+        classify_recursive(child, classify, stats);
     }
+}
 
-    for child in &mut node.children {
-        classify_recursive(child, stats);
+/// What the user says an element is, against the classifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Content,
+    Ad,
+}
+
+impl Verdict {
+    #[must_use]
+    pub const fn classification(self) -> Classification {
+        match self {
+            Self::Content => Classification::Content,
+            Self::Ad => Classification::Advertisement,
+        }
     }
 }
 
-/// Recursively classify using ALICE-ML ternary inference
-#[cfg(feature = "ml-filter")]
-fn classify_recursive_ml(
-    ml: &ml_classifier::MlClassifier,
-    node: &mut DomNode,
-    stats: &mut FilterStats,
-) {
-    stats.total_nodes += 1;
-
-    node.classification = ml.classify(node);
-
-    match node.classification {
-        Classification::Content => stats.content_nodes += 1,
-        Classification::Advertisement => stats.ad_nodes += 1,
-        Classification::Tracker => stats.tracker_nodes += 1,
-        Classification::Navigation => stats.nav_nodes += 1,
+/// One site's corrections to the classifier: elements, named by
+/// [`selector`], that the user marked as content or as ads. Applied after
+/// classification, they win over any backend; the marked element's
+/// descendants keep their own classes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeedbackRules {
+    pub content: BTreeSet<String>,
+    pub ads: BTreeSet<String>,
+}
+
+impl FeedbackRules {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty() && self.ads.is_empty()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.content.len() + self.ads.len()
+    }
+
+    /// Record that elements matching `selector` are `verdict`, replacing
+    /// an opposite mark; returns whether anything changed.
+    pub fn mark(&mut self, selector: &str, verdict: Verdict) -> bool {
+        let (keep, drop) = match verdict {
+            Verdict::Content => (&mut self.content, &mut self.ads),
+            Verdict::Ad => (&mut self.ads, &mut self.content),
+        };
+        let dropped = drop.remove(selector);
+        keep.insert(selector.to_string()) || dropped
+    }
+
+    /// Drop the mark on `selector`; returns whether there was one.
+    pub fn forget(&mut self, selector: &str) -> bool {
+        let content = self.content.remove(selector);
+        self.ads.remove(selector) || content
+    }
+
+    #[must_use]
+    pub fn verdict(&self, selector: &str) -> Option<Verdict> {
+        if self.ads.contains(selector) {
+            Some(Verdict::Ad)
+        } else if self.content.contains(selector) {
+            Some(Verdict::Content)
+        } else {
+            None
+        }
+    }
+
+    fn verdict_for(&self, node: &DomNode) -> Option<(Verdict, String)> {
+        let selector = selector(node)?;
+        self.verdict(&selector).map(|v| (v, selector))
+    }
+
+    /// Reclassify the marked elements of a classified tree, keeping
+    /// `stats` in step; returns how many elements were marked.
+    pub fn apply(&self, root: &mut DomNode, stats: &mut FilterStats) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        let applied = self.apply_recursive(root, stats);
+        stats.removed_nodes = stats.ad_nodes + stats.tracker_nodes;
+        applied
+    }
+
+    fn apply_recursive(&self, node: &mut DomNode, stats: &mut FilterStats) -> usize {
+        let mut applied = 0;
+        if let Some((verdict, _)) = self.verdict_for(node) {
+            let class = verdict.classification();
+            stats.reclassify(node.classification, class);
+            node.classification = class;
+            applied += 1;
+        }
+        for child in &mut node.children {
+            applied += self.apply_recursive(child, stats);
+        }
+        applied
+    }
+
+    /// `feedback:<selector>` for an element removed because the user marked
+    /// it as an ad.
+    #[must_use]
+    pub fn explain(&self, node: &DomNode) -> Option<RemovalReason> {
+        match self.verdict_for(node)? {
+            (Verdict::Ad, selector) => Some(RemovalReason::Rule {
+                rule: format!("feedback:{selector}"),
+            }),
+            (Verdict::Content, _) => None,
+        }
+    }
+}
+
+/// `tag#id.class1.class2` naming an element by its id and classes, the
+/// handle [`FeedbackRules`] keep; `None` for text nodes and for elements
+/// with neither, which could not be told apart from their siblings.
+#[must_use]
+pub fn selector(node: &DomNode) -> Option<String> {
+    if node.node_type != NodeType::Element {
+        return None;
+    }
+    let id = node.attr("id").filter(|id| !id.is_empty());
+    let classes = node.attr("class").unwrap_or_default();
+    if id.is_none() && classes.trim().is_empty() {
+        return None;
+    }
+    let mut label = node.tag.clone();
+    if let Some(id) = id {
+        label.push('#');
+        label.push_str(id);
+    }
+    for class in classes.split_whitespace() {
+        label.push('.');
+        label.push_str(class);
+    }
+    Some(label)
+}
+
+/// Remove the ad and tracker subtrees of a classified tree.
+pub fn prune(tree: &mut DomTree) {
+    prune_recursive(&mut tree.root);
+}
+
+/// Identify the heuristic that marks `node` as an ad or tracker.
+///
+/// Checks run in the same order as the rule-based classifier, so the first
+/// hit is the rule that decided. Nodes no rule matches (removed by another
+/// classifier) are reported as `classified:<class>`.
+#[must_use]
+pub fn removal_rule(node: &DomNode) -> String {
+    match node.tag.as_str() {
+        tag @ ("script" | "noscript") => return format!("tag:{tag}"),
+        "iframe" => {
+            let src = node.attr("src").unwrap_or_default().to_lowercase();
+            if let Some(domain) = AD_DOMAINS.iter().find(|d| src.contains(*d)) {
+                return format!("iframe-domain:{domain}");
+            }
+        }
         _ => {}
     }
 
-    for child in &mut node.children {
-        classify_recursive_ml(ml, child, stats);
+    let class = node.attr("class").unwrap_or("");
+    let id = node.attr("id").unwrap_or("");
+    let combined = format!("{class} {id}").to_lowercase();
+    if let Some(p) = AD_PATTERNS.iter().find(|p| combined.contains(*p)) {
+        return format!("ad-pattern:{p}");
     }
+    if let Some(p) = TRACKER_PATTERNS.iter().find(|p| combined.contains(*p)) {
+        return format!("tracker-pattern:{p}");
+    }
+
+    let mut keys: Vec<&String> = node
+        .attributes
+        .keys()
+        .filter(|k| k.starts_with("data-ad") || k.starts_with("data-tracking"))
+        .collect();
+    keys.sort();
+    if let Some(key) = keys.first() {
+        return format!("data-attr:{key}");
+    }
+
+    format!("classified:{:?}", node.classification).to_lowercase()
 }
 
-/// Remove ad and tracker subtrees
+/// Remove ad and tracker subtrees, the children of large subtrees in
+/// parallel
 fn prune_recursive(node: &mut DomNode) {
-    node.children.retain(|c| {
-        c.classification != Classification::Advertisement
-            && c.classification != Classification::Tracker
-    });
+    if node.node_count() < PARALLEL_GRAIN {
+        prune_sequential(node);
+        return;
+    }
+    node.children.retain(|c| !is_removed(c));
+    node.children.par_iter_mut().for_each(prune_recursive);
+}
+
+fn prune_sequential(node: &mut DomNode) {
+    node.children.retain(|c| !is_removed(c));
 
     for child in &mut node.children {
-        prune_recursive(child);
+        prune_sequential(child);
     }
 }
 
+fn is_removed(node: &DomNode) -> bool {
+    is_removed_class(node.classification)
+}
+
+/// Whether [`prune`] removes nodes of class `class`.
+#[must_use]
+pub const fn is_removed_class(class: Classification) -> bool {
+    matches!(
+        class,
+        Classification::Advertisement | Classification::Tracker
+    )
+}
+
 /// Classify a single DOM node using heuristics (rule-based fallback)
 #[cfg(not(feature = "ml-filter"))]
 fn classify_node(node: &DomNode) -> Classification {
@@ -279,6 +593,45 @@ mod tests {
         assert!(!text.contains("Buy stuff"));
     }
 
+    #[test]
+    fn large_trees_classify_and_prune_in_parallel() {
+        fn classes(node: &DomNode, out: &mut Vec<Classification>) {
+            out.push(node.classification);
+            for child in &node.children {
+                classes(child, out);
+            }
+        }
+        let sponsored = |node: &DomNode| {
+            if node.attr("class") == Some("sponsored") {
+                Classification::Advertisement
+            } else {
+                Classification::Content
+            }
+        };
+        let html: String = (0..300)
+            .map(|i| format!("<div><p>Item {i}</p><div class=\"sponsored\">Buy {i}</div></div>"))
+            .collect();
+        let tree = parse_html(&html, "https://example.com");
+        assert!(tree.root.node_count() > 2 * PARALLEL_GRAIN);
+
+        let mut sequential = tree.clone();
+        let mut expected = FilterStats::default();
+        classify_recursive(&mut sequential.root, &sponsored, &mut expected);
+        let mut parallel = tree.clone();
+        let stats = classify_tree(&mut parallel.root, &sponsored);
+        assert_eq!(stats.total_nodes, expected.total_nodes);
+        assert_eq!(stats.ad_nodes, 300);
+        let (mut a, mut b) = (Vec::new(), Vec::new());
+        classes(&sequential.root, &mut a);
+        classes(&parallel.root, &mut b);
+        assert_eq!(a, b);
+
+        prune(&mut parallel);
+        let text = parallel.root.collect_text();
+        assert!(text.contains("Item 299"));
+        assert!(!text.contains("Buy"));
+    }
+
     #[test]
     #[cfg(feature = "ml-filter")]
     fn ml_classifier_detects_ads() {
@@ -314,4 +667,77 @@ mod tests {
 
         assert!(stats.tracker_nodes > 0);
     }
+
+    #[test]
+    fn removal_rules_name_the_heuristic() {
+        fn elements<'a>(node: &'a DomNode, out: &mut Vec<&'a DomNode>) {
+            if matches!(node.tag.as_str(), "script" | "div" | "span") {
+                out.push(node);
+            }
+            for c in &node.children {
+                elements(c, out);
+            }
+        }
+        let html = r#"<html><body>
+            <script src="x.js"></script>
+            <div class="sidebar-advert">Buy</div>
+            <div id="analytics-beacon"></div>
+            <span data-ad-slot="1">x</span>
+        </body></html>"#;
+        let tree = parse_html(html, "https://example.com");
+        let mut found = Vec::new();
+        elements(&tree.root, &mut found);
+        let rules: Vec<String> = found.into_iter().map(removal_rule).collect();
+        assert_eq!(
+            rules,
+            [
+                "tag:script",
+                "ad-pattern:ad",
+                "tracker-pattern:analytics",
+                "data-attr:data-ad-slot"
+            ]
+        );
+    }
+
+    #[test]
+    fn feedback_rules_override_the_classifier() {
+        let html = r#"<html><body>
+            <div class="promo-story"><p>Our own story</p></div>
+            <div id="partner-box"><p>Brought to you by</p></div>
+            <div class="content">Real content here</div>
+        </body></html>"#;
+        let mut tree = parse_html(html, "https://example.com");
+        let filter = SemanticFilter::new();
+        let mut stats = filter.classify(&mut tree);
+
+        let mut rules = FeedbackRules::default();
+        assert!(rules.mark("div.promo-story", Verdict::Ad));
+        assert!(rules.mark("div.promo-story", Verdict::Content));
+        assert!(!rules.mark("div.promo-story", Verdict::Content));
+        assert!(rules.mark("div#partner-box", Verdict::Ad));
+        assert_eq!(rules.len(), 2);
+
+        assert_eq!(rules.apply(&mut tree.root, &mut stats), 2);
+        assert_eq!(stats.removed_nodes, stats.ad_nodes + stats.tracker_nodes);
+        prune(&mut tree);
+        let text = tree.root.collect_text();
+        assert!(text.contains("Our own story"));
+        assert!(!text.contains("Brought to you by"));
+        assert!(text.contains("Real content"));
+
+        let div = DomNode::element(
+            "div",
+            [("id".to_string(), "partner-box".to_string())].into(),
+            Vec::new(),
+        );
+        assert_eq!(selector(&div).as_deref(), Some("div#partner-box"));
+        assert_eq!(
+            rules.explain(&div),
+            Some(RemovalReason::Rule {
+                rule: "feedback:div#partner-box".into()
+            })
+        );
+        assert!(rules.forget("div#partner-box"));
+        assert_eq!(rules.explain(&div), None);
+    }
 }
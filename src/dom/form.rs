@@ -0,0 +1,333 @@
+//! HTML form controls and submission.
+//!
+//! Reads `<form>`, `<input>`, `<select>`, `<textarea>` and `<button>`
+//! elements into [`FormControl`] descriptions that layout carries to the UI,
+//! and turns the values the user entered into a [`FormSubmission`]
+//! (`application/x-www-form-urlencoded`, GET or POST).
+
+use super::DomNode;
+
+/// HTTP method of a form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormMethod {
+    #[default]
+    Get,
+    Post,
+}
+
+impl FormMethod {
+    /// Parse a `method` attribute; anything but `post` means GET.
+    #[must_use]
+    pub fn from_attr(value: Option<&str>) -> Self {
+        match value {
+            Some(m) if m.eq_ignore_ascii_case("post") => Self::Post,
+            _ => Self::Get,
+        }
+    }
+}
+
+/// Flavour of `<input>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    Text,
+    Password,
+    Email,
+    Search,
+    Number,
+    Hidden,
+    Checkbox,
+    Radio,
+    Submit,
+    Reset,
+}
+
+impl InputKind {
+    #[must_use]
+    pub fn from_attr(value: Option<&str>) -> Self {
+        match value.map(str::to_ascii_lowercase).as_deref() {
+            Some("password") => Self::Password,
+            Some("email") => Self::Email,
+            Some("search") => Self::Search,
+            Some("number") => Self::Number,
+            Some("hidden") => Self::Hidden,
+            Some("checkbox") => Self::Checkbox,
+            Some("radio") => Self::Radio,
+            Some("submit" | "image") => Self::Submit,
+            Some("reset") => Self::Reset,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// One `<option>` of a `<select>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectOption {
+    pub value: String,
+    pub label: String,
+}
+
+/// What kind of form element a layout node is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlKind {
+    /// The `<form>` itself
+    Form {
+        action: String,
+        method: FormMethod,
+    },
+    Input(InputKind),
+    Select {
+        options: Vec<SelectOption>,
+        selected: usize,
+    },
+    TextArea {
+        rows: usize,
+    },
+    /// `<button>`; `submit` is false for `type="button"` / `"reset"`
+    Button {
+        submit: bool,
+    },
+}
+
+/// A form element as seen by layout and the UI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormControl {
+    pub kind: ControlKind,
+    pub name: String,
+    pub id: String,
+    /// `autocomplete` attribute, as written
+    pub autocomplete: String,
+    /// Initial value (text, option value, or button value)
+    pub value: String,
+    /// Initial state of checkboxes and radio buttons
+    pub checked: bool,
+    pub placeholder: String,
+    /// Visible text of buttons
+    pub label: String,
+}
+
+impl FormControl {
+    /// Describe `node` if it is a form element.
+    #[must_use]
+    pub fn from_node(node: &DomNode) -> Option<Self> {
+        let attr = |name: &str| node.attr(name).unwrap_or_default().to_string();
+        let kind = match node.tag.as_str() {
+            "form" => ControlKind::Form {
+                action: attr("action"),
+                method: FormMethod::from_attr(node.attr("method")),
+            },
+            "input" => ControlKind::Input(InputKind::from_attr(node.attr("type"))),
+            "select" => {
+                let mut options = Vec::new();
+                let mut selected = 0;
+                collect_options(node, &mut options, &mut selected);
+                ControlKind::Select { options, selected }
+            }
+            "textarea" => ControlKind::TextArea {
+                rows: node
+                    .attr("rows")
+                    .and_then(|r| r.parse().ok())
+                    .unwrap_or(3)
+                    .clamp(1, 20),
+            },
+            "button" => ControlKind::Button {
+                submit: !matches!(node.attr("type"), Some("button" | "reset")),
+            },
+            _ => return None,
+        };
+
+        let value = match &kind {
+            ControlKind::Select { options, selected } => options
+                .get(*selected)
+                .map(|o| o.value.clone())
+                .unwrap_or_default(),
+            ControlKind::TextArea { .. } => node.collect_text(),
+            ControlKind::Input(InputKind::Checkbox | InputKind::Radio) => {
+                node.attr("value").unwrap_or("on").to_string()
+            }
+            _ => attr("value"),
+        };
+        let label = match &kind {
+            ControlKind::Button { .. } => node.collect_text().trim().to_string(),
+            ControlKind::Input(InputKind::Submit) => {
+                node.attr("value").unwrap_or("Submit").to_string()
+            }
+            ControlKind::Input(InputKind::Reset) => {
+                node.attr("value").unwrap_or("Reset").to_string()
+            }
+            _ => String::new(),
+        };
+
+        Some(Self {
+            kind,
+            name: attr("name"),
+            id: attr("id"),
+            autocomplete: attr("autocomplete"),
+            value,
+            checked: node.attributes.contains_key("checked"),
+            placeholder: attr("placeholder"),
+            label,
+        })
+    }
+
+    /// Whether the control can submit its form when activated.
+    #[must_use]
+    pub const fn is_submitter(&self) -> bool {
+        matches!(
+            self.kind,
+            ControlKind::Input(InputKind::Submit) | ControlKind::Button { submit: true }
+        )
+    }
+}
+
+fn collect_options(node: &DomNode, out: &mut Vec<SelectOption>, selected: &mut usize) {
+    for child in &node.children {
+        if child.tag == "option" {
+            let label = child.collect_text().trim().to_string();
+            let value = child
+                .attr("value")
+                .map_or_else(|| label.clone(), str::to_string);
+            if child.attributes.contains_key("selected") {
+                *selected = out.len();
+            }
+            out.push(SelectOption { value, label });
+        } else {
+            // <optgroup>
+            collect_options(child, out, selected);
+        }
+    }
+}
+
+/// Percent-encode `pairs` as `application/x-www-form-urlencoded`.
+#[must_use]
+pub fn encode_urlencoded(pairs: &[(String, String)]) -> String {
+    url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(pairs)
+        .finish()
+}
+
+/// A ready-to-send form request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormSubmission {
+    pub method: FormMethod,
+    /// Target URL (including the query string for GET)
+    pub url: String,
+    /// Encoded body for POST
+    pub body: Option<String>,
+}
+
+impl FormSubmission {
+    /// Build the request for a form with `action` on page `base_url`.
+    ///
+    /// An empty `action` submits to the page itself. Returns `None` if the
+    /// action cannot be resolved to an absolute URL.
+    #[must_use]
+    pub fn new(
+        base_url: &str,
+        action: &str,
+        method: FormMethod,
+        pairs: &[(String, String)],
+    ) -> Option<Self> {
+        let base = url::Url::parse(base_url).ok()?;
+        let mut target = if action.trim().is_empty() {
+            base
+        } else {
+            base.join(action.trim()).ok()?
+        };
+        target.set_fragment(None);
+        let encoded = encode_urlencoded(pairs);
+
+        Some(match method {
+            FormMethod::Get => {
+                target.set_query(if encoded.is_empty() {
+                    None
+                } else {
+                    Some(&encoded)
+                });
+                Self {
+                    method,
+                    url: target.into(),
+                    body: None,
+                }
+            }
+            FormMethod::Post => Self {
+                method,
+                url: target.into(),
+                body: Some(encoded),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+
+    fn find<'a>(node: &'a DomNode, tag: &str) -> Option<&'a DomNode> {
+        if node.tag == tag {
+            return Some(node);
+        }
+        node.children.iter().find_map(|c| find(c, tag))
+    }
+
+    #[test]
+    fn reads_controls() {
+        let dom = parse_html(
+            r#"<form action="/search" method="POST">
+                <input name="q" placeholder="Search">
+                <select name="lang"><option value="en">English</option>
+                    <option selected>日本語</option></select>
+                <textarea name="note" rows="5">hi</textarea>
+                <button>Go</button>
+            </form>"#,
+            "https://example.com/",
+        );
+        let form = FormControl::from_node(find(&dom.root, "form").unwrap()).unwrap();
+        assert_eq!(
+            form.kind,
+            ControlKind::Form {
+                action: "/search".into(),
+                method: FormMethod::Post
+            }
+        );
+        let input = FormControl::from_node(find(&dom.root, "input").unwrap()).unwrap();
+        assert_eq!(input.kind, ControlKind::Input(InputKind::Text));
+        assert_eq!(input.placeholder, "Search");
+        let select = FormControl::from_node(find(&dom.root, "select").unwrap()).unwrap();
+        assert_eq!(select.value, "日本語");
+        let area = FormControl::from_node(find(&dom.root, "textarea").unwrap()).unwrap();
+        assert_eq!(
+            (area.kind, area.value.as_str()),
+            (ControlKind::TextArea { rows: 5 }, "hi")
+        );
+        let button = FormControl::from_node(find(&dom.root, "button").unwrap()).unwrap();
+        assert!(button.is_submitter());
+        assert_eq!(button.label, "Go");
+    }
+
+    #[test]
+    fn get_submission_replaces_query() {
+        let pairs = vec![
+            ("q".to_string(), "rust egui".to_string()),
+            ("n".to_string(), "a&b".to_string()),
+        ];
+        let sub = FormSubmission::new(
+            "https://example.com/page?old=1#top",
+            "",
+            FormMethod::Get,
+            &pairs,
+        )
+        .unwrap();
+        assert_eq!(sub.url, "https://example.com/page?q=rust+egui&n=a%26b");
+        assert_eq!(sub.body, None);
+    }
+
+    #[test]
+    fn post_submission_has_body() {
+        let pairs = vec![("user".to_string(), "alice".to_string())];
+        let sub = FormSubmission::new("https://example.com/a/", "login", FormMethod::Post, &pairs)
+            .unwrap();
+        assert_eq!(sub.url, "https://example.com/a/login");
+        assert_eq!(sub.body.as_deref(), Some("user=alice"));
+    }
+}
@@ -4,13 +4,11 @@
 //! into semantic categories. Weights are {-1, 0, +1} only — no multiplication,
 //! just add/sub via `ternary_matvec`.
 
-use super::{AD_PATTERNS, TRACKER_PATTERNS};
+use crate::dom::model::{extract_features, NUM_CLASSES, NUM_FEATURES};
 use crate::dom::{Classification, DomNode, NodeType};
 use alice_ml::{ternary_matvec, TernaryWeight};
 
-const NUM_FEATURES: usize = 16;
 const HIDDEN_SIZE: usize = 32;
-const NUM_CLASSES: usize = 9;
 
 /// Ternary neural network classifier for DOM nodes.
 ///
@@ -31,9 +29,15 @@ impl MlClassifier {
 
     /// Classify a DOM node using ternary neural network inference.
     pub fn classify(&self, node: &DomNode) -> Classification {
+        self.classify_scored(node).0
+    }
+
+    /// [`classify`](Self::classify), also returning the winning output
+    /// activation.
+    pub fn classify_scored(&self, node: &DomNode) -> (Classification, f32) {
         // Text nodes are always content (skip inference)
         if node.node_type == NodeType::Text {
-            return Classification::Content;
+            return (Classification::Content, 0.0);
         }
 
         let features = extract_features(node);
@@ -50,138 +54,17 @@ impl MlClassifier {
         ternary_matvec(&hidden, &self.layer2, &mut output);
 
         // argmax → Classification
-        let best_idx = output
+        let (best_idx, best) = output
             .iter()
+            .copied()
             .enumerate()
             .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-            .map(|(i, _)| i)
-            .unwrap_or(8);
+            .unwrap_or((8, 0.0));
 
-        Classification::from_index(best_idx)
+        (Classification::from_index(best_idx), best)
     }
 }
 
-/// Extract 16-dimensional feature vector from a DOM node.
-///
-/// Features:
-///  0: tag_type (normalized encoding of HTML tag)
-///  1: text_density (text chars per node, normalized)
-///  2: link_density (ratio of link text to total text)
-///  3: child_count (normalized)
-///  4: has_ad_class (binary: class/id matches ad patterns)
-///  5: has_tracker_class (binary: class/id matches tracker patterns)
-///  6: has_data_ad_attr (binary: data-ad* or data-tracking* attributes)
-///  7: is_script (binary)
-///  8: is_style (binary)
-///  9: is_nav (binary)
-/// 10: is_interactive (binary: button/input/form/etc)
-/// 11: is_media (binary: img/video/audio/etc)
-/// 12: is_text_node (binary)
-/// 13: text_length (normalized)
-/// 14: has_href (binary)
-/// 15: attr_count (normalized)
-fn extract_features(node: &DomNode) -> [f32; NUM_FEATURES] {
-    let mut f = [0.0f32; NUM_FEATURES];
-
-    // F0: tag type encoding (normalized to ~[0, 1])
-    f[0] = match node.tag.as_str() {
-        "div" | "span" | "section" | "article" => 1.0,
-        "p" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => 2.0,
-        "a" => 3.0,
-        "script" | "noscript" => 4.0,
-        "style" => 5.0,
-        "nav" => 6.0,
-        "button" | "input" | "form" | "textarea" | "select" => 7.0,
-        "img" | "video" | "audio" | "canvas" | "picture" => 8.0,
-        "iframe" => 9.0,
-        "header" | "footer" => 10.0,
-        "ul" | "ol" | "li" => 11.0,
-        "table" | "tr" | "td" | "th" => 12.0,
-        _ => 0.0,
-    } / 12.0;
-
-    // F1: text density
-    f[1] = (node.text_density() / 50.0).min(1.0);
-
-    // F2: link density
-    f[2] = node.link_density();
-
-    // F3: child count (normalized)
-    f[3] = (node.children.len() as f32 / 20.0).min(1.0);
-
-    // F4-F5: class/id pattern matching
-    let class = node.attr("class").unwrap_or("");
-    let id = node.attr("id").unwrap_or("");
-    let combined = format!("{} {}", class, id).to_lowercase();
-
-    f[4] = if AD_PATTERNS.iter().any(|p| combined.contains(p)) {
-        1.0
-    } else {
-        0.0
-    };
-    f[5] = if TRACKER_PATTERNS.iter().any(|p| combined.contains(p)) {
-        1.0
-    } else {
-        0.0
-    };
-
-    // F6: data-ad / data-tracking attributes
-    f[6] = if node
-        .attributes
-        .keys()
-        .any(|k| k.starts_with("data-ad") || k.starts_with("data-tracking"))
-    {
-        1.0
-    } else {
-        0.0
-    };
-
-    // F7-F12: binary tag-type features
-    f[7] = if node.tag == "script" || node.tag == "noscript" {
-        1.0
-    } else {
-        0.0
-    };
-    f[8] = if node.tag == "style" { 1.0 } else { 0.0 };
-    f[9] = if node.tag == "nav" { 1.0 } else { 0.0 };
-    f[10] = if matches!(
-        node.tag.as_str(),
-        "button" | "input" | "textarea" | "select" | "form"
-    ) {
-        1.0
-    } else {
-        0.0
-    };
-    f[11] = if matches!(
-        node.tag.as_str(),
-        "img" | "video" | "audio" | "picture" | "canvas"
-    ) {
-        1.0
-    } else {
-        0.0
-    };
-    f[12] = if node.node_type == NodeType::Text {
-        1.0
-    } else {
-        0.0
-    };
-
-    // F13: text length (normalized)
-    f[13] = (node.collect_text().len() as f32 / 500.0).min(1.0);
-
-    // F14: has href
-    f[14] = if node.attr("href").is_some() {
-        1.0
-    } else {
-        0.0
-    };
-
-    // F15: attribute count (normalized)
-    f[15] = (node.attributes.len() as f32 / 10.0).min(1.0);
-
-    f
-}
-
 /// Initialize Layer 1 weights: NUM_FEATURES(16) → HIDDEN_SIZE(32)
 ///
 /// Hidden neurons are organized in groups of 4, each detecting one class:
@@ -372,35 +255,6 @@ mod tests {
     use super::*;
     use crate::dom::parser::parse_html;
 
-    #[test]
-    fn feature_extraction_basic() {
-        let html = r#"<html><body><p>Hello world</p></body></html>"#;
-        let tree = parse_html(html, "https://example.com");
-
-        // Find the <p> node
-        fn find_tag<'a>(node: &'a DomNode, tag: &str) -> Option<&'a DomNode> {
-            if node.tag == tag {
-                return Some(node);
-            }
-            for child in &node.children {
-                if let Some(found) = find_tag(child, tag) {
-                    return Some(found);
-                }
-            }
-            None
-        }
-
-        let p_node = find_tag(&tree.root, "p").expect("should find <p>");
-        let features = extract_features(p_node);
-
-        // tag_type for "p" should be 2.0/12.0
-        assert!((features[0] - 2.0 / 12.0).abs() < 0.01);
-        // is_script should be 0
-        assert_eq!(features[7], 0.0);
-        // is_text_node should be 0 (it's an element)
-        assert_eq!(features[12], 0.0);
-    }
-
     #[test]
     fn ml_classifies_script_as_tracker() {
         let html = r#"<html><body><script>var x = 1;</script></body></html>"#;
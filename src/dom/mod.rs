@@ -1,5 +1,10 @@
+pub mod arena;
+pub mod autofill;
 pub mod css;
+pub mod diff;
 pub mod filter;
+pub mod form;
+pub mod model;
 pub mod parser;
 pub mod readability;
 
@@ -0,0 +1,442 @@
+//! Loadable weights for the DOM node classifier.
+//!
+//! A [`ClassifierModel`] is a small dense network from the
+//! [`NUM_FEATURES`] node features of [`extract_features`] to the
+//! [`NUM_CLASSES`] outputs numbered by [`Classification::from_index`], with
+//! ReLU between layers. Models are read from JSON files:
+//!
+//! ```json
+//! { "name": "news", "version": "3",
+//!   "layers": [ { "weights": [[/* 16 floats */], /* … one row per output */],
+//!                 "bias": [/* one per output */] },
+//!               { "weights": [/* 9 rows */], "bias": [/* 9 */] } ] }
+//! ```
+//!
+//! Each layer's rows are as long as the previous layer's output, and every
+//! shape is checked when the file is loaded. An engine classifies with the
+//! model it was built with ([`BrowserEngineBuilder::model`]), so swapping
+//! the model handed to new engines takes effect from the next page load.
+//!
+//! [`BrowserEngineBuilder::model`]: crate::engine::pipeline::BrowserEngineBuilder::model
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::filter::{AD_PATTERNS, TRACKER_PATTERNS};
+use super::{Classification, DomNode, NodeType};
+
+/// Inputs of every classifier: the features of one node.
+pub const NUM_FEATURES: usize = 16;
+/// Outputs of every classifier, in [`Classification::from_index`] order.
+pub const NUM_CLASSES: usize = 9;
+
+/// Error loading a classifier model.
+#[derive(Debug)]
+pub struct ModelError {
+    pub message: String,
+}
+
+impl std::fmt::Display for ModelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+fn error(message: impl Into<String>) -> ModelError {
+    ModelError {
+        message: message.into(),
+    }
+}
+
+/// One fully connected layer: `weights[o][i]` from input `i` to output `o`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Layer {
+    pub weights: Vec<Vec<f32>>,
+    pub bias: Vec<f32>,
+}
+
+impl Layer {
+    #[must_use]
+    pub fn outputs(&self) -> usize {
+        self.weights.len()
+    }
+
+    fn forward(&self, input: &[f32]) -> Vec<f32> {
+        self.weights
+            .iter()
+            .zip(&self.bias)
+            .map(|(row, b)| row.iter().zip(input).map(|(w, x)| w * x).sum::<f32>() + b)
+            .collect()
+    }
+}
+
+/// Classifier weights loaded at runtime.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassifierModel {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    pub layers: Vec<Layer>,
+}
+
+impl ClassifierModel {
+    /// Parse and validate a model file's contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ModelError` if the text is not a model or its shapes do not
+    /// fit the classifier.
+    pub fn from_json(text: &str) -> Result<Self, ModelError> {
+        let model: Self =
+            serde_json::from_str(text).map_err(|e| error(format!("not a model file: {e}")))?;
+        model.validate()?;
+        Ok(model)
+    }
+
+    /// Read and validate the model file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ModelError` if the file cannot be read or is not a valid
+    /// model.
+    pub fn load(path: &Path) -> Result<Self, ModelError> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| error(format!("{}: {e}", path.display())))?;
+        Self::from_json(&text)
+    }
+
+    /// Check that the layers chain from [`NUM_FEATURES`] inputs to
+    /// [`NUM_CLASSES`] outputs and that every weight is finite.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ModelError` naming the first layer that does not fit.
+    pub fn validate(&self) -> Result<(), ModelError> {
+        if self.layers.is_empty() {
+            return Err(error("model has no layers"));
+        }
+        let mut inputs = NUM_FEATURES;
+        for (i, layer) in self.layers.iter().enumerate() {
+            let n = i + 1;
+            if layer.outputs() == 0 {
+                return Err(error(format!("layer {n} has no outputs")));
+            }
+            if layer.bias.len() != layer.outputs() {
+                return Err(error(format!(
+                    "layer {n}: {} biases for {} outputs",
+                    layer.bias.len(),
+                    layer.outputs()
+                )));
+            }
+            if let Some(row) = layer.weights.iter().position(|r| r.len() != inputs) {
+                return Err(error(format!(
+                    "layer {n}, row {}: {} weights, expected {inputs}",
+                    row + 1,
+                    layer.weights[row].len()
+                )));
+            }
+            let finite = layer.weights.iter().flatten().chain(&layer.bias);
+            if !finite.into_iter().all(|w| w.is_finite()) {
+                return Err(error(format!("layer {n} has a non-finite weight")));
+            }
+            inputs = layer.outputs();
+        }
+        if inputs != NUM_CLASSES {
+            return Err(error(format!(
+                "model has {inputs} outputs, expected {NUM_CLASSES}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Name and version, e.g. "news 3".
+    #[must_use]
+    pub fn label(&self) -> String {
+        if self.version.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} {}", self.name, self.version)
+        }
+    }
+
+    /// Number of weights and biases.
+    #[must_use]
+    pub fn parameter_count(&self) -> usize {
+        self.layers
+            .iter()
+            .map(|l| l.weights.iter().map(Vec::len).sum::<usize>() + l.bias.len())
+            .sum()
+    }
+
+    /// Output activations for `features`, ReLU between layers.
+    #[must_use]
+    pub fn forward(&self, features: &[f32]) -> Vec<f32> {
+        let last = self.layers.len().saturating_sub(1);
+        let mut values = features.to_vec();
+        for (i, layer) in self.layers.iter().enumerate() {
+            values = layer.forward(&values);
+            if i < last {
+                for v in &mut values {
+                    *v = v.max(0.0);
+                }
+            }
+        }
+        values
+    }
+
+    #[must_use]
+    pub fn classify(&self, node: &DomNode) -> Classification {
+        self.classify_scored(node).0
+    }
+
+    /// [`classify`](Self::classify), also returning the winning output
+    /// activation.
+    #[must_use]
+    pub fn classify_scored(&self, node: &DomNode) -> (Classification, f32) {
+        // Text nodes are always content (skip inference)
+        if node.node_type == NodeType::Text {
+            return (Classification::Content, 0.0);
+        }
+        let output = self.forward(&extract_features(node));
+        let (best_idx, best) = output
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap_or((NUM_CLASSES - 1, 0.0));
+        (Classification::from_index(best_idx), best)
+    }
+}
+
+/// Extract the 16-dimensional feature vector of a DOM node.
+///
+/// Features:
+///  0: tag_type (normalized encoding of HTML tag)
+///  1: text_density (text chars per node, normalized)
+///  2: link_density (ratio of link text to total text)
+///  3: child_count (normalized)
+///  4: has_ad_class (binary: class/id matches ad patterns)
+///  5: has_tracker_class (binary: class/id matches tracker patterns)
+///  6: has_data_ad_attr (binary: data-ad* or data-tracking* attributes)
+///  7: is_script (binary)
+///  8: is_style (binary)
+///  9: is_nav (binary)
+/// 10: is_interactive (binary: button/input/form/etc)
+/// 11: is_media (binary: img/video/audio/etc)
+/// 12: is_text_node (binary)
+/// 13: text_length (normalized)
+/// 14: has_href (binary)
+/// 15: attr_count (normalized)
+pub fn extract_features(node: &DomNode) -> [f32; NUM_FEATURES] {
+    let mut f = [0.0f32; NUM_FEATURES];
+
+    // F0: tag type encoding (normalized to ~[0, 1])
+    f[0] = match node.tag.as_str() {
+        "div" | "span" | "section" | "article" => 1.0,
+        "p" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => 2.0,
+        "a" => 3.0,
+        "script" | "noscript" => 4.0,
+        "style" => 5.0,
+        "nav" => 6.0,
+        "button" | "input" | "form" | "textarea" | "select" => 7.0,
+        "img" | "video" | "audio" | "canvas" | "picture" => 8.0,
+        "iframe" => 9.0,
+        "header" | "footer" => 10.0,
+        "ul" | "ol" | "li" => 11.0,
+        "table" | "tr" | "td" | "th" => 12.0,
+        _ => 0.0,
+    } / 12.0;
+
+    // F1: text density
+    f[1] = (node.text_density() / 50.0).min(1.0);
+
+    // F2: link density
+    f[2] = node.link_density();
+
+    // F3: child count (normalized)
+    f[3] = (node.children.len() as f32 / 20.0).min(1.0);
+
+    // F4-F5: class/id pattern matching
+    let class = node.attr("class").unwrap_or("");
+    let id = node.attr("id").unwrap_or("");
+    let combined = format!("{class} {id}").to_lowercase();
+
+    f[4] = if AD_PATTERNS.iter().any(|p| combined.contains(p)) {
+        1.0
+    } else {
+        0.0
+    };
+    f[5] = if TRACKER_PATTERNS.iter().any(|p| combined.contains(p)) {
+        1.0
+    } else {
+        0.0
+    };
+
+    // F6: data-ad / data-tracking attributes
+    f[6] = if node
+        .attributes
+        .keys()
+        .any(|k| k.starts_with("data-ad") || k.starts_with("data-tracking"))
+    {
+        1.0
+    } else {
+        0.0
+    };
+
+    // F7-F12: binary tag-type features
+    f[7] = if node.tag == "script" || node.tag == "noscript" {
+        1.0
+    } else {
+        0.0
+    };
+    f[8] = if node.tag == "style" { 1.0 } else { 0.0 };
+    f[9] = if node.tag == "nav" { 1.0 } else { 0.0 };
+    f[10] = if matches!(
+        node.tag.as_str(),
+        "button" | "input" | "textarea" | "select" | "form"
+    ) {
+        1.0
+    } else {
+        0.0
+    };
+    f[11] = if matches!(
+        node.tag.as_str(),
+        "img" | "video" | "audio" | "picture" | "canvas"
+    ) {
+        1.0
+    } else {
+        0.0
+    };
+    f[12] = if node.node_type == NodeType::Text {
+        1.0
+    } else {
+        0.0
+    };
+
+    // F13: text length (normalized)
+    f[13] = (node.collect_text().len() as f32 / 500.0).min(1.0);
+
+    // F14: has href
+    f[14] = if node.attr("href").is_some() {
+        1.0
+    } else {
+        0.0
+    };
+
+    // F15: attribute count (normalized)
+    f[15] = (node.attributes.len() as f32 / 10.0).min(1.0);
+
+    f
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+
+    #[test]
+    fn feature_extraction_basic() {
+        let html = r#"<html><body><p>Hello world</p></body></html>"#;
+        let tree = parse_html(html, "https://example.com");
+
+        // Find the <p> node
+        fn find_tag<'a>(node: &'a DomNode, tag: &str) -> Option<&'a DomNode> {
+            if node.tag == tag {
+                return Some(node);
+            }
+            for child in &node.children {
+                if let Some(found) = find_tag(child, tag) {
+                    return Some(found);
+                }
+            }
+            None
+        }
+
+        let p_node = find_tag(&tree.root, "p").expect("should find <p>");
+        let features = extract_features(p_node);
+
+        // tag_type for "p" should be 2.0/12.0
+        assert!((features[0] - 2.0 / 12.0).abs() < 0.01);
+        // is_script should be 0
+        assert_eq!(features[7], 0.0);
+        // is_text_node should be 0 (it's an element)
+        assert_eq!(features[12], 0.0);
+    }
+
+    /// One hidden unit per class, wired to the feature that marks it.
+    fn model(version: &str) -> ClassifierModel {
+        let mut hidden = vec![vec![0.0; NUM_FEATURES]; NUM_CLASSES];
+        hidden[0][13] = 1.0; // text length → content
+        hidden[1][9] = 1.0; // nav
+        hidden[2][4] = 2.0; // ad class
+        hidden[3][7] = 2.0; // script
+        let output = (0..NUM_CLASSES)
+            .map(|o| {
+                (0..NUM_CLASSES)
+                    .map(|h| if h == o { 1.0 } else { 0.0 })
+                    .collect()
+            })
+            .collect();
+        ClassifierModel {
+            name: "test".into(),
+            version: version.into(),
+            layers: vec![
+                Layer {
+                    weights: hidden,
+                    bias: vec![0.0; NUM_CLASSES],
+                },
+                Layer {
+                    weights: output,
+                    bias: vec![0.0; NUM_CLASSES],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn loaded_models_classify_nodes() {
+        let json = serde_json::to_string(&model("2")).unwrap();
+        let model = ClassifierModel::from_json(&json).ok().unwrap();
+        assert_eq!(model.label(), "test 2");
+        assert_eq!(model.parameter_count(), (16 + 1) * 9 + (9 + 1) * 9);
+
+        let ad = DomNode::element(
+            "div",
+            [("class".to_string(), "ad-banner".to_string())].into(),
+            Vec::new(),
+        );
+        assert_eq!(model.classify(&ad), Classification::Advertisement);
+        let script = DomNode::element("script", Default::default(), Vec::new());
+        assert_eq!(model.classify(&script), Classification::Tracker);
+        let nav = DomNode::element("nav", Default::default(), Vec::new());
+        assert_eq!(model.classify(&nav), Classification::Navigation);
+        assert_eq!(
+            model.classify(&DomNode::text("hello")),
+            Classification::Content
+        );
+    }
+
+    #[test]
+    fn shapes_are_validated() {
+        let mut short_row = model("1");
+        short_row.layers[0].weights[3].pop();
+        let e = short_row.validate().unwrap_err();
+        assert_eq!(e.message, "layer 1, row 4: 15 weights, expected 16");
+
+        let mut wrong_outputs = model("1");
+        wrong_outputs.layers[1].weights.pop();
+        wrong_outputs.layers[1].bias.pop();
+        let e = wrong_outputs.validate().unwrap_err();
+        assert_eq!(e.message, "model has 8 outputs, expected 9");
+
+        let mut nan = model("1");
+        nan.layers[1].bias[0] = f32::NAN;
+        assert!(nan.validate().is_err());
+
+        assert!(ClassifierModel::from_json("{\"name\": \"x\", \"layers\": []}").is_err());
+        assert!(ClassifierModel::from_json("weights").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::dom::arena::{ArenaNode, DomArena, NodeId};
 use crate::dom::{DomNode, DomTree};
 use scraper::{ElementRef, Html, Node};
 use std::collections::HashMap;
@@ -26,6 +27,67 @@ pub fn parse_html(html: &str, url: &str) -> DomTree {
     }
 }
 
+/// Parse raw HTML string into a [`DomArena`], without building owned
+/// `DomNode`s on the way.
+#[must_use]
+pub fn parse_arena(html: &str, url: &str) -> DomArena {
+    let document = Html::parse_document(html);
+    let title = scraper::Selector::parse("title")
+        .ok()
+        .and_then(|sel| document.select(&sel).next())
+        .map(|el| el.text().collect::<String>())
+        .unwrap_or_default();
+
+    let root_el = document.root_element();
+    let mut arena = DomArena::with_capacity(
+        document.tree.nodes().count(),
+        arena_element(root_el),
+        url,
+        title.trim(),
+    );
+    let mut stack = vec![(arena.root(), root_el)];
+    while let Some((id, el)) = stack.pop() {
+        if SKIP_CHILDREN.contains(&arena.node(id).tag.as_str()) {
+            continue;
+        }
+        append_children(&mut arena, id, el, &mut stack);
+    }
+    // Whitespace, comments and doctypes were counted but not kept
+    arena.shrink_to_fit();
+    arena
+}
+
+fn arena_element(el: ElementRef<'_>) -> ArenaNode {
+    let attributes = el
+        .value()
+        .attrs()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    ArenaNode::element(el.value().name(), attributes)
+}
+
+fn append_children<'a>(
+    arena: &mut DomArena,
+    parent: NodeId,
+    el: ElementRef<'a>,
+    stack: &mut Vec<(NodeId, ElementRef<'a>)>,
+) {
+    for child_ref in el.children() {
+        match child_ref.value() {
+            Node::Element(_) => {
+                if let Some(child_el) = ElementRef::wrap(child_ref) {
+                    let id = arena.append(parent, arena_element(child_el));
+                    stack.push((id, child_el));
+                }
+            }
+            Node::Text(t) if !t.text.trim().is_empty() => {
+                arena.append(parent, ArenaNode::text(t.text.to_string()));
+            }
+            _ => {}
+        }
+    }
+}
+
 fn convert_element(el: ElementRef<'_>) -> DomNode {
     let tag = el.value().name.local.as_ref().to_string();
     let attributes: HashMap<String, String> = el
@@ -2,14 +2,66 @@
 //!
 //! Scores DOM subtrees by text density, link density, tag semantics,
 //! and class/id keyword hints.  The highest-scoring block is promoted
-//! to `Classification::Content` so layout and paint emphasise it.
+//! to `Classification::Content` so layout and paint emphasise it, and
+//! [`extract_article`] lifts it out of the page for reader mode.
 
-use crate::dom::{Classification, DomNode, NodeType};
+use crate::dom::arena::{DomArena, NodeId};
+use crate::dom::{Classification, DomNode, DomTree, NodeType};
+
+/// Minimum score for a block to count as the main content.
+const MIN_CONTENT_SCORE: f32 = 5.0;
+/// Reading speed for space-separated scripts.
+const WORDS_PER_MINUTE: f32 = 230.0;
+/// Reading speed for CJK text, which has no word spacing.
+const CJK_CHARS_PER_MINUTE: f32 = 500.0;
+
+/// What a block is scored on, read off either DOM.
+struct Block<'a> {
+    tag: &'a str,
+    classification: Classification,
+    id: &'a str,
+    class: &'a str,
+    text_len: usize,
+    text_density: f32,
+    link_density: f32,
+    /// `<p>` children
+    paragraphs: usize,
+}
 
 /// Score a single element node for content-richness.
 fn score_node(node: &DomNode) -> f32 {
-    let text = node.collect_text();
-    let text_len = text.len() as f32;
+    score_block(&Block {
+        tag: &node.tag,
+        classification: node.classification,
+        id: node.attr("id").unwrap_or(""),
+        class: node.attr("class").unwrap_or(""),
+        text_len: node.collect_text().len(),
+        text_density: node.text_density(),
+        link_density: node.link_density(),
+        paragraphs: node.children.iter().filter(|c| c.tag == "p").count(),
+    })
+}
+
+/// Score an element node of `arena`, as [`score_node`].
+fn score_arena_node(arena: &DomArena, id: NodeId) -> f32 {
+    let node = arena.node(id);
+    score_block(&Block {
+        tag: &node.tag,
+        classification: node.classification,
+        id: node.attr("id").unwrap_or(""),
+        class: node.attr("class").unwrap_or(""),
+        text_len: arena.collect_text(id).len(),
+        text_density: arena.text_density(id),
+        link_density: arena.link_density(id),
+        paragraphs: arena
+            .children(id)
+            .filter(|&c| arena.node(c).tag == "p")
+            .count(),
+    })
+}
+
+fn score_block(node: &Block<'_>) -> f32 {
+    let text_len = node.text_len as f32;
 
     if text_len < 25.0 {
         return -1.0;
@@ -21,13 +73,13 @@ fn score_node(node: &DomNode) -> f32 {
     score += text_len.ln().min(8.0);
 
     // Text density bonus
-    score += node.text_density().min(50.0) * 0.3;
+    score += node.text_density.min(50.0) * 0.3;
 
     // Link density penalty
-    score -= node.link_density() * 25.0;
+    score -= node.link_density * 25.0;
 
     // Tag bonuses / penalties
-    match node.tag.as_str() {
+    match node.tag {
         "article" | "main" => score += 10.0,
         "section" => score += 5.0,
         "p" | "blockquote" | "pre" => score += 3.0,
@@ -46,12 +98,7 @@ fn score_node(node: &DomNode) -> f32 {
     }
 
     // ID / class keyword hints
-    let id_class = format!(
-        "{} {}",
-        node.attr("id").unwrap_or(""),
-        node.attr("class").unwrap_or("")
-    )
-    .to_lowercase();
+    let id_class = format!("{} {}", node.id, node.class).to_lowercase();
 
     for kw in &[
         "content",
@@ -74,8 +121,7 @@ fn score_node(node: &DomNode) -> f32 {
     }
 
     // Paragraph count bonus
-    let p_count = node.children.iter().filter(|c| c.tag == "p").count();
-    score += p_count as f32 * 2.0;
+    score += node.paragraphs as f32 * 2.0;
 
     score
 }
@@ -121,22 +167,125 @@ fn mark_content(node: &mut DomNode) {
     }
 }
 
-/// Boost the most content-rich subtree to `Classification::Content`.
-pub fn readability_boost(root: &mut DomNode) {
-    let mut best_score = 5.0f32; // minimum threshold
+/// Path to the most content-rich subtree, if any scores high enough.
+fn best_content_path(root: &DomNode) -> Option<Vec<usize>> {
+    let mut best_score = MIN_CONTENT_SCORE;
     let mut best_path: Vec<usize> = Vec::new();
 
     find_best_path(root, &mut Vec::new(), &mut best_path, &mut best_score);
 
-    if best_path.is_empty() {
+    (!best_path.is_empty()).then_some(best_path)
+}
+
+/// Boost the most content-rich subtree to `Classification::Content`.
+pub fn readability_boost(root: &mut DomNode) {
+    let Some(best_path) = best_content_path(root) else {
         return;
-    }
+    };
 
     if let Some(node) = walk_path_mut(root, &best_path) {
         mark_content(node);
     }
 }
 
+/// Boost the most content-rich subtree of `arena` to
+/// `Classification::Content`, as [`readability_boost`] does for a tree.
+pub fn readability_boost_arena(arena: &mut DomArena) {
+    let root = arena.root();
+    let mut best_score = MIN_CONTENT_SCORE;
+    let mut best = None;
+    for id in arena.descendants(root).into_iter().skip(1) {
+        if arena.node(id).node_type == NodeType::Element {
+            let s = score_arena_node(arena, id);
+            if s > best_score {
+                best_score = s;
+                best = Some(id);
+            }
+        }
+    }
+    let Some(best) = best else {
+        return;
+    };
+    for id in arena.descendants(best) {
+        let node = arena.node_mut(id);
+        if node.classification == Classification::Unknown {
+            node.classification = Classification::Content;
+        }
+    }
+}
+
+// ── Reader mode ──
+
+/// The main article of a page, as shown in reader mode.
+#[derive(Debug, Clone)]
+pub struct Article {
+    pub title: String,
+    /// Copy of the main content subtree
+    pub content: DomNode,
+    pub words: usize,
+    /// Estimated reading time, at least one minute
+    pub minutes: u32,
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF)
+}
+
+/// Word count of `text`; each CJK character counts as a word.
+#[must_use]
+pub fn word_count(text: &str) -> usize {
+    text.split_whitespace()
+        .map(|w| {
+            let cjk = w.chars().filter(|&c| is_cjk(c)).count();
+            let rest = w.chars().any(|c| c.is_alphanumeric() && !is_cjk(c));
+            cjk + usize::from(rest)
+        })
+        .sum()
+}
+
+/// Estimated reading time of `text` in whole minutes (at least one).
+#[must_use]
+pub fn reading_minutes(text: &str) -> u32 {
+    let cjk = text.chars().filter(|&c| is_cjk(c)).count() as f32;
+    let words = word_count(text) as f32 - cjk;
+    let minutes = words / WORDS_PER_MINUTE + cjk / CJK_CHARS_PER_MINUTE;
+    (minutes.ceil() as u32).max(1)
+}
+
+/// Extract the page's main article for reader mode.
+///
+/// Returns `None` if no block scores as main content (link lists, search
+/// pages, empty documents).
+#[must_use]
+pub fn extract_article(tree: &DomTree) -> Option<Article> {
+    let path = best_content_path(&tree.root)?;
+    let mut node = &tree.root;
+    for &idx in &path {
+        node = node.children.get(idx)?;
+    }
+
+    let text = node.collect_text();
+    let title = if tree.title.trim().is_empty() {
+        find_heading(node).unwrap_or_default()
+    } else {
+        tree.title.trim().to_string()
+    };
+    Some(Article {
+        title,
+        content: node.clone(),
+        words: word_count(&text),
+        minutes: reading_minutes(&text),
+    })
+}
+
+fn find_heading(node: &DomNode) -> Option<String> {
+    if node.tag == "h1" {
+        return Some(node.collect_text().trim().to_string());
+    }
+    node.children.iter().find_map(find_heading)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -195,4 +344,31 @@ mod tests {
             Classification::Content
         );
     }
+
+    #[test]
+    fn extracts_main_article() {
+        let html = format!(
+            "<html><head><title>Story</title></head><body>\
+             <nav><a href='/'>Home</a><a href='/a'>About</a></nav>\
+             <article><h1>Story</h1><p>{}</p><p>{}</p></article>\
+             <footer>Copyright</footer></body></html>",
+            "word ".repeat(300),
+            "more ".repeat(300)
+        );
+        let tree = crate::dom::parser::parse_html(&html, "https://example.com/");
+        let article = extract_article(&tree).unwrap();
+        assert_eq!(article.title, "Story");
+        assert_eq!(article.content.tag, "article");
+        assert_eq!(article.words, 601);
+        assert_eq!(article.minutes, 3);
+        assert!(!article.content.collect_text().contains("Copyright"));
+    }
+
+    #[test]
+    fn reading_time_counts_cjk_characters() {
+        assert_eq!(word_count("hello world"), 2);
+        assert_eq!(word_count("日本語の文章 and text"), 8);
+        assert_eq!(reading_minutes(&"あ".repeat(1000)), 2);
+        assert_eq!(reading_minutes(""), 1);
+    }
 }
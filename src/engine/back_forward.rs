@@ -0,0 +1,236 @@
+//! Back/forward cache.
+//!
+//! Pages the user navigates away from are kept, fully processed, with the
+//! position they were scrolled to, under their history index. Going back
+//! or forward to one takes it out of the cache and shows it at once, at
+//! the same reading position, instead of fetching and laying it out again.
+//!
+//! Memory is bounded: each entry is charged its [`PageResult::memory`]
+//! estimate, and the least recently left pages are evicted once the total
+//! passes the budget or more than [`MAX_ENTRIES`] pages are held. Pages
+//! larger than the whole budget are not kept. Under memory pressure the
+//! browser also evicts with [`BackForwardCache::shrink_by`].
+
+use crate::engine::pipeline::PageResult;
+
+/// Default memory budget for cached pages.
+pub const DEFAULT_BUDGET: usize = 96 * 1024 * 1024;
+
+/// Most pages kept, however small.
+pub const MAX_ENTRIES: usize = 16;
+
+/// A page left by navigating away, with where it was scrolled to.
+pub struct CachedPage {
+    /// History index of the page
+    pub index: usize,
+    /// History entry the page was loaded for (before any redirect)
+    pub url: String,
+    pub page: PageResult,
+    /// Top of the visible region, in layout pixels
+    pub scroll: f32,
+    /// Approximate bytes held (see [`PageResult::memory`])
+    pub bytes: usize,
+}
+
+/// Processed pages by history index, least recently left first.
+pub struct BackForwardCache {
+    entries: Vec<CachedPage>,
+    budget: usize,
+}
+
+impl Default for BackForwardCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUDGET)
+    }
+}
+
+impl BackForwardCache {
+    /// Empty cache holding up to `budget` bytes of pages.
+    #[must_use]
+    pub const fn new(budget: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            budget,
+        }
+    }
+
+    /// Keep `page`, scrolled to `scroll`, as history entry `index` for
+    /// `url`, replacing what was kept for that entry. Returns `false` if the
+    /// page alone exceeds the budget and was dropped.
+    pub fn store(&mut self, index: usize, url: &str, page: PageResult, scroll: f32) -> bool {
+        self.entries.retain(|e| e.index != index);
+        let bytes = page.memory().total();
+        if bytes > self.budget {
+            return false;
+        }
+        self.entries.push(CachedPage {
+            index,
+            url: url.to_string(),
+            page,
+            scroll,
+            bytes,
+        });
+        while self.entries.len() > MAX_ENTRIES || self.bytes() > self.budget {
+            self.entries.remove(0);
+        }
+        true
+    }
+
+    /// Take the page kept for history entry `index`, if it was loaded for
+    /// `url`.
+    pub fn take(&mut self, index: usize, url: &str) -> Option<CachedPage> {
+        let pos = self
+            .entries
+            .iter()
+            .position(|e| e.index == index && e.url == url)?;
+        Some(self.entries.remove(pos))
+    }
+
+    /// Forget entries from history index `len` on, when the forward
+    /// history is replaced.
+    pub fn truncate(&mut self, len: usize) {
+        self.entries.retain(|e| e.index < len);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Evict the least recently left pages until at least `bytes` are
+    /// freed, or none are left. Returns the bytes freed.
+    pub fn shrink_by(&mut self, bytes: usize) -> usize {
+        let mut freed = 0;
+        while freed < bytes && !self.entries.is_empty() {
+            freed += self.entries.remove(0).bytes;
+        }
+        freed
+    }
+
+    /// Whether history entry `index` is kept.
+    #[must_use]
+    pub fn contains(&self, index: usize) -> bool {
+        self.entries.iter().any(|e| e.index == index)
+    }
+
+    /// Where the page kept as history entry `index` was scrolled to.
+    #[must_use]
+    pub fn scroll(&self, index: usize) -> Option<f32> {
+        self.entries
+            .iter()
+            .find(|e| e.index == index)
+            .map(|e| e.scroll)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Approximate bytes held by all kept pages.
+    #[must_use]
+    pub fn bytes(&self) -> usize {
+        self.entries.iter().map(|e| e.bytes).sum()
+    }
+
+    #[must_use]
+    pub const fn budget(&self) -> usize {
+        self.budget
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::pipeline::BrowserEngine;
+
+    fn page(url: &str, paragraphs: usize) -> PageResult {
+        let html = format!(
+            "<div>{}</div>",
+            "<p>lorem ipsum dolor</p>".repeat(paragraphs)
+        );
+        BrowserEngine::new(800.0)
+            .process_html(&html, url, 200)
+            .ok()
+            .unwrap()
+    }
+
+    #[test]
+    fn pages_come_back_with_their_scroll_position() {
+        let mut cache = BackForwardCache::default();
+        // Entries are matched by the address navigated to, not where it
+        // redirected
+        assert!(cache.store(
+            0,
+            "http://example.com/a",
+            page("https://example.com/a", 5),
+            420.0
+        ));
+        let b = "https://example.com/b";
+        assert!(cache.store(1, b, page(b, 5), 0.0));
+        assert_eq!(cache.len(), 2);
+        assert!(cache
+            .scroll(0)
+            .is_some_and(|y| (y - 420.0).abs() < f32::EPSILON));
+        assert_eq!(cache.scroll(2), None);
+
+        // A different page at the same index is not a hit
+        assert!(cache.take(0, "https://example.com/other").is_none());
+        let hit = cache.take(0, "http://example.com/a").unwrap();
+        assert!((hit.scroll - 420.0).abs() < f32::EPSILON);
+        assert_eq!(hit.page.dom.url, "https://example.com/a");
+        assert!(!cache.contains(0));
+
+        // Following a link drops the forward history
+        cache.truncate(1);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn least_recently_left_pages_are_evicted_over_budget() {
+        let one = page("https://example.com/0", 200).memory().total();
+        let mut cache = BackForwardCache::new(one * 5 / 2);
+        for i in 0..3 {
+            let url = format!("https://example.com/{i}");
+            assert!(cache.store(i, &url, page(&url, 200), 0.0));
+        }
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains(0) && cache.contains(1) && cache.contains(2));
+        assert!(cache.bytes() <= cache.budget());
+
+        // A page larger than the whole budget is not kept
+        let big = "https://example.com/big";
+        assert!(!cache.store(3, big, page(big, 2000), 0.0));
+        assert!(!cache.contains(3));
+
+        let mut small = BackForwardCache::default();
+        for i in 0..MAX_ENTRIES + 2 {
+            let url = format!("https://example.com/{i}");
+            small.store(i, &url, page(&url, 1), 0.0);
+        }
+        assert_eq!(small.len(), MAX_ENTRIES);
+        assert!(!small.contains(0) && small.contains(MAX_ENTRIES + 1));
+    }
+
+    #[test]
+    fn shrinking_evicts_least_recently_left_first() {
+        let mut cache = BackForwardCache::default();
+        for i in 0..3 {
+            let url = format!("https://example.com/{i}");
+            cache.store(i, &url, page(&url, 50), 0.0);
+        }
+        let total = cache.bytes();
+        let freed = cache.shrink_by(1);
+        assert!(freed > 0);
+        assert_eq!(cache.bytes(), total - freed);
+        assert!(!cache.contains(0) && cache.contains(1) && cache.contains(2));
+
+        assert_eq!(cache.shrink_by(0), 0);
+        assert_eq!(cache.shrink_by(usize::MAX), total - freed);
+        assert!(cache.is_empty());
+    }
+}
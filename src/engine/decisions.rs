@@ -0,0 +1,282 @@
+//! Per-page-load decision log.
+//!
+//! Records why the pipeline did what it did for one page: which DOM
+//! subtrees the semantic filter removed (and by which rule or classifier
+//! score), which subresource requests the ad blocker refuses, and how the
+//! page cache answered. The log travels with the `PageResult`, is queried by
+//! the browser's page inspector and can be exported as JSON.
+
+use serde::Serialize;
+
+use crate::dom::filter::{self, RemovalReason};
+use crate::dom::{Classification, DomNode};
+use crate::engine::frames::FrameLoad;
+use crate::net::adblock::AdBlockEngine;
+use crate::simd::adblock::BatchMatcher;
+
+/// Longest text excerpt kept per removed subtree.
+const MAX_EXCERPT: usize = 80;
+
+/// How the page's HTML was obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheDecision {
+    /// HTML handed to the pipeline directly (no request made)
+    #[default]
+    NotFetched,
+    /// No page cache configured
+    NoCache,
+    /// Served from the page cache
+    Hit,
+    /// Fetched from the network and stored in the cache
+    Miss,
+    /// Never cached (POST form responses)
+    Bypassed,
+}
+
+/// A subresource request refused by the ad blocker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlockedRequest {
+    pub url: String,
+    /// Matching rule, e.g. `domain:doubleclick.net`
+    pub rule: String,
+    /// Element that referenced the URL
+    pub tag: String,
+}
+
+/// A subtree removed by the semantic filter.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Removal {
+    /// Selector-like path to the removed element, e.g.
+    /// `html > body > div#promo.ad-slot`
+    pub path: String,
+    pub tag: String,
+    pub classification: String,
+    pub reason: RemovalReason,
+    /// Nodes in the removed subtree
+    pub nodes: usize,
+    /// Start of the removed text
+    pub excerpt: String,
+}
+
+impl Removal {
+    /// One-line explanation for the inspector.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        match self.reason {
+            RemovalReason::Rule { ref rule } => {
+                format!("{} (rule {rule})", self.classification)
+            }
+            RemovalReason::Classifier { ref model, score } => {
+                format!("{} ({model} score {score:.2})", self.classification)
+            }
+        }
+    }
+}
+
+/// Everything the pipeline decided while loading one page.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LoadLog {
+    pub url: String,
+    /// Classifier backend used for the semantic filter
+    pub classifier: String,
+    pub cache: CacheDecision,
+    pub blocked: Vec<BlockedRequest>,
+    /// The site is on the ad-block allowlist: nothing was blocked, and
+    /// `removed` lists what would have been removed
+    pub allowlisted: bool,
+    pub removed: Vec<Removal>,
+    /// `<iframe>` documents fetched for embedding, each filtered on its
+    /// own (see [`crate::engine::frames`])
+    pub frames: Vec<FrameLoad>,
+}
+
+impl LoadLog {
+    /// Pretty-printed JSON export.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// "Why was this element removed?" — removals whose path or text
+    /// contains `query` (case-insensitive). An empty query matches all.
+    #[must_use]
+    pub fn why_removed(&self, query: &str) -> Vec<&Removal> {
+        let query = query.trim().to_lowercase();
+        self.removed
+            .iter()
+            .filter(|r| {
+                query.is_empty()
+                    || r.path.to_lowercase().contains(&query)
+                    || r.excerpt.to_lowercase().contains(&query)
+            })
+            .collect()
+    }
+}
+
+/// `tag#id.class1.class2` label of an element.
+fn node_label(node: &DomNode) -> String {
+    filter::selector(node).unwrap_or_else(|| node.tag.clone())
+}
+
+fn excerpt(node: &DomNode) -> String {
+    let text = node.collect_text();
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    flat.chars().take(MAX_EXCERPT).collect()
+}
+
+/// Record every subtree of a classified (not yet pruned) tree that pruning
+/// will remove. Only the topmost removed node of a subtree is recorded.
+pub fn record_removals(
+    root: &DomNode,
+    explain: &dyn Fn(&DomNode) -> RemovalReason,
+    out: &mut Vec<Removal>,
+) {
+    // The document node itself is never pruned
+    for child in &root.children {
+        walk(child, "", explain, out);
+    }
+}
+
+fn walk(
+    node: &DomNode,
+    parent: &str,
+    explain: &dyn Fn(&DomNode) -> RemovalReason,
+    out: &mut Vec<Removal>,
+) {
+    let label = node_label(node);
+    let path = if parent.is_empty() {
+        label
+    } else {
+        format!("{parent} > {label}")
+    };
+    if matches!(
+        node.classification,
+        Classification::Advertisement | Classification::Tracker
+    ) {
+        out.push(Removal {
+            tag: node.tag.clone(),
+            classification: format!("{:?}", node.classification),
+            reason: explain(node),
+            nodes: node.node_count(),
+            excerpt: excerpt(node),
+            path,
+        });
+        return;
+    }
+    for child in &node.children {
+        walk(child, &path, explain, out);
+    }
+}
+
+/// Subresources (`src` attributes) of the page that `adblock` refuses,
+/// decided in one batch (see [`BatchMatcher`]).
+#[must_use]
+pub fn blocked_requests(
+    root: &DomNode,
+    base: &str,
+    adblock: &AdBlockEngine,
+) -> Vec<BlockedRequest> {
+    let base = url::Url::parse(base).ok();
+    let mut sources = Vec::new();
+    collect_sources(root, base.as_ref(), &mut sources);
+    let urls: Vec<&str> = sources.iter().map(|(_, url)| url.as_str()).collect();
+    let bitmap = BatchMatcher::new(adblock).classify(&urls);
+    bitmap
+        .blocked()
+        .filter_map(|i| {
+            let (tag, url) = &sources[i];
+            let (kind, pattern) = adblock.matching_rule(url)?;
+            Some(BlockedRequest {
+                rule: format!("{}:{pattern}", kind.as_str()),
+                tag: tag.clone(),
+                url: url.clone(),
+            })
+        })
+        .collect()
+}
+
+/// `(tag, resolved URL)` of every `src` attribute, in document order.
+fn collect_sources(node: &DomNode, base: Option<&url::Url>, out: &mut Vec<(String, String)>) {
+    if let Some(src) = node.attr("src") {
+        let resolved = match base {
+            Some(b) => b.join(src).map(String::from).ok(),
+            None => url::Url::parse(src).map(String::from).ok(),
+        };
+        if let Some(url) = resolved {
+            out.push((node.tag.clone(), url));
+        }
+    }
+    for child in &node.children {
+        collect_sources(child, base, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::filter::{removal_rule, SemanticFilter};
+    use crate::dom::parser::parse_html;
+
+    const HTML: &str = r#"<html><body>
+        <div id="main"><p>Readable article text.</p></div>
+        <aside class="ad-slot"><p>Buy now</p><img src="https://doubleclick.net/px.gif"></aside>
+        <script src="/analytics.js"></script>
+    </body></html>"#;
+
+    fn log() -> LoadLog {
+        let mut tree = parse_html(HTML, "https://example.com/a");
+        let _ = SemanticFilter::new().classify(&mut tree);
+        let mut log = LoadLog {
+            url: tree.url.clone(),
+            blocked: blocked_requests(&tree.root, &tree.url, &AdBlockEngine::new()),
+            ..LoadLog::default()
+        };
+        record_removals(
+            &tree.root,
+            &|n| RemovalReason::Rule {
+                rule: removal_rule(n),
+            },
+            &mut log.removed,
+        );
+        log
+    }
+
+    #[test]
+    fn records_topmost_removals_with_rules() {
+        let log = log();
+        let ad = log
+            .removed
+            .iter()
+            .find(|r| r.tag == "aside")
+            .expect("ad removed");
+        assert!(ad.path.ends_with("body > aside.ad-slot"));
+        assert_eq!(
+            ad.reason,
+            RemovalReason::Rule {
+                rule: "ad-pattern:ad".into()
+            }
+        );
+        assert_eq!(ad.excerpt, "Buy now");
+        // Children of a removed subtree are not listed separately
+        assert!(!log.removed.iter().any(|r| r.tag == "img"));
+        assert!(log.removed.iter().any(|r| r.tag == "script"));
+    }
+
+    #[test]
+    fn query_and_export() {
+        let log = log();
+        assert_eq!(log.why_removed("ad-slot").len(), 1);
+        assert_eq!(log.why_removed("buy NOW").len(), 1);
+        assert!(log.why_removed("main").is_empty());
+
+        assert!(log
+            .blocked
+            .iter()
+            .any(|b| b.tag == "img" && b.rule == "domain:doubleclick.net"));
+
+        let json: serde_json::Value = serde_json::from_str(&log.to_json()).unwrap();
+        assert_eq!(json["cache"], "not_fetched");
+        assert_eq!(json["removed"][0]["reason"]["kind"], "rule");
+    }
+}
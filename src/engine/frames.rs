@@ -0,0 +1,297 @@
+//! Embedded documents (`<iframe>`).
+//!
+//! Frames are not loaded by default: the element stays empty and nothing
+//! of the embedded page is shown. With a [`FramePolicy`] enabled, the
+//! pipeline fetches the documents of same-origin frames, and of frames from
+//! allowed hosts, puts each through the semantic filter on its own and
+//! grafts what is left into the `<iframe>` element. Layout then treats the
+//! frame as a block of the page, drawn with a border and headed by a
+//! caption naming its origin and what its filter removed.
+//!
+//! At most [`MAX_FRAMES`] frames are embedded per page, and frames inside
+//! embedded documents are not followed. A page's frames are fetched side
+//! by side through a scheduler of their own ([`fetch_scheduler`]).
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use url::Url;
+
+use crate::dom::filter::FilterStats;
+use crate::dom::{Classification, DomNode};
+use crate::engine::workers::WorkerPool;
+use crate::net::scheduler::{Scheduler, MAX_PER_HOST};
+
+/// Most frames embedded into one page.
+pub const MAX_FRAMES: usize = 6;
+
+/// Frame documents fetched at once, for all pages together.
+pub const FRAME_FETCHES: usize = MAX_FRAMES;
+
+/// Scheduler frame documents are fetched through, on a pool of its own. It
+/// is kept apart from the shared [`scheduler`](crate::net::scheduler) and
+/// its pool: a page load waits there for its frames, and frames queued
+/// behind such loads might never get a thread.
+#[must_use]
+pub fn fetch_scheduler() -> &'static Scheduler {
+    static SCHEDULER: OnceLock<Scheduler> = OnceLock::new();
+    SCHEDULER.get_or_init(|| {
+        Scheduler::with_pool(FRAME_FETCHES, MAX_PER_HOST, WorkerPool::new(FRAME_FETCHES))
+    })
+}
+
+/// Which frames are fetched and embedded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FramePolicy {
+    /// Embed frames at all (default: off)
+    pub enabled: bool,
+    /// Hosts whose frames are embedded into any page, besides same-origin
+    /// frames; subdomains included
+    pub allowed_hosts: BTreeSet<String>,
+}
+
+impl FramePolicy {
+    /// Policy embedding same-origin frames and those from the hosts in
+    /// `hosts`, separated by commas or whitespace.
+    #[must_use]
+    pub fn new(enabled: bool, hosts: &str) -> Self {
+        Self {
+            enabled,
+            allowed_hosts: hosts
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .map(|h| h.trim().trim_start_matches("*.").to_ascii_lowercase())
+                .filter(|h| !h.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Whether the frame at `frame` is embedded into the page at `page`.
+    #[must_use]
+    pub fn allows(&self, page: &Url, frame: &Url) -> bool {
+        if !self.enabled || !matches!(frame.scheme(), "http" | "https") {
+            return false;
+        }
+        if frame.origin() == page.origin() {
+            return true;
+        }
+        let host = frame.host_str().unwrap_or_default().to_ascii_lowercase();
+        self.allowed_hosts.iter().any(|allowed| {
+            host == *allowed
+                || host
+                    .strip_suffix(allowed.as_str())
+                    .is_some_and(|rest| rest.ends_with('.'))
+        })
+    }
+}
+
+/// One frame the pipeline tried to embed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FrameLoad {
+    pub url: String,
+    /// Why the frame is not shown, if it is not
+    pub error: Option<String>,
+    /// The frame's own filter counts
+    pub filter_stats: FilterStats,
+    /// Subtrees its filter removed
+    pub removed: usize,
+    /// Time spent fetching and processing it
+    pub ms: f32,
+}
+
+impl FrameLoad {
+    /// A frame that could not be embedded.
+    #[must_use]
+    pub fn failed(url: &Url, error: impl Into<String>) -> Self {
+        Self {
+            url: url.to_string(),
+            error: Some(error.into()),
+            filter_stats: FilterStats::default(),
+            removed: 0,
+            ms: 0.0,
+        }
+    }
+
+    /// e.g. "3 ads, 1 tracker removed of 412 nodes"
+    #[must_use]
+    pub fn summary(&self) -> String {
+        if let Some(ref error) = self.error {
+            return error.clone();
+        }
+        let s = &self.filter_stats;
+        let plural = |n: usize, what: &str| format!("{n} {what}{}", if n == 1 { "" } else { "s" });
+        format!(
+            "{}, {} removed of {}",
+            plural(s.ad_nodes, "ad"),
+            plural(s.tracker_nodes, "tracker"),
+            plural(s.total_nodes, "node")
+        )
+    }
+}
+
+/// `<iframe>` elements under `node` that `policy` lets in, with their
+/// documents' addresses, in document order and at most [`MAX_FRAMES`].
+pub(crate) fn embeddable<'a>(
+    node: &'a mut DomNode,
+    page: &Url,
+    policy: &FramePolicy,
+    out: &mut Vec<(&'a mut DomNode, Url)>,
+) {
+    if out.len() >= MAX_FRAMES || !node.is_visible() {
+        return;
+    }
+    if node.tag == "iframe" {
+        let src = node
+            .attr("src")
+            .and_then(|src| page.join(src.trim()).ok())
+            .filter(|url| policy.allows(page, url));
+        if let Some(url) = src {
+            out.push((node, url));
+        }
+        return;
+    }
+    for child in &mut node.children {
+        embeddable(child, page, policy, out);
+    }
+}
+
+/// Resolve the `href` and `src` attributes under `node` against `base`, so
+/// the frame's links and images still work inside the page.
+pub(crate) fn absolutize(node: &mut DomNode, base: &Url) {
+    for name in ["href", "src"] {
+        if let Some(value) = node.attributes.get_mut(name) {
+            if let Ok(url) = base.join(value.trim()) {
+                *value = url.to_string();
+            }
+        }
+    }
+    for child in &mut node.children {
+        absolutize(child, base);
+    }
+}
+
+/// Replace the children of `frame` with the body of the filtered embedded
+/// `document`, headed by a caption for `load`.
+pub(crate) fn graft(frame: &mut DomNode, document: DomNode, load: &FrameLoad) {
+    let body = into_body(document);
+    let origin = Url::parse(&load.url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| load.url.clone());
+    let mut caption = DomNode::element(
+        "p",
+        HashMap::from([("class".to_string(), "alice-frame-caption".to_string())]),
+        vec![DomNode::text(format!(
+            "Embedded from {origin} \u{2014} {}",
+            load.summary()
+        ))],
+    );
+    caption.classification = Classification::Content;
+    frame.children = std::iter::once(caption).chain(body.children).collect();
+}
+
+fn into_body(mut document: DomNode) -> DomNode {
+    if document.tag == "body" {
+        return document;
+    }
+    match document.children.iter().position(|c| c.tag == "body") {
+        Some(i) => document.children.swap_remove(i),
+        None => document,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+    use crate::render::layout::{compute_layout, LayoutNode};
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn same_origin_and_allowed_hosts() {
+        let page = url("https://news.example/story");
+        let policy = FramePolicy::new(true, "maps.example, *.video.example");
+        assert!(policy.allows(&page, &url("https://news.example/chart.html")));
+        assert!(policy.allows(&page, &url("https://maps.example/embed")));
+        assert!(policy.allows(&page, &url("https://www.video.example/e/1")));
+        assert!(!policy.allows(&page, &url("https://evilmaps.example/embed")));
+        assert!(!policy.allows(&page, &url("http://news.example/chart.html")));
+        assert!(!policy.allows(&page, &url("javascript:alert(1)")));
+
+        let off = FramePolicy::new(false, "maps.example");
+        assert!(!off.allows(&page, &url("https://news.example/chart.html")));
+    }
+
+    fn find<'a>(node: &'a DomNode, tag: &str) -> Option<&'a DomNode> {
+        if node.tag == tag {
+            return Some(node);
+        }
+        node.children.iter().find_map(|c| find(c, tag))
+    }
+
+    fn find_layout<'a>(node: &'a LayoutNode, tag: &str) -> Vec<&'a LayoutNode> {
+        let mut out: Vec<&LayoutNode> = node
+            .children
+            .iter()
+            .flat_map(|c| find_layout(c, tag))
+            .collect();
+        if node.tag == tag {
+            out.insert(0, node);
+        }
+        out
+    }
+
+    #[test]
+    fn embedded_documents_become_captioned_blocks() {
+        let mut page = parse_html(
+            r#"<body><p>Story</p><iframe src="/chart.html"></iframe>
+            <iframe src="https://ads.example/slot"></iframe></body>"#,
+            "https://news.example/story",
+        );
+        let base = url(&page.url);
+        let policy = FramePolicy::new(true, "");
+        let mut slots = Vec::new();
+        embeddable(&mut page.root, &base, &policy, &mut slots);
+        assert_eq!(slots.len(), 1);
+        let (frame, frame_url) = slots.pop().unwrap();
+        assert_eq!(frame_url.as_str(), "https://news.example/chart.html");
+
+        let mut document = parse_html(
+            r#"<html><body><h2>Chart</h2><a href="data.csv">Data</a></body></html>"#,
+            frame_url.as_str(),
+        );
+        absolutize(&mut document.root, &frame_url);
+        let load = FrameLoad {
+            url: frame_url.to_string(),
+            error: None,
+            filter_stats: FilterStats {
+                total_nodes: 6,
+                ad_nodes: 1,
+                ..FilterStats::default()
+            },
+            removed: 1,
+            ms: 0.0,
+        };
+        graft(frame, document.root, &load);
+
+        let iframe = find(&page.root, "iframe").unwrap();
+        assert_eq!(
+            iframe.children[0].collect_text(),
+            "Embedded from news.example \u{2014} 1 ad, 0 trackers removed of 6 nodes"
+        );
+        assert_eq!(
+            find(iframe, "a").and_then(|a| a.attr("href")),
+            Some("https://news.example/data.csv")
+        );
+
+        // The embedded frame is a padded block; the other stays empty
+        let layout = compute_layout(&page.root, 800.0);
+        let frames = find_layout(&layout, "iframe");
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].is_block && frames[0].bounds.height > 40.0);
+        assert!(!frames[1].is_block);
+    }
+}
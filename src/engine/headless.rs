@@ -0,0 +1,259 @@
+//! Rendering pages to images without a window.
+//!
+//! [`render_to_png`] loads a page through the pipeline and rasterizes its
+//! first screen on the CPU: the 2-D views are painted by the same painter
+//! as on screen ([`SdfPaintState`]) into a software canvas
+//! ([`crate::render::raster`]), and the 3-D view is traced by the CPU
+//! raymarcher (feature `sdf-render`). No eframe context or GPU is needed,
+//! so CI can compare screenshots against golden images and page thumbnails
+//! can be made in the background. Text is set in egui's built-in fonts
+//! only, so scripts outside them show as boxes.
+
+use std::collections::HashMap;
+
+use image::ImageEncoder;
+
+use crate::engine::pipeline::{BrowserEngine, PageError, PageResult};
+use crate::engine::snapshot;
+use crate::render::raster::Canvas;
+use crate::render::sdf_paint::SdfPaintState;
+use crate::render::sdf_ui::layout_to_paint;
+use crate::render::thumbnail::{
+    downscale_rgba, sketch, Thumbnail, THUMB_MAX_HEIGHT, THUMB_MAX_WIDTH,
+};
+use crate::render::RenderMode;
+
+/// Background of the painted 2-D views, behind the page.
+const PAGE_BACKGROUND: egui::Color32 = egui::Color32::from_rgb(250, 250, 252);
+
+/// An RGBA frame of a page.
+#[derive(Debug, Clone)]
+pub struct Screenshot {
+    pub width: u32,
+    pub height: u32,
+    /// Straight RGBA, rows top to bottom
+    pub rgba: Vec<u8>,
+}
+
+impl Screenshot {
+    /// Encode as PNG.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PageError` (phase `"encode"`) if encoding fails.
+    pub fn to_png(&self) -> Result<Vec<u8>, PageError> {
+        let mut png = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut png)
+            .write_image(
+                &self.rgba,
+                self.width,
+                self.height,
+                image::ExtendedColorType::Rgba8,
+            )
+            .map_err(|e| PageError {
+                message: format!("Cannot encode PNG: {e}"),
+                phase: "encode",
+            })?;
+        Ok(png)
+    }
+}
+
+/// Load `url` with a default engine whose viewport is `width` × `height`
+/// and render it in `mode` as a PNG.
+///
+/// # Errors
+///
+/// Returns `PageError` if the page fails to load, `mode` cannot be
+/// rendered headless (see [`render_page`]) or encoding fails.
+pub fn render_to_png(
+    url: &str,
+    width: u32,
+    height: u32,
+    mode: RenderMode,
+) -> Result<Vec<u8>, PageError> {
+    let engine = BrowserEngine::builder()
+        .viewport(width as f32, height as f32)
+        .build();
+    render_to_png_with(&engine, url, width, height, mode)
+}
+
+/// [`render_to_png`] with a configured engine — an offline
+/// [`FetchBackend`](crate::net::backend::FetchBackend), an ad blocker, a
+/// viewport other than the image size. `file://` URLs open saved pages.
+///
+/// # Errors
+///
+/// Same as [`render_to_png`].
+pub fn render_to_png_with(
+    engine: &BrowserEngine,
+    url: &str,
+    width: u32,
+    height: u32,
+    mode: RenderMode,
+) -> Result<Vec<u8>, PageError> {
+    let mut page = if snapshot::is_file_url(url) {
+        engine.load_file(url)?
+    } else {
+        engine.load_page(url)?
+    };
+    render_page(&mut page, width, height, mode)?.to_png()
+}
+
+/// Rasterize the top `width` × `height` pixels of a loaded page: Flat and
+/// SDF 2D through the software painter, Spatial3D through the CPU
+/// raymarcher with the camera framing the whole page.
+///
+/// # Errors
+///
+/// Returns `PageError` (phase `"render"`) for the OZ and Reader views,
+/// which have no headless renderer, for Spatial3D without the
+/// `sdf-render` feature, and for an empty size.
+pub fn render_page(
+    page: &mut PageResult,
+    width: u32,
+    height: u32,
+    mode: RenderMode,
+) -> Result<Screenshot, PageError> {
+    if width == 0 || height == 0 {
+        return Err(render_error(format!("Empty image size {width}×{height}")));
+    }
+    let rgba = match mode {
+        RenderMode::Flat | RenderMode::Sdf2D => paint(page, width, height),
+        RenderMode::Spatial3D => raymarch(page, width, height)?,
+        RenderMode::OzMode | RenderMode::Reader => {
+            return Err(render_error(format!(
+                "{} cannot be rendered headless",
+                mode.label()
+            )));
+        }
+    };
+    Ok(Screenshot {
+        width,
+        height,
+        rgba,
+    })
+}
+
+/// Thumbnail of a loaded page: its first screen at the page's layout
+/// width, painted as in the 2-D views and downscaled. Falls back to a
+/// [`sketch`] if nothing could be painted.
+#[must_use]
+pub fn thumbnail(page: &PageResult) -> Thumbnail {
+    let width = page.viewport_width.round().clamp(320.0, 1600.0) as usize;
+    let height = width * THUMB_MAX_HEIGHT / THUMB_MAX_WIDTH;
+    let rgba = paint(page, width as u32, height as u32);
+    downscale_rgba(&rgba, width, height, THUMB_MAX_WIDTH, THUMB_MAX_HEIGHT)
+        .unwrap_or_else(|| sketch(&layout_to_paint(&page.layout), page.layout.bounds.width))
+}
+
+/// The page's paint elements, drawn by a headless egui pass.
+fn paint(page: &PageResult, width: u32, height: u32) -> Vec<u8> {
+    let elements = layout_to_paint(&page.layout);
+    let ctx = egui::Context::default();
+    let input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(width as f32, height as f32),
+        )),
+        ..egui::RawInput::default()
+    };
+    let mut state = SdfPaintState::new();
+    let output = ctx.run(input, |ctx| {
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none())
+            .show(ctx, |ui| {
+                state.paint(ui, ctx, &elements, false, &HashMap::new());
+            });
+    });
+    let mut canvas = Canvas::new(width as usize, height as usize, PAGE_BACKGROUND);
+    canvas.paint(&ctx, output);
+    canvas.rgba
+}
+
+/// The Spatial3D scene of the whole page, raymarched.
+#[cfg(feature = "sdf-render")]
+fn raymarch(page: &mut PageResult, width: u32, height: u32) -> Result<Vec<u8>, PageError> {
+    use crate::render::sdf_renderer::{auto_camera, render_sdf_interactive};
+    use crate::render::spatial::{layout_to_spatial, SpatialConfig};
+
+    page.complete_layout();
+    let config = SpatialConfig::default();
+    let scene = layout_to_spatial(&page.layout, &config);
+    render_sdf_interactive(
+        &scene,
+        width as usize,
+        height as usize,
+        &auto_camera(&scene),
+        &config.palette.sky,
+    )
+    .ok_or_else(|| render_error("Nothing to render in 3D".into()))
+}
+
+#[cfg(not(feature = "sdf-render"))]
+fn raymarch(_page: &mut PageResult, _width: u32, _height: u32) -> Result<Vec<u8>, PageError> {
+    Err(render_error(
+        "3D Spatial needs the sdf-render feature".into(),
+    ))
+}
+
+fn render_error(message: String) -> PageError {
+    PageError {
+        message,
+        phase: "render",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::net::backend::StaticBackend;
+
+    const PAGE: &str = "<html><head><title>Shot</title></head><body><article>\
+        <h1>Headless</h1><p>Rendered without a window.</p></article></body></html>";
+
+    fn engine() -> BrowserEngine {
+        let backend = Arc::new(StaticBackend::new());
+        backend.insert("https://example.com/", PAGE, "text/html");
+        BrowserEngine::builder()
+            .viewport(320.0, 240.0)
+            .backend(backend)
+            .build()
+    }
+
+    #[test]
+    fn renders_flat_page_to_png() {
+        let png = render_to_png_with(
+            &engine(),
+            "https://example.com/",
+            320,
+            240,
+            RenderMode::Flat,
+        )
+        .ok()
+        .unwrap();
+        let image = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (320, 240));
+        // Heading and text leave dark pixels on the light page
+        assert!(image.pixels().any(|p| p.0[0] < 100));
+    }
+
+    #[test]
+    fn thumbnail_fits_bounds() {
+        let page = engine().load_page("https://example.com/").ok().unwrap();
+        let thumb = thumbnail(&page);
+        assert!(thumb.width <= THUMB_MAX_WIDTH && thumb.height <= THUMB_MAX_HEIGHT);
+        assert_eq!(thumb.rgba.len(), thumb.width * thumb.height * 4);
+    }
+
+    #[test]
+    fn rejects_views_without_headless_renderer() {
+        let mut page = engine().load_page("https://example.com/").ok().unwrap();
+        let err = render_page(&mut page, 320, 240, RenderMode::Reader)
+            .err()
+            .unwrap();
+        assert_eq!(err.phase, "render");
+        assert!(render_page(&mut page, 0, 240, RenderMode::Flat).is_err());
+    }
+}
@@ -0,0 +1,332 @@
+//! Built-in `alice://` pages.
+//!
+//! Management surfaces (settings, history, cache, statistics) are served as
+//! generated HTML under the `alice://` scheme and run through the normal
+//! pipeline, so each one has a URL that can be bookmarked, linked to from
+//! other internal pages and loaded in tests. This module parses internal
+//! URLs and provides [`HtmlPage`], a small builder for the generated
+//! documents; the browser supplies the data.
+
+use std::fmt::Write as _;
+
+/// URL scheme of the internal pages.
+pub const SCHEME: &str = "alice";
+
+/// The internal pages the browser knows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InternalPage {
+    /// Index of all internal pages
+    About,
+    Settings,
+    History,
+    Cache,
+    Stats,
+    /// First-run demo page
+    Welcome,
+}
+
+impl InternalPage {
+    pub const ALL: [Self; 6] = [
+        Self::About,
+        Self::Settings,
+        Self::History,
+        Self::Cache,
+        Self::Stats,
+        Self::Welcome,
+    ];
+
+    /// Host part of the page's URL (`alice://<host>`).
+    #[must_use]
+    pub const fn host(self) -> &'static str {
+        match self {
+            Self::About => "about",
+            Self::Settings => "settings",
+            Self::History => "history",
+            Self::Cache => "cache",
+            Self::Stats => "stats",
+            Self::Welcome => "welcome",
+        }
+    }
+
+    #[must_use]
+    pub const fn title(self) -> &'static str {
+        match self {
+            Self::About => "Internal pages",
+            Self::Settings => "Settings",
+            Self::History => "History",
+            Self::Cache => "Cache",
+            Self::Stats => "Statistics",
+            Self::Welcome => "Welcome",
+        }
+    }
+
+    #[must_use]
+    pub fn url(self) -> String {
+        format!("{SCHEME}://{}", self.host())
+    }
+
+    #[must_use]
+    pub fn from_host(host: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.host().eq_ignore_ascii_case(host))
+    }
+}
+
+/// A parsed `alice://` URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InternalRequest {
+    /// `None` for an unknown host
+    pub page: Option<InternalPage>,
+    pub host: String,
+    /// Decoded query parameters, in order
+    pub query: Vec<(String, String)>,
+}
+
+impl InternalRequest {
+    /// First value of query parameter `key`.
+    #[must_use]
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Whether `url` belongs to the internal scheme.
+#[must_use]
+pub fn is_internal(url: &str) -> bool {
+    url.trim()
+        .split_once("://")
+        .is_some_and(|(scheme, _)| scheme.eq_ignore_ascii_case(SCHEME))
+}
+
+/// Parse an internal URL; `None` if `url` is not `alice://…`.
+#[must_use]
+pub fn parse(url: &str) -> Option<InternalRequest> {
+    if !is_internal(url) {
+        return None;
+    }
+    let parsed = url::Url::parse(url.trim()).ok()?;
+    let host = parsed.host_str().unwrap_or("about").to_ascii_lowercase();
+    Some(InternalRequest {
+        page: InternalPage::from_host(&host),
+        query: parsed.query_pairs().into_owned().collect(),
+        host,
+    })
+}
+
+/// Escape text for HTML content and attribute values.
+#[must_use]
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// One table cell of a generated page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cell {
+    Text(String),
+    Link { href: String, label: String },
+}
+
+impl Cell {
+    #[must_use]
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text(text.into())
+    }
+
+    #[must_use]
+    pub fn link(href: impl Into<String>, label: impl Into<String>) -> Self {
+        Self::Link {
+            href: href.into(),
+            label: label.into(),
+        }
+    }
+
+    fn write(&self, out: &mut String) {
+        match self {
+            Self::Text(text) => out.push_str(&escape(text)),
+            Self::Link { href, label } => {
+                let _ = write!(out, "<a href=\"{}\">{}</a>", escape(href), escape(label));
+            }
+        }
+    }
+}
+
+/// Builder for a generated internal page.
+///
+/// Every page starts with a navigation bar linking the other internal pages
+/// and an `<h1>` with the title. Text passed to the builder is escaped;
+/// [`HtmlPage::raw`] inserts markup as-is.
+#[derive(Debug, Clone)]
+pub struct HtmlPage {
+    title: String,
+    body: String,
+}
+
+impl HtmlPage {
+    #[must_use]
+    pub fn new(title: &str) -> Self {
+        let mut body = String::from("<nav>");
+        for page in InternalPage::ALL {
+            let _ = write!(body, "<a href=\"{}\">{}</a> ", page.url(), page.title());
+        }
+        body.push_str("</nav>");
+        let _ = write!(body, "<h1>{}</h1>", escape(title));
+        Self {
+            title: title.to_string(),
+            body,
+        }
+    }
+
+    pub fn heading(&mut self, text: &str) -> &mut Self {
+        let _ = write!(self.body, "<h2>{}</h2>", escape(text));
+        self
+    }
+
+    pub fn paragraph(&mut self, text: &str) -> &mut Self {
+        let _ = write!(self.body, "<p>{}</p>", escape(text));
+        self
+    }
+
+    /// A `<dl>`-style list of label / value pairs, rendered as a table.
+    pub fn facts<'a>(&mut self, facts: impl IntoIterator<Item = (&'a str, String)>) -> &mut Self {
+        let rows = facts
+            .into_iter()
+            .map(|(label, value)| vec![Cell::text(label), Cell::Text(value)]);
+        self.table(&[], rows)
+    }
+
+    /// Bulleted list of links.
+    pub fn links<'a>(&mut self, links: impl IntoIterator<Item = (&'a str, &'a str)>) -> &mut Self {
+        self.body.push_str("<ul>");
+        for (href, label) in links {
+            self.body.push_str("<li>");
+            Cell::link(href, label).write(&mut self.body);
+            self.body.push_str("</li>");
+        }
+        self.body.push_str("</ul>");
+        self
+    }
+
+    /// A table; `headers` may be empty.
+    pub fn table(
+        &mut self,
+        headers: &[&str],
+        rows: impl IntoIterator<Item = Vec<Cell>>,
+    ) -> &mut Self {
+        self.body.push_str("<table>");
+        if !headers.is_empty() {
+            self.body.push_str("<tr>");
+            for h in headers {
+                let _ = write!(self.body, "<th>{}</th>", escape(h));
+            }
+            self.body.push_str("</tr>");
+        }
+        for row in rows {
+            self.body.push_str("<tr>");
+            for cell in &row {
+                self.body.push_str("<td>");
+                cell.write(&mut self.body);
+                self.body.push_str("</td>");
+            }
+            self.body.push_str("</tr>");
+        }
+        self.body.push_str("</table>");
+        self
+    }
+
+    /// Insert markup unescaped (forms and other hand-built fragments).
+    pub fn raw(&mut self, html: &str) -> &mut Self {
+        self.body.push_str(html);
+        self
+    }
+
+    /// The finished document.
+    #[must_use]
+    pub fn finish(&self) -> String {
+        format!(
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{}</title></head>\
+             <body><main>{}</main></body></html>",
+            escape(&self.title),
+            self.body
+        )
+    }
+}
+
+/// Page shown for an unknown `alice://` host.
+#[must_use]
+pub fn not_found(host: &str) -> String {
+    let mut page = HtmlPage::new("Page not found");
+    page.paragraph(&format!(
+        "There is no internal page called “{host}”. The pages that exist are linked above."
+    ));
+    page.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::pipeline::BrowserEngine;
+
+    #[test]
+    fn parses_internal_urls() {
+        assert!(is_internal("alice://history"));
+        assert!(is_internal("ALICE://Stats"));
+        assert!(!is_internal("https://alice.example"));
+        assert!(parse("https://example.com").is_none());
+
+        let req = parse("alice://settings?render_mode=oz&dark_mode=on").unwrap();
+        assert_eq!(req.page, Some(InternalPage::Settings));
+        assert_eq!(req.param("render_mode"), Some("oz"));
+        assert_eq!(req.param("missing"), None);
+
+        let unknown = parse("alice://nope").unwrap();
+        assert_eq!(unknown.page, None);
+        assert_eq!(unknown.host, "nope");
+
+        for page in InternalPage::ALL {
+            assert_eq!(parse(&page.url()).unwrap().page, Some(page));
+        }
+        // Settings form values round-trip through the mode keys
+        for mode in crate::render::RenderMode::ALL {
+            assert_eq!(crate::render::RenderMode::from_key(mode.key()), Some(mode));
+        }
+    }
+
+    #[test]
+    fn generated_page_runs_through_pipeline() {
+        let mut page = HtmlPage::new("History <test>");
+        page.heading("Visits").table(
+            &["Page", "Visits"],
+            vec![vec![
+                Cell::link("https://example.com/?a=1&b=2", "Example & co"),
+                Cell::text("3"),
+            ]],
+        );
+        let html = page.finish();
+        assert!(html.contains("<title>History &lt;test&gt;</title>"));
+        assert!(html.contains("href=\"https://example.com/?a=1&amp;b=2\""));
+
+        let result = BrowserEngine::new(800.0)
+            .process_html(&html, "alice://history", 200)
+            .ok()
+            .unwrap();
+        assert_eq!(result.dom.title, "History <test>");
+        let text = result.dom.root.collect_text();
+        assert!(text.contains("Example & co"));
+        assert!(text.contains("Statistics"));
+    }
+}
@@ -0,0 +1,343 @@
+//! Approximate memory accounting for loaded pages.
+//!
+//! Sizes are estimated by walking the page's data structures: each value's
+//! inline size plus the heap buffers it owns (string and vector capacity,
+//! attribute maps). Allocator overhead and shared data are not counted, so
+//! the figures are lower bounds — good for comparing pages and seeing what
+//! dominates, not a replacement for the process's resident size.
+//!
+//! [`MemoryUsage`] adds up what the whole browser holds, and [`relief`]
+//! decides what to give back when that passes the memory budget: pages in
+//! the back/forward cache first, least recently left first, then decoded
+//! images and their textures. The shown page itself is never dropped.
+
+use std::collections::HashMap;
+use std::mem::size_of;
+
+use crate::dom::arena::{ArenaNode, DomArena};
+use crate::dom::DomNode;
+use crate::engine::pipeline::PageResult;
+use crate::render::layout::{LayoutNode, TextLine};
+use crate::render::sdf_ui::{PaintElement, SdfPrimitive, SdfScene};
+
+/// Heap bytes owned by a value, not counting the value itself.
+pub trait HeapSize {
+    fn heap_size(&self) -> usize;
+
+    /// Inline plus heap bytes.
+    fn total_size(&self) -> usize
+    where
+        Self: Sized,
+    {
+        size_of::<Self>() + self.heap_size()
+    }
+}
+
+impl HeapSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Option<T> {
+    fn heap_size(&self) -> usize {
+        self.as_ref().map_or(0, HeapSize::heap_size)
+    }
+}
+
+impl<T: HeapSize> HeapSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(HeapSize::heap_size).sum::<usize>()
+    }
+}
+
+impl HeapSize for HashMap<String, String> {
+    fn heap_size(&self) -> usize {
+        // One control byte per bucket besides the entry
+        self.capacity() * (size_of::<(String, String)>() + 1)
+            + self
+                .iter()
+                .map(|(k, v)| k.heap_size() + v.heap_size())
+                .sum::<usize>()
+    }
+}
+
+impl HeapSize for DomNode {
+    fn heap_size(&self) -> usize {
+        self.tag.heap_size()
+            + self.attributes.heap_size()
+            + self.text.heap_size()
+            + self.children.heap_size()
+    }
+}
+
+impl HeapSize for (String, String) {
+    fn heap_size(&self) -> usize {
+        self.0.heap_size() + self.1.heap_size()
+    }
+}
+
+impl HeapSize for ArenaNode {
+    fn heap_size(&self) -> usize {
+        self.tag.heap_size() + self.attributes.heap_size() + self.text.heap_size()
+    }
+}
+
+impl HeapSize for DomArena {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<ArenaNode>()
+            + self.nodes().iter().map(HeapSize::heap_size).sum::<usize>()
+            + self.url.heap_size()
+            + self.title.heap_size()
+    }
+}
+
+impl HeapSize for LayoutNode {
+    fn heap_size(&self) -> usize {
+        let table = match self.table {
+            Some(crate::render::layout::TablePart::Grid { ref col_widths, .. }) => {
+                col_widths.capacity() * size_of::<f32>()
+            }
+            _ => 0,
+        };
+        let media = self.media.as_ref().map_or(0, |m| {
+            m.src.heap_size() + m.mime.heap_size() + m.poster.heap_size() + m.title.heap_size()
+        });
+        self.tag.heap_size()
+            + self.text.heap_size()
+            + self.href.heap_size()
+            + self.selector.heap_size()
+            + self.id.heap_size()
+            + self.children.heap_size()
+            + table
+            + media
+            + self.lines.capacity() * size_of::<TextLine>()
+    }
+}
+
+impl HeapSize for SdfPrimitive {
+    fn heap_size(&self) -> usize {
+        match self {
+            Self::TextLabel { text, .. } | Self::Billboard { text, .. } => text.heap_size(),
+            _ => 0,
+        }
+    }
+}
+
+impl HeapSize for SdfScene {
+    fn heap_size(&self) -> usize {
+        self.primitives.heap_size()
+    }
+}
+
+impl HeapSize for PaintElement {
+    fn heap_size(&self) -> usize {
+        self.text.heap_size() + self.href.heap_size() + self.image_url.heap_size()
+    }
+}
+
+/// Approximate bytes held by one loaded page, by part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PageMemory {
+    pub dom: usize,
+    pub layout: usize,
+    /// 2-D SDF scene description
+    pub scene: usize,
+    /// Parsed text resource (JSON, CSV, …), if the page is one
+    pub text_view: usize,
+}
+
+impl PageMemory {
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.dom + self.layout + self.scene + self.text_view
+    }
+}
+
+impl PageResult {
+    /// Approximate memory held by the page's DOM, layout and scene.
+    #[must_use]
+    pub fn memory(&self) -> PageMemory {
+        PageMemory {
+            dom: self.dom.root.total_size() + self.dom.url.heap_size() + self.dom.title.heap_size(),
+            layout: self.layout.total_size(),
+            scene: self.sdf_scene.total_size(),
+            text_view: self.text_view.as_ref().map_or(0, |v| {
+                // The view holds roughly the decoded body once more
+                self.timings.html_bytes + size_of_val(v)
+            }),
+        }
+    }
+}
+
+/// Approximate bytes held by the browser, by part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryUsage {
+    /// DOM (and text view) of the shown page
+    pub dom: usize,
+    pub layout: usize,
+    /// 2-D scene, paint list and 3-D scene of the shown page
+    pub scenes: usize,
+    /// Downloaded images, decoded to RGBA
+    pub decoded_images: usize,
+    /// Images uploaded as textures
+    pub image_textures: usize,
+    /// Pages kept in the back/forward cache
+    pub cached_pages: usize,
+}
+
+impl MemoryUsage {
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.dom
+            + self.layout
+            + self.scenes
+            + self.decoded_images
+            + self.image_textures
+            + self.cached_pages
+    }
+
+    #[must_use]
+    pub const fn images(&self) -> usize {
+        self.decoded_images + self.image_textures
+    }
+
+    /// `(label, bytes)` of each part, for display.
+    #[must_use]
+    pub const fn rows(&self) -> [(&'static str, usize); 6] {
+        [
+            ("DOM", self.dom),
+            ("Layout", self.layout),
+            ("SDF scenes", self.scenes),
+            ("Decoded images", self.decoded_images),
+            ("Image textures", self.image_textures),
+            ("Cached pages", self.cached_pages),
+        ]
+    }
+}
+
+/// What to free to get back under a memory budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Relief {
+    /// Bytes of back/forward cached pages to evict
+    pub cached_pages: usize,
+    /// Drop decoded images and their textures
+    pub images: bool,
+}
+
+impl Relief {
+    #[must_use]
+    pub const fn is_none(&self) -> bool {
+        self.cached_pages == 0 && !self.images
+    }
+}
+
+/// What to free for `usage` to fit in `budget` bytes; nothing when it fits
+/// or the budget is 0 (no limit). Cached pages go first; images only if
+/// evicting every cached page is not enough. The rest belongs to the shown
+/// page and stays, even over budget.
+#[must_use]
+pub fn relief(usage: &MemoryUsage, budget: usize) -> Relief {
+    let total = usage.total();
+    if budget == 0 || total <= budget {
+        return Relief::default();
+    }
+    let over = total - budget;
+    let cached_pages = over.min(usage.cached_pages);
+    Relief {
+        cached_pages,
+        images: over > cached_pages && usage.images() > 0,
+    }
+}
+
+/// Human-readable byte count ("512 B", "1.5 KB", "12.3 MB").
+#[must_use]
+pub fn format_bytes(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    let b = bytes as f64;
+    if b < KB {
+        format!("{bytes} B")
+    } else if b < KB * KB {
+        format!("{:.1} KB", b / KB)
+    } else if b < KB * KB * KB {
+        format!("{:.1} MB", b / (KB * KB))
+    } else {
+        format!("{:.2} GB", b / (KB * KB * KB))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::pipeline::BrowserEngine;
+
+    #[test]
+    fn larger_pages_account_for_more_memory() {
+        let engine = BrowserEngine::new(800.0);
+        let small = engine
+            .process_html("<p>one</p>", "https://example.com/", 200)
+            .ok()
+            .unwrap()
+            .memory();
+        let big_html = format!("<div>{}</div>", "<p>lorem ipsum dolor</p>".repeat(500));
+        let big = engine
+            .process_html(&big_html, "https://example.com/", 200)
+            .ok()
+            .unwrap()
+            .memory();
+        assert!(small.dom > 0 && small.layout > 0);
+        assert!(big.dom > small.dom * 10);
+        assert!(big.total() > big.dom);
+        assert_eq!(small.text_view, 0);
+    }
+
+    #[test]
+    fn arena_dom_is_smaller_than_owned_nodes() {
+        let html = format!(
+            "<div>{}</div>",
+            "<p class=\"x\">lorem <a href=\"/y\">ipsum</a> dolor</p>".repeat(500)
+        );
+        let owned = crate::dom::parser::parse_html(&html, "https://example.com/");
+        let arena = crate::dom::parser::parse_arena(&html, "https://example.com/");
+        assert!(arena.total_size() * 3 / 2 < owned.root.total_size());
+    }
+
+    #[test]
+    fn cached_pages_are_relieved_before_images() {
+        let usage = MemoryUsage {
+            dom: 100,
+            layout: 100,
+            scenes: 50,
+            decoded_images: 200,
+            image_textures: 200,
+            cached_pages: 300,
+        };
+        assert_eq!(usage.total(), 950);
+        assert!(relief(&usage, 0).is_none());
+        assert!(relief(&usage, 950).is_none());
+
+        // Evicting some cached pages is enough
+        let r = relief(&usage, 800);
+        assert_eq!(r.cached_pages, 150);
+        assert!(!r.images);
+
+        // All cached pages and then the images
+        let r = relief(&usage, 500);
+        assert_eq!(r.cached_pages, 300);
+        assert!(r.images);
+
+        // The shown page alone is over budget: nothing more to free
+        let bare = MemoryUsage {
+            dom: 1000,
+            ..MemoryUsage::default()
+        };
+        assert!(relief(&bare, 10).is_none());
+    }
+
+    #[test]
+    fn bytes_are_formatted_with_units() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MB");
+    }
+}
@@ -1 +1,16 @@
+pub mod back_forward;
+pub mod decisions;
+pub mod frames;
+pub mod headless;
+pub mod internal;
+pub mod memory;
 pub mod pipeline;
+pub mod print;
+pub mod profiler;
+pub mod resources;
+pub mod restore;
+pub mod session;
+pub mod sitemap;
+pub mod snapshot;
+pub mod text_view;
+pub mod workers;
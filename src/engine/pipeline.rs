@@ -1,26 +1,208 @@
-use std::sync::Arc;
-
-use crate::dom::filter::{FilterStats, SemanticFilter};
-use crate::dom::parser::parse_html;
-use crate::dom::readability::readability_boost;
-use crate::dom::DomTree;
-use crate::net::adblock::AdBlockEngine;
-use crate::net::fetch::fetch_url;
-use crate::render::layout::{compute_layout, LayoutNode};
+use std::collections::BTreeSet;
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use url::Url;
+use web_time::Instant;
+
+use crate::dom::arena::DomArena;
+use crate::dom::diff::{self, DomDiff, LayoutPatch};
+use crate::dom::filter::{
+    self, removal_rule, FeedbackRules, FilterStats, RemovalReason, SemanticFilter,
+};
+use crate::dom::form::FormSubmission;
+use crate::dom::model::ClassifierModel;
+use crate::dom::parser::{parse_arena, parse_html};
+use crate::dom::readability::{readability_boost, readability_boost_arena};
+use crate::dom::{DomNode, DomTree};
+use crate::engine::decisions::{self, CacheDecision, LoadLog};
+use crate::engine::frames::{self, FrameLoad, FramePolicy};
+use crate::engine::profiler::{StageTimer, StageTimings};
+use crate::engine::resources::{self, ResourceManifest};
+use crate::engine::sitemap::{self, SiteNode, Sitemap};
+use crate::engine::snapshot;
+use crate::engine::text_view::TextView;
+use crate::engine::workers::CancelToken;
+use crate::net::adblock::{AdBlockEngine, Allowlist};
+use crate::net::backend::{default_backend, FetchBackend};
+use crate::net::data_url;
+use crate::net::fetch::{FetchError, FetchOptions, FetchResult, TimeoutPolicy, DEFAULT_USER_AGENT};
+use crate::net::netlog::{self, NetworkLog, RequestKind, RequestRecord};
+use crate::net::scheduler::Priority;
+use crate::net::sniff::{self, ResourceKind};
+use crate::net::throttle::NetworkConditions;
+use crate::net::tls::Certificate;
+use crate::net::wayback::{self, Snapshot};
+use crate::render::layout::{LayoutNode, LazyLayout, DEFAULT_FONT_SIZE, LAZY_MARGIN};
 use crate::render::sdf_ui::{layout_to_sdf, SdfScene};
 
 // Deep-Fried Rust: SIMD pipeline imports
-use crate::simd::classify::{apply_classifications, classify_batch, prune_ads, SimdFilterStats};
-use crate::simd::layout::{compute_layout_simd, flatten_dom, ComputedBox, FlatNode};
-use crate::simd::soa::dom_to_soa;
+use crate::simd::classify::{classify_arena, classify_tree, SimdFilterStats};
+use crate::simd::infer;
+use crate::simd::layout::{compute_layout_simd, flatten_arena, ComputedBox, FlatNode};
 
 /// Result of loading and processing a web page
 pub struct PageResult {
     pub dom: DomTree,
     pub filter_stats: FilterStats,
     pub layout: LayoutNode,
+    /// Pending part of a long page's layout, extended as the view scrolls
+    pub lazy: Option<LazyLayout>,
     pub sdf_scene: SdfScene,
+    /// Parsed body of a non-HTML text resource (JSON, CSV, XML, text);
+    /// `dom` then holds a plain HTML rendering of it
+    pub text_view: Option<TextView>,
     pub fetch_status: u16,
+    /// Server certificate, for pages fetched over HTTPS
+    pub certificate: Option<Certificate>,
+    /// Width the current `layout` was computed for
+    pub viewport_width: f32,
+    /// Page zoom: font sizes, and the text and control heights derived from
+    /// them, are scaled by this factor
+    pub zoom: f32,
+    /// Why nodes were removed and requests blocked during this load
+    pub decisions: LoadLog,
+    /// Subresources the page refers to, by origin
+    pub resources: ResourceManifest,
+    /// Requests made for the page: the document and frames here, images
+    /// as the browser loads them
+    pub network: NetworkLog,
+    /// Time spent in each pipeline stage
+    pub timings: StageTimings,
+}
+
+/// Width changes smaller than this (in pixels) don't trigger a reflow.
+const RELAYOUT_EPSILON: f32 = 0.5;
+
+/// Zoom levels stepped through by zooming in and out.
+pub const ZOOM_LEVELS: &[f32] = &[
+    0.5, 0.67, 0.75, 0.8, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0,
+];
+
+/// The zoom level after `zoom` in the direction of `step` (positive: in);
+/// levels between the steps snap to the neighbouring one.
+#[must_use]
+pub fn step_zoom(zoom: f32, step: i32) -> f32 {
+    let mut levels = ZOOM_LEVELS.iter().copied();
+    if step > 0 {
+        levels.find(|z| *z > zoom + 0.005).unwrap_or(zoom)
+    } else if step < 0 {
+        levels.rev().find(|z| *z < zoom - 0.005).unwrap_or(zoom)
+    } else {
+        1.0
+    }
+}
+
+impl PageResult {
+    /// Reflow the page for a new viewport width.
+    ///
+    /// Layout and the SDF scene are recomputed from the retained DOM; nothing
+    /// is refetched or re-parsed. Long pages start over from the top and are
+    /// extended again by [`PageResult::extend_layout`]. Returns `false` (and
+    /// keeps the current layout) if the width is unchanged or not positive.
+    pub fn relayout(&mut self, viewport_width: f32) -> bool {
+        if viewport_width <= 0.0 || (viewport_width - self.viewport_width).abs() < RELAYOUT_EPSILON
+        {
+            return false;
+        }
+        self.viewport_width = viewport_width;
+        self.layout_again();
+        true
+    }
+
+    /// Zoom the page to `zoom` (clamped to [`ZOOM_LEVELS`]' range) and lay
+    /// it out again at the current width. Returns `false` if the zoom is
+    /// unchanged.
+    pub fn set_zoom(&mut self, zoom: f32) -> bool {
+        let zoom = zoom.clamp(ZOOM_LEVELS[0], ZOOM_LEVELS[ZOOM_LEVELS.len() - 1]);
+        if (zoom - self.zoom).abs() < f32::EPSILON {
+            return false;
+        }
+        self.zoom = zoom;
+        self.layout_again();
+        true
+    }
+
+    /// Recompute layout and SDF scene from the retained DOM.
+    fn layout_again(&mut self) {
+        let mut timer = StageTimer::start();
+        (self.layout, self.lazy) = LazyLayout::compute(
+            &self.dom.root,
+            self.viewport_width,
+            DEFAULT_FONT_SIZE * self.zoom,
+            LAZY_MARGIN,
+        );
+        self.timings.layout_ms = timer.lap();
+        self.sdf_scene = layout_to_sdf(&self.layout, 1.0);
+        self.timings.scene_ms = timer.lap();
+    }
+
+    /// Lay out the pending part of a long page near `top..bottom` (layout
+    /// pixels). Returns whether the layout changed; once nothing is pending
+    /// the SDF scene is rebuilt from the complete layout.
+    pub fn extend_layout(&mut self, top: f32, bottom: f32) -> bool {
+        let Some(ref mut lazy) = self.lazy else {
+            return false;
+        };
+        let changed = lazy.extend(&mut self.layout, &self.dom.root, top, bottom);
+        if lazy.is_complete() {
+            self.lazy = None;
+            self.sdf_scene = layout_to_sdf(&self.layout, 1.0);
+        }
+        changed
+    }
+
+    /// Lay out whatever is still pending, for views that need the whole page.
+    pub fn complete_layout(&mut self) -> bool {
+        self.extend_layout(f32::NEG_INFINITY, f32::INFINITY)
+    }
+
+    /// The element a `#fragment` link leads to (`fragment` without the
+    /// `#`, as written or percent-decoded). The rest of a long page is laid
+    /// out if the element is not in the part laid out so far.
+    pub fn find_anchor(&mut self, fragment: &str) -> Option<&LayoutNode> {
+        let decoded = String::from_utf8_lossy(&data_url::percent_decode(fragment)).into_owned();
+        let found = |layout: &LayoutNode| {
+            layout.find_anchor(fragment).is_some() || layout.find_anchor(&decoded).is_some()
+        };
+        if !found(&self.layout) && self.lazy.is_some() {
+            self.complete_layout();
+        }
+        self.layout
+            .find_anchor(fragment)
+            .or_else(|| self.layout.find_anchor(&decoded))
+    }
+
+    /// Take `fresh`, a new load of this page, as a soft reload: the layout
+    /// tree is patched in place from `fresh`'s (see [`diff`]) rather than
+    /// replaced. `fresh` is first laid out at this page's width and zoom,
+    /// down to `bottom` (layout pixels), so long pages keep what is on
+    /// screen laid out. Returns how the DOM changed and what the patch did.
+    pub fn soft_reload(&mut self, mut fresh: Self, bottom: f32) -> (DomDiff, LayoutPatch) {
+        if (fresh.viewport_width - self.viewport_width).abs() >= RELAYOUT_EPSILON
+            || (fresh.zoom - self.zoom).abs() >= f32::EPSILON
+        {
+            fresh.viewport_width = self.viewport_width;
+            fresh.zoom = self.zoom;
+            fresh.layout_again();
+        }
+        fresh.extend_layout(0.0, bottom);
+
+        let changes = diff::diff(&self.dom.root, &fresh.dom.root);
+        let patch = diff::patch_layout(&mut self.layout, fresh.layout);
+        self.dom = fresh.dom;
+        self.filter_stats = fresh.filter_stats;
+        self.lazy = fresh.lazy;
+        self.sdf_scene = fresh.sdf_scene;
+        self.text_view = fresh.text_view;
+        self.fetch_status = fresh.fetch_status;
+        self.certificate = fresh.certificate;
+        self.decisions = fresh.decisions;
+        self.resources = fresh.resources;
+        self.network = fresh.network;
+        self.timings = fresh.timings;
+        (changes, patch)
+    }
 }
 
 /// Result from the SIMD-accelerated pipeline
@@ -38,31 +220,300 @@ pub struct PageError {
     pub phase: &'static str,
 }
 
+/// A failed fetch; phase `"tls"` when the certificate was rejected.
+impl From<FetchError> for PageError {
+    fn from(e: FetchError) -> Self {
+        Self {
+            phase: if e.invalid_certificate {
+                "tls"
+            } else {
+                "fetch"
+            },
+            message: e.message,
+        }
+    }
+}
+
 impl std::fmt::Display for PageError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "[{}] {}", self.phase, self.message)
     }
 }
 
+/// How the semantic-filter phase classifies DOM nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClassifierBackend {
+    /// Batch `SoA` classification, 8 nodes per SIMD instruction
+    #[default]
+    Simd,
+    /// Per-node [`SemanticFilter`] (heuristics, or ALICE-ML with `ml-filter`)
+    Semantic,
+}
+
+/// Per-page resource limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceBudget {
+    /// Largest HTML document accepted (bytes)
+    pub max_document_bytes: usize,
+    /// Largest parsed DOM accepted (nodes)
+    pub max_dom_nodes: usize,
+}
+
+impl Default for ResourceBudget {
+    fn default() -> Self {
+        Self {
+            max_document_bytes: 16 * 1024 * 1024,
+            max_dom_nodes: 250_000,
+        }
+    }
+}
+
+/// Viewport used when none is given (CSS px).
+pub const DEFAULT_VIEWPORT: (f32, f32) = (800.0, 600.0);
+
+/// Callback handed each image source of a page (see
+/// [`BrowserEngineBuilder::image_prefetch`]).
+pub type ImagePrefetch = Arc<dyn Fn(&str) + Send + Sync>;
+
 /// The browser engine pipeline: Fetch → `AdBlock` → Parse → Filter → Layout → SDF
+///
+/// Classification and pruning split large DOMs by subtree across rayon's
+/// threads, and the page's images are handed to the prefetch callback while
+/// the layout is computed.
 pub struct BrowserEngine {
     filter: SemanticFilter,
     viewport_width: f32,
+    viewport_height: f32,
     adblock: Option<Arc<AdBlockEngine>>,
-    /// Use SIMD-accelerated pipeline (default: true)
-    use_simd: bool,
+    allowlist: Allowlist,
+    classifier: ClassifierBackend,
+    backend: Arc<dyn FetchBackend>,
+    fetch_options: FetchOptions,
+    budget: ResourceBudget,
+    image_prefetch: Option<ImagePrefetch>,
+    blocked_origins: BTreeSet<String>,
+    feedback: FeedbackRules,
+    show_removed: bool,
+    frames: FramePolicy,
+    /// Cancels the load's background fetches
+    cancel: CancelToken,
+    #[cfg(feature = "smart-cache")]
+    cache: Option<Arc<crate::net::cache::CachedFetcher>>,
 }
 
-impl BrowserEngine {
+/// Builder for [`BrowserEngine`]; every setting starts at its default.
+#[derive(Clone, Default)]
+pub struct BrowserEngineBuilder {
+    viewport: Option<(f32, f32)>,
+    user_agent: Option<String>,
+    adblock: Option<Arc<AdBlockEngine>>,
+    allowlist: Allowlist,
+    classifier: ClassifierBackend,
+    backend: Option<Arc<dyn FetchBackend>>,
+    budget: ResourceBudget,
+    timeouts: TimeoutPolicy,
+    network: NetworkConditions,
+    image_prefetch: Option<ImagePrefetch>,
+    blocked_origins: BTreeSet<String>,
+    feedback: FeedbackRules,
+    show_removed: bool,
+    frames: FramePolicy,
+    cancel: CancelToken,
+    model: Option<Arc<ClassifierModel>>,
+    #[cfg(feature = "smart-cache")]
+    cache: Option<Arc<crate::net::cache::CachedFetcher>>,
+}
+
+impl BrowserEngineBuilder {
+    /// Layout viewport in CSS px (default [`DEFAULT_VIEWPORT`]).
     #[must_use]
-    pub const fn new(viewport_width: f32) -> Self {
-        Self {
-            filter: SemanticFilter::new(),
+    pub const fn viewport(mut self, width: f32, height: f32) -> Self {
+        self.viewport = Some((width, height));
+        self
+    }
+
+    /// `User-Agent` header (default [`DEFAULT_USER_AGENT`]).
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Ad blocker checked before each page fetch; `None` turns it off (default).
+    #[must_use]
+    pub fn adblock(mut self, adblock: Option<Arc<AdBlockEngine>>) -> Self {
+        self.adblock = adblock;
+        self
+    }
+
+    /// Sites loaded without blocking: their pages are neither refused by
+    /// the ad blocker nor pruned by the semantic filter, and the load log
+    /// lists what would have been removed (default: empty).
+    #[must_use]
+    pub fn allowlist(mut self, allowlist: Allowlist) -> Self {
+        self.allowlist = allowlist;
+        self
+    }
+
+    #[must_use]
+    pub const fn classifier(mut self, backend: ClassifierBackend) -> Self {
+        self.classifier = backend;
+        self
+    }
+
+    /// Classifier weights loaded at runtime, used instead of the built-in
+    /// classifier: in 8-node batches with [`ClassifierBackend::Simd`], node
+    /// by node with [`ClassifierBackend::Semantic`] (default: none).
+    #[must_use]
+    pub fn model(mut self, model: Option<Arc<ClassifierModel>>) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Page cache consulted by [`BrowserEngine::load_page`].
+    #[cfg(feature = "smart-cache")]
+    #[must_use]
+    pub fn cache(mut self, cache: Arc<crate::net::cache::CachedFetcher>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Where pages, frames and sitemaps are fetched from (default: the
+    /// network, or nothing on wasm32; see [`crate::net::backend`]). The
+    /// page cache, when set, still fetches through the network.
+    #[must_use]
+    pub fn backend(mut self, backend: Arc<dyn FetchBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    #[must_use]
+    pub const fn budget(mut self, budget: ResourceBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    #[must_use]
+    pub const fn timeouts(mut self, timeouts: TimeoutPolicy) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Simulated network conditions for page fetches (default: none).
+    #[must_use]
+    pub const fn network(mut self, network: NetworkConditions) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Called, on a worker thread, with the `src` of every image left on a
+    /// page after filtering, while the page is laid out; lets image
+    /// downloads start before the page is shown (default: none). Images
+    /// marked `loading="lazy"` are left for the viewport to request.
+    #[must_use]
+    pub fn image_prefetch(mut self, prefetch: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.image_prefetch = Some(Arc::new(prefetch));
+        self
+    }
+
+    /// Origins (`scheme://host[:port]`) whose subresources are not loaded:
+    /// elements referring to them are removed right after parsing (default:
+    /// none). See [`crate::engine::resources`].
+    #[must_use]
+    pub fn blocked_origins(mut self, origins: BTreeSet<String>) -> Self {
+        self.blocked_origins = origins;
+        self
+    }
+
+    /// The site's corrections to the classifier, applied after it
+    /// (default: none).
+    #[must_use]
+    pub fn feedback(mut self, rules: FeedbackRules) -> Self {
+        self.feedback = rules;
+        self
+    }
+
+    /// Keep the subtrees the semantic filter would remove, classified, so
+    /// they can be shown and corrected; the load log still lists them
+    /// (default: off).
+    #[must_use]
+    pub const fn show_removed(mut self, show: bool) -> Self {
+        self.show_removed = show;
+        self
+    }
+
+    /// Which `<iframe>` documents are fetched and shown inline, each
+    /// filtered on its own (default: none). See [`crate::engine::frames`].
+    #[must_use]
+    pub fn frames(mut self, policy: FramePolicy) -> Self {
+        self.frames = policy;
+        self
+    }
+
+    /// Token the engine's background fetches, such as those of frames, run
+    /// under: cancelling it drops the ones not yet started (default: a
+    /// token never cancelled).
+    #[must_use]
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Options the built engine fetches pages with.
+    #[must_use]
+    pub fn fetch_options(&self) -> FetchOptions {
+        FetchOptions {
+            user_agent: self
+                .user_agent
+                .clone()
+                .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()),
+            timeouts: self.timeouts,
+            max_body_bytes: self.budget.max_document_bytes,
+            network: self.network,
+        }
+    }
+
+    #[must_use]
+    pub fn build(self) -> BrowserEngine {
+        let (viewport_width, viewport_height) = self.viewport.unwrap_or(DEFAULT_VIEWPORT);
+        let fetch_options = self.fetch_options();
+        BrowserEngine {
+            filter: self
+                .model
+                .map_or_else(SemanticFilter::new, SemanticFilter::with_model),
             viewport_width,
-            adblock: None,
-            use_simd: true,
+            viewport_height,
+            adblock: self.adblock,
+            allowlist: self.allowlist,
+            classifier: self.classifier,
+            backend: self.backend.unwrap_or_else(default_backend),
+            fetch_options,
+            budget: self.budget,
+            image_prefetch: self.image_prefetch,
+            blocked_origins: self.blocked_origins,
+            feedback: self.feedback,
+            show_removed: self.show_removed,
+            frames: self.frames,
+            cancel: self.cancel,
+            #[cfg(feature = "smart-cache")]
+            cache: self.cache,
         }
     }
+}
+
+impl BrowserEngine {
+    #[must_use]
+    pub fn builder() -> BrowserEngineBuilder {
+        BrowserEngineBuilder::default()
+    }
+
+    /// Engine with default settings and the given viewport width.
+    #[must_use]
+    pub fn new(viewport_width: f32) -> Self {
+        Self::builder()
+            .viewport(viewport_width, DEFAULT_VIEWPORT.1)
+            .build()
+    }
 
     /// Set the ad blocker engine (shared reference).
     #[must_use]
@@ -74,17 +525,39 @@ impl BrowserEngine {
     /// Enable/disable SIMD pipeline
     #[must_use]
     pub const fn with_simd(mut self, enabled: bool) -> Self {
-        self.use_simd = enabled;
+        self.classifier = if enabled {
+            ClassifierBackend::Simd
+        } else {
+            ClassifierBackend::Semantic
+        };
         self
     }
 
-    /// Load a URL through the full pipeline
-    ///
-    /// # Errors
-    ///
-    /// Returns `PageError` if ad-block triggers, fetch fails, or processing fails.
-    pub fn load_page(&self, url: &str) -> Result<PageResult, PageError> {
-        // Ad block check on the main page URL
+    /// Layout viewport `(width, height)` in CSS px.
+    #[must_use]
+    pub const fn viewport(&self) -> (f32, f32) {
+        (self.viewport_width, self.viewport_height)
+    }
+
+    #[must_use]
+    pub const fn classifier(&self) -> ClassifierBackend {
+        self.classifier
+    }
+
+    #[must_use]
+    pub const fn fetch_options(&self) -> &FetchOptions {
+        &self.fetch_options
+    }
+
+    #[must_use]
+    pub const fn budget(&self) -> ResourceBudget {
+        self.budget
+    }
+
+    fn check_adblock(&self, url: &str) -> Result<(), PageError> {
+        if self.allowlist.allows(url) {
+            return Ok(());
+        }
         if let Some(ref ab) = self.adblock {
             if let Some(reason) = ab.should_block(url) {
                 return Err(PageError {
@@ -93,13 +566,268 @@ impl BrowserEngine {
                 });
             }
         }
+        Ok(())
+    }
+
+    fn fetch(&self, url: &str) -> Result<(FetchResult, CacheDecision), PageError> {
+        #[cfg(feature = "smart-cache")]
+        let result = match self.cache {
+            Some(ref cache) => {
+                cache
+                    .fetch_with_outcome(url, &self.fetch_options)
+                    .map(|(r, hit)| {
+                        (
+                            r,
+                            if hit {
+                                CacheDecision::Hit
+                            } else {
+                                CacheDecision::Miss
+                            },
+                        )
+                    })
+            }
+            None => self.fetch_uncached(url),
+        };
+        #[cfg(not(feature = "smart-cache"))]
+        let result = self.fetch_uncached(url);
+
+        result.map_err(PageError::from)
+    }
+
+    fn fetch_uncached(&self, url: &str) -> Result<(FetchResult, CacheDecision), FetchError> {
+        self.backend
+            .get(url, &self.fetch_options)
+            .map(|r| (r, CacheDecision::NoCache))
+    }
+
+    /// Parse `html`, rejecting documents over the resource budget.
+    fn parse_within_budget(&self, html: &str, url: &str) -> Result<DomTree, PageError> {
+        self.check_document_budget(html)?;
+        let dom = parse_html(html, url);
+        self.check_dom_budget(dom.root.node_count())?;
+        Ok(dom)
+    }
+
+    /// Parse `html` into an arena, within the resource budget.
+    fn parse_arena_within_budget(&self, html: &str, url: &str) -> Result<DomArena, PageError> {
+        self.check_document_budget(html)?;
+        let arena = parse_arena(html, url);
+        self.check_dom_budget(arena.len())?;
+        Ok(arena)
+    }
+
+    fn check_document_budget(&self, html: &str) -> Result<(), PageError> {
+        if html.len() > self.budget.max_document_bytes {
+            return Err(PageError {
+                message: format!(
+                    "Document too large ({} bytes, limit {})",
+                    html.len(),
+                    self.budget.max_document_bytes
+                ),
+                phase: "budget",
+            });
+        }
+        Ok(())
+    }
+
+    fn check_dom_budget(&self, nodes: usize) -> Result<(), PageError> {
+        if nodes > self.budget.max_dom_nodes {
+            return Err(PageError {
+                message: format!(
+                    "DOM too large ({nodes} nodes, limit {})",
+                    self.budget.max_dom_nodes
+                ),
+                phase: "budget",
+            });
+        }
+        Ok(())
+    }
+
+    /// Load a URL through the full pipeline (via the page cache, if one was
+    /// configured).
+    ///
+    /// # Errors
+    ///
+    /// Returns `PageError` if ad-block triggers, fetch fails, or processing fails.
+    pub fn load_page(&self, url: &str) -> Result<PageResult, PageError> {
+        self.check_adblock(url)?;
+        let started = netlog::now_ms();
+        let mut timer = StageTimer::start();
+        let (fetch_result, cache) = self.fetch(url)?;
+        let fetch_ms = timer.lap();
+        let mut page = self.process_fetched(&fetch_result, cache)?;
+        page.timings.fetch_ms = fetch_ms;
+        page.network.record(document_request(
+            "GET",
+            &fetch_result,
+            cache,
+            started,
+            fetch_ms,
+        ));
+        Ok(page)
+    }
 
-        let fetch_result = fetch_url(url).map_err(|e| PageError {
+    /// Load a URL again from the network, bypassing (and refreshing) the
+    /// page cache, for [`PageResult::soft_reload`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `PageError` if ad-block triggers, fetch fails, or processing fails.
+    pub fn reload_page(&self, url: &str) -> Result<PageResult, PageError> {
+        self.check_adblock(url)?;
+        let started = netlog::now_ms();
+        let mut timer = StageTimer::start();
+        #[cfg(feature = "smart-cache")]
+        let fetched = match self.cache {
+            Some(ref cache) => cache
+                .refetch_with(url, &self.fetch_options)
+                .map(|r| (r, CacheDecision::Miss)),
+            None => self.fetch_uncached(url),
+        };
+        #[cfg(not(feature = "smart-cache"))]
+        let fetched = self.fetch_uncached(url);
+        let (fetch_result, cache) = fetched.map_err(PageError::from)?;
+        let fetch_ms = timer.lap();
+        let mut page = self.process_fetched(&fetch_result, cache)?;
+        page.timings.fetch_ms = fetch_ms;
+        page.network.record(document_request(
+            "GET",
+            &fetch_result,
+            cache,
+            started,
+            fetch_ms,
+        ));
+        Ok(page)
+    }
+
+    /// Submit a form and run the response through the full pipeline.
+    /// GET submissions go through [`load_page`](Self::load_page); POST
+    /// responses are never cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PageError` if ad-block triggers, the request fails, or
+    /// processing fails.
+    pub fn submit_form(&self, submission: &FormSubmission) -> Result<PageResult, PageError> {
+        let Some(ref body) = submission.body else {
+            return self.load_page(&submission.url);
+        };
+        self.check_adblock(&submission.url)?;
+        let started = netlog::now_ms();
+        let mut timer = StageTimer::start();
+        let fetch_result = self
+            .backend
+            .post_form(&submission.url, body.clone(), &self.fetch_options)
+            .map_err(PageError::from)?;
+        let fetch_ms = timer.lap();
+        let mut page = self.process_fetched(&fetch_result, CacheDecision::Bypassed)?;
+        page.timings.fetch_ms = fetch_ms;
+        page.network.record(document_request(
+            "POST",
+            &fetch_result,
+            CacheDecision::Bypassed,
+            started,
+            fetch_ms,
+        ));
+        Ok(page)
+    }
+
+    /// Load an Internet Archive snapshot through the full pipeline: the
+    /// copy is fetched as originally served and its addresses pointed into
+    /// the archive (see [`crate::net::wayback`]). The page's URL is the
+    /// snapshot's archive address.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PageError` if the archive cannot be reached or processing
+    /// fails.
+    pub fn load_archived(&self, snapshot: &Snapshot) -> Result<PageResult, PageError> {
+        let started = netlog::now_ms();
+        let mut timer = StageTimer::start();
+        let (fetch_result, cache) = self.fetch(&snapshot.raw_url())?;
+        let fetch_ms = timer.lap();
+        let mut page = self.process_with(
+            &fetch_result.html,
+            &snapshot.url,
+            fetch_result.status,
+            cache,
+            |dom| wayback::rewrite_assets(&mut dom.root, &snapshot.original, &snapshot.timestamp),
+        )?;
+        page.timings.fetch_ms = fetch_ms;
+        page.network.record(document_request(
+            "GET",
+            &fetch_result,
+            cache,
+            started,
+            fetch_ms,
+        ));
+        Ok(page)
+    }
+
+    /// Open a page saved to disk (see [`crate::engine::snapshot`]) from its
+    /// `file://` URL: an MHTML archive is unpacked, other files are read as
+    /// HTML.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PageError` if `url` is not a local file, it cannot be read,
+    /// or processing fails.
+    pub fn load_file(&self, url: &str) -> Result<PageResult, PageError> {
+        let mut timer = StageTimer::start();
+        let path = snapshot::file_path(url).ok_or_else(|| PageError {
+            message: format!("Not a local file: {url}"),
+            phase: "file",
+        })?;
+        let html = snapshot::load(&path).map_err(|e| PageError {
             message: e.message,
-            phase: "fetch",
+            phase: "file",
         })?;
+        let fetch_ms = timer.lap();
+        let mut page = self.process_html(&html, url, 200)?;
+        page.timings.fetch_ms = fetch_ms;
+        Ok(page)
+    }
 
-        self.process_html(&fetch_result.html, &fetch_result.url, fetch_result.status)
+    /// Read the site map of the site serving `origin`: its `/sitemap.xml`,
+    /// following sitemap indexes up to [`sitemap::MAX_SITEMAPS`] files and
+    /// [`sitemap::MAX_PAGES`] pages.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PageError` if `origin` is not a web address, or the site
+    /// has no readable `sitemap.xml`.
+    pub fn fetch_sitemap(&self, origin: &str) -> Result<SiteNode, PageError> {
+        let first = sitemap::sitemap_url(origin).ok_or_else(|| PageError {
+            message: format!("Not a web address: {origin}"),
+            phase: "sitemap",
+        })?;
+        let mut queue = vec![first.clone()];
+        let mut pages = Vec::new();
+        let mut read = 0;
+        while let Some(url) = queue.pop() {
+            if read == sitemap::MAX_SITEMAPS || pages.len() >= sitemap::MAX_PAGES {
+                break;
+            }
+            read += 1;
+            self.check_adblock(&url)?;
+            let (result, _) = self.fetch(&url)?;
+            if result.status >= 400 {
+                // Nested sitemaps that fail are skipped; the first must load
+                if url == first {
+                    return Err(PageError {
+                        message: format!("{url} returned HTTP {}", result.status),
+                        phase: "sitemap",
+                    });
+                }
+                continue;
+            }
+            match sitemap::parse_sitemap(&result.html) {
+                Sitemap::Index(maps) => queue.extend(maps.into_iter().rev()),
+                Sitemap::Pages(urls) => pages.extend(urls),
+            }
+        }
+        pages.truncate(sitemap::MAX_PAGES);
+        Ok(SiteNode::from_urls(&first, &pages))
     }
 
     /// Load a URL through the pipeline using ALICE-Cache for caching
@@ -113,64 +841,465 @@ impl BrowserEngine {
         url: &str,
         cache: &crate::net::cache::CachedFetcher,
     ) -> Result<PageResult, PageError> {
-        // Ad block check on the main page URL
-        if let Some(ref ab) = self.adblock {
-            if let Some(reason) = ab.should_block(url) {
-                return Err(PageError {
-                    message: format!("Blocked ({:?}): {}", reason, url),
-                    phase: "adblock",
-                });
-            }
-        }
+        self.check_adblock(url)?;
 
-        let fetch_result = cache.fetch(url).map_err(|e| PageError {
-            message: e.message,
-            phase: "fetch",
-        })?;
+        let started = netlog::now_ms();
+        let mut timer = StageTimer::start();
+        let (fetch_result, hit) = cache
+            .fetch_with_outcome(url, &self.fetch_options)
+            .map_err(PageError::from)?;
+        let fetch_ms = timer.lap();
+        let decision = if hit {
+            CacheDecision::Hit
+        } else {
+            CacheDecision::Miss
+        };
 
-        self.process_html(&fetch_result.html, &fetch_result.url, fetch_result.status)
+        let mut page = self.process_fetched(&fetch_result, decision)?;
+        page.network.record(document_request(
+            "GET",
+            &fetch_result,
+            decision,
+            started,
+            fetch_ms,
+        ));
+        Ok(page)
     }
 
     /// Process raw HTML through the pipeline (for testing)
     ///
     /// # Errors
     ///
-    /// Returns `PageError` if DOM processing fails.
+    /// Returns `PageError` if the document exceeds the resource budget.
     pub fn process_html(
         &self,
         html: &str,
         url: &str,
         status: u16,
     ) -> Result<PageResult, PageError> {
-        // Phase 2: Parse
-        let mut dom = parse_html(html, url);
+        self.process(html, url, status, CacheDecision::NotFetched)
+    }
 
-        // Phase 3: Semantic Filter
-        // Use SIMD-accelerated classification if enabled
-        let filter_stats = if self.use_simd {
-            self.filter_simd(&mut dom)
-        } else {
-            self.filter.filter(&mut dom)
+    /// Process a response body through the branch for its sniffed content
+    /// type (for testing).
+    ///
+    /// # Errors
+    ///
+    /// Returns `PageError` if the document exceeds the resource budget.
+    pub fn process_response(
+        &self,
+        body: &str,
+        content_type: &str,
+        url: &str,
+        status: u16,
+    ) -> Result<PageResult, PageError> {
+        let result = FetchResult {
+            html: body.to_string(),
+            url: url.to_string(),
+            status,
+            content_type: content_type.to_string(),
+            certificate: None,
         };
+        self.process_fetched(&result, CacheDecision::NotFetched)
+    }
 
-        // Phase 3.5: Readability boost — promote main content
-        readability_boost(&mut dom.root);
+    /// HTML goes through the full pipeline; other text resources through
+    /// [`process_text`](Self::process_text).
+    fn process_fetched(
+        &self,
+        result: &FetchResult,
+        cache: CacheDecision,
+    ) -> Result<PageResult, PageError> {
+        let mut page = match sniff::sniff(&result.content_type, &result.url, &result.html) {
+            ResourceKind::Html => self.process(&result.html, &result.url, result.status, cache),
+            kind => self.process_text(kind, &result.html, &result.url, result.status, cache),
+        }?;
+        page.certificate.clone_from(&result.certificate);
+        Ok(page)
+    }
 
-        // Phase 4: Layout
-        let layout = compute_layout(&dom.root, self.viewport_width);
+    /// Parse a text resource into a [`TextView`] and lay out its HTML
+    /// projection. Nothing is filtered: every line is content.
+    fn process_text(
+        &self,
+        kind: ResourceKind,
+        text: &str,
+        url: &str,
+        status: u16,
+        cache: CacheDecision,
+    ) -> Result<PageResult, PageError> {
+        let mut timer = StageTimer::start();
+        let mut timings = StageTimings {
+            html_bytes: text.len(),
+            ..StageTimings::default()
+        };
+        if text.len() > self.budget.max_document_bytes {
+            return Err(PageError {
+                message: format!(
+                    "Document too large ({} bytes, limit {})",
+                    text.len(),
+                    self.budget.max_document_bytes
+                ),
+                phase: "budget",
+            });
+        }
+
+        let view = TextView::parse(kind, text);
+        let name = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit('/').find(|s| !s.is_empty()))
+            .unwrap_or(kind.label());
+        let dom = self.parse_within_budget(&view.to_html(name), url)?;
+        timings.parse_ms = timer.lap();
+
+        let nodes = dom.root.node_count();
+        let filter_stats = FilterStats {
+            total_nodes: nodes,
+            content_nodes: nodes,
+            ad_nodes: 0,
+            tracker_nodes: 0,
+            nav_nodes: 0,
+            removed_nodes: 0,
+        };
+        let decisions = LoadLog {
+            url: dom.url.clone(),
+            classifier: format!("none ({})", kind.label()),
+            cache,
+            ..LoadLog::default()
+        };
+
+        let (layout, lazy) = LazyLayout::compute(
+            &dom.root,
+            self.viewport_width,
+            DEFAULT_FONT_SIZE,
+            self.viewport_height,
+        );
+        timings.layout_ms = timer.lap();
+        let sdf_scene = layout_to_sdf(&layout, 1.0);
+        timings.scene_ms = timer.lap();
+
+        Ok(PageResult {
+            dom,
+            filter_stats,
+            layout,
+            lazy,
+            sdf_scene,
+            text_view: Some(view),
+            fetch_status: status,
+            certificate: None,
+            viewport_width: self.viewport_width,
+            zoom: 1.0,
+            decisions,
+            resources: ResourceManifest::default(),
+            network: NetworkLog::default(),
+            timings,
+        })
+    }
+
+    fn process(
+        &self,
+        html: &str,
+        url: &str,
+        status: u16,
+        cache: CacheDecision,
+    ) -> Result<PageResult, PageError> {
+        self.process_with(html, url, status, cache, |_| {})
+    }
+
+    /// [`process`](Self::process), letting `prepare` edit the DOM right
+    /// after parsing.
+    fn process_with(
+        &self,
+        html: &str,
+        url: &str,
+        status: u16,
+        cache: CacheDecision,
+        prepare: impl FnOnce(&mut DomTree),
+    ) -> Result<PageResult, PageError> {
+        let mut timer = StageTimer::start();
+        let mut timings = StageTimings {
+            html_bytes: html.len(),
+            ..StageTimings::default()
+        };
+
+        // Phase 2: Parse
+        let mut dom = self.parse_within_budget(html, url)?;
+        prepare(&mut dom);
+        let resources = ResourceManifest::collect(
+            &dom.root,
+            &dom.url,
+            self.adblock.as_deref(),
+            &self.blocked_origins,
+        );
+        resources::strip_blocked(&mut dom.root, &dom.url, &self.blocked_origins);
+        timings.parse_ms = timer.lap();
+        let mut decisions = LoadLog {
+            url: dom.url.clone(),
+            cache,
+            allowlisted: self.allowlist.allows(&dom.url),
+            ..LoadLog::default()
+        };
+        if let Some(ab) = self.adblock.as_ref().filter(|_| !decisions.allowlisted) {
+            decisions.blocked = decisions::blocked_requests(&dom.root, &dom.url, ab);
+        }
+        let mut network = NetworkLog::default();
+        let now = netlog::now_ms();
+        for request in &decisions.blocked {
+            network.record(RequestRecord::blocked(request, now));
+        }
+
+        // Phase 3: Semantic Filter (classify, log removals, prune)
+        let filter_stats = self.filter_dom(&mut dom, &mut decisions);
+        timings.filter_ms = timer.lap();
+
+        // Phase 3.6: Embedded frames, each through its own filter
+        if self.frames.enabled {
+            let requests;
+            (decisions.frames, requests) = self.embed_frames(&mut dom);
+            for request in requests {
+                network.record(request);
+            }
+            timings.frames_ms = timer.lap();
+        }
+
+        // Phase 4: Layout (only the first screens of a long page), with the
+        // page's images the ad blocker allows handed to the prefetcher
+        // meanwhile
+        let (width, height) = (self.viewport_width, self.viewport_height);
+        let prefetch = self.image_prefetch.as_deref();
+        let blocked: std::collections::HashSet<&str> =
+            decisions.blocked.iter().map(|b| b.url.as_str()).collect();
+        let base = Url::parse(&dom.url).ok();
+        let ((layout, lazy), ()) = rayon::join(
+            || LazyLayout::compute(&dom.root, width, DEFAULT_FONT_SIZE, height),
+            || {
+                if let Some(prefetch) = prefetch {
+                    for_each_image(&dom.root, &|src| {
+                        let resolved = base.as_ref().and_then(|b| b.join(src).ok());
+                        if !resolved.is_some_and(|u| blocked.contains(u.as_str())) {
+                            prefetch(src);
+                        }
+                    });
+                }
+            },
+        );
+        timings.layout_ms = timer.lap();
 
         // Phase 5: SDF Scene Generation
         let sdf_scene = layout_to_sdf(&layout, 1.0);
+        timings.scene_ms = timer.lap();
 
         Ok(PageResult {
             dom,
             filter_stats,
             layout,
+            lazy,
             sdf_scene,
+            text_view: None,
             fetch_status: status,
+            certificate: None,
+            viewport_width: self.viewport_width,
+            zoom: 1.0,
+            decisions,
+            resources,
+            network,
+            timings,
         })
     }
 
+    /// Classify `dom` with the configured backend and the site's
+    /// corrections, log the removals into `decisions`, prune (unless the
+    /// site is allowlisted or removed elements are shown) and boost the main
+    /// content.
+    fn filter_dom(&self, dom: &mut DomTree, decisions: &mut LoadLog) -> FilterStats {
+        let filter_stats = match (self.classifier, self.filter.model()) {
+            (ClassifierBackend::Simd, Some(model)) => {
+                decisions.classifier = format!("simd {}", self.filter.label());
+                let mut stats = infer::classify_tree(&mut dom.root, model);
+                self.feedback.apply(&mut dom.root, &mut stats);
+                decisions::record_removals(
+                    &dom.root,
+                    &|n| {
+                        self.feedback
+                            .explain(n)
+                            .unwrap_or_else(|| self.filter.explain(n))
+                    },
+                    &mut decisions.removed,
+                );
+                stats
+            }
+            (ClassifierBackend::Simd, None) => {
+                decisions.classifier = "simd".into();
+                let mut stats = self.classify_simd(dom);
+                self.feedback.apply(&mut dom.root, &mut stats);
+                decisions::record_removals(
+                    &dom.root,
+                    &|n| {
+                        self.feedback
+                            .explain(n)
+                            .unwrap_or_else(|| RemovalReason::Rule {
+                                rule: removal_rule(n),
+                            })
+                    },
+                    &mut decisions.removed,
+                );
+                stats
+            }
+            (ClassifierBackend::Semantic, _) => {
+                decisions.classifier = match self.filter.model() {
+                    Some(_) => self.filter.label(),
+                    None => "semantic".into(),
+                };
+                let mut stats = self.filter.classify(dom);
+                self.feedback.apply(&mut dom.root, &mut stats);
+                decisions::record_removals(
+                    &dom.root,
+                    &|n| {
+                        self.feedback
+                            .explain(n)
+                            .unwrap_or_else(|| self.filter.explain(n))
+                    },
+                    &mut decisions.removed,
+                );
+                stats
+            }
+        };
+        // Allowed sites keep everything; the log says what would have gone
+        if !decisions.allowlisted && !self.show_removed {
+            filter::prune(dom);
+        }
+
+        // Phase 3.5: Readability boost — promote main content
+        readability_boost(&mut dom.root);
+        filter_stats
+    }
+
+    /// Fetch the documents of the page's frames the policy lets in, put
+    /// each through its own filter and graft what is left into its
+    /// `<iframe>`.
+    fn embed_frames(&self, dom: &mut DomTree) -> (Vec<FrameLoad>, Vec<RequestRecord>) {
+        let Ok(page) = Url::parse(&dom.url) else {
+            return (Vec::new(), Vec::new());
+        };
+        let mut slots = Vec::new();
+        frames::embeddable(&mut dom.root, &page, &self.frames, &mut slots);
+
+        let fetch = |backend: &dyn FetchBackend, options: &FetchOptions, url: &Url| {
+            let started = netlog::now_ms();
+            let start = Instant::now();
+            let result = backend.get(url.as_str(), options).map_err(PageError::from);
+            (result, start.elapsed(), started)
+        };
+        let refused = |error: PageError| (Err(error), Duration::ZERO, netlog::now_ms());
+        // The fetches run side by side on the frames' own scheduler, dropped
+        // unstarted if the load is cancelled; filtering is quick next to
+        // them. wasm32 has no threads, so there they run one after another.
+        let fetched: Vec<_> = if cfg!(target_arch = "wasm32") {
+            slots
+                .iter()
+                .map(|(_, url)| match self.check_adblock(url.as_str()) {
+                    Ok(()) => fetch(&*self.backend, &self.fetch_options, url),
+                    Err(e) => refused(e),
+                })
+                .collect()
+        } else {
+            let (tx, rx) = mpsc::channel();
+            for (i, (_, url)) in slots.iter().enumerate() {
+                if let Err(e) = self.check_adblock(url.as_str()) {
+                    let _ = tx.send((i, refused(e)));
+                    continue;
+                }
+                let tx = tx.clone();
+                let backend = Arc::clone(&self.backend);
+                let options = self.fetch_options.clone();
+                let url = url.clone();
+                let host_key = url.to_string();
+                frames::fetch_scheduler().submit(
+                    &host_key,
+                    Priority::Page,
+                    &self.cancel,
+                    move |_| {
+                        let _ = tx.send((i, fetch(&*backend, &options, &url)));
+                    },
+                );
+            }
+            drop(tx);
+            // Ends once every job has sent, or was dropped unrun
+            let mut fetched: Vec<_> = slots.iter().map(|_| None).collect();
+            for (i, outcome) in rx {
+                fetched[i] = Some(outcome);
+            }
+            fetched
+                .into_iter()
+                .map(|outcome| {
+                    outcome.unwrap_or_else(|| {
+                        refused(PageError {
+                            message: "Frame fetch failed".into(),
+                            phase: "frame",
+                        })
+                    })
+                })
+                .collect()
+        };
+
+        slots
+            .into_iter()
+            .zip(fetched)
+            .map(|((node, url), (result, fetch_time, started))| {
+                let request = frame_request(&url, &result, started, fetch_time);
+                let start = Instant::now();
+                let mut load = match result.and_then(|r| self.frame_document(&r)) {
+                    Ok((document, load)) => {
+                        frames::graft(node, document, &load);
+                        load
+                    }
+                    Err(e) => FrameLoad::failed(&url, e.message),
+                };
+                load.ms = (fetch_time + start.elapsed()).as_secs_f32() * 1000.0;
+                (load, request)
+            })
+            .unzip()
+    }
+
+    /// The filtered document of a fetched frame, with absolute links.
+    fn frame_document(&self, result: &FetchResult) -> Result<(DomNode, FrameLoad), PageError> {
+        if result.status >= 400 {
+            return Err(PageError {
+                message: format!("HTTP {}", result.status),
+                phase: "frame",
+            });
+        }
+        if !matches!(
+            sniff::sniff(&result.content_type, &result.url, &result.html),
+            ResourceKind::Html
+        ) {
+            return Err(PageError {
+                message: "Not an HTML document".into(),
+                phase: "frame",
+            });
+        }
+        let mut dom = self.parse_within_budget(&result.html, &result.url)?;
+        resources::strip_blocked(&mut dom.root, &dom.url, &self.blocked_origins);
+        let mut log = LoadLog {
+            url: dom.url.clone(),
+            allowlisted: self.allowlist.allows(&dom.url),
+            ..LoadLog::default()
+        };
+        let filter_stats = self.filter_dom(&mut dom, &mut log);
+        if let Ok(base) = Url::parse(&dom.url) {
+            frames::absolutize(&mut dom.root, &base);
+        }
+        let load = FrameLoad {
+            url: dom.url,
+            error: None,
+            filter_stats,
+            removed: log.removed.len(),
+            ms: 0.0,
+        };
+        Ok((dom.root, load))
+    }
+
     /// SIMD-accelerated page processing pipeline.
     ///
     /// Fetch → Parse → `SoA` Transform → SIMD Classify → Prune → SIMD Layout
@@ -186,20 +1315,10 @@ impl BrowserEngine {
     /// Returns `PageError` if ad-block triggers, fetch fails, or SIMD processing fails.
     pub fn load_page_simd(&self, url: &str) -> Result<SimdPageResult, PageError> {
         // Phase 1: Ad block check
-        if let Some(ref ab) = self.adblock {
-            if let Some(reason) = ab.should_block(url) {
-                return Err(PageError {
-                    message: format!("Blocked ({reason:?}): {url}"),
-                    phase: "adblock",
-                });
-            }
-        }
+        self.check_adblock(url)?;
 
         // Phase 2: Fetch
-        let fetch_result = fetch_url(url).map_err(|e| PageError {
-            message: e.message,
-            phase: "fetch",
-        })?;
+        let (fetch_result, _) = self.fetch(url)?;
 
         self.process_html_simd(&fetch_result.html, &fetch_result.url, fetch_result.status)
     }
@@ -208,43 +1327,38 @@ impl BrowserEngine {
     ///
     /// # Errors
     ///
-    /// Returns `PageError` if SIMD processing fails.
+    /// Returns `PageError` if the document exceeds the resource budget.
     pub fn process_html_simd(
         &self,
         html: &str,
         url: &str,
         status: u16,
     ) -> Result<SimdPageResult, PageError> {
-        // Phase 2: Parse HTML → DOM tree
-        let mut dom = parse_html(html, url);
+        // Phase 2: Parse HTML → arena DOM
+        let mut arena = self.parse_arena_within_budget(html, url)?;
 
         // Phase 3: SoA Transform + SIMD Classify
         //
         // Traditional: iterate DOM tree, classify each node (N branches per node)
         // SIMD: flatten to SoA, classify 8 nodes per SIMD instruction (0 branches)
-        let mut soa = dom_to_soa(&dom.root);
-        let simd_stats = classify_batch(&mut soa);
-
-        // Phase 3.5: Apply classifications back to DOM tree
-        let mut idx = 0;
-        apply_classifications(&mut dom.root, soa.classifications.as_slice(), &mut idx);
+        let simd_stats = classify_arena(&mut arena);
 
         // Phase 3.6: Prune ad/tracker subtrees
-        prune_ads(&mut dom.root);
+        arena.prune();
 
         // Phase 3.7: Readability boost
-        readability_boost(&mut dom.root);
+        readability_boost_arena(&mut arena);
 
         // Phase 4: SIMD Layout
         //
         // Traditional: recursive layout_node() with cursor_y accumulation
         // SIMD: flatten visible nodes, batch-compute margins/padding/heights
         let mut flat_nodes = Vec::new();
-        flatten_dom(&dom.root, 0, &mut flat_nodes);
+        flatten_arena(&arena, arena.root(), 0, &mut flat_nodes);
         let layout_boxes = compute_layout_simd(&flat_nodes, self.viewport_width);
 
         Ok(SimdPageResult {
-            dom,
+            dom: arena.to_tree(),
             simd_stats,
             flat_nodes,
             layout_boxes,
@@ -254,13 +1368,9 @@ impl BrowserEngine {
 
     /// SIMD-accelerated filter pass (used by `process_html` when `use_simd=true`)
     #[allow(clippy::unused_self)]
-    fn filter_simd(&self, dom: &mut DomTree) -> FilterStats {
-        let mut soa = dom_to_soa(&dom.root);
-        let simd_stats = classify_batch(&mut soa);
-
-        let mut idx = 0;
-        apply_classifications(&mut dom.root, soa.classifications.as_slice(), &mut idx);
-        prune_ads(&mut dom.root);
+    /// SIMD classification of `dom`, without pruning.
+    fn classify_simd(&self, dom: &mut DomTree) -> FilterStats {
+        let simd_stats = classify_tree(&mut dom.root);
 
         FilterStats {
             total_nodes: simd_stats.total_nodes,
@@ -276,3 +1386,514 @@ impl BrowserEngine {
         self.viewport_width = width;
     }
 }
+
+/// The network-log record of a page's document request.
+fn document_request(
+    method: &str,
+    result: &FetchResult,
+    cache: CacheDecision,
+    started_ms: f64,
+    fetch_ms: f32,
+) -> RequestRecord {
+    RequestRecord {
+        method: method.to_string(),
+        status: Some(result.status),
+        duration_ms: f64::from(fetch_ms),
+        bytes: result.html.len(),
+        content_type: result.content_type.clone(),
+        from_cache: cache == CacheDecision::Hit,
+        ..RequestRecord::get(&result.url, RequestKind::Document, started_ms)
+    }
+}
+
+/// The network-log record of an embedded frame's fetch; frames the ad
+/// blocker refused are marked blocked.
+fn frame_request(
+    url: &Url,
+    result: &Result<FetchResult, PageError>,
+    started_ms: f64,
+    fetch_time: Duration,
+) -> RequestRecord {
+    let mut request = RequestRecord::get(url.as_str(), RequestKind::Frame, started_ms);
+    request.duration_ms = fetch_time.as_secs_f64() * 1000.0;
+    match *result {
+        Ok(ref r) => {
+            request.status = Some(r.status);
+            request.bytes = r.html.len();
+            request.content_type.clone_from(&r.content_type);
+        }
+        Err(ref e) if e.phase == "adblock" => request.blocked = Some(e.message.clone()),
+        Err(ref e) => request.error = Some(e.message.clone()),
+    }
+    request
+}
+
+/// Call `f` with the `src` of every `<img>` under `node`, in document order.
+fn for_each_image(node: &DomNode, f: &(dyn Fn(&str) + Send + Sync)) {
+    // `loading="lazy"` images wait until they are scrolled near
+    if node.tag == "img" && node.attr("loading") != Some("lazy") {
+        if let Some(src) = node.attr("src") {
+            f(src);
+        }
+    }
+    for child in &node.children {
+        for_each_image(child, f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "<html><body><h1>Title</h1><p>Hello</p></body></html>";
+
+    #[test]
+    fn builder_defaults() {
+        let engine = BrowserEngine::builder().build();
+        assert_eq!(engine.viewport(), DEFAULT_VIEWPORT);
+        assert_eq!(engine.classifier(), ClassifierBackend::Simd);
+        assert_eq!(engine.fetch_options().user_agent, DEFAULT_USER_AGENT);
+        assert_eq!(engine.budget(), ResourceBudget::default());
+    }
+
+    #[test]
+    fn builder_applies_settings() {
+        let timeouts = TimeoutPolicy {
+            max_redirects: 2,
+            ..TimeoutPolicy::default()
+        };
+        let engine = BrowserEngine::builder()
+            .viewport(1024.0, 768.0)
+            .user_agent("test-agent")
+            .classifier(ClassifierBackend::Semantic)
+            .timeouts(timeouts)
+            .build();
+        assert_eq!(engine.viewport(), (1024.0, 768.0));
+        assert_eq!(engine.fetch_options().user_agent, "test-agent");
+        assert_eq!(engine.fetch_options().timeouts.max_redirects, 2);
+        assert!(engine
+            .process_html(PAGE, "https://example.com/", 200)
+            .is_ok());
+    }
+
+    #[test]
+    fn loads_through_backend() {
+        let backend = Arc::new(crate::net::backend::StaticBackend::new());
+        backend.insert(
+            "https://example.com/",
+            "<html><head><title>Offline</title></head><body><p>Hi</p></body></html>",
+            "text/html",
+        );
+        let engine = BrowserEngine::builder().backend(backend).build();
+        let page = engine.load_page("https://example.com/").ok().unwrap();
+        assert_eq!(page.dom.title, "Offline");
+        let err = engine
+            .load_page("https://example.com/missing")
+            .err()
+            .unwrap();
+        assert_eq!(err.phase, "fetch");
+    }
+
+    #[test]
+    fn network_log_records_document_frames_and_blocked_requests() {
+        let backend = Arc::new(crate::net::backend::StaticBackend::new());
+        backend.insert(
+            "https://example.com/",
+            "<html><body><p>Main text</p><iframe src=\"/embed\"></iframe>\
+             <img src=\"https://doubleclick.net/px.gif\"></body></html>",
+            "text/html",
+        );
+        backend.insert(
+            "https://example.com/embed",
+            "<html><body><p>Framed text</p></body></html>",
+            "text/html",
+        );
+        let engine = BrowserEngine::builder()
+            .backend(backend)
+            .adblock(Some(Arc::new(AdBlockEngine::new())))
+            .frames(FramePolicy::new(true, ""))
+            .build();
+        let page = engine.load_page("https://example.com/").ok().unwrap();
+        let log = &page.network.entries;
+
+        assert_eq!(log[0].kind, RequestKind::Document);
+        assert_eq!((log[0].status, log[0].method.as_str()), (Some(200), "GET"));
+        assert_eq!(log[0].bytes, page.timings.html_bytes);
+        assert!(log.iter().any(|e| e.kind == RequestKind::Frame
+            && e.url == "https://example.com/embed"
+            && e.status == Some(200)));
+        assert!(log
+            .iter()
+            .any(|e| e.blocked.is_some() && e.url.contains("doubleclick.net")));
+    }
+
+    #[test]
+    fn cancelled_loads_fetch_no_frames() {
+        let backend = Arc::new(crate::net::backend::StaticBackend::new());
+        backend.insert(
+            "https://example.com/",
+            "<html><body><p>Main text</p><iframe src=\"/embed\"></iframe></body></html>",
+            "text/html",
+        );
+        backend.insert(
+            "https://example.com/embed",
+            "<html><body><p>Framed text</p></body></html>",
+            "text/html",
+        );
+        let token = CancelToken::new();
+        token.cancel();
+        let engine = BrowserEngine::builder()
+            .backend(backend)
+            .frames(FramePolicy::new(true, ""))
+            .cancel_token(token)
+            .build();
+        let page = engine.load_page("https://example.com/").ok().unwrap();
+        let frames = &page.decisions.frames;
+        assert_eq!(frames.len(), 1);
+        assert!(frames[0].error.is_some());
+        assert!(!page.dom.root.collect_text().contains("Framed text"));
+    }
+
+    #[test]
+    fn budget_rejects_large_documents() {
+        let budget = ResourceBudget {
+            max_document_bytes: 16,
+            ..ResourceBudget::default()
+        };
+        let engine = BrowserEngine::builder().budget(budget).build();
+        let err = engine
+            .process_html(PAGE, "https://example.com/", 200)
+            .err()
+            .unwrap();
+        assert_eq!(err.phase, "budget");
+        assert_eq!(engine.fetch_options().max_body_bytes, 16);
+
+        let budget = ResourceBudget {
+            max_dom_nodes: 2,
+            ..ResourceBudget::default()
+        };
+        let engine = BrowserEngine::builder().budget(budget).build();
+        assert!(engine
+            .process_html_simd(PAGE, "https://example.com/", 200)
+            .is_err());
+    }
+
+    #[test]
+    fn relayout_reflows_retained_dom() {
+        let engine = BrowserEngine::new(800.0);
+        let mut page = engine
+            .process_html(PAGE, "https://example.com/", 200)
+            .ok()
+            .unwrap();
+        assert!(!page.relayout(800.2));
+        assert!(!page.relayout(0.0));
+
+        assert!(page.relayout(400.0));
+        assert!((page.layout.bounds.width - 400.0).abs() < f32::EPSILON);
+        assert!((page.viewport_width - 400.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn images_are_prefetched_after_filtering() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let engine = BrowserEngine::builder()
+            .image_prefetch(move |src| sink.lock().unwrap().push(src.to_string()))
+            .build();
+        let html = r#"<p>Text <img src="/a.png"></p>
+            <div class="ad-banner"><img src="/ad.png"></div><img src="/b.png">
+            <img src="/c.png" loading="lazy">"#;
+        assert!(engine
+            .process_html(html, "https://example.com/", 200)
+            .is_ok());
+        assert_eq!(*seen.lock().unwrap(), ["/a.png", "/b.png"]);
+    }
+
+    #[test]
+    fn ad_blocked_images_are_not_prefetched() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let engine = BrowserEngine::builder()
+            .adblock(Some(Arc::new(AdBlockEngine::new())))
+            .image_prefetch(move |src| sink.lock().unwrap().push(src.to_string()))
+            .build();
+        let html = r#"<p>Text <img src="/a.png"></p>
+            <img src="https://doubleclick.net/pixel.gif"><img src="/b.png">"#;
+        let page = engine
+            .process_html(html, "https://example.com/", 200)
+            .ok()
+            .unwrap();
+        assert_eq!(*seen.lock().unwrap(), ["/a.png", "/b.png"]);
+        assert_eq!(page.decisions.blocked.len(), 1);
+    }
+
+    #[test]
+    fn blocked_origins_are_listed_but_not_loaded() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let engine = BrowserEngine::builder()
+            .blocked_origins(["https://cdn.example.net".to_string()].into())
+            .image_prefetch(move |src| sink.lock().unwrap().push(src.to_string()))
+            .build();
+        let html = r#"<p>Text <img src="/a.png"></p>
+            <img src="https://cdn.example.net/b.png">"#;
+        let page = engine
+            .process_html(html, "https://example.com/", 200)
+            .ok()
+            .unwrap();
+        assert_eq!(*seen.lock().unwrap(), ["/a.png"]);
+        assert_eq!(page.resources.resources.len(), 2);
+        assert_eq!(page.resources.blocked_count(), 1);
+    }
+
+    #[test]
+    fn saved_pages_load_from_file_urls() {
+        let engine = BrowserEngine::new(400.0);
+        let saved = engine
+            .process_html(
+                "<h1>Offline</h1><p>Kept text</p>",
+                "https://example.com/",
+                200,
+            )
+            .ok()
+            .unwrap();
+        let html = snapshot::Snapshot::capture(&saved.dom, |_| None).to_html();
+        let path = std::env::temp_dir().join(format!("alice-saved-{}.html", std::process::id()));
+        std::fs::write(&path, html).unwrap();
+
+        let url = snapshot::file_url(&path).unwrap();
+        let page = engine.load_file(&url).ok().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(page.dom.url, url);
+        assert!(page.dom.root.collect_text().contains("Kept text"));
+
+        let missing = engine.load_file(&url).err().unwrap();
+        assert_eq!(missing.phase, "file");
+        assert_eq!(
+            engine
+                .load_file("https://example.com/")
+                .err()
+                .unwrap()
+                .phase,
+            "file"
+        );
+    }
+
+    #[test]
+    fn anchors_are_found_in_the_lazily_laid_out_part() {
+        let mut html = String::from("<body><div>");
+        for i in 0..3_000 {
+            html.push_str(&format!("<p>Paragraph {i}</p>"));
+        }
+        html.push_str(r#"<h2 id="fin de page">The end</h2>"#);
+        html.push_str(r#"<a name="legacy">Back to top</a></div></body>"#);
+        let engine = BrowserEngine::new(800.0);
+        let mut page = engine
+            .process_html(&html, "https://example.com/", 200)
+            .ok()
+            .unwrap();
+        assert!(page.lazy.is_some());
+        assert!(page.layout.find_anchor("fin de page").is_none());
+
+        let top = page.find_anchor("fin%20de%20page").map(|n| n.bounds.y);
+        assert!(top.is_some_and(|y| y > 10_000.0));
+        assert!(page.lazy.is_none());
+        assert_eq!(
+            page.find_anchor("legacy").map(|n| n.tag.as_str()),
+            Some("a")
+        );
+        assert!(page.find_anchor("missing").is_none());
+    }
+
+    #[test]
+    fn soft_reload_patches_at_the_current_zoom() {
+        let engine = BrowserEngine::new(400.0);
+        let load = |html: &str| {
+            engine
+                .process_html(html, "https://example.com/", 200)
+                .ok()
+                .unwrap()
+        };
+        let mut page = load("<h1>Scores</h1><p>Home 1 - 0 Away</p>");
+        assert!(page.set_zoom(1.5));
+
+        let (changes, patch) =
+            page.soft_reload(load("<h1>Scores</h1><p>Home 1 - 1 Away</p>"), 600.0);
+        assert!(!changes.is_empty() && !changes.is_structural());
+        assert_eq!(patch.replaced, 0);
+        assert!((page.zoom - 1.5).abs() < f32::EPSILON);
+        assert!((page.layout.font_size - DEFAULT_FONT_SIZE * 1.5).abs() < f32::EPSILON);
+        assert!(page.dom.root.collect_text().contains("1 - 1"));
+    }
+
+    #[test]
+    fn zoom_scales_fonts_and_layout() {
+        let engine = BrowserEngine::new(400.0);
+        let mut page = engine
+            .process_html(PAGE, "https://example.com/", 200)
+            .ok()
+            .unwrap();
+        let height = page.layout.bounds.height;
+        assert!(!page.set_zoom(1.0));
+
+        assert!(page.set_zoom(2.0));
+        assert!((page.layout.font_size - DEFAULT_FONT_SIZE * 2.0).abs() < f32::EPSILON);
+        assert!(page.layout.bounds.height > height);
+        // Width changes keep the zoom
+        assert!(page.relayout(500.0));
+        assert!((page.layout.font_size - DEFAULT_FONT_SIZE * 2.0).abs() < f32::EPSILON);
+
+        assert!(page.set_zoom(10.0));
+        assert!((page.zoom - 3.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn zoom_steps_through_levels() {
+        assert!((step_zoom(1.0, 1) - 1.1).abs() < f32::EPSILON);
+        assert!((step_zoom(1.0, -1) - 0.9).abs() < f32::EPSILON);
+        assert!((step_zoom(1.2, 1) - 1.25).abs() < f32::EPSILON);
+        assert!((step_zoom(3.0, 1) - 3.0).abs() < f32::EPSILON);
+        assert!((step_zoom(0.5, -1) - 0.5).abs() < f32::EPSILON);
+        assert!((step_zoom(2.0, 0) - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn text_resources_take_their_own_branch() {
+        let engine = BrowserEngine::new(800.0);
+        let page = engine
+            .process_response(PAGE, "", "https://example.com/", 200)
+            .ok()
+            .unwrap();
+        assert!(page.text_view.is_none());
+
+        let page = engine
+            .process_response(
+                "id,name\n1,Ada\n",
+                "text/plain",
+                "https://example.com/people.csv",
+                200,
+            )
+            .ok()
+            .unwrap();
+        assert_eq!(page.text_view.map(|v| v.kind), Some(ResourceKind::Csv));
+        assert_eq!(page.dom.title, "people.csv");
+        assert!(page.dom.root.collect_text().contains("Ada"));
+    }
+
+    #[test]
+    fn simulated_offline_network_fails_loads() {
+        let engine = BrowserEngine::builder()
+            .network(crate::net::throttle::NetworkProfile::Offline.conditions())
+            .build();
+        let err = engine.load_page("https://example.com/").err().unwrap();
+        assert_eq!(err.phase, "fetch");
+        assert!(err.message.contains("simulated"));
+    }
+
+    #[test]
+    fn adblock_rejects_blocked_page() {
+        let engine = BrowserEngine::builder()
+            .adblock(Some(Arc::new(AdBlockEngine::new())))
+            .build();
+        let err = engine
+            .load_page("https://doubleclick.net/ad")
+            .err()
+            .unwrap();
+        assert_eq!(err.phase, "adblock");
+    }
+
+    #[test]
+    fn allowlisted_sites_are_not_blocked_or_pruned() {
+        let mut allowlist = Allowlist::new();
+        allowlist.insert("doubleclick.net");
+        allowlist.insert("example.com");
+        let engine = BrowserEngine::builder()
+            .adblock(Some(Arc::new(AdBlockEngine::new())))
+            .allowlist(allowlist)
+            .build();
+        assert!(engine.check_adblock("https://doubleclick.net/ad").is_ok());
+
+        let html = r#"<p>Text</p><aside class="ad-slot"><p>Buy now</p></aside>"#;
+        let page = engine
+            .process_html(html, "https://www.example.com/", 200)
+            .ok()
+            .unwrap();
+        assert!(page.decisions.allowlisted);
+        assert_eq!(page.decisions.removed.len(), 1);
+        assert!(page.dom.root.collect_text().contains("Buy now"));
+    }
+
+    #[test]
+    fn feedback_rules_apply_on_load() {
+        let mut rules = FeedbackRules::default();
+        rules.mark("aside.ad-slot", filter::Verdict::Content);
+        rules.mark("div.partner", filter::Verdict::Ad);
+        let html = r#"<p>Text</p><aside class="ad-slot"><p>House notice</p></aside>
+            <div class="partner"><p>Sponsored pick</p></div>"#;
+        let load = |show_removed: bool| {
+            BrowserEngine::builder()
+                .feedback(rules.clone())
+                .show_removed(show_removed)
+                .build()
+                .process_html(html, "https://example.com/", 200)
+                .ok()
+                .unwrap()
+        };
+        let page = load(false);
+        let text = page.dom.root.collect_text();
+        assert!(text.contains("House notice"));
+        assert!(!text.contains("Sponsored pick"));
+        let removal = &page.decisions.removed[0];
+        assert_eq!(
+            removal.describe(),
+            "Advertisement (rule feedback:div.partner)"
+        );
+
+        let shown = load(true);
+        assert!(shown.dom.root.collect_text().contains("Sponsored pick"));
+        assert_eq!(shown.decisions.removed.len(), 1);
+    }
+
+    #[test]
+    fn loaded_models_replace_the_classifier() {
+        use crate::dom::model::{Layer, NUM_CLASSES, NUM_FEATURES};
+
+        // Content by default, an ad when the class matches an ad pattern
+        let mut weights = vec![vec![0.0; NUM_FEATURES]; NUM_CLASSES];
+        weights[2][4] = 2.0;
+        let mut bias = vec![0.0; NUM_CLASSES];
+        bias[0] = 1.0;
+        let model = ClassifierModel {
+            name: "promo".into(),
+            version: "1".into(),
+            layers: vec![Layer { weights, bias }],
+        };
+        model.validate().ok().unwrap();
+        let model = Arc::new(model);
+
+        for (backend, label) in [
+            (ClassifierBackend::Simd, "simd model promo 1"),
+            (ClassifierBackend::Semantic, "model promo 1"),
+        ] {
+            let page = BrowserEngine::builder()
+                .classifier(backend)
+                .model(Some(Arc::clone(&model)))
+                .build()
+                .process_html(
+                    r#"<p>Story</p><div class="promo"><p>Deal</p></div>"#,
+                    "https://example.com/",
+                    200,
+                )
+                .ok()
+                .unwrap();
+            assert_eq!(page.decisions.classifier, label);
+            let text = page.dom.root.collect_text();
+            assert!(text.contains("Story"));
+            assert!(!text.contains("Deal"));
+            assert_eq!(
+                page.decisions.removed[0].describe(),
+                "Advertisement (promo 1 score 2.00)"
+            );
+        }
+    }
+}
@@ -0,0 +1,800 @@
+//! Printing to PDF.
+//!
+//! [`print_pdf`] lays a document out again at the width of the printed
+//! page, measured in points, cuts it into pages between lines of text and
+//! images, and writes a PDF: text in the standard Helvetica and Courier
+//! fonts, images as JPEG, links as link annotations, and a footer with the
+//! title and page number on every page. No fonts are embedded, so characters
+//! outside Windows-1252 print as `?`.
+//!
+//! [`PrintSource::Reader`], the default, prints the article reader mode
+//! shows; [`PrintSource::Page`] prints the whole filtered page.
+
+use std::fmt::Write as _;
+
+use image::ImageEncoder;
+use url::Url;
+
+use crate::dom::readability::extract_article;
+use crate::dom::{DomNode, DomTree};
+use crate::net::image::ImageData;
+use crate::render::layout::{compute_layout_with_font, LayoutNode};
+
+/// Points per millimetre.
+const PT_PER_MM: f32 = 72.0 / 25.4;
+/// Points per CSS pixel, for the natural size of images.
+const PT_PER_PX: f32 = 0.75;
+/// Body text size in points.
+pub const BODY_FONT_SIZE: f32 = 11.0;
+const FOOTER_FONT_SIZE: f32 = 8.0;
+/// Space between the footer and the printed area.
+const FOOTER_GAP: f32 = 12.0;
+/// Space below an image.
+const IMAGE_GAP: f32 = 6.0;
+const JPEG_QUALITY: u8 = 85;
+/// Widest margin accepted, in millimetres.
+pub const MAX_MARGIN_MM: f32 = 50.0;
+
+/// Sheet of paper printed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaperSize {
+    #[default]
+    A4,
+    A5,
+    Letter,
+    Legal,
+}
+
+impl PaperSize {
+    pub const ALL: [Self; 4] = [Self::A4, Self::A5, Self::Letter, Self::Legal];
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::A4 => "A4 (210 × 297 mm)",
+            Self::A5 => "A5 (148 × 210 mm)",
+            Self::Letter => "Letter (8.5 × 11 in)",
+            Self::Legal => "Legal (8.5 × 14 in)",
+        }
+    }
+
+    /// Width and height in points, portrait.
+    #[must_use]
+    pub const fn size(self) -> [f32; 2] {
+        match self {
+            Self::A4 => [595.28, 841.89],
+            Self::A5 => [419.53, 595.28],
+            Self::Letter => [612.0, 792.0],
+            Self::Legal => [612.0, 1008.0],
+        }
+    }
+}
+
+/// What is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrintSource {
+    /// The main article, as in reader mode; the whole page if there is none
+    #[default]
+    Reader,
+    /// Everything the filter kept
+    Page,
+}
+
+impl PrintSource {
+    pub const ALL: [Self; 2] = [Self::Reader, Self::Page];
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Reader => "Article (reader view)",
+            Self::Page => "Whole page",
+        }
+    }
+}
+
+/// Paper, margins and content of a print.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrintOptions {
+    pub paper: PaperSize,
+    /// Margin on every side, in millimetres
+    pub margin_mm: f32,
+    pub source: PrintSource,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            paper: PaperSize::A4,
+            margin_mm: 15.0,
+            source: PrintSource::Reader,
+        }
+    }
+}
+
+/// A finished print.
+#[derive(Debug, Clone)]
+pub struct PrintedPdf {
+    pub bytes: Vec<u8>,
+    pub pages: usize,
+    /// Source actually printed: the whole page if reader mode found no article
+    pub source: PrintSource,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+impl Font {
+    /// Resource name in the page's font dictionary.
+    const fn resource(self) -> &'static str {
+        match self {
+            Self::Regular => "F1",
+            Self::Bold => "F2",
+            Self::Mono => "F3",
+        }
+    }
+
+    /// Font of the text under `tag`, inside text set in `outer`.
+    fn for_tag(tag: &str, outer: Self) -> Self {
+        match tag {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "b" | "strong" | "th" => Self::Bold,
+            "pre" | "code" | "kbd" | "samp" | "tt" => Self::Mono,
+            _ => outer,
+        }
+    }
+}
+
+/// Something drawn on a page, positioned in layout coordinates (points
+/// from the top of the document).
+#[derive(Debug, Clone)]
+enum Item {
+    Text {
+        x: f32,
+        y: f32,
+        width: f32,
+        size: f32,
+        font: Font,
+        text: String,
+        link: Option<String>,
+    },
+    Image {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        /// Index into the embedded images
+        index: usize,
+    },
+    Rule {
+        x: f32,
+        y: f32,
+        width: f32,
+    },
+}
+
+impl Item {
+    const fn top(&self) -> f32 {
+        match *self {
+            Self::Text { y, .. } | Self::Image { y, .. } | Self::Rule { y, .. } => y,
+        }
+    }
+
+    fn height(&self) -> f32 {
+        match *self {
+            Self::Text { size, .. } => size * crate::render::line_break::LINE_HEIGHT,
+            Self::Image { height, .. } => height,
+            Self::Rule { .. } => 1.0,
+        }
+    }
+}
+
+/// An image encoded for embedding.
+struct Jpeg {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+/// Walks the layout, collecting what is drawn.
+struct Collector<'a, F> {
+    base: Option<Url>,
+    /// Printable width
+    width: f32,
+    /// Tallest image that fits on a page
+    max_height: f32,
+    image: F,
+    images: Vec<Jpeg>,
+    items: Vec<Item>,
+    /// Room added so far for images, which layout gives no height
+    extra: f32,
+    _data: std::marker::PhantomData<&'a ImageData>,
+}
+
+impl<'a, F: Fn(&str) -> Option<&'a ImageData>> Collector<'a, F> {
+    fn collect(&mut self, node: &LayoutNode, font: Font, link: Option<&str>) {
+        let font = Font::for_tag(&node.tag, font);
+        let link = match node.tag.as_str() {
+            "a" => node.href.as_deref().or(link),
+            _ => link,
+        };
+        match node.tag.as_str() {
+            "img" => {
+                if let Some(data) = node.href.as_deref().and_then(&self.image) {
+                    self.add_image(node, data);
+                }
+                return;
+            }
+            "hr" => {
+                self.items.push(Item::Rule {
+                    x: node.bounds.x,
+                    y: node.bounds.y + node.bounds.height / 2.0 + self.extra,
+                    width: node.bounds.width,
+                });
+            }
+            "" => {
+                let target = link.map(|href| self.resolve(href));
+                for (text, lb) in node.text_lines() {
+                    self.items.push(Item::Text {
+                        x: lb.x,
+                        y: lb.y + self.extra,
+                        width: lb.width,
+                        size: node.font_size,
+                        font,
+                        text: text.trim().to_string(),
+                        link: target.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+        for child in &node.children {
+            self.collect(child, font, link);
+        }
+    }
+
+    /// Place `data` at its natural size, shrunk to fit the page, and make
+    /// room for it below.
+    fn add_image(&mut self, node: &LayoutNode, data: &ImageData) {
+        if data.width == 0 || data.height == 0 {
+            return;
+        }
+        let aspect = data.height as f32 / data.width as f32;
+        let available = (self.width - node.bounds.x).max(1.0);
+        let mut width = (data.width as f32 * PT_PER_PX).min(available);
+        if width * aspect > self.max_height {
+            width = self.max_height / aspect;
+        }
+        let height = width * aspect;
+        let Some(jpeg) = encode_jpeg(data) else {
+            return;
+        };
+        self.images.push(jpeg);
+        self.items.push(Item::Image {
+            x: node.bounds.x,
+            y: node.bounds.y + self.extra,
+            width,
+            height,
+            index: self.images.len() - 1,
+        });
+        self.extra += height + IMAGE_GAP;
+    }
+
+    fn resolve(&self, href: &str) -> String {
+        self.base
+            .as_ref()
+            .and_then(|base| base.join(href).ok())
+            .map_or_else(|| href.to_string(), String::from)
+    }
+}
+
+/// Flatten RGBA onto white and encode as JPEG.
+fn encode_jpeg(data: &ImageData) -> Option<Jpeg> {
+    let rgb: Vec<u8> = data
+        .rgba
+        .chunks_exact(4)
+        .flat_map(|px| {
+            let a = u16::from(px[3]);
+            let over_white = |c: u8| ((u16::from(c) * a + 255 * (255 - a)) / 255) as u8;
+            [over_white(px[0]), over_white(px[1]), over_white(px[2])]
+        })
+        .collect();
+    let mut out = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY)
+        .write_image(
+            &rgb,
+            data.width,
+            data.height,
+            image::ExtendedColorType::Rgb8,
+        )
+        .map_err(|e| log::warn!("Cannot encode image for printing: {e}"))
+        .ok()?;
+    Some(Jpeg {
+        width: data.width,
+        height: data.height,
+        data: out,
+    })
+}
+
+/// Items of each page, moved to the page's own coordinates. A page ends
+/// before the first item that would cross its bottom edge.
+fn paginate(mut items: Vec<Item>, page_height: f32) -> Vec<Vec<Item>> {
+    items.sort_by(|a, b| a.top().total_cmp(&b.top()));
+    let mut pages: Vec<Vec<Item>> = vec![Vec::new()];
+    let mut page_top = items.first().map_or(0.0, Item::top);
+    for mut item in items {
+        let top = item.top();
+        if top + item.height() > page_top + page_height && top > page_top {
+            page_top = top;
+            pages.push(Vec::new());
+        }
+        match item {
+            Item::Text { ref mut y, .. }
+            | Item::Image { ref mut y, .. }
+            | Item::Rule { ref mut y, .. } => *y -= page_top,
+        }
+        pages.last_mut().expect("at least one page").push(item);
+    }
+    pages
+}
+
+/// Print `tree` as a PDF, with the decoded images `image` returns for the
+/// `src` of each `<img>` as written in the page.
+#[must_use]
+pub fn print_pdf<'a>(
+    tree: &DomTree,
+    options: &PrintOptions,
+    image: impl Fn(&str) -> Option<&'a ImageData>,
+) -> PrintedPdf {
+    let article = match options.source {
+        PrintSource::Reader => extract_article(tree),
+        PrintSource::Page => None,
+    };
+    let (title, root, source): (&str, &DomNode, _) = match article {
+        Some(ref article) => (
+            article.title.as_str(),
+            &article.content,
+            PrintSource::Reader,
+        ),
+        None => (tree.title.as_str(), &tree.root, PrintSource::Page),
+    };
+
+    let [page_width, page_height] = options.paper.size();
+    let margin = options.margin_mm.clamp(0.0, MAX_MARGIN_MM) * PT_PER_MM;
+    let width = margin.mul_add(-2.0, page_width);
+    let footer = FOOTER_GAP + FOOTER_FONT_SIZE;
+    let height = margin.mul_add(-2.0, page_height) - footer;
+
+    let layout = compute_layout_with_font(root, width, BODY_FONT_SIZE);
+    let mut collector = Collector {
+        base: Url::parse(&tree.url).ok(),
+        width,
+        max_height: height,
+        image,
+        images: Vec::new(),
+        items: Vec::new(),
+        extra: 0.0,
+        _data: std::marker::PhantomData,
+    };
+    collector.collect(&layout, Font::Regular, None);
+    let pages = paginate(collector.items, height);
+
+    let mut pdf = PdfWriter::default();
+    let page_count = pages.len();
+    // Objects 1–6 are fixed; images follow, then two per page
+    let first_image = 7;
+    let first_page = first_image + collector.images.len();
+    let kids: Vec<String> = (0..page_count)
+        .map(|i| format!("{} 0 R", first_page + 2 * i))
+        .collect();
+
+    pdf.object("<< /Type /Catalog /Pages 2 0 R >>");
+    pdf.object(&format!(
+        "<< /Type /Pages /Kids [{}] /Count {page_count} >>",
+        kids.join(" ")
+    ));
+    for base in ["Helvetica", "Helvetica-Bold", "Courier"] {
+        pdf.object(&format!(
+            "<< /Type /Font /Subtype /Type1 /BaseFont /{base} /Encoding /WinAnsiEncoding >>"
+        ));
+    }
+    pdf.object(&format!(
+        "<< /Title {} /Producer (ALICE Browser) >>",
+        text_string(title)
+    ));
+    for jpeg in &collector.images {
+        pdf.stream(
+            &format!(
+                "/Type /XObject /Subtype /Image /Width {} /Height {} \
+                 /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode",
+                jpeg.width, jpeg.height
+            ),
+            &jpeg.data,
+        );
+    }
+
+    let xobjects: String = (0..collector.images.len())
+        .map(|i| format!("/Im{i} {} 0 R ", first_image + i))
+        .collect();
+    for (number, items) in pages.iter().enumerate() {
+        let content = page_content(
+            items,
+            [page_width, page_height],
+            margin,
+            (number + 1, page_count),
+            title,
+        );
+        let annots = link_annotations(items, [margin, page_height - margin]);
+        let contents = pdf.next_id() + 1;
+        pdf.object(&format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {page_width} {page_height}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R /F3 5 0 R >> /XObject << {xobjects}>> >> \
+             /Contents {contents} 0 R /Annots [{annots}] >>"
+        ));
+        pdf.stream("", content.as_bytes());
+    }
+
+    PrintedPdf {
+        bytes: pdf.finish(1, 6),
+        pages: page_count,
+        source,
+    }
+}
+
+/// Drawing operators for page `number` of `count`, on a sheet `page`
+/// points large.
+fn page_content(
+    items: &[Item],
+    page: [f32; 2],
+    margin: f32,
+    (number, count): (usize, usize),
+    title: &str,
+) -> String {
+    let [page_width, page_height] = page;
+    let (left, top) = (margin, page_height - margin);
+    let mut out = String::new();
+    for item in items {
+        match *item {
+            Item::Text {
+                x,
+                y,
+                size,
+                font,
+                ref text,
+                ref link,
+                ..
+            } => {
+                let color = if link.is_some() { "0 0 0.7" } else { "0 0 0" };
+                // Baseline three quarters down the line box
+                let baseline = top - y - size * 1.05;
+                let _ = writeln!(
+                    out,
+                    "BT /{} {size:.2} Tf {color} rg {:.2} {baseline:.2} Td {} Tj ET",
+                    font.resource(),
+                    left + x,
+                    win_ansi_string(text)
+                );
+            }
+            Item::Image {
+                x,
+                y,
+                width,
+                height,
+                index,
+            } => {
+                let _ = writeln!(
+                    out,
+                    "q {width:.2} 0 0 {height:.2} {:.2} {:.2} cm /Im{index} Do Q",
+                    left + x,
+                    top - y - height
+                );
+            }
+            Item::Rule { x, y, width } => {
+                let _ = writeln!(
+                    out,
+                    "q 0.7 G 0.5 w {:.2} {2:.2} m {:.2} {2:.2} l S Q",
+                    left + x,
+                    left + x + width,
+                    top - y
+                );
+            }
+        }
+    }
+    // Footer in the bottom margin band: title on the left, page number on
+    // the right (Helvetica digits are 0.556 em wide)
+    let number = format!("{number} / {count}");
+    let number_width = number.chars().count() as f32 * FOOTER_FONT_SIZE * 0.556;
+    let room = ((page_width - 2.0 * margin - number_width - 24.0) / (FOOTER_FONT_SIZE * 0.5))
+        .max(1.0) as usize;
+    let title: String = if title.chars().count() > room {
+        title.chars().take(room - 1).chain(['…']).collect()
+    } else {
+        title.to_string()
+    };
+    let _ = writeln!(
+        out,
+        "BT /F1 {FOOTER_FONT_SIZE} Tf 0.4 g {left:.2} {y:.2} Td {} Tj ET\n\
+         BT /F1 {FOOTER_FONT_SIZE} Tf 0.4 g {:.2} {y:.2} Td {} Tj ET",
+        win_ansi_string(&title),
+        page_width - left - number_width,
+        win_ansi_string(&number),
+        y = margin,
+    );
+    out
+}
+
+/// `/Link` annotations over the lines of text inside links; `origin` is
+/// the top-left corner of the printed area.
+fn link_annotations(items: &[Item], origin: [f32; 2]) -> String {
+    let [left, top] = origin;
+    let mut out = String::new();
+    for item in items {
+        if let Item::Text {
+            x,
+            y,
+            width,
+            size,
+            link: Some(ref href),
+            ..
+        } = *item
+        {
+            let bottom = top - y - size * crate::render::line_break::LINE_HEIGHT;
+            let _ = write!(
+                out,
+                "<< /Type /Annot /Subtype /Link /Border [0 0 0] \
+                 /Rect [{:.2} {bottom:.2} {:.2} {:.2}] /A << /S /URI /URI {} >> >> ",
+                left + x,
+                left + x + width,
+                top - y,
+                literal_string(href.as_bytes())
+            );
+        }
+    }
+    out
+}
+
+/// `text` as a PDF string in Windows-1252, the encoding of the standard
+/// fonts; anything else becomes `?`.
+fn win_ansi_string(text: &str) -> String {
+    let bytes: Vec<u8> = text.chars().map(win_ansi).collect();
+    literal_string(&bytes)
+}
+
+fn win_ansi(c: char) -> u8 {
+    match c {
+        ' '..='~' => c as u8,
+        '\u{a0}'..='\u{ff}' => c as u32 as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8a,
+        '‹' => 0x8b,
+        'Œ' => 0x8c,
+        'Ž' => 0x8e,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9a,
+        '›' => 0x9b,
+        'œ' => 0x9c,
+        'ž' => 0x9e,
+        'Ÿ' => 0x9f,
+        '\t' | '\n' | '\r' => b' ',
+        _ => b'?',
+    }
+}
+
+/// `( … )` string with the delimiters and backslash escaped and bytes
+/// outside ASCII written in octal.
+fn literal_string(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() + 2);
+    out.push('(');
+    for &b in bytes {
+        match b {
+            b'(' | b')' | b'\\' => {
+                out.push('\\');
+                out.push(b as char);
+            }
+            b' '..=b'~' => out.push(b as char),
+            _ => {
+                let _ = write!(out, "\\{b:03o}");
+            }
+        }
+    }
+    out.push(')');
+    out
+}
+
+/// A text string for the document information: UTF-16 with a byte order
+/// mark, so titles in any script survive.
+fn text_string(text: &str) -> String {
+    let mut out = String::from("<FEFF");
+    for unit in text.encode_utf16() {
+        let _ = write!(out, "{unit:04X}");
+    }
+    out.push('>');
+    out
+}
+
+/// Writes numbered objects and the cross-reference table.
+#[derive(Default)]
+struct PdfWriter {
+    out: Vec<u8>,
+    /// Byte offset of each object, by number − 1
+    offsets: Vec<usize>,
+}
+
+impl PdfWriter {
+    /// Number the next object gets.
+    fn next_id(&self) -> usize {
+        self.offsets.len() + 1
+    }
+
+    fn begin(&mut self) {
+        if self.out.is_empty() {
+            // Binary marker after the header, as the format recommends
+            self.out.extend_from_slice(b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n");
+        }
+        self.offsets.push(self.out.len());
+        let header = format!("{} 0 obj\n", self.offsets.len());
+        self.out.extend_from_slice(header.as_bytes());
+    }
+
+    fn object(&mut self, body: &str) {
+        self.begin();
+        self.out.extend_from_slice(body.as_bytes());
+        self.out.extend_from_slice(b"\nendobj\n");
+    }
+
+    /// A stream object with the extra dictionary entries `dict`.
+    fn stream(&mut self, dict: &str, data: &[u8]) {
+        self.begin();
+        let head = format!("<< {dict} /Length {} >>\nstream\n", data.len());
+        self.out.extend_from_slice(head.as_bytes());
+        self.out.extend_from_slice(data);
+        self.out.extend_from_slice(b"\nendstream\nendobj\n");
+    }
+
+    fn finish(mut self, root: usize, info: usize) -> Vec<u8> {
+        let xref = self.out.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", self.offsets.len() + 1);
+        for offset in &self.offsets {
+            let _ = writeln!(table, "{offset:010} 00000 n ");
+        }
+        let _ = writeln!(
+            table,
+            "trailer\n<< /Size {} /Root {root} 0 R /Info {info} 0 R >>\nstartxref\n{xref}\n%%EOF",
+            self.offsets.len() + 1
+        );
+        self.out.extend_from_slice(table.as_bytes());
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+
+    /// Object offsets from the cross-reference table, checked against the
+    /// file.
+    fn check_structure(pdf: &[u8]) -> usize {
+        let text = String::from_utf8_lossy(pdf);
+        assert!(pdf.starts_with(b"%PDF-1.4\n"));
+        assert!(text.ends_with("%%EOF\n"));
+        let startxref: usize = text
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|rest| rest.lines().next())
+            .and_then(|n| n.parse().ok())
+            .unwrap();
+        // The table and trailer are ASCII, unlike the images before them
+        let table = std::str::from_utf8(&pdf[startxref..]).unwrap();
+        assert!(table.starts_with("xref\n"));
+        let entries: Vec<usize> = table
+            .lines()
+            .skip(3)
+            .take_while(|l| l.ends_with(" n "))
+            .map(|l| l[..10].parse().unwrap())
+            .collect();
+        for (i, &offset) in entries.iter().enumerate() {
+            let header = format!("{} 0 obj\n", i + 1);
+            assert!(
+                pdf[offset..].starts_with(header.as_bytes()),
+                "object {}",
+                i + 1
+            );
+        }
+        entries.len()
+    }
+
+    fn page_count(pdf: &[u8]) -> usize {
+        String::from_utf8_lossy(pdf).matches("/Type /Page ").count()
+    }
+
+    #[test]
+    fn long_documents_are_paginated() {
+        let paragraphs = "<p>Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do \
+                          eiusmod tempor incididunt ut labore et dolore magna aliqua.</p>"
+            .repeat(120);
+        let tree = parse_html(
+            &format!(
+                "<html><head><title>Long (read)</title></head><body>{paragraphs}</body></html>"
+            ),
+            "https://example.com/long",
+        );
+        let options = PrintOptions {
+            source: PrintSource::Page,
+            ..PrintOptions::default()
+        };
+        let printed = print_pdf(&tree, &options, |_| None);
+        assert!(printed.pages > 3, "{} pages", printed.pages);
+        assert_eq!(page_count(&printed.bytes), printed.pages);
+        assert_eq!(check_structure(&printed.bytes), 6 + 2 * printed.pages);
+        let text = String::from_utf8_lossy(&printed.bytes);
+        assert!(text.contains(&format!("/Count {}", printed.pages)));
+        assert!(text.contains(&format!("(1 / {}) Tj", printed.pages)));
+        // Parentheses in the title are escaped in the footer
+        assert!(text.contains("(Long \\(read\\)) Tj"));
+
+        // A smaller sheet takes more pages
+        let a5 = PrintOptions {
+            paper: PaperSize::A5,
+            ..options
+        };
+        assert!(print_pdf(&tree, &a5, |_| None).pages > printed.pages);
+    }
+
+    #[test]
+    fn reader_source_prints_the_article_with_images_and_links() {
+        let body = "Traits describe shared behaviour between types in Rust programs. ".repeat(6);
+        let tree = parse_html(
+            &format!(
+                r#"<html><head><title>Traits</title></head><body>
+                <nav><a href="/">Home</a> <a href="/about">About</a></nav>
+                <article><h1>Traits</h1><p>{body}</p><img src="chart.png">
+                <p>See <a href="/docs">the docs</a>.</p></article></body></html>"#
+            ),
+            "https://example.com/post",
+        );
+        let pixel = ImageData {
+            width: 40,
+            height: 20,
+            rgba: vec![200; 40 * 20 * 4],
+            encoded_bytes: 0,
+        };
+        let printed = print_pdf(&tree, &PrintOptions::default(), |src| {
+            (src == "chart.png").then_some(&pixel)
+        });
+        assert_eq!(printed.source, PrintSource::Reader);
+        assert_eq!(printed.pages, 1);
+        assert_eq!(check_structure(&printed.bytes), 7 + 2);
+        let text = String::from_utf8_lossy(&printed.bytes);
+        assert!(text.contains("/Filter /DCTDecode"));
+        assert!(text.contains("/Im0 Do"));
+        assert!(text.contains("/URI (https://example.com/docs)"));
+        assert!(!text.contains("(About)"), "navigation is left out");
+        assert!(text.contains("/F2 "), "the heading is bold");
+    }
+
+    #[test]
+    fn strings_are_escaped() {
+        assert_eq!(win_ansi_string("a(b)c\\"), "(a\\(b\\)c\\\\)");
+        assert_eq!(win_ansi_string("café – 猫"), "(caf\\351 \\226 ?)");
+        assert_eq!(text_string("猫A"), "<FEFF732B0041>");
+    }
+}
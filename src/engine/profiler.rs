@@ -0,0 +1,69 @@
+//! Per-stage timings of one page load.
+//!
+//! The pipeline times each phase (fetch, parse, filter, layout, scene) and
+//! keeps the result on the `PageResult`, next to the decision log. The
+//! resource-timing overlay apportions these totals to individual elements.
+
+use web_time::Instant;
+
+use serde::Serialize;
+
+/// Wall-clock time spent in each pipeline stage, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct StageTimings {
+    /// Network or cache (0 when HTML was handed in directly)
+    pub fetch_ms: f32,
+    pub parse_ms: f32,
+    /// Classification and pruning
+    pub filter_ms: f32,
+    /// Fetching and filtering embedded frames (0 unless enabled)
+    pub frames_ms: f32,
+    /// Most recent layout pass (updated on reflow)
+    pub layout_ms: f32,
+    /// SDF scene generation
+    pub scene_ms: f32,
+    /// Size of the HTML document
+    pub html_bytes: usize,
+}
+
+impl StageTimings {
+    /// Sum of all stages.
+    #[must_use]
+    pub fn total_ms(&self) -> f32 {
+        self.fetch_ms
+            + self.parse_ms
+            + self.filter_ms
+            + self.frames_ms
+            + self.layout_ms
+            + self.scene_ms
+    }
+}
+
+/// Lap timer for consecutive stages.
+#[derive(Debug, Clone, Copy)]
+pub struct StageTimer {
+    last: Instant,
+}
+
+impl Default for StageTimer {
+    fn default() -> Self {
+        Self::start()
+    }
+}
+
+impl StageTimer {
+    #[must_use]
+    pub fn start() -> Self {
+        Self {
+            last: Instant::now(),
+        }
+    }
+
+    /// Milliseconds since the previous lap (or the start).
+    pub fn lap(&mut self) -> f32 {
+        let now = Instant::now();
+        let ms = now.duration_since(self.last).as_secs_f32() * 1000.0;
+        self.last = now;
+        ms
+    }
+}
@@ -0,0 +1,372 @@
+//! Per-page resource manifest.
+//!
+//! Right after parsing, the pipeline lists every subresource the page refers
+//! to — scripts, frames, stylesheets, fonts, images and media — with the
+//! origin it would be loaded from. URLs the ad blocker files as trackers are
+//! listed as [`ResourceKind::Tracker`] whatever element refers to them.
+//!
+//! Origins can be blocked per site: the engine is given the set
+//! ([`BrowserEngineBuilder::blocked_origins`]) and [`strip_blocked`] removes
+//! the elements loading from them before the page is filtered and laid out,
+//! so their images are never requested. The manifest still lists them,
+//! marked blocked, so they can be allowed again.
+//!
+//! [`BrowserEngineBuilder::blocked_origins`]: crate::engine::pipeline::BrowserEngineBuilder::blocked_origins
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use url::Url;
+
+use crate::dom::DomNode;
+use crate::net::adblock::{AdBlockEngine, BlockReason};
+
+/// What a subresource is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceKind {
+    Script,
+    /// `<iframe>`, `<frame>`, `<embed>` and `<object>`
+    Frame,
+    /// Anything the ad blocker files as a tracker
+    Tracker,
+    Font,
+    Stylesheet,
+    Image,
+    /// `<video>`, `<audio>` and their `<source>`s
+    Media,
+}
+
+impl ResourceKind {
+    pub const ALL: [Self; 7] = [
+        Self::Script,
+        Self::Frame,
+        Self::Tracker,
+        Self::Font,
+        Self::Stylesheet,
+        Self::Image,
+        Self::Media,
+    ];
+
+    /// Plural label, e.g. "scripts".
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Script => "scripts",
+            Self::Frame => "frames",
+            Self::Tracker => "trackers",
+            Self::Font => "fonts",
+            Self::Stylesheet => "stylesheets",
+            Self::Image => "images",
+            Self::Media => "media",
+        }
+    }
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// One subresource reference.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Resource {
+    /// Absolute URL
+    pub url: String,
+    /// `scheme://host[:port]` of `url`
+    pub origin: String,
+    pub kind: ResourceKind,
+    /// Element that referred to it
+    pub tag: String,
+    /// Its origin was blocked, so the element was removed
+    pub blocked: bool,
+}
+
+/// The resources of one origin, for the resource panel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OriginResources {
+    pub origin: String,
+    /// Same origin as the page
+    pub first_party: bool,
+    pub blocked: bool,
+    /// Resources by kind, indexed like [`ResourceKind::ALL`]
+    pub counts: [usize; ResourceKind::ALL.len()],
+}
+
+impl OriginResources {
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    #[must_use]
+    pub const fn count(&self, kind: ResourceKind) -> usize {
+        self.counts[kind.index()]
+    }
+
+    /// "3 scripts, 1 fonts"-style summary of the non-zero counts.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        ResourceKind::ALL
+            .into_iter()
+            .filter(|k| self.count(*k) > 0)
+            .map(|k| format!("{} {}", self.count(k), k.label()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Every subresource a page refers to, in document order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ResourceManifest {
+    /// Origin of the page itself; empty for pages without one
+    pub page_origin: String,
+    pub resources: Vec<Resource>,
+}
+
+impl ResourceManifest {
+    /// List the subresources of the tree at `root`, resolved against
+    /// `base`. `adblock` picks out trackers; resources from `blocked`
+    /// origins are marked.
+    #[must_use]
+    pub fn collect(
+        root: &DomNode,
+        base: &str,
+        adblock: Option<&AdBlockEngine>,
+        blocked: &BTreeSet<String>,
+    ) -> Self {
+        let base = Url::parse(base).ok();
+        let mut manifest = Self {
+            page_origin: base.as_ref().map(origin_of).unwrap_or_default(),
+            resources: Vec::new(),
+        };
+        let mut visit = |node: &DomNode, url: Url, kind: ResourceKind| {
+            let url_str = url.to_string();
+            let kind = match adblock.and_then(|ab| ab.check(&url_str)) {
+                Some(BlockReason::Tracker) => ResourceKind::Tracker,
+                _ => kind,
+            };
+            let origin = origin_of(&url);
+            manifest.resources.push(Resource {
+                blocked: blocked.contains(&origin),
+                url: url_str,
+                origin,
+                kind,
+                tag: node.tag.clone(),
+            });
+        };
+        walk(root, base.as_ref(), &mut visit);
+        manifest
+    }
+
+    /// Resources grouped by origin: the page's own first, then by number of
+    /// resources.
+    #[must_use]
+    pub fn origins(&self) -> Vec<OriginResources> {
+        let mut out: Vec<OriginResources> = Vec::new();
+        for resource in &self.resources {
+            let entry = match out.iter().position(|o| o.origin == resource.origin) {
+                Some(i) => &mut out[i],
+                None => {
+                    out.push(OriginResources {
+                        origin: resource.origin.clone(),
+                        first_party: resource.origin == self.page_origin,
+                        blocked: resource.blocked,
+                        counts: [0; ResourceKind::ALL.len()],
+                    });
+                    out.last_mut().expect("just pushed")
+                }
+            };
+            entry.counts[resource.kind.index()] += 1;
+        }
+        out.sort_by(|a, b| {
+            b.first_party
+                .cmp(&a.first_party)
+                .then(b.total().cmp(&a.total()))
+                .then_with(|| a.origin.cmp(&b.origin))
+        });
+        out
+    }
+
+    /// Resources of `kind`.
+    #[must_use]
+    pub fn count(&self, kind: ResourceKind) -> usize {
+        self.resources.iter().filter(|r| r.kind == kind).count()
+    }
+
+    /// Resources removed because their origin was blocked.
+    #[must_use]
+    pub fn blocked_count(&self) -> usize {
+        self.resources.iter().filter(|r| r.blocked).count()
+    }
+}
+
+/// Remove every element of the tree at `root` that would load a resource
+/// from one of the `blocked` origins; returns how many were removed.
+pub fn strip_blocked(root: &mut DomNode, base: &str, blocked: &BTreeSet<String>) -> usize {
+    if blocked.is_empty() {
+        return 0;
+    }
+    let base = Url::parse(base).ok();
+    strip(root, base.as_ref(), blocked)
+}
+
+fn strip(node: &mut DomNode, base: Option<&Url>, blocked: &BTreeSet<String>) -> usize {
+    let before = node.children.len();
+    node.children.retain(|child| {
+        reference(child, base).is_none_or(|(url, _)| !blocked.contains(&origin_of(&url)))
+    });
+    let mut removed = before - node.children.len();
+    for child in &mut node.children {
+        removed += strip(child, base, blocked);
+    }
+    removed
+}
+
+fn walk(node: &DomNode, base: Option<&Url>, visit: &mut impl FnMut(&DomNode, Url, ResourceKind)) {
+    if let Some((url, kind)) = reference(node, base) {
+        visit(node, url, kind);
+    }
+    if node.tag == "video" {
+        // The poster is an image next to the video's own source
+        if let Some(poster) = node.attr("poster").and_then(|p| resolve(base, p)) {
+            visit(node, poster, ResourceKind::Image);
+        }
+    }
+    for child in &node.children {
+        walk(child, base, visit);
+    }
+}
+
+/// The resource `node` loads, if any, and what it is.
+fn reference(node: &DomNode, base: Option<&Url>) -> Option<(Url, ResourceKind)> {
+    let (attr, kind) = match node.tag.as_str() {
+        "script" => ("src", ResourceKind::Script),
+        "iframe" | "frame" | "embed" => ("src", ResourceKind::Frame),
+        "object" => ("data", ResourceKind::Frame),
+        "img" => ("src", ResourceKind::Image),
+        "video" | "audio" | "source" | "track" => ("src", ResourceKind::Media),
+        "link" => ("href", link_kind(node)?),
+        _ => return None,
+    };
+    let url = resolve(base, node.attr(attr)?)?;
+    let kind = if kind == ResourceKind::Stylesheet && is_font_url(&url) {
+        ResourceKind::Font
+    } else {
+        kind
+    };
+    Some((url, kind))
+}
+
+/// What a `<link>` loads; `None` for links that load nothing.
+fn link_kind(node: &DomNode) -> Option<ResourceKind> {
+    let rel = node.attr("rel").unwrap_or_default().to_ascii_lowercase();
+    let rels: Vec<&str> = rel.split_whitespace().collect();
+    if rels.contains(&"stylesheet") {
+        return Some(ResourceKind::Stylesheet);
+    }
+    if rels.iter().any(|r| r.contains("icon")) {
+        return Some(ResourceKind::Image);
+    }
+    if rels.contains(&"preload") || rels.contains(&"prefetch") {
+        return Some(match node.attr("as").unwrap_or_default() {
+            "font" => ResourceKind::Font,
+            "script" => ResourceKind::Script,
+            "image" => ResourceKind::Image,
+            "document" => ResourceKind::Frame,
+            _ => ResourceKind::Stylesheet,
+        });
+    }
+    None
+}
+
+/// Font files and the stylesheets of font services.
+fn is_font_url(url: &Url) -> bool {
+    let path = url.path().to_ascii_lowercase();
+    let host = url.host_str().unwrap_or_default();
+    [".woff", ".woff2", ".ttf", ".otf", ".eot"]
+        .iter()
+        .any(|ext| path.ends_with(ext))
+        || host.starts_with("fonts.")
+        || host == "use.typekit.net"
+}
+
+fn resolve(base: Option<&Url>, reference: &str) -> Option<Url> {
+    let reference = reference.trim();
+    if reference.is_empty() || reference.starts_with("data:") {
+        return None;
+    }
+    let url = match base {
+        Some(b) => b.join(reference).ok()?,
+        None => Url::parse(reference).ok()?,
+    };
+    matches!(url.scheme(), "http" | "https").then_some(url)
+}
+
+fn origin_of(url: &Url) -> String {
+    url.origin().ascii_serialization()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+
+    const HTML: &str = r#"<html><head>
+        <link rel="stylesheet" href="/site.css">
+        <link rel="stylesheet" href="https://fonts.googleapis.com/css2?family=Inter">
+        <link rel="preload" as="font" href="/inter.woff2">
+        <script src="https://cdn.example.net/app.js"></script>
+        <script src="https://www.google-analytics.com/analytics.js"></script>
+        </head><body>
+        <p>Text</p>
+        <img src="/logo.png"><img src="data:image/gif;base64,R0lGOD">
+        <iframe src="https://video.example.org/embed/1"></iframe>
+        <img src="https://cdn.example.net/hero.jpg">
+        </body></html>"#;
+
+    #[test]
+    fn lists_resources_by_kind_and_origin() {
+        let tree = parse_html(HTML, "https://example.com/a");
+        let manifest = ResourceManifest::collect(
+            &tree.root,
+            &tree.url,
+            Some(&AdBlockEngine::new()),
+            &BTreeSet::new(),
+        );
+        assert_eq!(manifest.page_origin, "https://example.com");
+        assert_eq!(manifest.count(ResourceKind::Stylesheet), 1);
+        assert_eq!(manifest.count(ResourceKind::Font), 2);
+        assert_eq!(manifest.count(ResourceKind::Script), 1);
+        assert_eq!(manifest.count(ResourceKind::Tracker), 1);
+        assert_eq!(manifest.count(ResourceKind::Image), 2);
+        assert_eq!(manifest.count(ResourceKind::Frame), 1);
+
+        let origins = manifest.origins();
+        assert_eq!(origins[0].origin, "https://example.com");
+        assert!(origins[0].first_party);
+        assert_eq!(origins[0].summary(), "1 fonts, 1 stylesheets, 1 images");
+        let cdn = origins
+            .iter()
+            .find(|o| o.origin == "https://cdn.example.net")
+            .unwrap();
+        assert_eq!(cdn.summary(), "1 scripts, 1 images");
+        assert!(!cdn.first_party && !cdn.blocked);
+    }
+
+    #[test]
+    fn blocked_origins_are_stripped_but_listed() {
+        let mut tree = parse_html(HTML, "https://example.com/a");
+        let blocked: BTreeSet<String> = ["https://cdn.example.net".to_string()].into();
+        let manifest = ResourceManifest::collect(&tree.root, &tree.url, None, &blocked);
+        assert_eq!(manifest.blocked_count(), 2);
+
+        let url = tree.url.clone();
+        assert_eq!(strip_blocked(&mut tree.root, &url, &blocked), 2);
+        let left = ResourceManifest::collect(&tree.root, &url, None, &BTreeSet::new());
+        assert!(left
+            .resources
+            .iter()
+            .all(|r| r.origin != "https://cdn.example.net"));
+        assert_eq!(left.resources.len(), manifest.resources.len() - 2);
+    }
+}
@@ -0,0 +1,230 @@
+//! Saved browsing sessions, for restoring after a crash or restart.
+//!
+//! A [`SavedSession`] is what the window showed: the back/forward list with
+//! where each page was scrolled to, the current entry, the render mode and
+//! the addresses still queued to open (zoom is already kept per site). It
+//! is stored as JSON Lines — a header record, then one record per entry or
+//! queued address — so that damage stays local: [`decode`] skips lines it
+//! cannot read, keeps the entries it can, and clamps the current index to
+//! them. A file cut short by a crash mid-write loses its tail, not the
+//! session.
+
+use serde::{Deserialize, Serialize};
+
+use crate::render::RenderMode;
+
+/// Format version written in the header.
+pub const VERSION: u32 = 1;
+
+/// Most history entries kept.
+pub const MAX_ENTRIES: usize = 100;
+
+/// One back/forward entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedEntry {
+    pub url: String,
+    #[serde(default)]
+    pub title: String,
+    /// Top of the visible region, in layout pixels
+    #[serde(default)]
+    pub scroll: f32,
+}
+
+/// The state of the window when it was saved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedSession {
+    pub entries: Vec<SavedEntry>,
+    /// Index of the entry on screen
+    pub index: usize,
+    pub render_mode: RenderMode,
+    /// Addresses waiting to be opened
+    pub queue: Vec<String>,
+    /// Unix seconds of the save
+    pub saved_at: u64,
+}
+
+impl SavedSession {
+    /// The entry on screen.
+    #[must_use]
+    pub fn current(&self) -> Option<&SavedEntry> {
+        self.entries.get(self.index)
+    }
+}
+
+/// One line of the file.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record {
+    Header {
+        version: u32,
+        index: usize,
+        #[serde(default)]
+        render_mode: RenderMode,
+        #[serde(default)]
+        saved_at: u64,
+    },
+    Entry(SavedEntry),
+    Queued {
+        url: String,
+    },
+}
+
+/// Write `session` as JSON Lines; the oldest entries beyond
+/// [`MAX_ENTRIES`] are dropped.
+#[must_use]
+pub fn encode(session: &SavedSession) -> String {
+    let skip = session.entries.len().saturating_sub(MAX_ENTRIES);
+    let header = Record::Header {
+        version: VERSION,
+        index: session.index.saturating_sub(skip),
+        render_mode: session.render_mode,
+        saved_at: session.saved_at,
+    };
+    let records = std::iter::once(header)
+        .chain(session.entries[skip..].iter().cloned().map(Record::Entry))
+        .chain(
+            session
+                .queue
+                .iter()
+                .map(|url| Record::Queued { url: url.clone() }),
+        );
+    let mut out = String::new();
+    for record in records {
+        if let Ok(line) = serde_json::to_string(&record) {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Read a session written by [`encode`], skipping unreadable lines and
+/// entries without an address. `None` if no entry survives.
+#[must_use]
+pub fn decode(text: &str) -> Option<SavedSession> {
+    let mut session = SavedSession {
+        entries: Vec::new(),
+        index: 0,
+        render_mode: RenderMode::default(),
+        queue: Vec::new(),
+        saved_at: 0,
+    };
+    let mut index = None;
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        match serde_json::from_str(line) {
+            Ok(Record::Header {
+                index: i,
+                render_mode,
+                saved_at,
+                ..
+            }) => {
+                index = Some(i);
+                session.render_mode = render_mode;
+                session.saved_at = saved_at;
+            }
+            Ok(Record::Entry(mut entry)) if !entry.url.trim().is_empty() => {
+                if !entry.scroll.is_finite() || entry.scroll < 0.0 {
+                    entry.scroll = 0.0;
+                }
+                session.entries.push(entry);
+            }
+            Ok(Record::Queued { url }) if !url.trim().is_empty() => session.queue.push(url),
+            Ok(_) => {}
+            Err(e) => log::debug!("Skipping unreadable session line: {e}"),
+        }
+    }
+    if session.entries.is_empty() {
+        return None;
+    }
+    // Without a header, the last entry is as good a guess as any
+    let last = session.entries.len() - 1;
+    session.index = index.unwrap_or(last).min(last);
+    Some(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> SavedSession {
+        SavedSession {
+            entries: vec![
+                SavedEntry {
+                    url: "https://example.com/".into(),
+                    title: "Example".into(),
+                    scroll: 0.0,
+                },
+                SavedEntry {
+                    url: "https://example.com/a".into(),
+                    title: "A \"quoted\" title\nwith a newline".into(),
+                    scroll: 1240.5,
+                },
+                SavedEntry {
+                    url: "https://example.org/".into(),
+                    title: String::new(),
+                    scroll: 80.0,
+                },
+            ],
+            index: 1,
+            render_mode: RenderMode::Reader,
+            queue: vec!["https://queued.example/".into()],
+            saved_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn sessions_round_trip() {
+        let session = sample();
+        let text = encode(&session);
+        assert_eq!(text.lines().count(), 5);
+        assert_eq!(decode(&text), Some(session));
+        assert_eq!(decode(""), None);
+    }
+
+    #[test]
+    fn damaged_files_keep_what_can_be_read() {
+        let text = encode(&sample());
+        let mut lines: Vec<&str> = text.lines().collect();
+        // A garbled entry and a line cut short by a crash mid-write
+        lines[2] = "{\"type\":\"entry\",\"url\":";
+        let cut = &lines[3][..lines[3].len() / 2];
+        lines[3] = cut;
+        let session = decode(&lines.join("\n")).unwrap();
+        assert_eq!(session.entries.len(), 1);
+        assert_eq!(session.queue.len(), 1);
+        // The header's index is clamped to the entries left
+        assert_eq!(session.index, 0);
+        assert_eq!(session.render_mode, RenderMode::Reader);
+
+        // No header: defaults, on the last entry
+        let headless: String = text.lines().skip(1).collect::<Vec<_>>().join("\n");
+        let session = decode(&headless).unwrap();
+        assert_eq!(session.index, 2);
+        assert_eq!(session.render_mode, RenderMode::Flat);
+
+        // Unknown record types, empty addresses and nonsense are skipped
+        let odd = "not json\n{\"type\":\"tab_group\"}\n{\"type\":\"entry\",\"url\":\"\"}\n\
+                   {\"type\":\"entry\",\"url\":\"https://a.example/\",\"scroll\":-3}";
+        let session = decode(odd).unwrap();
+        assert_eq!(session.entries.len(), 1);
+        assert!(session.entries[0].scroll.abs() < f32::EPSILON);
+        assert_eq!(decode("garbage\n\n"), None);
+    }
+
+    #[test]
+    fn long_histories_keep_their_newest_entries() {
+        let mut session = sample();
+        session.entries = (0..MAX_ENTRIES + 20)
+            .map(|i| SavedEntry {
+                url: format!("https://example.com/{i}"),
+                title: String::new(),
+                scroll: 0.0,
+            })
+            .collect();
+        session.index = MAX_ENTRIES + 10;
+        let restored = decode(&encode(&session)).unwrap();
+        assert_eq!(restored.entries.len(), MAX_ENTRIES);
+        assert_eq!(restored.entries[0].url, "https://example.com/20");
+        assert_eq!(restored.current().unwrap().url, "https://example.com/110");
+    }
+}
@@ -0,0 +1,539 @@
+//! Batch URL sessions: a list of addresses loaded one after another.
+//!
+//! A session comes from a plain-text list — one address per line, with
+//! `# Name` lines starting a group — or an OPML outline, whose nested
+//! outlines name the groups ([`parse_url_list`]). [`Session::start`] loads
+//! the pages one at a time as prefetch jobs of the fetch
+//! [`scheduler`] with its own [`BrowserEngine`] and reduces each to a
+//! [`SessionPage`] — title, summary, headings and a painted thumbnail — so
+//! a few dozen pages can be triaged side by side without keeping their DOMs
+//! around. The results also make an OZ stream ([`Session::constellation`]),
+//! one coloured constellation per group.
+
+use std::sync::mpsc;
+
+use crate::engine::headless;
+use crate::engine::internal::is_internal;
+use crate::engine::pipeline::{BrowserEngine, BrowserEngineBuilder, PageResult};
+use crate::engine::workers::CancelToken;
+use crate::net::paste::looks_like_url;
+use crate::net::scheduler::{self, Priority};
+use crate::render::layout::LayoutNode;
+use crate::render::stream::TextMeta;
+use crate::render::thumbnail::Thumbnail;
+
+/// Most headings kept per page.
+const MAX_HEADINGS: usize = 8;
+
+/// Longest summary kept per page, in characters.
+const MAX_SUMMARY_CHARS: usize = 240;
+
+/// One address of a session list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionEntry {
+    pub url: String,
+    /// Group from the list (`# Name` line or OPML outline), if any
+    pub group: Option<String>,
+}
+
+/// What was kept of a loaded page.
+#[derive(Debug, Clone)]
+pub struct SessionPage {
+    /// Final address, after redirects
+    pub url: String,
+    pub title: String,
+    /// First paragraph, shortened
+    pub summary: String,
+    /// `h1`–`h3` texts, in page order
+    pub headings: Vec<String>,
+    /// Sketch of the top of the page
+    pub thumbnail: Thumbnail,
+}
+
+/// Where an entry is in the queue.
+#[derive(Debug, Clone)]
+pub enum SessionStatus {
+    Queued,
+    Loading,
+    Loaded(Box<SessionPage>),
+    Failed(String),
+}
+
+/// An entry and its load status.
+#[derive(Debug, Clone)]
+pub struct SessionItem {
+    pub entry: SessionEntry,
+    pub status: SessionStatus,
+}
+
+impl SessionItem {
+    /// Group shown for the item: its list group, or else its host.
+    #[must_use]
+    pub fn group_name(&self) -> String {
+        if let Some(ref group) = self.entry.group {
+            return group.clone();
+        }
+        let url = &self.entry.url;
+        let with_scheme = if url.contains("://") {
+            url.clone()
+        } else {
+            format!("https://{url}")
+        };
+        url::Url::parse(&with_scheme)
+            .ok()
+            .and_then(|u| {
+                u.host_str()
+                    .map(|h| h.trim_start_matches("www.").to_string())
+            })
+            .unwrap_or_else(|| url.clone())
+    }
+}
+
+/// State carried from one page's fetch job to the next.
+struct Loader<F> {
+    urls: Vec<String>,
+    next: usize,
+    builder: BrowserEngineBuilder,
+    /// Built by the first job, off the caller's thread
+    engine: Option<BrowserEngine>,
+    tx: mpsc::Sender<(usize, SessionStatus)>,
+    on_update: F,
+    token: CancelToken,
+}
+
+/// Submit the load of the next page, which submits the one after it when
+/// done; stops at the end of the list, when the session is cancelled or
+/// when nobody listens any more.
+fn load_next<F>(mut loader: Loader<F>)
+where
+    F: Fn() + Send + 'static,
+{
+    let Some(url) = loader.urls.get(loader.next).cloned() else {
+        return;
+    };
+    let token = loader.token.clone();
+    let host_key = url.clone();
+    scheduler::shared().submit(&host_key, Priority::Prefetch, &token, move |stop| {
+        let i = loader.next;
+        if stop.is_cancelled() || loader.tx.send((i, SessionStatus::Loading)).is_err() {
+            return;
+        }
+        (loader.on_update)();
+        let builder = &loader.builder;
+        let engine = loader.engine.get_or_insert_with(|| builder.clone().build());
+        let status = match engine.load_page(&url) {
+            Ok(page) => SessionStatus::Loaded(Box::new(summarize(&page))),
+            Err(e) => SessionStatus::Failed(e.to_string()),
+        };
+        if loader.tx.send((i, status)).is_err() {
+            return;
+        }
+        (loader.on_update)();
+        loader.next += 1;
+        load_next(loader);
+    });
+}
+
+/// A list of pages being loaded in the background.
+pub struct Session {
+    pub items: Vec<SessionItem>,
+    rx: Option<mpsc::Receiver<(usize, SessionStatus)>>,
+    cancel: CancelToken,
+}
+
+impl Session {
+    /// Load `entries` one after another with an engine built from
+    /// `builder`; `on_update` runs on the loader thread after each change
+    /// (e.g. to request a repaint).
+    pub fn start<F>(entries: Vec<SessionEntry>, builder: BrowserEngineBuilder, on_update: F) -> Self
+    where
+        F: Fn() + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let cancel = CancelToken::new();
+        load_next(Loader {
+            urls: entries.iter().map(|e| e.url.clone()).collect(),
+            next: 0,
+            builder,
+            engine: None,
+            tx,
+            on_update,
+            token: cancel.clone(),
+        });
+        Self {
+            items: entries
+                .into_iter()
+                .map(|entry| SessionItem {
+                    entry,
+                    status: SessionStatus::Queued,
+                })
+                .collect(),
+            rx: Some(rx),
+            cancel,
+        }
+    }
+
+    /// Apply progress from the loader. Returns `true` if anything changed.
+    pub fn poll(&mut self) -> bool {
+        let Some(ref rx) = self.rx else {
+            return false;
+        };
+        let mut changed = false;
+        loop {
+            match rx.try_recv() {
+                Ok((i, status)) => {
+                    if let Some(item) = self.items.get_mut(i) {
+                        item.status = status;
+                        changed = true;
+                    }
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.rx = None;
+                    break;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Stop after the page being loaded; the rest stay queued.
+    pub fn cancel(&mut self) {
+        self.cancel.cancel();
+        self.rx = None;
+    }
+
+    #[must_use]
+    pub const fn is_running(&self) -> bool {
+        self.rx.is_some()
+    }
+
+    /// Entries loaded or failed so far.
+    #[must_use]
+    pub fn done(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|i| {
+                matches!(
+                    i.status,
+                    SessionStatus::Loaded(_) | SessionStatus::Failed(_)
+                )
+            })
+            .count()
+    }
+
+    /// Item indices by group, groups in order of first appearance.
+    #[must_use]
+    pub fn groups(&self) -> Vec<(String, Vec<usize>)> {
+        let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+        for (i, item) in self.items.iter().enumerate() {
+            let name = item.group_name();
+            match groups.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, members)) => members.push(i),
+                None => groups.push((name, vec![i])),
+            }
+        }
+        groups
+    }
+
+    /// Loaded pages as OZ stream texts, by group: the group's name heads it
+    /// (`h1`, the document map's inner ring), then each page's title
+    /// (`h2`), headings and summary, all linking to the page. Groups with
+    /// nothing loaded are left out.
+    #[must_use]
+    pub fn constellation(&self) -> Vec<(String, Vec<TextMeta>)> {
+        let text = |tag: &str, full: &str, href: Option<&String>, importance: f32| TextMeta {
+            display: shorten(full, 40),
+            full_text: full.to_string(),
+            tag: tag.to_string(),
+            href: href.cloned(),
+            category_index: 0,
+            importance,
+        };
+        self.groups()
+            .into_iter()
+            .filter_map(|(name, members)| {
+                let mut texts = vec![text("h1", &name, None, 1.0)];
+                for i in members {
+                    let SessionStatus::Loaded(ref page) = self.items[i].status else {
+                        continue;
+                    };
+                    let href = Some(&page.url);
+                    texts.push(text("h2", &page.title, href, 0.9));
+                    for heading in &page.headings {
+                        texts.push(text("h3", heading, href, 0.6));
+                    }
+                    if !page.summary.is_empty() {
+                        texts.push(text("p", &page.summary, href, 0.2));
+                    }
+                }
+                (texts.len() > 1).then_some((name, texts))
+            })
+            .collect()
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+/// Reduce a loaded page to what the session keeps of it.
+#[must_use]
+pub fn summarize(page: &PageResult) -> SessionPage {
+    let mut headings = Vec::new();
+    let mut summary = None;
+    collect_outline(&page.layout, &mut headings, &mut summary);
+    let title = match page.dom.title.trim() {
+        "" => headings
+            .first()
+            .cloned()
+            .unwrap_or_else(|| page.dom.url.clone()),
+        title => title.to_string(),
+    };
+    SessionPage {
+        url: page.dom.url.clone(),
+        title,
+        summary: summary.unwrap_or_default(),
+        headings,
+        thumbnail: headless::thumbnail(page),
+    }
+}
+
+/// Collect `h1`–`h3` texts and the first paragraph under `node`.
+fn collect_outline(node: &LayoutNode, headings: &mut Vec<String>, summary: &mut Option<String>) {
+    match node.tag.as_str() {
+        "h1" | "h2" | "h3" => {
+            let text = node_text(node);
+            if !text.is_empty() && headings.len() < MAX_HEADINGS {
+                headings.push(text);
+            }
+            return;
+        }
+        "p" if summary.is_none() => {
+            let text = node_text(node);
+            if text.chars().count() > 40 {
+                *summary = Some(shorten(&text, MAX_SUMMARY_CHARS));
+            }
+            return;
+        }
+        _ => {}
+    }
+    for child in &node.children {
+        collect_outline(child, headings, summary);
+    }
+}
+
+/// Text under `node`, whitespace collapsed.
+fn node_text(node: &LayoutNode) -> String {
+    fn walk(node: &LayoutNode, out: &mut Vec<String>) {
+        out.extend(node.text.split_whitespace().map(str::to_string));
+        for child in &node.children {
+            walk(child, out);
+        }
+    }
+    let mut words = Vec::new();
+    walk(node, &mut words);
+    words.join(" ")
+}
+
+fn shorten(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(max_chars - 1).collect();
+    short.push('…');
+    short
+}
+
+// ── List parsing ──
+
+/// Parse a session list: OPML if it looks like XML, otherwise one address
+/// per line, where a `# Name` line starts the group the following
+/// addresses belong to and anything else that isn't an address is
+/// skipped. Duplicates and `alice://` pages are dropped.
+#[must_use]
+pub fn parse_url_list(text: &str) -> Vec<SessionEntry> {
+    let entries = if text.trim_start().starts_with('<') {
+        parse_opml(text)
+    } else {
+        parse_lines(text)
+    };
+    let mut out: Vec<SessionEntry> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if !is_internal(&entry.url) && !out.iter().any(|e| e.url == entry.url) {
+            out.push(entry);
+        }
+    }
+    out
+}
+
+fn parse_lines(text: &str) -> Vec<SessionEntry> {
+    let mut group = None;
+    let mut out = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('#') {
+            let name = name.trim();
+            group = (!name.is_empty()).then(|| name.to_string());
+        } else if looks_like_url(line) {
+            out.push(SessionEntry {
+                url: line.to_string(),
+                group: group.clone(),
+            });
+        }
+    }
+    out
+}
+
+/// Addresses of an OPML outline: each `<outline>`'s `htmlUrl`, `url` or
+/// `xmlUrl`, grouped under the nearest enclosing outline's `title` (or
+/// `text`).
+fn parse_opml(text: &str) -> Vec<SessionEntry> {
+    let mut out = Vec::new();
+    // Enclosing outlines; `None` for those without a name
+    let mut stack: Vec<Option<String>> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+
+        if tag.trim_start().starts_with("/outline") {
+            stack.pop();
+            continue;
+        }
+        let Some(attrs) = tag.strip_prefix("outline") else {
+            continue;
+        };
+        let self_closing = attrs.trim_end().ends_with('/');
+        let attrs = xml_attributes(attrs.trim_end().trim_end_matches('/'));
+        let attr = |name: &str| {
+            attrs
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+
+        if let Some(url) = attr("htmlUrl")
+            .or_else(|| attr("url"))
+            .or_else(|| attr("xmlUrl"))
+        {
+            out.push(SessionEntry {
+                url,
+                group: stack.iter().rev().find_map(Clone::clone),
+            });
+        }
+        if !self_closing {
+            stack.push(attr("title").or_else(|| attr("text")));
+        }
+    }
+    out
+}
+
+/// `name="value"` pairs of a tag, entities decoded.
+fn xml_attributes(s: &str) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    let mut rest = s;
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].trim().to_string();
+        let after = rest[eq + 1..].trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(len) = after[1..].find(quote) else {
+            break;
+        };
+        out.push((name, decode_entities(&after[1..=len])));
+        rest = &after[len + 2..];
+    }
+    out
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_lists_with_groups() {
+        let list = "# Papers\nhttps://arxiv.org/abs/1\n\nnotes about it\narxiv.org/abs/2\n\
+                    #\nhttps://example.com/\nhttps://arxiv.org/abs/1\nalice://history\n";
+        let entries = parse_url_list(list);
+        let urls: Vec<&str> = entries.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://arxiv.org/abs/1",
+                "arxiv.org/abs/2",
+                "https://example.com/"
+            ]
+        );
+        assert_eq!(entries[1].group.as_deref(), Some("Papers"));
+        // A bare `#` ends the group
+        assert_eq!(entries[2].group, None);
+    }
+
+    #[test]
+    fn opml_outlines_name_their_groups() {
+        let opml = r#"<?xml version="1.0"?>
+            <opml version="2.0"><head><title>Reading</title></head><body>
+              <outline text="Rust">
+                <outline text="Blog" type="rss" xmlUrl="https://blog.rust-lang.org/feed.xml"
+                         htmlUrl="https://blog.rust-lang.org/"/>
+                <outline title="Q&amp;A" url='https://users.rust-lang.org/?a=1&amp;b=2' />
+              </outline>
+              <outline text="Loose" xmlUrl="https://example.com/feed"/>
+            </body></opml>"#;
+        let entries = parse_url_list(opml);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].url, "https://blog.rust-lang.org/");
+        assert_eq!(entries[0].group.as_deref(), Some("Rust"));
+        assert_eq!(entries[1].url, "https://users.rust-lang.org/?a=1&b=2");
+        assert_eq!(entries[1].group.as_deref(), Some("Rust"));
+        assert_eq!(entries[2].url, "https://example.com/feed");
+        assert_eq!(entries[2].group, None);
+    }
+
+    #[test]
+    fn ungrouped_items_group_by_host() {
+        let item = |url: &str, group: Option<&str>| SessionItem {
+            entry: SessionEntry {
+                url: url.to_string(),
+                group: group.map(str::to_string),
+            },
+            status: SessionStatus::Queued,
+        };
+        let session = Session {
+            items: vec![
+                item("https://www.example.com/a", None),
+                item("docs.rs/url", None),
+                item("example.com/b", None),
+                item("https://x.test/", Some("Misc")),
+            ],
+            rx: None,
+            cancel: CancelToken::new(),
+        };
+        assert_eq!(
+            session.groups(),
+            [
+                ("example.com".to_string(), vec![0, 2]),
+                ("docs.rs".to_string(), vec![1]),
+                ("Misc".to_string(), vec![3]),
+            ]
+        );
+        assert!(!session.is_running());
+        assert_eq!(session.done(), 0);
+    }
+}
@@ -0,0 +1,375 @@
+//! Site maps from `sitemap.xml`.
+//!
+//! [`parse_sitemap`] reads the `<loc>` entries of a sitemap or sitemap
+//! index; [`SiteNode::from_urls`] folds the page addresses into a tree by
+//! path segment, with the site as the root and the first path segment as
+//! its sections. The tree is shown as a list, as OZ constellations (one per
+//! section), and as an orbital system for Spatial3D: sections are planets
+//! on rings around the site, pages their satellites.
+
+use crate::render::sdf_ui::{SdfPrimitive, SdfScene};
+use crate::render::stream::TextMeta;
+
+/// Most page addresses kept from a site's sitemaps.
+pub const MAX_PAGES: usize = 2000;
+/// Most sitemap files read when following a sitemap index.
+pub const MAX_SITEMAPS: usize = 16;
+/// Most satellites drawn around one planet of the orbital system.
+const MAX_SATELLITES: usize = 24;
+
+/// The addresses listed by one sitemap file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sitemap {
+    /// A `<urlset>`: page addresses
+    Pages(Vec<String>),
+    /// A `<sitemapindex>`: addresses of further sitemaps
+    Index(Vec<String>),
+}
+
+/// Parse a sitemap or sitemap index.
+#[must_use]
+pub fn parse_sitemap(xml: &str) -> Sitemap {
+    let mut locs = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find("<loc>") {
+        rest = &rest[start + "<loc>".len()..];
+        let Some(end) = rest.find("</loc>") else {
+            break;
+        };
+        let loc = rest[..end].trim();
+        let loc = loc
+            .strip_prefix("<![CDATA[")
+            .and_then(|l| l.strip_suffix("]]>"))
+            .unwrap_or(loc)
+            .trim();
+        if !loc.is_empty() {
+            locs.push(decode_entities(loc));
+        }
+        rest = &rest[end..];
+    }
+    if xml.contains("<sitemapindex") {
+        Sitemap::Index(locs)
+    } else {
+        Sitemap::Pages(locs)
+    }
+}
+
+/// The conventional sitemap address of the site serving `origin`.
+#[must_use]
+pub fn sitemap_url(origin: &str) -> Option<String> {
+    let mut url = url::Url::parse(origin)
+        .or_else(|_| url::Url::parse(&format!("https://{origin}")))
+        .ok()?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return None;
+    }
+    url.set_path("/sitemap.xml");
+    url.set_query(None);
+    url.set_fragment(None);
+    Some(url.to_string())
+}
+
+/// One node of a site map: the site, a section or a page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiteNode {
+    /// Host name for the root, path segment below it
+    pub name: String,
+    /// Address, when the sitemap lists this path as a page
+    pub url: Option<String>,
+    pub children: Vec<SiteNode>,
+}
+
+impl SiteNode {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            url: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Fold page addresses into a tree by path segment. Addresses on other
+    /// hosts than `origin`'s are left out; children keep sitemap order.
+    #[must_use]
+    pub fn from_urls(origin: &str, urls: &[String]) -> Self {
+        let host = url::Url::parse(origin)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let mut root = Self::new(&host);
+        for address in urls {
+            let Ok(parsed) = url::Url::parse(address) else {
+                continue;
+            };
+            if parsed.host_str() != Some(host.as_str()) {
+                continue;
+            }
+            let mut node = &mut root;
+            for segment in parsed.path().split('/').filter(|s| !s.is_empty()) {
+                let index = match node.children.iter().position(|c| c.name == segment) {
+                    Some(index) => index,
+                    None => {
+                        node.children.push(Self::new(segment));
+                        node.children.len() - 1
+                    }
+                };
+                node = &mut node.children[index];
+            }
+            if node.url.is_none() {
+                node.url = Some(address.clone());
+            }
+        }
+        root
+    }
+
+    /// Pages in this subtree, this node included.
+    #[must_use]
+    pub fn page_count(&self) -> usize {
+        usize::from(self.url.is_some()) + self.children.iter().map(Self::page_count).sum::<usize>()
+    }
+
+    /// Pages of this subtree as `(label, url)`, depth first.
+    #[must_use]
+    pub fn pages(&self) -> Vec<(String, String)> {
+        let mut out = Vec::new();
+        self.collect_pages(&self.name, &mut out);
+        out
+    }
+
+    fn collect_pages(&self, label: &str, out: &mut Vec<(String, String)>) {
+        if let Some(ref url) = self.url {
+            out.push((label.to_string(), url.clone()));
+        }
+        for child in &self.children {
+            child.collect_pages(&format!("{label}/{}", child.name), out);
+        }
+    }
+
+    /// The site map as OZ constellations, one per section: the section's
+    /// name, then its pages as links.
+    #[must_use]
+    pub fn constellation(&self) -> Vec<(String, Vec<TextMeta>)> {
+        let text = |tag: &str, full: &str, href: Option<&String>, importance: f32| TextMeta {
+            display: full.chars().take(40).collect(),
+            full_text: full.to_string(),
+            tag: tag.to_string(),
+            href: href.cloned(),
+            category_index: 0,
+            importance,
+        };
+        self.children
+            .iter()
+            .filter(|section| section.page_count() > 0)
+            .map(|section| {
+                let mut texts = vec![text("h1", &section.name, section.url.as_ref(), 1.0)];
+                for (label, url) in section.pages() {
+                    if section.url.as_ref() != Some(&url) {
+                        texts.push(text("h2", &label, Some(&url), 0.8));
+                    }
+                }
+                (section.name.clone(), texts)
+            })
+            .collect()
+    }
+
+    /// The site map as an orbital system: the site at the centre, sections
+    /// as planets on rings around it and their pages as satellites. Returns
+    /// the scene and the click targets, the position and address of every
+    /// body that is a page.
+    #[must_use]
+    pub fn orbit_scene(&self) -> (SdfScene, Vec<([f32; 3], String)>) {
+        use std::f32::consts::TAU;
+
+        let mut primitives = vec![
+            SdfPrimitive::Sphere {
+                center: [0.0, 0.0, 0.0],
+                radius: 0.6,
+                color: SUN,
+            },
+            SdfPrimitive::Billboard {
+                position: [0.0, 1.0, 0.0],
+                size: [2.4, 0.35],
+                text: self.name.clone(),
+                color: LABEL,
+                opacity: 1.0,
+            },
+        ];
+        let mut targets = Vec::new();
+        if let Some(ref url) = self.url {
+            targets.push(([0.0, 0.0, 0.0], url.clone()));
+        }
+
+        let sections: Vec<&Self> = self
+            .children
+            .iter()
+            .filter(|s| s.page_count() > 0)
+            .collect();
+        let count = sections.len().max(1) as f32;
+        for (i, section) in sections.iter().enumerate() {
+            let orbit = (i as f32).mul_add(0.9, 2.0);
+            let angle = TAU * i as f32 / count;
+            let center = [orbit * angle.cos(), 0.0, orbit * angle.sin()];
+            let color = PLANETS[i % PLANETS.len()];
+
+            primitives.push(SdfPrimitive::Torus {
+                center: [0.0, 0.0, 0.0],
+                major_radius: orbit,
+                minor_radius: 0.012,
+                axis: [0.0, 1.0, 0.0],
+                color: RING,
+            });
+            primitives.push(SdfPrimitive::Sphere {
+                center,
+                radius: 0.28,
+                color,
+            });
+            primitives.push(SdfPrimitive::Billboard {
+                position: [center[0], center[1] + 0.45, center[2]],
+                size: [1.6, 0.25],
+                text: section.name.clone(),
+                color: LABEL,
+                opacity: 0.9,
+            });
+            if let Some(ref url) = section.url {
+                targets.push((center, url.clone()));
+            }
+
+            // Satellites on a tilted ring around the planet
+            let pages: Vec<(String, String)> = section
+                .pages()
+                .into_iter()
+                .filter(|(_, url)| section.url.as_ref() != Some(url))
+                .take(MAX_SATELLITES)
+                .collect();
+            let moons = pages.len().max(1) as f32;
+            for (j, (_, url)) in pages.into_iter().enumerate() {
+                let a = TAU * j as f32 / moons;
+                let r = 0.45 + 0.05 * (j % 3) as f32;
+                let position = [
+                    r.mul_add(a.cos(), center[0]),
+                    0.25 * r * a.sin(),
+                    r.mul_add(a.sin(), center[2]),
+                ];
+                primitives.push(SdfPrimitive::Sphere {
+                    center: position,
+                    radius: 0.07,
+                    color: SATELLITE,
+                });
+                targets.push((position, url));
+            }
+        }
+
+        (
+            SdfScene {
+                primitives,
+                background_color: [0.97, 0.97, 0.99, 1.0],
+            },
+            targets,
+        )
+    }
+}
+
+const SUN: [f32; 4] = [1.0, 0.82, 0.35, 1.0];
+const RING: [f32; 4] = [0.75, 0.78, 0.85, 1.0];
+const LABEL: [f32; 4] = [0.15, 0.18, 0.25, 1.0];
+const SATELLITE: [f32; 4] = [0.7, 0.72, 0.78, 1.0];
+const PLANETS: [[f32; 4]; 5] = [
+    [0.35, 0.55, 0.95, 1.0],
+    [0.4, 0.8, 0.6, 1.0],
+    [0.95, 0.5, 0.45, 1.0],
+    [0.7, 0.5, 0.9, 1.0],
+    [0.3, 0.75, 0.85, 1.0],
+];
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urlsets_and_indexes() {
+        let urlset = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>https://example.com/</loc><lastmod>2024-01-01</lastmod></url>
+  <url><loc> https://example.com/docs/a?x=1&amp;y=2 </loc></url>
+  <url><loc><![CDATA[https://example.com/blog/]]></loc></url>
+</urlset>"#;
+        assert_eq!(
+            parse_sitemap(urlset),
+            Sitemap::Pages(vec![
+                "https://example.com/".into(),
+                "https://example.com/docs/a?x=1&y=2".into(),
+                "https://example.com/blog/".into(),
+            ])
+        );
+
+        let index = "<sitemapindex><sitemap><loc>https://example.com/s1.xml</loc></sitemap>\
+                     </sitemapindex>";
+        assert_eq!(
+            parse_sitemap(index),
+            Sitemap::Index(vec!["https://example.com/s1.xml".into()])
+        );
+
+        assert_eq!(
+            sitemap_url("https://example.com/docs/page?q=1").as_deref(),
+            Some("https://example.com/sitemap.xml")
+        );
+        assert_eq!(
+            sitemap_url("example.com").as_deref(),
+            Some("https://example.com/sitemap.xml")
+        );
+        assert_eq!(sitemap_url("alice://history"), None);
+    }
+
+    #[test]
+    fn pages_fold_into_sections() {
+        let urls: Vec<String> = [
+            "https://example.com/",
+            "https://example.com/docs/",
+            "https://example.com/docs/intro",
+            "https://example.com/docs/api/types",
+            "https://example.com/blog/first",
+            "https://other.org/docs/",
+        ]
+        .iter()
+        .map(|s| (*s).to_string())
+        .collect();
+        let site = SiteNode::from_urls("https://example.com/", &urls);
+
+        assert_eq!(site.name, "example.com");
+        assert_eq!(site.url.as_deref(), Some("https://example.com/"));
+        assert_eq!(site.page_count(), 5);
+        let sections: Vec<&str> = site.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(sections, ["docs", "blog"]);
+        let docs = &site.children[0];
+        assert_eq!(docs.page_count(), 3);
+        assert_eq!(
+            docs.pages()[2],
+            (
+                "docs/api/types".into(),
+                "https://example.com/docs/api/types".into()
+            )
+        );
+
+        let groups = site.constellation();
+        assert_eq!(groups.len(), 2);
+        // The section's own page is its heading, not a satellite
+        assert_eq!(groups[0].1.len(), 3);
+
+        let (scene, targets) = site.orbit_scene();
+        assert_eq!(targets.len(), 5);
+        let rings = scene
+            .primitives
+            .iter()
+            .filter(|p| matches!(p, SdfPrimitive::Torus { .. }))
+            .count();
+        assert_eq!(rings, 2);
+    }
+}
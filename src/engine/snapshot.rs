@@ -0,0 +1,691 @@
+//! Saved pages.
+//!
+//! [`Snapshot::capture`] takes a page's filtered DOM — what the browser
+//! shows, without what the semantic filter removed — together with its
+//! images and stylesheets, and [`Snapshot::render`] writes it as one file
+//! that opens without the network: HTML with `data:` images and inline
+//! `<style>` sheets ([`SnapshotFormat::Html`]), or an MHTML archive holding
+//! the page and each resource as a MIME part ([`SnapshotFormat::Mhtml`]).
+//! Scripts, frames and event handlers are dropped, and links are made
+//! absolute so they still lead to the live site.
+//!
+//! [`read`] turns either kind of file back into HTML; the browser opens
+//! saved pages from `file://` URLs ([`BrowserEngine::load_file`]).
+//!
+//! [`BrowserEngine::load_file`]: crate::engine::pipeline::BrowserEngine::load_file
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Read;
+use std::path::{Path, PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+use url::Url;
+
+use crate::dom::{DomNode, DomTree, NodeType};
+use crate::engine::internal::escape;
+use crate::net::data_url;
+use crate::net::sniff::{self, mime_essence};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::net::{proxy, tls};
+
+/// Elements left out of a saved page: they run code, embed other pages
+/// or point the page elsewhere.
+const DROPPED_TAGS: &[&str] = &[
+    "script", "noscript", "iframe", "frame", "frameset", "object", "embed", "template", "base",
+    "style",
+];
+
+/// Attributes left out of a saved page, besides `on*` handlers.
+const DROPPED_ATTRS: &[&str] = &[
+    "srcset",
+    "sizes",
+    "integrity",
+    "crossorigin",
+    "nonce",
+    "ping",
+];
+
+/// Attributes holding a URL, made absolute when saved.
+const URL_ATTRS: &[&str] = &["href", "src", "action", "poster", "cite"];
+
+/// Elements without a closing tag.
+const VOID_TAGS: &[&str] = &[
+    "area", "br", "col", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+
+/// Separates the parts of an MHTML file; `=_` cannot occur in base64 or
+/// quoted-printable text.
+const BOUNDARY: &str = "----=_NextPart_AliceSnapshot";
+
+/// Largest resource saved with a page.
+pub const MAX_RESOURCE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Time allowed for fetching one resource.
+#[cfg(not(target_arch = "wasm32"))]
+const RESOURCE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Error reading a saved page.
+#[derive(Debug)]
+pub struct SnapshotError {
+    pub message: String,
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// File format of a saved page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapshotFormat {
+    /// One HTML file with `data:` images and inline styles
+    #[default]
+    Html,
+    /// A `multipart/related` web archive
+    Mhtml,
+}
+
+impl SnapshotFormat {
+    pub const ALL: [Self; 2] = [Self::Html, Self::Mhtml];
+
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Mhtml => "mhtml",
+        }
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Html => "Web page, single file (HTML)",
+            Self::Mhtml => "Web archive (MHTML)",
+        }
+    }
+}
+
+/// An image or stylesheet saved with a page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resource {
+    /// `Content-Type` it was served with
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// A page ready to be saved.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub url: String,
+    pub title: String,
+    /// The page without dropped elements, with absolute addresses
+    root: DomNode,
+    /// Images and stylesheets by absolute URL; those that could not be
+    /// fetched are missing and keep their live address
+    pub resources: BTreeMap<String, Resource>,
+}
+
+impl Snapshot {
+    /// Clean up `dom` for saving and fetch its images and stylesheets with
+    /// `fetch` (e.g. [`fetch_resource`]), once per address.
+    pub fn capture(dom: &DomTree, fetch: impl Fn(&str) -> Option<Resource>) -> Self {
+        let base = Url::parse(&dom.url).ok();
+        let root = clean(&dom.root, base.as_ref())
+            .unwrap_or_else(|| DomNode::element("html", HashMap::new(), Vec::new()));
+        let mut urls = BTreeSet::new();
+        collect_resources(&root, &mut urls);
+        let resources = urls
+            .into_iter()
+            .filter_map(|url| fetch(&url).map(|r| (url, r)))
+            .collect();
+        Self {
+            url: dom.url.clone(),
+            title: dom.title.clone(),
+            root,
+            resources,
+        }
+    }
+
+    /// The saved file's contents in `format`.
+    #[must_use]
+    pub fn render(&self, format: SnapshotFormat) -> String {
+        match format {
+            SnapshotFormat::Html => self.to_html(),
+            SnapshotFormat::Mhtml => self.to_mhtml(),
+        }
+    }
+
+    /// One HTML document with the resources inlined.
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        self.document(Some(&self.resources))
+    }
+
+    /// An MHTML archive: the page, then one part per resource.
+    #[must_use]
+    pub fn to_mhtml(&self) -> String {
+        let mut out = String::new();
+        out.push_str("From: <Saved by ALICE Browser>\r\n");
+        out.push_str(&format!("Snapshot-Content-Location: {}\r\n", self.url));
+        out.push_str(&format!("Subject: {}\r\n", encode_header(&self.title)));
+        out.push_str("MIME-Version: 1.0\r\n");
+        out.push_str(&format!(
+            "Content-Type: multipart/related;\r\n\ttype=\"text/html\";\r\n\tboundary=\"{BOUNDARY}\"\r\n\r\n"
+        ));
+
+        out.push_str(&format!("--{BOUNDARY}\r\n"));
+        out.push_str("Content-Type: text/html; charset=\"utf-8\"\r\n");
+        out.push_str("Content-Transfer-Encoding: quoted-printable\r\n");
+        out.push_str(&format!("Content-Location: {}\r\n\r\n", self.url));
+        out.push_str(&quoted_printable(self.document(None).as_bytes()));
+        out.push_str("\r\n");
+
+        for (url, resource) in &self.resources {
+            out.push_str(&format!("--{BOUNDARY}\r\n"));
+            out.push_str(&format!("Content-Type: {}\r\n", resource.content_type));
+            out.push_str("Content-Transfer-Encoding: base64\r\n");
+            out.push_str(&format!("Content-Location: {url}\r\n\r\n"));
+            let encoded = data_url::base64_encode(&resource.bytes);
+            for line in encoded.as_bytes().chunks(76) {
+                out.push_str(&String::from_utf8_lossy(line));
+                out.push_str("\r\n");
+            }
+        }
+        out.push_str(&format!("--{BOUNDARY}--\r\n"));
+        out
+    }
+
+    /// The page as HTML; resources are inlined when given.
+    fn document(&self, inline: Option<&BTreeMap<String, Resource>>) -> String {
+        let mut out = String::from("<!DOCTYPE html>\n");
+        out.push_str(&format!(
+            "<!-- Saved by ALICE Browser from {} -->\n",
+            self.url.replace("--", "%2D%2D")
+        ));
+        write_html(&self.root, inline, &mut out);
+        out.push('\n');
+        out
+    }
+}
+
+/// `node` without dropped elements and attributes, with URLs resolved
+/// against `base`; `None` if the element itself is dropped.
+fn clean(node: &DomNode, base: Option<&Url>) -> Option<DomNode> {
+    if node.node_type == NodeType::Text {
+        return Some(node.clone());
+    }
+    let tag = node.tag.as_str();
+    if DROPPED_TAGS.contains(&tag) {
+        return None;
+    }
+    match tag {
+        // Only stylesheets are kept: icons, preloads and the like would
+        // reach for the network
+        "link" if !is_stylesheet(node) => return None,
+        // Charset (rewritten as UTF-8), refresh and CSP declarations
+        "meta" if node.attr("charset").is_some() || node.attr("http-equiv").is_some() => {
+            return None
+        }
+        _ => {}
+    }
+
+    let attributes = node
+        .attributes
+        .iter()
+        .filter(|(name, _)| !name.starts_with("on") && !DROPPED_ATTRS.contains(&name.as_str()))
+        .filter_map(|(name, value)| {
+            if !URL_ATTRS.contains(&name.as_str()) {
+                return Some((name.clone(), value.clone()));
+            }
+            let trimmed = value.trim();
+            if trimmed.to_ascii_lowercase().starts_with("javascript:") {
+                return None;
+            }
+            let resolved = match base {
+                Some(base) if !trimmed.starts_with('#') => base
+                    .join(trimmed)
+                    .map_or_else(|_| value.clone(), String::from),
+                _ => value.clone(),
+            };
+            Some((name.clone(), resolved))
+        })
+        .collect();
+    let children = node
+        .children
+        .iter()
+        .filter_map(|child| clean(child, base))
+        .collect();
+    let mut cleaned = DomNode::element(node.tag.clone(), attributes, children);
+    cleaned.classification = node.classification;
+    Some(cleaned)
+}
+
+fn is_stylesheet(node: &DomNode) -> bool {
+    node.attr("rel").is_some_and(|rel| {
+        rel.split_ascii_whitespace()
+            .any(|r| r.eq_ignore_ascii_case("stylesheet"))
+    })
+}
+
+/// Web addresses of the images and stylesheets under `node`.
+fn collect_resources(node: &DomNode, out: &mut BTreeSet<String>) {
+    let url = match node.tag.as_str() {
+        "img" => node.attr("src"),
+        "link" => node.attr("href"),
+        _ => None,
+    };
+    if let Some(url) = url.filter(|u| u.starts_with("http://") || u.starts_with("https://")) {
+        out.insert(url.to_string());
+    }
+    for child in &node.children {
+        collect_resources(child, out);
+    }
+}
+
+fn write_html(node: &DomNode, inline: Option<&BTreeMap<String, Resource>>, out: &mut String) {
+    if node.node_type == NodeType::Text {
+        out.push_str(&escape(&node.text));
+        return;
+    }
+    let tag = node.tag.as_str();
+
+    // An inlined stylesheet replaces its <link>
+    if tag == "link" {
+        let sheet = node
+            .attr("href")
+            .and_then(|href| inline.and_then(|r| r.get(href)));
+        if let Some(sheet) = sheet {
+            let css = sniff::decode(&sheet.bytes, &sheet.content_type);
+            out.push_str("<style>");
+            out.push_str(&css.replace("</style", "<\\/style"));
+            out.push_str("</style>");
+            return;
+        }
+    }
+
+    let mut attributes: Vec<(&String, &String)> = node.attributes.iter().collect();
+    attributes.sort();
+    out.push('<');
+    out.push_str(tag);
+    for (name, value) in attributes {
+        let inlined = match (tag, name.as_str()) {
+            ("img", "src") => inline
+                .and_then(|r| r.get(value.as_str()))
+                .map(|image| data_url::encode(&mime_essence(&image.content_type), &image.bytes)),
+            _ => None,
+        };
+        let value = inlined.as_ref().unwrap_or(value);
+        out.push_str(&format!(" {name}=\"{}\"", escape(value)));
+    }
+    out.push('>');
+    if tag == "head" {
+        out.push_str("<meta charset=\"utf-8\">");
+    }
+    if VOID_TAGS.contains(&tag) {
+        return;
+    }
+    for child in &node.children {
+        write_html(child, inline, out);
+    }
+    out.push_str("</");
+    out.push_str(tag);
+    out.push('>');
+}
+
+/// Quoted-printable text with CRLF line ends and lines of at most 76
+/// characters.
+fn quoted_printable(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 11 / 10);
+    for (i, line) in bytes.split(|&b| b == b'\n').enumerate() {
+        if i > 0 {
+            out.push_str("\r\n");
+        }
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let mut width = 0;
+        for (j, &b) in line.iter().enumerate() {
+            let last = j + 1 == line.len();
+            let literal = matches!(b, b'!'..=b'<' | b'>'..=b'~') || (b == b' ' && !last);
+            let encoded = if literal {
+                (b as char).to_string()
+            } else {
+                format!("={b:02X}")
+            };
+            if width + encoded.len() > 75 {
+                out.push_str("=\r\n");
+                width = 0;
+            }
+            width += encoded.len();
+            out.push_str(&encoded);
+        }
+    }
+    out
+}
+
+fn decode_quoted_printable(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'=' {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        // Soft line break
+        if bytes[i + 1..].starts_with(b"\r\n") {
+            i += 3;
+            continue;
+        }
+        if bytes[i + 1..].starts_with(b"\n") {
+            i += 2;
+            continue;
+        }
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(b'=');
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// A header value, as an RFC 2047 encoded word unless it is plain ASCII.
+fn encode_header(value: &str) -> String {
+    let value = value.replace(['\r', '\n'], " ");
+    if value.bytes().all(|b| (b' '..=b'~').contains(&b)) {
+        value
+    } else {
+        format!("=?utf-8?B?{}?=", data_url::base64_encode(value.as_bytes()))
+    }
+}
+
+/// Headers of a MIME entity, names lower-cased, folded lines joined, and
+/// the body after them.
+fn split_entity(text: &str) -> (HashMap<String, String>, &str) {
+    let (head, body) = text
+        .split_once("\r\n\r\n")
+        .or_else(|| text.split_once("\n\n"))
+        .unwrap_or((text, ""));
+    let mut headers: HashMap<String, String> = HashMap::new();
+    let mut last: Option<String> = None;
+    for line in head.lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some(value) = last.as_ref().and_then(|name| headers.get_mut(name)) {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let name = name.trim().to_ascii_lowercase();
+            headers.insert(name.clone(), value.trim().to_string());
+            last = Some(name);
+        }
+    }
+    (headers, body)
+}
+
+/// `name=` parameter of a header value, unquoted.
+fn header_param<'a>(value: &'a str, name: &str) -> Option<&'a str> {
+    value.split(';').find_map(|param| {
+        let (key, v) = param.trim().split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| v.trim().trim_matches('"'))
+    })
+}
+
+/// The HTML of an MHTML archive, with the archived resources put back as
+/// `data:` URLs.
+fn read_mhtml(text: &str, boundary: &str) -> Option<String> {
+    let delimiter = format!("--{boundary}");
+    let mut html: Option<String> = None;
+    let mut resources: Vec<(String, String)> = Vec::new();
+    for part in text.split(delimiter.as_str()).skip(1) {
+        if part.starts_with("--") {
+            break;
+        }
+        let (headers, body) = split_entity(part.trim_start_matches(['\r', '\n']));
+        let content_type = headers
+            .get("content-type")
+            .map_or("application/octet-stream", String::as_str);
+        let bytes = match headers
+            .get("content-transfer-encoding")
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("base64") => data_url::base64_decode(body)?,
+            Some("quoted-printable") => decode_quoted_printable(body),
+            _ => body.trim_end_matches(['\r', '\n']).as_bytes().to_vec(),
+        };
+        if html.is_none() && mime_essence(content_type) == "text/html" {
+            html = Some(sniff::decode(&bytes, content_type));
+        } else if let Some(location) = headers.get("content-location") {
+            resources.push((
+                location.clone(),
+                data_url::encode(&mime_essence(content_type), &bytes),
+            ));
+        }
+    }
+    let mut html = html?;
+    for (location, data) in resources {
+        html = html.replace(
+            &format!("\"{}\"", escape(&location)),
+            &format!("\"{data}\""),
+        );
+    }
+    Some(html)
+}
+
+/// HTML of a saved page: an MHTML archive is unpacked, anything else is
+/// read as HTML.
+///
+/// # Errors
+///
+/// Returns `SnapshotError` if the file is an MHTML archive without an
+/// HTML part.
+pub fn read(bytes: &[u8]) -> Result<String, SnapshotError> {
+    // MIME headers are ASCII; the body of a plain page never parses as them
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(4096)]);
+    let (headers, _) = split_entity(&head);
+    let boundary = headers
+        .get("content-type")
+        .filter(|ct| mime_essence(ct) == "multipart/related")
+        .and_then(|ct| header_param(ct, "boundary"));
+    match boundary {
+        Some(boundary) => {
+            read_mhtml(&String::from_utf8_lossy(bytes), boundary).ok_or_else(|| SnapshotError {
+                message: "The web archive holds no readable page".into(),
+            })
+        }
+        None => Ok(sniff::decode(bytes, "text/html")),
+    }
+}
+
+/// Read the saved page at `path`.
+///
+/// # Errors
+///
+/// Returns `SnapshotError` if the file cannot be read or holds no page.
+pub fn load(path: &Path) -> Result<String, SnapshotError> {
+    let bytes = std::fs::read(path).map_err(|e| SnapshotError {
+        message: format!("{}: {e}", path.display()),
+    })?;
+    read(&bytes)
+}
+
+/// Whether `url` is a `file://` URL.
+#[must_use]
+pub fn is_file_url(url: &str) -> bool {
+    url.get(..7)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("file://"))
+}
+
+/// Local path of a `file://` URL.
+#[must_use]
+pub fn file_path(url: &str) -> Option<PathBuf> {
+    let url = Url::parse(url).ok()?;
+    (url.scheme() == "file").then(|| url.to_file_path().ok())?
+}
+
+/// `file://` URL of an absolute `path`.
+#[must_use]
+pub fn file_url(path: &Path) -> Option<String> {
+    Url::from_file_path(path).ok().map(String::from)
+}
+
+/// Fetch an image or stylesheet for a snapshot through the configured
+/// proxy (blocking); `None` if it fails or exceeds
+/// [`MAX_RESOURCE_BYTES`].
+#[cfg(not(target_arch = "wasm32"))]
+#[must_use]
+pub fn fetch_resource(url: &str) -> Option<Resource> {
+    let parsed = Url::parse(url).ok()?;
+    let excepted = parsed
+        .host_str()
+        .is_some_and(|host| tls::exceptions().is_allowed(host));
+    let response = proxy::client_builder()
+        .timeout(RESOURCE_TIMEOUT)
+        .danger_accept_invalid_certs(excepted)
+        .cookie_provider(crate::net::cookies::shared_jar())
+        .build()
+        .ok()?
+        .get(parsed.as_str())
+        .send()
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("application/octet-stream")
+        .to_string();
+    let mut bytes = Vec::new();
+    response
+        .take(MAX_RESOURCE_BYTES as u64 + 1)
+        .read_to_end(&mut bytes)
+        .ok()?;
+    (bytes.len() <= MAX_RESOURCE_BYTES).then_some(Resource {
+        content_type,
+        bytes,
+    })
+}
+
+/// No network on wasm32: snapshots keep their resources' addresses.
+#[cfg(target_arch = "wasm32")]
+#[must_use]
+pub fn fetch_resource(_url: &str) -> Option<Resource> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+
+    const PAGE: &str = r#"<html><head><title>Café notes</title>
+        <meta charset="windows-1252"><link rel="stylesheet" href="/site.css">
+        <link rel="icon" href="/favicon.ico"><script>track()</script></head>
+        <body onload="go()"><h1>Notes</h1>
+        <p>See <a href="/more#top">more</a> or <a href="javascript:void(0)">this</a>.</p>
+        <img src="img/photo.png" srcset="big.png 2x"><img src="https://cdn.example/gone.png">
+        <iframe src="https://ads.example/"></iframe></body></html>"#;
+
+    fn snapshot() -> Snapshot {
+        let dom = parse_html(PAGE, "https://example.com/blog/post");
+        Snapshot::capture(&dom, |url| match url {
+            "https://example.com/site.css" => Some(Resource {
+                content_type: "text/css".into(),
+                bytes: b"h1 { color: teal }".to_vec(),
+            }),
+            "https://example.com/blog/img/photo.png" => Some(Resource {
+                content_type: "image/png".into(),
+                bytes: vec![0x89, b'P', b'N', b'G'],
+            }),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn html_snapshots_are_self_contained() {
+        let snapshot = snapshot();
+        assert_eq!(snapshot.resources.len(), 2);
+        let html = snapshot.to_html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<meta charset=\"utf-8\">"));
+        assert!(html.contains("<style>h1 { color: teal }</style>"));
+        assert!(html.contains("src=\"data:image/png;base64,iVBORw==\""));
+        // Missing resources keep their address; links lead to the live site
+        assert!(html.contains("src=\"https://cdn.example/gone.png\""));
+        assert!(html.contains("href=\"https://example.com/more#top\""));
+        for dropped in [
+            "<script",
+            "<iframe",
+            "onload",
+            "srcset",
+            "favicon",
+            "javascript:",
+            "1252",
+        ] {
+            assert!(!html.contains(dropped), "{dropped} was saved");
+        }
+
+        let reopened = parse_html(&read(html.as_bytes()).unwrap(), "file:///tmp/post.html");
+        assert_eq!(reopened.title, "Café notes");
+        assert!(reopened.root.collect_text().contains("Notes"));
+    }
+
+    #[test]
+    fn mhtml_snapshots_round_trip() {
+        let snapshot = snapshot();
+        let archive = snapshot.to_mhtml();
+        assert!(archive.contains("Subject: =?utf-8?B?"));
+        assert!(archive.contains("Content-Location: https://example.com/site.css"));
+        assert!(archive.lines().all(|l| l.len() <= 78));
+
+        let html = read(archive.as_bytes()).unwrap();
+        assert!(html.contains("src=\"data:image/png;base64,iVBORw==\""));
+        let reopened = parse_html(&html, "file:///tmp/post.mhtml");
+        assert_eq!(reopened.title, "Café notes");
+
+        let broken = archive.replace("text/html", "text/plain");
+        assert!(read(broken.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn quoted_printable_round_trips() {
+        let text = format!("{}é = ok \nnext line\t", "x".repeat(100));
+        let encoded = quoted_printable(text.as_bytes());
+        assert!(encoded.lines().all(|l| l.len() <= 76));
+        assert!(encoded.contains("=C3=A9 =3D ok=20\r\n"));
+        let decoded = decode_quoted_printable(&encoded);
+        assert_eq!(decoded, text.replace('\n', "\r\n").as_bytes());
+    }
+
+    #[test]
+    fn file_urls() {
+        assert!(is_file_url("FILE:///home/a/page.html"));
+        assert!(!is_file_url("https://example.com/"));
+        let path = std::env::temp_dir().join("saved page.html");
+        let url = file_url(&path).unwrap();
+        assert!(url.starts_with("file://") && url.ends_with("saved%20page.html"));
+        assert_eq!(file_path(&url), Some(path));
+        assert_eq!(file_path("https://example.com/a.html"), None);
+    }
+}
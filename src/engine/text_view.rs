@@ -0,0 +1,285 @@
+//! Readable views of non-HTML text resources.
+//!
+//! JSON, CSV/TSV, XML and plain text skip the HTML stages of the pipeline.
+//! The body is parsed into a [`TextView`], which the browser draws with
+//! widgets that suit it (foldable JSON, a table, monospaced text), and is
+//! also projected to a plain HTML document so layout, search and the SDF
+//! views work as they do for any page. Very large files are projected only
+//! up to [`PROJECTED_LINES`]; the view itself always holds everything.
+
+use std::fmt::Write as _;
+
+use crate::engine::internal::escape;
+use crate::net::sniff::ResourceKind;
+
+/// Lines (or table rows) carried over into the HTML projection.
+pub const PROJECTED_LINES: usize = 5_000;
+
+/// A parsed text resource.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextView {
+    pub kind: ResourceKind,
+    pub content: TextContent,
+    /// Why the body could not be parsed as `kind` (it is shown as text)
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextContent {
+    Json(serde_json::Value),
+    /// Rows of a CSV/TSV file; the first is the header
+    Table(Vec<Vec<String>>),
+    /// Plain text, or XML re-indented one element per line
+    Text(String),
+}
+
+impl TextView {
+    /// Parse `text` as a resource of `kind`.
+    #[must_use]
+    pub fn parse(kind: ResourceKind, text: &str) -> Self {
+        let text = text.trim_start_matches('\u{feff}');
+        let (content, error) = match kind {
+            ResourceKind::Json => match serde_json::from_str(text) {
+                Ok(value) => (TextContent::Json(value), None),
+                Err(e) => (TextContent::Text(text.to_string()), Some(e.to_string())),
+            },
+            ResourceKind::Csv => (TextContent::Table(parse_csv(text, ',')), None),
+            ResourceKind::Tsv => (TextContent::Table(parse_csv(text, '\t')), None),
+            ResourceKind::Xml => (TextContent::Text(pretty_xml(text)), None),
+            ResourceKind::Html | ResourceKind::Text => (TextContent::Text(text.to_string()), None),
+        };
+        Self {
+            kind,
+            content,
+            error,
+        }
+    }
+
+    /// The view as a simple HTML document titled `title`: a table for
+    /// CSV, one paragraph per line otherwise.
+    #[must_use]
+    pub fn to_html(&self, title: &str) -> String {
+        let mut body = String::new();
+        if let Some(ref error) = self.error {
+            let _ = write!(body, "<p>{}</p>", escape(error));
+        }
+        let omitted = match self.content {
+            TextContent::Table(ref rows) => {
+                body.push_str("<table>");
+                for (i, row) in rows.iter().take(PROJECTED_LINES).enumerate() {
+                    let cell = if i == 0 { "th" } else { "td" };
+                    body.push_str("<tr>");
+                    for value in row {
+                        let _ = write!(body, "<{cell}>{}</{cell}>", escape(value));
+                    }
+                    body.push_str("</tr>");
+                }
+                body.push_str("</table>");
+                rows.len().saturating_sub(PROJECTED_LINES)
+            }
+            TextContent::Json(ref value) => {
+                let pretty = serde_json::to_string_pretty(value).unwrap_or_default();
+                project_lines(&pretty, &mut body)
+            }
+            TextContent::Text(ref text) => project_lines(text, &mut body),
+        };
+        if omitted > 0 {
+            let _ = write!(body, "<p>… {omitted} more lines</p>");
+        }
+        format!(
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{}</title></head>\
+             <body>{body}</body></html>",
+            escape(title)
+        )
+    }
+}
+
+/// One paragraph per non-empty line, leading spaces kept as no-break
+/// spaces. Returns how many lines were left out.
+fn project_lines(text: &str, body: &mut String) -> usize {
+    let mut lines = text.lines();
+    for line in lines.by_ref().take(PROJECTED_LINES) {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indent = "\u{a0}".repeat(line.len() - trimmed.len());
+        let _ = write!(body, "<p>{indent}{}</p>", escape(trimmed.trim_end()));
+    }
+    lines.count()
+}
+
+/// Split delimited text into rows, honouring RFC 4180 quoting (quoted
+/// fields may hold delimiters, newlines and `""` for a quote).
+#[must_use]
+pub fn parse_csv(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ if c == delimiter => row.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+/// Re-indent XML one tag per line; an element holding only text stays on
+/// one line. Comments, CDATA and declarations are kept as they are.
+#[must_use]
+pub fn pretty_xml(text: &str) -> String {
+    let tokens = xml_tokens(text);
+    let mut out = String::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        let indent = "  ".repeat(depth);
+        if let Some(kind) = tag_kind(token) {
+            match kind {
+                Tag::Close => {
+                    depth = depth.saturating_sub(1);
+                    let _ = writeln!(out, "{}{token}", "  ".repeat(depth));
+                }
+                Tag::Open => {
+                    // <a>text</a> on one line
+                    if let (Some(&text), Some(&close)) = (tokens.get(i + 1), tokens.get(i + 2)) {
+                        if tag_kind(text).is_none() && tag_kind(close) == Some(Tag::Close) {
+                            let _ = writeln!(out, "{indent}{token}{text}{close}");
+                            i += 3;
+                            continue;
+                        }
+                    }
+                    let _ = writeln!(out, "{indent}{token}");
+                    depth += 1;
+                }
+                Tag::Other => {
+                    let _ = writeln!(out, "{indent}{token}");
+                }
+            }
+        } else {
+            let _ = writeln!(out, "{indent}{token}");
+        }
+        i += 1;
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tag {
+    Open,
+    Close,
+    /// Self-closing tags, comments, CDATA, declarations
+    Other,
+}
+
+fn tag_kind(token: &str) -> Option<Tag> {
+    if !token.starts_with('<') {
+        return None;
+    }
+    Some(if token.starts_with("</") {
+        Tag::Close
+    } else if token.starts_with("<!") || token.starts_with("<?") || token.ends_with("/>") {
+        Tag::Other
+    } else {
+        Tag::Open
+    })
+}
+
+/// Tags (with comments and CDATA whole) and trimmed text between them.
+fn xml_tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        if rest.starts_with('<') {
+            let end = [("<!--", "-->"), ("<![CDATA[", "]]>")]
+                .iter()
+                .find(|(open, _)| rest.starts_with(open))
+                .map_or(">", |&(_, close)| close);
+            let len = rest.find(end).map_or(rest.len(), |i| i + end.len());
+            tokens.push(&rest[..len]);
+            rest = &rest[len..];
+        } else {
+            let len = rest.find('<').unwrap_or(rest.len());
+            let chunk = rest[..len].trim();
+            if !chunk.is_empty() {
+                tokens.push(chunk);
+            }
+            rest = &rest[len..];
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_handles_quotes_and_newlines() {
+        let rows = parse_csv(
+            "name,note\r\n\"Doe, J\",\"said \"\"hi\"\"\nthen left\"\nx,\n",
+            ',',
+        );
+        assert_eq!(
+            rows,
+            vec![
+                vec!["name".to_string(), "note".to_string()],
+                vec!["Doe, J".to_string(), "said \"hi\"\nthen left".to_string()],
+                vec!["x".to_string(), String::new()],
+            ]
+        );
+        assert_eq!(parse_csv("a\tb", '\t'), vec![vec!["a", "b"]]);
+    }
+
+    #[test]
+    fn xml_is_reindented() {
+        let xml = "<?xml version=\"1.0\"?><feed><title>News</title><!-- a > b --><entry><id>1</id><link href=\"x\"/></entry></feed>";
+        assert_eq!(
+            pretty_xml(xml),
+            "<?xml version=\"1.0\"?>\n<feed>\n  <title>News</title>\n  <!-- a > b -->\n  \
+             <entry>\n    <id>1</id>\n    <link href=\"x\"/>\n  </entry>\n</feed>\n"
+        );
+    }
+
+    #[test]
+    fn json_keeps_key_order_and_falls_back_to_text() {
+        let view = TextView::parse(ResourceKind::Json, r#"{"z": 1, "a": [true, null]}"#);
+        let TextContent::Json(ref value) = view.content else {
+            panic!("not parsed as JSON");
+        };
+        let keys: Vec<_> = value.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["z", "a"]);
+        assert!(view
+            .to_html("data.json")
+            .contains("<p>\u{a0}\u{a0}&quot;z&quot;: 1,</p>"));
+
+        let broken = TextView::parse(ResourceKind::Json, "{oops");
+        assert!(broken.error.is_some());
+        assert_eq!(broken.content, TextContent::Text("{oops".into()));
+    }
+}
@@ -0,0 +1,293 @@
+//! Shared pool of worker threads for background work.
+//!
+//! Page loads, image downloads, link previews and prefetches (through the
+//! [`net::scheduler`](crate::net::scheduler)), wayback lookups, search
+//! suggestions, media probes, saved pages, session restores and progressive
+//! 3-D renders all run as jobs of one [`WorkerPool`] instead of spawning an
+//! OS thread each. The pool starts threads as jobs arrive, up to its size,
+//! and keeps them for later jobs; beyond that, jobs wait their turn, so a
+//! page with hundreds of links never means hundreds of threads.
+//!
+//! A job may come with a [`CancelToken`]: cancelled before it starts, it is
+//! dropped without running; once running, it is handed the token to check
+//! between chunks of work.
+
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock, PoisonError};
+
+/// Threads in the shared pool: room for every fetch the scheduler runs at
+/// once ([`MAX_CONCURRENT`](crate::net::scheduler::MAX_CONCURRENT)) and
+/// for other background work beside them.
+pub const POOL_SIZE: usize = 24;
+
+/// Shared flag a job checks to stop early.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    /// Token whose cancellation also cancels this one
+    parent: Option<Arc<CancelToken>>,
+}
+
+impl CancelToken {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A token cancelled along with this one, or on its own.
+    #[must_use]
+    pub fn child(&self) -> Self {
+        Self {
+            cancelled: Arc::default(),
+            parent: Some(Arc::new(self.clone())),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self.parent.as_ref().is_some_and(|p| p.is_cancelled())
+    }
+}
+
+type Job = Box<dyn FnOnce(&CancelToken) + Send>;
+
+#[derive(Default)]
+struct State {
+    queue: VecDeque<(CancelToken, Job)>,
+    /// Threads started
+    threads: usize,
+    /// Threads waiting for a job
+    idle: usize,
+}
+
+#[derive(Default)]
+struct Inner {
+    state: Mutex<State>,
+    ready: Condvar,
+}
+
+/// Bounded pool of threads running jobs in submission order.
+#[derive(Clone)]
+pub struct WorkerPool {
+    inner: Arc<Inner>,
+    size: usize,
+}
+
+impl Default for WorkerPool {
+    fn default() -> Self {
+        Self::new(POOL_SIZE)
+    }
+}
+
+impl WorkerPool {
+    /// Pool of at most `size` threads, none started yet.
+    #[must_use]
+    pub fn new(size: usize) -> Self {
+        Self {
+            inner: Arc::default(),
+            size: size.max(1),
+        }
+    }
+
+    /// Run `job` on a worker thread.
+    pub fn spawn(&self, job: impl FnOnce() + Send + 'static) {
+        self.spawn_with(&CancelToken::new(), move |_| job());
+    }
+
+    /// Run `job` on a worker thread unless `token` is cancelled before it
+    /// starts.
+    pub fn spawn_with(&self, token: &CancelToken, job: impl FnOnce(&CancelToken) + Send + 'static) {
+        let mut state = lock(&self.inner.state);
+        self.enqueue(&mut state, token.clone(), Box::new(job));
+    }
+
+    /// Queue `job` and start a thread for it, or wake an idle one.
+    fn enqueue(&self, state: &mut State, token: CancelToken, job: Job) {
+        state.queue.push_back((token, job));
+        // An idle thread already woken for an earlier job still counts as
+        // idle until it takes it, so compare against the whole queue
+        if state.queue.len() > state.idle && state.threads < self.size {
+            let inner = Arc::clone(&self.inner);
+            match std::thread::Builder::new()
+                .name("alice-worker".into())
+                .spawn(move || work(&inner))
+            {
+                Ok(_) => state.threads += 1,
+                // The threads already started will get to the job
+                Err(e) => log::warn!("Could not start a worker thread: {e}"),
+            }
+        } else {
+            self.inner.ready.notify_one();
+        }
+    }
+
+    /// Most threads the pool starts.
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Threads started so far.
+    #[must_use]
+    pub fn threads(&self) -> usize {
+        lock(&self.inner.state).threads
+    }
+
+    /// Jobs waiting for a free thread.
+    #[must_use]
+    pub fn queued(&self) -> usize {
+        lock(&self.inner.state).queue.len()
+    }
+}
+
+/// A worker thread's loop: take the oldest job, run it unless cancelled,
+/// wait when there is none.
+fn work(inner: &Inner) {
+    loop {
+        let (token, job) = {
+            let mut state = lock(&inner.state);
+            loop {
+                if let Some(next) = state.queue.pop_front() {
+                    break next;
+                }
+                state.idle += 1;
+                state = inner
+                    .ready
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner);
+                state.idle -= 1;
+            }
+        };
+        // Dropped outside the lock: a job's captures may submit more work
+        if token.is_cancelled() {
+            drop(job);
+            continue;
+        }
+        // A panicking job must not take the thread with it
+        if panic::catch_unwind(AssertUnwindSafe(|| job(&token))).is_err() {
+            log::warn!("A background job panicked");
+        }
+    }
+}
+
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Process-wide pool all background work runs on.
+#[must_use]
+pub fn shared() -> &'static WorkerPool {
+    static POOL: OnceLock<WorkerPool> = OnceLock::new();
+    POOL.get_or_init(WorkerPool::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    const WAIT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn many_jobs_share_few_threads() {
+        let pool = WorkerPool::new(2);
+        let (done, finished) = mpsc::channel();
+        for i in 0..50 {
+            let done = done.clone();
+            pool.spawn(move || {
+                let _ = done.send(i);
+            });
+        }
+        let mut seen: Vec<i32> = (0..50)
+            .map(|_| finished.recv_timeout(WAIT).unwrap())
+            .collect();
+        seen.sort_unstable();
+        assert_eq!(seen, (0..50).collect::<Vec<_>>());
+        assert!(pool.threads() <= 2);
+    }
+
+    #[test]
+    fn cancelled_jobs_are_skipped() {
+        let pool = WorkerPool::new(1);
+        let (release, gate) = mpsc::channel::<()>();
+        let (busy, started) = mpsc::channel();
+        pool.spawn(move || {
+            let _ = busy.send(());
+            let _ = gate.recv_timeout(WAIT);
+        });
+        started.recv_timeout(WAIT).unwrap();
+
+        let page = CancelToken::new();
+        let (ran, skipped) = mpsc::channel();
+        let ran_too = ran.clone();
+        pool.spawn_with(&page.child(), move |_| {
+            let _ = ran_too.send("cancelled");
+        });
+        pool.spawn(move || {
+            let _ = ran.send("kept");
+        });
+        assert_eq!(pool.queued(), 2);
+        page.cancel();
+        assert!(!CancelToken::new().child().is_cancelled());
+
+        release.send(()).unwrap();
+        assert_eq!(skipped.recv_timeout(WAIT), Ok("kept"));
+        assert!(skipped.recv_timeout(Duration::from_millis(50)).is_err());
+    }
+
+    #[test]
+    fn bursts_start_threads_past_an_idle_one() {
+        let pool = WorkerPool::new(4);
+        let (done, finished) = mpsc::channel();
+        pool.spawn(move || {
+            let _ = done.send(());
+        });
+        finished.recv_timeout(WAIT).unwrap();
+        // Wait for the thread to go idle
+        while lock(&pool.inner.state).idle == 0 {
+            std::thread::yield_now();
+        }
+
+        // Each job waits for all of them to start: on fewer threads than
+        // jobs, none would ever finish. The burst is queued under one lock,
+        // so the idle thread cannot take a job before the last is queued.
+        let started = Arc::new(std::sync::Barrier::new(4));
+        let (done, finished) = mpsc::channel();
+        {
+            let mut state = lock(&pool.inner.state);
+            for _ in 0..4 {
+                let started = Arc::clone(&started);
+                let done = done.clone();
+                let job: Job = Box::new(move |_| {
+                    started.wait();
+                    let _ = done.send(());
+                });
+                pool.enqueue(&mut state, CancelToken::new(), job);
+            }
+        }
+        for _ in 0..4 {
+            finished.recv_timeout(WAIT).unwrap();
+        }
+        assert_eq!(pool.threads(), 4);
+    }
+
+    #[test]
+    fn panics_do_not_lose_the_thread() {
+        let pool = WorkerPool::new(1);
+        pool.spawn(|| panic!("job failed"));
+        let (done, finished) = mpsc::channel();
+        pool.spawn(move || {
+            let _ = done.send(());
+        });
+        assert_eq!(finished.recv_timeout(WAIT), Ok(()));
+        assert_eq!(pool.threads(), 1);
+    }
+}
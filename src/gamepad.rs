@@ -0,0 +1,313 @@
+//! Gamepad / controller navigation.
+//!
+//! Polls the first connected controller through `gilrs` and reduces it to a
+//! small, mode-agnostic [`PadInput`]: two dead-zoned sticks, the analog
+//! triggers, and the navigation buttons pressed since the last poll. What
+//! the sticks do is up to the active view mode:
+//!
+//! - **Left stick**: look (OZ), orbit (Spatial3D), scroll (2D modes)
+//! - **Right stick**: look (OZ), dolly (Spatial3D)
+//! - **LT / RT**: slow down / speed up the OZ flow
+//!
+//! Buttons go through [`PadBindings`], which the settings can change; by
+//! default **A** grabs the particle under the reticle (or opens it if
+//! already grabbed), **B** releases it or else goes back, **RB** goes
+//! forward and **View** shows or hides the on-screen hints.
+//!
+//! Stick axes follow the `gilrs` convention: `+x` right, `+y` up.
+
+use serde::{Deserialize, Serialize};
+
+/// Radial dead zone applied to both sticks.
+pub const DEADZONE: f32 = 0.18;
+
+/// Navigation action a button can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadButton {
+    /// Grab or open
+    Activate,
+    /// Release, or history back when nothing is held
+    Back,
+    /// History forward
+    Forward,
+    /// Show or hide the button hints
+    Hints,
+}
+
+impl PadButton {
+    pub const ALL: [Self; 4] = [Self::Activate, Self::Back, Self::Forward, Self::Hints];
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Activate => "Grab / open",
+            Self::Back => "Release / back",
+            Self::Forward => "Forward",
+            Self::Hints => "Button hints",
+        }
+    }
+}
+
+/// Physical button, by position, with Xbox names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PadKey {
+    South,
+    East,
+    West,
+    North,
+    LeftShoulder,
+    RightShoulder,
+    Select,
+    Start,
+}
+
+impl PadKey {
+    pub const ALL: [Self; 8] = [
+        Self::South,
+        Self::East,
+        Self::West,
+        Self::North,
+        Self::LeftShoulder,
+        Self::RightShoulder,
+        Self::Select,
+        Self::Start,
+    ];
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::South => "A",
+            Self::East => "B",
+            Self::West => "X",
+            Self::North => "Y",
+            Self::LeftShoulder => "LB",
+            Self::RightShoulder => "RB",
+            Self::Select => "View",
+            Self::Start => "Menu",
+        }
+    }
+
+    /// The key of a `gilrs` button, if it is one that can be bound.
+    #[must_use]
+    pub const fn from_gilrs(button: gilrs::Button) -> Option<Self> {
+        match button {
+            gilrs::Button::South => Some(Self::South),
+            gilrs::Button::East => Some(Self::East),
+            gilrs::Button::West => Some(Self::West),
+            gilrs::Button::North => Some(Self::North),
+            gilrs::Button::LeftTrigger => Some(Self::LeftShoulder),
+            gilrs::Button::RightTrigger => Some(Self::RightShoulder),
+            gilrs::Button::Select => Some(Self::Select),
+            gilrs::Button::Start => Some(Self::Start),
+            _ => None,
+        }
+    }
+}
+
+/// Which button triggers each action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PadBindings {
+    pub activate: PadKey,
+    pub back: PadKey,
+    pub forward: PadKey,
+    pub hints: PadKey,
+}
+
+impl Default for PadBindings {
+    fn default() -> Self {
+        Self {
+            activate: PadKey::South,
+            back: PadKey::East,
+            forward: PadKey::RightShoulder,
+            hints: PadKey::Select,
+        }
+    }
+}
+
+impl PadBindings {
+    /// The button bound to `action`.
+    #[must_use]
+    pub const fn key(&self, action: PadButton) -> PadKey {
+        match action {
+            PadButton::Activate => self.activate,
+            PadButton::Back => self.back,
+            PadButton::Forward => self.forward,
+            PadButton::Hints => self.hints,
+        }
+    }
+
+    pub fn key_mut(&mut self, action: PadButton) -> &mut PadKey {
+        match action {
+            PadButton::Activate => &mut self.activate,
+            PadButton::Back => &mut self.back,
+            PadButton::Forward => &mut self.forward,
+            PadButton::Hints => &mut self.hints,
+        }
+    }
+
+    /// The action `button` triggers; the first in [`PadButton::ALL`] when
+    /// two share a button.
+    #[must_use]
+    pub fn action(&self, button: gilrs::Button) -> Option<PadButton> {
+        let key = PadKey::from_gilrs(button)?;
+        PadButton::ALL.into_iter().find(|&a| self.key(a) == key)
+    }
+}
+
+/// Controller state for one frame.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PadInput {
+    /// Left stick after the dead zone
+    pub left: [f32; 2],
+    /// Right stick after the dead zone
+    pub right: [f32; 2],
+    /// Left and right trigger, `0..=1`
+    pub triggers: [f32; 2],
+    /// Buttons pressed since the previous poll, in order
+    pub pressed: Vec<PadButton>,
+}
+
+impl PadInput {
+    /// Whether both sticks are at rest.
+    #[must_use]
+    pub fn sticks_idle(&self) -> bool {
+        self.left == [0.0, 0.0] && self.right == [0.0, 0.0]
+    }
+}
+
+/// Radial dead zone: zero inside `deadzone`, rescaled to `0..=1` outside it
+/// so motion starts smoothly at the edge.
+#[must_use]
+pub fn apply_deadzone(stick: [f32; 2], deadzone: f32) -> [f32; 2] {
+    let mag = stick[0].hypot(stick[1]);
+    if mag <= deadzone {
+        return [0.0, 0.0];
+    }
+    let scaled = ((mag - deadzone) / (1.0 - deadzone)).min(1.0);
+    let k = scaled / mag;
+    [stick[0] * k, stick[1] * k]
+}
+
+/// Connected controllers.
+pub struct Gamepads {
+    gilrs: Option<gilrs::Gilrs>,
+    /// Controller that last sent input
+    active: Option<gilrs::GamepadId>,
+}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Gamepads {
+    /// Initialise the platform backend. Input is simply absent if it fails.
+    #[must_use]
+    pub fn new() -> Self {
+        let gilrs = match gilrs::Gilrs::new() {
+            Ok(g) => Some(g),
+            Err(e) => {
+                log::warn!("Gamepad support disabled: {e}");
+                None
+            }
+        };
+        let active = gilrs
+            .as_ref()
+            .and_then(|g| g.gamepads().next().map(|(id, _)| id));
+        Self { gilrs, active }
+    }
+
+    /// Whether a controller is connected.
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        self.gilrs
+            .as_ref()
+            .zip(self.active)
+            .is_some_and(|(g, id)| g.gamepad(id).is_connected())
+    }
+
+    /// Drain pending events, mapping buttons through `bindings`, and read
+    /// the active controller's sticks and triggers.
+    pub fn poll(&mut self, bindings: &PadBindings) -> PadInput {
+        let mut input = PadInput::default();
+        let Some(ref mut gilrs) = self.gilrs else {
+            return input;
+        };
+
+        while let Some(gilrs::Event { id, event, .. }) = gilrs.next_event() {
+            match event {
+                gilrs::EventType::ButtonPressed(button, _) => {
+                    self.active = Some(id);
+                    if let Some(b) = bindings.action(button) {
+                        input.pressed.push(b);
+                    }
+                }
+                gilrs::EventType::AxisChanged(..) | gilrs::EventType::Connected => {
+                    self.active = Some(id);
+                }
+                gilrs::EventType::Disconnected if self.active == Some(id) => {
+                    self.active = gilrs.gamepads().next().map(|(id, _)| id);
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(id) = self.active {
+            let pad = gilrs.gamepad(id);
+            let stick = |x, y| apply_deadzone([pad.value(x), pad.value(y)], DEADZONE);
+            input.left = stick(gilrs::Axis::LeftStickX, gilrs::Axis::LeftStickY);
+            input.right = stick(gilrs::Axis::RightStickX, gilrs::Axis::RightStickY);
+            let trigger = |b| pad.button_data(b).map_or(0.0, |d| d.value());
+            input.triggers = [
+                trigger(gilrs::Button::LeftTrigger2),
+                trigger(gilrs::Button::RightTrigger2),
+            ];
+        }
+        input
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadzone_zeroes_small_input() {
+        assert_eq!(apply_deadzone([0.1, -0.1], DEADZONE), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn deadzone_rescales_to_full_range() {
+        let [x, y] = apply_deadzone([1.0, 0.0], DEADZONE);
+        assert!((x - 1.0).abs() < 1e-6 && y == 0.0);
+        // Just past the edge starts near zero
+        let [x, _] = apply_deadzone([DEADZONE + 0.01, 0.0], DEADZONE);
+        assert!(x > 0.0 && x < 0.05);
+        // Direction is preserved
+        let [x, y] = apply_deadzone([-0.5, 0.5], DEADZONE);
+        assert!(x < 0.0 && (x + y).abs() < 1e-6);
+    }
+
+    #[test]
+    fn maps_buttons_through_bindings() {
+        let mut bindings = PadBindings::default();
+        assert_eq!(
+            bindings.action(gilrs::Button::South),
+            Some(PadButton::Activate)
+        );
+        assert_eq!(bindings.action(gilrs::Button::East), Some(PadButton::Back));
+        assert_eq!(bindings.action(gilrs::Button::Start), None);
+        // Triggers are analog, not bindable
+        assert_eq!(bindings.action(gilrs::Button::LeftTrigger2), None);
+
+        *bindings.key_mut(PadButton::Back) = PadKey::LeftShoulder;
+        assert_eq!(
+            bindings.action(gilrs::Button::LeftTrigger),
+            Some(PadButton::Back)
+        );
+        assert_eq!(bindings.action(gilrs::Button::East), None);
+    }
+}
@@ -13,8 +13,13 @@
 
 pub mod dom;
 pub mod engine;
+pub mod local_time;
 pub mod net;
+pub mod profile;
 pub mod render;
+// Encrypted profile files
+#[cfg(not(target_arch = "wasm32"))]
+pub mod vault;
 
 // Deep-Fried Rust: カリッカリ最適化モジュール
 pub mod branchless;
@@ -24,10 +29,14 @@ pub mod simd;
 // Mobile UI (always compiled, feature-gated internally where needed)
 pub mod mobile;
 
+// JavaScript bindings of the browser-hosted (wasm32) build
+#[cfg(target_arch = "wasm32")]
+pub mod web;
+
 #[cfg(feature = "search")]
 pub mod search;
 
-#[cfg(feature = "telemetry")]
+// Frame profiler; page-load metrics with feature `telemetry`
 pub mod telemetry;
 
 #[cfg(feature = "text")]
@@ -62,3 +71,12 @@ pub mod sdf_bridge;
 
 #[cfg(feature = "voice-web")]
 pub mod voice_bridge;
+
+#[cfg(feature = "voice-web")]
+pub mod speech;
+
+#[cfg(feature = "spatial-audio")]
+pub mod audio;
+
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
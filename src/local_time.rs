@@ -0,0 +1,61 @@
+//! Local calendar time, shared by the history, session restore and the
+//! day/night palette.
+//!
+//! The time zone comes from chrono's `Local`: the zone database on Unix
+//! (`$TZ` or `/etc/localtime`, read again when either changes), the system
+//! settings on Windows, UTC where there is neither. The offset is looked up
+//! for each instant asked about, so it follows daylight saving time and a
+//! running browser picks up a change of zone.
+
+use chrono::{Datelike, Local, TimeZone, Timelike};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Minutes past local midnight.
+#[must_use]
+pub fn local_minute_of_day() -> u32 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    Local.timestamp_opt(now, 0).single().map_or_else(
+        || (now.rem_euclid(86_400) / 60) as u32,
+        |local| local.hour() * 60 + local.minute(),
+    )
+}
+
+/// Local calendar date `(year, month, day)` of the Unix time `secs`.
+#[must_use]
+pub fn local_date(secs: u64) -> (i64, u32, u32) {
+    let t = i64::try_from(secs).unwrap_or(i64::MAX);
+    Local.timestamp_opt(t, 0).single().map_or_else(
+        || civil_from_days(t.div_euclid(86_400)),
+        |local| (i64::from(local.year()), local.month(), local.day()),
+    )
+}
+
+/// Proleptic Gregorian date of the day `days` after 1970-01-01.
+#[must_use]
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_dates_from_day_numbers() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(19_723), (2024, 1, 1));
+        assert_eq!(civil_from_days(20_743), (2026, 10, 17));
+    }
+}
@@ -4,6 +4,7 @@ mod app;
 mod oz;
 mod ui;
 
+use alice_browser::telemetry::frame_profiler::FrameStage;
 use app::BrowserApp;
 use oz::resolve_url;
 
@@ -12,6 +13,9 @@ fn main() {
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([1280.0, 800.0]),
+        // The GPU raymarcher draws into textures on eframe's wgpu device
+        #[cfg(feature = "sdf-render")]
+        renderer: eframe::Renderer::Wgpu,
         ..Default::default()
     };
 
@@ -19,34 +23,38 @@ fn main() {
         "ALICE Browser — The Web Recompiled",
         options,
         Box::new(|cc| {
-            // Load Japanese font (Hiragino Sans on macOS)
-            let mut fonts = egui::FontDefinitions::default();
-            let font_paths = [
-                "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
-                "/System/Library/Fonts/HiraginoSans-W3.otf",
-                "/System/Library/Fonts/ヒラギノ角ゴシック W4.ttc",
-            ];
-            for path in &font_paths {
-                if let Ok(data) = std::fs::read(path) {
-                    fonts
-                        .font_data
-                        .insert("japanese".to_owned(), egui::FontData::from_owned(data));
-                    fonts
-                        .families
-                        .get_mut(&egui::FontFamily::Proportional)
-                        .unwrap()
-                        .push("japanese".to_owned());
-                    fonts
-                        .families
-                        .get_mut(&egui::FontFamily::Monospace)
-                        .unwrap()
-                        .push("japanese".to_owned());
-                    break;
+            let settings = app::settings::Settings::load();
+            // System fonts with CJK and emoji fallbacks, or the bundled font
+            ui::fonts::install(&cc.egui_ctx, &settings.font_preferences());
+            // Cmd+Plus/Minus zoom the page, not the whole UI
+            cc.egui_ctx.options_mut(|o| o.zoom_with_keyboard = false);
+
+            let mut app = BrowserApp {
+                settings,
+                persist_cookies: !std::env::args().any(|a| a == "--no-persist-cookies"),
+                force_mobile: std::env::args().any(|a| a == "--mobile"),
+                bookmarks: app::bookmarks::BookmarkStore::load(),
+                visits: app::history::HistoryStore::load(),
+                site_settings: app::site_settings::SiteSettings::load(),
+                autofill: app::autofill::AutofillProfiles::load(),
+                ..Default::default()
+            };
+            #[cfg(feature = "sdf-render")]
+            app.attach_gpu(cc.wgpu_render_state.as_ref());
+            app.apply_settings(&cc.egui_ctx);
+            app.load_cookies();
+            app.load_adblock_allowlist();
+            app.load_classifier_model();
+            if app.settings.onboarding_done {
+                app.offer_saved_session();
+                if app.restore.offer.is_none() {
+                    app.go_home(&cc.egui_ctx);
                 }
+            } else {
+                app.start_onboarding(&cc.egui_ctx);
             }
-            cc.egui_ctx.set_fonts(fonts);
 
-            Ok(Box::new(BrowserApp::default()))
+            Ok(Box::new(app))
         }),
     )
     .expect("Failed to start ALICE Browser");
@@ -54,7 +62,24 @@ fn main() {
 
 impl eframe::App for BrowserApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.check_fetch();
+        let frame_start = std::time::Instant::now();
+        self.check_fetch(ctx);
+        self.poll_media();
+        self.poll_session();
+        self.poll_site_map();
+        self.check_memory_pressure();
+        #[cfg(feature = "voice-web")]
+        self.poll_read_aloud(ctx);
+        self.frame_profiler
+            .add(FrameStage::FetchPoll, frame_start.elapsed());
+        self.handle_zoom_keys(ctx);
+        self.handle_print_key(ctx);
+        self.handle_settings_key(ctx);
+        self.handle_keyboard_nav(ctx);
+        self.handle_gestures(ctx);
+
+        #[cfg(feature = "gamepad")]
+        self.handle_gamepad(ctx);
 
         // OZ: handle pending URL navigation from double-click
         #[cfg(feature = "sdf-render")]
@@ -68,6 +93,17 @@ impl eframe::App for BrowserApp {
         #[cfg(feature = "sdf-render")]
         if let Some(ref rx) = self.oz_preview_rx {
             if let Ok(preview) = rx.try_recv() {
+                // Directional ping from the grabbed particle's position
+                #[cfg(feature = "spatial-audio")]
+                if let Some(ref stream) = self.stream_state {
+                    if let Some(info) = stream.grabbed_info() {
+                        let pos = stream.particle_world_pos(info.index);
+                        let listener =
+                            alice_browser::audio::Listener::rotunda(self.cam_params.azimuth);
+                        self.audio
+                            .play(alice_browser::audio::CueKind::Ping, pos, &listener);
+                    }
+                }
                 self.oz_preview = Some(preview);
                 self.oz_preview_rx = None;
             }
@@ -76,27 +112,57 @@ impl eframe::App for BrowserApp {
         // Poll background prefetch results (runs in any mode)
         #[cfg(feature = "sdf-render")]
         if let Some(ref rx) = self.oz_prefetch_rx {
-            while let Ok(batch) = rx.try_recv() {
-                if let Some(ref mut stream) = self.stream_state {
-                    // OZ mode active: inject directly
-                    stream.append_texts(batch);
-                } else {
-                    // Not in OZ mode yet: buffer for later
-                    self.oz_prefetch_buffer.extend(batch);
+            let mut finished = false;
+            loop {
+                match rx.try_recv() {
+                    Ok(batch) => {
+                        if let Some(ref mut stream) = self.stream_state {
+                            // Prefetched texts join the nearest of the
+                            // page's topics
+                            #[cfg(feature = "search")]
+                            let batch = self
+                                .oz_topics
+                                .as_ref()
+                                .map_or(batch, |topics| topics.labelled(batch));
+                            // OZ mode active: inject directly
+                            stream.append_texts(batch);
+                        } else {
+                            // Not in OZ mode yet: buffer for later
+                            self.oz_prefetch_buffer.extend(batch);
+                        }
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                        finished = true;
+                        break;
+                    }
                 }
             }
+            if finished {
+                self.oz_prefetch_rx = None;
+            }
         }
 
         // Apply dark/light visuals
-        if self.dark_mode {
+        if self.effective_dark_mode() {
             ctx.set_visuals(egui::Visuals::dark());
         } else {
             ctx.set_visuals(egui::Visuals::light());
         }
 
         // Poll image loader and convert completed images to textures
+        let poll_start = std::time::Instant::now();
         self.image_loader.poll();
+        if let Some(ref mut page) = self.page {
+            for request in self.image_loader.take_requests(&page.dom.url) {
+                page.network.record(request);
+            }
+        }
+        self.frame_profiler
+            .add(FrameStage::FetchPoll, poll_start.elapsed());
         {
+            use alice_browser::net::image::{preview_key, LoadState, PREVIEW_PREFIX};
+
             let urls: Vec<String> = self.image_loader.loaded_urls();
             for url in urls {
                 if self.image_textures.contains_key(&url) {
@@ -109,18 +175,64 @@ impl eframe::App for BrowserApp {
                     );
                     let tex =
                         ctx.load_texture(format!("img_{url}"), image, egui::TextureOptions::LINEAR);
+                    self.image_textures.remove(&preview_key(&url));
                     self.image_textures.insert(url, tex);
                 }
             }
+
+            // Blurred previews of images still downloading
+            for url in self.image_loader.preview_urls() {
+                let key = preview_key(&url);
+                if self.image_textures.contains_key(&key) {
+                    continue;
+                }
+                if let Some(data) = self.image_loader.preview(&url) {
+                    let image = egui::ColorImage::from_rgba_unmultiplied(
+                        [data.width as usize, data.height as usize],
+                        &data.rgba,
+                    );
+                    let tex =
+                        ctx.load_texture(format!("img_{key}"), image, egui::TextureOptions::LINEAR);
+                    self.image_textures.insert(key, tex);
+                }
+            }
+            // Drop previews of images that failed to finish
+            let loader = &self.image_loader;
+            self.image_textures.retain(|key, _| {
+                key.strip_prefix(PREVIEW_PREFIX)
+                    .is_none_or(|url| matches!(loader.state(url), LoadState::Loading(_)))
+            });
+            self.sdf_paint_state.image_progress = self.image_loader.progress();
+            if !self.sdf_paint_state.image_progress.is_empty() {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            }
         }
 
-        // Top toolbar
-        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
-            self.draw_toolbar(ui, ctx);
-        });
+        // Top toolbar, or the mobile bars on narrow windows
+        self.draw_chrome(ctx);
 
-        // Stats side panel
-        if self.show_stats {
+        // Bookmarks side panel
+        if self.show_bookmarks {
+            let ctx_clone = ctx.clone();
+            egui::SidePanel::left("bookmarks")
+                .default_width(220.0)
+                .show(ctx, |ui| {
+                    self.draw_bookmarks_panel(ui, &ctx_clone);
+                });
+        }
+
+        // History side panel
+        if self.show_history_panel {
+            let ctx_clone = ctx.clone();
+            egui::SidePanel::left("history")
+                .default_width(240.0)
+                .show(ctx, |ui| {
+                    self.draw_history_panel(ui, &ctx_clone);
+                });
+        }
+
+        // Stats side panel; the mobile layout has its chip and menu instead
+        if self.show_stats && !self.mobile_layout(ctx) {
             egui::SidePanel::right("stats")
                 .default_width(220.0)
                 .show(ctx, |ui| {
@@ -131,7 +243,119 @@ impl eframe::App for BrowserApp {
         // Main content area
         let ctx_clone = ctx.clone();
         egui::CentralPanel::default().show(ctx, |ui| {
+            self.content_rect = Some(ui.max_rect());
             self.draw_content(ui, &ctx_clone);
         });
+
+        // Entries chosen from the page's right-click menu, then the copies
+        // requested from page context menus
+        self.handle_page_menu(ctx);
+        self.handle_copy_requests(ctx);
+        self.handle_table_exports(ctx);
+
+        // "This is content" / "This is an ad" chosen from a block's context menu
+        self.handle_feedback_marks(ctx);
+
+        // "Focus in 3D" chosen from a block's context menu
+        #[cfg(feature = "sdf-render")]
+        self.handle_focus_requests(ctx);
+
+        // Warm up connections to links the pointer rests on, and show
+        // their preview cards
+        let hovered = crate::ui::take_hovered_link(ctx);
+        self.gestures.hovered_link.clone_from(&hovered);
+        self.speculate_hovered_link(ctx, hovered.as_deref());
+        self.update_link_card(ctx, hovered.as_deref());
+
+        // Filter-list diagnostics window
+        if self.show_filter_diagnostics {
+            self.draw_filter_diagnostics(ctx);
+        }
+
+        // Preferences for the current site
+        if self.show_site_settings {
+            self.draw_site_settings(ctx);
+        }
+
+        // Form autofill profiles
+        if self.show_autofill {
+            self.draw_autofill(ctx);
+        }
+
+        // Page-load decision log
+        if self.show_inspector {
+            self.draw_inspector(ctx);
+        }
+
+        // Parsed DOM of the page
+        if self.show_dom_tree {
+            self.draw_dom_inspector(ctx);
+        }
+
+        // Requests of the page
+        if self.show_network {
+            self.draw_network_panel(ctx);
+        }
+
+        // Saved 3-D scenes; scene files dropped on the window open here
+        #[cfg(feature = "sdf-render")]
+        {
+            self.handle_dropped_scenes(ctx);
+            if self.show_gallery {
+                self.draw_scene_gallery(ctx);
+            }
+        }
+
+        // Page memory and background work
+        if self.show_tasks {
+            self.draw_task_manager(ctx);
+        }
+
+        // Batch URL session; list files dropped on the window load here
+        self.handle_dropped_lists(ctx);
+        if self.show_session {
+            self.draw_session(ctx);
+        }
+        if self.show_site_map {
+            self.draw_site_map(ctx);
+        }
+        if self.show_certificate {
+            self.draw_certificate(ctx);
+        }
+        if self.show_resources {
+            self.draw_resources(ctx);
+        }
+        if self.show_save_page {
+            self.draw_save_page(ctx);
+        }
+        if self.show_print {
+            self.draw_print(ctx);
+        }
+        if self.show_settings {
+            self.draw_settings(ctx);
+        }
+
+        // First-run tour
+        self.draw_onboarding(ctx);
+
+        // Last run's session, and saving this one
+        self.draw_restore_offer(ctx);
+        self.autosave_session(ctx);
+
+        // Snapshot the freshly loaded page for the history popup
+        self.poll_thumbnail_capture(ctx);
+
+        // Where the last frames' time went
+        if self.show_frame_profiler {
+            self.draw_frame_profiler(ctx);
+        }
+
+        #[cfg(feature = "gamepad")]
+        if self.show_gamepad_hints {
+            self.draw_gamepad_hints(ctx);
+        }
+
+        self.frame_ms = frame_start.elapsed().as_secs_f32() * 1000.0;
+        self.frame_profiler.end_frame(frame_start.elapsed());
     }
 }
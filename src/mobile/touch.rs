@@ -34,8 +34,11 @@ pub enum Gesture {
     Swipe {
         direction: SwipeDirection,
         velocity: f32,
+        /// Most fingers down at once during the swipe
+        fingers: usize,
     },
-    /// Pinch zoom with scale factor
+    /// Pinch zoom with scale factor, relative to the fingers' spread when
+    /// the second one touched down
     Pinch {
         scale: f32,
         center_x: f32,
@@ -62,6 +65,12 @@ pub enum SwipeDirection {
 pub struct GestureRecognizer {
     /// Current active touches
     touches: Vec<TouchPoint>,
+    /// Where each active touch began
+    origins: Vec<TouchPoint>,
+    /// Most touches down at once since the first one began
+    max_touches: usize,
+    /// Distance between the first two touches when the second began
+    pinch_start: Option<f32>,
     /// First touch point (for gesture start)
     start_point: Option<TouchPoint>,
     /// Last tap time (for double-tap detection)
@@ -90,6 +99,9 @@ impl GestureRecognizer {
     pub fn new(screen_width: f32, screen_height: f32) -> Self {
         Self {
             touches: Vec::with_capacity(4),
+            origins: Vec::with_capacity(4),
+            max_touches: 0,
+            pinch_start: None,
             start_point: None,
             last_tap_time: None,
             last_tap_pos: None,
@@ -118,13 +130,26 @@ impl GestureRecognizer {
             time: Instant::now(),
         };
         self.touches.push(point);
+        self.origins.push(point);
         if self.touches.len() == 1 {
             self.start_point = Some(point);
             self.is_dragging = false;
             self.drag_distance = 0.0;
+            self.max_touches = 0;
+        }
+        self.max_touches = self.max_touches.max(self.touches.len());
+        if self.touches.len() == 2 {
+            let (t0, t1) = (self.touches[0], self.touches[1]);
+            self.pinch_start = Some((t0.x - t1.x).hypot(t0.y - t1.y));
         }
     }
 
+    /// Number of touches down.
+    #[must_use]
+    pub fn touch_count(&self) -> usize {
+        self.touches.len()
+    }
+
     /// Process touch move event. Returns Scroll gesture for drag.
     pub fn touch_move(&mut self, x: f32, y: f32, id: u64) -> Gesture {
         // Find and update the touch point
@@ -144,8 +169,7 @@ impl GestureRecognizer {
                 let t1 = self.touches[1];
                 let current_dist = (t0.x - t1.x).hypot(t0.y - t1.y);
 
-                if let Some(start) = &self.start_point {
-                    let start_dist = (start.x - t1.x).hypot(start.y - t1.y);
+                if let Some(start_dist) = self.pinch_start {
                     if start_dist > 1.0 {
                         let scale = current_dist / start_dist;
                         let cx = (t0.x + t1.x) * 0.5;
@@ -170,16 +194,27 @@ impl GestureRecognizer {
     }
 
     /// Process touch end event. Returns the recognized gesture.
+    /// The first touch to lift ends the gesture; with several fingers
+    /// down, its own path is taken as theirs.
     pub fn touch_end(&mut self, x: f32, y: f32, id: u64) -> Gesture {
         self.touches.retain(|t| t.id != id);
+        if self.touches.len() < 2 {
+            self.pinch_start = None;
+        }
+        let origin = self
+            .origins
+            .iter()
+            .position(|t| t.id == id)
+            .map(|i| self.origins.swap_remove(i));
 
         let Some(start) = self.start_point.take() else {
             return Gesture::None;
         };
+        let from = origin.unwrap_or(start);
 
         let duration = start.time.elapsed();
-        let dx = x - start.x;
-        let dy = y - start.y;
+        let dx = x - from.x;
+        let dy = y - from.y;
         let dist = dx.hypot(dy);
 
         // Long press detection
@@ -212,10 +247,14 @@ impl GestureRecognizer {
             return Gesture::Swipe {
                 direction,
                 velocity,
+                fingers: self.max_touches,
             };
         }
 
-        // Tap detection (short touch, no significant movement)
+        // Tap detection (short touch, no significant movement, one finger)
+        if self.max_touches > 1 {
+            return Gesture::None;
+        }
         if dist < 20.0 && duration.as_millis() < self.long_press_ms as u128 {
             // Check for double-tap
             if let (Some(last_time), Some(last_pos)) = (self.last_tap_time, self.last_tap_pos) {
@@ -280,4 +319,41 @@ mod tests {
             _ => panic!("Expected Swipe gesture, got {gesture:?}"),
         }
     }
+
+    #[test]
+    fn pinch_scales_from_the_second_touch() {
+        let mut gr = GestureRecognizer::new(400.0, 800.0);
+        gr.touch_start(100.0, 400.0, 1);
+        gr.touch_start(200.0, 400.0, 2);
+        assert_eq!(gr.touch_count(), 2);
+        match gr.touch_move(300.0, 400.0, 2) {
+            Gesture::Pinch {
+                scale, center_x, ..
+            } => {
+                assert!((scale - 2.0).abs() < 1e-4);
+                assert!((center_x - 200.0).abs() < 1e-4);
+            }
+            other => panic!("Expected Pinch gesture, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn two_finger_swipe_counts_its_fingers() {
+        let mut gr = GestureRecognizer::new(400.0, 800.0);
+        gr.touch_start(100.0, 400.0, 1);
+        gr.touch_start(100.0, 480.0, 2);
+        gr.touch_move(250.0, 480.0, 2);
+        // The second finger lifts first, along its own path
+        match gr.touch_end(250.0, 480.0, 2) {
+            Gesture::Swipe {
+                direction, fingers, ..
+            } => {
+                assert_eq!(direction, SwipeDirection::Right);
+                assert_eq!(fingers, 2);
+            }
+            other => panic!("Expected Swipe gesture, got {other:?}"),
+        }
+        assert!(matches!(gr.touch_end(250.0, 400.0, 1), Gesture::None));
+        assert_eq!(gr.touch_count(), 0);
+    }
 }
@@ -11,9 +11,33 @@
 //! ├─────────────────────────┤
 //! │ [←] [→] [URL...   ] [⋮] │  ← Bottom bar (thumb-friendly)
 //! └─────────────────────────┘
+//!
+//! Both bars hide while the page is scrolled down and come back when it is
+//! scrolled up; the page's block count then floats as a small chip.
 
 use super::touch::{Gesture, GestureRecognizer, SwipeDirection};
 
+/// Window width below which the mobile layout is used.
+pub const COMPACT_WIDTH: f32 = 600.0;
+
+/// Smallest side of a button meant for a thumb.
+pub const TOUCH_TARGET: f32 = 44.0;
+
+/// Height of the status bar.
+pub const STATUS_BAR_HEIGHT: f32 = 28.0;
+
+/// Height of the bottom bar.
+pub const BOTTOM_BAR_HEIGHT: f32 = 48.0;
+
+/// Distance, in points, the page must scroll one way to hide or show the bars.
+const AUTO_HIDE_DISTANCE: f32 = 48.0;
+
+/// Whether a window `width` points wide gets the mobile layout.
+#[must_use]
+pub fn is_compact(width: f32) -> bool {
+    width < COMPACT_WIDTH
+}
+
 /// Mobile UI state
 #[allow(clippy::struct_excessive_bools)]
 pub struct MobileUI {
@@ -42,6 +66,10 @@ pub struct MobileUI {
     pub is_secure: bool,
     /// Menu open
     pub menu_open: bool,
+    /// Scrolling gathered in one direction toward hiding or showing the bars
+    scroll_run: f32,
+    /// Page whose blocks were last added to the lifetime totals
+    counted_url: String,
 }
 
 /// Block statistics for mobile display
@@ -104,6 +132,8 @@ impl MobileUI {
             can_go_forward: false,
             is_secure: false,
             menu_open: false,
+            scroll_run: 0.0,
+            counted_url: String::new(),
         }
     }
 
@@ -175,8 +205,7 @@ impl MobileUI {
             }
 
             Gesture::Scroll { dy, .. } => {
-                self.scroll_y -= dy;
-                self.scroll_y = self.scroll_y.max(0.0);
+                self.on_scroll(-dy);
                 MobileAction::None
             }
 
@@ -184,19 +213,58 @@ impl MobileUI {
         }
     }
 
-    /// Render the mobile UI using egui.
+    /// The page scrolled by `dy` points, positive down the page. Scrolling
+    /// down far enough hides both bars (fullscreen); scrolling back up, or
+    /// reaching the top, shows them again. Bars stay while the URL is being
+    /// edited or the menu is open.
+    pub fn on_scroll(&mut self, dy: f32) {
+        self.scroll_y = (self.scroll_y + dy).max(0.0);
+        if dy == 0.0 {
+            return;
+        }
+        // A change of direction starts a new run
+        if self.scroll_run * dy < 0.0 {
+            self.scroll_run = 0.0;
+        }
+        self.scroll_run += dy;
+
+        if self.scroll_y <= 0.0 || self.scroll_run <= -AUTO_HIDE_DISTANCE {
+            self.set_fullscreen(false);
+        } else if self.scroll_run >= AUTO_HIDE_DISTANCE && !self.url_editing && !self.menu_open {
+            self.set_fullscreen(true);
+        }
+    }
+
+    /// Hide both bars, or show them.
+    pub const fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.fullscreen = fullscreen;
+        self.bottom_bar_visible = !fullscreen;
+        self.status_bar_visible = !fullscreen;
+    }
+
+    /// Render the mobile UI using egui, returning what the bottom bar asked
+    /// for.
     ///
     /// Layout:
     /// - Status bar at top (if visible)
     /// - Content area (maximized)
     /// - Bottom bar at bottom (if visible)
-    pub fn render(&mut self, ui: &mut egui::Ui) {
+    pub fn render(&mut self, ui: &mut egui::Ui) -> MobileAction {
         let available = ui.available_rect_before_wrap();
+        let mut action = MobileAction::None;
 
         // Status bar height
-        let status_height = if self.status_bar_visible { 28.0 } else { 0.0 };
+        let status_height = if self.status_bar_visible {
+            STATUS_BAR_HEIGHT
+        } else {
+            0.0
+        };
         // Bottom bar height
-        let bottom_height = if self.bottom_bar_visible { 48.0 } else { 0.0 };
+        let bottom_height = if self.bottom_bar_visible {
+            BOTTOM_BAR_HEIGHT
+        } else {
+            0.0
+        };
 
         // Status bar
         if self.status_bar_visible {
@@ -226,13 +294,15 @@ impl MobileUI {
             );
             let builder = egui::UiBuilder::new().max_rect(bottom_rect);
             ui.allocate_new_ui(builder, |ui| {
-                self.render_bottom_bar(ui);
+                action = self.render_bottom_bar(ui);
             });
         }
+
+        action
     }
 
     /// Render status bar: [blocked: N] [lock icon]
-    fn render_status_bar(&self, ui: &mut egui::Ui) {
+    pub fn render_status_bar(&self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             ui.spacing_mut().item_spacing.x = 8.0;
 
@@ -261,7 +331,7 @@ impl MobileUI {
     }
 
     /// Render bottom bar: [back] [forward] [URL bar] [menu]
-    fn render_bottom_bar(&mut self, ui: &mut egui::Ui) -> MobileAction {
+    pub fn render_bottom_bar(&mut self, ui: &mut egui::Ui) -> MobileAction {
         let mut action = MobileAction::None;
 
         ui.horizontal(|ui| {
@@ -276,7 +346,7 @@ impl MobileUI {
             if ui
                 .add(
                     egui::Button::new(egui::RichText::new("<").size(20.0).color(back_color))
-                        .min_size(egui::vec2(40.0, 40.0)),
+                        .min_size(egui::vec2(TOUCH_TARGET, TOUCH_TARGET)),
                 )
                 .clicked()
                 && self.can_go_back
@@ -293,7 +363,7 @@ impl MobileUI {
             if ui
                 .add(
                     egui::Button::new(egui::RichText::new(">").size(20.0).color(fwd_color))
-                        .min_size(egui::vec2(40.0, 40.0)),
+                        .min_size(egui::vec2(TOUCH_TARGET, TOUCH_TARGET)),
                 )
                 .clicked()
                 && self.can_go_forward
@@ -302,13 +372,14 @@ impl MobileUI {
             }
 
             // URL bar (takes remaining space)
-            let url_width = ui.available_width() - 48.0;
+            let url_width = ui.available_width() - TOUCH_TARGET - 4.0;
             let response = ui.add_sized(
-                [url_width, 36.0],
+                [url_width, TOUCH_TARGET - 4.0],
                 egui::TextEdit::singleline(&mut self.current_url)
                     .font(egui::TextStyle::Body)
                     .desired_width(url_width),
             );
+            self.url_editing = response.has_focus();
 
             if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
                 let url = self.current_url.clone();
@@ -320,7 +391,7 @@ impl MobileUI {
             if ui
                 .add(
                     egui::Button::new(egui::RichText::new("...").size(20.0))
-                        .min_size(egui::vec2(40.0, 40.0)),
+                        .min_size(egui::vec2(TOUCH_TARGET, TOUCH_TARGET)),
                 )
                 .clicked()
             {
@@ -386,10 +457,59 @@ impl MobileUI {
         action
     }
 
+    /// Floating chip with the page's block count, shown while the bars are
+    /// hidden; tapping it asks for the block statistics.
+    pub fn render_block_chip(&self, ctx: &egui::Context) -> MobileAction {
+        let blocked = self.block_stats.page_total();
+        if !self.fullscreen || blocked == 0 {
+            return MobileAction::None;
+        }
+        let response = egui::Area::new(egui::Id::new("mobile_block_chip"))
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -12.0))
+            .show(ctx, |ui| {
+                ui.add(
+                    egui::Button::new(
+                        egui::RichText::new(format!("\u{1F6E1} {blocked}"))
+                            .size(14.0)
+                            .color(egui::Color32::from_rgb(76, 175, 80)),
+                    )
+                    .rounding(TOUCH_TARGET / 2.0)
+                    .min_size(egui::vec2(TOUCH_TARGET * 1.5, TOUCH_TARGET)),
+                )
+                .on_hover_text(format!("{blocked} ads and trackers blocked on this page"))
+            })
+            .inner;
+        if response.clicked() {
+            MobileAction::ShowBlockStats
+        } else {
+            MobileAction::None
+        }
+    }
+
     /// Check if a tap position is within the URL bar area
     fn is_in_url_bar(&self, _x: f32, y: f32) -> bool {
-        // Bottom bar is in the last 48 pixels
-        y > self.gestures.screen_height - 48.0
+        // Bottom bar is in the last pixels of the screen
+        y > self.gestures.screen_height - BOTTOM_BAR_HEIGHT
+    }
+
+    /// Show the block counts of the page at `url`. The first time the page
+    /// is seen its counts join the lifetime totals, and it starts at the
+    /// top with the bars shown.
+    pub fn record_page(&mut self, url: &str, page_ads: usize, page_trackers: usize) {
+        let (mut total_ads, mut total_trackers) = (
+            self.block_stats.total_ads_blocked,
+            self.block_stats.total_trackers_blocked,
+        );
+        if self.counted_url != url {
+            self.counted_url = url.to_string();
+            total_ads += page_ads;
+            total_trackers += page_trackers;
+            self.scroll_y = 0.0;
+            self.scroll_run = 0.0;
+            self.set_fullscreen(false);
+        }
+        self.update_block_stats(page_ads, page_trackers, total_ads, total_trackers);
     }
 
     /// Update block stats from engine
@@ -423,8 +543,16 @@ impl MobileUI {
     /// Calculate the content area rect based on UI visibility state
     #[must_use]
     pub fn content_area(&self) -> ContentArea {
-        let status_h = if self.status_bar_visible { 28.0 } else { 0.0 };
-        let bottom_h = if self.bottom_bar_visible { 48.0 } else { 0.0 };
+        let status_h = if self.status_bar_visible {
+            STATUS_BAR_HEIGHT
+        } else {
+            0.0
+        };
+        let bottom_h = if self.bottom_bar_visible {
+            BOTTOM_BAR_HEIGHT
+        } else {
+            0.0
+        };
 
         ContentArea {
             x: 0.0,
@@ -482,6 +610,7 @@ mod tests {
         let gesture = Gesture::Swipe {
             direction: SwipeDirection::Up,
             velocity: 500.0,
+            fingers: 1,
         };
         let action = ui.process_gesture(&gesture);
         assert!(ui.fullscreen);
@@ -512,4 +641,38 @@ mod tests {
             _ => panic!("Expected ZoomReset"),
         }
     }
+
+    #[test]
+    fn test_scroll_hides_and_shows_bars() {
+        let mut ui = MobileUI::new(400.0, 800.0);
+        ui.on_scroll(20.0);
+        assert!(ui.bottom_bar_visible);
+        ui.on_scroll(40.0);
+        assert!(ui.fullscreen);
+        assert!(!ui.bottom_bar_visible && !ui.status_bar_visible);
+
+        // A short way back up isn't enough; a longer one is
+        ui.on_scroll(-20.0);
+        assert!(ui.fullscreen);
+        ui.on_scroll(-30.0);
+        assert!(!ui.fullscreen);
+        assert!(ui.bottom_bar_visible);
+
+        // Editing the URL keeps the bars
+        ui.url_editing = true;
+        ui.on_scroll(200.0);
+        assert!(!ui.fullscreen);
+    }
+
+    #[test]
+    fn test_record_page_counts_each_page_once() {
+        let mut ui = MobileUI::new(400.0, 800.0);
+        ui.record_page("https://a.example/", 4, 2);
+        ui.record_page("https://a.example/", 4, 2);
+        ui.record_page("https://b.example/", 1, 0);
+        assert_eq!(ui.block_stats.page_total(), 1);
+        assert_eq!(ui.block_stats.lifetime_total(), 7);
+        assert!(is_compact(390.0));
+        assert!(!is_compact(1280.0));
+    }
 }
@@ -1,7 +1,9 @@
 /// ALICE Ad Blocker — EasyList-compatible filter engine.
 ///
 /// Blocks ads and trackers at the URL level before requests are made.
-/// Supports a subset of EasyList/AdBlock Plus filter syntax.
+/// Supports a subset of EasyList/AdBlock Plus filter syntax. Sites on the
+/// [`Allowlist`] are loaded with blocking off.
+use std::collections::BTreeSet;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
@@ -75,6 +77,29 @@ pub enum BlockReason {
     Tracker,
 }
 
+/// Which list a filter rule lives in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RuleKind {
+    /// `||example.com^`
+    Domain,
+    /// URL substring
+    Substring,
+    /// `@@||example.com^`
+    Exception,
+}
+
+impl RuleKind {
+    /// Short label used in diagnostics and decision logs.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Domain => "domain",
+            Self::Substring => "substr",
+            Self::Exception => "allow",
+        }
+    }
+}
+
 /// A single filter rule parsed from `EasyList` format.
 #[derive(Debug, Clone)]
 enum FilterRule {
@@ -91,6 +116,8 @@ pub struct AdBlockEngine {
     domain_blocks: Vec<String>,
     substring_blocks: Vec<String>,
     exceptions: Vec<String>,
+    /// Rules switched off from the diagnostics panel
+    disabled: Vec<(RuleKind, String)>,
     pub stats: BlockStats,
 }
 
@@ -108,6 +135,7 @@ impl AdBlockEngine {
             domain_blocks: Vec::new(),
             substring_blocks: Vec::new(),
             exceptions: Vec::new(),
+            disabled: Vec::new(),
             stats: BlockStats::new(),
         };
         engine.load_builtin_rules();
@@ -173,43 +201,102 @@ impl AdBlockEngine {
     pub fn should_block(&self, url: &str) -> Option<BlockReason> {
         self.stats.record_check();
 
+        let reason = self.check(url)?;
+        match reason {
+            BlockReason::Ad => self.stats.record_ad(),
+            BlockReason::Tracker => self.stats.record_tracker(),
+        }
+        Some(reason)
+    }
+
+    /// Same decision as [`Self::should_block`], without touching the stats.
+    #[must_use]
+    pub fn check(&self, url: &str) -> Option<BlockReason> {
+        self.matching_rule(url)
+            .map(|(_, pattern)| classify_block_reason(pattern))
+    }
+
+    /// The block rule that `url` hits, if it is blocked.
+    #[must_use]
+    pub fn matching_rule(&self, url: &str) -> Option<(RuleKind, &str)> {
         let url_lower = url.to_lowercase();
 
         // Check exceptions first
-        for exc in &self.exceptions {
-            if url_lower.contains(exc) {
-                return None;
-            }
+        if self.exceptions.iter().any(|exc| url_lower.contains(exc)) {
+            return None;
         }
 
         // Extract domain from URL
         let domain = extract_domain(&url_lower);
 
-        // Check domain blocks
-        for blocked_domain in &self.domain_blocks {
-            if domain == *blocked_domain || domain.ends_with(&format!(".{blocked_domain}")) {
-                let reason = classify_block_reason(blocked_domain);
-                match reason {
-                    BlockReason::Ad => self.stats.record_ad(),
-                    BlockReason::Tracker => self.stats.record_tracker(),
-                }
-                return Some(reason);
-            }
-        }
+        // Check domain blocks, then substring blocks
+        self.domain_blocks
+            .iter()
+            .find(|d| domain_matches(&domain, d))
+            .map(|d| (RuleKind::Domain, d.as_str()))
+            .or_else(|| {
+                self.substring_blocks
+                    .iter()
+                    .find(|p| url_lower.contains(p.as_str()))
+                    .map(|p| (RuleKind::Substring, p.as_str()))
+            })
+    }
 
-        // Check substring blocks
-        for pattern in &self.substring_blocks {
-            if url_lower.contains(pattern) {
-                let reason = classify_block_reason(pattern);
-                match reason {
-                    BlockReason::Ad => self.stats.record_ad(),
-                    BlockReason::Tracker => self.stats.record_tracker(),
-                }
-                return Some(reason);
-            }
+    // ── Rule inspection (diagnostics) ──
+
+    /// All active rules, in evaluation order.
+    pub fn rules(&self) -> impl Iterator<Item = (RuleKind, &str)> {
+        self.exceptions
+            .iter()
+            .map(|r| (RuleKind::Exception, r.as_str()))
+            .chain(
+                self.domain_blocks
+                    .iter()
+                    .map(|r| (RuleKind::Domain, r.as_str())),
+            )
+            .chain(
+                self.substring_blocks
+                    .iter()
+                    .map(|r| (RuleKind::Substring, r.as_str())),
+            )
+    }
+
+    /// Rules currently switched off.
+    #[must_use]
+    pub fn disabled_rules(&self) -> &[(RuleKind, String)] {
+        &self.disabled
+    }
+
+    /// Switch a rule off (or back on). Returns `false` if no such rule exists.
+    pub fn set_rule_enabled(&mut self, kind: RuleKind, pattern: &str, enabled: bool) -> bool {
+        if enabled {
+            let Some(pos) = self
+                .disabled
+                .iter()
+                .position(|(k, p)| *k == kind && p == pattern)
+            else {
+                return false;
+            };
+            let (_, pattern) = self.disabled.remove(pos);
+            self.list_mut(kind).push(pattern);
+            true
+        } else {
+            let list = self.list_mut(kind);
+            let Some(pos) = list.iter().position(|p| p == pattern) else {
+                return false;
+            };
+            let pattern = list.remove(pos);
+            self.disabled.push((kind, pattern));
+            true
         }
+    }
 
-        None
+    fn list_mut(&mut self, kind: RuleKind) -> &mut Vec<String> {
+        match kind {
+            RuleKind::Domain => &mut self.domain_blocks,
+            RuleKind::Substring => &mut self.substring_blocks,
+            RuleKind::Exception => &mut self.exceptions,
+        }
     }
 
     /// Load built-in ad/tracker domain rules (most common).
@@ -354,8 +441,24 @@ impl AdBlockEngine {
     }
 }
 
+/// Whether a single rule matches an already lower-cased URL and its domain.
+#[must_use]
+pub fn rule_matches(kind: RuleKind, pattern: &str, url_lower: &str, domain: &str) -> bool {
+    match kind {
+        RuleKind::Domain => domain_matches(domain, pattern),
+        RuleKind::Substring | RuleKind::Exception => url_lower.contains(pattern),
+    }
+}
+
+fn domain_matches(domain: &str, blocked: &str) -> bool {
+    domain == blocked
+        || (domain.len() > blocked.len()
+            && domain.ends_with(blocked)
+            && domain.as_bytes()[domain.len() - blocked.len() - 1] == b'.')
+}
+
 /// Extract domain from a URL string.
-fn extract_domain(url: &str) -> String {
+pub fn extract_domain(url: &str) -> String {
     let without_scheme = url
         .strip_prefix("https://")
         .or_else(|| url.strip_prefix("http://"))
@@ -365,6 +468,126 @@ fn extract_domain(url: &str) -> String {
     domain.to_string()
 }
 
+/// File name of the allowlist inside the profile directory.
+pub const ALLOWLIST_FILE: &str = "adblock-allowlist.txt";
+
+/// First line of the persisted allowlist.
+const ALLOWLIST_HEADER: &str = "# ALICE-Browser ad-block allowlist v1";
+
+/// Domains whose pages are loaded without ad blocking.
+///
+/// An entry covers the domain and its subdomains. Pages of an allowed site
+/// are neither refused nor pruned; the engine still classifies them, so the
+/// load log lists what blocking would have removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Allowlist {
+    domains: BTreeSet<String>,
+}
+
+impl Allowlist {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the page at `url` is loaded without blocking.
+    #[must_use]
+    pub fn allows(&self, url: &str) -> bool {
+        if self.domains.is_empty() {
+            return false;
+        }
+        let domain = extract_domain(url).to_lowercase();
+        self.domains.iter().any(|d| domain_matches(&domain, d))
+    }
+
+    /// Add `domain` (a host, or a URL whose site is meant); returns whether
+    /// it was new.
+    pub fn insert(&mut self, domain: &str) -> bool {
+        let domain = site_domain(domain);
+        !domain.is_empty() && self.domains.insert(domain)
+    }
+
+    /// Remove the entry that allows `url`'s site, if any; returns whether
+    /// one was removed.
+    pub fn remove(&mut self, url: &str) -> bool {
+        let domain = extract_domain(url).to_lowercase();
+        let before = self.domains.len();
+        self.domains.retain(|d| !domain_matches(&domain, d));
+        self.domains.len() != before
+    }
+
+    /// Allowed domains, sorted.
+    pub fn domains(&self) -> impl Iterator<Item = &str> {
+        self.domains.iter().map(String::as_str)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.domains.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.domains.is_empty()
+    }
+
+    /// One domain per line; `!` and `#` start comments, as in filter lists.
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let mut list = Self::new();
+        for line in text.lines().map(str::trim) {
+            if !line.is_empty() && !line.starts_with('!') && !line.starts_with('#') {
+                list.insert(line);
+            }
+        }
+        list
+    }
+
+    /// Text form read back by [`parse`](Self::parse).
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut text = String::from(ALLOWLIST_HEADER);
+        text.push('\n');
+        for domain in &self.domains {
+            text.push_str(domain);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Read the allowlist saved at `path`; a missing file yields an empty
+    /// list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file exists but cannot be read.
+    pub fn load(path: &std::path::Path) -> std::io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Write the allowlist to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file cannot be written.
+    pub fn save(&self, path: &std::path::Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_text())
+    }
+}
+
+/// Domain an allowlist entry for `url` (or a bare host) uses: lower-cased,
+/// without port or a leading `www.`, so the entry covers the whole site.
+#[must_use]
+pub fn site_domain(url: &str) -> String {
+    let domain = extract_domain(url.trim()).to_lowercase();
+    let domain = domain.trim_end_matches('.');
+    domain.strip_prefix("www.").unwrap_or(domain).to_string()
+}
+
 /// Classify whether a matched pattern is ad or tracker.
 fn classify_block_reason(pattern: &str) -> BlockReason {
     let tracker_keywords = [
@@ -470,6 +693,19 @@ example.com##.ad-banner
             .is_none());
     }
 
+    #[test]
+    fn test_disable_rule() {
+        let mut engine = AdBlockEngine::new();
+        let url = "https://doubleclick.net/x";
+        assert!(engine.check(url).is_some());
+        assert!(engine.set_rule_enabled(RuleKind::Domain, "doubleclick.net", false));
+        assert!(engine.check(url).is_none());
+        assert_eq!(engine.disabled_rules().len(), 1);
+        assert!(engine.set_rule_enabled(RuleKind::Domain, "doubleclick.net", true));
+        assert!(engine.check(url).is_some());
+        assert!(!engine.set_rule_enabled(RuleKind::Domain, "no-such-rule", false));
+    }
+
     #[test]
     fn test_extract_domain() {
         assert_eq!(
@@ -478,4 +714,27 @@ example.com##.ad-banner
         );
         assert_eq!(extract_domain("http://test.org:8080/x"), "test.org");
     }
+
+    #[test]
+    fn allowlist_covers_site_and_round_trips() {
+        let mut list = Allowlist::new();
+        assert!(!list.allows("https://news.example.com/"));
+        assert!(list.insert("https://www.Example.com:8443/article"));
+        assert!(!list.insert("example.com"));
+        assert_eq!(list.domains().collect::<Vec<_>>(), ["example.com"]);
+
+        assert!(list.allows("https://example.com/"));
+        assert!(list.allows("http://news.example.com/story"));
+        assert!(!list.allows("https://notexample.com/"));
+        assert!(!list.allows("https://example.com.evil.test/"));
+
+        let text = list.to_text();
+        assert!(text.starts_with(ALLOWLIST_HEADER));
+        assert_eq!(Allowlist::parse(&text), list);
+        assert_eq!(Allowlist::parse("! comment\n\n  shop.test  \n").len(), 1);
+
+        assert!(list.remove("https://news.example.com/"));
+        assert!(list.is_empty());
+        assert!(!list.remove("https://example.com/"));
+    }
 }
@@ -0,0 +1,221 @@
+/// Filter-list diagnostics: match rates and per-rule timing.
+///
+/// Runs every active `AdBlockEngine` rule against a corpus of request URLs —
+/// recorded samples, plus resource URLs pulled out of DOM snapshots such as
+/// the current page — so expensive or never-matching custom rules can be
+/// found and switched off.
+use std::time::{Duration, Instant};
+
+use crate::dom::parser::parse_html;
+use crate::dom::DomNode;
+
+use super::adblock::{extract_domain, rule_matches, AdBlockEngine, BlockReason, RuleKind};
+
+/// Recorded request URLs bundled as a baseline corpus.
+pub const SAMPLE_REQUESTS: &[&str] = &[
+    "https://www.example.com/",
+    "https://www.example.com/static/app.js",
+    "https://www.example.com/images/hero.jpg",
+    "https://pagead2.googlesyndication.com/pagead/js/adsbygoogle.js",
+    "https://securepubads.g.doubleclick.net/tag/js/gpt.js",
+    "https://www.google-analytics.com/collect?v=1&t=pageview",
+    "https://www.googletagmanager.com/gtm.js?id=GTM-XXXX",
+    "https://connect.facebook.net/en_US/fbevents.js",
+    "https://static.hotjar.com/c/hotjar-123.js",
+    "https://cdn.segment.com/analytics.js/v1/abc/analytics.min.js",
+    "https://news.example.org/ads/banner-728x90.png",
+    "https://news.example.org/article/2024/05/story.html",
+    "https://cdn.jsdelivr.net/npm/lodash@4/lodash.min.js",
+    "https://fonts.googleapis.com/css2?family=Inter",
+    "https://images.example.net/thumb/photo.webp",
+    "https://sb.scorecardresearch.com/beacon?c1=2",
+    "https://c.amazon-adsystem.com/aax2/apstag.js",
+    "https://example.com/wp-content/plugins/tracking.js",
+    "https://blog.example.com/feed.xml",
+    "https://api.example.com/v1/items?page=2",
+];
+
+/// Bundled DOM snapshot with a typical mix of content and ad resources.
+pub const SAMPLE_DOM: &str = r#"<html><head>
+<script src="https://www.googletagmanager.com/gtag/js?id=G-1"></script>
+<script src="/static/main.js"></script>
+<link rel="stylesheet" href="/static/site.css">
+</head><body>
+<a href="/news/today">Today</a>
+<img src="/img/logo.png">
+<iframe src="https://ad.doubleclick.net/ddm/adi/N123"></iframe>
+<img src="https://example.com/pixel.gif?u=1">
+<a href="https://partner.example.net/offer">Partner</a>
+<script src="https://cdn.taboola.com/libtrc/loader.js"></script>
+</body></html>"#;
+
+/// Base URL the bundled snapshot is resolved against.
+const SAMPLE_DOM_URL: &str = "https://www.example.com/";
+
+/// Match count and time spent for one rule.
+#[derive(Debug, Clone)]
+pub struct RuleTiming {
+    pub kind: RuleKind,
+    pub pattern: String,
+    /// Corpus URLs this rule matched
+    pub matches: usize,
+    /// Total evaluation time over all rounds
+    pub elapsed: Duration,
+}
+
+/// Result of a benchmark run.
+#[derive(Debug, Clone, Default)]
+pub struct BenchReport {
+    /// Corpus size
+    pub urls: usize,
+    pub blocked_ads: usize,
+    pub blocked_trackers: usize,
+    /// Rules sorted slowest first
+    pub rules: Vec<RuleTiming>,
+    /// Wall time of the whole run
+    pub elapsed: Duration,
+}
+
+impl BenchReport {
+    /// Fraction of corpus URLs the engine would block.
+    #[must_use]
+    pub fn match_rate(&self) -> f32 {
+        if self.urls == 0 {
+            0.0
+        } else {
+            (self.blocked_ads + self.blocked_trackers) as f32 / self.urls as f32
+        }
+    }
+
+    /// Rules that never matched anything in the corpus.
+    pub fn unused_rules(&self) -> impl Iterator<Item = &RuleTiming> {
+        self.rules.iter().filter(|r| r.matches == 0)
+    }
+}
+
+/// Resource URLs referenced by a DOM (`src`, `href`), resolved against `base`.
+#[must_use]
+pub fn urls_from_dom(root: &DomNode, base: &str) -> Vec<String> {
+    let base = url::Url::parse(base).ok();
+    let mut out = Vec::new();
+    collect_urls(root, base.as_ref(), &mut out);
+    out
+}
+
+fn collect_urls(node: &DomNode, base: Option<&url::Url>, out: &mut Vec<String>) {
+    for attr in ["src", "href"] {
+        let Some(raw) = node.attr(attr) else {
+            continue;
+        };
+        let resolved = match base {
+            Some(b) => b.join(raw).map(String::from).ok(),
+            None => url::Url::parse(raw).map(String::from).ok(),
+        };
+        if let Some(u) = resolved.filter(|u| u.starts_with("http")) {
+            out.push(u);
+        }
+    }
+    for child in &node.children {
+        collect_urls(child, base, out);
+    }
+}
+
+/// Bundled corpus: sample requests plus the sample DOM snapshot.
+#[must_use]
+pub fn bundled_corpus() -> Vec<String> {
+    let mut urls: Vec<String> = SAMPLE_REQUESTS.iter().map(|s| (*s).to_string()).collect();
+    let dom = parse_html(SAMPLE_DOM, SAMPLE_DOM_URL);
+    urls.extend(urls_from_dom(&dom.root, SAMPLE_DOM_URL));
+    urls
+}
+
+/// Time every active rule of `engine` against `urls`, `rounds` times over.
+///
+/// Uses [`AdBlockEngine::check`], so the engine's block stats are untouched.
+#[must_use]
+pub fn run(engine: &AdBlockEngine, urls: &[String], rounds: usize) -> BenchReport {
+    let start = Instant::now();
+    let rounds = rounds.max(1);
+
+    // Lower-case and split once, as `check` does per call
+    let prepared: Vec<(String, String)> = urls
+        .iter()
+        .map(|u| {
+            let lower = u.to_lowercase();
+            let domain = extract_domain(&lower);
+            (lower, domain)
+        })
+        .collect();
+
+    let mut rules: Vec<RuleTiming> = engine
+        .rules()
+        .map(|(kind, pattern)| {
+            let t0 = Instant::now();
+            let mut matches = 0;
+            for _ in 0..rounds {
+                matches = prepared
+                    .iter()
+                    .filter(|(lower, domain)| rule_matches(kind, pattern, lower, domain))
+                    .count();
+            }
+            RuleTiming {
+                kind,
+                pattern: pattern.to_string(),
+                matches,
+                elapsed: t0.elapsed(),
+            }
+        })
+        .collect();
+    rules.sort_by_key(|r| std::cmp::Reverse(r.elapsed));
+
+    let mut report = BenchReport {
+        urls: urls.len(),
+        rules,
+        ..BenchReport::default()
+    };
+    for u in urls {
+        match engine.check(u) {
+            Some(BlockReason::Ad) => report.blocked_ads += 1,
+            Some(BlockReason::Tracker) => report.blocked_trackers += 1,
+            None => {}
+        }
+    }
+    report.elapsed = start.elapsed();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_corpus_includes_dom_urls() {
+        let corpus = bundled_corpus();
+        assert!(corpus.len() > SAMPLE_REQUESTS.len());
+        assert!(corpus
+            .iter()
+            .any(|u| u == "https://www.example.com/static/main.js"));
+    }
+
+    #[test]
+    fn report_counts_matches() {
+        let engine = AdBlockEngine::new();
+        let report = run(&engine, &bundled_corpus(), 1);
+        assert_eq!(report.rules.len(), engine.rule_count());
+        assert!(report.match_rate() > 0.0 && report.match_rate() < 1.0);
+        let dc = report
+            .rules
+            .iter()
+            .find(|r| r.pattern == "doubleclick.net")
+            .unwrap();
+        assert!(dc.matches >= 2);
+        assert!(report.unused_rules().count() > 0);
+    }
+
+    #[test]
+    fn run_leaves_stats_untouched() {
+        let engine = AdBlockEngine::new();
+        let _ = run(&engine, &bundled_corpus(), 2);
+        assert_eq!(engine.stats.total_blocked(), 0);
+    }
+}
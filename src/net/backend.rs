@@ -0,0 +1,237 @@
+//! Where the engine's documents come from.
+//!
+//! [`BrowserEngine`](crate::engine::pipeline::BrowserEngine) fetches pages,
+//! frames and sitemaps through a [`FetchBackend`]. The native build uses
+//! [`NativeBackend`], which goes to the network through
+//! [`fetch_url_with`](super::fetch::fetch_url_with). A [`StaticBackend`]
+//! serves documents handed to it in advance instead; that is how the
+//! wasm32 build is fed (it has no blocking network of its own), and it lets
+//! tests run the whole pipeline offline.
+
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use url::Url;
+
+use super::fetch::{FetchError, FetchOptions, FetchResult};
+
+/// Source of the documents a `BrowserEngine` loads.
+pub trait FetchBackend: Send + Sync {
+    /// `GET` a document.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FetchError` if the document cannot be had.
+    fn get(&self, url: &str, options: &FetchOptions) -> Result<FetchResult, FetchError>;
+
+    /// `POST` an `application/x-www-form-urlencoded` body and return the
+    /// resulting page. Backends that cannot submit forms refuse.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FetchError` if the submission fails or is not supported.
+    fn post_form(
+        &self,
+        url: &str,
+        body: String,
+        options: &FetchOptions,
+    ) -> Result<FetchResult, FetchError> {
+        let _ = (body, options);
+        Err(FetchError {
+            message: format!("Form submission is not supported here: {url}"),
+            invalid_certificate: false,
+        })
+    }
+}
+
+/// The network, through `reqwest` (not on wasm32).
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NativeBackend;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FetchBackend for NativeBackend {
+    fn get(&self, url: &str, options: &FetchOptions) -> Result<FetchResult, FetchError> {
+        super::fetch::fetch_url_with(url, options)
+    }
+
+    fn post_form(
+        &self,
+        url: &str,
+        body: String,
+        options: &FetchOptions,
+    ) -> Result<FetchResult, FetchError> {
+        super::fetch::post_form(url, body, options)
+    }
+}
+
+/// A document held by a [`StaticBackend`].
+#[derive(Debug, Clone)]
+struct StaticDocument {
+    body: String,
+    content_type: String,
+    status: u16,
+}
+
+/// Documents provided ahead of time, by address; anything else fails to
+/// load. Addresses are compared parsed and without their fragment.
+#[derive(Debug, Default)]
+pub struct StaticBackend {
+    documents: RwLock<HashMap<String, StaticDocument>>,
+}
+
+impl StaticBackend {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve `body` for `url` with status 200, replacing what was there.
+    pub fn insert(&self, url: &str, body: impl Into<String>, content_type: &str) {
+        self.insert_with_status(url, body, content_type, 200);
+    }
+
+    /// Serve `body` for `url` with `status`.
+    pub fn insert_with_status(
+        &self,
+        url: &str,
+        body: impl Into<String>,
+        content_type: &str,
+        status: u16,
+    ) {
+        let document = StaticDocument {
+            body: body.into(),
+            content_type: content_type.to_string(),
+            status,
+        };
+        self.documents
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(document_key(url), document);
+    }
+
+    /// Number of documents provided.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.documents
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forget every document.
+    pub fn clear(&self) {
+        self.documents
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+impl FetchBackend for StaticBackend {
+    fn get(&self, url: &str, options: &FetchOptions) -> Result<FetchResult, FetchError> {
+        let key = document_key(url);
+        let documents = self
+            .documents
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        let document = documents.get(&key).ok_or_else(|| FetchError {
+            message: format!("Not provided: {url}"),
+            invalid_certificate: false,
+        })?;
+        if document.body.len() > options.max_body_bytes {
+            return Err(FetchError {
+                message: format!(
+                    "Response too large ({} bytes, limit {})",
+                    document.body.len(),
+                    options.max_body_bytes
+                ),
+                invalid_certificate: false,
+            });
+        }
+        Ok(FetchResult {
+            html: document.body.clone(),
+            url: key,
+            status: document.status,
+            content_type: document.content_type.clone(),
+            certificate: None,
+        })
+    }
+}
+
+/// Lookup key of `url`: parsed (so `https://a.example` and
+/// `https://a.example/` agree) and without its fragment.
+fn document_key(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(mut parsed) => {
+            parsed.set_fragment(None);
+            parsed.into()
+        }
+        Err(_) => url.to_string(),
+    }
+}
+
+/// The backend engines use unless given another: the network natively,
+/// an empty [`StaticBackend`] on wasm32.
+#[must_use]
+pub fn default_backend() -> Arc<dyn FetchBackend> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Arc::new(NativeBackend)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Arc::new(StaticBackend::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn static_backend_serves_provided_documents() {
+        let backend = StaticBackend::new();
+        backend.insert("https://a.example", "<p>hi</p>", "text/html");
+        let options = FetchOptions::default();
+
+        let Ok(result) = backend.get("https://a.example/#top", &options) else {
+            panic!("provided document not served");
+        };
+        assert_eq!(result.html, "<p>hi</p>");
+        assert_eq!(result.url, "https://a.example/");
+        assert_eq!(result.status, 200);
+        assert_eq!(result.content_type, "text/html");
+
+        let Err(missing) = backend.get("https://b.example/", &options) else {
+            panic!("unknown document served");
+        };
+        assert!(missing.message.contains("Not provided"));
+        assert!(backend
+            .post_form("https://a.example/", String::new(), &options)
+            .is_err());
+    }
+
+    #[test]
+    fn static_backend_enforces_body_limit() {
+        let backend = StaticBackend::new();
+        backend.insert_with_status("https://a.example/big", "x".repeat(64), "text/plain", 404);
+        let options = FetchOptions {
+            max_body_bytes: 16,
+            ..FetchOptions::default()
+        };
+        assert!(backend.get("https://a.example/big", &options).is_err());
+        let Ok(result) = backend.get("https://a.example/big", &FetchOptions::default()) else {
+            panic!("document within the limit not served");
+        };
+        assert_eq!(result.status, 404);
+        assert_eq!(backend.len(), 1);
+        backend.clear();
+        assert!(backend.is_empty());
+    }
+}
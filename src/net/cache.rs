@@ -5,7 +5,7 @@
 
 use alice_cache::AliceCache;
 
-use super::fetch::{fetch_url, FetchError, FetchResult};
+use super::fetch::{fetch_url_with, FetchError, FetchOptions, FetchResult};
 
 /// Page cache with predictive prefetching.
 ///
@@ -25,18 +25,46 @@ impl CachedFetcher {
 
     /// Fetch a URL, returning cached result on hit or fetching from network on miss.
     pub fn fetch(&self, url: &str) -> Result<FetchResult, FetchError> {
+        self.fetch_with(url, &FetchOptions::default())
+    }
+
+    /// [`fetch`](Self::fetch), using `options` for the network request on a miss.
+    pub fn fetch_with(&self, url: &str, options: &FetchOptions) -> Result<FetchResult, FetchError> {
+        self.fetch_with_outcome(url, options)
+            .map(|(result, _)| result)
+    }
+
+    /// [`fetch_with`](Self::fetch_with), also reporting whether the page
+    /// came from the cache (`true`) or the network.
+    pub fn fetch_with_outcome(
+        &self,
+        url: &str,
+        options: &FetchOptions,
+    ) -> Result<(FetchResult, bool), FetchError> {
         let key = url.to_string();
 
         // Cache hit
         if let Some(cached) = self.cache.get(&key) {
             log::debug!("Cache HIT: {}", url);
-            return Ok(cached);
+            return Ok((cached, true));
         }
 
         // Cache miss — fetch from network
         log::debug!("Cache MISS: {}", url);
-        let result = fetch_url(url)?;
+        let result = fetch_url_with(url, options)?;
         self.cache.put(key, result.clone());
+        Ok((result, false))
+    }
+
+    /// Fetch a URL from the network whatever the cache holds, replacing
+    /// the cached copy (reloads).
+    pub fn refetch_with(
+        &self,
+        url: &str,
+        options: &FetchOptions,
+    ) -> Result<FetchResult, FetchError> {
+        let result = fetch_url_with(url, options)?;
+        self.cache.put(url.to_string(), result.clone());
         Ok(result)
     }
 
@@ -0,0 +1,579 @@
+//! Cookie jar shared by page fetches and image loading.
+//!
+//! Implements the parts of RFC 6265 that matter for reading sites:
+//!
+//! - `Expires` / `Max-Age` (with `Max-Age` taking priority)
+//! - `Domain` / `Path` matching, host-only cookies
+//! - `Secure` (only set and sent over https)
+//! - `SameSite` basics: `Strict`/`Lax` cookies are withheld from
+//!   cross-site image requests, `SameSite=None` requires `Secure`; other
+//!   fetches do not name their page, so the rule is not applied to them
+//!
+//! Persistent cookies can be saved to and loaded from a tab-separated file
+//! in the profile directory, readable by its owner only; session cookies
+//! never touch the disk.
+
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::header::HeaderValue;
+use url::Url;
+
+/// File name of the persisted jar inside the profile directory.
+pub const COOKIE_FILE: &str = "cookies.tsv";
+
+/// First line of the persisted jar.
+const FILE_HEADER: &str = "# ALICE-Browser cookies v1";
+
+/// `SameSite` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    /// Default when the attribute is missing
+    Lax,
+    None,
+}
+
+impl SameSite {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Strict => "Strict",
+            Self::Lax => "Lax",
+            Self::None => "None",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "strict" => Some(Self::Strict),
+            "lax" => Some(Self::Lax),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+}
+
+/// A stored cookie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    /// Lower-case domain without a leading dot
+    pub domain: String,
+    /// Set without a `Domain` attribute: only sent to the exact host
+    pub host_only: bool,
+    pub path: String,
+    /// Expiry as Unix seconds; `None` for session cookies
+    pub expires: Option<u64>,
+    pub secure: bool,
+    pub same_site: SameSite,
+}
+
+impl Cookie {
+    /// Parse a `Set-Cookie` header value received from `url`.
+    ///
+    /// Returns `None` for malformed cookies and ones the origin may not set.
+    #[must_use]
+    pub fn parse(header: &str, url: &Url, now: u64) -> Option<Self> {
+        let host = url.host_str()?.to_ascii_lowercase();
+        let https = url.scheme() == "https";
+
+        let mut parts = header.split(';');
+        let (name, value) = parts.next()?.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut cookie = Self {
+            name: name.to_string(),
+            value: value.trim().trim_matches('"').to_string(),
+            domain: host.clone(),
+            host_only: true,
+            path: default_path(url),
+            expires: None,
+            secure: false,
+            same_site: SameSite::Lax,
+        };
+        let mut max_age: Option<i64> = None;
+        let mut expires: Option<u64> = None;
+
+        for attr in parts {
+            let (key, val) = attr.split_once('=').unwrap_or((attr, ""));
+            let val = val.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" if !val.is_empty() => {
+                    let domain = val.trim_start_matches('.').to_ascii_lowercase();
+                    // No public-suffix list: refuse bare TLDs unless it is the host itself
+                    if !domain_match(&host, &domain) || (!domain.contains('.') && domain != host) {
+                        return None;
+                    }
+                    cookie.domain = domain;
+                    cookie.host_only = false;
+                }
+                "path" if val.starts_with('/') => cookie.path = val.to_string(),
+                "max-age" => max_age = val.parse().ok().or(max_age),
+                "expires" => expires = parse_http_date(val).or(expires),
+                "secure" => cookie.secure = true,
+                "samesite" => {
+                    if let Some(s) = SameSite::parse(val) {
+                        cookie.same_site = s;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        cookie.expires = match max_age {
+            Some(secs) if secs <= 0 => Some(0),
+            Some(secs) => Some(now.saturating_add(secs as u64)),
+            None => expires,
+        };
+
+        if cookie.secure && !https {
+            return None;
+        }
+        if cookie.same_site == SameSite::None && !cookie.secure {
+            return None;
+        }
+        Some(cookie)
+    }
+
+    #[must_use]
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|t| t <= now)
+    }
+
+    /// Whether this cookie should accompany a request to `url`.
+    ///
+    /// `cross_site` marks subresource requests whose top-level page is on a
+    /// different site.
+    #[must_use]
+    pub fn matches(&self, url: &Url, cross_site: bool, now: u64) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.to_ascii_lowercase();
+        let host_ok = if self.host_only {
+            host == self.domain
+        } else {
+            domain_match(&host, &self.domain)
+        };
+        host_ok
+            && path_match(url.path(), &self.path)
+            && (!self.secure || url.scheme() == "https")
+            && !(cross_site && self.same_site != SameSite::None)
+            && !self.is_expired(now)
+    }
+
+    fn same_key(&self, other: &Self) -> bool {
+        self.name == other.name && self.domain == other.domain && self.path == other.path
+    }
+}
+
+/// Thread-safe cookie store.
+#[derive(Debug, Default)]
+pub struct CookieJar {
+    cookies: RwLock<Vec<Cookie>>,
+}
+
+impl CookieJar {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a `Set-Cookie` header received from `url`.
+    pub fn store(&self, header: &str, url: &Url) {
+        let now = unix_now();
+        let Some(cookie) = Cookie::parse(header, url, now) else {
+            return;
+        };
+        let mut cookies = self.cookies.write().unwrap_or_else(|e| e.into_inner());
+        cookies.retain(|c| !c.same_key(&cookie));
+        // An already-expired cookie is how servers delete one
+        if !cookie.is_expired(now) {
+            cookies.push(cookie);
+        }
+    }
+
+    /// `Cookie` header value for a request to `url`, if any cookie applies.
+    ///
+    /// `top_level` is the page that triggered a subresource request; pass
+    /// `None` for top-level navigations.
+    #[must_use]
+    pub fn header_for(&self, url: &Url, top_level: Option<&Url>) -> Option<String> {
+        let now = unix_now();
+        let cross_site = top_level.is_some_and(|page| !same_site(page, url));
+        let cookies = self.cookies.read().unwrap_or_else(|e| e.into_inner());
+
+        let mut matching: Vec<&Cookie> = cookies
+            .iter()
+            .filter(|c| c.matches(url, cross_site, now))
+            .collect();
+        if matching.is_empty() {
+            return None;
+        }
+        // Longer paths first (RFC 6265 §5.4)
+        matching.sort_by_key(|c| std::cmp::Reverse(c.path.len()));
+        Some(
+            matching
+                .iter()
+                .map(|c| format!("{}={}", c.name, c.value))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    }
+
+    /// Number of stored (possibly expired) cookies.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cookies.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.cookies
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// Serialize persistent, unexpired cookies (one tab-separated line each).
+    #[must_use]
+    pub fn to_persisted(&self) -> String {
+        let now = unix_now();
+        let cookies = self.cookies.read().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::from(FILE_HEADER);
+        out.push('\n');
+        for c in cookies.iter() {
+            let Some(expires) = c.expires.filter(|&t| t > now) else {
+                continue;
+            };
+            out.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                c.domain,
+                u8::from(c.host_only),
+                c.path,
+                u8::from(c.secure),
+                c.same_site.as_str(),
+                expires,
+                c.name,
+                c.value,
+            ));
+        }
+        out
+    }
+
+    /// Rebuild a jar from [`Self::to_persisted`] output, skipping bad lines.
+    #[must_use]
+    pub fn from_persisted(text: &str) -> Self {
+        let now = unix_now();
+        let cookies = text
+            .lines()
+            .filter(|l| !l.starts_with('#') && !l.trim().is_empty())
+            .filter_map(|line| {
+                let f: Vec<&str> = line.splitn(8, '\t').collect();
+                if f.len() != 8 {
+                    return None;
+                }
+                let cookie = Cookie {
+                    domain: f[0].to_string(),
+                    host_only: f[1] == "1",
+                    path: f[2].to_string(),
+                    secure: f[3] == "1",
+                    same_site: SameSite::parse(f[4])?,
+                    expires: Some(f[5].parse().ok()?),
+                    name: f[6].to_string(),
+                    value: f[7].to_string(),
+                };
+                (!cookie.is_expired(now)).then_some(cookie)
+            })
+            .collect();
+        Self {
+            cookies: RwLock::new(cookies),
+        }
+    }
+
+    /// Load the jar from `path`; a missing file yields an empty jar.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file exists but cannot be read.
+    pub fn load(path: &std::path::Path) -> std::io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Self::from_persisted(&text)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Write persistent cookies to `path`, readable by the owner only.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file cannot be written.
+    pub fn save(&self, path: &std::path::Path) -> std::io::Result<()> {
+        crate::profile::write_private(path, self.to_persisted())
+    }
+
+    /// Replace the contents of this jar with those of `other`.
+    pub fn replace_with(&self, other: Self) {
+        let loaded = other
+            .cookies
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner());
+        *self.cookies.write().unwrap_or_else(|e| e.into_inner()) = loaded;
+    }
+}
+
+/// reqwest does not say which page a request is made for, so fetches
+/// through the store are treated as same-site and `SameSite` is not
+/// enforced on them (pages, frames, previews). The image loader, which
+/// knows the page, sends its own header from [`CookieJar::header_for`].
+#[cfg(not(target_arch = "wasm32"))]
+impl reqwest::cookie::CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        for header in cookie_headers {
+            if let Ok(s) = header.to_str() {
+                self.store(s, url);
+            }
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        self.header_for(url, None)
+            .and_then(|s| HeaderValue::from_str(&s).ok())
+    }
+}
+
+/// Process-wide jar used by `fetch_url` and the image loader.
+#[must_use]
+pub fn shared_jar() -> Arc<CookieJar> {
+    static JAR: OnceLock<Arc<CookieJar>> = OnceLock::new();
+    Arc::clone(JAR.get_or_init(|| Arc::new(CookieJar::new())))
+}
+
+// ── Matching helpers ──
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// `host` equals `domain` or is a subdomain of it.
+fn domain_match(host: &str, domain: &str) -> bool {
+    host == domain
+        || (host.len() > domain.len()
+            && host.ends_with(domain)
+            && host.as_bytes()[host.len() - domain.len() - 1] == b'.')
+}
+
+fn path_match(request_path: &str, cookie_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/')
+                || request_path.as_bytes().get(cookie_path.len()) == Some(&b'/')))
+}
+
+/// Directory of the request path (RFC 6265 §5.1.4).
+fn default_path(url: &Url) -> String {
+    let path = url.path();
+    match path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(i) => path[..i].to_string(),
+    }
+}
+
+/// Approximate "site": the last two host labels.
+fn site_of(url: &Url) -> String {
+    let host = url.host_str().unwrap_or("").to_ascii_lowercase();
+    let labels: Vec<&str> = host.rsplitn(3, '.').collect();
+    if labels.len() >= 2 {
+        format!("{}.{}", labels[1], labels[0])
+    } else {
+        host
+    }
+}
+
+fn same_site(a: &Url, b: &Url) -> bool {
+    site_of(a) == site_of(b)
+}
+
+// ── HTTP dates ──
+
+/// Parse a cookie date (`Wed, 21 Oct 2015 07:28:00 GMT` and common variants)
+/// into Unix seconds.
+fn parse_http_date(s: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let (mut day, mut month, mut year, mut time) = (None, None, None, None);
+
+    for token in s.split([' ', ',', '-']).filter(|t| !t.is_empty()) {
+        if time.is_none() && token.contains(':') {
+            let hms: Vec<u64> = token.split(':').filter_map(|p| p.parse().ok()).collect();
+            if hms.len() == 3 {
+                time = Some(hms[0] * 3600 + hms[1] * 60 + hms[2]);
+                continue;
+            }
+        }
+        if month.is_none() && token.len() >= 3 {
+            let prefix = token[..3].to_ascii_lowercase();
+            if let Some(m) = MONTHS.iter().position(|&m| m == prefix) {
+                month = Some(m as u32 + 1);
+                continue;
+            }
+        }
+        if let Ok(n) = token.parse::<u32>() {
+            if day.is_none() && token.len() <= 2 {
+                day = Some(n);
+            } else if year.is_none() {
+                year = Some(match n {
+                    0..=69 => n + 2000,
+                    70..=99 => n + 1900,
+                    _ => n,
+                });
+            }
+        }
+    }
+
+    let (day, month, year) = (day?, month?, year?);
+    if !(1..=31).contains(&day) || year < 1970 {
+        return None;
+    }
+    let days = days_from_civil(i64::from(year), month, day);
+    Some(days as u64 * 86_400 + time.unwrap_or(0))
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = i64::from(month);
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn parses_rfc1123_date() {
+        assert_eq!(
+            parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(1_445_412_480)
+        );
+        assert_eq!(
+            parse_http_date("Wednesday, 21-Oct-15 07:28:00 GMT"),
+            Some(1_445_412_480)
+        );
+        assert_eq!(parse_http_date("garbage"), None);
+    }
+
+    #[test]
+    fn host_only_and_domain_cookies() {
+        let jar = CookieJar::new();
+        let origin = url("https://www.example.com/");
+        jar.store("a=1", &origin);
+        jar.store("b=2; Domain=example.com", &origin);
+
+        assert_eq!(jar.header_for(&origin, None).as_deref(), Some("a=1; b=2"));
+        let sub = url("https://api.example.com/");
+        assert_eq!(jar.header_for(&sub, None).as_deref(), Some("b=2"));
+        assert!(jar.header_for(&url("https://other.org/"), None).is_none());
+    }
+
+    #[test]
+    fn rejects_foreign_domain() {
+        let jar = CookieJar::new();
+        jar.store("x=1; Domain=evil.com", &url("https://example.com/"));
+        jar.store("y=1; Domain=com", &url("https://example.com/"));
+        assert!(jar.is_empty());
+    }
+
+    #[test]
+    fn path_scoping() {
+        let jar = CookieJar::new();
+        let origin = url("https://example.com/docs/page");
+        jar.store("p=1", &origin); // default path /docs
+        jar.store("q=1; Path=/", &origin);
+        assert_eq!(
+            jar.header_for(&url("https://example.com/docs/other"), None)
+                .as_deref(),
+            Some("p=1; q=1")
+        );
+        assert_eq!(
+            jar.header_for(&url("https://example.com/docsx"), None)
+                .as_deref(),
+            Some("q=1")
+        );
+    }
+
+    #[test]
+    fn secure_only_over_https() {
+        let jar = CookieJar::new();
+        jar.store("s=1; Secure", &url("http://example.com/"));
+        assert!(jar.is_empty());
+        jar.store("s=1; Secure", &url("https://example.com/"));
+        assert!(jar.header_for(&url("http://example.com/"), None).is_none());
+        assert!(jar.header_for(&url("https://example.com/"), None).is_some());
+    }
+
+    #[test]
+    fn same_site_withheld_cross_site() {
+        let jar = CookieJar::new();
+        let origin = url("https://cdn.example.com/");
+        jar.store("lax=1", &origin);
+        jar.store("none=1; SameSite=None; Secure", &origin);
+        jar.store("bad=1; SameSite=None", &origin); // None without Secure
+
+        let page = url("https://news.site.org/");
+        assert_eq!(
+            jar.header_for(&origin, Some(&page)).as_deref(),
+            Some("none=1")
+        );
+        let own_page = url("https://www.example.com/");
+        assert_eq!(
+            jar.header_for(&origin, Some(&own_page)).as_deref(),
+            Some("lax=1; none=1")
+        );
+    }
+
+    #[test]
+    fn max_age_zero_deletes() {
+        let jar = CookieJar::new();
+        let origin = url("https://example.com/");
+        jar.store("a=1", &origin);
+        jar.store("a=1; Max-Age=0", &origin);
+        assert!(jar.is_empty());
+    }
+
+    #[test]
+    fn persistence_round_trip_skips_session_cookies() {
+        let jar = CookieJar::new();
+        let origin = url("https://example.com/");
+        jar.store("session=1", &origin);
+        jar.store("keep=a=b; Max-Age=3600; SameSite=Strict", &origin);
+
+        let restored = CookieJar::from_persisted(&jar.to_persisted());
+        assert_eq!(restored.len(), 1);
+        assert_eq!(
+            restored.header_for(&origin, None).as_deref(),
+            Some("keep=a=b")
+        );
+    }
+}
@@ -1,17 +1,91 @@
+//! Blocking HTTP fetches.
+//!
+//! Requests go out through `reqwest`, which is not built for wasm32: there
+//! the fetch functions fail, and documents are handed to the engine through
+//! a [`FetchBackend`](super::backend::FetchBackend) instead.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Read;
+#[cfg(not(target_arch = "wasm32"))]
+use std::net::SocketAddr;
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
 use url::Url;
 
+use super::throttle::NetworkConditions;
+#[cfg(not(target_arch = "wasm32"))]
+use super::throttle::Transfer;
+use super::tls;
+#[cfg(not(target_arch = "wasm32"))]
+use super::{cookies, proxy, sniff, speculate};
+
+/// `User-Agent` sent unless overridden.
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "Mozilla/5.0 (compatible; ALICE-Browser/0.1; ",
+    "+https://github.com/ext-sakamoro/ALICE-Browser)"
+);
+
+/// Network timeouts and redirect limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutPolicy {
+    /// Time allowed to establish the connection
+    pub connect: Duration,
+    /// Time allowed for the whole request, body included
+    pub request: Duration,
+    pub max_redirects: usize,
+}
+
+impl Default for TimeoutPolicy {
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(10),
+            request: Duration::from_secs(15),
+            max_redirects: 10,
+        }
+    }
+}
+
+/// Per-request settings for [`fetch_url_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchOptions {
+    pub user_agent: String,
+    pub timeouts: TimeoutPolicy,
+    /// Largest response body accepted (bytes)
+    pub max_body_bytes: usize,
+    /// Simulated latency, bandwidth and failures (none by default)
+    pub network: NetworkConditions,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            timeouts: TimeoutPolicy::default(),
+            max_body_bytes: 16 * 1024 * 1024,
+            network: NetworkConditions::NONE,
+        }
+    }
+}
+
 /// Result of fetching a URL
 #[derive(Clone)]
 pub struct FetchResult {
+    /// Decoded body (HTML or, for other text resources, their text)
     pub html: String,
     pub url: String,
     pub status: u16,
+    /// `Content-Type` header; empty when the server sent none
     pub content_type: String,
+    /// Server certificate, for HTTPS
+    pub certificate: Option<tls::Certificate>,
 }
 
 /// Error during fetch
 pub struct FetchError {
     pub message: String,
+    /// The server's certificate failed verification (see [`tls`])
+    pub invalid_certificate: bool,
 }
 
 impl std::fmt::Display for FetchError {
@@ -20,65 +94,326 @@ impl std::fmt::Display for FetchError {
     }
 }
 
-/// Fetch a URL and return the HTML content (blocking).
+/// Fetch a URL and return the HTML content (blocking), with default options.
 ///
 /// # Errors
 ///
 /// Returns `FetchError` if the URL is invalid, the connection fails, or the server returns an error.
 pub fn fetch_url(url_str: &str) -> Result<FetchResult, FetchError> {
-    // Normalize URL
+    fetch_url_with(url_str, &FetchOptions::default())
+}
+
+/// Fetch a URL and return the HTML content (blocking).
+///
+/// # Errors
+///
+/// Returns `FetchError` if the URL is invalid, the connection fails, the
+/// server returns an error, or the body exceeds `options.max_body_bytes`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn fetch_url_with(url_str: &str, options: &FetchOptions) -> Result<FetchResult, FetchError> {
+    let parsed = normalize(url_str)?;
+    let transfer = options
+        .network
+        .begin(parsed.as_str(), options.timeouts.request)?;
+    let response = client(options, &parsed)?
+        .get(parsed.as_str())
+        .header("Accept", ACCEPT_HTML)
+        .header("Accept-Language", ACCEPT_LANGUAGE)
+        .send()
+        .map_err(request_failed)?;
+    read_response(response, options, &transfer)
+}
+
+/// POST an `application/x-www-form-urlencoded` body and return the
+/// resulting page (blocking).
+///
+/// # Errors
+///
+/// Same as [`fetch_url_with`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn post_form(
+    url_str: &str,
+    body: String,
+    options: &FetchOptions,
+) -> Result<FetchResult, FetchError> {
+    let parsed = normalize(url_str)?;
+    let transfer = options
+        .network
+        .begin(parsed.as_str(), options.timeouts.request)?;
+    let response = client(options, &parsed)?
+        .post(parsed.as_str())
+        .header("Accept", ACCEPT_HTML)
+        .header("Accept-Language", ACCEPT_LANGUAGE)
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .body(body)
+        .send()
+        .map_err(request_failed)?;
+    read_response(response, options, &transfer)
+}
+
+/// The first bytes of a resource, as fetched by [`fetch_prefix`].
+pub struct FetchedPrefix {
+    pub bytes: Vec<u8>,
+    /// `Content-Type` header; empty when the server sent none
+    pub content_type: String,
+    /// Size of the whole resource, when the server said
+    pub total: Option<u64>,
+}
+
+/// Fetch at most `limit` bytes from the start of a resource, asking with a
+/// `Range` header and cutting the download short if the server ignores it
+/// (blocking).
+///
+/// # Errors
+///
+/// Returns `FetchError` if the URL is invalid, the connection fails or the
+/// server returns an error status.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn fetch_prefix(
+    url_str: &str,
+    limit: usize,
+    options: &FetchOptions,
+) -> Result<FetchedPrefix, FetchError> {
+    let parsed = normalize(url_str)?;
+    let transfer = options
+        .network
+        .begin(parsed.as_str(), options.timeouts.request)?;
+    let response = client(options, &parsed)?
+        .get(parsed.as_str())
+        .header("Range", format!("bytes=0-{}", limit.saturating_sub(1)))
+        .send()
+        .map_err(request_failed)?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(FetchError {
+            message: format!("HTTP {}", status.as_u16()),
+            invalid_certificate: false,
+        });
+    }
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let content_type = header("content-type");
+    // "bytes 0-65535/1048576" for a partial response
+    let total = if status.as_u16() == 206 {
+        header("content-range")
+            .rsplit_once('/')
+            .and_then(|(_, total)| total.trim().parse().ok())
+    } else {
+        response.content_length()
+    };
+
+    let mut bytes = Vec::with_capacity(limit.min(1024 * 1024));
+    response
+        .take(limit as u64)
+        .read_to_end(&mut bytes)
+        .map_err(|e| FetchError {
+            message: format!("Failed to read body: {e}"),
+            invalid_certificate: false,
+        })?;
+    transfer.pace(bytes.len())?;
+    Ok(FetchedPrefix {
+        bytes,
+        content_type,
+        total,
+    })
+}
+
+/// A failed request, with its cause chain: reqwest's own message omits
+/// the cause, so a DNS failure would read as "error sending request".
+#[cfg(not(target_arch = "wasm32"))]
+fn request_failed(e: reqwest::Error) -> FetchError {
+    let mut message = format!("Request failed: {e}");
+    let mut source = std::error::Error::source(&e);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {cause}"));
+        source = cause.source();
+    }
+    FetchError {
+        message,
+        invalid_certificate: tls::is_certificate_error(&e),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const ACCEPT_HTML: &str = "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8";
+#[cfg(not(target_arch = "wasm32"))]
+const ACCEPT_LANGUAGE: &str = "ja,en-US;q=0.9,en;q=0.8";
+
+/// Parse `url_str`, defaulting to `https://` when no scheme is given.
+#[cfg(not(target_arch = "wasm32"))]
+fn normalize(url_str: &str) -> Result<Url, FetchError> {
     let url = if !url_str.starts_with("http://") && !url_str.starts_with("https://") {
         format!("https://{url_str}")
     } else {
         url_str.to_string()
     };
 
-    let parsed = Url::parse(&url).map_err(|e| FetchError {
+    Url::parse(&url).map_err(|e| FetchError {
         message: format!("Invalid URL: {e}"),
-    })?;
+        invalid_certificate: false,
+    })
+}
 
-    let client = reqwest::blocking::Client::builder()
-        .user_agent(concat!(
-            "Mozilla/5.0 (compatible; ALICE-Browser/0.1; ",
-            "+https://github.com/ext-sakamoro/ALICE-Browser)"
-        ))
-        .timeout(std::time::Duration::from_secs(15))
-        .redirect(reqwest::redirect::Policy::limited(10))
-        .build()
-        .map_err(|e| FetchError {
-            message: format!("Client error: {e}"),
-        })?;
+/// Client for a request to `url`; certificate checks are skipped for hosts
+/// with a [`tls::exceptions`] entry. A client left connected to the origin
+/// by [`speculate`] is reused, and hosts it resolved are not looked up again.
+#[cfg(not(target_arch = "wasm32"))]
+fn client(options: &FetchOptions, url: &Url) -> Result<reqwest::blocking::Client, FetchError> {
+    let excepted = url
+        .host_str()
+        .is_some_and(|host| tls::exceptions().is_allowed(host));
+    let speculator = speculate::shared();
+    if let Some(client) = speculator.take_client(url, options, excepted) {
+        return Ok(client);
+    }
+    let resolved = url
+        .domain()
+        .and_then(|domain| speculator.resolved(domain).map(|addrs| (domain, addrs)));
+    build_client(
+        options,
+        excepted,
+        resolved
+            .as_ref()
+            .map(|(domain, addrs)| (*domain, addrs.as_slice())),
+    )
+}
 
-    let response = client
-        .get(parsed.as_str())
-        .header(
-            "Accept",
-            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
-        )
-        .header("Accept-Language", "ja,en-US;q=0.9,en;q=0.8")
-        .send()
-        .map_err(|e| FetchError {
-            message: format!("Request failed: {e}"),
-        })?;
+/// Client for `options`, without certificate checks if `excepted`, with
+/// `resolved` addresses pinned for a host.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn build_client(
+    options: &FetchOptions,
+    excepted: bool,
+    resolved: Option<(&str, &[SocketAddr])>,
+) -> Result<reqwest::blocking::Client, FetchError> {
+    let mut builder = proxy::client_builder()
+        .tls_info(true)
+        .danger_accept_invalid_certs(excepted)
+        .user_agent(options.user_agent.as_str())
+        .connect_timeout(options.timeouts.connect)
+        .timeout(options.timeouts.request)
+        .redirect(reqwest::redirect::Policy::limited(
+            options.timeouts.max_redirects,
+        ))
+        .cookie_provider(cookies::shared_jar());
+    if let Some((domain, addrs)) = resolved {
+        builder = builder.resolve_to_addrs(domain, addrs);
+    }
+    builder.build().map_err(|e| FetchError {
+        message: format!("Client error: {e}"),
+        invalid_certificate: false,
+    })
+}
 
+#[cfg(not(target_arch = "wasm32"))]
+fn read_response(
+    response: reqwest::blocking::Response,
+    options: &FetchOptions,
+    transfer: &Transfer,
+) -> Result<FetchResult, FetchError> {
     let status = response.status().as_u16();
     let content_type = response
         .headers()
         .get("content-type")
         .and_then(|v| v.to_str().ok())
-        .unwrap_or("text/html")
+        .unwrap_or_default()
         .to_string();
 
     let final_url = response.url().to_string();
+    let certificate = response
+        .extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(reqwest::tls::TlsInfo::peer_certificate)
+        .and_then(tls::Certificate::from_der);
 
-    let html = response.text().map_err(|e| FetchError {
-        message: format!("Failed to read body: {e}"),
-    })?;
+    let too_large = |size: String| FetchError {
+        message: format!(
+            "Response too large ({size} bytes, limit {})",
+            options.max_body_bytes
+        ),
+        invalid_certificate: false,
+    };
+    if let Some(len) = response
+        .content_length()
+        .filter(|&len| len > options.max_body_bytes as u64)
+    {
+        return Err(too_large(len.to_string()));
+    }
+
+    // The length is only a hint (or absent, when chunked): read at most one
+    // byte past the limit, which tells a body over it
+    let mut body = Vec::new();
+    response
+        .take(options.max_body_bytes as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| FetchError {
+            message: format!("Failed to read body: {e}"),
+            invalid_certificate: false,
+        })?;
+    if body.len() > options.max_body_bytes {
+        return Err(too_large(format!("over {}", options.max_body_bytes)));
+    }
+    transfer.pace(body.len())?;
+    let html = sniff::decode(&body, &content_type);
 
     Ok(FetchResult {
         html,
         url: final_url,
         status,
         content_type,
+        certificate,
     })
 }
+
+/// The error every fetch ends in on wasm32.
+#[cfg(target_arch = "wasm32")]
+fn no_network(url_str: &str) -> FetchError {
+    FetchError {
+        message: format!("No network in this build; provide {url_str} through a FetchBackend"),
+        invalid_certificate: false,
+    }
+}
+
+/// No network on wasm32: always fails (see the module docs).
+///
+/// # Errors
+///
+/// Always.
+#[cfg(target_arch = "wasm32")]
+pub fn fetch_url_with(url_str: &str, _options: &FetchOptions) -> Result<FetchResult, FetchError> {
+    Err(no_network(url_str))
+}
+
+/// No network on wasm32: always fails (see the module docs).
+///
+/// # Errors
+///
+/// Always.
+#[cfg(target_arch = "wasm32")]
+pub fn post_form(
+    url_str: &str,
+    _body: String,
+    _options: &FetchOptions,
+) -> Result<FetchResult, FetchError> {
+    Err(no_network(url_str))
+}
+
+/// No network on wasm32: always fails (see the module docs).
+///
+/// # Errors
+///
+/// Always.
+#[cfg(target_arch = "wasm32")]
+pub fn fetch_prefix(
+    url_str: &str,
+    _limit: usize,
+    _options: &FetchOptions,
+) -> Result<FetchedPrefix, FetchError> {
+    Err(no_network(url_str))
+}
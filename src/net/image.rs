@@ -1,23 +1,184 @@
 //! Asynchronous image fetcher.
 //!
-//! Spawns background threads to download images and decode them
-//! into RGBA pixel buffers ready for egui texture creation.
+//! Downloads images in the background and decodes them into RGBA pixel
+//! buffers ready for egui texture creation.
+//! Requests share the cookie jar with page fetches, as cross-site
+//! subresources of the current page.
+//!
+//! JPEG, PNG, GIF and WebP are always decoded; AVIF needs the
+//! `image-modern` feature (libdav1d). Images larger than the loader's
+//! maximum texture size are downscaled before they reach the GPU.
+//!
+//! Downloads go through the shared [`scheduler`](super::scheduler) at
+//! [`Priority::Image`], each with a cancellation token of its own.
+//!
+//! Bodies are read incrementally. Once the header is in, the image's size
+//! is known; once a JPEG's first scans have arrived, a tiny blurred preview
+//! is decoded from the partial data so the UI can show something shaped
+//! like the image while the rest downloads. [`ImageLoader::state`] reports
+//! where each image is. Downloads can run under simulated network
+//! conditions ([`ImageLoader::set_network`]). `data:` URLs, as in saved
+//! pages, are decoded without a download; they are the only images loaded
+//! on wasm32, which has no network of its own.
+//!
+//! Pages load their images as they scroll into view: [`ImageLoader::load_near`]
+//! requests those of a page's [`PageImage`]s near the viewport — sooner for
+//! ordinary images than for `loading="lazy"` ones — and cancels downloads
+//! of images scrolled far away, so they can be fetched again on return.
+//!
+//! Each finished request is also kept as a network-log record for the
+//! [`NetworkLog`](super::netlog::NetworkLog) of the page it was made for
+//! ([`ImageLoader::take_requests`]).
 
 use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Read;
 use std::sync::mpsc;
 
+use url::Url;
+use web_time::Instant;
+
+use crate::engine::workers::CancelToken;
+use crate::render::layout::LayoutNode;
+
+use super::data_url;
+use super::netlog::{self, RequestKind, RequestRecord};
+use super::scheduler::{self, Priority};
+use super::throttle::NetworkConditions;
+#[cfg(not(target_arch = "wasm32"))]
+use super::{cookies, proxy, throttle::Transfer, tls};
+
+/// Longest side, in pixels, of a decoded image unless configured otherwise.
+pub const DEFAULT_MAX_TEXTURE_SIZE: u32 = 2048;
+
+/// Longest side of a blurred preview.
+const PREVIEW_SIZE: u32 = 32;
+
+/// Time allowed for one image download.
+const IMAGE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Read granularity of image bodies.
+const CHUNK_SIZE: usize = 16 * 1024;
+
+/// Largest image body downloaded; a longer one is abandoned.
+const MAX_IMAGE_BYTES: usize = 32 * 1024 * 1024;
+
+/// Data needed before a partial decode is worth trying: this share of the
+/// body when its length is known, else `PREVIEW_MIN_BYTES`.
+const PREVIEW_MIN_SHARE: f32 = 0.2;
+const PREVIEW_MIN_BYTES: usize = 32 * 1024;
+
+/// Screens beyond the viewport within which images are requested.
+const EAGER_MARGIN: f32 = 2.0;
+
+/// Screens beyond the viewport within which `loading="lazy"` images are
+/// requested.
+const LAZY_MARGIN: f32 = 0.5;
+
+/// Screens beyond the viewport past which downloads are cancelled.
+const CANCEL_MARGIN: f32 = 4.0;
+
 /// Decoded image data (RGBA).
 pub struct ImageData {
     pub width: u32,
     pub height: u32,
     pub rgba: Vec<u8>,
+    /// Size of the encoded file as downloaded
+    pub encoded_bytes: usize,
+}
+
+/// Download progress of an image still loading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Progress {
+    pub received: usize,
+    /// From `Content-Length`, when the server sent one
+    pub total: Option<usize>,
+    /// Intrinsic size, once the header has arrived
+    pub size: Option<(u32, u32)>,
+    /// A blurred preview is available
+    pub has_preview: bool,
+}
+
+impl Progress {
+    /// Share of the body received, if its length is known.
+    #[must_use]
+    pub fn fraction(&self) -> Option<f32> {
+        self.total
+            .filter(|&t| t > 0)
+            .map(|t| (self.received as f32 / t as f32).min(1.0))
+    }
+}
+
+/// Where an image is in its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadState {
+    NotRequested,
+    Loading(Progress),
+    Loaded,
+    Failed,
+}
+
+/// Messages from a download thread.
+enum ImageEvent {
+    Progress {
+        received: usize,
+        total: Option<usize>,
+    },
+    /// Response status and `Content-Type`
+    Response(u16, String),
+    Size(u32, u32),
+    Preview(ImageData),
+    Done(Option<ImageData>),
+}
+
+struct Pending {
+    rx: mpsc::Receiver<ImageEvent>,
+    progress: Progress,
+    /// Network-log record, completed when the image is done
+    request: RequestRecord,
+    start: Instant,
+    /// Page the image was requested for
+    page_url: Option<Url>,
+    /// Stops the download (see [`ImageLoader::cancel`])
+    token: CancelToken,
+}
+
+impl Pending {
+    fn new(
+        url: &str,
+        page_url: Option<Url>,
+        rx: mpsc::Receiver<ImageEvent>,
+        token: CancelToken,
+    ) -> Self {
+        Self {
+            rx,
+            progress: Progress::default(),
+            request: RequestRecord::get(url, RequestKind::Image, netlog::now_ms()),
+            start: Instant::now(),
+            page_url,
+            token,
+        }
+    }
 }
 
 /// Manages background image fetching and decoding.
 pub struct ImageLoader {
-    pending: HashMap<String, mpsc::Receiver<Option<ImageData>>>,
+    pending: HashMap<String, Pending>,
     loaded: HashMap<String, ImageData>,
+    /// Blurred previews of images still loading
+    previews: HashMap<String, ImageData>,
     failed: std::collections::HashSet<String>,
+    /// Absolute URLs the ad blocker refuses on the current page
+    blocked: std::collections::HashSet<String>,
+    /// Page the images belong to, for `SameSite` cookie decisions
+    page_url: Option<Url>,
+    /// Longest side of a decoded image; larger ones are downscaled
+    max_texture_size: u32,
+    /// Simulated network conditions for downloads
+    network: NetworkConditions,
+    /// Finished requests not yet taken into a page's network log, with
+    /// the page they were made for
+    requests: Vec<(Option<Url>, RequestRecord)>,
 }
 
 impl Default for ImageLoader {
@@ -32,57 +193,239 @@ impl ImageLoader {
         Self {
             pending: HashMap::new(),
             loaded: HashMap::new(),
+            previews: HashMap::new(),
             failed: std::collections::HashSet::new(),
+            blocked: std::collections::HashSet::new(),
+            page_url: None,
+            max_texture_size: DEFAULT_MAX_TEXTURE_SIZE,
+            network: NetworkConditions::NONE,
+            requests: Vec::new(),
+        }
+    }
+
+    /// Simulate network conditions for new downloads.
+    pub fn set_network(&mut self, network: NetworkConditions) {
+        self.network = network;
+    }
+
+    /// Limit the longest side of decoded images (applies to new requests).
+    pub fn set_max_texture_size(&mut self, px: u32) {
+        self.max_texture_size = px.max(1);
+    }
+
+    #[must_use]
+    pub const fn max_texture_size(&self) -> u32 {
+        self.max_texture_size
+    }
+
+    /// Set the page whose images are about to be requested.
+    pub fn set_page_url(&mut self, url: &str) {
+        self.page_url = Url::parse(url).ok();
+    }
+
+    /// Refuse requests for `urls` (absolute), the current page's resources
+    /// the ad blocker decided against, replacing the previous page's.
+    pub fn set_blocked(&mut self, urls: impl IntoIterator<Item = String>) {
+        self.blocked = urls.into_iter().collect();
+    }
+
+    /// Whether `url`, resolved against the page, is refused.
+    fn is_blocked(&self, url: &str) -> bool {
+        if self.blocked.is_empty() {
+            return false;
         }
+        let resolved = match self.page_url {
+            Some(ref base) => base.join(url).ok(),
+            None => Url::parse(url).ok(),
+        };
+        resolved.is_some_and(|u| self.blocked.contains(u.as_str()))
     }
 
-    /// Request an image to be fetched in the background.
+    /// Request an image to be fetched in the background. Images the ad
+    /// blocker refuses (see [`set_blocked`](Self::set_blocked)) are not.
     pub fn request(&mut self, url: &str) {
         if self.loaded.contains_key(url)
             || self.pending.contains_key(url)
             || self.failed.contains(url)
+            || self.is_blocked(url)
         {
             return;
         }
 
         let (tx, rx) = mpsc::channel();
         let url_owned = url.to_string();
+        let page_url = self.page_url.clone();
+        let max_size = self.max_texture_size;
+        let network = self.network;
+        let token = CancelToken::new();
 
-        std::thread::spawn(move || {
-            let result = fetch_and_decode(&url_owned);
-            let _ = tx.send(result);
+        scheduler::shared().submit(url, Priority::Image, &token, move |token| {
+            let result =
+                fetch_and_decode(&url_owned, page_url.as_ref(), max_size, network, token, &tx);
+            let _ = tx.send(ImageEvent::Done(result));
         });
 
-        self.pending.insert(url.to_string(), rx);
+        let pending = Pending::new(url, self.page_url.clone(), rx, token);
+        self.pending.insert(url.to_string(), pending);
+    }
+
+    /// Abandon the download of `url`. Unlike a failed image, it is fetched
+    /// again when next requested.
+    pub fn cancel(&mut self, url: &str) {
+        let Some(pending) = self.pending.remove(url) else {
+            return;
+        };
+        // Stops the download at its next chunk, or before it starts
+        pending.token.cancel();
+        let mut request = pending.request;
+        request.duration_ms = pending.start.elapsed().as_secs_f64() * 1000.0;
+        request.bytes = pending.progress.received;
+        request.error = Some("Cancelled".into());
+        self.requests.push((pending.page_url, request));
+        self.previews.remove(url);
+    }
+
+    /// Request the `images` near the viewport `top..bottom` (page
+    /// coordinates) and cancel downloads of those far outside it.
+    pub fn load_near(&mut self, images: &[PageImage], top: f32, bottom: f32) {
+        let screen = (bottom - top).max(1.0);
+        let near = |image: &PageImage, screens: f32| {
+            image.bottom >= screens.mul_add(-screen, top)
+                && image.top <= screens.mul_add(screen, bottom)
+        };
+        for image in images {
+            let margin = if image.lazy {
+                LAZY_MARGIN
+            } else {
+                EAGER_MARGIN
+            };
+            if near(image, margin) {
+                self.request(&image.url);
+            } else if !near(image, CANCEL_MARGIN) && self.pending.contains_key(&image.url) {
+                // The same image may also sit nearer, further down the list
+                if !images
+                    .iter()
+                    .any(|other| other.url == image.url && near(other, CANCEL_MARGIN))
+                {
+                    self.cancel(&image.url);
+                }
+            }
+        }
     }
 
-    /// Poll for completed downloads. Call every frame.
+    /// Abandon every download in flight, as when leaving the page.
+    pub fn cancel_all(&mut self) {
+        let urls: Vec<String> = self.pending.keys().cloned().collect();
+        for url in urls {
+            self.cancel(&url);
+        }
+    }
+
+    /// Poll for progress and completed downloads. Call every frame.
     pub fn poll(&mut self) {
-        let mut completed = Vec::new();
-        for (url, rx) in &self.pending {
-            if let Ok(result) = rx.try_recv() {
-                match result {
-                    Some(data) => {
-                        self.loaded.insert(url.clone(), data);
-                    }
-                    None => {
-                        self.failed.insert(url.clone());
-                    }
+        let mut events = Vec::new();
+        for (url, pending) in &self.pending {
+            while let Ok(event) = pending.rx.try_recv() {
+                events.push((url.clone(), event));
+            }
+        }
+        for (url, event) in events {
+            self.apply(&url, event);
+        }
+    }
+
+    fn apply(&mut self, url: &str, event: ImageEvent) {
+        if let ImageEvent::Done(result) = event {
+            if let Some(pending) = self.pending.remove(url) {
+                let mut request = pending.request;
+                request.duration_ms = pending.start.elapsed().as_secs_f64() * 1000.0;
+                request.bytes = result
+                    .as_ref()
+                    .map_or(pending.progress.received, |data| data.encoded_bytes);
+                if result.is_none() {
+                    request.error = Some("Not loaded or not decodable".into());
+                }
+                self.requests.push((pending.page_url, request));
+            }
+            self.previews.remove(url);
+            match result {
+                Some(data) => {
+                    self.loaded.insert(url.to_string(), data);
+                }
+                None => {
+                    self.failed.insert(url.to_string());
                 }
-                completed.push(url.clone());
             }
+            return;
         }
-        for url in completed {
-            self.pending.remove(&url);
+        let Some(pending) = self.pending.get_mut(url) else {
+            return;
+        };
+        match event {
+            ImageEvent::Progress { received, total } => {
+                pending.progress.received = received;
+                pending.progress.total = total;
+            }
+            ImageEvent::Response(status, content_type) => {
+                pending.request.status = Some(status);
+                pending.request.content_type = content_type;
+            }
+            ImageEvent::Size(w, h) => pending.progress.size = Some((w, h)),
+            ImageEvent::Preview(data) => {
+                pending.progress.has_preview = true;
+                self.previews.insert(url.to_string(), data);
+            }
+            ImageEvent::Done(_) => {}
         }
     }
 
+    /// Loading state of `url`.
+    #[must_use]
+    pub fn state(&self, url: &str) -> LoadState {
+        if self.loaded.contains_key(url) {
+            LoadState::Loaded
+        } else if let Some(pending) = self.pending.get(url) {
+            LoadState::Loading(pending.progress)
+        } else if self.failed.contains(url) {
+            LoadState::Failed
+        } else {
+            LoadState::NotRequested
+        }
+    }
+
+    /// Progress of every image still loading.
+    #[must_use]
+    pub fn progress(&self) -> HashMap<String, Progress> {
+        self.pending
+            .iter()
+            .map(|(url, p)| (url.clone(), p.progress))
+            .collect()
+    }
+
+    /// Blurred preview of an image still loading.
+    #[must_use]
+    pub fn preview(&self, url: &str) -> Option<&ImageData> {
+        self.previews.get(url)
+    }
+
+    /// URLs with a preview available.
+    #[must_use]
+    pub fn preview_urls(&self) -> Vec<String> {
+        self.previews.keys().cloned().collect()
+    }
+
     /// Get a loaded image's data.
     #[must_use]
     pub fn get(&self, url: &str) -> Option<&ImageData> {
         self.loaded.get(url)
     }
 
+    /// Encoded (downloaded) size of a loaded image.
+    #[must_use]
+    pub fn encoded_bytes(&self, url: &str) -> Option<usize> {
+        self.loaded.get(url).map(|d| d.encoded_bytes)
+    }
+
     /// Get all loaded image URLs.
     #[must_use]
     pub fn loaded_urls(&self) -> Vec<String> {
@@ -95,47 +438,321 @@ impl ImageLoader {
         self.loaded.len()
     }
 
+    /// Whether `url` is still being fetched.
+    #[must_use]
+    pub fn is_pending(&self, url: &str) -> bool {
+        self.pending.contains_key(url)
+    }
+
     /// Number of images still being fetched.
     #[must_use]
     pub fn pending_count(&self) -> usize {
         self.pending.len()
     }
+
+    /// Bytes of decoded pixels held (loaded images and previews).
+    #[must_use]
+    pub fn decoded_bytes(&self) -> usize {
+        self.loaded
+            .values()
+            .chain(self.previews.values())
+            .map(|d| d.rgba.capacity())
+            .sum()
+    }
+
+    /// Forget every image, abandoning downloads in flight; images are
+    /// fetched again when next requested.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.loaded.clear();
+        self.previews.clear();
+        self.failed.clear();
+        self.requests.clear();
+    }
+
+    /// Requests made for `page_url` that finished since the last call,
+    /// for its network log. Those of other pages are dropped.
+    pub fn take_requests(&mut self, page_url: &str) -> Vec<RequestRecord> {
+        let page = Url::parse(page_url).ok();
+        std::mem::take(&mut self.requests)
+            .into_iter()
+            .filter(|(requested_for, _)| *requested_for == page)
+            .map(|(_, request)| request)
+            .collect()
+    }
 }
 
-fn fetch_and_decode(url: &str) -> Option<ImageData> {
-    let resp = reqwest::blocking::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
+/// An image laid out on a page, and the vertical span it covers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageImage {
+    /// Source, as written in the page
+    pub url: String,
+    pub top: f32,
+    pub bottom: f32,
+    /// Marked `loading="lazy"`
+    pub lazy: bool,
+}
+
+/// Every image laid out under `root`, in document order.
+#[must_use]
+pub fn page_images(root: &LayoutNode) -> Vec<PageImage> {
+    fn collect(node: &LayoutNode, out: &mut Vec<PageImage>) {
+        if node.tag == "img" {
+            if let Some(ref src) = node.href {
+                out.push(PageImage {
+                    url: src.clone(),
+                    top: node.bounds.y,
+                    bottom: node.bounds.y + node.bounds.height,
+                    lazy: node.lazy,
+                });
+            }
+        }
+        for child in &node.children {
+            collect(child, out);
+        }
+    }
+    let mut out = Vec::new();
+    collect(root, &mut out);
+    out
+}
+
+/// Download and decode one image on the calling thread, downscaled to at
+/// most `max_size` pixels on the longest side — for small images that
+/// belong to no page, such as the favicons of link previews.
+#[must_use]
+pub fn fetch_image(url: &str, max_size: u32, network: NetworkConditions) -> Option<ImageData> {
+    // Nobody follows the progress
+    let (events, _) = mpsc::channel();
+    fetch_and_decode(url, None, max_size, network, &CancelToken::new(), &events)
+}
+
+fn fetch_and_decode(
+    url: &str,
+    page_url: Option<&Url>,
+    max_size: u32,
+    network: NetworkConditions,
+    token: &CancelToken,
+    events: &mpsc::Sender<ImageEvent>,
+) -> Option<ImageData> {
+    if let Some((_, bytes)) = data_url::decode(url) {
+        return decode(&bytes, max_size);
+    }
+    let bytes = download(url, page_url, network, token, events)?;
+    let decoded = decode(&bytes, max_size);
+    if decoded.is_none() {
+        log::debug!("Could not decode image {url}");
+    }
+    decoded
+}
+
+/// Download an image's body, sharing the page's cookies.
+#[cfg(not(target_arch = "wasm32"))]
+fn download(
+    url: &str,
+    page_url: Option<&Url>,
+    network: NetworkConditions,
+    token: &CancelToken,
+    events: &mpsc::Sender<ImageEvent>,
+) -> Option<Vec<u8>> {
+    let parsed = Url::parse(url).ok()?;
+    let jar = cookies::shared_jar();
+    let transfer = network.begin(parsed.as_str(), IMAGE_TIMEOUT).ok()?;
+
+    let excepted = parsed
+        .host_str()
+        .is_some_and(|host| tls::exceptions().is_allowed(host));
+    let mut req = proxy::client_builder()
+        .timeout(IMAGE_TIMEOUT)
+        .danger_accept_invalid_certs(excepted)
         .build()
         .ok()?
-        .get(url)
-        .send()
-        .ok()?;
+        .get(parsed.as_str());
+    if let Some(cookie) = jar.header_for(&parsed, page_url) {
+        req = req.header(reqwest::header::COOKIE, cookie);
+    }
+    let resp = req.send().ok()?;
+    let content_type = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let _ = events.send(ImageEvent::Response(resp.status().as_u16(), content_type));
+
+    for header in resp.headers().get_all(reqwest::header::SET_COOKIE) {
+        if let Ok(s) = header.to_str() {
+            jar.store(s, resp.url());
+        }
+    }
 
     if !resp.status().is_success() {
         return None;
     }
 
-    let bytes = resp.bytes().ok()?;
-    let img = image::load_from_memory(&bytes).ok()?;
-    let rgba = img.to_rgba8();
-    let (w, h) = rgba.dimensions();
-
-    // Cap to reasonable size (max 800px wide for browser)
-    let (w, h, pixels) = if w > 800 {
-        let ratio = 800.0 / w as f32;
-        let new_h = (h as f32 * ratio) as u32;
-        let resized =
-            image::imageops::resize(&rgba, 800, new_h, image::imageops::FilterType::Triangle);
-        let (rw, rh) = resized.dimensions();
-        (rw, rh, resized.into_raw())
+    read_progressively(resp, &transfer, token, events)
+}
+
+/// No network on wasm32: only `data:` images load.
+#[cfg(target_arch = "wasm32")]
+fn download(
+    _url: &str,
+    _page_url: Option<&Url>,
+    _network: NetworkConditions,
+    _token: &CancelToken,
+    _events: &mpsc::Sender<ImageEvent>,
+) -> Option<Vec<u8>> {
+    None
+}
+
+/// Read the body in chunks, reporting progress, the image size once the
+/// header is in and a blurred preview once enough of a JPEG has arrived.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_progressively(
+    mut resp: reqwest::blocking::Response,
+    transfer: &Transfer,
+    token: &CancelToken,
+    events: &mpsc::Sender<ImageEvent>,
+) -> Option<Vec<u8>> {
+    let total = resp.content_length().and_then(|n| usize::try_from(n).ok());
+    if total.is_some_and(|t| t > MAX_IMAGE_BYTES) {
+        return None;
+    }
+    let preview_at = total.map_or(PREVIEW_MIN_BYTES, |t| {
+        (t as f32 * PREVIEW_MIN_SHARE) as usize
+    });
+    // The server's length is only a hint: the body is capped as it arrives
+    let mut buf = Vec::with_capacity(total.map_or(CHUNK_SIZE, |t| t.min(MAX_IMAGE_BYTES)));
+    let mut chunk = vec![0; CHUNK_SIZE];
+    let mut sized = false;
+    let mut previewed = false;
+
+    loop {
+        if token.is_cancelled() {
+            return None;
+        }
+        let n = resp.read(&mut chunk).ok()?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if buf.len() > MAX_IMAGE_BYTES {
+            return None;
+        }
+        transfer.pace(buf.len()).ok()?;
+        let _ = events.send(ImageEvent::Progress {
+            received: buf.len(),
+            total,
+        });
+
+        if !sized {
+            if let Some((w, h)) = header_size(&buf) {
+                sized = true;
+                let _ = events.send(ImageEvent::Size(w, h));
+            }
+        }
+        let complete = total.is_some_and(|t| buf.len() >= t);
+        if sized && !previewed && !complete && buf.len() >= preview_at {
+            previewed = true;
+            if let Some(preview) = decode_partial(&buf) {
+                let _ = events.send(ImageEvent::Preview(preview));
+            }
+        }
+    }
+    Some(buf)
+}
+
+/// Intrinsic size from the image header, if enough of it has arrived.
+fn header_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    image::ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+/// Blurred preview from the first part of a JPEG.
+///
+/// Closing the truncated stream with an end-of-image marker lets the
+/// decoder render what has arrived: every scan so far of a progressive
+/// JPEG (a coarse but complete picture) or the top rows of a baseline one.
+/// Other formats get no preview.
+#[must_use]
+pub fn decode_partial(bytes: &[u8]) -> Option<ImageData> {
+    if image::guess_format(bytes).ok()? != image::ImageFormat::Jpeg {
+        return None;
+    }
+    let mut data = Vec::with_capacity(bytes.len() + 2);
+    data.extend_from_slice(bytes);
+    data.extend_from_slice(&[0xFF, 0xD9]);
+    let img = image::load_from_memory_with_format(&data, image::ImageFormat::Jpeg).ok()?;
+    Some(blurred_preview(&img, bytes.len()))
+}
+
+/// Tiny, blurred copy of `img`, meant to be stretched over the image's box.
+fn blurred_preview(img: &image::DynamicImage, encoded_bytes: usize) -> ImageData {
+    let (w, h) = fit_within(img.width(), img.height(), PREVIEW_SIZE);
+    let small = img.thumbnail_exact(w, h).to_rgba8();
+    let blurred = image::imageops::blur(&small, 1.0);
+    ImageData {
+        width: w,
+        height: h,
+        rgba: blurred.into_raw(),
+        encoded_bytes,
+    }
+}
+
+/// Prefix of [`preview_key`]s.
+pub const PREVIEW_PREFIX: &str = "preview:";
+
+/// Texture-map key under which the preview of `url` is stored.
+#[must_use]
+pub fn preview_key(url: &str) -> String {
+    format!("{PREVIEW_PREFIX}{url}")
+}
+
+/// Size that fits `width` × `height` within `max` on both sides, keeping the
+/// aspect ratio (never upscales, never zero).
+#[must_use]
+pub fn fit_within(width: u32, height: u32, max: u32) -> (u32, u32) {
+    let longest = width.max(height);
+    if longest <= max {
+        return (width, height);
+    }
+    let scale = max as f32 / longest as f32;
+    let w = (width as f32 * scale).round() as u32;
+    let h = (height as f32 * scale).round() as u32;
+    (w.clamp(1, max), h.clamp(1, max))
+}
+
+/// Decode encoded image bytes to RGBA, downscaled to at most `max_size`
+/// pixels on the longest side.
+///
+/// The format is sniffed from the data, not the URL or `Content-Type`, so
+/// WebP served as `.jpg` still decodes. Returns `None` for corrupt data and
+/// for formats this build cannot decode (AVIF without `image-modern`).
+#[must_use]
+pub fn decode(bytes: &[u8], max_size: u32) -> Option<ImageData> {
+    let format = image::guess_format(bytes).ok()?;
+    if !format.reading_enabled() {
+        log::debug!("{format:?} images need a build with the image-modern feature");
+        return None;
+    }
+    let img = image::load_from_memory_with_format(bytes, format).ok()?;
+
+    // Scale before converting, so a huge image is never expanded to RGBA
+    let (w, h) = fit_within(img.width(), img.height(), max_size);
+    let img = if (w, h) == (img.width(), img.height()) {
+        img
     } else {
-        (w, h, rgba.into_raw())
+        img.resize_exact(w, h, image::imageops::FilterType::Triangle)
     };
 
+    let rgba = img.to_rgba8();
     Some(ImageData {
         width: w,
         height: h,
-        rgba: pixels,
+        rgba: rgba.into_raw(),
+        encoded_bytes: bytes.len(),
     })
 }
 
@@ -150,4 +767,166 @@ mod tests {
         loader.request("https://example.com/img.png"); // should not duplicate
         assert_eq!(loader.pending.len(), 1);
     }
+
+    #[test]
+    fn images_load_near_the_viewport() {
+        let image = |url: &str, top: f32, lazy: bool| PageImage {
+            url: url.into(),
+            top,
+            bottom: top + 100.0,
+            lazy,
+        };
+        // Screens are 1000 px high; data: URLs decode without a download
+        let images = [
+            image("data:,top", 0.0, false),
+            image("data:,eager-below", 2500.0, false),
+            image("data:,lazy-below", 2500.0, true),
+            image("data:,far", 9000.0, false),
+        ];
+        let mut loader = ImageLoader::new();
+        loader.set_page_url("https://example.com/");
+        loader.load_near(&images, 0.0, 1000.0);
+        let mut requested: Vec<&String> = loader.pending.keys().collect();
+        requested.sort();
+        assert_eq!(requested, ["data:,eager-below", "data:,top"]);
+
+        // Scrolled to the bottom, the downloads above are abandoned
+        loader.load_near(&images, 8000.0, 9000.0);
+        assert!(!loader.is_pending("data:,top"));
+        assert!(!loader.is_pending("data:,eager-below"));
+        assert!(loader.is_pending("data:,far"));
+        assert_eq!(loader.state("data:,top"), LoadState::NotRequested);
+        let requests = loader.take_requests("https://example.com/");
+        assert_eq!(requests.len(), 2);
+        assert!(requests
+            .iter()
+            .all(|r| r.error.as_deref() == Some("Cancelled")));
+    }
+
+    #[test]
+    fn lazy_attribute_reaches_page_images() {
+        let dom = crate::dom::parser::parse_html(
+            r#"<img src="/a.png"><p>Text</p><img src="/b.png" loading="lazy">"#,
+            "https://example.com/",
+        );
+        let layout = crate::render::layout::compute_layout(&dom.root, 800.0);
+        let images = page_images(&layout);
+        assert_eq!(images.len(), 2);
+        assert_eq!((images[0].url.as_str(), images[0].lazy), ("/a.png", false));
+        assert_eq!((images[1].url.as_str(), images[1].lazy), ("/b.png", true));
+        assert!(images[1].top >= images[0].bottom);
+    }
+
+    #[test]
+    fn blocked_images_are_not_requested() {
+        let mut loader = ImageLoader::new();
+        loader.set_page_url("https://example.com/page");
+        loader.set_blocked([
+            "https://ads.example.net/a.png".to_string(),
+            "https://example.com/ad.png".to_string(),
+        ]);
+        loader.request("https://ads.example.net/a.png");
+        loader.request("/ad.png");
+        assert_eq!(loader.pending.len(), 0);
+    }
+
+    #[test]
+    fn fit_within_keeps_aspect() {
+        assert_eq!(fit_within(640, 480, 2048), (640, 480));
+        assert_eq!(fit_within(4000, 1000, 2000), (2000, 500));
+        assert_eq!(fit_within(1000, 4000, 2000), (500, 2000));
+        assert_eq!(fit_within(10_000, 1, 100), (100, 1));
+    }
+
+    fn encode(img: &image::RgbaImage, format: image::ImageFormat) -> Vec<u8> {
+        let mut out = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut out, format).unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn decodes_and_downscales() {
+        let img = image::RgbaImage::from_pixel(300, 150, image::Rgba([200, 10, 10, 255]));
+
+        let png = decode(&encode(&img, image::ImageFormat::Png), 100).unwrap();
+        assert_eq!((png.width, png.height), (100, 50));
+        assert_eq!(png.rgba.len(), 100 * 50 * 4);
+        assert_eq!(&png.rgba[..4], &[200, 10, 10, 255]);
+
+        // Lossless WebP, sniffed from the data
+        let webp = decode(&encode(&img, image::ImageFormat::WebP), 4096).unwrap();
+        assert_eq!((webp.width, webp.height), (300, 150));
+
+        assert!(decode(b"not an image", 100).is_none());
+    }
+
+    #[test]
+    fn partial_jpeg_gives_blurred_preview() {
+        let img = image::RgbaImage::from_fn(256, 128, |x, y| {
+            image::Rgba([(x % 256) as u8, (y * 2 % 256) as u8, 128, 255])
+        });
+        let rgb = image::DynamicImage::ImageRgba8(img).to_rgb8();
+        let mut jpeg = std::io::Cursor::new(Vec::new());
+        rgb.write_to(&mut jpeg, image::ImageFormat::Jpeg).unwrap();
+        let jpeg = jpeg.into_inner();
+
+        assert_eq!(header_size(&jpeg[..jpeg.len() / 2]), Some((256, 128)));
+        let preview = decode_partial(&jpeg[..jpeg.len() / 2]).unwrap();
+        assert_eq!((preview.width, preview.height), (32, 16));
+        assert_eq!(preview.rgba.len(), 32 * 16 * 4);
+
+        let png = encode(&image::RgbaImage::new(8, 8), image::ImageFormat::Png);
+        assert!(decode_partial(&png).is_none());
+    }
+
+    #[test]
+    fn events_drive_load_state() {
+        let mut loader = ImageLoader::new();
+        let url = "https://example.com/photo.jpg";
+        let (_tx, rx) = mpsc::channel();
+        loader.set_page_url("https://example.com/");
+        loader.pending.insert(
+            url.into(),
+            Pending::new(url, loader.page_url.clone(), rx, CancelToken::new()),
+        );
+
+        loader.apply(
+            url,
+            ImageEvent::Progress {
+                received: 25,
+                total: Some(100),
+            },
+        );
+        loader.apply(url, ImageEvent::Size(640, 480));
+        let LoadState::Loading(progress) = loader.state(url) else {
+            panic!("expected loading");
+        };
+        assert_eq!(progress.fraction(), Some(0.25));
+        assert_eq!(progress.size, Some((640, 480)));
+        assert!(!progress.has_preview);
+
+        let tiny = || ImageData {
+            width: 1,
+            height: 1,
+            rgba: vec![0; 4],
+            encoded_bytes: 25,
+        };
+        loader.apply(url, ImageEvent::Preview(tiny()));
+        assert!(loader.preview(url).is_some());
+
+        loader.apply(url, ImageEvent::Response(200, "image/jpeg".into()));
+        loader.apply(url, ImageEvent::Done(Some(tiny())));
+        assert_eq!(loader.state(url), LoadState::Loaded);
+        assert!(loader.preview(url).is_none());
+        let requests = loader.take_requests("https://example.com/");
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].status, Some(200));
+        assert_eq!(requests[0].content_type, "image/jpeg");
+        assert_eq!(requests[0].bytes, 25);
+        assert!(loader.take_requests("https://example.com/").is_empty());
+        assert_eq!(
+            loader.state("https://example.com/other.png"),
+            LoadState::NotRequested
+        );
+    }
 }
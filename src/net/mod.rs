@@ -1,7 +1,23 @@
 pub mod adblock;
+pub mod adblock_bench;
+pub mod backend;
+pub mod cookies;
+pub mod data_url;
 pub mod fetch;
 pub mod image;
+pub mod netlog;
+pub mod paste;
+pub mod prefetch_policy;
+pub mod proxy;
+pub mod scheduler;
+pub mod search_engine;
 pub mod service_worker;
+pub mod sniff;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod speculate;
+pub mod throttle;
+pub mod tls;
+pub mod wayback;
 
 #[cfg(feature = "smart-cache")]
 pub mod cache;
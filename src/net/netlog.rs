@@ -0,0 +1,348 @@
+//! Per-page network log.
+//!
+//! Every request made for a page — the document, its embedded frames, its
+//! images — is recorded as a [`RequestRecord`]: method, status, when it
+//! started and how long it took, how much came back and whether the page
+//! cache answered. Subresources the ad blocker refuses are recorded too,
+//! with the rule, though they are never sent. The log travels with the
+//! `PageResult`, is shown by the browser's network panel and exports as
+//! HAR 1.2 ([`NetworkLog::to_har`]) for other tools.
+
+use serde::Serialize;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use crate::engine::decisions::BlockedRequest;
+use crate::local_time::civil_from_days;
+
+/// What a request was for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestKind {
+    Document,
+    /// An embedded `<iframe>` document
+    Frame,
+    Image,
+    /// Scripts, stylesheets and anything else the page refers to
+    Other,
+}
+
+impl RequestKind {
+    pub const ALL: [Self; 4] = [Self::Document, Self::Frame, Self::Image, Self::Other];
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Document => "document",
+            Self::Frame => "frame",
+            Self::Image => "image",
+            Self::Other => "other",
+        }
+    }
+
+    /// Kind of a request made for a `tag` element.
+    #[must_use]
+    pub fn of_tag(tag: &str) -> Self {
+        match tag {
+            "img" | "picture" | "source" => Self::Image,
+            "iframe" | "frame" => Self::Frame,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// One request of a page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RequestRecord {
+    pub url: String,
+    pub method: String,
+    pub kind: RequestKind,
+    /// HTTP status; `None` if no response came (failed, blocked, `data:`)
+    pub status: Option<u16>,
+    /// Start, in milliseconds since the Unix epoch
+    pub started_ms: f64,
+    /// Time until the body was in
+    pub duration_ms: f64,
+    /// Body size
+    pub bytes: usize,
+    pub content_type: String,
+    /// Answered by the page cache
+    pub from_cache: bool,
+    /// Ad-block rule that refused it; such requests are never sent
+    pub blocked: Option<String>,
+    pub error: Option<String>,
+}
+
+impl RequestRecord {
+    /// A `GET` of `url` starting at `started_ms`, not finished yet.
+    #[must_use]
+    pub fn get(url: &str, kind: RequestKind, started_ms: f64) -> Self {
+        Self {
+            url: url.to_string(),
+            method: "GET".into(),
+            kind,
+            status: None,
+            started_ms,
+            duration_ms: 0.0,
+            bytes: 0,
+            content_type: String::new(),
+            from_cache: false,
+            blocked: None,
+            error: None,
+        }
+    }
+
+    /// A subresource request the ad blocker refused.
+    #[must_use]
+    pub fn blocked(request: &BlockedRequest, at_ms: f64) -> Self {
+        Self {
+            blocked: Some(request.rule.clone()),
+            ..Self::get(&request.url, RequestKind::of_tag(&request.tag), at_ms)
+        }
+    }
+
+    /// "200", "blocked", "failed" or "—".
+    #[must_use]
+    pub fn status_label(&self) -> String {
+        match (self.status, &self.blocked, &self.error) {
+            (Some(status), _, _) => status.to_string(),
+            (None, Some(_), _) => "blocked".into(),
+            (None, None, Some(_)) => "failed".into(),
+            (None, None, None) => "\u{2014}".into(),
+        }
+    }
+
+    /// Whether `query` (lowercase) occurs in the address, type, method or
+    /// status. An empty query matches everything.
+    #[must_use]
+    pub fn matches(&self, query: &str) -> bool {
+        query.is_empty()
+            || self.url.to_lowercase().contains(query)
+            || self.content_type.to_lowercase().contains(query)
+            || self.method.to_lowercase() == query
+            || self.status_label() == query
+    }
+}
+
+/// The requests of one page, in the order they started.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct NetworkLog {
+    pub entries: Vec<RequestRecord>,
+}
+
+impl NetworkLog {
+    /// Add `record` in start order.
+    pub fn record(&mut self, record: RequestRecord) {
+        let at = self
+            .entries
+            .partition_point(|e| e.started_ms <= record.started_ms);
+        self.entries.insert(at, record);
+    }
+
+    /// Start of the first request, in milliseconds since the Unix epoch.
+    #[must_use]
+    pub fn started_ms(&self) -> f64 {
+        self.entries.first().map_or(0.0, |e| e.started_ms)
+    }
+
+    /// Bytes received over the network (cache hits and blocked requests
+    /// excluded).
+    #[must_use]
+    pub fn transferred_bytes(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| !e.from_cache)
+            .map(|e| e.bytes)
+            .sum()
+    }
+
+    /// The log as a HAR 1.2 document, the page titled `title`. Headers and
+    /// cookies are not recorded, so their lists are empty; blocked
+    /// requests have status 0 and carry the rule as `_blockedBy`.
+    #[must_use]
+    pub fn to_har(&self, title: &str) -> String {
+        let page_id = "page_1";
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|e| {
+                let mut entry = serde_json::json!({
+                    "pageref": page_id,
+                    "startedDateTime": iso_8601(e.started_ms),
+                    "time": e.duration_ms,
+                    "request": {
+                        "method": e.method,
+                        "url": e.url,
+                        "httpVersion": "HTTP/1.1",
+                        "cookies": [],
+                        "headers": [],
+                        "queryString": query_string(&e.url),
+                        "headersSize": -1,
+                        "bodySize": -1,
+                    },
+                    "response": {
+                        "status": e.status.unwrap_or(0),
+                        "statusText": "",
+                        "httpVersion": "HTTP/1.1",
+                        "cookies": [],
+                        "headers": [],
+                        "content": {
+                            "size": e.bytes,
+                            "mimeType": e.content_type,
+                        },
+                        "redirectURL": "",
+                        "headersSize": -1,
+                        "bodySize": if e.from_cache { 0 } else { e.bytes as i64 },
+                    },
+                    "cache": {},
+                    "timings": {
+                        "send": 0,
+                        "wait": e.duration_ms,
+                        "receive": 0,
+                    },
+                });
+                if let Some(ref rule) = e.blocked {
+                    entry["_blockedBy"] = rule.as_str().into();
+                }
+                if let Some(ref error) = e.error {
+                    entry["_error"] = error.as_str().into();
+                }
+                entry
+            })
+            .collect();
+        let har = serde_json::json!({
+            "log": {
+                "version": "1.2",
+                "creator": {
+                    "name": "ALICE-Browser",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+                "pages": [{
+                    "startedDateTime": iso_8601(self.started_ms()),
+                    "id": page_id,
+                    "title": title,
+                    "pageTimings": {},
+                }],
+                "entries": entries,
+            }
+        });
+        serde_json::to_string_pretty(&har).unwrap_or_default()
+    }
+}
+
+/// Now, in milliseconds since the Unix epoch.
+#[must_use]
+pub fn now_ms() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_secs_f64() * 1000.0)
+}
+
+/// `2024-01-01T12:00:00.000Z` for `ms` since the Unix epoch.
+fn iso_8601(ms: f64) -> String {
+    let ms = ms.max(0.0) as i64;
+    let secs = ms.div_euclid(1000);
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let time = secs.rem_euclid(86_400);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60,
+        ms.rem_euclid(1000)
+    )
+}
+
+/// HAR `queryString` of `url`.
+fn query_string(url: &str) -> Vec<serde_json::Value> {
+    url::Url::parse(url)
+        .map(|u| {
+            u.query_pairs()
+                .map(|(name, value)| serde_json::json!({ "name": name, "value": value }))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> NetworkLog {
+        let mut log = NetworkLog::default();
+        let mut image =
+            RequestRecord::get("https://cdn.example/a.png", RequestKind::Image, 1_500.0);
+        image.status = Some(200);
+        image.bytes = 2048;
+        image.content_type = "image/png".into();
+        log.record(image);
+        let mut page = RequestRecord::get(
+            "https://example.com/?q=rust",
+            RequestKind::Document,
+            1_000.0,
+        );
+        page.status = Some(200);
+        page.duration_ms = 120.0;
+        page.bytes = 10_000;
+        page.from_cache = true;
+        log.record(page);
+        log.record(RequestRecord::blocked(
+            &BlockedRequest {
+                url: "https://ads.example/px.gif".into(),
+                rule: "domain:ads.example".into(),
+                tag: "img".into(),
+            },
+            1_200.0,
+        ));
+        log
+    }
+
+    #[test]
+    fn records_in_start_order_and_filters() {
+        let log = log();
+        let kinds: Vec<_> = log.entries.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                RequestKind::Document,
+                RequestKind::Image,
+                RequestKind::Image
+            ]
+        );
+        assert_eq!(log.started_ms(), 1_000.0);
+        assert_eq!(log.transferred_bytes(), 2048);
+        assert_eq!(log.entries[1].status_label(), "blocked");
+        let blocked: Vec<_> = log
+            .entries
+            .iter()
+            .filter(|e| e.matches("blocked"))
+            .collect();
+        assert_eq!(blocked.len(), 1);
+        assert_eq!(log.entries.iter().filter(|e| e.matches("png")).count(), 1);
+        assert_eq!(log.entries.iter().filter(|e| e.matches("")).count(), 3);
+    }
+
+    #[test]
+    fn exports_har() {
+        let har: serde_json::Value = serde_json::from_str(&log().to_har("Example")).unwrap();
+        let entries = har["log"]["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(har["log"]["version"], "1.2");
+        assert_eq!(har["log"]["pages"][0]["title"], "Example");
+        assert_eq!(
+            har["log"]["pages"][0]["startedDateTime"],
+            "1970-01-01T00:00:01.000Z"
+        );
+        let page = &entries[0];
+        assert_eq!(page["request"]["queryString"][0]["value"], "rust");
+        assert_eq!(page["response"]["bodySize"], 0);
+        assert_eq!(page["time"], 120.0);
+        assert_eq!(entries[1]["response"]["status"], 0);
+        assert_eq!(entries[1]["_blockedBy"], "domain:ads.example");
+    }
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(iso_8601(0.0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(iso_8601(1_704_110_400_250.0), "2024-01-01T12:00:00.250Z");
+    }
+}
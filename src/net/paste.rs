@@ -0,0 +1,143 @@
+//! Classifying text pasted into the URL bar.
+//!
+//! A single address is pasted as usual. Several addresses, one per line,
+//! or text that is not an address at all (a sentence, a paragraph copied
+//! from a page) call for a choice — open them all, search for the text —
+//! which [`classify_paste`] makes possible by telling the cases apart.
+
+/// Longest query sent to the search engine, in characters.
+pub const MAX_QUERY_CHARS: usize = 500;
+
+/// What pasted text most likely is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasteIntent {
+    /// One address: paste it into the bar as usual
+    Url(String),
+    /// Several addresses, one per line, in order and without duplicates
+    Urls(Vec<String>),
+    /// Anything else: a query, whitespace collapsed
+    Search(String),
+}
+
+impl PasteIntent {
+    /// Whether to ask what to do rather than paste: several addresses, or
+    /// text of more than one word. A single word or path fragment is pasted
+    /// as usual, since it is likely part of an address being edited.
+    #[must_use]
+    pub fn needs_choice(&self) -> bool {
+        match self {
+            Self::Url(_) => false,
+            Self::Urls(_) => true,
+            Self::Search(query) => query.contains(' '),
+        }
+    }
+}
+
+/// Classify `text` pasted into the URL bar.
+#[must_use]
+pub fn classify_paste(text: &str) -> PasteIntent {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    if !lines.is_empty() && lines.iter().all(|l| looks_like_url(l)) {
+        let mut urls: Vec<String> = Vec::with_capacity(lines.len());
+        for line in lines {
+            if !urls.iter().any(|u| u == line) {
+                urls.push(line.to_string());
+            }
+        }
+        return if urls.len() == 1 {
+            PasteIntent::Url(urls.remove(0))
+        } else {
+            PasteIntent::Urls(urls)
+        };
+    }
+    let query: String = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    PasteIntent::Search(query.chars().take(MAX_QUERY_CHARS).collect())
+}
+
+/// Whether `s` reads as an address rather than text: a URL with a scheme
+/// the browser opens, or a bare host (with an optional path) such as
+/// `example.com/docs` or `localhost:8080`.
+#[must_use]
+pub fn looks_like_url(s: &str) -> bool {
+    if s.chars().any(char::is_whitespace) {
+        return false;
+    }
+    if let Some((scheme, rest)) = s.split_once(':') {
+        match scheme.to_ascii_lowercase().as_str() {
+            "alice" => return true,
+            "http" | "https" => return rest.len() > 2 && rest.starts_with("//"),
+            _ if rest.starts_with("//") => return false,
+            _ => {}
+        }
+    }
+    let host = s.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = match host.rsplit_once(':') {
+        Some((h, port)) if !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => h,
+        _ => host,
+    };
+    if host.eq_ignore_ascii_case("localhost") || host.parse::<std::net::Ipv4Addr>().is_ok() {
+        return true;
+    }
+    // Dotted host name ending in an alphabetic top-level domain
+    let labels: Vec<&str> = host.split('.').collect();
+    labels.len() >= 2
+        && labels
+            .iter()
+            .all(|l| !l.is_empty() && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+        && labels
+            .last()
+            .is_some_and(|tld| tld.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_and_multiple_urls() {
+        assert_eq!(
+            classify_paste("  https://example.com/a \n"),
+            PasteIntent::Url("https://example.com/a".to_string())
+        );
+        assert_eq!(
+            classify_paste("https://a.example/\n\nexample.org/docs\r\nhttps://a.example/\n"),
+            PasteIntent::Urls(vec![
+                "https://a.example/".to_string(),
+                "example.org/docs".to_string(),
+            ])
+        );
+        assert!(!classify_paste("example.com").needs_choice());
+        assert!(classify_paste("a.example\nb.example").needs_choice());
+        assert!(looks_like_url("localhost:8080/x"));
+        assert!(looks_like_url("192.168.0.1"));
+        assert!(looks_like_url("alice://settings"));
+        assert!(!looks_like_url("ftp://example.com"));
+        assert!(!looks_like_url("e.g."));
+        assert!(!looks_like_url("3.14"));
+    }
+
+    #[test]
+    fn text_becomes_a_search() {
+        let snippet = "The quick brown fox\n  jumps over\tthe lazy dog.";
+        assert_eq!(
+            classify_paste(snippet),
+            PasteIntent::Search("The quick brown fox jumps over the lazy dog.".to_string())
+        );
+        // A URL among prose is still prose
+        assert!(matches!(
+            classify_paste("see https://example.com/\nfor details"),
+            PasteIntent::Search(_)
+        ));
+        assert!(classify_paste(snippet).needs_choice());
+        assert!(!classify_paste("/docs/intro").needs_choice());
+        let long = "word ".repeat(400);
+        let PasteIntent::Search(q) = classify_paste(&long) else {
+            panic!("expected a search");
+        };
+        assert_eq!(q.chars().count(), MAX_QUERY_CHARS);
+    }
+}
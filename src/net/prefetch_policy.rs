@@ -0,0 +1,557 @@
+//! Politeness rules for background link prefetch.
+//!
+//! OZ mode fetches a page's links in the background so their text can join
+//! the stream. A [`PrefetchPolicy`], shared by every prefetch worker across
+//! navigations, decides whether and when each of those requests goes out:
+//!
+//! - **robots.txt** — each origin's `/robots.txt` is read once and its
+//!   `Allow`/`Disallow` rules for our agent (or `*`) are obeyed, longest
+//!   match first;
+//! - **crawl-delay** — requests to one origin are spaced by its
+//!   `Crawl-delay`, and by [`PrefetchConfig::min_interval`] at least;
+//! - **dedupe** — a URL is prefetched once, however many pages link to it;
+//! - **concurrency** — at most [`PrefetchConfig::max_concurrent`] requests
+//!   are in flight at once;
+//! - **backoff** — a 429 or 503 answer pauses the origin, for twice as long
+//!   on each repeat, until a request succeeds.
+//!
+//! [`PrefetchPolicy::stats`] counts what was fetched and what was skipped
+//! and why.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use super::fetch::{FetchError, FetchResult};
+
+/// Product token matched against `User-agent` lines.
+pub const ROBOTS_AGENT: &str = "alice-browser";
+
+/// URLs remembered for dedupe before the oldest navigations are forgotten.
+const MAX_SEEN: usize = 10_000;
+
+/// Tuning for [`PrefetchPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrefetchConfig {
+    /// Requests in flight at once, over all origins
+    pub max_concurrent: usize,
+    /// Spacing between requests to one origin when robots.txt sets none
+    pub min_interval: Duration,
+    /// Longest `Crawl-delay` honoured; origins asking for more are skipped
+    pub max_crawl_delay: Duration,
+    /// First pause after a 429 or 503
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for PrefetchConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 2,
+            min_interval: Duration::from_millis(250),
+            max_crawl_delay: Duration::from_secs(10),
+            initial_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Rules of one origin's robots.txt that apply to us.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsRules {
+    /// `(allow, pattern)`, in file order
+    rules: Vec<(bool, String)>,
+    pub crawl_delay: Option<Duration>,
+}
+
+impl RobotsRules {
+    /// Parse a robots.txt for the agent `agent`: its own group if it has
+    /// one, the `*` group otherwise.
+    #[must_use]
+    pub fn parse(text: &str, agent: &str) -> Self {
+        let agent = agent.to_ascii_lowercase();
+        let mut ours = Self::default();
+        let mut star = Self::default();
+        let (mut found_ours, mut found_star) = (false, false);
+        // Agents the current group applies to, and whether rules have
+        // started (a user-agent line after rules opens a new group)
+        let (mut for_ours, mut for_star, mut in_rules) = (false, false, false);
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (key, value) = (key.trim().to_ascii_lowercase(), value.trim());
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        (for_ours, for_star, in_rules) = (false, false, false);
+                    }
+                    let name = value.to_ascii_lowercase();
+                    if name == "*" {
+                        for_star = true;
+                        found_star = true;
+                    } else if !name.is_empty() && agent.contains(&name) {
+                        for_ours = true;
+                        found_ours = true;
+                    }
+                }
+                "allow" | "disallow" | "crawl-delay" => {
+                    in_rules = true;
+                    for (applies, rules) in [(for_ours, &mut ours), (for_star, &mut star)] {
+                        if !applies {
+                            continue;
+                        }
+                        if key == "crawl-delay" {
+                            rules.crawl_delay = value
+                                .parse::<f64>()
+                                .ok()
+                                .filter(|s| s.is_finite() && *s >= 0.0)
+                                .map(Duration::from_secs_f64);
+                        } else if !value.is_empty() {
+                            rules.rules.push((key == "allow", value.to_string()));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if found_ours {
+            ours
+        } else if found_star {
+            star
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Whether `path` (with its query) may be fetched: the longest matching
+    /// rule decides, `Allow` winning ties; no match allows.
+    #[must_use]
+    pub fn allows(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| pattern_matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+}
+
+/// Match a robots.txt path pattern: a prefix, where `*` matches any run
+/// of characters and a trailing `$` anchors the end.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return true;
+    };
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let last = i + 1 == parts.len();
+        if anchored && last {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// Why a link was not prefetched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Prefetched (or tried) before
+    Duplicate,
+    /// Not an http(s) URL
+    Unsupported,
+    /// robots.txt disallows it
+    Disallowed,
+    /// The origin's `Crawl-delay` is longer than we wait
+    TooSlow,
+    /// The origin answered 429 or 503 recently
+    BackingOff,
+}
+
+/// What [`PrefetchPolicy::fetch`] did with a link.
+pub enum Outcome {
+    Fetched(FetchResult),
+    Skipped(SkipReason),
+    Failed(String),
+}
+
+/// Running totals, for the stats panel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrefetchStats {
+    pub fetched: usize,
+    pub failed: usize,
+    pub duplicates: usize,
+    pub disallowed: usize,
+    /// Skipped for a long crawl-delay or an origin backing off
+    pub deferred: usize,
+    /// 429 and 503 answers
+    pub throttled: usize,
+    /// Requests in flight now
+    pub in_flight: usize,
+    /// Total time spent waiting out crawl delays
+    pub waited: Duration,
+}
+
+/// Per-origin state.
+#[derive(Debug, Default)]
+struct Origin {
+    /// `None` until robots.txt has been read
+    robots: Option<RobotsRules>,
+    /// Earliest time of the next request
+    next_slot: Option<Instant>,
+    backoff: Option<Duration>,
+    backoff_until: Option<Instant>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    origins: HashMap<String, Origin>,
+    seen: HashSet<String>,
+    stats: PrefetchStats,
+}
+
+/// Shared prefetch politeness policy; see the module docs.
+pub struct PrefetchPolicy {
+    config: PrefetchConfig,
+    state: Mutex<State>,
+    /// Signalled when a request finishes and frees a slot
+    slot_freed: Condvar,
+}
+
+impl Default for PrefetchPolicy {
+    fn default() -> Self {
+        Self::new(PrefetchConfig::default())
+    }
+}
+
+impl PrefetchPolicy {
+    #[must_use]
+    pub fn new(config: PrefetchConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(State::default()),
+            slot_freed: Condvar::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn config(&self) -> &PrefetchConfig {
+        &self.config
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> PrefetchStats {
+        self.lock().stats
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Prefetch `url` with `fetch` if the policy allows it, blocking the
+    /// calling worker for robots.txt, a free slot and the origin's crawl
+    /// delay. `fetch` is also used for the origin's robots.txt.
+    pub fn fetch<F>(&self, url: &str, fetch: F) -> Outcome
+    where
+        F: Fn(&str) -> Result<FetchResult, FetchError>,
+    {
+        let Some((origin, path)) = split_url(url) else {
+            return Outcome::Skipped(SkipReason::Unsupported);
+        };
+        if !self.mark_seen(url) {
+            return self.skip(SkipReason::Duplicate);
+        }
+
+        if self
+            .lock()
+            .origins
+            .get(&origin)
+            .is_none_or(|o| o.robots.is_none())
+        {
+            let rules = self.read_robots(&origin, &fetch);
+            self.lock()
+                .origins
+                .entry(origin.clone())
+                .or_default()
+                .robots = Some(rules);
+        }
+
+        // Wait for a slot, then take the origin's next request time
+        let wait = {
+            let mut state = self.lock();
+            while state.stats.in_flight >= self.config.max_concurrent.max(1) {
+                state = self
+                    .slot_freed
+                    .wait(state)
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+            }
+            match self.admit(&mut state, &origin, &path, Instant::now()) {
+                Ok(wait) => {
+                    state.stats.in_flight += 1;
+                    state.stats.waited += wait;
+                    wait
+                }
+                Err(reason) => {
+                    drop(state);
+                    return self.skip(reason);
+                }
+            }
+        };
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+
+        let result = fetch(url);
+        let mut state = self.lock();
+        state.stats.in_flight -= 1;
+        self.slot_freed.notify_one();
+        let now = Instant::now();
+        let entry = state.origins.entry(origin).or_default();
+        match result {
+            Ok(page) if matches!(page.status, 429 | 503) => {
+                let backoff = entry.backoff.map_or(self.config.initial_backoff, |b| {
+                    (b * 2).min(self.config.max_backoff)
+                });
+                entry.backoff = Some(backoff);
+                entry.backoff_until = Some(now + backoff);
+                state.stats.throttled += 1;
+                state.stats.failed += 1;
+                Outcome::Failed(format!("HTTP {}, backing off {backoff:?}", page.status))
+            }
+            Ok(page) => {
+                entry.backoff = None;
+                entry.backoff_until = None;
+                state.stats.fetched += 1;
+                Outcome::Fetched(page)
+            }
+            Err(e) => {
+                state.stats.failed += 1;
+                Outcome::Failed(e.message)
+            }
+        }
+    }
+
+    /// Decide on a request to `path` at `origin` at time `now`: how long to
+    /// wait before sending it, or why not to. Reserves the origin's next
+    /// slot.
+    fn admit(
+        &self,
+        state: &mut State,
+        origin: &str,
+        path: &str,
+        now: Instant,
+    ) -> Result<Duration, SkipReason> {
+        let entry = state.origins.entry(origin.to_string()).or_default();
+        let robots = entry.robots.clone().unwrap_or_default();
+        if !robots.allows(path) {
+            return Err(SkipReason::Disallowed);
+        }
+        if entry.backoff_until.is_some_and(|until| until > now) {
+            return Err(SkipReason::BackingOff);
+        }
+        let delay = robots
+            .crawl_delay
+            .unwrap_or_default()
+            .max(self.config.min_interval);
+        if delay > self.config.max_crawl_delay {
+            return Err(SkipReason::TooSlow);
+        }
+        let start = entry.next_slot.map_or(now, |slot| slot.max(now));
+        entry.next_slot = Some(start + delay);
+        Ok(start - now)
+    }
+
+    /// Remember `url`; `false` if it was seen before.
+    fn mark_seen(&self, url: &str) -> bool {
+        let mut state = self.lock();
+        if state.seen.len() >= MAX_SEEN {
+            state.seen.clear();
+        }
+        state.seen.insert(url.to_string())
+    }
+
+    fn skip(&self, reason: SkipReason) -> Outcome {
+        let mut state = self.lock();
+        match reason {
+            SkipReason::Duplicate => state.stats.duplicates += 1,
+            SkipReason::Disallowed => state.stats.disallowed += 1,
+            SkipReason::TooSlow | SkipReason::BackingOff => state.stats.deferred += 1,
+            SkipReason::Unsupported => {}
+        }
+        Outcome::Skipped(reason)
+    }
+
+    /// Read `origin`'s robots.txt. A missing file (4xx) allows everything;
+    /// so does one that cannot be read, rather than stalling prefetch.
+    fn read_robots<F>(&self, origin: &str, fetch: &F) -> RobotsRules
+    where
+        F: Fn(&str) -> Result<FetchResult, FetchError>,
+    {
+        match fetch(&format!("{origin}/robots.txt")) {
+            Ok(file) if file.status < 400 => RobotsRules::parse(&file.html, ROBOTS_AGENT),
+            _ => RobotsRules::default(),
+        }
+    }
+}
+
+/// `(origin, path with query)` of an http(s) URL.
+fn split_url(url: &str) -> Option<(String, String)> {
+    let parsed = url::Url::parse(url).ok()?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return None;
+    }
+    let origin = parsed.origin().ascii_serialization();
+    let path = match parsed.query() {
+        Some(q) => format!("{}?{q}", parsed.path()),
+        None => parsed.path().to_string(),
+    };
+    Some((origin, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+# Example
+User-agent: *
+Disallow: /private/
+Allow: /private/open$
+Disallow: /*.pdf$
+Crawl-delay: 2
+
+User-agent: BadBot
+Disallow: /
+";
+
+    fn page(status: u16, body: &str) -> Result<FetchResult, FetchError> {
+        Ok(FetchResult {
+            html: body.to_string(),
+            url: String::new(),
+            status,
+            content_type: "text/plain".into(),
+            certificate: None,
+        })
+    }
+
+    #[test]
+    fn robots_rules_for_our_agent() {
+        let rules = RobotsRules::parse(ROBOTS, ROBOTS_AGENT);
+        assert!(rules.allows("/"));
+        assert!(rules.allows("/news/today"));
+        assert!(!rules.allows("/private/notes"));
+        assert!(rules.allows("/private/open"));
+        assert!(!rules.allows("/private/open/more"));
+        assert!(!rules.allows("/papers/a.pdf"));
+        assert!(rules.allows("/papers/a.pdf?view=1"));
+        assert_eq!(rules.crawl_delay, Some(Duration::from_secs(2)));
+
+        let bad = RobotsRules::parse(ROBOTS, "BadBot/1.0");
+        assert!(!bad.allows("/news/today"));
+        assert_eq!(bad.crawl_delay, None);
+
+        assert!(RobotsRules::parse("", ROBOTS_AGENT).allows("/anything"));
+    }
+
+    #[test]
+    fn crawl_delay_spaces_requests_per_origin() {
+        let policy = PrefetchPolicy::default();
+        let mut state = State::default();
+        state.origins.insert(
+            "https://a.example".into(),
+            Origin {
+                robots: Some(RobotsRules::parse(ROBOTS, ROBOTS_AGENT)),
+                ..Origin::default()
+            },
+        );
+        let now = Instant::now();
+        let admit =
+            |state: &mut State, origin: &str, path: &str| policy.admit(state, origin, path, now);
+        assert_eq!(
+            admit(&mut state, "https://a.example", "/1"),
+            Ok(Duration::ZERO)
+        );
+        assert_eq!(
+            admit(&mut state, "https://a.example", "/2"),
+            Ok(Duration::from_secs(2))
+        );
+        assert_eq!(
+            admit(&mut state, "https://a.example", "/private/x"),
+            Err(SkipReason::Disallowed)
+        );
+        // Other origins only keep the minimum spacing
+        assert_eq!(
+            admit(&mut state, "https://b.example", "/1"),
+            Ok(Duration::ZERO)
+        );
+        assert_eq!(
+            admit(&mut state, "https://b.example", "/2"),
+            Ok(policy.config().min_interval)
+        );
+    }
+
+    #[test]
+    fn dedupes_and_backs_off_on_429() {
+        let policy = PrefetchPolicy::new(PrefetchConfig {
+            min_interval: Duration::ZERO,
+            ..PrefetchConfig::default()
+        });
+        let fetch = |url: &str| {
+            if url.ends_with("/robots.txt") {
+                page(404, "")
+            } else if url.contains("busy") {
+                page(429, "slow down")
+            } else {
+                page(200, "<p>ok</p>")
+            }
+        };
+
+        assert!(matches!(
+            policy.fetch("https://a.example/one", fetch),
+            Outcome::Fetched(_)
+        ));
+        assert!(matches!(
+            policy.fetch("https://a.example/one", fetch),
+            Outcome::Skipped(SkipReason::Duplicate)
+        ));
+        assert!(matches!(
+            policy.fetch("https://a.example/busy", fetch),
+            Outcome::Failed(_)
+        ));
+        assert!(matches!(
+            policy.fetch("https://a.example/two", fetch),
+            Outcome::Skipped(SkipReason::BackingOff)
+        ));
+        assert!(matches!(
+            policy.fetch("mailto:someone@example.com", fetch),
+            Outcome::Skipped(SkipReason::Unsupported)
+        ));
+
+        let stats = policy.stats();
+        assert_eq!(
+            (
+                stats.fetched,
+                stats.duplicates,
+                stats.throttled,
+                stats.deferred
+            ),
+            (1, 1, 1, 1)
+        );
+        assert_eq!(stats.in_flight, 0);
+    }
+}
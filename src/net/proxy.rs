@@ -0,0 +1,358 @@
+//! Proxy configuration.
+//!
+//! Every request the browser makes — page fetches through [`fetch_url`],
+//! image downloads and background link prefetch — goes through a client
+//! from [`client_builder`], which routes it by the process-wide
+//! [`ProxyConfig`]. Until [`set_shared`] is called that is
+//! [`ProxyConfig::from_env`]: `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and
+//! `NO_PROXY`, as curl reads them.
+//!
+//! Proxies may be `http://`, `https://`, `socks5://` or `socks5h://` (DNS
+//! resolved by the proxy); a bare `host:port` means HTTP. Credentials are
+//! sent as Basic auth to HTTP proxies and as username/password auth to
+//! SOCKS5 ones. Hosts on the bypass list are reached directly.
+//!
+//! [`fetch_url`]: super::fetch::fetch_url
+
+use std::sync::{OnceLock, RwLock};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Where the proxy settings come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ProxyMode {
+    /// The `*_PROXY` environment variables
+    #[default]
+    #[serde(rename = "system")]
+    System,
+    /// Proxy, credentials and bypass list given explicitly
+    #[serde(rename = "manual")]
+    Manual,
+    /// Connect directly, ignoring the environment
+    #[serde(rename = "off")]
+    Off,
+}
+
+impl ProxyMode {
+    pub const ALL: [Self; 3] = [Self::System, Self::Manual, Self::Off];
+
+    /// Stable identifier, as stored in settings and used in `alice://` URLs.
+    #[must_use]
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Manual => "manual",
+            Self::Off => "off",
+        }
+    }
+
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.key() == key)
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::System => "From environment (HTTP_PROXY, …)",
+            Self::Manual => "Manual",
+            Self::Off => "No proxy",
+        }
+    }
+}
+
+/// Proxies to use, by URL scheme, and the hosts that bypass them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// Proxy for `http://` URLs
+    pub http: Option<Url>,
+    /// Proxy for `https://` URLs
+    pub https: Option<Url>,
+    /// Hosts reached directly, as in `NO_PROXY`
+    pub bypass: Vec<String>,
+}
+
+impl ProxyConfig {
+    /// Connect directly.
+    pub const DIRECT: Self = Self {
+        http: None,
+        https: None,
+        bypass: Vec::new(),
+    };
+
+    /// Read the proxy environment variables of this process.
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Read `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY` through
+    /// `var`, upper case first, then lower case. Unparsable values are
+    /// ignored.
+    #[must_use]
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let get = |name: &str| {
+            var(name)
+                .or_else(|| var(&name.to_ascii_lowercase()))
+                .filter(|v| !v.trim().is_empty())
+        };
+        let all = get("ALL_PROXY").and_then(|v| parse_proxy_url(&v).ok());
+        let http = get("HTTP_PROXY")
+            .and_then(|v| parse_proxy_url(&v).ok())
+            .or_else(|| all.clone());
+        let https = get("HTTPS_PROXY")
+            .and_then(|v| parse_proxy_url(&v).ok())
+            .or(all);
+        Self {
+            http,
+            https,
+            bypass: get("NO_PROXY").map_or_else(Vec::new, |v| parse_bypass(&v)),
+        }
+    }
+
+    /// One proxy for every scheme, with optional credentials and a bypass
+    /// list of hosts separated by commas or whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if `proxy` is not a usable proxy address.
+    pub fn manual(
+        proxy: &str,
+        username: &str,
+        password: &str,
+        bypass: &str,
+    ) -> Result<Self, String> {
+        let mut url = parse_proxy_url(proxy)?;
+        if !username.is_empty() {
+            url.set_username(username)
+                .and_then(|()| url.set_password(Some(password).filter(|p| !p.is_empty())))
+                .map_err(|()| format!("{proxy}: cannot carry credentials"))?;
+        }
+        Ok(Self {
+            http: Some(url.clone()),
+            https: Some(url),
+            bypass: parse_bypass(bypass),
+        })
+    }
+
+    /// The proxy a request for `url` goes through, or `None` to connect
+    /// directly.
+    #[must_use]
+    pub fn proxy_for(&self, url: &Url) -> Option<&Url> {
+        let proxy = match url.scheme() {
+            "http" => self.http.as_ref(),
+            "https" => self.https.as_ref(),
+            _ => None,
+        }?;
+        let host = url.host_str()?;
+        (!self.bypasses(host)).then_some(proxy)
+    }
+
+    /// Whether `host` is on the bypass list: `*` matches every host, a name
+    /// matches itself and its subdomains (a leading `.` or `*.` is
+    /// optional), and an IP address matches only itself.
+    #[must_use]
+    pub fn bypasses(&self, host: &str) -> bool {
+        let host = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+        self.bypass.iter().any(|entry| {
+            entry == "*"
+                || host == *entry
+                || host
+                    .strip_suffix(entry.as_str())
+                    .is_some_and(|rest| rest.ends_with('.'))
+        })
+    }
+
+    /// Whether every request connects directly.
+    #[must_use]
+    pub const fn is_direct(&self) -> bool {
+        self.http.is_none() && self.https.is_none()
+    }
+
+    /// One line for display, without credentials: "direct", or the
+    /// proxies and the number of bypassed hosts.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let show = |proxy: &Url| {
+            let mut shown = proxy.clone();
+            let _ = shown.set_username("");
+            let _ = shown.set_password(None);
+            shown.as_str().trim_end_matches('/').to_string()
+        };
+        let proxies = match (&self.http, &self.https) {
+            (None, None) => return "direct".into(),
+            (Some(http), Some(https)) if http == https => show(http),
+            (http, https) => format!(
+                "{} for http, {} for https",
+                http.as_ref().map_or_else(|| "direct".into(), show),
+                https.as_ref().map_or_else(|| "direct".into(), show)
+            ),
+        };
+        match self.bypass.len() {
+            0 => proxies,
+            n => format!("{proxies}, bypassed for {n} hosts"),
+        }
+    }
+
+    /// Route requests of `builder`'s client by this configuration.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn apply(
+        &self,
+        builder: reqwest::blocking::ClientBuilder,
+    ) -> reqwest::blocking::ClientBuilder {
+        // Drop reqwest's own reading of the environment; `from_env` covers it
+        let builder = builder.no_proxy();
+        if self.is_direct() {
+            return builder;
+        }
+        let config = self.clone();
+        builder.proxy(reqwest::Proxy::custom(move |url| {
+            config.proxy_for(url).cloned()
+        }))
+    }
+}
+
+/// Parse a proxy address; a bare `host:port` means an HTTP proxy.
+///
+/// # Errors
+///
+/// Returns a message if the address does not parse, has no host or names
+/// an unsupported scheme.
+pub fn parse_proxy_url(proxy: &str) -> Result<Url, String> {
+    let proxy = proxy.trim();
+    let url = if proxy.contains("://") {
+        Url::parse(proxy)
+    } else {
+        Url::parse(&format!("http://{proxy}"))
+    }
+    .map_err(|e| format!("{proxy}: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+        return Err(format!(
+            "{proxy}: unsupported proxy scheme {}",
+            url.scheme()
+        ));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err(format!("{proxy}: no host"));
+    }
+    Ok(url)
+}
+
+/// Split a `NO_PROXY`-style list, dropping ports and the leading `.` or
+/// `*.` of domain entries.
+fn parse_bypass(list: &str) -> Vec<String> {
+    list.split(|c: char| c == ',' || c.is_whitespace())
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(|e| {
+            let e = e.to_ascii_lowercase();
+            // "host:port" and "[::1]:port"; a bare IPv6 address keeps its colons
+            let host = match e.strip_prefix('[') {
+                Some(rest) => rest.split(']').next().unwrap_or_default().to_string(),
+                None if e.matches(':').count() == 1 => {
+                    e.split(':').next().unwrap_or_default().to_string()
+                }
+                None => e,
+            };
+            let host = host.strip_prefix("*.").unwrap_or(&host);
+            host.trim_start_matches('.')
+                .trim_end_matches('.')
+                .to_string()
+        })
+        .filter(|e| !e.is_empty())
+        .collect()
+}
+
+fn shared_config() -> &'static RwLock<ProxyConfig> {
+    static CONFIG: OnceLock<RwLock<ProxyConfig>> = OnceLock::new();
+    CONFIG.get_or_init(|| RwLock::new(ProxyConfig::from_env()))
+}
+
+/// Process-wide configuration used by [`client_builder`].
+#[must_use]
+pub fn shared() -> ProxyConfig {
+    shared_config()
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone()
+}
+
+/// Replace the process-wide configuration; clients built afterwards use it.
+/// Connections opened ahead by [`super::speculate`] are dropped.
+pub fn set_shared(config: ProxyConfig) {
+    *shared_config()
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = config;
+    #[cfg(not(target_arch = "wasm32"))]
+    super::speculate::shared().clear();
+}
+
+/// A client builder routed by the process-wide configuration.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn client_builder() -> reqwest::blocking::ClientBuilder {
+    shared().apply(reqwest::blocking::Client::builder())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn environment_variables() {
+        let vars = |name: &str| {
+            match name {
+                "http_proxy" => Some("proxy.lan:3128"),
+                "ALL_PROXY" => Some("socks5h://gate:1080"),
+                "NO_PROXY" => Some("localhost, .internal:8080,10.0.0.1"),
+                _ => None,
+            }
+            .map(String::from)
+        };
+        let config = ProxyConfig::from_vars(vars);
+        assert_eq!(config.http, Some(url("http://proxy.lan:3128")));
+        assert_eq!(config.https, Some(url("socks5h://gate:1080")));
+        assert_eq!(config.bypass, ["localhost", "internal", "10.0.0.1"]);
+
+        let to = |s: &str| config.proxy_for(&url(s)).map(Url::as_str);
+        assert_eq!(to("http://example.com/"), Some("http://proxy.lan:3128/"));
+        assert_eq!(to("https://example.com/"), Some("socks5h://gate:1080"));
+        assert_eq!(to("http://wiki.internal/"), None);
+        assert_eq!(to("http://localhost:8000/"), None);
+        assert_eq!(to("http://10.0.0.1/"), None);
+        assert_eq!(to("http://notinternal/"), Some("http://proxy.lan:3128/"));
+        assert_eq!(to("ftp://example.com/"), None);
+
+        assert_eq!(
+            config.summary(),
+            "http://proxy.lan:3128 for http, socks5h://gate:1080 for https, bypassed for 3 hosts"
+        );
+        assert!(ProxyConfig::from_vars(|_| None).is_direct());
+    }
+
+    #[test]
+    fn manual_with_credentials_and_bypass() {
+        let config =
+            ProxyConfig::manual("socks5://gate:1080", "ann", "s3cret", "*.corp [::1]:80").unwrap();
+        let proxy = config.proxy_for(&url("https://example.com/")).unwrap();
+        assert_eq!(proxy.username(), "ann");
+        assert_eq!(proxy.password(), Some("s3cret"));
+        assert_eq!(config.summary(), "socks5://gate:1080, bypassed for 2 hosts");
+        assert!(config.bypasses("intranet.corp"));
+        assert!(config.bypasses("[::1]"));
+        assert!(!config.bypasses("corp.example"));
+
+        assert!(ProxyConfig::manual("ftp://gate", "", "", "").is_err());
+        assert!(ProxyConfig::manual("", "", "", "").is_err());
+        let everything = ProxyConfig::manual("gate:8080", "", "", "*").unwrap();
+        assert!(everything.proxy_for(&url("http://example.com/")).is_none());
+    }
+}
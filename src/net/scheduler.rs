@@ -0,0 +1,334 @@
+//! Prioritized fetch queue with cancellation.
+//!
+//! Fetches — the page, its images, link previews with their favicons,
+//! prefetches of linked pages and the lookups the UI makes in the
+//! background — are submitted to a [`Scheduler`] as jobs
+//! with a [`Priority`] and a [`CancelToken`]. A job waits until a slot is
+//! free: at most [`MAX_CONCURRENT`] jobs run at once and at most
+//! [`MAX_PER_HOST`] against one host, so a gallery of images can neither
+//! hold up the page nor flood one server. The waiting job of highest
+//! priority whose host has room runs next; jobs of one priority run in the
+//! order they were submitted.
+//!
+//! Cancelling a token drops its waiting jobs without ever starting them.
+//! Running jobs are handed their token and check it between chunks of
+//! work. Tokens form a tree ([`CancelToken::child`]): navigating away
+//! cancels the page's token, and with it everything started for the page.
+//! Jobs run on the shared [`workers`] pool, whose threads outnumber the
+//! scheduler's slots; a scheduler of its own pool ([`Scheduler::with_pool`])
+//! serves fetches a job of the shared pool waits for, such as a page's
+//! frames.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+
+use url::Url;
+
+use crate::engine::workers::{self, CancelToken, WorkerPool};
+
+/// Jobs running at once.
+pub const MAX_CONCURRENT: usize = 16;
+
+/// Jobs running at once against one host.
+pub const MAX_PER_HOST: usize = 6;
+
+/// What a fetch is for, lowest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Background work nobody waits on: a linked page fetched ahead of a
+    /// click, an origin warmed up, the pages of a session list
+    Prefetch,
+    /// A lookup the UI waits on: a link preview and its favicon, search
+    /// suggestions, media probes, an archived copy, a site map, a save
+    Preview,
+    /// An image near the viewport
+    Image,
+    /// The page being navigated to
+    Page,
+}
+
+type Job = Box<dyn FnOnce(&CancelToken) + Send>;
+
+struct Queued {
+    priority: Priority,
+    /// Submission order
+    seq: u64,
+    host: String,
+    token: CancelToken,
+    job: Job,
+}
+
+#[derive(Default)]
+struct State {
+    queue: Vec<Queued>,
+    running: usize,
+    per_host: HashMap<String, usize>,
+    next_seq: u64,
+}
+
+/// Runs submitted jobs by priority within concurrency limits.
+#[derive(Clone)]
+pub struct Scheduler {
+    state: Arc<Mutex<State>>,
+    max_concurrent: usize,
+    max_per_host: usize,
+    /// Runs the jobs
+    pool: WorkerPool,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new(MAX_CONCURRENT, MAX_PER_HOST)
+    }
+}
+
+impl Scheduler {
+    /// Scheduler running its jobs on the shared pool.
+    #[must_use]
+    pub fn new(max_concurrent: usize, max_per_host: usize) -> Self {
+        Self::with_pool(max_concurrent, max_per_host, workers::shared().clone())
+    }
+
+    /// Scheduler running its jobs on `pool`.
+    #[must_use]
+    pub fn with_pool(max_concurrent: usize, max_per_host: usize, pool: WorkerPool) -> Self {
+        Self {
+            state: Arc::default(),
+            max_concurrent: max_concurrent.max(1),
+            max_per_host: max_per_host.max(1),
+            pool,
+        }
+    }
+
+    /// Queue `job`, a fetch of `url`, to run on a worker thread once a
+    /// slot is free — unless `token` is cancelled first.
+    pub fn submit(
+        &self,
+        url: &str,
+        priority: Priority,
+        token: &CancelToken,
+        job: impl FnOnce(&CancelToken) + Send + 'static,
+    ) {
+        {
+            let mut state = lock(&self.state);
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.queue.push(Queued {
+                priority,
+                seq,
+                host: host_of(url),
+                token: token.clone(),
+                job: Box::new(job),
+            });
+        }
+        self.pump();
+    }
+
+    /// Jobs waiting for a slot.
+    #[must_use]
+    pub fn queued(&self) -> usize {
+        lock(&self.state).queue.len()
+    }
+
+    /// Jobs running.
+    #[must_use]
+    pub fn running(&self) -> usize {
+        lock(&self.state).running
+    }
+
+    /// Start waiting jobs while there are free slots, dropping cancelled
+    /// ones.
+    fn pump(&self) {
+        let mut state = lock(&self.state);
+        state.queue.retain(|q| !q.token.is_cancelled());
+        while state.running < self.max_concurrent {
+            let next = state
+                .queue
+                .iter()
+                .enumerate()
+                .filter(|(_, q)| {
+                    state.per_host.get(&q.host).copied().unwrap_or(0) < self.max_per_host
+                })
+                .max_by(|(_, a), (_, b)| a.priority.cmp(&b.priority).then(b.seq.cmp(&a.seq)))
+                .map(|(i, _)| i);
+            let Some(i) = next else {
+                break;
+            };
+            let Queued {
+                host, token, job, ..
+            } = state.queue.swap_remove(i);
+            state.running += 1;
+            *state.per_host.entry(host.clone()).or_default() += 1;
+
+            let slot = Slot {
+                scheduler: self.clone(),
+                host,
+            };
+            // The slot is freed when the closure is dropped: after the job,
+            // if it panics, or unrun if the token is cancelled meanwhile
+            self.pool.spawn_with(&token, move |token| {
+                let _slot = slot;
+                job(token);
+            });
+        }
+    }
+
+    fn finish(&self, host: &str) {
+        {
+            let mut state = lock(&self.state);
+            state.running = state.running.saturating_sub(1);
+            if let Some(n) = state.per_host.get_mut(host) {
+                *n -= 1;
+                if *n == 0 {
+                    state.per_host.remove(host);
+                }
+            }
+        }
+        self.pump();
+    }
+}
+
+/// A running job's claim on the scheduler, given back when dropped.
+struct Slot {
+    scheduler: Scheduler,
+    host: String,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.scheduler.finish(&self.host);
+    }
+}
+
+/// Host a job for `url` counts against; `data:` and `file:` URLs share the
+/// empty host.
+fn host_of(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Process-wide scheduler every fetch goes through.
+#[must_use]
+pub fn shared() -> &'static Scheduler {
+    static SCHEDULER: OnceLock<Scheduler> = OnceLock::new();
+    SCHEDULER.get_or_init(Scheduler::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+
+    const WAIT: Duration = Duration::from_secs(5);
+
+    /// Wait until the scheduler has nothing running.
+    fn settle(scheduler: &Scheduler) {
+        let start = Instant::now();
+        while scheduler.running() > 0 && start.elapsed() < WAIT {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn higher_priorities_run_first() {
+        let scheduler = Scheduler::new(1, 1);
+        let token = CancelToken::new();
+        let (release, gate) = mpsc::channel::<()>();
+        scheduler.submit("https://a.example/", Priority::Page, &token, move |_| {
+            let _ = gate.recv_timeout(WAIT);
+        });
+
+        let (done, order) = mpsc::channel();
+        for (url, priority) in [
+            ("https://a.example/prefetch", Priority::Prefetch),
+            ("https://a.example/page", Priority::Page),
+            ("https://a.example/first.png", Priority::Image),
+            ("https://a.example/second.png", Priority::Image),
+            ("https://a.example/card", Priority::Preview),
+        ] {
+            let done = done.clone();
+            scheduler.submit(url, priority, &token, move |_| {
+                let _ = done.send(url);
+            });
+        }
+        assert_eq!(scheduler.queued(), 5);
+        release.send(()).unwrap();
+
+        let ran: Vec<&str> = (0..5).map(|_| order.recv_timeout(WAIT).unwrap()).collect();
+        assert_eq!(
+            ran,
+            [
+                "https://a.example/page",
+                "https://a.example/first.png",
+                "https://a.example/second.png",
+                "https://a.example/card",
+                "https://a.example/prefetch",
+            ]
+        );
+    }
+
+    #[test]
+    fn hosts_are_limited_separately() {
+        let scheduler = Scheduler::new(4, 1);
+        let token = CancelToken::new();
+        let (release, gate) = mpsc::channel::<()>();
+        let gate = Arc::new(Mutex::new(gate));
+        for url in [
+            "https://a.example/1.png",
+            "https://a.example/2.png",
+            "https://b.example/1.png",
+        ] {
+            let gate = Arc::clone(&gate);
+            scheduler.submit(url, Priority::Image, &token, move |_| {
+                let _ = gate.lock().unwrap().recv_timeout(WAIT);
+            });
+        }
+        assert_eq!(scheduler.running(), 2);
+        assert_eq!(scheduler.queued(), 1);
+        for _ in 0..3 {
+            release.send(()).unwrap();
+        }
+        settle(&scheduler);
+        assert_eq!((scheduler.running(), scheduler.queued()), (0, 0));
+    }
+
+    #[test]
+    fn cancelled_jobs_never_start() {
+        let scheduler = Scheduler::new(1, 1);
+        let (release, gate) = mpsc::channel::<()>();
+        scheduler.submit(
+            "https://a.example/",
+            Priority::Page,
+            &CancelToken::new(),
+            move |_| {
+                let _ = gate.recv_timeout(WAIT);
+            },
+        );
+
+        let page = CancelToken::new();
+        let image = page.child();
+        let started = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let flag = Arc::clone(&started);
+        scheduler.submit(
+            "https://a.example/a.png",
+            Priority::Image,
+            &image,
+            move |_| flag.store(true, std::sync::atomic::Ordering::Relaxed),
+        );
+        page.cancel();
+        assert!(image.is_cancelled());
+        assert!(!CancelToken::new().child().is_cancelled());
+
+        release.send(()).unwrap();
+        settle(&scheduler);
+        assert!(!started.load(std::sync::atomic::Ordering::Relaxed));
+        assert_eq!(scheduler.queued(), 0);
+    }
+}
@@ -0,0 +1,382 @@
+//! Web searches from the URL bar, pasted text and OZ mode.
+//!
+//! A [`SearchEngine`] preset, or a custom URL template with `%s` where the
+//! query goes, makes a [`WebSearch`], which turns queries into the address
+//! of a results page. [`is_search_query`] tells text typed into the URL bar
+//! that should be searched for from an address to open.
+//!
+//! Engines with an OpenSearch suggestion endpoint — a JSON array of the
+//! query and its completions — also complete queries as they are typed
+//! ([`WebSearch::suggestions`]).
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded::byte_serialize;
+
+use super::fetch::{fetch_url_with, FetchError, FetchOptions};
+use super::paste::looks_like_url;
+
+/// Where the query goes in a search template.
+pub const QUERY_PLACEHOLDER: &str = "%s";
+
+/// Most completions taken from a suggestion endpoint.
+pub const MAX_SUGGESTIONS: usize = 8;
+
+/// Time allowed for a suggestion request; a late answer is no longer
+/// wanted.
+pub const SUGGEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Largest suggestion response read.
+const MAX_SUGGEST_BYTES: usize = 64 * 1024;
+
+/// Search engine choices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SearchEngine {
+    /// DuckDuckGo's HTML endpoint, which needs no JavaScript, so its
+    /// results render in every mode
+    #[default]
+    #[serde(rename = "duckduckgo")]
+    DuckDuckGo,
+    #[serde(rename = "startpage")]
+    Startpage,
+    #[serde(rename = "mojeek")]
+    Mojeek,
+    #[serde(rename = "google")]
+    Google,
+    /// Wikipedia's own search; exact titles open the article
+    #[serde(rename = "wikipedia")]
+    Wikipedia,
+    /// A URL template of the user's
+    #[serde(rename = "custom")]
+    Custom,
+}
+
+impl SearchEngine {
+    pub const ALL: [Self; 6] = [
+        Self::DuckDuckGo,
+        Self::Startpage,
+        Self::Mojeek,
+        Self::Google,
+        Self::Wikipedia,
+        Self::Custom,
+    ];
+
+    /// Stable identifier, as stored in settings and used in `alice://` URLs.
+    #[must_use]
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::DuckDuckGo => "duckduckgo",
+            Self::Startpage => "startpage",
+            Self::Mojeek => "mojeek",
+            Self::Google => "google",
+            Self::Wikipedia => "wikipedia",
+            Self::Custom => "custom",
+        }
+    }
+
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.key() == key)
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::DuckDuckGo => "DuckDuckGo",
+            Self::Startpage => "Startpage",
+            Self::Mojeek => "Mojeek",
+            Self::Google => "Google",
+            Self::Wikipedia => "Wikipedia",
+            Self::Custom => "Custom…",
+        }
+    }
+
+    /// URL template of a preset; `None` for [`Custom`](Self::Custom).
+    #[must_use]
+    pub const fn template(self) -> Option<&'static str> {
+        match self {
+            Self::DuckDuckGo => Some("https://html.duckduckgo.com/html/?q=%s"),
+            Self::Startpage => Some("https://www.startpage.com/do/search?q=%s"),
+            Self::Mojeek => Some("https://www.mojeek.com/search?q=%s"),
+            Self::Google => Some("https://www.google.com/search?q=%s"),
+            Self::Wikipedia => Some("https://en.wikipedia.org/w/index.php?search=%s"),
+            Self::Custom => None,
+        }
+    }
+
+    /// OpenSearch suggestion endpoint of a preset, if it has one.
+    #[must_use]
+    pub const fn suggest_template(self) -> Option<&'static str> {
+        match self {
+            Self::DuckDuckGo => Some("https://duckduckgo.com/ac/?q=%s&type=list"),
+            Self::Startpage => Some("https://www.startpage.com/osuggestions?q=%s"),
+            Self::Google => {
+                Some("https://suggestqueries.google.com/complete/search?client=firefox&q=%s")
+            }
+            Self::Wikipedia => {
+                Some("https://en.wikipedia.org/w/api.php?action=opensearch&format=json&search=%s")
+            }
+            Self::Mojeek | Self::Custom => None,
+        }
+    }
+}
+
+/// Whether `template` is usable as a custom search engine: an `http` or
+/// `https` URL with [`QUERY_PLACEHOLDER`] in it.
+#[must_use]
+pub fn is_valid_template(template: &str) -> bool {
+    let template = template.trim();
+    template.contains(QUERY_PLACEHOLDER)
+        && url::Url::parse(&template.replace(QUERY_PLACEHOLDER, "q"))
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+/// A search engine, ready to turn queries into results page URLs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebSearch {
+    template: String,
+    suggest: Option<String>,
+}
+
+impl Default for WebSearch {
+    fn default() -> Self {
+        Self::new(SearchEngine::default(), "")
+    }
+}
+
+impl WebSearch {
+    /// Search with `engine`, or for [`SearchEngine::Custom`] with the URL
+    /// `template`; an unusable template searches with the default engine.
+    #[must_use]
+    pub fn new(engine: SearchEngine, template: &str) -> Self {
+        let engine = match engine {
+            SearchEngine::Custom if is_valid_template(template) => {
+                return Self {
+                    template: template.trim().to_string(),
+                    suggest: None,
+                };
+            }
+            SearchEngine::Custom => SearchEngine::default(),
+            preset => preset,
+        };
+        Self {
+            template: engine.template().unwrap_or_default().to_string(),
+            suggest: engine.suggest_template().map(str::to_string),
+        }
+    }
+
+    /// Take suggestions from the OpenSearch endpoint `template` (with `%s`
+    /// for the query) instead; ignored if the template is unusable.
+    #[must_use]
+    pub fn with_suggest_template(mut self, template: &str) -> Self {
+        if is_valid_template(template) {
+            self.suggest = Some(template.trim().to_string());
+        }
+        self
+    }
+
+    /// The URL template searched with.
+    #[must_use]
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// Results page for `query`.
+    #[must_use]
+    pub fn url(&self, query: &str) -> String {
+        fill_template(&self.template, query)
+    }
+
+    /// Whether the engine suggests completions.
+    #[must_use]
+    pub fn suggests(&self) -> bool {
+        self.suggest.is_some()
+    }
+
+    /// Suggestion request for `query`, if the engine has an endpoint.
+    #[must_use]
+    pub fn suggest_url(&self, query: &str) -> Option<String> {
+        self.suggest.as_ref().map(|t| fill_template(t, query))
+    }
+
+    /// Ask the engine to complete `query`: at most [`MAX_SUGGESTIONS`],
+    /// none if it has no suggestion endpoint. Blocks for up to
+    /// [`SUGGEST_TIMEOUT`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the fetch error if the endpoint could not be reached.
+    pub fn suggestions(
+        &self,
+        query: &str,
+        options: &FetchOptions,
+    ) -> Result<Vec<String>, FetchError> {
+        let Some(url) = self.suggest_url(query) else {
+            return Ok(Vec::new());
+        };
+        let mut options = options.clone();
+        options.timeouts.request = options.timeouts.request.min(SUGGEST_TIMEOUT);
+        options.timeouts.connect = options.timeouts.connect.min(SUGGEST_TIMEOUT);
+        options.max_body_bytes = MAX_SUGGEST_BYTES;
+        let result = fetch_url_with(&url, &options)?;
+        if result.status != 200 {
+            return Ok(Vec::new());
+        }
+        Ok(parse_suggestions(&result.html, query))
+    }
+}
+
+/// `template` with `query` in place of [`QUERY_PLACEHOLDER`]: form-encoded
+/// in the URL's query string (spaces as `+`), percent-encoded in its path.
+fn fill_template(template: &str, query: &str) -> String {
+    let form: String = byte_serialize(query.trim().as_bytes()).collect();
+    let in_query = template
+        .split_once(QUERY_PLACEHOLDER)
+        .is_some_and(|(head, _)| head.contains('?'));
+    let encoded = if in_query {
+        form
+    } else {
+        form.replace('+', "%20")
+    };
+    template.replace(QUERY_PLACEHOLDER, &encoded)
+}
+
+/// Completions from an OpenSearch suggestion response,
+/// `["query", ["completion", …], …]`: without blanks, duplicates or the
+/// query itself, at most [`MAX_SUGGESTIONS`]. Anything else yields none.
+#[must_use]
+pub fn parse_suggestions(body: &str, query: &str) -> Vec<String> {
+    let Ok(serde_json::Value::Array(parts)) = serde_json::from_str(body.trim()) else {
+        return Vec::new();
+    };
+    let Some(serde_json::Value::Array(items)) = parts.get(1) else {
+        return Vec::new();
+    };
+    let query = query.trim();
+    let mut out: Vec<String> = Vec::new();
+    for item in items.iter().filter_map(serde_json::Value::as_str) {
+        let item = item.trim();
+        if item.is_empty()
+            || item.eq_ignore_ascii_case(query)
+            || out.iter().any(|s| s.eq_ignore_ascii_case(item))
+        {
+            continue;
+        }
+        out.push(item.to_string());
+        if out.len() == MAX_SUGGESTIONS {
+            break;
+        }
+    }
+    out
+}
+
+/// Whether text typed into the URL bar is a search rather than an
+/// address: it has spaces, or neither reads as an address nor parses as a
+/// URL of any scheme (`file:`, `data:`).
+#[must_use]
+pub fn is_search_query(text: &str) -> bool {
+    let text = text.trim();
+    !text.is_empty()
+        && (text.chars().any(char::is_whitespace)
+            || (!looks_like_url(text) && url::Url::parse(text).is_err()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_and_custom_templates_build_result_urls() {
+        assert_eq!(
+            WebSearch::default().url("a b&c"),
+            "https://html.duckduckgo.com/html/?q=a+b%26c"
+        );
+        assert_eq!(
+            WebSearch::new(SearchEngine::Google, "ignored").url(" Kyoto "),
+            "https://www.google.com/search?q=Kyoto"
+        );
+        // Custom templates: in the query string or a path segment
+        let custom = WebSearch::new(SearchEngine::Custom, " https://s.example/?lang=en&q=%s ");
+        assert_eq!(
+            custom.url("東京 tower"),
+            "https://s.example/?lang=en&q=%E6%9D%B1%E4%BA%AC+tower"
+        );
+        let path = WebSearch::new(SearchEngine::Custom, "https://wiki.example/search/%s");
+        assert_eq!(path.url("a b+c"), "https://wiki.example/search/a%20b%2Bc");
+        // Unusable templates fall back to the default engine
+        for bad in [
+            "",
+            "https://s.example/?q=",
+            "ftp://s.example/%s",
+            "not a url %s",
+        ] {
+            assert!(!is_valid_template(bad), "{bad}");
+            assert_eq!(
+                WebSearch::new(SearchEngine::Custom, bad),
+                WebSearch::default()
+            );
+        }
+        for engine in SearchEngine::ALL {
+            assert_eq!(SearchEngine::from_key(engine.key()), Some(engine));
+            assert!(engine.template().is_none_or(is_valid_template));
+            assert!(engine.suggest_template().is_none_or(is_valid_template));
+        }
+    }
+
+    #[test]
+    fn suggestion_endpoints_follow_the_engine() {
+        assert_eq!(
+            WebSearch::default().suggest_url("rust ai").as_deref(),
+            Some("https://duckduckgo.com/ac/?q=rust+ai&type=list")
+        );
+        assert!(!WebSearch::new(SearchEngine::Mojeek, "").suggests());
+        // Custom engines suggest only with an endpoint of their own
+        let custom = WebSearch::new(SearchEngine::Custom, "https://s.example/?q=%s");
+        assert!(!custom.suggests());
+        let custom = custom.with_suggest_template("https://s.example/complete?q=%s");
+        assert_eq!(
+            custom.suggest_url("x").as_deref(),
+            Some("https://s.example/complete?q=x")
+        );
+        // A preset's endpoint is kept over an unusable one
+        let google = WebSearch::new(SearchEngine::Google, "").with_suggest_template("oops");
+        assert!(google.suggest_url("q").unwrap().contains("suggestqueries"));
+    }
+
+    #[test]
+    fn opensearch_responses_are_parsed_leniently() {
+        let body = r#"["kyo",["kyoto","Kyoto"," kyoto station ","","kyo"],["desc"],["urls"]]"#;
+        assert_eq!(parse_suggestions(body, "kyo"), ["kyoto", "kyoto station"]);
+        let many: Vec<String> = (0..20).map(|i| format!("\"q{i}\"")).collect();
+        let body = format!("[\"q\",[{}]]", many.join(","));
+        assert_eq!(parse_suggestions(&body, "q").len(), MAX_SUGGESTIONS);
+        for junk in [
+            "",
+            "{}",
+            "[\"q\"]",
+            "[\"q\", \"kyoto\"]",
+            "<html>",
+            "[\"q\",[1,null]]",
+        ] {
+            assert!(parse_suggestions(junk, "q").is_empty(), "{junk}");
+        }
+    }
+
+    #[test]
+    fn url_bar_text_that_is_not_an_address_is_searched() {
+        for query in ["rust borrow checker", "kyoto", "e.g.", "  what is 3.14?  "] {
+            assert!(is_search_query(query), "{query}");
+        }
+        for address in [
+            "example.com",
+            "localhost:8080",
+            "file:///tmp/page.html",
+            "data:text/html,hi",
+            "alice://settings",
+            "",
+        ] {
+            assert!(!is_search_query(address), "{address}");
+        }
+    }
+}
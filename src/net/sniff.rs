@@ -0,0 +1,352 @@
+//! Content-type and text-encoding sniffing.
+//!
+//! Servers label text resources loosely: JSON arrives as `text/plain`, CSV
+//! as `application/octet-stream`, and some responses carry no type at all.
+//! [`sniff`] trusts a specific declared type, then the URL's extension,
+//! then the first bytes of the body. [`decode`] turns a body into text the
+//! way browsers pick a charset: byte-order mark, then the `charset`
+//! parameter, then an in-document declaration, then UTF-8 if the bytes are
+//! valid UTF-8 and windows-1252 otherwise.
+//!
+//! Audio and video get the same treatment for their placeholder cards
+//! (see [`crate::render::media`]): [`media_type`] takes a known declared
+//! type, then the extension, then the container's signature.
+
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+
+/// How a response is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    Html,
+    Json,
+    /// Comma-separated values
+    Csv,
+    /// Tab-separated values
+    Tsv,
+    Xml,
+    /// Any other text
+    Text,
+}
+
+impl ResourceKind {
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Html => "HTML",
+            Self::Json => "JSON",
+            Self::Csv => "CSV",
+            Self::Tsv => "TSV",
+            Self::Xml => "XML",
+            Self::Text => "Text",
+        }
+    }
+
+    /// Kind for a MIME essence (`type/subtype`, lowercase); `None` when
+    /// the type says nothing useful.
+    fn from_mime(essence: &str) -> Option<Self> {
+        Some(match essence {
+            "text/html" | "application/xhtml+xml" => Self::Html,
+            "application/json" | "text/json" | "application/ld+json" => Self::Json,
+            "text/csv" | "application/csv" => Self::Csv,
+            "text/tab-separated-values" => Self::Tsv,
+            "text/xml" | "application/xml" | "application/rss+xml" | "application/atom+xml" => {
+                Self::Xml
+            }
+            _ if essence.ends_with("+json") => Self::Json,
+            _ if essence.ends_with("+xml") => Self::Xml,
+            _ => return None,
+        })
+    }
+
+    fn from_extension(ext: &str) -> Option<Self> {
+        Some(match ext.to_ascii_lowercase().as_str() {
+            "html" | "htm" | "xhtml" => Self::Html,
+            "json" | "geojson" | "webmanifest" => Self::Json,
+            "csv" => Self::Csv,
+            "tsv" | "tab" => Self::Tsv,
+            "xml" | "rss" | "atom" | "svg" => Self::Xml,
+            "txt" | "text" | "log" | "md" | "ini" | "toml" | "yaml" | "yml" => Self::Text,
+            _ => return None,
+        })
+    }
+}
+
+/// Lowercase `type/subtype` of a `Content-Type` value, without parameters.
+#[must_use]
+pub fn mime_essence(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Decide how to show a response.
+#[must_use]
+pub fn sniff(content_type: &str, url: &str, body: &str) -> ResourceKind {
+    let essence = mime_essence(content_type);
+    if let Some(kind) = ResourceKind::from_mime(&essence) {
+        return kind;
+    }
+    if let Some(kind) = url_extension(url).and_then(ResourceKind::from_extension) {
+        return kind;
+    }
+    let head = body.trim_start_matches('\u{feff}').trim_start();
+    let lower: String = head.chars().take(512).collect::<String>().to_lowercase();
+    if lower.starts_with("<?xml") {
+        return if lower.contains("<html") {
+            ResourceKind::Html
+        } else {
+            ResourceKind::Xml
+        };
+    }
+    if head.starts_with('{') || head.starts_with('[') {
+        return ResourceKind::Json;
+    }
+    // Declared text stays text; untyped markup is treated as HTML
+    if essence.starts_with("text/") || !lower.starts_with('<') {
+        ResourceKind::Text
+    } else {
+        ResourceKind::Html
+    }
+}
+
+/// Media type of an audio or video file, from a declared `Content-Type`,
+/// the URL's extension or the first bytes of the file, in that order.
+#[must_use]
+pub fn media_type(content_type: &str, url: &str, head: &[u8]) -> Option<&'static str> {
+    let essence = mime_essence(content_type);
+    MEDIA_TYPES
+        .iter()
+        .map(|&(_, mime)| mime)
+        .find(|&mime| mime == essence)
+        .or_else(|| media_type_from_url(url))
+        .or_else(|| sniff_media(head))
+}
+
+/// Extensions of audio and video files and their media types.
+const MEDIA_TYPES: &[(&str, &str)] = &[
+    ("mp4", "video/mp4"),
+    ("m4v", "video/mp4"),
+    ("webm", "video/webm"),
+    ("ogv", "video/ogg"),
+    ("mov", "video/quicktime"),
+    ("mkv", "video/x-matroska"),
+    ("m3u8", "application/vnd.apple.mpegurl"),
+    ("mp3", "audio/mpeg"),
+    ("m4a", "audio/mp4"),
+    ("aac", "audio/aac"),
+    ("ogg", "audio/ogg"),
+    ("oga", "audio/ogg"),
+    ("opus", "audio/ogg"),
+    ("wav", "audio/wav"),
+    ("flac", "audio/flac"),
+];
+
+/// Media type of an audio or video file by the URL's extension.
+#[must_use]
+pub fn media_type_from_url(url: &str) -> Option<&'static str> {
+    let ext = url_extension(url)?.to_ascii_lowercase();
+    MEDIA_TYPES
+        .iter()
+        .find(|&&(e, _)| e == ext)
+        .map(|&(_, mime)| mime)
+}
+
+/// Media type of an audio or video file by its container signature.
+#[must_use]
+pub fn sniff_media(head: &[u8]) -> Option<&'static str> {
+    let contains = |needle: &[u8]| {
+        head[..head.len().min(512)]
+            .windows(needle.len())
+            .any(|w| w == needle)
+    };
+    Some(match head {
+        [_, _, _, _, b'f', b't', b'y', b'p', brand @ ..] => match brand.get(..4) {
+            Some(b"M4A ") => "audio/mp4",
+            Some(b"qt  ") => "video/quicktime",
+            _ => "video/mp4",
+        },
+        [0x1A, 0x45, 0xDF, 0xA3, ..] if contains(b"webm") => "video/webm",
+        [0x1A, 0x45, 0xDF, 0xA3, ..] => "video/x-matroska",
+        [b'O', b'g', b'g', b'S', ..] if contains(b"theora") => "video/ogg",
+        [b'O', b'g', b'g', b'S', ..] => "audio/ogg",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => "audio/wav",
+        [b'f', b'L', b'a', b'C', ..] => "audio/flac",
+        [b'I', b'D', b'3', ..] => "audio/mpeg",
+        // Frame sync: AAC in ADTS has the layer bits clear, MPEG audio not
+        [0xFF, b, ..] if b & 0xF6 == 0xF0 => "audio/aac",
+        [0xFF, b, ..] if b & 0xE0 == 0xE0 => "audio/mpeg",
+        _ => return None,
+    })
+}
+
+/// Extension of the URL's last path segment; relative URLs, with or
+/// without a slash, have one too.
+fn url_extension(url: &str) -> Option<&str> {
+    let path = url.split(['?', '#']).next()?;
+    // The host of an absolute URL is no path segment
+    let path = match path.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?.1,
+        None => path,
+    };
+    let segment = path.rsplit('/').next()?;
+    let (_, ext) = segment.rsplit_once('.')?;
+    (!ext.is_empty()).then_some(ext)
+}
+
+/// Decode a response body to text.
+#[must_use]
+pub fn decode(bytes: &[u8], content_type: &str) -> String {
+    let (text, _) = detect_encoding(bytes, content_type).decode_with_bom_removal(bytes);
+    text.into_owned()
+}
+
+/// The encoding `bytes` should be decoded with.
+#[must_use]
+pub fn detect_encoding(bytes: &[u8], content_type: &str) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+    let declared = charset_param(content_type)
+        .or_else(|| declared_charset(&bytes[..bytes.len().min(1024)]))
+        .and_then(|label| Encoding::for_label(label.as_bytes()));
+    // A page can't be UTF-16 without a BOM; the declaration is wrong
+    if let Some(encoding) = declared.filter(|e| e.is_ascii_compatible()) {
+        return encoding;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        UTF_8
+    } else {
+        WINDOWS_1252
+    }
+}
+
+/// `charset` parameter of a `Content-Type` value.
+fn charset_param(content_type: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches(['"', '\'']).to_string())
+    })
+}
+
+/// Charset named by a `<meta charset>` / `<meta http-equiv>` tag or an XML
+/// declaration near the start of the document.
+fn declared_charset(head: &[u8]) -> Option<String> {
+    let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+    let start = head
+        .find("charset=")
+        .map(|i| i + "charset=".len())
+        .or_else(|| {
+            let decl = head.find("<?xml")?;
+            let end = head[decl..].find("?>").map_or(head.len(), |e| decl + e);
+            head[decl..end]
+                .find("encoding=")
+                .map(|i| decl + i + "encoding=".len())
+        })?;
+    let label: String = head[start..]
+        .trim_start_matches(['"', '\''])
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+        .collect();
+    (!label.is_empty()).then_some(label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniff_prefers_type_then_extension_then_body() {
+        let url = "https://example.com/data.csv";
+        assert_eq!(
+            sniff("application/json; charset=utf-8", url, ""),
+            ResourceKind::Json
+        );
+        assert_eq!(sniff("text/plain", url, "a,b"), ResourceKind::Csv);
+        assert_eq!(
+            sniff("", "https://example.com/api?x=1", "  {\"a\": 1}"),
+            ResourceKind::Json
+        );
+        assert_eq!(
+            sniff(
+                "application/octet-stream",
+                "https://example.com/",
+                "<?xml version=\"1.0\"?><a/>"
+            ),
+            ResourceKind::Xml
+        );
+        assert_eq!(
+            sniff("", "https://example.com/", "<!DOCTYPE html><p>hi"),
+            ResourceKind::Html
+        );
+        assert_eq!(
+            sniff("text/plain", "https://example.com/", "<b>"),
+            ResourceKind::Text
+        );
+        assert_eq!(
+            sniff("", "https://example.com/README", "hello"),
+            ResourceKind::Text
+        );
+    }
+
+    #[test]
+    fn media_types_from_header_extension_and_signature() {
+        assert_eq!(
+            media_type("video/webm", "https://example.com/clip", b""),
+            Some("video/webm")
+        );
+        assert_eq!(
+            media_type(
+                "application/octet-stream",
+                "https://example.com/a/Song.MP3?dl=1",
+                b""
+            ),
+            Some("audio/mpeg")
+        );
+        assert_eq!(media_type_from_url("https://example.com/page.html"), None);
+        assert_eq!(media_type_from_url("https://example.mp3"), None);
+        assert_eq!(media_type_from_url("talk.mp3?x=1"), Some("audio/mpeg"));
+
+        assert_eq!(
+            sniff_media(b"\0\0\0\x20ftypisom\0\0\x02\0"),
+            Some("video/mp4")
+        );
+        assert_eq!(sniff_media(b"\0\0\0\x1cftypM4A "), Some("audio/mp4"));
+        assert_eq!(
+            sniff_media(b"\x1a\x45\xdf\xa3\x9f\x42\x82\x84webm"),
+            Some("video/webm")
+        );
+        assert_eq!(sniff_media(b"OggS\0\x02"), Some("audio/ogg"));
+        assert_eq!(sniff_media(b"RIFF\x24\0\0\0WAVEfmt "), Some("audio/wav"));
+        assert_eq!(sniff_media(b"fLaC\0\0\0\x22"), Some("audio/flac"));
+        assert_eq!(sniff_media(b"ID3\x04\0"), Some("audio/mpeg"));
+        assert_eq!(sniff_media(&[0xFF, 0xFB, 0x90, 0x64]), Some("audio/mpeg"));
+        assert_eq!(sniff_media(&[0xFF, 0xF1, 0x50, 0x80]), Some("audio/aac"));
+        assert_eq!(sniff_media(b"<!DOCTYPE html>"), None);
+    }
+
+    #[test]
+    fn decode_follows_bom_header_and_declaration() {
+        // "日本" in Shift_JIS
+        let sjis = [0x93, 0xfa, 0x96, 0x7b];
+        assert_eq!(decode(&sjis, "text/plain; charset=Shift_JIS"), "日本");
+
+        let mut html = b"<meta charset=\"shift_jis\"><p>".to_vec();
+        html.extend_from_slice(&sjis);
+        assert!(decode(&html, "text/html").ends_with("日本"));
+
+        let xml = b"<?xml version=\"1.0\" encoding='ISO-8859-1'?><a>\xe9</a>";
+        assert!(decode(xml, "").contains('é'));
+
+        assert_eq!(
+            decode(b"\xef\xbb\xbfhi", "text/plain; charset=latin1"),
+            "hi"
+        );
+        assert_eq!(decode("café".as_bytes(), ""), "café");
+        assert_eq!(decode(b"caf\xe9", ""), "café");
+    }
+}
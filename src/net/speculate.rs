@@ -0,0 +1,527 @@
+//! Speculative connections to hovered links.
+//!
+//! A link the pointer rests on for [`HOVER_DELAY`] ([`HoverIntent`]) is
+//! likely to be clicked, so its host is resolved ahead of the click and,
+//! only if the user opts into [`Speculation::Preconnect`], a connection is
+//! opened to its origin. Hosts reached through a proxy are left alone: the
+//! proxy resolves them, and a local lookup would leak the name past it.
+//! reqwest only connects to send a request, so preconnecting sends a `HEAD`
+//! for the origin's root — never for the link itself, which may not be safe
+//! to request twice — through a client kept for the origin. A page fetch
+//! with the same options picks that client up ([`Speculator::take_client`])
+//! and sends its `GET` over the open connection, TCP and TLS handshakes
+//! already done; other fetches to the host use the resolved addresses
+//! ([`Speculator::resolved`]) and skip DNS.
+//!
+//! [`SpeculationStats`] counts what was warmed up and what a click used;
+//! the milliseconds saved are the time the used warm-ups took.
+
+use std::collections::{HashMap, HashSet};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::engine::workers::CancelToken;
+
+use super::fetch::{self, FetchOptions};
+use super::scheduler::{self, Priority};
+use super::{proxy, tls};
+
+/// How long the pointer rests on a link before it is warmed up.
+pub const HOVER_DELAY: Duration = Duration::from_millis(150);
+
+/// How long resolved addresses and open connections are trusted.
+pub const WARM_LIFETIME: Duration = Duration::from_secs(60);
+
+/// How much is done for a hovered link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Speculation {
+    #[serde(rename = "off")]
+    Off,
+    /// Resolve the host name only
+    #[default]
+    #[serde(rename = "dns")]
+    Dns,
+    /// Resolve the host and open a connection to the origin, which sends
+    /// it a request (with its cookies) for a link never clicked
+    #[serde(rename = "preconnect")]
+    Preconnect,
+}
+
+impl Speculation {
+    pub const ALL: [Self; 3] = [Self::Off, Self::Dns, Self::Preconnect];
+
+    /// Stable identifier, as stored in settings and used in `alice://` URLs.
+    #[must_use]
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Dns => "dns",
+            Self::Preconnect => "preconnect",
+        }
+    }
+
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.key() == key)
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Dns => "Resolve host names",
+            Self::Preconnect => "Resolve and connect",
+        }
+    }
+}
+
+/// Tracks the link under the pointer and reports it once it has been
+/// hovered for a delay, [`HOVER_DELAY`] by default.
+#[derive(Debug)]
+pub struct HoverIntent {
+    /// Link under the pointer and since when
+    target: Option<(String, Instant)>,
+    /// `target` was already reported
+    fired: bool,
+    delay: Duration,
+}
+
+impl Default for HoverIntent {
+    fn default() -> Self {
+        Self::with_delay(HOVER_DELAY)
+    }
+}
+
+impl HoverIntent {
+    /// Intent reporting links hovered for `delay`.
+    #[must_use]
+    pub const fn with_delay(delay: Duration) -> Self {
+        Self {
+            target: None,
+            fired: false,
+            delay,
+        }
+    }
+
+    /// Note the link under the pointer at `now` (`None` when there is
+    /// none). Returns it the first time it has been hovered long enough;
+    /// leaving and coming back starts over.
+    pub fn update(&mut self, href: Option<&str>, now: Instant) -> Option<String> {
+        let Some(href) = href else {
+            self.target = None;
+            return None;
+        };
+        match self.target {
+            Some((ref current, since)) if current == href => {
+                if self.fired || now.saturating_duration_since(since) < self.delay {
+                    return None;
+                }
+                self.fired = true;
+                Some(href.to_string())
+            }
+            _ => {
+                self.target = Some((href.to_string(), now));
+                self.fired = false;
+                None
+            }
+        }
+    }
+
+    /// Time until the hovered link is reported, if it has not been yet.
+    #[must_use]
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        if self.fired {
+            return None;
+        }
+        self.target.as_ref().map(|(_, since)| {
+            self.delay
+                .saturating_sub(now.saturating_duration_since(*since))
+        })
+    }
+}
+
+/// What speculation did and what it saved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpeculationStats {
+    /// Hovered links warmed up
+    pub hovers: usize,
+    /// Host names resolved
+    pub lookups: usize,
+    /// Connections opened
+    pub preconnects: usize,
+    /// Lookups and connections that failed
+    pub failures: usize,
+    /// Fetches that found their host resolved
+    pub dns_hits: usize,
+    /// Fetches that found a connection open
+    pub connection_hits: usize,
+    /// Time the used warm-ups took, which their fetches did not spend
+    pub saved: Duration,
+}
+
+impl SpeculationStats {
+    /// e.g. "12 hovers · 10 lookups · 8 connections · 5 used · 740 ms saved"
+    #[must_use]
+    pub fn summary(&self) -> String {
+        format!(
+            "{} hovers \u{00B7} {} lookups \u{00B7} {} connections \u{00B7} {} used \u{00B7} {} ms saved",
+            self.hovers,
+            self.lookups,
+            self.preconnects,
+            self.dns_hits + self.connection_hits,
+            self.saved.as_millis()
+        )
+    }
+}
+
+/// Addresses of a host resolved ahead of a fetch.
+struct Resolved {
+    addrs: Vec<SocketAddr>,
+    took: Duration,
+    at: Instant,
+    used: bool,
+}
+
+/// A client with a connection open to `origin`.
+struct Warm {
+    origin: String,
+    options: FetchOptions,
+    /// Built without certificate checks (see [`tls::exceptions`])
+    excepted: bool,
+    client: reqwest::blocking::Client,
+    took: Duration,
+    at: Instant,
+    used: bool,
+}
+
+/// Resolved hosts and open connections for hovered links.
+pub struct Speculator {
+    lifetime: Duration,
+    resolved: Mutex<HashMap<String, Resolved>>,
+    warm: Mutex<Vec<Warm>>,
+    /// Origins being warmed up
+    pending: Mutex<HashSet<String>>,
+    stats: Mutex<SpeculationStats>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Speculator {
+    /// Speculator trusting what it warmed up for `lifetime`.
+    #[must_use]
+    pub fn new(lifetime: Duration) -> Self {
+        Self {
+            lifetime,
+            resolved: Mutex::new(HashMap::new()),
+            warm: Mutex::new(Vec::new()),
+            pending: Mutex::new(HashSet::new()),
+            stats: Mutex::new(SpeculationStats::default()),
+        }
+    }
+
+    /// Warm up the origin of `url` for fetches made with `options`
+    /// (blocking), unless it is reached through a proxy. Hosts still resolved and origins still connected are not
+    /// warmed again, and an origin being warmed is skipped.
+    pub fn warm(&self, url: &str, level: Speculation, options: &FetchOptions) {
+        if level == Speculation::Off {
+            return;
+        }
+        let Ok(url) = Url::parse(url) else {
+            return;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            return;
+        }
+        if proxy::shared().proxy_for(&url).is_some() {
+            return;
+        }
+        let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+            return;
+        };
+        let origin = url.origin().ascii_serialization();
+        if !lock(&self.pending).insert(origin.clone()) {
+            return;
+        }
+        lock(&self.stats).hovers += 1;
+
+        // IP addresses need no lookup
+        let addrs = match url.domain() {
+            Some(domain) => self.lookup(domain, port),
+            None => Some(Vec::new()),
+        };
+        if let Some(addrs) = addrs {
+            if level == Speculation::Preconnect && !self.is_warm(&origin, options) {
+                self.preconnect(&url, host, &addrs, origin.clone(), options);
+            }
+        }
+        lock(&self.pending).remove(&origin);
+    }
+
+    /// Addresses of `domain`, looked up unless still fresh.
+    fn lookup(&self, domain: &str, port: u16) -> Option<Vec<SocketAddr>> {
+        if let Some(entry) = lock(&self.resolved).get(domain) {
+            if entry.at.elapsed() < self.lifetime {
+                return Some(entry.addrs.clone());
+            }
+        }
+        let start = Instant::now();
+        let addrs: Vec<SocketAddr> = match (domain, port).to_socket_addrs() {
+            Ok(addrs) => addrs.collect(),
+            Err(e) => {
+                log::debug!("Speculative lookup of {domain} failed: {e}");
+                lock(&self.stats).failures += 1;
+                return None;
+            }
+        };
+        if addrs.is_empty() {
+            lock(&self.stats).failures += 1;
+            return None;
+        }
+        lock(&self.stats).lookups += 1;
+        lock(&self.resolved).insert(
+            domain.to_string(),
+            Resolved {
+                addrs: addrs.clone(),
+                took: start.elapsed(),
+                at: Instant::now(),
+                used: false,
+            },
+        );
+        Some(addrs)
+    }
+
+    fn is_warm(&self, origin: &str, options: &FetchOptions) -> bool {
+        lock(&self.warm)
+            .iter()
+            .any(|w| w.origin == origin && w.options == *options && w.at.elapsed() < self.lifetime)
+    }
+
+    /// Open a connection to `url`'s origin with a `HEAD /` and keep its
+    /// client.
+    fn preconnect(
+        &self,
+        url: &Url,
+        host: &str,
+        addrs: &[SocketAddr],
+        origin: String,
+        options: &FetchOptions,
+    ) {
+        let excepted = tls::exceptions().is_allowed(host);
+        let pinned = url
+            .domain()
+            .filter(|_| !addrs.is_empty())
+            .map(|d| (d, addrs));
+        let Ok(client) = fetch::build_client(options, excepted, pinned) else {
+            lock(&self.stats).failures += 1;
+            return;
+        };
+        let mut root = url.clone();
+        root.set_path("/");
+        root.set_query(None);
+        root.set_fragment(None);
+        let start = Instant::now();
+        match client.head(root.as_str()).send() {
+            Ok(_) => {
+                lock(&self.stats).preconnects += 1;
+                let mut warm = lock(&self.warm);
+                warm.retain(|w| w.at.elapsed() < self.lifetime);
+                warm.push(Warm {
+                    origin,
+                    options: options.clone(),
+                    excepted,
+                    client,
+                    took: start.elapsed(),
+                    at: Instant::now(),
+                    used: false,
+                });
+            }
+            Err(e) => {
+                log::debug!("Preconnect to {origin} failed: {e}");
+                lock(&self.stats).failures += 1;
+            }
+        }
+    }
+
+    /// Client with a connection open to `url`'s origin, built for
+    /// `options` and the same certificate exception.
+    pub(crate) fn take_client(
+        &self,
+        url: &Url,
+        options: &FetchOptions,
+        excepted: bool,
+    ) -> Option<reqwest::blocking::Client> {
+        let origin = url.origin().ascii_serialization();
+        let mut warm = lock(&self.warm);
+        warm.retain(|w| w.at.elapsed() < self.lifetime);
+        let entry = warm
+            .iter_mut()
+            .find(|w| w.origin == origin && w.options == *options && w.excepted == excepted)?;
+        if !entry.used {
+            entry.used = true;
+            let mut saved = entry.took;
+            if let Some(resolved) = url
+                .domain()
+                .and_then(|d| lock(&self.resolved).get_mut(d).map(|r| r.mark_used()))
+            {
+                saved += resolved;
+            }
+            let mut stats = lock(&self.stats);
+            stats.connection_hits += 1;
+            stats.saved += saved;
+        }
+        Some(entry.client.clone())
+    }
+
+    /// Fresh addresses of `domain`, if it was resolved ahead.
+    pub(crate) fn resolved(&self, domain: &str) -> Option<Vec<SocketAddr>> {
+        let mut resolved = lock(&self.resolved);
+        let entry = resolved
+            .get_mut(domain)
+            .filter(|r| r.at.elapsed() < self.lifetime)?;
+        let saved = entry.mark_used();
+        let addrs = entry.addrs.clone();
+        drop(resolved);
+        if !saved.is_zero() {
+            let mut stats = lock(&self.stats);
+            stats.dns_hits += 1;
+            stats.saved += saved;
+        }
+        Some(addrs)
+    }
+
+    /// Forget resolved hosts and open connections, e.g. when the proxy
+    /// changes; the statistics are kept.
+    pub fn clear(&self) {
+        lock(&self.resolved).clear();
+        lock(&self.warm).clear();
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> SpeculationStats {
+        *lock(&self.stats)
+    }
+}
+
+impl Resolved {
+    /// Lookup time saved by this use: all of it the first time, none after.
+    fn mark_used(&mut self) -> Duration {
+        if std::mem::replace(&mut self.used, true) {
+            Duration::ZERO
+        } else {
+            self.took
+        }
+    }
+}
+
+/// Process-wide speculator consulted by `fetch_url`.
+#[must_use]
+pub fn shared() -> &'static Speculator {
+    static SPECULATOR: OnceLock<Speculator> = OnceLock::new();
+    SPECULATOR.get_or_init(|| Speculator::new(WARM_LIFETIME))
+}
+
+/// Warm up the origin of `url` in the background with the process-wide
+/// speculator, as a prefetch job of the fetch scheduler that `token`
+/// drops while it waits.
+pub fn speculate(url: &str, level: Speculation, options: &FetchOptions, token: &CancelToken) {
+    if level == Speculation::Off {
+        return;
+    }
+    let options = options.clone();
+    let host_key = url.to_string();
+    let url = url.to_string();
+    scheduler::shared().submit(&host_key, Priority::Prefetch, token, move |_| {
+        shared().warm(&url, level, &options);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_fire_once_after_the_hover_delay() {
+        let start = Instant::now();
+        let mut intent = HoverIntent::default();
+        let a = Some("https://example.com/a");
+        assert_eq!(intent.update(a, start), None);
+        assert_eq!(intent.update(a, start + Duration::from_millis(100)), None);
+        assert_eq!(
+            intent.remaining(start + Duration::from_millis(100)),
+            Some(Duration::from_millis(50))
+        );
+        assert_eq!(
+            intent.update(a, start + HOVER_DELAY).as_deref(),
+            Some("https://example.com/a")
+        );
+        assert_eq!(intent.update(a, start + Duration::from_secs(1)), None);
+        assert_eq!(intent.remaining(start + Duration::from_secs(1)), None);
+
+        // Moving to another link restarts the delay
+        let b = Some("https://example.com/b");
+        let later = start + Duration::from_secs(2);
+        assert_eq!(intent.update(b, later), None);
+        assert_eq!(intent.update(b, later + Duration::from_millis(149)), None);
+        assert!(intent.update(b, later + HOVER_DELAY).is_some());
+
+        // Leaving and coming back fires again
+        assert_eq!(intent.update(None, later), None);
+        assert_eq!(intent.update(a, later), None);
+        assert!(intent.update(a, later + HOVER_DELAY).is_some());
+    }
+
+    #[test]
+    fn custom_delay_is_waited_out() {
+        let start = Instant::now();
+        let mut intent = HoverIntent::with_delay(Duration::from_millis(500));
+        let a = Some("https://example.com/a");
+        assert_eq!(intent.update(a, start), None);
+        assert_eq!(intent.update(a, start + HOVER_DELAY), None);
+        assert_eq!(
+            intent.remaining(start + Duration::from_millis(200)),
+            Some(Duration::from_millis(300))
+        );
+        assert!(intent
+            .update(a, start + Duration::from_millis(500))
+            .is_some());
+    }
+
+    #[test]
+    fn resolved_hosts_are_used_once_for_the_savings() {
+        let speculator = Speculator::new(WARM_LIFETIME);
+        let options = FetchOptions::default();
+        speculator.warm("http://localhost:9/page", Speculation::Dns, &options);
+        let stats = speculator.stats();
+        assert_eq!((stats.hovers, stats.lookups, stats.preconnects), (1, 1, 0));
+
+        assert!(speculator.resolved("localhost").is_some());
+        assert!(speculator.resolved("localhost").is_some());
+        assert_eq!(speculator.stats().dns_hits, 1);
+        assert!(speculator.resolved("other.invalid").is_none());
+
+        // Nothing was connected, so no client is offered
+        let url = Url::parse("http://localhost:9/page").unwrap();
+        assert!(speculator.take_client(&url, &options, false).is_none());
+
+        speculator.clear();
+        assert!(speculator.resolved("localhost").is_none());
+        speculator.warm("file:///tmp/page.html", Speculation::Preconnect, &options);
+        speculator.warm("http://localhost/", Speculation::Off, &options);
+        assert_eq!(speculator.stats().hovers, 1);
+    }
+
+    #[test]
+    fn speculation_keys_round_trip() {
+        for level in Speculation::ALL {
+            assert_eq!(Speculation::from_key(level.key()), Some(level));
+        }
+        assert_eq!(Speculation::from_key("eager"), None);
+        assert_eq!(Speculation::default(), Speculation::Dns);
+    }
+}
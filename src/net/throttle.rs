@@ -0,0 +1,284 @@
+//! Simulated network conditions for testing.
+//!
+//! A [`NetworkProfile`] ("Slow 3G", "Flaky", …) describes latency,
+//! bandwidth and a failure rate. Page and image fetches made under its
+//! [`NetworkConditions`] wait out the latency before the request, are paced
+//! to the bandwidth while the body arrives and fail outright at the given
+//! rate — so progressive rendering, timeouts and error pages can be seen on
+//! a fast connection.
+//!
+//! Failures are not random: whether a request fails is a hash of the seed,
+//! the URL and the request's sequence number, so the same sequence of
+//! requests fails the same way on every run.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::fetch::FetchError;
+
+/// Built-in condition presets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NetworkProfile {
+    /// No simulation
+    #[default]
+    #[serde(rename = "off")]
+    Off,
+    #[serde(rename = "slow-3g")]
+    Slow3G,
+    #[serde(rename = "fast-3g")]
+    Fast3G,
+    /// Moderate speed, one request in four fails
+    #[serde(rename = "flaky")]
+    Flaky,
+    /// Every request fails
+    #[serde(rename = "offline")]
+    Offline,
+}
+
+impl NetworkProfile {
+    pub const ALL: [Self; 5] = [
+        Self::Off,
+        Self::Slow3G,
+        Self::Fast3G,
+        Self::Flaky,
+        Self::Offline,
+    ];
+
+    /// Stable identifier, as stored in settings and used in `alice://` URLs.
+    #[must_use]
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Slow3G => "slow-3g",
+            Self::Fast3G => "fast-3g",
+            Self::Flaky => "flaky",
+            Self::Offline => "offline",
+        }
+    }
+
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.key() == key)
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Off => "No throttling",
+            Self::Slow3G => "Slow 3G",
+            Self::Fast3G => "Fast 3G",
+            Self::Flaky => "Flaky",
+            Self::Offline => "Offline",
+        }
+    }
+
+    /// The conditions this profile simulates.
+    #[must_use]
+    pub const fn conditions(self) -> NetworkConditions {
+        let (latency_ms, bytes_per_sec, failure_per_mille) = match self {
+            Self::Off => return NetworkConditions::NONE,
+            Self::Slow3G => (2_000, Some(50_000), 0),
+            Self::Fast3G => (560, Some(180_000), 0),
+            Self::Flaky => (300, Some(250_000), 250),
+            Self::Offline => (0, None, 1_000),
+        };
+        NetworkConditions {
+            latency: Duration::from_millis(latency_ms),
+            bytes_per_sec,
+            failure_per_mille,
+            seed: 0,
+        }
+    }
+}
+
+/// Simulated latency, bandwidth and failure rate for fetches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NetworkConditions {
+    /// Added before each request is sent
+    pub latency: Duration,
+    /// Body transfer rate; `None` leaves it unthrottled
+    pub bytes_per_sec: Option<u64>,
+    /// Share of requests that fail, in thousandths
+    pub failure_per_mille: u16,
+    /// Varies which requests fail
+    pub seed: u64,
+}
+
+/// Requests made under simulated conditions so far, for failure decisions.
+static REQUESTS: AtomicU64 = AtomicU64::new(0);
+
+impl NetworkConditions {
+    /// No simulation.
+    pub const NONE: Self = Self {
+        latency: Duration::ZERO,
+        bytes_per_sec: None,
+        failure_per_mille: 0,
+        seed: 0,
+    };
+
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        *self != Self::NONE
+    }
+
+    /// Whether the `attempt`-th request, for `url`, fails.
+    #[must_use]
+    pub fn fails(&self, url: &str, attempt: u64) -> bool {
+        if self.failure_per_mille == 0 {
+            return false;
+        }
+        // FNV-1a over seed, attempt and URL
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let bytes = self
+            .seed
+            .to_le_bytes()
+            .into_iter()
+            .chain(attempt.to_le_bytes());
+        for byte in bytes.chain(url.bytes()) {
+            hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+        hash % 1_000 < u64::from(self.failure_per_mille)
+    }
+
+    /// Time `bytes` take to arrive at the simulated bandwidth.
+    #[must_use]
+    pub fn transfer_time(&self, bytes: usize) -> Duration {
+        self.bytes_per_sec
+            .filter(|&rate| rate > 0)
+            .map_or(Duration::ZERO, |rate| {
+                Duration::from_secs_f64(bytes as f64 / rate as f64)
+            })
+    }
+
+    /// Start a request for `url`: decide whether it fails, then wait out
+    /// the latency. `timeout` is the request's time limit, which the
+    /// simulated delays count against.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FetchError` if the request is chosen to fail or the latency
+    /// alone exceeds `timeout`.
+    pub fn begin(&self, url: &str, timeout: Duration) -> Result<Transfer, FetchError> {
+        let start = Instant::now();
+        if !self.is_active() {
+            return Ok(Transfer {
+                conditions: *self,
+                start,
+                timeout,
+            });
+        }
+        let attempt = REQUESTS.fetch_add(1, Ordering::Relaxed);
+        if self.fails(url, attempt) {
+            return Err(FetchError {
+                message: "Request failed: simulated network failure".to_string(),
+                invalid_certificate: false,
+            });
+        }
+        let transfer = Transfer {
+            conditions: *self,
+            start,
+            timeout,
+        };
+        transfer.wait_until(self.latency)?;
+        Ok(transfer)
+    }
+}
+
+/// A request in progress under simulated conditions.
+#[derive(Debug, Clone, Copy)]
+pub struct Transfer {
+    conditions: NetworkConditions,
+    start: Instant,
+    timeout: Duration,
+}
+
+impl Transfer {
+    /// Hold back until `received` body bytes would have arrived.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FetchError` once the simulated transfer runs past the
+    /// request's timeout.
+    pub fn pace(&self, received: usize) -> Result<(), FetchError> {
+        if self.conditions.bytes_per_sec.is_none() {
+            return Ok(());
+        }
+        self.wait_until(self.conditions.latency + self.conditions.transfer_time(received))
+    }
+
+    /// Sleep until `offset` after the start, or fail if that is past the
+    /// timeout.
+    fn wait_until(&self, offset: Duration) -> Result<(), FetchError> {
+        if offset > self.timeout {
+            std::thread::sleep(self.timeout.saturating_sub(self.start.elapsed()));
+            return Err(FetchError {
+                message: format!(
+                    "Request timed out after {:.1} s (simulated network)",
+                    self.timeout.as_secs_f32()
+                ),
+                invalid_certificate: false,
+            });
+        }
+        std::thread::sleep(offset.saturating_sub(self.start.elapsed()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_are_deterministic_and_follow_the_rate() {
+        let flaky = NetworkProfile::Flaky.conditions();
+        let runs: Vec<bool> = (0..1_000)
+            .map(|i| flaky.fails("https://example.com/", i))
+            .collect();
+        let again: Vec<bool> = (0..1_000)
+            .map(|i| flaky.fails("https://example.com/", i))
+            .collect();
+        assert_eq!(runs, again);
+        let failed = runs.iter().filter(|f| **f).count();
+        assert!((150..350).contains(&failed), "{failed} failures");
+
+        let offline = NetworkProfile::Offline.conditions();
+        assert!((0..100).all(|i| offline.fails("https://example.com/", i)));
+        assert!(!NetworkConditions::NONE.fails("https://example.com/", 0));
+    }
+
+    #[test]
+    fn bandwidth_sets_transfer_time() {
+        let slow = NetworkProfile::Slow3G.conditions();
+        assert_eq!(slow.transfer_time(100_000), Duration::from_secs(2));
+        assert_eq!(
+            NetworkProfile::Offline.conditions().transfer_time(100_000),
+            Duration::ZERO
+        );
+        assert!(!NetworkProfile::Off.conditions().is_active());
+    }
+
+    #[test]
+    fn slow_transfers_time_out() {
+        let slow = NetworkConditions {
+            bytes_per_sec: Some(1_000),
+            ..NetworkConditions::NONE
+        };
+        let transfer = slow
+            .begin("https://example.com/", Duration::from_millis(20))
+            .ok()
+            .unwrap();
+        assert!(transfer.pace(10).is_ok());
+        let err = transfer.pace(1_000_000).err().unwrap();
+        assert!(err.message.contains("timed out"));
+
+        let late = NetworkConditions {
+            latency: Duration::from_secs(60),
+            ..NetworkConditions::NONE
+        };
+        assert!(late
+            .begin("https://example.com/", Duration::from_millis(10))
+            .is_err());
+    }
+}
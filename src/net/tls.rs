@@ -0,0 +1,336 @@
+//! TLS certificate details and exceptions.
+//!
+//! Page fetches ask the TLS stack for the server's certificate, and
+//! [`Certificate::from_der`] turns it into what the padlock in the toolbar
+//! shows: subject, issuer, validity and the names it covers. A fetch that
+//! fails certificate verification is flagged
+//! ([`FetchError::invalid_certificate`]) so the browser can stop at an
+//! interstitial instead of showing a bare error. If the user proceeds
+//! anyway, the host goes into the process-wide [`TlsExceptions`], and for
+//! [`EXCEPTION_LIFETIME`] fetches from it skip verification; exceptions are
+//! never saved.
+//!
+//! [`FetchError::invalid_certificate`]: super::fetch::FetchError::invalid_certificate
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use url::Url;
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::{FromDer, X509Certificate, X509Name};
+
+/// How long a certificate exception lasts.
+pub const EXCEPTION_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
+/// What a server certificate says about itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    /// Common name of the subject, or its whole name when it has none
+    pub subject: String,
+    /// Common name and organization of the issuer
+    pub issuer: String,
+    /// Start of validity, Unix seconds
+    pub not_before: i64,
+    /// End of validity, Unix seconds
+    pub not_after: i64,
+    /// Subject alternative names: DNS names and IP addresses
+    pub san: Vec<String>,
+    /// Serial number, hex
+    pub serial: String,
+}
+
+/// Whether a certificate is valid at some moment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Validity {
+    NotYetValid,
+    Valid { days_left: i64 },
+    Expired,
+}
+
+impl Certificate {
+    /// Parse a DER-encoded X.509 certificate.
+    #[must_use]
+    pub fn from_der(der: &[u8]) -> Option<Self> {
+        let (_, cert) = X509Certificate::from_der(der).ok()?;
+        let san = cert
+            .subject_alternative_name()
+            .ok()
+            .flatten()
+            .map(|ext| {
+                ext.value
+                    .general_names
+                    .iter()
+                    .filter_map(|name| match name {
+                        GeneralName::DNSName(dns) => Some((*dns).to_string()),
+                        GeneralName::IPAddress(bytes) => ip_address(bytes),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let validity = cert.validity();
+        Some(Self {
+            subject: common_name(cert.subject()).unwrap_or_else(|| cert.subject().to_string()),
+            issuer: issuer_name(cert.issuer()),
+            not_before: validity.not_before.timestamp(),
+            not_after: validity.not_after.timestamp(),
+            san,
+            serial: cert.raw_serial_as_string(),
+        })
+    }
+
+    /// Validity at `now`, in Unix seconds.
+    #[must_use]
+    pub const fn validity_at(&self, now: i64) -> Validity {
+        if now < self.not_before {
+            Validity::NotYetValid
+        } else if now > self.not_after {
+            Validity::Expired
+        } else {
+            Validity::Valid {
+                days_left: (self.not_after - now) / 86_400,
+            }
+        }
+    }
+
+    /// Whether `host` is one of the names the certificate covers; a
+    /// `*.` name covers exactly one more label.
+    #[must_use]
+    pub fn covers(&self, host: &str) -> bool {
+        let host = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+        self.san.iter().any(|name| {
+            let name = name.to_ascii_lowercase();
+            match name.strip_prefix("*.") {
+                Some(parent) => host
+                    .split_once('.')
+                    .is_some_and(|(label, rest)| !label.is_empty() && rest == parent),
+                None => host == name,
+            }
+        })
+    }
+}
+
+fn common_name(name: &X509Name) -> Option<String> {
+    name.iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .map(str::to_string)
+}
+
+fn issuer_name(name: &X509Name) -> String {
+    let organization = name
+        .iter_organization()
+        .next()
+        .and_then(|o| o.as_str().ok());
+    match (common_name(name), organization) {
+        (Some(cn), Some(o)) if cn != o => format!("{cn} ({o})"),
+        (Some(cn), _) => cn,
+        (None, Some(o)) => o.to_string(),
+        (None, None) => name.to_string(),
+    }
+}
+
+fn ip_address(bytes: &[u8]) -> Option<String> {
+    let ip = match bytes.len() {
+        4 => IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?),
+        16 => IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?),
+        _ => return None,
+    };
+    Some(ip.to_string())
+}
+
+/// Whether `error`, or an error it wraps, is a failed certificate check.
+///
+/// TLS back ends report these differently; all of them mention the
+/// certificate.
+#[must_use]
+pub fn is_certificate_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    let mut current = Some(error);
+    while let Some(e) = current {
+        let message = e.to_string().to_ascii_lowercase();
+        if message.contains("certificate") || message.contains("cert verify") {
+            return true;
+        }
+        current = e.source();
+    }
+    false
+}
+
+/// How the connection to the current page is secured, for the padlock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Security {
+    /// HTTPS with a verified certificate
+    Secure,
+    /// HTTPS to a host with a certificate exception: not verified
+    Exception,
+    /// Plain HTTP
+    Insecure,
+    /// Built-in and local pages
+    Local,
+}
+
+impl Security {
+    /// Security of a page loaded from `url`.
+    #[must_use]
+    pub fn of(url: &str) -> Self {
+        let Ok(url) = Url::parse(url) else {
+            return Self::Local;
+        };
+        match url.scheme() {
+            "https" if url.host_str().is_some_and(|h| exceptions().is_allowed(h)) => {
+                Self::Exception
+            }
+            "https" => Self::Secure,
+            "http" => Self::Insecure,
+            _ => Self::Local,
+        }
+    }
+
+    #[must_use]
+    pub const fn icon(self) -> &'static str {
+        match self {
+            Self::Secure => "\u{1F512}",
+            Self::Exception => "\u{26A0}",
+            Self::Insecure => "\u{1F513}",
+            Self::Local => "\u{2139}",
+        }
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Secure => "Connection is secure",
+            Self::Exception => "Certificate not verified (exception)",
+            Self::Insecure => "Connection is not encrypted",
+            Self::Local => "Built-in or local page",
+        }
+    }
+}
+
+/// Hosts whose invalid certificates are accepted for a while.
+#[derive(Debug, Default)]
+pub struct TlsExceptions {
+    hosts: Mutex<HashMap<String, Instant>>,
+}
+
+impl TlsExceptions {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept any certificate from `host` for [`EXCEPTION_LIFETIME`].
+    pub fn allow(&self, host: &str) {
+        self.allow_for(host, EXCEPTION_LIFETIME);
+    }
+
+    /// Accept any certificate from `host` for `lifetime`.
+    pub fn allow_for(&self, host: &str, lifetime: Duration) {
+        if let Ok(mut hosts) = self.hosts.lock() {
+            hosts.insert(host.to_ascii_lowercase(), Instant::now() + lifetime);
+        }
+    }
+
+    /// Whether `host` has an exception that has not run out.
+    #[must_use]
+    pub fn is_allowed(&self, host: &str) -> bool {
+        let Ok(mut hosts) = self.hosts.lock() else {
+            return false;
+        };
+        let now = Instant::now();
+        hosts.retain(|_, until| *until > now);
+        hosts.contains_key(&host.to_ascii_lowercase())
+    }
+
+    /// Drop the exception for `host`.
+    pub fn revoke(&self, host: &str) {
+        if let Ok(mut hosts) = self.hosts.lock() {
+            hosts.remove(&host.to_ascii_lowercase());
+        }
+    }
+
+    /// Hosts with exceptions and the time each has left, soonest first.
+    #[must_use]
+    pub fn list(&self) -> Vec<(String, Duration)> {
+        let Ok(hosts) = self.hosts.lock() else {
+            return Vec::new();
+        };
+        let now = Instant::now();
+        let mut list: Vec<_> = hosts
+            .iter()
+            .filter(|(_, until)| **until > now)
+            .map(|(host, until)| (host.clone(), *until - now))
+            .collect();
+        list.sort_by_key(|(_, left)| *left);
+        list
+    }
+}
+
+/// Process-wide exceptions used by `fetch_url` and the image loader.
+#[must_use]
+pub fn exceptions() -> &'static TlsExceptions {
+    static EXCEPTIONS: OnceLock<TlsExceptions> = OnceLock::new();
+    EXCEPTIONS.get_or_init(TlsExceptions::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Self-signed, `O=ALICE Test, CN=example.test`, with SANs
+    /// `example.test`, `*.example.test` and `127.0.0.1`.
+    const EXAMPLE_CERT: &[u8] = include_bytes!("../../tests/fixtures/example-cert.der");
+
+    #[test]
+    fn certificate_details() {
+        let cert = Certificate::from_der(EXAMPLE_CERT).unwrap();
+        assert_eq!(cert.subject, "example.test");
+        assert_eq!(cert.issuer, "example.test (ALICE Test)");
+        assert_eq!(cert.san, ["example.test", "*.example.test", "127.0.0.1"]);
+        assert_eq!(cert.not_before, 1_792_198_343);
+        assert_eq!(cert.not_after, 2_107_558_343);
+
+        assert!(cert.covers("example.test"));
+        assert!(cert.covers("www.example.test"));
+        assert!(!cert.covers("a.b.example.test"));
+        assert!(cert.covers("127.0.0.1"));
+        assert!(!cert.covers("example.org"));
+
+        assert_eq!(cert.validity_at(cert.not_before - 1), Validity::NotYetValid);
+        assert_eq!(
+            cert.validity_at(cert.not_after - 2 * 86_400),
+            Validity::Valid { days_left: 2 }
+        );
+        assert_eq!(cert.validity_at(cert.not_after + 1), Validity::Expired);
+
+        assert!(Certificate::from_der(b"not a certificate").is_none());
+    }
+
+    #[test]
+    fn exceptions_expire_and_revoke() {
+        let exceptions = TlsExceptions::new();
+        exceptions.allow("Self-Signed.example");
+        assert!(exceptions.is_allowed("self-signed.example"));
+        assert!(!exceptions.is_allowed("other.example"));
+        exceptions.revoke("self-signed.example");
+        assert!(!exceptions.is_allowed("self-signed.example"));
+
+        exceptions.allow_for("brief.example", Duration::ZERO);
+        assert!(!exceptions.is_allowed("brief.example"));
+        assert!(exceptions.list().is_empty());
+    }
+
+    #[test]
+    fn certificate_errors_are_recognised() {
+        let io = std::io::Error::other("invalid peer certificate: UnknownIssuer");
+        assert!(is_certificate_error(&io));
+        let io = std::io::Error::other("connection refused");
+        assert!(!is_certificate_error(&io));
+    }
+}
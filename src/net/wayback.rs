@@ -0,0 +1,347 @@
+//! Internet Archive fallback for dead links.
+//!
+//! When a page that used to load is gone — 404, 410, or its host no longer
+//! resolves — the Wayback Machine often still has it. [`find_snapshot`]
+//! asks the archive's availability API for the latest copy, and
+//! [`BrowserEngine::load_archived`](crate::engine::pipeline::BrowserEngine::load_archived)
+//! loads it. The copy is fetched raw (the `id_` form, without the archive's
+//! toolbar and rewriting), so [`rewrite_assets`] points its links, images,
+//! stylesheets and scripts at the archive itself.
+
+use url::Url;
+
+use super::fetch::{fetch_url_with, FetchError, FetchOptions};
+use crate::dom::DomNode;
+
+/// The Wayback Machine's availability API.
+pub const AVAILABILITY_API: &str = "https://archive.org/wayback/available?url=";
+
+/// Prefix of archived page addresses.
+pub const ARCHIVE_PREFIX: &str = "https://web.archive.org/web/";
+
+/// Why a page is considered dead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkRot {
+    /// 404
+    NotFound,
+    /// 410
+    Gone,
+    /// The host name no longer resolves
+    Unresolvable,
+}
+
+impl LinkRot {
+    /// Rot indicated by an HTTP status.
+    #[must_use]
+    pub const fn from_status(status: u16) -> Option<Self> {
+        match status {
+            404 => Some(Self::NotFound),
+            410 => Some(Self::Gone),
+            _ => None,
+        }
+    }
+
+    /// Rot indicated by a fetch error: a failed DNS lookup.
+    #[must_use]
+    pub fn from_error(message: &str) -> Option<Self> {
+        let message = message.to_ascii_lowercase();
+        ["dns error", "failed to lookup address", "no such host"]
+            .iter()
+            .any(|m| message.contains(m))
+            .then_some(Self::Unresolvable)
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::NotFound => "Page not found (404)",
+            Self::Gone => "Page gone (410)",
+            Self::Unresolvable => "Site no longer resolves",
+        }
+    }
+}
+
+/// An archived copy of a page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Archive address, as the archive shows it
+    pub url: String,
+    /// Address the copy was taken of
+    pub original: String,
+    /// Capture time, `YYYYMMDDhhmmss`
+    pub timestamp: String,
+}
+
+impl Snapshot {
+    /// Parse an archive address, `…/web/<timestamp>[flags]/<original>`.
+    #[must_use]
+    pub fn from_archive_url(url: &str) -> Option<Self> {
+        let rest = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))?
+            .strip_prefix("web.archive.org/web/")?;
+        let (stamp, original) = rest.split_once('/')?;
+        let digits = stamp.len() - stamp.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits < 4 || original.is_empty() {
+            return None;
+        }
+        let timestamp = stamp[..digits].to_string();
+        Some(Self {
+            url: format!("{ARCHIVE_PREFIX}{timestamp}/{original}"),
+            original: original.to_string(),
+            timestamp,
+        })
+    }
+
+    /// Address of the copy as it was served, without the archive's toolbar.
+    #[must_use]
+    pub fn raw_url(&self) -> String {
+        archive_url(&self.timestamp, "id_", &self.original)
+    }
+
+    /// Capture date, `YYYY-MM-DD`.
+    #[must_use]
+    pub fn date(&self) -> String {
+        let t = &self.timestamp;
+        match (t.get(0..4), t.get(4..6), t.get(6..8)) {
+            (Some(y), Some(m), Some(d)) => format!("{y}-{m}-{d}"),
+            (Some(y), Some(m), None) => format!("{y}-{m}"),
+            _ => t.clone(),
+        }
+    }
+}
+
+/// Archive address of `url` as captured at `timestamp`; `flag` picks how
+/// the archive serves it (`""` page, `im_` image, `cs_` stylesheet, `js_`
+/// script, `if_` frame, `id_` unmodified).
+#[must_use]
+pub fn archive_url(timestamp: &str, flag: &str, url: &str) -> String {
+    format!("{ARCHIVE_PREFIX}{timestamp}{flag}/{url}")
+}
+
+/// Whether `url` already points into the archive.
+#[must_use]
+pub fn is_archive_url(url: &str) -> bool {
+    url.strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .is_some_and(|rest| rest.starts_with("web.archive.org/"))
+}
+
+/// Availability API query for `url`.
+#[must_use]
+pub fn availability_url(url: &str) -> String {
+    let query: String = url::form_urlencoded::byte_serialize(url.as_bytes()).collect();
+    format!("{AVAILABILITY_API}{query}")
+}
+
+/// The latest snapshot in an availability API response, if any.
+#[must_use]
+pub fn parse_availability(json: &str) -> Option<Snapshot> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    let closest = value.get("archived_snapshots")?.get("closest")?;
+    if closest
+        .get("available")
+        .and_then(serde_json::Value::as_bool)
+        == Some(false)
+    {
+        return None;
+    }
+    Snapshot::from_archive_url(closest.get("url")?.as_str()?)
+}
+
+/// Ask the archive for the latest snapshot of `url` (blocking).
+///
+/// # Errors
+///
+/// Returns `FetchError` if the archive cannot be reached.
+pub fn find_snapshot(url: &str, options: &FetchOptions) -> Result<Option<Snapshot>, FetchError> {
+    let response = fetch_url_with(&availability_url(url), options)?;
+    Ok(parse_availability(&response.html))
+}
+
+// ── Asset rewriting ──
+
+/// Archive flag for attribute `attr` of a `tag` element, or `None` if it
+/// holds no address.
+fn asset_flag(tag: &str, attr: &str, node: &DomNode) -> Option<&'static str> {
+    Some(match (tag, attr) {
+        ("a" | "area" | "form", "href" | "action") => "",
+        ("img" | "source" | "video" | "audio" | "input" | "track", "src" | "srcset")
+        | ("video", "poster") => "im_",
+        ("script", "src") => "js_",
+        ("iframe" | "frame", "src") => "if_",
+        ("link", "href") => {
+            let rel = node.attributes.get("rel").map(|r| r.to_ascii_lowercase());
+            match rel.as_deref() {
+                Some(r) if r.contains("stylesheet") => "cs_",
+                Some(r) if r.contains("icon") => "im_",
+                _ => "",
+            }
+        }
+        _ => return None,
+    })
+}
+
+/// Archive address for `value` found on a page captured at `timestamp`,
+/// or `None` to leave it alone (fragments, `data:`, `mailto:`, …).
+fn rewrite_one(base: &Url, timestamp: &str, flag: &str, value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || value.starts_with('#') || is_archive_url(value) {
+        return None;
+    }
+    let absolute = base.join(value).ok()?;
+    matches!(absolute.scheme(), "http" | "https")
+        .then(|| archive_url(timestamp, flag, absolute.as_str()))
+}
+
+/// Point every address on a page captured from `original` at `timestamp`
+/// into the archive, resolving relative ones against the original address
+/// (or the page's `<base>`).
+pub fn rewrite_assets(root: &mut DomNode, original: &str, timestamp: &str) {
+    let Ok(mut base) = Url::parse(original) else {
+        return;
+    };
+    if let Some(href) = find_base_href(root) {
+        if let Ok(joined) = base.join(&href) {
+            base = joined;
+        }
+    }
+    rewrite_node(root, &base, timestamp);
+}
+
+fn find_base_href(node: &DomNode) -> Option<String> {
+    if node.tag == "base" {
+        if let Some(href) = node.attributes.get("href") {
+            return Some(href.clone());
+        }
+    }
+    node.children.iter().find_map(find_base_href)
+}
+
+fn rewrite_node(node: &mut DomNode, base: &Url, timestamp: &str) {
+    let tag = node.tag.to_ascii_lowercase();
+    let mut rewritten = Vec::new();
+    for (attr, value) in &node.attributes {
+        let Some(flag) = asset_flag(&tag, attr, node) else {
+            continue;
+        };
+        let new = if attr == "srcset" {
+            // "a.jpg 1x, b.jpg 2x": rewrite each candidate's address
+            let candidates: Vec<String> = value
+                .split(',')
+                .map(|candidate| {
+                    let candidate = candidate.trim();
+                    let (url, descriptor) = candidate
+                        .split_once(char::is_whitespace)
+                        .unwrap_or((candidate, ""));
+                    let url = rewrite_one(base, timestamp, flag, url).unwrap_or_else(|| url.into());
+                    format!("{url} {descriptor}").trim_end().to_string()
+                })
+                .collect();
+            Some(candidates.join(", "))
+        } else {
+            rewrite_one(base, timestamp, flag, value)
+        };
+        if let Some(new) = new {
+            rewritten.push((attr.clone(), new));
+        }
+    }
+    node.attributes.extend(rewritten);
+    if tag == "base" {
+        node.attributes.remove("href");
+    }
+    for child in &mut node.children {
+        rewrite_node(child, base, timestamp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+
+    #[test]
+    fn link_rot_from_status_and_errors() {
+        assert_eq!(LinkRot::from_status(404), Some(LinkRot::NotFound));
+        assert_eq!(LinkRot::from_status(410), Some(LinkRot::Gone));
+        assert_eq!(LinkRot::from_status(500), None);
+        assert_eq!(
+            LinkRot::from_error(
+                "Request failed: error sending request: dns error: failed to lookup address \
+                 information: Name or service not known"
+            ),
+            Some(LinkRot::Unresolvable)
+        );
+        assert_eq!(
+            LinkRot::from_error("Request failed: operation timed out"),
+            None
+        );
+    }
+
+    #[test]
+    fn availability_response_yields_the_snapshot() {
+        let json = r#"{"url": "example.com/old", "archived_snapshots": {"closest":
+            {"status": "200", "available": true,
+             "url": "http://web.archive.org/web/20230115083000/https://example.com/old",
+             "timestamp": "20230115083000"}}}"#;
+        let snapshot = parse_availability(json).unwrap();
+        assert_eq!(
+            snapshot.url,
+            "https://web.archive.org/web/20230115083000/https://example.com/old"
+        );
+        assert_eq!(snapshot.original, "https://example.com/old");
+        assert_eq!(snapshot.date(), "2023-01-15");
+        assert_eq!(
+            snapshot.raw_url(),
+            "https://web.archive.org/web/20230115083000id_/https://example.com/old"
+        );
+        assert!(parse_availability(r#"{"archived_snapshots": {}}"#).is_none());
+        assert!(availability_url("https://a.example/?q=1")
+            .ends_with("https%3A%2F%2Fa.example%2F%3Fq%3D1"));
+    }
+
+    #[test]
+    fn assets_point_into_the_archive() {
+        let mut dom = parse_html(
+            r##"<a href="/docs">Docs</a><a href="#top">Top</a>
+               <img src="img/logo.png" srcset="a.png 1x, /b.png 2x">
+               <link rel="stylesheet" href="site.css"><script src="//cdn.example/app.js"></script>
+               <a href="mailto:me@example.com">Mail</a>
+               <a href="https://web.archive.org/web/2020/https://x.example/">Old</a>"##,
+            "https://web.archive.org/web/20230115083000id_/https://example.com/blog/post",
+        );
+        rewrite_assets(
+            &mut dom.root,
+            "https://example.com/blog/post",
+            "20230115083000",
+        );
+        let mut found = Vec::new();
+        fn walk(node: &DomNode, out: &mut Vec<String>) {
+            for attr in ["href", "src", "srcset"] {
+                if let Some(v) = node.attributes.get(attr) {
+                    out.push(v.clone());
+                }
+            }
+            for child in &node.children {
+                walk(child, out);
+            }
+        }
+        walk(&dom.root, &mut found);
+        let web = "https://web.archive.org/web/20230115083000";
+        assert_eq!(
+            found,
+            [
+                format!("{web}/https://example.com/docs"),
+                "#top".to_string(),
+                format!("{web}im_/https://example.com/blog/img/logo.png"),
+                format!(
+                    "{web}im_/https://example.com/blog/a.png 1x, {web}im_/https://example.com/b.png 2x"
+                ),
+                format!("{web}cs_/https://example.com/blog/site.css"),
+                format!("{web}js_/https://cdn.example/app.js"),
+                "mailto:me@example.com".to_string(),
+                "https://web.archive.org/web/2020/https://x.example/".to_string(),
+            ]
+        );
+    }
+}
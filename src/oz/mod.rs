@@ -9,13 +9,16 @@ use alice_browser::render::stream::TextMeta;
 
 // ─── Data types ──────────────────────────────────────────────────────────────
 
-/// Preview data fetched for a grabbed OZ-mode link.
+/// Preview data fetched for a grabbed OZ-mode link or a hovered Flat-mode
+/// link.
 #[derive(Clone)]
 pub struct LinkPreview {
     pub _url: String,
     pub title: String,
     pub description: String,
     pub texts: Vec<String>,
+    /// Absolute address of the page's icon (`/favicon.ico` if it names none)
+    pub favicon: Option<String>,
     pub status: LinkPreviewStatus,
 }
 
@@ -30,6 +33,10 @@ pub enum LinkPreviewStatus {
 
 /// Resolve a potentially relative URL against a base URL.
 pub fn resolve_url(base: &str, href: &str) -> String {
+    // Fragment-only links stay on the base document
+    if let Some(fragment) = href.strip_prefix('#') {
+        return format!("{}#{fragment}", split_fragment(base).0);
+    }
     if href.starts_with("http://") || href.starts_with("https://") {
         return href.to_string();
     }
@@ -44,6 +51,15 @@ pub fn resolve_url(base: &str, href: &str) -> String {
     href.to_string()
 }
 
+/// Split `url` at its `#`: the address without the fragment, and the
+/// fragment (without the `#`) if there is one.
+pub fn split_fragment(url: &str) -> (&str, Option<&str>) {
+    match url.split_once('#') {
+        Some((address, fragment)) => (address, Some(fragment)),
+        None => (url, None),
+    }
+}
+
 // ─── DOM href collection ─────────────────────────────────────────────────────
 
 /// Collect unique hrefs from a `DomNode` tree, resolved to absolute URLs.
@@ -155,6 +171,9 @@ pub fn fetch_link_preview(url: &str) -> LinkPreview {
             };
 
             let description = extract_meta_description(&dom.root);
+            let favicon = extract_favicon(&dom.root)
+                .map(|href| resolve_url(&result.url, &href))
+                .or_else(|| Some(resolve_url(&result.url, "/favicon.ico")));
 
             let mut headings = Vec::new();
             let mut paragraphs = Vec::new();
@@ -183,6 +202,7 @@ pub fn fetch_link_preview(url: &str) -> LinkPreview {
                 title,
                 description,
                 texts,
+                favicon,
                 status: LinkPreviewStatus::Ready,
             }
         }
@@ -191,6 +211,7 @@ pub fn fetch_link_preview(url: &str) -> LinkPreview {
             title: String::new(),
             description: String::new(),
             texts: Vec::new(),
+            favicon: None,
             status: LinkPreviewStatus::Error(e.to_string()),
         },
     }
@@ -241,6 +262,26 @@ fn extract_meta_description(node: &DomNode) -> String {
     String::new()
 }
 
+/// The `href` of the first `<link rel="icon">` (or `shortcut icon`,
+/// `apple-touch-icon`) in the tree.
+fn extract_favicon(node: &DomNode) -> Option<String> {
+    if node.tag == "link" {
+        let rel = node
+            .attributes
+            .get("rel")
+            .map(|s| s.to_lowercase())
+            .unwrap_or_default();
+        if rel.split_whitespace().any(|r| r.ends_with("icon")) {
+            if let Some(href) = node.attributes.get("href") {
+                if !href.trim().is_empty() {
+                    return Some(href.trim().to_string());
+                }
+            }
+        }
+    }
+    node.children.iter().find_map(extract_favicon)
+}
+
 /// Extract texts ranked by importance: headings, paragraphs, then others.
 fn extract_preview_texts_ranked(
     node: &DomNode,
@@ -0,0 +1,85 @@
+//! On-disk profile location.
+//!
+//! Everything the browser keeps between sessions lives in one directory:
+//! `$ALICE_BROWSER_HOME` if set, otherwise `~/.alice-browser`. Files holding
+//! secrets (cookies, autofill profiles) are written with [`write_private`];
+//! autofill profiles are also encrypted ([`crate::vault`]).
+
+use std::path::PathBuf;
+
+/// Environment variable overriding the profile directory.
+pub const HOME_ENV: &str = "ALICE_BROWSER_HOME";
+
+/// Profile directory, or `None` if no home directory can be determined.
+#[must_use]
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(HOME_ENV).filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|v| !v.is_empty())
+        .map(|home| PathBuf::from(home).join(".alice-browser"))
+}
+
+/// Path of a file inside the profile directory, creating the directory.
+///
+/// # Errors
+///
+/// Returns an I/O error if there is no home directory or it cannot be created.
+pub fn file_path(name: &str) -> std::io::Result<PathBuf> {
+    let dir = data_dir()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no profile directory"))?;
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(name))
+}
+
+/// Write `contents` to `path`, readable and writable by its owner only on
+/// Unix (mode `0o600`, also for a file that already existed).
+///
+/// # Errors
+///
+/// Returns an I/O error if the file cannot be written.
+#[cfg(unix)]
+pub fn write_private(path: &std::path::Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    // `mode` only applies when the file is created
+    file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents.as_ref())
+}
+
+/// Write `contents` to `path`.
+///
+/// # Errors
+///
+/// Returns an I/O error if the file cannot be written.
+#[cfg(not(unix))]
+pub fn write_private(path: &std::path::Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn private_files_are_owner_only() {
+        let path = std::env::temp_dir().join(format!("alice-private-{}", std::process::id()));
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        write_private(&path, "secret").unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "secret");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,501 @@
+//! Bounding volume hierarchy over scene primitives.
+//!
+//! A page's 3-D scene can hold thousands of primitives, and a raymarcher
+//! that takes the union of all of them pays for every one at every step.
+//! [`Bvh`] groups the primitives' bounding boxes into a binary tree so a
+//! distance query only evaluates the primitives whose boxes are nearer than
+//! the closest surface found so far ([`Bvh::nearest`]).
+//!
+//! The tree is stored flat, ready for upload to the GPU: nodes in one
+//! array, children of an interior node next to each other, and the leaves'
+//! primitives as contiguous ranges of [`Bvh::order`]. Both raymarchers store
+//! their per-primitive data in that order.
+
+use crate::render::sdf_ui::{SdfPrimitive, SdfScene};
+
+/// Most primitives in a leaf.
+pub const LEAF_SIZE: usize = 4;
+
+/// Deepest a tree gets; below it, nodes become leaves whatever their size.
+/// Keeps traversal within a fixed-size stack ([`STACK_SIZE`]).
+pub const MAX_DEPTH: usize = 30;
+
+/// Stack entries a traversal needs at most.
+pub const STACK_SIZE: usize = MAX_DEPTH + 2;
+
+/// Axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl Aabb {
+    /// Contains nothing; the identity of [`union`](Self::union).
+    pub const EMPTY: Self = Self {
+        min: [f32::MAX; 3],
+        max: [f32::MIN; 3],
+    };
+
+    #[must_use]
+    pub fn around(center: [f32; 3], half: [f32; 3]) -> Self {
+        Self {
+            min: [0, 1, 2].map(|i| center[i] - half[i]),
+            max: [0, 1, 2].map(|i| center[i] + half[i]),
+        }
+    }
+
+    /// Bounds of the surface the raymarchers draw for `prim` (text labels
+    /// and billboards are thin boxes, as in the renderers).
+    #[must_use]
+    pub fn of(prim: &SdfPrimitive) -> Self {
+        match prim {
+            SdfPrimitive::RoundedBox {
+                center,
+                size,
+                radius,
+                ..
+            } => {
+                // The core shrinks by the rounding, but never below 0.001
+                let r = if *radius > 0.001 { *radius } else { 0.0 };
+                let half = size.map(|s| 2.0f32.mul_add(-r, s).max(0.001).mul_add(0.5, r));
+                Self::around(*center, half)
+            }
+            SdfPrimitive::Plane { center, size, .. } => {
+                Self::around(*center, [size[0] * 0.5, size[1] * 0.5, 0.02])
+            }
+            SdfPrimitive::TextLabel {
+                position,
+                font_size,
+                text,
+                ..
+            } => {
+                let w = text.len().min(40) as f32 * font_size * 0.5;
+                Self::around(*position, [w * 0.5, font_size * 0.5, 0.005])
+            }
+            SdfPrimitive::Line {
+                start,
+                end,
+                thickness,
+                ..
+            } => {
+                let r = thickness * 0.5;
+                Self {
+                    min: [0, 1, 2].map(|i| start[i].min(end[i]) - r),
+                    max: [0, 1, 2].map(|i| start[i].max(end[i]) + r),
+                }
+            }
+            SdfPrimitive::Sphere { center, radius, .. } => Self::around(*center, [*radius; 3]),
+            SdfPrimitive::Billboard { position, size, .. } => {
+                Self::around(*position, [size[0] * 0.5, size[1] * 0.5, 0.0025])
+            }
+            SdfPrimitive::Torus {
+                center,
+                major_radius,
+                minor_radius,
+                ..
+            } => {
+                let r = major_radius + minor_radius;
+                Self::around(*center, [r, *minor_radius, r])
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            min: [0, 1, 2].map(|i| self.min[i].min(other.min[i])),
+            max: [0, 1, 2].map(|i| self.max[i].max(other.max[i])),
+        }
+    }
+
+    #[must_use]
+    pub fn center(&self) -> [f32; 3] {
+        [0, 1, 2].map(|i| (self.min[i] + self.max[i]) * 0.5)
+    }
+
+    /// Distance from `p` to the box; 0 inside it. Never more than the
+    /// distance to any surface the box bounds.
+    #[must_use]
+    pub fn distance(&self, p: [f32; 3]) -> f32 {
+        let q = [0, 1, 2].map(|i| (self.min[i] - p[i]).max(p[i] - self.max[i]).max(0.0));
+        q[2].mul_add(q[2], q[0].mul_add(q[0], q[1] * q[1])).sqrt()
+    }
+
+    /// Where the ray from `origin` along the direction whose components'
+    /// reciprocals are `inv_dir` enters and leaves the box, as distances
+    /// along it; `None` if it misses or the box is behind it.
+    #[must_use]
+    pub fn ray_span(&self, origin: [f32; 3], inv_dir: [f32; 3]) -> Option<(f32, f32)> {
+        let mut enter = 0.0f32;
+        let mut exit = f32::INFINITY;
+        for i in 0..3 {
+            let a = (self.min[i] - origin[i]) * inv_dir[i];
+            let b = (self.max[i] - origin[i]) * inv_dir[i];
+            // NaN (a flat axis the ray runs along the face of) keeps the span
+            enter = enter.max(a.min(b));
+            exit = exit.min(a.max(b));
+        }
+        (enter <= exit).then_some((enter, exit))
+    }
+}
+
+/// One node of a [`Bvh`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BvhNode {
+    pub bounds: Aabb,
+    /// Leaf: first slot in [`Bvh::order`]. Interior: index of the left
+    /// child; the right one follows it.
+    pub first: u32,
+    /// Primitives in a leaf; 0 for an interior node
+    pub count: u32,
+}
+
+impl BvhNode {
+    #[must_use]
+    pub const fn is_leaf(&self) -> bool {
+        self.count > 0
+    }
+}
+
+/// Bounding volume hierarchy built by median splits along the longest axis.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bvh {
+    /// Root first; empty for an empty scene
+    pub nodes: Vec<BvhNode>,
+    /// Primitive indices in leaf order: a leaf covers
+    /// `order[first..first + count]`
+    pub order: Vec<u32>,
+}
+
+impl Bvh {
+    /// Build over the primitives of `scene`.
+    #[must_use]
+    pub fn from_scene(scene: &SdfScene) -> Self {
+        let bounds: Vec<Aabb> = scene.primitives.iter().map(Aabb::of).collect();
+        Self::build(&bounds)
+    }
+
+    /// Build over the primitives of `scene` listed in `indices`;
+    /// [`order`](Self::order) holds their indices in the scene.
+    #[must_use]
+    pub fn from_subset(scene: &SdfScene, indices: &[u32]) -> Self {
+        let bounds: Vec<Aabb> = indices
+            .iter()
+            .map(|&i| Aabb::of(&scene.primitives[i as usize]))
+            .collect();
+        let mut bvh = Self::build(&bounds);
+        for slot in &mut bvh.order {
+            *slot = indices[*slot as usize];
+        }
+        bvh
+    }
+
+    /// Build over primitives with the given bounds.
+    #[must_use]
+    pub fn build(bounds: &[Aabb]) -> Self {
+        let mut bvh = Self {
+            nodes: Vec::with_capacity((bounds.len() / LEAF_SIZE + 1) * 2),
+            order: (0..bounds.len() as u32).collect(),
+        };
+        if !bounds.is_empty() {
+            bvh.nodes.push(BvhNode {
+                bounds: Aabb::EMPTY,
+                first: 0,
+                count: 0,
+            });
+            bvh.split(0, 0, bounds.len(), 0, bounds);
+        }
+        bvh
+    }
+
+    /// Fill node `index` with `order[start..end]`, splitting it further
+    /// unless it is small enough (or deep enough) to be a leaf.
+    fn split(&mut self, index: usize, start: usize, end: usize, depth: usize, bounds: &[Aabb]) {
+        let items = &mut self.order[start..end];
+        let node_bounds = items
+            .iter()
+            .fold(Aabb::EMPTY, |b, &i| b.union(bounds[i as usize]));
+        self.nodes[index].bounds = node_bounds;
+        if items.len() <= LEAF_SIZE || depth >= MAX_DEPTH {
+            self.nodes[index].first = start as u32;
+            self.nodes[index].count = items.len() as u32;
+            return;
+        }
+
+        // Median split on the axis the centres spread furthest along
+        let centers = items.iter().fold(Aabb::EMPTY, |b, &i| {
+            let c = bounds[i as usize].center();
+            b.union(Aabb { min: c, max: c })
+        });
+        let spread = [0, 1, 2].map(|i| centers.max[i] - centers.min[i]);
+        let axis = (0..3)
+            .max_by(|&a, &b| spread[a].total_cmp(&spread[b]))
+            .unwrap_or(0);
+        let mid = items.len() / 2;
+        items.select_nth_unstable_by(mid, |&a, &b| {
+            bounds[a as usize].center()[axis].total_cmp(&bounds[b as usize].center()[axis])
+        });
+
+        let left = self.nodes.len();
+        let child = BvhNode {
+            bounds: Aabb::EMPTY,
+            first: 0,
+            count: 0,
+        };
+        self.nodes.extend([child, child]);
+        self.nodes[index].first = left as u32;
+        self.split(left, start, start + mid, depth + 1, bounds);
+        self.split(left + 1, start + mid, end, depth + 1, bounds);
+    }
+
+    /// Levels below the root.
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        fn walk(bvh: &Bvh, index: usize) -> usize {
+            let node = bvh.nodes[index];
+            if node.is_leaf() {
+                0
+            } else {
+                1 + walk(bvh, node.first as usize).max(walk(bvh, node.first as usize + 1))
+            }
+        }
+        if self.nodes.is_empty() {
+            0
+        } else {
+            walk(self, 0)
+        }
+    }
+
+    /// The primitive nearest `p`: its slot in [`order`](Self::order) and
+    /// its distance, as given by `distance(slot)`. Only primitives whose
+    /// boxes are nearer than the best distance so far are evaluated.
+    pub fn nearest(
+        &self,
+        p: [f32; 3],
+        mut distance: impl FnMut(usize) -> f32,
+    ) -> Option<(usize, f32)> {
+        let mut best: Option<(usize, f32)> = None;
+        let mut best_d = f32::INFINITY;
+        let mut stack = [0u32; STACK_SIZE];
+        let mut len = usize::from(!self.nodes.is_empty());
+        while len > 0 {
+            len -= 1;
+            let node = self.nodes[stack[len] as usize];
+            if node.bounds.distance(p) >= best_d {
+                continue;
+            }
+            if node.is_leaf() {
+                let first = node.first as usize;
+                for slot in first..first + node.count as usize {
+                    let d = distance(slot);
+                    if d < best_d {
+                        best_d = d;
+                        best = Some((slot, d));
+                    }
+                }
+            } else {
+                // Push the farther child first so the nearer one is visited
+                // first and tightens the bound sooner
+                let left = node.first;
+                let d_left = self.nodes[left as usize].bounds.distance(p);
+                let d_right = self.nodes[left as usize + 1].bounds.distance(p);
+                let (near, far) = if d_left <= d_right {
+                    (left, left + 1)
+                } else {
+                    (left + 1, left)
+                };
+                stack[len] = far;
+                stack[len + 1] = near;
+                len += 2;
+            }
+        }
+        best
+    }
+
+    /// The first primitive the ray from `origin` along unit `dir` hits:
+    /// its slot in [`order`](Self::order) and the distance along the ray.
+    /// `hit(slot, enter, exit)` finds where the ray meets a primitive of a
+    /// leaf whose box it crosses between `enter` and `exit`. Leaves are
+    /// skipped once their box starts beyond the nearest hit so far.
+    pub fn raycast(
+        &self,
+        origin: [f32; 3],
+        dir: [f32; 3],
+        mut hit: impl FnMut(usize, f32, f32) -> Option<f32>,
+    ) -> Option<(usize, f32)> {
+        let inv_dir = dir.map(f32::recip);
+        let mut best: Option<(usize, f32)> = None;
+        let mut best_t = f32::INFINITY;
+        let mut stack = [0u32; STACK_SIZE];
+        let mut len = usize::from(!self.nodes.is_empty());
+        while len > 0 {
+            len -= 1;
+            let node = self.nodes[stack[len] as usize];
+            let Some((enter, exit)) = node.bounds.ray_span(origin, inv_dir) else {
+                continue;
+            };
+            if enter >= best_t {
+                continue;
+            }
+            if node.is_leaf() {
+                let first = node.first as usize;
+                for slot in first..first + node.count as usize {
+                    if let Some(t) = hit(slot, enter, exit.min(best_t)) {
+                        if t < best_t {
+                            best_t = t;
+                            best = Some((slot, t));
+                        }
+                    }
+                }
+            } else {
+                stack[len] = node.first;
+                stack[len + 1] = node.first + 1;
+                len += 2;
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sphere_scene(n: usize) -> SdfScene {
+        // Deterministic scatter over a 20×10×20 volume
+        let mut seed = 0x2545_f491_u32;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            (seed % 10_000) as f32 / 10_000.0
+        };
+        SdfScene {
+            primitives: (0..n)
+                .map(|_| SdfPrimitive::Sphere {
+                    center: [next() * 20.0, next() * 10.0, next() * 20.0],
+                    radius: next().mul_add(0.5, 0.05),
+                    color: [1.0; 4],
+                })
+                .collect(),
+            background_color: [0.0; 4],
+        }
+    }
+
+    fn sphere_distance(prim: &SdfPrimitive, p: [f32; 3]) -> f32 {
+        let SdfPrimitive::Sphere { center, radius, .. } = prim else {
+            unreachable!()
+        };
+        let d = [0, 1, 2].map(|i| p[i] - center[i]);
+        d[2].mul_add(d[2], d[0].mul_add(d[0], d[1] * d[1])).sqrt() - radius
+    }
+
+    #[test]
+    fn leaves_cover_every_primitive_once() {
+        let scene = sphere_scene(1_000);
+        let bvh = Bvh::from_scene(&scene);
+        let mut order = bvh.order.clone();
+        order.sort_unstable();
+        assert_eq!(order, (0..1_000).collect::<Vec<u32>>());
+        let covered: u32 = bvh.nodes.iter().map(|n| n.count).sum();
+        assert_eq!(covered, 1_000);
+        assert!(bvh
+            .nodes
+            .iter()
+            .all(|n| !n.is_leaf() || n.count as usize <= LEAF_SIZE));
+        assert!(bvh.depth() <= 10, "depth {}", bvh.depth());
+        assert!(Bvh::from_scene(&sphere_scene(0)).nodes.is_empty());
+    }
+
+    #[test]
+    fn nearest_matches_brute_force_with_fewer_evaluations() {
+        let scene = sphere_scene(500);
+        let bvh = Bvh::from_scene(&scene);
+        let mut evaluated = 0;
+        for k in 0..50 {
+            let p = [k as f32 * 0.4, 5.0 - k as f32 * 0.1, 20.0 - k as f32 * 0.4];
+            let brute = scene
+                .primitives
+                .iter()
+                .map(|prim| sphere_distance(prim, p))
+                .fold(f32::INFINITY, f32::min);
+            let (slot, d) = bvh
+                .nearest(p, |slot| {
+                    evaluated += 1;
+                    sphere_distance(&scene.primitives[bvh.order[slot] as usize], p)
+                })
+                .unwrap();
+            assert!((d - brute).abs() < 1e-5, "{d} vs {brute}");
+            let prim = &scene.primitives[bvh.order[slot] as usize];
+            assert!((sphere_distance(prim, p) - brute).abs() < 1e-5);
+        }
+        assert!(evaluated < 50 * 500 / 5, "{evaluated} evaluations");
+    }
+
+    #[test]
+    fn raycast_finds_the_first_sphere_hit() {
+        let scene = sphere_scene(500);
+        let bvh = Bvh::from_scene(&scene);
+        // Analytic ray–sphere intersection
+        let hit_sphere = |prim: &SdfPrimitive, origin: [f32; 3], dir: [f32; 3]| {
+            let SdfPrimitive::Sphere { center, radius, .. } = prim else {
+                unreachable!()
+            };
+            let oc = [0, 1, 2].map(|i| origin[i] - center[i]);
+            let b = (0..3).map(|i| oc[i] * dir[i]).sum::<f32>();
+            let c = (0..3).map(|i| oc[i] * oc[i]).sum::<f32>() - radius * radius;
+            let disc = b.mul_add(b, -c);
+            (disc >= 0.0)
+                .then(|| -b - disc.sqrt())
+                .filter(|&t| t >= 0.0)
+        };
+        let mut hits = 0;
+        for k in 0..40 {
+            let origin = [-5.0, k as f32 * 0.25, 10.0];
+            let len = (1.0f32 + 0.01 + 0.04).sqrt();
+            let dir = [1.0 / len, 0.1 / len, 0.2 / len];
+            let brute = scene
+                .primitives
+                .iter()
+                .filter_map(|prim| hit_sphere(prim, origin, dir))
+                .fold(f32::INFINITY, f32::min);
+            let found = bvh.raycast(origin, dir, |slot, _, _| {
+                hit_sphere(&scene.primitives[bvh.order[slot] as usize], origin, dir)
+            });
+            match found {
+                Some((_, t)) => {
+                    hits += 1;
+                    assert!((t - brute).abs() < 1e-4, "{t} vs {brute}");
+                }
+                None => assert!(brute.is_infinite()),
+            }
+        }
+        assert!(hits > 0);
+    }
+
+    #[test]
+    fn boxes_bound_their_primitives() {
+        let text = SdfPrimitive::TextLabel {
+            position: [1.0, 2.0, 0.0],
+            text: "abcd".to_string(),
+            font_size: 0.5,
+            color: [1.0; 4],
+        };
+        let b = Aabb::of(&text);
+        assert_eq!(b.min, [0.5, 1.75, -0.005]);
+        assert_eq!(b.max, [1.5, 2.25, 0.005]);
+        assert_eq!(b.distance([1.0, 2.0, 0.0]), 0.0);
+        assert!((b.distance([2.5, 2.0, 0.0]) - 1.0).abs() < 1e-6);
+
+        let line = SdfPrimitive::Line {
+            start: [0.0, 1.0, 0.0],
+            end: [2.0, -1.0, 0.0],
+            thickness: 0.2,
+            color: [1.0; 4],
+        };
+        let b = Aabb::of(&line);
+        assert_eq!(b.min, [-0.1, -1.1, -0.1]);
+        assert_eq!(b.max, [2.1, 1.1, 0.1]);
+    }
+}
@@ -0,0 +1,186 @@
+//! Clipboard flavours of rendered content.
+//!
+//! Copying part of a page puts two representations on the clipboard: basic
+//! HTML that keeps headings, paragraphs, lists, links, emphasis and tables
+//! (for rich-text editors), and plain text with the block structure kept as
+//! line breaks. Both are produced from the laid-out tree, so whatever the
+//! filter removed stays out of the copy as well. Layout-only wrappers
+//! (`div`, `span`, `section`, …) are dropped and their content kept.
+
+use crate::engine::internal::escape;
+use crate::render::layout::LayoutNode;
+
+/// Tags kept in copied HTML.
+const KEPT_TAGS: &[&str] = &[
+    "p",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "a",
+    "ul",
+    "ol",
+    "li",
+    "strong",
+    "b",
+    "em",
+    "i",
+    "u",
+    "s",
+    "code",
+    "pre",
+    "blockquote",
+    "table",
+    "caption",
+    "tr",
+    "td",
+    "th",
+];
+
+/// Both clipboard flavours of one subtree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardContent {
+    pub html: String,
+    pub text: String,
+}
+
+impl ClipboardContent {
+    #[must_use]
+    pub fn from_layout(node: &LayoutNode) -> Self {
+        Self {
+            html: to_html(node),
+            text: to_text(node),
+        }
+    }
+}
+
+/// Basic HTML for `node` and its subtree.
+#[must_use]
+pub fn to_html(node: &LayoutNode) -> String {
+    let mut out = String::new();
+    write_html(node, &mut out);
+    out
+}
+
+fn write_html(node: &LayoutNode, out: &mut String) {
+    let tag = node.tag.as_str();
+    match tag {
+        "br" => return out.push_str("<br>"),
+        "hr" => return out.push_str("<hr>"),
+        "img" => {
+            if let Some(ref src) = node.href {
+                out.push_str(&format!("<img src=\"{}\">", escape(src)));
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    let kept = KEPT_TAGS.contains(&tag);
+    if kept {
+        out.push('<');
+        out.push_str(tag);
+        if let (Some(href), "a") = (node.href.as_ref(), tag) {
+            out.push_str(&format!(" href=\"{}\"", escape(href)));
+        }
+        out.push('>');
+    }
+    out.push_str(&escape(&node.text));
+    for child in &node.children {
+        write_html(child, out);
+    }
+    if kept {
+        out.push_str("</");
+        out.push_str(tag);
+        out.push('>');
+    }
+}
+
+/// Plain text for `node` and its subtree: one line per block, `- ` before
+/// list items, tabs between table cells.
+#[must_use]
+pub fn to_text(node: &LayoutNode) -> String {
+    let mut out = String::new();
+    write_text(node, &mut out);
+    // Collapse the blank lines left by nested blocks
+    let lines: Vec<&str> = out
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+    lines.join("\n")
+}
+
+fn write_text(node: &LayoutNode, out: &mut String) {
+    let tag = node.tag.as_str();
+    match tag {
+        "li" => out.push_str("\n- "),
+        "td" | "th" if !out.ends_with('\n') && !out.is_empty() => out.push('\t'),
+        "br" => out.push('\n'),
+        _ if node.is_block => out.push('\n'),
+        _ => {}
+    }
+
+    let text = node.text.trim();
+    if !text.is_empty() {
+        let needs_space =
+            !node.is_block && !out.is_empty() && !out.ends_with(|c: char| c.is_whitespace());
+        if needs_space {
+            out.push(' ');
+        }
+        out.push_str(text);
+    }
+    for child in &node.children {
+        write_text(child, out);
+    }
+
+    if node.is_block && !matches!(tag, "td" | "th") {
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+    use crate::render::layout::compute_layout;
+
+    fn body(html: &str) -> LayoutNode {
+        let dom = parse_html(html, "https://example.com");
+        compute_layout(&dom.root, 800.0)
+    }
+
+    #[test]
+    fn html_keeps_basic_formatting() {
+        let layout = body(
+            "<html><body><div class=\"wrap\"><h2>Title</h2>\
+             <p>Some <strong>bold</strong> &amp; <a href=\"/x?a=1&b=2\">linked</a> text</p>\
+             <ul><li>one</li><li>two</li></ul></div></body></html>",
+        );
+        let html = to_html(&layout);
+        assert!(html.contains("<h2>Title</h2>"));
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(html.contains("&amp;"));
+        assert!(html.contains("<a href=\"/x?a=1&amp;b=2\">linked</a>"));
+        assert!(html.contains("<ul><li>one</li><li>two</li></ul>"));
+        assert!(!html.contains("div"));
+    }
+
+    #[test]
+    fn text_keeps_block_structure() {
+        let layout = body(
+            "<html><body><h1>Title</h1><p>First <em>para</em>.</p>\
+             <ul><li>one</li><li>two</li></ul>\
+             <table><tr><th>A</th><th>B</th></tr><tr><td>1</td><td>2</td></tr></table>\
+             </body></html>",
+        );
+        let text = to_text(&layout);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "Title");
+        assert!(lines.contains(&"- one"));
+        assert!(lines.contains(&"- two"));
+        assert!(lines.iter().any(|l| l.contains("A\tB")), "{text}");
+    }
+}
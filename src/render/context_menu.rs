@@ -0,0 +1,245 @@
+//! The page's context menu: what a right-click lands on, and what can be
+//! done with it.
+//!
+//! A [`MenuTarget`] gathers the link, image, text and blockable element
+//! under the pointer — hit-tested against the paint elements and the
+//! layout's [`SelectorBox`]es in the SDF view, or taken from the widget's
+//! own `LayoutNode` in the flat view — and [`items`] draws its entries. The
+//! entry chosen comes back as a [`MenuAction`] for the app to carry out;
+//! blocking an element goes through the site's classifier corrections
+//! ([`crate::dom::filter::FeedbackRules`]), the rules the filter applies on
+//! every later load.
+
+use crate::render::clipboard;
+use crate::render::layout::LayoutNode;
+use crate::render::sdf_ui::{PaintElement, PaintKind};
+
+/// Box, in page coordinates, of an element named by a
+/// [`crate::dom::filter::selector`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectorBox {
+    pub rect: [f32; 4],
+    pub selector: String,
+}
+
+/// Every laid-out element of the tree with a selector, outermost first.
+#[must_use]
+pub fn selector_boxes(root: &LayoutNode) -> Vec<SelectorBox> {
+    fn collect(node: &LayoutNode, out: &mut Vec<SelectorBox>) {
+        let b = &node.bounds;
+        if let Some(ref selector) = node.selector {
+            if b.width > 0.0 && b.height > 0.0 {
+                out.push(SelectorBox {
+                    rect: [b.x, b.y, b.width, b.height],
+                    selector: selector.clone(),
+                });
+            }
+        }
+        for child in &node.children {
+            collect(child, out);
+        }
+    }
+    let mut out = Vec::new();
+    collect(root, &mut out);
+    out
+}
+
+fn contains(rect: [f32; 4], x: f32, y: f32) -> bool {
+    x >= rect[0] && x <= rect[0] + rect[2] && y >= rect[1] && y <= rect[1] + rect[3]
+}
+
+/// What a context menu was opened on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MenuTarget {
+    /// Link, as written in the page
+    pub link: Option<String>,
+    /// Image source
+    pub image: Option<String>,
+    /// Text of the element
+    pub text: String,
+    /// Selector of the innermost element around it that has one
+    pub selector: Option<String>,
+}
+
+impl MenuTarget {
+    /// Target for the flat view's widget drawn for `node`; `named` is the
+    /// innermost element around it with a selector.
+    #[must_use]
+    pub fn for_node(node: &LayoutNode, named: Option<&LayoutNode>) -> Self {
+        Self {
+            link: node.href.clone().filter(|_| node.tag == "a"),
+            image: node.href.clone().filter(|_| node.tag == "img"),
+            text: clipboard::to_text(node).trim().to_string(),
+            selector: named.and_then(|n| n.selector.clone()),
+        }
+    }
+
+    /// Target at page point (`x`, `y`) of the SDF view: the topmost link,
+    /// image and text drawn there, and the innermost of `boxes`.
+    #[must_use]
+    pub fn at_point(elements: &[PaintElement], boxes: &[SelectorBox], x: f32, y: f32) -> Self {
+        let mut target = Self::default();
+        for elem in elements.iter().rev().filter(|e| contains(e.rect, x, y)) {
+            if target.link.is_none() {
+                target.link.clone_from(&elem.href);
+            }
+            if target.image.is_none() && elem.kind == PaintKind::ImagePlaceholder {
+                target.image.clone_from(&elem.image_url);
+            }
+            if target.text.is_empty()
+                && matches!(
+                    elem.kind,
+                    PaintKind::Text | PaintKind::Heading | PaintKind::Link
+                )
+            {
+                if let Some(ref text) = elem.text {
+                    target.text = text.trim().to_string();
+                }
+            }
+        }
+        target.selector = boxes
+            .iter()
+            .filter(|b| contains(b.rect, x, y))
+            .min_by(|a, b| (a.rect[2] * a.rect[3]).total_cmp(&(b.rect[2] * b.rect[3])))
+            .map(|b| b.selector.clone());
+        target
+    }
+
+    /// Whether there is nothing to offer.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.link.is_none()
+            && self.image.is_none()
+            && self.text.is_empty()
+            && self.selector.is_none()
+    }
+}
+
+/// An entry chosen from the context menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuAction {
+    /// Follow the link
+    Open(String),
+    /// Queue the link to open after this page (the browser has no tabs)
+    OpenInBackground(String),
+    /// Copy the link's address
+    CopyLink(String),
+    CopyText(String),
+    /// Copy the image's pixels
+    CopyImage(String),
+    /// Save the image to the profile's `saved` directory
+    SaveImage(String),
+    /// Remove elements matching this selector from the site's pages
+    Block(String),
+}
+
+/// Draw the entries for `target` in an open context menu, grouped by link,
+/// image, text and element. Returns the entry chosen, closing the menu.
+pub fn items(ui: &mut egui::Ui, target: &MenuTarget) -> Option<MenuAction> {
+    let mut groups: Vec<Vec<(&str, MenuAction)>> = Vec::new();
+    if let Some(ref link) = target.link {
+        groups.push(vec![
+            ("Open link", MenuAction::Open(link.clone())),
+            (
+                "Open link in background",
+                MenuAction::OpenInBackground(link.clone()),
+            ),
+            ("Copy link address", MenuAction::CopyLink(link.clone())),
+        ]);
+    }
+    if let Some(ref image) = target.image {
+        groups.push(vec![
+            ("Copy image", MenuAction::CopyImage(image.clone())),
+            ("Copy image address", MenuAction::CopyLink(image.clone())),
+            ("Save image", MenuAction::SaveImage(image.clone())),
+        ]);
+    }
+    if !target.text.is_empty() {
+        groups.push(vec![(
+            "Copy text",
+            MenuAction::CopyText(target.text.clone()),
+        )]);
+    }
+
+    let mut chosen = None;
+    for (i, group) in groups.into_iter().enumerate() {
+        if i > 0 {
+            ui.separator();
+        }
+        for (label, action) in group {
+            if ui.button(label).clicked() {
+                chosen = Some(action);
+            }
+        }
+    }
+    if let Some(ref selector) = target.selector {
+        if target.link.is_some() || target.image.is_some() || !target.text.is_empty() {
+            ui.separator();
+        }
+        if ui
+            .button("Block this element")
+            .on_hover_text(format!("Remove {selector} from this site's pages"))
+            .clicked()
+        {
+            chosen = Some(MenuAction::Block(selector.clone()));
+        }
+    }
+    if chosen.is_some() {
+        ui.close_menu();
+    }
+    chosen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+    use crate::render::layout::compute_layout;
+    use crate::render::sdf_ui::layout_to_paint;
+
+    #[test]
+    fn point_hits_link_text_and_innermost_named_element() {
+        let dom = parse_html(
+            "<div class=\"story\"><p>Intro</p>\
+             <div class=\"promo\"><a href=\"/offer\">Great offer</a></div></div>",
+            "https://example.com/",
+        );
+        let layout = compute_layout(&dom.root, 800.0);
+        let elements = layout_to_paint(&layout);
+        let boxes = selector_boxes(&layout);
+        assert_eq!(boxes[0].selector, "div.story");
+
+        let link = elements
+            .iter()
+            .find(|e| e.href.as_deref() == Some("/offer"))
+            .unwrap();
+        let (x, y) = (link.rect[0] + 2.0, link.rect[1] + 2.0);
+        let target = MenuTarget::at_point(&elements, &boxes, x, y);
+        assert_eq!(target.link.as_deref(), Some("/offer"));
+        assert_eq!(target.text, "Great offer");
+        assert_eq!(target.selector.as_deref(), Some("div.promo"));
+        assert!(target.image.is_none());
+
+        let nothing = MenuTarget::at_point(&elements, &boxes, -10.0, -10.0);
+        assert!(nothing.is_empty());
+    }
+
+    #[test]
+    fn flat_widgets_name_their_own_node() {
+        fn find<'a>(node: &'a LayoutNode, tag: &str) -> Option<&'a LayoutNode> {
+            if node.tag == tag {
+                return Some(node);
+            }
+            node.children.iter().find_map(|c| find(c, tag))
+        }
+        let dom = parse_html(
+            "<div class=\"gallery\"><img src=\"https://example.com/a.png\"></div>",
+            "https://example.com/",
+        );
+        let layout = compute_layout(&dom.root, 800.0);
+        let target = MenuTarget::for_node(find(&layout, "img").unwrap(), find(&layout, "div"));
+        assert_eq!(target.image.as_deref(), Some("https://example.com/a.png"));
+        assert_eq!(target.selector.as_deref(), Some("div.gallery"));
+        assert!(target.link.is_none());
+    }
+}
@@ -0,0 +1,208 @@
+//! Resource-cost heat map for the timing overlay.
+//!
+//! Splits the laid-out page into its smallest block-level units (and
+//! images) and measures, for each, the three things that make pages slow:
+//! text bytes to parse, encoded image bytes to download and decode, and
+//! layout time. The layout pass is timed as a whole by the stage profiler
+//! and apportioned to units by the number of layout nodes they contain.
+//! Each dimension is normalised against the most expensive unit on the
+//! page; an element's heat is its worst dimension.
+
+use crate::engine::profiler::StageTimings;
+use crate::render::layout::LayoutNode;
+use crate::render::sdf_ui::image_rect;
+
+/// Which cost dominates an element's heat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostKind {
+    Text,
+    Image,
+    Layout,
+}
+
+impl CostKind {
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Image => "image",
+            Self::Layout => "layout",
+        }
+    }
+}
+
+/// Cost of one rendered unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementCost {
+    /// Layout-space rectangle `[x, y, w, h]`
+    pub rect: [f32; 4],
+    pub tag: String,
+    /// Text bytes in the subtree
+    pub text_bytes: usize,
+    /// Encoded bytes of the images in the subtree (loaded ones only)
+    pub image_bytes: usize,
+    /// Layout nodes in the subtree
+    pub nodes: usize,
+    /// Share of the layout pass
+    pub layout_ms: f32,
+    /// 0 (cheapest) ..= 1 (most expensive on the page)
+    pub heat: f32,
+    pub dominant: CostKind,
+}
+
+#[derive(Default)]
+struct Subtree {
+    nodes: usize,
+    text_bytes: usize,
+    image_bytes: usize,
+    has_unit: bool,
+}
+
+/// Per-element costs of `root`.
+///
+/// `image_bytes` reports the encoded size of an image URL once it has been
+/// fetched.
+#[must_use]
+pub fn element_costs(
+    root: &LayoutNode,
+    timings: &StageTimings,
+    image_bytes: &dyn Fn(&str) -> Option<usize>,
+) -> Vec<ElementCost> {
+    let mut units = Vec::new();
+    let total = visit(root, image_bytes, &mut units);
+    let per_node = if total.nodes == 0 {
+        0.0
+    } else {
+        timings.layout_ms / total.nodes as f32
+    };
+    for unit in &mut units {
+        unit.layout_ms = per_node * unit.nodes as f32;
+    }
+
+    let max_text = units.iter().map(|u| u.text_bytes).max().unwrap_or(0);
+    let max_image = units.iter().map(|u| u.image_bytes).max().unwrap_or(0);
+    let max_layout = units.iter().map(|u| u.layout_ms).fold(0.0_f32, f32::max);
+    let share = |v: f32, max: f32| if max > 0.0 { v / max } else { 0.0 };
+    for unit in &mut units {
+        let scores = [
+            (
+                CostKind::Text,
+                share(unit.text_bytes as f32, max_text as f32),
+            ),
+            (
+                CostKind::Image,
+                share(unit.image_bytes as f32, max_image as f32),
+            ),
+            (CostKind::Layout, share(unit.layout_ms, max_layout)),
+        ];
+        let (kind, heat) =
+            scores.into_iter().fold(
+                (CostKind::Text, 0.0),
+                |best, s| if s.1 > best.1 { s } else { best },
+            );
+        unit.heat = heat;
+        unit.dominant = kind;
+    }
+    units
+}
+
+fn visit(
+    node: &LayoutNode,
+    image_bytes: &dyn Fn(&str) -> Option<usize>,
+    out: &mut Vec<ElementCost>,
+) -> Subtree {
+    let mut sub = Subtree {
+        nodes: 1,
+        text_bytes: node.text.len(),
+        ..Subtree::default()
+    };
+    let is_img = node.tag == "img";
+    if is_img {
+        sub.image_bytes = node.href.as_deref().and_then(image_bytes).unwrap_or(0);
+    }
+    for child in &node.children {
+        let c = visit(child, image_bytes, out);
+        sub.nodes += c.nodes;
+        sub.text_bytes += c.text_bytes;
+        sub.image_bytes += c.image_bytes;
+        sub.has_unit |= c.has_unit;
+    }
+
+    let b = &node.bounds;
+    let visible = is_img || (b.width > 0.0 && b.height > 0.0);
+    if (node.is_block || is_img) && !sub.has_unit && visible {
+        out.push(ElementCost {
+            // Same rectangle the SDF view paints
+            rect: if is_img {
+                image_rect(b)
+            } else {
+                [b.x, b.y, b.width, b.height]
+            },
+            tag: node.tag.clone(),
+            text_bytes: sub.text_bytes,
+            image_bytes: sub.image_bytes,
+            nodes: sub.nodes,
+            layout_ms: 0.0,
+            heat: 0.0,
+            dominant: CostKind::Text,
+        });
+        sub.has_unit = true;
+    }
+    sub
+}
+
+/// Overlay tint for `heat`: green through yellow to red, more opaque when
+/// hotter. Returns linear RGBA in `0..=1`.
+#[must_use]
+pub fn heat_color(heat: f32) -> [f32; 4] {
+    let t = heat.clamp(0.0, 1.0);
+    let r = (t * 2.0).min(1.0);
+    let g = ((1.0 - t) * 2.0).min(1.0);
+    [r, g, 0.0, t.mul_add(0.45, 0.1)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+    use crate::render::layout::compute_layout;
+
+    #[test]
+    fn costs_cover_leaf_blocks_and_images() {
+        let dom = parse_html(
+            "<html><body><div><p>short</p><p>a much longer paragraph of text here</p></div>\
+             <img src=\"https://example.com/big.webp\"></body></html>",
+            "https://example.com",
+        );
+        let layout = compute_layout(&dom.root, 800.0);
+        let timings = StageTimings {
+            layout_ms: 10.0,
+            ..StageTimings::default()
+        };
+        let costs = element_costs(&layout, &timings, &|url| {
+            url.ends_with("big.webp").then_some(500_000)
+        });
+
+        // Only the innermost blocks: the two paragraphs and the image
+        let tags: Vec<&str> = costs.iter().map(|c| c.tag.as_str()).collect();
+        assert_eq!(tags, ["p", "p", "img"]);
+
+        let img = &costs[2];
+        assert_eq!(img.image_bytes, 500_000);
+        assert!((img.heat - 1.0).abs() < 1e-6);
+        assert_eq!(img.dominant, CostKind::Image);
+        assert!(costs[1].text_bytes > costs[0].text_bytes);
+        assert!((costs[1].heat - 1.0).abs() < 1e-6);
+
+        let layout_total: f32 = costs.iter().map(|c| c.layout_ms).sum();
+        assert!(layout_total > 0.0 && layout_total <= 10.0);
+    }
+
+    #[test]
+    fn heat_color_ramps_green_to_red() {
+        assert_eq!(heat_color(0.0)[..3], [0.0, 1.0, 0.0]);
+        assert_eq!(heat_color(0.5)[..3], [1.0, 1.0, 0.0]);
+        assert_eq!(heat_color(1.0)[..3], [1.0, 0.0, 0.0]);
+        assert!(heat_color(1.0)[3] > heat_color(0.0)[3]);
+    }
+}
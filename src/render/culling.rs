@@ -0,0 +1,416 @@
+//! View-dependent culling of scene primitives.
+//!
+//! A `Spatial3D` corridor holds the whole page, but the camera sees one
+//! section of it. Before the raymarchers assemble a scene (BVH, compiled
+//! primitives, GPU buffers) [`cull`] drops the primitives that cannot show
+//! up in the frame: those outside the view frustum, and those hidden behind
+//! solid boxes according to a coarse screen-space occlusion grid.
+//!
+//! Both tests are conservative. A tile of the grid only counts as covered
+//! when the rays through all four of its corners hit the same box — boxes
+//! are convex, so every ray in between hits it too — and it is covered
+//! from the deepest of those four hits on: in view depth, the near side of
+//! a convex body seen through a tile is deepest at one of its corners. A
+//! primitive is hidden when every tile it projects onto is covered at a
+//! depth less than its own nearest. The raymarchers draw every box opaque,
+//! so any box can occlude.
+
+use std::ops::RangeInclusive;
+
+use crate::render::bvh::Aabb;
+use crate::render::sdf_ui::{SdfPrimitive, SdfScene};
+
+/// Columns of the occlusion grid.
+pub const GRID_COLS: usize = 16;
+
+/// Rows of the occlusion grid.
+pub const GRID_ROWS: usize = 12;
+
+/// Points nearer the eye plane than this don't project; primitives
+/// reaching them are never treated as hidden.
+const NEAR_EPSILON: f32 = 1e-4;
+
+/// Columns and rows of a block of grid tiles.
+type Tiles = (RangeInclusive<usize>, RangeInclusive<usize>);
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[2].mul_add(b[2], a[0].mul_add(b[0], a[1] * b[1]))
+}
+
+fn axpy(a: f32, x: [f32; 3], y: [f32; 3]) -> [f32; 3] {
+    [0, 1, 2].map(|i| a.mul_add(x[i], y[i]))
+}
+
+fn corners(b: &Aabb) -> [[f32; 3]; 8] {
+    std::array::from_fn(|i| {
+        [0, 1, 2].map(|axis| {
+            if i >> axis & 1 == 0 {
+                b.min[axis]
+            } else {
+                b.max[axis]
+            }
+        })
+    })
+}
+
+// ── View ──
+
+/// A pinhole camera as the raymarchers trace it: pixel rays through
+/// `forward + right * u * tan_half_fov * aspect + up * v * tan_half_fov`
+/// for `u`, `v` in −1..1, marched no further than `far`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct View {
+    pub eye: [f32; 3],
+    /// Unit vectors; `right` and `up` span the image plane
+    pub forward: [f32; 3],
+    pub right: [f32; 3],
+    pub up: [f32; 3],
+    /// Tangent of half the vertical field of view
+    pub tan_half_fov: f32,
+    /// Width over height
+    pub aspect: f32,
+    pub far: f32,
+}
+
+impl View {
+    /// Largest (or smallest) `n · (x - eye)` over the points `x` of `b`.
+    fn extreme(&self, b: &Aabb, n: [f32; 3], largest: bool) -> f32 {
+        let support = [0, 1, 2].map(|i| {
+            if (n[i] >= 0.0) == largest {
+                b.max[i]
+            } else {
+                b.min[i]
+            }
+        });
+        dot(n, sub(support, self.eye))
+    }
+
+    /// Depth along `forward` of the point of `b` nearest the eye plane.
+    fn near_depth(&self, b: &Aabb) -> f32 {
+        self.extreme(b, self.forward, false)
+    }
+
+    /// Whether any part of `b` may lie inside the frustum.
+    #[must_use]
+    pub fn sees(&self, b: &Aabb) -> bool {
+        let (f, r, u) = (self.forward, self.right, self.up);
+        let (tx, ty) = (self.tan_half_fov * self.aspect, self.tan_half_fov);
+        // Inward normals of the side planes, which all pass through the eye
+        let sides = [
+            axpy(tx, f, r.map(|c| -c)),
+            axpy(tx, f, r),
+            axpy(ty, f, u.map(|c| -c)),
+            axpy(ty, f, u),
+        ];
+        self.extreme(b, f, true) >= 0.0
+            && self.near_depth(b) <= self.far
+            && sides.iter().all(|&n| self.extreme(b, n, true) >= 0.0)
+    }
+
+    /// Image-plane position (`u`, `v` in −1..1 on screen, `v` up) of `p`,
+    /// or `None` if it is not in front of the eye.
+    fn project(&self, p: [f32; 3]) -> Option<[f32; 2]> {
+        let d = sub(p, self.eye);
+        let z = dot(self.forward, d);
+        (z > NEAR_EPSILON).then(|| {
+            [
+                dot(self.right, d) / (z * self.tan_half_fov * self.aspect),
+                dot(self.up, d) / (z * self.tan_half_fov),
+            ]
+        })
+    }
+
+    /// Grid tiles `(cols, rows)` covered by the projection of `b`, or
+    /// `None` if part of it is behind the eye.
+    fn tiles(&self, b: &Aabb) -> Option<Tiles> {
+        let mut lo = [f32::MAX; 2];
+        let mut hi = [f32::MIN; 2];
+        for corner in corners(b) {
+            let [u, v] = self.project(corner)?;
+            lo = [lo[0].min(u), lo[1].min(v)];
+            hi = [hi[0].max(u), hi[1].max(v)];
+        }
+        let tile =
+            |t: f32, cells: usize| ((t * cells as f32).floor().max(0.0) as usize).min(cells - 1);
+        let cols = tile((lo[0] + 1.0) * 0.5, GRID_COLS)..=tile((hi[0] + 1.0) * 0.5, GRID_COLS);
+        let rows = tile((1.0 - hi[1]) * 0.5, GRID_ROWS)..=tile((1.0 - lo[1]) * 0.5, GRID_ROWS);
+        Some((cols, rows))
+    }
+
+    /// Direction of the ray through grid vertex (`col`, `row`), row 0 at
+    /// the top of the image; scaled to unit depth along `forward`.
+    fn grid_ray(&self, col: usize, row: usize) -> [f32; 3] {
+        let u = (2.0 * col as f32 / GRID_COLS as f32) - 1.0;
+        let v = 1.0 - (2.0 * row as f32 / GRID_ROWS as f32);
+        let dir = axpy(
+            u * self.tan_half_fov * self.aspect,
+            self.right,
+            self.forward,
+        );
+        axpy(v * self.tan_half_fov, self.up, dir)
+    }
+}
+
+/// Where the ray from `origin` along `dir` enters box `b`, in multiples of
+/// `dir` (slab test); `None` if it misses.
+fn ray_entry(origin: [f32; 3], dir: [f32; 3], b: &Aabb) -> Option<f32> {
+    let mut near = 0.0f32;
+    let mut far = f32::MAX;
+    for i in 0..3 {
+        if dir[i].abs() < 1e-9 {
+            if origin[i] < b.min[i] || origin[i] > b.max[i] {
+                return None;
+            }
+            continue;
+        }
+        let t1 = (b.min[i] - origin[i]) / dir[i];
+        let t2 = (b.max[i] - origin[i]) / dir[i];
+        near = near.max(t1.min(t2));
+        far = far.min(t1.max(t2));
+    }
+    (near <= far).then_some(near)
+}
+
+// ── Occlusion grid ──
+
+/// Per tile of the image, the view depth beyond which some box is known
+/// to cover the whole tile.
+struct OcclusionGrid {
+    depth: [f32; GRID_COLS * GRID_ROWS],
+}
+
+impl OcclusionGrid {
+    const fn new() -> Self {
+        Self {
+            depth: [f32::MAX; GRID_COLS * GRID_ROWS],
+        }
+    }
+
+    /// Mark the tiles the solid box `b` covers entirely.
+    fn add_occluder(&mut self, view: &View, b: &Aabb) {
+        if b.distance(view.eye) <= 0.0 {
+            return;
+        }
+        let near = view.near_depth(b);
+        let (cols, rows) = view
+            .tiles(b)
+            .unwrap_or((0..=GRID_COLS - 1, 0..=GRID_ROWS - 1));
+        let tile_index = |col: usize, row: usize| row * GRID_COLS + col;
+        if !rows.clone().any(|row| {
+            cols.clone()
+                .any(|col| self.depth[tile_index(col, row)] > near)
+        }) {
+            return;
+        }
+
+        // Rays through the tile corners, shared between neighbouring tiles
+        let (c0, r0) = (*cols.start(), *rows.start());
+        let stride = cols.end() - c0 + 2;
+        let hits: Vec<Option<f32>> = (r0..=rows.end() + 1)
+            .flat_map(|row| (c0..=cols.end() + 1).map(move |col| (col, row)))
+            .map(|(col, row)| ray_entry(view.eye, view.grid_ray(col, row), b))
+            .collect();
+        let hit = |col: usize, row: usize| hits[(row - r0) * stride + col - c0];
+        for row in rows {
+            for col in cols.clone() {
+                let deepest = [(0, 0), (1, 0), (0, 1), (1, 1)]
+                    .into_iter()
+                    .try_fold(0.0f32, |deepest, (dc, dr)| {
+                        hit(col + dc, row + dr).map(|t| deepest.max(t))
+                    });
+                if let Some(deepest) = deepest {
+                    let depth = &mut self.depth[tile_index(col, row)];
+                    *depth = depth.min(deepest);
+                }
+            }
+        }
+    }
+
+    /// Whether every tile `b` projects onto is covered in front of `b`.
+    fn hides(&self, view: &View, b: &Aabb) -> bool {
+        let near = view.near_depth(b);
+        if near <= NEAR_EPSILON {
+            return false;
+        }
+        let Some((cols, rows)) = view.tiles(b) else {
+            return false;
+        };
+        rows.into_iter().all(|row| {
+            cols.clone()
+                .all(|col| self.depth[row * GRID_COLS + col] < near)
+        })
+    }
+}
+
+/// The solid box inside `prim` that can hide what is behind it: the core
+/// of a rounded box.
+fn occluder(prim: &SdfPrimitive) -> Option<Aabb> {
+    match prim {
+        SdfPrimitive::RoundedBox {
+            center,
+            size,
+            radius,
+            ..
+        } => {
+            let r = if *radius > 0.001 { *radius } else { 0.0 };
+            let half = size.map(|s| 2.0f32.mul_add(-r, s).max(0.001) * 0.5);
+            Some(Aabb::around(*center, half))
+        }
+        _ => None,
+    }
+}
+
+// ── Culling ──
+
+/// How many primitives culling kept for a frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CullStats {
+    pub total: usize,
+    pub outside_view: usize,
+    pub occluded: usize,
+}
+
+impl CullStats {
+    #[must_use]
+    pub const fn rendered(&self) -> usize {
+        self.total - self.outside_view - self.occluded
+    }
+
+    /// Share of the scene's primitives rendered, in percent.
+    #[must_use]
+    pub fn percent_rendered(&self) -> f32 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.rendered() as f32 * 100.0 / self.total as f32
+        }
+    }
+}
+
+/// The primitives of a scene that may be visible.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Visibility {
+    /// Indices into the scene's primitives, ascending
+    pub indices: Vec<u32>,
+    pub stats: CullStats,
+}
+
+/// Primitives of `scene` that may show up in `view`.
+#[must_use]
+pub fn cull(scene: &SdfScene, view: &View) -> Visibility {
+    let bounds: Vec<Aabb> = scene.primitives.iter().map(Aabb::of).collect();
+    let in_view: Vec<u32> = (0..bounds.len() as u32)
+        .filter(|&i| view.sees(&bounds[i as usize]))
+        .collect();
+
+    // Nearest occluders first: farther ones then mostly find their tiles
+    // settled and skip the ray tests
+    let mut occluders: Vec<(f32, Aabb)> = in_view
+        .iter()
+        .filter_map(|&i| occluder(&scene.primitives[i as usize]))
+        .map(|b| (view.near_depth(&b), b))
+        .collect();
+    occluders.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut grid = OcclusionGrid::new();
+    for (_, b) in &occluders {
+        grid.add_occluder(view, b);
+    }
+
+    let indices: Vec<u32> = in_view
+        .iter()
+        .copied()
+        .filter(|&i| !grid.hides(view, &bounds[i as usize]))
+        .collect();
+    Visibility {
+        stats: CullStats {
+            total: bounds.len(),
+            outside_view: bounds.len() - in_view.len(),
+            occluded: in_view.len() - indices.len(),
+        },
+        indices,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Camera at z = 5 looking down −z, 90° field of view, square image.
+    fn view() -> View {
+        View {
+            eye: [0.0, 0.0, 5.0],
+            forward: [0.0, 0.0, -1.0],
+            right: [1.0, 0.0, 0.0],
+            up: [0.0, 1.0, 0.0],
+            tan_half_fov: 1.0,
+            aspect: 1.0,
+            far: 50.0,
+        }
+    }
+
+    fn cube(center: [f32; 3], size: f32) -> SdfPrimitive {
+        SdfPrimitive::RoundedBox {
+            center,
+            size: [size; 3],
+            radius: 0.0,
+            color: [1.0; 4],
+        }
+    }
+
+    fn scene(primitives: Vec<SdfPrimitive>) -> SdfScene {
+        SdfScene {
+            primitives,
+            background_color: [0.0; 4],
+        }
+    }
+
+    #[test]
+    fn frustum_drops_what_is_behind_beside_and_beyond() {
+        let scene = scene(vec![
+            cube([0.0, 0.0, 0.0], 1.0),   // ahead
+            cube([0.0, 0.0, 8.0], 1.0),   // behind the eye
+            cube([20.0, 0.0, 0.0], 1.0),  // far to the right
+            cube([0.0, 0.0, -60.0], 1.0), // beyond the march distance
+            cube([4.6, 0.0, 0.0], 1.0),   // straddles the right edge
+        ]);
+        let visible = cull(&scene, &view());
+        assert_eq!(visible.indices, [0, 4]);
+        assert_eq!(visible.stats.outside_view, 3);
+        assert_eq!(visible.stats.occluded, 0);
+    }
+
+    #[test]
+    fn wall_hides_what_is_behind_it() {
+        let wall = SdfPrimitive::RoundedBox {
+            center: [0.0, 0.0, 0.0],
+            size: [30.0, 30.0, 0.2],
+            radius: 0.05,
+            color: [1.0; 4],
+        };
+        let scene = scene(vec![
+            wall,
+            cube([0.0, 0.0, -3.0], 1.0),  // behind the wall
+            cube([2.0, 0.0, 2.0], 1.0),   // in front of it
+            cube([0.0, 0.0, -0.05], 0.5), // pokes through it
+        ]);
+        let visible = cull(&scene, &view());
+        assert_eq!(visible.indices, [0, 2, 3]);
+        assert_eq!(visible.stats.occluded, 1);
+        assert_eq!(visible.stats.rendered(), 3);
+        assert!((visible.stats.percent_rendered() - 75.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn small_boxes_do_not_occlude() {
+        // Covers less than a tile, so nothing behind it is dropped
+        let scene = scene(vec![
+            cube([0.0, 0.0, 0.0], 0.2),
+            cube([0.0, 0.0, -3.0], 0.1),
+        ]);
+        assert_eq!(cull(&scene, &view()).indices, [0, 1]);
+    }
+}
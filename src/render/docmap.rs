@@ -0,0 +1,261 @@
+//! Heading-level document map for OZ mode.
+//!
+//! The page's `h1`/`h2` hierarchy is laid out as two faint rings on a plane
+//! below the viewer, inside the rotunda: `h1` sections on the inner ring,
+//! `h2` sections on the outer one. Each section is an arc whose length is
+//! proportional to how much text it holds, so nested `h2` arcs sit inside
+//! the span of their `h1`. Picking an arc selects the text-pool range the
+//! section covers, which [`StreamState`](super::stream::StreamState) can pull
+//! toward the viewer.
+
+use std::f32::consts::TAU;
+
+use super::stream::TextMeta;
+
+/// Radius of the `h1` ring.
+pub const H1_RING_RADIUS: f32 = 4.5;
+/// Radius of the `h2` ring.
+pub const H2_RING_RADIUS: f32 = 7.5;
+/// Height of the ring plane (below eye level).
+pub const RING_Y: f32 = -3.5;
+/// Radial tolerance when picking a ring.
+const PICK_TOLERANCE: f32 = 1.25;
+
+/// One heading and the text that follows it.
+#[derive(Debug, Clone)]
+pub struct HeadingSection {
+    /// 1 or 2
+    pub level: u8,
+    pub title: String,
+    /// Text-pool range `start..end` covered by the section
+    pub pool_start: usize,
+    pub pool_end: usize,
+    /// Arc on the ring (radians)
+    pub start_angle: f32,
+    pub end_angle: f32,
+}
+
+impl HeadingSection {
+    #[must_use]
+    pub fn mid_angle(&self) -> f32 {
+        (self.start_angle + self.end_angle) * 0.5
+    }
+}
+
+/// `h1`/`h2` sections of the current page.
+#[derive(Debug, Clone, Default)]
+pub struct DocMap {
+    pub sections: Vec<HeadingSection>,
+}
+
+impl DocMap {
+    /// Build the map from a stream's text pool (which is in document order).
+    #[must_use]
+    pub fn from_pool(pool: &[TextMeta]) -> Self {
+        let total = pool.len().max(1) as f32;
+        let mut sections: Vec<HeadingSection> = Vec::new();
+
+        for (i, meta) in pool.iter().enumerate() {
+            let level = match meta.tag.as_str() {
+                "h1" => 1,
+                "h2" => 2,
+                _ => continue,
+            };
+            // Long headings are split into several pool entries
+            if i > 0 && pool[i - 1].tag == meta.tag && pool[i - 1].full_text == meta.full_text {
+                continue;
+            }
+            // A heading closes every open section at its level or deeper
+            for s in sections.iter_mut().filter(|s| s.level >= level) {
+                if s.pool_end == usize::MAX {
+                    s.pool_end = i;
+                }
+            }
+            sections.push(HeadingSection {
+                level,
+                title: meta.full_text.clone(),
+                pool_start: i,
+                pool_end: usize::MAX,
+                start_angle: 0.0,
+                end_angle: 0.0,
+            });
+        }
+
+        for s in &mut sections {
+            if s.pool_end == usize::MAX {
+                s.pool_end = pool.len();
+            }
+            s.start_angle = s.pool_start as f32 / total * TAU;
+            s.end_angle = s.pool_end as f32 / total * TAU;
+        }
+        Self { sections }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    #[must_use]
+    pub const fn ring_radius(level: u8) -> f32 {
+        if level <= 1 {
+            H1_RING_RADIUS
+        } else {
+            H2_RING_RADIUS
+        }
+    }
+
+    /// World position of `angle` on the ring for `level`.
+    #[must_use]
+    pub fn ring_point(level: u8, angle: f32) -> [f32; 3] {
+        let r = Self::ring_radius(level);
+        [r * angle.cos(), RING_Y, r * angle.sin()]
+    }
+
+    /// Section under a point on the ring plane, if any.
+    #[must_use]
+    pub fn pick(&self, x: f32, z: f32) -> Option<usize> {
+        let radius = x.hypot(z);
+        let angle = z.atan2(x).rem_euclid(TAU);
+        let level = if (radius - H1_RING_RADIUS).abs() < PICK_TOLERANCE {
+            1
+        } else if (radius - H2_RING_RADIUS).abs() < PICK_TOLERANCE {
+            2
+        } else {
+            return None;
+        };
+        self.sections
+            .iter()
+            .position(|s| s.level == level && (s.start_angle..s.end_angle).contains(&angle))
+    }
+}
+
+/// Rotunda camera: project a world point to NDC.
+///
+/// Matches the OZ overlay (azimuth about Y, then elevation about X).
+/// Returns `(ndc_x, ndc_y, depth)`, or `None` behind the camera.
+#[must_use]
+pub fn project(
+    world: [f32; 3],
+    cam_az: f32,
+    cam_el: f32,
+    tan_fov_h: f32,
+    aspect: f32,
+) -> Option<(f32, f32, f32)> {
+    let (sin_az, cos_az) = cam_az.sin_cos();
+    let (sin_el, cos_el) = cam_el.sin_cos();
+    let [wx, wy, wz] = world;
+
+    let rx = wx.mul_add(cos_az, wz * sin_az);
+    let rz1 = (-wx).mul_add(sin_az, wz * cos_az);
+    let ry = wy.mul_add(cos_el, -(rz1 * sin_el));
+    let rz = wy.mul_add(sin_el, rz1 * cos_el);
+
+    if rz < 0.1 {
+        return None;
+    }
+    Some((rx / (rz * tan_fov_h), -ry / (rz * tan_fov_h / aspect), rz))
+}
+
+/// Inverse of [`project`]: where a screen ray from the origin meets the ring
+/// plane, as `(x, z)`.
+#[must_use]
+pub fn unproject_to_ring_plane(
+    ndc_x: f32,
+    ndc_y: f32,
+    cam_az: f32,
+    cam_el: f32,
+    tan_fov_h: f32,
+    aspect: f32,
+) -> Option<(f32, f32)> {
+    let (sin_az, cos_az) = cam_az.sin_cos();
+    let (sin_el, cos_el) = cam_el.sin_cos();
+
+    // Camera-space direction
+    let cx = ndc_x * tan_fov_h;
+    let cy = -ndc_y * tan_fov_h / aspect;
+    let cz = 1.0;
+
+    // Undo elevation, then azimuth
+    let y1 = cy.mul_add(cos_el, cz * sin_el);
+    let z1 = (-cy).mul_add(sin_el, cz * cos_el);
+    let dx = cx.mul_add(cos_az, -(z1 * sin_az));
+    let dz = cx.mul_add(sin_az, z1 * cos_az);
+
+    if y1 >= -1e-4 {
+        return None;
+    }
+    let t = RING_Y / y1;
+    Some((dx * t, dz * t))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(tag: &str, text: &str) -> TextMeta {
+        TextMeta {
+            display: text.into(),
+            full_text: text.into(),
+            tag: tag.into(),
+            href: None,
+            category_index: 0,
+            importance: 0.2,
+        }
+    }
+
+    fn sample() -> Vec<TextMeta> {
+        vec![
+            meta("h1", "Intro"),
+            meta("p", "a"),
+            meta("h2", "Detail"),
+            meta("p", "b"),
+            meta("h1", "Next"),
+            meta("p", "c"),
+            meta("p", "d"),
+            meta("p", "e"),
+        ]
+    }
+
+    #[test]
+    fn sections_nest() {
+        let map = DocMap::from_pool(&sample());
+        assert_eq!(map.sections.len(), 3);
+        let intro = &map.sections[0];
+        assert_eq!((intro.pool_start, intro.pool_end), (0, 4));
+        let detail = &map.sections[1];
+        assert_eq!(
+            (detail.level, detail.pool_start, detail.pool_end),
+            (2, 2, 4)
+        );
+        assert!(detail.start_angle >= intro.start_angle && detail.end_angle <= intro.end_angle);
+        assert_eq!(map.sections[2].pool_end, 8);
+        assert!((map.sections[2].end_angle - TAU).abs() < 1e-5);
+    }
+
+    #[test]
+    fn split_heading_is_one_section() {
+        let pool = vec![meta("h1", "Long title"), meta("h1", "Long title")];
+        assert_eq!(DocMap::from_pool(&pool).sections.len(), 1);
+    }
+
+    #[test]
+    fn pick_hits_ring_segment() {
+        let map = DocMap::from_pool(&sample());
+        let next = &map.sections[2];
+        let [x, _, z] = DocMap::ring_point(1, next.mid_angle());
+        assert_eq!(map.pick(x, z), Some(2));
+        let [x, _, z] = DocMap::ring_point(2, map.sections[1].mid_angle());
+        assert_eq!(map.pick(x, z), Some(1));
+        assert_eq!(map.pick(0.5, 0.5), None);
+    }
+
+    #[test]
+    fn unproject_inverts_project() {
+        let (az, el, tan_h, aspect) = (0.7, -0.4, 1.2, 1.6);
+        let world = DocMap::ring_point(2, 1.0);
+        let (nx, ny, _) = project(world, az, el, tan_h, aspect).unwrap();
+        let (x, z) = unproject_to_ring_plane(nx, ny, az, el, tan_h, aspect).unwrap();
+        assert!((x - world[0]).abs() < 1e-3 && (z - world[2]).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,609 @@
+//! Cross-platform font discovery and per-script fallback chains.
+//!
+//! System fonts are enumerated with `fontdb` (fontconfig configuration on
+//! Linux/BSD, the system and per-user font directories on Windows and
+//! macOS), plus, on Windows, fonts registered elsewhere under the registry
+//! keys DirectWrite reads. For each script a [`FallbackChain`] lists the
+//! installed faces to try, best first: the platform's native font (Hiragino,
+//! Yu Gothic, Microsoft YaHei, …), then any Noto / Source Han face covering
+//! the script, then, for CJK, the font bundled at build time with the
+//! `bundled-font` feature. Text falls back Latin → CJK → emoji, with the
+//! user's [`FontPreferences`] choosing the Latin family and which CJK script
+//! comes first.
+//!
+//! Chains hold font *sources*, not loaded data, so the egui font setup and
+//! the glyph-SDF atlas can both load exactly the faces they need.
+
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Writing systems that need a dedicated fallback font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Script {
+    /// Latin, Greek and Cyrillic text
+    #[serde(rename = "latin")]
+    Latin,
+    #[default]
+    #[serde(rename = "ja")]
+    Japanese,
+    #[serde(rename = "zh-hans")]
+    SimplifiedChinese,
+    #[serde(rename = "zh-hant")]
+    TraditionalChinese,
+    #[serde(rename = "ko")]
+    Korean,
+    #[serde(rename = "emoji")]
+    Emoji,
+}
+
+impl Script {
+    /// All scripts, in the order their fallbacks are installed.
+    pub const ALL: [Self; 6] = [
+        Self::Latin,
+        Self::Japanese,
+        Self::SimplifiedChinese,
+        Self::TraditionalChinese,
+        Self::Korean,
+        Self::Emoji,
+    ];
+
+    /// The scripts sharing Han ideographs; whichever comes first decides
+    /// how those are drawn.
+    pub const CJK: [Self; 4] = [
+        Self::Japanese,
+        Self::SimplifiedChinese,
+        Self::TraditionalChinese,
+        Self::Korean,
+    ];
+
+    /// Stable identifier, as stored in settings and used in `alice://` URLs.
+    #[must_use]
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::Latin => "latin",
+            Self::Japanese => "ja",
+            Self::SimplifiedChinese => "zh-hans",
+            Self::TraditionalChinese => "zh-hant",
+            Self::Korean => "ko",
+            Self::Emoji => "emoji",
+        }
+    }
+
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.key() == key)
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Latin => "Latin",
+            Self::Japanese => "Japanese",
+            Self::SimplifiedChinese => "Simplified Chinese",
+            Self::TraditionalChinese => "Traditional Chinese",
+            Self::Korean => "Korean",
+            Self::Emoji => "Emoji",
+        }
+    }
+
+    #[must_use]
+    pub fn is_cjk(self) -> bool {
+        Self::CJK.contains(&self)
+    }
+
+    /// Guess the CJK script of `text` from its first CJK character.
+    ///
+    /// Kana means Japanese and Hangul means Korean; bare Han ideographs are
+    /// ambiguous and reported as Japanese, the browser's primary locale.
+    #[must_use]
+    pub fn detect(text: &str) -> Option<Self> {
+        let mut han = false;
+        for c in text.chars() {
+            match c as u32 {
+                0x3040..=0x30FF | 0x31F0..=0x31FF | 0xFF66..=0xFF9F => {
+                    return Some(Self::Japanese);
+                }
+                0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => {
+                    return Some(Self::Korean);
+                }
+                0x4E00..=0x9FFF | 0x3400..=0x4DBF | 0xF900..=0xFAFF => han = true,
+                _ => {}
+            }
+        }
+        han.then_some(Self::Japanese)
+    }
+
+    /// Native families per platform, in order of preference.
+    const fn preferred_families(self) -> &'static [&'static str] {
+        match self {
+            Self::Latin => &[
+                "Helvetica Neue",
+                "Segoe UI",
+                "Cantarell",
+                "Noto Sans",
+                "DejaVu Sans",
+                "Liberation Sans",
+                "Arial",
+            ],
+            Self::Japanese => &[
+                "Hiragino Sans",
+                "Hiragino Kaku Gothic ProN",
+                "Yu Gothic UI",
+                "Yu Gothic",
+                "Meiryo",
+                "MS Gothic",
+                "IPAexGothic",
+                "IPAGothic",
+                "TakaoGothic",
+                "VL Gothic",
+            ],
+            Self::SimplifiedChinese => &[
+                "PingFang SC",
+                "Microsoft YaHei",
+                "SimHei",
+                "WenQuanYi Micro Hei",
+                "WenQuanYi Zen Hei",
+            ],
+            Self::TraditionalChinese => &[
+                "PingFang TC",
+                "Microsoft JhengHei",
+                "MingLiU",
+                "AR PL UMing TW",
+            ],
+            Self::Korean => &[
+                "Apple SD Gothic Neo",
+                "Malgun Gothic",
+                "NanumGothic",
+                "UnDotum",
+            ],
+            // Fonts with outlines only: egui cannot draw the colour bitmaps
+            // of Apple Color Emoji or Noto Color Emoji
+            Self::Emoji => &[
+                "Segoe UI Emoji",
+                "Noto Emoji",
+                "Twemoji Mozilla",
+                "Symbola",
+                "Segoe UI Symbol",
+            ],
+        }
+    }
+
+    /// Region suffix of the Noto Sans CJK / Source Han Sans families.
+    const fn noto_suffix(self) -> Option<&'static str> {
+        match self {
+            Self::Japanese => Some("JP"),
+            Self::SimplifiedChinese => Some("SC"),
+            Self::TraditionalChinese => Some("TC"),
+            Self::Korean => Some("KR"),
+            Self::Latin | Self::Emoji => None,
+        }
+    }
+}
+
+/// Where a face's data lives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FontSource {
+    File(PathBuf),
+    /// Compiled into the binary (`bundled-font` feature)
+    Bundled(&'static [u8]),
+}
+
+/// One face of an installed (or bundled) font.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontFace {
+    pub family: String,
+    pub source: FontSource,
+    /// Face index inside a collection (`.ttc`)
+    pub index: u32,
+    /// CSS-style weight (400 = regular)
+    pub weight: u16,
+    pub italic: bool,
+}
+
+impl FontFace {
+    /// Read the font data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the font file cannot be read.
+    pub fn load(&self) -> std::io::Result<Cow<'static, [u8]>> {
+        match self.source {
+            FontSource::File(ref path) => std::fs::read(path).map(Cow::Owned),
+            FontSource::Bundled(data) => Ok(Cow::Borrowed(data)),
+        }
+    }
+
+    /// Stable key for de-duplicating faces shared between chains.
+    #[must_use]
+    pub fn key(&self) -> String {
+        match self.source {
+            FontSource::File(ref path) => format!("{}#{}", path.display(), self.index),
+            FontSource::Bundled(_) => format!("bundled:{}#{}", self.family, self.index),
+        }
+    }
+}
+
+/// Faces to try for one script, best first.
+#[derive(Debug, Clone)]
+pub struct FallbackChain {
+    pub script: Script,
+    pub faces: Vec<FontFace>,
+}
+
+impl FallbackChain {
+    /// The face to use, if any font covers the script.
+    #[must_use]
+    pub fn primary(&self) -> Option<&FontFace> {
+        self.faces.first()
+    }
+}
+
+/// Font choices made by the user.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FontPreferences {
+    /// Family for Latin text; empty keeps the built-in font
+    pub family: String,
+    /// CJK script whose font comes first
+    pub cjk: Script,
+}
+
+/// The CJK subset bundled with the `bundled-font` feature.
+///
+/// The font file is chosen at build time with the `ALICE_BUNDLED_FONT`
+/// environment variable (e.g. a Noto Sans JP subset).
+#[cfg(feature = "bundled-font")]
+#[must_use]
+pub fn bundled() -> Option<FontFace> {
+    static DATA: &[u8] = include_bytes!(env!("ALICE_BUNDLED_FONT"));
+    Some(FontFace {
+        family: "ALICE Bundled CJK".into(),
+        source: FontSource::Bundled(DATA),
+        index: 0,
+        weight: 400,
+        italic: false,
+    })
+}
+
+/// The CJK subset bundled with the `bundled-font` feature (disabled).
+#[cfg(not(feature = "bundled-font"))]
+#[must_use]
+pub const fn bundled() -> Option<FontFace> {
+    None
+}
+
+/// Installed fonts, as far as fallback selection is concerned.
+#[derive(Debug, Clone, Default)]
+pub struct FontCatalog {
+    faces: Vec<FontFace>,
+}
+
+impl FontCatalog {
+    /// Enumerate the system's fonts.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[must_use]
+    pub fn discover() -> Self {
+        let mut db = fontdb::Database::new();
+        db.load_system_fonts();
+        #[cfg(target_os = "windows")]
+        for path in registry_font_files() {
+            if let Err(e) = db.load_font_file(&path) {
+                log::debug!("Skipping registered font {}: {e}", path.display());
+            }
+        }
+        let faces = db
+            .faces()
+            .filter_map(|info| {
+                let path = match info.source {
+                    fontdb::Source::File(ref p) | fontdb::Source::SharedFile(ref p, _) => p.clone(),
+                    fontdb::Source::Binary(_) => return None,
+                };
+                Some(info.families.iter().map(move |(family, _)| FontFace {
+                    family: family.clone(),
+                    source: FontSource::File(path.clone()),
+                    index: info.index,
+                    weight: info.weight.0,
+                    italic: info.style != fontdb::Style::Normal,
+                }))
+            })
+            .flatten()
+            .collect();
+        Self { faces }
+    }
+
+    /// No system fonts to enumerate on wasm32: an empty catalog, leaving
+    /// the bundled font, if any.
+    #[cfg(target_arch = "wasm32")]
+    #[must_use]
+    pub fn discover() -> Self {
+        Self::default()
+    }
+
+    /// Catalog over a fixed list of faces.
+    #[must_use]
+    pub const fn from_faces(faces: Vec<FontFace>) -> Self {
+        Self { faces }
+    }
+
+    /// Number of faces known (one per family name a face carries).
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.faces.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    /// The most regular upright face of `family` (case-insensitive).
+    fn regular(&self, family: &str) -> Option<&FontFace> {
+        self.faces
+            .iter()
+            .filter(|f| f.family.eq_ignore_ascii_case(family))
+            .min_by_key(|f| (f.italic, f.weight.abs_diff(400)))
+    }
+
+    /// Installed family names, sorted, without duplicates.
+    #[must_use]
+    pub fn families(&self) -> Vec<&str> {
+        let mut families: Vec<&str> = self.faces.iter().map(|f| f.family.as_str()).collect();
+        families.sort_unstable_by_key(|f| f.to_lowercase());
+        families.dedup();
+        families
+    }
+
+    /// Noto / Source Han families covering `script`, pan-CJK ones last.
+    fn noto_families(&self, script: Script) -> Vec<&str> {
+        let Some(suffix) = script.noto_suffix() else {
+            return Vec::new();
+        };
+        let mut regional = Vec::new();
+        let mut pan = Vec::new();
+        for face in &self.faces {
+            let family = face.family.as_str();
+            let sans = family.starts_with("Noto Sans") || family.starts_with("Source Han Sans");
+            if !sans {
+                continue;
+            }
+            let list = if family.ends_with(suffix) {
+                &mut regional
+            } else if family.ends_with("CJK") {
+                &mut pan
+            } else {
+                continue;
+            };
+            if !list.contains(&family) {
+                list.push(family);
+            }
+        }
+        // Prefer the CJK collections ("Noto Sans CJK JP") over the
+        // single-region fonts ("Noto Sans JP"), which cover fewer glyphs
+        regional.sort_by_key(|f| !f.contains("CJK"));
+        regional.extend(pan);
+        regional
+    }
+
+    /// Fallback chain for `script`.
+    #[must_use]
+    pub fn chain(&self, script: Script) -> FallbackChain {
+        let mut faces: Vec<FontFace> = Vec::new();
+        let mut push = |face: Option<&FontFace>| {
+            if let Some(face) = face {
+                if !faces.iter().any(|f| f.key() == face.key()) {
+                    faces.push(face.clone());
+                }
+            }
+        };
+        for family in script.preferred_families() {
+            push(self.regular(family));
+        }
+        for family in self.noto_families(script) {
+            push(self.regular(family));
+        }
+        if script.is_cjk() {
+            push(bundled().as_ref());
+        }
+        FallbackChain { script, faces }
+    }
+
+    /// Fallback chains for every script in [`Script::ALL`].
+    #[must_use]
+    pub fn chains(&self) -> Vec<FallbackChain> {
+        Script::ALL.iter().map(|&s| self.chain(s)).collect()
+    }
+
+    /// Fallback chains in the order text falls back through them: Latin,
+    /// headed by the preferred family if it is installed, then the CJK
+    /// scripts with the preferred one first, then emoji.
+    #[must_use]
+    pub fn chains_with(&self, prefs: &FontPreferences) -> Vec<FallbackChain> {
+        let mut latin = self.chain(Script::Latin);
+        if let Some(face) = self.regular(prefs.family.trim()) {
+            latin.faces.retain(|f| f.key() != face.key());
+            latin.faces.insert(0, face.clone());
+        }
+        let cjk = Script::CJK.into_iter().filter(|&s| s != prefs.cjk);
+        std::iter::once(latin)
+            .chain(prefs.cjk.is_cjk().then(|| self.chain(prefs.cjk)))
+            .chain(cjk.map(|s| self.chain(s)))
+            .chain(std::iter::once(self.chain(Script::Emoji)))
+            .collect()
+    }
+}
+
+/// Font files registered with Windows outside the fonts directories
+/// `fontdb` scans: the `Fonts` keys of the machine and of the user, whose
+/// values are file names (inside a fonts directory) or full paths.
+#[cfg(target_os = "windows")]
+fn registry_font_files() -> Vec<PathBuf> {
+    const KEYS: [&str; 2] = [
+        r"HKLM\SOFTWARE\Microsoft\Windows NT\CurrentVersion\Fonts",
+        r"HKCU\SOFTWARE\Microsoft\Windows NT\CurrentVersion\Fonts",
+    ];
+    let mut files = Vec::new();
+    for key in KEYS {
+        let Ok(output) = std::process::Command::new("reg")
+            .args(["query", key])
+            .output()
+        else {
+            continue;
+        };
+        files.extend(parse_registry_fonts(&String::from_utf8_lossy(
+            &output.stdout,
+        )));
+    }
+    files
+}
+
+/// Absolute paths among the values in `reg query` output, lines like
+/// `    Name (TrueType)    REG_SZ    C:\Fonts\name.ttf`.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_registry_fonts(output: &str) -> Vec<PathBuf> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once("REG_SZ"))
+        .map(|(_, value)| value.trim())
+        .filter(|value| {
+            let bytes = value.as_bytes();
+            bytes.len() > 3 && bytes[1] == b':' && matches!(bytes[2], b'\\' | b'/')
+        })
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn face(family: &str, file: &str, weight: u16) -> FontFace {
+        FontFace {
+            family: family.into(),
+            source: FontSource::File(PathBuf::from(file)),
+            index: 0,
+            weight,
+            italic: false,
+        }
+    }
+
+    #[test]
+    fn detects_scripts() {
+        assert_eq!(Script::detect("こんにちは"), Some(Script::Japanese));
+        assert_eq!(Script::detect("안녕하세요"), Some(Script::Korean));
+        assert_eq!(Script::detect("漢字"), Some(Script::Japanese));
+        assert_eq!(Script::detect("hello"), None);
+    }
+
+    #[test]
+    fn chain_prefers_native_then_noto() {
+        let catalog = FontCatalog::from_faces(vec![
+            face("DejaVu Sans", "dejavu.ttf", 400),
+            face("Noto Sans JP", "noto-jp.otf", 400),
+            face("Noto Sans CJK JP", "noto-cjk-bold.ttc", 700),
+            face("Noto Sans CJK JP", "noto-cjk.ttc", 400),
+            face("Meiryo", "meiryo.ttc", 400),
+            face("Malgun Gothic", "malgun.ttf", 400),
+        ]);
+
+        let jp = catalog.chain(Script::Japanese);
+        let files: Vec<String> = jp
+            .faces
+            .iter()
+            .map(|f| match f.source {
+                FontSource::File(ref p) => p.display().to_string(),
+                FontSource::Bundled(_) => "bundled".into(),
+            })
+            .collect();
+        assert_eq!(&files[..3], ["meiryo.ttc", "noto-cjk.ttc", "noto-jp.otf"]);
+
+        let kr = catalog.chain(Script::Korean);
+        assert_eq!(kr.primary().unwrap().family, "Malgun Gothic");
+        // Only pan-CJK or KR Noto faces would qualify; none are installed
+        assert_eq!(kr.faces.len(), 1 + usize::from(bundled().is_some()));
+    }
+
+    #[test]
+    fn chains_fall_back_latin_then_cjk_then_emoji() {
+        let catalog = FontCatalog::from_faces(vec![
+            face("DejaVu Sans", "dejavu.ttf", 400),
+            face("Inter", "inter.ttf", 400),
+            face("Meiryo", "meiryo.ttc", 400),
+            face("Malgun Gothic", "malgun.ttf", 400),
+            face("Noto Emoji", "noto-emoji.ttf", 400),
+        ]);
+        let families = |chains: &[FallbackChain]| -> Vec<(Script, String)> {
+            chains
+                .iter()
+                .filter_map(|c| c.primary().map(|f| (c.script, f)))
+                .filter(|(_, f)| matches!(f.source, FontSource::File(_)))
+                .map(|(script, f)| (script, f.family.clone()))
+                .collect()
+        };
+
+        let chains = catalog.chains_with(&FontPreferences::default());
+        let scripts: Vec<Script> = chains.iter().map(|c| c.script).collect();
+        assert_eq!(scripts, Script::ALL);
+        assert_eq!(
+            families(&chains)[..3],
+            [
+                (Script::Latin, "DejaVu Sans".to_string()),
+                (Script::Japanese, "Meiryo".to_string()),
+                (Script::Korean, "Malgun Gothic".to_string()),
+            ]
+        );
+        assert_eq!(
+            families(&chains).last(),
+            Some(&(Script::Emoji, "Noto Emoji".to_string()))
+        );
+
+        // The preferred family heads the Latin chain, the preferred CJK
+        // script comes right after it
+        let prefs = FontPreferences {
+            family: "inter".into(),
+            cjk: Script::Korean,
+        };
+        let chains = catalog.chains_with(&prefs);
+        assert_eq!(chains[0].primary().unwrap().family, "Inter");
+        assert_eq!(chains[0].faces[1].family, "DejaVu Sans");
+        assert_eq!(chains[1].script, Script::Korean);
+        assert_eq!(chains[2].script, Script::Japanese);
+        assert_eq!(chains.len(), Script::ALL.len());
+
+        // An unknown family leaves the platform default in place
+        let prefs = FontPreferences {
+            family: "Missing Sans".into(),
+            cjk: Script::Japanese,
+        };
+        assert_eq!(
+            catalog.chains_with(&prefs)[0].primary().unwrap().family,
+            "DejaVu Sans"
+        );
+        assert_eq!(
+            catalog.families(),
+            [
+                "DejaVu Sans",
+                "Inter",
+                "Malgun Gothic",
+                "Meiryo",
+                "Noto Emoji"
+            ]
+        );
+        assert_eq!(
+            Script::from_key("zh-hant"),
+            Some(Script::TraditionalChinese)
+        );
+    }
+
+    #[test]
+    fn registry_values_with_full_paths() {
+        let output =
+            "\r\nHKEY_CURRENT_USER\\SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\Fonts\r\n    \
+                      Inter (TrueType)    REG_SZ    C:\\Users\\a\\Fonts\\Inter.ttf\r\n    \
+                      Arial (TrueType)    REG_SZ    arial.ttf\r\n";
+        assert_eq!(
+            parse_registry_fonts(output),
+            [PathBuf::from("C:\\Users\\a\\Fonts\\Inter.ttf")]
+        );
+    }
+}
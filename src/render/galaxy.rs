@@ -0,0 +1,302 @@
+//! Past pages of OZ mode as background galaxies.
+//!
+//! When OZ mode moves to another page, the rotunda of the page left behind
+//! is kept as a [`Galaxy`]: a few of its most important particles, frozen
+//! where they stood on the wall and shrunk into a small slowly turning
+//! cloud. [`Galaxies`] holds the last [`MAX_GALAXIES`] of them behind the
+//! current wall, each further back and fainter than the one after it — a
+//! spatial history of where the reader has been. A galaxy can be picked
+//! ([`Galaxies::pick`]) to go back to its page.
+
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+
+use super::stream::{RotundaLayer, StreamState, ROTUNDA_RADIUS};
+
+/// Most past pages kept.
+pub const MAX_GALAXIES: usize = 4;
+
+/// Most particles a galaxy keeps from its page.
+const STARS_PER_GALAXY: usize = 36;
+
+/// Distance of the newest galaxy from the viewer, in rotunda radii; each
+/// older one is [`GALAXY_RECESSION`] radii further.
+const GALAXY_DISTANCE: f32 = 2.0;
+const GALAXY_RECESSION: f32 = 0.9;
+
+/// Radius of the newest galaxy's cloud.
+const GALAXY_RADIUS: f32 = 4.0;
+
+/// Height of the newest galaxy's centre, over the upper ring's text; older
+/// ones rise further.
+const GALAXY_Y: f32 = 14.0;
+const GALAXY_RISE: f32 = 2.0;
+
+/// Turn between neighbouring galaxies' directions (the golden angle), so
+/// they spread around the viewer instead of lining up.
+const GALAXY_TURN: f32 = 2.399_963;
+
+/// How fast a galaxy's cloud turns (rad/s).
+const GALAXY_SPIN: f32 = 0.05;
+
+/// Opacity of the newest galaxy's particles.
+const GALAXY_OPACITY: f32 = 0.45;
+
+/// One particle of a galaxy.
+#[derive(Debug, Clone)]
+pub struct Star {
+    pub text: String,
+    pub category_index: usize,
+    pub importance: f32,
+    /// Position in the cloud, within the unit ball
+    offset: [f32; 3],
+}
+
+/// A page left in OZ mode.
+#[derive(Debug, Clone)]
+pub struct Galaxy {
+    pub url: String,
+    pub title: String,
+    pub stars: Vec<Star>,
+    /// Category colours of the page's stream
+    pub colors: Vec<[f32; 4]>,
+}
+
+impl Galaxy {
+    /// Freeze the most important visible particles of `stream`.
+    #[must_use]
+    pub fn from_stream(url: &str, title: &str, stream: &StreamState) -> Self {
+        let mut visible: Vec<usize> = (0..stream.particles.len())
+            .filter(|&i| {
+                stream.particle_opacity(i) > 0.15
+                    && stream.particles[i].layer != RotundaLayer::Lower
+            })
+            .collect();
+        visible.sort_by(|&a, &b| {
+            stream.particles[b]
+                .importance
+                .total_cmp(&stream.particles[a].importance)
+        });
+        visible.truncate(STARS_PER_GALAXY);
+        let stars = visible
+            .into_iter()
+            .map(|i| {
+                let p = &stream.particles[i];
+                let [x, y, z] = stream.particle_world_pos(i);
+                Star {
+                    text: p.text.clone(),
+                    category_index: p.category_index,
+                    importance: p.importance,
+                    offset: [x, y, z].map(|c| c / ROTUNDA_RADIUS),
+                }
+            })
+            .collect();
+        Self {
+            url: url.to_owned(),
+            title: title.to_owned(),
+            stars,
+            colors: stream.categories.iter().map(|c| c.color).collect(),
+        }
+    }
+
+    /// Colour of `star`, from its page's categories.
+    #[must_use]
+    pub fn color(&self, star: &Star) -> [f32; 4] {
+        self.colors
+            .get(star.category_index)
+            .copied()
+            .unwrap_or([0.3, 0.3, 0.3, 1.0])
+    }
+}
+
+/// Where a galaxy is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub center: [f32; 3],
+    /// Radius of the cloud
+    pub radius: f32,
+    /// Opacity of its particles
+    pub opacity: f32,
+    /// Spin angle at the time asked for
+    spin: f32,
+}
+
+impl Placement {
+    /// World position of `star` in a galaxy placed here.
+    #[must_use]
+    pub fn star_pos(&self, star: &Star) -> [f32; 3] {
+        let (sin, cos) = self.spin.sin_cos();
+        let [x, y, z] = star.offset;
+        [
+            x.mul_add(cos, -(z * sin))
+                .mul_add(self.radius, self.center[0]),
+            y.mul_add(self.radius, self.center[1]),
+            x.mul_add(sin, z * cos).mul_add(self.radius, self.center[2]),
+        ]
+    }
+}
+
+/// The pages left in OZ mode, newest first, and the one on the wall now.
+#[derive(Debug, Clone, Default)]
+pub struct Galaxies {
+    list: VecDeque<Galaxy>,
+    /// URL and title of the page the rotunda shows
+    current: Option<(String, String)>,
+}
+
+impl Galaxies {
+    /// The rotunda now shows `url`; `previous` is the stream it showed
+    /// until now, kept as a galaxy if it was another page. A galaxy of
+    /// `url` itself becomes the rotunda again.
+    pub fn enter(&mut self, url: &str, title: &str, previous: Option<&StreamState>) {
+        if let (Some((old_url, old_title)), Some(stream)) = (self.current.take(), previous) {
+            if old_url != url {
+                self.list.retain(|g| g.url != old_url);
+                self.list
+                    .push_front(Galaxy::from_stream(&old_url, &old_title, stream));
+                self.list.truncate(MAX_GALAXIES);
+            }
+        }
+        self.list.retain(|g| g.url != url);
+        self.current = Some((url.to_owned(), title.to_owned()));
+    }
+
+    /// Galaxies, newest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Galaxy> {
+        self.list.iter()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Where the `k`th newest galaxy is drawn at `time` seconds.
+    #[must_use]
+    pub fn placement(k: usize, time: f32) -> Placement {
+        let k = k as f32;
+        let distance = ROTUNDA_RADIUS * k.mul_add(GALAXY_RECESSION, GALAXY_DISTANCE);
+        // Straight ahead of the viewer's starting direction first
+        let angle = k.mul_add(GALAXY_TURN, TAU * 0.25);
+        let scale = 1.0 / k.mul_add(0.5, 1.0);
+        Placement {
+            center: [
+                distance * angle.cos(),
+                k.mul_add(GALAXY_RISE, GALAXY_Y),
+                distance * angle.sin(),
+            ],
+            radius: GALAXY_RADIUS * scale,
+            opacity: GALAXY_OPACITY * scale,
+            spin: time * GALAXY_SPIN + k,
+        }
+    }
+
+    /// The galaxy whose cloud, seen through `project` (world point to
+    /// screen point, `None` when off screen), is within `reach` of `pos`
+    /// on screen; the nearest if several are.
+    pub fn pick(
+        &self,
+        pos: [f32; 2],
+        reach: f32,
+        time: f32,
+        project: impl Fn([f32; 3]) -> Option<[f32; 2]>,
+    ) -> Option<&Galaxy> {
+        self.list
+            .iter()
+            .enumerate()
+            .filter_map(|(k, galaxy)| {
+                let placement = Self::placement(k, time);
+                let nearest = galaxy
+                    .stars
+                    .iter()
+                    .filter_map(|star| project(placement.star_pos(star)))
+                    .chain(project(placement.center))
+                    .map(|p| (p[0] - pos[0]).hypot(p[1] - pos[1]))
+                    .fold(f32::INFINITY, f32::min);
+                (nearest <= reach).then_some((nearest, galaxy))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, galaxy)| galaxy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::stream::TextMeta;
+
+    fn stream(words: &[&str]) -> StreamState {
+        let texts = words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| TextMeta {
+                display: (*w).to_string(),
+                full_text: (*w).to_string(),
+                tag: if i == 0 { "h1" } else { "p" }.to_string(),
+                href: None,
+                category_index: 0,
+                importance: 1.0 - i as f32 * 0.1,
+            })
+            .collect();
+        let mut stream = StreamState::from_groups(vec![("Page".to_string(), texts)]);
+        stream.arrange_static();
+        stream
+    }
+
+    #[test]
+    fn left_pages_become_galaxies_newest_first() {
+        let mut galaxies = Galaxies::default();
+        galaxies.enter("https://a.example/", "A", None);
+        assert!(galaxies.is_empty());
+        let a = stream(&["Alpha", "apple", "acorn"]);
+        galaxies.enter("https://b.example/", "B", Some(&a));
+        let b = stream(&["Beta", "banana"]);
+        galaxies.enter("https://c.example/", "C", Some(&b));
+        let urls: Vec<&str> = galaxies.iter().map(|g| g.url.as_str()).collect();
+        assert_eq!(urls, ["https://b.example/", "https://a.example/"]);
+        assert!(galaxies.iter().all(|g| !g.stars.is_empty()));
+
+        // Going back to A brings it out of the background
+        let c = stream(&["Gamma"]);
+        galaxies.enter("https://a.example/", "A", Some(&c));
+        let urls: Vec<&str> = galaxies.iter().map(|g| g.url.as_str()).collect();
+        assert_eq!(urls, ["https://c.example/", "https://b.example/"]);
+
+        // Reloading the same page keeps nothing
+        galaxies.enter("https://a.example/", "A", Some(&a));
+        assert_eq!(galaxies.len(), 2);
+
+        for i in 0..MAX_GALAXIES + 2 {
+            galaxies.enter(&format!("https://{i}.example/"), "", Some(&a));
+        }
+        assert_eq!(galaxies.len(), MAX_GALAXIES);
+    }
+
+    #[test]
+    fn older_galaxies_recede_and_fade() {
+        let near = Galaxies::placement(0, 0.0);
+        let far = Galaxies::placement(1, 0.0);
+        let dist = |p: &Placement| p.center[0].hypot(p.center[2]);
+        assert!(dist(&near) > ROTUNDA_RADIUS);
+        assert!(dist(&far) > dist(&near));
+        assert!(far.opacity < near.opacity && far.radius < near.radius);
+    }
+
+    #[test]
+    fn picking_finds_the_galaxy_under_the_pointer() {
+        let mut galaxies = Galaxies::default();
+        galaxies.enter("https://a.example/", "A", None);
+        galaxies.enter("https://b.example/", "B", Some(&stream(&["Alpha"])));
+        // Looking straight down the x axis from above: screen = (z, -x)
+        let project = |p: [f32; 3]| Some([p[2], -p[0]]);
+        let center = Galaxies::placement(0, 0.0).center;
+        let hit = galaxies.pick([center[2], -center[0]], 1.0, 0.0, project);
+        assert_eq!(hit.map(|g| g.url.as_str()), Some("https://a.example/"));
+        assert!(galaxies.pick([0.0, 0.0], 1.0, 0.0, project).is_none());
+    }
+}
@@ -4,14 +4,34 @@
 //! shading, and compositing on the GPU. Falls back to CPU if unavailable.
 //!
 //! Architecture:
-//! - SDF union tree is transpiled to WGSL via ALICE-SDF's `WgslShader`
-//! - Per-primitive SDFs are generated inline for color lookup
-//! - A single compute dispatch renders all pixels in parallel
+//! - One fixed WGSL shader, compiled once when the renderer is created
+//! - Scene primitives are uploaded to a storage buffer as tagged records and
+//!   evaluated by a generic per-kind SDF in the shader, so a changed or
+//!   animated scene costs a buffer write, not a shader rebuild
+//! - A [`Bvh`] over the primitives is uploaded alongside them, flattened
+//!   into a node array the shader walks with a fixed-size stack, so each
+//!   distance query evaluates only the primitives near the ray; both hold
+//!   only the primitives that survive [culling](crate::render::culling)
+//!   for the frame's camera
+//! - A single compute dispatch renders all pixels in parallel, either into
+//!   a buffer that is read back ([`GpuRenderer::render`]) or into a
+//!   persistent texture that the UI samples directly
+//!   ([`GpuRenderer::render_to_texture`]) — no copy through the CPU
+//! - One primitive can be picked out with a glow and an outline
+//!   ([`GpuRenderer::set_highlight`]), for hover feedback
+//!
+//! The renderer can own its device or share the UI's
+//! ([`GpuRenderer::with_device`]); the texture path needs the latter, since
+//! a texture can only be drawn by the device that created it.
+
+use std::sync::Arc;
 
-use alice_sdf::compiled::{TranspileMode, WgslShader};
 use alice_sdf::prelude::*;
 use wgpu::util::DeviceExt;
 
+use crate::render::bvh::{self, Bvh};
+use crate::render::culling::{cull, CullStats, View};
+use crate::render::palette::{ScenePalette, SkyPalette};
 use crate::render::sdf_renderer::CameraParams;
 use crate::render::sdf_ui::{SdfPrimitive, SdfScene};
 
@@ -36,25 +56,127 @@ struct Uniforms {
     fog_end: f32,
     width: u32,
     height: u32,
-    _pad1: u32,
-    _pad2: u32,
+    prim_count: u32,
+    /// Slot of the highlighted primitive, or [`NO_HIGHLIGHT`]
+    highlight: u32,
+    // Palette (3 × vec4 = 48 bytes)
+    sky_zenith: [f32; 3],
+    _pad2: f32,
+    rim_color: [f32; 3],
+    _pad3: f32,
+    shadow_tint: [f32; 3],
+    _pad4: f32,
+}
+
+impl Uniforms {
+    /// The camera these uniforms trace with, as culling sees it.
+    const fn view(&self) -> View {
+        View {
+            eye: self.cam_origin,
+            forward: self.cam_forward,
+            right: self.cam_right,
+            up: self.cam_up,
+            tan_half_fov: self.cam_fov_factor,
+            aspect: self.cam_aspect,
+            far: self.cam_max_march_dist,
+        }
+    }
+}
+
+/// `Uniforms::highlight` when nothing is highlighted.
+const NO_HIGHLIGHT: u32 = u32::MAX;
+
+/// Primitive kinds understood by the shader's `sdf_prim`.
+const KIND_BOX: u32 = 0;
+const KIND_CAPSULE: u32 = 1;
+const KIND_SPHERE: u32 = 2;
+const KIND_TORUS: u32 = 3;
+
+/// One scene primitive as stored in the GPU buffer (64 bytes, matches the
+/// WGSL `Prim` struct). The meaning of `a` and `b` depends on `kind`:
+///
+/// - box: `a` = centre + edge rounding, `b` = half extents
+/// - capsule: `a` = start + radius, `b` = end − start
+/// - sphere: `a` = centre + radius
+/// - torus: `a` = centre + major radius, `b.x` = minor radius
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct GpuPrimitive {
+    kind: u32,
+    /// 1 for text and billboards, which skip toon shading
+    unlit: u32,
+    _pad: [u32; 2],
+    a: [f32; 4],
+    b: [f32; 4],
+    color: [f32; 4],
+}
+
+/// One BVH node as stored in the GPU buffer (32 bytes, matches the WGSL
+/// `BvhNode` struct); see [`bvh::BvhNode`].
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct GpuBvhNode {
+    min: [f32; 3],
+    /// First primitive of a leaf, or the left child of an interior node
+    first: u32,
+    max: [f32; 3],
+    /// Primitives in a leaf; 0 for an interior node
+    count: u32,
+}
+
+impl From<&bvh::BvhNode> for GpuBvhNode {
+    fn from(node: &bvh::BvhNode) -> Self {
+        Self {
+            min: node.bounds.min,
+            first: node.first,
+            max: node.bounds.max,
+            count: node.count,
+        }
+    }
 }
 
 // ── GPU Renderer ──
 
-/// Persistent GPU renderer that caches device/queue and recompiles
-/// the pipeline only when the scene changes.
-pub struct GpuRenderer {
-    device: wgpu::Device,
-    queue: wgpu::Queue,
-    cached: Option<CachedPipeline>,
-    /// Number of primitives in the cached scene (used to detect changes)
-    cached_prim_count: usize,
+/// Format of the output texture. Stored as plain `Rgba8Unorm` (storage
+/// textures cannot be sRGB) and sampled through an sRGB view, matching how
+/// the buffer path's bytes are interpreted.
+const OUTPUT_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// The texture the texture path renders into, kept across frames.
+struct OutputTexture {
+    texture: wgpu::Texture,
+    /// Written by the compute shader
+    storage_view: wgpu::TextureView,
+    /// Sampled by the UI
+    sample_view: wgpu::TextureView,
 }
 
-struct CachedPipeline {
+/// Persistent GPU renderer: device, queue and the raymarch pipelines are
+/// created once; each render uploads the scene into a reusable buffer.
+pub struct GpuRenderer {
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+    /// Raymarch into a pixel buffer for readback
     pipeline: wgpu::ComputePipeline,
     bind_group_layout: wgpu::BindGroupLayout,
+    /// Raymarch into `output`
+    texture_pipeline: wgpu::ComputePipeline,
+    texture_layout: wgpu::BindGroupLayout,
+    output: Option<OutputTexture>,
+    /// Primitive storage buffer, grown to the largest scene seen
+    prim_buf: Option<wgpu::Buffer>,
+    /// Capacity of `prim_buf`, in primitives
+    prim_capacity: usize,
+    /// BVH node storage buffer, grown like `prim_buf`
+    bvh_buf: Option<wgpu::Buffer>,
+    /// Capacity of `bvh_buf`, in nodes
+    bvh_capacity: usize,
+    /// Sky and lighting colours of the current theme
+    sky: SkyPalette,
+    /// What culling kept of the last scene rendered
+    culling: CullStats,
+    /// Scene index of the primitive drawn with an outline
+    highlight: Option<usize>,
 }
 
 impl GpuRenderer {
@@ -85,12 +207,57 @@ impl GpuRenderer {
 
         log::info!("GPU renderer initialised: {:?}", adapter.get_info().name);
 
-        Some(Self {
+        Some(Self::with_device(Arc::new(device), Arc::new(queue)))
+    }
+
+    /// Build the renderer on an existing device, typically the UI's, so
+    /// that [`render_to_texture`](Self::render_to_texture) output can be
+    /// drawn without leaving the GPU.
+    #[must_use]
+    pub fn with_device(device: Arc<wgpu::Device>, queue: Arc<wgpu::Queue>) -> Self {
+        let (pipeline, bind_group_layout, texture_pipeline, texture_layout) =
+            build_pipelines(&device);
+        Self {
             device,
             queue,
-            cached: None,
-            cached_prim_count: 0,
-        })
+            pipeline,
+            bind_group_layout,
+            texture_pipeline,
+            texture_layout,
+            output: None,
+            prim_buf: None,
+            prim_capacity: 0,
+            bvh_buf: None,
+            bvh_capacity: 0,
+            sky: ScenePalette::DAY.sky,
+            culling: CullStats::default(),
+            highlight: None,
+        }
+    }
+
+    /// Use `sky` for the sky gradient and lighting from the next render.
+    pub fn set_sky(&mut self, sky: SkyPalette) {
+        self.sky = sky;
+    }
+
+    /// Outline the primitive at `index` in the scene from the next render
+    /// on, or none. Returns whether that changes anything.
+    pub fn set_highlight(&mut self, index: Option<usize>) -> bool {
+        let changed = self.highlight != index;
+        self.highlight = index;
+        changed
+    }
+
+    /// What culling kept of the scene in the last render.
+    #[must_use]
+    pub const fn culling(&self) -> CullStats {
+        self.culling
+    }
+
+    /// The device the renderer runs on.
+    #[must_use]
+    pub fn device(&self) -> &wgpu::Device {
+        &self.device
     }
 
     /// Render the scene to an RGBA pixel buffer using the GPU.
@@ -105,56 +272,10 @@ impl GpuRenderer {
             return None;
         }
 
-        // Rebuild pipeline when scene changes
-        if self.cached.is_none() || self.cached_prim_count != scene.primitives.len() {
-            self.rebuild_pipeline(scene);
-        }
-        let cached = self.cached.as_ref()?;
-
-        // Compute camera vectors
-        let target = Vec3::new(cam.target[0], cam.target[1], cam.target[2]);
-        let eye = target
-            + Vec3::new(
-                cam.distance * cam.azimuth.sin() * cam.elevation.cos(),
-                cam.distance * cam.elevation.sin(),
-                cam.distance * cam.azimuth.cos() * cam.elevation.cos(),
-            );
-        let forward = (target - eye).normalize();
-        let world_up = Vec3::Y;
-        let right = forward.cross(world_up).normalize();
-        let up = right.cross(forward);
-        let fov_factor = (50.0f32.to_radians() * 0.5).tan();
-
-        // Scene bounds for fog / march distance
-        let (mn, mx) = scene_bounds(scene);
-        let extent = mx - mn;
-        let max_extent = extent.x.max(extent.y.max(extent.z)).max(0.5);
-        let max_march_dist = max_extent * 5.0;
-
-        let light_dir = Vec3::new(0.5, 0.8, 0.3).normalize();
-
-        let uniforms = Uniforms {
-            cam_origin: eye.into(),
-            cam_fov_factor: fov_factor,
-            cam_forward: forward.into(),
-            cam_aspect: width as f32 / height as f32,
-            cam_right: right.into(),
-            cam_max_march_dist: max_march_dist,
-            cam_up: up.into(),
-            _pad0: 0.0,
-            light_dir: light_dir.into(),
-            fog_start: max_extent * 1.5,
-            bg_color: [
-                scene.background_color[0],
-                scene.background_color[1],
-                scene.background_color[2],
-            ],
-            fog_end: max_extent * 4.0,
-            width: width as u32,
-            height: height as u32,
-            _pad1: 0,
-            _pad2: 0,
-        };
+        let mut uniforms = uniforms(scene, width as u32, height as u32, cam, &self.sky);
+        (uniforms.prim_count, uniforms.highlight) = self.upload_scene(scene, &uniforms.view());
+        let prim_buf = self.prim_buf.as_ref()?;
+        let bvh_buf = self.bvh_buf.as_ref()?;
 
         let pixel_count = width * height;
 
@@ -185,7 +306,7 @@ impl GpuRenderer {
         // Bind group
         let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Render Bind Group"),
-            layout: &cached.bind_group_layout,
+            layout: &self.bind_group_layout,
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
@@ -195,28 +316,20 @@ impl GpuRenderer {
                     binding: 1,
                     resource: output_buf.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: prim_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: bvh_buf.as_entire_binding(),
+                },
             ],
         });
 
         // Dispatch
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("Render Encoder"),
-            });
-
-        {
-            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                label: Some("Raymarch Pass"),
-                timestamp_writes: None,
-            });
-            pass.set_pipeline(&cached.pipeline);
-            pass.set_bind_group(0, &bind_group, &[]);
-            let wg_x = (width as u32).div_ceil(16);
-            let wg_y = (height as u32).div_ceil(16);
-            pass.dispatch_workgroups(wg_x, wg_y, 1);
-        }
-
+        let mut encoder =
+            self.encode_raymarch(&self.pipeline, &bind_group, width as u32, height as u32);
         encoder.copy_buffer_to_buffer(&output_buf, 0, &staging_buf, 0, output_size);
         self.queue.submit(std::iter::once(encoder.finish()));
 
@@ -251,130 +364,315 @@ impl GpuRenderer {
         Some(pixels)
     }
 
-    /// Invalidate the cached pipeline so it will be rebuilt on next render.
-    pub fn invalidate(&mut self) {
-        self.cached = None;
-        self.cached_prim_count = 0;
-    }
-
-    // ── Pipeline construction ──
+    /// Render the scene into the renderer's output texture, entirely on the
+    /// GPU: nothing is read back. The texture is kept between renders and
+    /// only recreated when the size changes; draw it through
+    /// [`output_view`](Self::output_view).
+    ///
+    /// Returns whether the texture was recreated — its view is then a new
+    /// one and must be handed to the UI again — or `None` if there is
+    /// nothing to render.
+    pub fn render_to_texture(
+        &mut self,
+        scene: &SdfScene,
+        width: u32,
+        height: u32,
+        cam: &CameraParams,
+    ) -> Option<bool> {
+        if scene.primitives.is_empty() || width == 0 || height == 0 {
+            return None;
+        }
 
-    fn rebuild_pipeline(&mut self, scene: &SdfScene) {
-        let wgsl = generate_shader(scene);
+        let mut uniforms = uniforms(scene, width, height, cam, &self.sky);
+        (uniforms.prim_count, uniforms.highlight) = self.upload_scene(scene, &uniforms.view());
+        let recreated = self.ensure_output(width, height);
+        let prim_buf = self.prim_buf.as_ref()?;
+        let bvh_buf = self.bvh_buf.as_ref()?;
+        let output = self.output.as_ref()?;
 
-        let shader_module = self
+        let uniform_buf = self
             .device
-            .create_shader_module(wgpu::ShaderModuleDescriptor {
-                label: Some("Raymarch Shader"),
-                source: wgpu::ShaderSource::Wgsl(wgsl.into()),
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Uniforms"),
+                contents: bytemuck::bytes_of(&uniforms),
+                usage: wgpu::BufferUsages::UNIFORM,
             });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Texture Render Bind Group"),
+            layout: &self.texture_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: prim_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(&output.storage_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: bvh_buf.as_entire_binding(),
+                },
+            ],
+        });
 
-        let bind_group_layout =
-            self.device
-                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                    label: Some("Render BGL"),
-                    entries: &[
-                        // Uniforms
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 0,
-                            visibility: wgpu::ShaderStages::COMPUTE,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Uniform,
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                        // Output pixels
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 1,
-                            visibility: wgpu::ShaderStages::COMPUTE,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Storage { read_only: false },
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                    ],
-                });
-
-        let pipeline_layout = self
-            .device
-            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Render PL"),
-                bind_group_layouts: &[&bind_group_layout],
-                push_constant_ranges: &[],
-            });
+        // Queue order puts this ahead of the UI pass that samples the texture
+        let encoder = self.encode_raymarch(&self.texture_pipeline, &bind_group, width, height);
+        self.queue.submit(std::iter::once(encoder.finish()));
+        Some(recreated)
+    }
+
+    /// View of the output texture for sampling, once
+    /// [`render_to_texture`](Self::render_to_texture) has run.
+    #[must_use]
+    pub fn output_view(&self) -> Option<&wgpu::TextureView> {
+        self.output.as_ref().map(|o| &o.sample_view)
+    }
+
+    /// Make sure the output texture is `width`×`height`; returns `true` if
+    /// it had to be created.
+    fn ensure_output(&mut self, width: u32, height: u32) -> bool {
+        if self
+            .output
+            .as_ref()
+            .is_some_and(|o| o.texture.width() == width && o.texture.height() == height)
+        {
+            return false;
+        }
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Raymarch Output"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: OUTPUT_FORMAT,
+            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[OUTPUT_FORMAT.add_srgb_suffix()],
+        });
+        let storage_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sample_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            format: Some(OUTPUT_FORMAT.add_srgb_suffix()),
+            ..Default::default()
+        });
+        log::debug!("GPU output texture resized to {width}x{height}");
+        self.output = Some(OutputTexture {
+            texture,
+            storage_view,
+            sample_view,
+        });
+        true
+    }
 
-        let pipeline = self
+    /// Record one raymarch dispatch over a `width`×`height` image.
+    fn encode_raymarch(
+        &self,
+        pipeline: &wgpu::ComputePipeline,
+        bind_group: &wgpu::BindGroup,
+        width: u32,
+        height: u32,
+    ) -> wgpu::CommandEncoder {
+        let mut encoder = self
             .device
-            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
-                label: Some("Raymarch Pipeline"),
-                layout: Some(&pipeline_layout),
-                module: &shader_module,
-                entry_point: Some("main"),
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-                cache: None,
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render Encoder"),
+            });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Raymarch Pass"),
+                timestamp_writes: None,
             });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.dispatch_workgroups(width.div_ceil(16), height.div_ceil(16), 1);
+        }
+        encoder
+    }
 
-        self.cached = Some(CachedPipeline {
-            pipeline,
-            bind_group_layout,
-        });
-        self.cached_prim_count = scene.primitives.len();
-        log::info!(
-            "GPU pipeline rebuilt for {} primitives",
-            scene.primitives.len()
+    /// Release the scene buffers. Scenes are uploaded on every render, so
+    /// a changed scene needs no call; this only frees the buffers of the old
+    /// one. The pipelines and the output texture are kept.
+    pub fn invalidate(&mut self) {
+        self.prim_buf = None;
+        self.prim_capacity = 0;
+        self.bvh_buf = None;
+        self.bvh_capacity = 0;
+    }
+
+    /// Cull `scene` for `view`, build the BVH of what is left and write it
+    /// and the primitives, in its leaf order, into the storage buffers,
+    /// growing them if the scene no longer fits. Returns the number of
+    /// primitives uploaded and the slot of the highlighted one.
+    fn upload_scene(&mut self, scene: &SdfScene, view: &View) -> (u32, u32) {
+        let visible = cull(scene, view);
+        self.culling = visible.stats;
+        let bvh = Bvh::from_subset(scene, &visible.indices);
+        let prims: Vec<GpuPrimitive> = bvh
+            .order
+            .iter()
+            .map(|&i| gpu_primitive(&scene.primitives[i as usize]))
+            .collect();
+        let nodes: Vec<GpuBvhNode> = bvh.nodes.iter().map(GpuBvhNode::from).collect();
+        let highlight = self
+            .highlight
+            .and_then(|index| bvh.order.iter().position(|&i| i as usize == index))
+            .map_or(NO_HIGHLIGHT, |slot| slot as u32);
+        write_storage(
+            &self.device,
+            &self.queue,
+            &mut self.prim_buf,
+            &mut self.prim_capacity,
+            &prims,
+            "Scene Primitives",
         );
+        write_storage(
+            &self.device,
+            &self.queue,
+            &mut self.bvh_buf,
+            &mut self.bvh_capacity,
+            &nodes,
+            "Scene BVH",
+        );
+        (prims.len() as u32, highlight)
     }
 }
 
-// ── WGSL Shader Generation ──
-
-/// Generate the complete WGSL compute shader for a given scene.
-fn generate_shader(scene: &SdfScene) -> String {
-    use std::fmt::Write;
-    // 1. Build the union tree and transpile to WGSL
-    let nodes: Vec<SdfNode> = scene
-        .primitives
-        .iter()
-        .map(|p| primitive_to_node(p).0)
-        .collect();
-    let union_tree = build_balanced_union(&nodes);
-    let sdf_shader = WgslShader::transpile(&union_tree, TranspileMode::Hardcoded);
-    let sdf_eval_src = sdf_shader.source; // contains helpers + fn sdf_eval(...)
-
-    // 2. Generate per-primitive SDF functions for color lookup
-    let mut prim_fns = String::new();
-    let mut color_body = String::new();
-    color_body.push_str("    var min_d = 1e10;\n");
-    color_body.push_str("    var col = vec3<f32>(0.0);\n");
-    color_body.push_str("    var unlit = 0.0;\n");
-    color_body.push_str("    var d: f32;\n");
-
-    for (i, prim) in scene.primitives.iter().enumerate() {
-        let (_, color) = primitive_to_node(prim);
-        prim_fns.push_str(&prim_to_wgsl(prim, i));
-        prim_fns.push('\n');
-        let is_unlit = matches!(
-            prim,
-            SdfPrimitive::TextLabel { .. } | SdfPrimitive::Billboard { .. }
-        );
-        let unlit_val = if is_unlit { 1.0 } else { 0.0 };
-        write!(
-            color_body,
-            "    d = sdf_prim_{i}(p);\n    if (d < min_d) {{ min_d = d; col = vec3<f32>({:.6}, {:.6}, {:.6}); unlit = {:.1}; }}\n",
-            color[0], color[1], color[2], unlit_val
-        )
-        .unwrap();
+/// Write `items` into the storage buffer `buf`, replacing it with one of the
+/// next power-of-two capacity if they don't fit.
+fn write_storage<T: bytemuck::Pod>(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    buf: &mut Option<wgpu::Buffer>,
+    capacity: &mut usize,
+    items: &[T],
+    label: &str,
+) {
+    if buf.is_none() || *capacity < items.len() {
+        *capacity = items.len().next_power_of_two();
+        *buf = Some(device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size: (*capacity * std::mem::size_of::<T>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+        log::debug!("GPU buffer {label:?} sized for {capacity} items");
+    }
+    if let Some(ref buf) = buf {
+        queue.write_buffer(buf, 0, bytemuck::cast_slice(items));
     }
+}
 
-    // 3. Compose the full shader
-    format!(
-        r"// ALICE Browser — GPU Raymarcher (auto-generated)
+// ── Pipeline construction ──
+
+/// Compile the raymarch shader and build its two pipelines (once per
+/// renderer): buffer output (`main`) and texture output (`main_texture`),
+/// each with its bind group layout.
+fn build_pipelines(
+    device: &wgpu::Device,
+) -> (
+    wgpu::ComputePipeline,
+    wgpu::BindGroupLayout,
+    wgpu::ComputePipeline,
+    wgpu::BindGroupLayout,
+) {
+    let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Raymarch Shader"),
+        source: wgpu::ShaderSource::Wgsl(RAYMARCH_WGSL.into()),
+    });
+
+    let buffer_entry = |binding: u32, ty: wgpu::BufferBindingType| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer {
+            ty,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    };
+    let build = |label: &str, entry_point: &str, entries: &[wgpu::BindGroupLayoutEntry]| {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some(label),
+            entries,
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(label),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(label),
+            layout: Some(&pipeline_layout),
+            module: &shader_module,
+            entry_point,
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+        (pipeline, bind_group_layout)
+    };
+
+    let (pipeline, bind_group_layout) = build(
+        "Raymarch Pipeline",
+        "main",
+        &[
+            // Uniforms
+            buffer_entry(0, wgpu::BufferBindingType::Uniform),
+            // Output pixels
+            buffer_entry(1, wgpu::BufferBindingType::Storage { read_only: false }),
+            // Scene primitives
+            buffer_entry(2, wgpu::BufferBindingType::Storage { read_only: true }),
+            // Scene BVH
+            buffer_entry(4, wgpu::BufferBindingType::Storage { read_only: true }),
+        ],
+    );
+    let (texture_pipeline, texture_layout) = build(
+        "Raymarch Texture Pipeline",
+        "main_texture",
+        &[
+            buffer_entry(0, wgpu::BufferBindingType::Uniform),
+            buffer_entry(2, wgpu::BufferBindingType::Storage { read_only: true }),
+            // Output texture
+            wgpu::BindGroupLayoutEntry {
+                binding: 3,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::StorageTexture {
+                    access: wgpu::StorageTextureAccess::WriteOnly,
+                    format: OUTPUT_FORMAT,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                },
+                count: None,
+            },
+            buffer_entry(4, wgpu::BufferBindingType::Storage { read_only: true }),
+        ],
+    );
 
-struct Uniforms {{
+    (
+        pipeline,
+        bind_group_layout,
+        texture_pipeline,
+        texture_layout,
+    )
+}
+
+// ── WGSL Shader ──
+
+// The shader's `BVH_STACK` must hold a traversal of the deepest tree
+const _: () = assert!(bvh::STACK_SIZE == 32);
+
+/// The raymarch compute shader. Scene-independent: primitives are read from
+/// the `prims` buffer in BVH leaf order, the first `u.prim_count` of them in
+/// use, and found through the `bvh` node array. `main` writes packed pixels
+/// to `output_pixels`, `main_texture` to `output_tex`.
+const RAYMARCH_WGSL: &str = r"// ALICE Browser — GPU Raymarcher
+
+struct Uniforms {
     cam_origin: vec3<f32>,
     cam_fov_factor: f32,
     cam_forward: vec3<f32>,
@@ -389,61 +687,169 @@ struct Uniforms {{
     fog_end: f32,
     width: u32,
     height: u32,
+    prim_count: u32,
+    highlight: u32,
+    sky_zenith: vec3<f32>,
+    _pad2: f32,
+    rim_color: vec3<f32>,
+    _pad3: f32,
+    shadow_tint: vec3<f32>,
+    _pad4: f32,
+}
+
+struct Prim {
+    kind: u32,
+    unlit: u32,
+    _pad0: u32,
     _pad1: u32,
-    _pad2: u32,
-}}
+    a: vec4<f32>,
+    b: vec4<f32>,
+    color: vec4<f32>,
+}
+
+// Leaf: prims[first .. first + count]. Interior (count 0): children at
+// bvh[first] and bvh[first + 1].
+struct BvhNode {
+    min: vec3<f32>,
+    first: u32,
+    max: vec3<f32>,
+    count: u32,
+}
+
+struct Nearest {
+    d: f32,
+    index: u32,
+}
 
 @group(0) @binding(0) var<uniform> u: Uniforms;
 @group(0) @binding(1) var<storage, read_write> output_pixels: array<u32>;
+@group(0) @binding(2) var<storage, read> prims: array<Prim>;
+@group(0) @binding(3) var output_tex: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(4) var<storage, read> bvh: array<BvhNode>;
+
+// Traversal stack depth (bvh::STACK_SIZE)
+const BVH_STACK: u32 = 32u;
+
+// Highlighted primitive: emissive colour, and how far from its silhouette
+// the outline reaches (as a fraction of the distance along the ray)
+const HIGHLIGHT_NONE: u32 = 0xffffffffu;
+const HIGHLIGHT_COLOR: vec3<f32> = vec3<f32>(1.0, 0.78, 0.25);
+const OUTLINE_WIDTH: f32 = 0.004;
+
+// ── Per-kind SDFs (kinds match GpuPrimitive) ──
+fn sdf_box(lp: vec3<f32>, h: vec3<f32>, r: f32) -> f32 {
+    let q = abs(lp) - h;
+    return length(max(q, vec3<f32>(0.0))) + min(max(q.x, max(q.y, q.z)), 0.0) - r;
+}
+
+fn sdf_prim(pr: Prim, p: vec3<f32>) -> f32 {
+    switch pr.kind {
+        case 1u: {
+            // Capsule from a.xyz along b.xyz
+            let pa = p - pr.a.xyz;
+            let ba = pr.b.xyz;
+            let h = clamp(dot(pa, ba) / max(dot(ba, ba), 1e-8), 0.0, 1.0);
+            return length(pa - ba * h) - pr.a.w;
+        }
+        case 2u: {
+            return length(p - pr.a.xyz) - pr.a.w;
+        }
+        case 3u: {
+            let lp = p - pr.a.xyz;
+            let q = vec2<f32>(length(lp.xz) - pr.a.w, lp.y);
+            return length(q) - pr.b.x;
+        }
+        default: {
+            return sdf_box(p - pr.a.xyz, pr.b.xyz, pr.a.w);
+        }
+    }
+}
 
-// ── SDF evaluation (transpiled by ALICE-SDF) ──
-{sdf_eval_src}
+// ── Distance from p to a node's box (0 inside) ──
+fn box_dist(node: BvhNode, p: vec3<f32>) -> f32 {
+    return length(max(max(node.min - p, p - node.max), vec3<f32>(0.0)));
+}
+
+// ── Nearest primitive: walk the BVH, skipping boxes farther than the
+// closest surface so far, nearer child first ──
+fn scene_nearest(p: vec3<f32>) -> Nearest {
+    var best = Nearest(1e10, 0u);
+    // Everything culled: the buffers hold no tree
+    if (u.prim_count == 0u) {
+        return best;
+    }
+    var stack: array<u32, BVH_STACK>;
+    stack[0] = 0u;
+    var sp = 1u;
+    while (sp > 0u) {
+        sp -= 1u;
+        let node = bvh[stack[sp]];
+        if (box_dist(node, p) >= best.d) {
+            continue;
+        }
+        if (node.count > 0u) {
+            for (var i = node.first; i < node.first + node.count; i++) {
+                let d = sdf_prim(prims[i], p);
+                if (d < best.d) {
+                    best = Nearest(d, i);
+                }
+            }
+        } else if (sp + 2u <= BVH_STACK) {
+            let left = node.first;
+            let right = node.first + 1u;
+            if (box_dist(bvh[left], p) <= box_dist(bvh[right], p)) {
+                stack[sp] = right;
+                stack[sp + 1u] = left;
+            } else {
+                stack[sp] = left;
+                stack[sp + 1u] = right;
+            }
+            sp += 2u;
+        }
+    }
+    return best;
+}
 
-// ── Per-primitive SDF for color lookup ──
-{prim_fns}
+// ── Scene SDF: union of all primitives ──
+fn sdf_eval(p: vec3<f32>) -> f32 {
+    return scene_nearest(p).d;
+}
 
-fn closest_color(p: vec3<f32>) -> vec4<f32> {{
-{color_body}    return vec4<f32>(col, unlit);
-}}
+// ── Color (xyz) and unlit flag (w) of the nearest primitive ──
+fn closest_color(p: vec3<f32>) -> vec4<f32> {
+    let pr = prims[scene_nearest(p).index];
+    return vec4<f32>(pr.color.xyz, f32(pr.unlit));
+}
 
 // ── Normal estimation (central differences) ──
-fn calc_normal(p: vec3<f32>) -> vec3<f32> {{
+fn calc_normal(p: vec3<f32>) -> vec3<f32> {
     let e = vec2<f32>(0.001, 0.0);
     return normalize(vec3<f32>(
         sdf_eval(p + e.xyy) - sdf_eval(p - e.xyy),
         sdf_eval(p + e.yxy) - sdf_eval(p - e.yxy),
         sdf_eval(p + e.yyx) - sdf_eval(p - e.yyx)
     ));
-}}
+}
 
 // ── Toon shading step function ──
-fn toon_step(n_dot_l: f32) -> f32 {{
+fn toon_step(n_dot_l: f32) -> f32 {
     return smoothstep(0.48, 0.52, n_dot_l);
-}}
+}
 
 // ── Rim lighting ──
-fn rim_light(n: vec3<f32>, v: vec3<f32>) -> f32 {{
+fn rim_light(n: vec3<f32>, v: vec3<f32>) -> f32 {
     let rim = 1.0 - max(dot(n, v), 0.0);
     return pow(rim, 3.0) * 0.6;
-}}
+}
 
-// ── Sky color (Cyber-White: pure white with subtle gradient) ──
-fn sky_color(dir: vec3<f32>) -> vec3<f32> {{
+// ── Sky color: background at the horizon, palette zenith overhead ──
+fn sky_color(dir: vec3<f32>) -> vec3<f32> {
     let t = clamp(dir.y * 0.5 + 0.5, 0.0, 1.0);
-    let horizon = u.bg_color;
-    let zenith = u.bg_color * 0.95;
-    return horizon * (1.0 - t) + zenith * t;
-}}
-
-// ── Main compute kernel: one thread per pixel ──
-@compute @workgroup_size(16, 16)
-fn main(@builtin(global_invocation_id) gid: vec3<u32>) {{
-    let px = gid.x;
-    let py = gid.y;
-    if (px >= u.width || py >= u.height) {{
-        return;
-    }}
+    return u.bg_color * (1.0 - t) + u.sky_zenith * t;
+}
 
+// ── Colour of one pixel, each channel in 0..1 ──
+fn shade(px: u32, py: u32) -> vec3<f32> {
     let uf = (f32(px) + 0.5) / f32(u.width) * 2.0 - 1.0;
     let vf = -((f32(py) + 0.5) / f32(u.height) * 2.0 - 1.0);
 
@@ -453,27 +859,32 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {{
         + u.cam_up * (vf * u.cam_fov_factor)
     );
 
-    // Sphere trace
+    // Sphere trace, noting how close the ray passes the highlighted
+    // primitive for its outline
     var t = 0.0;
     var hit = false;
-    for (var i = 0u; i < 80u; i++) {{
+    var hit_index = 0u;
+    var outline = 1e10;
+    for (var i = 0u; i < 80u; i++) {
         let p = u.cam_origin + ray_dir * t;
-        let d = sdf_eval(p);
-        if (d < 0.001) {{
+        let found = scene_nearest(p);
+        let d = found.d;
+        if (u.highlight != HIGHLIGHT_NONE) {
+            outline = min(outline, sdf_prim(prims[u.highlight], p) / max(t, 0.01));
+        }
+        if (d < 0.001) {
             hit = true;
+            hit_index = found.index;
             break;
-        }}
+        }
         t += d;
-        if (t > u.cam_max_march_dist) {{
+        if (t > u.cam_max_march_dist) {
             break;
-        }}
-    }}
-
-    var r: f32;
-    var g: f32;
-    var b: f32;
+        }
+    }
 
-    if (hit) {{
+    var col = clamp(sky_color(ray_dir), vec3<f32>(0.0), vec3<f32>(1.0));
+    if (hit) {
         let hit_pos = u.cam_origin + ray_dir * t;
         let n = calc_normal(hit_pos);
         let light_dir = normalize(u.light_dir);
@@ -487,52 +898,82 @@ fn main(@builtin(global_invocation_id) gid: vec3<u32>) {{
 
         // Branch: unlit primitives (TextLabel/Billboard) skip toon shading
         var col_rim: vec3<f32>;
-        if (is_unlit > 0.5) {{
+        if (is_unlit > 0.5) {
             col_rim = mat;
-        }} else {{
+        } else {
             // Toon: 2-tone shading (hard light/shadow boundary)
             let toon = toon_step(n_dot_l);
 
             // Shadow color: complementary dark (not black)
-            let shadow_col = mat * 0.35 + vec3<f32>(0.05, 0.03, 0.08);
+            let shadow_col = mat * 0.35 + u.shadow_tint;
 
             // Lit = bright material, shadow = complementary dark
             let col = mat * toon + shadow_col * (1.0 - toon);
 
             // Rim lighting: edge glow
             let rim = rim_light(n, view_dir);
-            let rim_col = mat * 0.5 + vec3<f32>(0.5, 0.5, 0.5);
+            let rim_col = mat * 0.5 + u.rim_color;
             col_rim = col + rim_col * rim;
-        }}
+        }
+
+        // Highlighted: glow, brightest toward the edges
+        if (hit_index == u.highlight) {
+            let edge = 1.0 - max(dot(n, view_dir), 0.0);
+            col_rim = mix(col_rim, HIGHLIGHT_COLOR, 0.25 + 0.6 * edge * edge);
+        }
 
         // Distance fog (gentle, into white)
         let fog_t = clamp((t - u.fog_start) / (u.fog_end - u.fog_start), 0.0, 1.0);
         let sky = sky_color(ray_dir);
         let final_col = col_rim * (1.0 - fog_t) + sky * fog_t;
+        col = clamp(final_col, vec3<f32>(0.0), vec3<f32>(1.0));
+    }
 
-        r = clamp(final_col.x, 0.0, 1.0);
-        g = clamp(final_col.y, 0.0, 1.0);
-        b = clamp(final_col.z, 0.0, 1.0);
-    }} else {{
-        let sky = sky_color(ray_dir);
-        r = clamp(sky.x, 0.0, 1.0);
-        g = clamp(sky.y, 0.0, 1.0);
-        b = clamp(sky.z, 0.0, 1.0);
-    }}
-
-    let idx = py * u.width + px;
-    output_pixels[idx] = u32(r * 255.0)
-                       | (u32(g * 255.0) << 8u)
-                       | (u32(b * 255.0) << 16u)
-                       | (255u << 24u);
-}}
-",
-    )
+    // Outline: rays that pass just by the highlighted primitive
+    if (u.highlight != HIGHLIGHT_NONE && !(hit && hit_index == u.highlight)) {
+        let glow = 1.0 - smoothstep(0.0, OUTLINE_WIDTH, outline);
+        col = mix(col, HIGHLIGHT_COLOR, glow);
+    }
+    return col;
 }
 
-// ── Scene helpers (duplicated from sdf_renderer to avoid pub exposure) ──
+// ── Compute kernels: one thread per pixel ──
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= u.width || gid.y >= u.height) {
+        return;
+    }
+    let c = shade(gid.x, gid.y);
+    output_pixels[gid.y * u.width + gid.x] = u32(c.x * 255.0)
+                                           | (u32(c.y * 255.0) << 8u)
+                                           | (u32(c.z * 255.0) << 16u)
+                                           | (255u << 24u);
+}
 
-fn primitive_to_node(prim: &SdfPrimitive) -> (SdfNode, [f32; 3]) {
+@compute @workgroup_size(16, 16)
+fn main_texture(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= u.width || gid.y >= u.height) {
+        return;
+    }
+    textureStore(output_tex, vec2<i32>(gid.xy), vec4<f32>(shade(gid.x, gid.y), 1.0));
+}
+";
+
+// ── Scene helpers ──
+
+/// Encode a primitive for the GPU buffer. Sizes follow the CPU renderer:
+/// text labels are thin boxes as wide as their (capped) text, planes and
+/// billboards thin boxes of their 2-D size.
+fn gpu_primitive(prim: &SdfPrimitive) -> GpuPrimitive {
+    let record = |kind: u32, a: [f32; 4], b: [f32; 3], color: &[f32; 4]| GpuPrimitive {
+        kind,
+        unlit: 0,
+        _pad: [0; 2],
+        a,
+        b: [b[0], b[1], b[2], 0.0],
+        color: [color[0], color[1], color[2], 1.0],
+    };
+    let at = |c: &[f32; 3], w: f32| [c[0], c[1], c[2], w];
     match prim {
         SdfPrimitive::RoundedBox {
             center,
@@ -540,27 +981,21 @@ fn primitive_to_node(prim: &SdfPrimitive) -> (SdfNode, [f32; 3]) {
             radius,
             color,
         } => {
-            let node = if *radius > 0.001 {
-                let w = (size[0] - 2.0 * radius).max(0.001);
-                let h = (size[1] - 2.0 * radius).max(0.001);
-                let d = (size[2] - 2.0 * radius).max(0.001);
-                SdfNode::box3d(w, h, d)
-                    .round(*radius)
-                    .translate(center[0], center[1], center[2])
-            } else {
-                SdfNode::box3d(size[0], size[1], size[2]).translate(center[0], center[1], center[2])
-            };
-            (node, [color[0], color[1], color[2]])
+            // The rounding grows the box back to its full size
+            let r = if *radius > 0.001 { *radius } else { 0.0 };
+            let half = size.map(|s| 2.0f32.mul_add(-r, s).max(0.001) * 0.5);
+            record(KIND_BOX, at(center, r), half, color)
         }
         SdfPrimitive::Plane {
             center,
             size,
             color,
-        } => {
-            let node =
-                SdfNode::box3d(size[0], size[1], 0.04).translate(center[0], center[1], center[2]);
-            (node, [color[0], color[1], color[2]])
-        }
+        } => record(
+            KIND_BOX,
+            at(center, 0.0),
+            [size[0] * 0.5, size[1] * 0.5, 0.02],
+            color,
+        ),
         SdfPrimitive::TextLabel {
             position,
             font_size,
@@ -568,67 +1003,118 @@ fn primitive_to_node(prim: &SdfPrimitive) -> (SdfNode, [f32; 3]) {
             text,
         } => {
             let w = text.len().min(40) as f32 * font_size * 0.5;
-            let h = *font_size;
-            let node = SdfNode::box3d(w, h, 0.01).translate(position[0], position[1], position[2]);
-            (node, [color[0], color[1], color[2]])
+            GpuPrimitive {
+                unlit: 1,
+                ..record(
+                    KIND_BOX,
+                    at(position, 0.0),
+                    [w * 0.5, font_size * 0.5, 0.005],
+                    color,
+                )
+            }
         }
         SdfPrimitive::Line {
             start,
             end,
             thickness,
             color,
-        } => {
-            let a = Vec3::new(start[0], start[1], start[2]);
-            let b = Vec3::new(end[0], end[1], end[2]);
-            let node = SdfNode::capsule(a, b, *thickness * 0.5);
-            (node, [color[0], color[1], color[2]])
-        }
+        } => record(
+            KIND_CAPSULE,
+            at(start, thickness * 0.5),
+            [end[0] - start[0], end[1] - start[1], end[2] - start[2]],
+            color,
+        ),
         SdfPrimitive::Sphere {
             center,
             radius,
             color,
-        } => {
-            let node = SdfNode::sphere(*radius).translate(center[0], center[1], center[2]);
-            (node, [color[0], color[1], color[2]])
-        }
+        } => record(KIND_SPHERE, at(center, *radius), [0.0; 3], color),
         SdfPrimitive::Billboard {
             position,
             size,
             color,
             ..
-        } => {
-            let node = SdfNode::box3d(size[0], size[1], 0.005).translate(
-                position[0],
-                position[1],
-                position[2],
-            );
-            (node, [color[0], color[1], color[2]])
-        }
+        } => GpuPrimitive {
+            unlit: 1,
+            ..record(
+                KIND_BOX,
+                at(position, 0.0),
+                [size[0] * 0.5, size[1] * 0.5, 0.0025],
+                color,
+            )
+        },
         SdfPrimitive::Torus {
             center,
             major_radius,
             minor_radius,
             color,
             ..
-        } => {
-            let node = SdfNode::torus(*major_radius, *minor_radius)
-                .translate(center[0], center[1], center[2]);
-            (node, [color[0], color[1], color[2]])
-        }
+        } => record(
+            KIND_TORUS,
+            at(center, *major_radius),
+            [*minor_radius, 0.0, 0.0],
+            color,
+        ),
     }
 }
 
-fn build_balanced_union(nodes: &[SdfNode]) -> SdfNode {
-    match nodes.len() {
-        0 => SdfNode::sphere(0.001),
-        1 => nodes[0].clone(),
-        2 => nodes[0].clone().union(nodes[1].clone()),
-        _ => {
-            let mid = nodes.len() / 2;
-            let left = build_balanced_union(&nodes[..mid]);
-            let right = build_balanced_union(&nodes[mid..]);
-            left.union(right)
-        }
+/// Uniforms for rendering `scene` at `width`×`height` from `cam`.
+fn uniforms(
+    scene: &SdfScene,
+    width: u32,
+    height: u32,
+    cam: &CameraParams,
+    sky: &SkyPalette,
+) -> Uniforms {
+    // Camera vectors
+    let target = Vec3::new(cam.target[0], cam.target[1], cam.target[2]);
+    let eye = target
+        + Vec3::new(
+            cam.distance * cam.azimuth.sin() * cam.elevation.cos(),
+            cam.distance * cam.elevation.sin(),
+            cam.distance * cam.azimuth.cos() * cam.elevation.cos(),
+        );
+    let forward = (target - eye).normalize();
+    let world_up = Vec3::Y;
+    let right = forward.cross(world_up).normalize();
+    let up = right.cross(forward);
+    let fov_factor = (50.0f32.to_radians() * 0.5).tan();
+
+    // Scene bounds for fog / march distance
+    let (mn, mx) = scene_bounds(scene);
+    let extent = mx - mn;
+    let max_extent = extent.x.max(extent.y.max(extent.z)).max(0.5);
+    let max_march_dist = max_extent * 5.0;
+
+    let light_dir = Vec3::new(0.5, 0.8, 0.3).normalize();
+
+    Uniforms {
+        cam_origin: eye.into(),
+        cam_fov_factor: fov_factor,
+        cam_forward: forward.into(),
+        cam_aspect: width as f32 / height as f32,
+        cam_right: right.into(),
+        cam_max_march_dist: max_march_dist,
+        cam_up: up.into(),
+        _pad0: 0.0,
+        light_dir: light_dir.into(),
+        fog_start: max_extent * 1.5,
+        bg_color: [
+            scene.background_color[0],
+            scene.background_color[1],
+            scene.background_color[2],
+        ],
+        fog_end: max_extent * 4.0,
+        width,
+        height,
+        prim_count: scene.primitives.len() as u32,
+        highlight: NO_HIGHLIGHT,
+        sky_zenith: sky.zenith,
+        _pad2: 0.0,
+        rim_color: sky.rim,
+        _pad3: 0.0,
+        shadow_tint: sky.shadow_tint,
+        _pad4: 0.0,
     }
 }
 
@@ -697,162 +1183,3 @@ fn scene_bounds(scene: &SdfScene) -> (Vec3, Vec3) {
     }
     (mn, mx)
 }
-
-// ── Per-primitive WGSL code generation ──
-
-/// Generate a standalone WGSL SDF function for a single primitive.
-fn prim_to_wgsl(prim: &SdfPrimitive, idx: usize) -> String {
-    match prim {
-        SdfPrimitive::RoundedBox {
-            center,
-            size,
-            radius,
-            ..
-        } => {
-            if *radius > 0.001 {
-                let hx = (size[0] - 2.0 * radius).max(0.001) * 0.5;
-                let hy = (size[1] - 2.0 * radius).max(0.001) * 0.5;
-                let hz = (size[2] - 2.0 * radius).max(0.001) * 0.5;
-                format!(
-                    "fn sdf_prim_{idx}(p: vec3<f32>) -> f32 {{\
-                    \n    let lp = p - vec3<f32>({cx:.6}, {cy:.6}, {cz:.6});\
-                    \n    let q = abs(lp) - vec3<f32>({hx:.6}, {hy:.6}, {hz:.6});\
-                    \n    return length(max(q, vec3<f32>(0.0))) + min(max(q.x, max(q.y, q.z)), 0.0) - {r:.6};\
-                    \n}}\n",
-                    cx = center[0],
-                    cy = center[1],
-                    cz = center[2],
-                    hx = hx,
-                    hy = hy,
-                    hz = hz,
-                    r = radius,
-                )
-            } else {
-                let hx = size[0] * 0.5;
-                let hy = size[1] * 0.5;
-                let hz = size[2] * 0.5;
-                format!(
-                    "fn sdf_prim_{idx}(p: vec3<f32>) -> f32 {{\
-                    \n    let lp = p - vec3<f32>({cx:.6}, {cy:.6}, {cz:.6});\
-                    \n    let q = abs(lp) - vec3<f32>({hx:.6}, {hy:.6}, {hz:.6});\
-                    \n    return length(max(q, vec3<f32>(0.0))) + min(max(q.x, max(q.y, q.z)), 0.0);\
-                    \n}}\n",
-                    cx = center[0],
-                    cy = center[1],
-                    cz = center[2],
-                    hx = hx,
-                    hy = hy,
-                    hz = hz,
-                )
-            }
-        }
-        SdfPrimitive::Plane { center, size, .. } => {
-            let hx = size[0] * 0.5;
-            let hy = size[1] * 0.5;
-            format!(
-                "fn sdf_prim_{idx}(p: vec3<f32>) -> f32 {{\
-                \n    let lp = p - vec3<f32>({cx:.6}, {cy:.6}, {cz:.6});\
-                \n    let q = abs(lp) - vec3<f32>({hx:.6}, {hy:.6}, 0.020000);\
-                \n    return length(max(q, vec3<f32>(0.0))) + min(max(q.x, max(q.y, q.z)), 0.0);\
-                \n}}\n",
-                cx = center[0],
-                cy = center[1],
-                cz = center[2],
-                hx = hx,
-                hy = hy,
-            )
-        }
-        SdfPrimitive::TextLabel {
-            position,
-            font_size,
-            text,
-            ..
-        } => {
-            let w = text.len().min(40) as f32 * font_size * 0.5;
-            let hx = w * 0.5;
-            let hy = font_size * 0.5;
-            format!(
-                "fn sdf_prim_{idx}(p: vec3<f32>) -> f32 {{\
-                \n    let lp = p - vec3<f32>({px:.6}, {py:.6}, {pz:.6});\
-                \n    let q = abs(lp) - vec3<f32>({hx:.6}, {hy:.6}, 0.005000);\
-                \n    return length(max(q, vec3<f32>(0.0))) + min(max(q.x, max(q.y, q.z)), 0.0);\
-                \n}}\n",
-                px = position[0],
-                py = position[1],
-                pz = position[2],
-                hx = hx,
-                hy = hy,
-            )
-        }
-        SdfPrimitive::Line {
-            start,
-            end,
-            thickness,
-            ..
-        } => {
-            let r = thickness * 0.5;
-            format!(
-                "fn sdf_prim_{idx}(p: vec3<f32>) -> f32 {{\
-                \n    let pa = p - vec3<f32>({ax:.6}, {ay:.6}, {az:.6});\
-                \n    let ba = vec3<f32>({bx:.6}, {by:.6}, {bz:.6});\
-                \n    let h = clamp(dot(pa, ba) / dot(ba, ba), 0.0, 1.0);\
-                \n    return length(pa - ba * h) - {r:.6};\
-                \n}}\n",
-                ax = start[0],
-                ay = start[1],
-                az = start[2],
-                bx = end[0] - start[0],
-                by = end[1] - start[1],
-                bz = end[2] - start[2],
-                r = r,
-            )
-        }
-        SdfPrimitive::Sphere { center, radius, .. } => {
-            format!(
-                "fn sdf_prim_{idx}(p: vec3<f32>) -> f32 {{\
-                \n    return length(p - vec3<f32>({cx:.6}, {cy:.6}, {cz:.6})) - {r:.6};\
-                \n}}\n",
-                cx = center[0],
-                cy = center[1],
-                cz = center[2],
-                r = radius,
-            )
-        }
-        SdfPrimitive::Billboard { position, size, .. } => {
-            let hx = size[0] * 0.5;
-            let hy = size[1] * 0.5;
-            format!(
-                "fn sdf_prim_{idx}(p: vec3<f32>) -> f32 {{\
-                \n    let lp = p - vec3<f32>({px:.6}, {py:.6}, {pz:.6});\
-                \n    let q = abs(lp) - vec3<f32>({hx:.6}, {hy:.6}, 0.002500);\
-                \n    return length(max(q, vec3<f32>(0.0))) + min(max(q.x, max(q.y, q.z)), 0.0);\
-                \n}}\n",
-                px = position[0],
-                py = position[1],
-                pz = position[2],
-                hx = hx,
-                hy = hy,
-            )
-        }
-        SdfPrimitive::Torus {
-            center,
-            major_radius,
-            minor_radius,
-            ..
-        } => {
-            // SDF torus: length(vec2(length(p.xz) - R, p.y)) - r
-            format!(
-                "fn sdf_prim_{idx}(p: vec3<f32>) -> f32 {{\
-                \n    let lp = p - vec3<f32>({cx:.6}, {cy:.6}, {cz:.6});\
-                \n    let q = vec2<f32>(length(lp.xz) - {R:.6}, lp.y);\
-                \n    return length(q) - {r:.6};\
-                \n}}\n",
-                cx = center[0],
-                cy = center[1],
-                cz = center[2],
-                R = major_radius,
-                r = minor_radius,
-            )
-        }
-    }
-}
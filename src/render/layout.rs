@@ -1,7 +1,11 @@
+use crate::dom::filter;
+use crate::dom::form::{ControlKind, FormControl, InputKind};
 use crate::dom::{Classification, DomNode, NodeType};
+use crate::render::line_break::{self, LINE_HEIGHT};
+use crate::render::media::MediaInfo;
 
 /// Bounding box for a laid-out DOM node
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct LayoutBox {
     pub x: f32,
     pub y: f32,
@@ -15,13 +19,67 @@ pub struct LayoutNode {
     pub tag: String,
     pub text: String,
     pub classification: Classification,
+    /// `tag#id.class` of an element with an id or class, naming it in
+    /// classifier corrections (see [`filter::selector`])
+    pub selector: Option<String>,
+    /// Element `id`, or the `name` of an `<a>`: where `#fragment` links
+    /// lead (see [`Self::find_anchor`])
+    pub id: Option<String>,
     pub bounds: LayoutBox,
     pub children: Vec<Self>,
     pub is_block: bool,
     pub font_size: f32,
     pub href: Option<String>,
+    /// Form element rendered as an interactive widget
+    pub control: Option<FormControl>,
+    /// Grid placement for `<table>` and its cells
+    pub table: Option<TablePart>,
+    /// `<video>` / `<audio>` drawn as a placeholder card
+    pub media: Option<MediaInfo>,
+    /// `<img loading="lazy">`: fetched only once it nears the viewport
+    /// (see [`crate::net::image::plan_loads`])
+    pub lazy: bool,
+    /// Line boxes of `text`, broken to the node's width (see
+    /// [`line_break`])
+    pub lines: Vec<TextLine>,
+}
+
+/// One line of a node's text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    /// Byte range in the node's `text`
+    pub range: std::ops::Range<usize>,
+    pub bounds: LayoutBox,
+}
+
+/// Placement of a cell in its table's grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableCell {
+    pub row: usize,
+    pub col: usize,
+    pub rowspan: usize,
+    pub colspan: usize,
+    /// `<th>`, or any cell inside `<thead>`
+    pub header: bool,
+}
+
+/// Table structure attached to layout nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TablePart {
+    /// The `<table>`: children are its `<caption>` (if any) and then one
+    /// `tr` node per row, with `thead`/`tbody`/`tfoot` flattened away
+    Grid {
+        rows: usize,
+        cols: usize,
+        col_widths: Vec<f32>,
+    },
+    /// A `td` / `th`
+    Cell(TableCell),
 }
 
+/// Body text size in pixels.
+pub const DEFAULT_FONT_SIZE: f32 = 16.0;
+
 const BLOCK_TAGS: &[&str] = &[
     "html",
     "body",
@@ -54,8 +112,125 @@ const BLOCK_TAGS: &[&str] = &[
     "figcaption",
     "details",
     "summary",
+    "video",
+    "audio",
 ];
 
+/// Blocks that can be opened on their own in the 3-D view ("Focus in 3D").
+pub const FOCUS_TAGS: &[&str] = &["article", "section"];
+
+impl LayoutNode {
+    /// Boxes (`[x, y, width, height]`) of every visible `article` and
+    /// `section` in the tree, outermost first.
+    #[must_use]
+    pub fn focus_blocks(&self) -> Vec<[f32; 4]> {
+        let mut out = Vec::new();
+        collect_focus_blocks(self, &mut out);
+        out
+    }
+
+    /// The outermost `article` / `section` laid out at `rect`, as returned
+    /// by [`Self::focus_blocks`].
+    #[must_use]
+    pub fn focus_block(&self, rect: [f32; 4]) -> Option<&Self> {
+        let b = &self.bounds;
+        let at_rect = [b.x, b.y, b.width, b.height]
+            .iter()
+            .zip(&rect)
+            .all(|(a, r)| (a - r).abs() < 0.01);
+        if at_rect && FOCUS_TAGS.contains(&self.tag.as_str()) {
+            return Some(self);
+        }
+        self.children.iter().find_map(|c| c.focus_block(rect))
+    }
+
+    /// The first node whose [`id`](Self::id) is `id`, in document order.
+    /// Regions still laid out as placeholders are not searched.
+    #[must_use]
+    pub fn find_anchor(&self, id: &str) -> Option<&Self> {
+        if self.id.as_deref() == Some(id) {
+            return Some(self);
+        }
+        self.children.iter().find_map(|c| c.find_anchor(id))
+    }
+
+    /// The node laid out for the DOM node at `path` (child indices from
+    /// `root`, the node this tree was laid out from). Where the layout does
+    /// not follow the DOM child for child — tables, form widgets, regions
+    /// still laid out as placeholders — the nearest laid-out ancestor is
+    /// returned. `None` for nodes that are not laid out (removed ones) and
+    /// paths that do not exist.
+    #[must_use]
+    pub fn for_dom_path(&self, root: &DomNode, path: &[usize]) -> Option<&Self> {
+        let Some((&index, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        let child = root.children.get(index)?;
+        if !child.is_visible() {
+            return None;
+        }
+        let visible = root.children.iter().filter(|c| c.is_visible()).count();
+        if self.table.is_some() || self.children.len() != visible {
+            return Some(self);
+        }
+        let at = root.children[..index]
+            .iter()
+            .filter(|c| c.is_visible())
+            .count();
+        self.children.get(at)?.for_dom_path(child, rest)
+    }
+
+    /// The node's text line by line, with each line's box. Text laid out
+    /// without line boxes comes back as a single line in the node's box.
+    pub fn text_lines(&self) -> impl Iterator<Item = (&str, &LayoutBox)> {
+        let whole = (self.lines.is_empty() && !self.text.is_empty())
+            .then_some((self.text.as_str(), &self.bounds));
+        self.lines
+            .iter()
+            .map(|line| (&self.text[line.range.clone()], &line.bounds))
+            .chain(whole)
+    }
+
+    /// A copy of this subtree moved so its box starts at the origin.
+    #[must_use]
+    pub fn detached(&self) -> Self {
+        let mut node = self.clone();
+        shift(&mut node, -self.bounds.x, -self.bounds.y);
+        node
+    }
+}
+
+fn collect_focus_blocks(node: &LayoutNode, out: &mut Vec<[f32; 4]>) {
+    let b = &node.bounds;
+    if FOCUS_TAGS.contains(&node.tag.as_str()) && b.height > 0.0 {
+        out.push([b.x, b.y, b.width, b.height]);
+    }
+    for child in &node.children {
+        collect_focus_blocks(child, out);
+    }
+}
+
+/// Target name of `node` for `#fragment` links: its `id`, or the legacy
+/// `name` of an `<a>`.
+fn anchor_id(node: &DomNode) -> Option<String> {
+    node.attr("id")
+        .or_else(|| (node.tag == "a").then(|| node.attr("name")).flatten())
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+}
+
+/// Whether `node` is laid out as a block: a block-level element, or an
+/// `<iframe>` holding an embedded document (see [`crate::engine::frames`]).
+fn is_block_node(node: &DomNode) -> bool {
+    node.node_type == NodeType::Element
+        && (BLOCK_TAGS.contains(&node.tag.as_str())
+            || (node.tag == "iframe"
+                && node
+                    .children
+                    .iter()
+                    .any(|c| c.node_type == NodeType::Element)))
+}
+
 /// Per-tag vertical margins (top, bottom) in pixels.
 fn tag_margins(tag: &str) -> (f32, f32) {
     match tag {
@@ -64,20 +239,40 @@ fn tag_margins(tag: &str) -> (f32, f32) {
         "h3" | "h4" => (16.0, 10.0),
         "h5" | "h6" => (12.0, 8.0),
         "p" => (4.0, 10.0),
-        "ul" | "ol" | "pre" | "hr" => (8.0, 8.0),
+        "ul" | "ol" | "pre" | "hr" | "table" => (8.0, 8.0),
         "li" => (2.0, 2.0),
         "section" | "article" | "main" => (16.0, 16.0),
-        "nav" | "header" | "footer" | "blockquote" => (12.0, 12.0),
+        "nav" | "header" | "footer" | "blockquote" | "iframe" => (12.0, 12.0),
         _ => (0.0, 0.0),
     }
 }
 
+/// Font size relative to the parent, like `em` in the default stylesheet.
+fn font_scale(tag: &str) -> f32 {
+    match tag {
+        "h1" => 2.0,
+        "h2" => 1.5,
+        "h3" => 1.25,
+        "h4" => 1.125,
+        "small" => 0.75,
+        _ => 1.0,
+    }
+}
+
+/// Font size of an element with tag `tag` at the default body size and no
+/// zoom; the ratio of a laid-out node's `font_size` to this is its zoom.
+#[must_use]
+pub fn default_font_size(tag: &str) -> f32 {
+    DEFAULT_FONT_SIZE * font_scale(tag)
+}
+
 /// Per-tag padding in pixels.
 fn tag_padding(tag: &str, is_block: bool) -> f32 {
     match tag {
         "section" | "article" | "main" | "aside" => 16.0,
         "nav" | "header" | "footer" => 12.0,
         "blockquote" => 20.0,
+        "iframe" if is_block => 12.0,
         _ if is_block => 4.0,
         _ => 0.0,
     }
@@ -86,8 +281,19 @@ fn tag_padding(tag: &str, is_block: bool) -> f32 {
 /// Compute layout for a DOM tree (simple top-to-bottom block model).
 #[must_use]
 pub fn compute_layout(root: &DomNode, viewport_width: f32) -> LayoutNode {
+    compute_layout_with_font(root, viewport_width, DEFAULT_FONT_SIZE)
+}
+
+/// [`compute_layout`] with body text at `base_font_size` pixels; headings
+/// scale with it.
+#[must_use]
+pub fn compute_layout_with_font(
+    root: &DomNode,
+    viewport_width: f32,
+    base_font_size: f32,
+) -> LayoutNode {
     let mut cursor_y = 0.0;
-    layout_node(root, 0.0, &mut cursor_y, viewport_width, 16.0)
+    layout_node(root, 0.0, &mut cursor_y, viewport_width, base_font_size)
 }
 
 fn layout_node(
@@ -96,6 +302,19 @@ fn layout_node(
     cursor_y: &mut f32,
     available_width: f32,
     parent_font_size: f32,
+) -> LayoutNode {
+    layout_node_lazy(node, x, cursor_y, available_width, parent_font_size, None)
+}
+
+/// [`layout_node`], leaving the flow container's far children to `lazy`
+/// when laying out the path to it.
+fn layout_node_lazy(
+    node: &DomNode,
+    x: f32,
+    cursor_y: &mut f32,
+    available_width: f32,
+    parent_font_size: f32,
+    mut lazy: Option<&mut LazyBuild>,
 ) -> LayoutNode {
     // Skip invisible nodes
     if !node.is_visible() {
@@ -103,6 +322,8 @@ fn layout_node(
             tag: node.tag.clone(),
             text: String::new(),
             classification: node.classification,
+            selector: None,
+            id: None,
             bounds: LayoutBox {
                 x,
                 y: *cursor_y,
@@ -113,20 +334,17 @@ fn layout_node(
             is_block: false,
             font_size: parent_font_size,
             href: None,
+            control: None,
+            table: None,
+            media: None,
+            lazy: false,
+            lines: Vec::new(),
         };
     }
 
-    let is_block = node.node_type == NodeType::Element && BLOCK_TAGS.contains(&node.tag.as_str());
+    let is_block = is_block_node(node);
 
-    let font_size = match node.tag.as_str() {
-        "h1" => 32.0,
-        "h2" => 24.0,
-        "h3" => 20.0,
-        "h4" => 18.0,
-        "h5" | "h6" => 16.0,
-        "small" => 12.0,
-        _ => parent_font_size,
-    };
+    let font_size = parent_font_size * font_scale(&node.tag);
 
     let (margin_top, margin_bottom) = tag_margins(&node.tag);
     let padding = tag_padding(&node.tag, is_block);
@@ -141,27 +359,81 @@ fn layout_node(
         *cursor_y += padding;
     }
 
+    // Form controls: widgets own their content (options, button label,
+    // textarea value), so their children are not laid out; nor are the
+    // `<source>` and fallback children of media cards
+    let control = FormControl::from_node(node);
+    let media = MediaInfo::from_node(node);
+    let is_widget = media.is_some()
+        || control
+            .as_ref()
+            .is_some_and(|c| !matches!(c.kind, ControlKind::Form { .. }));
+    if let Some(ref c) = control {
+        *cursor_y += control_height(&c.kind, font_size);
+    }
+    if let Some(ref m) = media {
+        *cursor_y += m.height(font_size);
+    }
+
     // Layout children
     let child_x = x + padding;
     let child_width = padding.mul_add(-2.0, available_width).max(0.0);
     let mut children = Vec::new();
+    let mut table = None;
 
-    for child in &node.children {
+    if node.tag == "table" {
+        let (rows, grid) = layout_table(node, child_x, cursor_y, child_width, font_size);
+        children = rows;
+        table = Some(grid);
+    }
+
+    for (i, child) in node
+        .children
+        .iter()
+        .enumerate()
+        .filter(|_| !is_widget && table.is_none())
+    {
         if !child.is_visible() {
             continue;
         }
-        let laid_out = layout_node(child, child_x, cursor_y, child_width, font_size);
+        let laid_out = match lazy.as_deref_mut() {
+            Some(build) if build.depth == build.dom_path.len() => {
+                build.chunk(child, i, child_x, cursor_y, child_width, font_size)
+            }
+            Some(build) if build.dom_path[build.depth] == i => {
+                build.layout_path.push(children.len());
+                build.depth += 1;
+                layout_node_lazy(
+                    child,
+                    child_x,
+                    cursor_y,
+                    child_width,
+                    font_size,
+                    Some(build),
+                )
+            }
+            _ => layout_node(child, child_x, cursor_y, child_width, font_size),
+        };
         children.push(laid_out);
     }
 
-    // Text content contributes to height
+    // Text content contributes to height, one line box per line
     let text = node.text.clone();
-    if !text.is_empty() {
-        let line_height = font_size * 1.4;
-        let chars_per_line = (available_width / (font_size * 0.6)).max(1.0) as usize;
-        let lines = (text.len() as f32 / chars_per_line as f32).ceil().max(1.0);
-        *cursor_y += lines * line_height;
-    }
+    let line_height = font_size * LINE_HEIGHT;
+    let lines: Vec<TextLine> = line_break::break_lines(&text, available_width, font_size)
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| TextLine {
+            range: line.range,
+            bounds: LayoutBox {
+                x,
+                y: (i as f32).mul_add(line_height, *cursor_y),
+                width: line.width,
+                height: line_height,
+            },
+        })
+        .collect();
+    *cursor_y += lines.len() as f32 * line_height;
 
     if padding > 0.0 {
         *cursor_y += padding;
@@ -184,6 +456,8 @@ fn layout_node(
         tag: node.tag.clone(),
         text,
         classification: node.classification,
+        selector: filter::selector(node),
+        id: anchor_id(node),
         bounds: LayoutBox {
             x,
             y: start_y,
@@ -194,6 +468,568 @@ fn layout_node(
         is_block,
         font_size,
         href,
+        control,
+        table,
+        media,
+        lazy: node.tag == "img" && node.attr("loading") == Some("lazy"),
+        lines,
+    }
+}
+
+// ── Tables ──
+
+/// Smallest column width in pixels.
+const MIN_COL_WIDTH: f32 = 40.0;
+
+/// A cell found while scanning the table, before placement.
+struct RawCell<'a> {
+    node: &'a DomNode,
+    placement: TableCell,
+}
+
+/// Collect rows (`tr`), looking through `thead` / `tbody` / `tfoot`.
+fn table_rows<'a>(node: &'a DomNode, in_head: bool, out: &mut Vec<(&'a DomNode, bool)>) {
+    for child in node.children.iter().filter(|c| c.is_visible()) {
+        match child.tag.as_str() {
+            "tr" => out.push((child, in_head)),
+            "thead" => table_rows(child, true, out),
+            "tbody" | "tfoot" => table_rows(child, in_head, out),
+            _ => {}
+        }
+    }
+}
+
+fn span_attr(node: &DomNode, name: &str) -> usize {
+    node.attr(name)
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(1)
+        .clamp(1, 64)
+}
+
+/// Assign grid positions, honouring `colspan` / `rowspan`.
+/// Returns the cells and the column count.
+fn place_cells<'a>(rows: &[(&'a DomNode, bool)]) -> (Vec<RawCell<'a>>, usize) {
+    // occupied[r][c]: slot covered by a cell from an earlier row
+    let mut occupied: Vec<Vec<bool>> = vec![Vec::new(); rows.len()];
+    let mut cells = Vec::new();
+    let mut cols = 0;
+
+    for (r, (tr, in_head)) in rows.iter().enumerate() {
+        let mut c = 0;
+        for cell in tr
+            .children
+            .iter()
+            .filter(|n| n.is_visible() && (n.tag == "td" || n.tag == "th"))
+        {
+            while occupied[r].get(c).copied().unwrap_or(false) {
+                c += 1;
+            }
+            let colspan = span_attr(cell, "colspan");
+            // Rows spanned past the end of the table are clipped
+            let rowspan = span_attr(cell, "rowspan").min(rows.len() - r);
+            for row in occupied.iter_mut().skip(r).take(rowspan) {
+                if row.len() < c + colspan {
+                    row.resize(c + colspan, false);
+                }
+                row[c..c + colspan].fill(true);
+            }
+            cells.push(RawCell {
+                node: cell,
+                placement: TableCell {
+                    row: r,
+                    col: c,
+                    rowspan,
+                    colspan,
+                    header: *in_head || cell.tag == "th",
+                },
+            });
+            c += colspan;
+            cols = cols.max(c);
+        }
+    }
+    (cells, cols)
+}
+
+/// Column widths proportional to the text in single-column cells.
+fn column_widths(cells: &[RawCell<'_>], cols: usize, width: f32) -> Vec<f32> {
+    let mut weights = vec![3.0_f32; cols];
+    for cell in cells.iter().filter(|c| c.placement.colspan == 1) {
+        let len = cell.node.collect_text().chars().count() as f32;
+        let w = &mut weights[cell.placement.col];
+        *w = w.max(len.clamp(3.0, 60.0));
+    }
+    let total: f32 = weights.iter().sum();
+    let mut widths: Vec<f32> = weights
+        .iter()
+        .map(|w| (w / total * width).max(MIN_COL_WIDTH))
+        .collect();
+    // Minimum widths may overflow a narrow table: scale back down
+    let sum: f32 = widths.iter().sum();
+    if sum > width && sum > 0.0 {
+        let k = width / sum;
+        for w in &mut widths {
+            *w *= k;
+        }
+    }
+    widths
+}
+
+/// Lay out a `<table>`'s caption and rows; returns the children and grid.
+fn layout_table(
+    node: &DomNode,
+    x: f32,
+    cursor_y: &mut f32,
+    width: f32,
+    font_size: f32,
+) -> (Vec<LayoutNode>, TablePart) {
+    let mut children = Vec::new();
+    if let Some(caption) = node.children.iter().find(|c| c.tag == "caption") {
+        children.push(layout_node(caption, x, cursor_y, width, font_size));
+    }
+
+    let mut rows = Vec::new();
+    table_rows(node, false, &mut rows);
+    let (cells, cols) = place_cells(&rows);
+    let col_widths = column_widths(&cells, cols, width);
+    let col_x: Vec<f32> = col_widths
+        .iter()
+        .scan(x, |acc, w| {
+            let left = *acc;
+            *acc += w;
+            Some(left)
+        })
+        .collect();
+
+    let min_row = font_size * 1.4;
+    let mut row_tops = Vec::with_capacity(rows.len());
+    let mut row_heights = vec![0.0_f32; rows.len()];
+    let mut laid_out: Vec<(TableCell, LayoutNode)> = Vec::with_capacity(cells.len());
+
+    // Cells in each row are laid out once the row's top is known; a
+    // rowspan cell settles its height against the last row it covers.
+    let mut next = 0;
+    let mut top = *cursor_y;
+    for r in 0..rows.len() {
+        row_tops.push(top);
+        let mut height = min_row;
+        while next < cells.len() && cells[next].placement.row == r {
+            let RawCell {
+                node: cell,
+                placement,
+            } = &cells[next];
+            let w: f32 = col_widths[placement.col..placement.col + placement.colspan]
+                .iter()
+                .sum();
+            let mut cell_y = top;
+            let laid = layout_node(cell, col_x[placement.col], &mut cell_y, w, font_size);
+            if placement.rowspan == 1 {
+                height = height.max(laid.bounds.height);
+            }
+            laid_out.push((*placement, laid));
+            next += 1;
+        }
+        row_heights[r] = height;
+        for (p, laid) in &laid_out {
+            let last = p.row + p.rowspan - 1;
+            if p.rowspan > 1 && last == r {
+                let above: f32 = row_heights[p.row..r].iter().sum();
+                row_heights[r] = row_heights[r].max(laid.bounds.height - above);
+            }
+        }
+        top += row_heights[r];
+    }
+
+    let mut row_nodes: Vec<LayoutNode> = rows
+        .iter()
+        .enumerate()
+        .map(|(r, (tr, _))| LayoutNode {
+            tag: "tr".into(),
+            text: String::new(),
+            classification: tr.classification,
+            selector: None,
+            id: anchor_id(tr),
+            bounds: LayoutBox {
+                x,
+                y: row_tops[r],
+                width,
+                height: row_heights[r],
+            },
+            children: Vec::new(),
+            is_block: true,
+            font_size,
+            href: None,
+            control: None,
+            table: None,
+            media: None,
+            lazy: false,
+            lines: Vec::new(),
+        })
+        .collect();
+    for (placement, mut laid) in laid_out {
+        laid.bounds.height = row_heights[placement.row..placement.row + placement.rowspan]
+            .iter()
+            .sum();
+        laid.table = Some(TablePart::Cell(placement));
+        row_nodes[placement.row].children.push(laid);
+    }
+
+    *cursor_y = top;
+    children.extend(row_nodes);
+    (
+        children,
+        TablePart::Grid {
+            rows: rows.len(),
+            cols,
+            col_widths,
+        },
+    )
+}
+
+// ── Lazy layout ──
+
+/// Documents with more nodes than this are laid out lazily by
+/// [`LazyLayout::compute`].
+pub const LAZY_NODE_THRESHOLD: usize = 5_000;
+
+/// Distance above and below the viewport kept laid out, in pixels.
+pub const LAZY_MARGIN: f32 = 1_500.0;
+
+/// Tag of a node standing in for a region not laid out yet. Its bounds
+/// hold the estimated size, margins included; it has no children.
+pub const PLACEHOLDER_TAG: &str = "#pending";
+
+/// One child of the flow container.
+#[derive(Debug, Clone, Copy)]
+struct Chunk {
+    /// Index among the container's DOM children
+    dom_index: usize,
+    /// Cursor position before the chunk's top margin
+    start: f32,
+    /// Cursor advance, margins included
+    extent: f32,
+    laid: bool,
+}
+
+/// A layout tree whose main flow is laid out on demand.
+///
+/// Long pages keep nearly all of their content under one "flow container"
+/// (`body`, or the wrapper `div` a site puts inside it). Its children are
+/// laid out once they come within [`LAZY_MARGIN`] of the viewport; until
+/// then each is a [`PLACEHOLDER_TAG`] node sized by an estimate that does
+/// not allocate, so a first render only pays for what is on screen. Laying
+/// out a placeholder shifts everything below it by the estimate's error.
+#[derive(Debug, Clone)]
+pub struct LazyLayout {
+    /// Child indices from the DOM root to the flow container
+    dom_path: Vec<usize>,
+    /// The same path through the layout tree (invisible nodes are skipped)
+    layout_path: Vec<usize>,
+    chunks: Vec<Chunk>,
+    child_x: f32,
+    child_width: f32,
+    font_size: f32,
+}
+
+/// State threaded through the initial lazy layout.
+struct LazyBuild {
+    dom_path: Vec<usize>,
+    /// How much of `dom_path` the current node is along
+    depth: usize,
+    layout_path: Vec<usize>,
+    chunks: Vec<Chunk>,
+    /// Chunks starting above this are laid out right away
+    until: f32,
+    child_x: f32,
+    child_width: f32,
+    font_size: f32,
+}
+
+impl LazyBuild {
+    /// Lay out or estimate one child of the flow container.
+    fn chunk(
+        &mut self,
+        node: &DomNode,
+        dom_index: usize,
+        x: f32,
+        cursor_y: &mut f32,
+        width: f32,
+        font_size: f32,
+    ) -> LayoutNode {
+        self.child_x = x;
+        self.child_width = width;
+        self.font_size = font_size;
+        let start = *cursor_y;
+        let laid = start < self.until;
+        let laid_out = if laid {
+            layout_node(node, x, cursor_y, width, font_size)
+        } else {
+            *cursor_y += estimate_extent(node, width, font_size);
+            placeholder(node, x, start, width, *cursor_y - start, font_size)
+        };
+        self.chunks.push(Chunk {
+            dom_index,
+            start,
+            extent: *cursor_y - start,
+            laid,
+        });
+        laid_out
+    }
+}
+
+impl LazyLayout {
+    /// Lay out `root`, lazily if it is large enough to be worth it.
+    ///
+    /// Chunks starting within `visible_bottom` + [`LAZY_MARGIN`] are laid
+    /// out right away. Returns the layout tree and, if anything was left
+    /// pending, the state needed to extend it.
+    #[must_use]
+    pub fn compute(
+        root: &DomNode,
+        viewport_width: f32,
+        base_font_size: f32,
+        visible_bottom: f32,
+    ) -> (LayoutNode, Option<Self>) {
+        let dom_path = match flow_path(root) {
+            Some(path) if root.node_count() > LAZY_NODE_THRESHOLD => path,
+            _ => {
+                let layout = compute_layout_with_font(root, viewport_width, base_font_size);
+                return (layout, None);
+            }
+        };
+        let mut build = LazyBuild {
+            dom_path,
+            depth: 0,
+            layout_path: Vec::new(),
+            chunks: Vec::new(),
+            until: visible_bottom + LAZY_MARGIN,
+            child_x: 0.0,
+            child_width: viewport_width,
+            font_size: base_font_size,
+        };
+        let mut cursor_y = 0.0;
+        let layout = layout_node_lazy(
+            root,
+            0.0,
+            &mut cursor_y,
+            viewport_width,
+            base_font_size,
+            Some(&mut build),
+        );
+        let lazy = Self {
+            dom_path: build.dom_path,
+            layout_path: build.layout_path,
+            chunks: build.chunks,
+            child_x: build.child_x,
+            child_width: build.child_width,
+            font_size: build.font_size,
+        };
+        let pending = !lazy.is_complete();
+        (layout, pending.then_some(lazy))
+    }
+
+    /// Lay out the placeholders of `layout` (built from `root`) that come
+    /// within [`LAZY_MARGIN`] of `top..bottom`. Returns whether anything
+    /// changed.
+    pub fn extend(
+        &mut self,
+        layout: &mut LayoutNode,
+        root: &DomNode,
+        top: f32,
+        bottom: f32,
+    ) -> bool {
+        let (top, bottom) = (top - LAZY_MARGIN, bottom + LAZY_MARGIN);
+        let Some(flow) = self
+            .dom_path
+            .iter()
+            .try_fold(root, |n, &i| n.children.get(i))
+        else {
+            return false;
+        };
+        let Some(container) = self
+            .layout_path
+            .iter()
+            .try_fold(&mut *layout, |n, &i| n.children.get_mut(i))
+        else {
+            return false;
+        };
+
+        let mut shift = 0.0;
+        let mut changed = false;
+        for (chunk, node) in self.chunks.iter_mut().zip(container.children.iter_mut()) {
+            if shift != 0.0 {
+                chunk.start += shift;
+                shift_y(node, shift);
+            }
+            if chunk.laid || chunk.start > bottom || chunk.start + chunk.extent < top {
+                continue;
+            }
+            let Some(dom) = flow.children.get(chunk.dom_index) else {
+                continue;
+            };
+            let mut y = chunk.start;
+            *node = layout_node(dom, self.child_x, &mut y, self.child_width, self.font_size);
+            let extent = y - chunk.start;
+            shift += extent - chunk.extent;
+            chunk.extent = extent;
+            chunk.laid = true;
+            changed = true;
+        }
+        if shift != 0.0 {
+            grow_along(layout, &self.layout_path, shift);
+        }
+        changed
+    }
+
+    /// Lay out every remaining placeholder.
+    pub fn finish(&mut self, layout: &mut LayoutNode, root: &DomNode) -> bool {
+        self.extend(layout, root, f32::NEG_INFINITY, f32::INFINITY)
+    }
+
+    /// Number of flow children still laid out as placeholders.
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.chunks.iter().filter(|c| !c.laid).count()
+    }
+
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.chunks.iter().all(|c| c.laid)
+    }
+}
+
+/// Path to the flow container: descend while one child holds nearly the
+/// whole subtree. `None` if the container would be a table or a widget,
+/// whose children are not laid out one after another.
+fn flow_path(root: &DomNode) -> Option<Vec<usize>> {
+    let mut path = Vec::new();
+    let mut node = root;
+    let mut total = root.node_count();
+    loop {
+        let dominant = node
+            .children
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.is_visible() && c.node_type == NodeType::Element)
+            .map(|(i, c)| (i, c, c.node_count()))
+            .max_by_key(|&(_, _, count)| count)
+            .filter(|&(_, _, count)| count * 10 >= total * 9);
+        match dominant {
+            Some((i, child, count)) => {
+                path.push(i);
+                node = child;
+                total = count;
+            }
+            None => break,
+        }
+    }
+    let flows = node.tag != "table"
+        && FormControl::from_node(node).is_none_or(|c| matches!(c.kind, ControlKind::Form { .. }));
+    flows.then_some(path)
+}
+
+/// Cursor advance of `node` as [`layout_node`] would compute it, without
+/// building the tree. Tables and form widgets are approximated as blocks.
+fn estimate_extent(node: &DomNode, width: f32, parent_font_size: f32) -> f32 {
+    if !node.is_visible() {
+        return 0.0;
+    }
+    let is_block = is_block_node(node);
+    let font_size = parent_font_size * font_scale(&node.tag);
+    let padding = tag_padding(&node.tag, is_block);
+    let child_width = padding.mul_add(-2.0, width).max(0.0);
+    let children: f32 = match MediaInfo::from_node(node) {
+        Some(media) => media.height(font_size),
+        None => node
+            .children
+            .iter()
+            .map(|c| estimate_extent(c, child_width, font_size))
+            .sum(),
+    };
+    let mut extent =
+        padding.mul_add(2.0, children) + line_break::text_height(&node.text, width, font_size);
+    if is_block {
+        let (top, bottom) = tag_margins(&node.tag);
+        extent += top + bottom;
+    }
+    extent
+}
+
+fn placeholder(
+    node: &DomNode,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    font_size: f32,
+) -> LayoutNode {
+    LayoutNode {
+        tag: PLACEHOLDER_TAG.to_string(),
+        text: String::new(),
+        classification: node.classification,
+        selector: None,
+        id: None,
+        bounds: LayoutBox {
+            x,
+            y,
+            width,
+            height,
+        },
+        children: Vec::new(),
+        is_block: true,
+        font_size,
+        href: None,
+        control: None,
+        table: None,
+        media: None,
+        lazy: false,
+        lines: Vec::new(),
+    }
+}
+
+/// Move `node` and its subtree down by `dy`.
+fn shift_y(node: &mut LayoutNode, dy: f32) {
+    node.bounds.y += dy;
+    for line in &mut node.lines {
+        line.bounds.y += dy;
+    }
+    for child in &mut node.children {
+        shift_y(child, dy);
+    }
+}
+
+/// Move `node` and its subtree by (`dx`, `dy`).
+fn shift(node: &mut LayoutNode, dx: f32, dy: f32) {
+    node.bounds.x += dx;
+    node.bounds.y += dy;
+    for line in &mut node.lines {
+        line.bounds.x += dx;
+        line.bounds.y += dy;
+    }
+    for child in &mut node.children {
+        shift(child, dx, dy);
+    }
+}
+
+/// Grow the nodes along `path` by `dy` and move whatever follows them.
+fn grow_along(node: &mut LayoutNode, path: &[usize], dy: f32) {
+    node.bounds.height += dy;
+    if let Some((&i, rest)) = path.split_first() {
+        for sibling in node.children.iter_mut().skip(i + 1) {
+            shift_y(sibling, dy);
+        }
+        if let Some(child) = node.children.get_mut(i) {
+            grow_along(child, rest, dy);
+        }
+    }
+}
+
+/// Height reserved for a form widget.
+fn control_height(kind: &ControlKind, font_size: f32) -> f32 {
+    let line = font_size * 1.4;
+    match kind {
+        ControlKind::Form { .. } | ControlKind::Input(InputKind::Hidden) => 0.0,
+        ControlKind::TextArea { rows } => *rows as f32 * line + 8.0,
+        _ => line + 8.0,
     }
 }
 
@@ -267,4 +1103,255 @@ mod tests {
         let link_layout = &layout.children[0];
         assert_eq!(link_layout.href.as_deref(), Some("https://example.com"));
     }
+
+    #[test]
+    fn test_layout_form_controls() {
+        let html = r#"<body><form action="/s"><select name="x"><option>A</option></select>
+            <input type="hidden" name="t" value="1"><input name="q"></form></body>"#;
+        let dom = crate::dom::parser::parse_html(html, "https://example.com/");
+        let layout = compute_layout(&dom.root, 800.0);
+
+        fn find<'a>(n: &'a LayoutNode, tag: &str) -> Option<&'a LayoutNode> {
+            if n.tag == tag {
+                return Some(n);
+            }
+            n.children.iter().find_map(|c| find(c, tag))
+        }
+        let select = find(&layout, "select").unwrap();
+        assert!(select.control.is_some());
+        // Options belong to the widget, not the layout tree
+        assert!(select.children.is_empty());
+        assert!(select.bounds.height > 0.0);
+        assert!(find(&layout, "form").unwrap().control.is_some());
+        let hidden = find(&layout, "input").unwrap();
+        assert!(hidden.bounds.height.abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_table_spans() {
+        let html = r#"<table><caption>Scores</caption>
+            <thead><tr><th colspan="2">Name</th><th>Score</th></tr></thead>
+            <tbody><tr><td rowspan="2">A</td><td>x</td><td>1</td></tr>
+            <tr><td>y</td><td>2</td></tr></tbody></table>"#;
+        let dom = crate::dom::parser::parse_html(html, "https://example.com/");
+
+        fn find<'a>(n: &'a LayoutNode, tag: &str) -> Option<&'a LayoutNode> {
+            if n.tag == tag {
+                return Some(n);
+            }
+            n.children.iter().find_map(|c| find(c, tag))
+        }
+        let layout = compute_layout(&dom.root, 600.0);
+        let table = find(&layout, "table").unwrap();
+        let Some(TablePart::Grid {
+            rows,
+            cols,
+            ref col_widths,
+        }) = table.table
+        else {
+            panic!("table has no grid");
+        };
+        assert_eq!((rows, cols), (3, 3));
+        assert!(col_widths.iter().sum::<f32>() <= 600.0 + 0.01);
+        assert_eq!(table.children[0].tag, "caption");
+
+        let cell = |row: usize, i: usize| match table.children[row + 1].children[i].table {
+            Some(TablePart::Cell(c)) => c,
+            _ => panic!("not a cell"),
+        };
+        let name = cell(0, 0);
+        assert!(name.header && name.colspan == 2);
+        let a = cell(1, 0);
+        assert_eq!((a.rowspan, a.col), (2, 0));
+        // The row under the rowspan starts one column in
+        assert_eq!(cell(2, 0).col, 1);
+        // Cells in a row share the row's top
+        let r1 = &table.children[2];
+        assert!(r1
+            .children
+            .iter()
+            .all(|c| (c.bounds.y - r1.bounds.y).abs() < 0.01));
+        // The rowspan cell covers both rows
+        let spanned = r1.children[0].bounds.height;
+        assert!((spanned - (r1.bounds.height + table.children[3].bounds.height)).abs() < 0.01);
+    }
+
+    #[test]
+    fn headings_scale_with_base_font() {
+        let dom =
+            crate::dom::parser::parse_html("<h1>Title</h1><p>Body</p>", "https://example.com/");
+        fn find<'a>(n: &'a LayoutNode, tag: &str) -> Option<&'a LayoutNode> {
+            if n.tag == tag {
+                return Some(n);
+            }
+            n.children.iter().find_map(|c| find(c, tag))
+        }
+        let normal = compute_layout(&dom.root, 600.0);
+        let large = compute_layout_with_font(&dom.root, 600.0, 20.0);
+        assert!((find(&normal, "h1").unwrap().font_size - 32.0).abs() < f32::EPSILON);
+        assert!((find(&large, "h1").unwrap().font_size - 40.0).abs() < f32::EPSILON);
+        assert!((find(&large, "p").unwrap().font_size - 20.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn text_is_broken_into_line_boxes() {
+        let url = "https://example.com/a/rather/long/path/to/some/resource?with=query&and=more";
+        let jp = "吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。";
+        let p = |t: &str| DomNode::element("p", HashMap::new(), vec![DomNode::text(t)]);
+        let body = DomNode::element("body", HashMap::new(), vec![p(url), p(jp)]);
+        let layout = compute_layout(&body, 200.0);
+
+        for (para, text) in layout.children.iter().zip([url, jp]) {
+            let node = &para.children[0];
+            assert!(node.lines.len() > 1, "{text} is wrapped");
+            let joined: String = node.text_lines().map(|(line, _)| line).collect();
+            assert_eq!(joined, text);
+            let b = node.bounds;
+            for (i, (_, lb)) in node.text_lines().enumerate() {
+                assert!(lb.width <= b.width);
+                assert!((lb.y - (b.y + i as f32 * lb.height)).abs() < 0.01);
+            }
+            assert!((node.lines.len() as f32 * 16.0 * LINE_HEIGHT - b.height).abs() < 0.01);
+        }
+        // The URL breaks after its separators
+        let lines: Vec<&str> = layout.children[0].children[0]
+            .text_lines()
+            .map(|(line, _)| line)
+            .collect();
+        assert!(lines[..lines.len() - 1]
+            .iter()
+            .all(|line| line.ends_with(['/', '.', '?', '&', '='])));
+        // No line of the Japanese text starts with "。"
+        let node = &layout.children[1].children[0];
+        assert!(node.text_lines().all(|(line, _)| !line.starts_with('。')));
+    }
+
+    /// `body > div` holding `n` sections of a heading, a paragraph and a
+    /// small table (whose estimate is off, so laying it out shifts content).
+    fn long_page(n: usize) -> DomNode {
+        let sections = (0..n)
+            .map(|i| {
+                let el = |tag: &str, children| DomNode::element(tag, HashMap::new(), children);
+                let text = |t: String| DomNode::text(t);
+                let cell = el("td", vec![text(format!("cell {i}"))]);
+                el(
+                    "section",
+                    vec![
+                        el("h2", vec![text(format!("Section {i}"))]),
+                        el(
+                            "p",
+                            vec![text("Lorem ipsum dolor sit amet. ".repeat(i % 7 + 1))],
+                        ),
+                        el("table", vec![el("tr", vec![cell.clone(), cell])]),
+                    ],
+                )
+            })
+            .collect();
+        let wrapper = DomNode::element("div", HashMap::new(), sections);
+        DomNode::element("body", HashMap::new(), vec![wrapper])
+    }
+
+    fn assert_same_layout(a: &LayoutNode, b: &LayoutNode) {
+        assert_eq!(a.tag, b.tag);
+        assert!((a.bounds.y - b.bounds.y).abs() < 0.01, "{} y", a.tag);
+        assert!(
+            (a.bounds.height - b.bounds.height).abs() < 0.01,
+            "{} h",
+            a.tag
+        );
+        assert_eq!(a.lines.len(), b.lines.len());
+        for (x, y) in a.lines.iter().zip(&b.lines) {
+            assert!((x.bounds.y - y.bounds.y).abs() < 0.01, "line y");
+        }
+        assert_eq!(a.children.len(), b.children.len());
+        for (x, y) in a.children.iter().zip(&b.children) {
+            assert_same_layout(x, y);
+        }
+    }
+
+    #[test]
+    fn focus_blocks_are_found_and_detached() {
+        let html = "<h1>Site</h1><article><h2>Post</h2><section><p>Part</p></section></article>";
+        let dom = crate::dom::parser::parse_html(html, "https://example.com/");
+        let layout = compute_layout(&dom.root, 600.0);
+
+        let blocks = layout.focus_blocks();
+        assert_eq!(blocks.len(), 2);
+        let article = layout.focus_block(blocks[0]).unwrap();
+        assert_eq!(article.tag, "article");
+        assert_eq!(layout.focus_block(blocks[1]).unwrap().tag, "section");
+        assert!(layout.focus_block([1.0, 2.0, 3.0, 4.0]).is_none());
+
+        // Detaching keeps the shape and moves the block to the origin
+        let detached = article.detached();
+        assert!(detached.bounds.x.abs() < 0.01 && detached.bounds.y.abs() < 0.01);
+        let section = &detached.children[1];
+        assert_eq!(section.tag, "section");
+        let original = &article.children[1];
+        assert!((section.bounds.y - (original.bounds.y - article.bounds.y)).abs() < 0.01);
+        assert!((section.bounds.height - original.bounds.height).abs() < 0.01);
+    }
+
+    #[test]
+    fn dom_paths_lead_to_their_layout_nodes() {
+        let el = |tag: &str, children| DomNode::element(tag, HashMap::new(), children);
+        let mut ad = el("div", vec![DomNode::text("Buy now")]);
+        ad.classification = Classification::Advertisement;
+        let cell = el("td", vec![DomNode::text("Cell")]);
+        let body = el(
+            "body",
+            vec![
+                ad,
+                el("h1", vec![DomNode::text("Title")]),
+                el("p", vec![DomNode::text("One "), el("a", vec![])]),
+                el("table", vec![el("tr", vec![cell])]),
+            ],
+        );
+        let layout = compute_layout(&body, 600.0);
+
+        assert!(std::ptr::eq(
+            layout.for_dom_path(&body, &[]).unwrap(),
+            &layout
+        ));
+        // The removed sibling is skipped when counting
+        assert_eq!(layout.for_dom_path(&body, &[2]).unwrap().tag, "p");
+        assert_eq!(layout.for_dom_path(&body, &[2, 1]).unwrap().tag, "a");
+        // Removed and missing nodes have no box
+        assert!(layout.for_dom_path(&body, &[0]).is_none());
+        assert!(layout.for_dom_path(&body, &[9]).is_none());
+        // Table cells resolve to their table
+        assert_eq!(layout.for_dom_path(&body, &[3, 0, 0]).unwrap().tag, "table");
+    }
+
+    #[test]
+    fn small_pages_are_laid_out_at_once() {
+        let (layout, lazy) = LazyLayout::compute(&long_page(10), 800.0, 16.0, 600.0);
+        assert!(lazy.is_none());
+        assert_same_layout(&layout, &compute_layout(&long_page(10), 800.0));
+    }
+
+    #[test]
+    fn lazy_layout_extends_to_full_layout() {
+        let page = long_page(1_000);
+        let full = compute_layout(&page, 800.0);
+        let (mut layout, lazy) = LazyLayout::compute(&page, 800.0, 16.0, 600.0);
+        let mut lazy = lazy.expect("large page is laid out lazily");
+
+        let pending = lazy.pending();
+        assert!(pending > 900, "only the first screens are laid out");
+        let flow = &layout.children[0];
+        assert_eq!(flow.children.last().unwrap().tag, PLACEHOLDER_TAG);
+        // Placeholders keep the page roughly its real height
+        let error = (layout.bounds.height - full.bounds.height).abs() / full.bounds.height;
+        assert!(error < 0.2, "estimate off by {error}");
+
+        // Scrolling further down lays out more, nothing above is lost
+        assert!(lazy.extend(&mut layout, &page, 20_000.0, 20_600.0));
+        assert!(lazy.pending() < pending);
+        assert!(!lazy.extend(&mut layout, &page, 20_000.0, 20_600.0));
+
+        assert!(lazy.finish(&mut layout, &page));
+        assert!(lazy.is_complete());
+        assert_same_layout(&layout, &full);
+    }
 }
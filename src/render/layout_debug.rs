@@ -0,0 +1,193 @@
+//! Layout boxes for the layout debug overlay.
+//!
+//! Flattens a laid-out page into the boxes the layout engine computed, one
+//! per node that takes up space, each with the category of its tag so the
+//! overlay can colour headings, links, media, tables, form widgets and
+//! plain blocks apart. The SDF 2-D view draws them at their layout
+//! positions; hovering one shows its computed position and size.
+
+use crate::render::layout::{LayoutNode, PLACEHOLDER_TAG};
+
+/// What kind of element a box belongs to, for its colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoxCategory {
+    /// Block-level container (`div`, `p`, `section`, …)
+    Block,
+    /// Inline element or text run
+    Inline,
+    Heading,
+    Link,
+    /// Image, video or audio
+    Media,
+    /// Table or one of its rows and cells
+    Table,
+    /// Form or form widget
+    Form,
+    /// Region not laid out yet, sized by an estimate
+    Pending,
+}
+
+impl BoxCategory {
+    pub const ALL: [Self; 8] = [
+        Self::Block,
+        Self::Inline,
+        Self::Heading,
+        Self::Link,
+        Self::Media,
+        Self::Table,
+        Self::Form,
+        Self::Pending,
+    ];
+
+    /// Category of `node`.
+    #[must_use]
+    pub fn of(node: &LayoutNode) -> Self {
+        match node.tag.as_str() {
+            PLACEHOLDER_TAG => Self::Pending,
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => Self::Heading,
+            "a" if node.href.is_some() => Self::Link,
+            "img" | "picture" | "svg" => Self::Media,
+            _ if node.media.is_some() => Self::Media,
+            _ if node.table.is_some() => Self::Table,
+            _ if node.control.is_some() => Self::Form,
+            _ if node.is_block => Self::Block,
+            _ => Self::Inline,
+        }
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Block => "block",
+            Self::Inline => "inline",
+            Self::Heading => "heading",
+            Self::Link => "link",
+            Self::Media => "media",
+            Self::Table => "table",
+            Self::Form => "form",
+            Self::Pending => "not laid out yet",
+        }
+    }
+
+    /// Outline colour (RGB).
+    #[must_use]
+    pub const fn rgb(self) -> [u8; 3] {
+        match self {
+            Self::Block => [70, 130, 220],
+            Self::Inline => [120, 120, 130],
+            Self::Heading => [220, 80, 60],
+            Self::Link => [40, 170, 90],
+            Self::Media => [200, 120, 20],
+            Self::Table => [150, 70, 200],
+            Self::Form => [20, 160, 170],
+            Self::Pending => [180, 180, 60],
+        }
+    }
+}
+
+/// One computed box.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugBox {
+    /// Layout-space rectangle `[x, y, w, h]`
+    pub rect: [f32; 4],
+    pub tag: String,
+    pub category: BoxCategory,
+    /// Depth in the layout tree (the root is 0)
+    pub depth: usize,
+    pub font_size: f32,
+    /// Line boxes of the node's own text
+    pub lines: usize,
+}
+
+impl DebugBox {
+    /// Box of `node`, `depth` levels below the root.
+    #[must_use]
+    pub fn of(node: &LayoutNode, depth: usize) -> Self {
+        let b = &node.bounds;
+        Self {
+            rect: [b.x, b.y, b.width, b.height],
+            tag: if node.tag.is_empty() {
+                "#text".into()
+            } else {
+                node.tag.clone()
+            },
+            category: BoxCategory::of(node),
+            depth,
+            font_size: node.font_size,
+            lines: node.lines.len(),
+        }
+    }
+
+    /// Whether `(x, y)` (layout space) lies in the box.
+    #[must_use]
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        let [bx, by, w, h] = self.rect;
+        x >= bx && x <= bx + w && y >= by && y <= by + h
+    }
+}
+
+/// Boxes of every node of `root` that takes up space, in document order
+/// (parents before their children).
+#[must_use]
+pub fn layout_boxes(root: &LayoutNode) -> Vec<DebugBox> {
+    let mut out = Vec::new();
+    collect(root, 0, &mut out);
+    out
+}
+
+/// The innermost of `boxes` containing `(x, y)`: the deepest, and of
+/// those the last drawn.
+#[must_use]
+pub fn box_at(boxes: &[DebugBox], x: f32, y: f32) -> Option<&DebugBox> {
+    boxes
+        .iter()
+        .filter(|b| b.contains(x, y))
+        .max_by_key(|b| b.depth)
+}
+
+fn collect(node: &LayoutNode, depth: usize, out: &mut Vec<DebugBox>) {
+    if node.bounds.width > 0.0 && node.bounds.height > 0.0 {
+        out.push(DebugBox::of(node, depth));
+    }
+    for child in &node.children {
+        collect(child, depth + 1, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::layout::compute_layout;
+
+    #[test]
+    fn collects_categorised_boxes() {
+        let html = "<h1>Title</h1><p>Text with <a href=\"/x\">a link</a></p>\
+            <table><tr><td>Cell</td></tr></table><img src=\"a.png\" alt=\"A\">";
+        let dom = crate::dom::parser::parse_html(html, "https://example.com/");
+        let layout = compute_layout(&dom.root, 600.0);
+        let boxes = layout_boxes(&layout);
+
+        let category = |tag: &str| boxes.iter().find(|b| b.tag == tag).map(|b| b.category);
+        assert_eq!(category("h1"), Some(BoxCategory::Heading));
+        assert_eq!(category("p"), Some(BoxCategory::Block));
+        assert_eq!(category("table"), Some(BoxCategory::Table));
+        assert!(boxes.iter().all(|b| b.rect[2] > 0.0 && b.rect[3] > 0.0));
+        // Parents come before their children
+        let body = boxes.iter().position(|b| b.tag == "body").unwrap();
+        let h1 = boxes.iter().position(|b| b.tag == "h1").unwrap();
+        assert!(body < h1);
+    }
+
+    #[test]
+    fn innermost_box_wins() {
+        let html = "<div><p>Inner paragraph</p></div>";
+        let dom = crate::dom::parser::parse_html(html, "https://example.com/");
+        let layout = compute_layout(&dom.root, 600.0);
+        let boxes = layout_boxes(&layout);
+        let p = boxes.iter().find(|b| b.tag == "p").unwrap();
+        let (x, y) = (p.rect[0] + 1.0, p.rect[1] + 1.0);
+        let hit = box_at(&boxes, x, y).unwrap();
+        assert!(hit.depth >= p.depth);
+        assert!(box_at(&boxes, -10.0, -10.0).is_none());
+    }
+}
@@ -0,0 +1,359 @@
+//! Line breaking for layout.
+//!
+//! A reduced form of the Unicode line breaking algorithm (UAX #14) with the
+//! Japanese line-start and line-end rules (kinsoku shori). Lines break after
+//! spaces, after hyphens between letters and between CJK characters, but
+//! never before closing brackets, ideographic commas and full stops, small
+//! kana or the prolonged sound mark, nor after opening brackets. A word too
+//! long for the line, typically a URL, is broken after one of its
+//! separators (`/ ? & = . _ -` and the like), and failing that between any
+//! two characters, so that no line is wider than the box it is laid out in.
+//!
+//! Widths are estimates in the layout's metric: 0.6 em for most characters,
+//! a full em for wide (CJK) ones and nothing for combining marks.
+
+use std::ops::Range;
+
+/// Line height as a multiple of the font size.
+pub const LINE_HEIGHT: f32 = 1.4;
+
+/// One line of broken text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    /// Byte range in the text, without the spaces the line breaks at
+    pub range: Range<usize>,
+    /// Estimated width in pixels
+    pub width: f32,
+}
+
+/// Break `text`, set at `font_size` pixels, into lines no wider than
+/// `width`. Runs of whitespace are collapsed at line ends; a line holds at
+/// least one character however narrow `width` is.
+#[must_use]
+pub fn break_lines(text: &str, width: f32, font_size: f32) -> Vec<Line> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let byte = |k: usize| chars.get(k).map_or(text.len(), |&(b, _)| b);
+    let measure = |range: Range<usize>| -> f32 {
+        chars[range]
+            .iter()
+            .map(|&(_, c)| char_width(c, font_size))
+            .sum()
+    };
+    let mut lines = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if is_space(chars[i].1) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        // Where a line may end: at a break opportunity, else inside an
+        // overlong word
+        let mut opportunity = None;
+        let mut fallback = None;
+        let mut used = 0.0;
+        let mut end = chars.len();
+        for k in start..chars.len() {
+            let c = chars[k].1;
+            if k > start {
+                let before = chars[k - 1].1;
+                if can_break(before, c) {
+                    opportunity = Some(k);
+                } else if can_break_word(before, c) {
+                    fallback = Some(k);
+                }
+            }
+            let w = char_width(c, font_size);
+            if k > start && w > 0.0 && !is_space(c) && used + w > width {
+                end = opportunity.or(fallback).unwrap_or(k);
+                break;
+            }
+            used += w;
+        }
+        i = end;
+        while end > start && is_space(chars[end - 1].1) {
+            end -= 1;
+        }
+        lines.push(Line {
+            range: byte(start)..byte(end),
+            width: measure(start..end),
+        });
+    }
+    lines
+}
+
+/// Height of `text` broken to `width` at `font_size` (zero for no text).
+#[must_use]
+pub fn text_height(text: &str, width: f32, font_size: f32) -> f32 {
+    break_lines(text, width, font_size).len() as f32 * font_size * LINE_HEIGHT
+}
+
+/// Estimated advance of `c` at `font_size` pixels.
+#[must_use]
+pub fn char_width(c: char, font_size: f32) -> f32 {
+    if is_zero_width(c) {
+        0.0
+    } else if is_wide(c) {
+        font_size
+    } else {
+        font_size * 0.6
+    }
+}
+
+/// Whitespace a line may break at; no-break spaces are not.
+fn is_space(c: char) -> bool {
+    c.is_whitespace() && !matches!(c, '\u{a0}' | '\u{2007}' | '\u{202f}')
+}
+
+/// Combining marks, variation selectors and format characters.
+fn is_zero_width(c: char) -> bool {
+    matches!(c,
+        '\u{300}'..='\u{36f}'
+        | '\u{1ab0}'..='\u{1aff}'
+        | '\u{1dc0}'..='\u{1dff}'
+        | '\u{200b}'..='\u{200f}'
+        | '\u{2060}'
+        | '\u{20d0}'..='\u{20ff}'
+        | '\u{3099}'..='\u{309a}'
+        | '\u{fe00}'..='\u{fe0f}'
+        | '\u{fe20}'..='\u{fe2f}'
+        | '\u{feff}'
+        | '\u{e0100}'..='\u{e01ef}')
+}
+
+/// Characters of scripts written without spaces, which lines may break
+/// between: CJK ideographs and symbols, kana, Hangul, full- and half-width
+/// forms, and pictographs.
+fn is_ideographic(c: char) -> bool {
+    matches!(c,
+        '\u{1100}'..='\u{115f}'
+        | '\u{2e80}'..='\u{a4cf}'
+        | '\u{ac00}'..='\u{d7af}'
+        | '\u{f900}'..='\u{faff}'
+        | '\u{fe30}'..='\u{fe4f}'
+        | '\u{ff00}'..='\u{ffef}'
+        | '\u{1f300}'..='\u{1faff}'
+        | '\u{20000}'..='\u{3ffff}')
+}
+
+/// Characters a full em wide.
+fn is_wide(c: char) -> bool {
+    is_ideographic(c) && !matches!(c, '\u{ff61}'..='\u{ffdc}' | '\u{ffe8}'..='\u{ffee}')
+}
+
+/// Closing punctuation, small kana and marks that may not start a line.
+fn no_break_before(c: char) -> bool {
+    matches!(
+        c,
+        ')' | ']'
+            | '}'
+            | ','
+            | '.'
+            | ':'
+            | ';'
+            | '!'
+            | '?'
+            | '%'
+            | '\u{bb}'
+            | '\u{2019}'
+            | '\u{201d}'
+            | '\u{2025}'
+            | '\u{2026}'
+            | '\u{2030}'
+            | '\u{2032}'
+            | '\u{2033}'
+            | '\u{2103}'
+            | '\u{3001}'
+            | '\u{3002}'
+            | '\u{3005}'
+            | '\u{3009}'
+            | '\u{300b}'
+            | '\u{300d}'
+            | '\u{300f}'
+            | '\u{3011}'
+            | '\u{3015}'
+            | '\u{3017}'
+            | '\u{3019}'
+            | '\u{301f}'
+            | '\u{3041}'
+            | '\u{3043}'
+            | '\u{3045}'
+            | '\u{3047}'
+            | '\u{3049}'
+            | '\u{3063}'
+            | '\u{3083}'
+            | '\u{3085}'
+            | '\u{3087}'
+            | '\u{308e}'
+            | '\u{3095}'
+            | '\u{3096}'
+            | '\u{309b}'..='\u{309e}'
+            | '\u{30a1}'
+            | '\u{30a3}'
+            | '\u{30a5}'
+            | '\u{30a7}'
+            | '\u{30a9}'
+            | '\u{30c3}'
+            | '\u{30e3}'
+            | '\u{30e5}'
+            | '\u{30e7}'
+            | '\u{30ee}'
+            | '\u{30f5}'
+            | '\u{30f6}'
+            | '\u{30fb}'..='\u{30fe}'
+            | '\u{31f0}'..='\u{31ff}'
+            | '\u{ff01}'
+            | '\u{ff05}'
+            | '\u{ff09}'
+            | '\u{ff0c}'
+            | '\u{ff0e}'
+            | '\u{ff1a}'
+            | '\u{ff1b}'
+            | '\u{ff1f}'
+            | '\u{ff3d}'
+            | '\u{ff5d}'
+            | '\u{ff5e}'
+            | '\u{ff60}'
+    )
+}
+
+/// Opening brackets and quotes that may not end a line.
+fn no_break_after(c: char) -> bool {
+    matches!(
+        c,
+        '(' | '['
+            | '{'
+            | '\u{a1}'
+            | '\u{ab}'
+            | '\u{bf}'
+            | '\u{2018}'
+            | '\u{201c}'
+            | '\u{3008}'
+            | '\u{300a}'
+            | '\u{300c}'
+            | '\u{300e}'
+            | '\u{3010}'
+            | '\u{3014}'
+            | '\u{3016}'
+            | '\u{3018}'
+            | '\u{301d}'
+            | '\u{ff08}'
+            | '\u{ff3b}'
+            | '\u{ff5b}'
+            | '\u{ff5f}'
+    )
+}
+
+/// Whether a line may break between `before` and `after`.
+fn can_break(before: char, after: char) -> bool {
+    if is_space(after) || is_zero_width(after) || before == '\u{2060}' {
+        return false;
+    }
+    if is_space(before) || before == '\u{200b}' {
+        return true;
+    }
+    if no_break_before(after) || no_break_after(before) {
+        return false;
+    }
+    match before {
+        // Hyphens between words, not before numbers ("-5")
+        '-' | '\u{2010}' => after.is_alphabetic() && !is_ideographic(after),
+        '\u{2013}' | '\u{2014}' => true,
+        _ => is_ideographic(before) || is_ideographic(after),
+    }
+}
+
+/// Whether a word too long for its line may break between `before` and
+/// `after`: after URL and path separators, but not inside `//`.
+fn can_break_word(before: char, after: char) -> bool {
+    !is_zero_width(after)
+        && matches!(
+            before,
+            '/' | '?' | '&' | '=' | '.' | ',' | '_' | '-' | '#' | '~' | '+' | ';' | '|'
+        )
+        && !(before == '/' && after == '/')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrap(text: &str, chars: f32) -> Vec<&str> {
+        // `chars` narrow characters per line, or 0.6 as many wide ones
+        break_lines(text, chars * 6.0, 10.0)
+            .into_iter()
+            .map(|line| &text[line.range])
+            .collect()
+    }
+
+    #[test]
+    fn latin_breaks_at_spaces_and_hyphens() {
+        assert_eq!(
+            wrap("the quick brown fox jumps", 10.0),
+            ["the quick", "brown fox", "jumps"]
+        );
+        assert_eq!(wrap("  padded   text  ", 40.0), ["padded   text"]);
+        assert_eq!(wrap("well-known", 6.0), ["well-", "known"]);
+        // No break before a number or a closing bracket
+        assert_eq!(wrap("x (-5)", 4.0), ["x", "(-5)"]);
+        assert!(wrap("   ", 10.0).is_empty());
+        // No-break spaces hold words together
+        assert_eq!(wrap("10\u{a0}km away", 6.0), ["10\u{a0}km", "away"]);
+    }
+
+    #[test]
+    fn cjk_breaks_between_characters_with_kinsoku() {
+        // Six wide characters per line
+        assert_eq!(
+            wrap("吾輩は猫である。名前はまだ無い。", 10.0),
+            ["吾輩は猫であ", "る。名前はま", "だ無い。"]
+        );
+        // The full stop that would start the second line takes the
+        // character before it along
+        assert_eq!(wrap("あいうえおか。きく", 10.0), ["あいうえお", "か。きく"]);
+        // A closing bracket or small kana never starts a line, an opening
+        // bracket never ends one
+        assert_eq!(wrap("あいうえお「か」", 10.0), ["あいうえお", "「か」"]);
+        assert_eq!(wrap("あいうえおかっと", 10.0), ["あいうえお", "かっと"]);
+        // Latin inside Japanese text keeps its words whole
+        assert_eq!(wrap("日本語とEnglish", 12.0), ["日本語と", "English"]);
+    }
+
+    #[test]
+    fn long_words_break_at_separators_then_anywhere() {
+        let url = "https://example.com/articles/2024/a-very-long-slug?ref=home";
+        let lines = wrap(url, 24.0);
+        assert_eq!(
+            lines,
+            [
+                "https://example.com/",
+                "articles/2024/a-very-",
+                "long-slug?ref=home"
+            ]
+        );
+        assert_eq!(lines.concat(), url);
+        assert_eq!(wrap("abcdefghij", 4.0), ["abcd", "efgh", "ij"]);
+        // Combining marks stay with their base character
+        assert_eq!(
+            wrap("e\u{301}e\u{301}e\u{301}", 2.0),
+            ["e\u{301}e\u{301}", "e\u{301}"]
+        );
+        // At least one character per line
+        assert_eq!(wrap("猫猫", 0.5), ["猫", "猫"]);
+    }
+
+    #[test]
+    fn lines_fit_their_width() {
+        let text = "東京特許許可局 https://example.com/path/to/a/resource.html ok";
+        for line in break_lines(text, 90.0, 10.0) {
+            assert!(
+                line.width <= 90.0,
+                "{:?} is {} wide",
+                &text[line.range],
+                line.width
+            );
+        }
+        assert!((text_height("one line", 800.0, 10.0) - 14.0).abs() < 1e-4);
+        assert!(text_height("", 800.0, 10.0).abs() < f32::EPSILON);
+    }
+}
@@ -0,0 +1,379 @@
+//! Audio and video placeholders.
+//!
+//! `<video>` and `<audio>` are not played. Layout gives each element a
+//! fixed-size card instead ([`MediaInfo`]): it names the media, shows a
+//! video's poster and links to the file so it can be opened in an external
+//! player. What the markup leaves out — the type, the duration, the size —
+//! is read from the first bytes of the file by [`probe`]: the MP4 `mvhd`
+//! box, the WebM `Duration` element and the WAV and FLAC headers. Files
+//! whose index sits at the end (some MP4s) or that have none (MP3) are
+//! shown without a duration.
+
+use std::time::Duration;
+
+use crate::dom::DomNode;
+use crate::engine::memory::format_bytes;
+use crate::net::fetch::{fetch_prefix, FetchError, FetchOptions};
+use crate::net::sniff;
+
+/// Bytes fetched from the start of a media file to read its metadata.
+pub const PROBE_BYTES: usize = 64 * 1024;
+
+/// Height of a video card, in layout pixels at the default font size.
+const VIDEO_HEIGHT: f32 = 200.0;
+/// Height of an audio card.
+const AUDIO_HEIGHT: f32 = 64.0;
+
+/// Whether the element is a `<video>` or an `<audio>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+    Video,
+    Audio,
+}
+
+impl MediaKind {
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Video => "Video",
+            Self::Audio => "Audio",
+        }
+    }
+}
+
+/// A `<video>` or `<audio>` element, as its placeholder card shows it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaInfo {
+    pub kind: MediaKind,
+    /// `src`, or that of the first `<source>` child that has one
+    pub src: Option<String>,
+    /// Declared `type` of the source, else guessed from its extension
+    pub mime: Option<String>,
+    /// `poster` of a `<video>`
+    pub poster: Option<String>,
+    /// `title` or `aria-label`
+    pub title: Option<String>,
+}
+
+impl MediaInfo {
+    /// The media element `node` describes, if it is one.
+    #[must_use]
+    pub fn from_node(node: &DomNode) -> Option<Self> {
+        let kind = match node.tag.as_str() {
+            "video" => MediaKind::Video,
+            "audio" => MediaKind::Audio,
+            _ => return None,
+        };
+        let attr = |node: &DomNode, name: &str| {
+            node.attr(name)
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let source = attr(node, "src")
+            .map(|src| (src, attr(node, "type")))
+            .or_else(|| {
+                node.children
+                    .iter()
+                    .filter(|c| c.tag == "source")
+                    .find_map(|c| Some((attr(c, "src")?, attr(c, "type"))))
+            });
+        let (src, declared) = source.map_or((None, None), |(src, t)| (Some(src), t));
+        let mime = declared.map(|t| sniff::mime_essence(&t)).or_else(|| {
+            src.as_deref()
+                .and_then(sniff::media_type_from_url)
+                .map(str::to_string)
+        });
+        Some(Self {
+            kind,
+            src,
+            mime,
+            poster: (kind == MediaKind::Video)
+                .then(|| attr(node, "poster"))
+                .flatten(),
+            title: attr(node, "title").or_else(|| attr(node, "aria-label")),
+        })
+    }
+
+    /// Height of the card at `font_size`.
+    #[must_use]
+    pub fn height(&self, font_size: f32) -> f32 {
+        let base = match self.kind {
+            MediaKind::Video => VIDEO_HEIGHT,
+            MediaKind::Audio => AUDIO_HEIGHT,
+        };
+        base * font_size / crate::render::layout::DEFAULT_FONT_SIZE
+    }
+
+    /// The title, else the file name of the source.
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.title.clone().unwrap_or_else(|| {
+            self.src
+                .as_deref()
+                .map(|src| src.split(['?', '#']).next().unwrap_or(src))
+                .and_then(|path| path.trim_end_matches('/').rsplit('/').next())
+                .filter(|name| !name.is_empty())
+                .map_or_else(|| "Untitled".to_string(), str::to_string)
+        })
+    }
+
+    /// e.g. "Video · MP4 · 3:25 · 12.4 MB", with what `meta` adds to the
+    /// markup.
+    #[must_use]
+    pub fn describe(&self, meta: Option<&MediaMetadata>) -> String {
+        let mime = self.mime.as_deref().or_else(|| meta.and_then(|m| m.mime));
+        let mut parts = vec![self.kind.label().to_string()];
+        parts.extend(mime.map(type_label));
+        if let Some(meta) = meta {
+            parts.extend(meta.duration.map(format_duration));
+            parts.extend(
+                meta.bytes
+                    .map(|b| format_bytes(usize::try_from(b).unwrap_or(usize::MAX))),
+            );
+        }
+        if self.src.is_none() {
+            parts.push("no source".to_string());
+        }
+        parts.join(" \u{b7} ")
+    }
+}
+
+/// What the first bytes of a media file say about it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaMetadata {
+    pub mime: Option<&'static str>,
+    pub duration: Option<Duration>,
+    /// Size of the whole file, when the server said
+    pub bytes: Option<u64>,
+}
+
+impl MediaMetadata {
+    /// Metadata from `head`, the start of the file at `url` served as
+    /// `content_type`, `total` bytes long.
+    #[must_use]
+    pub fn parse(head: &[u8], content_type: &str, url: &str, total: Option<u64>) -> Self {
+        let sniffed = sniff::sniff_media(head);
+        let duration = match sniffed {
+            Some("video/mp4" | "audio/mp4" | "video/quicktime") => mp4_duration(head),
+            Some("video/webm" | "video/x-matroska") => matroska_duration(head),
+            Some("audio/wav") => wav_duration(head),
+            Some("audio/flac") => flac_duration(head),
+            _ => None,
+        };
+        Self {
+            mime: sniff::media_type(content_type, url, head),
+            duration,
+            bytes: total,
+        }
+    }
+}
+
+/// Fetch the start of the media file at `url` and read its metadata
+/// (blocking).
+///
+/// # Errors
+///
+/// Returns `FetchError` if the file could not be fetched.
+pub fn probe(url: &str, options: &FetchOptions) -> Result<MediaMetadata, FetchError> {
+    let prefix = fetch_prefix(url, PROBE_BYTES, options)?;
+    Ok(MediaMetadata::parse(
+        &prefix.bytes,
+        &prefix.content_type,
+        url,
+        prefix.total,
+    ))
+}
+
+/// "3:25", or "1:02:03" from an hour on.
+#[must_use]
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m}:{s:02}")
+    }
+}
+
+/// Short name of a media type: "MP4", "WebM", "MP3".
+fn type_label(mime: &str) -> String {
+    let subtype = mime.rsplit('/').next().unwrap_or(mime);
+    match subtype {
+        "mpeg" => "MP3".to_string(),
+        "webm" => "WebM".to_string(),
+        "ogg" => "Ogg".to_string(),
+        "quicktime" => "MOV".to_string(),
+        "x-matroska" => "MKV".to_string(),
+        "vnd.apple.mpegurl" | "x-mpegurl" => "HLS stream".to_string(),
+        other => other.trim_start_matches("x-").to_ascii_uppercase(),
+    }
+}
+
+fn find(head: &[u8], needle: &[u8]) -> Option<usize> {
+    head.windows(needle.len()).position(|w| w == needle)
+}
+
+fn be(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |acc, &b| (acc << 8) | u64::from(b))
+}
+
+fn le(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .rev()
+        .fold(0, |acc, &b| (acc << 8) | u64::from(b))
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn ticks(count: u64, per_second: u64) -> Option<Duration> {
+    (per_second > 0).then(|| Duration::from_secs_f64(count as f64 / per_second as f64))
+}
+
+/// Duration in the `mvhd` box, when the `moov` box comes first.
+fn mp4_duration(head: &[u8]) -> Option<Duration> {
+    let body = &head[find(head, b"mvhd")? + 4..];
+    let (scale, duration) = if *body.first()? == 1 {
+        (body.get(20..24)?, body.get(24..32)?)
+    } else {
+        (body.get(12..16)?, body.get(16..20)?)
+    };
+    ticks(be(duration), be(scale))
+}
+
+/// `Duration` of the segment's `Info`, in `TimecodeScale` nanoseconds.
+fn matroska_duration(head: &[u8]) -> Option<Duration> {
+    let scale = find(head, &[0x2A, 0xD7, 0xB1])
+        .and_then(|i| {
+            let size = usize::from(head.get(i + 3)? & 0x7F);
+            head.get(i + 4..i + 4 + size).map(be)
+        })
+        .unwrap_or(1_000_000);
+    let i = find(head, &[0x44, 0x89])?;
+    let units = match *head.get(i + 2)? {
+        0x84 => f64::from(f32::from_bits(
+            u32::try_from(be(head.get(i + 3..i + 7)?)).ok()?,
+        )),
+        0x88 => f64::from_bits(be(head.get(i + 3..i + 11)?)),
+        _ => return None,
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let secs = units * scale as f64 / 1e9;
+    (secs.is_finite() && secs > 0.0).then(|| Duration::from_secs_f64(secs))
+}
+
+/// Size of the `data` chunk over the byte rate in `fmt `.
+fn wav_duration(head: &[u8]) -> Option<Duration> {
+    let (mut byte_rate, mut pos) = (None, 12);
+    while let (Some(id), Some(size)) = (head.get(pos..pos + 4), head.get(pos + 4..pos + 8)) {
+        let size = le(size);
+        match id {
+            b"fmt " => byte_rate = head.get(pos + 16..pos + 20).map(le),
+            b"data" => return ticks(size, byte_rate?),
+            _ => {}
+        }
+        // Chunks are padded to an even size
+        pos += 8 + usize::try_from(size + (size & 1)).ok()?;
+    }
+    None
+}
+
+/// Total samples over the sample rate in `STREAMINFO`.
+fn flac_duration(head: &[u8]) -> Option<Duration> {
+    if head.get(4)? & 0x7F != 0 {
+        return None;
+    }
+    let packed = be(head.get(18..26)?);
+    ticks(packed & 0xF_FFFF_FFFF, packed >> 44)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+
+    fn media(html: &str) -> MediaInfo {
+        fn first(node: &DomNode) -> Option<MediaInfo> {
+            MediaInfo::from_node(node).or_else(|| node.children.iter().find_map(first))
+        }
+        first(&parse_html(html, "https://example.com/").root).unwrap()
+    }
+
+    #[test]
+    fn sources_posters_and_descriptions() {
+        let video = media(
+            r#"<video poster="/p.jpg" controls>
+                <source src="/clips/intro.webm" type="video/webm; codecs=vp9">
+                <source src="/clips/intro.mp4"></video>"#,
+        );
+        assert_eq!(video.kind, MediaKind::Video);
+        assert_eq!(video.src.as_deref(), Some("/clips/intro.webm"));
+        assert_eq!(video.mime.as_deref(), Some("video/webm"));
+        assert_eq!(video.poster.as_deref(), Some("/p.jpg"));
+        assert_eq!(video.name(), "intro.webm");
+        assert_eq!(video.describe(None), "Video \u{b7} WebM");
+        let meta = MediaMetadata {
+            mime: Some("video/webm"),
+            duration: Some(Duration::from_secs(205)),
+            bytes: Some(5 * 1024 * 1024),
+        };
+        assert_eq!(
+            video.describe(Some(&meta)),
+            "Video \u{b7} WebM \u{b7} 3:25 \u{b7} 5.0 MB"
+        );
+
+        let audio = media(r#"<audio src="talk.mp3?x=1" title="Keynote" poster="x"></audio>"#);
+        assert_eq!(audio.kind, MediaKind::Audio);
+        assert_eq!(audio.mime.as_deref(), Some("audio/mpeg"));
+        assert_eq!(audio.poster, None);
+        assert_eq!(audio.name(), "Keynote");
+        assert!(audio.height(16.0) < video.height(16.0));
+
+        let empty = media("<video></video>");
+        assert_eq!(empty.describe(None), "Video \u{b7} no source");
+        assert_eq!(format_duration(Duration::from_secs(3723)), "1:02:03");
+    }
+
+    #[test]
+    fn durations_from_container_headers() {
+        // mvhd v0: timescale 1000, duration 90 500
+        let mut mp4 = b"\0\0\0\x18ftypisom\0\0\0\0isom\0\0\0\x6cmoov\0\0\0\x64mvhd".to_vec();
+        mp4.extend_from_slice(&[0; 12]);
+        mp4.extend_from_slice(&1000u32.to_be_bytes());
+        mp4.extend_from_slice(&90_500u32.to_be_bytes());
+        let meta = MediaMetadata::parse(&mp4, "", "https://example.com/v", Some(4096));
+        assert_eq!(meta.mime, Some("video/mp4"));
+        assert_eq!(meta.duration, Some(Duration::from_millis(90_500)));
+        assert_eq!(meta.bytes, Some(4096));
+
+        // 44.1 kHz 16-bit stereo, two seconds of data
+        let mut wav = b"RIFF\0\0\0\0WAVEfmt \x10\0\0\0\x01\0\x02\0".to_vec();
+        wav.extend_from_slice(&44_100u32.to_le_bytes());
+        wav.extend_from_slice(&176_400u32.to_le_bytes());
+        wav.extend_from_slice(b"\x04\0\x10\0data");
+        wav.extend_from_slice(&352_800u32.to_le_bytes());
+        let meta = MediaMetadata::parse(&wav, "audio/wav", "", None);
+        assert_eq!(meta.duration, Some(Duration::from_secs(2)));
+
+        // STREAMINFO: 48 kHz, 480 000 samples
+        let mut flac = b"fLaC\x80\0\0\x22".to_vec();
+        flac.extend_from_slice(&[0; 10]);
+        let packed = (48_000u64 << 44) | (1 << 41) | (15 << 36) | 480_000;
+        flac.extend_from_slice(&packed.to_be_bytes());
+        flac.extend_from_slice(&[0; 16]);
+        let meta = MediaMetadata::parse(&flac, "", "", None);
+        assert_eq!(meta.duration, Some(Duration::from_secs(10)));
+
+        // WebM Info: default timecode scale, Duration 2 500.0 ms
+        let mut webm =
+            b"\x1a\x45\xdf\xa3\x84\x42\x82\x84webm\x15\x49\xa9\x66\x8c\x44\x89\x88".to_vec();
+        webm.extend_from_slice(&2_500.0f64.to_bits().to_be_bytes());
+        let meta = MediaMetadata::parse(&webm, "", "", None);
+        assert_eq!(meta.duration, Some(Duration::from_millis(2500)));
+
+        // MP3 carries no index: type only
+        let meta = MediaMetadata::parse(b"ID3\x04\0\0\0\0\0\0", "", "", None);
+        assert_eq!((meta.mime, meta.duration), (Some("audio/mpeg"), None));
+    }
+}
@@ -1,13 +1,31 @@
 pub mod animator;
+pub mod bvh;
+pub mod clipboard;
+pub mod context_menu;
+pub mod cost;
+pub mod culling;
+pub mod docmap;
+pub mod fonts;
+pub mod galaxy;
 pub mod hot_reload;
 pub mod hyper_sdf;
 pub mod layout;
+pub mod layout_debug;
+pub mod line_break;
+pub mod media;
+pub mod palette;
 pub mod persistent_map;
+pub mod raster;
+pub mod scene_file;
 pub mod sdf_paint;
 pub mod sdf_ui;
+pub mod selection;
 pub mod spatial;
 pub mod stream;
+pub mod tab_order;
+pub mod table_data;
 pub mod text;
+pub mod thumbnail;
 
 #[cfg(feature = "sdf-render")]
 pub mod sdf_renderer;
@@ -15,16 +33,65 @@ pub mod sdf_renderer;
 #[cfg(feature = "sdf-render")]
 pub mod gpu_renderer;
 
+#[cfg(feature = "sdf-render")]
+pub mod oz_gpu;
+
 /// Rendering mode for the browser
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub enum RenderMode {
     /// Standard 2D rendering (egui widgets)
     #[default]
+    #[serde(rename = "flat")]
     Flat,
     /// SDF-based 2D rendering (ALICE-SDF)
+    #[serde(rename = "sdf2d")]
     Sdf2D,
     /// 3D spatial web (ALICE-SDF + `VRChat` mode)
+    #[serde(rename = "spatial3d")]
     Spatial3D,
     /// OZ Mode: orbital/planetary info-space (Cyber-White aesthetic)
+    #[serde(rename = "oz")]
     OzMode,
+    /// Reader view: the main article alone, typeset for reading
+    #[serde(rename = "reader")]
+    Reader,
+}
+
+impl RenderMode {
+    pub const ALL: [Self; 5] = [
+        Self::Flat,
+        Self::Sdf2D,
+        Self::Spatial3D,
+        Self::OzMode,
+        Self::Reader,
+    ];
+
+    /// Stable identifier, as stored in settings and used in `alice://` URLs.
+    #[must_use]
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::Flat => "flat",
+            Self::Sdf2D => "sdf2d",
+            Self::Spatial3D => "spatial3d",
+            Self::OzMode => "oz",
+            Self::Reader => "reader",
+        }
+    }
+
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.key() == key)
+    }
+
+    /// Human-readable name.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Flat => "2D Flat",
+            Self::Sdf2D => "SDF 2D",
+            Self::Spatial3D => "3D Spatial",
+            Self::OzMode => "OZ Orbital",
+            Self::Reader => "Reader",
+        }
+    }
 }
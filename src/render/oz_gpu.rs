@@ -0,0 +1,439 @@
+//! Instanced GPU drawing of OZ-mode text particles.
+//!
+//! Painting each particle's text through egui costs a layout lookup, a
+//! tessellated mesh and a few dozen vertices per label, every frame, which
+//! caps how many particles the stream can show. This renderer instead takes
+//! one [`GlyphInstance`] per glyph — a screen rectangle, a rectangle of the
+//! glyph atlas and a colour — uploads them into a reusable instance buffer
+//! and draws them all with one instanced draw of a four-vertex strip.
+//!
+//! Glyphs come from egui's own font atlas: labels are laid out once at
+//! [`LABEL_FONT_SIZE`] and scaled per particle ([`push_galley`]), so the
+//! layout cache stays warm while particles grow and shrink with depth, and
+//! the atlas is mirrored into a texture of the renderer's
+//! ([`OzGpuRenderer::upload_atlas`]) whenever egui adds glyphs to it.
+//!
+//! The renderer runs on the UI's device and draws into the UI's render
+//! pass, from an egui paint callback; colours are premultiplied and in
+//! gamma space, as egui's own vertices are.
+
+use egui::Galley;
+use wgpu::util::DeviceExt;
+
+/// Size labels are laid out at before scaling.
+pub const LABEL_FONT_SIZE: f32 = 32.0;
+
+/// One glyph (or solid quad) to draw (48 bytes, matches the WGSL
+/// `Instance` input).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GlyphInstance {
+    /// `[x, y, w, h]` in points, from the top left of the view
+    pub rect: [f32; 4],
+    /// `[u0, v0, u1, v1]` in the atlas, normalized
+    pub uv: [f32; 4],
+    /// Premultiplied RGBA
+    pub color: [f32; 4],
+}
+
+/// View size, in points (16 bytes, matches the WGSL `Uniforms`).
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct Uniforms {
+    view_size: [f32; 2],
+    _pad: [f32; 2],
+}
+
+/// Append the glyphs of `galley`, laid out at [`LABEL_FONT_SIZE`], drawn
+/// at `font_size` centred on `center`, in `color` (premultiplied RGBA).
+/// `atlas_size` is the size of egui's font atlas in texels.
+pub fn push_galley(
+    out: &mut Vec<GlyphInstance>,
+    galley: &Galley,
+    atlas_size: [usize; 2],
+    center: [f32; 2],
+    font_size: f32,
+    color: [f32; 4],
+) {
+    let scale = font_size / LABEL_FONT_SIZE;
+    let size = galley.size();
+    let origin = [
+        center[0] - size.x * scale * 0.5,
+        center[1] - size.y * scale * 0.5,
+    ];
+    let [aw, ah] = [atlas_size[0] as f32, atlas_size[1] as f32];
+    for row in &galley.rows {
+        for glyph in &row.glyphs {
+            let uv = &glyph.uv_rect;
+            if uv.is_nothing() {
+                // Whitespace
+                continue;
+            }
+            let x = glyph.pos.x + uv.offset.x;
+            let y = glyph.pos.y + uv.offset.y;
+            out.push(GlyphInstance {
+                rect: [
+                    x.mul_add(scale, origin[0]),
+                    y.mul_add(scale, origin[1]),
+                    uv.size.x * scale,
+                    uv.size.y * scale,
+                ],
+                uv: [
+                    f32::from(uv.min[0]) / aw,
+                    f32::from(uv.min[1]) / ah,
+                    f32::from(uv.max[0]) / aw,
+                    f32::from(uv.max[1]) / ah,
+                ],
+                color,
+            });
+        }
+    }
+}
+
+/// A copy of egui's font atlas on the GPU.
+struct Atlas {
+    size: [u32; 2],
+    bind_group: wgpu::BindGroup,
+}
+
+/// Instanced particle-text pipeline and its buffers, created once on the
+/// UI's device.
+pub struct OzGpuRenderer {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniform_buf: wgpu::Buffer,
+    atlas: Option<Atlas>,
+    /// Instance buffer, grown to the largest frame seen
+    instance_buf: Option<wgpu::Buffer>,
+    /// Capacity of `instance_buf`, in instances
+    instance_capacity: usize,
+    /// Instances uploaded by the last `prepare`
+    instance_count: u32,
+}
+
+impl OzGpuRenderer {
+    /// Build the pipeline for a render pass writing `target_format`.
+    #[must_use]
+    pub fn new(device: &wgpu::Device, target_format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("OZ Glyph Shader"),
+            source: wgpu::ShaderSource::Wgsl(GLYPH_WGSL.into()),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("OZ Glyphs"),
+            entries: &[
+                // Uniforms
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Glyph atlas
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("OZ Glyphs"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let instance_layout = wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<GlyphInstance>() as u64,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &wgpu::vertex_attr_array![
+                0 => Float32x4,
+                1 => Float32x4,
+                2 => Float32x4,
+            ],
+        };
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("OZ Glyph Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                buffers: &[instance_layout],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                ..wgpu::PrimitiveState::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("OZ Glyph Atlas"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..wgpu::SamplerDescriptor::default()
+        });
+        let uniform_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("OZ Glyph Uniforms"),
+            contents: bytemuck::bytes_of(&Uniforms {
+                view_size: [1.0, 1.0],
+                _pad: [0.0; 2],
+            }),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            uniform_buf,
+            atlas: None,
+            instance_buf: None,
+            instance_capacity: 0,
+            instance_count: 0,
+        }
+    }
+
+    /// Replace the glyph atlas with `coverage`, one byte per texel, row by
+    /// row, `size` texels large.
+    pub fn upload_atlas(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: [u32; 2],
+        coverage: &[u8],
+    ) {
+        let extent = wgpu::Extent3d {
+            width: size[0],
+            height: size[1],
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("OZ Glyph Atlas"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            coverage,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(size[0]),
+                rows_per_image: Some(size[1]),
+            },
+            extent,
+        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("OZ Glyphs"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.uniform_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        });
+        self.atlas = Some(Atlas { size, bind_group });
+    }
+
+    /// Size of the uploaded atlas, if any.
+    #[must_use]
+    pub fn atlas_size(&self) -> Option<[u32; 2]> {
+        self.atlas.as_ref().map(|a| a.size)
+    }
+
+    /// Upload this frame's `instances` for a view `view_size` points large.
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        view_size: [f32; 2],
+        instances: &[GlyphInstance],
+    ) {
+        queue.write_buffer(
+            &self.uniform_buf,
+            0,
+            bytemuck::bytes_of(&Uniforms {
+                view_size,
+                _pad: [0.0; 2],
+            }),
+        );
+        self.instance_count = instances.len() as u32;
+        if instances.is_empty() {
+            return;
+        }
+        if instances.len() > self.instance_capacity || self.instance_buf.is_none() {
+            let capacity = instances.len().next_power_of_two();
+            self.instance_buf = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("OZ Glyph Instances"),
+                size: (capacity * std::mem::size_of::<GlyphInstance>()) as u64,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+            self.instance_capacity = capacity;
+        }
+        if let Some(ref buf) = self.instance_buf {
+            queue.write_buffer(buf, 0, bytemuck::cast_slice(instances));
+        }
+    }
+
+    /// Draw the instances of the last [`prepare`](Self::prepare) into
+    /// `pass`, whose viewport is the view.
+    pub fn paint(&self, pass: &mut wgpu::RenderPass<'_>) {
+        let (Some(atlas), Some(buf)) = (self.atlas.as_ref(), self.instance_buf.as_ref()) else {
+            return;
+        };
+        if self.instance_count == 0 {
+            return;
+        }
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &atlas.bind_group, &[]);
+        pass.set_vertex_buffer(0, buf.slice(..));
+        pass.draw(0..4, 0..self.instance_count);
+    }
+}
+
+/// Quads expanded from instances in the vertex shader; the fragment shader
+/// multiplies the premultiplied colour by the atlas coverage.
+const GLYPH_WGSL: &str = r"
+struct Uniforms {
+    view_size: vec2<f32>,
+    _pad: vec2<f32>,
+};
+
+@group(0) @binding(0) var<uniform> u: Uniforms;
+@group(0) @binding(1) var atlas: texture_2d<f32>;
+@group(0) @binding(2) var atlas_sampler: sampler;
+
+struct Instance {
+    @location(0) rect: vec4<f32>,
+    @location(1) uv: vec4<f32>,
+    @location(2) color: vec4<f32>,
+};
+
+struct VsOut {
+    @builtin(position) pos: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vi: u32, inst: Instance) -> VsOut {
+    let corner = vec2<f32>(f32(vi & 1u), f32(vi >> 1u));
+    let p = inst.rect.xy + corner * inst.rect.zw;
+    var out: VsOut;
+    out.pos = vec4<f32>(
+        p.x / u.view_size.x * 2.0 - 1.0,
+        1.0 - p.y / u.view_size.y * 2.0,
+        0.0,
+        1.0,
+    );
+    out.uv = mix(inst.uv.xy, inst.uv.zw, corner);
+    out.color = inst.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VsOut) -> @location(0) vec4<f32> {
+    return in.color * textureSample(atlas, atlas_sampler, in.uv).r;
+}
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn galleys_become_scaled_centred_glyphs() {
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |_| {});
+        let galley = ctx.fonts(|f| {
+            f.layout_no_wrap(
+                "Hi there".into(),
+                egui::FontId::proportional(LABEL_FONT_SIZE),
+                egui::Color32::WHITE,
+            )
+        });
+        let atlas = ctx.fonts(|f| f.font_image_size());
+
+        let mut full = Vec::new();
+        push_galley(
+            &mut full,
+            &galley,
+            atlas,
+            [100.0, 50.0],
+            LABEL_FONT_SIZE,
+            [1.0; 4],
+        );
+        // One instance per visible glyph; the space has none
+        assert_eq!(full.len(), 7);
+        assert!(full
+            .iter()
+            .flat_map(|g| g.uv)
+            .all(|c| (0.0..=1.0).contains(&c)));
+        let left = full.iter().map(|g| g.rect[0]).fold(f32::MAX, f32::min);
+        let right = full
+            .iter()
+            .map(|g| g.rect[0] + g.rect[2])
+            .fold(f32::MIN, f32::max);
+        assert!((left + right) / 2.0 - 100.0 < 4.0);
+
+        let mut half = Vec::new();
+        push_galley(
+            &mut half,
+            &galley,
+            atlas,
+            [100.0, 50.0],
+            LABEL_FONT_SIZE / 2.0,
+            [1.0; 4],
+        );
+        assert!((half[0].rect[2] - full[0].rect[2] / 2.0).abs() < 0.01);
+        assert_eq!(half[0].uv, full[0].uv);
+    }
+}
@@ -0,0 +1,274 @@
+//! Colour palettes for the 3-D modes.
+//!
+//! Spatial 3D comes in two looks: the light corridor by day and a
+//! neon-on-dark "night city" after sunset or whenever the dark theme is on.
+//! A [`ThemeProvider`] decides which applies; the resulting
+//! [`ScenePalette`] colours the scene built by
+//! [`spatial`](crate::render::spatial) and, through its [`SkyPalette`], the
+//! raymarchers' sky gradient, rim light and shadow tint.
+//!
+//! "Sunset" is a fixed local hour (see [`ThemeProvider::night_start`]): the
+//! browser has no location to compute the real one from. Local time comes
+//! from [`crate::local_time`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::local_time::local_minute_of_day;
+
+// ── Palettes ──
+
+/// Sky and lighting colours used by the raymarchers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkyPalette {
+    /// Sky colour straight up; the horizon is the scene's background
+    pub zenith: [f32; 3],
+    /// Added at grazing angles on lit surfaces
+    pub rim: [f32; 3],
+    /// Added to the shadowed side of lit surfaces
+    pub shadow_tint: [f32; 3],
+}
+
+/// Colours for every kind of element in a spatial scene.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScenePalette {
+    pub background: [f32; 4],
+    pub ground: [f32; 4],
+    /// Section, article and other container walls
+    pub wall: [f32; 4],
+    /// Navigation, header and footer beams
+    pub beam: [f32; 4],
+    /// `h1`, `h2`, and `h3`–`h6`
+    pub headings: [[f32; 4]; 3],
+    /// Links
+    pub portal: [f32; 4],
+    pub button: [f32; 4],
+    /// Paragraphs and list items
+    pub panel: [f32; 4],
+    /// Bare text
+    pub text: [f32; 4],
+    pub picture_frame: [f32; 4],
+    pub picture: [f32; 4],
+    pub separator: [f32; 4],
+    pub corridor_wall: [f32; 4],
+    /// Feed items in a corridor
+    pub card: [f32; 4],
+    /// Floor lines between corridor cards
+    pub divider: [f32; 4],
+    pub sky: SkyPalette,
+}
+
+impl ScenePalette {
+    /// The light corridor.
+    pub const DAY: Self = Self {
+        background: [0.55, 0.75, 0.95, 1.0],
+        ground: [0.88, 0.88, 0.90, 1.0],
+        wall: [0.93, 0.93, 0.96, 0.85],
+        beam: [0.75, 0.78, 0.85, 1.0],
+        headings: [
+            [0.95, 0.85, 0.4, 1.0],
+            [0.90, 0.82, 0.5, 1.0],
+            [0.85, 0.83, 0.6, 1.0],
+        ],
+        portal: [0.10, 0.40, 0.95, 1.0],
+        button: [0.20, 0.55, 0.95, 1.0],
+        panel: [0.98, 0.98, 1.0, 0.7],
+        text: [0.96, 0.96, 0.98, 0.6],
+        picture_frame: [0.3, 0.3, 0.32, 1.0],
+        picture: [0.75, 0.78, 0.82, 1.0],
+        separator: [0.6, 0.6, 0.65, 1.0],
+        corridor_wall: [0.85, 0.85, 0.90, 0.6],
+        card: [0.96, 0.96, 1.0, 0.8],
+        divider: [0.70, 0.70, 0.75, 0.5],
+        sky: SkyPalette {
+            zenith: [0.5225, 0.7125, 0.9025],
+            rim: [0.5, 0.5, 0.5],
+            shadow_tint: [0.05, 0.03, 0.08],
+        },
+    };
+
+    /// Neon on dark: "night city".
+    pub const NIGHT: Self = Self {
+        background: [0.05, 0.04, 0.12, 1.0],
+        ground: [0.07, 0.07, 0.11, 1.0],
+        wall: [0.13, 0.13, 0.20, 0.85],
+        beam: [0.22, 0.12, 0.38, 1.0],
+        headings: [
+            [1.0, 0.25, 0.70, 1.0],
+            [0.90, 0.35, 0.85, 1.0],
+            [0.70, 0.45, 0.95, 1.0],
+        ],
+        portal: [0.0, 0.90, 1.0, 1.0],
+        button: [0.20, 1.0, 0.60, 1.0],
+        panel: [0.17, 0.17, 0.26, 0.7],
+        text: [0.22, 0.22, 0.32, 0.6],
+        picture_frame: [0.95, 0.20, 0.65, 1.0],
+        picture: [0.25, 0.27, 0.36, 1.0],
+        separator: [0.0, 0.70, 0.90, 1.0],
+        corridor_wall: [0.11, 0.11, 0.18, 0.6],
+        card: [0.19, 0.19, 0.28, 0.8],
+        divider: [0.0, 0.80, 1.0, 0.5],
+        sky: SkyPalette {
+            zenith: [0.01, 0.0, 0.04],
+            rim: [0.30, 0.60, 1.0],
+            shadow_tint: [0.02, 0.0, 0.06],
+        },
+    };
+}
+
+impl Default for ScenePalette {
+    fn default() -> Self {
+        Self::DAY
+    }
+}
+
+// ── Themes ──
+
+/// The look in effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SceneTheme {
+    #[default]
+    Day,
+    Night,
+}
+
+impl SceneTheme {
+    #[must_use]
+    pub const fn palette(self) -> &'static ScenePalette {
+        match self {
+            Self::Day => &ScenePalette::DAY,
+            Self::Night => &ScenePalette::NIGHT,
+        }
+    }
+}
+
+/// The user's preference, as stored in settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemeChoice {
+    /// Night after sunset or with the dark theme, day otherwise
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "day")]
+    Day,
+    #[serde(rename = "night")]
+    Night,
+}
+
+impl ThemeChoice {
+    pub const ALL: [Self; 3] = [Self::Auto, Self::Day, Self::Night];
+
+    /// Stable identifier, as stored in settings and used in `alice://` URLs.
+    #[must_use]
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Day => "day",
+            Self::Night => "night",
+        }
+    }
+
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.key() == key)
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Auto => "Automatic (night after sunset or in dark theme)",
+            Self::Day => "Always day",
+            Self::Night => "Always night",
+        }
+    }
+}
+
+/// Decides which theme the 3-D modes use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThemeProvider {
+    pub choice: ThemeChoice,
+    /// The UI's dark theme is on
+    pub dark_mode: bool,
+    /// Local hour night begins (`Auto` only)
+    pub night_start: u8,
+    /// Local hour night ends
+    pub night_end: u8,
+}
+
+impl Default for ThemeProvider {
+    fn default() -> Self {
+        Self {
+            choice: ThemeChoice::Auto,
+            dark_mode: false,
+            night_start: 19,
+            night_end: 6,
+        }
+    }
+}
+
+impl ThemeProvider {
+    /// The theme at `minute` minutes past local midnight.
+    #[must_use]
+    pub fn theme_at(&self, minute: u32) -> SceneTheme {
+        let night = match self.choice {
+            ThemeChoice::Day => false,
+            ThemeChoice::Night => true,
+            ThemeChoice::Auto => {
+                let hour = (minute / 60) % 24;
+                let (start, end) = (u32::from(self.night_start), u32::from(self.night_end));
+                let after_sunset = if start <= end {
+                    (start..end).contains(&hour)
+                } else {
+                    hour >= start || hour < end
+                };
+                self.dark_mode || after_sunset
+            }
+        };
+        if night {
+            SceneTheme::Night
+        } else {
+            SceneTheme::Day
+        }
+    }
+
+    /// The theme now.
+    #[must_use]
+    pub fn current(&self) -> SceneTheme {
+        self.theme_at(local_minute_of_day())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_theme_follows_the_clock_and_dark_mode() {
+        let auto = ThemeProvider::default();
+        assert_eq!(auto.theme_at(12 * 60), SceneTheme::Day);
+        assert_eq!(auto.theme_at(19 * 60), SceneTheme::Night);
+        assert_eq!(auto.theme_at(2 * 60 + 30), SceneTheme::Night);
+        assert_eq!(auto.theme_at(6 * 60), SceneTheme::Day);
+
+        let dark = ThemeProvider {
+            dark_mode: true,
+            ..auto
+        };
+        assert_eq!(dark.theme_at(12 * 60), SceneTheme::Night);
+
+        let day = ThemeProvider {
+            choice: ThemeChoice::Day,
+            dark_mode: true,
+            ..auto
+        };
+        assert_eq!(day.theme_at(23 * 60), SceneTheme::Day);
+        assert_eq!(ThemeChoice::from_key("night"), Some(ThemeChoice::Night));
+    }
+
+    #[test]
+    fn night_palette_is_dark() {
+        let luma = |c: [f32; 4]| 0.2126 * c[0] + 0.7152 * c[1] + 0.0722 * c[2];
+        let (day, night) = (SceneTheme::Day.palette(), SceneTheme::Night.palette());
+        assert!(luma(night.background) < 0.1 && luma(day.background) > 0.5);
+        assert!(luma(night.wall) < luma(day.wall));
+    }
+}
@@ -0,0 +1,240 @@
+//! Software rasterizer for egui output.
+//!
+//! Fills the shapes of an egui pass into an RGBA buffer on the CPU, so a
+//! page can be painted without a window or a GPU (see
+//! [`crate::engine::headless`]). Shapes are tessellated by egui as usual
+//! and each triangle is filled at pixel centres, sampling the font atlas
+//! and any other texture the pass uploaded. Colours are premultiplied and
+//! blended in gamma space, as egui's own renderers do; anti-aliasing comes
+//! from egui's feathered edges.
+
+use std::collections::HashMap;
+
+use egui::epaint::{ClippedPrimitive, ImageData, ImageDelta, Primitive, TextureId, Vertex};
+use egui::{Color32, FullOutput, Rect};
+
+/// A texture uploaded during the pass.
+struct Texture {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color32>,
+}
+
+impl Texture {
+    /// Nearest texel at normalized `(u, v)`.
+    fn sample(&self, u: f32, v: f32) -> Color32 {
+        let x = ((u * self.width as f32) as usize).min(self.width.saturating_sub(1));
+        let y = ((v * self.height as f32) as usize).min(self.height.saturating_sub(1));
+        self.pixels
+            .get(y * self.width + x)
+            .copied()
+            .unwrap_or(Color32::TRANSPARENT)
+    }
+
+    /// Apply an upload: a whole image, or a patch at `delta.pos`.
+    fn apply(textures: &mut HashMap<TextureId, Self>, id: TextureId, delta: &ImageDelta) {
+        let (size, pixels): ([usize; 2], Vec<Color32>) = match delta.image {
+            ImageData::Color(ref image) => (image.size, image.pixels.clone()),
+            ImageData::Font(ref image) => (image.size, image.srgba_pixels(None).collect()),
+        };
+        let Some([x0, y0]) = delta.pos else {
+            textures.insert(
+                id,
+                Self {
+                    width: size[0],
+                    height: size[1],
+                    pixels,
+                },
+            );
+            return;
+        };
+        let Some(texture) = textures.get_mut(&id) else {
+            return;
+        };
+        for (row, line) in pixels.chunks_exact(size[0].max(1)).enumerate() {
+            let y = y0 + row;
+            if y >= texture.height {
+                break;
+            }
+            let width = line.len().min(texture.width.saturating_sub(x0));
+            let start = y * texture.width + x0;
+            texture.pixels[start..start + width].copy_from_slice(&line[..width]);
+        }
+    }
+}
+
+/// An RGBA image painted on the CPU.
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    pub width: usize,
+    pub height: usize,
+    /// Straight RGBA, rows top to bottom
+    pub rgba: Vec<u8>,
+}
+
+impl Canvas {
+    /// Opaque canvas filled with `background`.
+    #[must_use]
+    pub fn new(width: usize, height: usize, background: Color32) -> Self {
+        let [r, g, b, _] = background.to_array();
+        Self {
+            width,
+            height,
+            rgba: [r, g, b, 255].repeat(width * height),
+        }
+    }
+
+    /// Paint the shapes of a finished pass of `ctx`, in order.
+    pub fn paint(&mut self, ctx: &egui::Context, output: FullOutput) {
+        let mut textures = HashMap::new();
+        for (id, delta) in &output.textures_delta.set {
+            Texture::apply(&mut textures, *id, delta);
+        }
+        let primitives = ctx.tessellate(output.shapes, output.pixels_per_point);
+        let scale = output.pixels_per_point;
+        for ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in primitives
+        {
+            let Primitive::Mesh(mesh) = primitive else {
+                continue;
+            };
+            let texture = textures.get(&mesh.texture_id);
+            let clip = Rect::from_min_max(
+                (clip_rect.min.to_vec2() * scale).to_pos2(),
+                (clip_rect.max.to_vec2() * scale).to_pos2(),
+            );
+            for triangle in mesh.indices.chunks_exact(3) {
+                let corner = |i: u32| {
+                    let mut v = mesh.vertices[i as usize];
+                    v.pos = (v.pos.to_vec2() * scale).to_pos2();
+                    v
+                };
+                self.fill_triangle(
+                    [
+                        corner(triangle[0]),
+                        corner(triangle[1]),
+                        corner(triangle[2]),
+                    ],
+                    texture,
+                    clip,
+                );
+            }
+        }
+    }
+
+    /// Fill the pixels whose centres lie in the triangle, blending the
+    /// interpolated vertex colour times the texture over what is there.
+    fn fill_triangle(&mut self, [a, b, c]: [Vertex; 3], texture: Option<&Texture>, clip: Rect) {
+        let area = edge(a, b, c.pos.x, c.pos.y);
+        if area.abs() < f32::EPSILON {
+            return;
+        }
+        let min_x = a.pos.x.min(b.pos.x).min(c.pos.x).max(clip.min.x).max(0.0);
+        let max_x = a.pos.x.max(b.pos.x).max(c.pos.x).min(clip.max.x);
+        let min_y = a.pos.y.min(b.pos.y).min(c.pos.y).max(clip.min.y).max(0.0);
+        let max_y = a.pos.y.max(b.pos.y).max(c.pos.y).min(clip.max.y);
+        if min_x >= max_x || min_y >= max_y {
+            return;
+        }
+        let (x0, x1) = (min_x as usize, (max_x.ceil() as usize).min(self.width));
+        let (y0, y1) = (min_y as usize, (max_y.ceil() as usize).min(self.height));
+
+        for y in y0..y1 {
+            let py = y as f32 + 0.5;
+            for x in x0..x1 {
+                let px = x as f32 + 0.5;
+                let wa = edge(b, c, px, py) / area;
+                let wb = edge(c, a, px, py) / area;
+                let wc = 1.0 - wa - wb;
+                if wa < 0.0 || wb < 0.0 || wc < 0.0 {
+                    continue;
+                }
+                let mix = |f: fn(&Vertex) -> f32| wa * f(&a) + wb * f(&b) + wc * f(&c);
+                let tint = [
+                    mix(|v| f32::from(v.color.r())),
+                    mix(|v| f32::from(v.color.g())),
+                    mix(|v| f32::from(v.color.b())),
+                    mix(|v| f32::from(v.color.a())),
+                ];
+                let texel = texture
+                    .map_or(Color32::WHITE, |t| {
+                        t.sample(mix(|v| v.uv.x), mix(|v| v.uv.y))
+                    })
+                    .to_array();
+                self.blend(x, y, tint, texel);
+            }
+        }
+    }
+
+    /// Blend premultiplied `tint × texel` over the pixel at `(x, y)`.
+    fn blend(&mut self, x: usize, y: usize, tint: [f32; 4], texel: [u8; 4]) {
+        let src: [f32; 4] = std::array::from_fn(|i| tint[i] * f32::from(texel[i]) / 255.0);
+        let keep = 1.0 - src[3] / 255.0;
+        let i = (y * self.width + x) * 4;
+        for (dst, src) in self.rgba[i..i + 3].iter_mut().zip(src) {
+            *dst = f32::from(*dst).mul_add(keep, src).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// Twice the signed area of `(a, b, (x, y))`.
+fn edge(a: Vertex, b: Vertex, x: f32, y: f32) -> f32 {
+    (b.pos.x - a.pos.x).mul_add(y - a.pos.y, -(b.pos.y - a.pos.y) * (x - a.pos.x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pass(ctx: &egui::Context, mut add: impl FnMut(&mut egui::Ui)) -> FullOutput {
+        let input = egui::RawInput {
+            screen_rect: Some(Rect::from_min_size(
+                egui::Pos2::ZERO,
+                egui::vec2(64.0, 32.0),
+            )),
+            ..egui::RawInput::default()
+        };
+        ctx.run(input, |ctx| {
+            egui::CentralPanel::default()
+                .frame(egui::Frame::none())
+                .show(ctx, &mut add);
+        })
+    }
+
+    #[test]
+    fn fills_rectangles() {
+        let ctx = egui::Context::default();
+        let output = pass(&ctx, |ui| {
+            ui.painter().rect_filled(
+                Rect::from_min_size(egui::pos2(8.0, 8.0), egui::vec2(16.0, 16.0)),
+                0.0,
+                Color32::from_rgb(200, 0, 0),
+            );
+        });
+        let mut canvas = Canvas::new(64, 32, Color32::WHITE);
+        canvas.paint(&ctx, output);
+        let px = |x: usize, y: usize| &canvas.rgba[(y * 64 + x) * 4..][..4];
+        assert_eq!(px(16, 16), [200, 0, 0, 255]);
+        assert_eq!(px(40, 16), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn draws_text_from_the_font_atlas() {
+        let ctx = egui::Context::default();
+        let output = pass(&ctx, |ui| {
+            ui.painter().text(
+                egui::pos2(2.0, 2.0),
+                egui::Align2::LEFT_TOP,
+                "Hi",
+                egui::FontId::proportional(20.0),
+                Color32::BLACK,
+            );
+        });
+        let mut canvas = Canvas::new(64, 32, Color32::WHITE);
+        canvas.paint(&ctx, output);
+        let dark = canvas.rgba.chunks_exact(4).filter(|p| p[0] < 128).count();
+        assert!(dark > 10, "{dark} dark pixels");
+    }
+}
@@ -0,0 +1,463 @@
+//! Scene snapshots and the shareable `.aliscene` file format.
+//!
+//! A [`SceneSnapshot`] is a generated 3-D scene together with the camera
+//! it was viewed from and the page it came from, so it can be shown again
+//! without fetching or laying out the page. Snapshots are written in a
+//! compact binary format: the magic bytes `ALSC`, a format version, then
+//! little-endian fields with LEB128 lengths and counts. Files written by
+//! one ALICE instance open in any other of the same or a newer version.
+
+use std::path::Path;
+
+use crate::render::sdf_ui::{SdfPrimitive, SdfScene};
+
+/// File extension of scene files.
+pub const EXTENSION: &str = "aliscene";
+
+const MAGIC: &[u8; 4] = b"ALSC";
+const VERSION: u16 = 1;
+
+/// Camera placement of a snapshot (orbit around `target`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SceneCamera {
+    pub azimuth: f32,
+    pub elevation: f32,
+    pub distance: f32,
+    pub target: [f32; 3],
+}
+
+#[cfg(feature = "sdf-render")]
+impl From<crate::render::sdf_renderer::CameraParams> for SceneCamera {
+    fn from(c: crate::render::sdf_renderer::CameraParams) -> Self {
+        Self {
+            azimuth: c.azimuth,
+            elevation: c.elevation,
+            distance: c.distance,
+            target: c.target,
+        }
+    }
+}
+
+#[cfg(feature = "sdf-render")]
+impl From<SceneCamera> for crate::render::sdf_renderer::CameraParams {
+    fn from(c: SceneCamera) -> Self {
+        Self {
+            azimuth: c.azimuth,
+            elevation: c.elevation,
+            distance: c.distance,
+            target: c.target,
+            mode: crate::render::sdf_renderer::CameraMode::Orbit,
+        }
+    }
+}
+
+/// A named, self-contained 3-D scene.
+#[derive(Debug, Clone)]
+pub struct SceneSnapshot {
+    pub name: String,
+    /// Page the scene was generated from
+    pub url: String,
+    /// Unix time the snapshot was taken (seconds)
+    pub created: u64,
+    pub camera: SceneCamera,
+    pub scene: SdfScene,
+}
+
+/// Error reading a scene file.
+#[derive(Debug)]
+pub struct SceneFileError {
+    pub message: String,
+}
+
+impl std::fmt::Display for SceneFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for SceneFileError {}
+
+impl SceneSnapshot {
+    /// Encode as scene-file bytes.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut w = Writer(Vec::with_capacity(64 + self.scene.primitives.len() * 40));
+        w.0.extend_from_slice(MAGIC);
+        w.0.extend_from_slice(&VERSION.to_le_bytes());
+        w.str(&self.name);
+        w.str(&self.url);
+        w.len(usize::try_from(self.created).unwrap_or(usize::MAX));
+        let c = &self.camera;
+        w.floats(&[c.azimuth, c.elevation, c.distance]);
+        w.floats(&c.target);
+        w.floats(&self.scene.background_color);
+        w.len(self.scene.primitives.len());
+        for prim in &self.scene.primitives {
+            w.primitive(prim);
+        }
+        w.0
+    }
+
+    /// Decode scene-file bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SceneFileError` if the data is not a scene file, comes from
+    /// a newer format version, or is truncated or corrupt.
+    pub fn decode(bytes: &[u8]) -> Result<Self, SceneFileError> {
+        let mut r = Reader { bytes, pos: 0 };
+        if r.take(4)? != MAGIC {
+            return Err(error("not an ALICE scene file"));
+        }
+        let version = u16::from_le_bytes([r.u8()?, r.u8()?]);
+        if version > VERSION {
+            return Err(error(format!(
+                "scene file version {version} is newer than supported ({VERSION})"
+            )));
+        }
+        let name = r.str()?;
+        let url = r.str()?;
+        let created = r.len()? as u64;
+        let [azimuth, elevation, distance] = r.floats()?;
+        let camera = SceneCamera {
+            azimuth,
+            elevation,
+            distance,
+            target: r.floats()?,
+        };
+        let background_color = r.floats()?;
+        let count = r.len()?;
+        // Every primitive takes at least a tag byte and a color
+        if count > r.remaining() / 17 {
+            return Err(error("primitive count exceeds file size"));
+        }
+        let mut primitives = Vec::with_capacity(count);
+        for _ in 0..count {
+            primitives.push(r.primitive()?);
+        }
+        Ok(Self {
+            name,
+            url,
+            created,
+            camera,
+            scene: SdfScene {
+                primitives,
+                background_color,
+            },
+        })
+    }
+
+    /// Write the snapshot to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.encode())
+    }
+
+    /// Read a snapshot from `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file cannot be read, or one of kind
+    /// `InvalidData` if it is not a valid scene file.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::decode(&bytes).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+fn error(message: impl Into<String>) -> SceneFileError {
+    SceneFileError {
+        message: message.into(),
+    }
+}
+
+// ── Encoding ──
+
+// Primitive tags; never reuse a retired value
+const TAG_ROUNDED_BOX: u8 = 0;
+const TAG_PLANE: u8 = 1;
+const TAG_TEXT_LABEL: u8 = 2;
+const TAG_LINE: u8 = 3;
+const TAG_SPHERE: u8 = 4;
+const TAG_BILLBOARD: u8 = 5;
+const TAG_TORUS: u8 = 6;
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    /// LEB128 unsigned
+    fn len(&mut self, mut n: usize) {
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+            if n == 0 {
+                self.0.push(byte);
+                return;
+            }
+            self.0.push(byte | 0x80);
+        }
+    }
+
+    fn str(&mut self, s: &str) {
+        self.len(s.len());
+        self.0.extend_from_slice(s.as_bytes());
+    }
+
+    fn floats(&mut self, values: &[f32]) {
+        for v in values {
+            self.0.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+
+    fn primitive(&mut self, prim: &SdfPrimitive) {
+        match prim {
+            SdfPrimitive::RoundedBox {
+                center,
+                size,
+                radius,
+                color,
+            } => {
+                self.0.push(TAG_ROUNDED_BOX);
+                self.floats(center);
+                self.floats(size);
+                self.floats(&[*radius]);
+                self.floats(color);
+            }
+            SdfPrimitive::Plane {
+                center,
+                size,
+                color,
+            } => {
+                self.0.push(TAG_PLANE);
+                self.floats(center);
+                self.floats(size);
+                self.floats(color);
+            }
+            SdfPrimitive::TextLabel {
+                position,
+                text,
+                font_size,
+                color,
+            } => {
+                self.0.push(TAG_TEXT_LABEL);
+                self.floats(position);
+                self.str(text);
+                self.floats(&[*font_size]);
+                self.floats(color);
+            }
+            SdfPrimitive::Line {
+                start,
+                end,
+                thickness,
+                color,
+            } => {
+                self.0.push(TAG_LINE);
+                self.floats(start);
+                self.floats(end);
+                self.floats(&[*thickness]);
+                self.floats(color);
+            }
+            SdfPrimitive::Sphere {
+                center,
+                radius,
+                color,
+            } => {
+                self.0.push(TAG_SPHERE);
+                self.floats(center);
+                self.floats(&[*radius]);
+                self.floats(color);
+            }
+            SdfPrimitive::Billboard {
+                position,
+                size,
+                text,
+                color,
+                opacity,
+            } => {
+                self.0.push(TAG_BILLBOARD);
+                self.floats(position);
+                self.floats(size);
+                self.str(text);
+                self.floats(color);
+                self.floats(&[*opacity]);
+            }
+            SdfPrimitive::Torus {
+                center,
+                major_radius,
+                minor_radius,
+                axis,
+                color,
+            } => {
+                self.0.push(TAG_TORUS);
+                self.floats(center);
+                self.floats(&[*major_radius, *minor_radius]);
+                self.floats(axis);
+                self.floats(color);
+            }
+        }
+    }
+}
+
+// ── Decoding ──
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    const fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take(&mut self, n: usize) -> Result<&[u8], SceneFileError> {
+        if n > self.remaining() {
+            return Err(error("scene file is truncated"));
+        }
+        let slice = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, SceneFileError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn len(&mut self) -> Result<usize, SceneFileError> {
+        let mut n = 0usize;
+        for shift in (0..usize::BITS).step_by(7) {
+            let byte = self.u8()?;
+            n |= usize::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(error("malformed length"))
+    }
+
+    fn str(&mut self) -> Result<String, SceneFileError> {
+        let len = self.len()?;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| error("text is not UTF-8"))
+    }
+
+    fn floats<const N: usize>(&mut self) -> Result<[f32; N], SceneFileError> {
+        let mut out = [0.0; N];
+        for v in &mut out {
+            let b = self.take(4)?;
+            *v = f32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+        }
+        Ok(out)
+    }
+
+    fn float(&mut self) -> Result<f32, SceneFileError> {
+        Ok(self.floats::<1>()?[0])
+    }
+
+    fn primitive(&mut self) -> Result<SdfPrimitive, SceneFileError> {
+        Ok(match self.u8()? {
+            TAG_ROUNDED_BOX => SdfPrimitive::RoundedBox {
+                center: self.floats()?,
+                size: self.floats()?,
+                radius: self.float()?,
+                color: self.floats()?,
+            },
+            TAG_PLANE => SdfPrimitive::Plane {
+                center: self.floats()?,
+                size: self.floats()?,
+                color: self.floats()?,
+            },
+            TAG_TEXT_LABEL => SdfPrimitive::TextLabel {
+                position: self.floats()?,
+                text: self.str()?,
+                font_size: self.float()?,
+                color: self.floats()?,
+            },
+            TAG_LINE => SdfPrimitive::Line {
+                start: self.floats()?,
+                end: self.floats()?,
+                thickness: self.float()?,
+                color: self.floats()?,
+            },
+            TAG_SPHERE => SdfPrimitive::Sphere {
+                center: self.floats()?,
+                radius: self.float()?,
+                color: self.floats()?,
+            },
+            TAG_BILLBOARD => SdfPrimitive::Billboard {
+                position: self.floats()?,
+                size: self.floats()?,
+                text: self.str()?,
+                color: self.floats()?,
+                opacity: self.float()?,
+            },
+            TAG_TORUS => SdfPrimitive::Torus {
+                center: self.floats()?,
+                major_radius: self.float()?,
+                minor_radius: self.float()?,
+                axis: self.floats()?,
+                color: self.floats()?,
+            },
+            tag => return Err(error(format!("unknown primitive type {tag}"))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+    use crate::render::layout::compute_layout;
+    use crate::render::spatial::{layout_to_spatial, SpatialConfig};
+
+    fn snapshot() -> SceneSnapshot {
+        let dom = parse_html(
+            "<html><body><h1>Scene</h1><p>日本語 text</p><a href=\"/x\">link</a><hr></body></html>",
+            "https://example.com/",
+        );
+        let layout = compute_layout(&dom.root, 800.0);
+        SceneSnapshot {
+            name: "Example".into(),
+            url: "https://example.com/".into(),
+            created: 1_700_000_000,
+            camera: SceneCamera {
+                azimuth: 0.3,
+                elevation: 0.6,
+                distance: 3.0,
+                target: [0.0, -1.5, 0.25],
+            },
+            scene: layout_to_spatial(&layout, &SpatialConfig::default()),
+        }
+    }
+
+    #[test]
+    fn snapshot_round_trips() {
+        let snap = snapshot();
+        assert!(!snap.scene.primitives.is_empty());
+        let bytes = snap.encode();
+        let back = SceneSnapshot::decode(&bytes).unwrap();
+        assert_eq!(back.name, snap.name);
+        assert_eq!(back.url, snap.url);
+        assert_eq!(back.created, snap.created);
+        assert_eq!(back.camera, snap.camera);
+        assert_eq!(back.scene.background_color, snap.scene.background_color);
+        assert_eq!(
+            format!("{:?}", back.scene.primitives),
+            format!("{:?}", snap.scene.primitives)
+        );
+    }
+
+    #[test]
+    fn corrupt_files_are_rejected() {
+        let bytes = snapshot().encode();
+        assert!(SceneSnapshot::decode(b"PNG\0").is_err());
+        assert!(SceneSnapshot::decode(&bytes[..bytes.len() - 3]).is_err());
+
+        let mut newer = bytes.clone();
+        newer[4] = 0xff;
+        let err = SceneSnapshot::decode(&newer).unwrap_err();
+        assert!(err.message.contains("newer"));
+    }
+}
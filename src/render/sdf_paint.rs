@@ -2,11 +2,23 @@
 //!
 //! Draws `PaintElements` using egui's Painter API with smooth hover
 //! animations, drop shadows, and rounded corners inspired by SDF rendering.
+//! Headings, text and links can be drag-selected and copied (see
+//! [`crate::render::selection`]). The keyboard focus target, if any, is
+//! ringed and scrolled into view (see [`crate::render::tab_order`]), and
+//! link hints, when shown, are drawn over their targets. Right-clicks open
+//! the page context menu (see [`crate::render::context_menu`]).
 
-use egui::{Color32, FontId, Pos2, Rect, Rounding, Stroke, TextureHandle, Vec2};
+use egui::{Color32, FontId, Galley, Pos2, Rect, Rounding, Stroke, TextureHandle, Vec2};
 use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
 
+use crate::net::image::{preview_key, Progress};
+use crate::render::context_menu::{self, MenuAction, MenuTarget, SelectorBox};
+use crate::render::cost::{heat_color, ElementCost};
+use crate::render::layout_debug::{box_at, DebugBox};
 use crate::render::sdf_ui::{PaintElement, PaintKind};
+use crate::render::selection::{hit_block, Hit, SelectionState, TextPos};
 
 /// Theme colors for SDF paint rendering.
 struct Theme {
@@ -21,6 +33,9 @@ struct Theme {
     img_bg: Color32,
     img_border: Color32,
     img_text: Color32,
+    selection: Color32,
+    focus_ring: Color32,
+    inspected: Color32,
 }
 
 impl Theme {
@@ -37,6 +52,9 @@ impl Theme {
             img_bg: Color32::from_rgb(235, 235, 240),
             img_border: Color32::from_rgb(200, 200, 205),
             img_text: Color32::from_rgb(160, 160, 165),
+            selection: Color32::from_rgba_premultiplied(0, 28, 60, 70),
+            focus_ring: Color32::from_rgb(225, 110, 0),
+            inspected: Color32::from_rgb(150, 60, 200),
         }
     }
 
@@ -53,6 +71,9 @@ impl Theme {
             img_bg: Color32::from_rgb(40, 40, 50),
             img_border: Color32::from_rgb(60, 60, 70),
             img_text: Color32::from_rgb(100, 100, 110),
+            selection: Color32::from_rgba_premultiplied(22, 45, 72, 90),
+            focus_ring: Color32::from_rgb(255, 170, 60),
+            inspected: Color32::from_rgb(200, 130, 255),
         }
     }
 }
@@ -60,6 +81,52 @@ impl Theme {
 /// Persistent state for SDF paint rendering.
 pub struct SdfPaintState {
     hovered_id: Option<usize>,
+    /// Resource-timing heat map drawn over the page, if enabled
+    pub overlay: Option<Vec<ElementCost>>,
+    /// Layout boxes outlined over the page, if the layout debug overlay is
+    /// on
+    pub layout_boxes: Option<Vec<DebugBox>>,
+    /// Elements that "Block this element" can name, in page coordinates;
+    /// set by the app
+    pub menu_boxes: Vec<SelectorBox>,
+    /// What the open context menu was opened on
+    menu_target: Option<MenuTarget>,
+    /// Entry chosen from the context menu; taken by the app
+    pub menu_action: Option<MenuAction>,
+    /// `article` / `section` boxes offered for "Focus in 3D", in page
+    /// coordinates; set by the app (empty without a 3-D view)
+    pub focus_blocks: Vec<[f32; 4]>,
+    /// Block whose context menu is open
+    menu_block: Option<[f32; 4]>,
+    /// Block the user chose "Focus in 3D" for; taken by the app
+    pub focus_request: Option<[f32; 4]>,
+    /// Download progress of images still loading, refreshed by the app
+    pub image_progress: HashMap<String, Progress>,
+    /// Drag-selected text
+    pub selection: SelectionState,
+    /// Box of the keyboard focus target, in page coordinates; set by the app
+    pub focus_ring: Option<[f32; 4]>,
+    /// Scroll `focus_ring` into view on the next frame
+    pub scroll_to_focus: bool,
+    /// Box of the node picked in the DOM tree inspector, in page
+    /// coordinates; set by the app
+    pub inspected: Option<[f32; 4]>,
+    /// Scroll `inspected` into view on the next frame
+    pub scroll_to_inspected: bool,
+    /// Page y to scroll to the top of the view on the next frame; set by
+    /// the app
+    pub scroll_to: Option<f32>,
+    /// Link hint codes and their targets' boxes in page coordinates; set
+    /// by the app
+    pub hints: Vec<([f32; 4], String)>,
+    /// Letters of the hint codes already typed
+    pub hint_typed: usize,
+    /// Page height to scroll through even if the elements end earlier
+    /// (the rest of a lazily laid out page)
+    pub min_height: f32,
+    /// Top and bottom of the region shown in the last frame, in page
+    /// coordinates
+    pub visible: Option<(f32, f32)>,
 }
 
 impl Default for SdfPaintState {
@@ -70,8 +137,39 @@ impl Default for SdfPaintState {
 
 impl SdfPaintState {
     #[must_use]
-    pub const fn new() -> Self {
-        Self { hovered_id: None }
+    pub fn new() -> Self {
+        Self {
+            hovered_id: None,
+            overlay: None,
+            layout_boxes: None,
+            menu_boxes: Vec::new(),
+            menu_target: None,
+            menu_action: None,
+            focus_blocks: Vec::new(),
+            menu_block: None,
+            focus_request: None,
+            image_progress: HashMap::new(),
+            selection: SelectionState::default(),
+            focus_ring: None,
+            scroll_to_focus: false,
+            inspected: None,
+            scroll_to_inspected: false,
+            scroll_to: None,
+            hints: Vec::new(),
+            hint_typed: 0,
+            min_height: 0.0,
+            visible: None,
+        }
+    }
+
+    /// Href of the link under the pointer in the last frame.
+    #[must_use]
+    pub fn hovered_link<'a>(&self, elements: &'a [PaintElement]) -> Option<&'a str> {
+        let id = self.hovered_id?;
+        elements
+            .iter()
+            .find(|e| e.id == id && e.kind == PaintKind::Link)
+            .and_then(|e| e.href.as_deref())
     }
 
     /// Draw all paint elements and return any clicked link href.
@@ -92,46 +190,60 @@ impl SdfPaintState {
         let total_height = elements
             .iter()
             .map(|e| e.rect[1] + e.rect[3])
-            .fold(0.0f32, f32::max)
+            .fold(self.min_height, f32::max)
             + 32.0;
 
         let mut clicked_href: Option<String> = None;
 
-        egui::ScrollArea::vertical().show(ui, |ui: &mut egui::Ui| {
-            let (full_rect, response) = ui.allocate_exact_size(
-                Vec2::new(available_width, total_height),
-                egui::Sense::click().union(egui::Sense::hover()),
-            );
-
-            let painter = ui.painter_at(full_rect);
-            let origin = full_rect.min;
-            let theme = if dark_mode {
-                Theme::dark()
+        // Selectable blocks, in reading order, and each element's block
+        let blocks: Vec<&PaintElement> = elements.iter().filter(|e| is_selectable(e)).collect();
+        let mut block_of = Vec::with_capacity(elements.len());
+        let mut next_block = 0;
+        for elem in elements {
+            if is_selectable(elem) {
+                block_of.push(Some(next_block));
+                next_block += 1;
             } else {
-                Theme::light()
-            };
-
-            // Page background
-            painter.rect_filled(full_rect, Rounding::ZERO, theme.page_bg);
+                block_of.push(None);
+            }
+        }
 
-            let mouse_pos = response.hover_pos();
+        // Dragging selects text, so the page scrolls by wheel only
+        egui::ScrollArea::vertical()
+            .drag_to_scroll(false)
+            .show(ui, |ui: &mut egui::Ui| {
+                let (full_rect, response) = ui.allocate_exact_size(
+                    Vec2::new(available_width, total_height),
+                    egui::Sense::click_and_drag(),
+                );
 
-            // Determine hovered element (foreground first, then cards)
-            self.hovered_id = None;
-            if let Some(pos) = mouse_pos {
-                for elem in elements.iter().rev() {
-                    if elem.kind == PaintKind::Card {
-                        continue;
-                    }
-                    let r = elem_rect(elem, origin);
-                    if r.contains(pos) {
-                        self.hovered_id = Some(elem.id);
-                        break;
-                    }
+                let painter = ui.painter_at(full_rect);
+                let origin = full_rect.min;
+                let clip = ui.clip_rect();
+                self.visible = Some((clip.min.y - origin.y, clip.max.y - origin.y));
+                if let Some(y) = self.scroll_to.take() {
+                    let top = Rect::from_min_size(
+                        origin + Vec2::new(0.0, y),
+                        Vec2::new(full_rect.width(), clip.height()),
+                    );
+                    ui.scroll_to_rect(top, Some(egui::Align::TOP));
                 }
-                if self.hovered_id.is_none() {
+                let theme = if dark_mode {
+                    Theme::dark()
+                } else {
+                    Theme::light()
+                };
+
+                // Page background
+                painter.rect_filled(full_rect, Rounding::ZERO, theme.page_bg);
+
+                let mouse_pos = response.hover_pos();
+
+                // Determine hovered element (foreground first, then cards)
+                self.hovered_id = None;
+                if let Some(pos) = mouse_pos {
                     for elem in elements.iter().rev() {
-                        if elem.kind != PaintKind::Card {
+                        if elem.kind == PaintKind::Card {
                             continue;
                         }
                         let r = elem_rect(elem, origin);
@@ -140,74 +252,463 @@ impl SdfPaintState {
                             break;
                         }
                     }
+                    if self.hovered_id.is_none() {
+                        for elem in elements.iter().rev() {
+                            if elem.kind != PaintKind::Card {
+                                continue;
+                            }
+                            let r = elem_rect(elem, origin);
+                            if r.contains(pos) {
+                                self.hovered_id = Some(elem.id);
+                                break;
+                            }
+                        }
+                    }
                 }
-            }
 
-            let mut animating = false;
+                self.handle_selection(ctx, &response, &blocks, origin);
+                if let Some(id) = self.hovered_id {
+                    let over_text = elements
+                        .iter()
+                        .any(|e| e.id == id && is_selectable(e) && e.kind != PaintKind::Link);
+                    if over_text {
+                        ctx.set_cursor_icon(egui::CursorIcon::Text);
+                    }
+                }
 
-            // Draw each element
-            for elem in elements {
-                let rect = elem_rect(elem, origin);
+                let mut animating = false;
 
-                // Cull offscreen
-                if rect.max.y < full_rect.min.y || rect.min.y > full_rect.max.y {
-                    continue;
-                }
+                // Draw each element
+                for (elem, block) in elements.iter().zip(&block_of) {
+                    let rect = elem_rect(elem, origin);
 
-                let is_hovered = self.hovered_id == Some(elem.id);
-                let hover_t = ctx.animate_value_with_time(
-                    egui::Id::new(("sdf_h", elem.id)),
-                    if is_hovered { 1.0 } else { 0.0 },
-                    0.15,
-                );
-                if hover_t > 0.001 && hover_t < 0.999 {
-                    animating = true;
-                }
+                    // Cull offscreen
+                    if rect.max.y < full_rect.min.y || rect.min.y > full_rect.max.y {
+                        continue;
+                    }
 
-                match elem.kind {
-                    PaintKind::Card => draw_card(&painter, rect, elem, hover_t, &theme),
-                    PaintKind::Heading => draw_heading(&painter, ctx, rect, elem, hover_t, &theme),
-                    PaintKind::Text => draw_text(&painter, ctx, rect, elem, &theme),
-                    PaintKind::Link => {
-                        draw_link(&painter, ctx, rect, elem, hover_t, &theme);
-                        if is_hovered {
-                            ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
+                    // Selection highlight under the text
+                    if let (Some(block), Some(sel), Some(text)) =
+                        (*block, self.selection.selection(), elem.text.as_deref())
+                    {
+                        if let Some(range) = sel.range_in(block, text.chars().count()) {
+                            draw_selection(&painter, ctx, rect, elem, range, &theme);
                         }
                     }
-                    PaintKind::Button => {
-                        draw_button(&painter, ctx, rect, elem, hover_t);
-                        if is_hovered {
-                            ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
+
+                    let is_hovered = self.hovered_id == Some(elem.id);
+                    let hover_t = ctx.animate_value_with_time(
+                        egui::Id::new(("sdf_h", elem.id)),
+                        if is_hovered { 1.0 } else { 0.0 },
+                        0.15,
+                    );
+                    if hover_t > 0.001 && hover_t < 0.999 {
+                        animating = true;
+                    }
+
+                    match elem.kind {
+                        PaintKind::Card => draw_card(&painter, rect, elem, hover_t, &theme),
+                        PaintKind::Heading => {
+                            draw_heading(&painter, ctx, rect, elem, hover_t, &theme)
+                        }
+                        PaintKind::Text => draw_text(&painter, ctx, rect, elem, &theme),
+                        PaintKind::Link => {
+                            draw_link(&painter, ctx, rect, elem, hover_t, &theme);
+                            if is_hovered {
+                                ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
+                            }
+                        }
+                        PaintKind::Button => {
+                            draw_button(&painter, ctx, rect, elem, hover_t);
+                            if is_hovered {
+                                ctx.set_cursor_icon(egui::CursorIcon::PointingHand);
+                            }
+                        }
+                        PaintKind::Separator => draw_separator(&painter, rect, &theme),
+                        PaintKind::ImagePlaceholder => {
+                            let progress = elem
+                                .image_url
+                                .as_ref()
+                                .and_then(|url| self.image_progress.get(url));
+                            draw_image_placeholder(
+                                &painter, rect, elem, hover_t, &theme, textures, progress,
+                            );
                         }
                     }
-                    PaintKind::Separator => draw_separator(&painter, rect, &theme),
-                    PaintKind::ImagePlaceholder => {
-                        draw_image_placeholder(&painter, rect, elem, hover_t, &theme, textures);
+                }
+
+                // Keyboard focus ring
+                if let Some([x, y, w, h]) = self.focus_ring {
+                    let rect = Rect::from_min_size(origin + Vec2::new(x, y), Vec2::new(w, h));
+                    painter.rect_stroke(
+                        rect.expand(3.0),
+                        Rounding::same(4.0),
+                        Stroke::new(2.0, theme.focus_ring),
+                    );
+                    if std::mem::take(&mut self.scroll_to_focus) {
+                        ui.scroll_to_rect(rect, Some(egui::Align::Center));
                     }
                 }
-            }
 
-            // Handle click
-            if response.clicked() {
-                if let Some(pos) = mouse_pos {
-                    for elem in elements.iter().rev() {
-                        if elem.href.is_some() {
-                            let r = elem_rect(elem, origin);
-                            if r.contains(pos) {
-                                clicked_href.clone_from(&elem.href);
-                                break;
+                // Node picked in the DOM tree inspector
+                if let Some([x, y, w, h]) = self.inspected {
+                    let rect = Rect::from_min_size(origin + Vec2::new(x, y), Vec2::new(w, h));
+                    painter.rect(
+                        rect,
+                        Rounding::same(2.0),
+                        theme.inspected.gamma_multiply(0.15),
+                        Stroke::new(1.5, theme.inspected),
+                    );
+                    if std::mem::take(&mut self.scroll_to_inspected) {
+                        ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                    }
+                }
+
+                // Link hints
+                for ([x, y, _, _], code) in &self.hints {
+                    draw_hint(&painter, origin + Vec2::new(*x, *y), code, self.hint_typed);
+                }
+
+                // Resource-timing heat map
+                if let Some(ref costs) = self.overlay {
+                    draw_cost_overlay(&painter, ctx, ui.layer_id(), costs, origin, mouse_pos);
+                }
+
+                // Layout debug overlay
+                if let Some(ref boxes) = self.layout_boxes {
+                    draw_box_overlay(&painter, ctx, ui.layer_id(), boxes, origin, mouse_pos);
+                }
+
+                // Context menu: link, image, text and element under the
+                // pointer, and the block around them
+                if response.secondary_clicked() {
+                    let page = mouse_pos.map(|pos| pos - origin);
+                    self.menu_target = page
+                        .map(|p| MenuTarget::at_point(elements, &self.menu_boxes, p.x, p.y))
+                        .filter(|t| !t.is_empty());
+                    self.menu_block =
+                        page.and_then(|p| innermost_block(&self.focus_blocks, p.x, p.y));
+                }
+                if self.menu_target.is_some() || self.menu_block.is_some() {
+                    let (target, block) = (self.menu_target.clone(), self.menu_block);
+                    let menu = response.context_menu(|ui| {
+                        if let Some(ref target) = target {
+                            if let Some(action) = context_menu::items(ui, target) {
+                                self.menu_action = Some(action);
+                            }
+                        }
+                        if let Some(rect) = block {
+                            if target.is_some() {
+                                ui.separator();
+                            }
+                            if ui.button("Focus in 3D").clicked() {
+                                self.focus_request = Some(rect);
+                                ui.close_menu();
                             }
                         }
+                    });
+                    if menu.is_none() {
+                        self.menu_target = None;
+                        self.menu_block = None;
                     }
                 }
-            }
 
-            if animating {
-                ctx.request_repaint();
+                // Handle click (a plain click also drops the selection)
+                if response.clicked() {
+                    self.selection.clear();
+                    if let Some(pos) = mouse_pos {
+                        for elem in elements.iter().rev() {
+                            if elem.href.is_some() {
+                                let r = elem_rect(elem, origin);
+                                if r.contains(pos) {
+                                    clicked_href.clone_from(&elem.href);
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if animating {
+                    ctx.request_repaint();
+                }
+            });
+
+        clicked_href
+    }
+}
+
+/// Link hint badge colours: background, border, letters still to type,
+/// letters typed.
+const HINT_BG: Color32 = Color32::from_rgb(255, 224, 90);
+const HINT_BORDER: Color32 = Color32::from_rgb(190, 150, 20);
+const HINT_TEXT: Color32 = Color32::from_rgb(40, 30, 0);
+const HINT_TYPED: Color32 = Color32::from_rgb(170, 140, 60);
+
+/// Draw a link hint badge for `code` with its top-left corner at `pos`,
+/// the first `typed` letters dimmed. Shared with the Flat view.
+pub fn draw_hint(painter: &egui::Painter, pos: Pos2, code: &str, typed: usize) {
+    let font = FontId::monospace(11.0);
+    let upper = code.to_uppercase();
+    let split = typed.min(upper.len());
+    let mut job = egui::text::LayoutJob::default();
+    job.append(
+        &upper[..split],
+        0.0,
+        egui::TextFormat::simple(font.clone(), HINT_TYPED),
+    );
+    job.append(
+        &upper[split..],
+        0.0,
+        egui::TextFormat::simple(font, HINT_TEXT),
+    );
+    let galley = painter.layout_job(job);
+    let rect = Rect::from_min_size(pos, galley.size()).expand2(Vec2::new(3.0, 1.0));
+    let rect = rect.translate(Vec2::new(3.0, 1.0));
+    painter.rect(
+        rect,
+        Rounding::same(3.0),
+        HINT_BG,
+        Stroke::new(1.0, HINT_BORDER),
+    );
+    painter.galley(rect.min + Vec2::new(3.0, 1.0), galley, HINT_TEXT);
+}
+
+/// Smallest of `blocks` containing the page point (`x`, `y`).
+fn innermost_block(blocks: &[[f32; 4]], x: f32, y: f32) -> Option<[f32; 4]> {
+    blocks
+        .iter()
+        .filter(|b| x >= b[0] && x <= b[0] + b[2] && y >= b[1] && y <= b[1] + b[3])
+        .min_by(|a, b| (a[2] * a[3]).total_cmp(&(b[2] * b[3])))
+        .copied()
+}
+
+/// Tint every costed element by its heat; hovering one shows its numbers.
+fn draw_cost_overlay(
+    painter: &egui::Painter,
+    ctx: &egui::Context,
+    layer: egui::LayerId,
+    costs: &[ElementCost],
+    origin: Pos2,
+    mouse_pos: Option<Pos2>,
+) {
+    let rect_of = |c: &ElementCost| {
+        Rect::from_min_size(
+            Pos2::new(origin.x + c.rect[0], origin.y + c.rect[1]),
+            Vec2::new(c.rect[2], c.rect[3]),
+        )
+    };
+    for cost in costs {
+        let rect = rect_of(cost);
+        let color = color4(heat_color(cost.heat));
+        painter.rect_filled(rect, Rounding::same(2.0), color);
+        painter.rect_stroke(
+            rect,
+            Rounding::same(2.0),
+            Stroke::new(1.0, color.to_opaque()),
+        );
+    }
+
+    let Some(pos) = mouse_pos else { return };
+    let Some(hovered) = costs.iter().find(|c| rect_of(c).contains(pos)) else {
+        return;
+    };
+    egui::show_tooltip_at_pointer(ctx, layer, egui::Id::new("cost_overlay_tip"), |ui| {
+        ui.strong(format!("<{}>", hovered.tag));
+        ui.label(format!("Text: {} B", hovered.text_bytes));
+        if hovered.image_bytes > 0 {
+            ui.label(format!(
+                "Images: {:.1} KiB",
+                hovered.image_bytes as f32 / 1024.0
+            ));
+        }
+        ui.label(format!(
+            "Layout: {:.2} ms ({} nodes)",
+            hovered.layout_ms, hovered.nodes
+        ));
+        ui.label(format!(
+            "Heat: {:.0}% ({})",
+            hovered.heat * 100.0,
+            hovered.dominant.label()
+        ));
+    });
+}
+
+/// Outline every layout box in its category's colour; hovering one
+/// highlights it and shows its computed position and size.
+fn draw_box_overlay(
+    painter: &egui::Painter,
+    ctx: &egui::Context,
+    layer: egui::LayerId,
+    boxes: &[DebugBox],
+    origin: Pos2,
+    mouse_pos: Option<Pos2>,
+) {
+    let rect_of = |b: &DebugBox| {
+        Rect::from_min_size(
+            Pos2::new(origin.x + b.rect[0], origin.y + b.rect[1]),
+            Vec2::new(b.rect[2], b.rect[3]),
+        )
+    };
+    let color_of = |b: &DebugBox| {
+        let [r, g, bl] = b.category.rgb();
+        Color32::from_rgb(r, g, bl)
+    };
+    let clip = painter.clip_rect();
+    for b in boxes {
+        let rect = rect_of(b);
+        if rect.intersects(clip) {
+            painter.rect_stroke(rect, Rounding::ZERO, Stroke::new(1.0, color_of(b)));
+        }
+    }
+
+    let Some(pos) = mouse_pos else { return };
+    let Some(hovered) = box_at(boxes, pos.x - origin.x, pos.y - origin.y) else {
+        return;
+    };
+    let color = color_of(hovered);
+    painter.rect(
+        rect_of(hovered),
+        Rounding::ZERO,
+        color.gamma_multiply(0.2),
+        Stroke::new(2.0, color),
+    );
+    egui::show_tooltip_at_pointer(ctx, layer, egui::Id::new("layout_box_tip"), |ui| {
+        box_tooltip(ui, hovered);
+    });
+}
+
+/// Computed geometry of `b`, for the layout debug overlays.
+pub fn box_tooltip(ui: &mut egui::Ui, b: &DebugBox) {
+    let [x, y, w, h] = b.rect;
+    ui.strong(format!("<{}> · {}", b.tag, b.category.label()));
+    ui.label(format!("Position: {x:.1}, {y:.1}"));
+    ui.label(format!("Size: {w:.1} × {h:.1}"));
+    ui.label(format!("Font: {:.1} px · depth {}", b.font_size, b.depth));
+    if b.lines > 0 {
+        ui.label(format!("Lines: {}", b.lines));
+    }
+}
+
+// ── Text selection ──
+
+/// Elements whose text can be selected.
+fn is_selectable(elem: &PaintElement) -> bool {
+    matches!(
+        elem.kind,
+        PaintKind::Heading | PaintKind::Text | PaintKind::Link
+    ) && elem.text.as_deref().is_some_and(|t| !t.is_empty())
+}
+
+impl SdfPaintState {
+    /// Track drags over the page and answer the copy and select-all
+    /// shortcuts while no widget has keyboard focus.
+    fn handle_selection(
+        &mut self,
+        ctx: &egui::Context,
+        response: &egui::Response,
+        blocks: &[&PaintElement],
+        origin: Pos2,
+    ) {
+        if response.drag_started() {
+            let press = ctx.input(|i| i.pointer.press_origin());
+            if let Some(pos) = press.and_then(|p| text_pos_at(ctx, blocks, origin, p)) {
+                self.selection.begin(pos);
             }
+        } else if response.dragged() {
+            let pointer = response.interact_pointer_pos();
+            if let Some(pos) = pointer.and_then(|p| text_pos_at(ctx, blocks, origin, p)) {
+                self.selection.extend(pos);
+            }
+        }
+        if response.drag_stopped() {
+            self.selection.finish();
+        }
+
+        if ctx.memory(|m| m.focused().is_some()) {
+            return;
+        }
+        let (copy, select_all) = ctx.input(|i| {
+            (
+                i.events.iter().any(|e| matches!(e, egui::Event::Copy)),
+                i.modifiers.command && i.key_pressed(egui::Key::A),
+            )
         });
+        if select_all {
+            let last_len = blocks
+                .last()
+                .and_then(|e| e.text.as_deref())
+                .map_or(0, |t| t.chars().count());
+            self.selection.select_all(blocks.len(), last_len);
+        }
+        if copy {
+            if let Some(sel) = self.selection.selection() {
+                let texts: Vec<&str> = blocks
+                    .iter()
+                    .map(|e| e.text.as_deref().unwrap_or_default())
+                    .collect();
+                ctx.copy_text(sel.text(&texts));
+            }
+        }
+    }
+}
 
-        clicked_href
+/// Text position under `pos`, snapping to the nearest block.
+fn text_pos_at(
+    ctx: &egui::Context,
+    blocks: &[&PaintElement],
+    origin: Pos2,
+    pos: Pos2,
+) -> Option<TextPos> {
+    let rects: Vec<[f32; 4]> = blocks.iter().map(|e| e.rect).collect();
+    let hit = hit_block(&rects, [pos.x - origin.x, pos.y - origin.y])?;
+    let chars = |i: usize| blocks[i].text.as_deref().map_or(0, |t| t.chars().count());
+    Some(match hit {
+        Hit::Inside(i) => {
+            let elem = blocks[i];
+            let rect = elem_rect(elem, origin);
+            let galley = layout_text(
+                ctx,
+                elem.text.as_deref().unwrap_or_default(),
+                elem.font_size,
+                Color32::WHITE,
+                rect.width(),
+            );
+            let cursor = galley.cursor_from_pos(pos - rect.min);
+            TextPos::new(i, cursor.ccursor.index)
+        }
+        Hit::Before(i) => TextPos::new(i, 0),
+        Hit::After(i) => TextPos::new(i, chars(i)),
+    })
+}
+
+/// Highlight the characters `range` of an element's text, row by row.
+fn draw_selection(
+    painter: &egui::Painter,
+    ctx: &egui::Context,
+    rect: Rect,
+    elem: &PaintElement,
+    range: Range<usize>,
+    theme: &Theme,
+) {
+    let text = elem.text.as_deref().unwrap_or_default();
+    let galley = layout_text(ctx, text, elem.font_size, Color32::WHITE, rect.width());
+    let mut row_start = 0;
+    for row in &galley.rows {
+        let from = range.start.max(row_start);
+        let to = range
+            .end
+            .min(row_start + row.char_count_excluding_newline());
+        if from < to {
+            let x0 = row.x_offset(from - row_start);
+            let x1 = row.x_offset(to - row_start);
+            let highlight = Rect::from_min_max(
+                rect.min + Vec2::new(x0, row.rect.min.y),
+                rect.min + Vec2::new(x1, row.rect.max.y),
+            );
+            painter.rect_filled(highlight, Rounding::same(2.0), theme.selection);
+        }
+        row_start += row.char_count_including_newline();
     }
 }
 
@@ -249,16 +750,28 @@ fn paint_text_wrapped(
     if text.is_empty() {
         return Rect::from_min_size(pos, Vec2::ZERO);
     }
+    let galley = layout_text(ctx, text, font_size, color, max_width);
+    let size = galley.rect.size();
+    painter.galley(pos, galley, color);
+    Rect::from_min_size(pos, size)
+}
+
+/// Shape `text` the way the painter draws it; selection hit-testing and
+/// highlights use the same layout so they line up with the glyphs.
+fn layout_text(
+    ctx: &egui::Context,
+    text: &str,
+    font_size: f32,
+    color: Color32,
+    max_width: f32,
+) -> Arc<Galley> {
     let job = egui::text::LayoutJob::simple(
         text.to_string(),
         FontId::proportional(font_size),
         color,
         max_width,
     );
-    let galley = ctx.fonts(|f: &egui::epaint::Fonts| f.layout_job(job));
-    let size = galley.rect.size();
-    painter.galley(pos, galley, color);
-    Rect::from_min_size(pos, size)
+    ctx.fonts(|f: &egui::epaint::Fonts| f.layout_job(job))
 }
 
 // ── Drawing functions ──
@@ -480,14 +993,15 @@ fn draw_image_placeholder(
     hover_t: f32,
     theme: &Theme,
     textures: &HashMap<String, TextureHandle>,
+    progress: Option<&Progress>,
 ) {
     let r = Rounding::same(elem.corner_radius + hover_t);
+    let uv = Rect::from_min_max(Pos2::new(0.0, 0.0), Pos2::new(1.0, 1.0));
 
     // If we have a loaded texture for this image, draw it
     if let Some(ref url) = elem.image_url {
         if let Some(tex) = textures.get(url) {
             painter.rect_filled(rect, r, theme.img_bg);
-            let uv = Rect::from_min_max(Pos2::new(0.0, 0.0), Pos2::new(1.0, 1.0));
             painter.image(tex.id(), rect, uv, Color32::WHITE);
             // Border on hover
             if hover_t > 0.01 {
@@ -495,18 +1009,48 @@ fn draw_image_placeholder(
             }
             return;
         }
+
+        // Blurred preview stretched over the box while the rest downloads
+        if let Some(tex) = textures.get(&preview_key(url)) {
+            painter.rect_filled(rect, r, theme.img_bg);
+            painter.image(tex.id(), rect, uv, Color32::from_gray(220));
+            draw_load_progress(painter, rect, theme, progress);
+            return;
+        }
     }
 
     // Fallback placeholder
     painter.rect_filled(rect, r, theme.img_bg);
     painter.rect_stroke(rect, r, Stroke::new(1.0, theme.img_border));
+    let label = match progress.and_then(Progress::fraction) {
+        Some(f) => format!("[Image {:.0}%]", f * 100.0),
+        None => "[Image]".to_string(),
+    };
     painter.text(
         rect.center(),
         egui::Align2::CENTER_CENTER,
-        "[Image]",
+        label,
         FontId::proportional(14.0),
         theme.img_text,
     );
+    draw_load_progress(painter, rect, theme, progress);
+}
+
+/// Thin bar along the bottom edge of a loading image.
+fn draw_load_progress(
+    painter: &egui::Painter,
+    rect: Rect,
+    theme: &Theme,
+    progress: Option<&Progress>,
+) {
+    let Some(fraction) = progress.and_then(Progress::fraction) else {
+        return;
+    };
+    let bar = Rect::from_min_size(
+        Pos2::new(rect.min.x, rect.max.y - 3.0),
+        Vec2::new(rect.width() * fraction, 3.0),
+    );
+    painter.rect_filled(bar, Rounding::ZERO, theme.heading_accent);
 }
 
 #[cfg(test)]
@@ -549,4 +1093,14 @@ mod tests {
         assert_eq!(mid.g(), 100);
         assert_eq!(mid.b(), 25);
     }
+
+    #[test]
+    fn innermost_block_wins() {
+        let outer = [0.0, 0.0, 600.0, 400.0];
+        let inner = [20.0, 100.0, 560.0, 120.0];
+        let blocks = [outer, inner];
+        assert_eq!(innermost_block(&blocks, 50.0, 150.0), Some(inner));
+        assert_eq!(innermost_block(&blocks, 50.0, 300.0), Some(outer));
+        assert_eq!(innermost_block(&blocks, 50.0, 500.0), None);
+    }
 }
@@ -2,25 +2,42 @@
 //!
 //! Converts `SdfScene` primitives into `alice_sdf::SdfNode` trees and renders
 //! via sphere-tracing with compiled SIMD evaluation + rayon parallel rows.
+//! Distance queries go through a [`Bvh`] over the primitives, so each step
+//! evaluates only those near the ray; primitives the camera cannot see are
+//! [culled](crate::render::culling) before it is built.
 
 use alice_sdf::prelude::*;
 use rayon::prelude::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
 
+use crate::engine::workers;
+use crate::render::bvh::Bvh;
+use crate::render::culling::{cull, CullStats, View};
+use crate::render::palette::SkyPalette;
 use crate::render::sdf_ui::{SdfPrimitive, SdfScene};
 
 // ── Camera parameters (public API, unchanged) ──
 
 /// Camera parameters for interactive 3D navigation.
+///
+/// Both modes share one placement — the eye sits `distance` from `target`
+/// in the direction `azimuth` and `elevation` give — so the renderers need
+/// not know the mode, and switching it leaves the view where it was.
 #[derive(Debug, Clone, Copy)]
 pub struct CameraParams {
-    /// Horizontal orbit angle in radians (0 = front)
+    /// Horizontal orbit angle in radians (0 = front); yaw when flying
     pub azimuth: f32,
-    /// Vertical orbit angle in radians (0 = level, positive = looking down)
+    /// Vertical orbit angle in radians (0 = level, positive = looking down);
+    /// pitch when flying
     pub elevation: f32,
     /// Distance from the camera to the target point
     pub distance: f32,
     /// Target point the camera looks at [x, y, z]
     pub target: [f32; 3],
+    /// How input steers the camera
+    pub mode: CameraMode,
 }
 
 impl Default for CameraParams {
@@ -30,28 +47,107 @@ impl Default for CameraParams {
             elevation: 0.6,
             distance: 3.0,
             target: [0.0, 0.0, 0.0],
+            mode: CameraMode::Orbit,
         }
     }
 }
 
+/// How the camera is steered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CameraMode {
+    /// Around `target`: dragging swings the eye, scrolling dollies
+    #[default]
+    Orbit,
+    /// First person: turning keeps the eye in place and moves the target,
+    /// moving carries both
+    Fly,
+}
+
+/// Steepest a flying camera looks up or down (radians).
+const FLY_PITCH_LIMIT: f32 = 1.45;
+
+impl CameraParams {
+    /// Unit vector from the target toward the eye.
+    fn back(&self) -> Vec3 {
+        Vec3::new(
+            self.azimuth.sin() * self.elevation.cos(),
+            self.elevation.sin(),
+            self.azimuth.cos() * self.elevation.cos(),
+        )
+    }
+
+    /// Eye position.
+    #[must_use]
+    pub fn eye(&self) -> [f32; 3] {
+        (Vec3::from(self.target) + self.back() * self.distance).into()
+    }
+
+    /// Unit view direction.
+    #[must_use]
+    pub fn forward(&self) -> [f32; 3] {
+        (-self.back()).into()
+    }
+
+    /// Turn the view about the eye by `yaw` and `pitch` radians (positive
+    /// pitch looks down), as a flying camera does.
+    pub fn look(&mut self, yaw: f32, pitch: f32) {
+        let eye = Vec3::from(self.eye());
+        self.azimuth += yaw;
+        self.elevation = (self.elevation + pitch).clamp(-FLY_PITCH_LIMIT, FLY_PITCH_LIMIT);
+        self.target = (eye - self.back() * self.distance).into();
+    }
+
+    /// Move the eye `ahead` along the view, `right` across it and `up`
+    /// along the world's vertical, keeping it inside `bounds` (min, max).
+    /// The target moves with it.
+    pub fn fly(&mut self, ahead: f32, right: f32, up: f32, bounds: ([f32; 3], [f32; 3])) {
+        let eye = Vec3::from(self.eye());
+        let forward = -self.back();
+        let side = forward.cross(Vec3::Y).normalize_or_zero();
+        let moved = (eye + forward * ahead + side * right + Vec3::Y * up)
+            .clamp(Vec3::from(bounds.0), Vec3::from(bounds.1));
+        self.target = (Vec3::from(self.target) + (moved - eye)).into();
+    }
+}
+
 // ── Compiled scene ──
 
-/// A scene compiled for fast rendering: SIMD bytecode + per-primitive color map.
+/// A scene compiled for fast rendering: per-primitive SIMD bytecode,
+/// colours and flags in BVH leaf order, and the BVH over them.
 struct CompiledScene {
-    /// Individual `SdfNodes` per primitive (for color lookup on hit)
-    nodes: Vec<SdfNode>,
+    /// Compiled bytecode per primitive
+    prims: Vec<CompiledSdf>,
     /// Colors per primitive [r, g, b]
     colors: Vec<[f32; 3]>,
     /// Per-primitive unlit flag (true = TextLabel/Billboard, skip toon shading)
     unlit: Vec<bool>,
-    /// Full scene union as a single `SdfNode` (for normals / shadows)
-    union_tree: SdfNode,
-    /// Compiled bytecode of the union tree (for fast SIMD raymarching)
-    compiled: CompiledSdf,
+    /// Hierarchy over the primitives; slots index the vectors above
+    bvh: Bvh,
     /// Background color
     background: [f32; 4],
 }
 
+impl CompiledScene {
+    /// Distance to the nearest surface from `p`, and the slot of the
+    /// primitive it belongs to.
+    fn nearest(&self, p: Vec3) -> (f32, usize) {
+        self.bvh
+            .nearest([p.x, p.y, p.z], |slot| eval_compiled(&self.prims[slot], p))
+            .map_or((f32::MAX, 0), |(slot, d)| (d, slot))
+    }
+
+    /// Surface normal at `p` by central differences.
+    fn normal(&self, p: Vec3, e: f32) -> Vec3 {
+        let d = |x: f32, y: f32, z: f32| self.nearest(p + Vec3::new(x, y, z)).0;
+        Vec3::new(
+            d(e, 0.0, 0.0) - d(-e, 0.0, 0.0),
+            d(0.0, e, 0.0) - d(0.0, -e, 0.0),
+            d(0.0, 0.0, e) - d(0.0, 0.0, -e),
+        )
+        .normalize()
+    }
+}
+
 /// Convert an `SdfPrimitive` to an `alice_sdf::SdfNode` + color.
 fn primitive_to_node(prim: &SdfPrimitive) -> (SdfNode, [f32; 3]) {
     match prim {
@@ -141,19 +237,22 @@ fn primitive_to_node(prim: &SdfPrimitive) -> (SdfNode, [f32; 3]) {
     }
 }
 
-/// Build a compiled scene from an `SdfScene`.
-fn compile_scene(scene: &SdfScene) -> Option<CompiledScene> {
+/// Build a compiled scene from the primitives of `scene` listed in
+/// `visible`.
+fn compile_scene(scene: &SdfScene, visible: &[u32]) -> Option<CompiledScene> {
     if scene.primitives.is_empty() {
         return None;
     }
 
-    let mut nodes = Vec::with_capacity(scene.primitives.len());
-    let mut colors = Vec::with_capacity(scene.primitives.len());
-    let mut unlit = Vec::with_capacity(scene.primitives.len());
+    let bvh = Bvh::from_subset(scene, visible);
+    let mut prims = Vec::with_capacity(visible.len());
+    let mut colors = Vec::with_capacity(visible.len());
+    let mut unlit = Vec::with_capacity(visible.len());
 
-    for prim in &scene.primitives {
+    for &index in &bvh.order {
+        let prim = &scene.primitives[index as usize];
         let (node, color) = primitive_to_node(prim);
-        nodes.push(node);
+        prims.push(CompiledSdf::compile(&node));
         colors.push(color);
         unlit.push(matches!(
             prim,
@@ -161,53 +260,18 @@ fn compile_scene(scene: &SdfScene) -> Option<CompiledScene> {
         ));
     }
 
-    // Build balanced union tree for better traversal
-    let union_tree = build_balanced_union(&nodes);
-    let compiled = CompiledSdf::compile(&union_tree);
-
     Some(CompiledScene {
-        nodes,
+        prims,
         colors,
         unlit,
-        union_tree,
-        compiled,
+        bvh,
         background: scene.background_color,
     })
 }
 
-/// Build a balanced binary union tree (reduces max depth for better perf).
-fn build_balanced_union(nodes: &[SdfNode]) -> SdfNode {
-    match nodes.len() {
-        0 => SdfNode::sphere(0.001), // should not happen
-        1 => nodes[0].clone(),
-        2 => nodes[0].clone().union(nodes[1].clone()),
-        _ => {
-            let mid = nodes.len() / 2;
-            let left = build_balanced_union(&nodes[..mid]);
-            let right = build_balanced_union(&nodes[mid..]);
-            left.union(right)
-        }
-    }
-}
-
-/// Find the closest primitive color at a point. Returns (color, `is_unlit`).
-fn closest_color(p: Vec3, scene: &CompiledScene) -> ([f32; 3], bool) {
-    let mut min_d = f32::MAX;
-    let mut col = [0.0f32; 3];
-    let mut is_unlit = false;
-    for (i, node) in scene.nodes.iter().enumerate() {
-        let d = eval(node, p);
-        if d < min_d {
-            min_d = d;
-            col = scene.colors[i];
-            is_unlit = scene.unlit[i];
-        }
-    }
-    (col, is_unlit)
-}
-
 // ── Camera ──
 
+#[derive(Clone, Copy)]
 struct Camera {
     origin: Vec3,
     forward: Vec3,
@@ -232,6 +296,20 @@ impl Camera {
         }
     }
 
+    /// The view culling tests against, for an image of `aspect` traced no
+    /// further than `far`.
+    fn view(&self, aspect: f32, far: f32) -> View {
+        View {
+            eye: self.origin.into(),
+            forward: self.forward.into(),
+            right: self.right.into(),
+            up: self.up.into(),
+            tan_half_fov: self.fov_factor,
+            aspect,
+            far,
+        }
+    }
+
     fn ray(&self, u: f32, v: f32, aspect: f32) -> Vec3 {
         (self.forward
             + self.right * (u * self.fov_factor * aspect)
@@ -240,6 +318,82 @@ impl Camera {
     }
 }
 
+/// The camera orbiting `cam.target` as `cam` describes.
+fn orbit_camera(cam: &CameraParams) -> Camera {
+    let target = Vec3::new(cam.target[0], cam.target[1], cam.target[2]);
+    let eye = target
+        + Vec3::new(
+            cam.distance * cam.azimuth.sin() * cam.elevation.cos(),
+            cam.distance * cam.elevation.sin(),
+            cam.distance * cam.azimuth.cos() * cam.elevation.cos(),
+        );
+    Camera::look_at(eye, target, 50.0)
+}
+
+/// Where `point` lands in a frame of `aspect` seen through `cam`, in
+/// normalised device coordinates (x right, y up, both -1..1 across the
+/// frame); `None` behind the camera.
+#[must_use]
+pub fn project_point(cam: &CameraParams, aspect: f32, point: [f32; 3]) -> Option<[f32; 2]> {
+    let camera = orbit_camera(cam);
+    let d = Vec3::new(point[0], point[1], point[2]) - camera.origin;
+    let depth = d.dot(camera.forward);
+    if depth <= 1e-4 {
+        return None;
+    }
+    Some([
+        d.dot(camera.right) / (depth * camera.fov_factor * aspect),
+        d.dot(camera.up) / (depth * camera.fov_factor),
+    ])
+}
+
+// ── Picking ──
+
+/// Finds the primitive under a point of the view, for hover and clicks.
+/// Holds the whole scene compiled, so build it once per scene.
+pub struct ScenePicker {
+    compiled: Option<CompiledScene>,
+}
+
+impl ScenePicker {
+    #[must_use]
+    pub fn new(scene: &SdfScene) -> Self {
+        let all: Vec<u32> = (0..scene.primitives.len() as u32).collect();
+        Self {
+            compiled: compile_scene(scene, &all),
+        }
+    }
+
+    /// Index in the scene of the primitive seen at `ndc` (normalised
+    /// device coordinates, as [`project_point`] gives) in a frame of
+    /// `aspect` through `cam`.
+    #[must_use]
+    pub fn pick(&self, cam: &CameraParams, aspect: f32, ndc: [f32; 2]) -> Option<usize> {
+        let compiled = self.compiled.as_ref()?;
+        let camera = orbit_camera(cam);
+        let dir = camera.ray(ndc[0], ndc[1], aspect);
+        let (slot, _) =
+            compiled
+                .bvh
+                .raycast(camera.origin.into(), dir.into(), |slot, enter, exit| {
+                    // Sphere-trace this primitive alone across its leaf's box
+                    let mut t = enter;
+                    for _ in 0..64 {
+                        let d = eval_compiled(&compiled.prims[slot], camera.origin + dir * t);
+                        if d < 0.001 {
+                            return Some(t);
+                        }
+                        t += d;
+                        if t > exit {
+                            break;
+                        }
+                    }
+                    None
+                })?;
+        Some(compiled.bvh.order[slot] as usize)
+    }
+}
+
 // ── Scene bounds ──
 
 fn scene_bounds(scene: &SdfScene) -> (Vec3, Vec3) {
@@ -312,14 +466,23 @@ fn scene_bounds(scene: &SdfScene) -> (Vec3, Vec3) {
 
 // ── Sky ──
 
-fn sky_color(dir: Vec3, bg: [f32; 4]) -> [f32; 3] {
+/// Sky and lighting for a scene rendered without a palette: a zenith
+/// darkened from the background, neutral rim light.
+fn default_sky(bg: [f32; 4]) -> SkyPalette {
+    SkyPalette {
+        zenith: [
+            (bg[0] * 0.5).min(0.4),
+            (bg[1] * 0.6).min(0.5),
+            (bg[2] * 0.8).min(0.9),
+        ],
+        rim: [0.5, 0.5, 0.5],
+        shadow_tint: [0.05, 0.03, 0.08],
+    }
+}
+
+fn sky_color(dir: Vec3, bg: [f32; 4], zenith: [f32; 3]) -> [f32; 3] {
     let t = dir.y.mul_add(0.5, 0.5).clamp(0.0, 1.0);
     let horizon = [bg[0], bg[1], bg[2]];
-    let zenith = [
-        (bg[0] * 0.5).min(0.4),
-        (bg[1] * 0.6).min(0.5),
-        (bg[2] * 0.8).min(0.9),
-    ];
     [
         horizon[0].mul_add(1.0 - t, zenith[0] * t),
         horizon[1].mul_add(1.0 - t, zenith[1] * t),
@@ -329,28 +492,34 @@ fn sky_color(dir: Vec3, bg: [f32; 4]) -> [f32; 3] {
 
 // ── Public rendering API ──
 
-/// Render an SDF scene with interactive camera parameters.
+/// Render an SDF scene with interactive camera parameters, lit and with the
+/// sky of `sky` (see [`ScenePalette`](crate::render::palette::ScenePalette)).
 #[must_use]
 pub fn render_sdf_interactive(
     scene: &SdfScene,
     width: usize,
     height: usize,
     cam: &CameraParams,
+    sky: &SkyPalette,
 ) -> Option<Vec<u8>> {
+    render_sdf_culled(scene, width, height, cam, sky).map(|(pixels, _)| pixels)
+}
+
+/// [`render_sdf_interactive`], also returning what culling kept of the
+/// scene.
+#[must_use]
+pub fn render_sdf_culled(
+    scene: &SdfScene,
+    width: usize,
+    height: usize,
+    cam: &CameraParams,
+    sky: &SkyPalette,
+) -> Option<(Vec<u8>, CullStats)> {
     if scene.primitives.is_empty() {
         return None;
     }
 
-    let target = Vec3::new(cam.target[0], cam.target[1], cam.target[2]);
-    let eye = target
-        + Vec3::new(
-            cam.distance * cam.azimuth.sin() * cam.elevation.cos(),
-            cam.distance * cam.elevation.sin(),
-            cam.distance * cam.azimuth.cos() * cam.elevation.cos(),
-        );
-
-    let camera = Camera::look_at(eye, target, 50.0);
-    render_scene(scene, width, height, &camera)
+    render_scene(scene, width, height, &orbit_camera(cam), sky)
 }
 
 /// Render an SDF scene to an RGBA pixel buffer (auto-framing).
@@ -378,7 +547,50 @@ pub fn render_sdf_image(
         Camera::look_at(eye, center, 45.0)
     };
 
-    render_scene(scene, width, height, &camera)
+    render_scene(
+        scene,
+        width,
+        height,
+        &camera,
+        &default_sky(scene.background_color),
+    )
+    .map(|(pixels, _)| pixels)
+}
+
+/// Box a flying camera is kept in: the scene's bounds with a quarter of its
+/// size to spare on every side, so the whole corridor can be seen from
+/// outside.
+#[must_use]
+pub fn fly_bounds(scene: &SdfScene) -> ([f32; 3], [f32; 3]) {
+    let (mn, mx) = scene_bounds(scene);
+    let extent = mx - mn;
+    let margin = Vec3::splat(extent.x.max(extent.y.max(extent.z)).max(0.5) * 0.25);
+    ((mn - margin).into(), (mx + margin).into())
+}
+
+/// How close to a portal a fly-through ends, as a distance from its face.
+const PORTAL_ARRIVAL: f32 = 0.05;
+
+/// Camera `t` (0.0–1.0) of the way through a fly-through from `from` into
+/// the portal at `portal`: the view swings round to face the portal head-on
+/// while the target slides onto it and the eye closes in, eased at both
+/// ends.
+#[must_use]
+pub fn portal_flight(from: &CameraParams, portal: [f32; 3], t: f32) -> CameraParams {
+    use std::f32::consts::{PI, TAU};
+
+    let t = t.clamp(0.0, 1.0);
+    let ease = t * t * 2.0f32.mul_add(-t, 3.0);
+    // Turn the short way round to face the portal's front (+z)
+    let turn = (from.azimuth + PI).rem_euclid(TAU) - PI;
+    let target = Vec3::from(from.target).lerp(Vec3::from(portal), ease);
+    CameraParams {
+        azimuth: turn * (1.0 - ease),
+        elevation: from.elevation * (1.0 - ease),
+        distance: (PORTAL_ARRIVAL - from.distance).mul_add(ease, from.distance),
+        target: target.into(),
+        mode: from.mode,
+    }
 }
 
 /// Compute initial camera params that auto-frame the scene.
@@ -394,124 +606,335 @@ pub fn auto_camera(scene: &SdfScene) -> CameraParams {
         elevation: 0.5,
         distance: max_ext * 1.8,
         target: [center.x, center.y, center.z],
+        mode: CameraMode::Orbit,
     }
 }
 
 // ── Core rendering (rayon-parallel rows, compiled SIMD eval) ──
 
-fn render_scene(
-    scene_data: &SdfScene,
+/// A compiled scene, camera and lighting ready to shade pixels of a
+/// `width`×`height` image.
+struct Shader {
+    compiled: CompiledScene,
+    camera: Camera,
+    sky: SkyPalette,
     width: usize,
     height: usize,
-    camera: &Camera,
-) -> Option<Vec<u8>> {
-    let compiled = compile_scene(scene_data)?;
+    max_extent: f32,
+    /// What culling kept of the scene
+    culling: CullStats,
+}
 
-    let (mn, mx) = scene_bounds(scene_data);
-    let extent = mx - mn;
-    let max_extent = extent.x.max(extent.y.max(extent.z)).max(0.5);
-    let max_march_dist = max_extent * 5.0;
+impl Shader {
+    fn new(
+        scene_data: &SdfScene,
+        width: usize,
+        height: usize,
+        camera: Camera,
+        sky: &SkyPalette,
+    ) -> Option<Self> {
+        // Fog and march distance follow the whole scene, not what is in view
+        let (mn, mx) = scene_bounds(scene_data);
+        let extent = mx - mn;
+        let max_extent = extent.x.max(extent.y.max(extent.z)).max(0.5);
+        let view = camera.view(width as f32 / height as f32, max_extent * 5.0);
+        let visible = cull(scene_data, &view);
+        Some(Self {
+            compiled: compile_scene(scene_data, &visible.indices)?,
+            camera,
+            sky: *sky,
+            width,
+            height,
+            max_extent,
+            culling: visible.stats,
+        })
+    }
+
+    /// RGBA of pixel (`px`, `py`).
+    fn shade(&self, px: usize, py: usize) -> [u8; 4] {
+        let camera = &self.camera;
+        let compiled = &self.compiled;
+        let sky = &self.sky;
+        let max_extent = self.max_extent;
+        let max_march_dist = max_extent * 5.0;
+        let rim_base = Vec3::new(sky.rim[0], sky.rim[1], sky.rim[2]);
+        let shadow_tint = Vec3::new(sky.shadow_tint[0], sky.shadow_tint[1], sky.shadow_tint[2]);
+        let light_dir = Vec3::new(0.5, 0.8, 0.3).normalize();
+        let aspect = self.width as f32 / self.height as f32;
+
+        let u = ((px as f32 + 0.5) / self.width as f32).mul_add(2.0, -1.0);
+        let v = -((py as f32 + 0.5) / self.height as f32).mul_add(2.0, -1.0);
+        let ray_dir = camera.ray(u, v, aspect);
+
+        // Sphere-trace using compiled SIMD evaluation
+        let mut t = 0.0f32;
+        let mut hit = false;
+        let mut hit_color = [0.0f32; 3];
+        let mut hit_unlit = false;
+
+        for _ in 0..80 {
+            let p = camera.origin + ray_dir * t;
+            let (d, slot) = compiled.nearest(p);
+            if d < 0.001 {
+                hit = true;
+                hit_color = compiled.colors[slot];
+                hit_unlit = compiled.unlit[slot];
+                break;
+            }
+            t += d;
+            if t > max_march_dist {
+                break;
+            }
+        }
+
+        let (r, g, b) = if hit {
+            let hit_pos = camera.origin + ray_dir * t;
+            let mat = Vec3::new(hit_color[0], hit_color[1], hit_color[2]);
+
+            let col_rim = if hit_unlit {
+                // Unlit: TextLabel/Billboard — use base color directly
+                mat
+            } else {
+                // Surface normal
+                let n = compiled.normal(hit_pos, 0.001);
+                let n_dot_l = n.dot(light_dir).max(0.0);
+                let view_dir = (camera.origin - hit_pos).normalize();
+
+                // Toon: 2-tone hard boundary
+                let toon = if n_dot_l > 0.5 { 1.0 } else { 0.0 };
 
-    let light_dir = Vec3::new(0.5, 0.8, 0.3).normalize();
-    let _light_col = Vec3::new(1.0, 0.98, 0.95);
-    let _ambient = Vec3::new(0.15, 0.17, 0.22);
+                // Shadow color: complementary dark
+                let shadow_col = mat * 0.35 + shadow_tint;
 
-    let aspect = width as f32 / height as f32;
+                let col = mat * toon + shadow_col * (1.0 - toon);
+
+                // Rim lighting
+                let rim = (1.0 - n.dot(view_dir).max(0.0)).powi(3) * 0.6;
+                let rim_col = mat * 0.5 + rim_base;
+                col + rim_col * rim
+            };
+
+            // Distance fog
+            let fog_start = max_extent * 1.5;
+            let fog_end = max_extent * 4.0;
+            let fog_t = ((t - fog_start) / (fog_end - fog_start)).clamp(0.0, 1.0);
+            let sky = sky_color(ray_dir, compiled.background, sky.zenith);
+            let fog_col = Vec3::new(sky[0], sky[1], sky[2]);
+            let final_col = col_rim * (1.0 - fog_t) + fog_col * fog_t;
+
+            (
+                (final_col.x.clamp(0.0, 1.0) * 255.0) as u8,
+                (final_col.y.clamp(0.0, 1.0) * 255.0) as u8,
+                (final_col.z.clamp(0.0, 1.0) * 255.0) as u8,
+            )
+        } else {
+            let sky = sky_color(ray_dir, compiled.background, sky.zenith);
+            (
+                (sky[0].clamp(0.0, 1.0) * 255.0) as u8,
+                (sky[1].clamp(0.0, 1.0) * 255.0) as u8,
+                (sky[2].clamp(0.0, 1.0) * 255.0) as u8,
+            )
+        };
+        [r, g, b, 255]
+    }
+}
 
+fn render_scene(
+    scene_data: &SdfScene,
+    width: usize,
+    height: usize,
+    camera: &Camera,
+    sky: &SkyPalette,
+) -> Option<(Vec<u8>, CullStats)> {
+    let shader = Shader::new(scene_data, width, height, *camera, sky)?;
     let mut pixels = vec![0u8; width * height * 4];
-    let row_size = width * 4;
 
     // Parallel row rendering via rayon
     pixels
-        .par_chunks_exact_mut(row_size)
+        .par_chunks_exact_mut(width * 4)
         .enumerate()
         .for_each(|(py, row_buf)| {
-            let v = -((py as f32 + 0.5) / height as f32).mul_add(2.0, -1.0);
-
-            for px in 0..width {
-                let u = ((px as f32 + 0.5) / width as f32).mul_add(2.0, -1.0);
-                let ray_dir = camera.ray(u, v, aspect);
-
-                // Sphere-trace using compiled SIMD evaluation
-                let mut t = 0.0f32;
-                let mut hit = false;
-                let mut hit_color = [0.0f32; 3];
-                let mut hit_unlit = false;
-
-                for _ in 0..80 {
-                    let p = camera.origin + ray_dir * t;
-                    let d = eval_compiled(&compiled.compiled, p);
-                    if d < 0.001 {
-                        hit = true;
-                        let (c, u) = closest_color(p, &compiled);
-                        hit_color = c;
-                        hit_unlit = u;
-                        break;
-                    }
-                    t += d;
-                    if t > max_march_dist {
-                        break;
-                    }
-                }
+            for (px, out) in row_buf.chunks_exact_mut(4).enumerate() {
+                out.copy_from_slice(&shader.shade(px, py));
+            }
+        });
+
+    Some((pixels, shader.culling))
+}
+
+// ── Progressive refinement ──
+
+/// Sample spacings of the refinement passes, coarsest first. Each pass
+/// traces only the pixels on its grid that a coarser pass has not.
+pub const REFINE_STRIDES: [usize; 3] = [4, 2, 1];
 
-                let (r, g, b) = if hit {
-                    let hit_pos = camera.origin + ray_dir * t;
-                    let mat = Vec3::new(hit_color[0], hit_color[1], hit_color[2]);
-
-                    let col_rim = if hit_unlit {
-                        // Unlit: TextLabel/Billboard — use base color directly
-                        mat
-                    } else {
-                        // Surface normal
-                        let n = normal(&compiled.union_tree, hit_pos, 0.001);
-                        let n_dot_l = n.dot(light_dir).max(0.0);
-                        let view_dir = (camera.origin - hit_pos).normalize();
-
-                        // Toon: 2-tone hard boundary
-                        let toon = if n_dot_l > 0.5 { 1.0 } else { 0.0 };
-
-                        // Shadow color: complementary dark
-                        let shadow_col = mat * 0.35 + Vec3::new(0.05, 0.03, 0.08);
-
-                        let col = mat * toon + shadow_col * (1.0 - toon);
-
-                        // Rim lighting
-                        let rim = (1.0 - n.dot(view_dir).max(0.0)).powi(3) * 0.6;
-                        let rim_col = mat * 0.5 + Vec3::splat(0.5);
-                        col + rim_col * rim
-                    };
-
-                    // Distance fog
-                    let fog_start = max_extent * 1.5;
-                    let fog_end = max_extent * 4.0;
-                    let fog_t = ((t - fog_start) / (fog_end - fog_start)).clamp(0.0, 1.0);
-                    let sky = sky_color(ray_dir, compiled.background);
-                    let fog_col = Vec3::new(sky[0], sky[1], sky[2]);
-                    let final_col = col_rim * (1.0 - fog_t) + fog_col * fog_t;
-
-                    (
-                        (final_col.x.clamp(0.0, 1.0) * 255.0) as u8,
-                        (final_col.y.clamp(0.0, 1.0) * 255.0) as u8,
-                        (final_col.z.clamp(0.0, 1.0) * 255.0) as u8,
-                    )
+/// One pass of a progressive render, upsampled to the full frame size.
+#[derive(Debug, Clone)]
+pub struct RefinedFrame {
+    /// RGBA, `width`×`height`
+    pub pixels: Vec<u8>,
+    pub width: usize,
+    pub height: usize,
+    /// Sample spacing of the pass; 1 is the finished frame
+    pub stride: usize,
+    /// What culling kept of the scene
+    pub culling: CullStats,
+}
+
+impl RefinedFrame {
+    #[must_use]
+    pub const fn is_final(&self) -> bool {
+        self.stride == 1
+    }
+}
+
+/// Renders a view on a background thread in passes of increasing
+/// resolution.
+///
+/// Each pass halves the sample spacing and traces only the new samples, so a still camera gets a coarse frame at once and the finished
+/// one for no more work than a single full render. Starting another render
+/// or calling [`cancel`](Self::cancel) abandons the one in progress.
+#[derive(Debug, Default)]
+pub struct ProgressiveRenderer {
+    generation: Arc<AtomicU64>,
+    rx: Option<Receiver<RefinedFrame>>,
+}
+
+impl ProgressiveRenderer {
+    /// Start rendering `scene` from `cam` at `width`×`height`, beginning
+    /// with the pass of spacing `first_stride` (one of [`REFINE_STRIDES`];
+    /// skip the coarsest when a low-resolution frame is already on screen).
+    pub fn start(
+        &mut self,
+        scene: SdfScene,
+        width: usize,
+        height: usize,
+        cam: CameraParams,
+        sky: SkyPalette,
+        first_stride: usize,
+    ) {
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        let current = Arc::clone(&self.generation);
+        let (tx, rx) = mpsc::channel();
+        self.rx = Some(rx);
+
+        workers::shared().spawn(move || {
+            let stale = || current.load(Ordering::Relaxed) != generation;
+            if scene.primitives.is_empty() || width == 0 || height == 0 {
+                return;
+            }
+            let Some(shader) = Shader::new(&scene, width, height, orbit_camera(&cam), &sky) else {
+                return;
+            };
+            let mut samples = vec![0u8; width * height * 4];
+            let mut coarser: Option<usize> = None;
+            for stride in REFINE_STRIDES
+                .into_iter()
+                .filter(|&s| s <= first_stride.max(1))
+            {
+                samples
+                    .par_chunks_exact_mut(width * 4)
+                    .enumerate()
+                    .filter(|(py, _)| py % stride == 0)
+                    .for_each(|(py, row_buf)| {
+                        if stale() {
+                            return;
+                        }
+                        let traced = coarser.filter(|c| py % c == 0);
+                        for px in (0..width).step_by(stride) {
+                            if traced.is_some_and(|c| px % c == 0) {
+                                continue;
+                            }
+                            row_buf[px * 4..px * 4 + 4].copy_from_slice(&shader.shade(px, py));
+                        }
+                    });
+                if stale() {
+                    return;
+                }
+                let pixels = if stride == 1 {
+                    std::mem::take(&mut samples)
                 } else {
-                    let sky = sky_color(ray_dir, compiled.background);
-                    (
-                        (sky[0].clamp(0.0, 1.0) * 255.0) as u8,
-                        (sky[1].clamp(0.0, 1.0) * 255.0) as u8,
-                        (sky[2].clamp(0.0, 1.0) * 255.0) as u8,
-                    )
+                    upsample(&samples, width, height, stride)
                 };
-
-                let idx = px * 4;
-                row_buf[idx] = r;
-                row_buf[idx + 1] = g;
-                row_buf[idx + 2] = b;
-                row_buf[idx + 3] = 255;
+                let frame = RefinedFrame {
+                    pixels,
+                    width,
+                    height,
+                    stride,
+                    culling: shader.culling,
+                };
+                if tx.send(frame).is_err() {
+                    return;
+                }
+                coarser = Some(stride);
             }
         });
+    }
+
+    /// Abandon the render in progress.
+    pub fn cancel(&mut self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.rx = None;
+    }
+
+    /// The newest pass finished since the last call. Call every frame.
+    pub fn poll(&mut self) -> Option<RefinedFrame> {
+        let rx = self.rx.as_ref()?;
+        let mut latest = None;
+        loop {
+            match rx.try_recv() {
+                Ok(frame) => latest = Some(frame),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.rx = None;
+                    break;
+                }
+            }
+        }
+        latest
+    }
+
+    /// Whether a render is still producing passes.
+    #[must_use]
+    pub const fn is_running(&self) -> bool {
+        self.rx.is_some()
+    }
+}
+
+/// Fill a `width`×`height` RGBA image from the samples on every
+/// `stride`-th row and column of `samples`, interpolating bilinearly
+/// between them (and holding the last sample at the right and bottom edges).
+fn upsample(samples: &[u8], width: usize, height: usize, stride: usize) -> Vec<u8> {
+    let last_x = (width - 1) / stride * stride;
+    let last_y = (height - 1) / stride * stride;
+    // Neighbouring samples of `p` and the weight of the second one
+    let span = |p: usize, last: usize| {
+        let p0 = p / stride * stride;
+        let p1 = (p0 + stride).min(last);
+        let f = if p1 > p0 {
+            (p - p0) as f32 / (p1 - p0) as f32
+        } else {
+            0.0
+        };
+        (p0, p1, f)
+    };
 
-    Some(pixels)
+    let mut out = vec![0u8; width * height * 4];
+    out.par_chunks_exact_mut(width * 4)
+        .enumerate()
+        .for_each(|(py, row_buf)| {
+            let (y0, y1, fy) = span(py, last_y);
+            for (px, pixel) in row_buf.chunks_exact_mut(4).enumerate() {
+                let (x0, x1, fx) = span(px, last_x);
+                for (c, channel) in pixel.iter_mut().enumerate() {
+                    let at = |x: usize, y: usize| f32::from(samples[(y * width + x) * 4 + c]);
+                    let top = at(x0, y0).mul_add(1.0 - fx, at(x1, y0) * fx);
+                    let bottom = at(x0, y1).mul_add(1.0 - fx, at(x1, y1) * fx);
+                    *channel = top.mul_add(1.0 - fy, bottom * fy).round() as u8;
+                }
+            }
+        });
+    out
 }
 
 #[cfg(test)]
@@ -519,6 +942,30 @@ mod tests {
     use super::*;
     use crate::render::sdf_ui::{SdfPrimitive, SdfScene};
 
+    #[test]
+    fn projected_points_match_camera_rays() {
+        let cam = CameraParams {
+            azimuth: 0.4,
+            elevation: 0.3,
+            distance: 6.0,
+            target: [1.0, 0.5, -1.0],
+            mode: CameraMode::Orbit,
+        };
+        assert_eq!(
+            project_point(&cam, 1.5, cam.target).map(|p| p.map(|c| (c * 1e4).round())),
+            Some([0.0, 0.0])
+        );
+
+        // A point along the ray through (u, v) projects back onto (u, v)
+        let camera = orbit_camera(&cam);
+        let ray = camera.ray(0.5, -0.25, 1.5);
+        let point = camera.origin + ray * 4.0;
+        let [u, v] = project_point(&cam, 1.5, point.into()).unwrap();
+        assert!((u - 0.5).abs() < 1e-4 && (v + 0.25).abs() < 1e-4);
+
+        assert_eq!(project_point(&cam, 1.5, (camera.origin - ray).into()), None);
+    }
+
     #[test]
     fn renders_single_box() {
         let scene = SdfScene {
@@ -602,7 +1049,186 @@ mod tests {
             background_color: [0.1, 0.1, 0.1, 1.0],
         };
         let cam = CameraParams::default();
-        let pixels = render_sdf_interactive(&scene, 32, 24, &cam).unwrap();
+        let sky = crate::render::palette::ScenePalette::NIGHT.sky;
+        let pixels = render_sdf_interactive(&scene, 32, 24, &cam, &sky).unwrap();
         assert_eq!(pixels.len(), 32 * 24 * 4);
     }
+
+    #[test]
+    fn upsample_keeps_samples_and_blends_between() {
+        // 3×1 image sampled at x = 0 and x = 2
+        let samples = [0, 0, 0, 255, 9, 9, 9, 9, 200, 100, 50, 255];
+        let out = upsample(&samples, 3, 1, 2);
+        assert_eq!(&out[0..4], &samples[0..4]);
+        assert_eq!(&out[8..12], &samples[8..12]);
+        assert_eq!(&out[4..8], &[100, 50, 25, 255]);
+    }
+
+    #[test]
+    fn progressive_render_refines_to_the_full_frame() {
+        let scene = SdfScene {
+            primitives: vec![SdfPrimitive::Sphere {
+                center: [0.0, 0.0, 0.0],
+                radius: 0.8,
+                color: [0.2, 0.8, 0.3, 1.0],
+            }],
+            background_color: [0.1, 0.1, 0.1, 1.0],
+        };
+        let cam = CameraParams::default();
+        let sky = crate::render::palette::ScenePalette::DAY.sky;
+        let mut renderer = ProgressiveRenderer::default();
+        renderer.start(scene.clone(), 30, 20, cam, sky, 4);
+        let mut last = None;
+        while renderer.is_running() {
+            if let Some(frame) = renderer.poll() {
+                assert_eq!(frame.pixels.len(), 30 * 20 * 4);
+                last = Some(frame);
+            }
+            std::thread::yield_now();
+        }
+        let last = last.unwrap();
+        assert!(last.is_final());
+        let direct = render_sdf_interactive(&scene, 30, 20, &cam, &sky).unwrap();
+        assert_eq!(last.pixels, direct);
+
+        renderer.start(scene, 30, 20, cam, sky, 4);
+        renderer.cancel();
+        assert!(!renderer.is_running());
+        assert!(renderer.poll().is_none());
+    }
+
+    #[test]
+    fn culling_leaves_the_image_unchanged() {
+        let cube = |center: [f32; 3], color: [f32; 4]| SdfPrimitive::RoundedBox {
+            center,
+            size: [0.6; 3],
+            radius: 0.05,
+            color,
+        };
+        let scene = SdfScene {
+            primitives: vec![
+                // A wall across the view, a cube in front of it, cubes
+                // behind it and one far off to the side
+                SdfPrimitive::RoundedBox {
+                    center: [0.0, 0.0, 0.0],
+                    size: [20.0, 20.0, 0.2],
+                    radius: 0.02,
+                    color: [0.9, 0.9, 0.9, 1.0],
+                },
+                cube([0.3, 0.2, 1.5], [0.9, 0.2, 0.2, 1.0]),
+                cube([0.0, 0.0, -2.0], [0.2, 0.9, 0.2, 1.0]),
+                cube([1.0, -0.5, -4.0], [0.2, 0.2, 0.9, 1.0]),
+                cube([40.0, 0.0, 2.0], [0.9, 0.9, 0.2, 1.0]),
+            ],
+            background_color: [0.1, 0.1, 0.1, 1.0],
+        };
+        let cam = CameraParams {
+            azimuth: 0.1,
+            elevation: 0.1,
+            distance: 5.0,
+            target: [0.0, 0.0, 0.0],
+            mode: CameraMode::Orbit,
+        };
+        let sky = crate::render::palette::ScenePalette::DAY.sky;
+        let (w, h) = (40, 30);
+        let (culled, stats) = render_sdf_culled(&scene, w, h, &cam, &sky).unwrap();
+        assert_eq!(stats.outside_view, 1);
+        assert_eq!(stats.occluded, 2);
+        assert_eq!(stats.rendered(), 2);
+
+        let mut shader = Shader::new(&scene, w, h, orbit_camera(&cam), &sky).unwrap();
+        let all: Vec<u32> = (0..scene.primitives.len() as u32).collect();
+        shader.compiled = compile_scene(&scene, &all).unwrap();
+        let full: Vec<u8> = (0..h)
+            .flat_map(|py| (0..w).map(move |px| (px, py)))
+            .flat_map(|(px, py)| shader.shade(px, py))
+            .collect();
+        assert_eq!(culled, full);
+    }
+
+    #[test]
+    fn picks_the_nearest_primitive_under_the_pointer() {
+        let panel = |x: f32, z: f32| SdfPrimitive::RoundedBox {
+            center: [x, 0.0, z],
+            size: [1.0, 1.0, 0.1],
+            radius: 0.0,
+            color: [1.0; 4],
+        };
+        // A panel in front of a wider one, and one off to the side
+        let scene = SdfScene {
+            primitives: vec![
+                panel(0.0, -1.0),
+                panel(0.0, 0.0),
+                SdfPrimitive::RoundedBox {
+                    center: [0.0, 0.0, -2.0],
+                    size: [6.0, 3.0, 0.1],
+                    radius: 0.0,
+                    color: [1.0; 4],
+                },
+                panel(2.5, 0.0),
+            ],
+            background_color: [1.0; 4],
+        };
+        let cam = CameraParams {
+            azimuth: 0.0,
+            elevation: 0.0,
+            distance: 5.0,
+            target: [0.0, 0.0, 0.0],
+            mode: CameraMode::Orbit,
+        };
+        let picker = ScenePicker::new(&scene);
+        assert_eq!(picker.pick(&cam, 1.0, [0.0, 0.0]), Some(1));
+        let side = project_point(&cam, 1.0, [2.5, 0.0, 0.0]).unwrap();
+        assert_eq!(picker.pick(&cam, 1.0, side), Some(3));
+        let behind = project_point(&cam, 1.0, [-2.0, 1.0, -2.0]).unwrap();
+        assert_eq!(picker.pick(&cam, 1.0, behind), Some(2));
+        assert_eq!(picker.pick(&cam, 1.0, [0.0, 0.95]), None);
+    }
+
+    #[test]
+    fn flying_turns_about_the_eye_and_stays_in_bounds() {
+        let mut cam = CameraParams {
+            mode: CameraMode::Fly,
+            ..CameraParams::default()
+        };
+        let eye = cam.eye();
+        cam.look(0.7, -0.4);
+        let turned = cam.eye();
+        assert!((0..3).all(|i| (turned[i] - eye[i]).abs() < 1e-4));
+        // The target is `distance` ahead of the eye
+        let forward = cam.forward();
+        assert!((0..3).all(|i| (eye[i] + forward[i] * cam.distance - cam.target[i]).abs() < 1e-4));
+        cam.look(0.0, 10.0);
+        assert!((cam.elevation - FLY_PITCH_LIMIT).abs() < 1e-6);
+
+        let bounds = ([-2.0, -2.0, -2.0], [2.0, 2.0, 2.0]);
+        let before = cam.eye();
+        cam.fly(0.0, 0.0, 0.5, bounds);
+        assert!((cam.eye()[1] - (before[1] + 0.5).min(2.0)).abs() < 1e-4);
+        cam.fly(100.0, 0.0, 0.0, bounds);
+        assert!(cam.eye().iter().all(|c| c.abs() <= 2.0 + 1e-4));
+    }
+
+    #[test]
+    fn portal_flight_ends_facing_the_portal() {
+        let from = CameraParams {
+            azimuth: 0.4 + std::f32::consts::TAU * 3.0,
+            elevation: 0.5,
+            distance: 6.0,
+            target: [0.0, 0.0, 0.0],
+            mode: CameraMode::Orbit,
+        };
+        let portal = [1.0, 0.2, 0.8];
+        let start = portal_flight(&from, portal, 0.0);
+        assert!((0..3).all(|i| (start.eye()[i] - from.eye()[i]).abs() < 1e-3));
+
+        let end = portal_flight(&from, portal, 1.0);
+        assert_eq!(end.target, portal);
+        assert!(end.azimuth.abs() < 1e-6 && end.elevation.abs() < 1e-6);
+        // Just in front of the portal, looking into it
+        let eye = end.eye();
+        assert!((eye[2] - portal[2] - PORTAL_ARRIVAL).abs() < 1e-4);
+        assert!(end.forward()[2] < -0.999);
+        assert_eq!(portal_flight(&from, portal, 2.0).target, portal);
+    }
 }
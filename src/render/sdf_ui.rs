@@ -3,11 +3,17 @@
 /// Maps DOM elements to SDF primitives for procedural rendering.
 /// Phase 1: Scene description generation.
 /// Phase 2: ALICE-SDF integration for GPU rendering.
+use std::collections::HashMap;
+
+use serde::Serialize;
+
 use crate::dom::Classification;
-use crate::render::layout::LayoutNode;
+use crate::render::layout::{LayoutBox, LayoutNode};
+use crate::render::media::{MediaInfo, MediaMetadata};
 
 /// SDF primitive types for UI elements
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum SdfPrimitive {
     /// Rounded rectangle (buttons, cards, containers)
     RoundedBox {
@@ -63,7 +69,7 @@ pub enum SdfPrimitive {
 }
 
 /// Complete SDF scene for a web page
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SdfScene {
     pub primitives: Vec<SdfPrimitive>,
     pub background_color: [f32; 4],
@@ -140,8 +146,8 @@ fn emit_sdf_primitives(
                 color: [0.2, 0.5, 0.9, 1.0],
             });
         }
-        // Images: placeholder plane
-        "img" => {
+        // Images and media cards: placeholder plane
+        "img" | "video" | "audio" => {
             primitives.push(SdfPrimitive::Plane {
                 center: [
                     (b.x + b.width / 2.0) * scale,
@@ -179,14 +185,16 @@ fn emit_sdf_primitives(
         _ => {}
     }
 
-    // Text-only nodes
-    if node.tag.is_empty() && !node.text.is_empty() {
-        primitives.push(SdfPrimitive::TextLabel {
-            position: [b.x * scale, -b.y * scale, z],
-            text: node.text.clone(),
-            font_size: node.font_size * scale,
-            color: [0.2, 0.2, 0.2, 1.0],
-        });
+    // Text-only nodes, a label per line
+    if node.tag.is_empty() {
+        for (line, lb) in node.text_lines() {
+            primitives.push(SdfPrimitive::TextLabel {
+                position: [lb.x * scale, -lb.y * scale, z],
+                text: line.to_string(),
+                font_size: node.font_size * scale,
+                color: [0.2, 0.2, 0.2, 1.0],
+            });
+        }
     }
 
     for child in &node.children {
@@ -214,7 +222,8 @@ fn collect_child_text(node: &LayoutNode) -> String {
 // ── Paint elements for egui Painter-based SDF rendering ──
 
 /// Paint element kind for interactive SDF UI.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PaintKind {
     /// Card container with shadow (section, article)
     Card,
@@ -233,7 +242,7 @@ pub enum PaintKind {
 }
 
 /// A UI element for egui Painter-based SDF rendering.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PaintElement {
     pub id: usize,
     pub kind: PaintKind,
@@ -248,21 +257,48 @@ pub struct PaintElement {
     pub image_url: Option<String>,
 }
 
+/// Paint rectangle of an `<img>`: images have no intrinsic layout height
+/// yet, so the placeholder is clamped to a readable size.
+pub(crate) fn image_rect(b: &LayoutBox) -> [f32; 4] {
+    [b.x, b.y, b.width.min(400.0), b.height.clamp(60.0, 200.0)]
+}
+
 /// Convert a layout tree into paint elements for egui SDF rendering.
 #[must_use]
 pub fn layout_to_paint(root: &LayoutNode) -> Vec<PaintElement> {
+    layout_to_paint_with_media(root, &HashMap::new())
+}
+
+/// [`layout_to_paint`], with the metadata probed so far for the page's
+/// audio and video, by source as written in the page.
+#[must_use]
+pub fn layout_to_paint_with_media(
+    root: &LayoutNode,
+    media: &HashMap<String, MediaMetadata>,
+) -> Vec<PaintElement> {
     let mut elements = Vec::new();
     let mut id = 0;
-    emit_paint_elements(root, &mut elements, &mut id);
+    emit_paint_elements(root, media, &mut elements, &mut id);
     elements
 }
 
-fn emit_paint_elements(node: &LayoutNode, out: &mut Vec<PaintElement>, id: &mut usize) {
+fn emit_paint_elements(
+    node: &LayoutNode,
+    media: &HashMap<String, MediaMetadata>,
+    out: &mut Vec<PaintElement>,
+    id: &mut usize,
+) {
     let b = &node.bounds;
     if b.height <= 0.0 && node.text.is_empty() && node.children.is_empty() {
         return;
     }
 
+    if let Some(ref info) = node.media {
+        let meta = info.src.as_ref().and_then(|src| media.get(src));
+        emit_media_card(node, info, meta, out, id);
+        return;
+    }
+
     match node.tag.as_str() {
         // Container cards
         "section" | "article" | "main" | "aside" => {
@@ -282,6 +318,22 @@ fn emit_paint_elements(node: &LayoutNode, out: &mut Vec<PaintElement>, id: &mut
                 });
             }
         }
+        // Embedded documents
+        "iframe" if node.is_block => {
+            *id += 1;
+            out.push(PaintElement {
+                id: *id,
+                kind: PaintKind::Card,
+                rect: [b.x, b.y, b.width, b.height],
+                color: [0.95, 0.97, 1.0, 1.0],
+                corner_radius: 6.0,
+                shadow_depth: 2.0,
+                text: None,
+                font_size: 0.0,
+                href: None,
+                image_url: None,
+            });
+        }
         "nav" | "header" | "footer" => {
             if b.height > 5.0 {
                 *id += 1;
@@ -385,7 +437,7 @@ fn emit_paint_elements(node: &LayoutNode, out: &mut Vec<PaintElement>, id: &mut
             out.push(PaintElement {
                 id: *id,
                 kind: PaintKind::ImagePlaceholder,
-                rect: [b.x, b.y, b.width.min(400.0), b.height.clamp(60.0, 200.0)],
+                rect: image_rect(b),
                 color: [0.92, 0.92, 0.94, 1.0],
                 corner_radius: 4.0,
                 shadow_depth: 1.0,
@@ -414,27 +466,108 @@ fn emit_paint_elements(node: &LayoutNode, out: &mut Vec<PaintElement>, id: &mut
             return;
         }
         _ => {
-            // Bare text nodes
-            if node.tag.is_empty() && !node.text.is_empty() {
-                *id += 1;
-                out.push(PaintElement {
-                    id: *id,
-                    kind: PaintKind::Text,
-                    rect: [b.x, b.y, b.width, b.height],
-                    color: [0.15, 0.15, 0.18, 1.0],
-                    corner_radius: 0.0,
-                    shadow_depth: 0.0,
-                    text: Some(node.text.trim().to_string()),
-                    font_size: node.font_size,
-                    href: None,
-                    image_url: None,
-                });
+            // Bare text nodes, an element per line as layout broke them
+            if node.tag.is_empty() {
+                for (line, lb) in node.text_lines() {
+                    *id += 1;
+                    out.push(PaintElement {
+                        id: *id,
+                        kind: PaintKind::Text,
+                        rect: [lb.x, lb.y, b.width, lb.height],
+                        color: [0.15, 0.15, 0.18, 1.0],
+                        corner_radius: 0.0,
+                        shadow_depth: 0.0,
+                        text: Some(line.trim().to_string()),
+                        font_size: node.font_size,
+                        href: None,
+                        image_url: None,
+                    });
+                }
             }
         }
     }
 
     // Recurse for container elements
     for child in &node.children {
-        emit_paint_elements(child, out, id);
+        emit_paint_elements(child, media, out, id);
+    }
+}
+
+/// Card for a `<video>` or `<audio>`: the poster, if any, above a line
+/// describing the media and a link that opens it externally.
+fn emit_media_card(
+    node: &LayoutNode,
+    info: &MediaInfo,
+    meta: Option<&MediaMetadata>,
+    out: &mut Vec<PaintElement>,
+    id: &mut usize,
+) {
+    let b = &node.bounds;
+    let (x, width) = (b.x, b.width.min(640.0));
+    let line = node.font_size * 1.4;
+    let pad = 8.0;
+    let mut push = |kind: PaintKind,
+                    rect: [f32; 4],
+                    color: [f32; 4],
+                    text: Option<String>,
+                    href: Option<String>,
+                    image_url: Option<String>| {
+        *id += 1;
+        out.push(PaintElement {
+            id: *id,
+            kind,
+            rect,
+            color,
+            corner_radius: if kind == PaintKind::Card { 6.0 } else { 0.0 },
+            shadow_depth: if kind == PaintKind::Card { 2.0 } else { 0.0 },
+            text,
+            font_size: node.font_size,
+            href,
+            image_url,
+        });
+    };
+    push(
+        PaintKind::Card,
+        [x, b.y, width, b.height],
+        [0.93, 0.94, 0.96, 1.0],
+        None,
+        None,
+        None,
+    );
+    let text_y = line.mul_add(-2.0, b.y + b.height - pad);
+    if let Some(ref poster) = info.poster {
+        push(
+            PaintKind::ImagePlaceholder,
+            [
+                x + pad,
+                b.y + pad,
+                pad.mul_add(-2.0, width),
+                text_y - b.y - pad * 2.0,
+            ],
+            [0.85, 0.85, 0.88, 1.0],
+            None,
+            None,
+            Some(poster.clone()),
+        );
+    }
+    let text_x = x + pad * 1.5;
+    let text_width = pad.mul_add(-3.0, width);
+    push(
+        PaintKind::Text,
+        [text_x, text_y, text_width, line],
+        [0.3, 0.3, 0.35, 1.0],
+        Some(info.describe(meta)),
+        None,
+        None,
+    );
+    if let Some(ref src) = info.src {
+        push(
+            PaintKind::Link,
+            [text_x, text_y + line, text_width, line],
+            [0.1, 0.4, 0.85, 1.0],
+            Some(format!("\u{25b6} Open {} externally", info.name())),
+            Some(src.clone()),
+            None,
+        );
     }
 }
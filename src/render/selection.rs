@@ -0,0 +1,244 @@
+//! Text selection over painted text blocks.
+//!
+//! The painter-based SDF 2D view draws each text-bearing layout node as one
+//! block; a [`Selection`] spans blocks in reading order and addresses text
+//! by character offset within a block. Dragging sets the anchor where the
+//! drag started and moves the focus with the pointer, so selections can run
+//! backwards. Turning pointer positions into offsets needs the text's shaped
+//! layout, which the renderer owns; [`hit_block`] only decides which block a
+//! point belongs to.
+//!
+//! The Flat view renders egui labels, whose built-in selection already
+//! spans widgets and handles the copy shortcut.
+
+use std::ops::Range;
+
+/// A position in the page's text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct TextPos {
+    /// Block index, in reading order
+    pub block: usize,
+    /// Character (not byte) offset within the block
+    pub offset: usize,
+}
+
+impl TextPos {
+    #[must_use]
+    pub const fn new(block: usize, offset: usize) -> Self {
+        Self { block, offset }
+    }
+}
+
+/// A selected range of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+    /// Where the drag started
+    pub anchor: TextPos,
+    /// Where the drag is now
+    pub focus: TextPos,
+}
+
+impl Selection {
+    /// Empty selection at `pos`.
+    #[must_use]
+    pub const fn caret(pos: TextPos) -> Self {
+        Self {
+            anchor: pos,
+            focus: pos,
+        }
+    }
+
+    #[must_use]
+    pub fn start(&self) -> TextPos {
+        self.anchor.min(self.focus)
+    }
+
+    #[must_use]
+    pub fn end(&self) -> TextPos {
+        self.anchor.max(self.focus)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.anchor == self.focus
+    }
+
+    /// Characters selected in `block`, which is `len` characters long.
+    #[must_use]
+    pub fn range_in(&self, block: usize, len: usize) -> Option<Range<usize>> {
+        let (start, end) = (self.start(), self.end());
+        if block < start.block || block > end.block {
+            return None;
+        }
+        let from = if block == start.block {
+            start.offset.min(len)
+        } else {
+            0
+        };
+        let to = if block == end.block {
+            end.offset.min(len)
+        } else {
+            len
+        };
+        (from < to).then_some(from..to)
+    }
+
+    /// The selected text, one line per block. `blocks` holds each block's
+    /// text by index; blocks without text contribute nothing.
+    #[must_use]
+    pub fn text(&self, blocks: &[&str]) -> String {
+        let mut lines = Vec::new();
+        for (i, block) in blocks.iter().enumerate() {
+            let len = block.chars().count();
+            if let Some(range) = self.range_in(i, len) {
+                let part: String = block.chars().skip(range.start).take(range.len()).collect();
+                lines.push(part);
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+/// Drag-selection state of one view.
+#[derive(Debug, Clone, Default)]
+pub struct SelectionState {
+    selection: Option<Selection>,
+    dragging: bool,
+}
+
+impl SelectionState {
+    /// Start a drag at `pos`, replacing any selection.
+    pub fn begin(&mut self, pos: TextPos) {
+        self.selection = Some(Selection::caret(pos));
+        self.dragging = true;
+    }
+
+    /// Move the focus of an ongoing drag.
+    pub fn extend(&mut self, pos: TextPos) {
+        if let Some(ref mut sel) = self.selection {
+            if self.dragging {
+                sel.focus = pos;
+            }
+        }
+    }
+
+    pub fn finish(&mut self) {
+        self.dragging = false;
+    }
+
+    pub fn clear(&mut self) {
+        self.selection = None;
+        self.dragging = false;
+    }
+
+    /// Select every block; `last_len` is the last block's length.
+    pub fn select_all(&mut self, blocks: usize, last_len: usize) {
+        if blocks == 0 {
+            return self.clear();
+        }
+        self.selection = Some(Selection {
+            anchor: TextPos::new(0, 0),
+            focus: TextPos::new(blocks - 1, last_len),
+        });
+        self.dragging = false;
+    }
+
+    #[must_use]
+    pub const fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    /// The current selection, if it covers any text.
+    #[must_use]
+    pub fn selection(&self) -> Option<&Selection> {
+        self.selection.as_ref().filter(|s| !s.is_empty())
+    }
+}
+
+/// Where a point falls relative to a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hit {
+    /// Over the block's text
+    Inside(usize),
+    /// Above or left of the block: snaps to its start
+    Before(usize),
+    /// Below or right of the block: snaps to its end
+    After(usize),
+}
+
+/// The block nearest to `pos`, given each block's `[x, y, w, h]` rect in
+/// reading order. Points over several blocks pick the last (innermost).
+#[must_use]
+pub fn hit_block(rects: &[[f32; 4]], pos: [f32; 2]) -> Option<Hit> {
+    let [px, py] = pos;
+    if let Some(i) = rects
+        .iter()
+        .rposition(|r| px >= r[0] && px <= r[0] + r[2] && py >= r[1] && py <= r[1] + r[3])
+    {
+        return Some(Hit::Inside(i));
+    }
+
+    // Nearest block by vertical distance, then horizontal
+    let distance = |r: &[f32; 4]| {
+        let dy = (r[1] - py).max(py - (r[1] + r[3])).max(0.0);
+        let dx = (r[0] - px).max(px - (r[0] + r[2])).max(0.0);
+        (dy, dx)
+    };
+    let (i, r) = rects.iter().enumerate().min_by(|(_, a), (_, b)| {
+        distance(a)
+            .partial_cmp(&distance(b))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    })?;
+    let before = py < r[1] || (py <= r[1] + r[3] && px < r[0]);
+    Some(if before {
+        Hit::Before(i)
+    } else {
+        Hit::After(i)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backwards_selection_spans_blocks() {
+        let blocks = ["Hello world", "", "second block", "tail"];
+        let sel = Selection {
+            anchor: TextPos::new(2, 6),
+            focus: TextPos::new(0, 6),
+        };
+        assert_eq!(sel.range_in(0, 11), Some(6..11));
+        assert_eq!(sel.range_in(2, 12), Some(0..6));
+        assert_eq!(sel.range_in(3, 4), None);
+        assert_eq!(sel.text(&blocks), "world\nsecond");
+
+        let mut state = SelectionState::default();
+        state.begin(TextPos::new(1, 0));
+        assert!(state.selection().is_none());
+        state.select_all(blocks.len(), 4);
+        assert_eq!(
+            state.selection().unwrap().text(&blocks),
+            "Hello world\nsecond block\ntail"
+        );
+    }
+
+    #[test]
+    fn offsets_count_characters() {
+        let sel = Selection {
+            anchor: TextPos::new(0, 1),
+            focus: TextPos::new(0, 3),
+        };
+        assert_eq!(sel.text(&["日本語です"]), "本語");
+    }
+
+    #[test]
+    fn hit_snaps_to_nearest_block() {
+        let rects = [[0.0, 0.0, 100.0, 20.0], [0.0, 40.0, 100.0, 20.0]];
+        assert_eq!(hit_block(&rects, [10.0, 10.0]), Some(Hit::Inside(0)));
+        assert_eq!(hit_block(&rects, [10.0, 38.0]), Some(Hit::Before(1)));
+        assert_eq!(hit_block(&rects, [10.0, 25.0]), Some(Hit::After(0)));
+        assert_eq!(hit_block(&rects, [150.0, 50.0]), Some(Hit::After(1)));
+        assert_eq!(hit_block(&[], [0.0, 0.0]), None);
+    }
+}
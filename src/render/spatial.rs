@@ -20,6 +20,7 @@
 ///   - `<img>`                → Framed picture on wall
 ///   - `<hr>`                 → Floor line
 use crate::render::layout::LayoutNode;
+use crate::render::palette::ScenePalette;
 use crate::render::sdf_ui::{SdfPrimitive, SdfScene};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -52,55 +53,41 @@ enum SdfElement {
     Invisible,
 }
 
-fn classify_tag(tag: &str, depth: u32) -> SdfElement {
+fn classify_tag(tag: &str, depth: u32, palette: &ScenePalette) -> SdfElement {
+    let heading = |level: u8| SdfElement::Heading {
+        level,
+        color: palette.headings[usize::from(level.min(3) - 1)],
+    };
     match tag {
         "section" | "article" | "main" | "div" => SdfElement::Wall {
             thickness: (depth as f32).mul_add(0.03, 0.10),
-            color: [0.93, 0.93, 0.96, 0.85],
+            color: palette.wall,
         },
         "nav" | "header" | "footer" => SdfElement::Beam {
-            color: [0.75, 0.78, 0.85, 1.0],
-        },
-        "h1" => SdfElement::Heading {
-            level: 1,
-            color: [0.95, 0.85, 0.4, 1.0],
-        },
-        "h2" => SdfElement::Heading {
-            level: 2,
-            color: [0.90, 0.82, 0.5, 1.0],
-        },
-        "h3" => SdfElement::Heading {
-            level: 3,
-            color: [0.85, 0.83, 0.6, 1.0],
-        },
-        "h4" => SdfElement::Heading {
-            level: 4,
-            color: [0.85, 0.83, 0.6, 1.0],
-        },
-        "h5" => SdfElement::Heading {
-            level: 5,
-            color: [0.85, 0.83, 0.6, 1.0],
-        },
-        "h6" => SdfElement::Heading {
-            level: 6,
-            color: [0.85, 0.83, 0.6, 1.0],
+            color: palette.beam,
         },
+        "h1" => heading(1),
+        "h2" => heading(2),
+        "h3" => heading(3),
+        "h4" => heading(4),
+        "h5" => heading(5),
+        "h6" => heading(6),
         "a" => SdfElement::Portal {
             thickness: 0.15,
-            color: [0.10, 0.40, 0.95, 1.0],
+            color: palette.portal,
         },
         "button" | "input" => SdfElement::Button {
             thickness: 0.10,
-            color: [0.20, 0.55, 0.95, 1.0],
+            color: palette.button,
         },
         "p" | "span" | "li" => SdfElement::Panel {
-            color: [0.98, 0.98, 1.0, 0.7],
+            color: palette.panel,
         },
         "ul" | "ol" => SdfElement::List,
         "img" => SdfElement::Picture,
         "hr" => SdfElement::Separator,
         "" => SdfElement::Text {
-            color: [0.96, 0.96, 0.98, 0.6],
+            color: palette.text,
         },
         _ => SdfElement::Invisible,
     }
@@ -123,6 +110,8 @@ pub struct SpatialConfig {
     pub corridor_item_spacing: f32,
     /// Minimum number of similar children to trigger corridor
     pub corridor_min_items: usize,
+    /// Element and sky colours (see `render::palette`)
+    pub palette: ScenePalette,
 }
 
 impl Default for SpatialConfig {
@@ -133,6 +122,7 @@ impl Default for SpatialConfig {
             protrusion: 0.35,
             corridor_item_spacing: 0.6,
             corridor_min_items: 3,
+            palette: ScenePalette::DAY,
         }
     }
 }
@@ -144,6 +134,8 @@ impl Default for SpatialConfig {
 struct SpatialBuilder {
     cfg: SpatialConfig,
     primitives: Vec<SdfPrimitive>,
+    /// Element behind each primitive, when asked for
+    sources: Option<Vec<Option<ElementSource>>>,
 }
 
 impl SpatialBuilder {
@@ -151,11 +143,21 @@ impl SpatialBuilder {
         Self {
             cfg,
             primitives: Vec::new(),
+            sources: None,
         }
     }
 
-    /// Entry point: build the full 3D scene from a layout tree
-    fn build(mut self, root: &LayoutNode) -> SdfScene {
+    /// Credit the primitives pushed since the last call to `node`.
+    fn attribute(&mut self, node: Option<&LayoutNode>) {
+        if let Some(ref mut sources) = self.sources {
+            let source = node.map(ElementSource::of);
+            sources.resize(self.primitives.len(), source);
+        }
+    }
+
+    /// Entry point: build the full 3D scene from a layout tree, and the
+    /// primitives' sources if recorded
+    fn build(mut self, root: &LayoutNode) -> (SdfScene, Option<Vec<Option<ElementSource>>>) {
         let s = self.cfg.pixel_to_meter;
         let width = (root.bounds.width * s).max(1.0);
         let depth = (root.bounds.height * s).max(1.0);
@@ -165,26 +167,29 @@ impl SpatialBuilder {
             center: [width / 2.0, -0.02, -depth / 2.0],
             size: [width + 0.5, 0.04, depth + 0.5],
             radius: 0.02,
-            color: [0.88, 0.88, 0.90, 1.0],
+            color: self.cfg.palette.ground,
         });
+        self.attribute(None);
 
         self.traverse(root, 0);
 
-        SdfScene {
+        let scene = SdfScene {
             primitives: self.primitives,
-            background_color: [0.55, 0.75, 0.95, 1.0],
-        }
+            background_color: self.cfg.palette.background,
+        };
+        (scene, self.sources)
     }
 
     /// Traverse the DOM tree, classifying each node and emitting primitives
     fn traverse(&mut self, node: &LayoutNode, depth: u32) {
-        let element = classify_tag(node.tag.as_str(), depth);
+        let element = classify_tag(node.tag.as_str(), depth, &self.cfg.palette);
 
         // Check for feed pattern on containers and lists
         match &element {
             SdfElement::Wall { .. } | SdfElement::List => {
                 if let Some(feed_items) = detect_feed_pattern(node, &self.cfg) {
                     self.emit_element(node, &element, depth);
+                    self.attribute(Some(node));
                     self.emit_corridor(node, &feed_items, depth);
                     return; // corridor handled all children
                 }
@@ -194,6 +199,7 @@ impl SpatialBuilder {
 
         // Emit the element itself
         let is_leaf = self.emit_element(node, &element, depth);
+        self.attribute(Some(node));
 
         if is_leaf {
             return;
@@ -308,14 +314,14 @@ impl SpatialBuilder {
                     center: [cx, img_h / 2.0 + 0.02, z_base + z_forward + 0.06],
                     size: [img_w + 0.04, img_h + 0.04, 0.025],
                     radius: 0.01,
-                    color: [0.3, 0.3, 0.32, 1.0],
+                    color: self.cfg.palette.picture_frame,
                 });
                 // Picture surface
                 self.primitives.push(SdfPrimitive::RoundedBox {
                     center: [cx, img_h / 2.0 + 0.02, z_base + z_forward + 0.075],
                     size: [img_w, img_h, 0.01],
                     radius: 0.005,
-                    color: [0.75, 0.78, 0.82, 1.0],
+                    color: self.cfg.palette.picture,
                 });
                 true // leaf
             }
@@ -325,7 +331,7 @@ impl SpatialBuilder {
                     start: [b.x * s, 0.005, z_base + z_forward],
                     end: [(b.x + b.width) * s, 0.005, z_base + z_forward],
                     thickness: 0.008,
-                    color: [0.6, 0.6, 0.65, 1.0],
+                    color: self.cfg.palette.separator,
                 });
                 true // leaf
             }
@@ -374,9 +380,10 @@ impl SpatialBuilder {
                 ],
                 size: [0.03, 0.6, corridor_len],
                 radius: 0.01,
-                color: [0.85, 0.85, 0.90, 0.6],
+                color: self.cfg.palette.corridor_wall,
             });
         }
+        self.attribute(None);
 
         // ── Each feed item as a panel + floor divider ──
         for (i, item) in items.iter().enumerate() {
@@ -390,8 +397,9 @@ impl SpatialBuilder {
                 center: [cx, item_h / 2.0 + 0.01, item_z],
                 size: [item_w, item_h, 0.02],
                 radius: 0.008,
-                color: [0.96, 0.96, 1.0, 0.8],
+                color: self.cfg.palette.card,
             });
+            self.attribute(Some(item));
 
             // Floor divider line between items
             if i > 0 {
@@ -400,8 +408,9 @@ impl SpatialBuilder {
                     start: [cx - item_w / 2.0, 0.003, div_z],
                     end: [cx + item_w / 2.0, 0.003, div_z],
                     thickness: 0.006,
-                    color: [0.70, 0.70, 0.75, 0.5],
+                    color: self.cfg.palette.divider,
                 });
+                self.attribute(None);
             }
 
             // Recurse into each item's children (links, headings, text, images)
@@ -495,7 +504,47 @@ fn detect_feed_pattern<'a>(
 #[must_use]
 pub fn layout_to_spatial(root: &LayoutNode, config: &SpatialConfig) -> SdfScene {
     let builder = SpatialBuilder::new(config.clone());
-    builder.build(root)
+    builder.build(root).0
+}
+
+/// Longest text kept in an [`ElementSource`], in characters.
+const SOURCE_TEXT_MAX: usize = 160;
+
+/// The page element a primitive of a spatial scene was built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementSource {
+    pub tag: String,
+    /// Text of the element, cut to [`SOURCE_TEXT_MAX`] characters
+    pub text: String,
+    pub href: Option<String>,
+}
+
+impl ElementSource {
+    fn of(node: &LayoutNode) -> Self {
+        let mut text = collect_text(node);
+        if let Some((cut, _)) = text.char_indices().nth(SOURCE_TEXT_MAX) {
+            text.truncate(cut);
+            text.push('\u{2026}');
+        }
+        Self {
+            tag: node.tag.clone(),
+            text,
+            href: node.href.clone(),
+        }
+    }
+}
+
+/// [`layout_to_spatial`], along with the element behind each primitive
+/// (`None` for the ground, corridor walls and dividers).
+#[must_use]
+pub fn layout_to_spatial_with_sources(
+    root: &LayoutNode,
+    config: &SpatialConfig,
+) -> (SdfScene, Vec<Option<ElementSource>>) {
+    let mut builder = SpatialBuilder::new(config.clone());
+    builder.sources = Some(Vec::new());
+    let (scene, sources) = builder.build(root);
+    (scene, sources.unwrap_or_default())
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -1057,3 +1106,38 @@ fn collect_text(node: &LayoutNode) -> String {
     }
     text
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+    use crate::render::layout::compute_layout_with_font;
+
+    #[test]
+    fn every_primitive_has_a_source_slot() {
+        let dom = parse_html(
+            "<main><h1>Title</h1><p>Some text</p>\
+             <ul><li>One</li><li>Two</li><li>Three</li></ul>\
+             <a href=\"https://example.com/next\">Next page</a></main>",
+            "https://example.com/",
+        );
+        let layout = compute_layout_with_font(&dom.root, 800.0, 16.0);
+        let config = SpatialConfig::default();
+        let (scene, sources) = layout_to_spatial_with_sources(&layout, &config);
+        assert_eq!(sources.len(), scene.primitives.len());
+        assert_eq!(
+            scene.primitives.len(),
+            layout_to_spatial(&layout, &config).primitives.len()
+        );
+        // The ground comes first and belongs to no element
+        assert!(sources[0].is_none());
+        let link = sources
+            .iter()
+            .flatten()
+            .find(|s| s.tag == "a")
+            .expect("link primitive");
+        assert_eq!(link.text, "Next page");
+        assert_eq!(link.href.as_deref(), Some("https://example.com/next"));
+        assert!(sources.iter().flatten().any(|s| s.text == "Two"));
+    }
+}
@@ -11,8 +11,23 @@
 ///
 /// All text faces the center (billboarding), so it's always readable.
 /// Drag to look around; click to grab & inspect.
+///
+/// A search ([`StreamState::search`]) runs over the whole text pool — the
+/// page and whatever prefetch has added since — dims the particles that
+/// don't match and pulls the ones that do toward the viewer, bringing
+/// matches onto the wall as particles respawn.
+///
+/// How important a page's text is — its lifetime on the wall, its size,
+/// whether a past page's galaxy keeps it — comes from its layout
+/// ([`score`]).
 use crate::render::layout::LayoutNode;
 use crate::render::sdf_ui::SdfScene;
+use crate::simd::particles::{lifecycle_opacity, update_particles};
+use crate::simd::soa::ParticlesSoA;
+
+pub mod score;
+
+use score::{link_density, Scorer};
 
 // ── Category ──
 
@@ -40,9 +55,27 @@ pub struct TextMeta {
     pub importance: f32,
 }
 
+impl TextMeta {
+    /// Whether every one of `terms` (lowercase) occurs in the text or its
+    /// link.
+    #[must_use]
+    pub fn matches_terms(&self, terms: &[String]) -> bool {
+        let haystack = format!(
+            "{}\n{}\n{}",
+            self.display,
+            self.full_text,
+            self.href.as_deref().unwrap_or_default()
+        )
+        .to_lowercase();
+        terms.iter().all(|t| haystack.contains(t.as_str()))
+    }
+}
+
 // ── GrabbedInfo ──
 
 pub struct GrabbedInfo<'a> {
+    /// Index into `particles` (and `motion`)
+    pub index: usize,
     pub particle: &'a TextParticle,
     pub meta: &'a TextMeta,
     pub category_name: &'a str,
@@ -62,17 +95,11 @@ pub enum RotundaLayer {
 
 // ── TextParticle ──
 
+/// What a particle shows. Its motion — angle, height, age, pull — lives in
+/// [`StreamState::motion`] at the same index.
 #[derive(Debug, Clone)]
 pub struct TextParticle {
     pub text: String,
-    /// Base angle on the cylinder wall (radians, 0–2π)
-    pub angle: f32,
-    /// Y position on the cylinder wall
-    pub y_pos: f32,
-    /// Time alive (seconds)
-    pub age: f32,
-    /// Total lifetime before respawn (seconds)
-    pub lifetime: f32,
     pub category_index: usize,
     /// 0.0 = niche, 1.0 = trending
     pub importance: f32,
@@ -92,6 +119,8 @@ pub struct TextParticle {
 #[derive(Debug, Clone)]
 pub struct StreamState {
     pub particles: Vec<TextParticle>,
+    /// Per-particle motion, `SoA` for the SIMD update
+    pub motion: ParticlesSoA,
     pub categories: Vec<StreamCategory>,
     pub text_pool: Vec<TextMeta>,
     pool_cursor: usize,
@@ -100,6 +129,18 @@ pub struct StreamState {
     pub time: f32,
     /// Currently grabbed particle
     pub grabbed_index: Option<usize>,
+    /// Text-pool range (document section) whose particles are pulled forward
+    pub focus_range: Option<(usize, usize)>,
+    /// Lowercase words of the current search; empty when not searching
+    search_terms: Vec<String>,
+    /// Text-pool entries matching the search, ascending
+    pub search_hits: Vec<usize>,
+    search_cursor: usize,
+    /// Particles respawned by the last `update_flow`
+    spawned: Vec<usize>,
+    /// Per-category respawn cursors, when categories are topics kept in
+    /// their own sectors (see [`StreamState::from_topics`])
+    topic_cursors: Option<Vec<usize>>,
 }
 
 // ── Constants ──
@@ -130,14 +171,25 @@ const LOWER_SLOTS: usize = 20;
 /// Lifecycle
 const LIFETIME_MIN: f32 = 15.0;
 const LIFETIME_MAX: f32 = 30.0;
-const FADE_IN_DURATION: f32 = 1.5;
-const FADE_OUT_DURATION: f32 = 2.5;
+pub const FADE_IN_DURATION: f32 = 1.5;
+pub const FADE_OUT_DURATION: f32 = 2.5;
+
+/// Fraction of the radius a focused particle moves toward the viewer
+const PULL_DEPTH: f32 = 0.35;
+/// Pull easing rate (1/s)
+const PULL_RATE: f32 = 4.0;
+
+/// Opacity factor of particles that don't match the search
+pub const SEARCH_DIM: f32 = 0.15;
 
 /// Angular jitter
 const ANGULAR_JITTER: f32 = 0.04;
 /// Y jitter
 const Y_JITTER: f32 = 0.15;
 
+/// Most categories told apart by colour; more wrap around the palette.
+pub const MAX_TOPICS: usize = CATEGORY_COLORS.len();
+
 /// Category colors — dark/saturated for white background
 const CATEGORY_COLORS: &[[f32; 4]] = &[
     [0.75, 0.12, 0.12, 1.0], // Dark Red
@@ -150,11 +202,24 @@ const CATEGORY_COLORS: &[[f32; 4]] = &[
     [0.65, 0.18, 0.35, 1.0], // Dark Pink
 ];
 
+fn in_range(range: Option<(usize, usize)>, idx: usize) -> bool {
+    range.is_some_and(|(start, end)| (start..end).contains(&idx))
+}
+
 fn stream_hash(seed: usize) -> f32 {
     let x = seed.wrapping_mul(2_654_435_761) ^ seed.wrapping_mul(340_573_321);
     ((x & 0xFFFF) as f32) / 65535.0
 }
 
+/// Rotation speed of a layer (rad/s).
+const fn layer_speed(layer: RotundaLayer) -> f32 {
+    match layer {
+        RotundaLayer::Upper => UPPER_SPEED,
+        RotundaLayer::Eye => EYE_SPEED,
+        RotundaLayer::Lower => LOWER_SPEED,
+    }
+}
+
 // ── Layer classification based on tag/importance ──
 
 fn classify_layer(meta: &TextMeta) -> RotundaLayer {
@@ -182,6 +247,7 @@ impl StreamState {
     pub fn from_layout(root: &LayoutNode) -> Self {
         let mut categories = Vec::new();
         let mut text_pool: Vec<TextMeta> = Vec::new();
+        let scorer = Scorer::for_page(root);
 
         let top_children: Vec<&LayoutNode> = root
             .children
@@ -193,7 +259,7 @@ impl StreamState {
             let name = extract_category_name(child);
             let color = CATEGORY_COLORS[ci % CATEGORY_COLORS.len()];
             categories.push(StreamCategory { name, color });
-            collect_rich_texts(child, ci, &mut text_pool);
+            collect_rich_texts(child, ci, &scorer, link_density(child), &mut text_pool);
         }
 
         if categories.is_empty() {
@@ -201,9 +267,60 @@ impl StreamState {
                 name: "INFO".into(),
                 color: [0.3, 0.3, 0.3, 1.0],
             });
-            collect_rich_texts(root, 0, &mut text_pool);
+            collect_rich_texts(root, 0, &scorer, link_density(root), &mut text_pool);
         }
 
+        Self::from_pool(categories, text_pool, false)
+    }
+
+    /// Stream of texts gathered elsewhere, one category (and colour) per
+    /// group — e.g. the pages of a batch session, grouped by topic.
+    #[must_use]
+    pub fn from_groups(groups: Vec<(String, Vec<TextMeta>)>) -> Self {
+        let mut categories = Vec::with_capacity(groups.len());
+        let mut text_pool = Vec::new();
+        for (ci, (name, texts)) in groups.into_iter().enumerate() {
+            categories.push(StreamCategory {
+                name,
+                color: CATEGORY_COLORS[ci % CATEGORY_COLORS.len()],
+            });
+            text_pool.extend(texts.into_iter().map(|t| TextMeta {
+                category_index: ci,
+                ..t
+            }));
+        }
+        Self::from_pool(categories, text_pool, false)
+    }
+
+    /// Stream of `text_pool` by topic: `names[i]` names the topic of the
+    /// entries whose `category_index` is `i` (as `search::cluster` sets
+    /// it). Each topic's texts sit together in
+    /// sectors of the wall, and particles respawn with texts of their own
+    /// topic, so the sectors hold as the stream turns. The pool keeps its
+    /// document order.
+    #[must_use]
+    pub fn from_topics(names: Vec<String>, text_pool: Vec<TextMeta>) -> Self {
+        let topics = names.len();
+        let categories = names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| StreamCategory {
+                name,
+                color: CATEGORY_COLORS[i % CATEGORY_COLORS.len()],
+            })
+            .collect();
+        let mut state = Self::from_pool(categories, text_pool, true);
+        state.topic_cursors = Some(vec![0; topics]);
+        state
+    }
+
+    /// Place `text_pool` on the rotunda's three rings; `sectored` groups
+    /// each ring's texts by category.
+    fn from_pool(
+        categories: Vec<StreamCategory>,
+        text_pool: Vec<TextMeta>,
+        sectored: bool,
+    ) -> Self {
         // Classify texts into 3 layers
         let mut upper_pool: Vec<usize> = Vec::new();
         let mut eye_pool: Vec<usize> = Vec::new();
@@ -229,8 +346,16 @@ impl StreamState {
         if eye_pool.is_empty() && !text_pool.is_empty() {
             eye_pool = (0..text_pool.len()).collect();
         }
+        if sectored {
+            // Neighbouring slots sit side by side on the wall
+            for pool in [&mut upper_pool, &mut eye_pool, &mut lower_pool] {
+                pool.sort_by_key(|&i| text_pool[i].category_index);
+            }
+        }
 
         let mut particles = Vec::new();
+        let mut motion =
+            ParticlesSoA::with_capacity(UPPER_SLOTS + EYE_SLOTS * EYE_ROWS + LOWER_SLOTS);
         let mut next_id: usize = 0;
 
         // ── Upper Ring ──
@@ -252,10 +377,6 @@ impl StreamState {
 
             particles.push(TextParticle {
                 text: meta.display.clone(),
-                angle: base_angle + jitter_a,
-                y_pos: y,
-                age,
-                lifetime,
                 category_index: meta.category_index,
                 importance: meta.importance,
                 grabbed: false,
@@ -264,6 +385,13 @@ impl StreamState {
                 layer: RotundaLayer::Upper,
                 slot_index: slot,
             });
+            motion.push(
+                base_angle + jitter_a,
+                y,
+                layer_speed(RotundaLayer::Upper),
+                age,
+                lifetime,
+            );
             next_id += 1;
         }
 
@@ -295,10 +423,6 @@ impl StreamState {
 
             particles.push(TextParticle {
                 text: meta.display.clone(),
-                angle: base_angle + jitter_a,
-                y_pos: y,
-                age,
-                lifetime,
                 category_index: meta.category_index,
                 importance: meta.importance,
                 grabbed: false,
@@ -307,6 +431,13 @@ impl StreamState {
                 layer: RotundaLayer::Eye,
                 slot_index: slot,
             });
+            motion.push(
+                base_angle + jitter_a,
+                y,
+                layer_speed(RotundaLayer::Eye),
+                age,
+                lifetime,
+            );
             next_id += 1;
         }
 
@@ -329,10 +460,6 @@ impl StreamState {
 
             particles.push(TextParticle {
                 text: meta.display.clone(),
-                angle: base_angle + jitter_a,
-                y_pos: y,
-                age,
-                lifetime,
                 category_index: meta.category_index,
                 importance: meta.importance,
                 grabbed: false,
@@ -341,66 +468,109 @@ impl StreamState {
                 layer: RotundaLayer::Lower,
                 slot_index: slot,
             });
+            motion.push(
+                base_angle + jitter_a,
+                y,
+                layer_speed(RotundaLayer::Lower),
+                age,
+                lifetime,
+            );
             next_id += 1;
         }
 
         let pool_cursor: usize = next_id;
 
-        Self {
+        let mut state = Self {
             particles,
+            motion,
             categories,
             text_pool,
             pool_cursor,
             next_id,
             time: 0.0,
             grabbed_index: None,
+            focus_range: None,
+            search_terms: Vec::new(),
+            search_hits: Vec::new(),
+            search_cursor: 0,
+            spawned: Vec::new(),
+            topic_cursors: None,
+        };
+        for i in 0..state.motion.count {
+            state.refresh_opacity(i);
         }
+        state
     }
 
     /// Update: rotate each layer at its own speed, respawn expired particles.
+    ///
+    /// Motion is advanced 8 particles at a time on the `SoA` layout (see
+    /// [`crate::simd::particles`]).
     pub fn update_flow(&mut self, dt: f32) -> bool {
         if self.particles.is_empty() {
             return false;
         }
 
         self.time += dt;
+        let ease = (PULL_RATE * dt).min(1.0);
         let mut respawn_indices = Vec::new();
+        update_particles(&mut self.motion, dt, ease, &mut respawn_indices);
 
-        for (i, p) in self.particles.iter_mut().enumerate() {
-            if p.grabbed {
-                continue;
-            }
-
-            // Rotate based on layer
-            let speed = match p.layer {
-                RotundaLayer::Upper => UPPER_SPEED,
-                RotundaLayer::Eye => EYE_SPEED,
-                RotundaLayer::Lower => LOWER_SPEED,
-            };
-            p.angle += speed * dt;
-
-            // Age & respawn
-            p.age += dt;
-            if p.age >= p.lifetime {
-                respawn_indices.push(i);
-            }
-        }
-
+        self.spawned.clear();
         for i in respawn_indices {
             self.respawn_at(i);
+            self.spawned.push(i);
         }
 
         true
     }
 
+    /// Particles that started over with new text in the last
+    /// [`Self::update_flow`], for spawn effects.
+    #[must_use]
+    pub fn spawned(&self) -> &[usize] {
+        &self.spawned
+    }
+
     fn respawn_at(&mut self, pi: usize) {
         if self.text_pool.is_empty() {
             return;
         }
 
-        let idx = self.pool_cursor % self.text_pool.len();
-        self.pool_cursor = self.pool_cursor.wrapping_add(1);
+        // While searching, every other respawn shows a match
+        let idx = if !self.search_hits.is_empty() && self.next_id.is_multiple_of(2) {
+            self.next_search_hit()
+        } else if let Some(idx) = self.next_in_topic(self.particles[pi].category_index) {
+            idx
+        } else {
+            let idx = self.pool_cursor % self.text_pool.len();
+            self.pool_cursor = self.pool_cursor.wrapping_add(1);
+            idx
+        };
+        self.respawn_with(pi, idx);
+    }
+
+    /// Next text of topic `category` to bring onto the wall, round-robin;
+    /// `None` unless the stream is by topic.
+    fn next_in_topic(&mut self, category: usize) -> Option<usize> {
+        let cursor = self.topic_cursors.as_mut()?.get_mut(category)?;
+        let len = self.text_pool.len();
+        let idx = (0..len)
+            .map(|o| (*cursor + o) % len)
+            .find(|&i| self.text_pool[i].category_index == category)?;
+        *cursor = idx + 1;
+        Some(idx)
+    }
+
+    /// Next match to bring onto the wall, round-robin.
+    fn next_search_hit(&mut self) -> usize {
+        let idx = self.search_hits[self.search_cursor % self.search_hits.len()];
+        self.search_cursor = self.search_cursor.wrapping_add(1);
+        idx
+    }
 
+    /// Restart particle `pi` showing text-pool entry `idx`.
+    fn respawn_with(&mut self, pi: usize, idx: usize) {
         let meta = &self.text_pool[idx];
         let display = meta.display.clone();
         let cat_idx = meta.category_index;
@@ -417,6 +587,8 @@ impl StreamState {
         p.category_index = cat_idx;
         p.importance = importance;
         p.pool_index = idx;
+        p.grabbed = false;
+        p.id = seed;
 
         // Maintain structural slot position with fresh jitter
         let slots_total = match layer {
@@ -429,67 +601,232 @@ impl StreamState {
         let jitter_a = (stream_hash(seed * 37) - 0.5) * 2.0 * ANGULAR_JITTER;
 
         // Current rotation offset (so new text appears in-phase with layer)
-        let layer_speed = match layer {
-            RotundaLayer::Upper => UPPER_SPEED,
-            RotundaLayer::Eye => EYE_SPEED,
-            RotundaLayer::Lower => LOWER_SPEED,
-        };
-        let rotation_offset = layer_speed * self.time;
-
-        p.angle = base_angle + jitter_a + rotation_offset;
+        let rotation_offset = layer_speed(layer) * self.time;
 
         let (y_min, y_max) = match layer {
             RotundaLayer::Upper => (UPPER_Y_MIN, UPPER_Y_MAX),
             RotundaLayer::Eye => (EYE_Y_MIN, EYE_Y_MAX),
             RotundaLayer::Lower => (LOWER_Y_MIN, LOWER_Y_MAX),
         };
-        p.y_pos = y_min + stream_hash(seed * 53) * (y_max - y_min);
-        p.lifetime = importance.mul_add(LIFETIME_MAX - LIFETIME_MIN, LIFETIME_MIN)
+
+        let pull_target = self.pull_target(idx);
+        let m = &mut self.motion;
+        m.angles.as_mut_slice()[pi] = base_angle + jitter_a + rotation_offset;
+        m.ys.as_mut_slice()[pi] = y_min + stream_hash(seed * 53) * (y_max - y_min);
+        m.lifetimes.as_mut_slice()[pi] = importance
+            .mul_add(LIFETIME_MAX - LIFETIME_MIN, LIFETIME_MIN)
             + stream_hash(seed * 71) * 3.0;
-        p.age = 0.0;
-        p.grabbed = false;
-        p.id = seed;
+        m.ages.as_mut_slice()[pi] = 0.0;
+        m.moving.as_mut_slice()[pi] = 1.0;
+        m.pull_targets.as_mut_slice()[pi] = pull_target;
+        self.refresh_opacity(pi);
+    }
+
+    /// Reduced-motion layout: pin every particle to an evenly spaced grid
+    /// cell on the wall, fully faded in and without jitter.
+    ///
+    /// Callers stop calling [`Self::update_flow`] while frozen; resuming it
+    /// lets the rotunda rotate again from the grid positions.
+    pub fn arrange_static(&mut self) {
+        let upper_count = self
+            .particles
+            .iter()
+            .filter(|p| p.layer == RotundaLayer::Upper)
+            .count()
+            .max(1);
+        let lower_count = self
+            .particles
+            .iter()
+            .filter(|p| p.layer == RotundaLayer::Lower)
+            .count()
+            .max(1);
+
+        for (i, p) in self.particles.iter().enumerate() {
+            let (col, cols, y) = match p.layer {
+                RotundaLayer::Upper => {
+                    (p.slot_index, upper_count, (UPPER_Y_MIN + UPPER_Y_MAX) * 0.5)
+                }
+                RotundaLayer::Lower => {
+                    (p.slot_index, lower_count, (LOWER_Y_MIN + LOWER_Y_MAX) * 0.5)
+                }
+                RotundaLayer::Eye => {
+                    let row = (p.slot_index / EYE_SLOTS).min(EYE_ROWS - 1);
+                    let row_frac = if EYE_ROWS <= 1 {
+                        0.5
+                    } else {
+                        row as f32 / (EYE_ROWS - 1) as f32
+                    };
+                    (
+                        p.slot_index % EYE_SLOTS,
+                        EYE_SLOTS,
+                        row_frac.mul_add(EYE_Y_MAX - EYE_Y_MIN, EYE_Y_MIN),
+                    )
+                }
+            };
+            let m = &mut self.motion;
+            m.angles.as_mut_slice()[i] = (col % cols) as f32 / cols as f32 * std::f32::consts::TAU;
+            m.ys.as_mut_slice()[i] = y;
+            // Past the fade-in, well before the fade-out
+            m.ages.as_mut_slice()[i] = FADE_IN_DURATION;
+        }
+        for i in 0..self.motion.count {
+            self.refresh_opacity(i);
+        }
     }
 
     /// Append new texts from background prefetch into the text pool.
     /// These will naturally appear as particles respawn.
+    /// A running search takes in the new texts that match.
     pub fn append_texts(&mut self, new_texts: Vec<TextMeta>) {
+        let start = self.text_pool.len();
         self.text_pool.extend(new_texts);
+        if !self.search_terms.is_empty() {
+            let terms = &self.search_terms;
+            self.search_hits.extend(
+                (start..self.text_pool.len()).filter(|&i| self.text_pool[i].matches_terms(terms)),
+            );
+        }
     }
 
-    /// Get 3D world position on the cylinder wall.
-    /// Billboarding: x = R*cos(angle), z = R*sin(angle), y = `y_pos`.
-    #[must_use]
-    pub fn particle_world_pos(p: &TextParticle, time: f32) -> [f32; 3] {
-        let phase = p.id as f32 * 1.618;
-        let drift_y = time.mul_add(0.2, phase * 0.7).sin() * 0.08;
+    /// Pull the particles showing text-pool entries `start..end` toward the
+    /// viewer (or release them with `None`). Eased in [`Self::update_flow`].
+    pub fn focus_pool_range(&mut self, range: Option<(usize, usize)>) {
+        self.focus_range = range;
+        self.refresh_pull_targets();
+    }
 
-        let a = p.angle;
+    /// Search the text pool for entries containing every word of `query`
+    /// (case-insensitive); an empty query ends the search. Matches are
+    /// pulled toward the viewer and the rest dimmed (see
+    /// [`Self::search_dim`]); eye-level particles not showing a match are
+    /// restarted on matches at once. Returns the number of matches.
+    pub fn search(&mut self, query: &str) -> usize {
+        self.search_terms = query.split_whitespace().map(str::to_lowercase).collect();
+        self.search_cursor = 0;
+        self.search_hits = if self.search_terms.is_empty() {
+            Vec::new()
+        } else {
+            let terms = &self.search_terms;
+            (0..self.text_pool.len())
+                .filter(|&i| self.text_pool[i].matches_terms(terms))
+                .collect()
+        };
+        self.refresh_pull_targets();
 
-        [
-            ROTUNDA_RADIUS * a.cos(),
-            p.y_pos + drift_y,
-            ROTUNDA_RADIUS * a.sin(),
-        ]
+        // Put matches not yet on the wall in place of eye-level non-matches
+        let mut missing: Vec<usize> = self
+            .search_hits
+            .iter()
+            .copied()
+            .filter(|&hit| !self.particles.iter().any(|p| p.pool_index == hit))
+            .collect();
+        missing.reverse();
+        for pi in 0..self.particles.len() {
+            let p = &self.particles[pi];
+            if p.layer != RotundaLayer::Eye || p.grabbed || self.is_search_hit(p.pool_index) {
+                continue;
+            }
+            let Some(hit) = missing.pop() else {
+                break;
+            };
+            self.respawn_with(pi, hit);
+        }
+        self.search_hits.len()
     }
 
-    /// Lifecycle-based opacity (fade in / visible / fade out).
+    /// Whether a search is running.
     #[must_use]
-    pub fn particle_opacity(p: &TextParticle) -> f32 {
-        if p.grabbed {
-            return 1.0;
-        }
-        let fade_out_start = p.lifetime - FADE_OUT_DURATION;
-        if p.age < FADE_IN_DURATION {
-            p.age / FADE_IN_DURATION
-        } else if p.age < fade_out_start {
+    pub fn is_searching(&self) -> bool {
+        !self.search_terms.is_empty()
+    }
+
+    /// Whether text-pool entry `pool_index` matches the search.
+    #[must_use]
+    pub fn is_search_hit(&self, pool_index: usize) -> bool {
+        self.search_hits.binary_search(&pool_index).is_ok()
+    }
+
+    /// Opacity factor of particle `i` for the search: [`SEARCH_DIM`] if a
+    /// search is running and it doesn't match, else 1.
+    #[must_use]
+    pub fn search_dim(&self, i: usize) -> f32 {
+        if !self.is_searching() || self.is_search_hit(self.particles[i].pool_index) {
             1.0
         } else {
-            let t = (p.age - fade_out_start) / FADE_OUT_DURATION;
-            (1.0 - t).max(0.0)
+            SEARCH_DIM
         }
     }
 
+    /// Recompute every particle's pull after the focus or search changed.
+    fn refresh_pull_targets(&mut self) {
+        for i in 0..self.particles.len() {
+            let target = self.pull_target(self.particles[i].pool_index);
+            self.motion.pull_targets.as_mut_slice()[i] = target;
+        }
+    }
+
+    /// Jump every particle straight to its focus pull (reduced motion).
+    pub fn settle_focus(&mut self) {
+        let count = self.motion.count;
+        let (pulls, targets) = (
+            self.motion.pulls.as_mut_slice(),
+            self.motion.pull_targets.as_slice(),
+        );
+        pulls[..count].copy_from_slice(&targets[..count]);
+    }
+
+    /// Pull target of a particle showing text-pool entry `pool_index`.
+    fn pull_target(&self, pool_index: usize) -> f32 {
+        if in_range(self.focus_range, pool_index) || self.is_search_hit(pool_index) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Recompute particle `i`'s opacity after its age or grab state changed
+    /// outside [`Self::update_flow`].
+    fn refresh_opacity(&mut self, i: usize) {
+        let m = &mut self.motion;
+        m.opacities.as_mut_slice()[i] = lifecycle_opacity(
+            m.ages.as_slice()[i],
+            m.lifetimes.as_slice()[i],
+            m.moving.as_slice()[i],
+        );
+    }
+
+    /// Freeze (grab) or release particle `i`.
+    fn set_grabbed(&mut self, i: usize, grabbed: bool) {
+        self.particles[i].grabbed = grabbed;
+        self.motion.moving.as_mut_slice()[i] = if grabbed { 0.0 } else { 1.0 };
+        self.refresh_opacity(i);
+    }
+
+    /// Get 3D world position of particle `i` on the cylinder wall.
+    /// Billboarding: x = R*cos(angle), z = R*sin(angle), y = wall height.
+    #[must_use]
+    pub fn particle_world_pos(&self, i: usize) -> [f32; 3] {
+        let m = &self.motion;
+        let phase = self.particles[i].id as f32 * 1.618;
+        let drift_y = self.time.mul_add(0.2, phase * 0.7).sin() * 0.08;
+
+        let a = m.angles.as_slice()[i];
+        let radius = ROTUNDA_RADIUS * m.pulls.as_slice()[i].mul_add(-PULL_DEPTH, 1.0);
+
+        [
+            radius * a.cos(),
+            m.ys.as_slice()[i] + drift_y,
+            radius * a.sin(),
+        ]
+    }
+
+    /// Lifecycle-based opacity of particle `i` (fade in / visible / fade out),
+    /// as of the last update.
+    #[must_use]
+    pub fn particle_opacity(&self, i: usize) -> f32 {
+        self.motion.opacities.as_slice()[i]
+    }
+
     /// Layer-based font size multiplier.
     #[must_use]
     pub const fn layer_font_scale(layer: RotundaLayer) -> f32 {
@@ -523,11 +860,11 @@ impl StreamState {
         let tan_fov_h = fov_h.tan();
 
         for (i, p) in self.particles.iter().enumerate() {
-            if Self::particle_opacity(p) < 0.15 {
+            if self.particle_opacity(i) < 0.15 {
                 continue;
             }
 
-            let world = Self::particle_world_pos(p, self.time);
+            let world = self.particle_world_pos(i);
             let wx = world[0];
             let wy = world[1];
             let wz = world[2];
@@ -565,12 +902,12 @@ impl StreamState {
         // Release previous grab
         if let Some(old) = self.grabbed_index {
             if old < self.particles.len() {
-                self.particles[old].grabbed = false;
+                self.set_grabbed(old, false);
             }
         }
 
         if let Some(idx) = best_idx {
-            self.particles[idx].grabbed = true;
+            self.set_grabbed(idx, true);
             self.grabbed_index = Some(idx);
         } else {
             self.grabbed_index = None;
@@ -580,8 +917,8 @@ impl StreamState {
 
     /// Release all grabbed particles.
     pub fn release_all(&mut self) {
-        for p in &mut self.particles {
-            p.grabbed = false;
+        for i in 0..self.particles.len() {
+            self.set_grabbed(i, false);
         }
         self.grabbed_index = None;
     }
@@ -600,6 +937,7 @@ impl StreamState {
             .get(p.category_index)
             .map_or("INFO", |c| c.name.as_str());
         Some(GrabbedInfo {
+            index: idx,
             particle: p,
             meta,
             category_name: cat_name,
@@ -650,8 +988,17 @@ fn extract_category_name(node: &LayoutNode) -> String {
     }
 }
 
-fn collect_rich_texts(node: &LayoutNode, category_index: usize, out: &mut Vec<TextMeta>) {
-    let (importance, is_leaf) = match node.tag.as_str() {
+/// Gather the texts under `node` into `out`, scored by `scorer`;
+/// `link_density` is that of the block around `node`.
+fn collect_rich_texts(
+    node: &LayoutNode,
+    category_index: usize,
+    scorer: &Scorer,
+    link_density: f32,
+    out: &mut Vec<TextMeta>,
+) {
+    // The tag's own weight, a prior to the layout's
+    let (prior, is_leaf) = match node.tag.as_str() {
         "h1" | "h2" => (1.0, true),
         "h3" | "h4" | "h5" | "h6" => (0.6, true),
         "a" => (0.5, true),
@@ -661,6 +1008,8 @@ fn collect_rich_texts(node: &LayoutNode, category_index: usize, out: &mut Vec<Te
         _ => (0.15, false),
     };
 
+    let importance = scorer.score(node, prior, link_density);
+
     if is_leaf {
         let full = collect_text_content(node);
         if full.len() > 1 {
@@ -720,7 +1069,7 @@ fn collect_rich_texts(node: &LayoutNode, category_index: usize, out: &mut Vec<Te
     if node.tag.is_empty() && !node.text.is_empty() {
         let t = node.text.trim();
         if t.len() > 1 {
-            push_text_meta(out, t, t, "", None, category_index, 0.15);
+            push_text_meta(out, t, t, "", None, category_index, importance);
         }
     }
 
@@ -734,13 +1083,19 @@ fn collect_rich_texts(node: &LayoutNode, category_index: usize, out: &mut Vec<Te
                 &node.tag,
                 node.href.as_ref(),
                 category_index,
-                0.15,
+                importance,
             );
         }
     }
 
+    // Blocks set the link density their texts are judged by
+    let link_density = if node.is_block {
+        score::link_density(node)
+    } else {
+        link_density
+    };
     for child in &node.children {
-        collect_rich_texts(child, category_index, out);
+        collect_rich_texts(child, category_index, scorer, link_density, out);
     }
 }
 
@@ -795,3 +1150,114 @@ fn collect_text_content(node: &LayoutNode) -> String {
     }
     text
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(tag: &str, text: &str) -> TextMeta {
+        TextMeta {
+            display: text.chars().take(40).collect(),
+            full_text: text.into(),
+            tag: tag.into(),
+            href: None,
+            category_index: 0,
+            importance: 0.3,
+        }
+    }
+
+    fn stream() -> StreamState {
+        let texts = (0..40)
+            .map(|i| meta("p", &format!("Paragraph {i} about gardens")))
+            .chain([meta("h2", "Rust compilers"), meta("a", "Why RUST is fast")])
+            .collect();
+        StreamState::from_groups(vec![("PAGE".into(), texts)])
+    }
+
+    #[test]
+    fn search_pulls_matches_and_dims_the_rest() {
+        let mut stream = stream();
+        assert_eq!(stream.search("rust"), 2);
+        assert_eq!(stream.search_hits, [40, 41]);
+        // Both matches were brought onto the wall, pulled forward, undimmed
+        for hit in [40, 41] {
+            let i = stream
+                .particles
+                .iter()
+                .position(|p| p.pool_index == hit)
+                .unwrap();
+            assert!((stream.search_dim(i) - 1.0).abs() < f32::EPSILON);
+            assert!((stream.motion.pull_targets.as_slice()[i] - 1.0).abs() < f32::EPSILON);
+        }
+        let other = stream
+            .particles
+            .iter()
+            .position(|p| !stream.is_search_hit(p.pool_index))
+            .unwrap();
+        assert!((stream.search_dim(other) - SEARCH_DIM).abs() < f32::EPSILON);
+
+        // Every word must match
+        assert_eq!(stream.search("rust fast"), 1);
+        assert_eq!(stream.search("  "), 0);
+        assert!(!stream.is_searching());
+        assert!((stream.search_dim(other) - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn prefetched_texts_join_a_running_search() {
+        let mut stream = stream();
+        stream.search("gardens rust");
+        assert!(stream.search_hits.is_empty());
+        stream.append_texts(vec![
+            meta("p", "Rust gardens of Kyoto"),
+            meta("p", "Unrelated"),
+        ]);
+        assert_eq!(stream.search_hits, [42]);
+    }
+
+    #[test]
+    fn respawns_are_reported_for_one_update() {
+        let mut stream = stream();
+        assert!(stream.spawned().is_empty());
+        // Long enough for every particle's lifetime to run out
+        stream.update_flow(LIFETIME_MAX + 4.0);
+        let spawned = stream.spawned().to_vec();
+        assert!(!spawned.is_empty());
+        assert!(spawned
+            .iter()
+            .all(|&i| stream.motion.ages.as_slice()[i].abs() < f32::EPSILON));
+        stream.update_flow(0.01);
+        assert!(stream.spawned().is_empty());
+    }
+
+    #[test]
+    fn topic_streams_keep_each_topic_in_its_sectors() {
+        let texts: Vec<TextMeta> = (0..30)
+            .map(|i| TextMeta {
+                category_index: i % 3,
+                ..meta("p", &format!("Text {i}"))
+            })
+            .collect();
+        let names = vec!["A".into(), "B".into(), "C".into()];
+        let mut stream = StreamState::from_topics(names, texts);
+        assert_eq!(stream.categories.len(), 3);
+        // Each topic's particles sit in runs of neighbouring eye-level
+        // slots, repeating as the ring wraps round the pool
+        let eye: Vec<usize> = stream
+            .particles
+            .iter()
+            .filter(|p| p.layer == RotundaLayer::Eye)
+            .map(|p| p.category_index)
+            .collect();
+        let changes = eye.windows(2).filter(|w| w[0] != w[1]).count();
+        assert!(changes < eye.len() / 8, "{eye:?}");
+
+        let before: Vec<usize> = stream.particles.iter().map(|p| p.category_index).collect();
+        stream.update_flow(LIFETIME_MAX + 4.0);
+        assert!(!stream.spawned().is_empty());
+        for (p, category) in stream.particles.iter().zip(before) {
+            assert_eq!(p.category_index, category);
+            assert_eq!(stream.text_pool[p.pool_index].category_index, category);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Importance of stream texts from how prominent the page makes them.
+//!
+//! A text's tag says what it is meant to be; its layout says how much the
+//! page actually makes of it. [`Scorer`] blends the two: a small tag prior,
+//! the font size against the page's body text, the area the element covers
+//! against the first screen, how near the top it sits, and — as a penalty —
+//! how much of its surrounding block is link text, so navigation menus and
+//! link farms sink while headlines rise on any site.
+
+use crate::render::layout::LayoutNode;
+
+/// Height of the first screen, in layout pixels.
+const FOLD_HEIGHT: f32 = 900.0;
+
+/// Font-size ratio to the body text at which the font counts fully.
+const FONT_RATIO_FULL: f32 = 2.5;
+
+/// Font-size ratio to the body text below which the font counts nothing.
+const FONT_RATIO_NONE: f32 = 0.8;
+
+/// Share of the first screen at which an element's area counts fully.
+const AREA_SHARE_FULL: f32 = 0.0625;
+
+/// Screens below the fold over which the position's credit runs out.
+const FOLD_FALLOFF: f32 = 3.0;
+
+/// Weights of the tag prior, font size, area and position.
+const W_PRIOR: f32 = 0.2;
+const W_FONT: f32 = 0.4;
+const W_AREA: f32 = 0.15;
+const W_FOLD: f32 = 0.25;
+
+/// Most of an element's score a link-only surrounding block takes away.
+const LINK_PENALTY: f32 = 0.6;
+
+/// Scores the texts of one page.
+#[derive(Debug, Clone, Copy)]
+pub struct Scorer {
+    /// Font size of most of the page's text
+    body_font: f32,
+    /// Area of the first screen
+    fold_area: f32,
+    /// Top of the page
+    top: f32,
+}
+
+impl Scorer {
+    /// Scorer for the page laid out under `root`.
+    #[must_use]
+    pub fn for_page(root: &LayoutNode) -> Self {
+        Self {
+            body_font: body_font(root),
+            fold_area: root.bounds.width.max(1.0) * FOLD_HEIGHT,
+            top: root.bounds.y,
+        }
+    }
+
+    /// Importance (0.0–1.0) of `node`, given its tag's `prior` and the link
+    /// density of the block around it.
+    #[must_use]
+    pub fn score(&self, node: &LayoutNode, prior: f32, link_density: f32) -> f32 {
+        let ratio = node.font_size / self.body_font;
+        let font =
+            ((ratio - FONT_RATIO_NONE) / (FONT_RATIO_FULL - FONT_RATIO_NONE)).clamp(0.0, 1.0);
+        let share = node.bounds.width * node.bounds.height / self.fold_area;
+        let area = (share / AREA_SHARE_FULL).sqrt().min(1.0);
+        let below = (node.bounds.y - self.top - FOLD_HEIGHT).max(0.0);
+        let fold = (1.0 - below / (FOLD_HEIGHT * FOLD_FALLOFF)).max(0.0);
+
+        let prominence = W_PRIOR.mul_add(
+            prior,
+            W_FONT.mul_add(font, W_AREA.mul_add(area, W_FOLD * fold)),
+        );
+        (prominence * LINK_PENALTY.mul_add(-link_density.clamp(0.0, 1.0), 1.0)).clamp(0.0, 1.0)
+    }
+}
+
+/// Share (0.0–1.0) of the text under `node` that sits in links.
+#[must_use]
+pub fn link_density(node: &LayoutNode) -> f32 {
+    let (linked, total) = text_lengths(node, false);
+    if total == 0 {
+        0.0
+    } else {
+        linked as f32 / total as f32
+    }
+}
+
+/// Characters of text under `node` inside links, and in all.
+fn text_lengths(node: &LayoutNode, in_link: bool) -> (usize, usize) {
+    let in_link = in_link || node.tag == "a";
+    let own = node.text.trim().chars().count();
+    node.children.iter().fold(
+        (if in_link { own } else { 0 }, own),
+        |(linked, total), child| {
+            let (l, t) = text_lengths(child, in_link);
+            (linked + l, total + t)
+        },
+    )
+}
+
+/// Font size carrying the most characters of text under `root`.
+fn body_font(root: &LayoutNode) -> f32 {
+    fn tally(node: &LayoutNode, sizes: &mut Vec<(f32, usize)>) {
+        let chars = node.text.trim().chars().count();
+        if chars > 0 && node.font_size > 0.0 {
+            match sizes
+                .iter_mut()
+                .find(|(size, _)| (size - node.font_size).abs() < 0.5)
+            {
+                Some((_, n)) => *n += chars,
+                None => sizes.push((node.font_size, chars)),
+            }
+        }
+        for child in &node.children {
+            tally(child, sizes);
+        }
+    }
+    let mut sizes = Vec::new();
+    tally(root, &mut sizes);
+    sizes
+        .into_iter()
+        .max_by_key(|&(_, chars)| chars)
+        .map_or(16.0, |(size, _)| size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+    use crate::render::layout::compute_layout_with_font;
+
+    fn find<'a>(node: &'a LayoutNode, tag: &str) -> Option<&'a LayoutNode> {
+        if node.tag == tag {
+            return Some(node);
+        }
+        node.children.iter().find_map(|c| find(c, tag))
+    }
+
+    #[test]
+    fn headlines_outrank_menus_and_footers() {
+        let filler = "<p>Body text that runs on for a while to fill the page.</p>".repeat(150);
+        let html = format!(
+            "<nav><ul><li><a href=\"/a\">Home</a></li><li><a href=\"/b\">About</a></li></ul></nav>\
+             <h1>Big headline</h1><p>Lead paragraph of the story.</p>{filler}\
+             <footer>Copyright notice</footer>"
+        );
+        let dom = parse_html(&html, "https://example.com/");
+        let layout = compute_layout_with_font(&dom.root, 1000.0, 16.0);
+        let scorer = Scorer::for_page(&layout);
+
+        let h1 = find(&layout, "h1").unwrap();
+        let nav = find(&layout, "nav").unwrap();
+        let link = find(nav, "a").unwrap();
+        let lead = find(&layout, "p").unwrap();
+        let footer = find(&layout, "footer").unwrap();
+
+        assert!((link_density(nav) - 1.0).abs() < f32::EPSILON);
+        assert!(link_density(h1) < f32::EPSILON);
+
+        let headline = scorer.score(h1, 1.0, 0.0);
+        let menu = scorer.score(link, 0.5, link_density(nav));
+        let lead = scorer.score(lead, 0.2, 0.0);
+        let footer = scorer.score(footer, 0.2, 0.0);
+        assert!(headline > lead, "{headline} vs {lead}");
+        assert!(lead > menu, "{lead} vs {menu}");
+        assert!(lead > footer, "{lead} vs {footer}");
+        assert!((0.0..=1.0).contains(&headline));
+    }
+}
@@ -0,0 +1,239 @@
+//! Keyboard focus order for the 2-D views.
+//!
+//! [`FocusIndex::build`] walks a laid-out page in document order and lists
+//! what Tab can land on: links with an address and the form fields a user
+//! can operate (hidden inputs and script-only buttons are skipped). Each
+//! target keeps its path in the layout tree, so the Flat view can find the
+//! node that shows it, and its box, so the SDF view can ring it and scroll
+//! to it. Form fields also carry their position among the page's controls,
+//! which is how the form widgets are keyed.
+//!
+//! The same targets are what link hints label: [`hint_labels`] gives each
+//! one a short letter code, none the start of another, so a code is
+//! complete as soon as it is typed.
+
+use crate::dom::form::{ControlKind, InputKind};
+use crate::render::layout::LayoutNode;
+
+/// What a focus target does when activated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FocusKind {
+    /// A link to this address (as written in the page)
+    Link(String),
+    /// A form field: its position among the page's controls, in document
+    /// order, counting `<input type="hidden">` and script-only buttons
+    Control(usize),
+}
+
+/// One stop of the Tab order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FocusTarget {
+    /// Child indices from the layout root to the target's node
+    pub path: Vec<usize>,
+    /// Bounds `[x, y, width, height]` in page coordinates
+    pub rect: [f32; 4],
+    pub kind: FocusKind,
+}
+
+impl FocusTarget {
+    /// The target's node in `root`'s tree.
+    #[must_use]
+    pub fn node<'a>(&self, root: &'a LayoutNode) -> Option<&'a LayoutNode> {
+        self.path
+            .iter()
+            .try_fold(root, |node, &i| node.children.get(i))
+    }
+}
+
+/// The focusable nodes of a page, in Tab order.
+#[derive(Debug, Clone, Default)]
+pub struct FocusIndex {
+    pub targets: Vec<FocusTarget>,
+}
+
+impl FocusIndex {
+    /// Collect the focus targets of the laid-out part of a page.
+    #[must_use]
+    pub fn build(root: &LayoutNode) -> Self {
+        let mut targets = Vec::new();
+        let mut controls = 0;
+        collect(root, &mut Vec::new(), &mut controls, &mut targets);
+        Self { targets }
+    }
+
+    /// The target Tab (`forward`) or Shift+Tab moves to from `current`,
+    /// wrapping around at either end. Without a current target — or one no
+    /// longer on the page — Tab starts at the first target below `top` and
+    /// Shift+Tab at the last one above `bottom`, the edges of the view.
+    #[must_use]
+    pub fn step(
+        &self,
+        current: Option<&FocusTarget>,
+        forward: bool,
+        top: f32,
+        bottom: f32,
+    ) -> Option<&FocusTarget> {
+        let len = self.targets.len();
+        if len == 0 {
+            return None;
+        }
+        let at = current.and_then(|c| {
+            self.targets
+                .iter()
+                .position(|t| t.path == c.path && t.kind == c.kind)
+        });
+        let next = match (at, forward) {
+            (Some(i), true) => (i + 1) % len,
+            (Some(i), false) => (i + len - 1) % len,
+            (None, true) => self
+                .targets
+                .iter()
+                .position(|t| t.rect[1] >= top)
+                .unwrap_or(0),
+            (None, false) => self
+                .targets
+                .iter()
+                .rposition(|t| t.rect[1] + t.rect[3] <= bottom)
+                .unwrap_or(len - 1),
+        };
+        self.targets.get(next)
+    }
+}
+
+/// Letters hint codes are made of, home row first.
+pub const HINT_CHARS: &str = "sadfjklewcmpgh";
+
+/// `count` distinct hint codes, as short as the count allows and none a
+/// prefix of another.
+#[must_use]
+pub fn hint_labels(count: usize) -> Vec<String> {
+    // Breadth-first over the code tree: each code taken apart makes room
+    // for its one-letter extensions, until enough leaves remain
+    let mut codes = vec![String::new()];
+    let mut taken = 0;
+    while codes.len() - taken < count || codes.len() == 1 {
+        let prefix = codes[taken].clone();
+        taken += 1;
+        codes.extend(HINT_CHARS.chars().map(|c| format!("{prefix}{c}")));
+    }
+    codes.drain(..taken);
+    codes.truncate(count);
+    codes
+}
+
+/// Whether a control can be operated from the keyboard.
+fn is_focusable(kind: &ControlKind) -> bool {
+    !matches!(
+        kind,
+        ControlKind::Form { .. }
+            | ControlKind::Input(InputKind::Hidden)
+            | ControlKind::Button { submit: false }
+    )
+}
+
+fn collect(
+    node: &LayoutNode,
+    path: &mut Vec<usize>,
+    controls: &mut usize,
+    out: &mut Vec<FocusTarget>,
+) {
+    let b = &node.bounds;
+    let rect = [b.x, b.y, b.width, b.height];
+    if let Some(ref control) = node.control {
+        if !matches!(control.kind, ControlKind::Form { .. }) {
+            if is_focusable(&control.kind) {
+                out.push(FocusTarget {
+                    path: path.clone(),
+                    rect,
+                    kind: FocusKind::Control(*controls),
+                });
+            }
+            *controls += 1;
+            return;
+        }
+    } else if node.tag == "a" {
+        if let Some(ref href) = node.href {
+            out.push(FocusTarget {
+                path: path.clone(),
+                rect,
+                kind: FocusKind::Link(href.clone()),
+            });
+            return;
+        }
+    }
+    for (i, child) in node.children.iter().enumerate() {
+        path.push(i);
+        collect(child, path, controls, out);
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+    use crate::render::layout::compute_layout;
+
+    fn index(html: &str) -> (LayoutNode, FocusIndex) {
+        let dom = parse_html(html, "https://example.com/");
+        let layout = compute_layout(&dom.root, 800.0);
+        let index = FocusIndex::build(&layout);
+        (layout, index)
+    }
+
+    #[test]
+    fn links_and_fields_in_document_order() {
+        let (layout, index) = index(
+            r#"<p><a href="/one">One</a></p>
+               <form action="/s"><input type="hidden" name="t" value="x">
+                 <input name="q"><button type="button">JS</button>
+                 <input type="submit" value="Go"></form>
+               <p><a>no address</a> <a href="/two">Two</a></p>"#,
+        );
+        let kinds: Vec<&FocusKind> = index.targets.iter().map(|t| &t.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                &FocusKind::Link("/one".into()),
+                &FocusKind::Control(1),
+                &FocusKind::Control(3),
+                &FocusKind::Link("/two".into()),
+            ]
+        );
+        let first = index.targets[0].node(&layout).unwrap();
+        assert_eq!(first.tag, "a");
+    }
+
+    #[test]
+    fn step_wraps_and_starts_at_the_view() {
+        let (_, index) =
+            index(r#"<p><a href="/a">A</a></p><p><a href="/b">B</a></p><p><a href="/c">C</a></p>"#);
+        let [a, b, c] = [0, 1, 2].map(|i| &index.targets[i]);
+
+        assert_eq!(index.step(Some(a), true, 0.0, 600.0), Some(b));
+        assert_eq!(index.step(Some(c), true, 0.0, 600.0), Some(a));
+        assert_eq!(index.step(Some(a), false, 0.0, 600.0), Some(c));
+
+        // Nothing focused: from the top of the view, or back from its bottom
+        assert_eq!(index.step(None, true, 0.0, 600.0), Some(a));
+        assert_eq!(index.step(None, true, b.rect[1], 600.0), Some(b));
+        assert_eq!(index.step(None, false, 0.0, 600.0), Some(c));
+        assert_eq!(index.step(None, false, 0.0, b.rect[1] + b.rect[3]), Some(b));
+        assert_eq!(FocusIndex::default().step(None, true, 0.0, 600.0), None);
+    }
+
+    #[test]
+    fn hint_codes_are_short_and_prefix_free() {
+        assert_eq!(hint_labels(3), ["s", "a", "d"]);
+        assert!(hint_labels(0).is_empty());
+
+        let codes = hint_labels(40);
+        assert_eq!(codes.len(), 40);
+        assert!(codes.iter().all(|c| c.len() <= 2));
+        for a in &codes {
+            for b in &codes {
+                assert!(a == b || !b.starts_with(a.as_str()), "{a} starts {b}");
+            }
+        }
+    }
+}
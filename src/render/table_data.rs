@@ -0,0 +1,205 @@
+//! Laid-out tables as data, for CSV and JSON export.
+//!
+//! [`TableData::from_layout`] reads a `<table>` node's grid (see
+//! [`TablePart`]) into a plain rectangle of strings. Cells spanning several
+//! rows or columns are repeated into every slot they cover, so each row has
+//! one value per column. Leading rows made only of header cells become the
+//! column names; stacked header rows are joined with " / " ("Population /
+//! 2020"), and empty or repeated names are made unique so JSON objects keep
+//! every column.
+
+use crate::render::clipboard::to_text;
+use crate::render::layout::{LayoutNode, TablePart};
+
+/// A table's contents.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableData {
+    pub caption: Option<String>,
+    /// One name per column
+    pub headers: Vec<String>,
+    /// Body rows, each `headers.len()` values long
+    pub rows: Vec<Vec<String>>,
+}
+
+impl TableData {
+    /// Read the table laid out at `node`; `None` if it is not a table grid.
+    #[must_use]
+    pub fn from_layout(node: &LayoutNode) -> Option<Self> {
+        let Some(TablePart::Grid { rows, cols, .. }) = node.table else {
+            return None;
+        };
+        let mut grid = vec![vec![String::new(); cols]; rows];
+        let mut is_header = vec![true; rows];
+        let mut caption = None;
+        for child in &node.children {
+            if child.tag == "caption" {
+                caption = Some(cell_text(child)).filter(|c| !c.is_empty());
+                continue;
+            }
+            for cell in &child.children {
+                let Some(TablePart::Cell(place)) = cell.table else {
+                    continue;
+                };
+                let text = cell_text(cell);
+                let spanned = grid.iter_mut().zip(&mut is_header);
+                for (cells, header) in spanned.skip(place.row).take(place.rowspan) {
+                    if !place.header {
+                        *header = false;
+                    }
+                    for cell in cells.iter_mut().skip(place.col).take(place.colspan) {
+                        cell.clone_from(&text);
+                    }
+                }
+            }
+        }
+
+        // Header rows lead the table and hold nothing but header cells
+        let header_rows = is_header.iter().take_while(|h| **h).count();
+        let header_rows = if header_rows == rows { 0 } else { header_rows };
+        let body = grid.split_off(header_rows);
+        let headers = (0..cols)
+            .map(|col| {
+                let mut parts: Vec<&str> = Vec::new();
+                for row in &grid {
+                    let part = row[col].as_str();
+                    if !part.is_empty() && parts.last() != Some(&part) {
+                        parts.push(part);
+                    }
+                }
+                parts.join(" / ")
+            })
+            .collect();
+        Some(Self {
+            caption,
+            headers: unique_names(headers),
+            rows: body,
+        })
+    }
+
+    /// The table as CSV (RFC 4180): a header line, then one line per row.
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        for row in std::iter::once(&self.headers).chain(&self.rows) {
+            let line: Vec<String> = row.iter().map(|v| csv_field(v)).collect();
+            out.push_str(&line.join(","));
+            out.push_str("\r\n");
+        }
+        out
+    }
+
+    /// The table as a JSON array with one object per row, keyed by column
+    /// name in column order.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let rows: Vec<serde_json::Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object = self
+                    .headers
+                    .iter()
+                    .zip(row)
+                    .map(|(name, value)| (name.clone(), serde_json::Value::from(value.as_str())))
+                    .collect();
+                serde_json::Value::Object(object)
+            })
+            .collect();
+        serde_json::to_string_pretty(&rows).unwrap_or_default()
+    }
+}
+
+/// A cell's text on one line.
+fn cell_text(node: &LayoutNode) -> String {
+    to_text(node)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Name empty columns "Column N" and number repeats ("Total 2").
+fn unique_names(names: Vec<String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::with_capacity(names.len());
+    for (i, name) in names.into_iter().enumerate() {
+        let base = if name.is_empty() {
+            format!("Column {}", i + 1)
+        } else {
+            name
+        };
+        let mut unique = base.clone();
+        let mut n = 2;
+        while out.contains(&unique) {
+            unique = format!("{base} {n}");
+            n += 1;
+        }
+        out.push(unique);
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+    use crate::render::layout::compute_layout;
+
+    fn table(html: &str) -> TableData {
+        fn find(node: &LayoutNode) -> Option<&LayoutNode> {
+            if node.tag == "table" {
+                return Some(node);
+            }
+            node.children.iter().find_map(find)
+        }
+        let dom = parse_html(html, "https://example.com/");
+        let layout = compute_layout(&dom.root, 800.0);
+        TableData::from_layout(find(&layout).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn spans_fill_the_grid_and_headers_stack() {
+        let data = table(
+            "<table><caption>Towns</caption>
+               <tr><th rowspan=2>Town</th><th colspan=2>Population</th></tr>
+               <tr><th>2010</th><th>2020</th></tr>
+               <tr><td>Ashby, North</td><td colspan=2>1 200</td></tr>
+               <tr><td>Brook</td><td>80</td><td>95</td></tr>
+             </table>",
+        );
+        assert_eq!(data.caption.as_deref(), Some("Towns"));
+        assert_eq!(
+            data.headers,
+            ["Town", "Population / 2010", "Population / 2020"]
+        );
+        assert_eq!(
+            data.rows,
+            [
+                vec!["Ashby, North", "1 200", "1 200"],
+                vec!["Brook", "80", "95"],
+            ]
+        );
+        assert_eq!(
+            data.to_csv(),
+            "Town,Population / 2010,Population / 2020\r\n\
+             \"Ashby, North\",1 200,1 200\r\nBrook,80,95\r\n"
+        );
+        let json: serde_json::Value = serde_json::from_str(&data.to_json()).unwrap();
+        assert_eq!(json[1]["Population / 2020"], "95");
+    }
+
+    #[test]
+    fn tables_without_header_rows_get_column_names() {
+        let data =
+            table("<table><tr><td>a</td><td>b</td></tr><tr><td>c</td><td>d</td></tr></table>");
+        assert_eq!(data.headers, ["Column 1", "Column 2"]);
+        assert_eq!(data.rows.len(), 2);
+        assert_eq!(unique_names(vec!["x".into(), "x".into()]), ["x", "x 2"]);
+    }
+}
@@ -0,0 +1,352 @@
+//! Page thumbnail snapshots.
+//!
+//! Downscales a captured RGBA frame (egui screenshot or raymarch output)
+//! into a small preview and keeps the most recent ones in a bounded,
+//! least-recently-used cache keyed by page URL. Pages that were never on
+//! screen get a [`sketch`] of their paint elements instead, or a painted
+//! thumbnail from [`crate::engine::headless::thumbnail`].
+//!
+//! Thumbnails are kept on disk as small PNG files named by
+//! [`file_name`], so the history keeps them between sessions.
+
+use std::collections::{HashMap, VecDeque};
+
+use image::ImageEncoder;
+
+use crate::render::sdf_ui::{PaintElement, PaintKind};
+
+/// Default thumbnail bounding box (pixels).
+pub const THUMB_MAX_WIDTH: usize = 160;
+pub const THUMB_MAX_HEIGHT: usize = 100;
+
+/// A downscaled RGBA page snapshot.
+#[derive(Debug, Clone)]
+pub struct Thumbnail {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+impl Thumbnail {
+    /// Encode as PNG; `None` if the buffer does not match the size.
+    #[must_use]
+    pub fn to_png(&self) -> Option<Vec<u8>> {
+        let mut png = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut png)
+            .write_image(
+                &self.rgba,
+                u32::try_from(self.width).ok()?,
+                u32::try_from(self.height).ok()?,
+                image::ExtendedColorType::Rgba8,
+            )
+            .ok()?;
+        Some(png)
+    }
+
+    /// Decode a PNG written by [`to_png`](Self::to_png).
+    #[must_use]
+    pub fn from_png(bytes: &[u8]) -> Option<Self> {
+        let image = image::load_from_memory_with_format(bytes, image::ImageFormat::Png)
+            .ok()?
+            .to_rgba8();
+        Some(Self {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            rgba: image.into_raw(),
+        })
+    }
+}
+
+/// Stable file name for the thumbnail of `url`: FNV-1a of the address, in
+/// hex, with a `.png` extension.
+#[must_use]
+pub fn file_name(url: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in url.bytes() {
+        hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}.png")
+}
+
+/// Box-filter an RGBA buffer down so it fits inside `max_w` x `max_h`,
+/// preserving aspect ratio. Returns `None` for empty or malformed input.
+#[must_use]
+pub fn downscale_rgba(
+    src: &[u8],
+    src_w: usize,
+    src_h: usize,
+    max_w: usize,
+    max_h: usize,
+) -> Option<Thumbnail> {
+    if src_w == 0 || src_h == 0 || max_w == 0 || max_h == 0 || src.len() < src_w * src_h * 4 {
+        return None;
+    }
+
+    let scale = (max_w as f32 / src_w as f32)
+        .min(max_h as f32 / src_h as f32)
+        .min(1.0);
+    let dst_w = ((src_w as f32 * scale).round() as usize).max(1);
+    let dst_h = ((src_h as f32 * scale).round() as usize).max(1);
+
+    let mut rgba = Vec::with_capacity(dst_w * dst_h * 4);
+    for dy in 0..dst_h {
+        let y0 = dy * src_h / dst_h;
+        let y1 = ((dy + 1) * src_h / dst_h).max(y0 + 1);
+        for dx in 0..dst_w {
+            let x0 = dx * src_w / dst_w;
+            let x1 = ((dx + 1) * src_w / dst_w).max(x0 + 1);
+            let mut acc = [0u32; 4];
+            for y in y0..y1 {
+                let row = y * src_w;
+                for x in x0..x1 {
+                    let i = (row + x) * 4;
+                    for c in 0..4 {
+                        acc[c] += u32::from(src[i + c]);
+                    }
+                }
+            }
+            let n = ((y1 - y0) * (x1 - x0)) as u32;
+            for a in acc {
+                rgba.push((a / n) as u8);
+            }
+        }
+    }
+
+    Some(Thumbnail {
+        width: dst_w,
+        height: dst_h,
+        rgba,
+    })
+}
+
+/// Rasterize the top of a page — its first `page_width` × 10:16 region —
+/// as flat boxes: cards, heading and text bars, links, images. Cheap enough
+/// to run off-screen for pages loaded in the background.
+#[must_use]
+pub fn sketch(elements: &[PaintElement], page_width: f32) -> Thumbnail {
+    let (width, height) = (THUMB_MAX_WIDTH, THUMB_MAX_HEIGHT);
+    let scale = width as f32 / page_width.max(1.0);
+    let mut rgba = [250, 250, 252, 255].repeat(width * height);
+
+    for elem in elements {
+        let color: [u8; 3] = match elem.kind {
+            PaintKind::Card => [232, 234, 240],
+            PaintKind::Heading => [40, 40, 56],
+            PaintKind::Text => [150, 150, 160],
+            PaintKind::Link => [0, 102, 217],
+            PaintKind::Button => [90, 110, 200],
+            PaintKind::Separator => [204, 204, 209],
+            PaintKind::ImagePlaceholder => [200, 205, 215],
+        };
+        let [x, y, w, h] = elem.rect;
+        // Text bars are drawn thinner than their line box
+        let h = if matches!(elem.kind, PaintKind::Text | PaintKind::Link) {
+            h * 0.6
+        } else {
+            h
+        };
+        let px = |v: f32, max: usize| ((v * scale).round().max(0.0) as usize).min(max);
+        let (x0, x1) = (
+            px(x, width),
+            px(x + w, width).max(px(x, width) + 1).min(width),
+        );
+        let (y0, y1) = (
+            px(y, height),
+            px(y + h, height).max(px(y, height) + 1).min(height),
+        );
+        for row in y0..y1 {
+            for col in x0..x1 {
+                let i = (row * width + col) * 4;
+                rgba[i..i + 3].copy_from_slice(&color);
+            }
+        }
+    }
+
+    Thumbnail {
+        width,
+        height,
+        rgba,
+    }
+}
+
+/// Bounded URL → thumbnail cache with LRU eviction.
+#[derive(Debug)]
+pub struct ThumbnailCache {
+    capacity: usize,
+    entries: HashMap<String, Thumbnail>,
+    /// Front = least recently used
+    order: VecDeque<String>,
+}
+
+impl ThumbnailCache {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Store a thumbnail, evicting the least recently used entry if full.
+    /// Returns the evicted URL, if any.
+    pub fn insert(&mut self, url: &str, thumb: Thumbnail) -> Option<String> {
+        if self.entries.insert(url.to_string(), thumb).is_some() {
+            self.touch(url);
+            return None;
+        }
+        self.order.push_back(url.to_string());
+        if self.entries.len() > self.capacity {
+            if let Some(old) = self.order.pop_front() {
+                self.entries.remove(&old);
+                return Some(old);
+            }
+        }
+        None
+    }
+
+    /// Look up a thumbnail and mark it as recently used.
+    pub fn get(&mut self, url: &str) -> Option<&Thumbnail> {
+        if self.entries.contains_key(url) {
+            self.touch(url);
+        }
+        self.entries.get(url)
+    }
+
+    #[must_use]
+    pub fn contains(&self, url: &str) -> bool {
+        self.entries.contains_key(url)
+    }
+
+    pub fn remove(&mut self, url: &str) -> Option<Thumbnail> {
+        self.order.retain(|u| u != url);
+        self.entries.remove(url)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn touch(&mut self, url: &str) {
+        if let Some(pos) = self.order.iter().position(|u| u == url) {
+            if let Some(u) = self.order.remove(pos) {
+                self.order.push_back(u);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thumb() -> Thumbnail {
+        Thumbnail {
+            width: 1,
+            height: 1,
+            rgba: vec![0, 0, 0, 255],
+        }
+    }
+
+    #[test]
+    fn downscale_preserves_aspect() {
+        let src = vec![255u8; 640 * 480 * 4];
+        let t = downscale_rgba(&src, 640, 480, 160, 100).unwrap();
+        assert!(t.width <= 160 && t.height <= 100);
+        assert_eq!(t.width * t.height * 4, t.rgba.len());
+        assert!(((t.width as f32 / t.height as f32) - 640.0 / 480.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn downscale_averages_pixels() {
+        // 2x1: black + white → 1x1 mid grey
+        let src = vec![0, 0, 0, 255, 255, 255, 255, 255];
+        let t = downscale_rgba(&src, 2, 1, 1, 1).unwrap();
+        assert_eq!(t.rgba[0], 127);
+    }
+
+    #[test]
+    fn downscale_rejects_bad_input() {
+        assert!(downscale_rgba(&[], 0, 0, 10, 10).is_none());
+        assert!(downscale_rgba(&[0; 4], 2, 2, 10, 10).is_none());
+    }
+
+    #[test]
+    fn sketch_draws_elements_to_scale() {
+        let heading = PaintElement {
+            id: 1,
+            kind: PaintKind::Heading,
+            rect: [0.0, 0.0, 400.0, 50.0],
+            color: [0.0; 4],
+            corner_radius: 0.0,
+            shadow_depth: 0.0,
+            text: Some("Title".into()),
+            font_size: 32.0,
+            href: None,
+            image_url: None,
+        };
+        // Far below the sketched region
+        let footer = PaintElement {
+            rect: [0.0, 5_000.0, 800.0, 40.0],
+            ..heading.clone()
+        };
+        let t = sketch(&[heading, footer], 800.0);
+        assert_eq!(t.rgba.len(), THUMB_MAX_WIDTH * THUMB_MAX_HEIGHT * 4);
+        let pixel = |x: usize, y: usize| t.rgba[(y * t.width + x) * 4];
+        // 800 px wide page → 0.2 scale: the heading covers 80 × 10 pixels
+        assert_eq!(pixel(10, 5), 40);
+        assert_eq!(pixel(100, 5), 250);
+        assert_eq!(pixel(10, 50), 250);
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let t = Thumbnail {
+            width: 2,
+            height: 1,
+            rgba: vec![255, 0, 0, 255, 0, 0, 255, 128],
+        };
+        let back = Thumbnail::from_png(&t.to_png().unwrap()).unwrap();
+        assert_eq!((back.width, back.height), (2, 1));
+        assert_eq!(back.rgba, t.rgba);
+        assert!(Thumbnail::from_png(b"not a png").is_none());
+    }
+
+    #[test]
+    fn file_names_are_stable_per_url() {
+        let name = file_name("https://example.com/");
+        assert_eq!(name, file_name("https://example.com/"));
+        assert_ne!(name, file_name("https://example.com/a"));
+        assert_eq!(name.len(), 20);
+        assert!(name.ends_with(".png"));
+    }
+
+    #[test]
+    fn cache_evicts_lru() {
+        let mut cache = ThumbnailCache::new(2);
+        cache.insert("a", thumb());
+        cache.insert("b", thumb());
+        let _ = cache.get("a"); // a is now most recent
+        let evicted = cache.insert("c", thumb());
+        assert_eq!(evicted.as_deref(), Some("b"));
+        assert!(cache.contains("a"));
+        assert!(cache.contains("c"));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn cache_replace_does_not_grow() {
+        let mut cache = ThumbnailCache::new(2);
+        cache.insert("a", thumb());
+        cache.insert("a", thumb());
+        assert_eq!(cache.len(), 1);
+        assert!(cache.remove("a").is_some());
+        assert!(cache.is_empty());
+    }
+}
@@ -3,9 +3,21 @@
 //! Builds an FM-Index from page text for O(pattern_length) search,
 //! independent of page size. Count, locate, and contains operations
 //! are all sublinear.
+//!
+//! An index built from a layout tree also remembers which text node each
+//! stretch of text came from, so located matches map back to the nodes
+//! that display them (for find-in-page navigation).
+//!
+//! [`cluster`] groups short texts by topic for the OZ stream.
+
+pub mod cluster;
+
+use std::ops::Range;
 
 use alice_search::AliceIndex;
 
+use crate::render::layout::LayoutNode;
+
 /// FM-Index based page search.
 ///
 /// Built once per page load, supports instant pattern matching
@@ -13,6 +25,34 @@ use alice_search::AliceIndex;
 pub struct PageSearch {
     index: AliceIndex,
     text: String,
+    /// Text nodes in document order, when built from a layout
+    segments: Vec<Segment>,
+}
+
+/// A text node's share of the indexed text.
+struct Segment {
+    /// Byte offset of the node's text in the index
+    start: usize,
+    path: Vec<usize>,
+}
+
+/// One occurrence of a query, mapped back to the layout tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageMatch {
+    /// Child indices from the layout root to the text node holding the match
+    pub path: Vec<usize>,
+    /// Byte range of the match in the node's trimmed, lowercased text
+    pub range: Range<usize>,
+}
+
+impl PageMatch {
+    /// The text node holding the match, in `root`'s tree.
+    #[must_use]
+    pub fn node<'a>(&self, root: &'a LayoutNode) -> Option<&'a LayoutNode> {
+        self.path
+            .iter()
+            .try_fold(root, |node, &i| node.children.get(i))
+    }
 }
 
 impl PageSearch {
@@ -23,7 +63,25 @@ impl PageSearch {
     pub fn build(text: &str) -> Self {
         let lower = text.to_lowercase();
         let index = AliceIndex::build(lower.as_bytes(), 4);
-        Self { index, text: lower }
+        Self {
+            index,
+            text: lower,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Build from the visible text of a layout tree. Each text node's text
+    /// ends with a newline, so matches never run across nodes.
+    pub fn from_layout(root: &LayoutNode) -> Self {
+        let mut text = String::new();
+        let mut segments = Vec::new();
+        collect_segments(root, &mut Vec::new(), &mut text, &mut segments);
+        let index = AliceIndex::build(text.as_bytes(), 4);
+        Self {
+            index,
+            text,
+            segments,
+        }
     }
 
     /// Count occurrences of query in the page text. O(query_length).
@@ -42,12 +100,57 @@ impl PageSearch {
         self.index.contains(query.to_lowercase().as_bytes())
     }
 
+    /// Every match of `query` in document order, mapped to its text node.
+    /// Empty for an index built with [`PageSearch::build`].
+    pub fn matches(&self, query: &str) -> Vec<PageMatch> {
+        if query.is_empty() || self.segments.is_empty() {
+            return Vec::new();
+        }
+        let query = query.to_lowercase();
+        let mut offsets = self.index.locate(query.as_bytes());
+        offsets.sort_unstable();
+        offsets
+            .into_iter()
+            .filter_map(|offset| {
+                let seg = self.segments.partition_point(|s| s.start <= offset);
+                let segment = self.segments.get(seg.checked_sub(1)?)?;
+                let start = offset - segment.start;
+                Some(PageMatch {
+                    path: segment.path.clone(),
+                    range: start..start + query.len(),
+                })
+            })
+            .collect()
+    }
+
     /// Total indexed text length in bytes.
     pub fn text_len(&self) -> usize {
         self.text.len()
     }
 }
 
+fn collect_segments(
+    node: &LayoutNode,
+    path: &mut Vec<usize>,
+    text: &mut String,
+    segments: &mut Vec<Segment>,
+) {
+    let own = node.text.trim();
+    if !own.is_empty() {
+        segments.push(Segment {
+            start: text.len(),
+            path: path.clone(),
+        });
+        text.push_str(&own.to_lowercase());
+        text.push('\n');
+    }
+    for (i, child) in node.children.iter().enumerate() {
+        path.push(i);
+        collect_segments(child, path, text, segments);
+        path.pop();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,6 +172,31 @@ mod tests {
         assert!(!search.contains(""));
     }
 
+    #[test]
+    fn matches_map_to_text_nodes() {
+        use crate::dom::parser::parse_html;
+        use crate::render::layout::compute_layout;
+
+        let dom = parse_html(
+            "<h1>Alice in Wonderland</h1><p>Down the rabbit hole with alice.</p>",
+            "https://example.com/",
+        );
+        let layout = compute_layout(&dom.root, 800.0);
+
+        let search = PageSearch::from_layout(&layout);
+        let found = search.matches("ALICE");
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].range, 0..5);
+        assert_eq!(found[0].node(&layout).unwrap().text, "Alice in Wonderland");
+        assert_eq!(found[1].range, 26..31);
+        assert_eq!(
+            found[1].node(&layout).unwrap().text,
+            "Down the rabbit hole with alice."
+        );
+        assert!(search.matches("wonderland down").is_empty());
+        assert!(PageSearch::build("alice").matches("alice").is_empty());
+    }
+
     #[test]
     fn search_japanese() {
         let search = PageSearch::build("東京都渋谷区で開催されるイベント");
@@ -0,0 +1,334 @@
+//! On-device topic clustering of short texts.
+//!
+//! Each text is embedded with a hashing vectorizer — its words hashed into
+//! [`DIMENSIONS`] signed buckets, weighted by inverse document frequency
+//! and normalised — and the embeddings are grouped with spherical k-means.
+//! A [`TopicModel`] keeps the centroids, so texts arriving later (prefetched
+//! links) join the nearest topic, and names each topic by the words most
+//! particular to it. Nothing leaves the device and no vocabulary is stored.
+
+use std::collections::HashMap;
+
+use crate::render::stream::TextMeta;
+
+/// Buckets of the hashing vectorizer.
+pub const DIMENSIONS: usize = 256;
+
+/// Rounds of k-means at most.
+const MAX_ROUNDS: usize = 20;
+
+/// Texts that make two topics; see [`topic_count`].
+const TEXTS_PER_TOPIC: f32 = 8.0;
+
+/// Words naming a topic.
+const NAME_WORDS: usize = 2;
+
+/// Words too common to tell topics apart.
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "all", "any", "can", "had", "her", "was",
+    "one", "our", "out", "has", "his", "how", "its", "may", "new", "now", "who", "did", "get",
+    "use", "with", "this", "that", "from", "they", "will", "have", "more", "your", "what", "when",
+    "been", "into", "than", "then", "them", "were", "also", "about", "after", "which", "their",
+    "there", "would", "other", "these", "those", "over", "just", "only", "some",
+];
+
+/// Lowercase words of `text` worth clustering on: runs of letters and
+/// digits of three characters or more (two for non-Latin scripts), minus
+/// stop words.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| {
+            let n = w.chars().count();
+            n >= 3 || (n == 2 && !w.is_ascii())
+        })
+        .map(str::to_lowercase)
+        .filter(|w| !STOP_WORDS.contains(&w.as_str()) && !w.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Bucket and sign of `word` (FNV-1a).
+fn bucket(word: &str) -> (usize, f32) {
+    let hash = word.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+    ((hash % DIMENSIONS as u64) as usize, sign)
+}
+
+/// Unweighted hashed word counts of `text`.
+fn counts(text: &str) -> Vec<f32> {
+    let mut v = vec![0.0; DIMENSIONS];
+    for word in words(text) {
+        let (i, sign) = bucket(&word);
+        v[i] += sign;
+    }
+    v
+}
+
+fn normalize(v: &mut [f32]) {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Index of the centroid most similar to `v` (the first for an empty `v`).
+fn nearest(centroids: &[Vec<f32>], v: &[f32]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, dot(c, v)))
+        .fold(
+            (0, f32::MIN),
+            |best, cur| if cur.1 > best.1 { cur } else { best },
+        )
+        .0
+}
+
+/// Number of topics for `n` texts, growing with the square root of `n`
+/// (two for [`TEXTS_PER_TOPIC`] texts), between 2 and `max_topics` — and
+/// never more than `n`.
+#[must_use]
+pub fn topic_count(n: usize, max_topics: usize) -> usize {
+    let k = ((n as f32 / TEXTS_PER_TOPIC).sqrt() * 2.0).round() as usize;
+    k.clamp(2, max_topics.max(2)).min(n)
+}
+
+/// Topics found in a set of texts.
+#[derive(Debug, Clone)]
+pub struct TopicModel {
+    /// Unit centroid of each topic
+    centroids: Vec<Vec<f32>>,
+    /// Inverse document frequency of each bucket
+    idf: Vec<f32>,
+    /// Name of each topic: its most particular words, uppercased
+    pub names: Vec<String>,
+}
+
+impl TopicModel {
+    /// Cluster `texts` into at most `max_topics` topics. Returns the model
+    /// and each text's topic.
+    #[must_use]
+    pub fn fit(texts: &[&str], max_topics: usize) -> (Self, Vec<usize>) {
+        let raw: Vec<Vec<f32>> = texts.iter().map(|t| counts(t)).collect();
+        let n = raw.len() as f32;
+        let idf: Vec<f32> = (0..DIMENSIONS)
+            .map(|i| {
+                let df = raw.iter().filter(|v| v[i] != 0.0).count() as f32;
+                ((1.0 + n) / (1.0 + df)).ln() + 1.0
+            })
+            .collect();
+        let embedded: Vec<Vec<f32>> = raw.into_iter().map(|v| weigh(v, &idf)).collect();
+
+        let k = topic_count(embedded.len(), max_topics);
+        let mut centroids = farthest_first(&embedded, k);
+        let mut assignment = vec![0; embedded.len()];
+        for round in 0..MAX_ROUNDS {
+            let next: Vec<usize> = embedded.iter().map(|v| nearest(&centroids, v)).collect();
+            if round > 0 && next == assignment {
+                break;
+            }
+            assignment = next;
+            for (c, centroid) in centroids.iter_mut().enumerate() {
+                let mut sum = vec![0.0; DIMENSIONS];
+                for (v, _) in embedded.iter().zip(&assignment).filter(|(_, a)| **a == c) {
+                    sum.iter_mut().zip(v).for_each(|(s, x)| *s += x);
+                }
+                // An emptied topic keeps its old centre
+                if sum.iter().any(|&x| x != 0.0) {
+                    normalize(&mut sum);
+                    *centroid = sum;
+                }
+            }
+        }
+
+        let names = name_topics(texts, &assignment, centroids.len());
+        (
+            Self {
+                centroids,
+                idf,
+                names,
+            },
+            assignment,
+        )
+    }
+
+    /// Topic `text` is nearest to.
+    #[must_use]
+    pub fn assign(&self, text: &str) -> usize {
+        nearest(&self.centroids, &weigh(counts(text), &self.idf))
+    }
+
+    /// `texts` with each one's `category_index` set to its topic.
+    #[must_use]
+    pub fn labelled(&self, mut texts: Vec<TextMeta>) -> Vec<TextMeta> {
+        for text in &mut texts {
+            text.category_index = self.assign(&text.full_text);
+        }
+        texts
+    }
+
+    /// Number of topics.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.centroids.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.centroids.is_empty()
+    }
+}
+
+/// Sublinear term weights times `idf`, normalised.
+fn weigh(mut v: Vec<f32>, idf: &[f32]) -> Vec<f32> {
+    for (x, w) in v.iter_mut().zip(idf) {
+        *x = x.signum() * x.abs().ln_1p() * w;
+    }
+    normalize(&mut v);
+    v
+}
+
+/// `k` starting centroids: the first non-empty embedding, then repeatedly
+/// the one least like any chosen so far. Deterministic, so a page clusters
+/// the same way each time.
+fn farthest_first(embedded: &[Vec<f32>], k: usize) -> Vec<Vec<f32>> {
+    let mut centroids: Vec<Vec<f32>> = Vec::with_capacity(k);
+    let Some(first) = embedded.iter().find(|v| v.iter().any(|&x| x != 0.0)) else {
+        return vec![vec![0.0; DIMENSIONS]];
+    };
+    centroids.push(first.clone());
+    while centroids.len() < k {
+        let next = embedded
+            .iter()
+            .map(|v| {
+                let closest = centroids.iter().map(|c| dot(c, v)).fold(f32::MIN, f32::max);
+                (v, closest)
+            })
+            .filter(|(v, _)| v.iter().any(|&x| x != 0.0))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        match next {
+            // Everything left duplicates a centroid
+            Some((v, closest)) if closest < 0.999 => centroids.push(v.clone()),
+            _ => break,
+        }
+    }
+    centroids
+}
+
+/// Name each of `k` topics by the [`NAME_WORDS`] words most particular to
+/// it: frequent among its texts and rare elsewhere.
+fn name_topics(texts: &[&str], assignment: &[usize], k: usize) -> Vec<String> {
+    let mut overall: HashMap<String, f32> = HashMap::new();
+    let mut per_topic: Vec<HashMap<String, f32>> = vec![HashMap::new(); k];
+    for (text, &topic) in texts.iter().zip(assignment) {
+        for word in words(text) {
+            *overall.entry(word.clone()).or_default() += 1.0;
+            *per_topic[topic].entry(word).or_default() += 1.0;
+        }
+    }
+    per_topic
+        .into_iter()
+        .enumerate()
+        .map(|(i, counts)| {
+            let mut scored: Vec<(String, f32)> = counts
+                .into_iter()
+                .map(|(word, c)| {
+                    let share = c / overall[&word];
+                    (word, c * share)
+                })
+                .collect();
+            // Highest score first, ties alphabetical for stable names
+            scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            let name: Vec<String> = scored
+                .into_iter()
+                .take(NAME_WORDS)
+                .map(|(word, _)| word.to_uppercase())
+                .collect();
+            if name.is_empty() {
+                format!("TOPIC {}", i + 1)
+            } else {
+                name.join(" \u{b7} ")
+            }
+        })
+        .collect()
+}
+
+/// Cluster `texts` by topic, setting each one's `category_index` to its
+/// topic. Returns the model, for texts that arrive later.
+#[must_use]
+pub fn cluster_texts(texts: &mut [TextMeta], max_topics: usize) -> TopicModel {
+    let bodies: Vec<&str> = texts.iter().map(|t| t.full_text.as_str()).collect();
+    let (model, assignment) = TopicModel::fit(&bodies, max_topics);
+    for (text, topic) in texts.iter_mut().zip(assignment) {
+        text.category_index = topic;
+    }
+    model
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPORT: &[&str] = &[
+        "Football match ends in late penalty drama",
+        "Penalty shootout decides the football cup final",
+        "Striker scores twice as football club wins match",
+        "Football manager praises the team after the cup match",
+    ];
+    const MARKETS: &[&str] = &[
+        "Stock markets fall as interest rates rise",
+        "Central bank raises interest rates again",
+        "Markets rally on hopes of lower interest rates",
+        "Bond markets react to the bank rates decision",
+    ];
+
+    #[test]
+    fn texts_on_one_subject_share_a_topic() {
+        let texts: Vec<&str> = SPORT.iter().chain(MARKETS).copied().collect();
+        let (model, topics) = TopicModel::fit(&texts, 2);
+        assert_eq!(model.len(), 2);
+        assert!(topics[..4].iter().all(|&t| t == topics[0]));
+        assert!(topics[4..].iter().all(|&t| t == topics[4]));
+        assert_ne!(topics[0], topics[4]);
+
+        assert!(model.names[topics[0]].contains("FOOTBALL"));
+        assert!(model.names[topics[4]].contains("RATES"));
+
+        // Later texts join the nearest topic
+        assert_eq!(model.assign("Football cup replay set"), topics[0]);
+        assert_eq!(model.assign("Interest rates on hold"), topics[4]);
+    }
+
+    #[test]
+    fn cluster_texts_sets_categories() {
+        let mut metas: Vec<TextMeta> = SPORT
+            .iter()
+            .chain(MARKETS)
+            .map(|t| TextMeta {
+                display: (*t).to_string(),
+                full_text: (*t).to_string(),
+                tag: "p".to_string(),
+                href: None,
+                category_index: 0,
+                importance: 0.2,
+            })
+            .collect();
+        let model = cluster_texts(&mut metas, 8);
+        assert!(model.len() >= 2);
+        assert!(metas.iter().all(|m| m.category_index < model.len()));
+        assert_ne!(metas[0].category_index, metas[4].category_index);
+    }
+
+    #[test]
+    fn stop_words_and_short_words_are_ignored() {
+        let found: Vec<String> = words("The cat and THE dog, 42 of them: 東京 x").collect();
+        assert_eq!(found, ["cat", "dog", "東京"]);
+        assert_eq!(topic_count(3, 8), 2);
+        assert_eq!(topic_count(1, 8), 1);
+        assert_eq!(topic_count(1000, 8), 8);
+    }
+}
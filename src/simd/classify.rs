@@ -8,8 +8,13 @@
 //!   - Traditional: 1 node × N branches × pipeline flush risk = slow
 //!   - SIMD batch:  8 nodes × 0 branches × full pipeline = 8x+ faster
 
-use super::soa::NodeFeaturesSoA;
+use rayon::prelude::*;
+
+use super::soa::{arena_to_soa, dom_to_soa, node_to_soa, NodeFeaturesSoA};
 use super::{F32x8, I32x8, MaskF32x8};
+use crate::dom::arena::DomArena;
+use crate::dom::filter::PARALLEL_GRAIN;
+use crate::dom::{Classification, DomNode};
 
 /// Classification indices matching `dom::Classification`
 const CLASS_CONTENT: i32 = 0;
@@ -23,7 +28,7 @@ const CLASS_STRUCTURAL: i32 = 7;
 const CLASS_UNKNOWN: i32 = 8;
 
 /// SIMD classification statistics (no atomic overhead in batch mode)
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq, Eq)]
 pub struct SimdFilterStats {
     pub total_nodes: usize,
     pub content_nodes: usize,
@@ -33,6 +38,17 @@ pub struct SimdFilterStats {
     pub removed_nodes: usize,
 }
 
+impl std::ops::AddAssign for SimdFilterStats {
+    fn add_assign(&mut self, other: Self) {
+        self.total_nodes += other.total_nodes;
+        self.content_nodes += other.content_nodes;
+        self.ad_nodes += other.ad_nodes;
+        self.tracker_nodes += other.tracker_nodes;
+        self.nav_nodes += other.nav_nodes;
+        self.removed_nodes += other.removed_nodes;
+    }
+}
+
 /// Classify all nodes in the `SoA` using branchless SIMD operations.
 ///
 /// This is the heart of the "カリッカリ" optimization:
@@ -231,19 +247,15 @@ fn blend_i32(mask: MaskF32x8, a: I32x8, b: I32x8) -> I32x8 {
 /// Convert SIMD classification index back to `dom::Classification`
 #[inline]
 #[must_use]
-pub const fn index_to_classification(idx: i32) -> crate::dom::Classification {
-    crate::dom::Classification::from_index(idx as usize)
+pub const fn index_to_classification(idx: i32) -> Classification {
+    Classification::from_index(idx as usize)
 }
 
 /// Apply SIMD classification results back to the DOM tree.
 ///
 /// Walks the DOM in the same order as `dom_to_soa` flattening,
 /// applying the SIMD-computed classifications.
-pub fn apply_classifications(
-    node: &mut crate::dom::DomNode,
-    classifications: &[i32],
-    index: &mut usize,
-) {
+pub fn apply_classifications(node: &mut DomNode, classifications: &[i32], index: &mut usize) {
     if *index < classifications.len() {
         node.classification = index_to_classification(classifications[*index]);
         *index += 1;
@@ -254,17 +266,67 @@ pub fn apply_classifications(
     }
 }
 
-/// Prune ad/tracker subtrees (same as original but called after SIMD classify)
-pub fn prune_ads(node: &mut crate::dom::DomNode) {
-    node.children.retain(|c| {
-        c.classification != crate::dom::Classification::Advertisement
-            && c.classification != crate::dom::Classification::Tracker
-    });
+/// Classify a whole DOM tree in `SoA` batches.
+///
+/// Subtrees smaller than [`PARALLEL_GRAIN`] nodes are flattened and
+/// classified as one batch. Above that, the node is classified on its own
+/// and its children's subtrees are handed to rayon, so large pages spread
+/// the flattening (the costly part) and the batches across threads. The
+/// result is the same as one [`classify_batch`] over [`dom_to_soa`].
+pub fn classify_tree(node: &mut DomNode) -> SimdFilterStats {
+    if node.node_count() < PARALLEL_GRAIN {
+        let mut soa = dom_to_soa(node);
+        let stats = classify_batch(&mut soa);
+        let mut idx = 0;
+        apply_classifications(node, soa.classifications.as_slice(), &mut idx);
+        return stats;
+    }
+    let mut own = node_to_soa(node);
+    let mut stats = classify_batch(&mut own);
+    node.classification = index_to_classification(own.classifications.as_slice()[0]);
+    stats += node.children.par_iter_mut().map(classify_tree).reduce(
+        SimdFilterStats::default,
+        |mut a, b| {
+            a += b;
+            a
+        },
+    );
+    stats
+}
+
+/// Classify every node of `arena` in one `SoA` batch.
+pub fn classify_arena(arena: &mut DomArena) -> SimdFilterStats {
+    let (mut soa, ids) = arena_to_soa(arena);
+    let stats = classify_batch(&mut soa);
+    for (&id, &class) in ids.iter().zip(soa.classifications.as_slice()) {
+        arena.node_mut(id).classification = index_to_classification(class);
+    }
+    stats
+}
+
+/// Prune ad/tracker subtrees (same as original but called after SIMD classify);
+/// the children of large subtrees are pruned in parallel.
+pub fn prune_ads(node: &mut DomNode) {
+    if node.node_count() < PARALLEL_GRAIN {
+        prune_ads_sequential(node);
+        return;
+    }
+    node.children.retain(|c| !is_ad_or_tracker(c));
+    node.children.par_iter_mut().for_each(prune_ads);
+}
+
+fn prune_ads_sequential(node: &mut DomNode) {
+    node.children.retain(|c| !is_ad_or_tracker(c));
     for child in &mut node.children {
-        prune_ads(child);
+        prune_ads_sequential(child);
     }
 }
 
+fn is_ad_or_tracker(node: &DomNode) -> bool {
+    node.classification == Classification::Advertisement
+        || node.classification == Classification::Tracker
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -390,4 +452,43 @@ mod tests {
         assert!(stats.content_nodes >= 1);
         assert!(stats.nav_nodes >= 1);
     }
+
+    #[test]
+    fn parallel_tree_matches_one_batch() {
+        fn classes(node: &DomNode, out: &mut Vec<Classification>) {
+            out.push(node.classification);
+            for child in &node.children {
+                classes(child, out);
+            }
+        }
+        let mut html = String::from("<body>");
+        for i in 0..120 {
+            html.push_str(&format!(
+                "<section><nav><a href=\"/{i}\">{i}</a></nav>\
+                 <p>Paragraph {i} with enough words to count as content.</p>\
+                 <div class=\"ad-slot\"><img src=\"/ad.png\"></div><script>x()</script></section>"
+            ));
+        }
+        let dom = crate::dom::parser::parse_html(&html, "https://example.com/");
+        assert!(dom.root.node_count() > 2 * PARALLEL_GRAIN);
+
+        let mut batch = dom.root.clone();
+        let mut soa = dom_to_soa(&batch);
+        let batch_stats = classify_batch(&mut soa);
+        let mut idx = 0;
+        apply_classifications(&mut batch, soa.classifications.as_slice(), &mut idx);
+
+        let mut tree = dom.root.clone();
+        let tree_stats = classify_tree(&mut tree);
+        assert_eq!(tree_stats, batch_stats);
+        let (mut a, mut b) = (Vec::new(), Vec::new());
+        classes(&batch, &mut a);
+        classes(&tree, &mut b);
+        assert_eq!(a, b);
+
+        prune_ads(&mut batch);
+        prune_ads_sequential(&mut tree);
+        assert_eq!(batch.node_count(), tree.node_count());
+        assert!(batch.node_count() < dom.root.node_count());
+    }
 }
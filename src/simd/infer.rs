@@ -0,0 +1,306 @@
+//! SIMD Batch Inference — a loaded classifier model, 8 nodes per FMA
+//!
+//! [`FeatureMatrix`] holds the [`extract_features`] of a whole subtree in
+//! `SoA` form, one column per feature, so a batch of 8 nodes' values for
+//! one feature is a single load. Each layer of the [`ClassifierModel`] then
+//! runs as splatted weights FMA'd into 8-lane accumulators:
+//!
+//!   acc[o] = bias[o];  for i: acc[o] = weight[o][i] * x[i] + acc[o]
+//!
+//! so one instruction advances 8 nodes, with no per-node loop. The argmax
+//! over the output classes is branchless too (`cmp_gt` + `blend`).
+//!
+//! [`infer_scalar`] computes the same thing one node at a time with
+//! [`ClassifierModel::forward`]; it is used where there is no SIMD
+//! ([`SIMD_WIDTH`] 1) and as the reference in tests. Both agree with
+//! [`ClassifierModel::classify_scored`] up to float rounding.
+
+use rayon::prelude::*;
+
+use super::soa::AlignedVec;
+use super::{F32x8, SIMD_WIDTH};
+use crate::dom::filter::{FilterStats, PARALLEL_GRAIN};
+use crate::dom::model::{extract_features, ClassifierModel, NUM_CLASSES, NUM_FEATURES};
+use crate::dom::{Classification, DomNode};
+
+/// Nodes per batch: the width of [`F32x8`].
+const LANES: usize = 8;
+
+/// Feature column marking text nodes, which are always content.
+const TEXT_NODE_FEATURE: usize = 12;
+
+/// `SoA` of the model features of a subtree, in document order, padded with
+/// zero rows to a multiple of [`LANES`].
+#[derive(Debug, Clone)]
+pub struct FeatureMatrix {
+    /// `columns[f][n]`: feature `f` of node `n`
+    pub columns: Vec<AlignedVec<f32>>,
+    /// Node count (without the padding)
+    pub count: usize,
+}
+
+impl FeatureMatrix {
+    /// Flatten `node`'s subtree, parent before children. Large subtrees
+    /// have their features extracted in parallel.
+    #[must_use]
+    pub fn from_tree(node: &DomNode) -> Self {
+        let mut nodes = Vec::with_capacity(node.node_count());
+        flatten(node, &mut nodes);
+        let rows: Vec<[f32; NUM_FEATURES]> = if nodes.len() < PARALLEL_GRAIN {
+            nodes.iter().map(|n| extract_features(n)).collect()
+        } else {
+            nodes.par_iter().map(|n| extract_features(n)).collect()
+        };
+        Self::from_rows(&rows)
+    }
+
+    /// Matrix of the given feature rows.
+    #[must_use]
+    pub fn from_rows(rows: &[[f32; NUM_FEATURES]]) -> Self {
+        let padded = rows.len().div_ceil(LANES) * LANES;
+        let mut columns: Vec<AlignedVec<f32>> = (0..NUM_FEATURES)
+            .map(|_| AlignedVec::with_capacity(padded))
+            .collect();
+        for row in rows {
+            for (column, value) in columns.iter_mut().zip(row) {
+                column.push(*value);
+            }
+        }
+        for column in &mut columns {
+            while column.len() < padded {
+                column.push(0.0);
+            }
+        }
+        Self {
+            columns,
+            count: rows.len(),
+        }
+    }
+
+    /// Number of 8-node batches.
+    #[inline(always)]
+    #[must_use]
+    pub const fn batch_count(&self) -> usize {
+        self.count.div_ceil(LANES)
+    }
+
+    /// Features of node `n`, as one row.
+    #[must_use]
+    pub fn row(&self, n: usize) -> [f32; NUM_FEATURES] {
+        let mut row = [0.0; NUM_FEATURES];
+        for (value, column) in row.iter_mut().zip(&self.columns) {
+            *value = column.as_slice()[n];
+        }
+        row
+    }
+}
+
+fn flatten<'a>(node: &'a DomNode, out: &mut Vec<&'a DomNode>) {
+    out.push(node);
+    for child in &node.children {
+        flatten(child, out);
+    }
+}
+
+/// Classify every node of `matrix` with `model`: the winning class and its
+/// output activation per node, in matrix order.
+#[must_use]
+pub fn infer(model: &ClassifierModel, matrix: &FeatureMatrix) -> Vec<(Classification, f32)> {
+    if SIMD_WIDTH == 1 {
+        return infer_scalar(model, matrix);
+    }
+    let batches = matrix.batch_count();
+    let run = |batch: usize| infer_batch(model, matrix, batch);
+    let lanes: Vec<[(Classification, f32); LANES]> = if matrix.count < PARALLEL_GRAIN {
+        (0..batches).map(run).collect()
+    } else {
+        (0..batches).into_par_iter().map(run).collect()
+    };
+    let mut out: Vec<_> = lanes.into_iter().flatten().collect();
+    out.truncate(matrix.count);
+    out
+}
+
+/// [`infer`] one node at a time, without SIMD.
+#[must_use]
+pub fn infer_scalar(model: &ClassifierModel, matrix: &FeatureMatrix) -> Vec<(Classification, f32)> {
+    (0..matrix.count)
+        .map(|n| {
+            let row = matrix.row(n);
+            if row[TEXT_NODE_FEATURE] > 0.5 {
+                return (Classification::Content, 0.0);
+            }
+            let output = model.forward(&row);
+            let (best, score) = output
+                .iter()
+                .copied()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .unwrap_or((NUM_CLASSES - 1, 0.0));
+            (Classification::from_index(best), score)
+        })
+        .collect()
+}
+
+/// Run the model over the 8 nodes of batch `batch`.
+fn infer_batch(
+    model: &ClassifierModel,
+    matrix: &FeatureMatrix,
+    batch: usize,
+) -> [(Classification, f32); LANES] {
+    let offset = batch * LANES;
+    let mut values: Vec<F32x8> = matrix
+        .columns
+        .iter()
+        .map(|column| F32x8::load(&column.as_slice()[offset..]))
+        .collect();
+
+    let last = model.layers.len().saturating_sub(1);
+    for (l, layer) in model.layers.iter().enumerate() {
+        values = layer
+            .weights
+            .iter()
+            .zip(&layer.bias)
+            .map(|(row, &bias)| {
+                row.iter()
+                    .zip(&values)
+                    .fold(F32x8::splat(bias), |acc, (&w, &x)| {
+                        F32x8::splat(w).fma(x, acc)
+                    })
+            })
+            .collect();
+        // ReLU between layers
+        if l < last {
+            for v in &mut values {
+                *v = v.max(F32x8::zero());
+            }
+        }
+    }
+
+    // Branchless argmax; `>=` keeps the last of equal outputs, like
+    // `Iterator::max_by`
+    let mut best = values.first().copied().unwrap_or_else(F32x8::zero);
+    let mut best_idx = F32x8::zero();
+    for (c, &v) in values.iter().enumerate().skip(1) {
+        let higher = best.cmp_gt(v).not();
+        best = higher.blend(v, best);
+        best_idx = higher.blend(F32x8::splat(c as f32), best_idx);
+    }
+
+    // Text nodes are always content (score 0, as the scalar path)
+    let text = F32x8::load(&matrix.columns[TEXT_NODE_FEATURE].as_slice()[offset..])
+        .cmp_gt(F32x8::splat(0.5));
+    best = text.blend(F32x8::zero(), best);
+    best_idx = text.blend(F32x8::zero(), best_idx);
+
+    let mut out = [(Classification::Unknown, 0.0); LANES];
+    for (lane, slot) in out.iter_mut().enumerate() {
+        *slot = (
+            Classification::from_index(best_idx.v[lane] as usize),
+            best.v[lane],
+        );
+    }
+    out
+}
+
+/// Classify `node`'s subtree with `model` in 8-node batches, like
+/// [`crate::dom::filter::SemanticFilter::classify`] does node by node.
+pub fn classify_tree(node: &mut DomNode, model: &ClassifierModel) -> FilterStats {
+    let matrix = FeatureMatrix::from_tree(node);
+    let results = infer(model, &matrix);
+    let mut stats = FilterStats::default();
+    let mut idx = 0;
+    apply(node, &results, &mut idx, &mut stats);
+    stats.removed_nodes = stats.ad_nodes + stats.tracker_nodes;
+    stats
+}
+
+/// Write `results` back in the order [`FeatureMatrix::from_tree`] read.
+fn apply(
+    node: &mut DomNode,
+    results: &[(Classification, f32)],
+    index: &mut usize,
+    stats: &mut FilterStats,
+) {
+    if let Some(&(class, _)) = results.get(*index) {
+        node.classification = class;
+        stats.count(class);
+        *index += 1;
+    }
+    for child in &mut node.children {
+        apply(child, results, index, stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::model::Layer;
+
+    /// Dense model with fixed pseudo-random weights in [-1, 1].
+    fn dense_model() -> ClassifierModel {
+        let mut seed = 0x2545_F491_u32;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            (seed % 2001) as f32 / 1000.0 - 1.0
+        };
+        let mut layer = |inputs: usize, outputs: usize| Layer {
+            weights: (0..outputs)
+                .map(|_| (0..inputs).map(|_| next()).collect())
+                .collect(),
+            bias: (0..outputs).map(|_| next()).collect(),
+        };
+        let layers = vec![layer(NUM_FEATURES, 24), layer(24, NUM_CLASSES)];
+        let model = ClassifierModel {
+            name: "dense".into(),
+            version: String::new(),
+            layers,
+        };
+        model.validate().unwrap();
+        model
+    }
+
+    #[test]
+    fn batches_match_the_scalar_path() {
+        let mut html = String::from("<body>");
+        for i in 0..150 {
+            html.push_str(&format!(
+                "<section><nav><a href=\"/{i}\">{i}</a></nav>\
+                 <p>Paragraph {i} with enough words to count as content.</p>\
+                 <div class=\"ad-slot\"><img src=\"/ad.png\"></div><script>x()</script></section>"
+            ));
+        }
+        let dom = crate::dom::parser::parse_html(&html, "https://example.com/");
+        // Not a multiple of 8, and large enough to run in parallel
+        let model = dense_model();
+        let matrix = FeatureMatrix::from_tree(&dom.root);
+        assert!(matrix.count > PARALLEL_GRAIN);
+        assert_ne!(matrix.count % LANES, 0);
+
+        let batched = infer(&model, &matrix);
+        let scalar = infer_scalar(&model, &matrix);
+        assert_eq!(batched.len(), matrix.count);
+        for (n, (&(class, score), &(expected, expected_score))) in
+            batched.iter().zip(&scalar).enumerate()
+        {
+            assert!(
+                (score - expected_score).abs() < 1e-4,
+                "node {n}: {score} vs {expected_score}"
+            );
+            if class != expected {
+                // Only a near-tie may come out differently
+                let output = model.forward(&matrix.row(n));
+                assert!((output[class as usize] - expected_score).abs() < 1e-4);
+            }
+        }
+
+        // The tree walk applies the same classes as per-node classification
+        let mut tree = dom.root.clone();
+        let stats = classify_tree(&mut tree, &model);
+        assert_eq!(stats.total_nodes, matrix.count);
+        let (class, _) = model.classify_scored(&tree.children[0]);
+        assert_eq!(tree.children[0].classification, class);
+    }
+}
@@ -10,6 +10,8 @@
 //! - Branchless `max()` for clamping
 
 use super::F32x8;
+use crate::dom::arena::{DomArena, NodeId};
+use crate::dom::filter::is_removed_class;
 use crate::dom::{Classification, DomNode, NodeType};
 
 /// Pre-computed reciprocals for Division Exorcism.
@@ -347,22 +349,14 @@ pub fn flatten_dom(node: &DomNode, depth: usize, out: &mut Vec<FlatNode>) {
         return;
     }
 
-    let is_block = node.node_type == NodeType::Element && is_block_tag(&node.tag);
-
-    out.push(FlatNode {
-        tag_type: super::soa::encode_tag(&node.tag),
-        text_len: node.text.len(),
-        is_block,
+    out.push(flat_node(
+        &node.tag,
+        &node.text,
+        node.node_type,
+        node.classification,
+        link_target(&node.tag, |name| node.attr(name)),
         depth,
-        classification: node.classification,
-        tag: node.tag.clone(),
-        text: node.text.clone(),
-        href: match node.tag.as_str() {
-            "a" => node.attr("href").map(std::string::ToString::to_string),
-            "img" => node.attr("src").map(std::string::ToString::to_string),
-            _ => None,
-        },
-    });
+    ));
 
     for child in &node.children {
         if child.is_visible() {
@@ -371,6 +365,56 @@ pub fn flatten_dom(node: &DomNode, depth: usize, out: &mut Vec<FlatNode>) {
     }
 }
 
+/// Flatten `id`'s subtree of an arena DOM, as [`flatten_dom`].
+pub fn flatten_arena(arena: &DomArena, id: NodeId, depth: usize, out: &mut Vec<FlatNode>) {
+    let node = arena.node(id);
+    if is_removed_class(node.classification) {
+        return;
+    }
+
+    out.push(flat_node(
+        &node.tag,
+        &node.text,
+        node.node_type,
+        node.classification,
+        link_target(&node.tag, |name| node.attr(name)),
+        depth,
+    ));
+
+    for child in arena.children(id) {
+        flatten_arena(arena, child, depth + 1, out);
+    }
+}
+
+fn flat_node(
+    tag: &str,
+    text: &str,
+    node_type: NodeType,
+    classification: Classification,
+    href: Option<String>,
+    depth: usize,
+) -> FlatNode {
+    FlatNode {
+        tag_type: super::soa::encode_tag(tag),
+        text_len: text.len(),
+        is_block: node_type == NodeType::Element && is_block_tag(tag),
+        depth,
+        classification,
+        tag: tag.to_string(),
+        text: text.to_string(),
+        href,
+    }
+}
+
+/// Where an `<a>` or `<img>` points.
+fn link_target<'a>(tag: &str, attr: impl Fn(&str) -> Option<&'a str>) -> Option<String> {
+    match tag {
+        "a" => attr("href").map(std::string::ToString::to_string),
+        "img" => attr("src").map(std::string::ToString::to_string),
+        _ => None,
+    }
+}
+
 fn is_block_tag(tag: &str) -> bool {
     matches!(
         tag,
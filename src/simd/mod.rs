@@ -6,10 +6,14 @@
 //! - `SoA` (Structure of Arrays) data layout for cache-friendly SIMD access
 //! - Platform-adaptive SIMD: AVX2 (8-wide) / SSE2 (4-wide) / NEON (4-wide) / Scalar fallback
 //! - Batch DOM classification, ad-block matching, and layout computation
+//! - Batch inference of loaded classifier models, 8 nodes per FMA
+//! - OZ-mode particle motion, 8 particles per update step
 
 pub mod adblock;
 pub mod classify;
+pub mod infer;
 pub mod layout;
+pub mod particles;
 pub mod soa;
 
 /// SIMD lane width detected at compile time.
@@ -134,6 +138,28 @@ impl F32x8 {
         Self { v: out }
     }
 
+    /// Element-wise subtraction
+    #[allow(clippy::should_implement_trait)]
+    #[inline(always)]
+    #[must_use]
+    pub fn sub(self, rhs: Self) -> Self {
+        #[cfg(target_arch = "x86_64")]
+        // SAFETY: AVX2 is checked at runtime. F32x8 is repr(C, align(32)) matching __m256 layout.
+        // All transmutes between F32x8 and __m256 are valid due to identical size and alignment.
+        unsafe {
+            if is_x86_feature_detected!("avx2") {
+                let a: core::arch::x86_64::__m256 = core::mem::transmute(self);
+                let b: core::arch::x86_64::__m256 = core::mem::transmute(rhs);
+                return core::mem::transmute(core::arch::x86_64::_mm256_sub_ps(a, b));
+            }
+        }
+        let mut out = [0.0f32; 8];
+        for (out_elem, (a, b)) in out.iter_mut().zip(self.v.iter().zip(rhs.v.iter())) {
+            *out_elem = a - b;
+        }
+        Self { v: out }
+    }
+
     /// Element-wise multiplication
     #[allow(clippy::should_implement_trait)]
     #[inline(always)]
@@ -195,6 +221,27 @@ impl F32x8 {
         Self { v: out }
     }
 
+    /// Element-wise minimum
+    #[inline(always)]
+    #[must_use]
+    pub fn min(self, rhs: Self) -> Self {
+        #[cfg(target_arch = "x86_64")]
+        // SAFETY: AVX2 is checked at runtime. F32x8 is repr(C, align(32)) matching __m256 layout.
+        // Transmutes between F32x8 and __m256 are valid due to identical size and alignment.
+        unsafe {
+            if is_x86_feature_detected!("avx2") {
+                let a: core::arch::x86_64::__m256 = core::mem::transmute(self);
+                let b: core::arch::x86_64::__m256 = core::mem::transmute(rhs);
+                return core::mem::transmute(core::arch::x86_64::_mm256_min_ps(a, b));
+            }
+        }
+        let mut out = [0.0f32; 8];
+        for (out_elem, (a, b)) in out.iter_mut().zip(self.v.iter().zip(rhs.v.iter())) {
+            *out_elem = if a < b { *a } else { *b };
+        }
+        Self { v: out }
+    }
+
     /// Compare greater-than, returns mask (all 1s or all 0s per lane)
     #[inline(always)]
     #[must_use]
@@ -0,0 +1,140 @@
+//! SIMD Particle Update — OZ-mode motion, 8 particles at once
+//!
+//! Each frame every rotunda particle eases its pull toward the viewer,
+//! rotates at its layer's speed, ages, and gets a lifecycle opacity. On the
+//! `SoA` layout all four are straight-line F32x8 arithmetic; the only
+//! per-particle decision, "expired?", becomes a mask that is scanned only
+//! when some lane is set.
+//!
+//! Performance model:
+//!   - `AoS`: 1 particle × (layer match + focus check + fade branches) per step
+//!   - `SoA`: 8 particles × 0 branches per step, speed and focus precomputed
+
+use super::soa::ParticlesSoA;
+use super::F32x8;
+use crate::render::stream::{FADE_IN_DURATION, FADE_OUT_DURATION};
+
+/// Lifecycle opacity: fades in over `FADE_IN_DURATION`, out over the last
+/// `FADE_OUT_DURATION` of `lifetime`; always 1.0 for a grabbed particle
+/// (`moving` 0.0).
+#[inline(always)]
+#[must_use]
+pub fn lifecycle_opacity(age: f32, lifetime: f32, moving: f32) -> f32 {
+    // Division Exorcism: reciprocal constants
+    let fade_in = age * (1.0 / FADE_IN_DURATION);
+    let fade_out = (lifetime - age) * (1.0 / FADE_OUT_DURATION);
+    fade_in.min(fade_out).clamp(0.0, 1.0).max(1.0 - moving)
+}
+
+/// Advance every particle by `dt` seconds, easing pulls by `ease` (0–1).
+/// Indices of moving particles that reached their lifetime are appended to
+/// `expired`, in order.
+pub fn update_particles(soa: &mut ParticlesSoA, dt: f32, ease: f32, expired: &mut Vec<usize>) {
+    let count = soa.count;
+    let full = count - count % 8;
+
+    let angles = soa.angles.as_mut_slice();
+    let speeds = soa.speeds.as_slice();
+    let ages = soa.ages.as_mut_slice();
+    let lifetimes = soa.lifetimes.as_slice();
+    let pulls = soa.pulls.as_mut_slice();
+    let pull_targets = soa.pull_targets.as_slice();
+    let moving = soa.moving.as_slice();
+    let opacities = soa.opacities.as_mut_slice();
+
+    // Constants (splatted once, reused across all batches)
+    let dt8 = F32x8::splat(dt);
+    let ease8 = F32x8::splat(ease);
+    let zero = F32x8::zero();
+    let one = F32x8::splat(1.0);
+    let inv_fade_in = F32x8::splat(1.0 / FADE_IN_DURATION);
+    let inv_fade_out = F32x8::splat(1.0 / FADE_OUT_DURATION);
+
+    for offset in (0..full).step_by(8) {
+        // Pull: p += (target - p) * ease
+        let pull = F32x8::load(&pulls[offset..]);
+        let target = F32x8::load(&pull_targets[offset..]);
+        target
+            .sub(pull)
+            .fma(ease8, pull)
+            .store(&mut pulls[offset..]);
+
+        // Grabbed lanes (moving = 0) neither rotate nor age
+        let mov = F32x8::load(&moving[offset..]);
+        let step = mov.mul(dt8);
+        let angle = F32x8::load(&angles[offset..]);
+        F32x8::load(&speeds[offset..])
+            .fma(step, angle)
+            .store(&mut angles[offset..]);
+        let age = F32x8::load(&ages[offset..]).add(step);
+        age.store(&mut ages[offset..]);
+
+        // Opacity: clamp(min(fade in, fade out), 0, 1), 1 while grabbed
+        let lifetime = F32x8::load(&lifetimes[offset..]);
+        age.mul(inv_fade_in)
+            .min(lifetime.sub(age).mul(inv_fade_out))
+            .min(one)
+            .max(zero)
+            .max(one.sub(mov))
+            .store(&mut opacities[offset..]);
+
+        // Expired: age >= lifetime on a moving lane
+        let done = lifetime.cmp_gt(age).not().and(mov.cmp_gt(zero));
+        if done.any() {
+            for (lane, &bit) in done.bits.iter().enumerate() {
+                if bit != 0 {
+                    expired.push(offset + lane);
+                }
+            }
+        }
+    }
+
+    // Scalar tail (fewer than 8 particles left)
+    for i in full..count {
+        pulls[i] = (pull_targets[i] - pulls[i]).mul_add(ease, pulls[i]);
+        let step = moving[i] * dt;
+        angles[i] = speeds[i].mul_add(step, angles[i]);
+        ages[i] += step;
+        opacities[i] = lifecycle_opacity(ages[i], lifetimes[i], moving[i]);
+        if moving[i] > 0.0 && ages[i] >= lifetimes[i] {
+            expired.push(i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simd_lanes_match_the_scalar_tail() {
+        // 21 particles: two SIMD batches and a 5-particle scalar tail, with
+        // the same state repeating every 7 so each appears in both paths
+        let mut soa = ParticlesSoA::with_capacity(21);
+        for i in 0..21 {
+            let k = (i % 7) as f32;
+            soa.push(k, 0.0, 0.1f32.mul_add(k, -0.3), 4.0 * k, 14.0);
+            soa.pull_targets.as_mut_slice()[i] = if i % 7 == 3 { 1.0 } else { 0.0 };
+            soa.moving.as_mut_slice()[i] = if i % 7 == 5 { 0.0 } else { 1.0 };
+        }
+        let mut expired = Vec::new();
+        update_particles(&mut soa, 0.5, 0.25, &mut expired);
+
+        for i in 7..21 {
+            let j = i % 7;
+            for field in [&soa.angles, &soa.ages, &soa.pulls, &soa.opacities] {
+                let (a, b) = (field.as_slice()[j], field.as_slice()[i]);
+                assert!((a - b).abs() < 1e-5, "particle {i}: {a} vs {b}");
+            }
+        }
+        // Ages 16.5 and 24.5 reach 14; the grabbed particle (k = 5) never expires
+        assert_eq!(expired, [4, 6, 11, 13, 18, 20]);
+        // Fading in, fully visible, fading out, grabbed
+        let opacity = soa.opacities.as_slice();
+        assert!((opacity[0] - 0.5 / FADE_IN_DURATION).abs() < 1e-5);
+        assert!((opacity[1] - 1.0).abs() < 1e-6);
+        assert!((opacity[3] - (14.0 - 12.5) / FADE_OUT_DURATION).abs() < 1e-5);
+        assert!((opacity[5] - 1.0).abs() < 1e-6);
+        assert!((soa.pulls.as_slice()[3] - 0.25).abs() < 1e-6);
+    }
+}
@@ -7,6 +7,7 @@
 //! instead of gathering scattered fields from different cache lines.
 
 use super::{align_up, F32x8, I32x8, SIMD_WIDTH};
+use crate::dom::arena::{DomArena, NodeId};
 
 /// `SoA` representation of DOM node features for SIMD batch processing.
 ///
@@ -245,6 +246,68 @@ impl LayoutBoxesSoA {
     }
 }
 
+/// `SoA` motion state of OZ-mode text particles ([`crate::render::stream`]).
+///
+/// Instead of Vec<TextParticle{angle,age,...}>, each per-frame quantity is
+/// its own array, so [`update_particles`](super::particles::update_particles)
+/// advances 8 particles per instruction.
+#[derive(Debug, Clone, Default)]
+pub struct ParticlesSoA {
+    /// Angle on the rotunda wall (radians)
+    pub angles: AlignedVec<f32>,
+    /// Height on the wall
+    pub ys: AlignedVec<f32>,
+    /// Angular velocity (rad/s)
+    pub speeds: AlignedVec<f32>,
+    /// Time alive (seconds)
+    pub ages: AlignedVec<f32>,
+    /// Time alive before respawn (seconds)
+    pub lifetimes: AlignedVec<f32>,
+    /// Pull toward the viewer (0 = on the wall, 1 = fully pulled)
+    pub pulls: AlignedVec<f32>,
+    /// Pull each particle eases toward (1.0 while its text is focused)
+    pub pull_targets: AlignedVec<f32>,
+    /// 1.0, or 0.0 while grabbed: scales rotation and ageing
+    pub moving: AlignedVec<f32>,
+    /// Lifecycle opacity (fade in / visible / fade out), written each update
+    pub opacities: AlignedVec<f32>,
+    pub count: usize,
+}
+
+impl ParticlesSoA {
+    #[must_use]
+    pub fn with_capacity(cap: usize) -> Self {
+        let aligned_cap = align_up(cap);
+        Self {
+            angles: AlignedVec::with_capacity(aligned_cap),
+            ys: AlignedVec::with_capacity(aligned_cap),
+            speeds: AlignedVec::with_capacity(aligned_cap),
+            ages: AlignedVec::with_capacity(aligned_cap),
+            lifetimes: AlignedVec::with_capacity(aligned_cap),
+            pulls: AlignedVec::with_capacity(aligned_cap),
+            pull_targets: AlignedVec::with_capacity(aligned_cap),
+            moving: AlignedVec::with_capacity(aligned_cap),
+            opacities: AlignedVec::with_capacity(aligned_cap),
+            count: 0,
+        }
+    }
+
+    /// Add a moving, unpulled particle; its opacity is set by the next
+    /// update.
+    pub fn push(&mut self, angle: f32, y: f32, speed: f32, age: f32, lifetime: f32) {
+        self.angles.push(angle);
+        self.ys.push(y);
+        self.speeds.push(speed);
+        self.ages.push(age);
+        self.lifetimes.push(lifetime);
+        self.pulls.push(0.0);
+        self.pull_targets.push(0.0);
+        self.moving.push(1.0);
+        self.opacities.push(0.0);
+        self.count += 1;
+    }
+}
+
 /// 32-byte aligned Vec for SIMD loads/stores without unaligned penalty.
 ///
 /// Standard Vec doesn't guarantee alignment > 8 bytes.
@@ -335,15 +398,89 @@ pub fn dom_to_soa(node: &crate::dom::DomNode) -> NodeFeaturesSoA {
     soa
 }
 
+/// `SoA` of `node` alone, without its descendants (for classifying the
+/// ancestors of subtrees flattened separately).
+#[must_use]
+pub fn node_to_soa(node: &crate::dom::DomNode) -> NodeFeaturesSoA {
+    let mut soa = NodeFeaturesSoA::with_capacity(1);
+    soa.push(&node_features(node));
+    soa.pad_to_simd_width();
+    soa
+}
+
 fn flatten_node(node: &crate::dom::DomNode, soa: &mut NodeFeaturesSoA) {
+    soa.push(&node_features(node));
+
+    for child in &node.children {
+        flatten_node(child, soa);
+    }
+}
+
+/// `SoA` of every node of `arena` reachable from its root, in document
+/// order, with the id each lane belongs to.
+#[must_use]
+pub fn arena_to_soa(arena: &DomArena) -> (NodeFeaturesSoA, Vec<NodeId>) {
+    let ids = arena.descendants(arena.root());
+    let mut soa = NodeFeaturesSoA::with_capacity(ids.len());
+    for &id in &ids {
+        let node = arena.node(id);
+        soa.push(&features(&FeatureSource {
+            tag: &node.tag,
+            text: &node.text,
+            class: node.attr("class").unwrap_or(""),
+            id: node.attr("id").unwrap_or(""),
+            has_href: node.attr("href").is_some(),
+            has_data_ad: node.attributes.iter().any(|(k, _)| is_data_ad(k)),
+            attr_count: node.attributes.len(),
+            child_count: arena.children(id).count(),
+            text_density: arena.text_density(id),
+            link_density: arena.link_density(id),
+        }));
+    }
+    soa.pad_to_simd_width();
+    (soa, ids)
+}
+
+fn node_features(node: &crate::dom::DomNode) -> NodeFeatures {
+    features(&FeatureSource {
+        tag: &node.tag,
+        text: &node.text,
+        class: node.attr("class").unwrap_or(""),
+        id: node.attr("id").unwrap_or(""),
+        has_href: node.attr("href").is_some(),
+        has_data_ad: node.attributes.keys().any(|k| is_data_ad(k)),
+        attr_count: node.attributes.len(),
+        child_count: node.children.len(),
+        text_density: node.text_density(),
+        link_density: node.link_density(),
+    })
+}
+
+/// What the features are computed from, read off either DOM.
+struct FeatureSource<'a> {
+    tag: &'a str,
+    text: &'a str,
+    class: &'a str,
+    id: &'a str,
+    has_href: bool,
+    has_data_ad: bool,
+    attr_count: usize,
+    child_count: usize,
+    text_density: f32,
+    link_density: f32,
+}
+
+fn is_data_ad(attr: &str) -> bool {
+    attr.starts_with("data-ad") || attr.starts_with("data-tracking")
+}
+
+fn features(node: &FeatureSource<'_>) -> NodeFeatures {
     // Division exorcism: multiply by reciprocal instead of dividing
     const INV_32: f32 = 1.0 / 32.0;
     const INV_1024: f32 = 1.0 / 1024.0;
     const INV_16: f32 = 1.0 / 16.0;
 
-    let class = node.attr("class").unwrap_or("");
-    let id = node.attr("id").unwrap_or("");
-    let combined = format!("{class} {id}").to_lowercase();
+    let combined = format!("{} {}", node.class, node.id).to_lowercase();
 
     let ad_patterns = [
         "ad",
@@ -371,20 +508,16 @@ fn flatten_node(node: &crate::dom::DomNode, soa: &mut NodeFeaturesSoA) {
 
     let has_ad = ad_patterns.iter().any(|p| combined.contains(p));
     let has_tracker = tracker_patterns.iter().any(|p| combined.contains(p));
-    let has_data_ad = node
-        .attributes
-        .keys()
-        .any(|k| k.starts_with("data-ad") || k.starts_with("data-tracking"));
 
-    let features = NodeFeatures {
-        tag_type: encode_tag(&node.tag),
-        text_density: node.text_density(),
-        link_density: node.link_density(),
-        child_count: node.children.len() as f32 * INV_32, // ÷32 → ×(1/32)
+    NodeFeatures {
+        tag_type: encode_tag(node.tag),
+        text_density: node.text_density,
+        link_density: node.link_density,
+        child_count: node.child_count as f32 * INV_32, // ÷32 → ×(1/32)
         has_ad_class: if has_ad { 1.0 } else { 0.0 },
         has_tracker_class: if has_tracker { 1.0 } else { 0.0 },
-        has_data_ad: if has_data_ad { 1.0 } else { 0.0 },
-        is_script: if matches!(node.tag.as_str(), "script" | "noscript") {
+        has_data_ad: if node.has_data_ad { 1.0 } else { 0.0 },
+        is_script: if matches!(node.tag, "script" | "noscript") {
             1.0
         } else {
             0.0
@@ -392,34 +525,21 @@ fn flatten_node(node: &crate::dom::DomNode, soa: &mut NodeFeaturesSoA) {
         is_style: if node.tag == "style" { 1.0 } else { 0.0 },
         is_nav: if node.tag == "nav" { 1.0 } else { 0.0 },
         is_interactive: if matches!(
-            node.tag.as_str(),
+            node.tag,
             "button" | "input" | "textarea" | "select" | "form"
         ) {
             1.0
         } else {
             0.0
         },
-        is_media: if matches!(
-            node.tag.as_str(),
-            "img" | "video" | "audio" | "picture" | "canvas"
-        ) {
+        is_media: if matches!(node.tag, "img" | "video" | "audio" | "picture" | "canvas") {
             1.0
         } else {
             0.0
         },
         text_length: node.text.len() as f32 * INV_1024, // ÷1024 → ×(1/1024)
-        has_href: if node.attr("href").is_some() {
-            1.0
-        } else {
-            0.0
-        },
-        attr_count: node.attributes.len() as f32 * INV_16, // ÷16 → ×(1/16)
-    };
-
-    soa.push(&features);
-
-    for child in &node.children {
-        flatten_node(child, soa);
+        has_href: if node.has_href { 1.0 } else { 0.0 },
+        attr_count: node.attr_count as f32 * INV_16, // ÷16 → ×(1/16)
     }
 }
 
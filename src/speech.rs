@@ -0,0 +1,362 @@
+//! Read-aloud: speaking a reader-mode article paragraph by paragraph.
+//!
+//! [`speakable_blocks`] splits an article's layout into the blocks the
+//! reader view draws — headings, paragraphs, list items, quotes, code — in
+//! reading order. [`ReadAloud`] hands them to the system synthesizer one at
+//! a time, so the block being spoken is always known and can be
+//! highlighted, skipped, or restarted at another rate.
+//!
+//! The synthesizer is a child process fed on stdin: `say` on macOS, which
+//! speaks with the `AVSpeechSynthesizer` voices, and `espeak-ng` or
+//! `espeak` elsewhere, which play through the default output themselves.
+//! Pausing stops the current block; resuming starts it over.
+
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+
+use crate::render::layout::LayoutNode;
+
+/// Words per minute at rate 1.0.
+pub const BASE_WPM: f32 = 175.0;
+
+/// Rates offered in the UI, as multiples of [`BASE_WPM`].
+pub const RATES: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+
+/// Whether `tag` is read as one block, as the reader view draws it.
+fn is_block(tag: &str) -> bool {
+    match tag {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => true,
+        _ => matches!(tag, "p" | "li" | "blockquote" | "figcaption" | "dd" | "pre"),
+    }
+}
+
+/// Blocks of `root` with text, in reading order. Bare text outside any
+/// block is a block of its own.
+#[must_use]
+pub fn speakable_blocks(root: &LayoutNode) -> Vec<&LayoutNode> {
+    fn walk<'a>(node: &'a LayoutNode, out: &mut Vec<&'a LayoutNode>) {
+        let tag = node.tag.as_str();
+        if is_block(tag) || (tag.is_empty() && !node.text.trim().is_empty()) {
+            if has_text(node) {
+                out.push(node);
+            }
+            return;
+        }
+        if matches!(tag, "img" | "script" | "style") {
+            return;
+        }
+        for child in &node.children {
+            walk(child, out);
+        }
+    }
+    let mut out = Vec::new();
+    walk(root, &mut out);
+    out
+}
+
+fn has_text(node: &LayoutNode) -> bool {
+    !node.text.trim().is_empty() || node.children.iter().any(has_text)
+}
+
+/// Text of a block, runs joined by spaces.
+#[must_use]
+pub fn block_text(node: &LayoutNode) -> String {
+    let mut text = node.text.trim().to_owned();
+    for child in &node.children {
+        let inner = block_text(child);
+        if !inner.is_empty() {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&inner);
+        }
+    }
+    text
+}
+
+/// A speech synthesizer program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Synthesizer {
+    /// macOS `say`
+    Say,
+    /// `espeak-ng` or `espeak`, by program name
+    Espeak(&'static str),
+}
+
+impl Synthesizer {
+    /// The synthesizer of this system, if one is installed.
+    #[must_use]
+    pub fn detect() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            return Some(Self::Say);
+        }
+        ["espeak-ng", "espeak"]
+            .into_iter()
+            .find(|program| {
+                Command::new(program)
+                    .arg("--version")
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .is_ok_and(|s| s.success())
+            })
+            .map(Self::Espeak)
+    }
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Say => "say",
+            Self::Espeak(program) => program,
+        }
+    }
+
+    /// Program and arguments speaking stdin at `rate` times [`BASE_WPM`].
+    #[must_use]
+    pub fn command_line(self, rate: f32) -> (&'static str, Vec<String>) {
+        let wpm = format!("{:.0}", BASE_WPM * rate);
+        match self {
+            Self::Say => ("say", vec!["-r".into(), wpm]),
+            Self::Espeak(program) => (program, vec!["-s".into(), wpm, "--stdin".into()]),
+        }
+    }
+}
+
+/// Where playback stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Playback {
+    Playing,
+    Paused,
+    /// Past the last block
+    Finished,
+}
+
+/// An article being read aloud, one block per synthesizer run.
+pub struct ReadAloud {
+    synth: Synthesizer,
+    blocks: Vec<String>,
+    current: usize,
+    state: Playback,
+    rate: f32,
+    child: Option<Child>,
+}
+
+impl ReadAloud {
+    /// Player for `blocks`, paused at the first.
+    #[must_use]
+    pub fn new(synth: Synthesizer, blocks: Vec<String>, rate: f32) -> Self {
+        let state = if blocks.is_empty() {
+            Playback::Finished
+        } else {
+            Playback::Paused
+        };
+        Self {
+            synth,
+            blocks,
+            current: 0,
+            state,
+            rate,
+            child: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn synthesizer(&self) -> Synthesizer {
+        self.synth
+    }
+
+    #[must_use]
+    pub const fn state(&self) -> Playback {
+        self.state
+    }
+
+    /// Index of the block being (or next to be) spoken; `None` when
+    /// finished.
+    #[must_use]
+    pub const fn current(&self) -> Option<usize> {
+        match self.state {
+            Playback::Finished => None,
+            _ => Some(self.current),
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    #[must_use]
+    pub const fn rate(&self) -> f32 {
+        self.rate
+    }
+
+    /// Speak from the start of the current block; from the top when
+    /// finished.
+    pub fn play(&mut self) {
+        if self.blocks.is_empty() {
+            return;
+        }
+        if self.state == Playback::Finished {
+            self.current = 0;
+        }
+        self.state = Playback::Playing;
+        self.speak_current();
+    }
+
+    /// Stop speaking, keeping the position.
+    pub fn pause(&mut self) {
+        self.silence();
+        if self.state == Playback::Playing {
+            self.state = Playback::Paused;
+        }
+    }
+
+    /// Move `by` blocks (negative goes back), speaking the new block if
+    /// playing.
+    pub fn skip(&mut self, by: isize) {
+        if self.blocks.is_empty() {
+            return;
+        }
+        let last = self.blocks.len() - 1;
+        self.current = self.current.saturating_add_signed(by).min(last);
+        if self.state == Playback::Finished {
+            self.state = Playback::Paused;
+        }
+        if self.state == Playback::Playing {
+            self.speak_current();
+        }
+    }
+
+    /// Speak at `rate` times [`BASE_WPM`] from now on; the current block
+    /// starts over if playing.
+    pub fn set_rate(&mut self, rate: f32) {
+        if (self.rate - rate).abs() < f32::EPSILON {
+            return;
+        }
+        self.rate = rate;
+        if self.state == Playback::Playing {
+            self.speak_current();
+        }
+    }
+
+    /// Move on when the current block has been spoken. Returns `true` if
+    /// the position changed.
+    pub fn poll(&mut self) -> bool {
+        if self.state != Playback::Playing {
+            return false;
+        }
+        let done = match self.child {
+            Some(ref mut child) => !matches!(child.try_wait(), Ok(None)),
+            None => true,
+        };
+        if !done {
+            return false;
+        }
+        self.child = None;
+        if self.current + 1 < self.blocks.len() {
+            self.current += 1;
+            self.speak_current();
+        } else {
+            self.state = Playback::Finished;
+        }
+        true
+    }
+
+    fn speak_current(&mut self) {
+        self.silence();
+        let (program, args) = self.synth.command_line(self.rate);
+        let spawned = Command::new(program)
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        match spawned {
+            Ok(mut child) => {
+                if let Some(mut stdin) = child.stdin.take() {
+                    // Dropping stdin closes it, which starts the speech
+                    if let Err(e) = stdin.write_all(self.blocks[self.current].as_bytes()) {
+                        log::debug!("Read aloud: {e}");
+                    }
+                }
+                self.child = Some(child);
+            }
+            Err(e) => {
+                log::warn!("Read aloud: cannot run {program}: {e}");
+                self.state = Playback::Paused;
+            }
+        }
+    }
+
+    fn silence(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for ReadAloud {
+    fn drop(&mut self) {
+        self.silence();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::parser::parse_html;
+    use crate::render::layout::compute_layout_with_font;
+
+    #[test]
+    fn blocks_follow_reading_order() {
+        let dom = parse_html(
+            "<article><h1>Title</h1><p>First <em>para</em>graph.</p>\
+             <ul><li>One</li><li>Two</li></ul><p>  </p><img src=\"a.png\">\
+             <blockquote><p>Quoted</p></blockquote></article>",
+            "https://example.com/",
+        );
+        let layout = compute_layout_with_font(&dom.root, 600.0, 16.0);
+        let texts: Vec<String> = speakable_blocks(&layout)
+            .into_iter()
+            .map(block_text)
+            .collect();
+        assert_eq!(
+            texts,
+            ["Title", "First para graph.", "One", "Two", "Quoted"]
+        );
+    }
+
+    #[test]
+    fn rate_sets_words_per_minute() {
+        let (program, args) = Synthesizer::Espeak("espeak-ng").command_line(1.5);
+        assert_eq!(program, "espeak-ng");
+        assert_eq!(args, ["-s", "263", "--stdin"]);
+        let (program, args) = Synthesizer::Say.command_line(1.0);
+        assert_eq!(program, "say");
+        assert_eq!(args, ["-r", "175"]);
+    }
+
+    #[test]
+    fn skipping_while_paused_only_moves() {
+        let blocks = vec!["a".into(), "b".into(), "c".into()];
+        let mut player = ReadAloud::new(Synthesizer::Say, blocks, 1.0);
+        assert_eq!(player.state(), Playback::Paused);
+        player.skip(5);
+        assert_eq!(player.current(), Some(2));
+        player.skip(-1);
+        assert_eq!(player.current(), Some(1));
+        player.skip(-4);
+        assert_eq!(player.current(), Some(0));
+        // Nothing is spoken until played
+        assert!(!player.poll());
+        assert!(ReadAloud::new(Synthesizer::Say, Vec::new(), 1.0)
+            .current()
+            .is_none());
+    }
+}
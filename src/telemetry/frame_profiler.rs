@@ -0,0 +1,205 @@
+//! Per-frame time profile of the browser window.
+//!
+//! Each frame the app reports how long it spent in the stages that can make
+//! a render mode slow — polling fetches and images, extending the layout,
+//! building the 2-D paint list or the 3-D scene, raymarching, moving OZ
+//! particles — with [`FrameProfiler::add`], then closes the frame with its
+//! total time. Whatever the stages do not account for is building and
+//! painting the egui UI. The last [`HISTORY_FRAMES`] frames are kept for
+//! the rolling flame-bar overlay, so a regression in one mode shows up as
+//! a taller band of one colour.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Frames kept.
+pub const HISTORY_FRAMES: usize = 240;
+
+/// Where part of a frame went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameStage {
+    /// Polling page fetches, image downloads and other background work
+    FetchPoll,
+    /// Laying out more of a long page
+    Layout,
+    /// Building the SDF 2-D paint list or the 3-D scene
+    SceneBuild,
+    /// Rendering the 3-D view (GPU pass or CPU raymarch)
+    Raymarch,
+    /// Moving OZ mode's particles
+    Particles,
+    /// Building and painting the egui UI: the rest of the frame
+    Paint,
+}
+
+impl FrameStage {
+    pub const ALL: [Self; 6] = [
+        Self::FetchPoll,
+        Self::Layout,
+        Self::SceneBuild,
+        Self::Raymarch,
+        Self::Particles,
+        Self::Paint,
+    ];
+
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::FetchPoll => "fetch poll",
+            Self::Layout => "layout",
+            Self::SceneBuild => "scene build",
+            Self::Raymarch => "raymarch",
+            Self::Particles => "particles",
+            Self::Paint => "UI paint",
+        }
+    }
+
+    /// Colour of the stage's band (RGB).
+    #[must_use]
+    pub const fn rgb(self) -> [u8; 3] {
+        match self {
+            Self::FetchPoll => [90, 160, 230],
+            Self::Layout => [240, 170, 50],
+            Self::SceneBuild => [170, 100, 220],
+            Self::Raymarch => [230, 80, 70],
+            Self::Particles => [60, 190, 150],
+            Self::Paint => [150, 150, 160],
+        }
+    }
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Time spent in each stage of one frame.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FrameSample {
+    /// Milliseconds, indexed in [`FrameStage::ALL`] order
+    pub stage_ms: [f32; 6],
+}
+
+impl FrameSample {
+    /// Milliseconds spent in `stage`.
+    #[must_use]
+    pub const fn ms(&self, stage: FrameStage) -> f32 {
+        self.stage_ms[stage.index()]
+    }
+
+    /// The whole frame.
+    #[must_use]
+    pub fn total_ms(&self) -> f32 {
+        self.stage_ms.iter().sum()
+    }
+}
+
+/// Stage times of the frame in progress and of the last frames.
+#[derive(Debug, Clone, Default)]
+pub struct FrameProfiler {
+    current: FrameSample,
+    /// Oldest first
+    history: VecDeque<FrameSample>,
+}
+
+impl FrameProfiler {
+    /// Count `elapsed` against `stage` in the frame in progress.
+    pub fn add(&mut self, stage: FrameStage, elapsed: Duration) {
+        self.current.stage_ms[stage.index()] += elapsed.as_secs_f32() * 1000.0;
+    }
+
+    /// Close the frame in progress, which took `total`; the time no stage
+    /// accounts for is counted as [`FrameStage::Paint`].
+    pub fn end_frame(&mut self, total: Duration) {
+        let mut sample = std::mem::take(&mut self.current);
+        let rest = total.as_secs_f32() * 1000.0 - sample.total_ms();
+        sample.stage_ms[FrameStage::Paint.index()] += rest.max(0.0);
+        if self.history.len() == HISTORY_FRAMES {
+            self.history.pop_front();
+        }
+        self.history.push_back(sample);
+    }
+
+    /// The kept frames, oldest first.
+    pub fn samples(&self) -> impl ExactSizeIterator<Item = &FrameSample> {
+        self.history.iter()
+    }
+
+    /// The last finished frame.
+    #[must_use]
+    pub fn last(&self) -> Option<&FrameSample> {
+        self.history.back()
+    }
+
+    /// Mean time of each stage over the kept frames.
+    #[must_use]
+    pub fn average(&self) -> FrameSample {
+        let mut mean = FrameSample::default();
+        if self.history.is_empty() {
+            return mean;
+        }
+        for sample in &self.history {
+            for (m, ms) in mean.stage_ms.iter_mut().zip(sample.stage_ms) {
+                *m += ms;
+            }
+        }
+        let n = self.history.len() as f32;
+        for m in &mut mean.stage_ms {
+            *m /= n;
+        }
+        mean
+    }
+
+    /// Longest kept frame, in milliseconds.
+    #[must_use]
+    pub fn worst_ms(&self) -> f32 {
+        self.history
+            .iter()
+            .map(FrameSample::total_ms)
+            .fold(0.0, f32::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn unaccounted_time_is_paint() {
+        let mut profiler = FrameProfiler::default();
+        profiler.add(FrameStage::Layout, ms(3));
+        profiler.add(FrameStage::Raymarch, ms(5));
+        profiler.add(FrameStage::Layout, ms(1));
+        profiler.end_frame(ms(12));
+
+        let frame = profiler.last().unwrap();
+        assert!((frame.ms(FrameStage::Layout) - 4.0).abs() < 0.01);
+        assert!((frame.ms(FrameStage::Raymarch) - 5.0).abs() < 0.01);
+        assert!((frame.ms(FrameStage::Paint) - 3.0).abs() < 0.01);
+        assert!((frame.total_ms() - 12.0).abs() < 0.01);
+
+        // The next frame starts empty
+        profiler.end_frame(ms(2));
+        let frame = profiler.last().unwrap();
+        assert_eq!(frame.ms(FrameStage::Layout), 0.0);
+        assert!((frame.ms(FrameStage::Paint) - 2.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn keeps_a_rolling_window() {
+        let mut profiler = FrameProfiler::default();
+        for i in 0..HISTORY_FRAMES + 10 {
+            profiler.add(FrameStage::Particles, ms(if i % 2 == 0 { 2 } else { 4 }));
+            profiler.end_frame(ms(10));
+        }
+        assert_eq!(profiler.samples().len(), HISTORY_FRAMES);
+        let mean = profiler.average();
+        assert!((mean.ms(FrameStage::Particles) - 3.0).abs() < 0.01);
+        assert!((mean.total_ms() - 10.0).abs() < 0.01);
+        assert!((profiler.worst_ms() - 10.0).abs() < 0.01);
+        assert_eq!(FrameProfiler::default().average(), FrameSample::default());
+    }
+}
@@ -3,12 +3,24 @@
 //! Tracks browsing performance using probabilistic data structures:
 //! - **DDSketch**: Page load latency quantiles (P50, P99)
 //! - **HyperLogLog**: Unique domains visited
+//! - **DDSketch**: Per-stage pipeline times (parse, filter, layout, scene)
 //! - **Counters**: Pages loaded, ads/trackers blocked
 //!
 //! All structures have fixed memory footprint with mathematical error guarantees.
 
 use alice_analytics::prelude::*;
 
+use crate::engine::profiler::StageTimings;
+
+/// Pipeline stages tracked, with their metric names.
+const STAGES: [(&str, &str); 5] = [
+    ("Fetch", "stage_fetch"),
+    ("Parse", "stage_parse"),
+    ("Filter", "stage_filter"),
+    ("Layout", "stage_layout"),
+    ("Scene", "stage_scene"),
+];
+
 fn h(name: &str) -> u64 {
     FnvHasher::hash_bytes(name.as_bytes())
 }
@@ -21,6 +33,8 @@ pub struct MetricsSnapshot {
     pub unique_domains: f64,
     pub total_blocked: u64,
     pub total_dom_nodes: u64,
+    /// Median time of each pipeline stage, by stage name
+    pub stage_p50_ms: Vec<(&'static str, f64)>,
 }
 
 /// Probabilistic browser telemetry using ALICE-Analytics.
@@ -68,6 +82,23 @@ impl BrowserMetrics {
         self.pipeline.flush();
     }
 
+    /// Record how long each stage of a page load took.
+    pub fn record_stage_timings(&mut self, timings: &StageTimings) {
+        let times = [
+            timings.fetch_ms,
+            timings.parse_ms,
+            timings.filter_ms,
+            timings.layout_ms,
+            timings.scene_ms,
+        ];
+        // Stages that didn't run (no fetch for handed-in HTML) are skipped
+        for ((_, metric), ms) in STAGES.iter().zip(times).filter(|(_, ms)| *ms > 0.0) {
+            self.pipeline
+                .submit(MetricEvent::histogram(h(metric), f64::from(ms)));
+        }
+        self.pipeline.flush();
+    }
+
     /// Get a snapshot of current metrics for display.
     pub fn snapshot(&self) -> MetricsSnapshot {
         let page_loads = self
@@ -100,6 +131,16 @@ impl BrowserMetrics {
             .map(|s| s.ddsketch.count() as u64)
             .unwrap_or(0);
 
+        let stage_p50_ms = STAGES
+            .iter()
+            .filter_map(|(name, metric)| {
+                self.pipeline
+                    .get_slot(h(metric))
+                    .filter(|s| s.ddsketch.count() > 0)
+                    .map(|s| (*name, s.ddsketch.quantile(0.50)))
+            })
+            .collect();
+
         MetricsSnapshot {
             page_loads,
             p50_load_ms: p50,
@@ -107,6 +148,7 @@ impl BrowserMetrics {
             unique_domains,
             total_blocked,
             total_dom_nodes,
+            stage_p50_ms,
         }
     }
 }
@@ -131,5 +173,17 @@ mod tests {
         assert!(snap.unique_domains >= 1.0); // at least 1 domain
         assert_eq!(snap.total_blocked, 40);
         assert_eq!(snap.total_dom_nodes, 2); // 2 dom_stats recorded
+        assert!(snap.stage_p50_ms.is_empty());
+
+        metrics.record_stage_timings(&StageTimings {
+            parse_ms: 4.0,
+            filter_ms: 2.0,
+            layout_ms: 8.0,
+            ..StageTimings::default()
+        });
+        let snap = metrics.snapshot();
+        let names: Vec<&str> = snap.stage_p50_ms.iter().map(|(n, _)| *n).collect();
+        assert_eq!(names, ["Parse", "Filter", "Layout"]);
+        assert!(snap.stage_p50_ms[2].1 > snap.stage_p50_ms[1].1);
     }
 }
@@ -0,0 +1,14 @@
+//! Browser telemetry.
+//!
+//! - [`frame_profiler`]: where each frame's time goes (fetch polling, layout,
+//!   scene building, raymarching, particles, painting), always available
+//! - `BrowserMetrics`: page-load statistics in fixed memory through
+//!   ALICE-Analytics (feature `telemetry`)
+
+pub mod frame_profiler;
+
+#[cfg(feature = "telemetry")]
+mod metrics;
+
+#[cfg(feature = "telemetry")]
+pub use metrics::{BrowserMetrics, MetricsSnapshot};
@@ -0,0 +1,147 @@
+//! Copy context menus for rendered page content.
+//!
+//! The menus live deep inside the layout renderers, which can't reach the
+//! app, so they park a [`CopyRequest`] in egui's temporary memory. The app
+//! takes it once the frame's content is drawn and hands it to the system
+//! clipboard (HTML and images need more than egui's plain-text copy).
+//! Tables also offer CSV and JSON: copied directly, or parked the same way
+//! as an [`ExportRequest`] for the app to write to a file.
+
+use alice_browser::render::clipboard::{self, ClipboardContent};
+use alice_browser::render::layout::LayoutNode;
+use alice_browser::render::table_data::TableData;
+use eframe::egui;
+
+/// Something the user asked to copy.
+#[derive(Debug, Clone)]
+pub enum CopyRequest {
+    /// HTML with a plain-text alternative
+    Rich(ClipboardContent),
+    /// Decoded pixels of the image at this URL
+    Image(String),
+}
+
+fn request_id() -> egui::Id {
+    egui::Id::new("alice_copy_request")
+}
+
+/// Queue `request` for the app to carry out after this frame.
+pub fn request(ctx: &egui::Context, request: CopyRequest) {
+    ctx.data_mut(|d| d.insert_temp(request_id(), request));
+}
+
+/// Take the queued request, if any.
+pub fn take(ctx: &egui::Context) -> Option<CopyRequest> {
+    ctx.data_mut(|d| {
+        let request = d.get_temp(request_id());
+        d.remove::<CopyRequest>(request_id());
+        request
+    })
+}
+
+/// "Copy" (HTML + text) and "Copy as plain text" for a block of content.
+pub fn block_menu(response: &egui::Response, node: &LayoutNode) {
+    response.context_menu(|ui| block_items(ui, node));
+}
+
+/// The entries of [`block_menu`], for menus that add their own.
+pub fn block_items(ui: &mut egui::Ui, node: &LayoutNode) {
+    if ui.button("Copy").clicked() {
+        request(
+            ui.ctx(),
+            CopyRequest::Rich(ClipboardContent::from_layout(node)),
+        );
+        ui.close_menu();
+    }
+    if ui.button("Copy as plain text").clicked() {
+        ui.ctx().copy_text(clipboard::to_text(node));
+        ui.close_menu();
+    }
+}
+
+/// File format of a table export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    Csv,
+    Json,
+}
+
+impl TableFormat {
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+
+    /// `table` in this format.
+    #[must_use]
+    pub fn render(self, table: &TableData) -> String {
+        match self {
+            Self::Csv => table.to_csv(),
+            Self::Json => table.to_json(),
+        }
+    }
+}
+
+/// A table the user asked to save.
+#[derive(Debug, Clone)]
+pub struct ExportRequest {
+    pub table: TableData,
+    pub format: TableFormat,
+}
+
+fn export_id() -> egui::Id {
+    egui::Id::new("alice_table_export")
+}
+
+/// Take the queued table export, if any.
+pub fn take_export(ctx: &egui::Context) -> Option<ExportRequest> {
+    ctx.data_mut(|d| {
+        let request = d.get_temp(export_id());
+        d.remove::<ExportRequest>(export_id());
+        request
+    })
+}
+
+/// The copy items of [`block_menu`], then copying or exporting the table
+/// at `node` as CSV or JSON.
+pub fn table_menu(response: &egui::Response, node: &LayoutNode) {
+    response.context_menu(|ui| {
+        if ui.button("Copy").clicked() {
+            request(
+                ui.ctx(),
+                CopyRequest::Rich(ClipboardContent::from_layout(node)),
+            );
+            ui.close_menu();
+        }
+        if ui.button("Copy as plain text").clicked() {
+            ui.ctx().copy_text(clipboard::to_text(node));
+            ui.close_menu();
+        }
+        let Some(table) = TableData::from_layout(node) else {
+            return;
+        };
+        ui.separator();
+        for (format, name) in [(TableFormat::Csv, "CSV"), (TableFormat::Json, "JSON")] {
+            if ui.button(format!("Copy table as {name}")).clicked() {
+                ui.ctx().copy_text(format.render(&table));
+                ui.close_menu();
+            }
+        }
+        for (format, name) in [(TableFormat::Csv, "CSV"), (TableFormat::Json, "JSON")] {
+            if ui
+                .button(format!("Export table as {name}\u{2026}"))
+                .clicked()
+            {
+                let request = ExportRequest {
+                    table: table.clone(),
+                    format,
+                };
+                ui.ctx().data_mut(|d| d.insert_temp(export_id(), request));
+                ui.close_menu();
+            }
+        }
+    });
+}
@@ -0,0 +1,65 @@
+//! Classifier corrections from the flat view's context menus.
+//!
+//! While the page inspector shows removed elements, blocks offer to mark
+//! the element around them as content or as an ad. Like the copy menus in
+//! [`super::clipboard`], the choice is parked in egui's temporary memory
+//! until the app takes it after the frame's content is drawn.
+
+use alice_browser::dom::filter::{is_removed_class, Verdict};
+use alice_browser::render::layout::LayoutNode;
+use eframe::egui;
+
+/// Text colour of elements the filter removes, while they are shown.
+pub const REMOVED_TEXT: egui::Color32 = egui::Color32::from_rgb(150, 150, 150);
+
+/// A correction chosen from a context menu.
+#[derive(Debug, Clone)]
+pub struct Mark {
+    /// [`alice_browser::dom::filter::selector`] of the element
+    pub selector: String,
+    pub verdict: Verdict,
+}
+
+fn request_id() -> egui::Id {
+    egui::Id::new("alice_feedback_request")
+}
+
+/// Take the correction chosen this frame, if any.
+pub fn take(ctx: &egui::Context) -> Option<Mark> {
+    ctx.data_mut(|d| {
+        let mark = d.get_temp(request_id());
+        d.remove::<Mark>(request_id());
+        mark
+    })
+}
+
+/// "This is content" / "This is an ad" for `target`, the element with a
+/// selector around the block the menu belongs to.
+pub fn mark_items(ui: &mut egui::Ui, target: &LayoutNode) {
+    let Some(ref selector) = target.selector else {
+        return;
+    };
+    let removed = is_removed_class(target.classification);
+    ui.label(egui::RichText::new(selector).monospace().weak());
+    let verdict = if ui
+        .add_enabled(removed, egui::Button::new("This is content"))
+        .clicked()
+    {
+        Some(Verdict::Content)
+    } else if ui
+        .add_enabled(!removed, egui::Button::new("This is an ad"))
+        .clicked()
+    {
+        Some(Verdict::Ad)
+    } else {
+        None
+    };
+    if let Some(verdict) = verdict {
+        let mark = Mark {
+            selector: selector.clone(),
+            verdict,
+        };
+        ui.ctx().data_mut(|d| d.insert_temp(request_id(), mark));
+        ui.close_menu();
+    }
+}
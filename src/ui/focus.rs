@@ -0,0 +1,33 @@
+//! "Focus in 3D" context menu for `article` / `section` blocks.
+//!
+//! Like the copy menus in [`super::clipboard`], it is raised deep inside the
+//! layout renderer, so the choice is parked in egui's temporary memory until
+//! the app takes it after the frame's content is drawn.
+
+use alice_browser::render::layout::LayoutNode;
+use eframe::egui;
+
+fn request_id() -> egui::Id {
+    egui::Id::new("alice_focus_request")
+}
+
+/// Take the box of the block chosen this frame, if any.
+pub fn take(ctx: &egui::Context) -> Option<[f32; 4]> {
+    ctx.data_mut(|d| {
+        let rect = d.get_temp(request_id());
+        d.remove::<[f32; 4]>(request_id());
+        rect
+    })
+}
+
+/// "Focus in 3D" for the block `node`.
+pub fn block_menu(response: &egui::Response, node: &LayoutNode) {
+    response.context_menu(|ui| {
+        if ui.button("Focus in 3D").clicked() {
+            let b = &node.bounds;
+            let rect = [b.x, b.y, b.width, b.height];
+            ui.ctx().data_mut(|d| d.insert_temp(request_id(), rect));
+            ui.close_menu();
+        }
+    });
+}
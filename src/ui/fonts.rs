@@ -0,0 +1,107 @@
+//! egui font setup.
+//!
+//! Installs one face per script — the head of each
+//! [`FallbackChain`](alice_browser::render::fonts::FallbackChain) — into
+//! egui's proportional and monospace families, so pages in Japanese,
+//! Chinese and Korean render on every platform. Glyphs are looked up Latin
+//! → CJK → emoji: the preferred Latin family (else egui's built-in font) and
+//! a system Latin face, the preferred CJK script's face and the others, then
+//! a system emoji face ahead of egui's own emoji fonts.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use alice_browser::render::fonts::{FontCatalog, FontFace, FontPreferences, FontSource, Script};
+use eframe::egui;
+
+/// Load `face`, sharing one copy of each file between the faces of a
+/// collection (Noto Sans CJK ships every region in one `.ttc`).
+///
+/// Fonts stay installed for the whole session, so file data is leaked
+/// rather than reference-counted.
+fn face_data(
+    face: &FontFace,
+    files: &mut HashMap<PathBuf, &'static [u8]>,
+) -> Option<&'static [u8]> {
+    match face.source {
+        FontSource::Bundled(data) => Some(data),
+        FontSource::File(ref path) => {
+            if let Some(data) = files.get(path) {
+                return Some(data);
+            }
+            let data = match face.load() {
+                Ok(Cow::Owned(bytes)) => &*Box::leak(bytes.into_boxed_slice()),
+                Ok(Cow::Borrowed(bytes)) => bytes,
+                Err(e) => {
+                    log::warn!("Cannot read font {}: {e}", path.display());
+                    return None;
+                }
+            };
+            files.insert(path.clone(), data);
+            Some(data)
+        }
+    }
+}
+
+/// Install the fonts `prefs` choose, with their fallbacks, into `ctx`.
+pub fn install(ctx: &egui::Context, prefs: &FontPreferences) {
+    let catalog = FontCatalog::discover();
+    let mut fonts = egui::FontDefinitions::default();
+    let mut files = HashMap::new();
+    let mut installed: Vec<String> = Vec::new();
+
+    // egui's emoji and icon fonts move to the end of each family, after
+    // the system faces
+    let mut builtin_emoji = Vec::new();
+    for (family, names) in &mut fonts.families {
+        let (emoji, text): (Vec<String>, Vec<String>) = names
+            .drain(..)
+            .partition(|name| name.to_lowercase().contains("emoji"));
+        *names = text;
+        builtin_emoji.push((family.clone(), emoji));
+    }
+
+    for chain in catalog.chains_with(prefs) {
+        // First face of the chain that actually loads
+        let Some((face, data)) = chain
+            .faces
+            .iter()
+            .find_map(|face| face_data(face, &mut files).map(|data| (face, data)))
+        else {
+            if chain.script.is_cjk() {
+                log::warn!("No font found for {:?} text", chain.script);
+            }
+            continue;
+        };
+        let key = face.key();
+        if installed.contains(&key) {
+            continue;
+        }
+        log::info!("{:?} text uses {} ({key})", chain.script, face.family);
+
+        let name = format!("system-{}", chain.script.key());
+        let mut font = egui::FontData::from_static(data);
+        font.index = face.index;
+        fonts.font_data.insert(name.clone(), Arc::new(font));
+        // The chosen family replaces the built-in font for page text;
+        // monospace text keeps its own
+        let chosen =
+            chain.script == Script::Latin && face.family.eq_ignore_ascii_case(prefs.family.trim());
+        for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+            let names = fonts.families.entry(family.clone()).or_default();
+            if chosen && family == egui::FontFamily::Proportional {
+                names.insert(0, name.clone());
+            } else {
+                names.push(name.clone());
+            }
+        }
+        installed.push(key);
+    }
+
+    for (family, emoji) in builtin_emoji {
+        fonts.families.entry(family).or_default().extend(emoji);
+    }
+    ctx.set_fonts(fonts);
+}
@@ -0,0 +1,359 @@
+//! Interactive form widgets for the 2-D view.
+//!
+//! `FormState` keeps what the user typed, ticked and selected between
+//! frames, keyed by each control's position in document order. Every frame
+//! `render_layout_node` re-registers the page's forms and controls; when a
+//! submit button (or Enter in a text field) fires, the controls of that form
+//! are collected into a [`FormSubmission`].
+//!
+//! With autofill profiles set, the first field of a form that asks for a
+//! name, email or address gets an **Autofill** button; choosing a profile
+//! fills every recognised field of that form on the next frame.
+//!
+//! The control with keyboard focus (by the same position as the Tab order's
+//! `FocusKind::Control`) is ringed; activating it with Enter presses a
+//! button, toggles a checkbox or radio button, and gives a text field or
+//! list the keyboard.
+
+use std::collections::HashMap;
+
+use alice_browser::dom::autofill::{classify_field, AutofillProfile};
+use alice_browser::dom::form::{ControlKind, FormControl, FormMethod, FormSubmission, InputKind};
+use eframe::egui;
+
+/// Current value of one control.
+#[derive(Debug, Clone)]
+enum ControlValue {
+    Text(String),
+    Checked(bool),
+    Selected(usize),
+}
+
+/// A control registered during the current frame.
+struct Entry {
+    form: Option<usize>,
+    control: usize,
+    name: String,
+    /// Value submitted with the form, if the control is "successful"
+    value: Option<String>,
+}
+
+/// Form values for the current page.
+#[derive(Default)]
+pub struct FormState {
+    values: HashMap<usize, ControlValue>,
+    /// Checked radio button per `(form, name)` group
+    radios: HashMap<(Option<usize>, String), usize>,
+    // Rebuilt every frame
+    forms: Vec<(String, FormMethod)>,
+    current_form: Option<usize>,
+    next_control: usize,
+    entries: Vec<Entry>,
+    /// `(form, submitter control)` activated this frame
+    submit: Option<(usize, Option<(String, String)>)>,
+    reset: Option<usize>,
+    /// Profiles offered by the Autofill buttons
+    profiles: Vec<AutofillProfile>,
+    /// Forms (`None`: controls outside any) offered autofill this frame
+    offered: Vec<Option<usize>>,
+    /// Form and profile chosen this frame, filled in the next
+    fill_request: Option<(Option<usize>, AutofillProfile)>,
+    /// Form being filled this frame
+    filling: Option<(Option<usize>, AutofillProfile)>,
+    /// Control with keyboard focus; set by the app every frame
+    pub focused: Option<usize>,
+    /// Scroll the focused control into view this frame
+    pub scroll_to_focused: bool,
+    /// Link hint codes shown on controls, by control index; set by the app
+    /// every frame
+    pub hints: Vec<(usize, String)>,
+    /// Letters of the hint codes already typed
+    pub hint_typed: usize,
+    /// Control activated from the keyboard, handled when next shown
+    activate: Option<usize>,
+}
+
+impl FormState {
+    /// Start registering controls for a new frame.
+    pub fn begin_frame(&mut self) {
+        self.forms.clear();
+        self.current_form = None;
+        self.next_control = 0;
+        self.entries.clear();
+        self.submit = None;
+        self.reset = None;
+        self.offered.clear();
+    }
+
+    /// Activate control `idx` as if clicked (Enter on the focus ring).
+    pub fn activate(&mut self, idx: usize) {
+        self.activate = Some(idx);
+    }
+
+    /// Set the profiles the Autofill buttons offer.
+    pub fn set_profiles(&mut self, profiles: &[AutofillProfile]) {
+        if self.profiles != profiles {
+            self.profiles = profiles.to_vec();
+        }
+    }
+
+    /// Enter a `<form>`; returns the enclosing form to restore afterwards.
+    pub fn enter_form(&mut self, control: &FormControl) -> Option<usize> {
+        let ControlKind::Form { ref action, method } = control.kind else {
+            return self.current_form;
+        };
+        self.forms.push((action.clone(), method));
+        self.current_form.replace(self.forms.len() - 1)
+    }
+
+    pub fn leave_form(&mut self, outer: Option<usize>) {
+        self.current_form = outer;
+    }
+
+    /// Finish the frame: apply a pending reset and build the pending
+    /// submission, if any.
+    pub fn end_frame(&mut self, base_url: &str) -> Option<FormSubmission> {
+        self.filling = self.fill_request.take();
+        if let Some(form) = self.reset.take() {
+            for e in self.entries.iter().filter(|e| e.form == Some(form)) {
+                self.values.remove(&e.control);
+            }
+            self.radios.retain(|(f, _), _| *f != Some(form));
+        }
+
+        let (form, submitter) = self.submit.take()?;
+        let (action, method) = self.forms.get(form)?.clone();
+        let mut pairs: Vec<(String, String)> = self
+            .entries
+            .iter()
+            .filter(|e| e.form == Some(form) && !e.name.is_empty())
+            .filter_map(|e| e.value.clone().map(|v| (e.name.clone(), v)))
+            .collect();
+        pairs.extend(submitter.filter(|(name, _)| !name.is_empty()));
+        FormSubmission::new(base_url, &action, method, &pairs)
+    }
+
+    fn register(&mut self, control: &FormControl, value: Option<String>) {
+        self.entries.push(Entry {
+            form: self.current_form,
+            control: self.next_control,
+            name: control.name.clone(),
+            value,
+        });
+    }
+
+    /// Autofill button for `form`, shown next to its first recognised field.
+    fn offer_autofill(&mut self, ui: &mut egui::Ui, form: Option<usize>) {
+        if self.profiles.is_empty() || self.offered.contains(&form) {
+            return;
+        }
+        self.offered.push(form);
+        let mut chosen = None;
+        if let [profile] = self.profiles.as_slice() {
+            if ui
+                .small_button("Autofill")
+                .on_hover_text(format!("Fill this form as {}", profile.display_label()))
+                .clicked()
+            {
+                chosen = Some(profile.clone());
+            }
+        } else {
+            ui.menu_button("Autofill \u{25BE}", |ui| {
+                for profile in &self.profiles {
+                    if ui.button(profile.display_label()).clicked() {
+                        chosen = Some(profile.clone());
+                        ui.close_menu();
+                    }
+                }
+            });
+        }
+        if let Some(profile) = chosen {
+            self.fill_request = Some((form, profile));
+            ui.ctx().request_repaint();
+        }
+    }
+
+    /// Draw one control as an egui widget.
+    pub fn show_control(&mut self, ui: &mut egui::Ui, control: &FormControl) {
+        if matches!(control.kind, ControlKind::Form { .. }) {
+            return;
+        }
+        let idx = self.next_control;
+        let form = self.current_form;
+        let initial = || match control.kind {
+            ControlKind::Select { selected, .. } => ControlValue::Selected(selected),
+            ControlKind::Input(InputKind::Checkbox | InputKind::Radio) => {
+                ControlValue::Checked(control.checked)
+            }
+            _ => ControlValue::Text(control.value.clone()),
+        };
+        if let std::collections::hash_map::Entry::Vacant(slot) = self.values.entry(idx) {
+            slot.insert(initial());
+            if control.kind == ControlKind::Input(InputKind::Radio) && control.checked {
+                self.radios.insert((form, control.name.clone()), idx);
+            }
+        }
+
+        let field = classify_field(control);
+        let fill = self
+            .filling
+            .as_ref()
+            .filter(|(fill_form, _)| *fill_form == form)
+            .zip(field)
+            .and_then(|((_, profile), kind)| profile.value(kind));
+        if let Some(value) = fill {
+            match (self.values.get_mut(&idx), &control.kind) {
+                (Some(ControlValue::Text(text)), _) => *text = value,
+                (Some(ControlValue::Selected(sel)), ControlKind::Select { options, .. }) => {
+                    if let Some(i) = options.iter().position(|o| {
+                        o.value.eq_ignore_ascii_case(&value) || o.label.eq_ignore_ascii_case(&value)
+                    }) {
+                        *sel = i;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let activate = self.activate == Some(idx);
+        if activate {
+            self.activate = None;
+        }
+        let mut shown: Option<egui::Response> = None;
+        match control.kind {
+            ControlKind::Form { .. } => {}
+            ControlKind::Input(InputKind::Hidden) => {
+                self.register(control, Some(control.value.clone()));
+            }
+            ControlKind::Input(InputKind::Checkbox) => {
+                if let Some(ControlValue::Checked(checked)) = self.values.get_mut(&idx) {
+                    if activate {
+                        *checked = !*checked;
+                    }
+                    shown = Some(ui.checkbox(checked, ""));
+                    let value = checked.then(|| control.value.clone());
+                    self.register(control, value);
+                }
+            }
+            ControlKind::Input(InputKind::Radio) => {
+                let key = (form, control.name.clone());
+                let selected = self.radios.get(&key) == Some(&idx);
+                let response = ui.radio(selected, "");
+                if response.clicked() || activate {
+                    self.radios.insert(key, idx);
+                }
+                shown = Some(response);
+                self.register(control, selected.then(|| control.value.clone()));
+            }
+            ControlKind::Input(InputKind::Submit) | ControlKind::Button { submit: true } => {
+                let response = ui.button(&control.label);
+                if response.clicked() || activate {
+                    if let Some(f) = form {
+                        self.submit =
+                            Some((f, Some((control.name.clone(), control.value.clone()))));
+                    }
+                }
+                shown = Some(response);
+                self.register(control, None);
+            }
+            ControlKind::Input(InputKind::Reset) => {
+                let response = ui.button(&control.label);
+                if response.clicked() || activate {
+                    self.reset = form;
+                }
+                shown = Some(response);
+                self.register(control, None);
+            }
+            ControlKind::Button { submit: false } => {
+                // Script-driven buttons do nothing without JS
+                ui.add_enabled(false, egui::Button::new(&control.label));
+                self.register(control, None);
+            }
+            ControlKind::Select {
+                ref options,
+                selected: _,
+            } => {
+                if let Some(ControlValue::Selected(sel)) = self.values.get_mut(&idx) {
+                    let current = options.get(*sel).map_or("", |o| o.label.as_str());
+                    let response = egui::ComboBox::from_id_salt(("form_select", idx))
+                        .selected_text(current)
+                        .show_ui(ui, |ui| {
+                            for (i, o) in options.iter().enumerate() {
+                                ui.selectable_value(sel, i, &o.label);
+                            }
+                        })
+                        .response;
+                    let value = options.get(*sel).map(|o| o.value.clone());
+                    self.register(control, value);
+                    shown = Some(response);
+                }
+                if field.is_some() {
+                    self.offer_autofill(ui, form);
+                }
+            }
+            ControlKind::TextArea { rows } => {
+                if let Some(ControlValue::Text(text)) = self.values.get_mut(&idx) {
+                    shown = Some(
+                        ui.add(
+                            egui::TextEdit::multiline(text)
+                                .desired_rows(rows)
+                                .hint_text(&control.placeholder),
+                        ),
+                    );
+                    let value = Some(text.clone());
+                    self.register(control, value);
+                }
+                if field.is_some() {
+                    self.offer_autofill(ui, form);
+                }
+            }
+            ControlKind::Input(kind) => {
+                if let Some(ControlValue::Text(text)) = self.values.get_mut(&idx) {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(text)
+                            .password(kind == InputKind::Password)
+                            .hint_text(&control.placeholder)
+                            .desired_width(240.0),
+                    );
+                    let value = Some(text.clone());
+                    // Enter in a text field submits its form
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        if let Some(f) = form {
+                            self.submit = Some((f, None));
+                        }
+                    }
+                    shown = Some(response);
+                    self.register(control, value);
+                }
+                if field.is_some() {
+                    self.offer_autofill(ui, form);
+                }
+            }
+        }
+        if let Some(ref response) = shown {
+            if let Some((_, code)) = self.hints.iter().find(|(i, _)| *i == idx) {
+                super::hint_badge(ui, response, code, self.hint_typed);
+            }
+        }
+        if let Some(response) = shown.filter(|_| self.focused == Some(idx)) {
+            super::focus_ring(ui, &response, self.scroll_to_focused);
+            // Text fields and lists take the keyboard; the rest acted above
+            let takes_keys = matches!(
+                control.kind,
+                ControlKind::Select { .. }
+                    | ControlKind::TextArea { .. }
+                    | ControlKind::Input(
+                        InputKind::Text
+                            | InputKind::Password
+                            | InputKind::Email
+                            | InputKind::Search
+                            | InputKind::Number
+                    )
+            );
+            if activate && takes_keys {
+                response.request_focus();
+            }
+        }
+        self.next_control += 1;
+    }
+}
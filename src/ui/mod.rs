@@ -1,12 +1,38 @@
 //! Generic egui UI helper functions shared across render modes.
 //!
-//! This module contains stateless functions that translate `LayoutNode` trees
-//! into egui widgets, plus small text-manipulation utilities used throughout
-//! the browser UI.
+//! This module contains functions that translate `LayoutNode` trees into
+//! egui widgets, plus small text-manipulation utilities used throughout the
+//! browser UI. Form widget state lives in [`forms`]; font setup in [`fonts`];
+//! the reader-mode renderer in [`reader`]; copy context menus in
+//! [`clipboard`] and the link, image and block menus in [`page_menu`];
+//! JSON, CSV and plain-text resources in [`text_view`].
+//! Blocks can be opened alone in the 3-D view through [`focus`], and
+//! classifier corrections are picked through [`feedback`].
+//! Find-in-page highlighting, the keyboard focus ring, the display of
+//! removed elements and the layout debug outlines are described by
+//! [`Find`].
 
-use alice_browser::render::layout::LayoutNode;
+pub mod clipboard;
+pub mod feedback;
+#[cfg(feature = "sdf-render")]
+pub mod focus;
+pub mod fonts;
+pub mod forms;
+pub mod page_menu;
+pub mod reader;
+pub mod text_view;
+
+use alice_browser::dom::filter::is_removed_class;
+use alice_browser::render::layout::{
+    default_font_size, LayoutNode, TableCell, TablePart, PLACEHOLDER_TAG,
+};
+use alice_browser::render::layout_debug::DebugBox;
+use alice_browser::render::media::{MediaInfo, MediaMetadata};
+use alice_browser::render::sdf_paint::{box_tooltip, draw_hint};
 use eframe::egui;
 
+use forms::FormState;
+
 // ─── Layout rendering ─────────────────────────────────────────────────────────
 
 /// Recursively render a `LayoutNode` tree using egui widgets.
@@ -16,51 +42,173 @@ pub fn render_layout_node(
     node: &LayoutNode,
     depth: usize,
     clicked_link: &mut Option<String>,
-    highlight: Option<&str>,
+    find: &Find<'_>,
+    forms: &mut FormState,
 ) {
-    // Skip invisible / empty nodes
-    if node.bounds.height <= 0.0 && node.text.is_empty() && node.children.is_empty() {
+    // Anchors are often empty (`<a name>`), so scroll before skipping
+    if find.anchor.is_some_and(|a| std::ptr::eq(a, node)) {
+        let top = egui::Rect::from_min_size(ui.cursor().min, egui::vec2(1.0, 1.0));
+        ui.scroll_to_rect(top, Some(egui::Align::TOP));
+    }
+
+    // The inspected node: drawn as usual, then outlined as a whole
+    if find.inspected.is_some_and(|n| std::ptr::eq(n, node)) {
+        let inner = Find {
+            inspected: None,
+            ..*find
+        };
+        let response = ui
+            .scope(|ui| render_layout_node(ui, node, depth, clicked_link, &inner, forms))
+            .response;
+        ui.painter().rect(
+            response.rect.expand(2.0),
+            egui::Rounding::same(2.0),
+            INSPECTED.gamma_multiply(0.15),
+            egui::Stroke::new(1.5, INSPECTED),
+        );
+        if find.scroll_to_inspected {
+            response.scroll_to_me(Some(egui::Align::Center));
+        }
         return;
     }
 
+    // Skip invisible / empty nodes (hidden inputs still carry form data)
+    if node.bounds.height <= 0.0
+        && node.text.is_empty()
+        && node.children.is_empty()
+        && node.control.is_none()
+    {
+        return;
+    }
+
+    // Layout debug overlay: drawn as usual, then outlined
+    if find.layout_boxes && !find.outlined.is_some_and(|n| std::ptr::eq(n, node)) {
+        let inner = Find {
+            outlined: Some(node),
+            ..*find
+        };
+        let response = ui
+            .scope(|ui| render_layout_node(ui, node, depth, clicked_link, &inner, forms))
+            .response;
+        outline_box(ui, response.rect, node, depth);
+        return;
+    }
+
+    // Removed elements, kept for the inspector: greyed out, subtree and all
+    if find.feedback
+        && is_removed_class(node.classification)
+        && ui.visuals().override_text_color != Some(feedback::REMOVED_TEXT)
+    {
+        ui.scope(|ui| {
+            ui.visuals_mut().override_text_color = Some(feedback::REMOVED_TEXT);
+            render_layout_node(ui, node, depth, clicked_link, find, forms);
+        });
+        return;
+    }
+    // Blocking and corrections name the innermost element with a selector
+    let target;
+    let find = if node.selector.is_some() {
+        target = Find {
+            named: Some(node),
+            ..*find
+        };
+        &target
+    } else {
+        find
+    };
+
     match node.tag.as_str() {
+        "form" => {
+            let outer = node.control.as_ref().and_then(|c| forms.enter_form(c));
+            ui.group(|ui| {
+                for child in &node.children {
+                    render_layout_node(ui, child, depth + 1, clicked_link, find, forms);
+                }
+            });
+            forms.leave_form(outer);
+            return;
+        }
+        // Embedded documents, framed off from the page
+        "iframe" if node.is_block => {
+            ui.group(|ui| {
+                for child in &node.children {
+                    render_layout_node(ui, child, depth + 1, clicked_link, find, forms);
+                }
+            });
+            return;
+        }
+        "video" | "audio" if node.media.is_some() => {
+            if let Some(ref media) = node.media {
+                render_media_card(ui, media, clicked_link, find);
+            }
+            return;
+        }
+        "input" | "select" | "textarea" | "button" if node.control.is_some() => {
+            if let Some(ref control) = node.control {
+                forms.show_control(ui, control);
+            }
+            return;
+        }
+        "table" if node.table.is_some() => {
+            let response = ui
+                .scope(|ui| render_table(ui, node, depth, clicked_link, find, forms))
+                .response;
+            clipboard::table_menu(&response, node);
+            return;
+        }
+        // Blocks that can be opened alone in the 3-D view
+        #[cfg(feature = "sdf-render")]
+        "article" | "section" => {
+            let response = ui
+                .scope(|ui| {
+                    for child in &node.children {
+                        render_layout_node(ui, child, depth + 1, clicked_link, find, forms);
+                    }
+                })
+                .response;
+            focus::block_menu(&response, node);
+            return;
+        }
         "h1" => {
             let text = collect_display_text(node);
             if !text.is_empty() {
-                let rt = maybe_highlight(
-                    egui::RichText::new(&text).size(28.0).strong(),
-                    &text,
-                    highlight,
-                );
-                ui.heading(rt);
+                let rt = egui::RichText::new(&text).size(28.0 * zoom_of(node));
+                let rt = find.style(rt.strong(), &text, node);
+                let response = ui.heading(rt);
+                find.reveal(&response, node);
+                page_menu::block_menu(&response, node, find);
                 ui.add_space(8.0);
             }
         }
         "h2" => {
             let text = collect_display_text(node);
             if !text.is_empty() {
-                let rt = maybe_highlight(
-                    egui::RichText::new(&text).size(22.0).strong(),
-                    &text,
-                    highlight,
-                );
-                ui.heading(rt);
+                let rt = egui::RichText::new(&text).size(22.0 * zoom_of(node));
+                let rt = find.style(rt.strong(), &text, node);
+                let response = ui.heading(rt);
+                find.reveal(&response, node);
+                page_menu::block_menu(&response, node, find);
                 ui.add_space(6.0);
             }
         }
         "h3" | "h4" | "h5" | "h6" => {
             let text = collect_display_text(node);
             if !text.is_empty() {
-                let rt = maybe_highlight(egui::RichText::new(&text).size(18.0), &text, highlight);
-                ui.heading(rt);
+                let rt = egui::RichText::new(&text).size(18.0 * zoom_of(node));
+                let rt = find.style(rt, &text, node);
+                let response = ui.heading(rt);
+                find.reveal(&response, node);
+                page_menu::block_menu(&response, node, find);
                 ui.add_space(4.0);
             }
         }
         "p" => {
             let text = collect_display_text(node);
             if !text.is_empty() {
-                let rt = maybe_highlight(egui::RichText::new(&text), &text, highlight);
-                ui.label(rt);
+                let rt = find.style(egui::RichText::new(&text), &text, node);
+                let response = ui.label(rt);
+                find.reveal(&response, node);
+                page_menu::block_menu(&response, node, find);
                 ui.add_space(8.0);
             }
         }
@@ -68,25 +216,29 @@ pub fn render_layout_node(
             let text = collect_display_text(node);
             if !text.is_empty() {
                 if let Some(ref href) = node.href {
-                    let mut rt = egui::RichText::new(&text)
+                    let rt = egui::RichText::new(&text)
                         .color(egui::Color32::from_rgb(0, 100, 200))
                         .underline();
-                    if text_matches(&text, highlight) {
-                        rt = rt.background_color(egui::Color32::from_rgb(255, 255, 100));
-                    }
+                    let rt = find.style(rt, &text, node);
                     let link = ui.add(egui::Label::new(rt).sense(egui::Sense::click()));
+                    find.reveal(&link, node);
+                    find.ring(ui, &link, node);
                     if link.clicked() {
                         *clicked_link = Some(href.clone());
                     }
+                    if link.hovered() {
+                        report_hovered_link(ui.ctx(), href);
+                    }
+                    page_menu::menu(&link, node, find);
                     link.on_hover_cursor(egui::CursorIcon::PointingHand)
                         .on_hover_text(href);
                 } else {
-                    let rt = maybe_highlight(
+                    let rt = find.style(
                         egui::RichText::new(&text).color(egui::Color32::from_rgb(0, 100, 200)),
                         &text,
-                        highlight,
+                        node,
                     );
-                    ui.label(rt);
+                    find.reveal(&ui.label(rt), node);
                 }
             }
         }
@@ -95,8 +247,10 @@ pub fn render_layout_node(
             if !text.is_empty() {
                 ui.horizontal(|ui| {
                     ui.label("  \u{2022}");
-                    let rt = maybe_highlight(egui::RichText::new(&text), &text, highlight);
-                    ui.label(rt);
+                    let rt = find.style(egui::RichText::new(&text), &text, node);
+                    let response = ui.label(rt);
+                    find.reveal(&response, node);
+                    page_menu::block_menu(&response, node, find);
                 });
             }
         }
@@ -104,21 +258,41 @@ pub fn render_layout_node(
             ui.separator();
         }
         "img" => {
-            ui.colored_label(egui::Color32::GRAY, "[Image]");
+            let response = ui.colored_label(egui::Color32::GRAY, "[Image]");
+            if node.href.is_some() {
+                page_menu::menu(&response, node, find);
+            }
         }
         "br" => {
             ui.add_space(4.0);
         }
+        // Not laid out yet: keep its room so the scrollbar stays honest
+        PLACEHOLDER_TAG => {
+            ui.add_space(node.bounds.height);
+        }
         _ => {
             // Text-only nodes
-            if node.tag.is_empty() && !node.text.is_empty() {
+            if node.tag.is_empty() && node.lines.len() > 1 {
+                // Lines as the layout broke them, so CJK text and long
+                // URLs wrap by its rules rather than egui's
+                ui.vertical(|ui| {
+                    ui.spacing_mut().item_spacing.y = 0.0;
+                    for (i, (line, _)) in node.text_lines().enumerate() {
+                        let rt = find.style(egui::RichText::new(line), &node.text, node);
+                        let response = ui.label(rt);
+                        if i == 0 {
+                            find.reveal(&response, node);
+                        }
+                    }
+                });
+            } else if node.tag.is_empty() && !node.text.is_empty() {
                 let text = node.text.trim();
-                let rt = maybe_highlight(egui::RichText::new(text), text, highlight);
-                ui.label(rt);
+                let rt = find.style(egui::RichText::new(text), text, node);
+                find.reveal(&ui.label(rt), node);
             }
             // Recurse into children for container elements
             for child in &node.children {
-                render_layout_node(ui, child, depth + 1, clicked_link, highlight);
+                render_layout_node(ui, child, depth + 1, clicked_link, find, forms);
             }
             return;
         }
@@ -126,8 +300,114 @@ pub fn render_layout_node(
 
     // Render children for non-container leaf elements
     for child in &node.children {
-        render_layout_node(ui, child, depth + 1, clicked_link, highlight);
+        render_layout_node(ui, child, depth + 1, clicked_link, find, forms);
+    }
+}
+
+/// Placeholder card for a `<video>` or `<audio>`: its name, what is known
+/// of its type, duration and size, and a link that opens it externally.
+fn render_media_card(
+    ui: &mut egui::Ui,
+    media: &MediaInfo,
+    clicked_link: &mut Option<String>,
+    find: &Find<'_>,
+) {
+    let meta = media
+        .src
+        .as_ref()
+        .and_then(|src| find.media.and_then(|m| m.get(src)));
+    ui.group(|ui| {
+        ui.set_width(ui.available_width().min(640.0));
+        ui.strong(media.name());
+        ui.weak(media.describe(meta));
+        let Some(ref src) = media.src else {
+            return;
+        };
+        let link = ui.link(format!(
+            "\u{25b6} Open {} externally",
+            media.kind.label().to_lowercase()
+        ));
+        if link.clicked() {
+            *clicked_link = Some(src.clone());
+        }
+        if link.hovered() {
+            report_hovered_link(ui.ctx(), src);
+        }
+        link.on_hover_text(src);
+    });
+}
+
+/// Render a laid-out `<table>` as an `egui::Grid`.
+///
+/// Cells spanning several columns or rows are drawn in their first slot;
+/// the slots they cover get empty placeholders so the grid stays aligned.
+fn render_table(
+    ui: &mut egui::Ui,
+    node: &LayoutNode,
+    depth: usize,
+    clicked_link: &mut Option<String>,
+    find: &Find<'_>,
+    forms: &mut FormState,
+) {
+    let Some(TablePart::Grid { rows, cols, .. }) = node.table else {
+        return;
+    };
+    let cell_at = |r: usize, c: usize| {
+        node.children.iter().flat_map(|row| &row.children).find(|cell| {
+            matches!(cell.table, Some(TablePart::Cell(TableCell { row, col, .. })) if row == r && col == c)
+        })
+    };
+
+    for caption in node.children.iter().filter(|c| c.tag == "caption") {
+        let text = collect_display_text(caption);
+        let rt = find.style(egui::RichText::new(&text).italics(), &text, caption);
+        find.reveal(&ui.label(rt), caption);
     }
+    egui::Grid::new(("layout_table", node.bounds.y.to_bits()))
+        .striped(true)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            for r in 0..rows {
+                for c in 0..cols {
+                    match cell_at(r, c) {
+                        Some(cell) => {
+                            let header = matches!(cell.table, Some(TablePart::Cell(p)) if p.header);
+                            if header {
+                                let text = collect_display_text(cell);
+                                let rt =
+                                    find.style(egui::RichText::new(&text).strong(), &text, cell);
+                                find.reveal(&ui.label(rt), cell);
+                            } else {
+                                ui.vertical(|ui| {
+                                    for child in &cell.children {
+                                        render_layout_node(
+                                            ui,
+                                            child,
+                                            depth + 1,
+                                            clicked_link,
+                                            find,
+                                            forms,
+                                        );
+                                    }
+                                });
+                            }
+                        }
+                        // Covered by a colspan/rowspan cell, or a short row
+                        None => {
+                            ui.label("");
+                        }
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    ui.add_space(8.0);
+}
+
+/// Page zoom a node was laid out at. Headings have fixed sizes here; other
+/// text follows the zoomed text styles set up by the caller.
+fn zoom_of(node: &LayoutNode) -> f32 {
+    node.font_size / default_font_size(&node.tag)
 }
 
 // ─── Text utilities ───────────────────────────────────────────────────────────
@@ -150,10 +430,171 @@ pub fn text_matches(text: &str, highlight: Option<&str>) -> bool {
     }
 }
 
+// ─── Find in page ─────────────────────────────────────────────────────────────
+
+/// Background of blocks matching the search query.
+const MATCH_BG: egui::Color32 = egui::Color32::from_rgb(255, 255, 100);
+/// Background of the block holding the current match.
+const CURRENT_MATCH_BG: egui::Color32 = egui::Color32::from_rgb(255, 150, 50);
+
+/// Find-in-page highlighting for [`render_layout_node`], the link under
+/// the keyboard focus ring and the links' hint codes (form fields are
+/// ringed and hinted by [`FormState`]).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Find<'a> {
+    /// Query highlighted in every matching block
+    pub query: Option<&'a str>,
+    /// Text node holding the current match
+    pub current: Option<&'a LayoutNode>,
+    /// Scroll the current match into view this frame
+    pub scroll_to_current: bool,
+    /// Link with keyboard focus
+    pub focused: Option<&'a LayoutNode>,
+    /// Scroll the focused link into view this frame
+    pub scroll_to_focused: bool,
+    /// Link hint codes shown, by link node
+    pub hints: &'a [(&'a LayoutNode, String)],
+    /// Letters of the hint codes already typed
+    pub hint_typed: usize,
+    /// Show what the semantic filter removes, greyed out, and offer
+    /// corrections in the blocks' context menus
+    pub feedback: bool,
+    /// Innermost element with a selector around the node being drawn: the
+    /// one "Block this element" and corrections name
+    pub named: Option<&'a LayoutNode>,
+    /// Target of a `#fragment` link, scrolled to the top of the view this
+    /// frame
+    pub anchor: Option<&'a LayoutNode>,
+    /// Metadata probed for the page's audio and video, by source
+    pub media: Option<&'a std::collections::HashMap<String, MediaMetadata>>,
+    /// Node picked in the DOM tree inspector, outlined
+    pub inspected: Option<&'a LayoutNode>,
+    /// Scroll the inspected node into view this frame
+    pub scroll_to_inspected: bool,
+    /// Outline every node's widgets in its box category's colour (layout
+    /// debug overlay)
+    pub layout_boxes: bool,
+    /// Node whose outline is being drawn around it
+    pub outlined: Option<&'a LayoutNode>,
+}
+
+impl Find<'_> {
+    /// Whether `node`'s subtree holds the current match.
+    fn is_current(&self, node: &LayoutNode) -> bool {
+        self.current.is_some_and(|c| contains_node(node, c))
+    }
+
+    /// Highlight `rt` (the display text of `node`) as the current match or
+    /// as one of the others.
+    fn style(&self, rt: egui::RichText, text: &str, node: &LayoutNode) -> egui::RichText {
+        if !text_matches(text, self.query) {
+            rt
+        } else if self.is_current(node) {
+            rt.background_color(CURRENT_MATCH_BG)
+        } else {
+            rt.background_color(MATCH_BG)
+        }
+    }
+
+    /// Scroll the widget showing `node` into view if it holds the current
+    /// match and a jump was requested.
+    fn reveal(&self, response: &egui::Response, node: &LayoutNode) {
+        if self.scroll_to_current && self.is_current(node) {
+            response.scroll_to_me(Some(egui::Align::Center));
+        }
+    }
+
+    /// Ring the widget showing `node` if it has keyboard focus, and label
+    /// it with its hint code if hints are shown.
+    fn ring(&self, ui: &egui::Ui, response: &egui::Response, node: &LayoutNode) {
+        if self.focused.is_some_and(|f| std::ptr::eq(f, node)) {
+            focus_ring(ui, response, self.scroll_to_focused);
+        }
+        if let Some((_, code)) = self.hints.iter().find(|(n, _)| std::ptr::eq(*n, node)) {
+            hint_badge(ui, response, code, self.hint_typed);
+        }
+    }
+}
+
+/// Outline `rect`, the widgets of `node`, in its box category's colour.
+/// The innermost outline under the pointer is highlighted and shows the
+/// node's computed layout box.
+fn outline_box(ui: &egui::Ui, rect: egui::Rect, node: &LayoutNode, depth: usize) {
+    let debug = DebugBox::of(node, depth);
+    let [r, g, b] = debug.category.rgb();
+    let color = egui::Color32::from_rgb(r, g, b);
+    ui.painter()
+        .rect_stroke(rect, egui::Rounding::ZERO, egui::Stroke::new(1.0, color));
+
+    // Children are outlined first, so the first claim is the innermost
+    let claim = egui::Id::new("layout_box_hover");
+    let frame = ui.ctx().cumulative_pass_nr();
+    if !ui.rect_contains_pointer(rect) || ui.ctx().data(|d| d.get_temp::<u64>(claim)) == Some(frame)
+    {
+        return;
+    }
+    ui.ctx().data_mut(|d| d.insert_temp(claim, frame));
+    ui.painter()
+        .rect_filled(rect, egui::Rounding::ZERO, color.gamma_multiply(0.2));
+    egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), claim, |ui| {
+        box_tooltip(ui, &debug);
+    });
+}
+
+/// Outline of the node picked in the DOM tree inspector.
+const INSPECTED: egui::Color32 = egui::Color32::from_rgb(150, 60, 200);
+
+/// Outline of the widget with keyboard focus.
+const FOCUS_RING: egui::Color32 = egui::Color32::from_rgb(225, 110, 0);
+
+/// Draw the keyboard focus ring around `response`, scrolling it into view
+/// if asked.
+pub fn focus_ring(ui: &egui::Ui, response: &egui::Response, scroll: bool) {
+    ui.painter().rect_stroke(
+        response.rect.expand(3.0),
+        egui::Rounding::same(4.0),
+        egui::Stroke::new(2.0, FOCUS_RING),
+    );
+    if scroll {
+        response.scroll_to_me(Some(egui::Align::Center));
+    }
+}
+
+fn hovered_link_id() -> egui::Id {
+    egui::Id::new("alice_hovered_link")
+}
+
+/// Note `href` as the link under the pointer this frame.
+pub fn report_hovered_link(ctx: &egui::Context, href: &str) {
+    ctx.data_mut(|d| d.insert_temp(hovered_link_id(), href.to_string()));
+}
+
+/// Take the link reported under the pointer this frame, if any.
+pub fn take_hovered_link(ctx: &egui::Context) -> Option<String> {
+    ctx.data_mut(|d| {
+        let href = d.get_temp(hovered_link_id());
+        d.remove::<String>(hovered_link_id());
+        href
+    })
+}
+
+/// Label `response` with a link hint code, the first `typed` letters dimmed.
+pub fn hint_badge(ui: &egui::Ui, response: &egui::Response, code: &str, typed: usize) {
+    let painter = ui.painter().clone().with_layer_id(egui::LayerId::new(
+        egui::Order::Foreground,
+        ui.layer_id().id.with("hints"),
+    ));
+    draw_hint(&painter, response.rect.left_top(), code, typed);
+}
+
+fn contains_node(node: &LayoutNode, target: &LayoutNode) -> bool {
+    std::ptr::eq(node, target) || node.children.iter().any(|c| contains_node(c, target))
+}
+
 /// Apply a yellow highlight background to `rt` if it matches the search query.
 pub fn maybe_highlight(rt: egui::RichText, text: &str, highlight: Option<&str>) -> egui::RichText {
     if text_matches(text, highlight) {
-        rt.background_color(egui::Color32::from_rgb(255, 255, 100))
+        rt.background_color(MATCH_BG)
     } else {
         rt
     }
@@ -0,0 +1,65 @@
+//! The page context menu in the flat view.
+//!
+//! Links, images and blocks offer the entries of
+//! [`alice_browser::render::context_menu`] for their own `LayoutNode`.
+//! Like the copy menus in [`super::clipboard`], the entry chosen is parked
+//! in egui's temporary memory until the app takes it after the frame's
+//! content is drawn; the SDF view hands its choice over the same way.
+
+use alice_browser::render::context_menu::{self, MenuAction, MenuTarget};
+use alice_browser::render::layout::LayoutNode;
+use eframe::egui;
+
+use super::{clipboard, feedback, Find};
+
+fn request_id() -> egui::Id {
+    egui::Id::new("alice_page_menu_request")
+}
+
+/// Queue `action` for the app to carry out after this frame.
+pub fn request(ctx: &egui::Context, action: MenuAction) {
+    ctx.data_mut(|d| d.insert_temp(request_id(), action));
+}
+
+/// Take the entry chosen this frame, if any.
+pub fn take(ctx: &egui::Context) -> Option<MenuAction> {
+    ctx.data_mut(|d| {
+        let action = d.get_temp(request_id());
+        d.remove::<MenuAction>(request_id());
+        action
+    })
+}
+
+/// Context menu for a link or image widget drawn for `node`.
+pub fn menu(response: &egui::Response, node: &LayoutNode, find: &Find<'_>) {
+    let target = MenuTarget::for_node(node, find.named);
+    response.context_menu(|ui| {
+        if let Some(action) = context_menu::items(ui, &target) {
+            request(ui.ctx(), action);
+        }
+    });
+}
+
+/// Context menu for a block of text: the copy entries, then blocking the
+/// element around it — or, while removed elements are shown, classifier
+/// corrections instead.
+pub fn block_menu(response: &egui::Response, node: &LayoutNode, find: &Find<'_>) {
+    response.context_menu(|ui| {
+        clipboard::block_items(ui, node);
+        let Some(named) = find.named else {
+            return;
+        };
+        ui.separator();
+        if find.feedback {
+            feedback::mark_items(ui, named);
+            return;
+        }
+        let target = MenuTarget {
+            selector: named.selector.clone(),
+            ..MenuTarget::default()
+        };
+        if let Some(action) = context_menu::items(ui, &target) {
+            request(ui.ctx(), action);
+        }
+    });
+}
@@ -0,0 +1,150 @@
+//! Reader-mode rendering of an article's `LayoutNode` tree.
+//!
+//! Unlike [`render_layout_node`](super::render_layout_node), text is drawn
+//! at the font size layout assigned each node, so the reader's font-size
+//! setting carries through headings and body text alike, and links inside
+//! paragraphs stay clickable.
+
+use alice_browser::render::layout::LayoutNode;
+use eframe::egui;
+
+use super::{collect_display_text, maybe_highlight, text_matches};
+
+const LINK_COLOR: egui::Color32 = egui::Color32::from_rgb(0, 100, 200);
+
+/// Background of the block being read aloud.
+const SPOKEN_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(60, 110, 200, 40);
+
+/// The block being read aloud.
+#[derive(Clone, Copy)]
+pub struct Spoken<'a> {
+    pub block: &'a LayoutNode,
+    /// Scroll the block into view (it just became current)
+    pub scroll: bool,
+}
+
+/// Render one article node and its descendants, with the block in
+/// `spoken` marked.
+pub fn render_article(
+    ui: &mut egui::Ui,
+    node: &LayoutNode,
+    clicked_link: &mut Option<String>,
+    highlight: Option<&str>,
+    spoken: Option<Spoken<'_>>,
+) {
+    if let Some(spoken) = spoken.filter(|s| std::ptr::eq(s.block, node)) {
+        let response = egui::Frame::none()
+            .fill(SPOKEN_COLOR)
+            .rounding(3.0)
+            .show(ui, |ui| {
+                render_article(ui, node, clicked_link, highlight, None)
+            })
+            .response;
+        if spoken.scroll {
+            response.scroll_to_me(Some(egui::Align::Center));
+        }
+        return;
+    }
+    let size = node.font_size;
+    match node.tag.as_str() {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let text = collect_display_text(node);
+            if !text.is_empty() {
+                ui.add_space(size * 0.5);
+                let rt = egui::RichText::new(&text).size(size).strong();
+                super::clipboard::block_menu(
+                    &ui.label(maybe_highlight(rt, &text, highlight)),
+                    node,
+                );
+                ui.add_space(size * 0.25);
+            }
+        }
+        "p" | "blockquote" | "figcaption" | "dd" => {
+            let indent = if node.tag == "blockquote" { size } else { 0.0 };
+            let response = ui
+                .horizontal_wrapped(|ui| {
+                    ui.add_space(indent);
+                    ui.spacing_mut().item_spacing.x = 0.0;
+                    render_inline(ui, node, clicked_link, highlight);
+                })
+                .response;
+            super::clipboard::block_menu(&response, node);
+            ui.add_space(size * 0.8);
+        }
+        "li" => {
+            let response = ui
+                .horizontal_wrapped(|ui| {
+                    ui.label(egui::RichText::new("  \u{2022} ").size(size));
+                    ui.spacing_mut().item_spacing.x = 0.0;
+                    render_inline(ui, node, clicked_link, highlight);
+                })
+                .response;
+            super::clipboard::block_menu(&response, node);
+        }
+        "pre" => {
+            let text = collect_display_text(node);
+            ui.label(egui::RichText::new(text).size(size * 0.9).monospace());
+            ui.add_space(size * 0.8);
+        }
+        "hr" => {
+            ui.separator();
+        }
+        "img" | "script" | "style" => {}
+        _ => {
+            if node.tag.is_empty() && !node.text.trim().is_empty() {
+                let text = node.text.trim();
+                let rt = egui::RichText::new(text).size(size);
+                ui.label(maybe_highlight(rt, text, highlight));
+            }
+            for child in &node.children {
+                render_article(ui, child, clicked_link, highlight, spoken);
+            }
+        }
+    }
+}
+
+/// Lay out a block's inline content as one wrapped run.
+fn render_inline(
+    ui: &mut egui::Ui,
+    node: &LayoutNode,
+    clicked_link: &mut Option<String>,
+    highlight: Option<&str>,
+) {
+    for child in &node.children {
+        if let (true, Some(href)) = (child.tag == "a", child.href.as_ref()) {
+            let text = collect_display_text(child);
+            let mut rt = egui::RichText::new(format!("{text} "))
+                .size(child.font_size)
+                .color(LINK_COLOR)
+                .underline();
+            if text_matches(&text, highlight) {
+                rt = rt.background_color(egui::Color32::from_rgb(255, 255, 100));
+            }
+            let link = ui.add(egui::Label::new(rt).sense(egui::Sense::click()));
+            if link.clicked() {
+                *clicked_link = Some(href.clone());
+            }
+            link.on_hover_cursor(egui::CursorIcon::PointingHand)
+                .on_hover_text(href);
+        } else if child.tag.is_empty() {
+            let text = child.text.trim();
+            if !text.is_empty() {
+                let rt = egui::RichText::new(format!("{text} ")).size(child.font_size);
+                ui.label(maybe_highlight(rt, text, highlight));
+            }
+        } else if matches!(child.tag.as_str(), "strong" | "b" | "em" | "i" | "code") {
+            let text = collect_display_text(child);
+            if !text.is_empty() {
+                let mut rt = egui::RichText::new(format!("{text} ")).size(child.font_size);
+                rt = match child.tag.as_str() {
+                    "strong" | "b" => rt.strong(),
+                    "em" | "i" => rt.italics(),
+                    _ => rt.monospace(),
+                };
+                ui.label(maybe_highlight(rt, &text, highlight));
+            }
+        } else {
+            render_inline(ui, child, clicked_link, highlight);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Rendering of non-HTML text resources (the pipeline's [`TextView`]).
+//!
+//! JSON is drawn as a tree whose objects and arrays fold; CSV/TSV as a
+//! table under a fixed header row; XML and plain text as
+//! monospaced lines, wrapped or scrolled sideways. Tables and unwrapped
+//! text only lay out the rows on screen, so large files stay responsive.
+
+use alice_browser::engine::text_view::{TextContent, TextView};
+use eframe::egui;
+
+/// Containers deeper than this start folded.
+const OPEN_DEPTH: usize = 2;
+
+/// Widest a table column grows before its cells are cut off (points).
+const MAX_COLUMN_WIDTH: f32 = 320.0;
+
+/// Render `view`; `wrap` is the wrap-lines toggle for text.
+pub fn render_text_view(ui: &mut egui::Ui, view: &TextView, wrap: &mut bool) {
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new(view.kind.label()).strong());
+        if matches!(view.content, TextContent::Text(_)) {
+            ui.checkbox(wrap, "Wrap lines");
+        }
+    });
+    if let Some(ref error) = view.error {
+        ui.colored_label(ui.visuals().warn_fg_color, format!("Not valid: {error}"));
+    }
+    ui.separator();
+
+    match view.content {
+        TextContent::Json(ref value) => {
+            egui::ScrollArea::both()
+                .auto_shrink([false, false])
+                .show(ui, |ui| json_value(ui, None, value, "$", 0));
+        }
+        TextContent::Table(ref rows) => table(ui, rows),
+        TextContent::Text(ref text) => monospace_text(ui, text, *wrap),
+    }
+}
+
+// ── JSON ──
+
+fn json_value(
+    ui: &mut egui::Ui,
+    key: Option<&str>,
+    value: &serde_json::Value,
+    path: &str,
+    depth: usize,
+) {
+    use serde_json::Value;
+
+    let prefix = key.map_or_else(String::new, |k| format!("{k}: "));
+    let (children, open, close): (Vec<(String, &Value)>, _, _) = match value {
+        Value::Object(map) => (
+            map.iter().map(|(k, v)| (format!("\"{k}\""), v)).collect(),
+            '{',
+            '}',
+        ),
+        Value::Array(items) => (
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i.to_string(), v))
+                .collect(),
+            '[',
+            ']',
+        ),
+        _ => {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 0.0;
+                ui.monospace(prefix);
+                ui.label(json_scalar(ui, value));
+            });
+            return;
+        }
+    };
+    if children.is_empty() {
+        ui.monospace(format!("{prefix}{open}{close}"));
+        return;
+    }
+
+    let summary = format!("{prefix}{open} {} {close}", children.len());
+    egui::CollapsingHeader::new(egui::RichText::new(summary).monospace())
+        .id_salt(path)
+        .default_open(depth < OPEN_DEPTH)
+        .show(ui, |ui| {
+            for (child_key, child) in &children {
+                let child_path = format!("{path}/{child_key}");
+                json_value(ui, Some(child_key), child, &child_path, depth + 1);
+            }
+        });
+}
+
+fn json_scalar(ui: &egui::Ui, value: &serde_json::Value) -> egui::RichText {
+    use serde_json::Value;
+
+    let dark = ui.visuals().dark_mode;
+    let (text, color) = match value {
+        Value::String(s) => (
+            format!("\"{s}\""),
+            if dark {
+                egui::Color32::from_rgb(150, 210, 130)
+            } else {
+                egui::Color32::from_rgb(30, 120, 30)
+            },
+        ),
+        Value::Number(n) => (
+            n.to_string(),
+            if dark {
+                egui::Color32::from_rgb(120, 180, 250)
+            } else {
+                egui::Color32::from_rgb(20, 80, 180)
+            },
+        ),
+        other => (other.to_string(), ui.visuals().weak_text_color()),
+    };
+    egui::RichText::new(text).monospace().color(color)
+}
+
+// ── Table ──
+
+fn table(ui: &mut egui::Ui, rows: &[Vec<String>]) {
+    let Some((header, body)) = rows.split_first() else {
+        ui.weak("(empty)");
+        return;
+    };
+    let font = egui::TextStyle::Body.resolve(ui.style());
+    let char_width = ui.fonts(|f| f.glyph_width(&font, '0')).max(1.0);
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<f32> = (0..columns)
+        .map(|c| {
+            let chars = rows
+                .iter()
+                .filter_map(|r| r.get(c))
+                .map(|s| s.chars().count())
+                .max()
+                .unwrap_or(0);
+            (chars as f32 * char_width + 12.0).clamp(40.0, MAX_COLUMN_WIDTH)
+        })
+        .collect();
+    let row_height = ui.spacing().interact_size.y + ui.spacing().item_spacing.y;
+
+    egui::ScrollArea::horizontal()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            ui.vertical(|ui| {
+                table_row(ui, header, &widths, true);
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show_rows(ui, row_height, body.len(), |ui, range| {
+                        for row in &body[range] {
+                            table_row(ui, row, &widths, false);
+                        }
+                    });
+            });
+        });
+}
+
+fn table_row(ui: &mut egui::Ui, row: &[String], widths: &[f32], header: bool) {
+    ui.horizontal(|ui| {
+        for (i, &width) in widths.iter().enumerate() {
+            let cell = row.get(i).map_or("", String::as_str);
+            let text = egui::RichText::new(cell.replace('\n', " "));
+            let text = if header { text.strong() } else { text };
+            ui.add_sized(
+                [width, ui.spacing().interact_size.y],
+                egui::Label::new(text).truncate(),
+            );
+        }
+    });
+}
+
+// ── Text ──
+
+fn monospace_text(ui: &mut egui::Ui, text: &str, wrap: bool) {
+    if wrap {
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                ui.add(egui::Label::new(egui::RichText::new(text).monospace()).wrap());
+            });
+        return;
+    }
+    let lines: Vec<&str> = text.lines().collect();
+    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+    egui::ScrollArea::both()
+        .auto_shrink([false, false])
+        .show_rows(ui, row_height, lines.len(), |ui, range| {
+            ui.spacing_mut().item_spacing.y = 0.0;
+            for line in &lines[range] {
+                ui.add(egui::Label::new(egui::RichText::new(*line).monospace()).extend());
+            }
+        });
+}
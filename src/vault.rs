@@ -0,0 +1,156 @@
+//! Encrypted files in the profile directory.
+//!
+//! A [`Vault`] seals data with ChaCha20-Poly1305 under a 256-bit key kept
+//! in [`KEY_FILE`], created on first use from the system's random source.
+//! A sealed file is a random 96-bit nonce followed by the ciphertext and
+//! its tag; any change to it fails to open rather than yielding garbage.
+//! Both files are written owner-only ([`crate::profile::write_private`]).
+//!
+//! The key lives beside the data, not in an OS keyring: a sealed file that
+//! leaks on its own (a backup, a synced folder, a bug report) reveals
+//! nothing, but anyone who can read the whole profile can open it.
+
+use std::io;
+use std::path::Path;
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// File name of the key inside the profile directory.
+pub const KEY_FILE: &str = "vault.key";
+
+/// Length of a key in bytes.
+pub const KEY_LEN: usize = 32;
+
+/// Seals and opens profile files under one key.
+pub struct Vault {
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl Vault {
+    /// Vault under `key`.
+    #[must_use]
+    pub fn with_key(key: &[u8; KEY_LEN]) -> Self {
+        let key = UnboundKey::new(&CHACHA20_POLY1305, key).expect("key has the cipher's length");
+        Self {
+            key: LessSafeKey::new(key),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    /// Vault under the profile's key, creating the key if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the key cannot be read or written, or if
+    /// the key file has the wrong length.
+    pub fn profile() -> io::Result<Self> {
+        let path = crate::profile::file_path(KEY_FILE)?;
+        match std::fs::read(&path) {
+            Ok(bytes) => {
+                let key: [u8; KEY_LEN] = bytes.try_into().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("{KEY_FILE} is damaged"))
+                })?;
+                Ok(Self::with_key(&key))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let mut key = [0u8; KEY_LEN];
+                SystemRandom::new()
+                    .fill(&mut key)
+                    .map_err(|_| io::Error::other("no random source"))?;
+                crate::profile::write_private(&path, key)?;
+                Ok(Self::with_key(&key))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Encrypt `plain`: nonce, ciphertext, tag.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if no nonce can be drawn.
+    pub fn seal(&self, plain: &[u8]) -> io::Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| io::Error::other("no random source"))?;
+        let mut sealed = nonce.to_vec();
+        let mut body = plain.to_vec();
+        self.key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut body)
+            .map_err(|_| io::Error::other("encryption failed"))?;
+        sealed.append(&mut body);
+        Ok(sealed)
+    }
+
+    /// Decrypt what [`Vault::seal`] produced.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::ErrorKind::InvalidData`] if `sealed` was changed,
+    /// truncated or sealed under another key.
+    pub fn open(&self, sealed: &[u8]) -> io::Result<Vec<u8>> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "cannot decrypt");
+        if sealed.len() < NONCE_LEN {
+            return Err(invalid());
+        }
+        let (nonce, body) = sealed.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| invalid())?;
+        let mut body = body.to_vec();
+        let plain = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut body)
+            .map_err(|_| invalid())?;
+        Ok(plain.to_vec())
+    }
+
+    /// Seal `plain` into `path`, owner-only.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if sealing or writing fails.
+    pub fn write(&self, path: &Path, plain: &[u8]) -> io::Result<()> {
+        crate::profile::write_private(path, self.seal(plain)?)
+    }
+
+    /// Read and open the sealed file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file cannot be read or opened.
+    pub fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.open(&std::fs::read(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_data_opens_again() {
+        let vault = Vault::with_key(&[7; KEY_LEN]);
+        let sealed = vault.seal(b"name = \"Ada\"").unwrap();
+        assert!(!sealed.windows(3).any(|w| w == b"Ada"));
+        assert_eq!(vault.open(&sealed).unwrap(), b"name = \"Ada\"");
+        // A fresh nonce every time
+        assert_ne!(vault.seal(b"x").unwrap(), vault.seal(b"x").unwrap());
+    }
+
+    #[test]
+    fn tampered_or_foreign_data_is_refused() {
+        let vault = Vault::with_key(&[7; KEY_LEN]);
+        let mut sealed = vault.seal(b"secret").unwrap();
+        let other = Vault::with_key(&[8; KEY_LEN]);
+        assert_eq!(
+            other.open(&sealed).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert!(vault.open(&sealed).is_err());
+        assert!(vault.open(&sealed[..NONCE_LEN - 1]).is_err());
+    }
+}
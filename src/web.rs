@@ -0,0 +1,127 @@
+//! JavaScript bindings of the wasm32 build, for the browser-hosted demo
+//! (see `web/`).
+//!
+//! A [`WebEngine`] runs the same pipeline as the native browser — parse,
+//! filter, layout, SDF scene — on documents the page hands it: there is no
+//! network in the module, so the host fetches with `fetch()` and passes the
+//! bodies in through [`WebEngine::provide`] (frames included), or loads a
+//! string directly with [`WebEngine::load_html`]. Results come back as
+//! JSON: the paint list the egui renderer draws from, and the SDF scene.
+
+use std::sync::Arc;
+
+use wasm_bindgen::prelude::*;
+
+use crate::engine::pipeline::{BrowserEngine, PageError, PageResult};
+use crate::net::backend::StaticBackend;
+use crate::render::sdf_ui::layout_to_paint;
+
+/// The engine, with the documents provided to it and the last page loaded.
+#[wasm_bindgen]
+pub struct WebEngine {
+    documents: Arc<StaticBackend>,
+    engine: BrowserEngine,
+    page: Option<PageResult>,
+}
+
+#[wasm_bindgen]
+impl WebEngine {
+    /// Engine laying pages out for a `width` × `height` CSS px viewport.
+    #[wasm_bindgen(constructor)]
+    #[must_use]
+    pub fn new(width: f32, height: f32) -> Self {
+        let documents = Arc::new(StaticBackend::new());
+        let engine = BrowserEngine::builder()
+            .viewport(width, height)
+            .backend(Arc::clone(&documents) as _)
+            .build();
+        Self {
+            documents,
+            engine,
+            page: None,
+        }
+    }
+
+    /// Serve `body` for `url` from now on.
+    pub fn provide(&self, url: &str, body: &str, content_type: &str) {
+        self.documents.insert(url, body, content_type);
+    }
+
+    /// Load a provided document through the pipeline.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `url` was not provided or the pipeline rejects the page.
+    pub fn load(&mut self, url: &str) -> Result<(), JsError> {
+        self.page = Some(self.engine.load_page(url).map_err(js_error)?);
+        Ok(())
+    }
+
+    /// Load `html` as the document at `url`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the pipeline rejects the page.
+    pub fn load_html(&mut self, html: &str, url: &str) -> Result<(), JsError> {
+        self.page = Some(self.engine.process_html(html, url, 200).map_err(js_error)?);
+        Ok(())
+    }
+
+    /// Title of the loaded page; empty before one is loaded.
+    #[must_use]
+    pub fn title(&self) -> String {
+        self.page
+            .as_ref()
+            .map(|page| page.dom.title.clone())
+            .unwrap_or_default()
+    }
+
+    /// Paint elements of the loaded page as a JSON array; empty before one
+    /// is loaded.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the elements cannot be serialized.
+    pub fn paint_list(&self) -> Result<String, JsError> {
+        let elements = self
+            .page
+            .as_ref()
+            .map_or_else(Vec::new, |page| layout_to_paint(&page.layout));
+        to_json(&elements)
+    }
+
+    /// SDF scene of the loaded page as JSON: `primitives`, each tagged with
+    /// its `type`, and `background_color`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if no page is loaded.
+    pub fn sdf_scene(&self) -> Result<String, JsError> {
+        let page = self
+            .page
+            .as_ref()
+            .ok_or_else(|| JsError::new("No page loaded"))?;
+        to_json(&page.sdf_scene)
+    }
+
+    /// Time spent in each pipeline stage for the loaded page, as JSON.
+    ///
+    /// # Errors
+    ///
+    /// Fails if no page is loaded.
+    pub fn timings(&self) -> Result<String, JsError> {
+        let page = self
+            .page
+            .as_ref()
+            .ok_or_else(|| JsError::new("No page loaded"))?;
+        to_json(&page.timings)
+    }
+}
+
+fn js_error(e: PageError) -> JsError {
+    JsError::new(&format!("{} ({})", e.message, e.phase))
+}
+
+fn to_json(value: &impl serde::Serialize) -> Result<String, JsError> {
+    serde_json::to_string(value).map_err(|e| JsError::new(&e.to_string()))
+}
@@ -0,0 +1,244 @@
+//! Golden-page regression tests.
+//!
+//! Every `tests/fixtures/*.html` page is run through the full offline
+//! pipeline (parse → classify → readability → layout) and rendered to a text
+//! snapshot of its `DomTree`, `LayoutNode` tree and `FilterStats`, which
+//! must match `tests/snapshots/<name>.snap`.
+//!
+//! After an intended behaviour change, regenerate the snapshots and review
+//! the diff:
+//!
+//! ```bash
+//! UPDATE_GOLDEN=1 cargo test --test golden
+//! ```
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use alice_browser::dom::filter::FilterStats;
+use alice_browser::dom::{DomNode, NodeType};
+use alice_browser::engine::pipeline::{BrowserEngine, ClassifierBackend, PageResult};
+use alice_browser::render::layout::{LayoutNode, TablePart};
+
+/// Base URL fixtures are "served" from.
+const FIXTURE_URL: &str = "https://fixtures.alice.test/";
+/// Longest text run kept in a snapshot line.
+const MAX_TEXT: usize = 60;
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn snapshots_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots")
+}
+
+fn update_mode() -> bool {
+    std::env::var_os("UPDATE_GOLDEN").is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+// ── Serialisation ──
+
+fn short(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > MAX_TEXT {
+        let cut: String = flat.chars().take(MAX_TEXT).collect();
+        format!("{cut}…")
+    } else {
+        flat
+    }
+}
+
+fn write_dom(out: &mut String, node: &DomNode, depth: usize) {
+    let indent = "  ".repeat(depth);
+    match node.node_type {
+        NodeType::Text => {
+            let _ = writeln!(out, "{indent}\"{}\"", short(&node.text));
+        }
+        NodeType::Element | NodeType::Document => {
+            let mut attrs: Vec<_> = node
+                .attributes
+                .iter()
+                .filter(|(k, _)| matches!(k.as_str(), "id" | "class" | "href" | "src" | "name"))
+                .map(|(k, v)| format!(" {k}={:?}", short(v)))
+                .collect();
+            attrs.sort();
+            let _ = writeln!(
+                out,
+                "{indent}<{}{}> {:?}",
+                node.tag,
+                attrs.concat(),
+                node.classification
+            );
+            for child in &node.children {
+                write_dom(out, child, depth + 1);
+            }
+        }
+    }
+}
+
+fn write_layout(out: &mut String, node: &LayoutNode, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let b = node.bounds;
+    let tag = if node.tag.is_empty() {
+        "#text"
+    } else {
+        &node.tag
+    };
+    let _ = write!(
+        out,
+        "{indent}{tag} [{:.1} {:.1} {:.1}x{:.1}] font={:.0}{}",
+        b.x,
+        b.y,
+        b.width,
+        b.height,
+        node.font_size,
+        if node.is_block { " block" } else { "" },
+    );
+    if let Some(ref href) = node.href {
+        let _ = write!(out, " href={href:?}");
+    }
+    if let Some(ref control) = node.control {
+        let _ = write!(out, " control={:?} name={:?}", control.kind, control.name);
+    }
+    match node.table {
+        Some(TablePart::Grid {
+            rows,
+            cols,
+            ref col_widths,
+        }) => {
+            let widths: Vec<_> = col_widths.iter().map(|w| format!("{w:.1}")).collect();
+            let _ = write!(out, " grid={rows}x{cols} cols=[{}]", widths.join(" "));
+        }
+        Some(TablePart::Cell(c)) => {
+            let _ = write!(
+                out,
+                " cell=({},{}) span={}x{}{}",
+                c.row,
+                c.col,
+                c.rowspan,
+                c.colspan,
+                if c.header { " header" } else { "" }
+            );
+        }
+        None => {}
+    }
+    if !node.text.trim().is_empty() {
+        let _ = write!(out, " \"{}\"", short(&node.text));
+    }
+    out.push('\n');
+    for child in &node.children {
+        write_layout(out, child, depth + 1);
+    }
+}
+
+fn write_stats(out: &mut String, s: &FilterStats) {
+    let _ = writeln!(
+        out,
+        "total={} content={} ad={} tracker={} nav={} removed={}",
+        s.total_nodes, s.content_nodes, s.ad_nodes, s.tracker_nodes, s.nav_nodes, s.removed_nodes
+    );
+}
+
+fn snapshot(page: &PageResult) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# title: {:?}", page.dom.title);
+    out.push_str("\n## FilterStats\n");
+    write_stats(&mut out, &page.filter_stats);
+    out.push_str("\n## DomTree\n");
+    write_dom(&mut out, &page.dom.root, 0);
+    out.push_str("\n## LayoutNode\n");
+    write_layout(&mut out, &page.layout, 0);
+    out
+}
+
+// ── Harness ──
+
+/// Run one fixture and compare (or, in update mode, rewrite) its snapshot.
+fn check_fixture(name: &str) {
+    let html_path = fixtures_dir().join(format!("{name}.html"));
+    let html = std::fs::read_to_string(&html_path)
+        .unwrap_or_else(|e| panic!("read {}: {e}", html_path.display()));
+
+    let engine = BrowserEngine::builder()
+        .viewport(800.0, 600.0)
+        .classifier(ClassifierBackend::Simd)
+        .build();
+    let page = engine
+        .process_html(&html, &format!("{FIXTURE_URL}{name}.html"), 200)
+        .unwrap_or_else(|e| panic!("{name}: {e}"));
+    let actual = snapshot(&page);
+
+    let snap_path = snapshots_dir().join(format!("{name}.snap"));
+    if update_mode() {
+        std::fs::create_dir_all(snapshots_dir()).unwrap();
+        std::fs::write(&snap_path, &actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&snap_path).unwrap_or_else(|_| {
+        panic!(
+            "missing snapshot {}; run `UPDATE_GOLDEN=1 cargo test --test golden`",
+            snap_path.display()
+        )
+    });
+    if expected != actual {
+        let first_diff = expected
+            .lines()
+            .zip(actual.lines())
+            .position(|(e, a)| e != a)
+            .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+        let line = |s: &str| s.lines().nth(first_diff).unwrap_or("<eof>").to_string();
+        panic!(
+            "{name}: snapshot mismatch at line {}\n  expected: {}\n  actual:   {}\n\
+             If the change is intended, run `UPDATE_GOLDEN=1 cargo test --test golden`.",
+            first_diff + 1,
+            line(&expected),
+            line(&actual),
+        );
+    }
+}
+
+#[test]
+fn golden_article() {
+    check_fixture("article");
+}
+
+#[test]
+fn golden_news_with_ads() {
+    check_fixture("news_with_ads");
+}
+
+#[test]
+fn golden_search_form() {
+    check_fixture("search_form");
+}
+
+#[test]
+fn golden_table() {
+    check_fixture("table");
+}
+
+#[test]
+fn golden_minimal() {
+    check_fixture("minimal");
+}
+
+/// Every fixture on disk has a test above (and therefore a snapshot).
+#[test]
+fn every_fixture_has_a_snapshot() {
+    let mut missing = Vec::new();
+    for entry in std::fs::read_dir(fixtures_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|e| e == "html") {
+            let stem = path.file_stem().unwrap().to_string_lossy().into_owned();
+            if !snapshots_dir().join(format!("{stem}.snap")).exists() && !update_mode() {
+                missing.push(stem);
+            }
+        }
+    }
+    assert!(
+        missing.is_empty(),
+        "fixtures without snapshots: {missing:?}"
+    );
+}